@@ -0,0 +1,1343 @@
+//! Admin API
+//!
+//! Tenant administration for platform admins under `/api/v1/admin`: tenants,
+//! their feature flags, dataset defaults and credential profiles, API keys,
+//! impersonation, audit logs, rate-limit violations and usage. Tenants see
+//! their own usage and quotas through `/api/v1/usage` and
+//! `/api/v1/tenant/usage`.
+
+use crate::control_plane::{
+    AuditContext as ControlPlaneAuditContext, AuditLogEntry, CreateTenantRequest,
+    ImpersonationRequest, ImpersonationToken, RateLimitViolationSummary, Tenant, TenantApiKey,
+    TenantDatasetDefaults, TenantFeatureFlags, TenantRole, UpdateTenantFeatureFlagsRequest,
+    UpdateTenantRequest,
+};
+use crate::error_codes::ErrorCode;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "rate-limiting")]
+use crate::rate_limiting;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::tenant_resolver::ResolvedTenant;
+#[cfg(feature = "usage-analytics")]
+use crate::usage_analytics;
+use crate::{control_plane, envelope, export_schedules, namespaces, sandbox};
+use axum::extract::{Extension, Path, Query, Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+/// Request to create a new API key for a tenant
+#[derive(Debug, Deserialize)]
+pub(crate) struct AdminCreateApiKeyRequest {
+    name: String,
+    role: TenantRole,
+    #[serde(default)]
+    expires_at: Option<String>,
+    /// Sandbox namespace every request made with the key is pinned to
+    #[serde(default)]
+    sandbox: Option<String>,
+}
+
+/// Response when creating a tenant (includes initial API key)
+#[derive(Debug, Serialize)]
+pub(crate) struct AdminCreateTenantResponse {
+    tenant: Tenant,
+    /// Initial admin API key - only returned at creation time
+    initial_api_key: String,
+}
+
+/// Tenant detail response (tenant record plus effective feature flags)
+#[derive(Debug, Serialize)]
+pub(crate) struct AdminTenantDetailResponse {
+    #[serde(flatten)]
+    tenant: Tenant,
+    feature_flags: TenantFeatureFlags,
+    dataset_defaults: TenantDatasetDefaults,
+    /// Residency zone, when the tenant has one
+    data_residency: Option<String>,
+}
+
+/// Response when creating an API key
+#[derive(Debug, Serialize)]
+pub(crate) struct AdminCreateApiKeyResponse {
+    /// The API key - only returned at creation time
+    api_key: String,
+}
+
+/// Query parameters for audit log
+#[derive(Debug, Deserialize)]
+pub(crate) struct AdminAuditLogQuery {
+    tenant_id: Option<String>,
+    #[serde(default = "default_audit_limit")]
+    limit: usize,
+}
+
+pub(crate) fn default_audit_limit() -> usize {
+    100
+}
+
+/// Query parameters for rate limit violation report
+#[derive(Debug, Deserialize)]
+pub(crate) struct AdminRateLimitViolationsQuery {
+    /// Lookback period in days, e.g. "7d"
+    #[serde(default = "default_violation_period")]
+    period: String,
+    tenant_id: Option<String>,
+    #[serde(default = "default_audit_limit")]
+    limit: usize,
+}
+
+pub(crate) fn default_violation_period() -> String {
+    "7d".to_string()
+}
+
+/// Parse a lookback period such as "7d" into a number of days (1-365).
+pub(crate) fn parse_period_days(period: &str) -> Option<i64> {
+    let days: i64 = period.strip_suffix('d')?.parse().ok()?;
+    (1..=365).contains(&days).then_some(days)
+}
+
+/// Response for rate limit violation report
+#[derive(Debug, Serialize)]
+pub(crate) struct RateLimitViolationsResponse {
+    period: String,
+    /// Sum of violation counts across all returned rows
+    total_violations: i64,
+    violations: Vec<RateLimitViolationSummary>,
+}
+
+/// Query parameters for listing tenants
+#[derive(Debug, Deserialize)]
+pub(crate) struct AdminListTenantsQuery {
+    status: Option<String>,
+}
+
+/// Response for tenant usage endpoint (admin view)
+#[derive(Debug, Serialize)]
+pub(crate) struct TenantUsageResponse {
+    tenant_id: String,
+    /// Current dataset count
+    dataset_count: i64,
+    /// Quota limits
+    quota_max_datasets: i64,
+    quota_max_storage_bytes: i64,
+    quota_max_api_calls_per_hour: i64,
+    /// Usage percentages (0.0 to 1.0+)
+    usage_ratio_datasets: f64,
+    /// Human-readable status
+    status: String,
+}
+
+/// Response for tenant self-service usage endpoint
+#[derive(Debug, Serialize)]
+pub(crate) struct MyUsageResponse {
+    /// Current dataset count
+    dataset_count: i64,
+    /// Quota limit for datasets (0 = unlimited)
+    quota_max_datasets: i64,
+    /// Usage percentage (0.0 to 1.0+)
+    usage_ratio: f64,
+    /// Human-readable status: "ok", "warning", "exceeded", "unlimited"
+    status: String,
+    /// Optional warning message when approaching or exceeding quota
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+/// Usage of a single quota-limited resource
+#[derive(Debug, Serialize)]
+pub(crate) struct QuotaUsage {
+    /// Current usage
+    used: i64,
+    /// Quota limit (0 = unlimited)
+    quota: i64,
+    /// Usage percentage (0.0 to 1.0+)
+    usage_ratio: f64,
+    /// Human-readable status: "ok", "warning", "exceeded", "unlimited"
+    status: String,
+}
+
+/// API call usage for the tenant usage dashboard
+#[derive(Debug, Serialize)]
+pub(crate) struct ApiCallUsage {
+    /// Hourly API call quota configured for the tenant (0 = unlimited)
+    quota_per_hour: i64,
+    /// Requests counted by the rate limiter in the current window
+    #[cfg(feature = "rate-limiting")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_window: Option<rate_limiting::TenantRequestUsage>,
+    /// Per-client request limit for the tenant's tier in each window
+    #[cfg(feature = "rate-limiting")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_per_window: Option<u32>,
+}
+
+/// Response for the tenant self-service usage dashboard
+#[derive(Debug, Serialize)]
+pub(crate) struct TenantUsageDashboardResponse {
+    tenant_id: String,
+    tier: String,
+    datasets: QuotaUsage,
+    storage_bytes: QuotaUsage,
+    api_calls: ApiCallUsage,
+    /// Catalog access totals over the last 30 days
+    #[cfg(feature = "usage-analytics")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activity: Option<usage_analytics::UsageTotals>,
+    /// Overall status: the worst of the individual quota statuses
+    status: String,
+    /// Warnings for quotas that are close to or over their limit
+    warnings: Vec<String>,
+}
+
+/// Platform admin authorization middleware.
+/// Validates the METAFUSE_ADMIN_KEY environment variable.
+pub(crate) async fn require_admin_auth(
+    headers: HeaderMap,
+    Extension(request_id): Extension<RequestId>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let admin_key = std::env::var("METAFUSE_ADMIN_KEY").map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Admin authentication not configured".to_string(),
+                code: ErrorCode::InternalError,
+                request_id: request_id.0.clone(),
+            }),
+        )
+    })?;
+
+    let auth_header = headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    error: "Missing Authorization header".to_string(),
+                    code: ErrorCode::Unauthorized,
+                    request_id: request_id.0.clone(),
+                }),
+            )
+        })?;
+
+    let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Invalid Authorization format. Expected: Bearer <token>".to_string(),
+                code: ErrorCode::Unauthorized,
+                request_id: request_id.0.clone(),
+            }),
+        )
+    })?;
+
+    if token != admin_key {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "Invalid admin key".to_string(),
+                code: ErrorCode::Forbidden,
+                request_id: request_id.0.clone(),
+            }),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// List all tenants
+pub(crate) async fn admin_list_tenants(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(params): Query<AdminListTenantsQuery>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<Tenant>>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let tenants = control_plane
+        .list_tenants(params.status.as_deref())
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(tenants)))
+}
+
+/// Create a new tenant
+pub(crate) async fn admin_create_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    JsonBody(req): JsonBody<CreateTenantRequest>,
+) -> Result<(StatusCode, Json<AdminCreateTenantResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let (tenant, initial_api_key) =
+        control_plane
+            .create_tenant(req, cp_audit)
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("already exists") {
+                    (
+                        StatusCode::CONFLICT,
+                        Json(ErrorResponse {
+                            error: e.to_string(),
+                            code: ErrorCode::Conflict,
+                            request_id: request_id.0.clone(),
+                        }),
+                    )
+                } else {
+                    internal_error(e.to_string(), request_id.0.clone())
+                }
+            })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AdminCreateTenantResponse {
+            tenant,
+            initial_api_key,
+        }),
+    ))
+}
+
+/// Get a specific tenant
+pub(crate) async fn admin_get_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<AdminTenantDetailResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let tenant = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Tenant '{}' not found", tenant_id),
+                    code: ErrorCode::TenantNotFound,
+                    request_id: request_id.0.clone(),
+                }),
+            )
+        })?;
+
+    let feature_flags = control_plane
+        .get_tenant_feature_flags(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_defaults = control_plane
+        .get_tenant_dataset_defaults(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let data_residency = control_plane
+        .get_tenant_residency(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .map(|residency| residency.zone);
+
+    Ok(Json(AdminTenantDetailResponse {
+        tenant,
+        feature_flags,
+        dataset_defaults,
+        data_residency,
+    }))
+}
+
+/// Get feature flags for a tenant
+pub(crate) async fn admin_get_tenant_features(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<TenantFeatureFlags>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let exists = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .is_some();
+    if !exists {
+        return Err(not_found(
+            format!("Tenant '{}' not found", tenant_id),
+            request_id.0.clone(),
+        ));
+    }
+
+    let flags = control_plane
+        .get_tenant_feature_flags(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(flags))
+}
+
+/// Toggle feature flags for a tenant
+pub(crate) async fn admin_update_tenant_features(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<UpdateTenantFeatureFlagsRequest>,
+) -> Result<Json<TenantFeatureFlags>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let flags = control_plane
+        .update_tenant_feature_flags(&tenant_id, req, cp_audit)
+        .await
+        .map_err(|e| match e {
+            metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+                bad_request(msg, request_id.0.clone())
+            }
+            metafuse_catalog_core::CatalogError::DatasetNotFound(msg) => {
+                not_found(msg, request_id.0.clone())
+            }
+            e => internal_error(e.to_string(), request_id.0.clone()),
+        })?;
+
+    tracing::info!(
+        tenant_id = %tenant_id,
+        classification = flags.classification,
+        preview = flags.preview,
+        usage_analytics = flags.usage_analytics,
+        "Tenant feature flags updated"
+    );
+
+    Ok(Json(flags))
+}
+
+/// Get dataset defaults for a tenant
+pub(crate) async fn admin_get_tenant_defaults(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<TenantDatasetDefaults>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let exists = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .is_some();
+    if !exists {
+        return Err(not_found(
+            format!("Tenant '{}' not found", tenant_id),
+            request_id.0.clone(),
+        ));
+    }
+
+    let defaults = control_plane
+        .get_tenant_dataset_defaults(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(defaults))
+}
+
+/// Replace dataset defaults for a tenant
+pub(crate) async fn admin_update_tenant_defaults(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<TenantDatasetDefaults>,
+) -> Result<Json<TenantDatasetDefaults>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let defaults = control_plane
+        .set_tenant_dataset_defaults(&tenant_id, req, cp_audit)
+        .await
+        .map_err(|e| match e {
+            metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+                bad_request(msg, request_id.0.clone())
+            }
+            metafuse_catalog_core::CatalogError::DatasetNotFound(msg) => {
+                not_found(msg, request_id.0.clone())
+            }
+            e => internal_error(e.to_string(), request_id.0.clone()),
+        })?;
+
+    tracing::info!(
+        tenant_id = %tenant_id,
+        tags = defaults.tags.len(),
+        domain = ?defaults.domain,
+        owner = ?defaults.owner,
+        "Tenant dataset defaults updated"
+    );
+
+    Ok(Json(defaults))
+}
+
+/// List a tenant's credentials profiles, without their secrets
+pub(crate) async fn admin_list_credentials_profiles(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<
+    Json<envelope::Collection<export_schedules::CredentialsProfile>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let exists = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .is_some();
+    if !exists {
+        return Err(not_found(
+            format!("Tenant '{}' not found", tenant_id),
+            request_id.0.clone(),
+        ));
+    }
+
+    let profiles = control_plane
+        .list_credentials_profiles(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(profiles)))
+}
+
+/// Store or replace one of a tenant's credentials profiles
+pub(crate) async fn admin_put_credentials_profile(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path((tenant_id, name)): Path<(String, String)>,
+    JsonBody(credentials): JsonBody<export_schedules::ProfileCredentials>,
+) -> Result<
+    (StatusCode, Json<export_schedules::CredentialsProfile>),
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let (profile, created) = control_plane
+        .put_credentials_profile(&tenant_id, &name, credentials, cp_audit)
+        .await
+        .map_err(|e| export_schedules::export_schedule_error(e, &request_id.0))?;
+
+    let status = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(profile)))
+}
+
+/// Delete one of a tenant's credentials profiles
+pub(crate) async fn admin_delete_credentials_profile(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path((tenant_id, name)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let deleted = control_plane
+        .delete_credentials_profile(&tenant_id, &name, cp_audit)
+        .await
+        .map_err(|e| export_schedules::export_schedule_error(e, &request_id.0))?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found(
+            format!("Credentials profile '{}' not found", name),
+            request_id.0.clone(),
+        ))
+    }
+}
+
+/// Update a tenant
+pub(crate) async fn admin_update_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<UpdateTenantRequest>,
+) -> Result<Json<Tenant>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let tenant = control_plane
+        .update_tenant(&tenant_id, req, cp_audit)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("not found") {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        error: e.to_string(),
+                        code: ErrorCode::TenantNotFound,
+                        request_id: request_id.0.clone(),
+                    }),
+                )
+            } else {
+                internal_error(e.to_string(), request_id.0.clone())
+            }
+        })?;
+
+    Ok(Json(tenant))
+}
+
+/// Suspend a tenant
+pub(crate) async fn admin_suspend_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<Tenant>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let tenant = control_plane
+        .suspend_tenant(&tenant_id, cp_audit)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(tenant))
+}
+
+/// Reactivate a suspended tenant
+pub(crate) async fn admin_reactivate_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<Tenant>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let tenant = control_plane
+        .reactivate_tenant(&tenant_id, cp_audit)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(tenant))
+}
+
+/// Delete a tenant (soft delete)
+pub(crate) async fn admin_delete_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<Tenant>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let tenant = control_plane
+        .delete_tenant(&tenant_id, cp_audit)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(tenant))
+}
+
+/// List API keys for a tenant
+pub(crate) async fn admin_list_api_keys(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<TenantApiKey>>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let keys = control_plane
+        .list_tenant_api_keys(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(keys)))
+}
+
+/// Create a new API key for a tenant
+pub(crate) async fn admin_create_api_key(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<AdminCreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<AdminCreateApiKeyResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    if let Some(sandbox) = &req.sandbox {
+        namespaces::validate_namespace(sandbox)
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    }
+
+    let api_key = control_plane
+        .create_sandbox_api_key(&tenant_id, req.name, req.role, req.expires_at, req.sandbox)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AdminCreateApiKeyResponse { api_key }),
+    ))
+}
+
+/// Mint a short-lived viewer token for a platform operator to see a tenant's view
+pub(crate) async fn admin_impersonate_tenant(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<ImpersonationRequest>,
+) -> Result<(StatusCode, Json<ImpersonationToken>), (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let token = control_plane
+        .create_impersonation_token(&tenant_id, &req, cp_audit)
+        .await
+        .map_err(|e| match e {
+            metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+                bad_request(msg, request_id.0.clone())
+            }
+            metafuse_catalog_core::CatalogError::DatasetNotFound(msg) => {
+                not_found(msg, request_id.0.clone())
+            }
+            e => internal_error(e.to_string(), request_id.0.clone()),
+        })?;
+
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+/// Revoke an API key
+pub(crate) async fn admin_revoke_api_key(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path((tenant_id, key_id)): Path<(String, i64)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let revoked = control_plane
+        .revoke_tenant_api_key(&tenant_id, key_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if revoked {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("API key {} not found for tenant {}", key_id, tenant_id),
+                code: ErrorCode::NotFound,
+                request_id: request_id.0.clone(),
+            }),
+        ))
+    }
+}
+
+/// Get audit log
+pub(crate) async fn admin_get_audit_log(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(params): Query<AdminAuditLogQuery>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<AuditLogEntry>>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let (limit, offset) = envelope.bounds(params.limit);
+    let logs = control_plane
+        .get_audit_log_page(params.tenant_id.as_deref(), limit, offset)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let total = if envelope.is_enabled() {
+        control_plane
+            .count_audit_log(params.tenant_id.as_deref())
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    } else {
+        0
+    };
+
+    Ok(Json(envelope.window(logs, total)))
+}
+
+/// List rate limit violations grouped by tenant, client and route
+pub(crate) async fn admin_list_rate_limit_violations(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(params): Query<AdminRateLimitViolationsQuery>,
+) -> Result<Json<RateLimitViolationsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let days = parse_period_days(&params.period).ok_or_else(|| {
+        bad_request(
+            format!(
+                "Invalid period '{}': expected '<days>d' between 1d and 365d",
+                params.period
+            ),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let violations = control_plane
+        .list_rate_limit_violations(days, params.tenant_id.as_deref(), params.limit)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let total_violations = violations.iter().map(|v| v.violation_count).sum();
+
+    Ok(Json(RateLimitViolationsResponse {
+        period: params.period,
+        total_violations,
+        violations,
+    }))
+}
+
+/// Get usage statistics for a tenant (admin endpoint)
+pub(crate) async fn admin_get_tenant_usage(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<TenantUsageResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    // Get tenant info
+    let tenant = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Tenant '{}' not found", tenant_id),
+                    code: ErrorCode::TenantNotFound,
+                    request_id: request_id.0.clone(),
+                }),
+            )
+        })?;
+
+    // Get tenant's backend to count datasets
+    let factory = state.multi_tenant.factory().ok_or_else(|| {
+        internal_error(
+            "Tenant factory not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let backend = factory
+        .get_backend_by_id(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Count datasets
+    let dataset_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM datasets WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Calculate usage ratio
+    let usage_ratio_datasets = if tenant.quota_max_datasets > 0 {
+        dataset_count as f64 / tenant.quota_max_datasets as f64
+    } else {
+        0.0 // Unlimited quota
+    };
+
+    // Determine status
+    let status = if tenant.quota_max_datasets <= 0 {
+        "unlimited".to_string()
+    } else if usage_ratio_datasets >= 1.0 {
+        "exceeded".to_string()
+    } else if usage_ratio_datasets >= 0.8 {
+        "warning".to_string()
+    } else {
+        "ok".to_string()
+    };
+
+    tracing::info!(
+        tenant_id = %tenant_id,
+        dataset_count,
+        quota_max = tenant.quota_max_datasets,
+        usage_ratio = usage_ratio_datasets,
+        "Returning tenant usage stats"
+    );
+
+    Ok(Json(TenantUsageResponse {
+        tenant_id,
+        dataset_count,
+        quota_max_datasets: tenant.quota_max_datasets,
+        quota_max_storage_bytes: tenant.quota_max_storage_bytes,
+        quota_max_api_calls_per_hour: tenant.quota_max_api_calls_per_hour,
+        usage_ratio_datasets,
+        status,
+    }))
+}
+
+/// Get my usage statistics (tenant self-service endpoint)
+///
+/// Returns the authenticated tenant's current usage and quota status.
+pub(crate) async fn get_my_usage(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    resolved_tenant: Option<Extension<ResolvedTenant>>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+) -> Result<Json<MyUsageResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Require tenant authentication
+    let resolved = resolved_tenant.as_ref().map(|e| &e.0).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required to view usage".to_string(),
+                code: ErrorCode::Unauthorized,
+                request_id: request_id.0.clone(),
+            }),
+        )
+    })?;
+
+    let tenant_id = resolved.tenant_id();
+
+    // Get control plane to fetch quota info
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let tenant = control_plane
+        .get_tenant(tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            internal_error(
+                format!("Tenant '{}' not found in control plane", tenant_id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let (datasets, _) = quota_usage(&conn, &tenant)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        dataset_count = datasets.used,
+        quota_max = datasets.quota,
+        usage_ratio = datasets.usage_ratio,
+        status = %datasets.status,
+        "Returning tenant usage"
+    );
+
+    Ok(Json(MyUsageResponse {
+        dataset_count: datasets.used,
+        quota_max_datasets: datasets.quota,
+        usage_ratio: datasets.usage_ratio,
+        warning: datasets.warning("Dataset"),
+        status: datasets.status,
+    }))
+}
+
+impl QuotaUsage {
+    /// Compute usage ratio and status for a quota-limited resource.
+    fn new(used: i64, quota: i64) -> Self {
+        let (usage_ratio, status) = if quota <= 0 {
+            (0.0, "unlimited")
+        } else {
+            let ratio = used as f64 / quota as f64;
+            if ratio >= 1.0 {
+                (ratio, "exceeded")
+            } else if ratio >= 0.8 {
+                (ratio, "warning")
+            } else {
+                (ratio, "ok")
+            }
+        };
+
+        Self {
+            used,
+            quota,
+            usage_ratio,
+            status: status.to_string(),
+        }
+    }
+
+    /// Warning for a quota close to or over its limit, e.g. `label = "Dataset"`
+    fn warning(&self, label: &str) -> Option<String> {
+        match self.status.as_str() {
+            "exceeded" => Some(format!(
+                "{} quota exceeded: {} of {} used",
+                label, self.used, self.quota
+            )),
+            "warning" => Some(format!(
+                "Approaching {} quota: {} of {} ({:.0}%)",
+                label.to_lowercase(),
+                self.used,
+                self.quota,
+                self.usage_ratio * 100.0
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Dataset and storage quota usage of a tenant's catalog
+///
+/// Trashed and sandbox datasets don't count against the quotas.
+pub(crate) fn quota_usage(
+    conn: &rusqlite::Connection,
+    tenant: &control_plane::Tenant,
+) -> rusqlite::Result<(QuotaUsage, QuotaUsage)> {
+    let (not_sandboxed, _) = sandbox::visibility_clause("datasets.name", None);
+    let (dataset_count, storage_bytes): (i64, i64) = conn.query_row(
+        &format!(
+            "SELECT COUNT(*), COALESCE(SUM(size_bytes), 0) FROM datasets
+             WHERE deleted_at IS NULL AND {}",
+            not_sandboxed
+        ),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok((
+        QuotaUsage::new(dataset_count, tenant.quota_max_datasets),
+        QuotaUsage::new(storage_bytes, tenant.quota_max_storage_bytes),
+    ))
+}
+
+/// Get the tenant usage dashboard (tenant self-service endpoint)
+///
+/// Summarizes the authenticated tenant's dataset count, storage footprint,
+/// API call volume, and quota status. Any valid tenant API key can call this;
+/// platform-admin access is not required.
+pub(crate) async fn get_tenant_usage_dashboard(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    resolved_tenant: Option<Extension<ResolvedTenant>>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "rate-limiting")] rate_limiter: Option<Extension<rate_limiting::RateLimiter>>,
+) -> Result<Json<TenantUsageDashboardResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Require tenant authentication
+    let resolved = resolved_tenant.as_ref().map(|e| &e.0).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required to view usage".to_string(),
+                code: ErrorCode::Unauthorized,
+                request_id: request_id.0.clone(),
+            }),
+        )
+    })?;
+
+    let tenant_id = resolved.tenant_id();
+
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let tenant = control_plane
+        .get_tenant(tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            internal_error(
+                format!("Tenant '{}' not found in control plane", tenant_id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let (datasets, storage) = quota_usage(&conn, &tenant)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Usage stats are best-effort: a failure here should not hide quota status
+    #[cfg(feature = "usage-analytics")]
+    let activity = usage_analytics::query_usage_totals(&conn, tenant_id, "30d")
+        .map_err(|e| {
+            tracing::warn!(tenant_id = %tenant_id, error = %e, "Failed to query usage totals");
+        })
+        .ok();
+
+    let warnings = [("Dataset", &datasets), ("Storage", &storage)]
+        .into_iter()
+        .filter_map(|(label, usage)| usage.warning(label))
+        .collect();
+
+    let status = ["exceeded", "warning", "ok"]
+        .into_iter()
+        .find(|s| datasets.status == *s || storage.status == *s)
+        .unwrap_or("unlimited")
+        .to_string();
+
+    #[cfg(feature = "rate-limiting")]
+    let (current_window, limit_per_window) = match rate_limiter.as_ref() {
+        Some(Extension(limiter)) => {
+            let tier = tenant
+                .tier
+                .parse::<rate_limiting::TenantTier>()
+                .unwrap_or(rate_limiting::TenantTier::Standard);
+            (
+                Some(limiter.tenant_usage(tenant_id)),
+                Some(limiter.tier_limit(tier)),
+            )
+        }
+        None => (None, None),
+    };
+
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        dataset_count = datasets.used,
+        storage_bytes = storage.used,
+        status = %status,
+        "Returning tenant usage dashboard"
+    );
+
+    Ok(Json(TenantUsageDashboardResponse {
+        tenant_id: tenant_id.to_string(),
+        tier: tenant.tier.clone(),
+        datasets,
+        storage_bytes: storage,
+        api_calls: ApiCallUsage {
+            quota_per_hour: tenant.quota_max_api_calls_per_hour,
+            #[cfg(feature = "rate-limiting")]
+            current_window,
+            #[cfg(feature = "rate-limiting")]
+            limit_per_window,
+        },
+        #[cfg(feature = "usage-analytics")]
+        activity,
+        status,
+        warnings,
+    }))
+}
+
+/// Get the dataset defaults applied to the calling tenant's new datasets
+/// (tenant self-service endpoint)
+pub(crate) async fn get_tenant_defaults(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    resolved_tenant: Option<Extension<ResolvedTenant>>,
+) -> Result<Json<TenantDatasetDefaults>, (StatusCode, Json<ErrorResponse>)> {
+    let resolved = resolved_tenant.as_ref().map(|e| &e.0).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required to view tenant defaults".to_string(),
+                code: ErrorCode::Unauthorized,
+                request_id: request_id.0.clone(),
+            }),
+        )
+    })?;
+
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let defaults = control_plane
+        .get_tenant_dataset_defaults(resolved.tenant_id())
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(defaults))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_period_days() {
+        assert_eq!(parse_period_days("7d"), Some(7));
+        assert_eq!(parse_period_days("365d"), Some(365));
+        assert_eq!(parse_period_days("0d"), None);
+        assert_eq!(parse_period_days("400d"), None);
+        assert_eq!(parse_period_days("7"), None);
+        assert_eq!(parse_period_days("1w"), None);
+    }
+}
@@ -5,6 +5,7 @@
 //! - Quality alerts (data quality scores below threshold)
 //! - Schema alerts (schema drift detected)
 //! - Contract alerts (data contract violations)
+//! - Watcher notifications (see `subscriptions`)
 //!
 //! # Architecture
 //!
@@ -39,6 +40,11 @@ use tracing::{debug, error, info, warn};
 #[cfg(feature = "metrics")]
 use crate::metrics;
 
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
 #[cfg(feature = "alerting")]
 use rand::Rng;
 
@@ -46,7 +52,7 @@ use rand::Rng;
 const DEFAULT_CHECK_INTERVAL_SECS: u64 = 60;
 
 /// Maximum webhook delivery attempts before giving up
-const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 3;
 
 /// Timeout for webhook requests
 const WEBHOOK_TIMEOUT_SECS: u64 = 10;
@@ -88,6 +94,8 @@ pub enum AlertType {
     Schema,
     /// Data contract violation
     Contract,
+    /// Dataset marked deprecated (watchers only)
+    Deprecation,
 }
 
 impl AlertType {
@@ -97,6 +105,7 @@ impl AlertType {
             AlertType::Quality => "quality",
             AlertType::Schema => "schema",
             AlertType::Contract => "contract",
+            AlertType::Deprecation => "deprecation",
         }
     }
 }
@@ -300,6 +309,55 @@ impl AlertPayload {
         }
     }
 
+    /// Create a quality drop payload (score fell since it was last checked)
+    pub fn quality_drop(dataset_name: &str, dataset_id: i64, previous: f64, current: f64) -> Self {
+        let severity = if current < previous * 0.5 {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        };
+
+        Self {
+            alert_type: AlertType::Quality,
+            severity,
+            dataset_name: dataset_name.to_string(),
+            dataset_id: Some(dataset_id),
+            message: format!(
+                "Dataset '{}' quality dropped from {:.1}% to {:.1}%",
+                dataset_name,
+                previous * 100.0,
+                current * 100.0
+            ),
+            details: Some(serde_json::json!({
+                "metric_type": "overall",
+                "previous_score": previous,
+                "score": current,
+            })),
+            integration_id: None,
+            source_system: "metafuse".to_string(),
+            customer_visible: false,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            alert_history_id: None,
+        }
+    }
+
+    /// Create a deprecation payload
+    pub fn deprecation(dataset_name: &str, dataset_id: i64) -> Self {
+        Self {
+            alert_type: AlertType::Deprecation,
+            severity: Severity::Warning,
+            dataset_name: dataset_name.to_string(),
+            dataset_id: Some(dataset_id),
+            message: format!("Dataset '{}' is deprecated", dataset_name),
+            details: None,
+            integration_id: None,
+            source_system: "metafuse".to_string(),
+            customer_visible: false,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            alert_history_id: None,
+        }
+    }
+
     /// Set integration ID for Servo correlation
     pub fn with_integration_id(mut self, id: Option<String>) -> Self {
         self.integration_id = id;
@@ -364,10 +422,39 @@ impl WebhookClient {
         Self::new(AlertConfig::default())
     }
 
+    /// A client that sends only to `url`'s addresses allowed by the URL
+    /// policy, without following redirects. Use it for caller-supplied URLs.
+    pub async fn pinned_to(
+        &self,
+        policy: &crate::webhooks::UrlPolicy,
+        url: &str,
+    ) -> Result<Self, WebhookError> {
+        let timeout = Duration::from_secs(WEBHOOK_TIMEOUT_SECS);
+        let client = crate::webhooks::pinned_client(policy, url, timeout)
+            .await
+            .map_err(WebhookError::Refused)?;
+        Ok(Self {
+            client,
+            config: self.config.clone(),
+        })
+    }
+
     /// Send alert payload to a webhook URL
     pub async fn send(&self, url: &str, payload: &AlertPayload) -> Result<(), WebhookError> {
+        self.send_json(url, payload.alert_type.as_str(), payload)
+            .await
+    }
+
+    /// Send any JSON payload to a webhook URL.
+    ///
+    /// `alert_type` labels the delivery in metrics.
+    pub async fn send_json<T: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        alert_type: &str,
+        payload: &T,
+    ) -> Result<(), WebhookError> {
         let start = std::time::Instant::now();
-        let alert_type = payload.alert_type.as_str();
 
         let response = self
             .client
@@ -406,6 +493,18 @@ impl WebhookClient {
         url: &str,
         payload: &AlertPayload,
         max_attempts: u32,
+    ) -> Result<u32, WebhookError> {
+        self.send_json_with_retry(url, payload.alert_type.as_str(), payload, max_attempts)
+            .await
+    }
+
+    /// Send any JSON payload with retry logic and jitter
+    pub async fn send_json_with_retry<T: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        alert_type: &str,
+        payload: &T,
+        max_attempts: u32,
     ) -> Result<u32, WebhookError> {
         let mut attempts = 0;
         let mut last_error = None;
@@ -414,7 +513,7 @@ impl WebhookClient {
         while attempts < max_attempts {
             attempts += 1;
 
-            match self.send(url, payload).await {
+            match self.send_json(url, alert_type, payload).await {
                 Ok(()) => return Ok(attempts),
                 Err(e) => {
                     warn!(
@@ -477,6 +576,8 @@ pub enum WebhookError {
     HttpStatus(u16, String),
     /// Max retries exceeded
     MaxRetriesExceeded,
+    /// URL refused by the webhook URL policy
+    Refused(String),
 }
 
 impl std::fmt::Display for WebhookError {
@@ -487,6 +588,7 @@ impl std::fmt::Display for WebhookError {
                 write!(f, "HTTP {} error: {}", code, body)
             }
             WebhookError::MaxRetriesExceeded => write!(f, "Max retries exceeded"),
+            WebhookError::Refused(e) => write!(f, "Refused: {}", e),
         }
     }
 }
@@ -617,6 +719,7 @@ pub fn find_stale_datasets(
         FROM datasets d
         JOIN freshness_config fc ON d.id = fc.dataset_id
         WHERE fc.alert_on_stale = 1
+          AND d.deleted_at IS NULL
           AND d.last_updated IS NOT NULL
           AND (julianday('now') - julianday(d.last_updated)) * 86400 > (fc.expected_interval_secs + fc.grace_period_secs)
         "#,
@@ -1022,6 +1125,44 @@ pub fn query_alert_history(
     })
 }
 
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// List alert history
+///
+/// SECURITY: This handler enforces tenant isolation by overriding any
+/// tenant_id passed in query params with the resolved tenant from the
+/// request context. This prevents cross-tenant data leakage.
+pub(crate) async fn list_alerts(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(mut params): Query<AlertHistoryParams>,
+) -> Result<Json<AlertHistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // CRITICAL: Enforce tenant isolation by overriding tenant_id from request context
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    // Override any user-provided tenant_id with the authenticated tenant
+    params.tenant_id = Some(tenant_id.to_string());
+
+    tracing::debug!(tenant_id = %tenant_id, "Listing alert history");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let response = query_alert_history(&conn, &params)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(response))
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -1036,6 +1177,7 @@ mod tests {
         assert_eq!(AlertType::Quality.as_str(), "quality");
         assert_eq!(AlertType::Schema.as_str(), "schema");
         assert_eq!(AlertType::Contract.as_str(), "contract");
+        assert_eq!(AlertType::Deprecation.as_str(), "deprecation");
     }
 
     #[test]
@@ -616,6 +616,7 @@ pub async fn api_key_required_middleware(
                     axum::http::StatusCode::UNAUTHORIZED,
                     axum::Json(serde_json::json!({
                         "error": "Unauthorized",
+                        "code": crate::error_codes::ErrorCode::Unauthorized,
                         "message": "Invalid API key",
                         "request_id": request_id
                     })),
@@ -628,6 +629,7 @@ pub async fn api_key_required_middleware(
                     axum::http::StatusCode::INTERNAL_SERVER_ERROR,
                     axum::Json(serde_json::json!({
                         "error": "Internal Server Error",
+                        "code": crate::error_codes::ErrorCode::InternalError,
                         "message": "Failed to validate API key",
                         "request_id": request_id
                     })),
@@ -642,6 +644,7 @@ pub async fn api_key_required_middleware(
         axum::http::StatusCode::UNAUTHORIZED,
         axum::Json(serde_json::json!({
             "error": "Unauthorized",
+            "code": crate::error_codes::ErrorCode::Unauthorized,
             "message": "API key required. Provide via Authorization header (Bearer token) or ?api_key= query parameter",
             "request_id": request_id
         })),
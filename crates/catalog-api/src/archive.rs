@@ -0,0 +1,329 @@
+//! Dataset Archival
+//!
+//! Retires datasets that are no longer produced but must stay on record, e.g.
+//! for compliance. Deleting a dataset eventually purges it along with its
+//! lineage and the context its audit trail refers to; archiving keeps all of
+//! it.
+//!
+//! # Architecture
+//!
+//! Archiving sets `archived_at` and `archived_by` (migration v1.44.0) and the
+//! dataset status to `archived`. Archived datasets:
+//! - are left out of `GET /api/v1/datasets` and `GET /api/v1/search` unless
+//!   the request passes `?include_archived=true`
+//! - can still be read by name, and carry `archived_at` in the response
+//! - keep their fields, tags, lineage, and history, and are never purged
+//!
+//! Deleting an archived dataset moves it to the trash as usual.
+//!
+//! # Endpoints
+//!
+//! - `POST /api/v1/datasets/{name}/archive` - Archive a dataset
+//! - `DELETE /api/v1/datasets/{name}/archive` - Return an archived dataset to use
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_acl;
+use crate::dataset_writes::apply_hook_changes;
+use crate::dataset_writes::run_partial_write_hooks;
+use crate::dataset_writes::spawn_post_commit_hooks;
+use crate::dataset_writes::stored_dataset_write;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_delete_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    accessible_dataset_id, bad_request, dataset_not_found, internal_error, AppState, AuditContext,
+    DatasetPath, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::hooks::WriteOperation;
+use metafuse_catalog_core::{placeholders, validation};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Status of an archived dataset
+pub const STATUS_ARCHIVED: &str = "archived";
+
+/// Archival state of a dataset
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArchiveState {
+    pub name: String,
+    pub status: String,
+    pub archived_at: Option<String>,
+    pub archived_by: Option<String>,
+}
+
+/// Parse `?include_archived=`.
+pub fn parse_include_archived(value: Option<&str>) -> Result<bool, String> {
+    match value {
+        None | Some("false") => Ok(false),
+        Some("true") => Ok(true),
+        Some(other) => Err(format!(
+            "Invalid include_archived '{}'. Valid values: true, false",
+            other
+        )),
+    }
+}
+
+/// SQL condition that hides archived datasets, for a datasets table `alias`.
+pub fn visibility_clause(alias: &str) -> String {
+    format!("{}.archived_at IS NULL", alias)
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+fn state(conn: &Connection, name: &str) -> rusqlite::Result<Option<ArchiveState>> {
+    conn.query_row(
+        "SELECT name, status, archived_at, archived_by FROM datasets
+         WHERE name = ?1 AND deleted_at IS NULL",
+        [name],
+        |row| {
+            Ok(ArchiveState {
+                name: row.get(0)?,
+                status: row.get(1)?,
+                archived_at: row.get(2)?,
+                archived_by: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Archive a live dataset. Archiving an archived dataset changes nothing.
+///
+/// Returns `None` if no live dataset has this name.
+pub fn archive_dataset(
+    conn: &Connection,
+    name: &str,
+    archived_by: Option<&str>,
+) -> rusqlite::Result<Option<ArchiveState>> {
+    conn.execute(
+        "UPDATE datasets SET status = ?2, archived_at = datetime('now'), archived_by = ?3
+         WHERE name = ?1 AND deleted_at IS NULL AND archived_at IS NULL",
+        params![name, STATUS_ARCHIVED, archived_by],
+    )?;
+    state(conn, name)
+}
+
+/// Return an archived dataset to use. Unarchiving a dataset in use changes
+/// nothing.
+///
+/// Returns `None` if no live dataset has this name.
+pub fn unarchive_dataset(conn: &Connection, name: &str) -> rusqlite::Result<Option<ArchiveState>> {
+    conn.execute(
+        "UPDATE datasets SET status = ?2, archived_at = NULL, archived_by = NULL
+         WHERE name = ?1 AND deleted_at IS NULL AND archived_at IS NOT NULL",
+        params![name, placeholders::STATUS_ACTIVE],
+    )?;
+    state(conn, name)
+}
+
+/// When each of the given datasets was archived, for the archived ones.
+pub fn archived_at_for(conn: &Connection, ids: &[i64]) -> rusqlite::Result<HashMap<i64, String>> {
+    let mut archived = HashMap::new();
+    if ids.is_empty() {
+        return Ok(archived);
+    }
+    let mut stmt = conn.prepare_cached(
+        "SELECT archived_at FROM datasets WHERE id = ?1 AND archived_at IS NOT NULL",
+    )?;
+    for id in ids {
+        if let Some(at) = stmt.query_row([id], |row| row.get(0)).optional()? {
+            archived.insert(*id, at);
+        }
+    }
+    Ok(archived)
+}
+
+/// Archive a dataset, hiding it from listings and search but keeping it and
+/// its lineage on record
+pub(crate) async fn post_dataset_archive(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<ArchiveState>, (StatusCode, Json<ErrorResponse>)> {
+    // Archiving retires a dataset; same permission as delete
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let archived = archive_dataset(&tx, &name, audit_context.api_key_id.as_deref())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| dataset_not_found(&name, request_id.0.clone()))?;
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Dataset archived");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset",
+            &name,
+            serde_json::json!({ "archived": false }),
+            serde_json::json!({ "archived": true }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(archived))
+}
+
+/// Return an archived dataset to listings and search
+pub(crate) async fn delete_dataset_archive(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<ArchiveState>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let restored = unarchive_dataset(&tx, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| dataset_not_found(&name, request_id.0.clone()))?;
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Dataset unarchived");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset",
+            &name,
+            serde_json::json!({ "archived": true }),
+            serde_json::json!({ "archived": false }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(restored))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_include_archived() {
+        assert_eq!(parse_include_archived(None), Ok(false));
+        assert_eq!(parse_include_archived(Some("true")), Ok(true));
+        assert!(parse_include_archived(Some("yes")).is_err());
+    }
+
+    #[test]
+    fn test_archive_lifecycle() {
+        let conn = setup_db();
+        let archived = archive_dataset(&conn, "orders", Some("alice"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(archived.status, STATUS_ARCHIVED);
+        assert_eq!(archived.archived_by.as_deref(), Some("alice"));
+        assert!(archived.archived_at.is_some());
+
+        // Archiving again keeps the original archival
+        let again = archive_dataset(&conn, "orders", Some("bob"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(again, archived);
+        assert_eq!(archived_at_for(&conn, &[1]).unwrap().len(), 1);
+
+        let restored = unarchive_dataset(&conn, "orders").unwrap().unwrap();
+        assert_eq!(restored.status, placeholders::STATUS_ACTIVE);
+        assert!(restored.archived_at.is_none());
+        assert!(archived_at_for(&conn, &[1]).unwrap().is_empty());
+
+        assert!(archive_dataset(&conn, "missing", None).unwrap().is_none());
+    }
+}
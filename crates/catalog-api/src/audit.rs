@@ -21,8 +21,20 @@
 //!
 //! - `METAFUSE_AUDIT_BUFFER_SIZE`: Max events in buffer (default: 1000)
 //! - `METAFUSE_AUDIT_FLUSH_INTERVAL_MS`: Flush interval in milliseconds (default: 1000)
-
+//! - `METAFUSE_AUDIT_DB_ENABLED`: Write events to `audit_log` (default: true); set to
+//!   `false` when a forwarder (see `audit_forwarder`) is the system of record
+
+use crate::audit_diff::FieldChange;
+use crate::external_url;
+use crate::external_url::PaginationLinks;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
@@ -58,6 +70,19 @@ impl AuditAction {
             AuditAction::Import => "import",
         }
     }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "create" => Some(AuditAction::Create),
+            "update" => Some(AuditAction::Update),
+            "delete" => Some(AuditAction::Delete),
+            "read" => Some(AuditAction::Read),
+            "search" => Some(AuditAction::Search),
+            "export" => Some(AuditAction::Export),
+            "import" => Some(AuditAction::Import),
+            _ => None,
+        }
+    }
 }
 
 /// Actor type (matches DB CHECK constraint)
@@ -106,6 +131,8 @@ pub struct AuditEvent {
     pub new_values: Option<serde_json::Value>,
     /// Additional context
     pub context: Option<serde_json::Value>,
+    /// When the event was recorded
+    pub occurred_at: chrono::DateTime<chrono::Utc>,
 }
 
 impl AuditEvent {
@@ -128,6 +155,7 @@ impl AuditEvent {
             old_values: None,
             new_values: Some(new_values),
             context: None,
+            occurred_at: chrono::Utc::now(),
         }
     }
 
@@ -151,6 +179,7 @@ impl AuditEvent {
             old_values: Some(old_values),
             new_values: Some(new_values),
             context: None,
+            occurred_at: chrono::Utc::now(),
         }
     }
 
@@ -173,6 +202,7 @@ impl AuditEvent {
             old_values: Some(old_values),
             new_values: None,
             context: None,
+            occurred_at: chrono::Utc::now(),
         }
     }
 
@@ -209,6 +239,8 @@ pub struct AuditConfig {
     pub buffer_size: usize,
     /// Flush interval in milliseconds
     pub flush_interval_ms: u64,
+    /// Whether events are written to the `audit_log` table
+    pub db_enabled: bool,
 }
 
 impl Default for AuditConfig {
@@ -222,6 +254,9 @@ impl Default for AuditConfig {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(DEFAULT_FLUSH_INTERVAL_MS),
+            db_enabled: std::env::var("METAFUSE_AUDIT_DB_ENABLED")
+                .map(|v| !matches!(v.to_lowercase().as_str(), "false" | "0" | "no"))
+                .unwrap_or(true),
         }
     }
 }
@@ -244,6 +279,16 @@ impl AuditLogger {
         (Self { sender }, receiver)
     }
 
+    /// Events queued for the writer task
+    pub fn backlog(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Maximum events the buffer holds before events are dropped
+    pub fn buffer_size(&self) -> usize {
+        self.sender.max_capacity()
+    }
+
     /// Log an audit event (non-blocking)
     ///
     /// If the buffer is full, the event is dropped and a warning is logged.
@@ -310,16 +355,31 @@ impl AuditLogger {
     }
 }
 
+/// Future returned by [`AuditSink::send`]
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// A destination audit batches are forwarded to alongside the database
+/// (e.g. a SIEM). Sinks do their own filtering.
+pub trait AuditSink: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    /// Deliver a batch of events
+    fn send<'a>(&'a self, events: &'a [AuditEvent]) -> SinkFuture<'a>;
+}
+
 /// Background task that writes audit events to the database
 ///
 /// This task:
 /// 1. Batches events from the channel
-/// 2. Writes them to the audit_log table
-/// 3. Handles failures gracefully (logs to tracing as fallback)
+/// 2. Forwards them to the configured sinks
+/// 3. Writes them to the audit_log table (unless `db_enabled` is off)
+/// 4. Handles failures gracefully (logs to tracing as fallback)
 pub async fn audit_writer_task(
     mut receiver: mpsc::Receiver<AuditEvent>,
     backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
     config: AuditConfig,
+    sinks: Vec<Arc<dyn AuditSink>>,
 ) {
     let flush_interval = std::time::Duration::from_millis(config.flush_interval_ms);
     let mut batch: Vec<AuditEvent> = Vec::with_capacity(100);
@@ -328,6 +388,8 @@ pub async fn audit_writer_task(
     info!(
         buffer_size = config.buffer_size,
         flush_interval_ms = config.flush_interval_ms,
+        db_enabled = config.db_enabled,
+        sinks = sinks.len(),
         "Audit writer task started"
     );
 
@@ -340,13 +402,13 @@ pub async fn audit_writer_task(
                         batch.push(e);
                         // Flush immediately if batch is getting large
                         if batch.len() >= 100 {
-                            flush_batch(&mut batch, &backend).await;
+                            flush_batch(&mut batch, &backend, &config, &sinks).await;
                         }
                     }
                     None => {
                         // Channel closed, flush remaining and exit
                         if !batch.is_empty() {
-                            flush_batch(&mut batch, &backend).await;
+                            flush_batch(&mut batch, &backend, &config, &sinks).await;
                         }
                         info!("Audit writer task shutting down");
                         break;
@@ -356,17 +418,19 @@ pub async fn audit_writer_task(
             // Periodic flush
             _ = interval.tick() => {
                 if !batch.is_empty() {
-                    flush_batch(&mut batch, &backend).await;
+                    flush_batch(&mut batch, &backend, &config, &sinks).await;
                 }
             }
         }
     }
 }
 
-/// Flush a batch of audit events to the database
+/// Flush a batch of audit events to the sinks and the database
 async fn flush_batch(
     batch: &mut Vec<AuditEvent>,
     backend: &Arc<metafuse_catalog_storage::DynCatalogBackend>,
+    config: &AuditConfig,
+    sinks: &[Arc<dyn AuditSink>],
 ) {
     if batch.is_empty() {
         return;
@@ -377,6 +441,22 @@ async fn flush_batch(
 
     debug!(count, "Flushing audit batch");
 
+    let mut forward_failed = false;
+    for sink in sinks {
+        if let Err(e) = sink.send(&events).await {
+            error!(sink = sink.name(), error = %e, count, "Failed to forward audit batch");
+            forward_failed = true;
+        }
+    }
+
+    if !config.db_enabled {
+        // Nothing else holds these events; keep them visible in the logs
+        if forward_failed {
+            log_events_as_fallback(&events, "forwarder failure");
+        }
+        return;
+    }
+
     // Get connection and write events
     match backend.get_connection().await {
         Ok(conn) => {
@@ -511,11 +591,20 @@ pub struct AuditLogEntry {
     pub entity_id: Option<String>,
     pub actor: Option<String>,
     pub actor_type: Option<String>,
+    /// Display name of the actor, from the user directory (see `users`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_name: Option<String>,
+    /// Teams of the actor, from the user directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_teams: Option<Vec<String>>,
     pub api_key_id: Option<i64>,
     pub request_id: Option<String>,
     pub client_ip: Option<String>,
     pub old_values: Option<serde_json::Value>,
     pub new_values: Option<serde_json::Value>,
+    /// Field-level changes of an update (see `audit_diff`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<Vec<FieldChange>>,
     pub context: Option<serde_json::Value>,
 }
 
@@ -526,6 +615,9 @@ pub struct AuditLogResponse {
     pub total: i64,
     pub limit: i64,
     pub offset: i64,
+    /// Self/next/prev page links (set by the HTTP handler)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<PaginationLinks>,
 }
 
 /// Query audit logs from the database
@@ -600,20 +692,34 @@ pub fn query_audit_logs(
             let old_values: Option<String> = row.get(10)?;
             let new_values: Option<String> = row.get(11)?;
             let context: Option<String> = row.get(12)?;
+            let action: String = row.get(2)?;
+            let old_values: Option<serde_json::Value> =
+                old_values.and_then(|s| serde_json::from_str(&s).ok());
+            let new_values: Option<serde_json::Value> =
+                new_values.and_then(|s| serde_json::from_str(&s).ok());
+            let changes = match (&old_values, &new_values) {
+                (Some(old), Some(new)) if action == AuditAction::Update.as_str() => {
+                    crate::audit_diff::diff(old, new)
+                }
+                _ => None,
+            };
 
             Ok(AuditLogEntry {
                 id: row.get(0)?,
                 timestamp: row.get(1)?,
-                action: row.get(2)?,
+                action,
                 entity_type: row.get(3)?,
                 entity_id: row.get(4)?,
                 actor: row.get(5)?,
                 actor_type: row.get(6)?,
+                actor_name: None,
+                actor_teams: None,
                 api_key_id: row.get(7)?,
                 request_id: row.get(8)?,
                 client_ip: row.get(9)?,
-                old_values: old_values.and_then(|s| serde_json::from_str(&s).ok()),
-                new_values: new_values.and_then(|s| serde_json::from_str(&s).ok()),
+                old_values,
+                new_values,
+                changes,
                 context: context.and_then(|s| serde_json::from_str(&s).ok()),
             })
         })?
@@ -624,9 +730,67 @@ pub fn query_audit_logs(
         total,
         limit,
         offset,
+        links: None,
     })
 }
 
+/// List audit logs with optional filtering
+pub(crate) async fn list_audit_logs(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    external_url: Option<Extension<external_url::ExternalUrl>>,
+    axum::extract::RawQuery(raw_query): axum::extract::RawQuery,
+    Query(params): Query<AuditQueryParams>,
+) -> Result<Json<AuditLogResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        entity_type = ?params.entity_type,
+        entity_id = ?params.entity_id,
+        action = ?params.action,
+        limit = ?params.limit,
+        offset = ?params.offset,
+        "Querying audit logs"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Run DB query in blocking task to avoid blocking async runtime
+    let req_id = request_id.0.clone();
+    let mut result = tokio::task::spawn_blocking(move || query_audit_logs(&conn, &params))
+        .await
+        .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+        .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    state.users.annotate_audit_entries(&mut result.entries);
+
+    let url = external_url.map(|e| e.0).unwrap_or_default();
+    result.links = Some(external_url::PaginationLinks::new(
+        &url,
+        "/api/v1/audit",
+        raw_query.as_deref(),
+        result.limit,
+        result.offset,
+        result.total,
+    ));
+
+    tracing::info!(
+        total = result.total,
+        returned = result.entries.len(),
+        "Audit logs query completed"
+    );
+
+    Ok(Json(result))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -864,4 +1028,53 @@ mod tests {
         assert_eq!(result.limit, 1);
         assert_eq!(result.offset, 0);
     }
+
+    #[test]
+    fn test_query_audit_logs_update_changes() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        let events = vec![
+            AuditEvent::create("dataset", "ds1", serde_json::json!({"owner": "a"}), "req-1"),
+            AuditEvent::update(
+                "dataset",
+                "ds1",
+                serde_json::json!({"name": "ds1", "owner": "a"}),
+                serde_json::json!({"name": "ds1", "owner": "b"}),
+                "req-2",
+            ),
+        ];
+        write_events_to_db(&conn, &events).unwrap();
+
+        let params = AuditQueryParams {
+            entity_type: None,
+            entity_id: Some("ds1".to_string()),
+            action: None,
+            actor: None,
+            request_id: None,
+            limit: None,
+            offset: None,
+        };
+        let result = query_audit_logs(&conn, &params).unwrap();
+        let update = result
+            .entries
+            .iter()
+            .find(|e| e.action == "update")
+            .unwrap();
+        assert_eq!(
+            update.changes,
+            Some(vec![FieldChange {
+                field: "owner".to_string(),
+                old: serde_json::json!("a"),
+                new: serde_json::json!("b"),
+            }])
+        );
+        let create = result
+            .entries
+            .iter()
+            .find(|e| e.action == "create")
+            .unwrap();
+        assert!(create.changes.is_none());
+    }
 }
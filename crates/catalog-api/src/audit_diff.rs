@@ -0,0 +1,120 @@
+//! Audit Diffs
+//!
+//! Update events store JSON snapshots of the entity before and after the
+//! change (`old_values`/`new_values`). [`diff`] turns a pair of snapshots into
+//! a field-level change list, computed on read by the audit API
+//! (`GET /api/v1/audit`) and the dataset timeline:
+//!
+//! ```json
+//! [{"field": "owner", "old": "sales", "new": "finance"}]
+//! ```
+//!
+//! - Nested objects are compared key by key, with dotted field names
+//!   (`custom_metadata.team`); arrays and scalars are compared whole
+//! - A field only present on one side is reported with `null` on the other
+//! - Entries that did not record both snapshots (an empty or missing side)
+//!   have no change list
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// One changed field of an update.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Field-level changes between two snapshots, in field order.
+///
+/// Returns `None` unless both snapshots are non-empty objects.
+pub fn diff(old: &Value, new: &Value) -> Option<Vec<FieldChange>> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) if !old.is_empty() && !new.is_empty() => {
+            let mut changes = Vec::new();
+            diff_objects("", old, new, &mut changes);
+            Some(changes)
+        }
+        _ => None,
+    }
+}
+
+fn diff_objects(
+    prefix: &str,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    changes: &mut Vec<FieldChange>,
+) {
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    for field in fields {
+        let name = if prefix.is_empty() {
+            field.clone()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        let old = old.get(field).unwrap_or(&Value::Null);
+        let new = new.get(field).unwrap_or(&Value::Null);
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => diff_objects(&name, old, new, changes),
+            (old, new) if old != new => changes.push(FieldChange {
+                field: name,
+                old: old.clone(),
+                new: new.clone(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff() {
+        let old = json!({
+            "name": "orders",
+            "owner": "sales",
+            "tags": ["a"],
+            "custom_metadata": {"team": "core", "tier": 1},
+        });
+        let new = json!({
+            "name": "orders",
+            "owner": "finance",
+            "tags": ["a", "b"],
+            "custom_metadata": {"team": "core", "sla": "daily"},
+            "domain": "payments",
+        });
+        let changes = diff(&old, &new).unwrap();
+        let fields: Vec<_> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "custom_metadata.sla",
+                "custom_metadata.tier",
+                "domain",
+                "owner",
+                "tags"
+            ]
+        );
+        assert_eq!(changes[1].old, json!(1));
+        assert_eq!(changes[1].new, Value::Null);
+        assert_eq!(
+            changes[3],
+            FieldChange {
+                field: "owner".to_string(),
+                old: json!("sales"),
+                new: json!("finance"),
+            }
+        );
+
+        assert_eq!(diff(&old, &old), Some(Vec::new()));
+        // Old values that were never recorded
+        assert_eq!(diff(&json!({}), &new), None);
+        assert_eq!(diff(&Value::Null, &new), None);
+    }
+}
@@ -0,0 +1,445 @@
+//! Audit Forwarding
+//!
+//! Forwards audit batches to a SIEM in near real time, in addition to (or
+//! instead of) the `audit_log` table. Two transports are supported:
+//!
+//! - **Syslog**: one CEF message per event, wrapped in RFC 5424 framing and
+//!   sent over UDP or TCP (RFC 6587 octet counting)
+//! - **HTTPS**: the batch as a JSON array, POSTed with a bearer token
+//!   (requires the `http-audit-forwarder` feature)
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_AUDIT_SYSLOG_ADDR`: syslog collector `host:port`
+//! - `METAFUSE_AUDIT_SYSLOG_PROTOCOL`: `udp` (default) or `tcp`
+//! - `METAFUSE_AUDIT_HTTP_URL`: HTTPS collector endpoint
+//! - `METAFUSE_AUDIT_HTTP_TOKEN`: bearer token for the HTTPS collector
+//! - `METAFUSE_AUDIT_FORWARD_ACTIONS`: comma-separated actions to forward (default: all)
+//! - `METAFUSE_AUDIT_FORWARD_ENTITY_TYPES`: comma-separated entity types to forward (default: all)
+//!
+//! Forwarding failures are logged and never block the database write. With
+//! `METAFUSE_AUDIT_DB_ENABLED=false` a failed batch is logged to tracing.
+
+use crate::audit::{AuditAction, AuditEvent, AuditSink, SinkFuture};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Timeout for connecting to and writing to a collector
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Syslog facility 13 ("log audit"), severity 6 (informational)
+const SYSLOG_PRIORITY: u8 = 13 * 8 + 6;
+
+/// Syslog transport protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyslogProtocol {
+    #[default]
+    Udp,
+    Tcp,
+}
+
+impl SyslogProtocol {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "udp" => Some(SyslogProtocol::Udp),
+            "tcp" => Some(SyslogProtocol::Tcp),
+            _ => None,
+        }
+    }
+}
+
+/// Which events get forwarded. Empty lists match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actions: Vec<AuditAction>,
+    pub entity_types: Vec<String>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        (self.actions.is_empty() || self.actions.contains(&event.action))
+            && (self.entity_types.is_empty() || self.entity_types.contains(&event.entity_type))
+    }
+
+    fn select<'a>(&self, events: &'a [AuditEvent]) -> Vec<&'a AuditEvent> {
+        events.iter().filter(|e| self.matches(e)).collect()
+    }
+}
+
+/// Audit forwarder configuration
+#[derive(Debug, Clone, Default)]
+pub struct AuditForwarderConfig {
+    /// Syslog collector `host:port`
+    pub syslog_addr: Option<String>,
+    pub syslog_protocol: SyslogProtocol,
+    /// HTTPS collector endpoint (only used with the `http-audit-forwarder` feature)
+    #[cfg_attr(not(feature = "http-audit-forwarder"), allow(dead_code))]
+    pub http_url: Option<String>,
+    #[cfg_attr(not(feature = "http-audit-forwarder"), allow(dead_code))]
+    pub http_token: Option<String>,
+    pub filter: AuditFilter,
+}
+
+impl AuditForwarderConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let env = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        let list = |key: &str| -> Vec<String> {
+            env(key)
+                .map(|v| {
+                    v.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let syslog_protocol = match env("METAFUSE_AUDIT_SYSLOG_PROTOCOL") {
+            Some(v) => SyslogProtocol::parse(&v).ok_or_else(|| {
+                format!(
+                    "Invalid METAFUSE_AUDIT_SYSLOG_PROTOCOL '{}': expected 'udp' or 'tcp'",
+                    v
+                )
+            })?,
+            None => SyslogProtocol::default(),
+        };
+
+        let http_url = env("METAFUSE_AUDIT_HTTP_URL");
+        #[cfg(not(feature = "http-audit-forwarder"))]
+        if http_url.is_some() {
+            return Err(
+                "METAFUSE_AUDIT_HTTP_URL requires the 'http-audit-forwarder' feature".to_string(),
+            );
+        }
+        if let Some(url) = &http_url {
+            if !url.starts_with("https://") && !is_loopback_url(url) {
+                return Err(format!(
+                    "METAFUSE_AUDIT_HTTP_URL '{}' must use https:// (plain http is allowed for localhost only)",
+                    url
+                ));
+            }
+        }
+
+        let actions = list("METAFUSE_AUDIT_FORWARD_ACTIONS")
+            .iter()
+            .map(|a| {
+                AuditAction::parse(&a.to_lowercase()).ok_or_else(|| {
+                    format!("Invalid action '{}' in METAFUSE_AUDIT_FORWARD_ACTIONS", a)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            syslog_addr: env("METAFUSE_AUDIT_SYSLOG_ADDR"),
+            syslog_protocol,
+            http_url,
+            http_token: env("METAFUSE_AUDIT_HTTP_TOKEN"),
+            filter: AuditFilter {
+                actions,
+                entity_types: list("METAFUSE_AUDIT_FORWARD_ENTITY_TYPES"),
+            },
+        })
+    }
+
+    /// Build the configured sinks (empty when forwarding is off).
+    pub fn build(&self) -> Vec<Arc<dyn AuditSink>> {
+        let mut sinks: Vec<Arc<dyn AuditSink>> = Vec::new();
+        if let Some(addr) = &self.syslog_addr {
+            sinks.push(Arc::new(SyslogForwarder {
+                addr: addr.clone(),
+                protocol: self.syslog_protocol,
+                hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string()),
+                filter: self.filter.clone(),
+            }));
+        }
+        #[cfg(feature = "http-audit-forwarder")]
+        if let Some(url) = &self.http_url {
+            sinks.push(Arc::new(http::HttpForwarder::new(
+                url.clone(),
+                self.http_token.clone(),
+                self.filter.clone(),
+            )));
+        }
+        sinks
+    }
+}
+
+fn is_loopback_url(url: &str) -> bool {
+    ["http://localhost", "http://127.0.0.1", "http://[::1]"]
+        .iter()
+        .any(|prefix| {
+            url.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '/']))
+        })
+}
+
+/// Forwards events as CEF over syslog.
+pub struct SyslogForwarder {
+    addr: String,
+    protocol: SyslogProtocol,
+    hostname: String,
+    filter: AuditFilter,
+}
+
+impl SyslogForwarder {
+    async fn deliver(&self, messages: Vec<String>) -> Result<(), String> {
+        match self.protocol {
+            SyslogProtocol::Udp => {
+                let target = tokio::net::lookup_host(&self.addr)
+                    .await
+                    .map_err(|e| format!("failed to resolve {}: {}", self.addr, e))?
+                    .next()
+                    .ok_or_else(|| format!("no address for {}", self.addr))?;
+                let bind = if target.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(bind)
+                    .await
+                    .map_err(|e| format!("failed to bind UDP socket: {}", e))?;
+                for message in messages {
+                    socket
+                        .send_to(message.as_bytes(), target)
+                        .await
+                        .map_err(|e| format!("send failed: {}", e))?;
+                }
+                Ok(())
+            }
+            SyslogProtocol::Tcp => {
+                let mut stream = TcpStream::connect(&self.addr)
+                    .await
+                    .map_err(|e| format!("failed to connect to {}: {}", self.addr, e))?;
+                let mut framed = String::new();
+                for message in messages {
+                    framed.push_str(&format!("{} {}", message.len(), message));
+                }
+                stream
+                    .write_all(framed.as_bytes())
+                    .await
+                    .map_err(|e| format!("write failed: {}", e))?;
+                stream
+                    .flush()
+                    .await
+                    .map_err(|e| format!("write failed: {}", e))
+            }
+        }
+    }
+}
+
+impl AuditSink for SyslogForwarder {
+    fn name(&self) -> &str {
+        "syslog"
+    }
+
+    fn send<'a>(&'a self, events: &'a [AuditEvent]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let messages: Vec<String> = self
+                .filter
+                .select(events)
+                .into_iter()
+                .map(|e| syslog_message(&self.hostname, e))
+                .collect();
+            if messages.is_empty() {
+                return Ok(());
+            }
+            tokio::time::timeout(FORWARD_TIMEOUT, self.deliver(messages))
+                .await
+                .map_err(|_| format!("timed out after {:?}", FORWARD_TIMEOUT))?
+        })
+    }
+}
+
+/// Wrap a CEF record in an RFC 5424 syslog header.
+fn syslog_message(hostname: &str, event: &AuditEvent) -> String {
+    format!(
+        "<{}>1 {} {} metafuse-api - audit - {}",
+        SYSLOG_PRIORITY,
+        event
+            .occurred_at
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        hostname,
+        to_cef(event)
+    )
+}
+
+/// Format an event as an ArcSight CEF record.
+pub fn to_cef(event: &AuditEvent) -> String {
+    let action = event.action.as_str();
+    let severity = match event.action {
+        AuditAction::Delete => 7,
+        AuditAction::Update | AuditAction::Import => 5,
+        AuditAction::Create | AuditAction::Export => 3,
+        AuditAction::Read | AuditAction::Search => 1,
+    };
+
+    let mut extensions = vec![
+        ("rt", event.occurred_at.timestamp_millis().to_string()),
+        ("act", action.to_string()),
+        ("cs1Label", "entityType".to_string()),
+        ("cs1", event.entity_type.clone()),
+        ("cs2Label", "requestId".to_string()),
+        ("cs2", event.request_id.clone()),
+        ("cs3Label", "actorType".to_string()),
+        ("cs3", event.actor_type.as_str().to_string()),
+    ];
+    if let Some(entity_id) = &event.entity_id {
+        extensions.push(("cs4Label", "entityId".to_string()));
+        extensions.push(("cs4", entity_id.clone()));
+    }
+    if let Some(actor) = &event.actor {
+        extensions.push(("suser", actor.clone()));
+    }
+    if let Some(ip) = &event.client_ip {
+        extensions.push(("src", ip.clone()));
+    }
+
+    let extensions = extensions
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key, cef_extension_escape(&value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "CEF:0|MetaFuse|Catalog|{}|{}|{}|{}|{}",
+        env!("CARGO_PKG_VERSION"),
+        cef_header_escape(&format!("{}:{}", action, event.entity_type)),
+        cef_header_escape(&format!("{} {}", action, event.entity_type)),
+        severity,
+        extensions
+    )
+}
+
+fn cef_header_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+fn cef_extension_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+#[cfg(feature = "http-audit-forwarder")]
+pub mod http {
+    //! Forwards audit batches to an HTTPS collector.
+
+    use super::{AuditFilter, FORWARD_TIMEOUT};
+    use crate::audit::{AuditEvent, AuditSink, SinkFuture};
+
+    /// POSTs each batch as a JSON array of events.
+    pub struct HttpForwarder {
+        client: reqwest::Client,
+        url: String,
+        token: Option<String>,
+        filter: AuditFilter,
+    }
+
+    impl HttpForwarder {
+        pub fn new(url: String, token: Option<String>, filter: AuditFilter) -> Self {
+            Self {
+                client: reqwest::Client::builder()
+                    .timeout(FORWARD_TIMEOUT)
+                    .build()
+                    .unwrap_or_default(),
+                url,
+                token,
+                filter,
+            }
+        }
+    }
+
+    impl AuditSink for HttpForwarder {
+        fn name(&self) -> &str {
+            "http"
+        }
+
+        fn send<'a>(&'a self, events: &'a [AuditEvent]) -> SinkFuture<'a> {
+            Box::pin(async move {
+                let events = self.filter.select(events);
+                if events.is_empty() {
+                    return Ok(());
+                }
+                let mut request = self.client.post(&self.url).json(&events);
+                if let Some(token) = &self.token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|e| format!("request failed: {}", e))?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err(format!("unexpected status {}", response.status()))
+                }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> AuditEvent {
+        AuditEvent::update(
+            "dataset",
+            "orders=v2|raw",
+            serde_json::json!({}),
+            serde_json::json!({}),
+            "req-1",
+        )
+        .with_actor("alice\\ops", crate::audit::ActorType::User)
+        .with_client_ip("10.0.0.7")
+    }
+
+    #[test]
+    fn test_cef_format_and_filter() {
+        let cef = to_cef(&event());
+        assert!(cef.starts_with("CEF:0|MetaFuse|Catalog|"));
+        assert!(cef.contains("|update:dataset|update dataset|5|"));
+        assert!(cef.contains("cs4=orders\\=v2|raw"));
+        assert!(cef.contains("suser=alice\\\\ops"));
+        assert!(cef.contains("src=10.0.0.7"));
+        assert_eq!(cef_header_escape("a|b\nc"), "a\\|b c");
+
+        let filter = AuditFilter {
+            actions: vec![AuditAction::Delete, AuditAction::Update],
+            entity_types: vec!["dataset_acl".to_string()],
+        };
+        assert!(!filter.matches(&event()));
+        let mut acl = event();
+        acl.entity_type = "dataset_acl".to_string();
+        assert!(filter.matches(&acl));
+        assert!(AuditFilter::default().matches(&event()));
+        assert!(is_loopback_url("http://localhost:8088/collector"));
+        assert!(!is_loopback_url("http://localhost.evil.com"));
+    }
+
+    #[tokio::test]
+    async fn test_syslog_udp_delivery() {
+        let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let forwarder = SyslogForwarder {
+            addr: collector.local_addr().unwrap().to_string(),
+            protocol: SyslogProtocol::Udp,
+            hostname: "catalog-1".to_string(),
+            filter: AuditFilter::default(),
+        };
+        forwarder.send(&[event()]).await.unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = collector.recv(&mut buf).await.unwrap();
+        let message = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(message.starts_with("<110>1 "));
+        assert!(message.contains(" catalog-1 metafuse-api - audit - CEF:0|"));
+    }
+}
@@ -0,0 +1,324 @@
+//! Auto-Tagging Rules
+//!
+//! CRUD for the rules that tag datasets on write (see
+//! `metafuse_catalog_core::auto_tagging`), and running them over the
+//! existing catalog.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::envelope;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::auto_tagging;
+
+/// Map an auto-tagging error to a response
+pub(crate) fn auto_tag_rule_error(
+    e: metafuse_catalog_core::CatalogError,
+    name: &str,
+    request_id: &str,
+) -> (StatusCode, Json<ErrorResponse>) {
+    match e {
+        metafuse_catalog_core::CatalogError::ValidationError(message) => {
+            bad_request(message, request_id.to_string())
+        }
+        e if e.to_string().contains("UNIQUE constraint failed") => bad_request(
+            format!("Auto-tag rule '{}' already exists", name),
+            request_id.to_string(),
+        ),
+        e => internal_error(e.to_string(), request_id.to_string()),
+    }
+}
+
+/// List auto-tagging rules with hit statistics
+pub(crate) async fn list_auto_tag_rules(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<auto_tagging::AutoTagRule>>, (StatusCode, Json<ErrorResponse>)>
+{
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, "Listing auto-tag rules");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rules = auto_tagging::list_rules(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(rules)))
+}
+
+/// Create an auto-tagging rule
+pub(crate) async fn create_auto_tag_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<auto_tagging::NewAutoTagRule>,
+) -> Result<(StatusCode, Json<auto_tagging::AutoTagRule>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %req.name, "Creating auto-tag rule");
+
+    req.validate()
+        .map_err(|e| auto_tag_rule_error(e, &req.name, &request_id.0))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rule = auto_tagging::create_rule(&conn, &req)
+        .map_err(|e| auto_tag_rule_error(e, &req.name, &request_id.0))?;
+
+    tracing::info!(name = %rule.name, id = rule.id, "Auto-tag rule created successfully");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "auto_tag_rule",
+            rule.id.to_string(),
+            serde_json::to_value(&rule).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Get an auto-tagging rule by ID
+pub(crate) async fn get_auto_tag_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(id): Path<i64>,
+) -> Result<Json<auto_tagging::AutoTagRule>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, id = %id, "Getting auto-tag rule");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    auto_tagging::get_rule(&conn, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .map(Json)
+        .ok_or_else(|| {
+            not_found(
+                format!("Auto-tag rule '{}' not found", id),
+                request_id.0.clone(),
+            )
+        })
+}
+
+/// Replace an auto-tagging rule's definition
+pub(crate) async fn update_auto_tag_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<auto_tagging::NewAutoTagRule>,
+) -> Result<Json<auto_tagging::AutoTagRule>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, id = %id, "Updating auto-tag rule");
+
+    req.validate()
+        .map_err(|e| auto_tag_rule_error(e, &req.name, &request_id.0))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rule = auto_tagging::update_rule(&conn, id, &req)
+        .map_err(|e| auto_tag_rule_error(e, &req.name, &request_id.0))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Auto-tag rule '{}' not found", id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    tracing::info!(id = %id, "Auto-tag rule updated successfully");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "auto_tag_rule",
+            id.to_string(),
+            serde_json::json!({}),
+            serde_json::to_value(&rule).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(rule))
+}
+
+/// Delete an auto-tagging rule (tags it already added are kept)
+pub(crate) async fn delete_auto_tag_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, id = %id, "Deleting auto-tag rule");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let deleted = auto_tagging::delete_rule(&conn, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if !deleted {
+        return Err(not_found(
+            format!("Auto-tag rule '{}' not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(id = %id, "Auto-tag rule deleted successfully");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "auto_tag_rule",
+            id.to_string(),
+            serde_json::json!({ "id": id }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Apply all active auto-tagging rules to every dataset
+pub(crate) async fn run_auto_tag_rules(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+) -> Result<Json<auto_tagging::AutoTagRunSummary>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, "Running auto-tag rules");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let summary = auto_tagging::apply_to_all(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(
+        tenant_id = %tenant_id,
+        datasets_matched = summary.datasets_matched,
+        tags_added = summary.tags_added,
+        domains_set = summary.domains_set,
+        "Auto-tag rules applied"
+    );
+
+    Ok(Json(summary))
+}
@@ -0,0 +1,396 @@
+//! Dataset Badges
+//!
+//! `GET /api/v1/datasets/{name}/badge.svg` renders a small SVG badge, like a
+//! CI status badge, for embedding in READMEs and wikis:
+//!
+//! ```markdown
+//! ![quality](https://catalog.example.com/api/v1/datasets/orders/badge.svg)
+//! ```
+//!
+//! | Metric | Value | Color |
+//! |--------|-------|-------|
+//! | `quality` (default) | latest overall quality score, e.g. `92%` | green at 90%, down to red below 50% |
+//! | `freshness` | age of the last update, e.g. `3h ago` | green within the SLA, red when stale, blue without an SLA |
+//!
+//! A dataset without quality scores or a readable update time gets a grey
+//! `unknown` badge. Hidden (ACL-protected) and missing datasets are 404s, as
+//! on every other dataset endpoint.
+//!
+//! Badges carry their own `Cache-Control`: `public` for anonymous requests
+//! so image proxies (such as GitHub's) can cache them, `private` otherwise.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_BADGE_ANONYMOUS`: set to `false` to require a caller identity
+//!   or credentials (default: `true`)
+//! - `METAFUSE_BADGE_MAX_AGE`: seconds badges may be cached (default: 300)
+
+use crate::error_codes::ErrorCode;
+use crate::freshness::{FreshnessCheckEntry, FreshnessStatus};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    bad_request, dataset_not_found, internal_error, require_dataset_access, AppState, DatasetPath,
+    ErrorResponse, RequestId,
+};
+use crate::{cache_control, dataset_acl, freshness, quality};
+use axum::extract::{Extension, Query, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+
+/// Default seconds a badge may be cached
+pub const DEFAULT_MAX_AGE: u32 = 300;
+
+const GREY: &str = "#9f9f9f";
+const BLUE: &str = "#007ec6";
+const BRIGHT_GREEN: &str = "#4c1";
+const GREEN: &str = "#97ca00";
+const YELLOW: &str = "#dfb317";
+const ORANGE: &str = "#fe7d37";
+const RED: &str = "#e05d44";
+
+/// Metric shown on a badge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadgeMetric {
+    #[default]
+    Quality,
+    Freshness,
+}
+
+impl BadgeMetric {
+    /// Parse the `metric` query parameter.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "quality" => Ok(BadgeMetric::Quality),
+            "freshness" => Ok(BadgeMetric::Freshness),
+            _ => Err(format!(
+                "Invalid metric '{}': expected 'quality' or 'freshness'",
+                value
+            )),
+        }
+    }
+
+    /// Left-hand badge text
+    pub fn label(&self) -> &'static str {
+        match self {
+            BadgeMetric::Quality => "quality",
+            BadgeMetric::Freshness => "freshness",
+        }
+    }
+}
+
+/// Badge configuration
+#[derive(Debug, Clone)]
+pub struct BadgeConfig {
+    /// Serve badges to callers without an identity or credentials
+    pub anonymous: bool,
+    pub max_age: u32,
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        Self {
+            anonymous: true,
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+impl BadgeConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let max_age = match std::env::var("METAFUSE_BADGE_MAX_AGE") {
+            Ok(v) => v
+                .parse()
+                .map_err(|_| format!("Invalid METAFUSE_BADGE_MAX_AGE '{}': expected seconds", v))?,
+            Err(_) => defaults.max_age,
+        };
+        Ok(Self {
+            anonymous: std::env::var("METAFUSE_BADGE_ANONYMOUS")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.anonymous),
+            max_age,
+        })
+    }
+
+    /// `Cache-Control` value for a badge response.
+    pub fn cache_control(&self, authenticated: bool) -> String {
+        let scope = if authenticated { "private" } else { "public" };
+        if self.max_age == 0 {
+            format!("{}, no-cache", scope)
+        } else {
+            format!("{}, max-age={}", scope, self.max_age)
+        }
+    }
+}
+
+/// Text and color of a badge's right-hand side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadgeValue {
+    pub message: String,
+    pub color: &'static str,
+}
+
+impl BadgeValue {
+    fn unknown() -> Self {
+        Self {
+            message: "unknown".to_string(),
+            color: GREY,
+        }
+    }
+
+    /// Value for an overall quality score in `[0, 1]`.
+    pub fn quality(score: Option<f64>) -> Self {
+        let Some(score) = score else {
+            return Self::unknown();
+        };
+        let color = match score {
+            s if s >= 0.9 => BRIGHT_GREEN,
+            s if s >= 0.8 => GREEN,
+            s if s >= 0.7 => YELLOW,
+            s if s >= 0.5 => ORANGE,
+            _ => RED,
+        };
+        Self {
+            message: format!("{:.0}%", score * 100.0),
+            color,
+        }
+    }
+
+    /// Value for a dataset's freshness check result.
+    pub fn freshness(entry: &FreshnessCheckEntry) -> Self {
+        let Some(age) = entry.age_seconds else {
+            return Self::unknown();
+        };
+        let age = format_age(age);
+        match entry.status {
+            FreshnessStatus::Fresh => Self {
+                message: age,
+                color: BRIGHT_GREEN,
+            },
+            FreshnessStatus::Stale => Self {
+                message: format!("stale, {}", age),
+                color: RED,
+            },
+            FreshnessStatus::NoSla => Self {
+                message: age,
+                color: BLUE,
+            },
+            FreshnessStatus::NotFound => Self::unknown(),
+        }
+    }
+}
+
+/// Compact age such as `45s ago` or `3d ago`.
+fn format_age(secs: i64) -> String {
+    let (value, unit) = match secs {
+        s if s < 60 => (s, "s"),
+        s if s < 3_600 => (s / 60, "m"),
+        s if s < 86_400 => (s / 3_600, "h"),
+        s => (s / 86_400, "d"),
+    };
+    format!("{}{} ago", value, unit)
+}
+
+/// Approximate rendered width of badge text (Verdana, 11px), plus padding.
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render a flat two-part badge.
+pub fn render(label: &str, value: &BadgeValue) -> String {
+    let label_width = text_width(label);
+    let message_width = text_width(&value.message);
+    let width = label_width + message_width;
+    let label = escape(label);
+    let message = escape(&value.message);
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}"><title>{label}: {message}</title><linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient><clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11"><text x="{label_x}" y="14">{label}</text><text x="{message_x}" y="14">{message}</text></g></svg>"##,
+        color = value.color,
+        label_x = label_width / 2,
+        message_x = label_width + message_width / 2,
+    )
+}
+
+/// Query parameters for dataset badges
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct BadgeQueryParams {
+    /// `quality` (default) or `freshness`
+    metric: Option<String>,
+}
+
+/// Render a dataset's quality or freshness as an SVG badge
+pub(crate) async fn get_dataset_badge(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    headers: HeaderMap,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<BadgeQueryParams>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let metric = params
+        .metric
+        .as_deref()
+        .map(BadgeMetric::parse)
+        .transpose()
+        .map_err(|e| bad_request(e, request_id.0.clone()))?
+        .unwrap_or_default();
+
+    let authenticated = tenant_backend.is_some()
+        || identity.as_ref().is_some_and(|e| e.0.user.is_some())
+        || cache_control::is_authenticated(&headers);
+    if !authenticated && !state.badges.anonymous {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Dataset badges require authentication".to_string(),
+                code: ErrorCode::Unauthorized,
+                request_id: request_id.0,
+            }),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let value = match metric {
+        BadgeMetric::Quality => {
+            let quality = quality::get_latest_quality(&conn, dataset_id, &name)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            BadgeValue::quality(quality.and_then(|q| q.scores.overall_score))
+        }
+        BadgeMetric::Freshness => {
+            let check = freshness::check_datasets(
+                &conn,
+                std::slice::from_ref(&name),
+                false,
+                chrono::Utc::now(),
+            )
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            BadgeValue::freshness(&check.datasets[0])
+        }
+    };
+
+    let mut response = render(metric.label(), &value).into_response();
+    let response_headers = response.headers_mut();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("image/svg+xml; charset=utf-8"),
+    );
+    if let Ok(value) = HeaderValue::from_str(&state.badges.cache_control(authenticated)) {
+        response_headers.insert(header::CACHE_CONTROL, value);
+    }
+    response_headers.insert(
+        header::VARY,
+        HeaderValue::from_static("Authorization, X-Tenant-ID"),
+    );
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(status: FreshnessStatus, age_seconds: Option<i64>) -> FreshnessCheckEntry {
+        FreshnessCheckEntry {
+            dataset: "orders".to_string(),
+            status,
+            ready: status == FreshnessStatus::Fresh,
+            last_updated: None,
+            age_seconds,
+            sla_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_badge_values() {
+        assert_eq!(
+            BadgeValue::quality(Some(0.923)),
+            BadgeValue {
+                message: "92%".to_string(),
+                color: BRIGHT_GREEN
+            }
+        );
+        assert_eq!(BadgeValue::quality(Some(0.75)).color, YELLOW);
+        assert_eq!(BadgeValue::quality(Some(0.2)).color, RED);
+        assert_eq!(BadgeValue::quality(None).message, "unknown");
+
+        let fresh = BadgeValue::freshness(&entry(FreshnessStatus::Fresh, Some(7_200)));
+        assert_eq!(
+            (fresh.message.as_str(), fresh.color),
+            ("2h ago", BRIGHT_GREEN)
+        );
+        let stale = BadgeValue::freshness(&entry(FreshnessStatus::Stale, Some(3 * 86_400)));
+        assert_eq!(
+            (stale.message.as_str(), stale.color),
+            ("stale, 3d ago", RED)
+        );
+        let no_sla = BadgeValue::freshness(&entry(FreshnessStatus::NoSla, Some(30)));
+        assert_eq!((no_sla.message.as_str(), no_sla.color), ("30s ago", BLUE));
+        assert_eq!(
+            BadgeValue::freshness(&entry(FreshnessStatus::Stale, None)).color,
+            GREY
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let svg = render("quality", &BadgeValue::quality(Some(0.5)));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"90\""));
+        assert!(svg.contains("<title>quality: 50%</title>"));
+        assert!(svg.contains(&format!("fill=\"{}\"", ORANGE)));
+
+        let svg = render(
+            "a<b",
+            &BadgeValue {
+                message: "\"x\" & y".to_string(),
+                color: GREY,
+            },
+        );
+        assert!(svg.contains("a&lt;b: &quot;x&quot; &amp; y"));
+    }
+
+    #[test]
+    fn test_metric_and_cache_control() {
+        assert_eq!(BadgeMetric::parse("freshness"), Ok(BadgeMetric::Freshness));
+        assert!(BadgeMetric::parse("size").is_err());
+
+        let config = BadgeConfig::default();
+        assert_eq!(config.cache_control(false), "public, max-age=300");
+        assert_eq!(config.cache_control(true), "private, max-age=300");
+        let config = BadgeConfig {
+            max_age: 0,
+            ..Default::default()
+        };
+        assert_eq!(config.cache_control(false), "public, no-cache");
+    }
+}
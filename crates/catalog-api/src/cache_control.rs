@@ -0,0 +1,388 @@
+//! Cache-Control Policy
+//!
+//! Sets `Cache-Control` on responses by endpoint class so browsers and CDNs can
+//! reuse unchanged responses instead of re-fetching them on every page view.
+//!
+//! | Class | Endpoints | Default |
+//! |-------|-----------|---------|
+//! | Search | `/api/v1/search`, `/api/v1/suggest` | `max-age=30` |
+//! | List | collection endpoints (`/api/v1/datasets`, `/api/v1/domains/{name}/datasets`, ...) | `max-age=30` |
+//! | Detail | single resources and their subresources (`/api/v1/datasets/{name}`, ...) | `no-cache` |
+//! | Sensitive | admin, audit, usage, health, metrics | `no-store` |
+//!
+//! Non-GET requests, error responses, and responses that already carry a
+//! `Cache-Control` header are not cached or are left unchanged.
+//!
+//! Detail responses change with every write and with the caller's dataset
+//! ACLs, so by default clients revalidate them on each use.
+//!
+//! Responses are `private` unless `METAFUSE_CACHE_CONTROL_SCOPE=public`. Even
+//! then, requests with credentials, a tenant, sandbox or identity header stay
+//! `private`, and `Vary` lists all of those headers, so shared caches never
+//! serve one caller's view of the catalog to another.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_CACHE_CONTROL_ENABLED`: set to `false` to omit the header entirely (default: `true`)
+//! - `METAFUSE_CACHE_CONTROL_SCOPE`: `private` (default) or `public`
+//! - `METAFUSE_CACHE_SEARCH_MAX_AGE`: seconds for search responses (default: 30)
+//! - `METAFUSE_CACHE_LIST_MAX_AGE`: seconds for list responses (default: 30)
+//! - `METAFUSE_CACHE_DETAIL_MAX_AGE`: seconds for detail responses (default: 0)
+//!
+//! A max-age of `0` sends `no-cache`, so clients revalidate every time.
+
+use crate::dataset_acl::IdentityConfig;
+use crate::sandbox::SANDBOX_HEADER;
+use axum::{
+    extract::{Extension, Request},
+    http::{header, HeaderMap, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// Default max-age for search responses, in seconds
+pub const DEFAULT_SEARCH_MAX_AGE: u32 = 30;
+
+/// Default max-age for list responses, in seconds
+pub const DEFAULT_LIST_MAX_AGE: u32 = 30;
+
+/// Default max-age for detail responses, in seconds (`no-cache`)
+pub const DEFAULT_DETAIL_MAX_AGE: u32 = 0;
+
+/// Route prefixes whose responses must never be stored
+const NO_STORE_PREFIXES: &[&str] = &[
+    "/api/v1/admin",
+    "/api/v1/audit",
+    "/api/v1/usage",
+    "/api/v1/tenant",
+    "/health",
+    "/metrics",
+];
+
+/// Route prefixes for search-style endpoints
+const SEARCH_PREFIXES: &[&str] = &["/api/v1/search", "/api/v1/suggest"];
+
+/// Collections whose `/{id}` routes are single resources
+const DETAIL_COLLECTIONS: &[&str] = &[
+    "datasets",
+    "domains",
+    "owners",
+    "glossary",
+    "contracts",
+    "refs",
+    "namespaces",
+    "fields",
+];
+
+/// Endpoint class that determines the caching policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointClass {
+    Search,
+    List,
+    Detail,
+    /// Admin, audit, and other responses that must never be stored
+    Sensitive,
+}
+
+impl EndpointClass {
+    /// Classify a request by method and route path.
+    ///
+    /// Only GET and HEAD are cacheable; everything else is `Sensitive`.
+    pub fn classify(method: &Method, path: &str) -> Self {
+        if method != Method::GET && method != Method::HEAD {
+            return EndpointClass::Sensitive;
+        }
+        let has_prefix = |prefix: &&str| {
+            path == *prefix
+                || path
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        };
+        if NO_STORE_PREFIXES.iter().any(has_prefix) {
+            return EndpointClass::Sensitive;
+        }
+        if SEARCH_PREFIXES.iter().any(has_prefix) {
+            return EndpointClass::Search;
+        }
+
+        let segments: Vec<&str> = path
+            .strip_prefix("/api/v1/")
+            .unwrap_or_default()
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        match segments.as_slice() {
+            // Nested listings such as /domains/{name}/datasets
+            [.., "datasets"] if segments.len() > 1 => EndpointClass::List,
+            [collection, _, ..] if DETAIL_COLLECTIONS.contains(collection) => EndpointClass::Detail,
+            _ => EndpointClass::List,
+        }
+    }
+}
+
+/// Cache-Control configuration
+#[derive(Debug, Clone)]
+pub struct CacheControlConfig {
+    pub enabled: bool,
+    /// Mark cacheable responses `public` for anonymous requests
+    pub public: bool,
+    pub search_max_age: u32,
+    pub list_max_age: u32,
+    pub detail_max_age: u32,
+    /// Identity headers read by the identity middleware, if configured
+    pub identity_headers: Vec<String>,
+}
+
+impl Default for CacheControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            public: false,
+            search_max_age: DEFAULT_SEARCH_MAX_AGE,
+            list_max_age: DEFAULT_LIST_MAX_AGE,
+            detail_max_age: DEFAULT_DETAIL_MAX_AGE,
+            identity_headers: Vec::new(),
+        }
+    }
+}
+
+impl CacheControlConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let max_age = |var: &str, default: u32| -> Result<u32, String> {
+            match std::env::var(var) {
+                Ok(v) => v
+                    .parse()
+                    .map_err(|_| format!("Invalid {} '{}': expected seconds", var, v)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let public = match std::env::var("METAFUSE_CACHE_CONTROL_SCOPE") {
+            Ok(v) => match v.to_lowercase().as_str() {
+                "public" => true,
+                "private" => false,
+                _ => {
+                    return Err(format!(
+                        "Invalid METAFUSE_CACHE_CONTROL_SCOPE '{}': expected 'private' or 'public'",
+                        v
+                    ))
+                }
+            },
+            Err(_) => defaults.public,
+        };
+
+        Ok(Self {
+            enabled: std::env::var("METAFUSE_CACHE_CONTROL_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.enabled),
+            public,
+            search_max_age: max_age("METAFUSE_CACHE_SEARCH_MAX_AGE", defaults.search_max_age)?,
+            list_max_age: max_age("METAFUSE_CACHE_LIST_MAX_AGE", defaults.list_max_age)?,
+            detail_max_age: max_age("METAFUSE_CACHE_DETAIL_MAX_AGE", defaults.detail_max_age)?,
+            identity_headers: defaults.identity_headers,
+        })
+    }
+
+    /// Treat the user and groups headers of `identity` as caller identity.
+    pub fn with_identity_headers(mut self, identity: &IdentityConfig) -> Self {
+        self.identity_headers = [&identity.user_header, &identity.groups_header]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        self
+    }
+
+    /// Headers that select which catalog a response shows, for `Vary`.
+    pub fn vary(&self) -> String {
+        let mut headers = vec!["Authorization", "X-Tenant-ID", "X-MetaFuse-Sandbox"];
+        headers.extend(self.identity_headers.iter().map(String::as_str));
+        headers.join(", ")
+    }
+
+    /// Whether the request identifies its caller or selects a tenant or sandbox.
+    fn is_personalized(&self, headers: &HeaderMap) -> bool {
+        is_authenticated(headers)
+            || headers.contains_key(SANDBOX_HEADER)
+            || self
+                .identity_headers
+                .iter()
+                .any(|name| headers.contains_key(name.as_str()))
+    }
+
+    /// Header value for a class, given whether the request was authenticated.
+    pub fn directive(&self, class: EndpointClass, authenticated: bool) -> String {
+        let max_age = match class {
+            EndpointClass::Search => self.search_max_age,
+            EndpointClass::List => self.list_max_age,
+            EndpointClass::Detail => self.detail_max_age,
+            EndpointClass::Sensitive => return "no-store".to_string(),
+        };
+        let scope = if self.public && !authenticated {
+            "public"
+        } else {
+            "private"
+        };
+        if max_age == 0 {
+            format!("{}, no-cache", scope)
+        } else {
+            format!("{}, max-age={}", scope, max_age)
+        }
+    }
+}
+
+/// Whether the request carries credentials or selects a tenant.
+pub fn is_authenticated(headers: &HeaderMap) -> bool {
+    headers.contains_key(header::AUTHORIZATION) || headers.contains_key("x-tenant-id")
+}
+
+/// Middleware that sets `Cache-Control` on responses.
+///
+/// Requires `Extension<Arc<CacheControlConfig>>`.
+pub async fn cache_control_middleware(
+    Extension(config): Extension<Arc<CacheControlConfig>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !config.enabled {
+        return next.run(req).await;
+    }
+
+    let class = EndpointClass::classify(req.method(), req.uri().path());
+    let authenticated = config.is_personalized(req.headers());
+
+    let mut response = next.run(req).await;
+    if response.headers().contains_key(header::CACHE_CONTROL) {
+        return response;
+    }
+
+    // Errors (404s, 429s, ...) must not be served from cache after they clear
+    let class = if response.status().is_success() || response.status().as_u16() == 304 {
+        class
+    } else {
+        EndpointClass::Sensitive
+    };
+    if let Ok(value) = HeaderValue::from_str(&config.directive(class, authenticated)) {
+        response.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
+    // Shared caches must not serve an anonymous response to an identified request
+    if config.public && class != EndpointClass::Sensitive {
+        if let Ok(value) = HeaderValue::from_str(&config.vary()) {
+            response.headers_mut().append(header::VARY, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let get = Method::GET;
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/search"),
+            EndpointClass::Search
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/datasets"),
+            EndpointClass::List
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/datasets/orders"),
+            EndpointClass::Detail
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/datasets/orders/schema"),
+            EndpointClass::Detail
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/domains/finance/datasets"),
+            EndpointClass::List
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/analytics/popular"),
+            EndpointClass::List
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/admin/trash"),
+            EndpointClass::Sensitive
+        );
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/audit"),
+            EndpointClass::Sensitive
+        );
+        // Prefix matches stop at segment boundaries
+        assert_eq!(
+            EndpointClass::classify(&get, "/api/v1/usagex"),
+            EndpointClass::List
+        );
+        assert_eq!(
+            EndpointClass::classify(&Method::POST, "/api/v1/datasets"),
+            EndpointClass::Sensitive
+        );
+    }
+
+    #[test]
+    fn test_directive() {
+        let config = CacheControlConfig::default();
+        assert_eq!(
+            config.directive(EndpointClass::Detail, false),
+            "private, no-cache"
+        );
+        assert_eq!(
+            config.directive(EndpointClass::Sensitive, false),
+            "no-store"
+        );
+
+        let config = CacheControlConfig {
+            public: true,
+            search_max_age: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.directive(EndpointClass::List, false),
+            "public, max-age=30"
+        );
+        assert_eq!(
+            config.directive(EndpointClass::List, true),
+            "private, max-age=30"
+        );
+        assert_eq!(
+            config.directive(EndpointClass::Search, false),
+            "public, no-cache"
+        );
+    }
+
+    #[test]
+    fn test_identity_headers() {
+        let config = CacheControlConfig::default();
+        assert_eq!(
+            config.vary(),
+            "Authorization, X-Tenant-ID, X-MetaFuse-Sandbox"
+        );
+
+        let config = config.with_identity_headers(&IdentityConfig {
+            user_header: Some("x-forwarded-user".to_string()),
+            groups_header: Some("x-forwarded-groups".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            config.vary(),
+            "Authorization, X-Tenant-ID, X-MetaFuse-Sandbox, x-forwarded-user, x-forwarded-groups"
+        );
+
+        let mut headers = HeaderMap::new();
+        assert!(!config.is_personalized(&headers));
+        headers.insert("x-forwarded-groups", HeaderValue::from_static("finance"));
+        assert!(config.is_personalized(&headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(SANDBOX_HEADER, HeaderValue::from_static("ci_42"));
+        assert!(config.is_personalized(&headers));
+    }
+}
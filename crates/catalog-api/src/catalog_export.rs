@@ -0,0 +1,224 @@
+//! Catalog Export / Import
+//!
+//! `POST /api/v1/export` serializes the catalog to a versioned bundle (JSON
+//! or NDJSON) and `POST /api/v1/import` loads one, e.g. to copy a catalog
+//! between environments. Both are admin-only.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_writes::{run_partial_write_hooks, spawn_post_commit_hooks};
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_admin_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, AppState, AuditContext, Caller, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use metafuse_catalog_core::bundle;
+use metafuse_catalog_core::hooks::{DatasetWrite, WriteOperation, WriteSource};
+use rusqlite::OptionalExtension;
+use serde::Deserialize;
+
+/// Largest bundle accepted by `POST /api/v1/import`
+pub(crate) const IMPORT_BODY_LIMIT: usize = 256 * 1024 * 1024;
+
+pub(crate) const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ExportQuery {
+    /// `json` (default) or `ndjson`
+    format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct ImportQuery {
+    strategy: Option<String>,
+}
+
+/// Serialize the catalog to a versioned bundle
+pub(crate) async fn export_catalog(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    // Bundles hold the whole catalog, so exports are admin-only
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let ndjson = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("ndjson") => true,
+        Some(other) => {
+            return Err(bad_request(
+                format!("Invalid format '{}'. Valid values: json, ndjson", other),
+                request_id.0.clone(),
+            ))
+        }
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let req_id = request_id.0.clone();
+    let bundle = tokio::task::spawn_blocking(move || bundle::export_catalog(&conn))
+        .await
+        .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+        .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        datasets = bundle.datasets.len(),
+        glossary_terms = bundle.glossary_terms.len(),
+        ndjson,
+        "Exported catalog"
+    );
+
+    if ndjson {
+        let body = bundle
+            .to_ndjson()
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        Ok(([(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)], body).into_response())
+    } else {
+        Ok(Json(bundle).into_response())
+    }
+}
+
+/// Restore a bundle from `POST /api/v1/export`, as JSON or NDJSON
+pub(crate) async fn import_catalog(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    Query(query): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Json<bundle::ImportSummary>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let strategy = bundle::ConflictStrategy::parse(query.strategy.as_deref().unwrap_or("skip"))
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let ndjson = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case(NDJSON_CONTENT_TYPE));
+    let parsed = if ndjson {
+        std::str::from_utf8(&body)
+            .map_err(|e| metafuse_catalog_core::CatalogError::SerializationError(e.to_string()))
+            .and_then(bundle::CatalogBundle::from_ndjson)
+    } else {
+        serde_json::from_slice::<bundle::CatalogBundle>(&body)
+            .map_err(|e| metafuse_catalog_core::CatalogError::SerializationError(e.to_string()))
+    };
+    let mut catalog_bundle =
+        parsed.map_err(|e| bad_request(format!("Invalid bundle: {}", e), request_id.0.clone()))?;
+    catalog_bundle
+        .check_version()
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Run pre-validate write hooks on each dataset the import writes
+    let mut writes = Vec::new();
+    for dataset in &mut catalog_bundle.datasets {
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM datasets WHERE name = ?1",
+                [&dataset.name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let operation = match (existing, strategy) {
+            (None, _) => WriteOperation::Create,
+            (Some(_), bundle::ConflictStrategy::Skip) => continue,
+            (Some(_), _) => WriteOperation::Update,
+        };
+        let intended = DatasetWrite {
+            path: Some(dataset.path.clone()),
+            format: Some(dataset.format.clone()),
+            description: dataset.description.clone(),
+            tenant: dataset.tenant.clone(),
+            domain: dataset.domain.clone(),
+            owner: dataset.owner.clone(),
+            tags: dataset.tags.clone(),
+            columns: dataset.fields.iter().map(|f| f.name.clone()).collect(),
+            ..DatasetWrite::new(operation, WriteSource::Api, &dataset.name)
+        };
+        let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+        if let Some(path) = &write.path {
+            dataset.path = path.clone();
+        }
+        if let Some(format) = &write.format {
+            dataset.format = format.clone();
+        }
+        dataset.description = write.description.clone();
+        dataset.tenant = write.tenant.clone();
+        dataset.domain = write.domain.clone();
+        dataset.owner = write.owner.clone();
+        dataset.tags = write.tags.clone();
+        writes.push(write);
+    }
+
+    let req_id = request_id.0.clone();
+    let summary = tokio::task::spawn_blocking(move || {
+        bundle::import_catalog(&conn, &catalog_bundle, strategy)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| match e {
+        metafuse_catalog_core::CatalogError::ValidationError(_) => {
+            bad_request(e.to_string(), req_id.clone())
+        }
+        e => internal_error(e.to_string(), req_id.clone()),
+    })?;
+
+    tracing::info!(
+        strategy = ?summary.strategy,
+        datasets_created = summary.datasets_created,
+        datasets_updated = summary.datasets_updated,
+        datasets_skipped = summary.datasets_skipped,
+        warnings = summary.warnings.len(),
+        "Imported catalog bundle"
+    );
+
+    for write in writes {
+        spawn_post_commit_hooks(&state.write_hooks, write);
+    }
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "catalog_import",
+            "catalog",
+            serde_json::to_value(&summary).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+    Ok(Json(summary))
+}
@@ -0,0 +1,431 @@
+//! Catalog Statistics
+//!
+//! Catalog-wide totals over time for dashboards: dataset count, total size,
+//! field count, lineage edges, and classification coverage.
+//!
+//! # Architecture
+//!
+//! Snapshots live in `catalog_stats_snapshots` (migration v1.35.0), one row
+//! per UTC day. [`stats_snapshot_task`] records the current totals
+//! periodically, replacing the day's row, so each past day keeps its last
+//! recorded state. `GET /api/v1/stats` also records today's snapshot, so
+//! tenant catalogs, which the background task does not visit, build up a
+//! history too.
+//!
+//! Totals count live datasets only: trashed datasets, their fields, and
+//! lineage edges touching them are left out.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_STATS_SNAPSHOT_INTERVAL_SECS`: how often totals are recorded
+//!   (default: 3600, 0 = never)
+
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::NaiveDate;
+use metafuse_catalog_core::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Default interval between snapshots
+pub const DEFAULT_SNAPSHOT_INTERVAL_SECS: u64 = 3600;
+
+/// Default days of history returned
+pub const DEFAULT_HISTORY_DAYS: u32 = 30;
+
+/// Most days of history returned
+pub const MAX_HISTORY_DAYS: u32 = 366;
+
+/// Catalog statistics configuration
+#[derive(Debug, Clone)]
+pub struct StatsConfig {
+    /// Seconds between snapshots; 0 disables the background task
+    pub snapshot_interval_secs: u64,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            snapshot_interval_secs: DEFAULT_SNAPSHOT_INTERVAL_SECS,
+        }
+    }
+}
+
+impl StatsConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_STATS_SNAPSHOT_INTERVAL_SECS`: seconds between snapshots (0 = never)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            snapshot_interval_secs: std::env::var("METAFUSE_STATS_SNAPSHOT_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.snapshot_interval_secs),
+        }
+    }
+
+    /// Whether the background task records snapshots
+    pub fn enabled(&self) -> bool {
+        self.snapshot_interval_secs > 0
+    }
+}
+
+/// Catalog-wide totals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CatalogStats {
+    pub dataset_count: i64,
+    /// Sum of known dataset sizes
+    pub total_size_bytes: i64,
+    pub field_count: i64,
+    pub lineage_edge_count: i64,
+    /// Fields with a classification other than `unknown`
+    pub classified_field_count: i64,
+    /// Share of fields classified; `None` without fields
+    pub classification_coverage: Option<f64>,
+}
+
+impl CatalogStats {
+    fn with_coverage(mut self) -> Self {
+        self.classification_coverage = (self.field_count > 0).then(|| {
+            let coverage = self.classified_field_count as f64 / self.field_count as f64;
+            (coverage * 10_000.0).round() / 10_000.0
+        });
+        self
+    }
+}
+
+/// Totals recorded on a day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsSnapshot {
+    /// UTC day (`YYYY-MM-DD`)
+    pub date: String,
+    #[serde(flatten)]
+    pub stats: CatalogStats,
+}
+
+/// Change in the totals over the history period
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct StatsChange {
+    pub dataset_count: i64,
+    pub total_size_bytes: i64,
+    pub field_count: i64,
+    pub lineage_edge_count: i64,
+    pub classified_field_count: i64,
+}
+
+impl StatsChange {
+    fn between(from: &CatalogStats, to: &CatalogStats) -> Self {
+        Self {
+            dataset_count: to.dataset_count - from.dataset_count,
+            total_size_bytes: to.total_size_bytes - from.total_size_bytes,
+            field_count: to.field_count - from.field_count,
+            lineage_edge_count: to.lineage_edge_count - from.lineage_edge_count,
+            classified_field_count: to.classified_field_count - from.classified_field_count,
+        }
+    }
+}
+
+/// Response for the catalog statistics endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsResponse {
+    pub current: CatalogStats,
+    /// Daily snapshots, oldest first
+    pub history: Vec<StatsSnapshot>,
+    /// `current` minus the oldest snapshot; `None` without history
+    pub change: Option<StatsChange>,
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Current totals of the catalog.
+pub fn compute(conn: &Connection) -> Result<CatalogStats> {
+    let classified = if table_exists(conn, "column_classifications")? {
+        "(SELECT COUNT(*) FROM fields f JOIN datasets d ON d.id = f.dataset_id
+          WHERE d.deleted_at IS NULL AND EXISTS (
+              SELECT 1 FROM column_classifications c
+              WHERE c.field_id = f.id AND c.classification != 'unknown'))"
+    } else {
+        "0"
+    };
+    let stats = conn.query_row(
+        &format!(
+            "SELECT
+                 (SELECT COUNT(*) FROM datasets WHERE deleted_at IS NULL),
+                 (SELECT COALESCE(SUM(size_bytes), 0) FROM datasets WHERE deleted_at IS NULL),
+                 (SELECT COUNT(*) FROM fields f JOIN datasets d ON d.id = f.dataset_id
+                  WHERE d.deleted_at IS NULL),
+                 (SELECT COUNT(*) FROM lineage l
+                  JOIN datasets u ON u.id = l.upstream_dataset_id
+                  JOIN datasets dn ON dn.id = l.downstream_dataset_id
+                  WHERE u.deleted_at IS NULL AND dn.deleted_at IS NULL),
+                 {}",
+            classified
+        ),
+        [],
+        |row| {
+            Ok(CatalogStats {
+                dataset_count: row.get(0)?,
+                total_size_bytes: row.get(1)?,
+                field_count: row.get(2)?,
+                lineage_edge_count: row.get(3)?,
+                classified_field_count: row.get(4)?,
+                classification_coverage: None,
+            })
+        },
+    )?;
+    Ok(stats.with_coverage())
+}
+
+/// Record the current totals as the snapshot for `date`, replacing any
+/// earlier snapshot of that day. Returns the current totals.
+pub fn record_snapshot(conn: &Connection, date: NaiveDate) -> Result<CatalogStats> {
+    let stats = compute(conn)?;
+    conn.execute(
+        "INSERT INTO catalog_stats_snapshots
+             (snapshot_date, dataset_count, total_size_bytes, field_count,
+              lineage_edge_count, classified_field_count, recorded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now'))
+         ON CONFLICT(snapshot_date) DO UPDATE SET
+             dataset_count = excluded.dataset_count,
+             total_size_bytes = excluded.total_size_bytes,
+             field_count = excluded.field_count,
+             lineage_edge_count = excluded.lineage_edge_count,
+             classified_field_count = excluded.classified_field_count,
+             recorded_at = excluded.recorded_at",
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            stats.dataset_count,
+            stats.total_size_bytes,
+            stats.field_count,
+            stats.lineage_edge_count,
+            stats.classified_field_count,
+        ],
+    )?;
+    Ok(stats)
+}
+
+/// Snapshots from `since` on, oldest first.
+pub fn history(conn: &Connection, since: NaiveDate) -> Result<Vec<StatsSnapshot>> {
+    let mut stmt = conn.prepare(
+        "SELECT snapshot_date, dataset_count, total_size_bytes, field_count,
+                lineage_edge_count, classified_field_count
+         FROM catalog_stats_snapshots
+         WHERE snapshot_date >= ?1
+         ORDER BY snapshot_date",
+    )?;
+    let snapshots = stmt
+        .query_map([since.format("%Y-%m-%d").to_string()], |row| {
+            Ok(StatsSnapshot {
+                date: row.get(0)?,
+                stats: CatalogStats {
+                    dataset_count: row.get(1)?,
+                    total_size_bytes: row.get(2)?,
+                    field_count: row.get(3)?,
+                    lineage_edge_count: row.get(4)?,
+                    classified_field_count: row.get(5)?,
+                    classification_coverage: None,
+                }
+                .with_coverage(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(snapshots)
+}
+
+/// Current totals and the last `days` days of history.
+///
+/// Records today's snapshot first.
+pub fn stats(conn: &Connection, today: NaiveDate, days: u32) -> Result<StatsResponse> {
+    let current = record_snapshot(conn, today)?;
+    let since = today - chrono::Duration::days(i64::from(days.saturating_sub(1)));
+    let history = history(conn, since)?;
+    let change = history
+        .first()
+        .map(|oldest| StatsChange::between(&oldest.stats, &current));
+    Ok(StatsResponse {
+        current,
+        history,
+        change,
+    })
+}
+
+/// Background task that records the day's snapshot periodically
+pub async fn stats_snapshot_task(
+    config: StatsConfig,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(config.snapshot_interval_secs);
+
+    info!(
+        interval_secs = config.snapshot_interval_secs,
+        "Catalog stats snapshot task started"
+    );
+
+    loop {
+        match backend.get_connection().await {
+            Ok(conn) => {
+                let today = chrono::Utc::now().date_naive();
+                if let Err(e) = record_snapshot(&conn, today) {
+                    error!(error = %e, "Failed to record catalog stats snapshot");
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for catalog stats snapshot");
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Query parameters for the catalog statistics endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct CatalogStatsQueryParams {
+    #[serde(default = "default_stats_days")]
+    days: u32,
+}
+
+pub(crate) fn default_stats_days() -> u32 {
+    DEFAULT_HISTORY_DAYS
+}
+
+/// Catalog-wide totals with their daily history
+pub(crate) async fn get_catalog_stats(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(params): Query<CatalogStatsQueryParams>,
+) -> std::result::Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if !(1..=MAX_HISTORY_DAYS).contains(&params.days) {
+        return Err(bad_request(
+            format!("days must be between 1 and {}", MAX_HISTORY_DAYS),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.0.clone();
+    let days = params.days;
+    let stats =
+        tokio::task::spawn_blocking(move || stats(&conn, chrono::Utc::now().date_naive(), days))
+            .await
+            .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+            .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    Ok(Json(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_dataset(conn: &Connection, name: &str, size_bytes: i64) -> i64 {
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, size_bytes, created_at, last_updated)
+             VALUES (?1, '/data', 'parquet', ?2, datetime('now'), datetime('now'))",
+            params![name, size_bytes],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn day(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_compute_counts_live_datasets() {
+        let conn = setup();
+        let orders = add_dataset(&conn, "orders", 100);
+        let mart = add_dataset(&conn, "mart", 50);
+        let trashed = add_dataset(&conn, "trashed", 1_000);
+        for (dataset_id, name) in [(orders, "id"), (orders, "email"), (trashed, "x")] {
+            conn.execute(
+                "INSERT INTO fields (dataset_id, name, data_type) VALUES (?1, ?2, 'string')",
+                params![dataset_id, name],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO column_classifications (field_id, classification) VALUES (2, 'pii')",
+            [],
+        )
+        .unwrap();
+        for (up, down) in [(orders, mart), (trashed, mart)] {
+            conn.execute(
+                "INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+                 VALUES (?1, ?2, datetime('now'))",
+                params![up, down],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "UPDATE datasets SET deleted_at = datetime('now') WHERE id = ?1",
+            [trashed],
+        )
+        .unwrap();
+
+        let stats = compute(&conn).unwrap();
+        assert_eq!(stats.dataset_count, 2);
+        assert_eq!(stats.total_size_bytes, 150);
+        assert_eq!(stats.field_count, 2);
+        assert_eq!(stats.lineage_edge_count, 1);
+        assert_eq!(stats.classified_field_count, 1);
+        assert_eq!(stats.classification_coverage, Some(0.5));
+    }
+
+    #[test]
+    fn test_history_and_change() {
+        let conn = setup();
+        add_dataset(&conn, "orders", 100);
+        record_snapshot(&conn, day("2025-11-01")).unwrap();
+        add_dataset(&conn, "clicks", 20);
+        record_snapshot(&conn, day("2025-11-10")).unwrap();
+        add_dataset(&conn, "mart", 5);
+
+        let response = stats(&conn, day("2025-11-20"), 30).unwrap();
+        let dates: Vec<&str> = response.history.iter().map(|s| s.date.as_str()).collect();
+        assert_eq!(dates, vec!["2025-11-01", "2025-11-10", "2025-11-20"]);
+        assert_eq!(response.current.dataset_count, 3);
+        let change = response.change.unwrap();
+        assert_eq!(change.dataset_count, 2);
+        assert_eq!(change.total_size_bytes, 25);
+
+        // Reads refresh today's snapshot; older days keep theirs
+        add_dataset(&conn, "late", 1);
+        let response = stats(&conn, day("2025-11-20"), 15).unwrap();
+        assert_eq!(response.history.len(), 2);
+        assert_eq!(response.history[0].stats.dataset_count, 2);
+        assert_eq!(response.history[1].stats.dataset_count, 4);
+        assert_eq!(response.change.unwrap().dataset_count, 2);
+    }
+}
@@ -16,8 +16,21 @@
 
 #![allow(dead_code)]
 
+use crate::i18n::Locale;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, bad_request, internal_error, not_found, AppState, DatasetPath,
+    ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::{dataset_acl, i18n, webhooks};
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_storage::DynCatalogBackend;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tracing::{info, warn};
 
 /// Classification types
@@ -336,21 +349,27 @@ impl From<serde_json::Error> for ClassificationError {
 // =============================================================================
 
 /// Store a column classification in the database
+///
+/// Reviewed classifications (verified or dismissed) are kept as they are.
 pub fn store_classification(
     conn: &rusqlite::Connection,
     field_id: i64,
     classification: &ColumnClassification,
 ) -> Result<i64, rusqlite::Error> {
     // First, check if a classification exists for this field
-    let existing: Option<i64> = conn
+    let existing: Option<(i64, bool)> = conn
         .query_row(
-            "SELECT id FROM column_classifications WHERE field_id = ?1",
+            "SELECT id, verified = 1 OR dismissed_at IS NOT NULL
+             FROM column_classifications WHERE field_id = ?1",
             [field_id],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
         .ok();
 
-    if let Some(existing_id) = existing {
+    if let Some((existing_id, reviewed)) = existing {
+        if reviewed {
+            return Ok(existing_id);
+        }
         // Update existing classification
         conn.execute(
             r#"
@@ -414,11 +433,12 @@ pub fn get_dataset_classifications(
             c.rule_id,
             c.verified,
             c.verified_by,
-            c.verified_at
+            c.verified_at,
+            c.dismissed_at
         FROM fields f
         LEFT JOIN column_classifications c ON c.field_id = f.id
         WHERE f.dataset_id = ?1
-        ORDER BY f.id
+        ORDER BY f.ordinal, f.id
         "#,
     )?;
 
@@ -430,6 +450,7 @@ pub fn get_dataset_classifications(
                 classification: classification_str
                     .map(|s| Classification::parse(&s))
                     .unwrap_or(Classification::Unknown),
+                classification_label: None,
                 category: row.get(2)?,
                 confidence: row.get(3)?,
                 source: row.get::<_, Option<String>>(4)?.map(|s| match s.as_str() {
@@ -443,7 +464,9 @@ pub fn get_dataset_classifications(
                     .map(|v| v == 1)
                     .unwrap_or(false),
                 verified_by: row.get(7)?,
+                verified_by_name: None,
                 verified_at: row.get(8)?,
+                dismissed_at: row.get(9)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
@@ -452,10 +475,18 @@ pub fn get_dataset_classifications(
 }
 
 /// Get all columns with PII classification
+///
+/// `visibility` is an extra SQL condition on the dataset alias `d` hiding
+/// datasets from the caller, with its bindings.
 pub fn get_pii_columns(
     conn: &rusqlite::Connection,
+    visibility: Option<(String, Vec<String>)>,
 ) -> Result<Vec<PiiColumnEntry>, rusqlite::Error> {
-    let mut stmt = conn.prepare(
+    let (visible, bindings) = match visibility {
+        Some((clause, values)) => (format!("AND {}", clause), values),
+        None => (String::new(), Vec::new()),
+    };
+    let mut stmt = conn.prepare(&format!(
         r#"
         SELECT
             d.name as dataset_name,
@@ -466,13 +497,14 @@ pub fn get_pii_columns(
         FROM column_classifications c
         JOIN fields f ON f.id = c.field_id
         JOIN datasets d ON d.id = f.dataset_id
-        WHERE c.classification = 'pii'
+        WHERE c.classification = 'pii' AND c.dismissed_at IS NULL AND d.deleted_at IS NULL {}
         ORDER BY d.name, f.name
         "#,
-    )?;
+        visible
+    ))?;
 
     let entries = stmt
-        .query_map([], |row| {
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
             Ok(PiiColumnEntry {
                 dataset_name: row.get(0)?,
                 field_name: row.get(1)?,
@@ -515,6 +547,8 @@ pub fn set_manual_classification(
                 verified = 1,
                 verified_by = ?3,
                 verified_at = datetime('now'),
+                dismissed_at = NULL,
+                dismissed_by = NULL,
                 updated_at = datetime('now')
             WHERE id = ?4
             "#,
@@ -548,6 +582,9 @@ pub fn set_manual_classification(
 pub struct ColumnClassificationEntry {
     pub field_name: String,
     pub classification: Classification,
+    /// Human-readable `classification`, in the request's language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classification_label: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -559,8 +596,14 @@ pub struct ColumnClassificationEntry {
     pub verified: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verified_by: Option<String>,
+    /// Display name of `verified_by`, from the user directory (see `users`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_by_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verified_at: Option<String>,
+    /// When the finding was dismissed as a false positive
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dismissed_at: Option<String>,
 }
 
 /// Response for dataset classifications endpoint
@@ -573,6 +616,17 @@ pub struct DatasetClassificationsResponse {
     pub unclassified_count: usize,
 }
 
+impl DatasetClassificationsResponse {
+    /// Add `classification_label` to each entry
+    pub fn with_labels(mut self, locale: &Locale) -> Self {
+        for entry in &mut self.classifications {
+            entry.classification_label =
+                Some(locale.label(&format!("classification.{}", entry.classification.as_str())));
+        }
+        self
+    }
+}
+
 /// Entry in PII columns response
 #[derive(Debug, Clone, Serialize)]
 pub struct PiiColumnEntry {
@@ -600,6 +654,427 @@ pub struct SetClassificationRequest {
     pub category: Option<String>,
 }
 
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Get classifications for a dataset's columns
+pub(crate) async fn get_dataset_classifications_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    locale: Option<Extension<i18n::Locale>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<DatasetClassificationsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset_name = %name, "Getting dataset classifications");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Run DB queries in blocking task to avoid blocking async runtime
+    let req_id = request_id.0.clone();
+    let dataset_name_clone = name.clone();
+
+    let mut response = tokio::task::spawn_blocking(move || {
+        // Look up dataset
+        let dataset: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT id, name FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+                [&dataset_name_clone],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+
+        let (dataset_id, dataset_name) = match dataset {
+            Some(d) => d,
+            None => return Err(format!("Dataset '{}' not found", dataset_name_clone)),
+        };
+
+        let classifications =
+            get_dataset_classifications(&conn, dataset_id).map_err(|e| e.to_string())?;
+
+        let pii_count = classifications
+            .iter()
+            .filter(|c| c.classification == Classification::Pii && c.dismissed_at.is_none())
+            .count();
+        let unclassified_count = classifications
+            .iter()
+            .filter(|c| c.classification == Classification::Unknown)
+            .count();
+
+        Ok(DatasetClassificationsResponse {
+            dataset_id,
+            dataset_name,
+            classifications,
+            pii_count,
+            unclassified_count,
+        })
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e: String| {
+        if e.contains("not found") {
+            not_found(e, req_id.clone())
+        } else {
+            internal_error(e, req_id.clone())
+        }
+    })?;
+
+    for entry in &mut response.classifications {
+        entry.verified_by_name = entry
+            .verified_by
+            .as_deref()
+            .and_then(|v| state.users.display_name(v));
+    }
+
+    tracing::info!(
+        dataset_name = %name,
+        pii_count = response.pii_count,
+        "Dataset classifications retrieved"
+    );
+
+    Ok(Json(
+        response.with_labels(&locale.map(|e| e.0).unwrap_or_default()),
+    ))
+}
+
+/// Scan a dataset for classifications
+pub(crate) async fn scan_dataset_classifications(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    locale: Option<Extension<i18n::Locale>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<DatasetClassificationsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::info!(tenant_id = %tenant_id, dataset_name = %name, "Scanning dataset for classifications");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    // Run heavy classification work in blocking task
+    let req_id = request_id.0.clone();
+    let dataset_name_clone = name.clone();
+
+    let (response, fields_scanned) = tokio::task::spawn_blocking(move || {
+        // Look up dataset
+        let dataset: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT id, name FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+                [&dataset_name_clone],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+
+        let (dataset_id, dataset_name) = match dataset {
+            Some(d) => d,
+            None => return Err(format!("Dataset '{}' not found", dataset_name_clone)),
+        };
+
+        // Load classification engine
+        let engine = ClassificationEngine::load_from_db(&conn).map_err(|e| e.to_string())?;
+
+        // Get fields for this dataset
+        let mut stmt = conn
+            .prepare("SELECT id, name, data_type FROM fields WHERE dataset_id = ?1")
+            .map_err(|e| e.to_string())?;
+
+        let fields: Vec<(i64, String, String)> = stmt
+            .query_map([dataset_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        let fields_count = fields.len();
+
+        // Scan and store classifications
+        let mut pii_count = 0;
+        let mut unclassified_count = 0;
+
+        for (field_id, field_name, data_type) in &fields {
+            let result = engine.classify_column(field_name, data_type);
+
+            if result.classification == Classification::Pii {
+                pii_count += 1;
+            } else if result.classification == Classification::Unknown {
+                unclassified_count += 1;
+            }
+
+            store_classification(&conn, *field_id, &result).map_err(|e| e.to_string())?;
+        }
+
+        // Get updated classifications
+        let classifications =
+            get_dataset_classifications(&conn, dataset_id).map_err(|e| e.to_string())?;
+
+        let response = DatasetClassificationsResponse {
+            dataset_id,
+            dataset_name,
+            classifications,
+            pii_count,
+            unclassified_count,
+        };
+
+        Ok((response, fields_count))
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e: String| {
+        if e.contains("not found") {
+            not_found(e, req_id.clone())
+        } else {
+            internal_error(e, req_id.clone())
+        }
+    })?;
+
+    tracing::info!(
+        dataset_name = %name,
+        pii_count = response.pii_count,
+        fields_scanned,
+        "Dataset classification scan completed"
+    );
+
+    Ok(Json(
+        response.with_labels(&locale.map(|e| e.0).unwrap_or_default()),
+    ))
+}
+
+/// Get all PII columns across all datasets
+pub(crate) async fn get_all_pii_columns(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+) -> Result<Json<PiiColumnsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, "Getting all PII columns");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+
+    // Run DB query in blocking task
+    let req_id = request_id.0.clone();
+    let response = tokio::task::spawn_blocking(move || {
+        let columns = get_pii_columns(&conn, visibility)?;
+        let verified_count = columns.iter().filter(|c| c.verified).count();
+
+        Ok::<_, rusqlite::Error>(PiiColumnsResponse {
+            total_pii_columns: columns.len(),
+            verified_count,
+            columns,
+        })
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        total_pii = response.total_pii_columns,
+        verified = response.verified_count,
+        "PII columns query completed"
+    );
+
+    Ok(Json(response))
+}
+
+/// Set a manual classification for a field
+pub(crate) async fn set_field_classification(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path(field_id): Path<i64>,
+    JsonBody(req): JsonBody<SetClassificationRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::info!(tenant_id = %tenant_id, field_id, classification = %req.classification, "Setting manual classification");
+
+    // The verifier is the calling user, or a placeholder without an identity
+    let verified_by = identity
+        .and_then(|e| e.0.user)
+        .unwrap_or_else(|| "api_user".to_string());
+    state
+        .users
+        .check_user(&verified_by)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Run DB operations in blocking task
+    let req_id = request_id.0.clone();
+    let classification_str = req.classification.clone();
+    let category = req.category.clone();
+    let verified_by_response = verified_by.clone();
+
+    let (conn, dataset, field) = tokio::task::spawn_blocking(move || {
+        // Verify field exists
+        let Some((dataset, field)) = conn
+            .query_row(
+                "SELECT d.name, f.name FROM fields f JOIN datasets d ON d.id = f.dataset_id
+                 WHERE f.id = ?1",
+                [field_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .ok()
+        else {
+            return Err(format!("Field {} not found", field_id));
+        };
+
+        let classification_type = Classification::parse(&classification_str);
+
+        set_manual_classification(
+            &conn,
+            field_id,
+            classification_type,
+            category.as_deref(),
+            &verified_by,
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok((conn, dataset, field))
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e: String| {
+        if e.contains("not found") {
+            not_found(e, req_id.clone())
+        } else {
+            internal_error(e, req_id.clone())
+        }
+    })?;
+
+    tracing::info!(field_id, "Manual classification set");
+
+    state.webhooks.notify(
+        &conn,
+        &backend,
+        tenant_id,
+        webhooks::WebhookEvent::ClassificationChanged,
+        serde_json::json!({
+            "name": dataset,
+            "field": field,
+            "field_id": field_id,
+            "classification": req.classification,
+            "category": req.category,
+        }),
+    );
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "field_id": field_id,
+        "classification": req.classification,
+        "verified_by": verified_by_response,
+    })))
+}
+
+/// Auto-classify a dataset's fields (background task)
+pub(crate) async fn auto_classify_dataset(
+    backend: &Arc<DynCatalogBackend>,
+    dataset_id: i64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use crate::classification::{Classification, ClassificationEngine};
+
+    let conn = backend.get_connection().await?;
+
+    // Load classification engine with rules
+    let engine = ClassificationEngine::load_from_db(&conn)?;
+
+    // Get fields for this dataset
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, data_type
+        FROM fields
+        WHERE dataset_id = ?1
+        "#,
+    )?;
+
+    let fields: Vec<(i64, String, String)> = stmt
+        .query_map([dataset_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    if fields.is_empty() {
+        tracing::debug!(dataset_id, "No fields to classify");
+        return Ok(());
+    }
+
+    let mut classified_count = 0;
+    let mut pii_count = 0;
+
+    for (field_id, field_name, data_type) in fields {
+        let classification = engine.classify_column(&field_name, &data_type);
+
+        // Store classification
+        store_classification(&conn, field_id, &classification)?;
+
+        classified_count += 1;
+        if classification.classification != Classification::Unknown
+            && classification.classification != Classification::Public
+        {
+            pii_count += 1;
+        }
+    }
+
+    tracing::info!(
+        dataset_id,
+        classified_count,
+        pii_count,
+        "Auto-classification completed"
+    );
+
+    Ok(())
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -893,6 +1368,19 @@ mod tests {
             results[0].verified_by,
             Some("admin@example.com".to_string())
         );
+
+        // A later scan doesn't overwrite the verified classification
+        let scanned = ColumnClassification {
+            column_name: "secret_key".to_string(),
+            classification: Classification::Sensitive,
+            category: None,
+            confidence: 0.7,
+            source: ClassificationSource::Rule,
+            rule_id: None,
+        };
+        store_classification(&conn, field_id, &scanned).unwrap();
+        let results = get_dataset_classifications(&conn, dataset_id).unwrap();
+        assert_eq!(results[0].classification, Classification::Confidential);
     }
 
     #[test]
@@ -942,7 +1430,7 @@ mod tests {
         store_classification(&conn, email_field_id, &classification).unwrap();
 
         // Get all PII columns
-        let pii_columns = get_pii_columns(&conn).unwrap();
+        let pii_columns = get_pii_columns(&conn, None).unwrap();
 
         assert_eq!(pii_columns.len(), 1);
         assert_eq!(pii_columns[0].dataset_name, "users");
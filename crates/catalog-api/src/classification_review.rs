@@ -0,0 +1,610 @@
+//! Bulk Classification Review
+//!
+//! After a scan, stewards review classification findings in bulk instead of
+//! one field at a time. A request selects findings with a [`ReviewFilter`]
+//! and applies one [`ReviewAction`] to all of them in a single transaction:
+//!
+//! - `verify`: confirm the finding as it is
+//! - `reclassify`: replace the classification (and optionally the category),
+//!   as a verified manual classification
+//! - `dismiss`: mark the finding as a false positive (migration v1.46.0)
+//!
+//! Verified and dismissed findings are reviewed: scans leave them alone, and
+//! filters skip them unless `include_reviewed` is set. Dismissed findings are
+//! left out of the PII column listing.
+//!
+//! A request reviews at most `limit` findings (default 500, max 1000) in
+//! dataset and field order; `remaining` counts the matching findings left for
+//! the next request. Findings on datasets whose ACL denies the caller write
+//! access are skipped, and each applied finding is audited.
+//!
+//! # Endpoints
+//!
+//! - `POST /api/v1/governance/classifications/bulk-verify` - Review findings in bulk
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::classification::Classification;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    accessible_dataset_id, bad_request, internal_error, AppState, AuditContext, ErrorResponse,
+    RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use crate::{dataset_acl, webhooks};
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Default number of findings reviewed per request
+pub const DEFAULT_LIMIT: usize = 500;
+
+/// Maximum number of findings reviewed per request
+pub const MAX_LIMIT: usize = 1000;
+
+/// Which findings to review
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReviewFilter {
+    /// Only findings on this dataset
+    pub dataset: Option<String>,
+    /// Only findings with this classification (e.g. `pii`)
+    pub classification: Option<String>,
+    /// Only findings with this category (e.g. `email`)
+    pub category: Option<String>,
+    /// Only findings with at least this confidence
+    pub min_confidence: Option<f64>,
+    /// Only findings with at most this confidence
+    pub max_confidence: Option<f64>,
+    /// Also review findings that were already verified or dismissed
+    #[serde(default)]
+    pub include_reviewed: bool,
+}
+
+/// What to do with the selected findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewAction {
+    Verify,
+    Reclassify,
+    Dismiss,
+}
+
+impl ReviewAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewAction::Verify => "verify",
+            ReviewAction::Reclassify => "reclassify",
+            ReviewAction::Dismiss => "dismiss",
+        }
+    }
+}
+
+/// Request to review findings in bulk
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkVerifyRequest {
+    #[serde(default)]
+    pub filter: ReviewFilter,
+    pub action: ReviewAction,
+    /// New classification, for `reclassify`
+    pub classification: Option<String>,
+    /// New category, for `reclassify`; the current one is kept when absent
+    pub category: Option<String>,
+    /// Maximum findings to review (default 500, max 1000)
+    pub limit: Option<usize>,
+}
+
+impl BulkVerifyRequest {
+    /// Check the request, returning the new classification for `reclassify`.
+    pub fn validate(&self) -> Result<Option<Classification>, String> {
+        let filter = &self.filter;
+        for confidence in [filter.min_confidence, filter.max_confidence]
+            .into_iter()
+            .flatten()
+        {
+            if !(0.0..=1.0).contains(&confidence) {
+                return Err(format!(
+                    "Invalid confidence {}: must be between 0.0 and 1.0",
+                    confidence
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (filter.min_confidence, filter.max_confidence) {
+            if min > max {
+                return Err(format!(
+                    "Invalid confidence range: min_confidence {} is greater than max_confidence {}",
+                    min, max
+                ));
+            }
+        }
+        if let Some(classification) = &filter.classification {
+            parse_classification(classification)?;
+        }
+        if let Some(limit) = self.limit {
+            if limit == 0 || limit > MAX_LIMIT {
+                return Err(format!(
+                    "Invalid limit {}: must be between 1 and {}",
+                    limit, MAX_LIMIT
+                ));
+            }
+        }
+
+        match (self.action, &self.classification) {
+            (ReviewAction::Reclassify, Some(classification)) => {
+                parse_classification(classification).map(Some)
+            }
+            (ReviewAction::Reclassify, None) => {
+                Err("Action 'reclassify' requires a classification".to_string())
+            }
+            (action, Some(_)) => Err(format!(
+                "Action '{}' does not take a classification",
+                action.as_str()
+            )),
+            (_, None) if self.category.is_some() => {
+                Err("Only action 'reclassify' takes a category".to_string())
+            }
+            (_, None) => Ok(None),
+        }
+    }
+}
+
+fn parse_classification(value: &str) -> Result<Classification, String> {
+    match Classification::parse(value) {
+        Classification::Unknown if !value.eq_ignore_ascii_case("unknown") => Err(format!(
+            "Invalid classification '{}'. Valid values: pii, sensitive, confidential, public, unknown",
+            value
+        )),
+        classification => Ok(classification),
+    }
+}
+
+/// Outcome for one finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewStatus {
+    Applied,
+    Skipped,
+}
+
+/// A reviewed finding
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewResult {
+    pub field_id: i64,
+    pub dataset_name: String,
+    pub field_name: String,
+    pub status: ReviewStatus,
+    /// Why the finding was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub previous_classification: Classification,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_category: Option<String>,
+    pub classification: Classification,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// Response of a bulk review
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkVerifyResponse {
+    pub action: ReviewAction,
+    pub applied: usize,
+    pub skipped: usize,
+    /// Matching findings beyond `limit`, left for another request
+    pub remaining: usize,
+    pub results: Vec<ReviewResult>,
+}
+
+struct Finding {
+    id: i64,
+    field_id: i64,
+    dataset_id: i64,
+    dataset_name: String,
+    field_name: String,
+    classification: Classification,
+    category: Option<String>,
+}
+
+/// Apply a review to the findings matching the request, in one transaction.
+///
+/// `visibility` is an extra SQL condition on the dataset alias `d` hiding
+/// datasets from the caller, with its bindings. Findings on datasets for
+/// which `can_write` is false are skipped.
+pub fn bulk_review(
+    conn: &Connection,
+    request: &BulkVerifyRequest,
+    reviewer: &str,
+    visibility: Option<(String, Vec<String>)>,
+    can_write: impl Fn(&Connection, i64) -> rusqlite::Result<bool>,
+) -> Result<BulkVerifyResponse, String> {
+    let reclassify_to = request.validate()?;
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let findings =
+        matching_findings(&tx, &request.filter, visibility).map_err(|e| e.to_string())?;
+    let remaining = findings.len().saturating_sub(limit);
+
+    let mut results = Vec::new();
+    for finding in findings.into_iter().take(limit) {
+        let mut result = ReviewResult {
+            field_id: finding.field_id,
+            dataset_name: finding.dataset_name,
+            field_name: finding.field_name,
+            status: ReviewStatus::Applied,
+            reason: None,
+            previous_classification: finding.classification,
+            previous_category: finding.category.clone(),
+            classification: finding.classification,
+            category: finding.category,
+        };
+        if !can_write(&tx, finding.dataset_id).map_err(|e| e.to_string())? {
+            result.status = ReviewStatus::Skipped;
+            result.reason = Some("Write access denied by the dataset ACL".to_string());
+            results.push(result);
+            continue;
+        }
+
+        match request.action {
+            ReviewAction::Verify => tx.execute(
+                "UPDATE column_classifications SET
+                    verified = 1, verified_by = ?2, verified_at = datetime('now'),
+                    dismissed_at = NULL, dismissed_by = NULL, updated_at = datetime('now')
+                 WHERE id = ?1",
+                rusqlite::params![finding.id, reviewer],
+            ),
+            ReviewAction::Reclassify => {
+                let classification = reclassify_to.unwrap_or(finding.classification);
+                if request.category.is_some() {
+                    result.category = request.category.clone();
+                }
+                result.classification = classification;
+                tx.execute(
+                    "UPDATE column_classifications SET
+                        classification = ?2, category = ?3, confidence = 1.0, source = 'manual',
+                        verified = 1, verified_by = ?4, verified_at = datetime('now'),
+                        dismissed_at = NULL, dismissed_by = NULL, updated_at = datetime('now')
+                     WHERE id = ?1",
+                    rusqlite::params![
+                        finding.id,
+                        classification.as_str(),
+                        result.category,
+                        reviewer
+                    ],
+                )
+            }
+            ReviewAction::Dismiss => tx.execute(
+                "UPDATE column_classifications SET
+                    verified = 0, verified_by = NULL, verified_at = NULL,
+                    dismissed_at = datetime('now'), dismissed_by = ?2,
+                    updated_at = datetime('now')
+                 WHERE id = ?1",
+                rusqlite::params![finding.id, reviewer],
+            ),
+        }
+        .map_err(|e| e.to_string())?;
+        results.push(result);
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let applied = results
+        .iter()
+        .filter(|r| r.status == ReviewStatus::Applied)
+        .count();
+    Ok(BulkVerifyResponse {
+        action: request.action,
+        applied,
+        skipped: results.len() - applied,
+        remaining,
+        results,
+    })
+}
+
+fn matching_findings(
+    conn: &Connection,
+    filter: &ReviewFilter,
+    visibility: Option<(String, Vec<String>)>,
+) -> rusqlite::Result<Vec<Finding>> {
+    let mut sql = String::from(
+        "SELECT c.id, c.field_id, d.id, d.name, f.name, c.classification, c.category
+         FROM column_classifications c
+         JOIN fields f ON f.id = c.field_id
+         JOIN datasets d ON d.id = f.dataset_id
+         WHERE d.deleted_at IS NULL",
+    );
+    let mut bindings: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    if !filter.include_reviewed {
+        sql.push_str(" AND c.verified = 0 AND c.dismissed_at IS NULL");
+    }
+    if let Some(dataset) = &filter.dataset {
+        sql.push_str(" AND d.name = ?");
+        bindings.push(Box::new(dataset.clone()));
+    }
+    if let Some(classification) = &filter.classification {
+        sql.push_str(" AND c.classification = ?");
+        bindings.push(Box::new(classification.to_lowercase()));
+    }
+    if let Some(category) = &filter.category {
+        sql.push_str(" AND c.category = ?");
+        bindings.push(Box::new(category.clone()));
+    }
+    if let Some(min) = filter.min_confidence {
+        sql.push_str(" AND c.confidence >= ?");
+        bindings.push(Box::new(min));
+    }
+    if let Some(max) = filter.max_confidence {
+        sql.push_str(" AND c.confidence <= ?");
+        bindings.push(Box::new(max));
+    }
+    if let Some((clause, values)) = visibility {
+        sql.push_str(" AND ");
+        sql.push_str(&clause);
+        bindings.extend(
+            values
+                .into_iter()
+                .map(|v| Box::new(v) as Box<dyn rusqlite::ToSql>),
+        );
+    }
+    sql.push_str(" ORDER BY d.name, f.ordinal, f.id");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let findings = stmt
+        .query_map(params_from_iter(bindings.iter()), |row| {
+            Ok(Finding {
+                id: row.get(0)?,
+                field_id: row.get(1)?,
+                dataset_id: row.get(2)?,
+                dataset_name: row.get(3)?,
+                field_name: row.get(4)?,
+                classification: Classification::parse(&row.get::<_, String>(5)?),
+                category: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(findings)
+}
+
+/// Verify, reclassify, or dismiss classification findings in bulk
+pub(crate) async fn bulk_verify_classifications(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    JsonBody(req): JsonBody<BulkVerifyRequest>,
+) -> Result<Json<BulkVerifyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::info!(
+        tenant_id = %tenant_id,
+        action = req.action.as_str(),
+        dataset = ?req.filter.dataset,
+        "Reviewing classifications in bulk"
+    );
+
+    req.validate()
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let reviewer = identity
+        .user
+        .clone()
+        .unwrap_or_else(|| "api_user".to_string());
+    state
+        .users
+        .check_user(&reviewer)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.clone();
+    let action = req.action;
+    let (conn, response) = tokio::task::spawn_blocking(move || {
+        if let Some(dataset) = &req.filter.dataset {
+            accessible_dataset_id(
+                &conn,
+                dataset,
+                Some(&identity),
+                dataset_acl::AclPermission::Write,
+                &req_id,
+            )?;
+        }
+        let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+        let response = bulk_review(&conn, &req, &reviewer, visibility, |conn, id| {
+            dataset_acl::check(conn, id, &identity, dataset_acl::AclPermission::Write)
+        })
+        .map_err(|e| internal_error(e, req_id.0.clone()))?;
+        Ok((conn, response))
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), request_id.0.clone()))??;
+
+    tracing::info!(
+        action = action.as_str(),
+        applied = response.applied,
+        skipped = response.skipped,
+        remaining = response.remaining,
+        "Bulk classification review completed"
+    );
+
+    for result in &response.results {
+        if result.status != ReviewStatus::Applied {
+            continue;
+        }
+
+        // Emit audit event (non-blocking)
+        #[cfg(feature = "audit")]
+        {
+            let event = audit::AuditEvent::update(
+                "column_classification",
+                result.field_id.to_string(),
+                serde_json::json!({
+                    "dataset": result.dataset_name,
+                    "field": result.field_name,
+                    "classification": result.previous_classification,
+                    "category": result.previous_category,
+                }),
+                serde_json::json!({
+                    "dataset": result.dataset_name,
+                    "field": result.field_name,
+                    "classification": result.classification,
+                    "category": result.category,
+                    "review": action,
+                }),
+                &request_id.0,
+            );
+            state.audit_logger.log(audit_context.enrich_event(event));
+        }
+
+        if action != ReviewAction::Verify {
+            state.webhooks.notify(
+                &conn,
+                &backend,
+                tenant_id,
+                webhooks::WebhookEvent::ClassificationChanged,
+                serde_json::json!({
+                    "name": result.dataset_name,
+                    "field": result.field_name,
+                    "field_id": result.field_id,
+                    "classification": result.classification,
+                    "category": result.category,
+                    "review": action,
+                }),
+            );
+        }
+    }
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+                ('users', '/users', 'delta', datetime('now'), datetime('now')),
+                ('orders', '/orders', 'delta', datetime('now'), datetime('now'));
+             INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES
+                (1, 'email', 'STRING', 1), (1, 'phone', 'STRING', 1),
+                (1, 'zip', 'STRING', 1), (2, 'buyer_email', 'STRING', 1);
+             INSERT INTO column_classifications (field_id, classification, category, confidence)
+             VALUES (1, 'pii', 'email', 0.95), (2, 'pii', 'phone', 0.9),
+                    (3, 'pii', 'address', 0.4), (4, 'pii', 'email', 0.85);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn request(json: serde_json::Value) -> BulkVerifyRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = request(serde_json::json!({
+            "action": "reclassify", "classification": "sensitive",
+            "filter": {"min_confidence": 0.2, "max_confidence": 0.5}
+        }));
+        assert_eq!(valid.validate(), Ok(Some(Classification::Sensitive)));
+
+        for invalid in [
+            serde_json::json!({"action": "reclassify"}),
+            serde_json::json!({"action": "verify", "classification": "pii"}),
+            serde_json::json!({"action": "dismiss", "category": "email"}),
+            serde_json::json!({"action": "reclassify", "classification": "secret"}),
+            serde_json::json!({"action": "verify", "filter": {"min_confidence": 1.5}}),
+            serde_json::json!({"action": "verify", "filter": {"min_confidence": 0.8, "max_confidence": 0.2}}),
+            serde_json::json!({"action": "verify", "limit": 0}),
+        ] {
+            assert!(request(invalid).validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_bulk_review() {
+        let conn = setup_db();
+        let allow_all = |_: &Connection, _: i64| Ok(true);
+
+        // Verify confident email findings, one at a time
+        let verify = request(serde_json::json!({
+            "action": "verify", "limit": 1,
+            "filter": {"category": "email", "min_confidence": 0.8}
+        }));
+        let response = bulk_review(&conn, &verify, "ana", None, allow_all).unwrap();
+        assert_eq!(response.applied, 1);
+        assert_eq!(response.remaining, 1);
+        assert_eq!(response.results[0].dataset_name, "orders");
+        let response = bulk_review(&conn, &verify, "ana", None, allow_all).unwrap();
+        assert_eq!(response.results[0].field_name, "email");
+        assert_eq!(response.remaining, 0);
+
+        // Dismiss low-confidence findings on users
+        let dismiss = request(serde_json::json!({
+            "action": "dismiss", "filter": {"dataset": "users", "max_confidence": 0.5}
+        }));
+        let response = bulk_review(&conn, &dismiss, "ana", None, allow_all).unwrap();
+        assert_eq!(response.applied, 1);
+        assert_eq!(response.results[0].field_name, "zip");
+        let dismissed_by: Option<String> = conn
+            .query_row(
+                "SELECT dismissed_by FROM column_classifications WHERE field_id = 3",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(dismissed_by.as_deref(), Some("ana"));
+
+        // Reclassify the rest; datasets the caller can't write are skipped
+        let reclassify = request(serde_json::json!({
+            "action": "reclassify", "classification": "sensitive",
+            "filter": {"include_reviewed": true, "classification": "pii", "min_confidence": 0.85}
+        }));
+        let response = bulk_review(&conn, &reclassify, "bo", None, |_, dataset_id| {
+            Ok(dataset_id == 1)
+        })
+        .unwrap();
+        assert_eq!(response.applied, 2);
+        assert_eq!(response.skipped, 1);
+        let skipped = response
+            .results
+            .iter()
+            .find(|r| r.status == ReviewStatus::Skipped)
+            .unwrap();
+        assert_eq!(skipped.dataset_name, "orders");
+        assert_eq!(skipped.classification, Classification::Pii);
+        let phone = response
+            .results
+            .iter()
+            .find(|r| r.field_name == "phone")
+            .unwrap();
+        assert_eq!(phone.classification, Classification::Sensitive);
+        assert_eq!(phone.category.as_deref(), Some("phone"));
+
+        // Nothing unreviewed is left
+        let all = request(serde_json::json!({"action": "verify"}));
+        let response = bulk_review(&conn, &all, "ana", None, allow_all).unwrap();
+        assert!(response.results.is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! Dataset Consumers
+//!
+//! Services, reports, and other readers outside the catalog that rely on a
+//! dataset. Lineage only knows consumers that are themselves datasets; the
+//! rest register here with a contact, how often they read, and the SLA they
+//! expect, so the people who would be hurt by a change can be found.
+//!
+//! # Architecture
+//!
+//! Consumers live in `dataset_consumers` (migration v1.42.0), one row per
+//! dataset and consumer name. Registering a consumer again replaces its
+//! details. Consumers are removed with their dataset.
+//!
+//! Registered consumers show up in:
+//! - impact analysis, for the dataset and every readable downstream dataset
+//! - `deprecation` watch notifications, for the deprecated dataset
+//!
+//! # Endpoints
+//!
+//! - `POST /api/v1/datasets/{name}/consumers` - Register or update a consumer
+//! - `GET /api/v1/datasets/{name}/consumers` - List a dataset's consumers
+//! - `DELETE /api/v1/datasets/{name}/consumers?consumer=` - Unregister a consumer
+
+#[cfg(feature = "audit")]
+use crate::audit;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    accessible_dataset_id, bad_request, internal_error, not_found, AppState, AuditContext, Caller,
+    DatasetPath, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::{dataset_acl, envelope};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of a consumer name
+pub const MAX_NAME_LEN: usize = 256;
+
+/// Accepted read frequencies
+pub const READ_FREQUENCIES: &[&str] = &[
+    "continuous",
+    "hourly",
+    "daily",
+    "weekly",
+    "monthly",
+    "ad_hoc",
+];
+
+/// A consumer of a dataset outside the catalog.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatasetConsumer {
+    pub name: String,
+    pub contact: Option<String>,
+    pub read_frequency: Option<String>,
+    pub sla: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request to register a consumer
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterConsumerRequest {
+    pub name: String,
+    /// Email, Slack channel, or on-call alias
+    pub contact: Option<String>,
+    /// One of [`READ_FREQUENCIES`]
+    pub read_frequency: Option<String>,
+    /// Expectation in the consumer's words, e.g. "refreshed by 06:00 UTC"
+    pub sla: Option<String>,
+}
+
+/// Validate a registration.
+pub fn validate(req: &RegisterConsumerRequest) -> Result<(), String> {
+    if req.name.trim().is_empty() {
+        return Err("Consumer name cannot be empty".to_string());
+    }
+    if req.name.len() > MAX_NAME_LEN {
+        return Err(format!(
+            "Consumer name too long: {} > {} characters",
+            req.name.len(),
+            MAX_NAME_LEN
+        ));
+    }
+    if req.name.chars().any(char::is_control) {
+        return Err("Consumer name cannot contain control characters".to_string());
+    }
+    if let Some(frequency) = &req.read_frequency {
+        if !READ_FREQUENCIES.contains(&frequency.as_str()) {
+            return Err(format!(
+                "Invalid read_frequency '{}'. Must be one of: {}",
+                frequency,
+                READ_FREQUENCIES.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+fn consumer_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DatasetConsumer> {
+    Ok(DatasetConsumer {
+        name: row.get(0)?,
+        contact: row.get(1)?,
+        read_frequency: row.get(2)?,
+        sla: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// Register a consumer, replacing the details of one with the same name.
+/// Returns the consumer and whether it is new.
+pub fn register(
+    conn: &Connection,
+    dataset_id: i64,
+    req: &RegisterConsumerRequest,
+) -> rusqlite::Result<(DatasetConsumer, bool)> {
+    let existed = conn
+        .query_row(
+            "SELECT 1 FROM dataset_consumers WHERE dataset_id = ?1 AND name = ?2",
+            params![dataset_id, req.name],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    let consumer = conn.query_row(
+        "INSERT INTO dataset_consumers (dataset_id, name, contact, read_frequency, sla)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(dataset_id, name) DO UPDATE SET
+             contact = ?3, read_frequency = ?4, sla = ?5, updated_at = datetime('now')
+         RETURNING name, contact, read_frequency, sla, created_at, updated_at",
+        params![
+            dataset_id,
+            req.name,
+            req.contact,
+            req.read_frequency,
+            req.sla
+        ],
+        consumer_from_row,
+    )?;
+    Ok((consumer, !existed))
+}
+
+/// Consumers of a dataset, by name.
+pub fn list(conn: &Connection, dataset_id: i64) -> rusqlite::Result<Vec<DatasetConsumer>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT name, contact, read_frequency, sla, created_at, updated_at
+         FROM dataset_consumers
+         WHERE dataset_id = ?1
+         ORDER BY name",
+    )?;
+    let consumers = stmt.query_map([dataset_id], consumer_from_row)?.collect();
+    consumers
+}
+
+/// Unregister a consumer. Returns false if there was none.
+pub fn remove(conn: &Connection, dataset_id: i64, name: &str) -> rusqlite::Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM dataset_consumers WHERE dataset_id = ?1 AND name = ?2",
+        params![dataset_id, name],
+    )?;
+    Ok(rows > 0)
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ConsumerQuery {
+    consumer: Option<String>,
+}
+
+/// Register a consumer of a dataset, or update one with the same name
+pub(crate) async fn register_dataset_consumer(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<RegisterConsumerRequest>,
+) -> Result<(StatusCode, Json<DatasetConsumer>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    validate(&req).map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let (consumer, created) = register(&conn, dataset_id, &req)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(dataset = %name, consumer = %consumer.name, created, "Dataset consumer registered");
+
+    #[cfg(feature = "audit")]
+    {
+        let resource = format!("{}:{}", name, consumer.name);
+        let value = serde_json::to_value(&consumer).unwrap_or_default();
+        let event = if created {
+            audit::AuditEvent::create("dataset_consumer", resource, value, &request_id.0)
+        } else {
+            audit::AuditEvent::update(
+                "dataset_consumer",
+                resource,
+                serde_json::Value::Null,
+                value,
+                &request_id.0,
+            )
+        };
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    let status = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(consumer)))
+}
+
+/// List the registered consumers of a dataset
+pub(crate) async fn list_dataset_consumers(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<DatasetConsumer>>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let consumers =
+        list(&conn, dataset_id).map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.page(consumers)))
+}
+
+/// Unregister a consumer of a dataset
+pub(crate) async fn unregister_dataset_consumer(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    Query(query): Query<ConsumerQuery>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let Some(consumer) = query.consumer else {
+        return Err(bad_request(
+            "The consumer query parameter is required".to_string(),
+            request_id.0.clone(),
+        ));
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let removed = remove(&conn, dataset_id, &consumer)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !removed {
+        return Err(not_found(
+            format!(
+                "No consumer '{}' registered for dataset '{}'",
+                consumer, name
+            ),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(dataset = %name, consumer = %consumer, "Dataset consumer unregistered");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "dataset_consumer",
+            format!("{}:{}", name, consumer),
+            serde_json::json!({ "dataset": name, "consumer": consumer }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn request(name: &str, frequency: Option<&str>) -> RegisterConsumerRequest {
+        RegisterConsumerRequest {
+            name: name.to_string(),
+            contact: Some("#billing".to_string()),
+            read_frequency: frequency.map(String::from),
+            sla: Some("refreshed by 06:00 UTC".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&request("billing-service", Some("hourly"))).is_ok());
+        assert!(validate(&request("billing-service", None)).is_ok());
+        assert!(validate(&request(" ", None)).is_err());
+        assert!(validate(&request(&"x".repeat(MAX_NAME_LEN + 1), None)).is_err());
+        assert!(validate(&request("billing-service", Some("sometimes"))).is_err());
+    }
+
+    #[test]
+    fn test_register_replaces_by_name() {
+        let conn = setup_db();
+        let (consumer, created) =
+            register(&conn, 1, &request("billing-service", Some("hourly"))).unwrap();
+        assert!(created);
+        assert_eq!(consumer.read_frequency.as_deref(), Some("hourly"));
+
+        let (consumer, created) =
+            register(&conn, 1, &request("billing-service", Some("daily"))).unwrap();
+        assert!(!created);
+        assert_eq!(consumer.read_frequency.as_deref(), Some("daily"));
+
+        register(&conn, 1, &request("audit-report", None)).unwrap();
+        let names: Vec<_> = list(&conn, 1)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, vec!["audit-report", "billing-service"]);
+
+        assert!(remove(&conn, 1, "audit-report").unwrap());
+        assert!(!remove(&conn, 1, "audit-report").unwrap());
+        assert_eq!(list(&conn, 1).unwrap().len(), 1);
+    }
+}
@@ -14,6 +14,13 @@
 //!
 //! Contract violations trigger alerts through the alerting module.
 
+use crate::envelope;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, not_found, AppState, ErrorResponse, RequestId};
+use crate::strict_json::JsonBody;
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::Json;
 use serde::{Deserialize, Serialize};
 
 // =============================================================================
@@ -597,6 +604,221 @@ pub fn delete_contract(conn: &rusqlite::Connection, name: &str) -> Result<bool,
     Ok(rows > 0)
 }
 
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// List all contracts
+pub(crate) async fn list_contracts_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<DataContract>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, "Listing contracts");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let contracts =
+        list_contracts(&conn).map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(contracts)))
+}
+
+/// Create a new contract
+pub(crate) async fn create_contract_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    JsonBody(contract): JsonBody<DataContract>,
+) -> Result<Json<ContractCreatedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, contract_name = %contract.name, "Creating contract");
+
+    // Validate contract input
+    let validation_errors = validate_contract(&contract);
+    if !validation_errors.is_empty() {
+        let error_messages: Vec<String> = validation_errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        return Err(bad_request(
+            format!("Validation failed: {}", error_messages.join("; ")),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let id = create_contract(&conn, &contract).map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint") {
+            bad_request(
+                format!("Contract '{}' already exists", contract.name),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    Ok(Json(ContractCreatedResponse {
+        id,
+        name: contract.name,
+        message: "Contract created successfully".to_string(),
+    }))
+}
+
+#[derive(Serialize)]
+pub(crate) struct ContractCreatedResponse {
+    id: i64,
+    name: String,
+    message: String,
+}
+
+/// Get a contract by name
+pub(crate) async fn get_contract_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(name): Path<String>,
+) -> Result<Json<DataContract>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, contract_name = %name, "Getting contract");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let contract = get_contract(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Contract '{}' not found", name),
+                request_id.0.clone(),
+            )
+        })?;
+
+    Ok(Json(contract))
+}
+
+/// Update an existing contract
+pub(crate) async fn update_contract_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(name): Path<String>,
+    JsonBody(contract): JsonBody<DataContract>,
+) -> Result<Json<ContractUpdatedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, contract_name = %name, "Updating contract");
+
+    // Validate contract input
+    let validation_errors = validate_contract(&contract);
+    if !validation_errors.is_empty() {
+        let error_messages: Vec<String> = validation_errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        return Err(bad_request(
+            format!("Validation failed: {}", error_messages.join("; ")),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let updated = update_contract(&conn, &name, &contract)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if !updated {
+        return Err(not_found(
+            format!("Contract '{}' not found", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    Ok(Json(ContractUpdatedResponse {
+        name,
+        version: contract.version,
+        message: "Contract updated successfully".to_string(),
+    }))
+}
+
+#[derive(Serialize)]
+pub(crate) struct ContractUpdatedResponse {
+    name: String,
+    version: i32,
+    message: String,
+}
+
+/// Delete a contract
+pub(crate) async fn delete_contract_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(name): Path<String>,
+) -> Result<Json<ContractDeletedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, contract_name = %name, "Deleting contract");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let deleted = delete_contract(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if !deleted {
+        return Err(not_found(
+            format!("Contract '{}' not found", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    Ok(Json(ContractDeletedResponse {
+        name,
+        message: "Contract deleted successfully".to_string(),
+    }))
+}
+
+#[derive(Serialize)]
+pub(crate) struct ContractDeletedResponse {
+    name: String,
+    message: String,
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -12,7 +12,9 @@
 //! │ tenants          │──────────▶│ tenant-a/catalog │
 //! │ tenant_api_keys  │           ├──────────────────┤
 //! │ tenant_audit_log │           │ tenant-b/catalog │
-//! └──────────────────┘           └──────────────────┘
+//! │ tenant_dataset_  │           └──────────────────┘
+//! │   defaults       │
+//! └──────────────────┘
 //! ```
 //!
 //! # Security Model
@@ -37,10 +39,17 @@
 //! }, "platform-admin@example.com").await?;
 //! ```
 
+use crate::data_residency::{ResidencyZones, TenantResidency};
+use crate::export_schedules::{
+    self, CreateExportScheduleRequest, CredentialsKey, CredentialsProfile, ExportFormat,
+    ExportFrequency, ExportOutput, ExportRun, ExportRunStatus, ExportSchedule, ProfileCredentials,
+    UpdateExportScheduleRequest,
+};
 use metafuse_catalog_core::{CatalogError, Result};
 use metafuse_catalog_storage::{TenantContext, TenantStatus, TenantTier};
 use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use tracing::{info, warn};
 
 #[cfg(feature = "api-keys")]
@@ -221,6 +230,12 @@ pub struct TenantApiKey {
     pub revoked_at: Option<String>,
     pub last_used_at: Option<String>,
     pub expires_at: Option<String>,
+    /// Operator the key was minted for, when it is an impersonation key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<String>,
+    /// Sandbox namespace the key's requests are pinned to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<String>,
 }
 
 /// Validated tenant API key information.
@@ -234,6 +249,10 @@ pub struct ValidatedTenantKey {
     pub tier: TenantTier,
     /// Region for multi-region deployments.
     pub region: Option<String>,
+    /// Operator impersonating the tenant, when this is an impersonation key.
+    pub impersonated_by: Option<String>,
+    /// Sandbox namespace the key's requests are pinned to.
+    pub sandbox: Option<String>,
 }
 
 /// Audit log entry for control plane operations.
@@ -249,6 +268,272 @@ pub struct AuditLogEntry {
     pub client_ip: Option<String>,
 }
 
+/// Aggregated rate limit violations for one client on one route.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitViolationSummary {
+    /// Tenant the client belongs to (None for non-tenant clients)
+    pub tenant_id: Option<String>,
+    /// Client identity: `auth:<key hash>`, `ip:<address>`, or `anon:<address>`
+    pub client_key: String,
+    /// Matched route template
+    pub route: String,
+    /// Total rejected requests in the period
+    pub violation_count: i64,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+}
+
+/// Per-tenant feature that can be toggled from the control plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TenantFeature {
+    /// PII/column classification endpoints and auto-scan on create
+    Classification,
+    /// Data preview endpoints
+    Preview,
+    /// Usage tracking and analytics endpoints
+    UsageAnalytics,
+}
+
+impl TenantFeature {
+    /// All known features.
+    #[allow(dead_code)] // Used by library consumers
+    pub const ALL: [TenantFeature; 3] = [
+        TenantFeature::Classification,
+        TenantFeature::Preview,
+        TenantFeature::UsageAnalytics,
+    ];
+
+    /// Get feature as string (as stored in the control plane).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TenantFeature::Classification => "classification",
+            TenantFeature::Preview => "preview",
+            TenantFeature::UsageAnalytics => "usage_analytics",
+        }
+    }
+}
+
+impl std::str::FromStr for TenantFeature {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "classification" => Ok(TenantFeature::Classification),
+            "preview" => Ok(TenantFeature::Preview),
+            "usage_analytics" => Ok(TenantFeature::UsageAnalytics),
+            _ => Err(format!("unknown feature: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for TenantFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Effective feature flags for a tenant.
+///
+/// Features without an explicit override are enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantFeatureFlags {
+    pub classification: bool,
+    pub preview: bool,
+    pub usage_analytics: bool,
+}
+
+impl Default for TenantFeatureFlags {
+    fn default() -> Self {
+        Self {
+            classification: true,
+            preview: true,
+            usage_analytics: true,
+        }
+    }
+}
+
+impl TenantFeatureFlags {
+    /// Check whether a feature is enabled.
+    pub fn is_enabled(&self, feature: TenantFeature) -> bool {
+        match feature {
+            TenantFeature::Classification => self.classification,
+            TenantFeature::Preview => self.preview,
+            TenantFeature::UsageAnalytics => self.usage_analytics,
+        }
+    }
+
+    /// Set a feature flag.
+    pub fn set(&mut self, feature: TenantFeature, enabled: bool) {
+        match feature {
+            TenantFeature::Classification => self.classification = enabled,
+            TenantFeature::Preview => self.preview = enabled,
+            TenantFeature::UsageAnalytics => self.usage_analytics = enabled,
+        }
+    }
+}
+
+/// Request to update tenant feature flags. Omitted flags are left unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateTenantFeatureFlagsRequest {
+    #[serde(default)]
+    pub classification: Option<bool>,
+    #[serde(default)]
+    pub preview: Option<bool>,
+    #[serde(default)]
+    pub usage_analytics: Option<bool>,
+}
+
+impl UpdateTenantFeatureFlagsRequest {
+    /// Flags explicitly set in this request.
+    pub fn changes(&self) -> Vec<(TenantFeature, bool)> {
+        let mut changes = Vec::new();
+        if let Some(enabled) = self.classification {
+            changes.push((TenantFeature::Classification, enabled));
+        }
+        if let Some(enabled) = self.preview {
+            changes.push((TenantFeature::Preview, enabled));
+        }
+        if let Some(enabled) = self.usage_analytics {
+            changes.push((TenantFeature::UsageAnalytics, enabled));
+        }
+        changes
+    }
+}
+
+/// Placeholder in default tags replaced by the tenant id.
+pub const TENANT_ID_PLACEHOLDER: &str = "{tenant_id}";
+
+/// Defaults for datasets a tenant creates.
+///
+/// Applied at write time to fields the request leaves out; values in the
+/// request always win. Replaced as a whole by the admin API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantDatasetDefaults {
+    /// Tags for datasets created without tags; `{tenant_id}` is replaced
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Domain for datasets whose owner has no entry in `owner_domains`
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Owner for datasets created without one
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Domain per owner (team)
+    #[serde(default)]
+    pub owner_domains: std::collections::BTreeMap<String, String>,
+}
+
+impl TenantDatasetDefaults {
+    /// Whether no default is set.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.domain.is_none()
+            && self.owner.is_none()
+            && self.owner_domains.is_empty()
+    }
+
+    /// Default tags with the tenant id filled in.
+    pub fn render_tags(&self, tenant_id: &str) -> Vec<String> {
+        self.tags
+            .iter()
+            .map(|tag| tag.replace(TENANT_ID_PLACEHOLDER, tenant_id))
+            .collect()
+    }
+
+    /// Fill in absent fields of a new dataset.
+    ///
+    /// The owner is resolved first, so an explicit or defaulted owner picks
+    /// its domain from `owner_domains` before falling back to `domain`.
+    pub fn apply(
+        &self,
+        tenant_id: &str,
+        tags: &mut Option<Vec<String>>,
+        domain: &mut Option<String>,
+        owner: &mut Option<String>,
+    ) {
+        if owner.is_none() {
+            *owner = self.owner.clone();
+        }
+        if domain.is_none() {
+            *domain = owner
+                .as_ref()
+                .and_then(|o| self.owner_domains.get(o))
+                .or(self.domain.as_ref())
+                .cloned();
+        }
+        if tags.is_none() && !self.tags.is_empty() {
+            *tags = Some(self.render_tags(tenant_id));
+        }
+    }
+
+    /// Check the defaults would produce valid dataset fields.
+    pub fn validate(&self, tenant_id: &str) -> Result<()> {
+        for tag in self.render_tags(tenant_id) {
+            metafuse_catalog_core::validation::validate_tag(&tag)?;
+        }
+        for domain in self.domain.iter().chain(self.owner_domains.values()) {
+            metafuse_catalog_core::validation::validate_identifier(domain, "domain")?;
+        }
+        Ok(())
+    }
+}
+
+/// Default lifetime of an impersonation token (15 minutes).
+pub const IMPERSONATION_DEFAULT_TTL_SECS: u64 = 900;
+
+/// Longest lifetime an impersonation token can be minted with (1 hour).
+pub const IMPERSONATION_MAX_TTL_SECS: u64 = 3600;
+
+/// Request from a platform operator to see a tenant's view.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImpersonationRequest {
+    /// Operator doing the impersonation, e.g. an email address
+    pub operator: String,
+    /// Why, e.g. a support ticket reference
+    pub reason: String,
+    /// Token lifetime in seconds (default 15 minutes)
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+impl ImpersonationRequest {
+    /// Check the request and return the token lifetime.
+    pub fn validate(&self) -> Result<u64> {
+        if self.operator.trim().is_empty() || self.operator.len() > 255 {
+            return Err(CatalogError::ValidationError(
+                "operator must be 1-255 characters".to_string(),
+            ));
+        }
+        if self.reason.trim().is_empty() {
+            return Err(CatalogError::ValidationError(
+                "reason is required".to_string(),
+            ));
+        }
+        let ttl_secs = self.ttl_secs.unwrap_or(IMPERSONATION_DEFAULT_TTL_SECS);
+        if ttl_secs == 0 || ttl_secs > IMPERSONATION_MAX_TTL_SECS {
+            return Err(CatalogError::ValidationError(format!(
+                "ttl_secs must be between 1 and {}",
+                IMPERSONATION_MAX_TTL_SECS
+            )));
+        }
+        Ok(ttl_secs)
+    }
+}
+
+/// Short-lived viewer key minted for impersonation.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpersonationToken {
+    /// Tenant API key to send as a Bearer token. Cannot be retrieved again.
+    pub token: String,
+    /// Key id, for early revocation
+    pub key_id: i64,
+    pub tenant_id: String,
+    pub role: TenantRole,
+    pub impersonated_by: String,
+    pub expires_at: String,
+}
+
 /// Audit context for control plane operations.
 #[derive(Debug, Clone, Default)]
 pub struct AuditContext {
@@ -266,9 +551,72 @@ struct CachedTenantKey {
     role: TenantRole,
     tier: TenantTier,
     region: Option<String>,
+    impersonated_by: Option<String>,
+    sandbox: Option<String>,
+    /// Key expiry, so a cached key stops working when it expires
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
     cached_at: Instant,
 }
 
+const EXPORT_SCHEDULE_SELECT: &str =
+    "SELECT id, tenant_id, target_uri, format, frequency, enabled, next_run_at, created_at,
+            updated_at, credentials_profile
+     FROM tenant_export_schedules";
+
+fn export_schedule_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ExportSchedule> {
+    Ok(ExportSchedule {
+        id: row.get(0)?,
+        tenant_id: row.get(1)?,
+        target_uri: row.get(2)?,
+        format: ExportFormat::parse(&row.get::<_, String>(3)?).unwrap_or_default(),
+        frequency: ExportFrequency::parse(&row.get::<_, String>(4)?).unwrap_or_default(),
+        enabled: row.get(5)?,
+        credentials_profile: row.get(9)?,
+        next_run_at: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+const CREDENTIALS_PROFILE_SELECT: &str =
+    "SELECT id, tenant_id, name, provider, created_at, updated_at
+     FROM tenant_credentials_profiles";
+
+fn credentials_profile_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CredentialsProfile> {
+    Ok(CredentialsProfile {
+        id: row.get(0)?,
+        tenant_id: row.get(1)?,
+        name: row.get(2)?,
+        provider: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// Check a schedule's credentials profile exists and can upload to its target.
+fn check_schedule_profile(
+    conn: &Connection,
+    tenant_id: &str,
+    target_uri: &str,
+    profile: Option<&str>,
+) -> Result<()> {
+    let Some(name) = profile else {
+        return Ok(());
+    };
+    let provider: Option<String> = conn
+        .query_row(
+            "SELECT provider FROM tenant_credentials_profiles WHERE tenant_id = ?1 AND name = ?2",
+            [tenant_id, name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let provider = provider.ok_or_else(|| {
+        CatalogError::ValidationError(format!("Credentials profile '{}' not found", name))
+    })?;
+    export_schedules::check_profile_target(name, &provider, target_uri)
+        .map_err(CatalogError::ValidationError)
+}
+
 /// Control Plane manager for multi-tenant operations.
 ///
 /// Manages tenant lifecycle, tenant-scoped API keys, and audit logging.
@@ -278,12 +626,24 @@ pub struct ControlPlane {
     db_path: String,
     /// Template for tenant storage URIs (e.g., "gs://bucket/tenants/{tenant_id}/catalog.db").
     storage_uri_template: String,
+    /// Region-specific storage URI templates, keyed by region.
+    region_storage_templates: HashMap<String, String>,
+    /// Residency zones tenants can be pinned to.
+    residency_zones: ResidencyZones,
+    /// Key credentials profiles are encrypted with.
+    credentials_key: Option<CredentialsKey>,
     /// Cache for validated tenant API keys.
     #[cfg(feature = "api-keys")]
     key_cache: Arc<DashMap<u64, CachedTenantKey>>,
     /// Pending last_used_at updates for background flush.
     #[cfg(feature = "api-keys")]
     pending_updates: Arc<DashMap<String, Instant>>,
+    /// Cache for resolved tenant feature flags.
+    #[cfg(feature = "api-keys")]
+    feature_flag_cache: Arc<DashMap<String, (TenantFeatureFlags, Instant)>>,
+    /// Cache for tenant residency lookups.
+    #[cfg(feature = "api-keys")]
+    residency_cache: Arc<DashMap<String, (Option<TenantResidency>, Instant)>>,
 }
 
 impl ControlPlane {
@@ -309,13 +669,45 @@ impl ControlPlane {
         Ok(Self {
             db_path,
             storage_uri_template,
+            region_storage_templates: HashMap::new(),
+            residency_zones: ResidencyZones::from_env(),
+            credentials_key: None,
             #[cfg(feature = "api-keys")]
             key_cache: Arc::new(DashMap::new()),
             #[cfg(feature = "api-keys")]
             pending_updates: Arc::new(DashMap::new()),
+            #[cfg(feature = "api-keys")]
+            feature_flag_cache: Arc::new(DashMap::new()),
+            #[cfg(feature = "api-keys")]
+            residency_cache: Arc::new(DashMap::new()),
         })
     }
 
+    /// Use region-specific storage URI templates for tenants in those regions.
+    pub fn with_region_storage_templates(mut self, templates: HashMap<String, String>) -> Self {
+        self.region_storage_templates = templates;
+        self
+    }
+
+    /// Replace the residency zones loaded from `METAFUSE_RESIDENCY_ZONES`.
+    #[allow(dead_code)] // Used by library consumers
+    pub fn with_residency_zones(mut self, zones: ResidencyZones) -> Self {
+        self.residency_zones = zones;
+        self
+    }
+
+    /// Encrypt credentials profiles with `key`. Without a key, profiles
+    /// can't be stored or read.
+    pub fn with_credentials_key(mut self, key: Option<CredentialsKey>) -> Self {
+        self.credentials_key = key;
+        self
+    }
+
+    /// Residency zones tenants can be pinned to.
+    pub fn residency_zones(&self) -> &ResidencyZones {
+        &self.residency_zones
+    }
+
     /// Initialize the control plane database schema.
     #[allow(dead_code)] // Called during server startup
     pub async fn initialize(&self) -> Result<()> {
@@ -340,6 +732,19 @@ impl ControlPlane {
         self.storage_uri_template.replace("{tenant_id}", tenant_id)
     }
 
+    /// Generate storage URI for a tenant in a region, using the region's own
+    /// template when one is configured.
+    pub fn storage_uri_for_tenant_in_region(
+        &self,
+        tenant_id: &str,
+        region: Option<&str>,
+    ) -> String {
+        region
+            .and_then(|r| self.region_storage_templates.get(r))
+            .map(|template| template.replace("{tenant_id}", tenant_id))
+            .unwrap_or_else(|| self.storage_uri_for_tenant(tenant_id))
+    }
+
     /// Get the default region from environment.
     /// Returns None if METAFUSE_DEFAULT_REGION is not set.
     pub fn get_default_region() -> Option<String> {
@@ -363,13 +768,13 @@ impl ControlPlane {
         // Validate tenant_id
         let _ctx = TenantContext::new(&req.tenant_id)?;
 
-        let storage_uri = self.storage_uri_for_tenant(&req.tenant_id);
         let tier = req.tier.unwrap_or_else(|| "standard".to_string());
         let quota_max_datasets = req.quota_max_datasets.unwrap_or(10000);
         let quota_max_storage_bytes = req.quota_max_storage_bytes.unwrap_or(10737418240);
         let quota_max_api_calls_per_hour = req.quota_max_api_calls_per_hour.unwrap_or(10000);
         // Use provided region or fall back to default from environment
         let region = req.region.or_else(Self::get_default_region);
+        let storage_uri = self.storage_uri_for_tenant_in_region(&req.tenant_id, region.as_deref());
 
         // Validate tier
         if tier.parse::<TenantTier>().is_err() {
@@ -503,13 +908,13 @@ impl ControlPlane {
         // Validate tenant_id
         let _ctx = TenantContext::new(&req.tenant_id)?;
 
-        let storage_uri = self.storage_uri_for_tenant(&req.tenant_id);
         let tier = req.tier.unwrap_or_else(|| "standard".to_string());
         let quota_max_datasets = req.quota_max_datasets.unwrap_or(10000);
         let quota_max_storage_bytes = req.quota_max_storage_bytes.unwrap_or(10737418240);
         let quota_max_api_calls_per_hour = req.quota_max_api_calls_per_hour.unwrap_or(10000);
         // Use provided region or fall back to default from environment
         let region = req.region.or_else(Self::get_default_region);
+        let storage_uri = self.storage_uri_for_tenant_in_region(&req.tenant_id, region.as_deref());
 
         // Validate tier
         if tier.parse::<TenantTier>().is_err() {
@@ -743,11 +1148,27 @@ impl ControlPlane {
         let req_json = serde_json::to_string(&req).unwrap_or_default();
         #[cfg(feature = "api-keys")]
         let tier_updated = req.tier.is_some();
+        let region_updated = req.region.is_some();
+        let zones = self.residency_zones.clone();
 
         let tenant = tokio::task::spawn_blocking(move || {
             let conn = Connection::open(&db_path)?;
             conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
+            // A resident tenant can only move within its zone
+            if let Some(ref region) = req.region {
+                let zone: Option<String> = conn
+                    .query_row(
+                        "SELECT zone FROM tenant_residency WHERE tenant_id = ?1",
+                        [&tenant_id_owned],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if let Some(zone) = zone {
+                    zones.check(&zone, Some(region))?;
+                }
+            }
+
             // Build dynamic UPDATE query
             let mut updates = Vec::new();
             let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -838,11 +1259,18 @@ impl ControlPlane {
         .await
         .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
 
-        // If tier was updated, invalidate cached keys so new limits take effect immediately.
+        // If tier or region was updated, invalidate cached keys so the change takes effect
+        // immediately.
         #[cfg(feature = "api-keys")]
-        if tier_updated {
+        if tier_updated || region_updated {
             self.invalidate_tenant_cache(tenant_id);
         }
+        #[cfg(feature = "api-keys")]
+        if region_updated {
+            self.residency_cache.remove(tenant_id);
+        }
+        #[cfg(not(feature = "api-keys"))]
+        let _ = region_updated;
 
         // Audit log
         self.audit_log(
@@ -1152,178 +1580,1306 @@ impl ControlPlane {
     }
 
     // =========================================================================
-    // Tenant API Key Management
+    // Tenant Feature Flags
     // =========================================================================
 
-    #[cfg(feature = "api-keys")]
-    /// Create a tenant-scoped API key.
+    /// Get the effective feature flags for a tenant.
     ///
-    /// Returns the plaintext key. **Store securely - cannot be retrieved again!**
-    pub async fn create_tenant_api_key(
-        &self,
-        tenant_id: &str,
-        name: String,
-        role: TenantRole,
-        expires_at: Option<String>,
-    ) -> Result<String> {
-        // Generate key
-        let plaintext = self.generate_api_key();
-
-        // Hash with bcrypt
-        let key_hash = {
-            let plaintext_clone = plaintext.clone();
-            tokio::task::spawn_blocking(move || {
-                hash(&plaintext_clone, DEFAULT_BCRYPT_COST).map_err(|e| e.to_string())
-            })
-            .await
-            .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
-            .map_err(|e| CatalogError::Other(format!("Hash error: {}", e)))?
-        };
+    /// Flags without an explicit override fall back to the default (enabled).
+    /// Results are cached for the same TTL as API key validation.
+    pub async fn get_tenant_feature_flags(&self, tenant_id: &str) -> Result<TenantFeatureFlags> {
+        #[cfg(feature = "api-keys")]
+        if let Some(entry) = self.feature_flag_cache.get(tenant_id) {
+            let (flags, cached_at) = *entry;
+            if cached_at.elapsed() < Duration::from_secs(CACHE_TTL_SECS) {
+                return Ok(flags);
+            }
+        }
 
         let db_path = self.db_path.clone();
         let tenant_id_owned = tenant_id.to_string();
-        let name_owned = name.clone();
-        let role_str = role.as_str().to_string();
 
-        tokio::task::spawn_blocking(move || {
+        let flags = tokio::task::spawn_blocking(move || {
             let conn = Connection::open(&db_path)?;
-            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
-            conn.execute(
-                "INSERT INTO tenant_api_keys (tenant_id, key_hash, name, role, expires_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                rusqlite::params![tenant_id_owned, key_hash, name_owned, role_str, expires_at],
-            )?;
+            let mut stmt = conn
+                .prepare("SELECT flag, enabled FROM tenant_feature_flags WHERE tenant_id = ?1")?;
+            let rows = stmt
+                .query_map([&tenant_id_owned], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
 
-            Ok::<_, CatalogError>(())
+            let mut flags = TenantFeatureFlags::default();
+            for (flag, enabled) in rows {
+                // Unknown flags are ignored so older servers tolerate newer rows
+                if let Ok(feature) = flag.parse::<TenantFeature>() {
+                    flags.set(feature, enabled);
+                }
+            }
+
+            Ok::<_, CatalogError>(flags)
         })
         .await
         .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
 
-        info!(tenant_id = %tenant_id, name = %name, role = %role, "Created tenant API key");
-        Ok(plaintext)
+        #[cfg(feature = "api-keys")]
+        self.feature_flag_cache
+            .insert(tenant_id.to_string(), (flags, Instant::now()));
+
+        Ok(flags)
     }
 
-    #[cfg(feature = "api-keys")]
-    /// Validate a tenant API key and return identity information.
-    pub async fn validate_tenant_api_key(
+    /// Update feature flags for a tenant.
+    ///
+    /// Only flags present in the request are changed. Returns the effective flags.
+    pub async fn update_tenant_feature_flags(
         &self,
-        plaintext: &str,
-    ) -> Result<Option<ValidatedTenantKey>> {
-        let cache_key = self.cache_key_from_plaintext(plaintext);
-
-        // Check cache first
-        if let Some(cached) = self.key_cache.get(&cache_key) {
-            let age = cached.cached_at.elapsed();
-            if age < Duration::from_secs(CACHE_TTL_SECS) {
-                debug!("Tenant API key validation: cache hit");
-                self.mark_key_used(&cached.key_hash);
-                return Ok(Some(ValidatedTenantKey {
-                    key_hash: cached.key_hash.clone(),
-                    tenant_id: cached.tenant_id.clone(),
-                    name: cached.name.clone(),
-                    role: cached.role,
-                    tier: cached.tier,
-                    region: cached.region.clone(),
-                }));
-            } else {
-                debug!("Tenant API key validation: cache expired");
-                self.key_cache.remove(&cache_key);
-            }
+        tenant_id: &str,
+        req: UpdateTenantFeatureFlagsRequest,
+        audit: AuditContext,
+    ) -> Result<TenantFeatureFlags> {
+        let changes = req.changes();
+        if changes.is_empty() {
+            return Err(CatalogError::ValidationError(
+                "No feature flags to update".to_string(),
+            ));
         }
 
-        // Cache miss - query database
         let db_path = self.db_path.clone();
-        let plaintext = plaintext.to_string();
+        let tenant_id_owned = tenant_id.to_string();
+        let req_json = serde_json::to_string(&req).unwrap_or_default();
 
-        let result = tokio::task::spawn_blocking(move || {
+        tokio::task::spawn_blocking(move || {
             let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
-            // Get all valid (non-revoked, non-expired) keys for active tenants
-            // Include region for multi-region deployments
-            let mut stmt = conn.prepare(
-                r#"
-                SELECT k.key_hash, k.tenant_id, k.name, k.role, t.tier, t.region
-                FROM tenant_api_keys k
-                JOIN tenants t ON k.tenant_id = t.tenant_id
-                WHERE k.revoked_at IS NULL
-                  AND (k.expires_at IS NULL OR k.expires_at > datetime('now'))
-                  AND t.status = 'active'
-                "#,
-            )?;
+            let exists: bool = conn
+                .query_row(
+                    "SELECT 1 FROM tenants WHERE tenant_id = ?1 AND status != 'deleted'",
+                    [&tenant_id_owned],
+                    |_| Ok(true),
+                )
+                .optional()?
+                .unwrap_or(false);
 
-            let keys: Vec<(String, String, String, String, String, Option<String>)> = stmt
-                .query_map([], |row| {
-                    Ok((
-                        row.get(0)?,
-                        row.get(1)?,
-                        row.get(2)?,
-                        row.get(3)?,
-                        row.get(4)?,
-                        row.get(5)?,
-                    ))
-                })?
-                .collect::<std::result::Result<Vec<_>, _>>()?;
+            if !exists {
+                return Err(CatalogError::DatasetNotFound(format!(
+                    "Tenant not found or already deleted: {}",
+                    tenant_id_owned
+                )));
+            }
 
-            // Verify against each hash
-            for (key_hash, tenant_id, name, role, tier_str, region) in keys {
-                if verify(&plaintext, &key_hash).unwrap_or(false) {
-                    let role = role.parse::<TenantRole>().unwrap_or_default();
-                    let tier = tier_str.parse::<TenantTier>().unwrap_or_default();
-                    return Ok::<
-                        Option<(
-                            String,
-                            String,
-                            String,
-                            TenantRole,
-                            TenantTier,
-                            Option<String>,
-                        )>,
-                        CatalogError,
-                    >(Some((
-                        key_hash, tenant_id, name, role, tier, region,
-                    )));
-                }
+            let tx = conn.unchecked_transaction()?;
+            for (feature, enabled) in &changes {
+                tx.execute(
+                    "INSERT INTO tenant_feature_flags (tenant_id, flag, enabled, updated_at)
+                     VALUES (?1, ?2, ?3, datetime('now'))
+                     ON CONFLICT(tenant_id, flag) DO UPDATE SET
+                        enabled = excluded.enabled, updated_at = excluded.updated_at",
+                    rusqlite::params![&tenant_id_owned, feature.as_str(), enabled],
+                )?;
             }
+            tx.commit()?;
 
-            Ok(None)
+            Ok::<_, CatalogError>(())
         })
         .await
         .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
 
-        if let Some((key_hash, tenant_id, name, role, tier, region)) = result {
-            // Cache the valid key
-            self.key_cache.insert(
-                cache_key,
-                CachedTenantKey {
-                    key_hash: key_hash.clone(),
-                    tenant_id: tenant_id.clone(),
-                    name: name.clone(),
-                    role,
-                    tier,
-                    region: region.clone(),
-                    cached_at: Instant::now(),
-                },
-            );
+        // Drop the cached entry so the change takes effect immediately
+        #[cfg(feature = "api-keys")]
+        self.feature_flag_cache.remove(tenant_id);
 
-            self.mark_key_used(&key_hash);
+        self.audit_log(
+            "feature_flags_update",
+            tenant_id,
+            &audit.actor,
+            Some(req_json),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
 
-            debug!(tenant_id = %tenant_id, name = %name, tier = ?tier, region = ?region, "Tenant API key validated");
-            Ok(Some(ValidatedTenantKey {
-                key_hash,
-                tenant_id,
-                name,
-                role,
-                tier,
-                region,
-            }))
-        } else {
-            warn!("Tenant API key validation failed");
-            Ok(None)
-        }
+        info!(tenant_id = %tenant_id, "Updated tenant feature flags");
+        self.get_tenant_feature_flags(tenant_id).await
     }
 
-    #[cfg(feature = "api-keys")]
+    // =========================================================================
+    // Tenant Data Residency
+    // =========================================================================
+
+    /// Get a tenant's residency zone and current region, or `None` when the
+    /// tenant has no residency requirement.
+    ///
+    /// Results are cached for the same TTL as API key validation.
+    pub async fn get_tenant_residency(&self, tenant_id: &str) -> Result<Option<TenantResidency>> {
+        #[cfg(feature = "api-keys")]
+        if let Some(entry) = self.residency_cache.get(tenant_id) {
+            let (residency, cached_at) = entry.value();
+            if cached_at.elapsed() < Duration::from_secs(CACHE_TTL_SECS) {
+                return Ok(residency.clone());
+            }
+        }
+
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+
+        let residency = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let residency = conn
+                .query_row(
+                    "SELECT r.zone, t.region FROM tenant_residency r
+                     JOIN tenants t ON t.tenant_id = r.tenant_id
+                     WHERE r.tenant_id = ?1",
+                    [&tenant_id_owned],
+                    |row| {
+                        Ok(TenantResidency {
+                            zone: row.get(0)?,
+                            region: row.get(1)?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok::<_, CatalogError>(residency)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        #[cfg(feature = "api-keys")]
+        self.residency_cache
+            .insert(tenant_id.to_string(), (residency.clone(), Instant::now()));
+
+        Ok(residency)
+    }
+
+    /// Set or clear a tenant's residency zone.
+    ///
+    /// The tenant's current region must belong to the zone.
+    pub async fn set_tenant_residency(
+        &self,
+        tenant_id: &str,
+        zone: Option<String>,
+        audit: AuditContext,
+    ) -> Result<Option<TenantResidency>> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let zones = self.residency_zones.clone();
+        let req_json = serde_json::json!({ "zone": zone }).to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+            let region: Option<Option<String>> = conn
+                .query_row(
+                    "SELECT region FROM tenants WHERE tenant_id = ?1 AND status != 'deleted'",
+                    [&tenant_id_owned],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let Some(region) = region else {
+                return Err(CatalogError::DatasetNotFound(format!(
+                    "Tenant not found or already deleted: {}",
+                    tenant_id_owned
+                )));
+            };
+
+            match zone {
+                Some(zone) => {
+                    zones.check(&zone, region.as_deref())?;
+                    conn.execute(
+                        "INSERT INTO tenant_residency (tenant_id, zone, updated_at)
+                         VALUES (?1, ?2, datetime('now'))
+                         ON CONFLICT(tenant_id) DO UPDATE SET
+                            zone = excluded.zone, updated_at = excluded.updated_at",
+                        rusqlite::params![&tenant_id_owned, zone],
+                    )?;
+                }
+                None => {
+                    conn.execute(
+                        "DELETE FROM tenant_residency WHERE tenant_id = ?1",
+                        [&tenant_id_owned],
+                    )?;
+                }
+            }
+
+            Ok::<_, CatalogError>(())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        // Drop the cached entry so the change takes effect immediately
+        #[cfg(feature = "api-keys")]
+        self.residency_cache.remove(tenant_id);
+
+        self.audit_log(
+            "residency_update",
+            tenant_id,
+            &audit.actor,
+            Some(req_json),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
+
+        info!(tenant_id = %tenant_id, "Updated tenant data residency");
+        self.get_tenant_residency(tenant_id).await
+    }
+
+    // =========================================================================
+    // Tenant Dataset Defaults
+    // =========================================================================
+
+    /// Get the dataset defaults for a tenant (empty when none are set).
+    pub async fn get_tenant_dataset_defaults(
+        &self,
+        tenant_id: &str,
+    ) -> Result<TenantDatasetDefaults> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let row: Option<(String, Option<String>, Option<String>, String)> = conn
+                .query_row(
+                    "SELECT tags, domain, owner, owner_domains
+                     FROM tenant_dataset_defaults WHERE tenant_id = ?1",
+                    [&tenant_id_owned],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .optional()?;
+
+            let Some((tags, domain, owner, owner_domains)) = row else {
+                return Ok(TenantDatasetDefaults::default());
+            };
+            Ok::<_, CatalogError>(TenantDatasetDefaults {
+                tags: serde_json::from_str(&tags).unwrap_or_default(),
+                domain,
+                owner,
+                owner_domains: serde_json::from_str(&owner_domains).unwrap_or_default(),
+            })
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Replace the dataset defaults for a tenant.
+    ///
+    /// Empty defaults clear the tenant's row.
+    pub async fn set_tenant_dataset_defaults(
+        &self,
+        tenant_id: &str,
+        defaults: TenantDatasetDefaults,
+        audit: AuditContext,
+    ) -> Result<TenantDatasetDefaults> {
+        defaults.validate(tenant_id)?;
+
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let req_json = serde_json::to_string(&defaults).unwrap_or_default();
+        let stored = defaults.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+            let exists: bool = conn
+                .query_row(
+                    "SELECT 1 FROM tenants WHERE tenant_id = ?1 AND status != 'deleted'",
+                    [&tenant_id_owned],
+                    |_| Ok(true),
+                )
+                .optional()?
+                .unwrap_or(false);
+
+            if !exists {
+                return Err(CatalogError::DatasetNotFound(format!(
+                    "Tenant not found or already deleted: {}",
+                    tenant_id_owned
+                )));
+            }
+
+            if stored.is_empty() {
+                conn.execute(
+                    "DELETE FROM tenant_dataset_defaults WHERE tenant_id = ?1",
+                    [&tenant_id_owned],
+                )?;
+            } else {
+                conn.execute(
+                    "INSERT INTO tenant_dataset_defaults
+                        (tenant_id, tags, domain, owner, owner_domains, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
+                     ON CONFLICT(tenant_id) DO UPDATE SET
+                        tags = excluded.tags, domain = excluded.domain, owner = excluded.owner,
+                        owner_domains = excluded.owner_domains, updated_at = excluded.updated_at",
+                    rusqlite::params![
+                        &tenant_id_owned,
+                        serde_json::to_string(&stored.tags).unwrap_or_else(|_| "[]".into()),
+                        stored.domain,
+                        stored.owner,
+                        serde_json::to_string(&stored.owner_domains)
+                            .unwrap_or_else(|_| "{}".into()),
+                    ],
+                )?;
+            }
+
+            Ok::<_, CatalogError>(())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        self.audit_log(
+            "dataset_defaults_update",
+            tenant_id,
+            &audit.actor,
+            Some(req_json),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
+
+        info!(tenant_id = %tenant_id, "Updated tenant dataset defaults");
+        Ok(defaults)
+    }
+
+    // =========================================================================
+    // Tenant Export Schedules
+    // =========================================================================
+
+    /// List a tenant's export schedules, oldest first.
+    pub async fn list_export_schedules(&self, tenant_id: &str) -> Result<Vec<ExportSchedule>> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE tenant_id = ?1 ORDER BY id",
+                EXPORT_SCHEDULE_SELECT
+            ))?;
+            let schedules = stmt
+                .query_map([&tenant_id_owned], export_schedule_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok::<_, CatalogError>(schedules)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Get a tenant's export schedule.
+    pub async fn get_export_schedule(
+        &self,
+        tenant_id: &str,
+        schedule_id: i64,
+    ) -> Result<Option<ExportSchedule>> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let schedule = conn
+                .query_row(
+                    &format!(
+                        "{} WHERE id = ?1 AND tenant_id = ?2",
+                        EXPORT_SCHEDULE_SELECT
+                    ),
+                    rusqlite::params![schedule_id, &tenant_id_owned],
+                    export_schedule_from_row,
+                )
+                .optional()?;
+            Ok::<_, CatalogError>(schedule)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Schedule exports of a tenant's catalog.
+    pub async fn create_export_schedule(
+        &self,
+        tenant_id: &str,
+        mut req: CreateExportScheduleRequest,
+        audit: AuditContext,
+    ) -> Result<ExportSchedule> {
+        req.credentials_profile = req.credentials_profile.filter(|name| !name.is_empty());
+        export_schedules::validate_target_uri(&req.target_uri)
+            .map_err(CatalogError::ValidationError)?;
+        let next_run_at = req
+            .next_run_at
+            .as_deref()
+            .map(export_schedules::parse_run_time)
+            .transpose()
+            .map_err(CatalogError::ValidationError)?;
+
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let req_json = serde_json::json!({
+            "target_uri": req.target_uri,
+            "format": req.format,
+            "frequency": req.frequency,
+            "credentials_profile": req.credentials_profile,
+            "next_run_at": next_run_at,
+        })
+        .to_string();
+
+        let schedule = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+            let exists: bool = conn
+                .query_row(
+                    "SELECT 1 FROM tenants WHERE tenant_id = ?1 AND status != 'deleted'",
+                    [&tenant_id_owned],
+                    |_| Ok(true),
+                )
+                .optional()?
+                .unwrap_or(false);
+
+            if !exists {
+                return Err(CatalogError::DatasetNotFound(format!(
+                    "Tenant not found or already deleted: {}",
+                    tenant_id_owned
+                )));
+            }
+
+            check_schedule_profile(
+                &conn,
+                &tenant_id_owned,
+                &req.target_uri,
+                req.credentials_profile.as_deref(),
+            )?;
+
+            conn.execute(
+                "INSERT INTO tenant_export_schedules
+                    (tenant_id, target_uri, format, frequency, credentials_profile, next_run_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, datetime('now')))",
+                rusqlite::params![
+                    &tenant_id_owned,
+                    req.target_uri,
+                    req.format.as_str(),
+                    req.frequency.as_str(),
+                    req.credentials_profile,
+                    next_run_at,
+                ],
+            )?;
+            let schedule = conn.query_row(
+                &format!("{} WHERE id = ?1", EXPORT_SCHEDULE_SELECT),
+                [conn.last_insert_rowid()],
+                export_schedule_from_row,
+            )?;
+            Ok::<_, CatalogError>(schedule)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        self.audit_log(
+            "export_schedule_create",
+            tenant_id,
+            &audit.actor,
+            Some(req_json),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
+
+        info!(tenant_id = %tenant_id, schedule_id = schedule.id, "Created export schedule");
+        Ok(schedule)
+    }
+
+    /// Change a tenant's export schedule. Returns `None` if it doesn't exist.
+    pub async fn update_export_schedule(
+        &self,
+        tenant_id: &str,
+        schedule_id: i64,
+        req: UpdateExportScheduleRequest,
+        audit: AuditContext,
+    ) -> Result<Option<ExportSchedule>> {
+        if let Some(target_uri) = &req.target_uri {
+            export_schedules::validate_target_uri(target_uri)
+                .map_err(CatalogError::ValidationError)?;
+        }
+        let next_run_at = req
+            .next_run_at
+            .as_deref()
+            .map(export_schedules::parse_run_time)
+            .transpose()
+            .map_err(CatalogError::ValidationError)?;
+
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let req_json = serde_json::json!({
+            "schedule_id": schedule_id,
+            "target_uri": req.target_uri,
+            "format": req.format,
+            "frequency": req.frequency,
+            "enabled": req.enabled,
+            "credentials_profile": req.credentials_profile,
+            "next_run_at": next_run_at,
+        })
+        .to_string();
+
+        let updated = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let tx = conn.unchecked_transaction()?;
+            // An empty profile switches back to the server's credentials
+            let updated = tx.execute(
+                "UPDATE tenant_export_schedules SET
+                    target_uri = COALESCE(?3, target_uri),
+                    format = COALESCE(?4, format),
+                    frequency = COALESCE(?5, frequency),
+                    enabled = COALESCE(?6, enabled),
+                    credentials_profile = CASE WHEN ?7 IS NULL THEN credentials_profile
+                                               ELSE NULLIF(?7, '') END,
+                    next_run_at = COALESCE(?8, next_run_at),
+                    updated_at = datetime('now')
+                 WHERE id = ?1 AND tenant_id = ?2",
+                rusqlite::params![
+                    schedule_id,
+                    &tenant_id_owned,
+                    req.target_uri,
+                    req.format.map(|f| f.as_str()),
+                    req.frequency.map(|f| f.as_str()),
+                    req.enabled,
+                    req.credentials_profile,
+                    next_run_at,
+                ],
+            )?;
+            if updated == 0 {
+                return Ok(false);
+            }
+            let schedule = tx.query_row(
+                &format!("{} WHERE id = ?1", EXPORT_SCHEDULE_SELECT),
+                [schedule_id],
+                export_schedule_from_row,
+            )?;
+            check_schedule_profile(
+                &tx,
+                &tenant_id_owned,
+                &schedule.target_uri,
+                schedule.credentials_profile.as_deref(),
+            )?;
+            tx.commit()?;
+            Ok::<_, CatalogError>(true)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        if !updated {
+            return Ok(None);
+        }
+
+        self.audit_log(
+            "export_schedule_update",
+            tenant_id,
+            &audit.actor,
+            Some(req_json),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
+
+        info!(tenant_id = %tenant_id, schedule_id, "Updated export schedule");
+        self.get_export_schedule(tenant_id, schedule_id).await
+    }
+
+    /// Delete a tenant's export schedule and its runs. Returns whether it existed.
+    pub async fn delete_export_schedule(
+        &self,
+        tenant_id: &str,
+        schedule_id: i64,
+        audit: AuditContext,
+    ) -> Result<bool> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+
+        let deleted = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+            let deleted = conn.execute(
+                "DELETE FROM tenant_export_schedules WHERE id = ?1 AND tenant_id = ?2",
+                rusqlite::params![schedule_id, &tenant_id_owned],
+            )?;
+            Ok::<_, CatalogError>(deleted > 0)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        if deleted {
+            self.audit_log(
+                "export_schedule_delete",
+                tenant_id,
+                &audit.actor,
+                Some(serde_json::json!({ "schedule_id": schedule_id }).to_string()),
+                audit.request_id.as_deref(),
+                audit.client_ip.as_deref(),
+            )
+            .await?;
+            info!(tenant_id = %tenant_id, schedule_id, "Deleted export schedule");
+        }
+        Ok(deleted)
+    }
+
+    /// List a schedule's runs, newest first.
+    pub async fn list_export_runs(&self, schedule_id: i64, limit: usize) -> Result<Vec<ExportRun>> {
+        let db_path = self.db_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let mut stmt = conn.prepare(
+                "SELECT id, schedule_id, started_at, finished_at, status, object_uri,
+                        dataset_count, size_bytes, error
+                 FROM tenant_export_runs
+                 WHERE schedule_id = ?1
+                 ORDER BY id DESC
+                 LIMIT ?2",
+            )?;
+            let runs = stmt
+                .query_map(rusqlite::params![schedule_id, limit as i64], |row| {
+                    Ok(ExportRun {
+                        id: row.get(0)?,
+                        schedule_id: row.get(1)?,
+                        started_at: row.get(2)?,
+                        finished_at: row.get(3)?,
+                        status: ExportRunStatus::parse(&row.get::<_, String>(4)?),
+                        object_uri: row.get(5)?,
+                        dataset_count: row.get(6)?,
+                        size_bytes: row.get(7)?,
+                        error: row.get(8)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok::<_, CatalogError>(runs)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Take the enabled schedules of active tenants that are due.
+    ///
+    /// Each is advanced to its next run and gets a `running` run, returned
+    /// with the schedule. A schedule another server took first is skipped.
+    pub async fn claim_due_export_schedules(&self) -> Result<Vec<(ExportSchedule, i64)>> {
+        let db_path = self.db_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let due: Vec<ExportSchedule> = {
+                let mut stmt = conn.prepare(&format!(
+                    "{} WHERE enabled = 1 AND next_run_at <= datetime('now')
+                       AND tenant_id IN (SELECT tenant_id FROM tenants WHERE status = 'active')
+                     ORDER BY next_run_at",
+                    EXPORT_SCHEDULE_SELECT
+                ))?;
+                let due = stmt
+                    .query_map([], export_schedule_from_row)?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                due
+            };
+
+            let mut claimed = Vec::new();
+            for schedule in due {
+                let tx = conn.unchecked_transaction()?;
+                // Advance from now, so a schedule that was down for a while
+                // runs once rather than catching up
+                let taken = tx.execute(
+                    "UPDATE tenant_export_schedules
+                     SET next_run_at = datetime(max(next_run_at, datetime('now')), ?3)
+                     WHERE id = ?1 AND next_run_at = ?2",
+                    rusqlite::params![
+                        schedule.id,
+                        schedule.next_run_at,
+                        schedule.frequency.sqlite_modifier(),
+                    ],
+                )?;
+                if taken == 0 {
+                    continue;
+                }
+                tx.execute(
+                    "INSERT INTO tenant_export_runs (schedule_id) VALUES (?1)",
+                    [schedule.id],
+                )?;
+                let run_id = tx.last_insert_rowid();
+                tx.commit()?;
+                claimed.push((schedule, run_id));
+            }
+            Ok::<_, CatalogError>(claimed)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Record the outcome of a run, keeping the schedule's most recent runs.
+    pub async fn finish_export_run(
+        &self,
+        run_id: i64,
+        result: &std::result::Result<ExportOutput, String>,
+    ) -> Result<()> {
+        let db_path = self.db_path.clone();
+        let (status, object_uri, dataset_count, size_bytes, error) = match result {
+            Ok(output) => (
+                "succeeded",
+                Some(output.object_uri.clone()),
+                Some(output.dataset_count as i64),
+                Some(output.size_bytes as i64),
+                None,
+            ),
+            Err(e) => ("failed", None, None, None, Some(e.clone())),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute(
+                "UPDATE tenant_export_runs SET
+                    finished_at = datetime('now'), status = ?2, object_uri = ?3,
+                    dataset_count = ?4, size_bytes = ?5, error = ?6
+                 WHERE id = ?1",
+                rusqlite::params![run_id, status, object_uri, dataset_count, size_bytes, error],
+            )?;
+            conn.execute(
+                "DELETE FROM tenant_export_runs
+                 WHERE schedule_id = (SELECT schedule_id FROM tenant_export_runs WHERE id = ?1)
+                   AND id NOT IN (
+                       SELECT id FROM tenant_export_runs
+                       WHERE schedule_id = (SELECT schedule_id FROM tenant_export_runs WHERE id = ?1)
+                       ORDER BY id DESC LIMIT ?2
+                   )",
+                rusqlite::params![run_id, export_schedules::RUN_HISTORY_LIMIT as i64],
+            )?;
+            Ok::<_, CatalogError>(())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    // =========================================================================
+    // Tenant Credentials Profiles
+    // =========================================================================
+
+    /// List a tenant's credentials profiles by name, without their secrets.
+    pub async fn list_credentials_profiles(
+        &self,
+        tenant_id: &str,
+    ) -> Result<Vec<CredentialsProfile>> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE tenant_id = ?1 ORDER BY name",
+                CREDENTIALS_PROFILE_SELECT
+            ))?;
+            let profiles = stmt
+                .query_map([&tenant_id_owned], credentials_profile_from_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok::<_, CatalogError>(profiles)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Store a tenant's credentials profile, replacing one of the same name.
+    ///
+    /// Replacing a profile is checked against the targets of the schedules
+    /// using it. Returns the profile and whether it was created.
+    pub async fn put_credentials_profile(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        credentials: ProfileCredentials,
+        audit: AuditContext,
+    ) -> Result<(CredentialsProfile, bool)> {
+        export_schedules::validate_profile_name(name).map_err(CatalogError::ValidationError)?;
+        credentials
+            .validate()
+            .map_err(CatalogError::ValidationError)?;
+
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let name_owned = name.to_string();
+        let provider = credentials.provider();
+        let sealed = self
+            .credentials_key()?
+            .seal(tenant_id, name, &credentials)
+            .map_err(CatalogError::Other)?;
+
+        let (profile, created) = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+            let tx = conn.unchecked_transaction()?;
+
+            let exists: bool = tx
+                .query_row(
+                    "SELECT 1 FROM tenants WHERE tenant_id = ?1 AND status != 'deleted'",
+                    [&tenant_id_owned],
+                    |_| Ok(true),
+                )
+                .optional()?
+                .unwrap_or(false);
+            if !exists {
+                return Err(CatalogError::DatasetNotFound(format!(
+                    "Tenant not found or already deleted: {}",
+                    tenant_id_owned
+                )));
+            }
+
+            let targets: Vec<String> = {
+                let mut stmt = tx.prepare(
+                    "SELECT target_uri FROM tenant_export_schedules
+                     WHERE tenant_id = ?1 AND credentials_profile = ?2",
+                )?;
+                let targets = stmt
+                    .query_map([&tenant_id_owned, &name_owned], |row| row.get(0))?
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                targets
+            };
+            for target_uri in &targets {
+                export_schedules::check_profile_target(&name_owned, provider, target_uri)
+                    .map_err(CatalogError::ValidationError)?;
+            }
+
+            let updated = tx.execute(
+                "UPDATE tenant_credentials_profiles
+                 SET provider = ?3, credentials = ?4, updated_at = datetime('now')
+                 WHERE tenant_id = ?1 AND name = ?2",
+                rusqlite::params![&tenant_id_owned, &name_owned, provider, &sealed],
+            )?;
+            if updated == 0 {
+                tx.execute(
+                    "INSERT INTO tenant_credentials_profiles
+                        (tenant_id, name, provider, credentials)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![&tenant_id_owned, &name_owned, provider, &sealed],
+                )?;
+            }
+            let profile = tx.query_row(
+                &format!(
+                    "{} WHERE tenant_id = ?1 AND name = ?2",
+                    CREDENTIALS_PROFILE_SELECT
+                ),
+                [&tenant_id_owned, &name_owned],
+                credentials_profile_from_row,
+            )?;
+            tx.commit()?;
+            Ok::<_, CatalogError>((profile, updated == 0))
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        self.audit_log(
+            "credentials_profile_put",
+            tenant_id,
+            &audit.actor,
+            Some(serde_json::json!({ "name": name, "provider": provider }).to_string()),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
+
+        info!(tenant_id = %tenant_id, name = %name, provider, created, "Stored credentials profile");
+        Ok((profile, created))
+    }
+
+    /// Delete a tenant's credentials profile. Returns whether it existed.
+    ///
+    /// Fails with a conflict while an export schedule uses the profile.
+    pub async fn delete_credentials_profile(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        audit: AuditContext,
+    ) -> Result<bool> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let name_owned = name.to_string();
+
+        let deleted = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let tx = conn.unchecked_transaction()?;
+            let schedules: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM tenant_export_schedules
+                 WHERE tenant_id = ?1 AND credentials_profile = ?2",
+                [&tenant_id_owned, &name_owned],
+                |row| row.get(0),
+            )?;
+            if schedules > 0 {
+                return Err(CatalogError::ConflictError(format!(
+                    "Credentials profile '{}' is used by {} export schedule(s)",
+                    name_owned, schedules
+                )));
+            }
+            let deleted = tx.execute(
+                "DELETE FROM tenant_credentials_profiles WHERE tenant_id = ?1 AND name = ?2",
+                [&tenant_id_owned, &name_owned],
+            )?;
+            tx.commit()?;
+            Ok::<_, CatalogError>(deleted > 0)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        if deleted {
+            self.audit_log(
+                "credentials_profile_delete",
+                tenant_id,
+                &audit.actor,
+                Some(serde_json::json!({ "name": name }).to_string()),
+                audit.request_id.as_deref(),
+                audit.client_ip.as_deref(),
+            )
+            .await?;
+            info!(tenant_id = %tenant_id, name = %name, "Deleted credentials profile");
+        }
+        Ok(deleted)
+    }
+
+    /// The key credentials profiles are encrypted with.
+    fn credentials_key(&self) -> Result<&CredentialsKey> {
+        self.credentials_key.as_ref().ok_or_else(|| {
+            CatalogError::Other(
+                "Credentials profiles require METAFUSE_CREDENTIALS_KEY to be set".to_string(),
+            )
+        })
+    }
+
+    /// Read the credentials of a tenant's profile, for uploads.
+    pub async fn get_profile_credentials(
+        &self,
+        tenant_id: &str,
+        name: &str,
+    ) -> Result<Option<ProfileCredentials>> {
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let name_owned = name.to_string();
+
+        let credentials: Option<String> = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let credentials = conn
+                .query_row(
+                    "SELECT credentials FROM tenant_credentials_profiles
+                     WHERE tenant_id = ?1 AND name = ?2",
+                    [&tenant_id_owned, &name_owned],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok::<_, CatalogError>(credentials)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        credentials
+            .map(|sealed| {
+                self.credentials_key()?
+                    .open(tenant_id, name, &sealed)
+                    .map_err(CatalogError::Other)
+            })
+            .transpose()
+    }
+
+    // =========================================================================
+    // Tenant API Key Management
+    // =========================================================================
+
+    #[cfg(feature = "api-keys")]
+    /// Create a tenant-scoped API key.
+    ///
+    /// Returns the plaintext key. **Store securely - cannot be retrieved again!**
+    pub async fn create_tenant_api_key(
+        &self,
+        tenant_id: &str,
+        name: String,
+        role: TenantRole,
+        expires_at: Option<String>,
+    ) -> Result<String> {
+        self.create_sandbox_api_key(tenant_id, name, role, expires_at, None)
+            .await
+    }
+
+    #[cfg(feature = "api-keys")]
+    /// Create a tenant-scoped API key, optionally pinned to a sandbox namespace.
+    ///
+    /// Every request made with a pinned key is sandboxed (see [`crate::sandbox`]).
+    /// Returns the plaintext key. **Store securely - cannot be retrieved again!**
+    pub async fn create_sandbox_api_key(
+        &self,
+        tenant_id: &str,
+        name: String,
+        role: TenantRole,
+        expires_at: Option<String>,
+        sandbox: Option<String>,
+    ) -> Result<String> {
+        // Generate key
+        let plaintext = self.generate_api_key();
+
+        // Hash with bcrypt
+        let key_hash = {
+            let plaintext_clone = plaintext.clone();
+            tokio::task::spawn_blocking(move || {
+                hash(&plaintext_clone, DEFAULT_BCRYPT_COST).map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+            .map_err(|e| CatalogError::Other(format!("Hash error: {}", e)))?
+        };
+
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let name_owned = name.clone();
+        let role_str = role.as_str().to_string();
+        let sandbox_owned = sandbox.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+            conn.execute(
+                "INSERT INTO tenant_api_keys
+                     (tenant_id, key_hash, name, role, expires_at, sandbox_namespace)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    tenant_id_owned,
+                    key_hash,
+                    name_owned,
+                    role_str,
+                    expires_at,
+                    sandbox_owned
+                ],
+            )?;
+
+            Ok::<_, CatalogError>(())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        info!(tenant_id = %tenant_id, name = %name, role = %role, sandbox = ?sandbox, "Created tenant API key");
+        Ok(plaintext)
+    }
+
+    #[cfg(feature = "api-keys")]
+    /// Mint a short-lived viewer key for a platform operator to see a tenant's view.
+    ///
+    /// The key is a regular tenant API key marked with the operator, so it is
+    /// listed and revoked like any other. Audited as `impersonation_start`.
+    pub async fn create_impersonation_token(
+        &self,
+        tenant_id: &str,
+        req: &ImpersonationRequest,
+        audit: AuditContext,
+    ) -> Result<ImpersonationToken> {
+        let ttl_secs = req.validate()?;
+        let tenant = self.get_tenant(tenant_id).await?.ok_or_else(|| {
+            CatalogError::DatasetNotFound(format!("Tenant not found: {}", tenant_id))
+        })?;
+        if !tenant.is_operational() {
+            return Err(CatalogError::ValidationError(format!(
+                "Tenant '{}' is not active (status: {})",
+                tenant_id, tenant.status
+            )));
+        }
+
+        let plaintext = self.generate_api_key();
+        let key_hash = {
+            let plaintext_clone = plaintext.clone();
+            tokio::task::spawn_blocking(move || {
+                hash(&plaintext_clone, DEFAULT_BCRYPT_COST).map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+            .map_err(|e| CatalogError::Other(format!("Hash error: {}", e)))?
+        };
+
+        let expires_at = (chrono::Utc::now() + chrono::Duration::seconds(ttl_secs as i64))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let operator = req.operator.trim().to_string();
+        let db_path = self.db_path.clone();
+        let tenant_id_owned = tenant_id.to_string();
+        let name = format!("impersonation:{}", operator);
+        let role = TenantRole::Viewer;
+        let (expires_at_owned, operator_owned, reason) =
+            (expires_at.clone(), operator.clone(), req.reason.clone());
+
+        let key_id = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+            conn.execute(
+                "INSERT INTO tenant_api_keys
+                    (tenant_id, key_hash, name, role, expires_at,
+                     impersonated_by, impersonation_reason)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    tenant_id_owned,
+                    key_hash,
+                    name,
+                    role.as_str(),
+                    expires_at_owned,
+                    operator_owned,
+                    reason,
+                ],
+            )?;
+            Ok::<_, CatalogError>(conn.last_insert_rowid())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        let details = serde_json::json!({
+            "operator": operator,
+            "reason": req.reason,
+            "key_id": key_id,
+            "role": role.as_str(),
+            "expires_at": expires_at,
+        });
+        self.audit_log(
+            "impersonation_start",
+            tenant_id,
+            &audit.actor,
+            Some(details.to_string()),
+            audit.request_id.as_deref(),
+            audit.client_ip.as_deref(),
+        )
+        .await?;
+
+        warn!(
+            tenant_id = %tenant_id,
+            impersonated_by = %operator,
+            key_id,
+            expires_at = %expires_at,
+            "Impersonation token minted"
+        );
+        Ok(ImpersonationToken {
+            token: plaintext,
+            key_id,
+            tenant_id: tenant_id.to_string(),
+            role,
+            impersonated_by: operator,
+            expires_at,
+        })
+    }
+
+    #[cfg(feature = "api-keys")]
+    /// Validate a tenant API key and return identity information.
+    pub async fn validate_tenant_api_key(
+        &self,
+        plaintext: &str,
+    ) -> Result<Option<ValidatedTenantKey>> {
+        let cache_key = self.cache_key_from_plaintext(plaintext);
+
+        // Check cache first
+        if let Some(cached) = self.key_cache.get(&cache_key) {
+            let key_expired = cached
+                .expires_at
+                .is_some_and(|expires_at| expires_at <= chrono::Utc::now());
+            if !key_expired && cached.cached_at.elapsed() < Duration::from_secs(CACHE_TTL_SECS) {
+                debug!("Tenant API key validation: cache hit");
+                self.mark_key_used(&cached.key_hash);
+                return Ok(Some(ValidatedTenantKey {
+                    key_hash: cached.key_hash.clone(),
+                    tenant_id: cached.tenant_id.clone(),
+                    name: cached.name.clone(),
+                    role: cached.role,
+                    tier: cached.tier,
+                    region: cached.region.clone(),
+                    impersonated_by: cached.impersonated_by.clone(),
+                    sandbox: cached.sandbox.clone(),
+                }));
+            }
+            // Release the read guard before removing the entry
+            drop(cached);
+            debug!(key_expired, "Tenant API key validation: cache expired");
+            self.key_cache.remove(&cache_key);
+        }
+
+        // Cache miss - query database
+        let db_path = self.db_path.clone();
+        let plaintext = plaintext.to_string();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+
+            // Get all valid (non-revoked, non-expired) keys for active tenants
+            // Include region for multi-region deployments
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT k.key_hash, k.tenant_id, k.name, k.role, t.tier, t.region,
+                       k.impersonated_by, k.expires_at, k.sandbox_namespace
+                FROM tenant_api_keys k
+                JOIN tenants t ON k.tenant_id = t.tenant_id
+                WHERE k.revoked_at IS NULL
+                  AND (k.expires_at IS NULL OR k.expires_at > datetime('now'))
+                  AND t.status = 'active'
+                "#,
+            )?;
+
+            let keys = stmt
+                .query_map([], |row| {
+                    let role: String = row.get(3)?;
+                    let tier: String = row.get(4)?;
+                    let expires_at: Option<String> = row.get(7)?;
+                    Ok((
+                        ValidatedTenantKey {
+                            key_hash: row.get(0)?,
+                            tenant_id: row.get(1)?,
+                            name: row.get(2)?,
+                            role: role.parse::<TenantRole>().unwrap_or_default(),
+                            tier: tier.parse::<TenantTier>().unwrap_or_default(),
+                            region: row.get(5)?,
+                            impersonated_by: row.get(6)?,
+                            sandbox: row.get(8)?,
+                        },
+                        expires_at,
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            // Verify against each hash
+            for (key, expires_at) in keys {
+                if verify(&plaintext, &key.key_hash).unwrap_or(false) {
+                    return Ok::<_, CatalogError>(Some((key, expires_at)));
+                }
+            }
+
+            Ok(None)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        if let Some((key, expires_at)) = result {
+            // Cache the valid key
+            self.key_cache.insert(
+                cache_key,
+                CachedTenantKey {
+                    key_hash: key.key_hash.clone(),
+                    tenant_id: key.tenant_id.clone(),
+                    name: key.name.clone(),
+                    role: key.role,
+                    tier: key.tier,
+                    region: key.region.clone(),
+                    impersonated_by: key.impersonated_by.clone(),
+                    sandbox: key.sandbox.clone(),
+                    expires_at: expires_at.as_deref().and_then(parse_key_expiry),
+                    cached_at: Instant::now(),
+                },
+            );
+
+            self.mark_key_used(&key.key_hash);
+
+            debug!(
+                tenant_id = %key.tenant_id,
+                name = %key.name,
+                tier = ?key.tier,
+                region = ?key.region,
+                impersonated_by = ?key.impersonated_by,
+                "Tenant API key validated"
+            );
+            Ok(Some(key))
+        } else {
+            warn!("Tenant API key validation failed");
+            Ok(None)
+        }
+    }
+
+    #[cfg(feature = "api-keys")]
     /// List API keys for a tenant.
     pub async fn list_tenant_api_keys(&self, tenant_id: &str) -> Result<Vec<TenantApiKey>> {
         let db_path = self.db_path.clone();
@@ -1333,7 +2889,8 @@ impl ControlPlane {
             let conn = Connection::open(&db_path)?;
 
             let mut stmt = conn.prepare(
-                "SELECT id, tenant_id, name, role, created_at, revoked_at, last_used_at, expires_at
+                "SELECT id, tenant_id, name, role, created_at, revoked_at, last_used_at, expires_at,
+                        impersonated_by, sandbox_namespace
                  FROM tenant_api_keys WHERE tenant_id = ?1 ORDER BY created_at DESC, id DESC",
             )?;
 
@@ -1348,6 +2905,8 @@ impl ControlPlane {
                         revoked_at: row.get(5)?,
                         last_used_at: row.get(6)?,
                         expires_at: row.get(7)?,
+                        impersonated_by: row.get(8)?,
+                        sandbox: row.get(9)?,
                     })
                 })?
                 .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -1499,11 +3058,21 @@ impl ControlPlane {
         .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
     }
 
-    /// Get audit log entries for a tenant.
-    pub async fn get_audit_log(
+    /// Get audit log entries for a tenant.
+    pub async fn get_audit_log(
+        &self,
+        tenant_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<AuditLogEntry>> {
+        self.get_audit_log_page(tenant_id, limit, 0).await
+    }
+
+    /// Get a page of audit log entries, newest first.
+    pub async fn get_audit_log_page(
         &self,
         tenant_id: Option<&str>,
         limit: usize,
+        offset: usize,
     ) -> Result<Vec<AuditLogEntry>> {
         let db_path = self.db_path.clone();
         let tenant_id = tenant_id.map(String::from);
@@ -1515,9 +3084,9 @@ impl ControlPlane {
                 let mut stmt = conn.prepare(
                     "SELECT id, timestamp, action, tenant_id, actor, details, request_id, client_ip
                      FROM tenant_audit_log WHERE tenant_id = ?1
-                     ORDER BY timestamp DESC LIMIT ?2",
+                     ORDER BY timestamp DESC LIMIT ?2 OFFSET ?3",
                 )?;
-                let rows = stmt.query_map(rusqlite::params![tid, limit], |row| {
+                let rows = stmt.query_map(rusqlite::params![tid, limit, offset], |row| {
                     Ok(AuditLogEntry {
                         id: row.get(0)?,
                         timestamp: row.get(1)?,
@@ -1534,9 +3103,9 @@ impl ControlPlane {
                 let mut stmt = conn.prepare(
                     "SELECT id, timestamp, action, tenant_id, actor, details, request_id, client_ip
                      FROM tenant_audit_log
-                     ORDER BY timestamp DESC LIMIT ?1",
+                     ORDER BY timestamp DESC LIMIT ?1 OFFSET ?2",
                 )?;
-                let rows = stmt.query_map(rusqlite::params![limit], |row| {
+                let rows = stmt.query_map(rusqlite::params![limit, offset], |row| {
                     Ok(AuditLogEntry {
                         id: row.get(0)?,
                         timestamp: row.get(1)?,
@@ -1556,6 +3125,148 @@ impl ControlPlane {
         .await
         .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
     }
+
+    /// Count audit log entries, for one tenant or all tenants.
+    pub async fn count_audit_log(&self, tenant_id: Option<&str>) -> Result<i64> {
+        let db_path = self.db_path.clone();
+        let tenant_id = tenant_id.map(String::from);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM tenant_audit_log WHERE ?1 IS NULL OR tenant_id = ?1",
+                [&tenant_id],
+                |row| row.get(0),
+            )?;
+            Ok::<_, CatalogError>(count)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    // =========================================================================
+    // Rate Limit Events
+    // =========================================================================
+
+    /// Persist aggregated rate limit violations.
+    ///
+    /// Writes one `rate_limit_events` row per violation and one
+    /// `rate_limit_exceeded` audit entry per affected tenant, in a single transaction.
+    /// Returns the number of event rows written.
+    #[cfg(feature = "rate-limiting")]
+    pub async fn record_rate_limit_violations(
+        &self,
+        violations: &[crate::rate_limiting::RateLimitViolation],
+    ) -> Result<usize> {
+        let db_path = self.db_path.clone();
+        let violations = violations.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let mut conn = Connection::open(&db_path)?;
+            let tx = conn.transaction()?;
+
+            let mut per_tenant: std::collections::BTreeMap<&str, (u64, Vec<&str>)> =
+                std::collections::BTreeMap::new();
+
+            for v in &violations {
+                tx.execute(
+                    "INSERT INTO rate_limit_events
+                     (tenant_id, client_key, route, violation_count, first_seen_at, last_seen_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    rusqlite::params![
+                        v.tenant_id,
+                        v.client_key,
+                        v.route,
+                        v.count as i64,
+                        v.first_seen.to_rfc3339(),
+                        v.last_seen.to_rfc3339(),
+                    ],
+                )?;
+
+                if let Some(tenant_id) = v.tenant_id.as_deref() {
+                    let entry = per_tenant.entry(tenant_id).or_default();
+                    entry.0 += v.count;
+                    if !entry.1.contains(&v.route.as_str()) {
+                        entry.1.push(&v.route);
+                    }
+                }
+            }
+
+            for (tenant_id, (count, routes)) in &per_tenant {
+                let details = serde_json::json!({
+                    "violations": count,
+                    "routes": routes,
+                });
+                tx.execute(
+                    "INSERT INTO tenant_audit_log (action, tenant_id, actor, details)
+                     VALUES ('rate_limit_exceeded', ?1, 'rate-limiter', ?2)",
+                    rusqlite::params![tenant_id, details.to_string()],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok::<_, CatalogError>(violations.len())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+
+    /// Summarize rate limit violations recorded in the last `since_days` days.
+    ///
+    /// Results are grouped by (tenant, client, route) and ordered by violation count.
+    pub async fn list_rate_limit_violations(
+        &self,
+        since_days: i64,
+        tenant_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RateLimitViolationSummary>> {
+        let db_path = self.db_path.clone();
+        let tenant_id = tenant_id.map(String::from);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open(&db_path)?;
+
+            let mut stmt = conn.prepare(
+                "SELECT tenant_id, client_key, route, SUM(violation_count),
+                        MIN(first_seen_at), MAX(last_seen_at)
+                 FROM rate_limit_events
+                 WHERE recorded_at >= datetime('now', ?1)
+                   AND (?2 IS NULL OR tenant_id = ?2)
+                 GROUP BY tenant_id, client_key, route
+                 ORDER BY SUM(violation_count) DESC, MAX(last_seen_at) DESC
+                 LIMIT ?3",
+            )?;
+            let rows = stmt.query_map(
+                rusqlite::params![format!("-{} days", since_days), tenant_id, limit],
+                |row| {
+                    Ok(RateLimitViolationSummary {
+                        tenant_id: row.get(0)?,
+                        client_key: row.get(1)?,
+                        route: row.get(2)?,
+                        violation_count: row.get(3)?,
+                        first_seen_at: row.get(4)?,
+                        last_seen_at: row.get(5)?,
+                    })
+                },
+            )?;
+
+            let summaries = rows.collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(summaries)
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+    }
+}
+
+/// Parse a stored key expiry (RFC 3339 or SQLite `datetime()` form) as UTC.
+#[cfg(feature = "api-keys")]
+fn parse_key_expiry(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|ts| ts.and_utc())
 }
 
 #[cfg(test)]
@@ -1589,6 +3300,112 @@ mod tests {
         assert!(!TenantRole::Viewer.can_manage_keys());
     }
 
+    #[test]
+    fn test_tenant_feature_parsing() {
+        for feature in TenantFeature::ALL {
+            assert_eq!(feature.as_str().parse::<TenantFeature>().unwrap(), feature);
+        }
+        assert_eq!(
+            "USAGE_ANALYTICS".parse::<TenantFeature>().unwrap(),
+            TenantFeature::UsageAnalytics
+        );
+        assert!("unknown".parse::<TenantFeature>().is_err());
+    }
+
+    #[test]
+    fn test_tenant_feature_flags_default_enabled() {
+        let mut flags = TenantFeatureFlags::default();
+        for feature in TenantFeature::ALL {
+            assert!(flags.is_enabled(feature));
+        }
+
+        flags.set(TenantFeature::Preview, false);
+        assert!(!flags.is_enabled(TenantFeature::Preview));
+        assert!(flags.is_enabled(TenantFeature::Classification));
+    }
+
+    #[test]
+    fn test_update_feature_flags_request_changes() {
+        let req = UpdateTenantFeatureFlagsRequest {
+            classification: Some(false),
+            preview: None,
+            usage_analytics: Some(true),
+        };
+        assert_eq!(
+            req.changes(),
+            vec![
+                (TenantFeature::Classification, false),
+                (TenantFeature::UsageAnalytics, true)
+            ]
+        );
+        assert!(UpdateTenantFeatureFlagsRequest::default()
+            .changes()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dataset_defaults_apply() {
+        let defaults = TenantDatasetDefaults {
+            tags: vec!["tenant:{tenant_id}".to_string(), "managed".to_string()],
+            domain: Some("shared".to_string()),
+            owner: Some("data-eng".to_string()),
+            owner_domains: [("growth".to_string(), "marketing".to_string())].into(),
+        };
+        assert!(defaults.validate("acme").is_ok());
+
+        let (mut tags, mut domain, mut owner) = (None, None, None);
+        defaults.apply("acme", &mut tags, &mut domain, &mut owner);
+        assert_eq!(tags.unwrap(), vec!["tenant:acme", "managed"]);
+        assert_eq!(domain.as_deref(), Some("shared"));
+        assert_eq!(owner.as_deref(), Some("data-eng"));
+
+        // Request values win; the owner's team picks the domain
+        let (mut tags, mut domain, mut owner) = (Some(vec![]), None, Some("growth".to_string()));
+        defaults.apply("acme", &mut tags, &mut domain, &mut owner);
+        assert_eq!(tags.unwrap(), Vec::<String>::new());
+        assert_eq!(domain.as_deref(), Some("marketing"));
+
+        let invalid = TenantDatasetDefaults {
+            tags: vec!["has space".to_string()],
+            ..Default::default()
+        };
+        assert!(invalid.validate("acme").is_err());
+        assert!(TenantDatasetDefaults::default().is_empty());
+    }
+
+    #[test]
+    fn test_impersonation_request_validate() {
+        let req = |operator: &str, reason: &str, ttl_secs: Option<u64>| ImpersonationRequest {
+            operator: operator.to_string(),
+            reason: reason.to_string(),
+            ttl_secs,
+        };
+        assert_eq!(
+            req("sam@platform.test", "ticket 42", None)
+                .validate()
+                .unwrap(),
+            IMPERSONATION_DEFAULT_TTL_SECS
+        );
+        assert_eq!(
+            req("sam@platform.test", "ticket 42", Some(60))
+                .validate()
+                .unwrap(),
+            60
+        );
+        assert!(req(" ", "ticket 42", None).validate().is_err());
+        assert!(req("sam@platform.test", "", None).validate().is_err());
+        assert!(req("sam@platform.test", "ticket 42", Some(0))
+            .validate()
+            .is_err());
+        assert!(req(
+            "sam@platform.test",
+            "ticket 42",
+            Some(IMPERSONATION_MAX_TTL_SECS + 1)
+        )
+        .validate()
+        .is_err());
+    }
+
     #[tokio::test]
     async fn test_control_plane_new() {
         // Valid template
@@ -2206,4 +4023,412 @@ mod tests {
             .await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    #[cfg(feature = "api-keys")]
+    async fn test_tenant_residency() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("control.db")
+            .to_string_lossy()
+            .to_string();
+        let storage = temp_dir
+            .path()
+            .join("{tenant_id}/db")
+            .to_string_lossy()
+            .to_string();
+
+        let cp = ControlPlane::new(db_path, storage)
+            .unwrap()
+            .with_residency_zones(
+                ResidencyZones::parse("eu=europe-west1,eu-central-1;us=us-east1").unwrap(),
+            );
+        cp.initialize().await.unwrap();
+
+        cp.create_tenant(
+            CreateTenantRequest {
+                tenant_id: "eu-tenant".to_string(),
+                display_name: "EU Tenant".to_string(),
+                admin_email: "admin@test.com".to_string(),
+                tier: None,
+                quota_max_datasets: None,
+                quota_max_storage_bytes: None,
+                quota_max_api_calls_per_hour: None,
+                region: Some("europe-west1".to_string()),
+            },
+            AuditContext::default(),
+        )
+        .await
+        .unwrap();
+        assert!(cp
+            .get_tenant_residency("eu-tenant")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Zone must exist and contain the tenant's region
+        assert!(cp
+            .set_tenant_residency("eu-tenant", Some("us".to_string()), AuditContext::default())
+            .await
+            .is_err());
+        let residency = cp
+            .set_tenant_residency("eu-tenant", Some("eu".to_string()), AuditContext::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(residency.zone, "eu");
+        assert_eq!(residency.region.as_deref(), Some("europe-west1"));
+
+        // Region changes stay inside the zone
+        let region_update = |region: &str| UpdateTenantRequest {
+            display_name: None,
+            tier: None,
+            admin_email: None,
+            quota_max_datasets: None,
+            quota_max_storage_bytes: None,
+            quota_max_api_calls_per_hour: None,
+            region: Some(region.to_string()),
+        };
+        assert!(cp
+            .update_tenant(
+                "eu-tenant",
+                region_update("us-east1"),
+                AuditContext::default()
+            )
+            .await
+            .is_err());
+        cp.update_tenant(
+            "eu-tenant",
+            region_update("eu-central-1"),
+            AuditContext::default(),
+        )
+        .await
+        .unwrap();
+        let residency = cp.get_tenant_residency("eu-tenant").await.unwrap().unwrap();
+        assert_eq!(residency.region.as_deref(), Some("eu-central-1"));
+
+        // Clearing the zone lifts the restriction
+        assert!(cp
+            .set_tenant_residency("eu-tenant", None, AuditContext::default())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(cp
+            .get_tenant_residency("eu-tenant")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Unknown tenant
+        assert!(cp
+            .set_tenant_residency("missing", Some("eu".to_string()), AuditContext::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_schedules() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("control.db")
+            .to_string_lossy()
+            .to_string();
+        let storage = temp_dir
+            .path()
+            .join("{tenant_id}/db")
+            .to_string_lossy()
+            .to_string();
+
+        let cp = ControlPlane::new(db_path, storage).unwrap();
+        cp.initialize().await.unwrap();
+        cp.create_tenant(
+            CreateTenantRequest {
+                tenant_id: "acme".to_string(),
+                display_name: "Acme".to_string(),
+                admin_email: "admin@test.com".to_string(),
+                tier: None,
+                quota_max_datasets: None,
+                quota_max_storage_bytes: None,
+                quota_max_api_calls_per_hour: None,
+                region: None,
+            },
+            AuditContext::default(),
+        )
+        .await
+        .unwrap();
+
+        let create = |target_uri: &str| CreateExportScheduleRequest {
+            target_uri: target_uri.to_string(),
+            format: ExportFormat::Ndjson,
+            frequency: ExportFrequency::Daily,
+            credentials_profile: None,
+            next_run_at: None,
+        };
+        assert!(matches!(
+            cp.create_export_schedule("acme", create("/tmp/exports"), AuditContext::default())
+                .await,
+            Err(CatalogError::ValidationError(_))
+        ));
+        assert!(matches!(
+            cp.create_export_schedule("missing", create("gs://b/x"), AuditContext::default())
+                .await,
+            Err(CatalogError::DatasetNotFound(_))
+        ));
+
+        let schedule = cp
+            .create_export_schedule("acme", create("gs://acme/exports"), AuditContext::default())
+            .await
+            .unwrap();
+        assert!(schedule.enabled);
+        assert_eq!(cp.list_export_schedules("acme").await.unwrap().len(), 1);
+
+        // A due schedule is claimed once and advanced a day
+        let claimed = cp.claim_due_export_schedules().await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        let (claimed_schedule, run_id) = &claimed[0];
+        assert_eq!(claimed_schedule.id, schedule.id);
+        assert!(cp.claim_due_export_schedules().await.unwrap().is_empty());
+        let advanced = cp
+            .get_export_schedule("acme", schedule.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(advanced.next_run_at > schedule.next_run_at);
+
+        let runs = cp.list_export_runs(schedule.id, 10).await.unwrap();
+        assert_eq!(runs[0].status, ExportRunStatus::Running);
+        cp.finish_export_run(*run_id, &Err("bucket not found".to_string()))
+            .await
+            .unwrap();
+        let runs = cp.list_export_runs(schedule.id, 10).await.unwrap();
+        assert_eq!(runs[0].status, ExportRunStatus::Failed);
+        assert_eq!(runs[0].error.as_deref(), Some("bucket not found"));
+        assert!(runs[0].finished_at.is_some());
+
+        // Disabled schedules aren't claimed even when due
+        let updated = cp
+            .update_export_schedule(
+                "acme",
+                schedule.id,
+                UpdateExportScheduleRequest {
+                    enabled: Some(false),
+                    next_run_at: Some("2020-01-01T00:00:00Z".to_string()),
+                    ..Default::default()
+                },
+                AuditContext::default(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!updated.enabled);
+        assert_eq!(updated.target_uri, "gs://acme/exports");
+        assert_eq!(updated.next_run_at, "2020-01-01 00:00:00");
+        assert!(cp.claim_due_export_schedules().await.unwrap().is_empty());
+
+        // Other tenants can't see or change it
+        assert!(cp
+            .get_export_schedule("other", schedule.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!cp
+            .delete_export_schedule("other", schedule.id, AuditContext::default())
+            .await
+            .unwrap());
+
+        assert!(cp
+            .delete_export_schedule("acme", schedule.id, AuditContext::default())
+            .await
+            .unwrap());
+        assert!(cp
+            .list_export_runs(schedule.id, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_credentials_profiles() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir
+            .path()
+            .join("control.db")
+            .to_string_lossy()
+            .to_string();
+        let storage = temp_dir
+            .path()
+            .join("{tenant_id}/db")
+            .to_string_lossy()
+            .to_string();
+
+        let key = |base64: &str| Some(CredentialsKey::from_base64(base64).unwrap());
+        let cp = ControlPlane::new(db_path.clone(), storage.clone())
+            .unwrap()
+            .with_credentials_key(key("BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc="));
+        cp.initialize().await.unwrap();
+        cp.create_tenant(
+            CreateTenantRequest {
+                tenant_id: "acme".to_string(),
+                display_name: "Acme".to_string(),
+                admin_email: "admin@test.com".to_string(),
+                tier: None,
+                quota_max_datasets: None,
+                quota_max_storage_bytes: None,
+                quota_max_api_calls_per_hour: None,
+                region: None,
+            },
+            AuditContext::default(),
+        )
+        .await
+        .unwrap();
+
+        let s3 = |secret: &str| ProfileCredentials::S3 {
+            access_key_id: "AKIAEXAMPLE".to_string(),
+            secret_access_key: secret.to_string(),
+            session_token: None,
+        };
+        let (profile, created) = cp
+            .put_credentials_profile("acme", "exports", s3("first"), AuditContext::default())
+            .await
+            .unwrap();
+        assert!(created);
+        assert_eq!(profile.provider, "s3");
+        let listed = cp.list_credentials_profiles("acme").await.unwrap();
+        for response in [
+            serde_json::to_string(&profile).unwrap(),
+            serde_json::to_string(&listed).unwrap(),
+        ] {
+            assert!(!response.contains("AKIAEXAMPLE") && !response.contains("first"));
+        }
+
+        // Secrets are stored encrypted, and only the key reads them back
+        let stored: String = Connection::open(&db_path)
+            .unwrap()
+            .query_row(
+                "SELECT credentials FROM tenant_credentials_profiles WHERE name = 'exports'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(stored.starts_with("v1:"));
+        assert!(!stored.contains("AKIAEXAMPLE"));
+        let without_key = ControlPlane::new(db_path.clone(), storage.clone()).unwrap();
+        let other_key = ControlPlane::new(db_path.clone(), storage.clone())
+            .unwrap()
+            .with_credentials_key(key("CQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQk="));
+        for other in [&without_key, &other_key] {
+            assert!(other
+                .get_profile_credentials("acme", "exports")
+                .await
+                .is_err());
+        }
+        assert!(matches!(
+            without_key
+                .put_credentials_profile("acme", "other", s3("x"), AuditContext::default())
+                .await,
+            Err(CatalogError::Other(_))
+        ));
+        assert!(matches!(
+            cp.put_credentials_profile("missing", "exports", s3("x"), AuditContext::default())
+                .await,
+            Err(CatalogError::DatasetNotFound(_))
+        ));
+
+        // Schedules must name an existing profile for their target's store
+        let create = |target_uri: &str, profile: &str| CreateExportScheduleRequest {
+            target_uri: target_uri.to_string(),
+            format: ExportFormat::Json,
+            frequency: ExportFrequency::Daily,
+            credentials_profile: Some(profile.to_string()),
+            next_run_at: None,
+        };
+        for (target_uri, profile) in [
+            ("s3://acme/dumps", "missing"),
+            ("gs://acme/dumps", "exports"),
+        ] {
+            assert!(matches!(
+                cp.create_export_schedule(
+                    "acme",
+                    create(target_uri, profile),
+                    AuditContext::default()
+                )
+                .await,
+                Err(CatalogError::ValidationError(_))
+            ));
+        }
+        let schedule = cp
+            .create_export_schedule(
+                "acme",
+                create("s3://acme/dumps", "exports"),
+                AuditContext::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(schedule.credentials_profile.as_deref(), Some("exports"));
+        let moved = UpdateExportScheduleRequest {
+            target_uri: Some("gs://acme/dumps".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            cp.update_export_schedule("acme", schedule.id, moved, AuditContext::default())
+                .await,
+            Err(CatalogError::ValidationError(_))
+        ));
+
+        // Replacing rotates the credentials the schedule uploads with
+        let (_, created) = cp
+            .put_credentials_profile("acme", "exports", s3("second"), AuditContext::default())
+            .await
+            .unwrap();
+        assert!(!created);
+        assert_eq!(
+            cp.get_profile_credentials("acme", "exports").await.unwrap(),
+            Some(s3("second"))
+        );
+        assert!(cp
+            .get_profile_credentials("other", "exports")
+            .await
+            .unwrap()
+            .is_none());
+        let gcs = ProfileCredentials::Gcs {
+            service_account_key: serde_json::json!({"private_key": "-----BEGIN"}),
+        };
+        assert!(matches!(
+            cp.put_credentials_profile("acme", "exports", gcs, AuditContext::default())
+                .await,
+            Err(CatalogError::ValidationError(_))
+        ));
+
+        // A profile in use can't be deleted
+        assert!(matches!(
+            cp.delete_credentials_profile("acme", "exports", AuditContext::default())
+                .await,
+            Err(CatalogError::ConflictError(_))
+        ));
+        let cleared = cp
+            .update_export_schedule(
+                "acme",
+                schedule.id,
+                UpdateExportScheduleRequest {
+                    credentials_profile: Some(String::new()),
+                    ..Default::default()
+                },
+                AuditContext::default(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cleared.credentials_profile, None);
+        assert!(cp
+            .delete_credentials_profile("acme", "exports", AuditContext::default())
+            .await
+            .unwrap());
+        assert!(cp
+            .list_credentials_profiles("acme")
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,324 @@
+//! Custom Metadata
+//!
+//! Reading and merge-patching a dataset's `custom_metadata`, and the optional
+//! JSON Schema it is validated against.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_acl;
+use crate::dataset_writes::{
+    apply_hook_changes, run_partial_write_hooks, spawn_post_commit_hooks, stored_dataset_write,
+};
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    bad_request, dataset_not_found, internal_error, not_found, require_dataset_access, AppState,
+    AuditContext, Caller, DatasetPath, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::server::{rbac_error, require_field_permissions};
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::hooks::WriteOperation;
+use metafuse_catalog_core::{custom_metadata, validation};
+use serde::Serialize;
+
+/// Dataset custom metadata response
+#[derive(Debug, Serialize)]
+pub(crate) struct CustomMetadataResponse {
+    dataset_name: String,
+    /// Empty object when none is set
+    custom_metadata: serde_json::Value,
+}
+
+/// Custom metadata schema response
+#[derive(Debug, Serialize)]
+pub(crate) struct CustomMetadataSchemaResponse {
+    /// `null` when custom metadata is unvalidated
+    schema: Option<serde_json::Value>,
+}
+
+/// Map a custom metadata error to a response
+pub(crate) fn custom_metadata_error(
+    e: metafuse_catalog_core::CatalogError,
+    request_id: &str,
+) -> (StatusCode, Json<ErrorResponse>) {
+    match e {
+        metafuse_catalog_core::CatalogError::ValidationError(message) => {
+            bad_request(message, request_id.to_string())
+        }
+        e => internal_error(e.to_string(), request_id.to_string()),
+    }
+}
+
+/// Get a dataset's custom metadata
+pub(crate) async fn get_custom_metadata(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<CustomMetadataResponse>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let metadata = custom_metadata::load(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(CustomMetadataResponse {
+        dataset_name: name,
+        custom_metadata: metadata.unwrap_or_else(|| serde_json::json!({})),
+    }))
+}
+
+/// Merge a JSON Merge Patch (RFC 7396) into a dataset's custom metadata
+///
+/// Keys set to `null` are removed; the result is validated against the
+/// catalog's schema before it is stored.
+pub(crate) async fn patch_custom_metadata(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    Json(patch): Json<serde_json::Value>,
+) -> Result<Json<CustomMetadataResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+    #[cfg(feature = "api-keys")]
+    require_field_permissions(
+        &state.field_permissions,
+        resolved_tenant.as_ref().map(|e| &e.0),
+        ["custom_metadata"],
+        &request_id.0,
+    )?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    if !patch.is_object() {
+        return Err(bad_request(
+            "Custom metadata patch must be a JSON object".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    // Run pre-validate write hooks; custom metadata isn't part of the write
+    let intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let old_metadata = custom_metadata::load(&tx, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .unwrap_or_else(|| serde_json::json!({}));
+    let mut metadata = old_metadata.clone();
+    custom_metadata::merge_patch(&mut metadata, &patch);
+    custom_metadata::check(&tx, &metadata).map_err(|e| custom_metadata_error(e, &request_id.0))?;
+    custom_metadata::store(&tx, dataset_id, Some(&metadata))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.execute(
+        "UPDATE datasets SET last_updated = datetime('now') WHERE id = ?1",
+        [dataset_id],
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Custom metadata updated");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset_custom_metadata",
+            &name,
+            old_metadata,
+            metadata.clone(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(CustomMetadataResponse {
+        dataset_name: name,
+        custom_metadata: metadata,
+    }))
+}
+
+/// Get the catalog's custom metadata schema
+pub(crate) async fn get_custom_metadata_schema(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+) -> Result<Json<CustomMetadataSchemaResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let schema = custom_metadata::get_schema(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(CustomMetadataSchemaResponse { schema }))
+}
+
+/// Set the catalog's custom metadata schema
+///
+/// Applies to later writes only; metadata already stored isn't revalidated.
+pub(crate) async fn set_custom_metadata_schema(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Json(schema): Json<serde_json::Value>,
+) -> Result<Json<CustomMetadataSchemaResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let old_schema = custom_metadata::get_schema(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    custom_metadata::set_schema(&conn, &schema)
+        .map_err(|e| custom_metadata_error(e, &request_id.0))?;
+
+    tracing::info!("Custom metadata schema updated");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "custom_metadata_schema",
+            "schema",
+            serde_json::json!({ "schema": old_schema }),
+            serde_json::json!({ "schema": schema }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(CustomMetadataSchemaResponse {
+        schema: Some(schema),
+    }))
+}
+
+/// Remove the catalog's custom metadata schema
+pub(crate) async fn delete_custom_metadata_schema(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let old_schema = custom_metadata::get_schema(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !custom_metadata::clear_schema(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    {
+        return Err(not_found(
+            "No custom metadata schema is set".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!("Custom metadata schema removed");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "custom_metadata_schema",
+            "schema",
+            serde_json::json!({ "schema": old_schema }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,378 @@
+// Allow dead_code: zones and checks are only used by the binary with the
+// `api-keys` feature (control plane and tenant middleware)
+#![allow(dead_code)]
+
+//! Tenant Data Residency
+//!
+//! Some tenants must keep their data in one jurisdiction, e.g. EU-only
+//! storage. A tenant's residency zone (control plane, migration v1.36.0)
+//! names the regions its catalog may live in; the tenant's `region` must be
+//! one of them, and that region must be routed to region-specific storage.
+//!
+//! # Routing
+//!
+//! Regions are routed to their own storage by a region-specific template in
+//! `METAFUSE_REGION_STORAGE_TEMPLATES`, or by a `{region}` placeholder in
+//! `METAFUSE_TENANT_STORAGE_TEMPLATE`. Without either, every region shares
+//! the default template's location.
+//!
+//! # Enforcement
+//!
+//! - Setting a zone, or changing a resident tenant's region, fails unless the
+//!   region belongs to the zone.
+//! - Writes of a resident tenant are rejected when its region is outside the
+//!   zone or not routed to region-specific storage, e.g. after the
+//!   configuration changed. Reads still work.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_RESIDENCY_ZONES`: zones and their regions, e.g.
+//!   `eu=europe-west1,eu-central-1;us=us-east1,us-central1`
+//! - `METAFUSE_REGION_STORAGE_TEMPLATES`: region-specific templates, e.g.
+//!   `europe-west1=gs://metafuse-eu/tenants/{tenant_id}/catalog.db`
+
+#[cfg(feature = "api-keys")]
+use crate::control_plane::AuditContext as ControlPlaneAuditContext;
+#[cfg(feature = "api-keys")]
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use axum::extract::{Extension, Path, State};
+#[cfg(feature = "api-keys")]
+use axum::http::StatusCode;
+#[cfg(feature = "api-keys")]
+use axum::Json;
+use metafuse_catalog_core::{CatalogError, Result};
+use metafuse_catalog_storage::TenantBackendFactory;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Residency zones and the regions that belong to each.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResidencyZones {
+    zones: BTreeMap<String, Vec<String>>,
+}
+
+impl ResidencyZones {
+    /// Load zones from `METAFUSE_RESIDENCY_ZONES`.
+    ///
+    /// An invalid value is ignored with a warning.
+    pub fn from_env() -> Self {
+        match std::env::var("METAFUSE_RESIDENCY_ZONES") {
+            Ok(value) => Self::parse(&value).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Ignoring invalid METAFUSE_RESIDENCY_ZONES");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Parse `zone=region,region;zone=region`.
+    pub fn parse(value: &str) -> std::result::Result<Self, String> {
+        let mut zones = BTreeMap::new();
+        for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (zone, regions) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected zone=regions, got '{}'", entry))?;
+            let regions: Vec<String> = regions
+                .split(',')
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(String::from)
+                .collect();
+            if zone.trim().is_empty() || regions.is_empty() {
+                return Err(format!("zone '{}' needs a name and regions", entry));
+            }
+            zones.insert(zone.trim().to_string(), regions);
+        }
+        Ok(Self { zones })
+    }
+
+    /// Whether any zone is configured.
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+
+    /// Regions of a zone, or `None` for an unknown zone.
+    pub fn regions(&self, zone: &str) -> Option<&[String]> {
+        self.zones.get(zone).map(Vec::as_slice)
+    }
+
+    /// Check that a tenant in `region` may have residency `zone`.
+    pub fn check(&self, zone: &str, region: Option<&str>) -> Result<()> {
+        let Some(regions) = self.regions(zone) else {
+            let known: Vec<&str> = self.zones.keys().map(String::as_str).collect();
+            return Err(CatalogError::ValidationError(format!(
+                "Unknown residency zone '{}'. Configured zones: {}",
+                zone,
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            )));
+        };
+        match region {
+            Some(region) if regions.iter().any(|r| r == region) => Ok(()),
+            Some(region) => Err(CatalogError::ValidationError(format!(
+                "Region '{}' is outside residency zone '{}' ({})",
+                region,
+                zone,
+                regions.join(", ")
+            ))),
+            None => Err(CatalogError::ValidationError(format!(
+                "Residency zone '{}' requires a region ({})",
+                zone,
+                regions.join(", ")
+            ))),
+        }
+    }
+
+    /// Check that a resident tenant's writes stay in its zone: the region
+    /// belongs to the zone and is routed to region-specific storage.
+    pub fn check_placement(
+        &self,
+        residency: &TenantResidency,
+        factory: &TenantBackendFactory,
+    ) -> Result<()> {
+        self.check(&residency.zone, residency.region.as_deref())?;
+        match residency.region.as_deref() {
+            Some(region) if factory.routes_region(region) => Ok(()),
+            region => Err(CatalogError::ValidationError(format!(
+                "Region '{}' has no region-specific storage configured",
+                region.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+/// A tenant's residency zone and its current region.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantResidency {
+    pub zone: String,
+    pub region: Option<String>,
+}
+
+/// Request to set or clear a tenant's residency zone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateTenantResidencyRequest {
+    /// Zone to require, or `null` to drop the requirement
+    pub zone: Option<String>,
+}
+
+/// Load region-specific storage templates from
+/// `METAFUSE_REGION_STORAGE_TEMPLATES` (`region=template,region=template`).
+pub fn region_templates_from_env() -> HashMap<String, String> {
+    std::env::var("METAFUSE_REGION_STORAGE_TEMPLATES")
+        .map(|value| parse_region_templates(&value))
+        .unwrap_or_default()
+}
+
+/// Parse `region=template` pairs separated by commas.
+pub fn parse_region_templates(value: &str) -> HashMap<String, String> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (region, template) = entry.split_once('=')?;
+            let (region, template) = (region.trim(), template.trim());
+            (!region.is_empty() && !template.is_empty())
+                .then(|| (region.to_string(), template.to_string()))
+        })
+        .collect()
+}
+
+/// Tenant data residency, as returned by the admin API
+#[cfg(feature = "api-keys")]
+#[derive(Debug, Serialize)]
+pub(crate) struct AdminTenantResidencyResponse {
+    tenant_id: String,
+    /// Residency zone, or null without a requirement
+    zone: Option<String>,
+    region: Option<String>,
+    /// Whether the region is routed to region-specific storage
+    region_storage: bool,
+}
+
+/// Get the data residency of a tenant
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_get_tenant_residency(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+) -> std::result::Result<Json<AdminTenantResidencyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let tenant = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Tenant '{}' not found", tenant_id),
+                request_id.0.clone(),
+            )
+        })?;
+    let residency = control_plane
+        .get_tenant_residency(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let region_storage = match (&tenant.region, state.multi_tenant.factory()) {
+        (Some(region), Some(factory)) => factory.routes_region(region),
+        _ => false,
+    };
+
+    Ok(Json(AdminTenantResidencyResponse {
+        tenant_id,
+        zone: residency.map(|r| r.zone),
+        region: tenant.region,
+        region_storage,
+    }))
+}
+
+/// Set or clear the data residency of a tenant
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_update_tenant_residency(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<UpdateTenantResidencyRequest>,
+) -> std::result::Result<Json<AdminTenantResidencyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let tenant = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Tenant '{}' not found", tenant_id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // Refuse a zone the tenant's writes could not satisfy
+    if let (Some(zone), Some(factory)) = (&req.zone, state.multi_tenant.factory()) {
+        let residency = TenantResidency {
+            zone: zone.clone(),
+            region: tenant.region.clone(),
+        };
+        if let Err(metafuse_catalog_core::CatalogError::ValidationError(msg)) = control_plane
+            .residency_zones()
+            .check_placement(&residency, factory)
+        {
+            return Err(bad_request(msg, request_id.0.clone()));
+        }
+    }
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let residency = control_plane
+        .set_tenant_residency(&tenant_id, req.zone, cp_audit)
+        .await
+        .map_err(|e| match e {
+            metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+                bad_request(msg, request_id.0.clone())
+            }
+            metafuse_catalog_core::CatalogError::DatasetNotFound(msg) => {
+                not_found(msg, request_id.0.clone())
+            }
+            e => internal_error(e.to_string(), request_id.0.clone()),
+        })?;
+
+    tracing::info!(
+        tenant_id = %tenant_id,
+        zone = ?residency.as_ref().map(|r| &r.zone),
+        "Tenant data residency updated"
+    );
+
+    let region_storage = match (&tenant.region, state.multi_tenant.factory()) {
+        (Some(region), Some(factory)) => factory.routes_region(region),
+        _ => false,
+    };
+
+    Ok(Json(AdminTenantResidencyResponse {
+        tenant_id,
+        zone: residency.map(|r| r.zone),
+        region: tenant.region,
+        region_storage,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zones() -> ResidencyZones {
+        ResidencyZones::parse("eu=europe-west1, eu-central-1; us=us-east1").unwrap()
+    }
+
+    #[test]
+    fn test_parse_zones() {
+        let zones = zones();
+        assert_eq!(
+            zones.regions("eu").unwrap(),
+            &["europe-west1".to_string(), "eu-central-1".to_string()]
+        );
+        assert!(zones.regions("apac").is_none());
+        assert!(ResidencyZones::parse("").unwrap().is_empty());
+        assert!(ResidencyZones::parse("eu").is_err());
+        assert!(ResidencyZones::parse("eu=").is_err());
+    }
+
+    #[test]
+    fn test_check_region_in_zone() {
+        let zones = zones();
+        assert!(zones.check("eu", Some("eu-central-1")).is_ok());
+        assert!(zones.check("eu", Some("us-east1")).is_err());
+        assert!(zones.check("eu", None).is_err());
+        assert!(zones.check("apac", Some("us-east1")).is_err());
+    }
+
+    #[test]
+    fn test_check_placement_requires_routing() {
+        let zones = zones();
+        let residency = TenantResidency {
+            zone: "eu".to_string(),
+            region: Some("europe-west1".to_string()),
+        };
+
+        let shared = TenantBackendFactory::new("gs://shared/{tenant_id}/catalog.db", 10).unwrap();
+        assert!(zones.check_placement(&residency, &shared).is_err());
+
+        let routed = TenantBackendFactory::new("gs://shared/{tenant_id}/catalog.db", 10)
+            .unwrap()
+            .with_region_templates(parse_region_templates(
+                "europe-west1=gs://eu-only/{tenant_id}/catalog.db",
+            ))
+            .unwrap();
+        assert!(zones.check_placement(&residency, &routed).is_ok());
+    }
+
+    #[test]
+    fn test_parse_region_templates() {
+        let templates = parse_region_templates(
+            "europe-west1=gs://eu/{tenant_id}/catalog.db, us-east1 = s3://us/{tenant_id}/db,bad",
+        );
+        assert_eq!(templates.len(), 2);
+        assert_eq!(templates["us-east1"], "s3://us/{tenant_id}/db");
+    }
+}
@@ -0,0 +1,769 @@
+//! Dataset Access Control Lists
+//!
+//! Tenant roles decide what a caller may do across the whole catalog. Dataset
+//! ACLs narrow that per dataset: a dataset with an ACL is only visible to the
+//! listed principals, and only principals with `write` may change it.
+//!
+//! - Principals are `user:<id>` or `group:<id>`
+//! - `write` implies `read`
+//! - A dataset with entries of its own uses only those; otherwise it inherits
+//!   the default entries of its domain
+//! - A dataset with no entries either way is open to every tenant member
+//! - Tenant admins bypass ACLs
+//!
+//! Hidden datasets are reported as not found, so their names do not leak.
+//!
+//! # Identity
+//!
+//! The caller's user and groups come from headers set by a trusted
+//! authenticating proxy. Header identity is off unless configured, in which
+//! case every caller is anonymous and can only see datasets without an ACL.
+//!
+//! - `METAFUSE_IDENTITY_USER_HEADER`: header carrying the user id (e.g. `X-Forwarded-User`)
+//! - `METAFUSE_IDENTITY_GROUPS_HEADER`: header carrying comma-separated groups (e.g. `X-Forwarded-Groups`)
+//!
+//! Only set these when the proxy strips the same headers from client requests.
+//!
+//! The header groups are claims; the caller's groups are resolved from them
+//! and any other configured group providers (see `groups`).
+//!
+//! Requests authenticated with an OIDC token (see `oidc`) take the user and
+//! group claims from the verified token instead of the headers.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::groups::GroupResolver;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_admin_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, dataset_not_found, internal_error, require_dataset_access, AppState, AuditContext,
+    Caller, DatasetPath, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use axum::{
+    extract::{Extension, Request},
+    middleware::Next,
+    response::Response,
+};
+use metafuse_catalog_core::validation;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Maximum number of entries in one ACL
+pub const MAX_ACL_ENTRIES: usize = 200;
+
+/// Access level granted to a principal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AclPermission {
+    Read,
+    Write,
+}
+
+impl AclPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AclPermission::Read => "read",
+            AclPermission::Write => "write",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "read" => Some(AclPermission::Read),
+            "write" => Some(AclPermission::Write),
+            _ => None,
+        }
+    }
+}
+
+/// One ACL entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AclEntry {
+    /// `user:<id>` or `group:<id>`
+    pub principal: String,
+    pub permission: AclPermission,
+}
+
+/// Validate a principal string.
+pub fn validate_principal(principal: &str) -> Result<(), String> {
+    let id = principal
+        .strip_prefix("user:")
+        .or_else(|| principal.strip_prefix("group:"))
+        .ok_or_else(|| {
+            format!(
+                "Invalid principal '{}': expected 'user:<id>' or 'group:<id>'",
+                principal
+            )
+        })?;
+    if id.trim().is_empty() || id.len() > 256 || id.chars().any(char::is_control) {
+        return Err(format!("Invalid principal '{}'", principal));
+    }
+    Ok(())
+}
+
+/// Validate a full ACL before storing it.
+pub fn validate_entries(entries: &[AclEntry]) -> Result<(), String> {
+    if entries.len() > MAX_ACL_ENTRIES {
+        return Err(format!(
+            "Too many ACL entries: {} (max {})",
+            entries.len(),
+            MAX_ACL_ENTRIES
+        ));
+    }
+    for entry in entries {
+        validate_principal(&entry.principal)?;
+    }
+    Ok(())
+}
+
+/// The caller, as seen by dataset ACLs.
+#[derive(Debug, Clone, Default)]
+pub struct Identity {
+    pub user: Option<String>,
+    pub groups: Vec<String>,
+    /// Tenant admins are not subject to dataset ACLs
+    pub bypass: bool,
+}
+
+impl Identity {
+    /// Principals this caller matches.
+    pub fn principals(&self) -> Vec<String> {
+        self.user
+            .iter()
+            .map(|u| format!("user:{}", u))
+            .chain(self.groups.iter().map(|g| format!("group:{}", g)))
+            .collect()
+    }
+
+    /// Owner ids this caller answers for: their user id, then their groups.
+    pub fn owner_ids(&self) -> Vec<String> {
+        self.user.iter().chain(&self.groups).cloned().collect()
+    }
+}
+
+/// Identity header configuration
+#[derive(Debug, Clone, Default)]
+pub struct IdentityConfig {
+    pub user_header: Option<String>,
+    pub groups_header: Option<String>,
+    /// Resolves the caller's groups from the header claims
+    pub groups: GroupResolver,
+}
+
+impl IdentityConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Self {
+        let header = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .map(|h| h.trim().to_lowercase())
+                .filter(|h| !h.is_empty())
+        };
+        Self {
+            user_header: header("METAFUSE_IDENTITY_USER_HEADER"),
+            groups_header: header("METAFUSE_IDENTITY_GROUPS_HEADER"),
+            groups: GroupResolver::default(),
+        }
+    }
+
+    /// Resolve groups with the given providers instead of header claims only.
+    pub fn with_groups(mut self, groups: GroupResolver) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Resolve the caller from request headers.
+    pub fn identity(&self, headers: &axum::http::HeaderMap) -> Identity {
+        let value = |name: &Option<String>| {
+            name.as_ref()
+                .and_then(|n| headers.get(n.as_str()))
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let user = value(&self.user_header).map(str::to_string);
+        let claimed: Vec<String> = value(&self.groups_header)
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|g| !g.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Identity {
+            groups: self.groups.resolve(user.as_deref(), &claimed),
+            user,
+            bypass: false,
+        }
+    }
+
+    /// Resolve the caller from a verified OIDC token, whose groups claim
+    /// takes the place of the identity groups header.
+    #[cfg(feature = "oidc")]
+    pub fn oidc_identity(&self, principal: &crate::oidc::OidcPrincipal) -> Identity {
+        Identity {
+            groups: self
+                .groups
+                .resolve(Some(&principal.user), &principal.groups),
+            user: Some(principal.user.clone()),
+            bypass: false,
+        }
+    }
+}
+
+/// Middleware that attaches the caller's [`Identity`] to the request.
+///
+/// Requires `Extension<Arc<IdentityConfig>>`. Must run after tenant
+/// resolution so tenant admins get the ACL bypass.
+pub async fn identity_middleware(
+    Extension(config): Extension<Arc<IdentityConfig>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    #[allow(unused_mut)] // only changed when the api-keys feature is enabled
+    let mut identity = config.identity(req.headers());
+    #[cfg(feature = "oidc")]
+    if let Some(principal) = req.extensions().get::<crate::oidc::OidcPrincipal>() {
+        identity = config.oidc_identity(principal);
+    }
+    #[cfg(feature = "api-keys")]
+    {
+        identity.bypass = req
+            .extensions()
+            .get::<crate::tenant_resolver::ResolvedTenant>()
+            .and_then(|t| t.role())
+            .is_some_and(|role| role == crate::control_plane::TenantRole::Admin);
+    }
+    req.extensions_mut().insert(identity);
+    next.run(req).await
+}
+
+fn read_entries(
+    conn: &Connection,
+    sql: &str,
+    key: &dyn rusqlite::ToSql,
+) -> rusqlite::Result<Vec<AclEntry>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([key], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut entries = Vec::new();
+    for row in rows {
+        let (principal, permission) = row?;
+        if let Some(permission) = AclPermission::parse(&permission) {
+            entries.push(AclEntry {
+                principal,
+                permission,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Entries set directly on a dataset.
+pub fn dataset_acl(conn: &Connection, dataset_id: i64) -> rusqlite::Result<Vec<AclEntry>> {
+    read_entries(
+        conn,
+        "SELECT principal, permission FROM dataset_acls WHERE dataset_id = ?1 ORDER BY principal",
+        &dataset_id,
+    )
+}
+
+/// Default entries for datasets in a domain.
+pub fn domain_acl(conn: &Connection, domain: &str) -> rusqlite::Result<Vec<AclEntry>> {
+    read_entries(
+        conn,
+        "SELECT principal, permission FROM domain_acls WHERE domain = ?1 ORDER BY principal",
+        &domain,
+    )
+}
+
+/// Replace a dataset's entries. Call within a transaction.
+pub fn set_dataset_acl(
+    conn: &Connection,
+    dataset_id: i64,
+    entries: &[AclEntry],
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM dataset_acls WHERE dataset_id = ?1",
+        [dataset_id],
+    )?;
+    for entry in entries {
+        conn.execute(
+            "INSERT OR REPLACE INTO dataset_acls (dataset_id, principal, permission) VALUES (?1, ?2, ?3)",
+            params![dataset_id, entry.principal, entry.permission.as_str()],
+        )?;
+    }
+    Ok(())
+}
+
+/// Replace a domain's default entries. Call within a transaction.
+pub fn set_domain_acl(
+    conn: &Connection,
+    domain: &str,
+    entries: &[AclEntry],
+) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM domain_acls WHERE domain = ?1", [domain])?;
+    for entry in entries {
+        conn.execute(
+            "INSERT OR REPLACE INTO domain_acls (domain, principal, permission) VALUES (?1, ?2, ?3)",
+            params![domain, entry.principal, entry.permission.as_str()],
+        )?;
+    }
+    Ok(())
+}
+
+/// Entries that govern a dataset: its own, or else its domain's.
+pub fn effective_acl(conn: &Connection, dataset_id: i64) -> rusqlite::Result<Vec<AclEntry>> {
+    let own = dataset_acl(conn, dataset_id)?;
+    if !own.is_empty() {
+        return Ok(own);
+    }
+    let domain: Option<String> = conn
+        .query_row(
+            "SELECT domain FROM datasets WHERE id = ?1",
+            [dataset_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    match domain {
+        Some(domain) => domain_acl(conn, &domain),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Check whether the caller has a permission on a dataset.
+pub fn check(
+    conn: &Connection,
+    dataset_id: i64,
+    identity: &Identity,
+    required: AclPermission,
+) -> rusqlite::Result<bool> {
+    if identity.bypass {
+        return Ok(true);
+    }
+    let entries = effective_acl(conn, dataset_id)?;
+    if entries.is_empty() {
+        return Ok(true);
+    }
+    let principals = identity.principals();
+    Ok(entries
+        .iter()
+        .any(|e| e.permission >= required && principals.contains(&e.principal)))
+}
+
+/// SQL condition hiding datasets the caller cannot read.
+///
+/// Returns `None` for callers that bypass ACLs. Otherwise the condition uses
+/// `?` placeholders that must be bound, in order, to the returned values.
+pub fn visibility_clause(
+    identity: &Identity,
+    id_column: &str,
+    domain_column: &str,
+) -> Option<(String, Vec<String>)> {
+    if identity.bypass {
+        return None;
+    }
+    let principals = identity.principals();
+    let placeholders = vec!["?"; principals.len()].join(", ");
+    let clause = format!(
+        "(CASE \
+            WHEN EXISTS (SELECT 1 FROM dataset_acls a WHERE a.dataset_id = {id}) \
+            THEN EXISTS (SELECT 1 FROM dataset_acls a WHERE a.dataset_id = {id} AND a.principal IN ({p})) \
+            WHEN EXISTS (SELECT 1 FROM domain_acls g WHERE g.domain = {domain}) \
+            THEN EXISTS (SELECT 1 FROM domain_acls g WHERE g.domain = {domain} AND g.principal IN ({p})) \
+            ELSE 1 END)",
+        id = id_column,
+        domain = domain_column,
+        p = placeholders
+    );
+    let mut bindings = principals.clone();
+    bindings.extend(principals);
+    Some((clause, bindings))
+}
+
+/// Request body for replacing an ACL
+#[derive(Debug, Deserialize)]
+pub(crate) struct SetAclRequest {
+    entries: Vec<AclEntry>,
+}
+
+/// Dataset ACL response
+#[derive(Debug, Serialize)]
+pub(crate) struct DatasetAclResponse {
+    dataset_name: String,
+    /// Entries set on the dataset itself
+    entries: Vec<AclEntry>,
+    /// Domain whose defaults apply when the dataset has no entries of its own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inherited_from: Option<String>,
+    /// Entries actually enforced; empty means the dataset is open to the tenant
+    effective: Vec<AclEntry>,
+}
+
+/// Domain default ACL response
+#[derive(Debug, Serialize)]
+pub(crate) struct DomainAclResponse {
+    domain: String,
+    entries: Vec<AclEntry>,
+}
+
+/// Build the ACL response for a dataset
+pub(crate) fn dataset_acl_response(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    name: &str,
+) -> rusqlite::Result<DatasetAclResponse> {
+    let entries = dataset_acl(conn, dataset_id)?;
+    let domain: Option<String> = conn.query_row(
+        "SELECT domain FROM datasets WHERE id = ?1",
+        [dataset_id],
+        |row| row.get(0),
+    )?;
+    let effective = effective_acl(conn, dataset_id)?;
+    let inherited_from = if entries.is_empty() && !effective.is_empty() {
+        domain
+    } else {
+        None
+    };
+    Ok(DatasetAclResponse {
+        dataset_name: name.to_string(),
+        entries,
+        inherited_from,
+        effective,
+    })
+}
+
+/// Get a dataset's access control list
+pub(crate) async fn get_dataset_acl(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<Identity>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<DatasetAclResponse>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        AclPermission::Read,
+        &request_id,
+    )?;
+
+    let response = dataset_acl_response(&conn, dataset_id, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(response))
+}
+
+/// Replace a dataset's access control list
+///
+/// An empty list removes the dataset's own entries, so it falls back to its
+/// domain's defaults.
+pub(crate) async fn put_dataset_acl(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<SetAclRequest>,
+) -> Result<Json<DatasetAclResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    validate_entries(&req.entries).map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    // Only principals who can write the dataset may change who can see it
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        AclPermission::Write,
+        &request_id,
+    )?;
+
+    let old_entries = dataset_acl(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    set_dataset_acl(&tx, dataset_id, &req.entries)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let response = dataset_acl_response(&conn, dataset_id, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, entries = req.entries.len(), "Dataset ACL updated");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset_acl",
+            &name,
+            serde_json::json!({ "entries": old_entries }),
+            serde_json::json!({ "entries": response.entries }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(response))
+}
+
+/// Get a domain's default access control list
+pub(crate) async fn get_domain_acl(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(name): Path<String>,
+) -> Result<Json<DomainAclResponse>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_identifier(&name, "domain")
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let entries = domain_acl(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(DomainAclResponse {
+        domain: name,
+        entries,
+    }))
+}
+
+/// Replace a domain's default access control list
+///
+/// Defaults apply to every dataset in the domain without entries of its own.
+pub(crate) async fn put_domain_acl(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(name): Path<String>,
+    JsonBody(req): JsonBody<SetAclRequest>,
+) -> Result<Json<DomainAclResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Domain defaults can hide many datasets at once, so they are admin-only
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_identifier(&name, "domain")
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    validate_entries(&req.entries).map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let old_entries = domain_acl(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    set_domain_acl(&tx, &name, &req.entries)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let entries = domain_acl(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(domain = %name, entries = entries.len(), "Domain ACL updated");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "domain_acl",
+            &name,
+            serde_json::json!({ "entries": old_entries }),
+            serde_json::json!({ "entries": entries }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(DomainAclResponse {
+        domain: name,
+        entries,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO datasets (name, path, format, domain, created_at, last_updated) VALUES
+                ('open', '/open', 'parquet', NULL, datetime('now'), datetime('now')),
+                ('ledger', '/ledger', 'parquet', 'finance', datetime('now'), datetime('now')),
+                ('payroll', '/payroll', 'parquet', 'finance', datetime('now'), datetime('now'));
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    fn entry(principal: &str, permission: AclPermission) -> AclEntry {
+        AclEntry {
+            principal: principal.to_string(),
+            permission,
+        }
+    }
+
+    fn visible(conn: &Connection, identity: &Identity) -> Vec<String> {
+        let (clause, bindings) = visibility_clause(identity, "d.id", "d.domain").unwrap();
+        let sql = format!(
+            "SELECT d.name FROM datasets d WHERE {} ORDER BY d.id",
+            clause
+        );
+        let mut stmt = conn.prepare(&sql).unwrap();
+        stmt.query_map(rusqlite::params_from_iter(bindings.iter()), |row| {
+            row.get(0)
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_domain_defaults_and_overrides() {
+        let conn = setup();
+        set_domain_acl(
+            &conn,
+            "finance",
+            &[entry("group:finance", AclPermission::Write)],
+        )
+        .unwrap();
+        // payroll overrides the domain default
+        set_dataset_acl(&conn, 3, &[entry("user:hr-lead", AclPermission::Read)]).unwrap();
+
+        let analyst = Identity {
+            user: Some("ana".to_string()),
+            groups: vec!["finance".to_string()],
+            bypass: false,
+        };
+        let hr = Identity {
+            user: Some("hr-lead".to_string()),
+            ..Default::default()
+        };
+        let anonymous = Identity::default();
+
+        assert!(check(&conn, 2, &analyst, AclPermission::Write).unwrap());
+        assert!(!check(&conn, 3, &analyst, AclPermission::Read).unwrap());
+        assert!(check(&conn, 3, &hr, AclPermission::Read).unwrap());
+        assert!(!check(&conn, 3, &hr, AclPermission::Write).unwrap());
+        assert!(check(&conn, 1, &anonymous, AclPermission::Write).unwrap());
+
+        assert_eq!(visible(&conn, &analyst), vec!["open", "ledger"]);
+        assert_eq!(visible(&conn, &hr), vec!["open", "payroll"]);
+        assert_eq!(visible(&conn, &anonymous), vec!["open"]);
+
+        let admin = Identity {
+            bypass: true,
+            ..Default::default()
+        };
+        assert!(check(&conn, 3, &admin, AclPermission::Write).unwrap());
+        assert!(visibility_clause(&admin, "d.id", "d.domain").is_none());
+    }
+
+    #[test]
+    fn test_identity_from_headers() {
+        let config = IdentityConfig {
+            user_header: Some("x-forwarded-user".to_string()),
+            groups_header: Some("x-forwarded-groups".to_string()),
+            ..Default::default()
+        };
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("x-forwarded-user", "ana".parse().unwrap());
+        headers.insert("x-forwarded-groups", "finance, analysts,".parse().unwrap());
+
+        let identity = config.identity(&headers);
+        assert_eq!(
+            identity.principals(),
+            vec!["user:ana", "group:finance", "group:analysts"]
+        );
+        assert_eq!(identity.owner_ids(), vec!["ana", "finance", "analysts"]);
+        // Headers are ignored unless configured
+        assert!(IdentityConfig::default()
+            .identity(&headers)
+            .principals()
+            .is_empty());
+
+        assert!(validate_principal("group:finance").is_ok());
+        assert!(validate_principal("finance").is_err());
+        assert!(validate_principal("user:").is_err());
+    }
+}
@@ -0,0 +1,800 @@
+//! Dataset Refs Module
+//!
+//! Named, immutable references to a dataset at a specific format version or
+//! point in time, e.g. "training run X used `orders` at Delta version 42".
+//!
+//! # Architecture
+//!
+//! Refs are stored in the `dataset_refs` table (migration v1.10.0). Creating a ref
+//! copies the dataset's location so it can be resolved later even if the dataset
+//! moves or is deleted. Runs and models that used a ref are recorded in
+//! `dataset_ref_consumers`, giving lineage from dataset versions to ML artifacts.
+//!
+//! # Endpoints
+//!
+//! - `GET /api/v1/refs` - List refs (filter by dataset or consumer)
+//! - `POST /api/v1/refs` - Create a ref
+//! - `GET /api/v1/refs/{name}` - Resolve a ref
+//! - `POST /api/v1/refs/{name}/consumers` - Record a run or model that used a ref
+
+#[cfg(feature = "audit")]
+use crate::audit;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, dataset_not_found, internal_error, not_found, require_dataset_access, AppState,
+    AuditContext, Caller, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::{dataset_acl, envelope};
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::{formats, validation};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Kind of artifact that consumed a ref.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsumerType {
+    /// A training or pipeline run
+    Run,
+    /// A trained model
+    Model,
+}
+
+impl ConsumerType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsumerType::Run => "run",
+            ConsumerType::Model => "model",
+        }
+    }
+
+    fn from_db(s: &str) -> rusqlite::Result<Self> {
+        match s {
+            "run" => Ok(ConsumerType::Run),
+            "model" => Ok(ConsumerType::Model),
+            other => Err(rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                format!("unknown consumer type '{}'", other).into(),
+            )),
+        }
+    }
+}
+
+/// A run or model that used a ref.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefConsumer {
+    #[serde(rename = "type")]
+    pub consumer_type: ConsumerType,
+    pub name: String,
+    #[serde(default, skip_deserializing)]
+    pub created_at: String,
+}
+
+/// A resolved dataset ref.
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetRef {
+    pub id: i64,
+    pub name: String,
+    /// None if the dataset has been deleted since the ref was created
+    pub dataset_id: Option<i64>,
+    pub dataset_name: String,
+    pub path: String,
+    pub format: String,
+    pub delta_location: Option<String>,
+    /// Pinned table format version (Delta/Iceberg)
+    pub version: Option<i64>,
+    /// Pinned point in time (RFC 3339)
+    pub as_of: Option<String>,
+    pub description: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    pub consumers: Vec<RefConsumer>,
+}
+
+/// Values captured when a ref is created.
+#[derive(Debug, Clone)]
+pub struct NewDatasetRef {
+    pub name: String,
+    pub dataset_id: i64,
+    pub dataset_name: String,
+    pub path: String,
+    pub format: String,
+    pub delta_location: Option<String>,
+    pub version: Option<i64>,
+    pub as_of: Option<String>,
+    pub description: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// Filters for [`list_refs`].
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RefFilter {
+    /// Only refs to this dataset
+    pub dataset: Option<String>,
+    /// Only refs used by consumers of this type
+    pub consumer_type: Option<ConsumerType>,
+    /// Only refs used by the consumer with this name
+    pub consumer_name: Option<String>,
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+const SELECT_REF: &str = r#"
+    SELECT r.id, r.name, r.dataset_id, r.dataset_name, r.path, r.format, r.delta_location,
+           r.version, r.as_of, r.description, r.created_by, r.created_at
+    FROM dataset_refs r
+"#;
+
+fn row_to_ref(row: &rusqlite::Row) -> rusqlite::Result<DatasetRef> {
+    Ok(DatasetRef {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        dataset_id: row.get(2)?,
+        dataset_name: row.get(3)?,
+        path: row.get(4)?,
+        format: row.get(5)?,
+        delta_location: row.get(6)?,
+        version: row.get(7)?,
+        as_of: row.get(8)?,
+        description: row.get(9)?,
+        created_by: row.get(10)?,
+        created_at: row.get(11)?,
+        consumers: Vec::new(),
+    })
+}
+
+/// Create a ref. Returns the new ref ID.
+///
+/// Fails with a UNIQUE constraint error if the name is taken.
+pub fn create_ref(conn: &Connection, new_ref: &NewDatasetRef) -> rusqlite::Result<i64> {
+    conn.execute(
+        r#"
+        INSERT INTO dataset_refs (name, dataset_id, dataset_name, path, format, delta_location,
+                                  version, as_of, description, created_by)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        "#,
+        params![
+            new_ref.name,
+            new_ref.dataset_id,
+            new_ref.dataset_name,
+            new_ref.path,
+            new_ref.format,
+            new_ref.delta_location,
+            new_ref.version,
+            new_ref.as_of,
+            new_ref.description,
+            new_ref.created_by,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Get a ref by name, including its consumers.
+pub fn get_ref(conn: &Connection, name: &str) -> rusqlite::Result<Option<DatasetRef>> {
+    let dataset_ref = conn
+        .query_row(
+            &format!("{} WHERE r.name = ?1", SELECT_REF),
+            [name],
+            row_to_ref,
+        )
+        .optional()?;
+
+    match dataset_ref {
+        Some(mut dataset_ref) => {
+            dataset_ref.consumers = list_consumers(conn, dataset_ref.id)?;
+            Ok(Some(dataset_ref))
+        }
+        None => Ok(None),
+    }
+}
+
+/// List refs matching `filter`, newest first, including their consumers.
+///
+/// `visibility` is an extra SQL condition on `r` with its bindings (e.g. ACL
+/// visibility of the referenced dataset).
+pub fn list_refs(
+    conn: &Connection,
+    filter: &RefFilter,
+    visibility: Option<(String, Vec<String>)>,
+) -> rusqlite::Result<Vec<DatasetRef>> {
+    let mut sql = format!("{} WHERE 1 = 1", SELECT_REF);
+    let mut values: Vec<String> = Vec::new();
+
+    if let Some(dataset) = &filter.dataset {
+        values.push(dataset.clone());
+        sql.push_str(&format!(" AND r.dataset_name = ?{}", values.len()));
+    }
+    if filter.consumer_type.is_some() || filter.consumer_name.is_some() {
+        sql.push_str(" AND EXISTS (SELECT 1 FROM dataset_ref_consumers c WHERE c.ref_id = r.id");
+        if let Some(consumer_type) = filter.consumer_type {
+            values.push(consumer_type.as_str().to_string());
+            sql.push_str(&format!(" AND c.consumer_type = ?{}", values.len()));
+        }
+        if let Some(consumer_name) = &filter.consumer_name {
+            values.push(consumer_name.clone());
+            sql.push_str(&format!(" AND c.consumer_name = ?{}", values.len()));
+        }
+        sql.push(')');
+    }
+    // Anonymous `?` placeholders number on from the highest `?N` above
+    if let Some((clause, bindings)) = visibility {
+        sql.push_str(&format!(" AND {}", clause));
+        values.extend(bindings);
+    }
+    sql.push_str(" ORDER BY r.created_at DESC, r.id DESC");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut refs = stmt
+        .query_map(rusqlite::params_from_iter(values.iter()), row_to_ref)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for dataset_ref in &mut refs {
+        dataset_ref.consumers = list_consumers(conn, dataset_ref.id)?;
+    }
+    Ok(refs)
+}
+
+/// List the runs and models that used a ref, oldest first.
+pub fn list_consumers(conn: &Connection, ref_id: i64) -> rusqlite::Result<Vec<RefConsumer>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT consumer_type, consumer_name, created_at
+        FROM dataset_ref_consumers
+        WHERE ref_id = ?1
+        ORDER BY created_at, id
+        "#,
+    )?;
+    let consumers = stmt
+        .query_map([ref_id], |row| {
+            Ok(RefConsumer {
+                consumer_type: ConsumerType::from_db(&row.get::<_, String>(0)?)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?
+        .collect();
+    consumers
+}
+
+/// Record that a run or model used a ref.
+///
+/// Returns false if the consumer was already recorded.
+pub fn add_consumer(
+    conn: &Connection,
+    ref_id: i64,
+    consumer_type: ConsumerType,
+    consumer_name: &str,
+) -> rusqlite::Result<bool> {
+    let inserted = conn.execute(
+        r#"
+        INSERT OR IGNORE INTO dataset_ref_consumers (ref_id, consumer_type, consumer_name)
+        VALUES (?1, ?2, ?3)
+        "#,
+        params![ref_id, consumer_type.as_str(), consumer_name],
+    )?;
+    Ok(inserted > 0)
+}
+
+/// Request to pin a dataset version under a name
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateDatasetRefRequest {
+    name: String,
+    dataset: String,
+    /// Table format version; requires a format with time travel
+    version: Option<i64>,
+    /// Point in time (RFC 3339)
+    as_of: Option<String>,
+    description: Option<String>,
+    /// Defaults to the calling API key
+    created_by: Option<String>,
+}
+
+/// Request to record a run or model that used a ref
+#[derive(Debug, Deserialize)]
+pub(crate) struct AddRefConsumerRequest {
+    #[serde(rename = "type")]
+    consumer_type: ConsumerType,
+    name: String,
+}
+
+/// List dataset refs, optionally filtered by dataset or consumer
+pub(crate) async fn list_dataset_refs(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(filter): Query<RefFilter>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<DatasetRef>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, "Listing dataset refs");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Hide refs to datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(
+        &identity,
+        "r.dataset_id",
+        "(SELECT domain FROM datasets WHERE id = r.dataset_id)",
+    );
+    let refs = list_refs(&conn, &filter, visibility)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(refs)))
+}
+
+/// Create an immutable ref to a dataset version
+///
+/// If neither `version` nor `as_of` is given, the ref pins the current Delta
+/// version when the dataset has a `delta_location`, otherwise the current time.
+pub(crate) async fn create_dataset_ref(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    caller: Caller,
+    JsonBody(req): JsonBody<CreateDatasetRefRequest>,
+) -> Result<(StatusCode, Json<DatasetRef>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(caller.resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = caller
+        .resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| caller.tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = caller
+        .tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, ref_name = %req.name, dataset = %req.dataset, "Creating dataset ref");
+
+    validation::validate_identifier(&req.name, "Ref name")
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    validation::validate_dataset_name(&req.dataset)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    if let Some(version) = req.version {
+        if version < 0 {
+            return Err(bad_request(
+                "version must be >= 0".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+    }
+    let as_of = req
+        .as_of
+        .as_deref()
+        .map(|ts| {
+            chrono::DateTime::parse_from_rfc3339(ts)
+                .map(|dt| dt.with_timezone(&chrono::Utc).to_rfc3339())
+                .map_err(|_| {
+                    bad_request(
+                        format!("Invalid as_of '{}': expected an RFC 3339 timestamp", ts),
+                        request_id.0.clone(),
+                    )
+                })
+        })
+        .transpose()?;
+
+    let backend = resolve_backend(&state.backend, caller.tenant_backend.as_ref().map(|e| &e.0));
+
+    // Copy the dataset's current location into the ref
+    let (dataset_id, path, format, delta_location) = {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let (dataset_id, path, format, delta_location) = conn.query_row(
+            "SELECT id, path, format, delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&req.dataset],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        )
+        .map_err(|_| {
+            dataset_not_found(&req.dataset, request_id.0.clone())
+        })?;
+        require_dataset_access(
+            &conn,
+            dataset_id,
+            &req.dataset,
+            caller.identity.as_ref().map(|e| &e.0),
+            dataset_acl::AclPermission::Read,
+            &request_id,
+        )?;
+        (dataset_id, path, format, delta_location)
+    };
+
+    let time_travel = formats::lookup_format(&format).is_some_and(|spec| spec.supports_time_travel);
+
+    let mut version = req.version;
+    if let Some(v) = version {
+        if !time_travel {
+            return Err(bad_request(
+                format!(
+                    "Cannot pin a version of dataset '{}': format '{}' does not support time travel",
+                    req.dataset, format
+                ),
+                request_id.0.clone(),
+            ));
+        }
+        if let Some(loc) = &delta_location {
+            state
+                .delta_reader
+                .get_metadata_at_version(loc, v)
+                .await
+                .map_err(|e| {
+                    bad_request(
+                        format!(
+                            "Version {} of dataset '{}' could not be resolved: {}",
+                            v, req.dataset, e
+                        ),
+                        request_id.0.clone(),
+                    )
+                })?;
+        }
+    }
+
+    let as_of = match (version, as_of) {
+        (None, None) => match delta_location.as_deref().filter(|_| time_travel) {
+            Some(loc) => {
+                let meta = state.delta_reader.get_metadata(loc).await.map_err(|e| {
+                    internal_error(
+                        format!("Failed to read current Delta version: {}", e),
+                        request_id.0.clone(),
+                    )
+                })?;
+                version = Some(meta.version);
+                None
+            }
+            None => Some(chrono::Utc::now().to_rfc3339()),
+        },
+        (_, as_of) => as_of,
+    };
+
+    let new_ref = NewDatasetRef {
+        name: req.name.clone(),
+        dataset_id,
+        dataset_name: req.dataset.clone(),
+        path,
+        format,
+        delta_location,
+        version,
+        as_of,
+        description: req.description,
+        created_by: req.created_by.or_else(|| audit_context.api_key_id.clone()),
+    };
+
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    create_ref(&conn, &new_ref).map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            bad_request(
+                format!("Ref '{}' already exists", req.name),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    let dataset_ref = get_ref(&conn, &req.name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            internal_error("Ref missing after insert".to_string(), request_id.0.clone())
+        })?;
+
+    tracing::info!(
+        ref_name = %dataset_ref.name,
+        dataset = %dataset_ref.dataset_name,
+        version = ?dataset_ref.version,
+        as_of = ?dataset_ref.as_of,
+        "Dataset ref created"
+    );
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "dataset_ref",
+            dataset_ref.name.clone(),
+            serde_json::json!({
+                "id": dataset_ref.id,
+                "dataset": dataset_ref.dataset_name,
+                "version": dataset_ref.version,
+                "as_of": dataset_ref.as_of,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(dataset_ref)))
+}
+
+/// Look up a ref whose dataset the caller can read
+///
+/// Refs to datasets hidden by their ACL are reported as not found.
+pub(crate) fn accessible_dataset_ref(
+    conn: &rusqlite::Connection,
+    name: &str,
+    identity: Option<&dataset_acl::Identity>,
+    request_id: &RequestId,
+) -> Result<DatasetRef, (StatusCode, Json<ErrorResponse>)> {
+    let ref_not_found = || not_found(format!("Ref '{}' not found", name), request_id.0.clone());
+    let dataset_ref = get_ref(conn, name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(ref_not_found)?;
+    if let Some(dataset_id) = dataset_ref.dataset_id {
+        let anonymous = dataset_acl::Identity::default();
+        let readable = dataset_acl::check(
+            conn,
+            dataset_id,
+            identity.unwrap_or(&anonymous),
+            dataset_acl::AclPermission::Read,
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if !readable {
+            return Err(ref_not_found());
+        }
+    }
+    Ok(dataset_ref)
+}
+
+/// Resolve a dataset ref by name
+pub(crate) async fn get_dataset_ref(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path(name): Path<String>,
+) -> Result<Json<DatasetRef>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, ref_name = %name, "Resolving dataset ref");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_ref =
+        accessible_dataset_ref(&conn, &name, identity.as_ref().map(|e| &e.0), &request_id)?;
+
+    Ok(Json(dataset_ref))
+}
+
+/// Record a run or model that used a dataset ref
+///
+/// Returns 201 when the consumer is new and 200 if it was already recorded.
+pub(crate) async fn add_dataset_ref_consumer(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    caller: Caller,
+    Path(name): Path<String>,
+    JsonBody(req): JsonBody<AddRefConsumerRequest>,
+) -> Result<(StatusCode, Json<DatasetRef>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(caller.resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let tenant_id = caller
+        .tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, ref_name = %name, consumer = %req.name, "Adding dataset ref consumer");
+
+    validation::validate_identifier(&req.name, "Consumer name")
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, caller.tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let ref_id = accessible_dataset_ref(
+        &conn,
+        &name,
+        caller.identity.as_ref().map(|e| &e.0),
+        &request_id,
+    )?
+    .id;
+
+    let inserted = add_consumer(&conn, ref_id, req.consumer_type, &req.name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_ref = get_ref(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| not_found(format!("Ref '{}' not found", name), request_id.0.clone()))?;
+
+    if !inserted {
+        return Ok((StatusCode::OK, Json(dataset_ref)));
+    }
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset_ref_consumers",
+            &name,
+            serde_json::json!({}),
+            serde_json::json!({
+                "consumer_type": req.consumer_type.as_str(),
+                "consumer_name": req.name,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(dataset_ref)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metafuse_catalog_core::{init_sqlite_schema, migrations::run_migrations};
+
+    fn setup() -> (Connection, i64) {
+        let conn = Connection::open_in_memory().unwrap();
+        init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', 's3://lake/orders', 'delta', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let dataset_id = conn.last_insert_rowid();
+        (conn, dataset_id)
+    }
+
+    fn new_ref(name: &str, dataset_id: i64, version: Option<i64>) -> NewDatasetRef {
+        NewDatasetRef {
+            name: name.to_string(),
+            dataset_id,
+            dataset_name: "orders".to_string(),
+            path: "s3://lake/orders".to_string(),
+            format: "delta".to_string(),
+            delta_location: Some("s3://lake/orders".to_string()),
+            version,
+            as_of: None,
+            description: None,
+            created_by: Some("ml-team".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_create_and_get_ref() {
+        let (conn, dataset_id) = setup();
+        create_ref(&conn, &new_ref("churn_train", dataset_id, Some(42))).unwrap();
+
+        let dataset_ref = get_ref(&conn, "churn_train").unwrap().unwrap();
+        assert_eq!(dataset_ref.dataset_id, Some(dataset_id));
+        assert_eq!(dataset_ref.dataset_name, "orders");
+        assert_eq!(dataset_ref.version, Some(42));
+        assert!(dataset_ref.consumers.is_empty());
+
+        assert!(get_ref(&conn, "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_duplicate_ref_name_rejected() {
+        let (conn, dataset_id) = setup();
+        create_ref(&conn, &new_ref("churn_train", dataset_id, Some(42))).unwrap();
+        let err = create_ref(&conn, &new_ref("churn_train", dataset_id, Some(43))).unwrap_err();
+        assert!(err.to_string().contains("UNIQUE constraint"));
+    }
+
+    #[test]
+    fn test_consumers() {
+        let (conn, dataset_id) = setup();
+        let ref_id = create_ref(&conn, &new_ref("churn_train", dataset_id, Some(42))).unwrap();
+
+        assert!(add_consumer(&conn, ref_id, ConsumerType::Run, "run-001").unwrap());
+        assert!(add_consumer(&conn, ref_id, ConsumerType::Model, "churn-v3").unwrap());
+        assert!(!add_consumer(&conn, ref_id, ConsumerType::Run, "run-001").unwrap());
+
+        let consumers = get_ref(&conn, "churn_train").unwrap().unwrap().consumers;
+        assert_eq!(consumers.len(), 2);
+        assert_eq!(consumers[0].consumer_type, ConsumerType::Run);
+        assert_eq!(consumers[1].name, "churn-v3");
+    }
+
+    #[test]
+    fn test_list_refs_filters() {
+        let (conn, dataset_id) = setup();
+        let v1 = create_ref(&conn, &new_ref("train_v1", dataset_id, Some(1))).unwrap();
+        create_ref(&conn, &new_ref("train_v2", dataset_id, Some(2))).unwrap();
+        add_consumer(&conn, v1, ConsumerType::Model, "churn-v1").unwrap();
+
+        let all = list_refs(&conn, &RefFilter::default(), None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].name, "train_v2", "newest first");
+
+        let by_dataset = RefFilter {
+            dataset: Some("orders".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(list_refs(&conn, &by_dataset, None).unwrap().len(), 2);
+
+        let by_model = RefFilter {
+            consumer_type: Some(ConsumerType::Model),
+            consumer_name: Some("churn-v1".to_string()),
+            ..Default::default()
+        };
+        let refs = list_refs(&conn, &by_model, None).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].name, "train_v1");
+
+        let by_run = RefFilter {
+            consumer_type: Some(ConsumerType::Run),
+            ..Default::default()
+        };
+        assert!(list_refs(&conn, &by_run, None).unwrap().is_empty());
+
+        // Visibility binds after the numbered filter parameters
+        let visibility = Some(("r.name <> ?".to_string(), vec!["train_v1".to_string()]));
+        let refs = list_refs(&conn, &by_dataset, visibility).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].name, "train_v2");
+    }
+
+    #[test]
+    fn test_ref_survives_dataset_deletion() {
+        let (conn, dataset_id) = setup();
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        create_ref(&conn, &new_ref("churn_train", dataset_id, Some(42))).unwrap();
+
+        conn.execute("DELETE FROM datasets WHERE id = ?1", [dataset_id])
+            .unwrap();
+
+        let dataset_ref = get_ref(&conn, "churn_train").unwrap().unwrap();
+        assert_eq!(dataset_ref.dataset_id, None);
+        assert_eq!(dataset_ref.path, "s3://lake/orders");
+        assert_eq!(dataset_ref.version, Some(42));
+    }
+}
@@ -0,0 +1,2190 @@
+//! Dataset Writes
+//!
+//! Registering, replacing, updating, patching and deleting datasets and
+//! their fields, with the write hooks, webhooks, quota checks and audit
+//! events every dataset write goes through.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+#[cfg(feature = "classification")]
+use crate::classification::auto_classify_dataset;
+#[cfg(feature = "quota-enforcement")]
+use crate::control_plane;
+#[cfg(all(
+    feature = "api-keys",
+    any(feature = "usage-analytics", feature = "classification")
+))]
+use crate::control_plane::TenantFeature;
+use crate::custom_metadata::custom_metadata_error;
+use crate::datasets::{
+    annotate_archived, attach_uuids, load_dataset_fields, parse_partition_keys, DatasetResponse,
+    FieldResponse, OperationalMetaResponse,
+};
+use crate::error_codes::ErrorCode;
+use crate::glossary::{find_glossary_term, request_glossary_scope};
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(all(
+    feature = "api-keys",
+    any(feature = "usage-analytics", feature = "classification")
+))]
+use crate::server::feature_enabled;
+use crate::server::{
+    bad_request, catalog_etag, check_catalog_version, dataset_not_found, if_match_version,
+    internal_error, not_found, require_dataset_access, AppState, AuditContext, Caller,
+    DatasetFieldPath, DatasetPath, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::server::{rbac_error, require_field_permissions};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::timeline;
+use crate::{dataset_acl, namespaces, sandbox, strict_json, trash, webhooks};
+use axum::extract::{Extension, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use metafuse_catalog_core::hooks::{
+    DatasetWrite, WriteHookError, WriteHooks, WriteOperation, WriteSource,
+};
+use metafuse_catalog_core::lineage_mode::LineageMode;
+use metafuse_catalog_core::write_report::WriteReport;
+use metafuse_catalog_core::{
+    auto_tagging, custom_metadata, field_ordinals, formats, json_patch, lineage_cycles,
+    lineage_mode, paths, placeholders, urn, validation, write_report, FieldMeta,
+};
+use metafuse_catalog_storage::DynCatalogBackend;
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Request to create a new dataset
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateDatasetRequest {
+    name: String,
+    path: String,
+    format: String,
+    delta_location: Option<String>,
+    description: Option<String>,
+    tenant: Option<String>,
+    domain: Option<String>,
+    owner: Option<String>,
+    tags: Option<Vec<String>>,
+    upstream_datasets: Option<Vec<String>>,
+    /// Handling of unregistered upstreams (default: the server's lineage
+    /// mode, else `ignore`)
+    lineage_mode: Option<LineageMode>,
+    /// Registered namespace; the stored name becomes `<namespace>.<name>`
+    namespace: Option<String>,
+    /// Integrator-defined JSON object, validated against the catalog's schema
+    custom_metadata: Option<serde_json::Value>,
+    /// Schema, in column order
+    fields: Option<Vec<CreateFieldRequest>>,
+    /// Glossary terms (by name) to link to the dataset
+    glossary_terms: Option<Vec<String>>,
+}
+
+/// A field in a create request
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateFieldRequest {
+    name: String,
+    data_type: String,
+    #[serde(default = "default_nullable")]
+    nullable: bool,
+    description: Option<String>,
+    /// Glossary terms (by name) to link to the field
+    glossary_terms: Option<Vec<String>>,
+}
+
+pub(crate) fn default_nullable() -> bool {
+    true
+}
+
+/// Changes to a field's curated metadata; unset fields are kept
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateFieldRequest {
+    /// Description; empty clears it
+    description: Option<String>,
+    /// Display name, e.g. "Customer ID"; empty clears it
+    business_name: Option<String>,
+    /// Glossary terms (by name) to link, replacing the current links
+    glossary_terms: Option<Vec<String>>,
+}
+
+/// Request to update an existing dataset
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct UpdateDatasetRequest {
+    path: Option<String>,
+    format: Option<String>,
+    delta_location: Option<String>,
+    description: Option<String>,
+    tenant: Option<String>,
+    domain: Option<String>,
+    owner: Option<String>,
+    /// Replaces the whole document (use the custom-metadata endpoint to merge)
+    custom_metadata: Option<serde_json::Value>,
+}
+
+impl UpdateDatasetRequest {
+    /// Dataset columns the request changes
+    #[cfg(any(feature = "api-keys", feature = "audit"))]
+    fn changed_fields(&self) -> Vec<&'static str> {
+        [
+            ("path", self.path.is_some()),
+            ("format", self.format.is_some()),
+            ("delta_location", self.delta_location.is_some()),
+            ("description", self.description.is_some()),
+            ("tenant", self.tenant.is_some()),
+            ("domain", self.domain.is_some()),
+            ("owner", self.owner.is_some()),
+            ("custom_metadata", self.custom_metadata.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, set)| set.then_some(field))
+        .collect()
+    }
+}
+
+/// Audit snapshot of a dataset: its id, name, path and format, plus the
+/// given columns, so update entries can be diffed (see `audit_diff`).
+#[cfg(feature = "audit")]
+pub(crate) fn dataset_audit_values(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    fields: &[&str],
+) -> rusqlite::Result<serde_json::Value> {
+    conn.query_row(
+        r#"
+        SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+               custom_metadata
+        FROM datasets WHERE id = ?1
+        "#,
+        [dataset_id],
+        |row| {
+            let custom_metadata: Option<String> = row.get(9)?;
+            let columns = [
+                (
+                    "delta_location",
+                    serde_json::json!(row.get::<_, Option<String>>(4)?),
+                ),
+                (
+                    "description",
+                    serde_json::json!(row.get::<_, Option<String>>(5)?),
+                ),
+                (
+                    "tenant",
+                    serde_json::json!(row.get::<_, Option<String>>(6)?),
+                ),
+                (
+                    "domain",
+                    serde_json::json!(row.get::<_, Option<String>>(7)?),
+                ),
+                ("owner", serde_json::json!(row.get::<_, Option<String>>(8)?)),
+                (
+                    "custom_metadata",
+                    custom_metadata
+                        .and_then(|m| serde_json::from_str(&m).ok())
+                        .unwrap_or(serde_json::Value::Null),
+                ),
+            ];
+            let mut values = serde_json::json!({
+                "id": row.get::<_, i64>(0)?,
+                "name": row.get::<_, String>(1)?,
+                "path": row.get::<_, String>(2)?,
+                "format": row.get::<_, String>(3)?,
+            });
+            for (column, value) in columns {
+                if fields.contains(&column) {
+                    values[column] = value;
+                }
+            }
+            Ok(values)
+        },
+    )
+}
+
+/// Map a write hook error: rejections are 400, fail-closed hook failures 503
+pub(crate) fn write_hook_error(
+    e: WriteHookError,
+    request_id: String,
+) -> (StatusCode, Json<ErrorResponse>) {
+    match e {
+        WriteHookError::Rejected { .. } => bad_request(e.to_string(), request_id),
+        WriteHookError::Unavailable { .. } => {
+            tracing::warn!(error = %e, "Write hook unavailable");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                    code: ErrorCode::ServiceUnavailable,
+                    request_id,
+                }),
+            )
+        }
+    }
+}
+
+/// Run post-commit write hooks without delaying the response
+pub(crate) fn spawn_post_commit_hooks(hooks: &WriteHooks, write: DatasetWrite) {
+    if hooks.is_empty() {
+        return;
+    }
+    let hooks = hooks.clone();
+    tokio::spawn(async move {
+        hooks.run_post_commit(&write).await;
+    });
+}
+
+/// Load a dataset's stored metadata as a hook write
+///
+/// Writes that change part of a dataset (tags, custom metadata, a field)
+/// start from this, so hooks see the whole dataset the write leaves behind.
+pub(crate) fn stored_dataset_write(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    name: &str,
+    operation: WriteOperation,
+) -> rusqlite::Result<DatasetWrite> {
+    let mut write = conn.query_row(
+        "SELECT path, format, description, tenant, domain, owner FROM datasets WHERE id = ?1",
+        [dataset_id],
+        |row| {
+            Ok(DatasetWrite {
+                path: row.get(0)?,
+                format: row.get(1)?,
+                description: row.get(2)?,
+                tenant: row.get(3)?,
+                domain: row.get(4)?,
+                owner: row.get(5)?,
+                ..DatasetWrite::new(operation, WriteSource::Api, name)
+            })
+        },
+    )?;
+    write.tags = conn
+        .prepare("SELECT tag FROM tags WHERE dataset_id = ?1 ORDER BY tag")?
+        .query_map([dataset_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    write.columns = conn
+        .prepare(&format!(
+            "SELECT name FROM fields WHERE dataset_id = ?1 ORDER BY {}",
+            field_ordinals::ORDER_BY
+        ))?
+        .query_map([dataset_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(write)
+}
+
+/// Run pre-validate write hooks on the full result of a partial write
+///
+/// Returns the write as the hooks left it. A path, format, owner, or tag
+/// set by a hook is validated as on `PUT`.
+pub(crate) async fn run_partial_write_hooks(
+    state: &AppState,
+    intended: &DatasetWrite,
+    request_id: &RequestId,
+) -> Result<DatasetWrite, (StatusCode, Json<ErrorResponse>)> {
+    let mut write = intended.clone();
+    state
+        .write_hooks
+        .run_pre_validate(&mut write)
+        .await
+        .map_err(|e| write_hook_error(e, request_id.0.clone()))?;
+
+    if write.path != intended.path {
+        if let Some(path) = write.path.as_mut() {
+            *path = paths::normalize_path(path)
+                .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+        }
+    }
+    if write.format != intended.format {
+        if let Some(format) = write.format.as_mut() {
+            *format = formats::normalize_format(format)
+                .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?
+                .to_string();
+        }
+    }
+    if write.owner != intended.owner {
+        if let Some(owner) = &write.owner {
+            state
+                .users
+                .check_owner(owner)
+                .map_err(|e| bad_request(e, request_id.0.clone()))?;
+        }
+    }
+    for tag in write.tags.iter().filter(|t| !intended.tags.contains(t)) {
+        validation::validate_tag(tag)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    Ok(write)
+}
+
+/// Store the changes pre-validate hooks made to a partial write
+pub(crate) fn apply_hook_changes(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    intended: &DatasetWrite,
+    write: &DatasetWrite,
+) -> rusqlite::Result<()> {
+    let columns = [
+        ("path", &intended.path, &write.path),
+        ("format", &intended.format, &write.format),
+        ("description", &intended.description, &write.description),
+        ("tenant", &intended.tenant, &write.tenant),
+        ("domain", &intended.domain, &write.domain),
+        ("owner", &intended.owner, &write.owner),
+    ];
+    for (column, before, after) in columns {
+        // Path and format are required; hooks can replace but not clear them
+        if before == after || (after.is_none() && matches!(column, "path" | "format")) {
+            continue;
+        }
+        conn.execute(
+            &format!(
+                "UPDATE datasets SET {} = ?2, last_updated = datetime('now') WHERE id = ?1",
+                column
+            ),
+            rusqlite::params![dataset_id, after],
+        )?;
+    }
+    apply_hook_tags(conn, dataset_id, &intended.tags, &write.tags)
+}
+
+/// Store the tag changes pre-validate hooks made to a write
+pub(crate) fn apply_hook_tags(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    intended: &[String],
+    written: &[String],
+) -> rusqlite::Result<()> {
+    for tag in intended.iter().filter(|t| !written.contains(t)) {
+        conn.execute(
+            "DELETE FROM tags WHERE dataset_id = ?1 AND tag = ?2",
+            rusqlite::params![dataset_id, tag],
+        )?;
+    }
+    for tag in written.iter().filter(|t| !intended.contains(t)) {
+        conn.execute(
+            "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)",
+            rusqlite::params![dataset_id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+/// Queue webhook deliveries for a committed dataset write
+pub(crate) fn notify_dataset_write(
+    state: &AppState,
+    conn: &rusqlite::Connection,
+    backend: &Arc<DynCatalogBackend>,
+    tenant_backend: Option<&Extension<TenantBackend>>,
+    write: &DatasetWrite,
+) {
+    state.webhooks.notify(
+        conn,
+        backend,
+        webhook_tenant(tenant_backend),
+        webhooks::WebhookEvent::for_write(write.operation),
+        serde_json::to_value(write).unwrap_or_default(),
+    );
+}
+
+/// Tenant whose webhooks see changes to the request's catalog
+pub(crate) fn webhook_tenant(tenant_backend: Option<&Extension<TenantBackend>>) -> &str {
+    tenant_backend
+        .map(|e| e.0.tenant_id())
+        .unwrap_or(webhooks::DEFAULT_TENANT)
+}
+
+/// Helper function to create quota exceeded error response (HTTP 403)
+#[cfg(feature = "quota-enforcement")]
+pub(crate) fn quota_exceeded(
+    message: String,
+    request_id: String,
+) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::warn!(message = %message, "Quota exceeded");
+    (
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            error: message,
+            code: ErrorCode::QuotaExceeded,
+            request_id,
+        }),
+    )
+}
+
+/// Result of quota check including soft limit warning state
+#[cfg(feature = "quota-enforcement")]
+pub(crate) struct QuotaCheckResult {
+    /// Whether a soft limit warning should be returned
+    warning: Option<String>,
+}
+
+/// Check dataset quota for a tenant.
+///
+/// Returns Ok(QuotaCheckResult) if the tenant is within their quota (or in dry-run mode).
+/// Returns Err with 403 if quota is exceeded and enforcement is enabled.
+///
+/// # Dry-run mode
+///
+/// When METAFUSE_QUOTA_DRY_RUN=true (default), quota violations are logged but not enforced.
+/// This allows safe production rollout with metering before enforcement.
+#[cfg(feature = "quota-enforcement")]
+pub(crate) fn check_dataset_quota(
+    conn: &rusqlite::Connection,
+    tenant: &control_plane::Tenant,
+    request_id: &str,
+) -> Result<QuotaCheckResult, (StatusCode, Json<ErrorResponse>)> {
+    let quota_max = tenant.quota_max_datasets;
+    let tenant_id = &tenant.tenant_id;
+
+    // Defensive check: treat 0 or negative as unlimited
+    // Note: The database has a CHECK constraint (quota_max_datasets > 0) that prevents
+    // storing invalid values. This check is defensive programming for edge cases like
+    // manual database modifications or future schema changes.
+    if quota_max <= 0 {
+        #[cfg(feature = "metrics")]
+        metrics::record_quota_check_ok(tenant_id, "datasets");
+        return Ok(QuotaCheckResult { warning: None });
+    }
+
+    // Count current datasets
+    let current_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM datasets WHERE deleted_at IS NULL",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+
+    let dry_run = std::env::var("METAFUSE_QUOTA_DRY_RUN")
+        .map(|v| v != "false" && v != "0")
+        .unwrap_or(true); // Default to dry-run mode for safe rollout
+
+    // Calculate usage ratio for metrics and soft limit warning
+    let usage_ratio = current_count as f64 / quota_max as f64;
+    let usage_percent = usage_ratio * 100.0;
+    let soft_limit_threshold = 80.0;
+
+    // Update quota usage ratio metric
+    #[cfg(feature = "metrics")]
+    metrics::update_quota_usage_ratio(tenant_id, "datasets", usage_ratio);
+
+    // Check if at hard limit
+    if current_count >= quota_max {
+        let msg = format!(
+            "Dataset quota exceeded: {} of {} datasets used",
+            current_count, quota_max
+        );
+
+        #[cfg(feature = "metrics")]
+        metrics::record_quota_exceeded(tenant_id, "datasets");
+
+        if dry_run {
+            tracing::warn!(
+                tenant_id = %tenant_id,
+                current = current_count,
+                quota = quota_max,
+                "Quota exceeded (dry-run mode - not enforced)"
+            );
+
+            #[cfg(feature = "metrics")]
+            metrics::record_quota_dry_run_allowed(tenant_id, "datasets");
+
+            // In dry-run mode, return warning but allow operation
+            return Ok(QuotaCheckResult {
+                warning: Some(format!("Quota exceeded (dry-run): {}", msg)),
+            });
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::record_quota_blocked(tenant_id, "datasets");
+
+        return Err(quota_exceeded(msg, request_id.to_string()));
+    }
+
+    // Soft limit warning at 80%
+    let warning = if usage_percent >= soft_limit_threshold {
+        #[cfg(feature = "metrics")]
+        metrics::record_quota_warning(tenant_id, "datasets");
+
+        Some(format!(
+            "Approaching dataset quota: {} of {} ({:.0}%)",
+            current_count, quota_max, usage_percent
+        ))
+    } else {
+        #[cfg(feature = "metrics")]
+        metrics::record_quota_check_ok(tenant_id, "datasets");
+
+        None
+    };
+
+    Ok(QuotaCheckResult { warning })
+}
+
+/// Create a new dataset
+pub(crate) async fn create_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    caller: Caller,
+    JsonBody(req): JsonBody<CreateDatasetRequest>,
+) -> Result<(StatusCode, Json<DatasetResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let (_, dataset) = insert_dataset(state, request_id, audit_context, caller, req)
+        .await
+        .map_err(CreateDatasetError::into_response)?;
+    Ok((StatusCode::CREATED, Json(dataset)))
+}
+
+/// Why [`insert_dataset`] did not create a dataset
+pub(crate) enum CreateDatasetError {
+    /// A registered dataset holds the name, possibly written by a concurrent
+    /// request since it was checked
+    NameTaken((StatusCode, Json<ErrorResponse>)),
+    Failed((StatusCode, Json<ErrorResponse>)),
+}
+
+impl CreateDatasetError {
+    fn into_response(self) -> (StatusCode, Json<ErrorResponse>) {
+        match self {
+            Self::NameTaken(e) | Self::Failed(e) => e,
+        }
+    }
+}
+
+impl From<(StatusCode, Json<ErrorResponse>)> for CreateDatasetError {
+    fn from(e: (StatusCode, Json<ErrorResponse>)) -> Self {
+        Self::Failed(e)
+    }
+}
+
+/// Create a dataset, returning the catalog version its transaction advanced
+/// the catalog to
+pub(crate) async fn insert_dataset(
+    state: AppState,
+    request_id: RequestId,
+    audit_context: AuditContext,
+    caller: Caller,
+    mut req: CreateDatasetRequest,
+) -> Result<(i64, DatasetResponse), CreateDatasetError> {
+    let Caller {
+        tenant_backend,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        #[cfg(all(feature = "api-keys", feature = "classification"))]
+        feature_flags,
+        request_sandbox,
+        ..
+    } = caller;
+
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %req.name, "Creating dataset");
+
+    // Datasets created in a sandbox live in its namespace
+    if let Some(Extension(sandbox)) = &request_sandbox {
+        if let Some(namespace) = req.namespace.take().filter(|ns| *ns != sandbox.0) {
+            return Err(bad_request(
+                format!(
+                    "Namespace '{}' is outside sandbox '{}'",
+                    namespace, sandbox.0
+                ),
+                request_id.0.clone(),
+            )
+            .into());
+        }
+        req.name = sandbox.qualify(&req.name);
+    }
+
+    // Qualify the name with its namespace
+    if let Some(namespace) = &req.namespace {
+        namespaces::validate_namespace(namespace)
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+        req.name = format!("{}.{}", namespace, req.name);
+    }
+
+    // Fill absent fields from the tenant's dataset defaults
+    #[cfg(feature = "api-keys")]
+    if let Some(control_plane) = state.multi_tenant.control_plane() {
+        match control_plane.get_tenant_dataset_defaults(tenant_id).await {
+            Ok(defaults) => {
+                defaults.apply(tenant_id, &mut req.tags, &mut req.domain, &mut req.owner)
+            }
+            Err(e) => {
+                tracing::warn!(tenant_id = %tenant_id, error = %e, "Failed to load tenant dataset defaults")
+            }
+        }
+    }
+
+    // Run pre-validate write hooks, which may enrich or reject the request
+    let mut write = DatasetWrite {
+        path: Some(req.path.clone()),
+        format: Some(req.format.clone()),
+        description: req.description.clone(),
+        tenant: req.tenant.clone(),
+        domain: req.domain.clone(),
+        owner: req.owner.clone(),
+        tags: req.tags.clone().unwrap_or_default(),
+        columns: req
+            .fields
+            .iter()
+            .flatten()
+            .map(|f| f.name.clone())
+            .collect(),
+        ..DatasetWrite::new(WriteOperation::Create, WriteSource::Api, &req.name)
+    };
+    state
+        .write_hooks
+        .run_pre_validate(&mut write)
+        .await
+        .map_err(|e| write_hook_error(e, request_id.0.clone()))?;
+    if let Some(path) = &write.path {
+        req.path = path.clone();
+    }
+    if let Some(format) = &write.format {
+        req.format = format.clone();
+    }
+    req.description = write.description.clone();
+    req.tenant = write.tenant.clone();
+    req.domain = write.domain.clone();
+    req.owner = write.owner.clone();
+    req.tags = (!write.tags.is_empty()).then(|| write.tags.clone());
+
+    // Validate inputs
+    validation::validate_dataset_name(&req.name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    req.path = paths::normalize_path(&req.path)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    req.format = formats::normalize_format(&req.format)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?
+        .to_string();
+    if let Some(owner) = &req.owner {
+        state
+            .users
+            .check_owner(owner)
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    }
+    let mut field_names = HashSet::new();
+    for field in req.fields.iter().flatten() {
+        validation::validate_field_name(&field.name)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+        if field.data_type.trim().is_empty() {
+            return Err(bad_request(
+                format!("Field '{}' has an empty data_type", field.name),
+                request_id.0.clone(),
+            )
+            .into());
+        }
+        if !field_names.insert(field.name.as_str()) {
+            return Err(bad_request(
+                format!("Duplicate field '{}'", field.name),
+                request_id.0.clone(),
+            )
+            .into());
+        }
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if let Some(namespace) = &req.namespace {
+        let registered = namespaces::get_namespace(&conn, namespace)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if registered.is_none() {
+            return Err(bad_request(
+                format!("Namespace '{}' does not exist", namespace),
+                request_id.0.clone(),
+            )
+            .into());
+        }
+    }
+    if let Some(Extension(sandbox)) = &request_sandbox {
+        let status = sandbox::status(&conn, &sandbox.0)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if let Some(message) = status.rejection(&sandbox.0) {
+            return Err(bad_request(message, request_id.0.clone()).into());
+        }
+    }
+
+    // Check dataset quota before creation
+    #[cfg(feature = "quota-enforcement")]
+    let _quota_warning = {
+        // Get tenant from control plane to check quota
+        if let Some(control_plane) = state.multi_tenant.control_plane() {
+            if let Ok(Some(tenant)) = control_plane.get_tenant(tenant_id).await {
+                let result = check_dataset_quota(&conn, &tenant, &request_id.0)?;
+                if let Some(ref warning) = result.warning {
+                    tracing::info!(
+                        tenant_id = %tenant_id,
+                        warning = %warning,
+                        "Quota warning for dataset creation"
+                    );
+                }
+                result.warning
+            } else {
+                tracing::debug!(tenant_id = %tenant_id, "Tenant not found in control plane, skipping quota check");
+                None
+            }
+        } else {
+            tracing::debug!("Control plane not configured, skipping quota check");
+            None
+        }
+    };
+
+    // Check if dataset already exists; a lineage placeholder is registered in place
+    let existing_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1",
+            [&req.name],
+            |row| row.get(0),
+        )
+        .ok();
+    let placeholder_id = match existing_id {
+        Some(id) if placeholders::is_placeholder(&conn, id).unwrap_or(false) => Some(id),
+        _ => None,
+    };
+
+    if existing_id.is_some() && placeholder_id.is_none() {
+        // A trashed dataset still holds its name until restored or purged
+        if trash::is_trashed(&conn, &req.name).unwrap_or(false) {
+            return Err(bad_request(
+                format!(
+                    "Dataset '{}' is in the trash. Restore or purge it before creating a new one",
+                    req.name
+                ),
+                request_id.0.clone(),
+            )
+            .into());
+        }
+        return Err(CreateDatasetError::NameTaken(bad_request(
+            format!("Dataset '{}' already exists", req.name),
+            request_id.0.clone(),
+        )));
+    }
+
+    if let Some(metadata) = &req.custom_metadata {
+        custom_metadata::check(&conn, metadata)
+            .map_err(|e| custom_metadata_error(e, &request_id.0))?;
+    }
+
+    // Resolve glossary terms up front so an unknown term rejects the whole write
+    let scope = request_glossary_scope(tenant_backend.as_ref());
+    let mut term_ids: HashMap<String, i64> = HashMap::new();
+    let term_names = req.glossary_terms.iter().flatten().chain(
+        req.fields
+            .iter()
+            .flatten()
+            .flat_map(|f| f.glossary_terms.iter().flatten()),
+    );
+    for term in term_names {
+        if term_ids.contains_key(term) {
+            continue;
+        }
+        let found = find_glossary_term(&conn, term, scope)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+            .ok_or_else(|| {
+                bad_request(
+                    format!("Glossary term '{}' does not exist", term),
+                    request_id.0.clone(),
+                )
+            })?;
+        term_ids.insert(term.clone(), found.id);
+    }
+
+    // Use transaction for multi-step write
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Insert dataset, or fill in the placeholder keeping its id and lineage
+    let dataset_id = if let Some(id) = placeholder_id {
+        tx.execute(
+            r#"
+            UPDATE datasets
+            SET path = ?1, format = ?2, delta_location = ?3, description = ?4, tenant = ?5,
+                domain = ?6, owner = ?7, last_updated = datetime('now')
+            WHERE id = ?8
+            "#,
+            rusqlite::params![
+                req.path,
+                req.format,
+                req.delta_location,
+                req.description,
+                req.tenant,
+                req.domain,
+                req.owner,
+                id,
+            ],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        // A concurrent write registered the placeholder first
+        let activated = placeholders::activate(&tx, id)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if !activated {
+            return Err(CreateDatasetError::NameTaken(bad_request(
+                format!("Dataset '{}' already exists", req.name),
+                request_id.0.clone(),
+            )));
+        }
+        id
+    } else {
+        tx.execute(
+            r#"
+            INSERT INTO datasets (name, path, format, delta_location, description, tenant, domain, owner, created_at, last_updated)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'), datetime('now'))
+            "#,
+            rusqlite::params![
+                req.name,
+                req.path,
+                req.format,
+                req.delta_location,
+                req.description,
+                req.tenant,
+                req.domain,
+                req.owner,
+            ],
+        )
+        .map_err(|e| {
+            // A concurrent write created the dataset since it was checked
+            if e.to_string().contains("UNIQUE constraint failed") {
+                CreateDatasetError::NameTaken(bad_request(
+                    format!("Dataset '{}' already exists", req.name),
+                    request_id.0.clone(),
+                ))
+            } else {
+                internal_error(e.to_string(), request_id.0.clone()).into()
+            }
+        })?;
+        tx.last_insert_rowid()
+    };
+
+    if req.custom_metadata.is_some() {
+        custom_metadata::store(&tx, dataset_id, req.custom_metadata.as_ref())
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+
+    // Insert tags if provided, once each
+    let mut report = WriteReport::default();
+    if let Some(tags) = &req.tags {
+        let stored = write_report::stored_tags(&tx, dataset_id)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        for tag in write_report::dedupe(tags, &mut report.tags) {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)",
+                rusqlite::params![dataset_id, tag],
+            )
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            report.tags.written(tag, stored.contains(tag));
+        }
+    }
+
+    // Insert fields and glossary term links if provided
+    if let Some(fields) = &req.fields {
+        let metas: Vec<FieldMeta> = fields
+            .iter()
+            .map(|f| FieldMeta {
+                name: f.name.clone(),
+                data_type: f.data_type.clone(),
+                nullable: f.nullable,
+                description: f.description.clone(),
+            })
+            .collect();
+        field_ordinals::replace_fields(&tx, dataset_id, &metas)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    for term in req.glossary_terms.iter().flatten() {
+        tx.execute(
+            "INSERT OR IGNORE INTO term_links (term_id, dataset_id) VALUES (?1, ?2)",
+            rusqlite::params![term_ids[term], dataset_id],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    for field in req.fields.iter().flatten() {
+        for term in field.glossary_terms.iter().flatten() {
+            tx.execute(
+                "INSERT OR IGNORE INTO term_links (term_id, field_id)
+                 SELECT ?1, id FROM fields WHERE dataset_id = ?2 AND name = ?3",
+                rusqlite::params![term_ids[term], dataset_id, field.name],
+            )
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+    }
+
+    // Insert lineage if provided
+    if let Some(upstream) = &req.upstream_datasets {
+        let mode = req
+            .lineage_mode
+            .or(state.lineage_mode)
+            .unwrap_or(LineageMode::Ignore);
+        let linked = write_report::linked_upstream_ids(&tx, dataset_id)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        for given_name in write_report::dedupe(upstream, &mut report.upstreams) {
+            // Sandbox datasets shadow catalog datasets of the same name
+            let upstream_name = match &request_sandbox {
+                Some(Extension(sandbox)) => sandbox::resolve_upstream(&tx, sandbox, given_name)
+                    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?,
+                None => given_name.clone(),
+            };
+            let upstream_id =
+                lineage_mode::resolve(&tx, &upstream_name, mode).map_err(|e| match e {
+                    metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+                        bad_request(msg, request_id.0.clone())
+                    }
+                    e => internal_error(e.to_string(), request_id.0.clone()),
+                })?;
+            if let Some(uid) = upstream_id.id() {
+                lineage_cycles::check_edge(&tx, uid, dataset_id).map_err(|e| match e {
+                    metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+                        bad_request(msg, request_id.0.clone())
+                    }
+                    e => internal_error(e.to_string(), request_id.0.clone()),
+                })?;
+                tx.execute(
+                    "INSERT OR IGNORE INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES (?1, ?2, datetime('now'))",
+                    rusqlite::params![uid, dataset_id],
+                )
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+                report.upstreams.written(given_name, linked.contains(&uid));
+            } else {
+                report.upstreams.skipped.push(given_name.clone());
+            }
+        }
+    }
+
+    // Apply auto-tagging rules (may set the domain, so run before fetching)
+    let rules = auto_tagging::load_active_rules(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let auto_tags = auto_tagging::apply_rules(&tx, &rules, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Fetch the created dataset (still within transaction)
+    let mut dataset: DatasetResponse = tx
+        .query_row(
+            r#"
+            SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+                   created_at, last_updated, row_count, size_bytes, partition_keys
+            FROM datasets WHERE id = ?1
+            "#,
+            [dataset_id],
+            |row| {
+                let row_count: Option<i64> = row.get(11)?;
+                let size_bytes: Option<i64> = row.get(12)?;
+                let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+                Ok(DatasetResponse {
+                    id: row.get(0)?,
+                    uuid: None,
+                    name: row.get(1)?,
+                    path: row.get(2)?,
+                    format: row.get(3)?,
+                    delta_location: row.get(4)?,
+                    description: row.get(5)?,
+                    tenant: row.get(6)?,
+                    domain: row.get(7)?,
+                    owner: row.get(8)?,
+                    created_at: row.get(9)?,
+                    last_updated: row.get(10)?,
+                    operational: OperationalMetaResponse {
+                        row_count,
+                        size_bytes,
+                        partition_keys,
+                    },
+                    freshness: None,
+                    metadata_completeness: None,
+                    custom_metadata: None,
+                    tags: None,
+                    quality_summary: None,
+                    archived_at: None,
+                    write_report: None,
+                    owner_profile: None,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    attach_uuids(&tx, std::slice::from_mut(&mut dataset))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    dataset.custom_metadata = req.custom_metadata.clone();
+    if report.has_warnings() {
+        tracing::warn!(
+            name = %req.name,
+            duplicate_upstreams = ?report.upstreams.deduplicated,
+            skipped_upstreams = ?report.upstreams.skipped,
+            duplicate_tags = ?report.tags.deduplicated,
+            "Dataset created with repeated or skipped upstreams or tags"
+        );
+    }
+    dataset.write_report = Some(report);
+    let version = metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Commit transaction
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %req.name, id = dataset_id, "Dataset created successfully");
+
+    notify_dataset_write(&state, &conn, &backend, tenant_backend.as_ref(), &write);
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    if !auto_tags.rules_matched.is_empty() {
+        tracing::info!(
+            name = %req.name,
+            rules = ?auto_tags.rules_matched,
+            tags_added = ?auto_tags.tags_added,
+            domain_set = ?auto_tags.domain_set,
+            "Applied auto-tagging rules"
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    metrics::record_catalog_operation("create_dataset", "success");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "dataset",
+            &dataset.name,
+            serde_json::json!({
+                "id": dataset.id,
+                "name": dataset.name,
+                "path": dataset.path,
+                "format": dataset.format,
+                "fields": req
+                    .fields
+                    .iter()
+                    .flatten()
+                    .map(|f| serde_json::json!({
+                        "name": f.name,
+                        "data_type": f.data_type,
+                        "glossary_terms": f.glossary_terms,
+                    }))
+                    .collect::<Vec<_>>(),
+                "tags": req.tags,
+                "upstream_datasets": req.upstream_datasets,
+                "glossary_terms": req.glossary_terms,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    // Auto-classification trigger (opt-in via METAFUSE_CLASSIFICATION_AUTO_SCAN)
+    #[cfg(feature = "classification")]
+    {
+        let auto_scan_enabled = std::env::var("METAFUSE_CLASSIFICATION_AUTO_SCAN")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        #[cfg(feature = "api-keys")]
+        let auto_scan_enabled = auto_scan_enabled
+            && feature_enabled(feature_flags.as_ref(), TenantFeature::Classification);
+
+        if auto_scan_enabled {
+            let dataset_id = dataset.id;
+            let dataset_name = dataset.name.clone();
+            let backend = state.backend.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = auto_classify_dataset(&backend, dataset_id).await {
+                    tracing::warn!(
+                        dataset_id,
+                        dataset_name = %dataset_name,
+                        error = %e,
+                        "Auto-classification failed"
+                    );
+                }
+            });
+        }
+    }
+
+    Ok((version, dataset))
+}
+
+/// Whether an upsert created or updated the dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UpsertOutcome {
+    Created,
+    Updated,
+}
+
+/// Result of a `PUT` by URN
+#[derive(Debug, Serialize)]
+pub(crate) struct UpsertDatasetResponse {
+    result: UpsertOutcome,
+    /// Catalog version after the write, also returned as the `ETag`
+    version: i64,
+    dataset: DatasetResponse,
+}
+
+/// Replace a dataset's metadata, or upsert it when addressed by URN
+///
+/// `PUT /api/v1/datasets/{name}` updates an existing dataset. When the path
+/// is a dataset URN (`urn:metafuse:dataset:<name>`), a missing dataset is
+/// created from the body instead (`path` and `format` required), so sync jobs
+/// can write the same URN repeatedly without checking for it first.
+pub(crate) async fn put_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    caller: Caller,
+    DatasetPath(reference): DatasetPath,
+    headers: HeaderMap,
+    JsonBody(req): JsonBody<UpdateDatasetRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if !reference.starts_with("urn:") {
+        let (version, dataset) = update_dataset(
+            State(state),
+            Extension(request_id),
+            Extension(audit_context),
+            caller,
+            DatasetPath(reference),
+            headers,
+            JsonBody(req),
+        )
+        .await?;
+        return Ok((catalog_etag(version), Json(dataset)).into_response());
+    }
+
+    let mut name = urn::resolve_dataset_ref(&reference)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    if let Some(Extension(sandbox)) = &caller.request_sandbox {
+        name = sandbox.qualify(&name);
+    }
+
+    // Placeholders and trashed datasets go through create, which fills in
+    // the former and rejects the latter
+    let existing = {
+        let backend = resolve_backend(&state.backend, caller.tenant_backend.as_ref().map(|e| &e.0));
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+                [&name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        match id {
+            Some(id) => !placeholders::is_placeholder(&conn, id)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?,
+            None => false,
+        }
+    };
+
+    if existing {
+        return upsert_update(state, request_id, audit_context, caller, name, headers, req).await;
+    }
+
+    // There is no version to match for a dataset that does not exist yet
+    if if_match_version(&headers, &request_id)?.is_some() {
+        return Err((
+            StatusCode::PRECONDITION_FAILED,
+            Json(ErrorResponse {
+                error: format!("Dataset '{}' does not exist", name),
+                code: ErrorCode::PreconditionFailed,
+                request_id: request_id.0.clone(),
+            }),
+        ));
+    }
+    let (Some(path), Some(format)) = (req.path.clone(), req.format.clone()) else {
+        return Err(bad_request(
+            format!(
+                "Dataset '{}' does not exist; path and format are required to create it",
+                name
+            ),
+            request_id.0.clone(),
+        ));
+    };
+    let create = CreateDatasetRequest {
+        name: name.clone(),
+        path,
+        format,
+        delta_location: req.delta_location.clone(),
+        description: req.description.clone(),
+        tenant: req.tenant.clone(),
+        domain: req.domain.clone(),
+        owner: req.owner.clone(),
+        tags: None,
+        upstream_datasets: None,
+        lineage_mode: None,
+        namespace: None,
+        custom_metadata: req.custom_metadata.clone(),
+        fields: None,
+        glossary_terms: None,
+    };
+    // The create advances the catalog version in its own transaction
+    match insert_dataset(
+        state.clone(),
+        request_id.clone(),
+        audit_context.clone(),
+        caller.clone(),
+        create,
+    )
+    .await
+    {
+        Ok((version, dataset)) => {
+            let response = UpsertDatasetResponse {
+                result: UpsertOutcome::Created,
+                version,
+                dataset,
+            };
+            Ok((StatusCode::CREATED, catalog_etag(version), Json(response)).into_response())
+        }
+        // A concurrent PUT created the dataset first; update it like a
+        // PUT that found it
+        Err(CreateDatasetError::NameTaken(_)) => {
+            upsert_update(state, request_id, audit_context, caller, name, headers, req).await
+        }
+        Err(CreateDatasetError::Failed(e)) => Err(e),
+    }
+}
+
+/// Update the dataset a `PUT` by URN found
+pub(crate) async fn upsert_update(
+    state: AppState,
+    request_id: RequestId,
+    audit_context: AuditContext,
+    caller: Caller,
+    name: String,
+    headers: HeaderMap,
+    req: UpdateDatasetRequest,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let (version, dataset) = update_dataset(
+        State(state),
+        Extension(request_id),
+        Extension(audit_context),
+        caller,
+        DatasetPath(name),
+        headers,
+        JsonBody(req),
+    )
+    .await?;
+    let response = UpsertDatasetResponse {
+        result: UpsertOutcome::Updated,
+        version,
+        dataset,
+    };
+    Ok((catalog_etag(version), Json(response)).into_response())
+}
+
+/// Update an existing dataset, returning the new catalog version
+///
+/// With `If-Match`, the update only applies if the catalog is still at that
+/// version.
+pub(crate) async fn update_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    headers: HeaderMap,
+    JsonBody(mut req): JsonBody<UpdateDatasetRequest>,
+) -> Result<(i64, DatasetResponse), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Updating dataset");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let expected_version = if_match_version(&headers, &request_id)?;
+
+    #[cfg(feature = "api-keys")]
+    require_field_permissions(
+        &state.field_permissions,
+        resolved_tenant.as_ref().map(|e| &e.0),
+        req.changed_fields(),
+        &request_id.0,
+    )?;
+
+    if let Some(path) = req.path.as_mut() {
+        *path = paths::normalize_path(path)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    if let Some(format) = req.format.as_mut() {
+        *format = formats::normalize_format(format)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?
+            .to_string();
+    }
+    if let Some(owner) = &req.owner {
+        state
+            .users
+            .check_owner(owner)
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Get the dataset ID first
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    // Run pre-validate write hooks on the dataset as updated, then take
+    // the fields they changed into the request
+    let mut intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let overlay = [
+        (&mut intended.path, &req.path),
+        (&mut intended.format, &req.format),
+        (&mut intended.description, &req.description),
+        (&mut intended.tenant, &req.tenant),
+        (&mut intended.domain, &req.domain),
+        (&mut intended.owner, &req.owner),
+    ];
+    for (stored, requested) in overlay {
+        if requested.is_some() {
+            stored.clone_from(requested);
+        }
+    }
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+    let hooked = [
+        (&mut req.path, &intended.path, &write.path),
+        (&mut req.format, &intended.format, &write.format),
+        (
+            &mut req.description,
+            &intended.description,
+            &write.description,
+        ),
+        (&mut req.tenant, &intended.tenant, &write.tenant),
+        (&mut req.domain, &intended.domain, &write.domain),
+        (&mut req.owner, &intended.owner, &write.owner),
+    ];
+    for (requested, before, after) in hooked {
+        if after != before && after.is_some() {
+            requested.clone_from(after);
+        }
+    }
+
+    if let Some(metadata) = &req.custom_metadata {
+        custom_metadata::check(&conn, metadata)
+            .map_err(|e| custom_metadata_error(e, &request_id.0))?;
+    }
+
+    // Values before and after the update, for the audit entry
+    #[cfg(feature = "audit")]
+    let audit_values;
+
+    // Build dynamic update query and execute in a block to drop non-Send types before await
+    let (catalog_version, delta_location_to_invalidate) = {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        check_catalog_version(&tx, expected_version, &request_id)?;
+        #[cfg(feature = "audit")]
+        let old_values = dataset_audit_values(&tx, dataset_id, &req.changed_fields())
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        let mut updates = vec!["last_updated = datetime('now')".to_string()];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+        if let Some(path) = &req.path {
+            updates.push(format!("path = ?{}", params.len() + 1));
+            params.push(Box::new(path.clone()));
+        }
+        if let Some(format) = &req.format {
+            updates.push(format!("format = ?{}", params.len() + 1));
+            params.push(Box::new(format.clone()));
+        }
+        if let Some(delta_location) = &req.delta_location {
+            updates.push(format!("delta_location = ?{}", params.len() + 1));
+            params.push(Box::new(delta_location.clone()));
+        }
+        if let Some(description) = &req.description {
+            updates.push(format!("description = ?{}", params.len() + 1));
+            params.push(Box::new(description.clone()));
+        }
+        if let Some(tenant) = &req.tenant {
+            updates.push(format!("tenant = ?{}", params.len() + 1));
+            params.push(Box::new(tenant.clone()));
+        }
+        if let Some(domain) = &req.domain {
+            updates.push(format!("domain = ?{}", params.len() + 1));
+            params.push(Box::new(domain.clone()));
+        }
+        if let Some(owner) = &req.owner {
+            updates.push(format!("owner = ?{}", params.len() + 1));
+            params.push(Box::new(owner.clone()));
+        }
+        if let Some(metadata) = &req.custom_metadata {
+            updates.push(format!("custom_metadata = ?{}", params.len() + 1));
+            params.push(Box::new(metadata.to_string()));
+        }
+
+        let sql = format!(
+            "UPDATE datasets SET {} WHERE id = ?{}",
+            updates.join(", "),
+            params.len() + 1
+        );
+        params.push(Box::new(dataset_id));
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        tx.execute(&sql, params_refs.as_slice())
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        apply_hook_tags(&tx, dataset_id, &intended.tags, &write.tags)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let catalog_version = metafuse_catalog_core::increment_catalog_version(&tx)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        #[cfg(feature = "audit")]
+        {
+            let new_values = dataset_audit_values(&tx, dataset_id, &req.changed_fields())
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            audit_values = (old_values, new_values);
+        }
+
+        // Capture delta_location for cache invalidation if it was updated
+        let delta_location = if req.delta_location.is_some() {
+            tx.query_row::<String, _, _>(
+                "SELECT delta_location FROM datasets WHERE id = ?1",
+                [dataset_id],
+                |row| row.get(0),
+            )
+            .ok()
+        } else {
+            None
+        };
+        tx.commit()
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        (catalog_version, delta_location)
+    };
+
+    // Now that non-Send types are dropped, we can await
+    if let Some(loc) = delta_location_to_invalidate {
+        state.delta_reader.invalidate_cache(&loc).await;
+    }
+
+    // Fetch updated dataset
+    let mut dataset: DatasetResponse = conn
+        .query_row(
+            r#"
+            SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+                   created_at, last_updated, row_count, size_bytes, partition_keys
+            FROM datasets WHERE id = ?1
+            "#,
+            [dataset_id],
+            |row| {
+                let row_count: Option<i64> = row.get(11)?;
+                let size_bytes: Option<i64> = row.get(12)?;
+                let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+                Ok(DatasetResponse {
+                    id: row.get(0)?,
+                    uuid: None,
+                    name: row.get(1)?,
+                    path: row.get(2)?,
+                    format: row.get(3)?,
+                    delta_location: row.get(4)?,
+                    description: row.get(5)?,
+                    tenant: row.get(6)?,
+                    domain: row.get(7)?,
+                    owner: row.get(8)?,
+                    created_at: row.get(9)?,
+                    last_updated: row.get(10)?,
+                    operational: OperationalMetaResponse {
+                        row_count,
+                        size_bytes,
+                        partition_keys,
+                    },
+                    freshness: None,
+                    metadata_completeness: None,
+                    custom_metadata: None,
+                    tags: None,
+                    quality_summary: None,
+                    archived_at: None,
+                    write_report: None,
+                    owner_profile: None,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    attach_uuids(&conn, std::slice::from_mut(&mut dataset))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    annotate_archived(&conn, std::slice::from_mut(&mut dataset))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    dataset.custom_metadata = custom_metadata::load(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Dataset updated successfully");
+
+    notify_dataset_write(&state, &conn, &backend, tenant_backend.as_ref(), &write);
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    #[cfg(feature = "metrics")]
+    metrics::record_catalog_operation("update_dataset", "success");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let (old_values, new_values) = audit_values;
+        let event =
+            audit::AuditEvent::update("dataset", &name, old_values, new_values, &request_id.0);
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((catalog_version, dataset))
+}
+
+/// Query parameters for deleting a dataset
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct DeleteDatasetQuery {
+    /// Skip the trash and delete immediately
+    #[serde(default)]
+    purge: bool,
+}
+
+/// Dataset metadata after a JSON Patch
+#[derive(Debug, Serialize)]
+pub(crate) struct DatasetPatchResponse {
+    dataset_name: String,
+    description: Option<String>,
+    tags: Vec<String>,
+    properties: serde_json::Value,
+}
+
+/// Media type of JSON Merge Patch (RFC 7396) documents
+pub(crate) const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// Read a JSON Merge Patch of a dataset as the equivalent partial update
+///
+/// Only the members `PUT` accepts may be set. A merge patch can't clear a
+/// member (`null`), and custom metadata is merged by its own endpoint rather
+/// than replaced.
+pub(crate) fn merge_patch_request(
+    body: &[u8],
+    request_id: &RequestId,
+) -> Result<UpdateDatasetRequest, (StatusCode, Json<ErrorResponse>)> {
+    let invalid = |message: String| bad_request(message, request_id.0.clone());
+    let patch: serde_json::Value = serde_json::from_slice(body)
+        .map_err(|e| invalid(format!("Invalid JSON Merge Patch: {}", e)))?;
+    let Some(members) = patch.as_object() else {
+        return Err(invalid(
+            "JSON Merge Patch must be an object of dataset fields".to_string(),
+        ));
+    };
+    let unknown = strict_json::unknown_fields::<UpdateDatasetRequest>(&patch);
+    if !unknown.is_empty() {
+        return Err(invalid(strict_json::unknown_fields_message(&unknown)));
+    }
+    if members.contains_key("custom_metadata") {
+        return Err(invalid(
+            "custom_metadata cannot be merge-patched here; use PATCH /api/v1/datasets/{name}/custom-metadata"
+                .to_string(),
+        ));
+    }
+    if let Some((member, _)) = members.iter().find(|(_, value)| value.is_null()) {
+        return Err(invalid(format!(
+            "'{}' cannot be removed with a merge patch",
+            member
+        )));
+    }
+    serde_json::from_value(patch).map_err(|e| invalid(format!("Invalid JSON Merge Patch: {}", e)))
+}
+
+/// Partially update a dataset with a JSON Patch or JSON Merge Patch
+///
+/// A JSON Merge Patch (`application/merge-patch+json`) sets any of the fields
+/// `PUT` accepts, such as `owner` and `domain`, and is applied like a `PUT`.
+/// A JSON Patch (`application/json-patch+json`, RFC 6902) edits the
+/// description, tags, and properties (custom metadata); it is applied
+/// atomically, and any failing operation rejects it. With `If-Match`, either
+/// only applies if the catalog is still at that version.
+pub(crate) async fn patch_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    caller: Caller,
+    DatasetPath(name): DatasetPath,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(str::trim)
+        .unwrap_or_default();
+    if content_type.eq_ignore_ascii_case(MERGE_PATCH_CONTENT_TYPE) {
+        let req = merge_patch_request(&body, &request_id)?;
+        let (version, dataset) = update_dataset(
+            State(state),
+            Extension(request_id),
+            Extension(audit_context),
+            caller,
+            DatasetPath(name),
+            headers,
+            JsonBody(req),
+        )
+        .await?;
+        return Ok((catalog_etag(version), Json(dataset)).into_response());
+    }
+
+    let Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    } = caller;
+
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    if !content_type.eq_ignore_ascii_case(json_patch::CONTENT_TYPE) {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(ErrorResponse {
+                error: format!(
+                    "PATCH requires Content-Type: {} or {} (use PUT for full updates)",
+                    json_patch::CONTENT_TYPE,
+                    MERGE_PATCH_CONTENT_TYPE
+                ),
+                code: ErrorCode::UnsupportedMediaType,
+                request_id: request_id.0.clone(),
+            }),
+        ));
+    }
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let expected_version = if_match_version(&headers, &request_id)?;
+    let operations =
+        json_patch::parse(&body).map_err(|e| custom_metadata_error(e, &request_id.0))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let (dataset_id, description): (i64, Option<String>) = conn
+        .query_row(
+            "SELECT id, description FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let tags: Vec<String> = conn
+        .prepare("SELECT tag FROM tags WHERE dataset_id = ?1")
+        .and_then(|mut stmt| {
+            stmt.query_map([dataset_id], |row| row.get(0))?
+                .collect::<Result<_, _>>()
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let properties = custom_metadata::load(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let before = json_patch::PatchableMetadata::new(description, tags, properties);
+    let mut after = json_patch::apply(&before, &operations)
+        .map_err(|e| custom_metadata_error(e, &request_id.0))?;
+    let changed = json_patch::changed_members(&before, &after);
+
+    #[cfg(feature = "api-keys")]
+    {
+        let certification_changed = before.tags.iter().any(|t| t == timeline::CERTIFIED_TAG)
+            != after.tags.iter().any(|t| t == timeline::CERTIFIED_TAG);
+        require_field_permissions(
+            &state.field_permissions,
+            resolved_tenant.as_ref().map(|e| &e.0),
+            [
+                ("description", changed.contains_key("description")),
+                ("custom_metadata", changed.contains_key("properties")),
+                ("certification", certification_changed),
+            ]
+            .into_iter()
+            .filter_map(|(field, set)| set.then_some(field)),
+            &request_id.0,
+        )?;
+    }
+
+    // Run pre-validate write hooks on the dataset as patched
+    let hook_writes = if changed.is_empty() {
+        None
+    } else {
+        let mut intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        intended.description = after.description.clone();
+        intended.tags = after.tags.clone();
+        let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+        after.description = write.description.clone();
+        after.tags = write.tags.clone();
+        Some((intended, write))
+    };
+
+    if changed.contains_key("properties") {
+        custom_metadata::check(&conn, &after.properties)
+            .map_err(|e| custom_metadata_error(e, &request_id.0))?;
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    check_catalog_version(&tx, expected_version, &request_id)?;
+    let catalog_version = if changed.is_empty() {
+        metafuse_catalog_core::get_catalog_version(&tx)
+    } else {
+        tx.execute(
+            "UPDATE datasets SET description = ?2, last_updated = datetime('now') WHERE id = ?1",
+            rusqlite::params![dataset_id, after.description],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        for tag in before.tags.iter().filter(|t| !after.tags.contains(t)) {
+            tx.execute(
+                "DELETE FROM tags WHERE dataset_id = ?1 AND tag = ?2",
+                rusqlite::params![dataset_id, tag],
+            )
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+        for tag in after.tags.iter().filter(|t| !before.tags.contains(t)) {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)",
+                rusqlite::params![dataset_id, tag],
+            )
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+        if changed.contains_key("properties") {
+            custom_metadata::store(&tx, dataset_id, Some(&after.properties))
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+        if let Some((intended, write)) = &hook_writes {
+            apply_hook_changes(&tx, dataset_id, intended, write)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+        metafuse_catalog_core::increment_catalog_version(&tx)
+    }
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, operations = operations.len(), changed = ?changed.keys().collect::<Vec<_>>(), "Dataset patched");
+
+    if let Some((_, write)) = hook_writes {
+        notify_dataset_write(&state, &conn, &backend, tenant_backend.as_ref(), &write);
+        spawn_post_commit_hooks(&state.write_hooks, write);
+    }
+
+    // Emit audit event (non-blocking), including the patch document
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset",
+            &name,
+            before.to_document(),
+            after.to_document(),
+            &request_id.0,
+        )
+        .with_context(serde_json::json!({ "json_patch": operations }));
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((
+        catalog_etag(catalog_version),
+        Json(DatasetPatchResponse {
+            dataset_name: name,
+            description: after.description,
+            tags: after.tags,
+            properties: after.properties,
+        }),
+    )
+        .into_response())
+}
+
+/// Delete a dataset
+///
+/// Moves it to the trash, or with `?purge=true` (or no retention) deletes it
+/// and everything attached to it immediately.
+pub(crate) async fn delete_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    Query(query): Query<DeleteDatasetQuery>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Deleting dataset");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    // Write hooks can block deletes too
+    let mut write = DatasetWrite::new(WriteOperation::Delete, WriteSource::Api, &name);
+    state
+        .write_hooks
+        .run_pre_validate(&mut write)
+        .await
+        .map_err(|e| write_hook_error(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Dataset ACLs can limit deletes to specific principals
+    if let Ok(dataset_id) = conn.query_row::<i64, _, _>(
+        "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+        [&name],
+        |row| row.get(0),
+    ) {
+        require_dataset_access(
+            &conn,
+            dataset_id,
+            &name,
+            identity.as_ref().map(|e| &e.0),
+            dataset_acl::AclPermission::Write,
+            &request_id,
+        )?;
+    }
+
+    // Get delta_location before deleting to invalidate cache
+    if let Ok(loc) = conn.query_row::<String, _, _>(
+        "SELECT delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+        [&name],
+        |row| row.get(0),
+    ) {
+        state.delta_reader.invalidate_cache(&loc).await;
+    }
+
+    // Move to the trash when retention is enabled, otherwise delete immediately
+    let trashed = state.trash_config.enabled() && !query.purge;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let deleted = if trashed {
+        trash::trash_dataset(&tx, &name, audit_context.api_key_id.as_deref())
+    } else {
+        trash::delete_dataset(&tx, &name)
+    }
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if !deleted {
+        return Err(dataset_not_found(&name, request_id.0.clone()));
+    }
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Purge expired trash on each delete so tenant catalogs, which the
+    // background purge task does not visit, are cleaned up too
+    if state.trash_config.enabled() {
+        match trash::purge_expired(&conn, state.trash_config.retention_days) {
+            Ok(purged) if !purged.is_empty() => {
+                tracing::info!(count = purged.len(), "Purged expired datasets from trash");
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to purge expired trash"),
+        }
+    }
+
+    tracing::info!(
+        name = %name,
+        trashed,
+        "Dataset deleted successfully"
+    );
+
+    notify_dataset_write(&state, &conn, &backend, tenant_backend.as_ref(), &write);
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    #[cfg(feature = "metrics")]
+    metrics::record_catalog_operation("delete_dataset", "success");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "dataset",
+            &name,
+            serde_json::json!({ "name": name, "trashed": trashed }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Update a field's description, business name, and glossary links
+///
+/// These survive re-emits of the dataset as long as the field stays in its
+/// schema.
+pub(crate) async fn patch_dataset_field(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetFieldPath(name, field): DatasetFieldPath,
+    JsonBody(req): JsonBody<UpdateFieldRequest>,
+) -> Result<Json<FieldResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let field_id: i64 = conn
+        .query_row(
+            "SELECT id FROM fields WHERE dataset_id = ?1 AND name = ?2",
+            rusqlite::params![dataset_id, &field],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Field '{}' not found in dataset '{}'", field, name),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // Resolve glossary terms up front so an unknown term rejects the whole update
+    let scope = request_glossary_scope(tenant_backend.as_ref());
+    let mut term_ids = Vec::new();
+    for term in req.glossary_terms.iter().flatten() {
+        let found = find_glossary_term(&conn, term, scope)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+            .ok_or_else(|| {
+                bad_request(
+                    format!("Glossary term '{}' does not exist", term),
+                    request_id.0.clone(),
+                )
+            })?;
+        term_ids.push(found.id);
+    }
+
+    let fields = load_dataset_fields(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let old = fields.into_iter().find(|f| f.name == field);
+
+    // Run pre-validate write hooks; field metadata isn't part of the write
+    let intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if let Some(description) = &req.description {
+        // Curated descriptions are kept over emitted ones (see MergeStrategy)
+        tx.execute(
+            "UPDATE fields SET description = NULLIF(?2, ''), description_curated = ?2 <> ''
+             WHERE id = ?1",
+            rusqlite::params![field_id, description],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    if let Some(business_name) = &req.business_name {
+        tx.execute(
+            "UPDATE fields SET business_name = NULLIF(?2, '') WHERE id = ?1",
+            rusqlite::params![field_id, business_name],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    if req.glossary_terms.is_some() {
+        tx.execute("DELETE FROM term_links WHERE field_id = ?1", [field_id])
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        for term_id in &term_ids {
+            tx.execute(
+                "INSERT OR IGNORE INTO term_links (term_id, field_id) VALUES (?1, ?2)",
+                rusqlite::params![term_id, field_id],
+            )
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+    }
+    tx.execute(
+        "UPDATE datasets SET last_updated = datetime('now') WHERE id = ?1",
+        [dataset_id],
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let updated = load_dataset_fields(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .into_iter()
+        .find(|f| f.name == field)
+        .ok_or_else(|| internal_error("Updated field missing".to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, field = %field, "Field metadata updated");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "field",
+            format!("{}.{}", name, field),
+            serde_json::json!(old),
+            serde_json::json!(updated),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "audit")]
+    fn test_dataset_audit_values_diff() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, owner, created_at, last_updated)
+             VALUES ('orders', '/lake/orders', 'parquet', 'sales', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let fields = ["owner", "custom_metadata"];
+        let old = dataset_audit_values(&conn, 1, &fields).unwrap();
+        conn.execute(
+            "UPDATE datasets SET owner = 'finance', custom_metadata = '{\"tier\": \"gold\"}'",
+            [],
+        )
+        .unwrap();
+        let new = dataset_audit_values(&conn, 1, &fields).unwrap();
+
+        assert_eq!(old["owner"], "sales");
+        assert!(old.get("domain").is_none());
+        let changes: Vec<_> = crate::audit_diff::diff(&old, &new)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.field)
+            .collect();
+        assert_eq!(changes, vec!["custom_metadata", "owner"]);
+    }
+}
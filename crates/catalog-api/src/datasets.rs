@@ -0,0 +1,1742 @@
+//! Dataset Reads
+//!
+//! `GET /api/v1/datasets`, `GET /api/v1/datasets/:name` with its `?include=`
+//! expansions, and `GET /api/v1/search`, along with the dataset response
+//! types the write endpoints share.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+#[cfg(all(
+    feature = "api-keys",
+    any(feature = "usage-analytics", feature = "classification")
+))]
+use crate::control_plane::TenantFeature;
+use crate::dataset_writes::webhook_tenant;
+use crate::error_codes::ErrorCode;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::resolve_backend;
+#[cfg(feature = "api-keys")]
+use crate::public_catalog;
+use crate::quality::compute_and_store_quality;
+#[cfg(feature = "semantic-search")]
+use crate::semantic_search;
+#[cfg(all(
+    feature = "api-keys",
+    any(feature = "usage-analytics", feature = "classification")
+))]
+use crate::server::feature_enabled;
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, catalog_etag, dataset_not_found, internal_error, require_dataset_access, AppState,
+    AuditContext, Caller, DatasetPath, ErrorResponse, RequestId,
+};
+use crate::sparse_fields::FieldSet;
+#[cfg(feature = "usage-analytics")]
+use crate::usage_analytics;
+use crate::{
+    archive, dataset_acl, dataset_list, envelope, freshness, metadata_completeness, quality,
+    response_profiles, sandbox, schema_on_read, sparse_fields, timeouts, users,
+};
+use axum::extract::{Extension, Query, State};
+use axum::http::{header, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use metafuse_catalog_core::write_report::WriteReport;
+use metafuse_catalog_core::{
+    custom_metadata, dataset_uuids, emission_state, external_nodes, field_ordinals, formats,
+    path_history, paths, validation,
+};
+use metafuse_catalog_storage::read_snapshot;
+use rusqlite::params_from_iter;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Dataset response structure
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DatasetResponse {
+    pub(crate) id: i64,
+    /// Stable external identifier (migration v1.21.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) uuid: Option<String>,
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) delta_location: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) tenant: Option<String>,
+    pub(crate) domain: Option<String>,
+    pub(crate) owner: Option<String>,
+    pub(crate) created_at: String,
+    pub(crate) last_updated: String,
+    pub(crate) operational: OperationalMetaResponse,
+    /// Age and staleness (list, detail, and search responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) freshness: Option<freshness::Freshness>,
+    /// Documentation score (list and search responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) metadata_completeness: Option<metadata_completeness::MetadataCompleteness>,
+    /// Integrator-defined JSON object (detail and write responses, migration
+    /// v1.24.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) custom_metadata: Option<serde_json::Value>,
+    /// Tags (list responses with `include=tags`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tags: Option<Vec<String>>,
+    /// Latest quality score (list responses with `include=quality_summary`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) quality_summary: Option<dataset_list::QualitySummary>,
+    /// When the dataset was archived (migration v1.44.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) archived_at: Option<String>,
+    /// What the write did with the given upstreams and tags (create responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) write_report: Option<WriteReport>,
+    /// Directory profile of the owner (single-dataset responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) owner_profile: Option<users::DirectoryUser>,
+}
+
+/// Field response structure
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct FieldResponse {
+    pub(crate) name: String,
+    data_type: String,
+    nullable: bool,
+    description: Option<String>,
+    /// Curated display name (migration v1.41.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    business_name: Option<String>,
+    /// Linked glossary terms, by name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    glossary_terms: Vec<String>,
+    /// 0-based position in the dataset's schema
+    #[serde(default)]
+    ordinal: usize,
+}
+
+/// Operational metadata response
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub(crate) struct OperationalMetaResponse {
+    pub(crate) row_count: Option<i64>,
+    pub(crate) size_bytes: Option<i64>,
+    pub(crate) partition_keys: Vec<String>,
+}
+
+impl DatasetResponse {
+    /// Strip storage locations, ownership and custom metadata for anonymous
+    /// public-catalog responses
+    #[cfg(feature = "api-keys")]
+    fn redacted(mut self) -> Self {
+        self.path = public_catalog::REDACTED.to_string();
+        self.delta_location = None;
+        self.owner = None;
+        self.owner_profile = None;
+        self.custom_metadata = None;
+        self
+    }
+}
+
+/// Query params for get_dataset endpoint with optional includes
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct DatasetQueryParams {
+    /// Comma-separated list of additional data to include: delta,quality,lineage
+    pub(crate) include: Option<String>,
+    /// Named response profile supplying `include` when it is not given
+    pub(crate) profile: Option<String>,
+    /// Read the schema from storage when the dataset has no fields
+    #[serde(default)]
+    pub(crate) resolve_schema: bool,
+    /// Store the schema read with `resolve_schema` in the catalog
+    #[serde(default)]
+    pub(crate) persist_schema: bool,
+    /// Recompute `?include=quality` from Delta instead of reusing a stored result
+    #[serde(default)]
+    pub(crate) refresh: bool,
+}
+
+/// Valid include values
+pub(crate) const VALID_INCLUDE_VALUES: &[&str] = &["delta", "quality", "lineage"];
+
+/// Parsed include options
+#[derive(Debug, Default)]
+pub(crate) struct IncludeOptions {
+    delta: bool,
+    quality: bool,
+    lineage: bool,
+}
+
+impl IncludeOptions {
+    /// Parse and validate include query parameter
+    ///
+    /// Returns an error if any unknown include values are provided.
+    /// Valid values: delta, quality, lineage
+    fn parse(include: &Option<String>) -> Result<Self, String> {
+        match include {
+            None => Ok(Self::default()),
+            Some(s) if s.trim().is_empty() => Ok(Self::default()),
+            Some(s) => {
+                let parts: Vec<String> = s.split(',').map(|p| p.trim().to_lowercase()).collect();
+
+                // Validate all values are known
+                let invalid: Vec<&String> = parts
+                    .iter()
+                    .filter(|p| !p.is_empty() && !VALID_INCLUDE_VALUES.contains(&p.as_str()))
+                    .collect();
+
+                if !invalid.is_empty() {
+                    return Err(format!(
+                        "Invalid include value(s): {}. Valid values: {}",
+                        invalid
+                            .iter()
+                            .map(|s| format!("'{}'", s))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        VALID_INCLUDE_VALUES.join(", ")
+                    ));
+                }
+
+                Ok(Self {
+                    delta: parts.iter().any(|p| p == "delta"),
+                    quality: parts.iter().any(|p| p == "quality"),
+                    lineage: parts.iter().any(|p| p == "lineage"),
+                })
+            }
+        }
+    }
+}
+
+/// Delta table info (from live Delta metadata)
+#[derive(Debug, Serialize)]
+pub(crate) struct DeltaInfo {
+    version: i64,
+    row_count: i64,
+    size_bytes: i64,
+    num_files: i64,
+    partition_columns: Vec<String>,
+    last_modified: Option<String>,
+}
+
+/// Quality metrics info (latest computed scores)
+#[derive(Debug, Serialize)]
+pub(crate) struct QualityInfo {
+    overall_score: Option<f64>,
+    completeness_score: Option<f64>,
+    freshness_score: Option<f64>,
+    file_health_score: Option<f64>,
+    last_computed: Option<String>,
+    /// Profiler that computed the scores (`delta` for MetaFuse itself)
+    source: Option<String>,
+    /// Whether the scores were computed for this request
+    recomputed: bool,
+}
+
+/// Lineage info (upstream and downstream datasets)
+#[derive(Debug, Serialize)]
+pub(crate) struct LineageInfo {
+    upstream: Vec<String>,
+    downstream: Vec<String>,
+    /// External nodes feeding the dataset
+    external_upstream: Vec<external_nodes::ExternalNode>,
+    /// External nodes fed by the dataset
+    external_downstream: Vec<external_nodes::ExternalNode>,
+}
+
+/// Extended dataset detail response with optional includes
+#[derive(Debug, Serialize)]
+pub(crate) struct ExtendedDatasetResponse {
+    #[serde(flatten)]
+    dataset: DatasetResponse,
+    fields: Vec<FieldResponse>,
+    tags: Vec<String>,
+    upstream_datasets: Vec<String>,
+    downstream_datasets: Vec<String>,
+    /// Delta table metadata (optional, via ?include=delta)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delta: Option<DeltaInfo>,
+    /// Quality metrics (optional, via ?include=quality)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quality: Option<QualityInfo>,
+    /// Lineage info (optional, via ?include=lineage) - separate from upstream/downstream for structured access
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lineage: Option<LineageInfo>,
+    /// Previous storage paths, newest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    path_history: Vec<path_history::PathChange>,
+    /// Where `fields` were read from (optional, via ?resolve_schema=true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schema_resolution: Option<schema_on_read::SchemaResolution>,
+}
+
+/// Pagination query params for list endpoints
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct PaginationParams {
+    /// Maximum number of items to return (default: 100, max: 1000)
+    pub(crate) limit: Option<usize>,
+    /// Number of items to skip (default: 0)
+    pub(crate) offset: Option<usize>,
+}
+
+impl PaginationParams {
+    pub(crate) fn limit(&self) -> usize {
+        self.limit.unwrap_or(100).min(1000)
+    }
+
+    pub(crate) fn offset(&self) -> usize {
+        self.offset.unwrap_or(0)
+    }
+}
+
+/// List all datasets
+pub(crate) async fn list_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        public_access,
+        request_sandbox,
+        ..
+    }: Caller,
+    Query(params): Query<HashMap<String, String>>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<serde_json::Value>>, (StatusCode, Json<ErrorResponse>)> {
+    let options = state
+        .response_profiles
+        .resolve(
+            params.get("profile").map(String::as_str),
+            None,
+            params.get("fields").map(String::as_str),
+        )
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let includes = dataset_list::ListIncludes::parse(params.get("include").map(String::as_str))
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let fields = FieldSet::parse(options.fields.as_deref(), sparse_fields::DATASET_FIELDS)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?
+        .map(|fields| fields.with_keys(&includes.keys()));
+    let stale = match params.get("stale").map(String::as_str) {
+        None => None,
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        Some(other) => {
+            return Err(bad_request(
+                format!("Invalid stale '{}'. Valid values: true, false", other),
+                request_id.0.clone(),
+            ))
+        }
+    };
+    let include_archived =
+        archive::parse_include_archived(params.get("include_archived").map(String::as_str))
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        filter_tenant = ?params.get("tenant"),
+        filter_domain = ?params.get("domain"),
+        filter_owner = ?params.get("owner"),
+        filter_format = ?params.get("format"),
+        filter_tag = ?params.get("tag"),
+        "Listing datasets with filters"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    // Datasets and their annotations come from one snapshot
+    let conn =
+        read_snapshot(&conn).map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut query = String::from(
+        r#"
+        SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+               created_at, last_updated, row_count, size_bytes, partition_keys
+        FROM datasets
+        WHERE deleted_at IS NULL
+        "#,
+    );
+
+    let mut bindings: Vec<String> = Vec::new();
+
+    if !include_archived {
+        query.push_str(" AND ");
+        query.push_str(&archive::visibility_clause("datasets"));
+    }
+
+    // Validate and apply tenant filter
+    if let Some(tenant) = params.get("tenant") {
+        validation::validate_identifier(tenant, "tenant")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+        query.push_str(" AND tenant = ?");
+        bindings.push(tenant.clone());
+    }
+
+    // Validate and apply domain filter
+    if let Some(domain) = params.get("domain") {
+        validation::validate_identifier(domain, "domain")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+        query.push_str(" AND domain = ?");
+        bindings.push(domain.clone());
+    }
+
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    match params.get("owner").map(String::as_str) {
+        // Datasets owned by the caller or one of their groups
+        Some("me") => {
+            let owners = identity.owner_ids();
+            if owners.is_empty() {
+                return Err(bad_request(
+                    "owner=me requires a user or group identity".to_string(),
+                    request_id.0.clone(),
+                ));
+            }
+            query.push_str(&format!(
+                " AND owner IN ({})",
+                vec!["?"; owners.len()].join(", ")
+            ));
+            bindings.extend(owners);
+        }
+        Some(owner) => {
+            query.push_str(" AND owner = ?");
+            bindings.push(owner.to_string());
+        }
+        None => {}
+    }
+    if let Some(format) = params.get("format") {
+        query.push_str(" AND format = ? COLLATE NOCASE");
+        bindings.push(format.clone());
+    }
+    if let Some(tag) = params.get("tag") {
+        query.push_str(" AND id IN (SELECT dataset_id FROM tags WHERE tag = ?)");
+        bindings.push(tag.clone());
+    }
+    for (param, op) in [("created_after", ">="), ("created_before", "<")] {
+        if let Some(raw) = params.get(param) {
+            let bound = dataset_list::normalize_bound(param, raw)
+                .map_err(|e| bad_request(e, request_id.0.clone()))?;
+            query.push_str(&format!(" AND {} {} ?", dataset_list::CREATED_AT_EXPR, op));
+            bindings.push(bound);
+        }
+    }
+
+    // Hide restricted datasets from anonymous public-catalog requests
+    #[cfg(feature = "api-keys")]
+    if let Some(Extension(access)) = public_access.as_ref() {
+        if let Some(clause) = access.exclusion_clause("id") {
+            query.push_str(" AND ");
+            query.push_str(&clause);
+            bindings.extend(access.excluded_tags().iter().cloned());
+        }
+    }
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    if let Some((clause, principals)) =
+        dataset_acl::visibility_clause(&identity, "datasets.id", "datasets.domain")
+    {
+        query.push_str(" AND ");
+        query.push_str(&clause);
+        bindings.extend(principals);
+    }
+
+    // Hide sandbox datasets, except the request's own sandbox
+    let (clause, sandboxes) =
+        sandbox::visibility_clause("datasets.name", request_sandbox.as_ref().map(|e| &e.0));
+    query.push_str(" AND ");
+    query.push_str(&clause);
+    bindings.extend(sandboxes);
+
+    let order_by = dataset_list::order_by(
+        params.get("sort_by").map(String::as_str),
+        params.get("sort_dir").map(String::as_str),
+    )
+    .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    query.push_str(&order_by);
+
+    let mut stmt = conn
+        .prepare(&query)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut datasets: Vec<DatasetResponse> = stmt
+        .query_map(params_from_iter(bindings.iter()), |row| {
+            let row_count: Option<i64> = row.get(11)?;
+            let size_bytes: Option<i64> = row.get(12)?;
+            let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+            Ok(DatasetResponse {
+                id: row.get(0)?,
+                uuid: None,
+                name: row.get(1)?,
+                path: row.get(2)?,
+                format: row.get(3)?,
+                delta_location: row.get(4)?,
+                description: row.get(5)?,
+                tenant: row.get(6)?,
+                domain: row.get(7)?,
+                owner: row.get(8)?,
+                created_at: row.get(9)?,
+                last_updated: row.get(10)?,
+                operational: OperationalMetaResponse {
+                    row_count,
+                    size_bytes,
+                    partition_keys,
+                },
+                freshness: None,
+                metadata_completeness: None,
+                custom_metadata: None,
+                tags: None,
+                quality_summary: None,
+                archived_at: None,
+                write_report: None,
+                owner_profile: None,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    attach_uuids(&conn, &mut datasets)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if include_archived {
+        annotate_archived(&conn, &mut datasets)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    annotate_freshness(&conn, &mut datasets)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    annotate_metadata_completeness(&conn, &mut datasets)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if let Some(stale) = stale {
+        datasets.retain(|d| d.freshness.as_ref().and_then(|f| f.is_stale) == Some(stale));
+    }
+    annotate_list_includes(&conn, &mut datasets, includes)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    #[cfg(feature = "api-keys")]
+    let datasets: Vec<DatasetResponse> = if public_access.is_some() {
+        datasets
+            .into_iter()
+            .map(DatasetResponse::redacted)
+            .collect()
+    } else {
+        datasets
+    };
+
+    tracing::info!(count = datasets.len(), "Listed datasets successfully");
+
+    #[cfg(feature = "metrics")]
+    metrics::record_catalog_operation("list_datasets", "success");
+
+    let datasets = sparse_fields::project_all(&datasets, fields.as_ref())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.page(datasets)))
+}
+
+/// Get a specific dataset by name with optional includes via ?include=delta,quality,lineage
+///
+/// Applies the request's time budget here rather than in the timeout
+/// middleware, since only some queries read Delta.
+pub(crate) async fn get_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Extension(timeouts): Extension<Arc<timeouts::TimeoutConfig>>,
+    caller: Caller,
+    path: DatasetPath,
+    Query(params): Query<DatasetQueryParams>,
+) -> Response {
+    let budget = dataset_read_budget(&timeouts, &state.response_profiles, &params);
+    let request_id_value = request_id.0.clone();
+    let read = read_dataset(
+        State(state),
+        Extension(request_id),
+        Extension(audit_context),
+        caller,
+        path,
+        Query(params),
+    );
+    let Some(budget) = budget else {
+        return read.await.into_response();
+    };
+    match tokio::time::timeout(budget, read).await {
+        Ok(result) => result.into_response(),
+        Err(_) => timeouts::timed_out(&Method::GET, DATASET_ROUTE, budget, &request_id_value),
+    }
+}
+
+/// Route of [`get_dataset`]
+pub(crate) const DATASET_ROUTE: &str = "/api/v1/datasets/{name}";
+
+/// Time budget for a dataset read: slow when it reads Delta, through
+/// `?resolve_schema=true` or a `delta` or `quality` include
+pub(crate) fn dataset_read_budget(
+    timeouts: &timeouts::TimeoutConfig,
+    profiles: &response_profiles::ResponseProfiles,
+    params: &DatasetQueryParams,
+) -> Option<Duration> {
+    // Invalid includes are rejected by the handler, within the read budget
+    let includes = profiles
+        .resolve(params.profile.as_deref(), params.include.as_deref(), None)
+        .ok()
+        .and_then(|options| IncludeOptions::parse(&options.include).ok())
+        .unwrap_or_default();
+    let slow = params.resolve_schema || includes.delta || includes.quality;
+    timeouts.read_budget(DATASET_ROUTE, slow)
+}
+
+/// [`get_dataset`] within its time budget
+pub(crate) async fn read_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(all(feature = "api-keys", feature = "usage-analytics"))]
+        feature_flags,
+        #[cfg(feature = "api-keys")]
+        public_access,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<DatasetQueryParams>,
+) -> Result<
+    (
+        [(header::HeaderName, String); 1],
+        Json<ExtendedDatasetResponse>,
+    ),
+    (StatusCode, Json<ErrorResponse>),
+> {
+    // Validate include parameter first
+    let options = state
+        .response_profiles
+        .resolve(params.profile.as_deref(), params.include.as_deref(), None)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let includes = IncludeOptions::parse(&options.include)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    if params.persist_schema && !params.resolve_schema {
+        return Err(bad_request(
+            "persist_schema requires resolve_schema=true".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+    if params.refresh && !includes.quality {
+        return Err(bad_request(
+            "refresh requires include=quality".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        dataset_name = %name,
+        include_delta = includes.delta,
+        include_quality = includes.quality,
+        include_lineage = includes.lineage,
+        "Getting dataset details"
+    );
+
+    // Validate dataset name
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    // Perform all synchronous database operations in a block to properly scope borrows
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let (
+        catalog_version,
+        dataset,
+        mut fields,
+        tags,
+        upstream_datasets,
+        downstream_datasets,
+        external_lineage,
+        mut quality_info,
+        path_changes,
+    ) = {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        // One snapshot for all queries, so the response and its ETag reflect a
+        // single catalog state even while writers are active
+        let conn = read_snapshot(&conn)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        let catalog_version = metafuse_catalog_core::get_catalog_version(&conn)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        // Get dataset
+        let mut dataset: DatasetResponse = conn
+            .query_row(
+                r#"
+            SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+                   created_at, last_updated, row_count, size_bytes, partition_keys
+            FROM datasets
+            WHERE name = ?1 AND deleted_at IS NULL
+            "#,
+                [&name],
+                |row| {
+                    let row_count: Option<i64> = row.get(11)?;
+                    let size_bytes: Option<i64> = row.get(12)?;
+                    let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+                    Ok(DatasetResponse {
+                        id: row.get(0)?,
+                        uuid: None,
+                        name: row.get(1)?,
+                        path: row.get(2)?,
+                        format: row.get(3)?,
+                        delta_location: row.get(4)?,
+                        description: row.get(5)?,
+                        tenant: row.get(6)?,
+                        domain: row.get(7)?,
+                        owner: row.get(8)?,
+                        created_at: row.get(9)?,
+                        last_updated: row.get(10)?,
+                        operational: OperationalMetaResponse {
+                            row_count,
+                            size_bytes,
+                            partition_keys,
+                        },
+                        freshness: None,
+                        metadata_completeness: None,
+                        custom_metadata: None,
+                        tags: None,
+                        quality_summary: None,
+                        archived_at: None,
+                        write_report: None,
+                        owner_profile: None,
+                    })
+                },
+            )
+            .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+        attach_uuids(&conn, std::slice::from_mut(&mut dataset))
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        annotate_archived(&conn, std::slice::from_mut(&mut dataset))
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        annotate_freshness(&conn, std::slice::from_mut(&mut dataset))
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        dataset.owner_profile = dataset.owner.as_deref().and_then(|o| state.users.user(o));
+        dataset.custom_metadata = custom_metadata::load(&conn, dataset.id)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        // Get fields
+        let fields = load_dataset_fields(&conn, dataset.id)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        // Get tags
+        let mut stmt = conn
+            .prepare("SELECT tag FROM tags WHERE dataset_id = ?1")
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let tags: Vec<String> = stmt
+            .query_map([dataset.id], |row| row.get::<_, String>(0))
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        drop(stmt);
+
+        // Restricted datasets are invisible to anonymous public-catalog requests
+        #[cfg(feature = "api-keys")]
+        if public_access
+            .as_ref()
+            .map(|e| e.0.is_restricted(&tags))
+            .unwrap_or(false)
+        {
+            return Err(dataset_not_found(&name, request_id.0.clone()));
+        }
+
+        require_dataset_access(
+            &conn,
+            dataset.id,
+            &name,
+            identity.as_ref().map(|e| &e.0),
+            dataset_acl::AclPermission::Read,
+            &request_id,
+        )?;
+
+        // Lineage neighbours with restricted tags are hidden from anonymous
+        // public-catalog requests, like the datasets themselves
+        #[cfg(feature = "api-keys")]
+        let exclusion = public_access.as_ref().and_then(|e| {
+            e.0.exclusion_clause("d.id")
+                .map(|clause| (clause, e.0.excluded_tags().to_vec()))
+        });
+        #[cfg(not(feature = "api-keys"))]
+        let exclusion: Option<(String, Vec<String>)> = None;
+
+        // Neighbours the caller cannot read under dataset ACLs are hidden too
+        let anonymous = dataset_acl::Identity::default();
+        let acl = dataset_acl::visibility_clause(
+            identity.as_ref().map(|e| &e.0).unwrap_or(&anonymous),
+            "d.id",
+            "d.domain",
+        );
+        let exclusion = match (exclusion, acl) {
+            (Some((clause, mut bindings)), Some((acl_clause, principals))) => {
+                bindings.extend(principals);
+                Some((format!("{} AND {}", clause, acl_clause), bindings))
+            }
+            (exclusion, acl) => exclusion.or(acl),
+        };
+
+        let upstream_datasets = lineage_names(&conn, dataset.id, true, exclusion.as_ref())
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let downstream_datasets = lineage_names(&conn, dataset.id, false, exclusion.as_ref())
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        // External sources and sinks are only part of the structured lineage
+        let external_lineage = if includes.lineage {
+            external_nodes::for_dataset(&conn, dataset.id)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        } else {
+            Vec::new()
+        };
+
+        // Fetch quality metrics from current connection (before dropping it)
+        let quality_info = if includes.quality {
+            conn.query_row(
+                r#"
+                SELECT overall_score, completeness_score, freshness_score, file_health_score,
+                       computed_at, source
+                FROM quality_metrics
+                WHERE dataset_id = ?1
+                ORDER BY computed_at DESC
+                LIMIT 1
+                "#,
+                [dataset.id],
+                |row| {
+                    Ok(QualityInfo {
+                        overall_score: row.get(0)?,
+                        completeness_score: row.get(1)?,
+                        freshness_score: row.get(2)?,
+                        file_health_score: row.get(3)?,
+                        last_computed: row.get(4)?,
+                        source: row.get(5)?,
+                        recomputed: false,
+                    })
+                },
+            )
+            .ok()
+        } else {
+            None
+        };
+
+        let path_changes = path_history::history_for(&conn, dataset.id)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        // Return all data - conn and statements are dropped at end of block
+        (
+            catalog_version,
+            dataset,
+            fields,
+            tags,
+            upstream_datasets,
+            downstream_datasets,
+            external_lineage,
+            quality_info,
+            path_changes,
+        )
+    };
+
+    // Read the schema from storage for datasets registered without fields
+    let mut schema_resolution = None;
+    if params.resolve_schema && fields.is_empty() {
+        #[cfg(feature = "api-keys")]
+        if public_access.is_some() {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    error: "Schema resolution is not available to anonymous requests".to_string(),
+                    code: ErrorCode::Forbidden,
+                    request_id: request_id.0.clone(),
+                }),
+            ));
+        }
+        let (source, location) = schema_on_read::source_for(
+            &dataset.format,
+            &dataset.path,
+            dataset.delta_location.as_deref(),
+        )
+        .ok_or_else(|| {
+            bad_request(
+                format!(
+                    "Cannot resolve schema for dataset '{}': format '{}' is not Delta or Parquet and delta_location is not configured",
+                    name, dataset.format
+                ),
+                request_id.0.clone(),
+            )
+        })?;
+
+        // Check write access before reading storage
+        if params.persist_schema {
+            #[cfg(feature = "api-keys")]
+            require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+                .map_err(rbac_error)?;
+            let conn = backend
+                .get_connection()
+                .await
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            require_dataset_access(
+                &conn,
+                dataset.id,
+                &name,
+                identity.as_ref().map(|e| &e.0),
+                dataset_acl::AclPermission::Write,
+                &request_id,
+            )?;
+        }
+
+        // Like delta metadata, an unreachable table leaves the fields empty
+        match schema_on_read::resolve(&state.delta_reader, source, &location).await {
+            Ok(resolved) => {
+                let persisted = if params.persist_schema {
+                    let conn = backend
+                        .get_connection()
+                        .await
+                        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+                    schema_on_read::persist(&conn, dataset.id, &resolved)
+                        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+                } else {
+                    false
+                };
+                if persisted {
+                    tracing::info!(
+                        dataset_name = %name,
+                        field_count = resolved.len(),
+                        "Stored schema resolved from storage"
+                    );
+
+                    // Storing the fields is a write like any other
+                    #[cfg(feature = "audit")]
+                    {
+                        let field_names: Vec<&str> =
+                            resolved.iter().map(|f| f.name.as_str()).collect();
+                        let event = audit::AuditEvent::update(
+                            "dataset",
+                            &name,
+                            serde_json::json!({ "fields": [] }),
+                            serde_json::json!({ "fields": field_names }),
+                            &request_id.0,
+                        )
+                        .with_context(serde_json::json!({
+                            "schema_resolution": { "source": source, "location": location }
+                        }));
+                        state.audit_logger.log(audit_context.enrich_event(event));
+                    }
+                }
+                fields = resolved
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ordinal, f)| FieldResponse {
+                        name: f.name,
+                        data_type: f.data_type,
+                        nullable: f.nullable,
+                        description: f.description,
+                        business_name: None,
+                        glossary_terms: Vec::new(),
+                        ordinal,
+                    })
+                    .collect();
+                schema_resolution = Some(schema_on_read::SchemaResolution {
+                    source,
+                    location,
+                    persisted,
+                });
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, location = %location, "Failed to resolve dataset schema");
+            }
+        }
+    }
+
+    // Stored quality results are reused until they go stale; datasets without
+    // a Delta table keep whatever was last pushed. Anonymous public-catalog
+    // requests only ever get the stored result.
+    if includes.quality {
+        #[cfg(feature = "api-keys")]
+        let anonymous = public_access.is_some();
+        #[cfg(not(feature = "api-keys"))]
+        let anonymous = false;
+        if anonymous && params.refresh {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    error: "Quality refresh is not available to anonymous requests".to_string(),
+                    code: ErrorCode::Forbidden,
+                    request_id: request_id.0.clone(),
+                }),
+            ));
+        }
+        let stale = !anonymous
+            && quality_info
+                .as_ref()
+                .and_then(|q| q.last_computed.as_deref())
+                .is_none_or(|computed_at| {
+                    !state
+                        .quality_cache
+                        .is_fresh(computed_at, chrono::Utc::now())
+                });
+        match &dataset.delta_location {
+            Some(location) if params.refresh || stale => {
+                if params.refresh {
+                    state.delta_reader.invalidate_cache(location).await;
+                }
+                match compute_and_store_quality(
+                    &state,
+                    &backend,
+                    webhook_tenant(tenant_backend.as_ref()),
+                    dataset.id,
+                    &dataset.name,
+                    location,
+                )
+                .await
+                {
+                    Ok(scores) => {
+                        quality_info = Some(QualityInfo {
+                            overall_score: scores.overall_score,
+                            completeness_score: scores.completeness_score,
+                            freshness_score: scores.freshness_score,
+                            file_health_score: scores.file_health_score,
+                            last_computed: Some(chrono::Utc::now().to_rfc3339()),
+                            source: Some(quality::DELTA_STATS_SOURCE.to_string()),
+                            recomputed: true,
+                        });
+                    }
+                    Err(e) if params.refresh => {
+                        return Err(internal_error(
+                            format!("Failed to recompute quality: {}", e),
+                            request_id.0.clone(),
+                        ));
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, delta_location = %location, "Failed to recompute stale quality scores; serving stored result");
+                    }
+                }
+            }
+            None if params.refresh => {
+                return Err(bad_request(
+                    format!(
+                        "Dataset '{}' has no delta_location configured for quality computation",
+                        name
+                    ),
+                    request_id.0.clone(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    // Fetch delta info asynchronously if requested
+    let delta_info = if includes.delta {
+        // Unknown formats predate the registry; let delta_location decide for those
+        if formats::lookup_format(&dataset.format).is_some_and(|f| !f.supports_time_travel) {
+            return Err(bad_request(
+                format!(
+                    "Cannot include delta metadata for dataset '{}': format '{}' does not support time travel",
+                    name, dataset.format
+                ),
+                request_id.0.clone(),
+            ));
+        }
+        match &dataset.delta_location {
+            Some(loc) => match state.delta_reader.get_metadata_cached(loc).await {
+                Ok(meta) => Some(DeltaInfo {
+                    version: meta.version,
+                    row_count: meta.row_count,
+                    size_bytes: meta.size_bytes,
+                    num_files: meta.num_files,
+                    partition_columns: meta.partition_columns,
+                    last_modified: Some(meta.last_modified.to_rfc3339()),
+                }),
+                Err(e) => {
+                    tracing::warn!(error = %e, delta_location = %loc, "Failed to fetch delta metadata");
+                    None
+                }
+            },
+            None => {
+                return Err(bad_request(
+                    format!(
+                        "Cannot include delta metadata for dataset '{}': delta_location is not configured",
+                        name
+                    ),
+                    request_id.0.clone(),
+                ));
+            }
+        }
+    } else {
+        None
+    };
+
+    // Build structured lineage if requested
+    let lineage_info = if includes.lineage {
+        let (external_upstream, external_downstream): (Vec<_>, Vec<_>) = external_lineage
+            .into_iter()
+            .partition(|(_, direction)| *direction == external_nodes::ExternalDirection::Upstream);
+        Some(LineageInfo {
+            upstream: upstream_datasets.clone(),
+            downstream: downstream_datasets.clone(),
+            external_upstream: external_upstream
+                .into_iter()
+                .map(|(node, _)| node)
+                .collect(),
+            external_downstream: external_downstream
+                .into_iter()
+                .map(|(node, _)| node)
+                .collect(),
+        })
+    } else {
+        None
+    };
+
+    tracing::info!(
+        dataset_name = %name,
+        field_count = fields.len(),
+        tag_count = tags.len(),
+        upstream_count = upstream_datasets.len(),
+        downstream_count = downstream_datasets.len(),
+        include_delta = includes.delta,
+        include_quality = includes.quality,
+        include_lineage = includes.lineage,
+        request_id = %request_id.0,
+        "Retrieved dataset details successfully"
+    );
+
+    #[cfg(feature = "metrics")]
+    metrics::record_catalog_operation("get_dataset", "success");
+
+    // Track usage (non-blocking), unless disabled for this tenant
+    #[cfg(feature = "usage-analytics")]
+    {
+        #[cfg(feature = "api-keys")]
+        let track_usage = feature_enabled(feature_flags.as_ref(), TenantFeature::UsageAnalytics);
+        #[cfg(not(feature = "api-keys"))]
+        let track_usage = true;
+
+        if track_usage {
+            let tracker = state.usage_tracker.clone();
+            let tenant_id = tenant_id.to_string();
+            let dataset_id = dataset.id;
+            tokio::spawn(async move {
+                tracker
+                    .record_access(
+                        &tenant_id,
+                        dataset_id,
+                        None,
+                        usage_analytics::AccessType::Read,
+                    )
+                    .await;
+            });
+        }
+    }
+
+    // Paths are redacted for anonymous public-catalog requests, old ones too
+    #[cfg(feature = "api-keys")]
+    let (dataset, path_changes) = if public_access.is_some() {
+        (dataset.redacted(), Vec::new())
+    } else {
+        (dataset, path_changes)
+    };
+
+    Ok((
+        catalog_etag(catalog_version),
+        Json(ExtendedDatasetResponse {
+            dataset,
+            fields,
+            tags,
+            upstream_datasets,
+            downstream_datasets,
+            delta: delta_info,
+            quality: quality_info,
+            lineage: lineage_info,
+            path_history: path_changes,
+            schema_resolution,
+        }),
+    ))
+}
+
+/// Names of a dataset's live upstream (or downstream) datasets
+///
+/// `exclusion` is a restricted-tag and dataset-ACL condition on `d` and its
+/// bindings.
+pub(crate) fn lineage_names(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    upstream: bool,
+    exclusion: Option<&(String, Vec<String>)>,
+) -> rusqlite::Result<Vec<String>> {
+    let (neighbour, own) = if upstream {
+        ("upstream_dataset_id", "downstream_dataset_id")
+    } else {
+        ("downstream_dataset_id", "upstream_dataset_id")
+    };
+    let mut sql = format!(
+        r#"
+        SELECT d.name
+        FROM lineage l
+        JOIN datasets d ON l.{} = d.id
+        WHERE l.{} = ? AND d.deleted_at IS NULL
+        "#,
+        neighbour, own
+    );
+    let mut bindings: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(dataset_id)];
+    if let Some((clause, values)) = exclusion {
+        sql.push_str(" AND ");
+        sql.push_str(clause);
+        bindings.extend(
+            values
+                .iter()
+                .map(|v| Box::new(v.clone()) as Box<dyn rusqlite::ToSql>),
+        );
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let names = stmt
+        .query_map(
+            params_from_iter(bindings.iter().map(|b| b.as_ref())),
+            |row| row.get::<_, String>(0),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Search datasets using FTS, or embeddings blended with FTS via `?mode=semantic`
+pub(crate) async fn search_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(all(feature = "api-keys", feature = "usage-analytics"))]
+        feature_flags,
+        #[cfg(feature = "api-keys")]
+        public_access,
+        request_sandbox,
+        ..
+    }: Caller,
+    Query(params): Query<HashMap<String, String>>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<serde_json::Value>>, (StatusCode, Json<ErrorResponse>)> {
+    let query = params
+        .get("q")
+        .ok_or_else(|| bad_request("Missing 'q' parameter".to_string(), request_id.0.clone()))?;
+    let options = state
+        .response_profiles
+        .resolve(
+            params.get("profile").map(String::as_str),
+            None,
+            params.get("fields").map(String::as_str),
+        )
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let fields = FieldSet::parse(options.fields.as_deref(), sparse_fields::DATASET_FIELDS)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let mode = params.get("mode").map(String::as_str).unwrap_or("fts");
+    let include_archived =
+        archive::parse_include_archived(params.get("include_archived").map(String::as_str))
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, search_query = %query, mode = %mode, "Executing search");
+
+    // Validate FTS query (operators are allowed for powerful search)
+    let validated_query = validation::validate_fts_query(query)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+
+    // Hide restricted datasets from anonymous public-catalog requests
+    #[allow(unused_mut)] // only set when api-keys feature is enabled
+    let mut exclusion: Option<(String, Vec<String>)> = None;
+    #[cfg(feature = "api-keys")]
+    if let Some(Extension(access)) = public_access.as_ref() {
+        exclusion = access
+            .exclusion_clause("d.id")
+            .map(|clause| (clause, access.excluded_tags().to_vec()));
+    }
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let exclusion = match (
+        exclusion,
+        dataset_acl::visibility_clause(&identity, "d.id", "d.domain"),
+    ) {
+        (Some((clause, mut bindings)), Some((acl_clause, principals))) => {
+            bindings.extend(principals);
+            Some((format!("{} AND {}", clause, acl_clause), bindings))
+        }
+        (exclusion, acl) => exclusion.or(acl),
+    };
+
+    // Hide sandbox datasets, except the request's own sandbox
+    let (sandbox_clause, sandboxes) =
+        sandbox::visibility_clause("d.name", request_sandbox.as_ref().map(|e| &e.0));
+    let mut exclusion = match exclusion {
+        Some((clause, mut bindings)) => {
+            bindings.extend(sandboxes);
+            (format!("{} AND {}", clause, sandbox_clause), bindings)
+        }
+        None => (sandbox_clause, sandboxes),
+    };
+
+    // Hide archived datasets unless asked for
+    if !include_archived {
+        exclusion.0 = format!("{} AND {}", exclusion.0, archive::visibility_clause("d"));
+    }
+    let exclusion = Some(exclusion);
+
+    let mut datasets = match mode {
+        "fts" => {
+            let conn = backend
+                .get_connection()
+                .await
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+            let mut sql = String::from(
+                r#"
+                    SELECT d.id, d.name, d.path, d.format, d.delta_location, d.description, d.tenant, d.domain, d.owner,
+                           d.created_at, d.last_updated, d.row_count, d.size_bytes, d.partition_keys
+                    FROM datasets d
+                    JOIN dataset_search s ON d.name = s.dataset_name
+                    WHERE dataset_search MATCH ? AND d.deleted_at IS NULL
+                    "#,
+            );
+            let mut bindings: Vec<String> = vec![validated_query];
+            if let Some((clause, tags)) = &exclusion {
+                sql.push_str(" AND ");
+                sql.push_str(clause);
+                bindings.extend(tags.iter().cloned());
+            }
+
+            sql.push_str(" ORDER BY bm25(dataset_search)");
+
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+            let datasets = stmt
+                .query_map(params_from_iter(bindings.iter()), |row| {
+                    let row_count: Option<i64> = row.get(11)?;
+                    let size_bytes: Option<i64> = row.get(12)?;
+                    let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+                    Ok(DatasetResponse {
+                        id: row.get(0)?,
+                        uuid: None,
+                        name: row.get(1)?,
+                        path: row.get(2)?,
+                        format: row.get(3)?,
+                        delta_location: row.get(4)?,
+                        description: row.get(5)?,
+                        tenant: row.get(6)?,
+                        domain: row.get(7)?,
+                        owner: row.get(8)?,
+                        created_at: row.get(9)?,
+                        last_updated: row.get(10)?,
+                        operational: OperationalMetaResponse {
+                            row_count,
+                            size_bytes,
+                            partition_keys,
+                        },
+                        freshness: None,
+                        metadata_completeness: None,
+                        custom_metadata: None,
+                        tags: None,
+                        quality_summary: None,
+                        archived_at: None,
+                        write_report: None,
+                        owner_profile: None,
+                    })
+                })
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            datasets
+        }
+        #[cfg(feature = "semantic-search")]
+        "semantic" => {
+            let limit = params
+                .get("limit")
+                .and_then(|l| l.parse::<usize>().ok())
+                .unwrap_or(semantic_search::DEFAULT_SEMANTIC_LIMIT)
+                .clamp(1, semantic_search::MAX_SEMANTIC_LIMIT);
+            semantic_search::semantic_search_datasets(
+                &state.semantic_search,
+                &backend,
+                &validated_query,
+                limit,
+                exclusion.as_ref(),
+                &request_id.0,
+            )
+            .await?
+        }
+        #[cfg(not(feature = "semantic-search"))]
+        "semantic" => {
+            return Err(bad_request(
+                "Semantic search is not enabled on this server".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+        other => {
+            return Err(bad_request(
+                format!("Invalid mode '{}'. Valid values: fts, semantic", other),
+                request_id.0.clone(),
+            ));
+        }
+    };
+
+    // Paths are hidden from anonymous public-catalog requests, so don't match old ones
+    #[allow(unused_mut)] // only cleared when api-keys feature is enabled
+    let mut grace_days = state.path_search_grace_days;
+    #[cfg(feature = "api-keys")]
+    if public_access.is_some() {
+        grace_days = 0;
+    }
+
+    {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let relocated = relocated_datasets(&conn, query, grace_days, exclusion.as_ref())
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if !relocated.is_empty() {
+            datasets.retain(|d| !relocated.iter().any(|r| r.id == d.id));
+            datasets.splice(0..0, relocated);
+        }
+        attach_uuids(&conn, &mut datasets)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if include_archived {
+            annotate_archived(&conn, &mut datasets)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        }
+        annotate_freshness(&conn, &mut datasets)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        annotate_metadata_completeness(&conn, &mut datasets)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+
+    #[cfg(feature = "api-keys")]
+    let datasets: Vec<DatasetResponse> = if public_access.is_some() {
+        datasets
+            .into_iter()
+            .map(DatasetResponse::redacted)
+            .collect()
+    } else {
+        datasets
+    };
+
+    tracing::info!(
+        search_query = %query,
+        result_count = datasets.len(),
+        "Search completed successfully"
+    );
+
+    #[cfg(feature = "metrics")]
+    metrics::record_catalog_operation("search_datasets", "success");
+
+    // Track search appearances for all returned datasets (inline: counters only)
+    #[cfg(feature = "usage-analytics")]
+    {
+        #[cfg(feature = "api-keys")]
+        let track_usage = feature_enabled(feature_flags.as_ref(), TenantFeature::UsageAnalytics);
+        #[cfg(not(feature = "api-keys"))]
+        let track_usage = true;
+
+        if track_usage {
+            let dataset_ids: Vec<i64> = datasets.iter().map(|d| d.id).collect();
+            state
+                .usage_tracker
+                .record_search_appearances(tenant_id, &dataset_ids, None);
+        }
+    }
+
+    let datasets = sparse_fields::project_all(&datasets, fields.as_ref())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.page(datasets)))
+}
+
+/// Datasets that moved away from the path in a search query within the grace period
+///
+/// The query may quote the path (`"s3://bucket/orders"`), as FTS requires.
+/// Queries that aren't a valid path match nothing.
+pub(crate) fn relocated_datasets(
+    conn: &rusqlite::Connection,
+    query: &str,
+    grace_days: u32,
+    exclusion: Option<&(String, Vec<String>)>,
+) -> metafuse_catalog_core::Result<Vec<DatasetResponse>> {
+    if grace_days == 0 {
+        return Ok(Vec::new());
+    }
+    let Ok(path) = paths::normalize_path(query.trim().trim_matches('"')) else {
+        return Ok(Vec::new());
+    };
+    let ids = path_history::relocated_from(conn, &path, grace_days)?;
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut sql = format!(
+        r#"
+        SELECT d.id, d.name, d.path, d.format, d.delta_location, d.description, d.tenant, d.domain, d.owner,
+               d.created_at, d.last_updated, d.row_count, d.size_bytes, d.partition_keys
+        FROM datasets d
+        WHERE d.id IN ({}) AND d.deleted_at IS NULL
+        "#,
+        vec!["?"; ids.len()].join(", ")
+    );
+    let mut bindings: Vec<Box<dyn rusqlite::ToSql>> = ids
+        .iter()
+        .map(|id| Box::new(*id) as Box<dyn rusqlite::ToSql>)
+        .collect();
+    if let Some((clause, values)) = exclusion {
+        sql.push_str(" AND ");
+        sql.push_str(clause);
+        bindings.extend(
+            values
+                .iter()
+                .map(|v| Box::new(v.clone()) as Box<dyn rusqlite::ToSql>),
+        );
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut datasets = stmt
+        .query_map(
+            params_from_iter(bindings.iter().map(|b| b.as_ref())),
+            |row| {
+                let row_count: Option<i64> = row.get(11)?;
+                let size_bytes: Option<i64> = row.get(12)?;
+                let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+                Ok(DatasetResponse {
+                    id: row.get(0)?,
+                    uuid: None,
+                    name: row.get(1)?,
+                    path: row.get(2)?,
+                    format: row.get(3)?,
+                    delta_location: row.get(4)?,
+                    description: row.get(5)?,
+                    tenant: row.get(6)?,
+                    domain: row.get(7)?,
+                    owner: row.get(8)?,
+                    created_at: row.get(9)?,
+                    last_updated: row.get(10)?,
+                    operational: OperationalMetaResponse {
+                        row_count,
+                        size_bytes,
+                        partition_keys,
+                    },
+                    freshness: None,
+                    metadata_completeness: None,
+                    custom_metadata: None,
+                    tags: None,
+                    quality_summary: None,
+                    archived_at: None,
+                    write_report: None,
+                    owner_profile: None,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    // Most recently moved first, as returned by the history lookup
+    datasets.sort_by_key(|d| ids.iter().position(|id| *id == d.id));
+    Ok(datasets)
+}
+
+/// Fill in the external UUID of each dataset
+pub(crate) fn attach_uuids(
+    conn: &rusqlite::Connection,
+    datasets: &mut [DatasetResponse],
+) -> metafuse_catalog_core::Result<()> {
+    let ids: Vec<i64> = datasets.iter().map(|d| d.id).collect();
+    let mut uuids = dataset_uuids::uuids_for(conn, &ids)?;
+    for dataset in datasets {
+        dataset.uuid = uuids.remove(&dataset.id);
+    }
+    Ok(())
+}
+
+/// Attach when archived datasets were archived
+pub(crate) fn annotate_archived(
+    conn: &rusqlite::Connection,
+    datasets: &mut [DatasetResponse],
+) -> rusqlite::Result<()> {
+    let ids: Vec<i64> = datasets.iter().map(|d| d.id).collect();
+    let mut archived = archive::archived_at_for(conn, &ids)?;
+    for dataset in datasets {
+        dataset.archived_at = archived.remove(&dataset.id);
+    }
+    Ok(())
+}
+
+/// A dataset's fields in schema order, with their curated metadata
+pub(crate) fn load_dataset_fields(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+) -> rusqlite::Result<Vec<FieldResponse>> {
+    let mut terms: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT tl.field_id, gt.term FROM term_links tl
+         JOIN glossary_terms gt ON gt.id = tl.term_id
+         JOIN fields f ON f.id = tl.field_id
+         WHERE f.dataset_id = ?1
+         ORDER BY gt.term COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map([dataset_id], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+    })?;
+    for row in rows {
+        let (field_id, term) = row?;
+        terms.entry(field_id).or_default().push(term);
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, name, data_type, nullable, description, business_name
+         FROM fields WHERE dataset_id = ?1 ORDER BY {}",
+        field_ordinals::ORDER_BY
+    ))?;
+    let fields = stmt
+        .query_map([dataset_id], |row| {
+            Ok(FieldResponse {
+                glossary_terms: terms.remove(&row.get::<_, i64>(0)?).unwrap_or_default(),
+                name: row.get(1)?,
+                data_type: row.get(2)?,
+                nullable: row.get::<_, i32>(3)? != 0,
+                description: row.get(4)?,
+                business_name: row.get(5)?,
+                ordinal: 0,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    // Dense positions, also for fields written before ordinals were stored
+    Ok(fields
+        .into_iter()
+        .enumerate()
+        .map(|(ordinal, field)| FieldResponse { ordinal, ..field })
+        .collect())
+}
+
+/// Attach age and staleness from `freshness_config` SLAs, and the emitter
+/// heartbeat
+pub(crate) fn annotate_freshness(
+    conn: &rusqlite::Connection,
+    datasets: &mut [DatasetResponse],
+) -> metafuse_catalog_core::Result<()> {
+    let ids: Vec<i64> = datasets.iter().map(|d| d.id).collect();
+    let slas = freshness::load_slas(conn, &ids)?;
+    let mut last_seen = emission_state::last_seen_for(conn, &ids)?;
+    let now = chrono::Utc::now();
+    for dataset in datasets {
+        dataset.freshness = freshness::Freshness::compute(
+            &dataset.last_updated,
+            slas.get(&dataset.id).copied(),
+            now,
+        )
+        .map(|f| freshness::Freshness {
+            last_seen_at: last_seen.remove(&dataset.id),
+            ..f
+        });
+    }
+    Ok(())
+}
+
+/// Attach the metadata completeness score
+pub(crate) fn annotate_metadata_completeness(
+    conn: &rusqlite::Connection,
+    datasets: &mut [DatasetResponse],
+) -> metafuse_catalog_core::Result<()> {
+    let ids: Vec<i64> = datasets.iter().map(|d| d.id).collect();
+    let mut scores = metadata_completeness::load(conn, &ids)?;
+    for dataset in datasets {
+        dataset.metadata_completeness = scores.remove(&dataset.id);
+    }
+    Ok(())
+}
+
+/// Attach the tags and quality summaries requested with `include`, one
+/// batched query each
+pub(crate) fn annotate_list_includes(
+    conn: &rusqlite::Connection,
+    datasets: &mut [DatasetResponse],
+    includes: dataset_list::ListIncludes,
+) -> metafuse_catalog_core::Result<()> {
+    let ids: Vec<i64> = datasets.iter().map(|d| d.id).collect();
+    if includes.tags {
+        let mut tags = dataset_list::load_tags(conn, &ids)?;
+        for dataset in datasets.iter_mut() {
+            dataset.tags = Some(tags.remove(&dataset.id).unwrap_or_default());
+        }
+    }
+    if includes.quality_summary {
+        let mut summaries = dataset_list::load_quality_summaries(conn, &ids)?;
+        for dataset in datasets.iter_mut() {
+            dataset.quality_summary = Some(summaries.remove(&dataset.id).unwrap_or_default());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_partition_keys(raw: Option<String>) -> Vec<String> {
+    raw.and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_read_budget() {
+        let timeouts = timeouts::TimeoutConfig::default();
+        let profiles = response_profiles::ResponseProfiles::default();
+        let budget =
+            |params: DatasetQueryParams| dataset_read_budget(&timeouts, &profiles, &params);
+        let include = |include: &str| DatasetQueryParams {
+            include: Some(include.to_string()),
+            ..Default::default()
+        };
+        let slow = Some(Duration::from_millis(timeouts::DEFAULT_SLOW_MS));
+        let read = Some(Duration::from_millis(timeouts::DEFAULT_READ_MS));
+
+        assert_eq!(budget(DatasetQueryParams::default()), read);
+        assert_eq!(budget(include("lineage")), read);
+        assert_eq!(budget(include("quality")), slow);
+        assert_eq!(
+            budget(DatasetQueryParams {
+                refresh: true,
+                ..include("quality")
+            }),
+            slow
+        );
+        assert_eq!(budget(include("lineage,delta")), slow);
+        assert_eq!(
+            budget(DatasetQueryParams {
+                resolve_schema: true,
+                ..Default::default()
+            }),
+            slow
+        );
+        // Built-in profile including quality
+        assert_eq!(
+            budget(DatasetQueryParams {
+                profile: Some("ui".to_string()),
+                ..Default::default()
+            }),
+            slow
+        );
+    }
+}
@@ -0,0 +1,499 @@
+//! Delta-Delegated Metadata
+//!
+//! Schema, schema diffs, statistics and version history read from a
+//! dataset's Delta table rather than from the catalog.
+
+use crate::dataset_acl;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, bad_request, dataset_not_found, internal_error, AppState, DatasetPath,
+    ErrorResponse, RequestId,
+};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Schema response from Delta table
+#[derive(Debug, Serialize)]
+pub(crate) struct SchemaResponse {
+    dataset_name: String,
+    delta_version: i64,
+    schema: serde_json::Value,
+    partition_columns: Vec<String>,
+}
+
+/// Schema diff response from Delta table
+#[derive(Debug, Serialize)]
+pub(crate) struct SchemaDiffResponse {
+    dataset_name: String,
+    from_version: i64,
+    to_version: i64,
+    added_columns: Vec<SchemaDiffField>,
+    removed_columns: Vec<SchemaDiffField>,
+    modified_columns: Vec<SchemaDiffFieldChange>,
+}
+
+/// Field info for schema diff response
+#[derive(Debug, Serialize)]
+pub(crate) struct SchemaDiffField {
+    name: String,
+    data_type: String,
+    nullable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Position in the version the column appears in
+    ordinal: usize,
+}
+
+/// Field change info for schema diff response
+#[derive(Debug, Serialize)]
+pub(crate) struct SchemaDiffFieldChange {
+    name: String,
+    old_type: String,
+    new_type: String,
+    old_nullable: bool,
+    new_nullable: bool,
+    old_ordinal: usize,
+    new_ordinal: usize,
+}
+
+/// Stats response from Delta table
+#[derive(Debug, Serialize)]
+pub(crate) struct StatsResponse {
+    dataset_name: String,
+    delta_version: i64,
+    row_count: i64,
+    size_bytes: i64,
+    num_files: i64,
+    last_modified: Option<String>,
+}
+
+/// History response from a Delta or Iceberg table
+#[derive(Debug, Serialize)]
+pub(crate) struct HistoryResponse {
+    dataset_name: String,
+    versions: Vec<VersionInfo>,
+}
+
+/// Version info for history response
+#[derive(Debug, Serialize)]
+pub(crate) struct VersionInfo {
+    /// Delta version, or Iceberg sequence number
+    version: i64,
+    timestamp: String,
+    operation: String,
+    parameters: HashMap<String, String>,
+    /// Iceberg snapshot ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_id: Option<i64>,
+}
+
+/// Query params for schema endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct SchemaQueryParams {
+    version: Option<i64>,
+}
+
+/// Query params for schema diff endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct SchemaDiffQueryParams {
+    from: i64,
+    to: i64,
+}
+
+/// Query params for history endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct HistoryQueryParams {
+    limit: Option<usize>,
+}
+
+/// Get schema from Delta table
+pub(crate) async fn get_dataset_schema(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<SchemaQueryParams>,
+) -> Result<Json<SchemaResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, version = ?params.version, "Getting dataset schema from Delta");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Get delta_location from dataset
+    let delta_location: Option<String> = conn
+        .query_row(
+            "SELECT delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    let delta_location = delta_location.ok_or_else(|| {
+        bad_request(
+            format!("Dataset '{}' does not have a delta_location", name),
+            request_id.0.clone(),
+        )
+    })?;
+
+    // Get schema from Delta
+    let schema = state
+        .delta_reader
+        .get_schema(&delta_location, params.version)
+        .await
+        .map_err(|e| {
+            internal_error(
+                format!("Failed to read Delta schema: {}", e),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // Get current version
+    let metadata = state
+        .delta_reader
+        .get_metadata_cached(&delta_location)
+        .await
+        .map_err(|e| {
+            internal_error(
+                format!("Failed to read Delta metadata: {}", e),
+                request_id.0.clone(),
+            )
+        })?;
+
+    let response = SchemaResponse {
+        dataset_name: name,
+        delta_version: params.version.unwrap_or(metadata.version),
+        schema: serde_json::to_value(&schema).unwrap_or(serde_json::Value::Null),
+        partition_columns: schema.partition_columns,
+    };
+
+    Ok(Json(response))
+}
+
+/// Get schema diff between two Delta versions
+pub(crate) async fn get_dataset_schema_diff(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<SchemaDiffQueryParams>,
+) -> Result<Json<SchemaDiffResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        dataset = %name,
+        from = params.from,
+        to = params.to,
+        "Getting schema diff from Delta"
+    );
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    // Validate version range
+    if params.from >= params.to {
+        return Err(bad_request(
+            "Parameter 'from' must be less than 'to'".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Limit version range to prevent expensive operations
+    let max_version_range: i64 = std::env::var("METAFUSE_SCHEMA_DIFF_MAX_VERSIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100);
+
+    if params.to - params.from > max_version_range {
+        return Err(bad_request(
+            format!(
+                "Version range exceeds maximum of {} versions",
+                max_version_range
+            ),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Get delta_location from dataset
+    let delta_location: Option<String> = conn
+        .query_row(
+            "SELECT delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    let delta_location = delta_location.ok_or_else(|| {
+        bad_request(
+            format!("Dataset '{}' does not have a delta_location", name),
+            request_id.0.clone(),
+        )
+    })?;
+
+    // Get schema diff from Delta
+    let diff = state
+        .delta_reader
+        .diff_schemas(&delta_location, params.from, params.to)
+        .await
+        .map_err(|e| {
+            internal_error(
+                format!("Failed to compute schema diff: {}", e),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // Convert to response types
+    let response = SchemaDiffResponse {
+        dataset_name: name,
+        from_version: diff.from_version,
+        to_version: diff.to_version,
+        added_columns: diff
+            .added_columns
+            .into_iter()
+            .map(|f| SchemaDiffField {
+                name: f.name,
+                data_type: f.data_type,
+                nullable: f.nullable,
+                description: f.description,
+                ordinal: f.ordinal,
+            })
+            .collect(),
+        removed_columns: diff
+            .removed_columns
+            .into_iter()
+            .map(|f| SchemaDiffField {
+                name: f.name,
+                data_type: f.data_type,
+                nullable: f.nullable,
+                description: f.description,
+                ordinal: f.ordinal,
+            })
+            .collect(),
+        modified_columns: diff
+            .modified_columns
+            .into_iter()
+            .map(|c| SchemaDiffFieldChange {
+                name: c.name,
+                old_type: c.old_type,
+                new_type: c.new_type,
+                old_nullable: c.old_nullable,
+                new_nullable: c.new_nullable,
+                old_ordinal: c.old_ordinal,
+                new_ordinal: c.new_ordinal,
+            })
+            .collect(),
+    };
+
+    Ok(Json(response))
+}
+
+/// Get stats from Delta table
+pub(crate) async fn get_dataset_stats(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, "Getting dataset stats from Delta");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Get delta_location from dataset
+    let delta_location: Option<String> = conn
+        .query_row(
+            "SELECT delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    let delta_location = delta_location.ok_or_else(|| {
+        bad_request(
+            format!("Dataset '{}' does not have a delta_location", name),
+            request_id.0.clone(),
+        )
+    })?;
+
+    // Get metadata from Delta (with caching)
+    let metadata = state
+        .delta_reader
+        .get_metadata_cached(&delta_location)
+        .await
+        .map_err(|e| {
+            internal_error(
+                format!("Failed to read Delta stats: {}", e),
+                request_id.0.clone(),
+            )
+        })?;
+
+    let response = StatsResponse {
+        dataset_name: name,
+        delta_version: metadata.version,
+        row_count: metadata.row_count,
+        size_bytes: metadata.size_bytes,
+        num_files: metadata.num_files,
+        last_modified: Some(metadata.last_modified.to_rfc3339()),
+    };
+
+    Ok(Json(response))
+}
+
+/// Get history from a Delta table, or snapshots from an Iceberg table
+pub(crate) async fn get_dataset_history(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<HistoryQueryParams>,
+) -> Result<Json<HistoryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, limit = ?params.limit, "Getting dataset history");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let (format, path, delta_location): (String, String, Option<String>) = conn
+        .query_row(
+            "SELECT format, path, delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    let limit = params.limit.unwrap_or(10);
+
+    // Iceberg tables are read at their path unless a location is configured
+    let versions: Vec<VersionInfo> = if format == "iceberg" {
+        let location = delta_location.unwrap_or(path);
+        let snapshots = metafuse_catalog_iceberg::IcebergReader::new()
+            .get_history(&location, limit)
+            .await
+            .map_err(|e| {
+                internal_error(
+                    format!("Failed to read Iceberg history: {}", e),
+                    request_id.0.clone(),
+                )
+            })?;
+        snapshots
+            .into_iter()
+            .map(|s| VersionInfo {
+                version: s.sequence_number,
+                timestamp: s.timestamp.to_rfc3339(),
+                operation: s.operation.unwrap_or_default(),
+                parameters: s.summary,
+                snapshot_id: Some(s.snapshot_id),
+            })
+            .collect()
+    } else {
+        let delta_location = delta_location.ok_or_else(|| {
+            bad_request(
+                format!("Dataset '{}' does not have a delta_location", name),
+                request_id.0.clone(),
+            )
+        })?;
+
+        // Get history from Delta
+        let history = state
+            .delta_reader
+            .get_history(&delta_location, limit)
+            .await
+            .map_err(|e| {
+                internal_error(
+                    format!("Failed to read Delta history: {}", e),
+                    request_id.0.clone(),
+                )
+            })?;
+
+        history
+            .into_iter()
+            .map(|v| VersionInfo {
+                version: v.version,
+                timestamp: v.timestamp.to_rfc3339(),
+                operation: v.operation,
+                parameters: v.parameters,
+                snapshot_id: None,
+            })
+            .collect()
+    };
+
+    let response = HistoryResponse {
+        dataset_name: name,
+        versions,
+    };
+
+    Ok(Json(response))
+}
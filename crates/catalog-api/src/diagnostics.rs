@@ -0,0 +1,483 @@
+//! Service diagnostics
+//!
+//! `GET /api/v1/admin/diagnostics` reports what an operator checks first when
+//! the catalog feels slow, in one call:
+//!
+//! - Database and full-text index size, and the schema version
+//! - The slowest recent requests, by route
+//! - Background task lag: queued audit events and time since the last usage flush
+//! - Hit rates of the Delta metadata and tenant backend caches
+//!
+//! Database figures describe one catalog; everything else is server-wide,
+//! so with `api-keys` the endpoint is part of the admin API
+//! (`METAFUSE_ADMIN_KEY`) and `tenant_id` picks the catalog. Tenant keys,
+//! even with the Admin role, cannot call it.
+//!
+//! ## Configuration
+//!
+//! - `METAFUSE_DIAGNOSTICS_SLOW_REQUESTS`: Slowest requests kept (default: 10)
+//! - `METAFUSE_DIAGNOSTICS_WINDOW_SECS`: How long a request counts as recent (default: 900)
+
+#[cfg(feature = "api-keys")]
+use crate::error_codes::ErrorCode;
+use crate::server::{internal_error, AppState, ErrorResponse, RequestId};
+#[cfg(feature = "api-keys")]
+use crate::webhooks;
+#[cfg(feature = "api-keys")]
+use axum::extract::Query;
+use axum::{
+    extract::{Extension, MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use metafuse_catalog_core::migrations::{self, MigrationVersion};
+use rusqlite::Connection;
+#[cfg(feature = "api-keys")]
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default number of slow requests kept
+const DEFAULT_SLOW_REQUESTS: usize = 10;
+
+/// Default window for recent requests (15 minutes)
+const DEFAULT_WINDOW_SECS: u64 = 900;
+
+/// Configuration for slow request tracking
+#[derive(Debug, Clone)]
+pub struct DiagnosticsConfig {
+    /// Slowest requests kept
+    pub slow_requests: usize,
+    /// How long a request counts as recent
+    pub window_secs: u64,
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            slow_requests: DEFAULT_SLOW_REQUESTS,
+            window_secs: DEFAULT_WINDOW_SECS,
+        }
+    }
+}
+
+impl DiagnosticsConfig {
+    /// Load configuration from environment variables
+    pub fn from_env() -> Self {
+        let slow_requests = std::env::var("METAFUSE_DIAGNOSTICS_SLOW_REQUESTS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SLOW_REQUESTS);
+        let window_secs = std::env::var("METAFUSE_DIAGNOSTICS_WINDOW_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&secs| secs > 0)
+            .unwrap_or(DEFAULT_WINDOW_SECS);
+        Self {
+            slow_requests,
+            window_secs,
+        }
+    }
+}
+
+/// A request among the slowest recent ones
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowRequest {
+    pub method: String,
+    /// Route template, e.g. `/api/v1/datasets/{name}`
+    pub route: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Keeps the slowest requests completed within the window
+pub struct SlowRequestLog {
+    config: DiagnosticsConfig,
+    entries: Mutex<Vec<(Instant, SlowRequest)>>,
+}
+
+impl SlowRequestLog {
+    pub fn new(config: DiagnosticsConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn window_secs(&self) -> u64 {
+        self.config.window_secs
+    }
+
+    /// Record a completed request, keeping it if it is among the slowest
+    pub fn record(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        self.record_at(Instant::now(), method, route, status, duration);
+    }
+
+    fn record_at(&self, now: Instant, method: &str, route: &str, status: u16, duration: Duration) {
+        if self.config.slow_requests == 0 {
+            return;
+        }
+        let duration_ms = duration.as_millis() as u64;
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.prune(&mut entries, now);
+        if entries.len() >= self.config.slow_requests
+            && entries
+                .last()
+                .is_some_and(|(_, fastest)| fastest.duration_ms >= duration_ms)
+        {
+            return;
+        }
+        let request = SlowRequest {
+            method: method.to_string(),
+            route: route.to_string(),
+            status,
+            duration_ms,
+            completed_at: Utc::now(),
+        };
+        // Slowest first
+        let at = entries.partition_point(|(_, r)| r.duration_ms >= duration_ms);
+        entries.insert(at, (now, request));
+        entries.truncate(self.config.slow_requests);
+    }
+
+    /// Slowest requests within the window, slowest first
+    pub fn slowest(&self) -> Vec<SlowRequest> {
+        self.slowest_at(Instant::now())
+    }
+
+    fn slowest_at(&self, now: Instant) -> Vec<SlowRequest> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.prune(&mut entries, now);
+        entries.iter().map(|(_, r)| r.clone()).collect()
+    }
+
+    fn prune(&self, entries: &mut Vec<(Instant, SlowRequest)>, now: Instant) {
+        let window = Duration::from_secs(self.config.window_secs);
+        entries.retain(|(at, _)| now.saturating_duration_since(*at) < window);
+    }
+}
+
+/// Middleware that times requests for the slow request log
+pub async fn timing_middleware(
+    Extension(log): Extension<Arc<SlowRequestLog>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let response = next.run(req).await;
+    log.record(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
+
+/// Size and schema version of a catalog database
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseDiagnostics {
+    pub size_bytes: u64,
+    /// Unused pages that `VACUUM` would reclaim
+    pub free_bytes: u64,
+    /// Size of the full-text search index, when SQLite can report it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fts_index_bytes: Option<u64>,
+    pub schema_version: String,
+    pub latest_schema_version: String,
+    pub pending_migrations: usize,
+}
+
+/// Format a migration version number (e.g., 1038000 -> v1.38.0)
+fn format_version(version: MigrationVersion) -> String {
+    format!(
+        "v{}.{}.{}",
+        version / 1_000_000,
+        (version / 1_000) % 1_000,
+        version % 1_000
+    )
+}
+
+/// Read database diagnostics without modifying the catalog
+pub fn database(conn: &Connection) -> rusqlite::Result<DatabaseDiagnostics> {
+    let pragma = |name: &str| -> rusqlite::Result<u64> {
+        conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0))
+            .map(|v| v.max(0) as u64)
+    };
+    let page_size = pragma("page_size")?;
+    let size_bytes = pragma("page_count")? * page_size;
+    let free_bytes = pragma("freelist_count")? * page_size;
+
+    // dbstat is compiled into the bundled SQLite; the index's shadow tables
+    // share its name as a prefix
+    let fts_index_bytes = conn
+        .query_row(
+            "SELECT COALESCE(SUM(pgsize), 0) FROM dbstat WHERE name LIKE 'dataset\\_search%' ESCAPE '\\'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .ok()
+        .map(|v| v.max(0) as u64);
+
+    // Catalogs that never ran migrations have no schema_migrations table
+    let applied: Vec<MigrationVersion> = conn
+        .prepare("SELECT version FROM schema_migrations")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()
+        })
+        .unwrap_or_default();
+    let all = migrations::all_migrations();
+    let latest = all.last().map(|m| m.version).unwrap_or(0);
+    let pending_migrations = all.iter().filter(|m| !applied.contains(&m.version)).count();
+
+    Ok(DatabaseDiagnostics {
+        size_bytes,
+        free_bytes,
+        fts_index_bytes,
+        schema_version: format_version(applied.iter().copied().max().unwrap_or(0)),
+        latest_schema_version: format_version(latest),
+        pending_migrations,
+    })
+}
+
+/// Hit rate of an in-memory cache
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheDiagnostics {
+    pub entries: usize,
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Share of lookups served from the cache; absent before the first lookup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_rate: Option<f64>,
+}
+
+impl CacheDiagnostics {
+    pub fn new(entries: usize, capacity: usize, hits: u64, misses: u64) -> Self {
+        let lookups = hits + misses;
+        Self {
+            entries,
+            capacity,
+            hits,
+            misses,
+            hit_rate: (lookups > 0).then(|| hits as f64 / lookups as f64),
+        }
+    }
+}
+
+/// Audit events waiting for the writer task
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditBacklog {
+    pub queued: usize,
+    /// Events are dropped once the buffer is full
+    pub buffer_size: usize,
+}
+
+/// Delay of the usage analytics flush
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageFlushLag {
+    pub interval_secs: u64,
+    pub secs_since_flush: u64,
+    /// Time past the interval without a completed flush
+    pub lag_secs: u64,
+    /// Datasets with counters held in memory
+    pub tracked_datasets: usize,
+}
+
+impl UsageFlushLag {
+    pub fn new(interval: Duration, since_flush: Duration, tracked_datasets: usize) -> Self {
+        Self {
+            interval_secs: interval.as_secs(),
+            secs_since_flush: since_flush.as_secs(),
+            lag_secs: since_flush.saturating_sub(interval).as_secs(),
+            tracked_datasets,
+        }
+    }
+}
+
+/// Lag of background tasks; a task is absent when its feature is disabled
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackgroundTasks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit: Option<AuditBacklog>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_flush: Option<UsageFlushLag>,
+}
+
+/// Response for `GET /api/v1/admin/diagnostics`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsResponse {
+    pub database: DatabaseDiagnostics,
+    /// Slowest requests completed within `window_secs`, slowest first
+    pub slowest_requests: Vec<SlowRequest>,
+    pub window_secs: u64,
+    pub background: BackgroundTasks,
+    /// Caches by name
+    pub caches: BTreeMap<&'static str, CacheDiagnostics>,
+}
+
+/// Query parameters for the diagnostics endpoint
+#[cfg(feature = "api-keys")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct DiagnosticsQuery {
+    /// Tenant whose catalog database is described (default: the default catalog)
+    tenant_id: Option<String>,
+}
+
+/// Service health in one call: database, slow requests, background lag, caches
+///
+/// Most figures are server-wide, so with `api-keys` this is an admin API
+/// endpoint (`METAFUSE_ADMIN_KEY`) rather than a tenant one.
+pub(crate) async fn diagnostics(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    #[cfg(feature = "api-keys")] Query(params): Query<DiagnosticsQuery>,
+) -> Result<Json<DiagnosticsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    let backend = match params.tenant_id {
+        Some(tenant_id) if tenant_id != webhooks::DEFAULT_TENANT => {
+            let tenant_not_found = || {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        error: format!("Tenant '{}' not found", tenant_id),
+                        code: ErrorCode::TenantNotFound,
+                        request_id: request_id.0.clone(),
+                    }),
+                )
+            };
+            let (Some(control_plane), Some(factory)) = (
+                state.multi_tenant.control_plane(),
+                state.multi_tenant.factory(),
+            ) else {
+                return Err(tenant_not_found());
+            };
+            control_plane
+                .get_tenant(&tenant_id)
+                .await
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+                .ok_or_else(tenant_not_found)?;
+            factory
+                .get_backend_by_id(&tenant_id)
+                .await
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        }
+        _ => Arc::clone(&state.backend),
+    };
+    #[cfg(not(feature = "api-keys"))]
+    let backend = Arc::clone(&state.backend);
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let req_id = request_id.0.clone();
+    let database = tokio::task::spawn_blocking(move || database(&conn))
+        .await
+        .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+        .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    #[allow(unused_mut)]
+    let mut background = BackgroundTasks::default();
+    #[cfg(feature = "audit")]
+    {
+        background.audit = Some(AuditBacklog {
+            queued: state.audit_logger.backlog(),
+            buffer_size: state.audit_logger.buffer_size(),
+        });
+    }
+    #[cfg(feature = "usage-analytics")]
+    {
+        background.usage_flush = Some(UsageFlushLag::new(
+            state.usage_tracker.flush_interval(),
+            state.usage_tracker.since_last_flush(),
+            state.usage_tracker.tracked_dataset_count(),
+        ));
+    }
+
+    let mut caches = BTreeMap::new();
+    let delta = state.delta_reader.cache_stats().await;
+    caches.insert(
+        "delta_metadata",
+        CacheDiagnostics::new(delta.entries, delta.capacity, delta.hits, delta.misses),
+    );
+    if let Some(factory) = state.multi_tenant.factory() {
+        let tenants = factory.stats();
+        caches.insert(
+            "tenant_backends",
+            CacheDiagnostics::new(tenants.size, tenants.capacity, tenants.hits, tenants.misses),
+        );
+    }
+
+    Ok(Json(DiagnosticsResponse {
+        database,
+        slowest_requests: state.slow_requests.slowest(),
+        window_secs: state.slow_requests.window_secs(),
+        background,
+        caches,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_request_log() {
+        let log = SlowRequestLog::new(DiagnosticsConfig {
+            slow_requests: 2,
+            window_secs: 60,
+        });
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        log.record_at(start, "GET", "/a", 200, ms(50));
+        log.record_at(start, "GET", "/b", 200, ms(300));
+        log.record_at(start, "GET", "/c", 200, ms(10));
+        log.record_at(start, "POST", "/d", 500, ms(120));
+
+        let routes = |requests: Vec<SlowRequest>| -> Vec<String> {
+            requests.into_iter().map(|r| r.route).collect()
+        };
+        assert_eq!(routes(log.slowest_at(start)), vec!["/b", "/d"]);
+
+        // Old requests leave the window, so faster ones are kept again
+        let later = start + Duration::from_secs(61);
+        log.record_at(later, "GET", "/e", 200, ms(5));
+        assert_eq!(routes(log.slowest_at(later)), vec!["/e"]);
+    }
+
+    #[test]
+    fn test_database_diagnostics() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        let before = database(&conn).unwrap();
+        assert!(before.size_bytes > 0);
+        assert!(before.fts_index_bytes.unwrap() > 0);
+        assert_eq!(before.schema_version, "v0.0.0");
+        assert_eq!(
+            before.pending_migrations,
+            migrations::all_migrations().len()
+        );
+
+        migrations::run_migrations(&conn).unwrap();
+        let after = database(&conn).unwrap();
+        assert_eq!(after.schema_version, after.latest_schema_version);
+        assert_eq!(after.pending_migrations, 0);
+    }
+
+    #[test]
+    fn test_cache_hit_rate() {
+        assert_eq!(CacheDiagnostics::new(0, 10, 0, 0).hit_rate, None);
+        assert_eq!(CacheDiagnostics::new(2, 10, 3, 1).hit_rate, Some(0.75));
+        let lag = UsageFlushLag::new(Duration::from_secs(60), Duration::from_secs(90), 4);
+        assert_eq!((lag.secs_since_flush, lag.lag_secs), (90, 30));
+    }
+}
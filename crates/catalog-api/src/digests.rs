@@ -0,0 +1,1051 @@
+//! Change Digests
+//!
+//! Compiles what changed in the catalog over a period, per domain or owner,
+//! so stewards get one summary instead of a stream of alerts:
+//!
+//! - `new_datasets`: datasets created in the period
+//! - `schema_changes`: columns added, removed, or retyped since the last
+//!   scheduled digest (snapshots in `digest_snapshots`, migration v1.34.0)
+//! - `quality_regressions`: the latest overall quality score in the period
+//!   dropped by at least `quality_drop` from the latest one before it
+//! - `pii_findings`: columns classified as `pii` in the period
+//!
+//! Trashed datasets and lineage placeholders are left out. Datasets without
+//! a domain (or owner) are grouped together under a `null` group.
+//!
+//! # Delivery
+//!
+//! `GET /api/v1/digests` returns digests as JSON or markdown. With the
+//! `alerting` feature and `METAFUSE_DIGEST_WEBHOOK_URL` set, [`digest_task`]
+//! posts each non-empty digest of the past day to the webhook once a day, then
+//! snapshots every schema for the next run.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_DIGEST_WEBHOOK_URL`: webhook receiving the daily digests
+//! - `METAFUSE_DIGEST_GROUP_BY`: `domain` or `owner` (default: domain)
+//! - `METAFUSE_DIGEST_HOUR_UTC`: hour of day the digest is sent, 0-23 (default: 0)
+//! - `METAFUSE_DIGEST_QUALITY_DROP`: score drop reported as a regression,
+//!   0.0-1.0 (default: 0.1)
+
+use crate::dataset_acl;
+use crate::freshness::parse_timestamp;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use crate::subscriptions::{self, WatchChange};
+use axum::extract::{Extension, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use metafuse_catalog_core::{placeholders, validation, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+/// Default period covered by a digest, in hours
+pub const DEFAULT_DIGEST_HOURS: i64 = 24;
+
+/// Longest period a digest can cover, in hours (30 days)
+pub const MAX_DIGEST_HOURS: i64 = 720;
+
+/// Default hour of day (UTC) the scheduled digest is sent
+pub const DEFAULT_DIGEST_HOUR_UTC: u32 = 0;
+
+/// How datasets are grouped into digests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestGroupBy {
+    #[default]
+    Domain,
+    Owner,
+}
+
+impl DigestGroupBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestGroupBy::Domain => "domain",
+            DigestGroupBy::Owner => "owner",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "domain" => Some(DigestGroupBy::Domain),
+            "owner" => Some(DigestGroupBy::Owner),
+            _ => None,
+        }
+    }
+}
+
+/// Where to reach the owner of a digest's group, from the owner registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DigestContact {
+    pub owner_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_channel: Option<String>,
+}
+
+/// A dataset created in the period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NewDataset {
+    pub dataset_name: String,
+    pub domain: Option<String>,
+    pub owner: Option<String>,
+    pub created_at: String,
+}
+
+/// Columns changed since the last scheduled digest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaChange {
+    pub dataset_name: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+/// An overall quality score that dropped in the period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityRegression {
+    pub dataset_name: String,
+    pub previous: f64,
+    pub current: f64,
+    pub computed_at: String,
+}
+
+/// A column classified as PII in the period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PiiFinding {
+    pub dataset_name: String,
+    pub field_name: String,
+    pub category: Option<String>,
+    /// `auto`, `manual`, or `rule`
+    pub source: String,
+    pub confidence: Option<f64>,
+    pub classified_at: String,
+}
+
+/// Changes to one domain's or owner's datasets over a period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Digest {
+    pub group_by: DigestGroupBy,
+    /// Domain or owner; `None` for datasets without one
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<DigestContact>,
+    pub since: String,
+    pub until: String,
+    pub new_datasets: Vec<NewDataset>,
+    pub schema_changes: Vec<SchemaChange>,
+    pub quality_regressions: Vec<QualityRegression>,
+    pub pii_findings: Vec<PiiFinding>,
+}
+
+impl Digest {
+    fn new(group_by: DigestGroupBy, group: Option<String>, query: &DigestQuery) -> Self {
+        Self {
+            group_by,
+            group,
+            contact: None,
+            since: query.since.to_rfc3339(),
+            until: query.until.to_rfc3339(),
+            new_datasets: Vec::new(),
+            schema_changes: Vec::new(),
+            quality_regressions: Vec::new(),
+            pii_findings: Vec::new(),
+        }
+    }
+
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.new_datasets.is_empty()
+            && self.schema_changes.is_empty()
+            && self.quality_regressions.is_empty()
+            && self.pii_findings.is_empty()
+    }
+
+    /// Render as markdown for chat or email delivery
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let group = match &self.group {
+            Some(group) => format!("{} `{}`", self.group_by.as_str(), group),
+            None => format!("no {}", self.group_by.as_str()),
+        };
+        let _ = writeln!(out, "# MetaFuse digest: {}", group);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "_{} to {}_", self.since, self.until);
+        if self.is_empty() {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "No changes.");
+            return out;
+        }
+
+        section(&mut out, "New datasets", &self.new_datasets, |d| {
+            match &d.owner {
+                Some(owner) => format!("`{}` (owner: {})", d.dataset_name, owner),
+                None => format!("`{}`", d.dataset_name),
+            }
+        });
+        section(&mut out, "Schema changes", &self.schema_changes, |c| {
+            let mut parts = Vec::new();
+            for (label, columns) in [
+                ("added", &c.added),
+                ("removed", &c.removed),
+                ("retyped", &c.changed),
+            ] {
+                if !columns.is_empty() {
+                    let columns: Vec<String> = columns.iter().map(|c| format!("`{}`", c)).collect();
+                    parts.push(format!("{} {}", label, columns.join(", ")));
+                }
+            }
+            format!("`{}`: {}", c.dataset_name, parts.join("; "))
+        });
+        section(
+            &mut out,
+            "Quality regressions",
+            &self.quality_regressions,
+            |r| format!("`{}`: {:.2} → {:.2}", r.dataset_name, r.previous, r.current),
+        );
+        section(
+            &mut out,
+            "New PII findings",
+            &self.pii_findings,
+            |f| match &f.category {
+                Some(category) => format!("`{}.{}` ({})", f.dataset_name, f.field_name, category),
+                None => format!("`{}.{}`", f.dataset_name, f.field_name),
+            },
+        );
+        out
+    }
+}
+
+fn section<T>(out: &mut String, title: &str, items: &[T], line: impl Fn(&T) -> String) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## {} ({})", title, items.len());
+    let _ = writeln!(out);
+    for item in items {
+        let _ = writeln!(out, "- {}", line(item));
+    }
+}
+
+/// What to compile into digests
+#[derive(Debug, Clone)]
+pub struct DigestQuery {
+    pub group_by: DigestGroupBy,
+    /// Only this domain or owner
+    pub group: Option<String>,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Score drop reported as a regression
+    pub quality_drop: f64,
+    /// Extra SQL condition on `d` with its bindings, e.g. ACL visibility
+    pub visibility: Option<(String, Vec<String>)>,
+}
+
+struct DatasetRow {
+    name: String,
+    domain: Option<String>,
+    owner: Option<String>,
+    created_at: String,
+}
+
+/// Latest quality score before the period, and latest in it
+#[derive(Default)]
+struct ScoreWindow {
+    before: Option<f64>,
+    during: Option<(f64, String)>,
+}
+
+impl DatasetRow {
+    fn group(&self, group_by: DigestGroupBy) -> Option<&String> {
+        match group_by {
+            DigestGroupBy::Domain => self.domain.as_ref(),
+            DigestGroupBy::Owner => self.owner.as_ref(),
+        }
+    }
+}
+
+/// The digest of a dataset's group, created on first use
+fn digest_for<'a>(
+    digests: &'a mut BTreeMap<Option<String>, Digest>,
+    dataset: &DatasetRow,
+    query: &DigestQuery,
+) -> &'a mut Digest {
+    let group = dataset.group(query.group_by).cloned();
+    digests
+        .entry(group.clone())
+        .or_insert_with(|| Digest::new(query.group_by, group, query))
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+fn in_period(timestamp: &str, query: &DigestQuery) -> bool {
+    parse_timestamp(timestamp).is_some_and(|ts| ts >= query.since && ts < query.until)
+}
+
+/// Columns of every dataset, by dataset id
+fn schemas(conn: &Connection) -> rusqlite::Result<HashMap<i64, BTreeMap<String, String>>> {
+    let mut schemas: HashMap<i64, BTreeMap<String, String>> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT dataset_id, name, data_type FROM fields")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (dataset_id, name, data_type) = row?;
+        schemas
+            .entry(dataset_id)
+            .or_default()
+            .insert(name, data_type);
+    }
+    Ok(schemas)
+}
+
+/// Compile digests for the query's period.
+///
+/// Returns one digest per group with changes, ordered by group. When the
+/// query names a group, its digest is returned even if nothing changed.
+pub fn generate(conn: &Connection, query: &DigestQuery) -> Result<Vec<Digest>> {
+    let mut sql = String::from(
+        "SELECT d.id, d.name, d.domain, d.owner, d.created_at FROM datasets d
+         WHERE d.deleted_at IS NULL",
+    );
+    let mut bindings: Vec<String> = Vec::new();
+    if placeholders::has_status_column(conn)? {
+        sql.push_str(&format!(
+            " AND d.status != '{}'",
+            placeholders::STATUS_PENDING
+        ));
+    }
+    if let Some(group) = &query.group {
+        sql.push_str(match query.group_by {
+            DigestGroupBy::Domain => " AND d.domain = ?",
+            DigestGroupBy::Owner => " AND d.owner = ?",
+        });
+        bindings.push(group.clone());
+    }
+    if let Some((clause, clause_bindings)) = &query.visibility {
+        sql.push_str(" AND ");
+        sql.push_str(clause);
+        bindings.extend(clause_bindings.iter().cloned());
+    }
+    sql.push_str(" ORDER BY d.name");
+
+    let datasets: Vec<(i64, DatasetRow)> = conn
+        .prepare(&sql)?
+        .query_map(rusqlite::params_from_iter(bindings.iter()), |row| {
+            Ok((
+                row.get(0)?,
+                DatasetRow {
+                    name: row.get(1)?,
+                    domain: row.get(2)?,
+                    owner: row.get(3)?,
+                    created_at: row.get(4)?,
+                },
+            ))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    let by_id: HashMap<i64, &DatasetRow> = datasets.iter().map(|(id, d)| (*id, d)).collect();
+
+    let mut digests: BTreeMap<Option<String>, Digest> = BTreeMap::new();
+    if query.group.is_some() {
+        digests.insert(
+            query.group.clone(),
+            Digest::new(query.group_by, query.group.clone(), query),
+        );
+    }
+
+    for (_, dataset) in &datasets {
+        if in_period(&dataset.created_at, query) {
+            digest_for(&mut digests, dataset, query)
+                .new_datasets
+                .push(NewDataset {
+                    dataset_name: dataset.name.clone(),
+                    domain: dataset.domain.clone(),
+                    owner: dataset.owner.clone(),
+                    created_at: dataset.created_at.clone(),
+                });
+        }
+    }
+
+    if table_exists(conn, "digest_snapshots")? {
+        let mut current = schemas(conn)?;
+        let mut stmt = conn.prepare("SELECT dataset_id, schema FROM digest_snapshots")?;
+        let snapshots = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut changes = Vec::new();
+        for (dataset_id, snapshot) in snapshots {
+            let Some(dataset) = by_id.get(&dataset_id) else {
+                continue;
+            };
+            let previous: BTreeMap<String, String> =
+                serde_json::from_str(&snapshot).unwrap_or_default();
+            let schema = current.remove(&dataset_id).unwrap_or_default();
+            if let Some(WatchChange::Schema {
+                added,
+                removed,
+                changed,
+            }) = subscriptions::schema_change(&previous, &schema)
+            {
+                changes.push((
+                    *dataset,
+                    SchemaChange {
+                        dataset_name: dataset.name.clone(),
+                        added,
+                        removed,
+                        changed,
+                    },
+                ));
+            }
+        }
+        changes.sort_by(|a, b| a.1.dataset_name.cmp(&b.1.dataset_name));
+        for (dataset, change) in changes {
+            digest_for(&mut digests, dataset, query)
+                .schema_changes
+                .push(change);
+        }
+    }
+
+    let mut scores: BTreeMap<i64, ScoreWindow> = BTreeMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT dataset_id, overall_score, computed_at FROM quality_metrics
+         WHERE overall_score IS NOT NULL
+         ORDER BY computed_at, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, f64>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (dataset_id, score, computed_at) = row?;
+        if !by_id.contains_key(&dataset_id) {
+            continue;
+        }
+        let Some(ts) = parse_timestamp(&computed_at) else {
+            continue;
+        };
+        let entry = scores.entry(dataset_id).or_default();
+        if ts < query.since {
+            entry.before = Some(score);
+        } else if ts < query.until {
+            entry.during = Some((score, computed_at));
+        }
+    }
+    let mut regressions: Vec<(&DatasetRow, QualityRegression)> = scores
+        .into_iter()
+        .filter_map(|(dataset_id, window)| {
+            let previous = window.before?;
+            let (current, computed_at) = window.during?;
+            (previous - current >= query.quality_drop).then(|| {
+                let dataset = by_id[&dataset_id];
+                (
+                    dataset,
+                    QualityRegression {
+                        dataset_name: dataset.name.clone(),
+                        previous,
+                        current,
+                        computed_at,
+                    },
+                )
+            })
+        })
+        .collect();
+    regressions.sort_by(|a, b| a.1.dataset_name.cmp(&b.1.dataset_name));
+    for (dataset, regression) in regressions {
+        digest_for(&mut digests, dataset, query)
+            .quality_regressions
+            .push(regression);
+    }
+
+    if table_exists(conn, "column_classifications")? {
+        let mut stmt = conn.prepare(
+            "SELECT f.dataset_id, f.name, c.category, c.source, c.confidence, c.created_at
+             FROM column_classifications c
+             JOIN fields f ON f.id = c.field_id
+             WHERE c.classification = 'pii'
+             ORDER BY f.dataset_id, f.name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<f64>>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+        let mut findings = Vec::new();
+        for row in rows {
+            let (dataset_id, field_name, category, source, confidence, classified_at) = row?;
+            let Some(dataset) = by_id.get(&dataset_id) else {
+                continue;
+            };
+            if in_period(&classified_at, query) {
+                findings.push((
+                    *dataset,
+                    PiiFinding {
+                        dataset_name: dataset.name.clone(),
+                        field_name,
+                        category,
+                        source,
+                        confidence,
+                        classified_at,
+                    },
+                ));
+            }
+        }
+        findings.sort_by(|a, b| {
+            (&a.1.dataset_name, &a.1.field_name).cmp(&(&b.1.dataset_name, &b.1.field_name))
+        });
+        for (dataset, finding) in findings {
+            digest_for(&mut digests, dataset, query)
+                .pii_findings
+                .push(finding);
+        }
+    }
+
+    let mut digests: Vec<Digest> = digests
+        .into_values()
+        .filter(|d| query.group.is_some() || !d.is_empty())
+        .collect();
+    for digest in &mut digests {
+        if let Some(group) = &digest.group {
+            digest.contact = contact(conn, query.group_by, group)?;
+        }
+    }
+    Ok(digests)
+}
+
+/// Contact details of a group's owner, when registered
+fn contact(
+    conn: &Connection,
+    group_by: DigestGroupBy,
+    group: &str,
+) -> rusqlite::Result<Option<DigestContact>> {
+    if !table_exists(conn, "owners")? {
+        return Ok(None);
+    }
+    let owner_id = match group_by {
+        DigestGroupBy::Owner => Some(group.to_string()),
+        DigestGroupBy::Domain => {
+            if !table_exists(conn, "domains")? {
+                return Ok(None);
+            }
+            conn.query_row(
+                "SELECT owner_id FROM domains WHERE name = ?1",
+                [group],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten()
+        }
+    };
+    let Some(owner_id) = owner_id else {
+        return Ok(None);
+    };
+    conn.query_row(
+        "SELECT owner_id, email, slack_channel FROM owners WHERE owner_id = ?1",
+        [&owner_id],
+        |row| {
+            Ok(DigestContact {
+                owner_id: row.get(0)?,
+                email: row.get(1)?,
+                slack_channel: row.get(2)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Response for the digests endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestsResponse {
+    pub group_by: DigestGroupBy,
+    pub since: String,
+    pub until: String,
+    pub digests: Vec<Digest>,
+}
+
+impl DigestsResponse {
+    /// All digests as one markdown document
+    pub fn to_markdown(&self) -> String {
+        if self.digests.is_empty() {
+            return format!(
+                "# MetaFuse digest\n\n_{} to {}_\n\nNo changes.\n",
+                self.since, self.until
+            );
+        }
+        self.digests
+            .iter()
+            .map(Digest::to_markdown)
+            .collect::<Vec<_>>()
+            .join("\n---\n\n")
+    }
+}
+
+/// Whether any schema snapshot has been taken
+pub fn has_snapshots(conn: &Connection) -> rusqlite::Result<bool> {
+    if !table_exists(conn, "digest_snapshots")? {
+        return Ok(false);
+    }
+    Ok(conn
+        .query_row("SELECT 1 FROM digest_snapshots LIMIT 1", [], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Replace the schema snapshots with every live dataset's current columns.
+///
+/// Returns the number of datasets snapshotted.
+pub fn snapshot_schemas(conn: &Connection) -> Result<usize> {
+    if !table_exists(conn, "digest_snapshots")? {
+        return Ok(0);
+    }
+    let mut schemas = schemas(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    let ids: Vec<i64> = tx
+        .prepare("SELECT id FROM datasets WHERE deleted_at IS NULL")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    tx.execute("DELETE FROM digest_snapshots", [])?;
+    for id in &ids {
+        let schema = schemas.remove(id).unwrap_or_default();
+        tx.execute(
+            "INSERT INTO digest_snapshots (dataset_id, schema, taken_at)
+             VALUES (?1, ?2, datetime('now'))",
+            params![id, serde_json::to_string(&schema).unwrap_or_default()],
+        )?;
+    }
+    tx.commit()?;
+    Ok(ids.len())
+}
+
+/// Scheduled digest settings
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// Webhook receiving the digests; the task doesn't run without one
+    pub webhook_url: Option<String>,
+    pub group_by: DigestGroupBy,
+    /// Hour of day (UTC) the digest is sent
+    pub hour_utc: u32,
+    pub quality_drop: f64,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            group_by: DigestGroupBy::Domain,
+            hour_utc: DEFAULT_DIGEST_HOUR_UTC,
+            quality_drop: subscriptions::DEFAULT_QUALITY_DROP,
+        }
+    }
+}
+
+impl DigestConfig {
+    /// Create config from environment variables
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            webhook_url: std::env::var("METAFUSE_DIGEST_WEBHOOK_URL")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            group_by: std::env::var("METAFUSE_DIGEST_GROUP_BY")
+                .ok()
+                .and_then(|v| DigestGroupBy::parse(v.trim()))
+                .unwrap_or(defaults.group_by),
+            hour_utc: std::env::var("METAFUSE_DIGEST_HOUR_UTC")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|h| *h < 24)
+                .unwrap_or(defaults.hour_utc),
+            quality_drop: std::env::var("METAFUSE_DIGEST_QUALITY_DROP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|d: &f64| (0.0..=1.0).contains(d))
+                .unwrap_or(defaults.quality_drop),
+        }
+    }
+
+    /// Next time the digest is due after `now`
+    pub fn next_run(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let today = now
+            .date_naive()
+            .and_hms_opt(self.hour_utc, 0, 0)
+            .expect("hour is below 24")
+            .and_utc();
+        if today > now {
+            today
+        } else {
+            today + chrono::Duration::days(1)
+        }
+    }
+}
+
+/// Webhook payload for a scheduled digest
+#[derive(Debug, Serialize)]
+pub struct DigestWebhookPayload<'a> {
+    /// Always `digest`
+    pub event: &'static str,
+    #[serde(flatten)]
+    pub digest: &'a Digest,
+    pub markdown: String,
+    pub source_system: &'static str,
+}
+
+/// Background task that sends the daily digests
+#[cfg(feature = "alerting")]
+pub async fn digest_task(
+    config: DigestConfig,
+    webhook_client: std::sync::Arc<crate::alerting::WebhookClient>,
+    backend: std::sync::Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    use crate::alerting::MAX_DELIVERY_ATTEMPTS;
+
+    let Some(url) = config.webhook_url.clone() else {
+        return;
+    };
+    tracing::info!(
+        group_by = config.group_by.as_str(),
+        hour_utc = config.hour_utc,
+        "Digest task started"
+    );
+
+    // Baseline schemas, so the first digest can report schema changes
+    match backend.get_connection().await {
+        Ok(conn) => {
+            if !has_snapshots(&conn).unwrap_or(true) {
+                if let Err(e) = snapshot_schemas(&conn) {
+                    tracing::warn!(error = %e, "Failed to take baseline digest snapshots");
+                }
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "Failed to get connection for digest snapshots"),
+    }
+
+    loop {
+        let now = Utc::now();
+        let next = config.next_run(now);
+        tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+
+        let conn = match backend.get_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to get connection for digest");
+                continue;
+            }
+        };
+        let query = DigestQuery {
+            group_by: config.group_by,
+            group: None,
+            since: next - chrono::Duration::hours(DEFAULT_DIGEST_HOURS),
+            until: next,
+            quality_drop: config.quality_drop,
+            visibility: None,
+        };
+        let digests = match generate(&conn, &query) {
+            Ok(digests) => digests,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to generate digests");
+                continue;
+            }
+        };
+
+        for digest in &digests {
+            let payload = DigestWebhookPayload {
+                event: "digest",
+                digest,
+                markdown: digest.to_markdown(),
+                source_system: "metafuse",
+            };
+            if let Err(e) = webhook_client
+                .send_json_with_retry(&url, "digest", &payload, MAX_DELIVERY_ATTEMPTS)
+                .await
+            {
+                tracing::error!(group = ?digest.group, error = %e, "Digest delivery failed");
+            }
+        }
+        if let Err(e) = snapshot_schemas(&conn) {
+            tracing::error!(error = %e, "Failed to snapshot schemas after digest");
+        }
+        tracing::info!(digests = digests.len(), "Daily digest sent");
+    }
+}
+
+/// Query parameters for the digests endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct DigestQueryParams {
+    #[serde(default)]
+    group_by: DigestGroupBy,
+    /// Only this domain or owner
+    group: Option<String>,
+    #[serde(default = "default_digest_hours")]
+    hours: i64,
+    #[serde(default = "default_digest_quality_drop")]
+    quality_drop: f64,
+    format: Option<String>,
+}
+
+pub(crate) fn default_digest_hours() -> i64 {
+    DEFAULT_DIGEST_HOURS
+}
+
+pub(crate) fn default_digest_quality_drop() -> f64 {
+    subscriptions::DEFAULT_QUALITY_DROP
+}
+
+/// Compile what changed over the last `hours`, per domain or owner
+pub(crate) async fn get_digests(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<DigestQueryParams>,
+) -> std::result::Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if !(1..=MAX_DIGEST_HOURS).contains(&params.hours) {
+        return Err(bad_request(
+            format!("hours must be between 1 and {}", MAX_DIGEST_HOURS),
+            request_id.0.clone(),
+        ));
+    }
+    validation::validate_score(params.quality_drop, "quality_drop")
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let markdown = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("markdown") => true,
+        Some(other) => {
+            return Err(bad_request(
+                format!("Invalid format '{}'. Valid values: json, markdown", other),
+                request_id.0.clone(),
+            ))
+        }
+    };
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let until = chrono::Utc::now();
+    let query = DigestQuery {
+        group_by: params.group_by,
+        group: params.group,
+        since: until - chrono::Duration::hours(params.hours),
+        until,
+        quality_drop: params.quality_drop,
+        visibility: dataset_acl::visibility_clause(&identity, "d.id", "d.domain"),
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.0.clone();
+    let response = tokio::task::spawn_blocking(move || {
+        generate(&conn, &query).map(|digests| DigestsResponse {
+            group_by: query.group_by,
+            since: query.since.to_rfc3339(),
+            until: query.until.to_rfc3339(),
+            digests,
+        })
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(digests = response.digests.len(), "Digests compiled");
+
+    if markdown {
+        Ok((
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            response.to_markdown(),
+        )
+            .into_response())
+    } else {
+        Ok(Json(response).into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_dataset(conn: &Connection, name: &str, domain: &str, created_at: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, domain, created_at, last_updated)
+             VALUES (?1, '/data', 'parquet', ?2, ?3, ?3)",
+            params![name, domain, created_at],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn add_field(conn: &Connection, dataset_id: i64, name: &str, data_type: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO fields (dataset_id, name, data_type) VALUES (?1, ?2, ?3)",
+            params![dataset_id, name, data_type],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn query(group: Option<&str>) -> DigestQuery {
+        DigestQuery {
+            group_by: DigestGroupBy::Domain,
+            group: group.map(str::to_string),
+            since: ts("2025-11-20T00:00:00Z"),
+            until: ts("2025-11-21T00:00:00Z"),
+            quality_drop: 0.1,
+            visibility: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_digests() {
+        let conn = setup();
+        let orders = add_dataset(&conn, "orders", "sales", "2025-11-01 00:00:00");
+        add_field(&conn, orders, "id", "int");
+        add_field(&conn, orders, "amount", "int");
+        snapshot_schemas(&conn).unwrap();
+
+        // Schema change, quality regression, and PII on an old dataset
+        conn.execute(
+            "UPDATE fields SET data_type = 'decimal' WHERE name = 'amount'",
+            [],
+        )
+        .unwrap();
+        let email = add_field(&conn, orders, "email", "string");
+        for (score, computed_at) in [(0.9, "2025-11-19T12:00:00Z"), (0.6, "2025-11-20T12:00:00Z")] {
+            conn.execute(
+                "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score) VALUES (?1, ?2, ?3)",
+                params![orders, computed_at, score],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO column_classifications (field_id, classification, category, created_at)
+             VALUES (?1, 'pii', 'email', '2025-11-20 08:00:00')",
+            [email],
+        )
+        .unwrap();
+        // New datasets in another domain and without one
+        add_dataset(&conn, "clicks", "web", "2025-11-20T09:00:00Z");
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('scratch', '/tmp', 'csv', '2025-11-20 10:00:00', '2025-11-20 10:00:00')",
+            [],
+        )
+        .unwrap();
+        // Outside the period
+        add_dataset(&conn, "later", "web", "2025-11-21T00:00:00Z");
+
+        let digests = generate(&conn, &query(None)).unwrap();
+        let groups: Vec<Option<&str>> = digests.iter().map(|d| d.group.as_deref()).collect();
+        assert_eq!(groups, vec![None, Some("sales"), Some("web")]);
+
+        let sales = &digests[1];
+        assert!(sales.new_datasets.is_empty());
+        assert_eq!(
+            sales.schema_changes,
+            vec![SchemaChange {
+                dataset_name: "orders".to_string(),
+                added: vec!["email".to_string()],
+                removed: vec![],
+                changed: vec!["amount".to_string()],
+            }]
+        );
+        assert_eq!(sales.quality_regressions[0].previous, 0.9);
+        assert_eq!(sales.quality_regressions[0].current, 0.6);
+        assert_eq!(sales.pii_findings[0].field_name, "email");
+        assert_eq!(digests[2].new_datasets[0].dataset_name, "clicks");
+
+        let markdown = sales.to_markdown();
+        assert!(markdown.starts_with("# MetaFuse digest: domain `sales`"));
+        assert!(markdown.contains("- `orders`: added `email`; retyped `amount`"));
+        assert!(markdown.contains("- `orders`: 0.90 → 0.60"));
+        assert!(markdown.contains("- `orders.email` (email)"));
+
+        // Snapshotting clears schema changes for the next period
+        snapshot_schemas(&conn).unwrap();
+        let digests = generate(&conn, &query(Some("sales"))).unwrap();
+        assert_eq!(digests.len(), 1);
+        assert!(digests[0].schema_changes.is_empty());
+    }
+
+    #[test]
+    fn test_named_group_digest_and_contact() {
+        let conn = setup();
+        conn.execute(
+            "INSERT INTO owners (owner_id, name, email) VALUES ('sales-team', 'Sales', 'sales@example.com')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO domains (name, display_name, owner_id) VALUES ('sales', 'Sales', 'sales-team')",
+            [],
+        )
+        .unwrap();
+
+        let digests = generate(&conn, &query(Some("sales"))).unwrap();
+        assert_eq!(digests.len(), 1);
+        assert!(digests[0].is_empty());
+        let contact = digests[0].contact.as_ref().unwrap();
+        assert_eq!(contact.email.as_deref(), Some("sales@example.com"));
+        assert!(digests[0].to_markdown().contains("No changes."));
+
+        assert!(generate(&conn, &query(None)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_next_run() {
+        let config = DigestConfig {
+            hour_utc: 6,
+            ..DigestConfig::default()
+        };
+        assert_eq!(
+            config.next_run(ts("2025-11-20T05:00:00Z")),
+            ts("2025-11-20T06:00:00Z")
+        );
+        assert_eq!(
+            config.next_run(ts("2025-11-20T06:00:00Z")),
+            ts("2025-11-21T06:00:00Z")
+        );
+    }
+}
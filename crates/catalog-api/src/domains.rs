@@ -0,0 +1,616 @@
+//! Domains
+//!
+//! The domain registry and the datasets in each domain.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::datasets::{
+    attach_uuids, parse_partition_keys, DatasetResponse, OperationalMetaResponse, PaginationParams,
+};
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use crate::{dataset_acl, envelope};
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use rusqlite::params_from_iter;
+use serde::{Deserialize, Serialize};
+
+/// Request to create a new domain
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateDomainRequest {
+    name: String,
+    display_name: String,
+    description: Option<String>,
+    owner_id: Option<String>,
+}
+
+/// Request to update an existing domain
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateDomainRequest {
+    display_name: Option<String>,
+    description: Option<String>,
+    owner_id: Option<String>,
+    is_active: Option<bool>,
+}
+
+/// Domain response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct DomainResponse {
+    id: i64,
+    name: String,
+    display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_id: Option<String>,
+    is_active: bool,
+    dataset_count: i64,
+    created_at: String,
+    updated_at: String,
+}
+
+/// List all domains
+pub(crate) async fn list_domains(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(params): Query<PaginationParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<DomainResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, "Listing domains");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let limit = params.limit.unwrap_or(100).min(1000);
+    let offset = params.offset.unwrap_or(0);
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT d.id, d.name, d.display_name, d.description, d.owner_id,
+                   d.is_active, d.created_at, d.updated_at,
+                   (SELECT COUNT(*) FROM datasets WHERE domain = d.name AND deleted_at IS NULL) as dataset_count
+            FROM domains d
+            WHERE d.is_active = 1
+            ORDER BY d.name
+            LIMIT ?1 OFFSET ?2
+            "#,
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let domains: Vec<DomainResponse> = stmt
+        .query_map([limit as i64, offset as i64], |row| {
+            Ok(DomainResponse {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                display_name: row.get(2)?,
+                description: row.get(3)?,
+                owner_id: row.get(4)?,
+                is_active: row.get::<_, i32>(5)? == 1,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+                dataset_count: row.get(8)?,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let total = envelope
+        .total(|| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM domains WHERE is_active = 1",
+                [],
+                |row| row.get(0),
+            )
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.window(domains, total)))
+}
+
+/// Create a new domain
+pub(crate) async fn create_domain(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<CreateDomainRequest>,
+) -> Result<(StatusCode, Json<DomainResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %req.name, "Creating domain");
+
+    // Validate domain name (lowercase alphanumeric with hyphens/underscores)
+    if !req
+        .name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(bad_request(
+            "Domain name must be lowercase alphanumeric with hyphens or underscores".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    if req.name.is_empty() || req.name.len() > 64 {
+        return Err(bad_request(
+            "Domain name must be 1-64 characters".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Insert domain
+    conn.execute(
+        r#"
+        INSERT INTO domains (name, display_name, description, owner_id, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, datetime('now'), datetime('now'))
+        "#,
+        rusqlite::params![req.name, req.display_name, req.description, req.owner_id],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            bad_request(
+                format!("Domain '{}' already exists", req.name),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    // Fetch the created domain
+    let domain: DomainResponse = conn
+        .query_row(
+            r#"
+            SELECT id, name, display_name, description, owner_id, is_active, created_at, updated_at
+            FROM domains WHERE name = ?1
+            "#,
+            [&req.name],
+            |row| {
+                Ok(DomainResponse {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    display_name: row.get(2)?,
+                    description: row.get(3)?,
+                    owner_id: row.get(4)?,
+                    is_active: row.get::<_, i32>(5)? == 1,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                    dataset_count: 0,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %req.name, id = domain.id, "Domain created successfully");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "domain",
+            &domain.name,
+            serde_json::json!({
+                "id": domain.id,
+                "name": domain.name,
+                "display_name": domain.display_name,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(domain)))
+}
+
+/// Get a domain by name
+pub(crate) async fn get_domain(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(name): Path<String>,
+) -> Result<Json<DomainResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Getting domain");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let domain: DomainResponse = conn
+        .query_row(
+            r#"
+            SELECT d.id, d.name, d.display_name, d.description, d.owner_id,
+                   d.is_active, d.created_at, d.updated_at,
+                   (SELECT COUNT(*) FROM datasets WHERE domain = d.name AND deleted_at IS NULL) as dataset_count
+            FROM domains d
+            WHERE d.name = ?1
+            "#,
+            [&name],
+            |row| {
+                Ok(DomainResponse {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    display_name: row.get(2)?,
+                    description: row.get(3)?,
+                    owner_id: row.get(4)?,
+                    is_active: row.get::<_, i32>(5)? == 1,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                    dataset_count: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|_| not_found(format!("Domain '{}' not found", name), request_id.0.clone()))?;
+
+    Ok(Json(domain))
+}
+
+/// Update a domain
+pub(crate) async fn update_domain(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(name): Path<String>,
+    JsonBody(req): JsonBody<UpdateDomainRequest>,
+) -> Result<Json<DomainResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Updating domain");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Build dynamic update query
+    let mut updates = vec!["updated_at = datetime('now')".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(display_name) = &req.display_name {
+        updates.push(format!("display_name = ?{}", params.len() + 1));
+        params.push(Box::new(display_name.clone()));
+    }
+    if let Some(description) = &req.description {
+        updates.push(format!("description = ?{}", params.len() + 1));
+        params.push(Box::new(description.clone()));
+    }
+    if let Some(owner_id) = &req.owner_id {
+        updates.push(format!("owner_id = ?{}", params.len() + 1));
+        params.push(Box::new(owner_id.clone()));
+    }
+    if let Some(is_active) = req.is_active {
+        updates.push(format!("is_active = ?{}", params.len() + 1));
+        params.push(Box::new(if is_active { 1i32 } else { 0i32 }));
+    }
+
+    params.push(Box::new(name.clone()));
+    let sql = format!(
+        "UPDATE domains SET {} WHERE name = ?{}",
+        updates.join(", "),
+        params.len()
+    );
+
+    let rows_updated = conn
+        .execute(&sql, params_from_iter(params.iter().map(|p| p.as_ref())))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows_updated == 0 {
+        return Err(not_found(
+            format!("Domain '{}' not found", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Fetch updated domain
+    let domain: DomainResponse = conn
+        .query_row(
+            r#"
+            SELECT d.id, d.name, d.display_name, d.description, d.owner_id,
+                   d.is_active, d.created_at, d.updated_at,
+                   (SELECT COUNT(*) FROM datasets WHERE domain = d.name AND deleted_at IS NULL) as dataset_count
+            FROM domains d
+            WHERE d.name = ?1
+            "#,
+            [&name],
+            |row| {
+                Ok(DomainResponse {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    display_name: row.get(2)?,
+                    description: row.get(3)?,
+                    owner_id: row.get(4)?,
+                    is_active: row.get::<_, i32>(5)? == 1,
+                    created_at: row.get(6)?,
+                    updated_at: row.get(7)?,
+                    dataset_count: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Domain updated successfully");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "domain",
+            &domain.name,
+            serde_json::json!({}),
+            serde_json::json!({
+                "display_name": domain.display_name,
+                "description": domain.description,
+                "owner_id": domain.owner_id,
+                "is_active": domain.is_active,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(domain))
+}
+
+/// Delete (soft delete) a domain
+pub(crate) async fn delete_domain(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Deleting domain");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Check for datasets in this domain (warning only, soft delete still proceeds)
+    let dataset_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM datasets WHERE domain = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    if dataset_count > 0 {
+        tracing::warn!(
+            domain = %name,
+            dataset_count,
+            "Soft-deleting domain with existing datasets"
+        );
+    }
+
+    // Soft delete - set is_active = 0
+    let rows_updated = conn
+        .execute(
+            "UPDATE domains SET is_active = 0, updated_at = datetime('now') WHERE name = ?1 AND is_active = 1",
+            [&name],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows_updated == 0 {
+        return Err(not_found(
+            format!("Domain '{}' not found", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(name = %name, dataset_count, "Domain deleted (soft delete)");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "domain",
+            &name,
+            serde_json::json!({"name": name}),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List datasets in a domain
+pub(crate) async fn list_domain_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path(name): Path<String>,
+    Query(params): Query<PaginationParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<DatasetResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, domain = %name, "Listing datasets in domain");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Verify domain exists
+    let domain_exists: bool = conn
+        .query_row(
+            "SELECT 1 FROM domains WHERE name = ?1 AND is_active = 1",
+            [&name],
+            |_| Ok(true),
+        )
+        .unwrap_or(false);
+
+    if !domain_exists {
+        return Err(not_found(
+            format!("Domain '{}' not found", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    let limit = params.limit.unwrap_or(100).min(1000);
+    let offset = params.offset.unwrap_or(0);
+
+    let mut filter = String::from("domain = ? AND deleted_at IS NULL");
+    let mut bindings = vec![name.clone()];
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    if let Some((clause, principals)) =
+        dataset_acl::visibility_clause(&identity, "datasets.id", "datasets.domain")
+    {
+        filter.push_str(" AND ");
+        filter.push_str(&clause);
+        bindings.extend(principals);
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+                   created_at, last_updated, row_count, size_bytes, partition_keys
+            FROM datasets
+            WHERE {}
+            ORDER BY name
+            LIMIT {} OFFSET {}
+            "#,
+            filter, limit, offset
+        ))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut datasets: Vec<DatasetResponse> = stmt
+        .query_map(params_from_iter(bindings.iter()), |row| {
+            let row_count: Option<i64> = row.get(11)?;
+            let size_bytes: Option<i64> = row.get(12)?;
+            let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+            Ok(DatasetResponse {
+                id: row.get(0)?,
+                uuid: None,
+                name: row.get(1)?,
+                path: row.get(2)?,
+                format: row.get(3)?,
+                delta_location: row.get(4)?,
+                description: row.get(5)?,
+                tenant: row.get(6)?,
+                domain: row.get(7)?,
+                owner: row.get(8)?,
+                created_at: row.get(9)?,
+                last_updated: row.get(10)?,
+                operational: OperationalMetaResponse {
+                    row_count,
+                    size_bytes,
+                    partition_keys,
+                },
+                freshness: None,
+                metadata_completeness: None,
+                custom_metadata: None,
+                tags: None,
+                quality_summary: None,
+                archived_at: None,
+                write_report: None,
+                owner_profile: None,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .filter_map(|r| r.ok())
+        .collect();
+    attach_uuids(&conn, &mut datasets)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let total = envelope
+        .total(|| {
+            conn.query_row(
+                &format!("SELECT COUNT(*) FROM datasets WHERE {}", filter),
+                params_from_iter(bindings.iter()),
+                |row| row.get(0),
+            )
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.window(datasets, total)))
+}
@@ -0,0 +1,247 @@
+// Allow dead_code for public API items that are used by library consumers
+// but not within the binary for every feature combination
+#![allow(dead_code)]
+
+//! Collection Envelopes
+//!
+//! Collection endpoints return bare JSON arrays. Scripted consumers can ask
+//! for `?envelope=true` instead and get the page wrapped with its position:
+//!
+//! ```json
+//! {
+//!   "data": [...],
+//!   "meta": {"total": 240, "limit": 100, "offset": 100},
+//!   "links": {"self": "...", "next": "...?limit=100&offset=200", "prev": "...?limit=100&offset=0"}
+//! }
+//! ```
+//!
+//! Handlers opt in by taking an [`EnvelopeQuery`] and returning
+//! [`Collection`]. With the envelope, `limit` (default 100, max 1000) and
+//! `offset` page the collection; without it, they only apply when given.
+
+use crate::external_url::{ExternalUrl, PaginationLinks};
+use serde::{Deserialize, Serialize};
+
+/// Page size when `limit` is not given
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Largest accepted page size
+pub const MAX_LIMIT: usize = 1000;
+
+/// Query parameters shared by collection endpoints.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EnvelopeParams {
+    /// Wrap the collection in an envelope (default: false)
+    #[serde(default)]
+    pub envelope: bool,
+    /// Page size (default: 100, max: 1000)
+    pub limit: Option<usize>,
+    /// Number of items to skip (default: 0)
+    pub offset: Option<usize>,
+}
+
+/// Position of a page in its collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageMeta {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A page of a collection with its position and neighbouring pages.
+#[derive(Debug, Clone, Serialize)]
+pub struct Envelope<T> {
+    pub data: Vec<T>,
+    pub meta: PageMeta,
+    pub links: PaginationLinks,
+}
+
+/// Collection response: a bare array, or an envelope on request.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Collection<T> {
+    Items(Vec<T>),
+    Envelope(Envelope<T>),
+}
+
+/// Envelope options of a collection request, with what is needed to link
+/// to other pages.
+#[derive(Debug, Clone, Default)]
+pub struct EnvelopeQuery {
+    params: EnvelopeParams,
+    url: ExternalUrl,
+    path: String,
+    query: Option<String>,
+}
+
+impl EnvelopeQuery {
+    /// `path` is the route path without the base path; `query` the raw query
+    /// string, kept in page links.
+    pub fn new(
+        params: EnvelopeParams,
+        url: ExternalUrl,
+        path: impl Into<String>,
+        query: Option<String>,
+    ) -> Self {
+        Self {
+            params,
+            url,
+            path: path.into(),
+            query,
+        }
+    }
+
+    /// Whether the caller asked for an envelope.
+    pub fn is_enabled(&self) -> bool {
+        self.params.envelope
+    }
+
+    /// Page size.
+    pub fn limit(&self) -> usize {
+        self.params.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+    }
+
+    /// Items to skip.
+    pub fn offset(&self) -> usize {
+        self.params.offset.unwrap_or(0)
+    }
+
+    /// Row limit and offset for handlers that page in SQL under their own
+    /// `limit`: the requested page with an envelope, else the handler's
+    /// limit from the given offset.
+    pub fn bounds(&self, limit: usize) -> (usize, usize) {
+        if self.is_enabled() {
+            (self.limit(), self.offset())
+        } else {
+            (limit, self.offset())
+        }
+    }
+
+    /// Count the collection, for handlers that page in SQL. The count only
+    /// runs with an envelope.
+    pub fn total<E>(&self, count: impl FnOnce() -> Result<i64, E>) -> Result<i64, E> {
+        if self.is_enabled() {
+            count()
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Respond with a whole collection, paged here. Bare arrays are only
+    /// cut by an explicit `limit` or `offset`.
+    pub fn page<T>(&self, items: Vec<T>) -> Collection<T> {
+        if !self.is_enabled() {
+            let limit = self.params.limit.map_or(usize::MAX, |l| l.min(MAX_LIMIT));
+            return Collection::Items(items.into_iter().skip(self.offset()).take(limit).collect());
+        }
+        let total = items.len() as i64;
+        let data = items
+            .into_iter()
+            .skip(self.offset())
+            .take(self.limit())
+            .collect();
+        self.envelope(data, total)
+    }
+
+    /// Respond with a page the handler already cut with [`Self::limit`] and
+    /// [`Self::offset`], out of `total` items.
+    pub fn window<T>(&self, items: Vec<T>, total: i64) -> Collection<T> {
+        if self.is_enabled() {
+            self.envelope(items, total)
+        } else {
+            Collection::Items(items)
+        }
+    }
+
+    fn envelope<T>(&self, data: Vec<T>, total: i64) -> Collection<T> {
+        let (limit, offset) = (self.limit() as i64, self.offset() as i64);
+        Collection::Envelope(Envelope {
+            data,
+            meta: PageMeta {
+                total,
+                limit,
+                offset,
+            },
+            links: PaginationLinks::new(
+                &self.url,
+                &self.path,
+                self.query.as_deref(),
+                limit,
+                offset,
+                total,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(envelope: bool, limit: Option<usize>, offset: Option<usize>) -> EnvelopeQuery {
+        EnvelopeQuery::new(
+            EnvelopeParams {
+                envelope,
+                limit,
+                offset,
+            },
+            ExternalUrl::default(),
+            "/api/v1/owners",
+            Some("envelope=true&limit=2&offset=2".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_bare_array_without_envelope() {
+        let q = query(false, None, None);
+        let body = serde_json::to_value(q.page(vec![1, 2, 3, 4, 5])).unwrap();
+        assert_eq!(body, serde_json::json!([1, 2, 3, 4, 5]));
+        assert_eq!(q.bounds(50), (50, 0));
+        assert_eq!(q.total(|| Err::<i64, ()>(())), Ok(0));
+
+        // An explicit limit and offset still apply
+        let q = query(false, Some(2), Some(2));
+        let body = serde_json::to_value(q.page(vec![1, 2, 3, 4, 5])).unwrap();
+        assert_eq!(body, serde_json::json!([3, 4]));
+        assert_eq!(q.bounds(50), (50, 2));
+    }
+
+    #[test]
+    fn test_page_envelope() {
+        let q = query(true, Some(2), Some(2));
+        let body = serde_json::to_value(q.page(vec![1, 2, 3, 4, 5])).unwrap();
+        assert_eq!(body["data"], serde_json::json!([3, 4]));
+        assert_eq!(
+            body["meta"],
+            serde_json::json!({"total": 5, "limit": 2, "offset": 2})
+        );
+        assert_eq!(
+            body["links"]["next"],
+            "/api/v1/owners?envelope=true&limit=2&offset=4"
+        );
+        assert_eq!(
+            body["links"]["prev"],
+            "/api/v1/owners?envelope=true&limit=2&offset=0"
+        );
+    }
+
+    #[test]
+    fn test_window_envelope() {
+        let q = query(true, Some(2), None);
+        let body = serde_json::to_value(q.window(vec!["a", "b"], 2)).unwrap();
+        assert_eq!(body["data"], serde_json::json!(["a", "b"]));
+        assert_eq!(body["meta"]["total"], 2);
+        assert!(body["links"].get("next").is_none());
+        assert!(body["links"].get("prev").is_none());
+    }
+
+    #[test]
+    fn test_limit_defaults_and_cap() {
+        assert_eq!(query(true, None, None).limit(), DEFAULT_LIMIT);
+        assert_eq!(query(true, Some(5000), None).limit(), MAX_LIMIT);
+        assert_eq!(
+            query(true, Some(5000), Some(10)).bounds(50),
+            (MAX_LIMIT, 10)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Error Codes
+//!
+//! Every error payload carries a stable, machine-readable `code` (see
+//! [`ErrorCode`]) next to the human-readable `error` message, so clients can
+//! branch on the code instead of matching message strings:
+//!
+//! ```json
+//! { "error": "Dataset 'orders' not found", "code": "DATASET_NOT_FOUND", "request_id": "..." }
+//! ```
+//!
+//! Handlers set specific codes (`DATASET_NOT_FOUND`, `TENANT_SUSPENDED`, ...).
+//! [`error_code_middleware`] fills in the generic code for the status on any
+//! error response that has none, and wraps plain-text errors (for example
+//! extractor rejections) in a JSON payload, so no error goes out without one.
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{json, Value};
+
+pub use metafuse_catalog_errors::ErrorCode;
+
+/// Largest error body rewritten; bigger or streamed bodies are passed through
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Middleware that makes sure every error response carries a code.
+///
+/// Must run outside `request_id_middleware`, so the `X-Request-ID` response
+/// header is available for wrapped plain-text errors.
+pub async fn error_code_middleware(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+
+    // Reading the body consumes it, so check its size first
+    let too_big = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_none_or(|size| size > MAX_ERROR_BODY as u64);
+    if too_big {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read error response to add its code");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let request_id = parts
+        .headers
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok());
+
+    match with_code(status, &bytes, request_id) {
+        Some(coded) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts.headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            Response::from_parts(parts, Body::from(coded))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+/// Add the generic code for `status` to an error body that has none.
+///
+/// JSON objects get a `code` member; anything else becomes the `error`
+/// message of a new JSON payload. Returns `None` if the body already has a
+/// code.
+fn with_code(status: StatusCode, body: &[u8], request_id: Option<&str>) -> Option<Vec<u8>> {
+    let code = ErrorCode::from_status(status.as_u16());
+    let value = match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Object(mut object)) => {
+            if object.get("code").is_some_and(Value::is_string) {
+                return None;
+            }
+            object.insert("code".to_string(), json!(code));
+            Value::Object(object)
+        }
+        _ => {
+            let text = String::from_utf8_lossy(body);
+            let message = match text.trim() {
+                "" => status.canonical_reason().unwrap_or("Error").to_string(),
+                text => text.to_string(),
+            };
+            json!({
+                "error": message,
+                "code": code,
+                "request_id": request_id.unwrap_or_default(),
+            })
+        }
+    };
+    serde_json::to_vec(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coded(status: StatusCode, body: &[u8]) -> Option<Value> {
+        with_code(status, body, Some("r1")).map(|b| serde_json::from_slice(&b).unwrap())
+    }
+
+    #[test]
+    fn test_adds_generic_code_to_json_errors() {
+        let body = coded(StatusCode::NOT_FOUND, br#"{"error":"Owner 'x' not found"}"#).unwrap();
+        assert_eq!(body["code"], "NOT_FOUND");
+        assert_eq!(body["error"], "Owner 'x' not found");
+
+        // Specific codes set by handlers are kept
+        assert!(coded(
+            StatusCode::NOT_FOUND,
+            br#"{"error":"Dataset 'x' not found","code":"DATASET_NOT_FOUND"}"#
+        )
+        .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_passes_oversized_errors_through() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/big",
+                get(|| async { (StatusCode::BAD_GATEWAY, "x".repeat(MAX_ERROR_BODY + 1)) }),
+            )
+            .layer(axum::middleware::from_fn(error_code_middleware));
+        let request = Request::builder().uri("/big").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(bytes.len(), MAX_ERROR_BODY + 1);
+    }
+
+    #[test]
+    fn test_wraps_plain_text_errors() {
+        let body = coded(StatusCode::BAD_REQUEST, b"Parse error: unexpected token").unwrap();
+        assert_eq!(body["error"], "Parse error: unexpected token");
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["request_id"], "r1");
+
+        let body = coded(StatusCode::METHOD_NOT_ALLOWED, b"").unwrap();
+        assert_eq!(body["error"], "Method Not Allowed");
+        assert_eq!(body["code"], "METHOD_NOT_ALLOWED");
+    }
+}
@@ -0,0 +1,1027 @@
+// Allow dead_code: the scheduler only runs with the `api-keys` feature
+// (control plane)
+#![allow(dead_code)]
+
+//! Scheduled Catalog Exports
+//!
+//! Tenants can have their catalog exported on a schedule to their own object
+//! storage, e.g. a nightly dump to `gs://acme-exports/metafuse`. Schedules and
+//! their run history live in the control plane (migration v1.40.0) and are
+//! managed by platform admins.
+//!
+//! # Runs
+//!
+//! The export task checks for due schedules every
+//! `METAFUSE_EXPORT_CHECK_INTERVAL_SECS` (default: 60). A due schedule is
+//! advanced by its frequency before it runs, so a run is taken once even with
+//! several servers. Each run writes the same bundle as `POST /api/v1/export`
+//! to `{target_uri}/catalog-{YYYYMMDDTHHMMSSZ}.{json|ndjson}`.
+//!
+//! # Credentials
+//!
+//! A schedule with a `credentials_profile` uploads with that profile: one of
+//! the tenant's stored credentials (migration v1.48.0), a GCS service account
+//! key or an AWS access key, managed by platform admins. The profile's
+//! provider must match the target. Secrets are write-only: profiles are
+//! listed by name and provider. A profile used by a schedule can't be
+//! deleted, and replacing it rotates the credentials of its schedules.
+//!
+//! Secrets are encrypted with `METAFUSE_CREDENTIALS_KEY` (AES-256-GCM)
+//! before they are stored, so a copy of the control plane database doesn't
+//! reveal them. Without the key, profiles can't be stored or used; after
+//! changing it, profiles must be stored again.
+//!
+//! A schedule without a profile uploads with the server's object storage
+//! credentials, the same as catalogs (Application Default Credentials for
+//! GCS, the AWS credential chain for S3). Uploads require the storage crate's
+//! `gcs` or `s3` feature.
+//!
+//! # Failures
+//!
+//! Failed runs are recorded with their error, logged, and sent to the tenant's
+//! webhooks as `export.failed`. The schedule stays enabled and runs again at
+//! its next time.
+
+#[cfg(feature = "api-keys")]
+use crate::control_plane::AuditContext as ControlPlaneAuditContext;
+use crate::control_plane::ControlPlane;
+#[cfg(feature = "api-keys")]
+use crate::envelope;
+#[cfg(feature = "api-keys")]
+use crate::error_codes::ErrorCode;
+#[cfg(feature = "api-keys")]
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::strict_json::JsonBody;
+use crate::webhooks::{WebhookDispatcher, WebhookEvent};
+#[cfg(feature = "api-keys")]
+use axum::extract::{Extension, Path, State};
+#[cfg(feature = "api-keys")]
+use axum::http::StatusCode;
+#[cfg(feature = "api-keys")]
+use axum::Json;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use metafuse_catalog_core::bundle;
+use metafuse_catalog_storage::{DynCatalogBackend, ObjectCredentials, TenantBackendFactory};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Default seconds between checks for due schedules
+pub const DEFAULT_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Runs kept per schedule
+pub const RUN_HISTORY_LIMIT: usize = 100;
+
+/// Bundle encoding of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "json" => Some(ExportFormat::Json),
+            "ndjson" => Some(ExportFormat::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+/// How often a schedule runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFrequency {
+    Hourly,
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl ExportFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFrequency::Hourly => "hourly",
+            ExportFrequency::Daily => "daily",
+            ExportFrequency::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "hourly" => Some(ExportFrequency::Hourly),
+            "daily" => Some(ExportFrequency::Daily),
+            "weekly" => Some(ExportFrequency::Weekly),
+            _ => None,
+        }
+    }
+
+    /// SQLite `datetime` modifier advancing a time by one period
+    pub fn sqlite_modifier(&self) -> &'static str {
+        match self {
+            ExportFrequency::Hourly => "+1 hour",
+            ExportFrequency::Daily => "+1 day",
+            ExportFrequency::Weekly => "+7 days",
+        }
+    }
+}
+
+/// A tenant's scheduled export
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportSchedule {
+    pub id: i64,
+    pub tenant_id: String,
+    pub target_uri: String,
+    pub format: ExportFormat,
+    pub frequency: ExportFrequency,
+    pub enabled: bool,
+    /// Tenant credentials profile uploads use; the server's without one
+    pub credentials_profile: Option<String>,
+    pub next_run_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request to schedule an export
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateExportScheduleRequest {
+    pub target_uri: String,
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub frequency: ExportFrequency,
+    /// Tenant credentials profile to upload with
+    #[serde(default)]
+    pub credentials_profile: Option<String>,
+    /// First run, as RFC 3339 (default: now)
+    #[serde(default)]
+    pub next_run_at: Option<String>,
+}
+
+/// Changes to a schedule; unset fields are kept
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UpdateExportScheduleRequest {
+    pub target_uri: Option<String>,
+    pub format: Option<ExportFormat>,
+    pub frequency: Option<ExportFrequency>,
+    pub enabled: Option<bool>,
+    /// Tenant credentials profile to upload with; empty for the server's
+    pub credentials_profile: Option<String>,
+    /// Next run, as RFC 3339
+    pub next_run_at: Option<String>,
+}
+
+/// Outcome of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl ExportRunStatus {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "succeeded" => ExportRunStatus::Succeeded,
+            "failed" => ExportRunStatus::Failed,
+            _ => ExportRunStatus::Running,
+        }
+    }
+}
+
+/// One run of a schedule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportRun {
+    pub id: i64,
+    pub schedule_id: i64,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub status: ExportRunStatus,
+    pub object_uri: Option<String>,
+    pub dataset_count: Option<i64>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// What a successful run wrote
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOutput {
+    pub object_uri: String,
+    pub dataset_count: usize,
+    pub size_bytes: usize,
+}
+
+/// A tenant's stored object storage credentials, without the secrets
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CredentialsProfile {
+    pub id: i64,
+    pub tenant_id: String,
+    pub name: String,
+    /// `gcs` or `s3`
+    pub provider: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Credentials stored in a profile, tagged by `provider`
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "provider", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ProfileCredentials {
+    /// A service account key file, as a JSON object
+    Gcs {
+        service_account_key: serde_json::Value,
+    },
+    /// An AWS access key, with a session token for temporary credentials
+    S3 {
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_token: Option<String>,
+    },
+}
+
+impl std::fmt::Debug for ProfileCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_object_credentials().fmt(f)
+    }
+}
+
+impl ProfileCredentials {
+    pub fn provider(&self) -> &'static str {
+        match self {
+            ProfileCredentials::Gcs { .. } => "gcs",
+            ProfileCredentials::S3 { .. } => "s3",
+        }
+    }
+
+    /// Check the credentials are complete.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ProfileCredentials::Gcs {
+                service_account_key,
+            } => {
+                let has_key = service_account_key
+                    .get("private_key")
+                    .and_then(|k| k.as_str())
+                    .is_some_and(|k| !k.is_empty());
+                if !has_key {
+                    return Err(
+                        "service_account_key must be a service account key file with a private_key"
+                            .to_string(),
+                    );
+                }
+            }
+            ProfileCredentials::S3 {
+                access_key_id,
+                secret_access_key,
+                ..
+            } => {
+                if access_key_id.trim().is_empty() || secret_access_key.trim().is_empty() {
+                    return Err("access_key_id and secret_access_key must not be empty".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_object_credentials(&self) -> ObjectCredentials {
+        match self {
+            ProfileCredentials::Gcs {
+                service_account_key,
+            } => ObjectCredentials::Gcs {
+                service_account_key: service_account_key.to_string(),
+            },
+            ProfileCredentials::S3 {
+                access_key_id,
+                secret_access_key,
+                session_token,
+            } => ObjectCredentials::S3 {
+                access_key_id: access_key_id.clone(),
+                secret_access_key: secret_access_key.clone(),
+                session_token: session_token.clone(),
+            },
+        }
+    }
+}
+
+/// Version prefix of encrypted profile credentials
+const SEALED_PREFIX: &str = "v1:";
+
+/// Key that profile credentials are encrypted with in the control plane
+/// (`METAFUSE_CREDENTIALS_KEY`: 32 random bytes in base64).
+#[derive(Clone)]
+pub struct CredentialsKey([u8; 32]);
+
+impl std::fmt::Debug for CredentialsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CredentialsKey(..)")
+    }
+}
+
+impl CredentialsKey {
+    /// Read `METAFUSE_CREDENTIALS_KEY`. Without it, profiles can't be stored
+    /// or read.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match std::env::var("METAFUSE_CREDENTIALS_KEY") {
+            Ok(v) if !v.trim().is_empty() => Self::from_base64(&v)
+                .map(Some)
+                .map_err(|e| format!("Invalid METAFUSE_CREDENTIALS_KEY: {}", e)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn from_base64(value: &str) -> Result<Self, String> {
+        let bytes = STANDARD
+            .decode(value.trim())
+            .map_err(|_| "expected base64".to_string())?;
+        let key = bytes
+            .try_into()
+            .map_err(|_| "expected 32 bytes, e.g. from `openssl rand -base64 32`".to_string())?;
+        Ok(Self(key))
+    }
+
+    fn key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("32-byte AES-256 key"))
+    }
+
+    /// Encrypt a profile's credentials with AES-256-GCM for storage.
+    ///
+    /// The tenant and profile name are authenticated along with them, so a
+    /// stored value can't be copied to another profile.
+    pub fn seal(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        credentials: &ProfileCredentials,
+    ) -> Result<String, String> {
+        let mut in_out = serde_json::to_vec(credentials).map_err(|e| e.to_string())?;
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "Failed to generate a nonce".to_string())?;
+        self.key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                profile_aad(tenant_id, name),
+                &mut in_out,
+            )
+            .map_err(|_| "Failed to encrypt credentials".to_string())?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(in_out);
+        Ok(format!("{}{}", SEALED_PREFIX, STANDARD.encode(sealed)))
+    }
+
+    /// Decrypt credentials stored by [`CredentialsKey::seal`].
+    pub fn open(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        sealed: &str,
+    ) -> Result<ProfileCredentials, String> {
+        let bytes = sealed
+            .strip_prefix(SEALED_PREFIX)
+            .and_then(|encoded| STANDARD.decode(encoded).ok())
+            .filter(|bytes| bytes.len() > NONCE_LEN)
+            .ok_or_else(|| {
+                "Stored credentials are not encrypted; store the profile again".to_string()
+            })?;
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| "Invalid nonce in stored credentials".to_string())?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key()
+            .open_in_place(nonce, profile_aad(tenant_id, name), &mut in_out)
+            .map_err(|_| {
+                "Failed to decrypt stored credentials: was METAFUSE_CREDENTIALS_KEY changed?"
+                    .to_string()
+            })?;
+        serde_json::from_slice(plaintext).map_err(|e| e.to_string())
+    }
+}
+
+fn profile_aad(tenant_id: &str, name: &str) -> Aad<Vec<u8>> {
+    Aad::from(format!("{}\0{}", tenant_id, name).into_bytes())
+}
+
+/// Validate a credentials profile name: letters, digits, `-`, `_` and `.`.
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "Invalid credentials profile name '{}': use up to 64 letters, digits, '-', '_' or '.'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Check a profile of `provider` can upload to `target_uri`.
+pub fn check_profile_target(name: &str, provider: &str, target_uri: &str) -> Result<(), String> {
+    let scheme = match provider {
+        "gcs" => "gs://",
+        _ => "s3://",
+    };
+    if !target_uri.starts_with(scheme) {
+        return Err(format!(
+            "Credentials profile '{}' is for {}, but target_uri '{}' is not a {} prefix",
+            name, provider, target_uri, scheme
+        ));
+    }
+    Ok(())
+}
+
+/// Scheduler configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportScheduleConfig {
+    pub check_interval_secs: u64,
+}
+
+impl Default for ExportScheduleConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: DEFAULT_CHECK_INTERVAL_SECS,
+        }
+    }
+}
+
+impl ExportScheduleConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Self {
+        Self {
+            check_interval_secs: std::env::var("METAFUSE_EXPORT_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS),
+        }
+    }
+}
+
+/// Validate an export target: an object storage prefix.
+pub fn validate_target_uri(uri: &str) -> Result<(), String> {
+    let valid = ["gs://", "s3://", "file://"]
+        .iter()
+        .any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme));
+    if !valid {
+        return Err(format!(
+            "Invalid target_uri '{}': expected a gs://, s3://, or file:// prefix",
+            uri
+        ));
+    }
+    metafuse_catalog_storage::parse_catalog_uri(&object_uri(
+        uri,
+        ExportFormat::Json,
+        chrono::Utc::now(),
+    ))
+    .map(|_| ())
+    .map_err(|e| format!("Invalid target_uri '{}': {}", uri, e))
+}
+
+/// Parse an RFC 3339 run time into the control plane's UTC format.
+pub fn parse_run_time(value: &str) -> Result<String, String> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|t| {
+            t.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .map_err(|e| format!("Invalid next_run_at '{}': {}", value, e))
+}
+
+/// Object a run at `at` writes under `target_uri`. A query string (such as
+/// an S3 `?region=`) stays at the end.
+pub fn object_uri(
+    target_uri: &str,
+    format: ExportFormat,
+    at: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let (prefix, query) = match target_uri.split_once('?') {
+        Some((prefix, query)) => (prefix, Some(query)),
+        None => (target_uri, None),
+    };
+    let object = format!(
+        "{}/catalog-{}.{}",
+        prefix.trim_end_matches('/'),
+        at.format("%Y%m%dT%H%M%SZ"),
+        format.as_str()
+    );
+    match query {
+        Some(query) => format!("{}?{}", object, query),
+        None => object,
+    }
+}
+
+/// Export a tenant's catalog to the schedule's target, uploading with
+/// `credentials` or the server's.
+pub async fn run_export(
+    backend: &DynCatalogBackend,
+    schedule: &ExportSchedule,
+    credentials: Option<&ObjectCredentials>,
+) -> Result<ExportOutput, String> {
+    let conn = backend.get_connection().await.map_err(|e| e.to_string())?;
+    let catalog_bundle = tokio::task::spawn_blocking(move || bundle::export_catalog(&conn))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+        .map_err(|e| e.to_string())?;
+
+    let data = match schedule.format {
+        ExportFormat::Json => serde_json::to_vec(&catalog_bundle).map_err(|e| e.to_string())?,
+        ExportFormat::Ndjson => catalog_bundle
+            .to_ndjson()
+            .map_err(|e| e.to_string())?
+            .into_bytes(),
+    };
+    let output = ExportOutput {
+        object_uri: object_uri(&schedule.target_uri, schedule.format, chrono::Utc::now()),
+        dataset_count: catalog_bundle.datasets.len(),
+        size_bytes: data.len(),
+    };
+    metafuse_catalog_storage::put_object_with_credentials(&output.object_uri, data, credentials)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+/// Run one claimed schedule and record the outcome.
+async fn run_schedule(
+    control_plane: &ControlPlane,
+    tenants: &TenantBackendFactory,
+    webhooks: &WebhookDispatcher,
+    schedule: ExportSchedule,
+    run_id: i64,
+) {
+    let backend = tenants.get_backend_by_id(&schedule.tenant_id).await;
+    let credentials = match &schedule.credentials_profile {
+        Some(name) => control_plane
+            .get_profile_credentials(&schedule.tenant_id, name)
+            .await
+            .map_err(|e| format!("Failed to read credentials profile '{}': {}", name, e))
+            .and_then(|credentials| {
+                credentials
+                    .map(|c| Some(c.to_object_credentials()))
+                    .ok_or_else(|| format!("Credentials profile '{}' not found", name))
+            }),
+        None => Ok(None),
+    };
+    let result = match (&backend, credentials) {
+        (Ok(backend), Ok(credentials)) => {
+            run_export(backend.as_ref(), &schedule, credentials.as_ref()).await
+        }
+        (Err(e), _) => Err(format!("Failed to resolve tenant catalog: {}", e)),
+        (_, Err(e)) => Err(e),
+    };
+
+    if let Err(e) = control_plane.finish_export_run(run_id, &result).await {
+        tracing::error!(tenant_id = %schedule.tenant_id, run_id, error = %e, "Failed to record export run");
+    }
+
+    match result {
+        Ok(output) => {
+            tracing::info!(
+                tenant_id = %schedule.tenant_id,
+                schedule_id = schedule.id,
+                object_uri = %output.object_uri,
+                datasets = output.dataset_count,
+                size_bytes = output.size_bytes,
+                "Scheduled export written"
+            );
+        }
+        Err(error) => {
+            tracing::warn!(
+                tenant_id = %schedule.tenant_id,
+                schedule_id = schedule.id,
+                run_id,
+                error = %error,
+                "Scheduled export failed"
+            );
+            let Ok(backend) = backend else {
+                return;
+            };
+            match backend.get_connection().await {
+                Ok(conn) => webhooks.notify(
+                    &conn,
+                    &backend,
+                    &schedule.tenant_id,
+                    WebhookEvent::ExportFailed,
+                    serde_json::json!({
+                        "schedule_id": schedule.id,
+                        "run_id": run_id,
+                        "target_uri": schedule.target_uri,
+                        "error": error,
+                    }),
+                ),
+                Err(e) => {
+                    tracing::warn!(tenant_id = %schedule.tenant_id, error = %e, "Failed to get connection for export failure webhook");
+                }
+            }
+        }
+    }
+}
+
+/// Background task that runs due export schedules
+pub async fn export_schedule_task(
+    config: ExportScheduleConfig,
+    control_plane: Arc<ControlPlane>,
+    tenants: Arc<TenantBackendFactory>,
+    webhooks: WebhookDispatcher,
+) {
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(config.check_interval_secs));
+    tracing::info!(
+        check_interval_secs = config.check_interval_secs,
+        "Export schedule task started"
+    );
+
+    loop {
+        interval.tick().await;
+        let due = match control_plane.claim_due_export_schedules().await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to claim due export schedules");
+                continue;
+            }
+        };
+        for (schedule, run_id) in due {
+            run_schedule(&control_plane, &tenants, &webhooks, schedule, run_id).await;
+        }
+    }
+}
+
+/// Map a control plane error from an export schedule change
+#[cfg(feature = "api-keys")]
+pub(crate) fn export_schedule_error(
+    e: metafuse_catalog_core::CatalogError,
+    request_id: &str,
+) -> (StatusCode, Json<ErrorResponse>) {
+    match e {
+        metafuse_catalog_core::CatalogError::ValidationError(msg) => {
+            bad_request(msg, request_id.to_string())
+        }
+        metafuse_catalog_core::CatalogError::DatasetNotFound(msg) => {
+            not_found(msg, request_id.to_string())
+        }
+        metafuse_catalog_core::CatalogError::ConflictError(msg) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: msg,
+                code: ErrorCode::Conflict,
+                request_id: request_id.to_string(),
+            }),
+        ),
+        e => internal_error(e.to_string(), request_id.to_string()),
+    }
+}
+
+/// List a tenant's scheduled exports
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_list_export_schedules(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(tenant_id): Path<String>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<ExportSchedule>>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let exists = control_plane
+        .get_tenant(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .is_some();
+    if !exists {
+        return Err(not_found(
+            format!("Tenant '{}' not found", tenant_id),
+            request_id.0.clone(),
+        ));
+    }
+
+    let schedules = control_plane
+        .list_export_schedules(&tenant_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(schedules)))
+}
+
+/// Schedule exports of a tenant's catalog
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_create_export_schedule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(tenant_id): Path<String>,
+    JsonBody(req): JsonBody<CreateExportScheduleRequest>,
+) -> Result<(StatusCode, Json<ExportSchedule>), (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let schedule = control_plane
+        .create_export_schedule(&tenant_id, req, cp_audit)
+        .await
+        .map_err(|e| export_schedule_error(e, &request_id.0))?;
+
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+/// Get one of a tenant's scheduled exports
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_get_export_schedule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path((tenant_id, schedule_id)): Path<(String, i64)>,
+) -> Result<Json<ExportSchedule>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    control_plane
+        .get_export_schedule(&tenant_id, schedule_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .map(Json)
+        .ok_or_else(|| {
+            not_found(
+                format!("Export schedule {} not found", schedule_id),
+                request_id.0.clone(),
+            )
+        })
+}
+
+/// Change one of a tenant's scheduled exports
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_update_export_schedule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path((tenant_id, schedule_id)): Path<(String, i64)>,
+    JsonBody(req): JsonBody<UpdateExportScheduleRequest>,
+) -> Result<Json<ExportSchedule>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    control_plane
+        .update_export_schedule(&tenant_id, schedule_id, req, cp_audit)
+        .await
+        .map_err(|e| export_schedule_error(e, &request_id.0))?
+        .map(Json)
+        .ok_or_else(|| {
+            not_found(
+                format!("Export schedule {} not found", schedule_id),
+                request_id.0.clone(),
+            )
+        })
+}
+
+/// Delete one of a tenant's scheduled exports and its run history
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_delete_export_schedule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path((tenant_id, schedule_id)): Path<(String, i64)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let cp_audit = ControlPlaneAuditContext {
+        actor: "platform-admin".to_string(),
+        request_id: Some(request_id.0.clone()),
+        client_ip: audit_ctx.client_ip.clone(),
+    };
+
+    let deleted = control_plane
+        .delete_export_schedule(&tenant_id, schedule_id, cp_audit)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(not_found(
+            format!("Export schedule {} not found", schedule_id),
+            request_id.0.clone(),
+        ))
+    }
+}
+
+/// List the recent runs of a tenant's scheduled export, newest first
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_list_export_runs(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path((tenant_id, schedule_id)): Path<(String, i64)>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<ExportRun>>, (StatusCode, Json<ErrorResponse>)> {
+    let control_plane = state.multi_tenant.control_plane().ok_or_else(|| {
+        internal_error(
+            "Control plane not available".to_string(),
+            request_id.0.clone(),
+        )
+    })?;
+
+    let exists = control_plane
+        .get_export_schedule(&tenant_id, schedule_id)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .is_some();
+    if !exists {
+        return Err(not_found(
+            format!("Export schedule {} not found", schedule_id),
+            request_id.0.clone(),
+        ));
+    }
+
+    let runs = control_plane
+        .list_export_runs(schedule_id, RUN_HISTORY_LIMIT)
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(runs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_object_uri() {
+        let at = chrono::Utc.with_ymd_and_hms(2026, 1, 15, 2, 0, 0).unwrap();
+        assert_eq!(
+            object_uri("gs://acme-exports/metafuse/", ExportFormat::Json, at),
+            "gs://acme-exports/metafuse/catalog-20260115T020000Z.json"
+        );
+        assert_eq!(
+            object_uri("s3://acme/dumps?region=eu-west-1", ExportFormat::Ndjson, at),
+            "s3://acme/dumps/catalog-20260115T020000Z.ndjson?region=eu-west-1"
+        );
+    }
+
+    #[test]
+    fn test_validate_target_uri() {
+        assert!(validate_target_uri("gs://acme-exports/metafuse").is_ok());
+        assert!(validate_target_uri("s3://acme/dumps?region=eu-west-1").is_ok());
+        assert!(validate_target_uri("file:///var/exports/acme").is_ok());
+        assert!(validate_target_uri("/var/exports/acme").is_err());
+        assert!(validate_target_uri("https://example.com/upload").is_err());
+        assert!(validate_target_uri("gs://").is_err());
+        assert!(validate_target_uri("file:///var/../etc").is_err());
+    }
+
+    #[test]
+    fn test_parse_run_time() {
+        assert_eq!(
+            parse_run_time("2026-01-15T03:00:00+01:00").unwrap(),
+            "2026-01-15 02:00:00"
+        );
+        assert!(parse_run_time("tomorrow").is_err());
+    }
+
+    #[test]
+    fn test_profile_credentials() {
+        let s3: ProfileCredentials = serde_json::from_value(serde_json::json!({
+            "provider": "s3",
+            "access_key_id": "AKIAEXAMPLE",
+            "secret_access_key": "wJalrXUtnFEMI"
+        }))
+        .unwrap();
+        assert_eq!(s3.provider(), "s3");
+        assert!(s3.validate().is_ok());
+        assert!(!format!("{:?}", s3).contains("wJalrXUtnFEMI"));
+
+        let gcs: ProfileCredentials = serde_json::from_value(serde_json::json!({
+            "provider": "gcs",
+            "service_account_key": {"type": "service_account", "private_key": "-----BEGIN"}
+        }))
+        .unwrap();
+        assert!(gcs.validate().is_ok());
+        let empty: ProfileCredentials = serde_json::from_value(serde_json::json!({
+            "provider": "gcs",
+            "service_account_key": {}
+        }))
+        .unwrap();
+        assert!(empty.validate().is_err());
+        assert!(serde_json::from_value::<ProfileCredentials>(
+            serde_json::json!({"provider": "azure"})
+        )
+        .is_err());
+
+        assert!(check_profile_target("exports", "s3", "s3://acme/dumps").is_ok());
+        assert!(check_profile_target("exports", "s3", "gs://acme/dumps").is_err());
+        assert!(check_profile_target("exports", "gcs", "file:///var/exports").is_err());
+        assert!(validate_profile_name("acme-exports.v2").is_ok());
+        assert!(validate_profile_name("acme/exports").is_err());
+        assert!(validate_profile_name("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_export_writes_bundle() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = metafuse_catalog_storage::backend_from_uri(
+            dir.path().join("catalog.db").to_str().unwrap(),
+        )
+        .unwrap();
+        backend.initialize().await.unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&backend.get_connection().await.unwrap())
+            .unwrap();
+        let schedule = ExportSchedule {
+            id: 1,
+            tenant_id: "acme".to_string(),
+            target_uri: format!("file://{}", dir.path().join("exports").display()),
+            format: ExportFormat::Ndjson,
+            frequency: ExportFrequency::Daily,
+            enabled: true,
+            credentials_profile: None,
+            next_run_at: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        let output = run_export(backend.as_ref(), &schedule, None).await.unwrap();
+        assert!(output.object_uri.ends_with(".ndjson"));
+        let written =
+            std::fs::read_to_string(output.object_uri.trim_start_matches("file://")).unwrap();
+        assert_eq!(written.len(), output.size_bytes);
+        assert!(bundle::CatalogBundle::from_ndjson(&written).is_ok());
+    }
+
+    #[test]
+    fn test_credentials_key() {
+        for invalid in ["not base64!", "c2hvcnQ="] {
+            assert!(CredentialsKey::from_base64(invalid).is_err());
+        }
+        let key =
+            CredentialsKey::from_base64("BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=").unwrap();
+        assert_eq!(format!("{:?}", key), "CredentialsKey(..)");
+        let credentials = ProfileCredentials::S3 {
+            access_key_id: "AKIAEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+        };
+
+        let sealed = key.seal("acme", "exports", &credentials).unwrap();
+        assert!(!sealed.contains("AKIAEXAMPLE"));
+        assert_ne!(sealed, key.seal("acme", "exports", &credentials).unwrap());
+        assert_eq!(key.open("acme", "exports", &sealed).unwrap(), credentials);
+
+        // Bound to the profile, and never read back unencrypted
+        assert!(key.open("other", "exports", &sealed).is_err());
+        assert!(key.open("acme", "backups", &sealed).is_err());
+        let json = serde_json::to_string(&credentials).unwrap();
+        assert!(key.open("acme", "exports", &json).is_err());
+    }
+}
@@ -0,0 +1,393 @@
+// Allow dead_code for public API items that are used by library consumers
+// but not within the binary for every feature combination
+#![allow(dead_code)]
+
+//! External URL Resolution
+//!
+//! Builds self-referencing URLs (pagination links, resource locations) that are
+//! correct when the API is deployed behind a reverse proxy or path-prefixed ingress.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_BASE_PATH`: Path prefix the API is served under (e.g., `/catalog`).
+//!   All routes are nested under this prefix. Default: empty (served at `/`).
+//! - `METAFUSE_TRUSTED_PROXIES`: Comma-separated list of trusted proxy IPs. Shared with
+//!   rate limiting; `X-Forwarded-Proto` and `X-Forwarded-Host` are only honored when
+//!   the immediate peer is in this list.
+//!
+//! # Security
+//!
+//! Forwarded headers are attacker-controlled unless they were set by a proxy we trust.
+//! Without a trusted proxy match, the scheme defaults to `http` and the host comes from
+//! the `Host` header, which the client had to use to reach us in the first place.
+
+use axum::{
+    extract::{ConnectInfo, Extension, Request},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Header carrying the original request scheme from a proxy
+pub const FORWARDED_PROTO_HEADER: &str = "x-forwarded-proto";
+
+/// Header carrying the original request host from a proxy
+pub const FORWARDED_HOST_HEADER: &str = "x-forwarded-host";
+
+/// Configuration for external URL generation.
+#[derive(Debug, Clone, Default)]
+pub struct ExternalUrlConfig {
+    /// Normalized base path (empty, or `/segment[/segment...]` without trailing slash)
+    pub base_path: String,
+    /// Proxy IPs whose forwarded headers are trusted
+    pub trusted_proxies: Option<Vec<String>>,
+}
+
+impl ExternalUrlConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_BASE_PATH`: Path prefix (default: empty)
+    /// - `METAFUSE_TRUSTED_PROXIES`: Comma-separated trusted proxy IPs
+    pub fn from_env() -> Result<Self, String> {
+        let base_path =
+            normalize_base_path(&std::env::var("METAFUSE_BASE_PATH").unwrap_or_default())?;
+
+        let trusted_proxies = std::env::var("METAFUSE_TRUSTED_PROXIES")
+            .ok()
+            .map(|s| s.split(',').map(|ip| ip.trim().to_string()).collect());
+
+        Ok(Self {
+            base_path,
+            trusted_proxies,
+        })
+    }
+
+    /// Check whether forwarded headers from this peer should be honored.
+    pub fn is_trusted_proxy(&self, peer: Option<IpAddr>) -> bool {
+        match (&self.trusted_proxies, peer) {
+            (Some(proxies), Some(ip)) => proxies.contains(&ip.to_string()),
+            _ => false,
+        }
+    }
+}
+
+/// Normalize a configured base path.
+///
+/// Adds a leading slash, strips trailing slashes, and rejects characters that
+/// are not valid in a route prefix. `""` and `"/"` both mean "no base path".
+pub fn normalize_base_path(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return Ok(String::new());
+    }
+
+    for segment in trimmed.split('/') {
+        if segment.is_empty() {
+            return Err(format!("Invalid base path '{}': empty path segment", raw));
+        }
+        if segment == "." || segment == ".." {
+            return Err(format!("Invalid base path '{}': relative segment", raw));
+        }
+        if !segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~'))
+        {
+            return Err(format!(
+                "Invalid base path '{}': only letters, digits, '-', '_', '.', '~' are allowed",
+                raw
+            ));
+        }
+    }
+
+    Ok(format!("/{}", trimmed))
+}
+
+/// External URL context for the current request.
+///
+/// Inserted into request extensions by [`external_url_middleware`].
+#[derive(Debug, Clone, Default)]
+pub struct ExternalUrl {
+    scheme: String,
+    host: Option<String>,
+    base_path: String,
+}
+
+impl ExternalUrl {
+    /// Resolve the external URL from request headers and the immediate peer.
+    pub fn resolve(headers: &HeaderMap, peer: Option<IpAddr>, config: &ExternalUrlConfig) -> Self {
+        let trusted = config.is_trusted_proxy(peer);
+
+        let forwarded_proto = trusted
+            .then(|| header_value(headers, FORWARDED_PROTO_HEADER))
+            .flatten()
+            .map(|p| p.to_ascii_lowercase())
+            .filter(|p| p == "http" || p == "https");
+
+        let forwarded_host = trusted
+            .then(|| header_value(headers, FORWARDED_HOST_HEADER))
+            .flatten()
+            .filter(|h| is_valid_host(h));
+
+        let host = forwarded_host
+            .or_else(|| header_value(headers, header::HOST.as_str()).filter(|h| is_valid_host(h)));
+
+        Self {
+            scheme: forwarded_proto.unwrap_or_else(|| "http".to_string()),
+            host,
+            base_path: config.base_path.clone(),
+        }
+    }
+
+    /// Prefix a route path with the base path (e.g., `/api/v1/audit` -> `/catalog/api/v1/audit`).
+    pub fn path(&self, path: &str) -> String {
+        format!("{}{}", self.base_path, path)
+    }
+
+    /// Strip the base path from a request path (e.g., `/catalog/api/v1/audit` -> `/api/v1/audit`).
+    pub fn route_path<'a>(&self, path: &'a str) -> &'a str {
+        path.strip_prefix(self.base_path.as_str())
+            .filter(|p| p.starts_with('/'))
+            .unwrap_or(path)
+    }
+
+    /// Build an absolute URL for a route path.
+    ///
+    /// Falls back to a base-path-relative path if the host is unknown.
+    pub fn url(&self, path: &str) -> String {
+        match &self.host {
+            Some(host) => format!("{}://{}{}", self.scheme, host, self.path(path)),
+            None => self.path(path),
+        }
+    }
+
+    /// Build a URL for a page of results, preserving other query parameters.
+    pub fn page_url(&self, path: &str, query: Option<&str>, limit: i64, offset: i64) -> String {
+        let mut params: Vec<&str> = query
+            .unwrap_or_default()
+            .split('&')
+            .filter(|p| !p.is_empty() && !p.starts_with("limit=") && !p.starts_with("offset="))
+            .collect();
+        let paging = format!("limit={}&offset={}", limit, offset);
+        params.push(&paging);
+        format!("{}?{}", self.url(path), params.join("&"))
+    }
+}
+
+/// Pagination links for limit/offset list responses.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaginationLinks {
+    #[serde(rename = "self")]
+    pub self_link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+}
+
+impl PaginationLinks {
+    /// Build links for the current page of a limit/offset listing.
+    pub fn new(
+        url: &ExternalUrl,
+        path: &str,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+        total: i64,
+    ) -> Self {
+        let next = (limit > 0 && offset + limit < total)
+            .then(|| url.page_url(path, query, limit, offset + limit));
+        let prev = (offset > 0).then(|| url.page_url(path, query, limit, (offset - limit).max(0)));
+
+        Self {
+            self_link: url.page_url(path, query, limit, offset),
+            next,
+            prev,
+        }
+    }
+}
+
+/// Get a trimmed, non-empty header value as a string.
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        // Proxy chains may append values; the first one is the client-facing value
+        .and_then(|v| v.split(',').next())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Reject hosts that could inject paths, credentials, or whitespace into URLs.
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+}
+
+/// Middleware that attaches [`ExternalUrl`] to request extensions.
+///
+/// Requires `Extension<Arc<ExternalUrlConfig>>`. The peer address is read from
+/// `ConnectInfo<SocketAddr>` when the server was started with connect info.
+pub async fn external_url_middleware(
+    Extension(config): Extension<Arc<ExternalUrlConfig>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ci| ci.0.ip());
+
+    let external_url = ExternalUrl::resolve(req.headers(), peer, &config);
+    debug!(
+        scheme = %external_url.scheme,
+        host = ?external_url.host,
+        base_path = %external_url.base_path,
+        "Resolved external URL"
+    );
+    req.extensions_mut().insert(external_url);
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn config(base_path: &str, proxies: Option<&[&str]>) -> ExternalUrlConfig {
+        ExternalUrlConfig {
+            base_path: normalize_base_path(base_path).unwrap(),
+            trusted_proxies: proxies.map(|p| p.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_normalize_base_path() {
+        assert_eq!(normalize_base_path("").unwrap(), "");
+        assert_eq!(normalize_base_path("/").unwrap(), "");
+        assert_eq!(normalize_base_path("catalog").unwrap(), "/catalog");
+        assert_eq!(normalize_base_path("/catalog/").unwrap(), "/catalog");
+        assert_eq!(
+            normalize_base_path("/data/catalog").unwrap(),
+            "/data/catalog"
+        );
+
+        assert!(normalize_base_path("/data//catalog").is_err());
+        assert!(normalize_base_path("/../catalog").is_err());
+        assert!(normalize_base_path("/catalog?x=1").is_err());
+        assert!(normalize_base_path("/cat alog").is_err());
+    }
+
+    #[test]
+    fn test_forwarded_headers_from_trusted_proxy() {
+        let cfg = config("/catalog", Some(&["10.0.0.1"]));
+        let h = headers(&[
+            ("host", "metafuse-api:8080"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "data.example.com"),
+        ]);
+
+        let url = ExternalUrl::resolve(&h, Some("10.0.0.1".parse().unwrap()), &cfg);
+        assert_eq!(
+            url.url("/api/v1/audit"),
+            "https://data.example.com/catalog/api/v1/audit"
+        );
+    }
+
+    #[test]
+    fn test_forwarded_headers_ignored_from_untrusted_peer() {
+        let cfg = config("/catalog", Some(&["10.0.0.1"]));
+        let h = headers(&[
+            ("host", "metafuse-api:8080"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "evil.example.com"),
+        ]);
+
+        let url = ExternalUrl::resolve(&h, Some("203.0.113.9".parse().unwrap()), &cfg);
+        assert_eq!(
+            url.url("/api/v1/audit"),
+            "http://metafuse-api:8080/catalog/api/v1/audit"
+        );
+
+        // No trusted proxies configured at all
+        let url = ExternalUrl::resolve(&h, None, &config("", None));
+        assert_eq!(url.url("/health"), "http://metafuse-api:8080/health");
+    }
+
+    #[test]
+    fn test_invalid_forwarded_values_rejected() {
+        let cfg = config("", Some(&["10.0.0.1"]));
+        let h = headers(&[
+            ("host", "metafuse-api"),
+            ("x-forwarded-proto", "javascript"),
+            ("x-forwarded-host", "evil.com/phish"),
+        ]);
+
+        let url = ExternalUrl::resolve(&h, Some("10.0.0.1".parse().unwrap()), &cfg);
+        assert_eq!(url.url("/x"), "http://metafuse-api/x");
+    }
+
+    #[test]
+    fn test_relative_url_without_host() {
+        let url = ExternalUrl::resolve(&HeaderMap::new(), None, &config("/catalog", None));
+        assert_eq!(url.url("/api/v1/audit"), "/catalog/api/v1/audit");
+    }
+
+    #[test]
+    fn test_route_path_strips_base_path() {
+        let url = ExternalUrl::resolve(&HeaderMap::new(), None, &config("/catalog", None));
+        assert_eq!(url.route_path("/catalog/api/v1/audit"), "/api/v1/audit");
+        assert_eq!(url.route_path("/catalogue/x"), "/catalogue/x");
+        assert_eq!(
+            ExternalUrl::default().route_path("/api/v1/audit"),
+            "/api/v1/audit"
+        );
+    }
+
+    #[test]
+    fn test_pagination_links() {
+        let url = ExternalUrl::resolve(&HeaderMap::new(), None, &config("/catalog", None));
+
+        let links = PaginationLinks::new(
+            &url,
+            "/api/v1/audit",
+            Some("action=create&limit=10&offset=10"),
+            10,
+            10,
+            25,
+        );
+        assert_eq!(
+            links.self_link,
+            "/catalog/api/v1/audit?action=create&limit=10&offset=10"
+        );
+        assert_eq!(
+            links.next.as_deref(),
+            Some("/catalog/api/v1/audit?action=create&limit=10&offset=20")
+        );
+        assert_eq!(
+            links.prev.as_deref(),
+            Some("/catalog/api/v1/audit?action=create&limit=10&offset=0")
+        );
+
+        let last = PaginationLinks::new(&url, "/api/v1/audit", None, 10, 20, 25);
+        assert!(last.next.is_none());
+
+        let first = PaginationLinks::new(&url, "/api/v1/audit", None, 10, 0, 25);
+        assert!(first.prev.is_none());
+    }
+}
@@ -0,0 +1,155 @@
+//! Field-Level Write Permissions
+//!
+//! Tenant roles decide whether a caller may write at all; this narrows which
+//! dataset fields each role may change. A write that touches a field the
+//! caller's role may not change is rejected with `403` naming the fields.
+//!
+//! Restrictions apply to dataset updates (`PUT /api/v1/datasets/{name}`),
+//! custom metadata patches, and the tag endpoints, where adding or removing
+//! the `certified` tag counts as the `certification` field. Values set when a
+//! dataset is created are not restricted.
+//!
+//! # Configuration
+//!
+//! By default `owner` and `certification` require the admin role and every
+//! other field only write permission. `METAFUSE_FIELD_WRITE_ROLES` adjusts the
+//! matrix with comma-separated `field=role` entries, where role is `editor` or
+//! `admin`, e.g. `owner=editor,domain=admin`. Unknown fields or roles stop the
+//! server at startup.
+
+use std::collections::BTreeMap;
+
+/// Fields whose write role can be configured
+pub const FIELDS: &[&str] = &[
+    "path",
+    "format",
+    "delta_location",
+    "description",
+    "tenant",
+    "domain",
+    "owner",
+    "custom_metadata",
+    "certification",
+];
+
+/// Minimum role needed to change a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldRole {
+    Editor,
+    Admin,
+}
+
+impl FieldRole {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "editor" => Some(FieldRole::Editor),
+            "admin" => Some(FieldRole::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Per-field write permissions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldPermissions {
+    /// Fields needing more than write permission
+    required: BTreeMap<&'static str, FieldRole>,
+}
+
+impl Default for FieldPermissions {
+    fn default() -> Self {
+        Self {
+            required: BTreeMap::from([
+                ("owner", FieldRole::Admin),
+                ("certification", FieldRole::Admin),
+            ]),
+        }
+    }
+}
+
+impl FieldPermissions {
+    /// Parse `field=role` entries on top of the defaults.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut permissions = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (field, role) = entry.split_once('=').ok_or_else(|| {
+                format!("Invalid field permission '{}': expected field=role", entry)
+            })?;
+            let field = FIELDS
+                .iter()
+                .find(|f| **f == field.trim())
+                .ok_or_else(|| format!("Unknown field '{}' in field permissions", field.trim()))?;
+            let role = FieldRole::parse(role.trim()).ok_or_else(|| {
+                format!(
+                    "Invalid role '{}' for field '{}': expected 'editor' or 'admin'",
+                    role.trim(),
+                    field
+                )
+            })?;
+            permissions.required.insert(field, role);
+        }
+        Ok(permissions)
+    }
+
+    /// Create from `METAFUSE_FIELD_WRITE_ROLES`, or the defaults when unset.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("METAFUSE_FIELD_WRITE_ROLES") {
+            Ok(spec) => {
+                Self::parse(&spec).map_err(|e| format!("Invalid METAFUSE_FIELD_WRITE_ROLES: {}", e))
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Role required to change a field
+    pub fn required_role(&self, field: &str) -> FieldRole {
+        self.required
+            .get(field)
+            .copied()
+            .unwrap_or(FieldRole::Editor)
+    }
+
+    /// The given fields that a tenant role (`viewer`, `editor`, `admin`) may
+    /// not change, in input order. Viewers may change none.
+    pub fn restricted<'a>(
+        &self,
+        role: &str,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<&'a str> {
+        let rank = FieldRole::parse(role);
+        fields
+            .into_iter()
+            .filter(|field| rank.is_none_or(|rank| rank < self.required_role(field)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matrix() {
+        let permissions = FieldPermissions::default();
+        let fields = ["description", "owner", "certification"];
+        assert_eq!(
+            permissions.restricted("editor", fields),
+            vec!["owner", "certification"]
+        );
+        assert!(permissions.restricted("admin", fields).is_empty());
+        assert_eq!(permissions.restricted("viewer", fields), fields.to_vec());
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let permissions = FieldPermissions::parse(" owner=editor, domain=admin ,").unwrap();
+        assert_eq!(
+            permissions.restricted("editor", ["owner", "domain", "certification"]),
+            vec!["domain", "certification"]
+        );
+
+        assert!(FieldPermissions::parse("owner").is_err());
+        assert!(FieldPermissions::parse("colour=admin").is_err());
+        assert!(FieldPermissions::parse("owner=viewer").is_err());
+    }
+}
@@ -0,0 +1,338 @@
+//! Storage Format Advisor
+//!
+//! Flags row-oriented text datasets (CSV, JSON) that are worth converting to
+//! a columnar format: large ones, where compression saves storage, and
+//! frequently read ones, where every scan reads the whole file.
+//!
+//! Frequently read datasets are pointed at Delta (file statistics for
+//! skipping, compaction, time travel); the rest at Parquet. Savings are
+//! estimated from typical compression ratios and are only a guide; reads come
+//! from `usage_stats`, so they are zero unless usage analytics is enabled.
+//! Placeholders and trashed datasets are never flagged, nor are datasets
+//! hidden from the caller by dataset ACLs.
+
+use crate::dataset_acl;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::{formats, placeholders, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Default size above which a dataset is flagged (1 GiB)
+pub const DEFAULT_MIN_SIZE_BYTES: i64 = 1 << 30;
+
+/// Default reads in the period above which a dataset is flagged
+pub const DEFAULT_MIN_READS: i64 = 100;
+
+/// Default days of usage counted as reads
+pub const DEFAULT_PERIOD_DAYS: i64 = 30;
+
+/// Formats the advisor recommends converting
+pub const CONVERTIBLE_FORMATS: &[&str] = &["csv", "json"];
+
+/// Expected columnar size as a fraction of the source size
+fn columnar_size_ratio(format: &str) -> f64 {
+    match format {
+        // Field names repeat in every record
+        "json" => 0.15,
+        _ => 0.25,
+    }
+}
+
+/// Thresholds for flagging a dataset; either one is enough
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdvisorThresholds {
+    pub min_size_bytes: i64,
+    pub min_reads: i64,
+    pub period_days: i64,
+}
+
+impl Default for AdvisorThresholds {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: DEFAULT_MIN_SIZE_BYTES,
+            min_reads: DEFAULT_MIN_READS,
+            period_days: DEFAULT_PERIOD_DAYS,
+        }
+    }
+}
+
+/// A dataset that should move to a columnar format
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormatRecommendation {
+    pub dataset_id: i64,
+    pub dataset_name: String,
+    pub current_format: String,
+    /// `parquet` or `delta`
+    pub recommended_format: &'static str,
+    /// Why it was flagged: `large`, `frequently_read`
+    pub reasons: Vec<&'static str>,
+    pub size_bytes: Option<i64>,
+    pub reads: i64,
+    /// Estimated size after conversion (unknown without `size_bytes`)
+    pub estimated_size_bytes: Option<i64>,
+    /// Estimated storage saved by converting
+    pub estimated_savings_bytes: Option<i64>,
+    /// Estimated bytes no longer scanned over the period, assuming each
+    /// read scans the whole dataset
+    pub estimated_scan_savings_bytes: Option<i64>,
+}
+
+/// Response for the recommendations endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendationsResponse {
+    pub period_days: i64,
+    pub format_conversions: Vec<FormatRecommendation>,
+}
+
+/// CSV and JSON datasets over either threshold, largest estimated savings first.
+///
+/// `visibility` is an extra SQL condition on the dataset alias `d` hiding
+/// datasets from the caller, with its bindings.
+pub fn find_format_recommendations(
+    conn: &Connection,
+    tenant_id: &str,
+    thresholds: AdvisorThresholds,
+    visibility: Option<(String, Vec<String>)>,
+) -> Result<Vec<FormatRecommendation>> {
+    let not_placeholder = if placeholders::has_status_column(conn)? {
+        format!("AND d.status != '{}'", placeholders::STATUS_PENDING)
+    } else {
+        String::new()
+    };
+    let formats = CONVERTIBLE_FORMATS
+        .iter()
+        .map(|f| format!("'{}'", f))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut bindings = vec![
+        tenant_id.to_string(),
+        format!("-{} days", thresholds.period_days),
+    ];
+    let visible = match visibility {
+        Some((clause, values)) => {
+            bindings.extend(values);
+            format!("AND {}", clause)
+        }
+        None => String::new(),
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT d.id, d.name, d.format, d.size_bytes,
+                COALESCE((SELECT SUM(u.read_count) FROM usage_stats u
+                          WHERE u.dataset_id = d.id AND u.tenant_id = ?1
+                            AND u.stat_date >= date('now', ?2)), 0)
+         FROM datasets d
+         WHERE d.deleted_at IS NULL AND d.format IN ({}) {} {}
+         ORDER BY d.id",
+        formats, not_placeholder, visible
+    ))?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut recommendations: Vec<FormatRecommendation> = rows
+        .into_iter()
+        .filter_map(|(dataset_id, dataset_name, format, size_bytes, reads)| {
+            recommend(
+                dataset_id,
+                dataset_name,
+                format,
+                size_bytes,
+                reads,
+                thresholds,
+            )
+        })
+        .collect();
+    recommendations.sort_by_key(|r| {
+        (
+            std::cmp::Reverse(r.estimated_savings_bytes.unwrap_or(0)),
+            std::cmp::Reverse(r.reads),
+        )
+    });
+    Ok(recommendations)
+}
+
+fn recommend(
+    dataset_id: i64,
+    dataset_name: String,
+    format: String,
+    size_bytes: Option<i64>,
+    reads: i64,
+    thresholds: AdvisorThresholds,
+) -> Option<FormatRecommendation> {
+    // Canonical names only; aliases are normalized on write
+    formats::lookup_format(&format).filter(|spec| CONVERTIBLE_FORMATS.contains(&spec.name))?;
+
+    let large = size_bytes.is_some_and(|size| size >= thresholds.min_size_bytes);
+    let frequently_read = reads >= thresholds.min_reads;
+    let reasons: Vec<&'static str> = [("large", large), ("frequently_read", frequently_read)]
+        .into_iter()
+        .filter_map(|(reason, flagged)| flagged.then_some(reason))
+        .collect();
+    if reasons.is_empty() {
+        return None;
+    }
+
+    let estimated_size_bytes =
+        size_bytes.map(|size| (size as f64 * columnar_size_ratio(&format)).round() as i64);
+    let estimated_savings_bytes = size_bytes.zip(estimated_size_bytes).map(|(s, e)| s - e);
+    Some(FormatRecommendation {
+        dataset_id,
+        dataset_name,
+        current_format: format,
+        recommended_format: if frequently_read { "delta" } else { "parquet" },
+        reasons,
+        size_bytes,
+        reads,
+        estimated_size_bytes,
+        estimated_savings_bytes,
+        estimated_scan_savings_bytes: estimated_savings_bytes
+            .map(|saved| saved.saturating_mul(reads)),
+    })
+}
+
+/// Query parameters for recommendations endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecommendationsQueryParams {
+    #[serde(default = "default_min_size_bytes")]
+    min_size_bytes: i64,
+    #[serde(default = "default_min_reads")]
+    min_reads: i64,
+    #[serde(default = "default_recommendation_period_days")]
+    period_days: i64,
+}
+
+pub(crate) fn default_min_size_bytes() -> i64 {
+    DEFAULT_MIN_SIZE_BYTES
+}
+
+pub(crate) fn default_min_reads() -> i64 {
+    DEFAULT_MIN_READS
+}
+
+pub(crate) fn default_recommendation_period_days() -> i64 {
+    DEFAULT_PERIOD_DAYS
+}
+
+/// Recommend converting large or frequently read CSV/JSON datasets
+pub(crate) async fn get_recommendations(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<RecommendationsQueryParams>,
+) -> std::result::Result<Json<RecommendationsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if params.min_size_bytes < 1 || params.min_reads < 1 || params.period_days < 1 {
+        return Err(bad_request(
+            "min_size_bytes, min_reads, and period_days must be at least 1".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id().to_string())
+        .unwrap_or_else(|| "default".to_string());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.0.clone();
+    let thresholds = AdvisorThresholds {
+        min_size_bytes: params.min_size_bytes,
+        min_reads: params.min_reads,
+        period_days: params.period_days,
+    };
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+    let format_conversions = tokio::task::spawn_blocking(move || {
+        find_format_recommendations(&conn, &tenant_id, thresholds, visibility)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        format_conversions = format_conversions.len(),
+        "Recommendations query completed"
+    );
+
+    Ok(Json(RecommendationsResponse {
+        period_days: params.period_days,
+        format_conversions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_format_recommendations() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        for (name, format, size) in [
+            ("big_csv", "csv", Some(4_000)),
+            ("hot_json", "json", Some(100)),
+            ("small_csv", "csv", Some(100)),
+            ("big_parquet", "parquet", Some(4_000)),
+            ("unsized_csv", "csv", None),
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, size_bytes, created_at, last_updated)
+                 VALUES (?1, '/data', ?2, ?3, datetime('now'), datetime('now'))",
+                rusqlite::params![name, format, size],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO usage_stats (dataset_id, stat_date, read_count, tenant_id)
+             VALUES (2, date('now'), 60, 'default'), (2, date('now', '-1 day'), 60, 'default'),
+                    (2, date('now', '-60 days'), 500, 'default'), (3, date('now'), 500, 'other');",
+        )
+        .unwrap();
+
+        let thresholds = AdvisorThresholds {
+            min_size_bytes: 1_000,
+            min_reads: 100,
+            period_days: 30,
+        };
+        let recs = find_format_recommendations(&conn, "default", thresholds, None).unwrap();
+        let names: Vec<&str> = recs.iter().map(|r| r.dataset_name.as_str()).collect();
+        assert_eq!(names, vec!["big_csv", "hot_json"]);
+
+        assert_eq!(recs[0].recommended_format, "parquet");
+        assert_eq!(recs[0].reasons, vec!["large"]);
+        assert_eq!(recs[0].estimated_size_bytes, Some(1_000));
+        assert_eq!(recs[0].estimated_savings_bytes, Some(3_000));
+        assert_eq!(recs[0].estimated_scan_savings_bytes, Some(0));
+
+        assert_eq!(recs[1].recommended_format, "delta");
+        assert_eq!(recs[1].reasons, vec!["frequently_read"]);
+        assert_eq!(recs[1].reads, 120);
+        assert_eq!(recs[1].estimated_savings_bytes, Some(85));
+        assert_eq!(recs[1].estimated_scan_savings_bytes, Some(85 * 120));
+
+        // Another tenant's reads make small_csv hot for that tenant only
+        let other = find_format_recommendations(&conn, "other", thresholds, None).unwrap();
+        let names: Vec<&str> = other.iter().map(|r| r.dataset_name.as_str()).collect();
+        assert_eq!(names, vec!["big_csv", "small_csv"]);
+    }
+}
@@ -0,0 +1,581 @@
+//! Relative Freshness Annotations
+//!
+//! Computes `age_seconds` and `is_stale` for datasets in list, detail, and
+//! search responses, so clients don't each re-derive "updated 3 hours ago".
+//!
+//! Age is measured from the dataset's `last_updated` timestamp. A dataset is
+//! stale when its age exceeds `expected_interval_secs + grace_period_secs`
+//! from `freshness_config`; datasets without a freshness SLA report no
+//! staleness.
+//!
+//! `last_seen_at` is the emitter heartbeat (migration v1.23.0): the last
+//! emission, even one skipped because the metadata was unchanged.
+//!
+//! # Dependency Checks
+//!
+//! [`check_datasets`] backs `POST /api/v1/freshness/check`: orchestrators pass
+//! a job's upstreams and gate the run on the combined `ready` verdict.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_acl;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{accessible_dataset_id, not_found, AuditContext, DatasetPath};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use crate::strict_json::JsonBody;
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use metafuse_catalog_core::validation;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Freshness SLA for a dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreshnessSla {
+    pub expected_interval_secs: i64,
+    pub grace_period_secs: i64,
+}
+
+impl FreshnessSla {
+    /// Age after which the dataset is stale
+    pub fn threshold_secs(&self) -> i64 {
+        self.expected_interval_secs + self.grace_period_secs
+    }
+}
+
+/// Freshness annotation attached to dataset responses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Freshness {
+    /// Seconds since `last_updated`
+    pub age_seconds: i64,
+    /// Whether the age exceeds the SLA; `None` without a freshness SLA
+    pub is_stale: Option<bool>,
+    /// Expected update interval plus grace period, when configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sla_secs: Option<i64>,
+    /// Last emission of the dataset, when the emitter has recorded one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_at: Option<String>,
+}
+
+impl Freshness {
+    /// Annotate a `last_updated` timestamp. Returns `None` if it can't be parsed.
+    pub fn compute(
+        last_updated: &str,
+        sla: Option<FreshnessSla>,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let updated = parse_timestamp(last_updated)?;
+        let age_seconds = (now - updated).num_seconds().max(0);
+        Some(Self {
+            age_seconds,
+            is_stale: sla.map(|sla| age_seconds > sla.threshold_secs()),
+            sla_secs: sla.map(|sla| sla.threshold_secs()),
+            last_seen_at: None,
+        })
+    }
+}
+
+/// Load freshness SLAs for the given datasets.
+pub fn load_slas(
+    conn: &Connection,
+    dataset_ids: &[i64],
+) -> rusqlite::Result<HashMap<i64, FreshnessSla>> {
+    let mut slas = HashMap::new();
+    // Chunked to stay under SQLite's bound parameter limit
+    for chunk in dataset_ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT dataset_id, expected_interval_secs, grace_period_secs
+             FROM freshness_config WHERE dataset_id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                FreshnessSla {
+                    expected_interval_secs: row.get(1)?,
+                    grace_period_secs: row.get(2)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (id, sla) = row?;
+            slas.insert(id, sla);
+        }
+    }
+    Ok(slas)
+}
+
+/// Most datasets accepted by one freshness check
+pub const MAX_CHECK_DATASETS: usize = 500;
+
+/// Request to check a set of datasets before a run
+#[derive(Debug, Clone, Deserialize)]
+pub struct FreshnessCheckRequest {
+    /// Dataset names to check
+    pub datasets: Vec<String>,
+    /// Treat datasets without a freshness SLA as not ready
+    #[serde(default)]
+    pub require_sla: bool,
+}
+
+/// Freshness status of one checked dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshnessStatus {
+    /// Within its SLA
+    Fresh,
+    /// Older than its SLA
+    Stale,
+    /// No freshness SLA configured
+    NoSla,
+    /// Not in the catalog (or in the trash)
+    NotFound,
+}
+
+/// Result for one checked dataset
+#[derive(Debug, Clone, Serialize)]
+pub struct FreshnessCheckEntry {
+    pub dataset: String,
+    pub status: FreshnessStatus,
+    /// Whether this dataset lets the run proceed
+    pub ready: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sla_secs: Option<i64>,
+}
+
+/// Combined result of a freshness check
+#[derive(Debug, Clone, Serialize)]
+pub struct FreshnessCheckResponse {
+    /// True when every dataset is ready
+    pub ready: bool,
+    pub checked_at: String,
+    /// One entry per requested dataset, in request order
+    pub datasets: Vec<FreshnessCheckEntry>,
+}
+
+/// Check each dataset against its freshness SLA.
+///
+/// Missing and stale datasets are never ready; datasets without an SLA are
+/// ready unless `require_sla` is set. Repeated names are checked once.
+pub fn check_datasets(
+    conn: &Connection,
+    names: &[String],
+    require_sla: bool,
+    now: DateTime<Utc>,
+) -> rusqlite::Result<FreshnessCheckResponse> {
+    let mut stmt = conn.prepare(
+        "SELECT d.last_updated, f.expected_interval_secs, f.grace_period_secs
+         FROM datasets d
+         LEFT JOIN freshness_config f ON f.dataset_id = d.id
+         WHERE d.name = ?1 AND d.deleted_at IS NULL",
+    )?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut datasets = Vec::new();
+    for name in names {
+        if !seen.insert(name.as_str()) {
+            continue;
+        }
+        let row = stmt
+            .query_row([name], |row| {
+                let last_updated: String = row.get(0)?;
+                let expected: Option<i64> = row.get(1)?;
+                let grace: Option<i64> = row.get(2)?;
+                let sla = expected.map(|expected_interval_secs| FreshnessSla {
+                    expected_interval_secs,
+                    grace_period_secs: grace.unwrap_or(0),
+                });
+                Ok((last_updated, sla))
+            })
+            .optional()?;
+
+        let entry = match row {
+            None => FreshnessCheckEntry {
+                dataset: name.clone(),
+                status: FreshnessStatus::NotFound,
+                ready: false,
+                last_updated: None,
+                age_seconds: None,
+                sla_secs: None,
+            },
+            Some((last_updated, sla)) => {
+                let freshness = Freshness::compute(&last_updated, sla, now);
+                let status = match sla {
+                    None => FreshnessStatus::NoSla,
+                    // An unparseable timestamp can't be shown to be fresh
+                    Some(_) => match freshness.as_ref().and_then(|f| f.is_stale) {
+                        Some(false) => FreshnessStatus::Fresh,
+                        _ => FreshnessStatus::Stale,
+                    },
+                };
+                FreshnessCheckEntry {
+                    dataset: name.clone(),
+                    status,
+                    ready: status == FreshnessStatus::Fresh
+                        || (status == FreshnessStatus::NoSla && !require_sla),
+                    last_updated: Some(last_updated),
+                    age_seconds: freshness.as_ref().map(|f| f.age_seconds),
+                    sla_secs: sla.map(|sla| sla.threshold_secs()),
+                }
+            }
+        };
+        datasets.push(entry);
+    }
+
+    Ok(FreshnessCheckResponse {
+        ready: datasets.iter().all(|d| d.ready),
+        checked_at: now.to_rfc3339(),
+        datasets,
+    })
+}
+
+/// Parse an RFC 3339 or SQLite `datetime()` timestamp as UTC.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|ts| ts.and_utc())
+}
+
+/// Check whether a set of datasets (e.g. a job's upstreams) are fresh
+pub(crate) async fn check_freshness(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    JsonBody(req): JsonBody<FreshnessCheckRequest>,
+) -> Result<Json<FreshnessCheckResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, count = req.datasets.len(), "Checking dataset freshness");
+
+    if req.datasets.is_empty() {
+        return Err(bad_request(
+            "datasets must not be empty".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+    if req.datasets.len() > MAX_CHECK_DATASETS {
+        return Err(bad_request(
+            format!(
+                "At most {} datasets can be checked at once",
+                MAX_CHECK_DATASETS
+            ),
+            request_id.0.clone(),
+        ));
+    }
+    for name in &req.datasets {
+        validation::validate_dataset_name(name)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let result = check_datasets(&conn, &req.datasets, req.require_sla, chrono::Utc::now())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(
+        count = result.datasets.len(),
+        ready = result.ready,
+        "Freshness check completed"
+    );
+
+    Ok(Json(result))
+}
+
+/// Request to set freshness configuration
+#[derive(Debug, Deserialize)]
+pub(crate) struct SetFreshnessConfigRequest {
+    expected_interval_secs: i64,
+    grace_period_secs: Option<i64>,
+    timezone: Option<String>,
+    cron_schedule: Option<String>,
+    alert_on_stale: Option<bool>,
+    alert_channels: Option<Vec<String>>,
+}
+
+/// Freshness config response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct FreshnessConfigResponse {
+    id: i64,
+    dataset_id: i64,
+    expected_interval_secs: i64,
+    grace_period_secs: i64,
+    timezone: String,
+    cron_schedule: Option<String>,
+    alert_on_stale: bool,
+    alert_channels: Option<Vec<String>>,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Set freshness configuration for a dataset
+pub(crate) async fn set_freshness_config(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<SetFreshnessConfigRequest>,
+) -> Result<Json<FreshnessConfigResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, "Setting freshness config");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let grace_period_secs = req.grace_period_secs.unwrap_or(0);
+    let timezone = req.timezone.as_deref().unwrap_or("UTC");
+    let alert_on_stale = req.alert_on_stale.unwrap_or(true);
+    let alert_channels_json = req
+        .alert_channels
+        .as_ref()
+        .map(|v| serde_json::to_string(v).unwrap_or_default());
+
+    // Upsert freshness config
+    conn.execute(
+        r#"
+        INSERT INTO freshness_config (dataset_id, expected_interval_secs, grace_period_secs, timezone, cron_schedule, alert_on_stale, alert_channels, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'), datetime('now'))
+        ON CONFLICT(dataset_id) DO UPDATE SET
+            expected_interval_secs = ?2,
+            grace_period_secs = ?3,
+            timezone = ?4,
+            cron_schedule = ?5,
+            alert_on_stale = ?6,
+            alert_channels = ?7,
+            updated_at = datetime('now')
+        "#,
+        rusqlite::params![
+            dataset_id,
+            req.expected_interval_secs,
+            grace_period_secs,
+            timezone,
+            req.cron_schedule,
+            if alert_on_stale { 1 } else { 0 },
+            alert_channels_json,
+        ],
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Fetch the config
+    let config = conn
+        .query_row(
+            r#"
+            SELECT id, dataset_id, expected_interval_secs, grace_period_secs, timezone, cron_schedule, alert_on_stale, alert_channels, created_at, updated_at
+            FROM freshness_config WHERE dataset_id = ?1
+            "#,
+            [dataset_id],
+            |row| {
+                let alert_channels_str: Option<String> = row.get(7)?;
+                Ok(FreshnessConfigResponse {
+                    id: row.get(0)?,
+                    dataset_id: row.get(1)?,
+                    expected_interval_secs: row.get(2)?,
+                    grace_period_secs: row.get(3)?,
+                    timezone: row.get(4)?,
+                    cron_schedule: row.get(5)?,
+                    alert_on_stale: row.get::<_, i32>(6)? != 0,
+                    alert_channels: alert_channels_str.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(dataset = %name, "Freshness config set successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "freshness_config",
+            &name,
+            serde_json::json!({}),
+            serde_json::json!({
+                "dataset": name,
+                "expected_interval_secs": config.expected_interval_secs,
+                "grace_period_secs": config.grace_period_secs,
+                "alert_on_stale": config.alert_on_stale,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(config))
+}
+
+/// Get freshness configuration for a dataset
+pub(crate) async fn get_freshness_config(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<Json<FreshnessConfigResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, "Getting freshness config");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let config = conn
+        .query_row(
+            r#"
+            SELECT id, dataset_id, expected_interval_secs, grace_period_secs, timezone, cron_schedule, alert_on_stale, alert_channels, created_at, updated_at
+            FROM freshness_config WHERE dataset_id = ?1
+            "#,
+            [dataset_id],
+            |row| {
+                let alert_channels_str: Option<String> = row.get(7)?;
+                Ok(FreshnessConfigResponse {
+                    id: row.get(0)?,
+                    dataset_id: row.get(1)?,
+                    expected_interval_secs: row.get(2)?,
+                    grace_period_secs: row.get(3)?,
+                    timezone: row.get(4)?,
+                    cron_schedule: row.get(5)?,
+                    alert_on_stale: row.get::<_, i32>(6)? != 0,
+                    alert_channels: alert_channels_str.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                })
+            },
+        )
+        .map_err(|_| {
+            not_found(
+                format!("Freshness config not found for dataset '{}'", name),
+                request_id.0.clone(),
+            )
+        })?;
+
+    Ok(Json(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_freshness() {
+        let now = DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let sla = FreshnessSla {
+            expected_interval_secs: 3600,
+            grace_period_secs: 600,
+        };
+
+        let fresh = Freshness::compute("2025-11-20 11:00:00", Some(sla), now).unwrap();
+        assert_eq!(fresh.age_seconds, 3600);
+        assert_eq!(fresh.is_stale, Some(false));
+        assert_eq!(fresh.sla_secs, Some(4200));
+
+        let stale = Freshness::compute("2025-11-20T10:00:00+00:00", Some(sla), now).unwrap();
+        assert_eq!(stale.is_stale, Some(true));
+
+        let unconfigured = Freshness::compute("2025-11-19 12:00:00", None, now).unwrap();
+        assert_eq!(unconfigured.age_seconds, 86400);
+        assert_eq!(unconfigured.is_stale, None);
+
+        assert!(Freshness::compute("yesterday", Some(sla), now).is_none());
+    }
+
+    #[test]
+    fn test_check_datasets() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated, deleted_at)
+             VALUES ('orders', '/o', 'delta', '2025-11-20 11:00:00', '2025-11-20 11:00:00', NULL),
+                    ('customers', '/c', 'delta', '2025-11-20 06:00:00', '2025-11-20 06:00:00', NULL),
+                    ('events', '/e', 'delta', '2025-11-01 00:00:00', '2025-11-01 00:00:00', NULL),
+                    ('trashed', '/t', 'delta', '2025-11-20 11:00:00', '2025-11-20 11:00:00', '2025-11-20 11:30:00');
+             INSERT INTO freshness_config (dataset_id, expected_interval_secs, grace_period_secs, created_at, updated_at)
+             VALUES (1, 3600, 600, datetime('now'), datetime('now')),
+                    (2, 3600, 0, datetime('now'), datetime('now'));",
+        )
+        .unwrap();
+        let now = DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let names: Vec<String> = ["orders", "events", "orders"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let result = check_datasets(&conn, &names, false, now).unwrap();
+        assert!(result.ready);
+        assert_eq!(result.datasets.len(), 2);
+        assert_eq!(result.datasets[0].status, FreshnessStatus::Fresh);
+        assert_eq!(result.datasets[0].age_seconds, Some(3600));
+        assert_eq!(result.datasets[1].status, FreshnessStatus::NoSla);
+
+        // Without an SLA a dataset can't be shown to be fresh
+        assert!(!check_datasets(&conn, &names, true, now).unwrap().ready);
+
+        let names: Vec<String> = ["orders", "customers", "trashed"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = check_datasets(&conn, &names, false, now).unwrap();
+        assert!(!result.ready);
+        assert_eq!(result.datasets[1].status, FreshnessStatus::Stale);
+        assert!(!result.datasets[1].ready);
+        assert_eq!(result.datasets[2].status, FreshnessStatus::NotFound);
+    }
+}
@@ -0,0 +1,1098 @@
+//! Business Glossary
+//!
+//! Glossary terms and their links to datasets and fields. Tenants see the
+//! global glossary alongside their own terms (see `glossary_scope`); platform
+//! admins manage the global terms.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::glossary_scope::{GlossaryScope, GlossaryView};
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use crate::{dataset_acl, envelope, glossary_scope};
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+/// Request to create a glossary term
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateGlossaryTermRequest {
+    term: String,
+    description: Option<String>,
+    domain: Option<String>,
+    owner_id: Option<String>,
+    status: Option<String>,
+}
+
+/// Request to update a glossary term
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateGlossaryTermRequest {
+    term: Option<String>,
+    description: Option<String>,
+    domain: Option<String>,
+    owner_id: Option<String>,
+    status: Option<String>,
+}
+
+/// Request to link a term to a dataset or field
+#[derive(Debug, Deserialize)]
+pub(crate) struct LinkTermRequest {
+    dataset_id: Option<i64>,
+    field_id: Option<i64>,
+}
+
+/// Glossary term response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct GlossaryTermResponse {
+    pub(crate) id: i64,
+    term: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner_id: Option<String>,
+    status: String,
+    link_count: i64,
+    created_at: String,
+    updated_at: String,
+    /// Glossary the term is defined in
+    scope: GlossaryScope,
+    /// Set on tenant terms that shadow a global term of the same name
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    overrides_global: bool,
+}
+
+/// Query params for listing glossary terms
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct ListGlossaryParams {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    /// `effective` (default), `tenant`, or `global`
+    scope: Option<String>,
+}
+
+/// Query params for resolving a glossary term by name
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlossaryLookupParams {
+    term: String,
+}
+
+/// Term link response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct TermLinkResponse {
+    id: i64,
+    term_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dataset_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dataset_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field_name: Option<String>,
+}
+
+/// Create a term in the global glossary inherited by every tenant
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_create_global_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    JsonBody(req): JsonBody<CreateGlossaryTermRequest>,
+) -> Result<(StatusCode, Json<GlossaryTermResponse>), (StatusCode, Json<ErrorResponse>)> {
+    create_glossary_term(
+        State(state),
+        Extension(request_id),
+        Extension(audit_ctx),
+        None,
+        None,
+        JsonBody(req),
+    )
+    .await
+}
+
+/// Update a term in the global glossary
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_update_global_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<UpdateGlossaryTermRequest>,
+) -> Result<Json<GlossaryTermResponse>, (StatusCode, Json<ErrorResponse>)> {
+    update_glossary_term(
+        State(state),
+        Extension(request_id),
+        Extension(audit_ctx),
+        None,
+        None,
+        Path(id),
+        JsonBody(req),
+    )
+    .await
+}
+
+/// Delete a term from the global glossary
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_delete_global_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_ctx): Extension<AuditContext>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    delete_glossary_term(
+        State(state),
+        Extension(request_id),
+        Extension(audit_ctx),
+        None,
+        None,
+        Path(id),
+    )
+    .await
+}
+
+/// Columns of a glossary term response, joined with its link count
+pub(crate) const GLOSSARY_TERM_SELECT: &str = r#"
+    SELECT
+        gt.id, gt.term, gt.description, gt.domain, gt.owner_id,
+        COALESCE(gt.status, 'draft') as status,
+        COALESCE(gt.created_at, datetime('now')) as created_at,
+        COALESCE(gt.updated_at, datetime('now')) as updated_at,
+        COUNT(tl.id) as link_count
+    FROM glossary_terms gt
+    LEFT JOIN term_links tl ON gt.id = tl.term_id
+"#;
+
+pub(crate) fn glossary_term_from_row(
+    row: &rusqlite::Row<'_>,
+    scope: GlossaryScope,
+) -> rusqlite::Result<GlossaryTermResponse> {
+    Ok(GlossaryTermResponse {
+        id: row.get(0)?,
+        term: row.get(1)?,
+        description: row.get(2)?,
+        domain: row.get(3)?,
+        owner_id: row.get(4)?,
+        status: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        link_count: row.get(8)?,
+        scope,
+        overrides_global: false,
+    })
+}
+
+/// Scope of the glossary a request reads and writes: the tenant's catalog when
+/// a tenant is resolved, otherwise the global (default) catalog.
+pub(crate) fn request_glossary_scope(
+    tenant_backend: Option<&Extension<TenantBackend>>,
+) -> GlossaryScope {
+    if tenant_backend.is_some() {
+        GlossaryScope::Tenant
+    } else {
+        GlossaryScope::Global
+    }
+}
+
+/// All terms in one catalog's glossary, sorted by name
+pub(crate) fn load_glossary_terms(
+    conn: &rusqlite::Connection,
+    scope: GlossaryScope,
+) -> rusqlite::Result<Vec<GlossaryTermResponse>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} GROUP BY gt.id ORDER BY gt.term COLLATE NOCASE",
+        GLOSSARY_TERM_SELECT
+    ))?;
+    let terms = stmt
+        .query_map([], |row| glossary_term_from_row(row, scope))?
+        .collect();
+    terms
+}
+
+/// A term in one catalog's glossary by name (case-insensitive)
+pub(crate) fn find_glossary_term(
+    conn: &rusqlite::Connection,
+    term: &str,
+    scope: GlossaryScope,
+) -> rusqlite::Result<Option<GlossaryTermResponse>> {
+    conn.query_row(
+        &format!(
+            "{} WHERE gt.term = ?1 COLLATE NOCASE GROUP BY gt.id",
+            GLOSSARY_TERM_SELECT
+        ),
+        [term],
+        |row| glossary_term_from_row(row, scope),
+    )
+    .optional()
+}
+
+/// List glossary terms.
+///
+/// With a resolved tenant the default `effective` view merges the tenant's
+/// terms with the inherited global glossary (tenant terms win on name
+/// clashes); `?scope=tenant` or `?scope=global` returns one glossary only.
+pub(crate) async fn list_glossary_terms(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(params): Query<ListGlossaryParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<GlossaryTermResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, "Listing glossary terms");
+
+    let view = match params.scope.as_deref() {
+        None => GlossaryView::default(),
+        Some(scope) => GlossaryView::parse(scope).ok_or_else(|| {
+            bad_request(
+                format!(
+                    "Invalid scope '{}'. Must be one of: effective, tenant, global",
+                    scope
+                ),
+                request_id.0.clone(),
+            )
+        })?,
+    };
+
+    let tenant_terms = match (&tenant_backend, view) {
+        (Some(Extension(tenant)), GlossaryView::Effective | GlossaryView::Tenant) => {
+            let conn = tenant
+                .backend()
+                .get_connection()
+                .await
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            load_glossary_terms(&conn, GlossaryScope::Tenant)
+                .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        }
+        (None, GlossaryView::Tenant) => {
+            return Err(bad_request(
+                "scope=tenant requires a tenant; this request uses the global glossary".to_string(),
+                request_id.0.clone(),
+            ))
+        }
+        _ => Vec::new(),
+    };
+
+    let global_terms = if view == GlossaryView::Tenant {
+        Vec::new()
+    } else {
+        let conn = state
+            .backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        load_glossary_terms(&conn, GlossaryScope::Global)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    };
+
+    let limit = params.limit.unwrap_or(100).min(1000);
+    let offset = params.offset.unwrap_or(0);
+
+    let resolved = glossary_scope::resolve(tenant_terms, global_terms, |t| t.term.as_str());
+    let total = resolved.len() as i64;
+    let terms = resolved
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|resolved| GlossaryTermResponse {
+            scope: resolved.scope,
+            overrides_global: resolved.overrides_global,
+            ..resolved.term
+        })
+        .collect();
+
+    Ok(Json(envelope.window(terms, total)))
+}
+
+/// Resolve a term by name: the tenant's glossary first, then the global one
+pub(crate) async fn lookup_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(params): Query<GlossaryLookupParams>,
+) -> Result<Json<GlossaryTermResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let global_conn = state
+        .backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let global_term = find_glossary_term(&global_conn, &params.term, GlossaryScope::Global)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if let Some(Extension(tenant)) = &tenant_backend {
+        let conn = tenant
+            .backend()
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let tenant_term = find_glossary_term(&conn, &params.term, GlossaryScope::Tenant)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if let Some(mut term) = tenant_term {
+            term.overrides_global = global_term.is_some();
+            return Ok(Json(term));
+        }
+    }
+
+    global_term.map(Json).ok_or_else(|| {
+        not_found(
+            format!("Glossary term '{}' not found", params.term),
+            request_id.0.clone(),
+        )
+    })
+}
+
+/// Get a term from the global glossary, even when a tenant is resolved
+pub(crate) async fn get_global_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(id): Path<i64>,
+) -> Result<Json<GlossaryTermResponse>, (StatusCode, Json<ErrorResponse>)> {
+    get_glossary_term(State(state), Extension(request_id), None, Path(id)).await
+}
+
+/// Create a new glossary term
+pub(crate) async fn create_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<CreateGlossaryTermRequest>,
+) -> Result<(StatusCode, Json<GlossaryTermResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, term = %req.term, "Creating glossary term");
+
+    // Validate term
+    if req.term.trim().is_empty() {
+        return Err(bad_request(
+            "Term cannot be empty".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    if req.term.len() > 255 {
+        return Err(bad_request(
+            "Term must be 255 characters or less".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Validate status if provided
+    if let Some(ref status) = req.status {
+        let valid_statuses = ["draft", "approved", "deprecated"];
+        if !valid_statuses.contains(&status.as_str()) {
+            return Err(bad_request(
+                format!(
+                    "Invalid status '{}'. Must be one of: draft, approved, deprecated",
+                    status
+                ),
+                request_id.0.clone(),
+            ));
+        }
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let status = req.status.unwrap_or_else(|| "draft".to_string());
+
+    conn.execute(
+        r#"
+        INSERT INTO glossary_terms (term, description, domain, owner_id, status, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'), datetime('now'))
+        "#,
+        rusqlite::params![req.term, req.description, req.domain, req.owner_id, status],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            bad_request(
+                format!("Glossary term '{}' already exists", req.term),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    let id = conn.last_insert_rowid();
+
+    let term_response = GlossaryTermResponse {
+        id,
+        term: req.term.clone(),
+        description: req.description.clone(),
+        domain: req.domain.clone(),
+        owner_id: req.owner_id.clone(),
+        status: status.clone(),
+        link_count: 0,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+        scope: request_glossary_scope(tenant_backend.as_ref()),
+        overrides_global: false,
+    };
+
+    tracing::info!(term = %req.term, id, "Glossary term created");
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "glossary_term",
+            req.term.clone(),
+            serde_json::json!({
+                "id": id,
+                "term": req.term,
+                "domain": req.domain,
+                "status": status,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(term_response)))
+}
+
+/// Get a glossary term by ID
+pub(crate) async fn get_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(id): Path<i64>,
+) -> Result<Json<GlossaryTermResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, id, "Getting glossary term");
+
+    let scope = request_glossary_scope(tenant_backend.as_ref());
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let term = conn
+        .query_row(
+            &format!("{} WHERE gt.id = ?1 GROUP BY gt.id", GLOSSARY_TERM_SELECT),
+            [id],
+            |row| glossary_term_from_row(row, scope),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => not_found(
+                format!("Glossary term {} not found", id),
+                request_id.0.clone(),
+            ),
+            _ => internal_error(e.to_string(), request_id.0.clone()),
+        })?;
+
+    Ok(Json(term))
+}
+
+/// Update a glossary term
+pub(crate) async fn update_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<UpdateGlossaryTermRequest>,
+) -> Result<Json<GlossaryTermResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, id, "Updating glossary term");
+
+    // Validate term if provided
+    if let Some(ref term) = req.term {
+        if term.trim().is_empty() {
+            return Err(bad_request(
+                "Term cannot be empty".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+    }
+
+    // Validate status if provided
+    if let Some(ref status) = req.status {
+        let valid_statuses = ["draft", "approved", "deprecated"];
+        if !valid_statuses.contains(&status.as_str()) {
+            return Err(bad_request(
+                format!(
+                    "Invalid status '{}'. Must be one of: draft, approved, deprecated",
+                    status
+                ),
+                request_id.0.clone(),
+            ));
+        }
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Check term exists
+    let exists: bool = conn
+        .query_row("SELECT 1 FROM glossary_terms WHERE id = ?1", [id], |_| {
+            Ok(true)
+        })
+        .unwrap_or(false);
+
+    if !exists {
+        return Err(not_found(
+            format!("Glossary term {} not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Build dynamic update
+    let mut updates = vec!["updated_at = datetime('now')"];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(ref term) = req.term {
+        updates.push("term = ?");
+        params.push(Box::new(term.clone()));
+    }
+    if let Some(ref description) = req.description {
+        updates.push("description = ?");
+        params.push(Box::new(description.clone()));
+    }
+    if let Some(ref domain) = req.domain {
+        updates.push("domain = ?");
+        params.push(Box::new(domain.clone()));
+    }
+    if let Some(ref owner_id) = req.owner_id {
+        updates.push("owner_id = ?");
+        params.push(Box::new(owner_id.clone()));
+    }
+    if let Some(ref status) = req.status {
+        updates.push("status = ?");
+        params.push(Box::new(status.clone()));
+    }
+
+    params.push(Box::new(id));
+
+    let sql = format!(
+        "UPDATE glossary_terms SET {} WHERE id = ?",
+        updates.join(", ")
+    );
+
+    conn.execute(
+        &sql,
+        rusqlite::params_from_iter(params.iter().map(|p| p.as_ref())),
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            bad_request(
+                "A glossary term with that name already exists".to_string(),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    // Fetch updated term
+    let scope = request_glossary_scope(tenant_backend.as_ref());
+    let term = conn
+        .query_row(
+            &format!("{} WHERE gt.id = ?1 GROUP BY gt.id", GLOSSARY_TERM_SELECT),
+            [id],
+            |row| glossary_term_from_row(row, scope),
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(id, term = %term.term, "Glossary term updated");
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "glossary_term",
+            term.term.clone(),
+            serde_json::json!({ "id": id }),
+            serde_json::json!({
+                "term": req.term,
+                "description": req.description,
+                "domain": req.domain,
+                "owner_id": req.owner_id,
+                "status": req.status,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(term))
+}
+
+/// Delete a glossary term
+pub(crate) async fn delete_glossary_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, id, "Deleting glossary term");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Get term name for audit
+    let term_name: Option<String> = conn
+        .query_row(
+            "SELECT term FROM glossary_terms WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .ok();
+
+    let rows = conn
+        .execute("DELETE FROM glossary_terms WHERE id = ?1", [id])
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows == 0 {
+        return Err(not_found(
+            format!("Glossary term {} not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(id, "Glossary term deleted");
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "glossary_term",
+            term_name.unwrap_or_else(|| id.to_string()),
+            serde_json::json!({ "id": id }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get links for a glossary term
+pub(crate) async fn get_term_links(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path(id): Path<i64>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<TermLinkResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, term_id = id, "Getting term links");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Verify term exists
+    let exists: bool = conn
+        .query_row("SELECT 1 FROM glossary_terms WHERE id = ?1", [id], |_| {
+            Ok(true)
+        })
+        .unwrap_or(false);
+
+    if !exists {
+        return Err(not_found(
+            format!("Glossary term {} not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Hide links to datasets the caller cannot read under dataset ACLs,
+    // including field links through the field's dataset
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let mut bindings: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(id)];
+    let visible = match dataset_acl::visibility_clause(&identity, "od.id", "od.domain") {
+        Some((clause, values)) => {
+            bindings.extend(
+                values
+                    .into_iter()
+                    .map(|v| Box::new(v) as Box<dyn rusqlite::ToSql>),
+            );
+            format!("AND {}", clause)
+        }
+        None => String::new(),
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT
+                tl.id, tl.term_id, tl.dataset_id, tl.field_id,
+                d.name as dataset_name, f.name as field_name
+            FROM term_links tl
+            LEFT JOIN datasets d ON tl.dataset_id = d.id
+            LEFT JOIN fields f ON tl.field_id = f.id
+            LEFT JOIN datasets od ON od.id = COALESCE(tl.dataset_id, f.dataset_id)
+            WHERE tl.term_id = ?1 {}
+            ORDER BY tl.id
+            "#,
+            visible
+        ))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let links: Vec<TermLinkResponse> = stmt
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            Ok(TermLinkResponse {
+                id: row.get(0)?,
+                term_id: row.get(1)?,
+                dataset_id: row.get(2)?,
+                field_id: row.get(3)?,
+                dataset_name: row.get(4)?,
+                field_name: row.get(5)?,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Json(envelope.page(links)))
+}
+
+/// Link a term to a dataset or field
+pub(crate) async fn link_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<LinkTermRequest>,
+) -> Result<(StatusCode, Json<TermLinkResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, term_id = id, ?req.dataset_id, ?req.field_id, "Linking term");
+
+    // Validate - must provide exactly one of dataset_id or field_id
+    match (&req.dataset_id, &req.field_id) {
+        (None, None) => {
+            return Err(bad_request(
+                "Must provide either dataset_id or field_id".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+        (Some(_), Some(_)) => {
+            return Err(bad_request(
+                "Must provide only one of dataset_id or field_id, not both".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+        _ => {}
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Verify term exists
+    let term_name: String = conn
+        .query_row(
+            "SELECT term FROM glossary_terms WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|_| {
+            not_found(
+                format!("Glossary term {} not found", id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // Verify dataset/field exists
+    if let Some(dataset_id) = req.dataset_id {
+        let exists: bool = conn
+            .query_row(
+                "SELECT 1 FROM datasets WHERE id = ?1 AND deleted_at IS NULL",
+                [dataset_id],
+                |_| Ok(true),
+            )
+            .unwrap_or(false);
+        if !exists {
+            return Err(not_found(
+                format!("Dataset {} not found", dataset_id),
+                request_id.0.clone(),
+            ));
+        }
+    }
+
+    if let Some(field_id) = req.field_id {
+        let exists: bool = conn
+            .query_row("SELECT 1 FROM fields WHERE id = ?1", [field_id], |_| {
+                Ok(true)
+            })
+            .unwrap_or(false);
+        if !exists {
+            return Err(not_found(
+                format!("Field {} not found", field_id),
+                request_id.0.clone(),
+            ));
+        }
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO term_links (term_id, dataset_id, field_id) VALUES (?1, ?2, ?3)",
+        rusqlite::params![id, req.dataset_id, req.field_id],
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let link_id = conn.last_insert_rowid();
+
+    // Get the link with names
+    let link = conn
+        .query_row(
+            r#"
+            SELECT
+                tl.id, tl.term_id, tl.dataset_id, tl.field_id,
+                d.name as dataset_name, f.name as field_name
+            FROM term_links tl
+            LEFT JOIN datasets d ON tl.dataset_id = d.id
+            LEFT JOIN fields f ON tl.field_id = f.id
+            WHERE tl.id = ?1
+            "#,
+            [link_id],
+            |row| {
+                Ok(TermLinkResponse {
+                    id: row.get(0)?,
+                    term_id: row.get(1)?,
+                    dataset_id: row.get(2)?,
+                    field_id: row.get(3)?,
+                    dataset_name: row.get(4)?,
+                    field_name: row.get(5)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(term_id = id, link_id, "Term linked");
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "term_link",
+            term_name,
+            serde_json::json!({
+                "term_id": id,
+                "link_id": link_id,
+                "dataset_id": req.dataset_id,
+                "field_id": req.field_id,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+/// Unlink a term from datasets/fields
+pub(crate) async fn unlink_term(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<LinkTermRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode (unlinking is a delete-style operation)
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, term_id = id, ?req.dataset_id, ?req.field_id, "Unlinking term");
+
+    // Validate - must provide exactly one of dataset_id or field_id
+    match (&req.dataset_id, &req.field_id) {
+        (None, None) => {
+            return Err(bad_request(
+                "Must provide either dataset_id or field_id".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+        (Some(_), Some(_)) => {
+            return Err(bad_request(
+                "Must provide only one of dataset_id or field_id, not both".to_string(),
+                request_id.0.clone(),
+            ));
+        }
+        _ => {}
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Verify term exists and get name for audit
+    let term_name: String = conn
+        .query_row(
+            "SELECT term FROM glossary_terms WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|_| {
+            not_found(
+                format!("Glossary term {} not found", id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    let rows = if let Some(dataset_id) = req.dataset_id {
+        conn.execute(
+            "DELETE FROM term_links WHERE term_id = ?1 AND dataset_id = ?2",
+            rusqlite::params![id, dataset_id],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    } else if let Some(field_id) = req.field_id {
+        conn.execute(
+            "DELETE FROM term_links WHERE term_id = ?1 AND field_id = ?2",
+            rusqlite::params![id, field_id],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    } else {
+        0
+    };
+
+    if rows == 0 {
+        return Err(not_found(
+            "Link not found".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(term_id = id, "Term unlinked");
+
+    // Emit audit event
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "term_link",
+            term_name,
+            serde_json::json!({
+                "term_id": id,
+                "dataset_id": req.dataset_id,
+                "field_id": req.field_id,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
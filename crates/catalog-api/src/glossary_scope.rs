@@ -0,0 +1,114 @@
+//! Glossary Scopes
+//!
+//! Tenants have their own glossary and inherit the company-wide glossary.
+//! Tenant catalogs are separate databases, so the scope is where a term is
+//! stored: the **global** glossary lives in the default catalog, a
+//! **tenant** glossary in the tenant's catalog.
+//!
+//! # Resolution
+//!
+//! A tenant term overrides a global term with the same name (compared
+//! case-insensitively). The effective glossary is every tenant term plus the
+//! global terms not overridden. Without a resolved tenant the effective
+//! glossary is the global one.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a glossary term is defined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlossaryScope {
+    Tenant,
+    Global,
+}
+
+/// Which terms a glossary listing returns (`?scope=`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlossaryView {
+    /// Tenant terms plus inherited global terms
+    #[default]
+    Effective,
+    Tenant,
+    Global,
+}
+
+impl GlossaryView {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "effective" => Some(GlossaryView::Effective),
+            "tenant" => Some(GlossaryView::Tenant),
+            "global" => Some(GlossaryView::Global),
+            _ => None,
+        }
+    }
+}
+
+/// A term in the effective glossary
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved<T> {
+    pub term: T,
+    pub scope: GlossaryScope,
+    /// A tenant term that shadows a global term of the same name
+    pub overrides_global: bool,
+}
+
+/// Merge tenant and global terms, tenant first on name clashes, sorted by
+/// name.
+pub fn resolve<T>(tenant: Vec<T>, global: Vec<T>, name: impl Fn(&T) -> &str) -> Vec<Resolved<T>> {
+    let global_names: std::collections::HashSet<String> =
+        global.iter().map(|t| name(t).to_lowercase()).collect();
+    let tenant_names: std::collections::HashSet<String> =
+        tenant.iter().map(|t| name(t).to_lowercase()).collect();
+
+    let mut merged: Vec<Resolved<T>> = tenant
+        .into_iter()
+        .map(|term| Resolved {
+            overrides_global: global_names.contains(&name(&term).to_lowercase()),
+            term,
+            scope: GlossaryScope::Tenant,
+        })
+        .collect();
+    merged.extend(
+        global
+            .into_iter()
+            .filter(|term| !tenant_names.contains(&name(term).to_lowercase()))
+            .map(|term| Resolved {
+                term,
+                scope: GlossaryScope::Global,
+                overrides_global: false,
+            }),
+    );
+    merged.sort_by_key(|r| name(&r.term).to_lowercase());
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_overrides_global() {
+        let tenant = vec!["revenue", "Churn"];
+        let global = vec!["Revenue", "Customer", "ARR"];
+        let merged = resolve(tenant, global, |t| t);
+
+        let names: Vec<(&str, GlossaryScope, bool)> = merged
+            .iter()
+            .map(|r| (r.term, r.scope, r.overrides_global))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("ARR", GlossaryScope::Global, false),
+                ("Churn", GlossaryScope::Tenant, false),
+                ("Customer", GlossaryScope::Global, false),
+                ("revenue", GlossaryScope::Tenant, true),
+            ]
+        );
+        assert_eq!(
+            GlossaryView::parse("effective"),
+            Some(GlossaryView::Effective)
+        );
+        assert_eq!(GlossaryView::parse("all"), None);
+    }
+}
@@ -0,0 +1,477 @@
+//! Governance Rules
+//!
+//! CRUD for the governance rules stored in the catalog.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::datasets::PaginationParams;
+use crate::envelope;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use serde::{Deserialize, Serialize};
+
+/// Request to create a governance rule
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateGovernanceRuleRequest {
+    name: String,
+    rule_type: String,
+    description: Option<String>,
+    config: serde_json::Value,
+    priority: Option<i32>,
+}
+
+/// Request to update a governance rule
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateGovernanceRuleRequest {
+    name: Option<String>,
+    rule_type: Option<String>,
+    description: Option<String>,
+    config: Option<serde_json::Value>,
+    priority: Option<i32>,
+    is_active: Option<bool>,
+}
+
+/// Governance rule response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct GovernanceRuleResponse {
+    id: i64,
+    name: String,
+    rule_type: String,
+    description: Option<String>,
+    config: serde_json::Value,
+    priority: i32,
+    is_active: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Create a governance rule
+pub(crate) async fn create_governance_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<CreateGovernanceRuleRequest>,
+) -> Result<(StatusCode, Json<GovernanceRuleResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %req.name, "Creating governance rule");
+
+    // Validate rule type
+    validation::validate_rule_type(&req.rule_type)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let priority = req.priority.unwrap_or(100);
+
+    conn.execute(
+        r#"
+        INSERT INTO governance_rules (name, rule_type, description, config, priority, is_active, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, 1, datetime('now'), datetime('now'))
+        "#,
+        rusqlite::params![
+            req.name,
+            req.rule_type,
+            req.description,
+            req.config.to_string(),
+            priority,
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            bad_request(
+                format!("Governance rule '{}' already exists", req.name),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    let id = conn.last_insert_rowid();
+
+    let rule = GovernanceRuleResponse {
+        id,
+        name: req.name,
+        rule_type: req.rule_type,
+        description: req.description,
+        config: req.config,
+        priority,
+        is_active: true,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    tracing::info!(name = %rule.name, "Governance rule created successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "governance_rule",
+            &rule.name,
+            serde_json::json!({
+                "id": rule.id,
+                "name": rule.name,
+                "rule_type": rule.rule_type,
+                "priority": rule.priority,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// List all governance rules
+pub(crate) async fn list_governance_rules(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(pagination): Query<PaginationParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<GovernanceRuleResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        limit = pagination.limit(),
+        offset = pagination.offset(),
+        "Listing governance rules"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, name, rule_type, description, config, priority, is_active, created_at, updated_at
+            FROM governance_rules ORDER BY priority, name LIMIT ?1 OFFSET ?2
+            "#,
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rules = stmt
+        .query_map(
+            rusqlite::params![pagination.limit() as i64, pagination.offset() as i64],
+            |row| {
+                let config_str: String = row.get(4)?;
+                Ok(GovernanceRuleResponse {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    rule_type: row.get(2)?,
+                    description: row.get(3)?,
+                    config: serde_json::from_str(&config_str).unwrap_or(serde_json::Value::Null),
+                    priority: row.get(5)?,
+                    is_active: row.get::<_, i32>(6)? != 0,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(count = rules.len(), "Listed governance rules successfully");
+
+    let total = envelope
+        .total(|| {
+            conn.query_row("SELECT COUNT(*) FROM governance_rules", [], |row| {
+                row.get(0)
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.window(rules, total)))
+}
+
+/// Get a governance rule by ID
+pub(crate) async fn get_governance_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(id): Path<i64>,
+) -> Result<Json<GovernanceRuleResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, id = %id, "Getting governance rule");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rule = conn
+        .query_row(
+            r#"
+            SELECT id, name, rule_type, description, config, priority, is_active, created_at, updated_at
+            FROM governance_rules WHERE id = ?1
+            "#,
+            [id],
+            |row| {
+                let config_str: String = row.get(4)?;
+                Ok(GovernanceRuleResponse {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    rule_type: row.get(2)?,
+                    description: row.get(3)?,
+                    config: serde_json::from_str(&config_str).unwrap_or(serde_json::Value::Null),
+                    priority: row.get(5)?,
+                    is_active: row.get::<_, i32>(6)? != 0,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|_| {
+            not_found(
+                format!("Governance rule '{}' not found", id),
+                request_id.0.clone(),
+            )
+        })?;
+
+    Ok(Json(rule))
+}
+
+/// Update a governance rule
+pub(crate) async fn update_governance_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<UpdateGovernanceRuleRequest>,
+) -> Result<Json<GovernanceRuleResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, id = %id, "Updating governance rule");
+
+    // Validate rule type if provided
+    if let Some(ref rule_type) = req.rule_type {
+        validation::validate_rule_type(rule_type)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut updates = vec!["updated_at = datetime('now')".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(name) = &req.name {
+        updates.push(format!("name = ?{}", params.len() + 1));
+        params.push(Box::new(name.clone()));
+    }
+    if let Some(rule_type) = &req.rule_type {
+        updates.push(format!("rule_type = ?{}", params.len() + 1));
+        params.push(Box::new(rule_type.clone()));
+    }
+    if let Some(description) = &req.description {
+        updates.push(format!("description = ?{}", params.len() + 1));
+        params.push(Box::new(description.clone()));
+    }
+    if let Some(config) = &req.config {
+        updates.push(format!("config = ?{}", params.len() + 1));
+        params.push(Box::new(config.to_string()));
+    }
+    if let Some(priority) = &req.priority {
+        updates.push(format!("priority = ?{}", params.len() + 1));
+        params.push(Box::new(*priority));
+    }
+    if let Some(is_active) = &req.is_active {
+        updates.push(format!("is_active = ?{}", params.len() + 1));
+        params.push(Box::new(if *is_active { 1i32 } else { 0i32 }));
+    }
+
+    let sql = format!(
+        "UPDATE governance_rules SET {} WHERE id = ?{}",
+        updates.join(", "),
+        params.len() + 1
+    );
+    params.push(Box::new(id));
+
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = conn
+        .execute(&sql, params_refs.as_slice())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows == 0 {
+        return Err(not_found(
+            format!("Governance rule '{}' not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Fetch updated rule
+    let rule = conn
+        .query_row(
+            r#"
+            SELECT id, name, rule_type, description, config, priority, is_active, created_at, updated_at
+            FROM governance_rules WHERE id = ?1
+            "#,
+            [id],
+            |row| {
+                let config_str: String = row.get(4)?;
+                Ok(GovernanceRuleResponse {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    rule_type: row.get(2)?,
+                    description: row.get(3)?,
+                    config: serde_json::from_str(&config_str).unwrap_or(serde_json::Value::Null),
+                    priority: row.get(5)?,
+                    is_active: row.get::<_, i32>(6)? != 0,
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(id = %id, "Governance rule updated successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "governance_rule",
+            id.to_string(),
+            serde_json::json!({}),
+            serde_json::json!({
+                "id": rule.id,
+                "name": rule.name,
+                "rule_type": rule.rule_type,
+                "is_active": rule.is_active,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(rule))
+}
+
+/// Delete a governance rule
+pub(crate) async fn delete_governance_rule(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, id = %id, "Deleting governance rule");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rows = conn
+        .execute("DELETE FROM governance_rules WHERE id = ?1", [id])
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows == 0 {
+        return Err(not_found(
+            format!("Governance rule '{}' not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(id = %id, "Governance rule deleted successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "governance_rule",
+            id.to_string(),
+            serde_json::json!({ "id": id }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
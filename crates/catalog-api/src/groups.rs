@@ -0,0 +1,340 @@
+//! Group Providers
+//!
+//! Ownership and ACL features need to know which groups (teams) a caller
+//! belongs to. A [`GroupProvider`] maps a user to groups; the configured
+//! providers are combined by a [`GroupResolver`], whose groups are used by:
+//!
+//! - dataset ACLs (`group:<id>` principals, see `dataset_acl`)
+//! - the `owner=me` filter of `GET /api/v1/datasets`, matching datasets owned
+//!   by the caller or one of their groups
+//! - dataset watch notifications, which list the `teams` of the user
+//!   subscribers so a notification service can route to team channels
+//!
+//! # Providers
+//!
+//! - `claims`: groups asserted on the request, e.g. the OIDC `groups` claim
+//!   forwarded by the authenticating proxy in the identity groups header
+//! - `static`: memberships from configuration
+//! - `scim`: memberships synced from a directory into the `group_members`
+//!   table (migration v1.37.0), reloaded from the default catalog periodically
+//!
+//! Claimed groups only exist while handling a request, so background work
+//! such as notifications only sees `static` and `scim` memberships.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_GROUP_PROVIDERS`: comma-separated providers (default: `claims`)
+//! - `METAFUSE_STATIC_GROUPS`: static memberships as `group=user,user;group=user`
+//! - `METAFUSE_GROUP_SYNC_INTERVAL_SECS`: seconds between reloads of the
+//!   `scim` memberships (default: 300)
+
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Default seconds between reloads of directory memberships
+pub const DEFAULT_SYNC_INTERVAL_SECS: u64 = 300;
+
+/// Source of a user's group memberships.
+pub trait GroupProvider: Send + Sync {
+    /// Provider name, as configured in `METAFUSE_GROUP_PROVIDERS`
+    fn name(&self) -> &'static str;
+
+    /// Groups of `user`. `claimed` are the groups asserted on the current
+    /// request, empty outside of one.
+    fn groups(&self, user: &str, claimed: &[String]) -> Vec<String>;
+}
+
+/// Groups asserted on the request by the authenticating proxy.
+#[derive(Debug, Clone, Default)]
+pub struct ClaimGroups;
+
+impl GroupProvider for ClaimGroups {
+    fn name(&self) -> &'static str {
+        "claims"
+    }
+
+    fn groups(&self, _user: &str, claimed: &[String]) -> Vec<String> {
+        claimed.to_vec()
+    }
+}
+
+/// Memberships from configuration.
+#[derive(Debug, Clone, Default)]
+pub struct StaticGroups {
+    members: HashMap<String, Vec<String>>,
+}
+
+impl StaticGroups {
+    /// Parse memberships written as `group=user,user;group=user`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut members: HashMap<String, Vec<String>> = HashMap::new();
+        for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (group, users) = entry
+                .split_once('=')
+                .map(|(g, u)| (g.trim(), u))
+                .filter(|(g, _)| !g.is_empty())
+                .ok_or_else(|| {
+                    format!("Invalid group membership '{}': expected group=users", entry)
+                })?;
+            for user in users.split(',').map(str::trim).filter(|u| !u.is_empty()) {
+                members
+                    .entry(user.to_string())
+                    .or_default()
+                    .push(group.to_string());
+            }
+        }
+        Ok(Self { members })
+    }
+}
+
+impl GroupProvider for StaticGroups {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    fn groups(&self, user: &str, _claimed: &[String]) -> Vec<String> {
+        self.members.get(user).cloned().unwrap_or_default()
+    }
+}
+
+/// Memberships synced from a directory into `group_members`.
+///
+/// Lookups read an in-memory copy, refreshed by [`group_sync_task`].
+#[derive(Debug, Clone, Default)]
+pub struct ScimGroups {
+    members: Arc<RwLock<HashMap<String, Vec<String>>>>,
+}
+
+impl ScimGroups {
+    /// Reload memberships from a catalog. Returns how many were loaded.
+    ///
+    /// Catalogs without the `group_members` table have no memberships.
+    pub fn refresh(&self, conn: &Connection) -> rusqlite::Result<usize> {
+        let mut members: HashMap<String, Vec<String>> = HashMap::new();
+        let mut count = 0;
+        let has_table = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'group_members'",
+                [],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if has_table {
+            let mut stmt =
+                conn.prepare("SELECT user_id, group_id FROM group_members ORDER BY group_id")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (user, group) = row?;
+                members.entry(user).or_default().push(group);
+                count += 1;
+            }
+        }
+        *self.members.write().unwrap_or_else(|e| e.into_inner()) = members;
+        Ok(count)
+    }
+}
+
+impl GroupProvider for ScimGroups {
+    fn name(&self) -> &'static str {
+        "scim"
+    }
+
+    fn groups(&self, user: &str, _claimed: &[String]) -> Vec<String> {
+        self.members
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(user)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// The configured group providers, combined.
+#[derive(Clone)]
+pub struct GroupResolver {
+    providers: Vec<Arc<dyn GroupProvider>>,
+    scim: Option<ScimGroups>,
+    /// Seconds between reloads of the `scim` memberships
+    pub sync_interval_secs: u64,
+}
+
+impl std::fmt::Debug for GroupResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupResolver")
+            .field("providers", &self.provider_names())
+            .field("sync_interval_secs", &self.sync_interval_secs)
+            .finish()
+    }
+}
+
+/// Only claimed groups, the behavior before providers were configurable.
+impl Default for GroupResolver {
+    fn default() -> Self {
+        Self::new(vec![Arc::new(ClaimGroups)])
+    }
+}
+
+impl GroupResolver {
+    /// Combine providers; a [`ScimGroups`] among them is not refreshed.
+    pub fn new(providers: Vec<Arc<dyn GroupProvider>>) -> Self {
+        Self {
+            providers,
+            scim: None,
+            sync_interval_secs: DEFAULT_SYNC_INTERVAL_SECS,
+        }
+    }
+
+    /// Create the resolver from environment variables.
+    ///
+    /// Unknown provider names and invalid static memberships are logged and
+    /// ignored.
+    pub fn from_env() -> Self {
+        let names = std::env::var("METAFUSE_GROUP_PROVIDERS").unwrap_or_else(|_| "claims".into());
+        let mut resolver = Self::new(Vec::new());
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name {
+                "claims" => resolver.providers.push(Arc::new(ClaimGroups)),
+                "static" => {
+                    let spec = std::env::var("METAFUSE_STATIC_GROUPS").unwrap_or_default();
+                    match StaticGroups::parse(&spec) {
+                        Ok(groups) => resolver.providers.push(Arc::new(groups)),
+                        Err(e) => warn!(error = %e, "Ignoring METAFUSE_STATIC_GROUPS"),
+                    }
+                }
+                "scim" => {
+                    let scim = ScimGroups::default();
+                    resolver.providers.push(Arc::new(scim.clone()));
+                    resolver.scim = Some(scim);
+                }
+                other => warn!(provider = %other, "Ignoring unknown group provider"),
+            }
+        }
+        resolver.sync_interval_secs = std::env::var("METAFUSE_GROUP_SYNC_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_SYNC_INTERVAL_SECS);
+        resolver
+    }
+
+    /// Names of the configured providers, in order.
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Directory memberships to keep refreshed, when the `scim` provider is
+    /// configured.
+    pub fn scim(&self) -> Option<&ScimGroups> {
+        self.scim.as_ref()
+    }
+
+    /// Groups of a caller across all providers, in provider order without
+    /// duplicates.
+    ///
+    /// Without a user only the claimed groups of configured `claims`
+    /// providers apply.
+    pub fn resolve(&self, user: Option<&str>, claimed: &[String]) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.providers
+            .iter()
+            .flat_map(|p| match user {
+                Some(user) => p.groups(user, claimed),
+                None if p.name() == "claims" => claimed.to_vec(),
+                None => Vec::new(),
+            })
+            .filter(|g| seen.insert(g.clone()))
+            .collect()
+    }
+
+    /// Groups of a user outside of a request, from non-claim providers.
+    pub fn groups_of(&self, user: &str) -> Vec<String> {
+        self.resolve(Some(user), &[])
+    }
+}
+
+/// Background task that reloads directory memberships periodically
+pub async fn group_sync_task(
+    scim: ScimGroups,
+    interval_secs: u64,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(interval_secs);
+
+    info!(interval_secs, "Group membership sync task started");
+
+    loop {
+        match backend.get_connection().await {
+            Ok(conn) => match scim.refresh(&conn) {
+                Ok(count) => {
+                    tracing::debug!(memberships = count, "Reloaded group memberships");
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to reload group memberships");
+                }
+            },
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for group membership sync");
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_groups() {
+        let groups = StaticGroups::parse("finance=ana, bo; analysts=ana;").unwrap();
+        assert_eq!(groups.groups("ana", &[]), vec!["finance", "analysts"]);
+        assert_eq!(groups.groups("bo", &[]), vec!["finance"]);
+        assert!(groups.groups("cy", &[]).is_empty());
+        assert!(StaticGroups::parse("finance").is_err());
+        assert!(StaticGroups::parse("=ana").is_err());
+    }
+
+    #[test]
+    fn test_resolver_combines_providers() {
+        let conn = Connection::open_in_memory().unwrap();
+        let scim = ScimGroups::default();
+        // Catalogs without the table have no memberships
+        assert_eq!(scim.refresh(&conn).unwrap(), 0);
+
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO group_members (group_id, user_id) VALUES
+                ('platform', 'ana'), ('finance', 'ana'), ('platform', 'bo');",
+        )
+        .unwrap();
+        assert_eq!(scim.refresh(&conn).unwrap(), 3);
+
+        let resolver = GroupResolver::new(vec![
+            Arc::new(ClaimGroups),
+            Arc::new(StaticGroups::parse("analysts=ana").unwrap()),
+            Arc::new(scim),
+        ]);
+        let claimed = vec!["finance".to_string(), "oncall".to_string()];
+        assert_eq!(
+            resolver.resolve(Some("ana"), &claimed),
+            vec!["finance", "oncall", "analysts", "platform"]
+        );
+        assert_eq!(resolver.resolve(None, &claimed), vec!["finance", "oncall"]);
+        assert_eq!(
+            resolver.groups_of("ana"),
+            vec!["analysts", "finance", "platform"]
+        );
+
+        // Claimed groups are ignored unless the claims provider is configured
+        let resolver = GroupResolver::new(vec![]);
+        assert!(resolver.resolve(Some("ana"), &claimed).is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+//! HyperLogLog Sketches
+//!
+//! Approximate distinct counting in fixed memory, used for unique users in
+//! usage analytics. A sketch has 4096 one-byte registers (precision 12), for
+//! a standard error of about 1.6% at any count.
+//!
+//! Sketches merge by taking the maximum of each register, so a day's sketch
+//! can be built up across flushes and unique users over a period estimated
+//! by merging its days.
+//!
+//! # Serialization
+//!
+//! The first byte is the encoding: `1` dense (every register), `2` sparse
+//! (big-endian `u16` index and the value of each non-zero register).
+//! Whichever is smaller is written. Hashing is FNV-1a with a fixed finalizer,
+//! so sketches stored by one release merge with those of the next.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// Bits of the hash used to pick a register
+const PRECISION: u32 = 12;
+
+/// Number of registers
+const REGISTERS: usize = 1 << PRECISION;
+
+const DENSE: u8 = 1;
+const SPARSE: u8 = 2;
+
+/// Stable 64-bit hash of a value
+fn hash(value: &str) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in value.as_bytes() {
+        h ^= u64::from(*byte);
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    // splitmix64 finalizer: FNV's high bits are poorly mixed for short keys
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
+
+/// A value's register index and rank (position of the first set bit).
+/// Computing it once lets one value be added to many sketches cheaply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SketchKey {
+    index: usize,
+    rank: u8,
+}
+
+impl SketchKey {
+    pub fn new(value: &str) -> Self {
+        let h = hash(value);
+        let index = (h >> (64 - PRECISION)) as usize;
+        let rank = (h << PRECISION).leading_zeros().min(64 - PRECISION) + 1;
+        Self {
+            index,
+            rank: rank as u8,
+        }
+    }
+}
+
+/// A HyperLogLog sketch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sketch {
+    registers: Vec<u8>,
+}
+
+impl Default for Sketch {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+        }
+    }
+}
+
+impl Sketch {
+    /// Add a value
+    pub fn insert(&mut self, value: &str) {
+        let key = SketchKey::new(value);
+        self.registers[key.index] = self.registers[key.index].max(key.rank);
+    }
+
+    /// Add every value counted by another sketch
+    pub fn merge(&mut self, other: &Sketch) {
+        for (mine, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *mine = (*mine).max(*theirs);
+        }
+    }
+
+    /// Whether no value has been added
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|r| *r == 0)
+    }
+
+    /// Estimated number of distinct values
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|r| 2f64.powi(-i32::from(*r)))
+            .sum();
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+
+        let raw = alpha * m * m / sum;
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+
+    /// Serialize for storage
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let set: Vec<(usize, u8)> = self
+            .registers
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| **r != 0)
+            .map(|(i, r)| (i, *r))
+            .collect();
+
+        if set.len() * 3 < REGISTERS {
+            let mut bytes = Vec::with_capacity(1 + set.len() * 3);
+            bytes.push(SPARSE);
+            for (index, value) in set {
+                bytes.extend_from_slice(&(index as u16).to_be_bytes());
+                bytes.push(value);
+            }
+            bytes
+        } else {
+            let mut bytes = Vec::with_capacity(1 + REGISTERS);
+            bytes.push(DENSE);
+            bytes.extend_from_slice(&self.registers);
+            bytes
+        }
+    }
+
+    /// Deserialize a stored sketch; `None` if the bytes aren't a sketch
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (encoding, body) = bytes.split_first()?;
+        match *encoding {
+            DENSE if body.len() == REGISTERS => Some(Self {
+                registers: body.to_vec(),
+            }),
+            SPARSE if body.len() % 3 == 0 => {
+                let mut sketch = Self::default();
+                for entry in body.chunks_exact(3) {
+                    let index = u16::from_be_bytes([entry[0], entry[1]]) as usize;
+                    *sketch.registers.get_mut(index)? = entry[2];
+                }
+                Some(sketch)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A sketch that can be added to concurrently. Registers are allocated on
+/// first insert, so idle counters cost nothing.
+#[derive(Debug, Default)]
+pub struct AtomicSketch {
+    registers: OnceLock<Box<[AtomicU8]>>,
+}
+
+impl AtomicSketch {
+    fn registers(&self) -> &[AtomicU8] {
+        self.registers
+            .get_or_init(|| (0..REGISTERS).map(|_| AtomicU8::new(0)).collect())
+    }
+
+    /// Add a value
+    pub fn insert(&self, value: &str) {
+        self.insert_key(SketchKey::new(value));
+    }
+
+    /// Add a value hashed beforehand
+    pub fn insert_key(&self, key: SketchKey) {
+        self.registers()[key.index].fetch_max(key.rank, Ordering::Relaxed);
+    }
+
+    /// Take the current registers, leaving the sketch empty; values added
+    /// meanwhile stay for the next take
+    pub fn take(&self) -> Sketch {
+        match self.registers.get() {
+            Some(registers) => Sketch {
+                registers: registers
+                    .iter()
+                    .map(|r| r.swap(0, Ordering::Relaxed))
+                    .collect(),
+            },
+            None => Sketch::default(),
+        }
+    }
+
+    /// Merge back a sketch returned by `take`
+    pub fn restore(&self, sketch: &Sketch) {
+        if sketch.is_empty() {
+            return;
+        }
+        for (register, value) in self.registers().iter().zip(&sketch.registers) {
+            register.fetch_max(*value, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether no value is waiting to be taken
+    pub fn is_empty(&self) -> bool {
+        self.registers
+            .get()
+            .is_none_or(|registers| registers.iter().all(|r| r.load(Ordering::Relaxed) == 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_accuracy() {
+        let mut sketch = Sketch::default();
+        assert_eq!(sketch.estimate(), 0);
+
+        for n in [10u64, 1_000, 100_000] {
+            let mut sketch = Sketch::default();
+            for i in 0..n {
+                sketch.insert(&format!("user-{i}"));
+                // Duplicates don't change the count
+                sketch.insert(&format!("user-{i}"));
+            }
+            let error = (sketch.estimate() as f64 - n as f64).abs() / n as f64;
+            assert!(error < 0.05, "n={n} estimate={}", sketch.estimate());
+        }
+
+        sketch.insert("alice");
+        sketch.insert("bob");
+        sketch.insert("alice");
+        assert_eq!(sketch.estimate(), 2);
+    }
+
+    #[test]
+    fn test_merge_and_round_trip() {
+        let mut a = Sketch::default();
+        let mut b = Sketch::default();
+        for i in 0..3_000 {
+            a.insert(&format!("user-{i}"));
+        }
+        for i in 2_000..5_000 {
+            b.insert(&format!("user-{i}"));
+        }
+
+        // Sparse and dense encodings both round-trip
+        let small = Sketch::from_bytes(&b.to_bytes()).unwrap();
+        assert_eq!(small, b);
+        a.merge(&b);
+        assert_eq!(a.to_bytes()[0], DENSE);
+        assert_eq!(Sketch::from_bytes(&a.to_bytes()).unwrap(), a);
+
+        let error = (a.estimate() as f64 - 5_000.0).abs() / 5_000.0;
+        assert!(error < 0.05, "estimate={}", a.estimate());
+
+        assert!(Sketch::from_bytes(&[]).is_none());
+        assert!(Sketch::from_bytes(&[SPARSE, 0xff, 0xff, 1]).is_none());
+    }
+
+    #[test]
+    fn test_atomic_take_and_restore() {
+        let atomic = AtomicSketch::default();
+        assert!(atomic.is_empty());
+        assert!(atomic.take().is_empty());
+
+        atomic.insert("alice");
+        atomic.insert("bob");
+        let taken = atomic.take();
+        assert_eq!(taken.estimate(), 2);
+        assert!(atomic.is_empty());
+
+        atomic.insert("carol");
+        atomic.restore(&taken);
+        assert_eq!(atomic.take().estimate(), 3);
+    }
+}
@@ -0,0 +1,599 @@
+//! Response Localization
+//!
+//! Translates human-readable strings in responses into the language the client
+//! asks for with `Accept-Language`, falling back to English:
+//!
+//! - Error messages (`error` in error responses)
+//! - Classification labels (`classification_label`)
+//! - Quality score labels (`labels` in quality responses)
+//!
+//! Machine-readable values such as `"classification": "pii"` never change.
+//!
+//! # Message Bundles
+//!
+//! A bundle maps message keys to translations for one language. Labels use
+//! dotted keys (`classification.pii`, `quality.overall_score`). Error messages
+//! are keyed by their English text, with `{}` standing for each value filled
+//! into it:
+//!
+//! ```json
+//! { "Dataset '{}' not found": "Datensatz '{}' nicht gefunden" }
+//! ```
+//!
+//! Translations may use `{0}`, `{1}`, ... to reorder values. Messages and
+//! labels missing from a bundle stay in English.
+//!
+//! Bundles are files named by language tag (`de.json`, `pt-BR.properties`).
+//! The extension selects the [`BundleFormat`]: JSON (nested objects are
+//! flattened with dots) and `key = value` properties files are built in, and
+//! other formats can be passed to [`Localizer::load_dir`].
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_I18N_DIR`: directory of message bundles (default: unset, English only)
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Extension, Request},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Language of the built-in messages
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Largest error body that is translated; larger or streamed ones pass through
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Built-in English labels
+const ENGLISH_LABELS: &[(&str, &str)] = &[
+    ("classification.pii", "Personally identifiable information"),
+    ("classification.sensitive", "Sensitive"),
+    ("classification.confidential", "Confidential"),
+    ("classification.public", "Public"),
+    ("classification.unknown", "Unclassified"),
+    ("quality.completeness_score", "Completeness"),
+    ("quality.freshness_score", "Freshness"),
+    ("quality.file_health_score", "File health"),
+    ("quality.overall_score", "Overall quality"),
+    ("quality.propagated_score", "Quality with upstreams"),
+];
+
+/// A message bundle file format.
+pub trait BundleFormat: Send + Sync {
+    /// File extension this format reads, without the dot
+    fn extension(&self) -> &str;
+
+    /// Parse a bundle into message keys and translations
+    fn parse(&self, content: &str) -> Result<HashMap<String, String>, String>;
+}
+
+/// JSON bundles: an object of strings, where nested objects are flattened
+/// with dots (`{"classification": {"pii": "..."}}` is `classification.pii`).
+pub struct JsonFormat;
+
+impl BundleFormat for JsonFormat {
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn parse(&self, content: &str) -> Result<HashMap<String, String>, String> {
+        fn flatten(
+            prefix: &str,
+            value: &Value,
+            out: &mut HashMap<String, String>,
+        ) -> Result<(), String> {
+            match value {
+                Value::String(s) => {
+                    out.insert(prefix.to_string(), s.clone());
+                }
+                Value::Object(map) => {
+                    for (key, value) in map {
+                        let key = if prefix.is_empty() {
+                            key.clone()
+                        } else {
+                            format!("{}.{}", prefix, key)
+                        };
+                        flatten(&key, value, out)?;
+                    }
+                }
+                _ => return Err(format!("'{}' must be a string or an object", prefix)),
+            }
+            Ok(())
+        }
+
+        let value: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        if !value.is_object() {
+            return Err("bundle must be a JSON object".to_string());
+        }
+        let mut messages = HashMap::new();
+        flatten("", &value, &mut messages)?;
+        Ok(messages)
+    }
+}
+
+/// Properties bundles: one `key = value` per line, with `#` or `!` comments.
+/// `\=` in a key is a literal `=`, and `\n` in a value is a newline.
+pub struct PropertiesFormat;
+
+impl BundleFormat for PropertiesFormat {
+    fn extension(&self) -> &str {
+        "properties"
+    }
+
+    fn parse(&self, content: &str) -> Result<HashMap<String, String>, String> {
+        let mut messages = HashMap::new();
+        for (n, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+                continue;
+            }
+            // The separator is the first `=` not escaped with a backslash
+            let mut separator = None;
+            let mut escaped = false;
+            for (i, c) in line.char_indices() {
+                match c {
+                    '\\' if !escaped => escaped = true,
+                    '=' if !escaped => {
+                        separator = Some(i);
+                        break;
+                    }
+                    _ => escaped = false,
+                }
+            }
+            let Some(i) = separator else {
+                return Err(format!("line {}: expected 'key = value'", n + 1));
+            };
+            let key = line[..i].trim().replace("\\=", "=").replace("\\\\", "\\");
+            let value = line[i + 1..].trim().replace("\\n", "\n");
+            messages.insert(key, value);
+        }
+        Ok(messages)
+    }
+}
+
+/// Bundle formats read by [`Localizer::from_env`]
+pub const DEFAULT_FORMATS: &[&dyn BundleFormat] = &[&JsonFormat, &PropertiesFormat];
+
+/// An English message with `{}` placeholders, split at the placeholders
+#[derive(Debug)]
+struct Template {
+    parts: Vec<String>,
+    translation: String,
+}
+
+impl Template {
+    /// Values filled into the placeholders, if `message` matches
+    fn captures<'a>(&self, message: &'a str) -> Option<Vec<&'a str>> {
+        let (first, rest) = self.parts.split_first()?;
+        let mut remaining = message.strip_prefix(first.as_str())?;
+        let mut values = Vec::new();
+        for (i, part) in rest.iter().enumerate() {
+            let end = if i == rest.len() - 1 {
+                // The last literal anchors the end of the message
+                remaining.strip_suffix(part.as_str())?.len()
+            } else {
+                remaining.find(part.as_str())?
+            };
+            values.push(&remaining[..end]);
+            remaining = &remaining[end + part.len()..];
+        }
+        Some(values)
+    }
+}
+
+/// Fill `{}` (in order) and `{N}` (by position) placeholders
+fn fill(translation: &str, values: &[&str]) -> String {
+    let mut out = String::with_capacity(translation.len());
+    let mut next = 0;
+    let mut rest = translation;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let index = &after[..end];
+            let position = if index.is_empty() {
+                next += 1;
+                next - 1
+            } else {
+                index.parse().ok()?
+            };
+            Some((values.get(position)?, end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Translations for one language
+#[derive(Debug, Default)]
+struct Bundle {
+    messages: HashMap<String, String>,
+    templates: Vec<Template>,
+}
+
+impl Bundle {
+    fn new(messages: HashMap<String, String>) -> Self {
+        let templates = messages
+            .iter()
+            .filter(|(key, _)| key.contains("{}"))
+            .map(|(key, translation)| Template {
+                parts: key.split("{}").map(str::to_string).collect(),
+                translation: translation.clone(),
+            })
+            .collect();
+        Self {
+            messages,
+            templates,
+        }
+    }
+
+    fn translate(&self, message: &str) -> Option<String> {
+        if let Some(translation) = self.messages.get(message) {
+            return Some(translation.clone());
+        }
+        // Prefer the most specific template, the one with the most literal text
+        self.templates
+            .iter()
+            .filter_map(|t| Some((t, t.captures(message)?)))
+            .max_by_key(|(t, _)| t.parts.iter().map(String::len).sum::<usize>())
+            .map(|(t, values)| fill(&t.translation, &values))
+    }
+}
+
+/// Message bundles by language.
+#[derive(Debug, Default)]
+pub struct Localizer {
+    /// Keyed by lowercase language tag
+    bundles: HashMap<String, Bundle>,
+}
+
+impl Localizer {
+    /// Load bundles from `METAFUSE_I18N_DIR`, if set.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("METAFUSE_I18N_DIR") {
+            Ok(dir) if !dir.is_empty() => Self::load_dir(Path::new(&dir), DEFAULT_FORMATS),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Load every bundle in `dir` whose extension matches one of `formats`.
+    pub fn load_dir(dir: &Path, formats: &[&dyn BundleFormat]) -> Result<Self, String> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Invalid METAFUSE_I18N_DIR '{}': {}", dir.display(), e))?;
+        let mut localizer = Self::default();
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            let (Some(tag), Some(extension)) = (
+                path.file_stem().and_then(|s| s.to_str()),
+                path.extension().and_then(|s| s.to_str()),
+            ) else {
+                continue;
+            };
+            let Some(format) = formats.iter().find(|f| f.extension() == extension) else {
+                continue;
+            };
+            let invalid = |e: String| format!("Invalid message bundle '{}': {}", path.display(), e);
+            if !is_language_tag(tag) {
+                return Err(invalid("file name must be a language tag".to_string()));
+            }
+            let content = std::fs::read_to_string(&path).map_err(|e| invalid(e.to_string()))?;
+            let messages = format.parse(&content).map_err(invalid)?;
+            localizer.add_bundle(tag, messages);
+        }
+        Ok(localizer)
+    }
+
+    /// Add translations for a language, merged over any already loaded.
+    pub fn add_bundle(&mut self, tag: &str, messages: HashMap<String, String>) {
+        let bundle = self.bundles.entry(tag.to_ascii_lowercase()).or_default();
+        let mut merged = std::mem::take(&mut bundle.messages);
+        merged.extend(messages);
+        *bundle = Bundle::new(merged);
+    }
+
+    /// Languages with a bundle, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.bundles.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+
+    /// Pick the best language for an `Accept-Language` header.
+    ///
+    /// Preferences are tried by quality; `de-AT` falls back to a `de` bundle.
+    /// English, `*`, and no match select [`DEFAULT_LANGUAGE`].
+    pub fn negotiate(&self, accept_language: Option<&str>) -> String {
+        let mut preferences: Vec<(String, f32)> = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| {
+                let mut params = item.split(';');
+                let tag = params.next()?.trim().to_ascii_lowercase();
+                let quality = params
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable, so equal qualities keep header order
+        preferences.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (tag, _) in preferences {
+            let primary = tag.split('-').next().unwrap_or_default();
+            if tag == "*" || primary == DEFAULT_LANGUAGE {
+                break;
+            }
+            if self.bundles.contains_key(&tag) {
+                return tag;
+            }
+            if self.bundles.contains_key(primary) {
+                return primary.to_string();
+            }
+        }
+        DEFAULT_LANGUAGE.to_string()
+    }
+
+    /// Translate an English message, or return it unchanged
+    pub fn message(&self, language: &str, message: &str) -> String {
+        self.bundles
+            .get(language)
+            .and_then(|b| b.translate(message))
+            .unwrap_or_else(|| message.to_string())
+    }
+
+    /// Label for a key, falling back to English, then to the key itself
+    pub fn label(&self, language: &str, key: &str) -> String {
+        self.bundles
+            .get(language)
+            .and_then(|b| b.messages.get(key).cloned())
+            .or_else(|| {
+                ENGLISH_LABELS
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            })
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+fn is_language_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// The language negotiated for a request.
+///
+/// Inserted as a request extension by [`i18n_middleware`]; the default is
+/// English.
+#[derive(Debug, Clone)]
+pub struct Locale {
+    pub language: String,
+    localizer: Arc<Localizer>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            language: DEFAULT_LANGUAGE.to_string(),
+            localizer: Arc::new(Localizer::default()),
+        }
+    }
+}
+
+impl Locale {
+    pub fn new(language: &str, localizer: Arc<Localizer>) -> Self {
+        Self {
+            language: language.to_string(),
+            localizer,
+        }
+    }
+
+    /// Translate an English message
+    pub fn message(&self, message: &str) -> String {
+        self.localizer.message(&self.language, message)
+    }
+
+    /// Label for a key such as `classification.pii`
+    pub fn label(&self, key: &str) -> String {
+        self.localizer.label(&self.language, key)
+    }
+}
+
+/// Middleware that negotiates the response language and translates error
+/// messages.
+///
+/// Requires `Extension<Arc<Localizer>>`. Without bundles, responses are left
+/// untouched.
+pub async fn i18n_middleware(
+    Extension(localizer): Extension<Arc<Localizer>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let language = localizer.negotiate(
+        req.headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok()),
+    );
+    req.extensions_mut()
+        .insert(Locale::new(&language, Arc::clone(&localizer)));
+
+    let mut response = next.run(req).await;
+    if localizer.bundles.is_empty() {
+        return response;
+    }
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("Accept-Language"));
+    if let Ok(value) = HeaderValue::from_str(&language) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LANGUAGE, value);
+    }
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if language == DEFAULT_LANGUAGE || response.status().is_success() || !is_json {
+        return response;
+    }
+    // Reading the body consumes it, so check its size first
+    let too_big = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_none_or(|size| size > MAX_ERROR_BODY as u64);
+    if too_big {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read error response for translation");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let body = match translate_error_body(&localizer, &language, &bytes) {
+        Some(translated) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(translated)
+        }
+        None => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+/// Translate the `error` field of a JSON error body
+fn translate_error_body(localizer: &Localizer, language: &str, body: &[u8]) -> Option<Vec<u8>> {
+    let mut value: Value = serde_json::from_slice(body).ok()?;
+    let error = value.get_mut("error")?;
+    let translated = localizer.message(language, error.as_str()?);
+    *error = Value::String(translated);
+    serde_json::to_vec(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localizer() -> Localizer {
+        let mut localizer = Localizer::default();
+        localizer.add_bundle(
+            "de",
+            JsonFormat
+                .parse(
+                    r#"{
+                        "Dataset '{}' not found": "Datensatz '{}' nicht gefunden",
+                        "Tag '{}' is already on dataset '{}'": "Datensatz '{1}' hat bereits das Tag '{0}'",
+                        "classification": { "pii": "Personenbezogene Daten" }
+                    }"#,
+                )
+                .unwrap(),
+        );
+        localizer
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let localizer = localizer();
+        assert_eq!(localizer.negotiate(None), "en");
+        assert_eq!(localizer.negotiate(Some("de-AT,de;q=0.9")), "de");
+        assert_eq!(localizer.negotiate(Some("fr, de;q=0.5")), "de");
+        assert_eq!(localizer.negotiate(Some("en-US, de;q=0.5")), "en");
+        assert_eq!(localizer.negotiate(Some("de;q=0")), "en");
+    }
+
+    #[test]
+    fn test_translate_messages_and_labels() {
+        let localizer = localizer();
+        assert_eq!(
+            localizer.message("de", "Dataset 'orders' not found"),
+            "Datensatz 'orders' nicht gefunden"
+        );
+        assert_eq!(
+            localizer.message("de", "Tag 'pii' is already on dataset 'orders'"),
+            "Datensatz 'orders' hat bereits das Tag 'pii'"
+        );
+        assert_eq!(
+            localizer.message("de", "Internal server error"),
+            "Internal server error"
+        );
+        assert_eq!(
+            localizer.label("de", "classification.pii"),
+            "Personenbezogene Daten"
+        );
+        assert_eq!(localizer.label("de", "classification.public"), "Public");
+
+        let body = br#"{"error":"Dataset 'x' not found","request_id":"r1"}"#;
+        let translated: Value =
+            serde_json::from_slice(&translate_error_body(&localizer, "de", body).unwrap()).unwrap();
+        assert_eq!(translated["error"], "Datensatz 'x' nicht gefunden");
+        assert_eq!(translated["request_id"], "r1");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_errors_pass_through() {
+        use axum::{http::StatusCode, routing::get, Json, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/big",
+                get(|| async {
+                    let error = "x".repeat(MAX_ERROR_BODY);
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": error })),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn(i18n_middleware))
+            .layer(Extension(Arc::new(localizer())));
+        let request = Request::builder()
+            .uri("/big")
+            .header(header::ACCEPT_LANGUAGE, "de")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"].as_str().unwrap().len(), MAX_ERROR_BODY);
+    }
+
+    #[test]
+    fn test_properties_format() {
+        let messages = PropertiesFormat
+            .parse("# German\nquality.overall_score = Gesamtqualität\nlimit\\=1 = Grenze=1\n")
+            .unwrap();
+        assert_eq!(messages["quality.overall_score"], "Gesamtqualität");
+        assert_eq!(messages["limit=1"], "Grenze=1");
+        assert!(PropertiesFormat.parse("no separator").is_err());
+    }
+}
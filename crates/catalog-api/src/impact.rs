@@ -0,0 +1,455 @@
+//! Impact Analysis
+//!
+//! `GET /api/v1/datasets/{name}/impact` answers "who breaks if I change
+//! this?" before a column is dropped or a dataset renamed: the full
+//! transitive downstream closure of a dataset in dataset-level lineage, with
+//! each dataset's owner, owner contact details and tags, and the external
+//! sinks fed by any of them.
+//!
+//! - Trashed datasets are skipped, along with anything only reachable
+//!   through them (as in lineage diagrams)
+//! - Placeholders are walked through but not reported
+//! - Datasets the caller cannot read are walked through and counted in
+//!   `restricted`, without names or owners
+//! - Owners are matched to registered owners by `owner_id` or email
+//! - Registered [consumers](crate::consumers) of the dataset and of readable
+//!   downstream datasets are listed with their SLAs
+
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, bad_request, internal_error, AppState, DatasetPath, ErrorResponse,
+    RequestId,
+};
+use crate::{consumers, dataset_acl};
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::{external_nodes, placeholders, validation, Result};
+use metafuse_catalog_storage::read_snapshot;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+/// Maximum downstream datasets walked before the report is truncated
+pub const MAX_IMPACT_DATASETS: usize = 10_000;
+
+/// Contact details of a registered owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OwnerContact {
+    pub owner_id: String,
+    pub name: String,
+    pub owner_type: String,
+    pub email: Option<String>,
+    pub slack_channel: Option<String>,
+}
+
+/// A dataset downstream of the analyzed one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactedDataset {
+    pub name: String,
+    pub domain: Option<String>,
+    pub owner: Option<String>,
+    /// Contact of the owner, when registered in `/api/v1/owners`
+    pub owner_contact: Option<OwnerContact>,
+    pub tags: Vec<String>,
+    /// Lineage hops from the analyzed dataset (1 = direct consumer)
+    pub hops: usize,
+    /// Upstream dataset it was first reached through; `null` when that
+    /// dataset is restricted
+    pub via: Option<String>,
+}
+
+/// An owner with the downstream datasets they own.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactedOwner {
+    pub owner: String,
+    pub contact: Option<OwnerContact>,
+    pub datasets: Vec<String>,
+}
+
+/// An external sink fed by the analyzed dataset or its downstream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpactedExternal {
+    pub uri: String,
+    pub name: String,
+    pub system: Option<String>,
+    /// Dataset feeding it
+    pub via: String,
+}
+
+/// A registered consumer of the analyzed dataset or its downstream.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactedConsumer {
+    pub name: String,
+    pub contact: Option<String>,
+    pub read_frequency: Option<String>,
+    pub sla: Option<String>,
+    /// Dataset it reads
+    pub via: String,
+}
+
+/// Response for the impact analysis endpoint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactReport {
+    pub dataset: String,
+    /// Downstream datasets, nearest first
+    pub downstream: Vec<ImpactedDataset>,
+    /// Owners of downstream datasets, by owner
+    pub owners: Vec<ImpactedOwner>,
+    /// External sinks, by URI
+    pub external: Vec<ImpactedExternal>,
+    /// Registered consumers, nearest dataset first
+    pub consumers: Vec<ImpactedConsumer>,
+    /// Downstream datasets hidden by dataset ACLs
+    pub restricted: usize,
+    /// Whether the walk stopped at `MAX_IMPACT_DATASETS`
+    pub truncated: bool,
+}
+
+/// A downstream dataset as stored.
+struct Downstream {
+    id: i64,
+    name: String,
+    domain: Option<String>,
+    owner: Option<String>,
+    placeholder: bool,
+}
+
+/// Live datasets one hop downstream.
+fn downstream_of(conn: &Connection, id: i64, has_status: bool) -> Result<Vec<Downstream>> {
+    let status_expr = if has_status { "d.status" } else { "NULL" };
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT d.id, d.name, d.domain, d.owner, {}
+         FROM lineage l
+         JOIN datasets d ON d.id = l.downstream_dataset_id
+         WHERE l.upstream_dataset_id = ?1 AND d.deleted_at IS NULL
+         ORDER BY d.name",
+        status_expr
+    ))?;
+    let rows = stmt
+        .query_map([id], |row| {
+            let status: Option<String> = row.get(4)?;
+            Ok(Downstream {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                domain: row.get(2)?,
+                owner: row.get(3)?,
+                placeholder: status.as_deref() == Some(placeholders::STATUS_PENDING),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+fn tags_of(conn: &Connection, id: i64) -> Result<Vec<String>> {
+    let mut stmt =
+        conn.prepare_cached("SELECT tag FROM tags WHERE dataset_id = ?1 ORDER BY tag")?;
+    let tags = stmt
+        .query_map([id], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(tags)
+}
+
+/// Registered owner matching a dataset's `owner` by id or email.
+fn owner_contact(conn: &Connection, owner: &str) -> Result<Option<OwnerContact>> {
+    let contact = conn
+        .query_row(
+            "SELECT owner_id, name, owner_type, email, slack_channel FROM owners
+             WHERE owner_id = ?1 OR email = ?1
+             ORDER BY owner_id = ?1 DESC LIMIT 1",
+            [owner],
+            |row| {
+                Ok(OwnerContact {
+                    owner_id: row.get(0)?,
+                    name: row.get(1)?,
+                    owner_type: row.get(2)?,
+                    email: row.get(3)?,
+                    slack_channel: row.get(4)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(contact)
+}
+
+/// Walk everything downstream of a dataset.
+///
+/// `can_read` decides whether the caller may see a downstream dataset.
+pub fn analyze(
+    conn: &Connection,
+    dataset_id: i64,
+    dataset_name: &str,
+    mut can_read: impl FnMut(i64) -> Result<bool>,
+) -> Result<ImpactReport> {
+    let has_status = placeholders::has_status_column(conn)?;
+    let mut report = ImpactReport {
+        dataset: dataset_name.to_string(),
+        downstream: Vec::new(),
+        owners: Vec::new(),
+        external: Vec::new(),
+        consumers: Vec::new(),
+        restricted: 0,
+        truncated: false,
+    };
+
+    // Readable datasets whose external sinks are reported, with their names
+    let mut visible: Vec<(i64, String)> = vec![(dataset_id, dataset_name.to_string())];
+    let mut owners: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut contacts: BTreeMap<String, Option<OwnerContact>> = BTreeMap::new();
+    let mut visited = HashSet::from([dataset_id]);
+    let mut queue = VecDeque::from([(dataset_id, Some(dataset_name.to_string()), 0)]);
+
+    'walk: while let Some((id, name, hops)) = queue.pop_front() {
+        for next in downstream_of(conn, id, has_status)? {
+            if !visited.insert(next.id) {
+                continue;
+            }
+            if visited.len() > MAX_IMPACT_DATASETS {
+                report.truncated = true;
+                break 'walk;
+            }
+            if next.placeholder {
+                queue.push_back((next.id, Some(next.name), hops + 1));
+                continue;
+            }
+            if !can_read(next.id)? {
+                report.restricted += 1;
+                queue.push_back((next.id, None, hops + 1));
+                continue;
+            }
+            queue.push_back((next.id, Some(next.name.clone()), hops + 1));
+
+            let owner_contact = match next.owner.as_deref().filter(|o| !o.trim().is_empty()) {
+                Some(owner) => {
+                    owners
+                        .entry(owner.to_string())
+                        .or_default()
+                        .push(next.name.clone());
+                    if !contacts.contains_key(owner) {
+                        contacts.insert(owner.to_string(), owner_contact(conn, owner)?);
+                    }
+                    contacts[owner].clone()
+                }
+                None => None,
+            };
+            visible.push((next.id, next.name.clone()));
+            report.downstream.push(ImpactedDataset {
+                tags: tags_of(conn, next.id)?,
+                name: next.name,
+                domain: next.domain,
+                owner: next.owner,
+                owner_contact,
+                hops: hops + 1,
+                via: name.clone(),
+            });
+        }
+    }
+
+    report.owners = owners
+        .into_iter()
+        .map(|(owner, mut datasets)| {
+            datasets.sort();
+            ImpactedOwner {
+                contact: contacts.remove(&owner).flatten(),
+                owner,
+                datasets,
+            }
+        })
+        .collect();
+
+    for (id, name) in &visible {
+        for consumer in consumers::list(conn, *id)? {
+            report.consumers.push(ImpactedConsumer {
+                name: consumer.name,
+                contact: consumer.contact,
+                read_frequency: consumer.read_frequency,
+                sla: consumer.sla,
+                via: name.clone(),
+            });
+        }
+    }
+    let mut seen = BTreeSet::new();
+    for (id, name) in visible {
+        for (node, side) in external_nodes::for_dataset(conn, id)? {
+            if side == external_nodes::ExternalDirection::Downstream
+                && seen.insert(node.uri.clone())
+            {
+                report.external.push(ImpactedExternal {
+                    uri: node.uri,
+                    name: node.name,
+                    system: node.system,
+                    via: name.clone(),
+                });
+            }
+        }
+    }
+    report.external.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+    Ok(report)
+}
+
+/// Everything downstream of a dataset, with owners to notify before a breaking change
+pub(crate) async fn get_dataset_impact(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+) -> std::result::Result<Json<ImpactReport>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let req_id = request_id.0.clone();
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let report = tokio::task::spawn_blocking(move || {
+        let conn = read_snapshot(&conn)?;
+        analyze(&conn, dataset_id, &name, |id| {
+            Ok(dataset_acl::check(
+                &conn,
+                id,
+                &identity,
+                dataset_acl::AclPermission::Read,
+            )?)
+        })
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        dataset = %report.dataset,
+        downstream = report.downstream.len(),
+        owners = report.owners.len(),
+        "Impact analysis completed"
+    );
+
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        // raw -> staged -> mart -> report, raw -> audit (trashed) -> hidden,
+        // and mart -> raw closes a cycle
+        for (name, owner) in [
+            ("raw", Some("ingest")),
+            ("staged", Some("ingest")),
+            ("mart", Some("bi@example.com")),
+            ("report", None),
+            ("audit", Some("ingest")),
+            ("hidden", Some("ingest")),
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, owner, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', ?2, datetime('now'), datetime('now'))",
+                rusqlite::params![name, owner],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            r#"
+            UPDATE datasets SET deleted_at = datetime('now') WHERE name = 'audit';
+            INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+                (1, 2, datetime('now')), (2, 3, datetime('now')), (3, 4, datetime('now')),
+                (1, 5, datetime('now')), (5, 6, datetime('now')), (3, 1, datetime('now'));
+            INSERT INTO tags (dataset_id, tag) VALUES (3, 'gold'), (3, 'finance');
+            INSERT INTO owners (owner_id, name, owner_type, email, slack_channel)
+                VALUES ('bi-team', 'BI Team', 'team', 'bi@example.com', '#bi');
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_transitive_downstream_with_owners() {
+        let conn = setup();
+        let report = analyze(&conn, 1, "raw", |_| Ok(true)).unwrap();
+
+        let names: Vec<_> = report.downstream.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["staged", "mart", "report"]);
+        let mart = &report.downstream[1];
+        assert_eq!((mart.hops, mart.via.as_deref()), (2, Some("staged")));
+        assert_eq!(mart.tags, vec!["finance", "gold"]);
+        assert_eq!(
+            mart.owner_contact
+                .as_ref()
+                .unwrap()
+                .slack_channel
+                .as_deref(),
+            Some("#bi")
+        );
+
+        assert_eq!(report.owners.len(), 2);
+        assert_eq!(report.owners[0].owner, "bi@example.com");
+        assert_eq!(
+            report.owners[0].contact.as_ref().unwrap().owner_id,
+            "bi-team"
+        );
+        assert_eq!(report.owners[1].owner, "ingest");
+        assert!(report.owners[1].contact.is_none());
+        assert_eq!(report.restricted, 0);
+        assert!(!report.truncated);
+    }
+
+    #[test]
+    fn test_restricted_datasets_are_counted() {
+        let conn = setup();
+        let report = analyze(&conn, 1, "raw", |id| Ok(id != 3)).unwrap();
+
+        let names: Vec<_> = report.downstream.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["staged", "report"]);
+        assert_eq!(report.downstream[1].via, None);
+        assert_eq!(report.restricted, 1);
+        assert!(report.owners.iter().all(|o| o.owner != "bi@example.com"));
+    }
+
+    #[test]
+    fn test_consumers_of_readable_datasets() {
+        let conn = setup();
+        conn.execute_batch(
+            "INSERT INTO dataset_consumers (dataset_id, name, contact, read_frequency, sla) VALUES
+                (1, 'ingest-monitor', '#ingest', 'continuous', NULL),
+                (3, 'revenue-dashboard', 'bi@example.com', 'daily', 'refreshed by 06:00 UTC'),
+                (6, 'audit-export', NULL, NULL, NULL);",
+        )
+        .unwrap();
+
+        let report = analyze(&conn, 1, "raw", |_| Ok(true)).unwrap();
+        let consumers: Vec<_> = report
+            .consumers
+            .iter()
+            .map(|c| (c.name.as_str(), c.via.as_str()))
+            .collect();
+        // audit-export reads a dataset only reachable through the trash
+        assert_eq!(
+            consumers,
+            vec![("ingest-monitor", "raw"), ("revenue-dashboard", "mart")]
+        );
+        assert_eq!(
+            report.consumers[1].sla.as_deref(),
+            Some("refreshed by 06:00 UTC")
+        );
+
+        let report = analyze(&conn, 1, "raw", |id| Ok(id != 3)).unwrap();
+        assert_eq!(report.consumers.len(), 1);
+    }
+}
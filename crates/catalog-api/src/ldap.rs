@@ -0,0 +1,547 @@
+//! LDAP User Directory
+//!
+//! The `ldap` user directory (see `users`) reads people from an LDAP server
+//! or Active Directory. Users are searched under a base DN and reloaded
+//! periodically by [`ldap_sync_task`]; lookups read the in-memory copy, so a
+//! directory outage keeps the last loaded users.
+//!
+//! A user's teams are the groups of their `memberOf` attribute, named by the
+//! first value of each group DN (`cn=finance,ou=groups,dc=example,dc=com` is
+//! `finance`). Active Directory accounts with the disabled flag of
+//! `userAccountControl` set are deactivated.
+//!
+//! The directory connects with [`ldap3`] over `ldaps://`, or over `ldap://`
+//! upgraded with StartTLS. Certificates are verified against the system
+//! certificate store, or against `METAFUSE_LDAP_CA_FILE` for directories with
+//! a private CA. It binds with a simple bind, or anonymously without a bind
+//! DN, and pages searches with the simple paged results control, so
+//! directories with a server size limit are read in full.
+//!
+//! A bind password is never sent in clear text: configuring one on a plain
+//! `ldap://` URL without StartTLS stops the server from starting.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_LDAP_URL`: `ldaps://host:636` or `ldap://host:389` (required)
+//! - `METAFUSE_LDAP_STARTTLS`: upgrade `ldap://` connections with StartTLS
+//!   (default: false)
+//! - `METAFUSE_LDAP_CA_FILE`: PEM bundle of the CAs trusted for the server
+//!   certificate (default: the system certificate store)
+//! - `METAFUSE_LDAP_BASE_DN`: DN users are searched under (required)
+//! - `METAFUSE_LDAP_BIND_DN`, `METAFUSE_LDAP_BIND_PASSWORD`: simple bind
+//!   credentials (default: anonymous)
+//! - `METAFUSE_LDAP_USER_FILTER`: search filter (default: `(objectClass=person)`)
+//! - `METAFUSE_LDAP_ID_ATTRIBUTE`: attribute holding the user id (default:
+//!   `uid`; `sAMAccountName` on Active Directory)
+//! - `METAFUSE_LDAP_NAME_ATTRIBUTE`: display name attribute (default: `cn`)
+//! - `METAFUSE_LDAP_EMAIL_ATTRIBUTE`: email attribute (default: `mail`)
+//! - `METAFUSE_LDAP_GROUP_ATTRIBUTE`: group DN attribute (default: `memberOf`)
+//! - `METAFUSE_LDAP_TIMEOUT_SECS`: seconds a reload may take (default: 30)
+
+use crate::users::{DirectoryUser, UserDirectory};
+use ldap3::adapters::{Adapter, EntriesOnly, PagedResults};
+use ldap3::{LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Default seconds a reload may take
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Entries requested per page of a search
+const PAGE_SIZE: i32 = 500;
+
+/// `userAccountControl` flag of disabled Active Directory accounts
+const ACCOUNT_DISABLED: i64 = 0x2;
+
+/// Connection and mapping settings of the `ldap` directory
+#[derive(Clone)]
+pub struct LdapConfig {
+    pub url: String,
+    pub starttls: bool,
+    pub ca_file: Option<String>,
+    pub base_dn: String,
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    pub user_filter: String,
+    pub id_attribute: String,
+    pub name_attribute: String,
+    pub email_attribute: String,
+    pub group_attribute: String,
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for LdapConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LdapConfig")
+            .field("url", &self.url)
+            .field("starttls", &self.starttls)
+            .field("ca_file", &self.ca_file)
+            .field("base_dn", &self.base_dn)
+            .field("bind_dn", &self.bind_dn)
+            .field("user_filter", &self.user_filter)
+            .field("id_attribute", &self.id_attribute)
+            .finish()
+    }
+}
+
+impl LdapConfig {
+    /// Create config from environment variables.
+    ///
+    /// Fails without a URL or base DN, with an invalid URL, filter or CA
+    /// file, or with a bind password that would be sent in clear text, so a
+    /// misconfigured directory does not leave owners unvalidated.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let starttls = match var("METAFUSE_LDAP_STARTTLS") {
+            Some(v) => match v.to_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => {
+                    return Err(format!(
+                        "Invalid METAFUSE_LDAP_STARTTLS '{}': expected 'true' or 'false'",
+                        v
+                    ))
+                }
+            },
+            None => false,
+        };
+        let config = Self {
+            url: var("METAFUSE_LDAP_URL").ok_or_else(|| {
+                "METAFUSE_LDAP_URL is required for the ldap user directory".to_string()
+            })?,
+            starttls,
+            ca_file: var("METAFUSE_LDAP_CA_FILE"),
+            base_dn: var("METAFUSE_LDAP_BASE_DN").ok_or_else(|| {
+                "METAFUSE_LDAP_BASE_DN is required for the ldap user directory".to_string()
+            })?,
+            bind_dn: var("METAFUSE_LDAP_BIND_DN"),
+            bind_password: std::env::var("METAFUSE_LDAP_BIND_PASSWORD").ok(),
+            user_filter: var("METAFUSE_LDAP_USER_FILTER")
+                .unwrap_or_else(|| "(objectClass=person)".to_string()),
+            id_attribute: var("METAFUSE_LDAP_ID_ATTRIBUTE").unwrap_or_else(|| "uid".to_string()),
+            name_attribute: var("METAFUSE_LDAP_NAME_ATTRIBUTE").unwrap_or_else(|| "cn".to_string()),
+            email_attribute: var("METAFUSE_LDAP_EMAIL_ATTRIBUTE")
+                .unwrap_or_else(|| "mail".to_string()),
+            group_attribute: var("METAFUSE_LDAP_GROUP_ATTRIBUTE")
+                .unwrap_or_else(|| "memberOf".to_string()),
+            timeout_secs: var("METAFUSE_LDAP_TIMEOUT_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(DEFAULT_TIMEOUT_SECS),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the URL, TLS settings and filter.
+    ///
+    /// A bind password needs `ldaps://` or StartTLS: a simple bind sends it
+    /// as is.
+    pub fn validate(&self) -> Result<(), String> {
+        let tls = if self.url.starts_with("ldaps://") {
+            true
+        } else if self.url.starts_with("ldap://") {
+            false
+        } else {
+            return Err(format!(
+                "Invalid LDAP URL '{}': expected ldaps://host[:port] or ldap://host[:port]",
+                self.url
+            ));
+        };
+        if tls && self.starttls {
+            return Err(
+                "METAFUSE_LDAP_STARTTLS applies to ldap:// URLs; ldaps:// is already TLS"
+                    .to_string(),
+            );
+        }
+        if !tls && !self.starttls && self.bind_dn.is_some() && self.bind_password.is_some() {
+            return Err(
+                "METAFUSE_LDAP_BIND_PASSWORD would be sent in clear text over ldap://; \
+                 use an ldaps:// URL or set METAFUSE_LDAP_STARTTLS=true"
+                    .to_string(),
+            );
+        }
+        if let Some(path) = &self.ca_file {
+            tls_config(path)?;
+        }
+        ldap3::parse_filter(&self.user_filter)
+            .map_err(|_| format!("Invalid LDAP filter '{}'", self.user_filter))?;
+        Ok(())
+    }
+
+    /// Settings of a connection: StartTLS, the CA bundle and the timeout.
+    fn settings(&self) -> Result<LdapConnSettings, String> {
+        let mut settings = LdapConnSettings::new()
+            .set_conn_timeout(Duration::from_secs(self.timeout_secs))
+            .set_starttls(self.starttls);
+        if let Some(path) = &self.ca_file {
+            settings = settings.set_config(tls_config(path)?);
+        }
+        Ok(settings)
+    }
+}
+
+/// TLS client config trusting the CAs of a PEM bundle.
+fn tls_config(path: &str) -> Result<Arc<rustls::ClientConfig>, String> {
+    let pem = std::fs::read(path)
+        .map_err(|e| format!("Failed to read METAFUSE_LDAP_CA_FILE '{}': {}", path, e))?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .map_err(|e| format!("Invalid METAFUSE_LDAP_CA_FILE '{}': {}", path, e))?;
+    let mut roots = rustls::RootCertStore::empty();
+    let (added, _) = roots.add_parsable_certificates(&certs);
+    if added == 0 {
+        return Err(format!(
+            "METAFUSE_LDAP_CA_FILE '{}' contains no usable certificates",
+            path
+        ));
+    }
+    Ok(Arc::new(
+        rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
+
+/// Users read from an LDAP server.
+///
+/// Lookups read an in-memory copy, refreshed by [`ldap_sync_task`].
+#[derive(Debug, Clone)]
+pub struct LdapUsers {
+    config: Arc<LdapConfig>,
+    users: Arc<RwLock<HashMap<String, DirectoryUser>>>,
+}
+
+impl LdapUsers {
+    pub fn new(config: LdapConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            users: Arc::default(),
+        }
+    }
+
+    pub fn config(&self) -> &LdapConfig {
+        &self.config
+    }
+
+    /// Reload users from the server. Returns how many were loaded.
+    ///
+    /// On failure the previously loaded users are kept.
+    pub async fn refresh(&self) -> Result<usize, String> {
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let entries = tokio::time::timeout(timeout, search_users(&self.config))
+            .await
+            .map_err(|_| format!("LDAP search timed out after {:?}", timeout))??;
+        let mut users = HashMap::new();
+        for entry in &entries {
+            if let Some(user) = to_user(entry, &self.config) {
+                users.entry(user.id.clone()).or_insert(user);
+            }
+        }
+        let count = users.len();
+        *self.users.write().unwrap_or_else(|e| e.into_inner()) = users;
+        Ok(count)
+    }
+}
+
+impl UserDirectory for LdapUsers {
+    fn name(&self) -> &'static str {
+        "ldap"
+    }
+
+    fn user(&self, id: &str) -> Option<DirectoryUser> {
+        self.users
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+            .cloned()
+    }
+
+    fn users(&self) -> Vec<DirectoryUser> {
+        self.users
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Background task that reloads `ldap` users periodically
+pub async fn ldap_sync_task(directory: LdapUsers, interval_secs: u64) {
+    let interval = Duration::from_secs(interval_secs);
+
+    info!(
+        interval_secs,
+        url = %directory.config.url,
+        "LDAP user directory sync task started"
+    );
+
+    loop {
+        match directory.refresh().await {
+            Ok(count) => tracing::debug!(users = count, "Reloaded LDAP users"),
+            Err(e) => error!(error = %e, "Failed to reload LDAP users"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// =============================================================================
+// Search
+// =============================================================================
+
+/// Values of an attribute; attribute names are case-insensitive.
+fn values<'a>(entry: &'a SearchEntry, name: &str) -> &'a [String] {
+    entry
+        .attrs
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map_or(&[], |(_, values)| values.as_slice())
+}
+
+fn first(entry: &SearchEntry, name: &str) -> Option<String> {
+    values(entry, name).first().cloned()
+}
+
+fn to_user(entry: &SearchEntry, config: &LdapConfig) -> Option<DirectoryUser> {
+    let id = first(entry, &config.id_attribute)?;
+    let disabled = first(entry, "userAccountControl")
+        .and_then(|v| v.parse::<i64>().ok())
+        .is_some_and(|flags| flags & ACCOUNT_DISABLED != 0);
+    Some(DirectoryUser {
+        id,
+        display_name: first(entry, &config.name_attribute),
+        email: first(entry, &config.email_attribute),
+        teams: values(entry, &config.group_attribute)
+            .iter()
+            .filter_map(|dn| group_name(dn))
+            .collect(),
+        active: !disabled,
+        source: "ldap".to_string(),
+    })
+}
+
+/// Name of a group: the first value of its DN
+fn group_name(dn: &str) -> Option<String> {
+    let rdn = dn.split(',').next()?.trim();
+    let name = rdn.split_once('=').map_or(rdn, |(_, value)| value).trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Search the configured base DN for users, following result pages.
+async fn search_users(config: &LdapConfig) -> Result<Vec<SearchEntry>, String> {
+    let (conn, mut ldap) = LdapConnAsync::with_settings(config.settings()?, &config.url)
+        .await
+        .map_err(|e| format!("Failed to connect to LDAP server: {}", e))?;
+    tokio::spawn(async move {
+        if let Err(e) = conn.drive().await {
+            warn!(error = %e, "LDAP connection closed with an error");
+        }
+    });
+
+    if let Some(bind_dn) = &config.bind_dn {
+        ldap.simple_bind(bind_dn, config.bind_password.as_deref().unwrap_or(""))
+            .await
+            .and_then(|result| result.success())
+            .map_err(|e| format!("LDAP bind failed: {}", e))?;
+    }
+
+    let attributes = [
+        config.id_attribute.as_str(),
+        config.name_attribute.as_str(),
+        config.email_attribute.as_str(),
+        config.group_attribute.as_str(),
+        "userAccountControl",
+    ];
+    let adapters: Vec<Box<dyn Adapter<_, _>>> = vec![
+        Box::new(EntriesOnly::new()),
+        Box::new(PagedResults::new(PAGE_SIZE)),
+    ];
+    let search_failed = |e: ldap3::LdapError| format!("LDAP search failed: {}", e);
+    let mut stream = ldap
+        .streaming_search_with(
+            adapters,
+            &config.base_dn,
+            Scope::Subtree,
+            &config.user_filter,
+            attributes,
+        )
+        .await
+        .map_err(search_failed)?;
+    let mut entries = Vec::new();
+    while let Some(entry) = stream.next().await.map_err(search_failed)? {
+        entries.push(SearchEntry::construct(entry));
+    }
+    stream.finish().await.success().map_err(search_failed)?;
+    let _ = ldap.unbind().await;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn config(url: &str) -> LdapConfig {
+        LdapConfig {
+            url: url.to_string(),
+            starttls: false,
+            ca_file: None,
+            base_dn: "ou=people,dc=example,dc=com".to_string(),
+            bind_dn: Some("cn=metafuse,dc=example,dc=com".to_string()),
+            bind_password: Some("secret".to_string()),
+            user_filter: "(objectClass=person)".to_string(),
+            id_attribute: "uid".to_string(),
+            name_attribute: "cn".to_string(),
+            email_attribute: "mail".to_string(),
+            group_attribute: "memberOf".to_string(),
+            timeout_secs: 5,
+        }
+    }
+
+    fn entry(dn: &str, attributes: &[(&str, &[&str])]) -> SearchEntry {
+        SearchEntry {
+            dn: dn.to_string(),
+            attrs: attributes
+                .iter()
+                .map(|(name, values)| {
+                    (
+                        name.to_string(),
+                        values.iter().map(|v| v.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            bin_attrs: HashMap::new(),
+        }
+    }
+
+    /// A bind password is only accepted over ldaps:// or StartTLS
+    #[test]
+    fn test_validate_rejects_clear_text_bind() {
+        let error = config("ldap://ldap.example.com").validate().unwrap_err();
+        assert!(error.contains("clear text"), "{}", error);
+
+        let starttls = LdapConfig {
+            starttls: true,
+            ..config("ldap://ldap.example.com")
+        };
+        assert!(starttls.validate().is_ok());
+        assert!(config("ldaps://ldap.example.com:636").validate().is_ok());
+
+        // Anonymous binds send no password
+        let anonymous = LdapConfig {
+            bind_dn: None,
+            bind_password: None,
+            ..config("ldap://ldap.example.com")
+        };
+        assert!(anonymous.validate().is_ok());
+
+        let starttls_over_ldaps = LdapConfig {
+            starttls: true,
+            ..config("ldaps://ldap.example.com")
+        };
+        assert!(starttls_over_ldaps.validate().is_err());
+        assert!(config("https://ldap.example.com").validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_filter_and_ca_file() {
+        for invalid in ["(uid=a", "(uid=a)x", "(=a)", "(uid>=a*)"] {
+            let invalid = LdapConfig {
+                user_filter: invalid.to_string(),
+                ..config("ldaps://ldap.example.com")
+            };
+            assert!(invalid.validate().is_err(), "{}", invalid.user_filter);
+        }
+
+        let missing = LdapConfig {
+            ca_file: Some("/nonexistent/ldap-ca.pem".to_string()),
+            ..config("ldaps://ldap.example.com")
+        };
+        let error = missing.validate().unwrap_err();
+        assert!(error.contains("METAFUSE_LDAP_CA_FILE"), "{}", error);
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"not a certificate\n").unwrap();
+        let empty = LdapConfig {
+            ca_file: Some(file.path().display().to_string()),
+            ..config("ldaps://ldap.example.com")
+        };
+        let error = empty.validate().unwrap_err();
+        assert!(error.contains("no usable certificates"), "{}", error);
+    }
+
+    #[test]
+    fn test_entry_to_user() {
+        let config = config("ldaps://ldap.example.com");
+        let ana = to_user(
+            &entry(
+                "uid=ana,ou=people,dc=example,dc=com",
+                &[
+                    ("uid", &["ana"]),
+                    ("cn", &["Ana Lima"]),
+                    ("mail", &["ana@example.com"]),
+                    (
+                        "memberOf",
+                        &["cn=finance,ou=groups,dc=example,dc=com", "analysts"],
+                    ),
+                ],
+            ),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(ana.id, "ana");
+        assert_eq!(ana.display_name.as_deref(), Some("Ana Lima"));
+        assert_eq!(ana.email.as_deref(), Some("ana@example.com"));
+        assert_eq!(ana.teams, vec!["finance", "analysts"]);
+        assert_eq!(ana.source, "ldap");
+        assert!(ana.active);
+
+        // Attribute names are case-insensitive; disabled AD accounts are inactive
+        let bo = to_user(
+            &entry(
+                "uid=bo,ou=people,dc=example,dc=com",
+                &[("UID", &["bo"]), ("userAccountControl", &["514"])],
+            ),
+            &config,
+        )
+        .unwrap();
+        assert_eq!(bo.id, "bo");
+        assert!(!bo.active);
+
+        // Entries without an id are skipped
+        assert!(to_user(&entry("cn=service", &[("cn", &["service"])]), &config).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_keeps_loaded_users() {
+        // A port nothing listens on
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ldaps://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let users = LdapUsers::new(config(&url));
+        users.users.write().unwrap().insert(
+            "ana".to_string(),
+            DirectoryUser {
+                id: "ana".to_string(),
+                display_name: None,
+                email: None,
+                teams: Vec::new(),
+                active: true,
+                source: "ldap".to_string(),
+            },
+        );
+        let error = users.refresh().await.unwrap_err();
+        assert!(error.contains("Failed to connect"), "{}", error);
+        assert!(users.user("ana").is_some());
+    }
+}
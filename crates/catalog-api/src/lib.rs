@@ -2,6 +2,11 @@
 //!
 //! This crate provides library components for the MetaFuse Catalog API server,
 //! including API key management, rate limiting, and enterprise features.
+//!
+//! The server itself is available as a library too: [`build_router`] returns
+//! the API as an axum router to mount in another application, along with the
+//! [`BackgroundTasks`] it needs, and [`serve`] runs it the way the
+//! `metafuse-api` binary does.
 
 #[cfg(feature = "metrics")]
 pub mod metrics;
@@ -16,15 +21,170 @@ pub mod rate_limiting;
 #[cfg(feature = "audit")]
 pub mod audit;
 
+// Audit forwarding to SIEM collectors (syslog/HTTPS)
+#[cfg(feature = "audit")]
+pub mod audit_forwarder;
+
+// HyperLogLog sketches for unique user counts
+#[cfg(feature = "usage-analytics")]
+pub mod hll;
+
 #[cfg(feature = "usage-analytics")]
 pub mod usage_analytics;
 
+// Queryable API request logs with sampling and retention
+#[cfg(feature = "usage-analytics")]
+pub mod request_log;
+
 // Quality Framework (core functionality, not feature-gated)
 pub mod quality;
 
+// Dataset refs: pinned dataset versions for reproducibility
+pub mod dataset_refs;
+
+// Typeahead suggestions for the search box
+pub mod suggest;
+
+// Soft delete and scheduled purge of datasets
+pub mod trash;
+
+// Archival of retired datasets kept on record
+pub mod archive;
+
+// Catalog-wide tag and domain renames
+pub mod renames;
+
+// Dataset watch lists and watcher notifications
+pub mod subscriptions;
+
+// Outbound webhooks for catalog changes
+pub mod webhooks;
+
+// Scheduled catalog exports to tenant object storage (control plane)
+pub mod export_schedules;
+
+// Partition completion markers for producer/consumer handoffs
+pub mod markers;
+
+// External consumers of datasets with their SLAs
+pub mod consumers;
+
+// Hierarchical namespaces for dataset names
+pub mod namespaces;
+
+// Ephemeral sandbox namespaces for CI runs
+pub mod sandbox;
+
+// Self-diagnostics for operators
+pub mod diagnostics;
+
+// Opt-in rejection of unknown request body fields
+pub mod strict_json;
+
+// Dataset activity timeline
+pub mod timeline;
+
+// Bulk dataset lineage registration
+pub mod lineage_edges;
+
+// Lineage diagram export in DOT and Mermaid
+pub mod lineage_graph;
+
+// Relative freshness annotations for dataset responses
+pub mod freshness;
+
+// SVG quality and freshness badges for READMEs
+pub mod badges;
+
+// Orphaned dataset detection from the emitter heartbeat
+pub mod orphans;
+
+// Transitive downstream impact analysis
+pub mod impact;
+
+// Lineage cycle reports and periodic integrity checks
+pub mod lineage_integrity;
+
+// Schema-on-read for datasets registered without fields
+pub mod schema_on_read;
+
+// Dataset list sorting and date filters
+pub mod dataset_list;
+
+// Group providers resolving a caller's teams
+pub mod groups;
+
+// User directories for owners, verifiers and audit actors
+pub mod users;
+
+// LDAP / Active Directory user directory (optional)
+#[cfg(feature = "ldap-directory")]
+pub mod ldap;
+
+// SCIM provisioning of the user directory
+pub mod scim;
+
+// Field-level diffs of audited updates
+pub mod audit_diff;
+
+// Metadata completeness scores and leaderboard
+pub mod metadata_completeness;
+
+// Daily change digests per domain or owner
+pub mod digests;
+
+// Catalog-wide statistics with daily history
+pub mod catalog_stats;
+
+// Columnar format recommendations for CSV/JSON datasets
+pub mod format_advisor;
+
+// Tenant glossary inheritance from the global glossary
+pub mod glossary_scope;
+
+// Dataset UUIDs and integer id exposure
+pub mod public_ids;
+
+// Sparse fieldsets (?fields=) for list and search responses
+pub mod sparse_fields;
+
+// Named response profiles (?profile=) expanding to includes and fields
+pub mod response_profiles;
+
+// Dataset-level access control lists
+pub mod dataset_acl;
+
+// Per-field write permissions by tenant role
+pub mod field_permissions;
+
+// Write-path hooks on dataset writes
+pub mod write_hooks;
+
+// Base path and forwarded header handling for self-referencing URLs
+pub mod external_url;
+
+// ?envelope=true paging envelopes for collection endpoints
+pub mod envelope;
+
+// Cache-Control headers by endpoint class
+pub mod cache_control;
+
+// Per-route request time budgets
+pub mod timeouts;
+
+// Accept-Language localization of error messages and labels
+pub mod i18n;
+
+// Stable error codes in every error payload
+pub mod error_codes;
+
 #[cfg(feature = "classification")]
 pub mod classification;
 
+// Bulk review of classification findings
+#[cfg(feature = "classification")]
+pub mod classification_review;
+
 // Multi-Tenant Control Plane
 pub mod control_plane;
 
@@ -32,9 +192,20 @@ pub mod control_plane;
 #[cfg(feature = "api-keys")]
 pub mod tenant_resolver;
 
+// OIDC / JWT authentication for people, resolved alongside tenant API keys
+#[cfg(feature = "oidc")]
+pub mod oidc;
+
+// Public Read-Only Catalog Mode
+#[cfg(feature = "api-keys")]
+pub mod public_catalog;
+
 // Multi-Tenant Integration Layer
 pub mod multi_tenant;
 
+// Tenant data residency zones and region-specific storage
+pub mod data_residency;
+
 // v0.9.0: Alerting and Data Contracts
 #[cfg(feature = "alerting")]
 pub mod alerting;
@@ -46,6 +217,51 @@ pub mod contracts;
 #[cfg(feature = "column-lineage")]
 pub mod lineage;
 
+// Embedding-based semantic search
+#[cfg(feature = "semantic-search")]
+pub mod semantic_search;
+
+// Tenant administration and self-service usage endpoints
+#[cfg(feature = "api-keys")]
+pub mod admin;
+
+// Dataset listing, detail and search endpoints
+pub mod datasets;
+
+// Dataset create, update, patch and delete endpoints
+pub mod dataset_writes;
+
+// Catalog export and import bundles
+pub mod catalog_export;
+
+// Dataset tag endpoints
+pub mod tags;
+
+// Owner registry endpoints
+pub mod owners;
+
+// Domain registry endpoints
+pub mod domains;
+
+// Business glossary endpoints
+pub mod glossary;
+
+// Governance rule endpoints
+pub mod governance;
+
+// Auto-tagging rule endpoints
+pub mod auto_tag_rules;
+
+// Custom metadata endpoints
+pub mod custom_metadata;
+
+// Delta-delegated schema, stats and history endpoints
+pub mod delta_metadata;
+
+// Router and server entry points, shared by the binary and embedding applications
+pub mod server;
+pub use server::{build_router, serve, BackgroundTasks, ServerConfig};
+
 // Test utilities (feature-gated)
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
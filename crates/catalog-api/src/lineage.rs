@@ -14,7 +14,21 @@
 //! - `GET /api/v1/lineage/dataset/:id/columns/:column/pii-propagation` - Track PII propagation
 //! - `GET /api/v1/lineage/fields/:id/impact` - Impact analysis for field changes
 //! - `DELETE /api/v1/lineage/dataset/:id` - Delete lineage edges for a dataset
-
+//!
+//! Lookups start only from datasets the caller may read, and leave out
+//! datasets hidden by dataset ACLs along the way.
+
+use crate::dataset_acl;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{require_dataset_access, AppState, RequestId};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::Extension;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
@@ -22,14 +36,18 @@ use axum::{
 };
 use metafuse_catalog_lineage::{ColumnLineageEdge, ColumnLineageParser, LineageParseResult};
 use metafuse_catalog_storage::DynCatalogBackend;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// Application state containing the catalog backend.
-/// This is a copy of the AppState structure from main.rs to avoid circular dependencies.
+/// This is a copy of the AppState structure from server.rs to avoid circular dependencies.
 #[derive(Clone)]
 pub struct LineageAppState {
     pub backend: Arc<DynCatalogBackend>,
+    /// Extra SQL condition on the dataset alias `d` hiding datasets from the
+    /// caller under dataset ACLs, with its bindings
+    pub visibility: Option<(String, Vec<String>)>,
 }
 
 /// Request to parse SQL and extract lineage.
@@ -218,10 +236,28 @@ pub struct PiiDownstreamColumn {
     pub expression: Option<String>,
 }
 
+/// Bindings for a lineage traversal query, extended with those of the
+/// caller's visibility condition, and that condition as an `AND` suffix for
+/// the final join on `datasets d`.
+fn visible_traversal(
+    visibility: &Option<(String, Vec<String>)>,
+    mut bindings: Vec<Box<dyn rusqlite::ToSql>>,
+) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    match visibility {
+        Some((clause, values)) => {
+            for value in values {
+                bindings.push(Box::new(value.clone()));
+            }
+            (format!("AND {}", clause), bindings)
+        }
+        None => (String::new(), bindings),
+    }
+}
+
 /// Parse SQL and extract column lineage.
 #[axum::debug_handler]
 pub async fn parse_lineage(
-    Json(request): Json<ParseLineageRequest>,
+    JsonBody(request): JsonBody<ParseLineageRequest>,
 ) -> Result<Json<ParseLineageResponse>, (StatusCode, String)> {
     let parser = ColumnLineageParser::new();
 
@@ -235,7 +271,7 @@ pub async fn parse_lineage(
 #[axum::debug_handler]
 pub async fn record_lineage(
     State(state): State<LineageAppState>,
-    Json(request): Json<RecordLineageRequest>,
+    JsonBody(request): JsonBody<RecordLineageRequest>,
 ) -> Result<Json<RecordLineageResponse>, (StatusCode, String)> {
     let conn = state.backend.get_connection().await.map_err(|e| {
         (
@@ -287,7 +323,7 @@ pub async fn get_upstream_lineage(
     // Get root node info
     let dataset_name: String = conn
         .query_row(
-            "SELECT name FROM datasets WHERE id = ?1",
+            "SELECT name FROM datasets WHERE id = ?1 AND deleted_at IS NULL",
             [dataset_id],
             |row| row.get(0),
         )
@@ -303,7 +339,14 @@ pub async fn get_upstream_lineage(
     };
 
     // Query upstream lineage (recursive CTE)
-    let query = r#"
+    let traversal: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(dataset_id),
+        Box::new(column.clone()),
+        Box::new(params.max_depth),
+    ];
+    let (visible, bindings) = visible_traversal(&state.visibility, traversal);
+    let query = format!(
+        r#"
         WITH RECURSIVE upstream_lineage AS (
             -- Base case: direct upstream of target column
             SELECT
@@ -338,11 +381,12 @@ pub async fn get_upstream_lineage(
             ul.expression,
             ul.depth
         FROM upstream_lineage ul
-        JOIN datasets d ON d.id = ul.source_dataset_id
+        JOIN datasets d ON d.id = ul.source_dataset_id AND d.deleted_at IS NULL {visible}
         ORDER BY ul.depth
-        "#;
+        "#
+    );
 
-    let mut stmt = conn.prepare(query).map_err(|e| {
+    let mut stmt = conn.prepare(&query).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Query error: {}", e),
@@ -350,19 +394,16 @@ pub async fn get_upstream_lineage(
     })?;
 
     let nodes: Vec<LineageNode> = stmt
-        .query_map(
-            rusqlite::params![dataset_id, column, params.max_depth],
-            |row| {
-                Ok(LineageNode {
-                    dataset_id: row.get(0)?,
-                    dataset_name: row.get(1)?,
-                    column_name: row.get(2)?,
-                    transformation_type: row.get(3)?,
-                    expression: row.get(4)?,
-                    depth: row.get(5)?,
-                })
-            },
-        )
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            Ok(LineageNode {
+                dataset_id: row.get(0)?,
+                dataset_name: row.get(1)?,
+                column_name: row.get(2)?,
+                transformation_type: row.get(3)?,
+                expression: row.get(4)?,
+                depth: row.get(5)?,
+            })
+        })
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -397,7 +438,7 @@ pub async fn get_downstream_lineage(
     // Get root node info
     let dataset_name: String = conn
         .query_row(
-            "SELECT name FROM datasets WHERE id = ?1",
+            "SELECT name FROM datasets WHERE id = ?1 AND deleted_at IS NULL",
             [dataset_id],
             |row| row.get(0),
         )
@@ -413,7 +454,14 @@ pub async fn get_downstream_lineage(
     };
 
     // Query downstream lineage (recursive CTE)
-    let query = r#"
+    let traversal: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(dataset_id),
+        Box::new(column.clone()),
+        Box::new(params.max_depth),
+    ];
+    let (visible, bindings) = visible_traversal(&state.visibility, traversal);
+    let query = format!(
+        r#"
         WITH RECURSIVE downstream_lineage AS (
             -- Base case: direct downstream of source column
             SELECT
@@ -448,11 +496,12 @@ pub async fn get_downstream_lineage(
             dl.expression,
             dl.depth
         FROM downstream_lineage dl
-        JOIN datasets d ON d.id = dl.target_dataset_id
+        JOIN datasets d ON d.id = dl.target_dataset_id AND d.deleted_at IS NULL {visible}
         ORDER BY dl.depth
-        "#;
+        "#
+    );
 
-    let mut stmt = conn.prepare(query).map_err(|e| {
+    let mut stmt = conn.prepare(&query).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Query error: {}", e),
@@ -460,19 +509,16 @@ pub async fn get_downstream_lineage(
     })?;
 
     let nodes: Vec<LineageNode> = stmt
-        .query_map(
-            rusqlite::params![dataset_id, column, params.max_depth],
-            |row| {
-                Ok(LineageNode {
-                    dataset_id: row.get(0)?,
-                    dataset_name: row.get(1)?,
-                    column_name: row.get(2)?,
-                    transformation_type: row.get(3)?,
-                    expression: row.get(4)?,
-                    depth: row.get(5)?,
-                })
-            },
-        )
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            Ok(LineageNode {
+                dataset_id: row.get(0)?,
+                dataset_name: row.get(1)?,
+                column_name: row.get(2)?,
+                transformation_type: row.get(3)?,
+                expression: row.get(4)?,
+                depth: row.get(5)?,
+            })
+        })
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -507,7 +553,14 @@ pub async fn get_pii_propagation(
     })?;
 
     // Query downstream lineage with anonymization detection
-    let query = r#"
+    let traversal: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(dataset_id),
+        Box::new(column.clone()),
+        Box::new(params.max_depth),
+    ];
+    let (visible, bindings) = visible_traversal(&state.visibility, traversal);
+    let query = format!(
+        r#"
         WITH RECURSIVE downstream_lineage AS (
             -- Base case: direct downstream of source column
             SELECT
@@ -551,11 +604,12 @@ pub async fn get_pii_propagation(
                  OR dl.expression LIKE '%ROUND%'
                  THEN 1 ELSE 0 END as may_anonymize
         FROM downstream_lineage dl
-        JOIN datasets d ON d.id = dl.target_dataset_id
+        JOIN datasets d ON d.id = dl.target_dataset_id AND d.deleted_at IS NULL {visible}
         ORDER BY dl.depth
-    "#;
+    "#
+    );
 
-    let mut stmt = conn.prepare(query).map_err(|e| {
+    let mut stmt = conn.prepare(&query).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Query error: {}", e),
@@ -563,21 +617,18 @@ pub async fn get_pii_propagation(
     })?;
 
     let downstream_columns: Vec<PiiDownstreamColumn> = stmt
-        .query_map(
-            rusqlite::params![dataset_id, column, params.max_depth],
-            |row| {
-                Ok(PiiDownstreamColumn {
-                    dataset_id: row.get(0)?,
-                    dataset_name: row.get(1)?,
-                    column_name: row.get(2)?,
-                    transformation_type: row
-                        .get::<_, Option<String>>(3)?
-                        .unwrap_or_else(|| "Direct".to_string()),
-                    expression: row.get(4)?,
-                    may_anonymize: row.get::<_, i32>(5)? != 0,
-                })
-            },
-        )
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            Ok(PiiDownstreamColumn {
+                dataset_id: row.get(0)?,
+                dataset_name: row.get(1)?,
+                column_name: row.get(2)?,
+                transformation_type: row
+                    .get::<_, Option<String>>(3)?
+                    .unwrap_or_else(|| "Direct".to_string()),
+                expression: row.get(4)?,
+                may_anonymize: row.get::<_, i32>(5)? != 0,
+            })
+        })
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -667,7 +718,7 @@ pub async fn get_field_impact(
             SELECT f.id, f.name, f.dataset_id, d.name as dataset_name
             FROM fields f
             JOIN datasets d ON d.id = f.dataset_id
-            WHERE f.id = ?1
+            WHERE f.id = ?1 AND d.deleted_at IS NULL
             "#,
             [field_id],
             |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
@@ -682,7 +733,14 @@ pub async fn get_field_impact(
     };
 
     // Query downstream lineage
-    let query = r#"
+    let traversal: Vec<Box<dyn rusqlite::ToSql>> = vec![
+        Box::new(field.dataset_id),
+        Box::new(field.field_name.clone()),
+        Box::new(params.max_depth),
+    ];
+    let (visible, bindings) = visible_traversal(&state.visibility, traversal);
+    let query = format!(
+        r#"
         WITH RECURSIVE downstream_lineage AS (
             SELECT
                 cl.target_dataset_id,
@@ -712,11 +770,12 @@ pub async fn get_field_impact(
             dl.transformation_type,
             dl.depth
         FROM downstream_lineage dl
-        JOIN datasets d ON d.id = dl.target_dataset_id
+        JOIN datasets d ON d.id = dl.target_dataset_id AND d.deleted_at IS NULL {visible}
         ORDER BY dl.depth, d.name, dl.target_field_name
-    "#;
+    "#
+    );
 
-    let mut stmt = conn.prepare(query).map_err(|e| {
+    let mut stmt = conn.prepare(&query).map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Query error: {}", e),
@@ -724,30 +783,27 @@ pub async fn get_field_impact(
     })?;
 
     let affected_columns: Vec<AffectedColumn> = stmt
-        .query_map(
-            rusqlite::params![field.dataset_id, field.field_name, params.max_depth],
-            |row| {
-                let depth: i32 = row.get(4)?;
-                let transformation_type: String = row
-                    .get::<_, Option<String>>(3)?
-                    .unwrap_or_else(|| "Direct".to_string());
-                let severity = match depth {
-                    1 if transformation_type == "Direct" => "high",
-                    1 => "medium",
-                    _ => "low",
-                }
-                .to_string();
-
-                Ok(AffectedColumn {
-                    dataset_id: row.get(0)?,
-                    dataset_name: row.get(1)?,
-                    column_name: row.get(2)?,
-                    transformation_type,
-                    depth,
-                    severity,
-                })
-            },
-        )
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            let depth: i32 = row.get(4)?;
+            let transformation_type: String = row
+                .get::<_, Option<String>>(3)?
+                .unwrap_or_else(|| "Direct".to_string());
+            let severity = match depth {
+                1 if transformation_type == "Direct" => "high",
+                1 => "medium",
+                _ => "low",
+            }
+            .to_string();
+
+            Ok(AffectedColumn {
+                dataset_id: row.get(0)?,
+                dataset_name: row.get(1)?,
+                column_name: row.get(2)?,
+                transformation_type,
+                depth,
+                severity,
+            })
+        })
         .map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -832,9 +888,11 @@ pub async fn delete_dataset_lineage(
 
     // Verify the dataset exists (provides better error message and implicit tenant check)
     let dataset_exists: bool = conn
-        .query_row("SELECT 1 FROM datasets WHERE id = ?1", [dataset_id], |_| {
-            Ok(true)
-        })
+        .query_row(
+            "SELECT 1 FROM datasets WHERE id = ?1 AND deleted_at IS NULL",
+            [dataset_id],
+            |_| Ok(true),
+        )
         .unwrap_or(false);
 
     if !dataset_exists {
@@ -861,9 +919,388 @@ pub async fn delete_dataset_lineage(
     }))
 }
 
-// Note: Handlers are exported for use in main.rs routes
+// Note: Handlers are exported for use in server.rs routes
 // They require LineageAppState which wraps the catalog backend
 
+/// Parse SQL and extract column lineage
+pub(crate) async fn lineage_parse(
+    JsonBody(request): JsonBody<ParseLineageRequest>,
+) -> Result<Json<ParseLineageResponse>, (StatusCode, String)> {
+    use metafuse_catalog_lineage::ColumnLineageParser;
+
+    let parser = ColumnLineageParser::new();
+    match parser.parse_lineage(&request.sql, &request.target_dataset) {
+        Ok(result) => Ok(Json(ParseLineageResponse::from(result))),
+        Err(e) => Err((StatusCode::BAD_REQUEST, format!("Parse error: {}", e))),
+    }
+}
+
+/// Record lineage edges in the database
+pub(crate) async fn lineage_record(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(request): JsonBody<RecordLineageRequest>,
+) -> Result<Json<RecordLineageResponse>, (StatusCode, String)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(|e| (e.0, e.1.error.clone()))?;
+
+    // Input validation: dataset IDs must be positive
+    if request.source_dataset_id <= 0 || request.target_dataset_id <= 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Dataset IDs must be positive integers".to_string(),
+        ));
+    }
+
+    // Input validation: limit number of edges per request
+    const MAX_EDGES_PER_REQUEST: usize = 1000;
+    if request.edges.len() > MAX_EDGES_PER_REQUEST {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Maximum {} edges per request", MAX_EDGES_PER_REQUEST),
+        ));
+    }
+
+    // Input validation: column names
+    for edge in &request.edges {
+        if edge.source_column.is_empty() || edge.target_column.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Column names cannot be empty".to_string(),
+            ));
+        }
+        if edge.source_column.len() > 256 || edge.target_column.len() > 256 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Column names cannot exceed 256 characters".to_string(),
+            ));
+        }
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend.get_connection().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database connection error: {}", e),
+        )
+    })?;
+
+    let mut edges_recorded = 0;
+    for edge in &request.edges {
+        conn.execute(
+            "INSERT INTO column_lineage (source_dataset_id, source_field_name, target_dataset_id, target_field_name, transformation_type, expression)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                request.source_dataset_id,
+                edge.source_column,
+                request.target_dataset_id,
+                edge.target_column,
+                edge.transformation_type,
+                edge.expression
+            ],
+        ).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?;
+        edges_recorded += 1;
+    }
+
+    #[cfg(feature = "metrics")]
+    metrics::record_lineage_operation("record", "success");
+
+    Ok(Json(RecordLineageResponse { edges_recorded }))
+}
+
+/// Maximum recursion depth for lineage traversal
+pub(crate) const MAX_LINEAGE_DEPTH: i32 = 50;
+
+/// Validate and clamp lineage lookup parameters
+pub(crate) fn validate_lineage_params(
+    dataset_id: i64,
+    column: &str,
+    params: &LineageLookupParams,
+) -> Result<LineageLookupParams, (StatusCode, String)> {
+    // Dataset ID must be positive
+    if dataset_id <= 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Dataset ID must be a positive integer".to_string(),
+        ));
+    }
+
+    // Column name validation
+    if column.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Column name cannot be empty".to_string(),
+        ));
+    }
+    if column.len() > 256 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Column name cannot exceed 256 characters".to_string(),
+        ));
+    }
+
+    // Clamp max_depth to prevent excessive recursion
+    let clamped_depth = params.max_depth.clamp(1, MAX_LINEAGE_DEPTH);
+
+    Ok(LineageLookupParams {
+        max_depth: clamped_depth,
+    })
+}
+
+/// Check the caller may read the dataset a column-lineage lookup starts from,
+/// returning the condition that hides restricted datasets along the way.
+/// Hidden datasets are reported as not found; missing ones are left to the
+/// lookup itself.
+pub(crate) async fn require_lineage_dataset_access(
+    backend: &Arc<DynCatalogBackend>,
+    dataset_id: i64,
+    identity: &dataset_acl::Identity,
+    request_id: &RequestId,
+) -> Result<Option<(String, Vec<String>)>, (StatusCode, String)> {
+    let conn = backend.get_connection().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database connection error: {}", e),
+        )
+    })?;
+    let name: Option<String> = conn
+        .query_row(
+            "SELECT name FROM datasets WHERE id = ?1 AND deleted_at IS NULL",
+            [dataset_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?;
+    if let Some(name) = name {
+        require_dataset_access(
+            &conn,
+            dataset_id,
+            &name,
+            Some(identity),
+            dataset_acl::AclPermission::Read,
+            request_id,
+        )
+        .map_err(|e| (e.0, e.1.error.clone()))?;
+    }
+    Ok(dataset_acl::visibility_clause(identity, "d.id", "d.domain"))
+}
+
+/// Get upstream lineage for a column
+pub(crate) async fn lineage_upstream(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path((dataset_id, column)): Path<(i64, String)>,
+    Query(params): Query<LineageLookupParams>,
+) -> Result<Json<LineageLookupResponse>, (StatusCode, String)> {
+    // Validate and clamp parameters
+    let validated_params = validate_lineage_params(dataset_id, &column, &params)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility =
+        require_lineage_dataset_access(&backend, dataset_id, &identity, &request_id).await?;
+    let lineage_state = LineageAppState {
+        backend,
+        visibility,
+    };
+
+    #[cfg(feature = "metrics")]
+    metrics::record_lineage_query("upstream", "success");
+
+    get_upstream_lineage(
+        State(lineage_state),
+        Path((dataset_id, column)),
+        Query(validated_params),
+    )
+    .await
+}
+
+/// Get downstream lineage for a column
+pub(crate) async fn lineage_downstream(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path((dataset_id, column)): Path<(i64, String)>,
+    Query(params): Query<LineageLookupParams>,
+) -> Result<Json<LineageLookupResponse>, (StatusCode, String)> {
+    // Validate and clamp parameters
+    let validated_params = validate_lineage_params(dataset_id, &column, &params)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility =
+        require_lineage_dataset_access(&backend, dataset_id, &identity, &request_id).await?;
+    let lineage_state = LineageAppState {
+        backend,
+        visibility,
+    };
+
+    #[cfg(feature = "metrics")]
+    metrics::record_lineage_query("downstream", "success");
+
+    get_downstream_lineage(
+        State(lineage_state),
+        Path((dataset_id, column)),
+        Query(validated_params),
+    )
+    .await
+}
+
+/// Get PII propagation for a column
+pub(crate) async fn lineage_pii_propagation(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path((dataset_id, column)): Path<(i64, String)>,
+    Query(params): Query<LineageLookupParams>,
+) -> Result<Json<PiiPropagationResponse>, (StatusCode, String)> {
+    // Validate and clamp parameters
+    let validated_params = validate_lineage_params(dataset_id, &column, &params)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility =
+        require_lineage_dataset_access(&backend, dataset_id, &identity, &request_id).await?;
+    let lineage_state = LineageAppState {
+        backend,
+        visibility,
+    };
+
+    #[cfg(feature = "metrics")]
+    metrics::record_lineage_query("pii_propagation", "success");
+
+    get_pii_propagation(
+        State(lineage_state),
+        Path((dataset_id, column)),
+        Query(validated_params),
+    )
+    .await
+}
+
+/// Delete lineage edges for a dataset
+pub(crate) async fn lineage_delete_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(dataset_id): Path<i64>,
+) -> Result<Json<DeleteLineageResponse>, (StatusCode, String)> {
+    // Check delete permission in multi-tenant mode (requires Admin role)
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(|e| (e.0, e.1.error.clone()))?;
+
+    // Input validation
+    if dataset_id <= 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Dataset ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let lineage_state = LineageAppState {
+        backend,
+        visibility: None,
+    };
+
+    let result = delete_dataset_lineage(State(lineage_state), Path(dataset_id)).await;
+
+    #[cfg(feature = "metrics")]
+    if result.is_ok() {
+        metrics::record_lineage_operation("delete", "success");
+    } else {
+        metrics::record_lineage_operation("delete", "error");
+    }
+
+    result
+}
+
+/// Get impact analysis for a field change
+pub(crate) async fn lineage_field_impact(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path(field_id): Path<i64>,
+    Query(params): Query<LineageLookupParams>,
+) -> Result<Json<ImpactAnalysisResponse>, (StatusCode, String)> {
+    // Input validation
+    if field_id <= 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Field ID must be a positive integer".to_string(),
+        ));
+    }
+
+    // Clamp max_depth
+    let clamped_depth = params.max_depth.clamp(1, MAX_LINEAGE_DEPTH);
+    let validated_params = LineageLookupParams {
+        max_depth: clamped_depth,
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let conn = backend.get_connection().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database connection error: {}", e),
+        )
+    })?;
+    let dataset_id: Option<i64> = conn
+        .query_row(
+            "SELECT dataset_id FROM fields WHERE id = ?1",
+            [field_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Database error: {}", e),
+            )
+        })?;
+    drop(conn);
+    let visibility = match dataset_id {
+        Some(dataset_id) => {
+            require_lineage_dataset_access(&backend, dataset_id, &identity, &request_id).await?
+        }
+        None => None,
+    };
+    let lineage_state = LineageAppState {
+        backend,
+        visibility,
+    };
+
+    #[cfg(feature = "metrics")]
+    metrics::record_lineage_query("impact_analysis", "success");
+
+    get_field_impact(
+        State(lineage_state),
+        Path(field_id),
+        Query(validated_params),
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
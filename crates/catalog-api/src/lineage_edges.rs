@@ -0,0 +1,1026 @@
+//! Bulk Dataset Lineage
+//!
+//! Lets orchestrators register dataset-level lineage separately from datasets:
+//! `POST /api/v1/lineage` with a batch of upstream -> downstream edges, each
+//! endpoint given by dataset name or URN (`urn:metafuse:dataset:<name>`).
+//!
+//! Edges are validated one by one and reported individually, so one bad edge
+//! does not reject the batch. An edge whose endpoint is not registered is
+//! handled by the lineage mode (see `metafuse_catalog_core::lineage_mode`):
+//! it fails (`strict`, the default), gets a placeholder dataset
+//! (`placeholder`, or `create_placeholders`), or is skipped (`ignore`).
+//!
+//! Either endpoint may instead be an external node (`{"type": "external",
+//! "uri": ..., "system": ...}`) for sources and sinks outside the catalog;
+//! the other endpoint must then be a dataset (see
+//! `metafuse_catalog_core::external_nodes`).
+//!
+//! Job and run metadata from the request are stored on every edge in the
+//! batch; re-reporting an edge updates them to the latest run.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_LINEAGE_MODE`: server-wide lineage mode (`strict`,
+//!   `placeholder`, or `ignore`) for requests that don't set one. When unset,
+//!   bulk lineage is strict and dataset creation ignores unknown upstreams.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_acl::{self, AclPermission, Identity};
+use crate::envelope;
+use crate::error_codes::ErrorCode;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::not_found;
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use metafuse_catalog_core::external_nodes::{self, ExternalDirection};
+use metafuse_catalog_core::lineage_mode::{self, LineageMode, Resolved};
+use metafuse_catalog_core::validation;
+use metafuse_catalog_core::{lineage_cycles, urn, CatalogError};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Maximum number of edges per request
+pub const MAX_EDGES_PER_REQUEST: usize = 1000;
+
+/// External node type tag
+pub const EXTERNAL_NODE_TYPE: &str = "external";
+
+/// One side of an edge.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LineageEndpoint {
+    /// Dataset name or URN
+    Dataset(String),
+    External(ExternalNodeRef),
+}
+
+/// Reference to a node outside the catalog.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalNodeRef {
+    /// Must be `external`
+    #[serde(rename = "type")]
+    pub node_type: String,
+    /// Location in the external system; identifies the node
+    pub uri: String,
+    /// Display name (default: the URI)
+    pub name: Option<String>,
+    /// Owning system, e.g. `vendor_sftp`
+    pub system: Option<String>,
+}
+
+impl LineageEndpoint {
+    /// Label used in per-edge results.
+    pub fn label(&self) -> &str {
+        match self {
+            LineageEndpoint::Dataset(reference) => reference,
+            LineageEndpoint::External(node) => &node.uri,
+        }
+    }
+}
+
+impl From<&str> for LineageEndpoint {
+    fn from(reference: &str) -> Self {
+        LineageEndpoint::Dataset(reference.to_string())
+    }
+}
+
+/// One edge in a bulk request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewLineageEdge {
+    pub upstream: LineageEndpoint,
+    pub downstream: LineageEndpoint,
+}
+
+/// Orchestrator job that produced the edges.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LineageJob {
+    pub name: Option<String>,
+    pub run_id: Option<String>,
+    /// Free-form JSON (orchestrator, DAG, task, ...)
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Bulk lineage request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkLineageRequest {
+    pub edges: Vec<NewLineageEdge>,
+    pub job: Option<LineageJob>,
+    /// Create placeholder datasets for unregistered endpoints
+    #[serde(default)]
+    pub create_placeholders: bool,
+    /// Handling of unregistered endpoints; overrides `create_placeholders`
+    #[serde(default)]
+    pub lineage_mode: Option<LineageMode>,
+}
+
+impl BulkLineageRequest {
+    /// Lineage mode for the batch, falling back to the server-wide mode
+    pub fn effective_mode(&self, server_mode: Option<LineageMode>) -> LineageMode {
+        self.lineage_mode
+            .or(self.create_placeholders.then_some(LineageMode::Placeholder))
+            .or(server_mode)
+            .unwrap_or(LineageMode::Strict)
+    }
+}
+
+/// Read `METAFUSE_LINEAGE_MODE`.
+pub fn lineage_mode_from_env() -> Result<Option<LineageMode>, String> {
+    match std::env::var("METAFUSE_LINEAGE_MODE") {
+        Ok(v) => LineageMode::parse(&v.to_lowercase())
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                "Invalid METAFUSE_LINEAGE_MODE '{}': expected 'strict', 'placeholder', or 'ignore'",
+                v
+            )
+            }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Outcome of one edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeStatus {
+    Created,
+    /// Edge already existed; job metadata refreshed
+    Updated,
+    /// An endpoint isn't registered and the lineage mode is `ignore`
+    Skipped,
+    Failed,
+}
+
+/// Result for one edge, in request order.
+#[derive(Debug, Clone, Serialize)]
+pub struct EdgeResult {
+    pub index: usize,
+    pub upstream: String,
+    pub downstream: String,
+    pub status: EdgeStatus,
+    /// Edge links an external node; `edge_id` refers to an external edge
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub external: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Bulk lineage response.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkLineageResponse {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// Placeholder datasets created for this batch
+    pub placeholders_created: Vec<String>,
+    pub results: Vec<EdgeResult>,
+}
+
+/// Validate batch-level input before touching the database.
+pub fn validate_request(req: &BulkLineageRequest) -> Result<(), String> {
+    if req.edges.is_empty() {
+        return Err("At least one edge is required".to_string());
+    }
+    if req.edges.len() > MAX_EDGES_PER_REQUEST {
+        return Err(format!(
+            "Too many edges: {} (max {})",
+            req.edges.len(),
+            MAX_EDGES_PER_REQUEST
+        ));
+    }
+    Ok(())
+}
+
+/// Look up a live dataset id per the lineage mode, checking the caller holds
+/// `required` on it. `None` skips the edge.
+///
+/// A dataset hidden from the caller by its ACL is reported as not found in
+/// `strict` mode and skipped otherwise, like a trashed one.
+fn resolve_node(
+    conn: &Connection,
+    name: &str,
+    mode: LineageMode,
+    identity: &Identity,
+    required: AclPermission,
+    placeholders_created: &mut BTreeSet<String>,
+) -> Result<Option<i64>, String> {
+    let resolved = lineage_mode::resolve(conn, name, mode).map_err(|e| match e {
+        CatalogError::ValidationError(msg) => msg,
+        e => e.to_string(),
+    })?;
+    match resolved {
+        Resolved::Registered(id) => {
+            let allowed = |permission| {
+                dataset_acl::check(conn, id, identity, permission).map_err(|e| e.to_string())
+            };
+            if !allowed(AclPermission::Read)? {
+                return match mode {
+                    LineageMode::Strict => Err(format!("Dataset '{}' not found", name)),
+                    _ => Ok(None),
+                };
+            }
+            if required == AclPermission::Write && !allowed(required)? {
+                return Err(format!(
+                    "Write access to dataset '{}' denied by its ACL",
+                    name
+                ));
+            }
+            Ok(Some(id))
+        }
+        Resolved::Placeholder(id) => {
+            placeholders_created.insert(name.to_string());
+            Ok(Some(id))
+        }
+        Resolved::Skipped => Ok(None),
+    }
+}
+
+/// Link an external node to a dataset endpoint.
+#[allow(clippy::too_many_arguments)]
+fn link_external(
+    conn: &Connection,
+    node: &ExternalNodeRef,
+    dataset: &str,
+    direction: ExternalDirection,
+    mode: LineageMode,
+    identity: &Identity,
+    placeholders_created: &mut BTreeSet<String>,
+    job: &LineageJob,
+    job_metadata: Option<&str>,
+) -> Result<(Option<i64>, EdgeStatus), String> {
+    if node.node_type != EXTERNAL_NODE_TYPE {
+        return Err(format!(
+            "Unsupported node type '{}': expected '{}'",
+            node.node_type, EXTERNAL_NODE_TYPE
+        ));
+    }
+    let dataset = urn::resolve_dataset_ref(dataset).map_err(|e| e.to_string())?;
+    // Feeding a dataset changes it; reading one into a sink does not
+    let required = match direction {
+        ExternalDirection::Upstream => AclPermission::Write,
+        ExternalDirection::Downstream => AclPermission::Read,
+    };
+    let Some(dataset_id) = resolve_node(
+        conn,
+        &dataset,
+        mode,
+        identity,
+        required,
+        placeholders_created,
+    )?
+    else {
+        return Ok((None, EdgeStatus::Skipped));
+    };
+    let node_id = external_nodes::upsert_node(
+        conn,
+        &node.uri,
+        node.name.as_deref(),
+        node.system.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    let (edge_id, created) = external_nodes::link(
+        conn,
+        node_id,
+        dataset_id,
+        direction,
+        job.name.as_deref(),
+        job.run_id.as_deref(),
+        job_metadata,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok((
+        Some(edge_id),
+        if created {
+            EdgeStatus::Created
+        } else {
+            EdgeStatus::Updated
+        },
+    ))
+}
+
+/// Apply a batch of edges. Call within a transaction so the batch is atomic
+/// with respect to database errors; validation failures are per edge.
+///
+/// `identity` needs write access to each downstream dataset and read access
+/// to each upstream one.
+pub fn apply_edges(
+    conn: &Connection,
+    req: &BulkLineageRequest,
+    mode: LineageMode,
+    identity: &Identity,
+) -> rusqlite::Result<BulkLineageResponse> {
+    let job = req.job.clone().unwrap_or_default();
+    let job_metadata = job.metadata.as_ref().map(|m| m.to_string());
+    let mut placeholders_created = BTreeSet::new();
+    let mut results = Vec::with_capacity(req.edges.len());
+
+    for (index, edge) in req.edges.iter().enumerate() {
+        let outcome = (|| -> Result<(Option<i64>, EdgeStatus), String> {
+            let (upstream, downstream) = match (&edge.upstream, &edge.downstream) {
+                (LineageEndpoint::Dataset(upstream), LineageEndpoint::Dataset(downstream)) => {
+                    (upstream, downstream)
+                }
+                (LineageEndpoint::External(node), LineageEndpoint::Dataset(dataset)) => {
+                    return link_external(
+                        conn,
+                        node,
+                        dataset,
+                        ExternalDirection::Upstream,
+                        mode,
+                        identity,
+                        &mut placeholders_created,
+                        &job,
+                        job_metadata.as_deref(),
+                    );
+                }
+                (LineageEndpoint::Dataset(dataset), LineageEndpoint::External(node)) => {
+                    return link_external(
+                        conn,
+                        node,
+                        dataset,
+                        ExternalDirection::Downstream,
+                        mode,
+                        identity,
+                        &mut placeholders_created,
+                        &job,
+                        job_metadata.as_deref(),
+                    );
+                }
+                (LineageEndpoint::External(_), LineageEndpoint::External(_)) => {
+                    return Err("At least one side of an edge must be a dataset".to_string());
+                }
+            };
+
+            let upstream = urn::resolve_dataset_ref(upstream).map_err(|e| e.to_string())?;
+            let downstream = urn::resolve_dataset_ref(downstream).map_err(|e| e.to_string())?;
+            if upstream == downstream {
+                return Err("A dataset cannot be its own upstream".to_string());
+            }
+
+            let upstream_id = resolve_node(
+                conn,
+                &upstream,
+                mode,
+                identity,
+                AclPermission::Read,
+                &mut placeholders_created,
+            )?;
+            let downstream_id = resolve_node(
+                conn,
+                &downstream,
+                mode,
+                identity,
+                AclPermission::Write,
+                &mut placeholders_created,
+            )?;
+            let (Some(upstream_id), Some(downstream_id)) = (upstream_id, downstream_id) else {
+                return Ok((None, EdgeStatus::Skipped));
+            };
+
+            lineage_cycles::check_edge(conn, upstream_id, downstream_id).map_err(|e| match e {
+                CatalogError::ValidationError(msg) => msg,
+                e => e.to_string(),
+            })?;
+
+            let existing: Option<i64> = conn
+                .query_row(
+                    "SELECT id FROM lineage WHERE upstream_dataset_id = ?1 AND downstream_dataset_id = ?2",
+                    params![upstream_id, downstream_id],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?;
+
+            match existing {
+                Some(id) => {
+                    conn.execute(
+                        r#"
+                        UPDATE lineage
+                        SET job_name = COALESCE(?1, job_name),
+                            run_id = COALESCE(?2, run_id),
+                            job_metadata = COALESCE(?3, job_metadata),
+                            updated_at = datetime('now')
+                        WHERE id = ?4
+                        "#,
+                        params![job.name, job.run_id, job_metadata, id],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    Ok((Some(id), EdgeStatus::Updated))
+                }
+                None => {
+                    conn.execute(
+                        r#"
+                        INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at,
+                                             job_name, run_id, job_metadata, updated_at)
+                        VALUES (?1, ?2, datetime('now'), ?3, ?4, ?5, datetime('now'))
+                        "#,
+                        params![
+                            upstream_id,
+                            downstream_id,
+                            job.name,
+                            job.run_id,
+                            job_metadata
+                        ],
+                    )
+                    .map_err(|e| e.to_string())?;
+                    Ok((Some(conn.last_insert_rowid()), EdgeStatus::Created))
+                }
+            }
+        })();
+
+        let external = matches!(edge.upstream, LineageEndpoint::External(_))
+            || matches!(edge.downstream, LineageEndpoint::External(_));
+        results.push(match outcome {
+            Ok((edge_id, status)) => EdgeResult {
+                index,
+                upstream: edge.upstream.label().to_string(),
+                downstream: edge.downstream.label().to_string(),
+                status,
+                external,
+                edge_id,
+                error: None,
+            },
+            Err(error) => EdgeResult {
+                index,
+                upstream: edge.upstream.label().to_string(),
+                downstream: edge.downstream.label().to_string(),
+                status: EdgeStatus::Failed,
+                external,
+                edge_id: None,
+                error: Some(error),
+            },
+        });
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    Ok(BulkLineageResponse {
+        created: count(EdgeStatus::Created),
+        updated: count(EdgeStatus::Updated),
+        skipped: count(EdgeStatus::Skipped),
+        failed: count(EdgeStatus::Failed),
+        placeholders_created: placeholders_created.into_iter().collect(),
+        results,
+    })
+}
+
+/// Whether `identity` can see an external node: it must be linked to no
+/// dataset, or to at least one dataset the caller can read.
+fn external_node_visible(
+    conn: &Connection,
+    node_id: i64,
+    identity: &Identity,
+) -> rusqlite::Result<bool> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT dataset_id FROM external_lineage WHERE external_node_id = ?1")?;
+    let dataset_ids = stmt
+        .query_map([node_id], |row| row.get::<_, i64>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if dataset_ids.is_empty() {
+        return Ok(true);
+    }
+    for dataset_id in dataset_ids {
+        if dataset_acl::check(conn, dataset_id, identity, AclPermission::Read)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Query parameters for listing external lineage nodes
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListExternalNodesParams {
+    /// Only list nodes owned by this system
+    system: Option<String>,
+}
+
+/// List external lineage nodes (sources and sinks outside the catalog)
+///
+/// Nodes linked only to datasets hidden from the caller are left out.
+pub(crate) async fn list_external_nodes(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<Identity>>,
+    Query(params): Query<ListExternalNodesParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<
+    Json<envelope::Collection<external_nodes::ExternalNode>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, system = ?params.system, "Listing external lineage nodes");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let mut nodes = Vec::new();
+    for node in external_nodes::list_nodes(&conn, params.system.as_deref())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    {
+        if external_node_visible(&conn, node.id, &identity)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        {
+            nodes.push(node);
+        }
+    }
+
+    Ok(Json(envelope.page(nodes)))
+}
+
+/// Register a batch of lineage edges
+pub(crate) async fn create_lineage_edges(
+    state: AppState,
+    request_id: RequestId,
+    audit_context: AuditContext,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<Identity>>,
+    req: BulkLineageRequest,
+) -> Result<BulkLineageResponse, (StatusCode, Json<ErrorResponse>)> {
+    validate_request(&req).map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let mode = req.effective_mode(state.lineage_mode);
+    let response = apply_edges(&tx, &req, mode, &identity)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(
+        created = response.created,
+        updated = response.updated,
+        skipped = response.skipped,
+        failed = response.failed,
+        placeholders = response.placeholders_created.len(),
+        "Lineage batch registered"
+    );
+
+    #[cfg(feature = "audit")]
+    {
+        let job_name = req.job.as_ref().and_then(|j| j.name.clone());
+        let event = audit::AuditEvent::create(
+            "lineage_edges",
+            job_name.as_deref().unwrap_or("batch"),
+            serde_json::json!({
+                "job": req.job,
+                "created": response.created,
+                "updated": response.updated,
+                "failed": response.failed,
+                "placeholders_created": response.placeholders_created,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+    Ok(response)
+}
+
+/// Request to create a lineage edge
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateLineageEdgeRequest {
+    source_dataset: String,
+    target_dataset: String,
+    /// Handling of an unregistered source or target
+    #[serde(default)]
+    lineage_mode: Option<LineageMode>,
+}
+
+/// `POST /api/v1/lineage` body: a batch of edges, or a single edge
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LineageRequest {
+    Bulk(BulkLineageRequest),
+    Single(CreateLineageEdgeRequest),
+}
+
+/// Lineage edge response
+#[derive(Debug, Serialize)]
+pub(crate) struct LineageEdgeResponse {
+    id: i64,
+    upstream_dataset_id: i64,
+    downstream_dataset_id: i64,
+    created_at: String,
+}
+
+/// Create lineage edges between datasets
+///
+/// Accepts either a single `{source_dataset, target_dataset}` edge (201 with
+/// the edge, or 200 with per-edge results when the lineage mode skips it) or
+/// a batch `{edges, job, create_placeholders}` (200 with per-edge results).
+pub(crate) async fn create_lineage_edge(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<LineageRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let req = match req {
+        LineageRequest::Bulk(bulk) => {
+            return create_lineage_edges(
+                state,
+                request_id,
+                audit_context,
+                tenant_backend,
+                identity,
+                bulk,
+            )
+            .await
+            .map(|response| Json(response).into_response());
+        }
+        LineageRequest::Single(req) => req,
+    };
+
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        source = %req.source_dataset,
+        target = %req.target_dataset,
+        "Creating lineage edge"
+    );
+
+    validation::validate_dataset_name(&req.source_dataset)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    validation::validate_dataset_name(&req.target_dataset)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // A single edge goes through the batch path so the lineage mode and
+    // dataset ACLs apply to it the same way
+    let batch = BulkLineageRequest {
+        edges: vec![NewLineageEdge {
+            upstream: req.source_dataset.as_str().into(),
+            downstream: req.target_dataset.as_str().into(),
+        }],
+        job: None,
+        create_placeholders: false,
+        lineage_mode: req.lineage_mode,
+    };
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let response = apply_edges(
+        &tx,
+        &batch,
+        batch.effective_mode(state.lineage_mode),
+        &identity,
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let result = &response.results[0];
+    if result.status == EdgeStatus::Failed {
+        // Dropping the transaction discards any placeholder made for the edge
+        return Err(lineage_edge_error(
+            result.error.clone().unwrap_or_default(),
+            request_id.0.clone(),
+        ));
+    }
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let Some(edge_id) = result.edge_id else {
+        // Skipped under the lineage mode
+        return Ok(Json(response).into_response());
+    };
+
+    let edge = conn
+        .query_row(
+            "SELECT id, upstream_dataset_id, downstream_dataset_id, created_at FROM lineage WHERE id = ?1",
+            [edge_id],
+            |row| {
+                Ok(LineageEdgeResponse {
+                    id: row.get(0)?,
+                    upstream_dataset_id: row.get(1)?,
+                    downstream_dataset_id: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(
+        source = %req.source_dataset,
+        target = %req.target_dataset,
+        "Lineage edge created successfully"
+    );
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "lineage_edge",
+            format!("{}:{}", req.source_dataset, req.target_dataset),
+            serde_json::json!({
+                "id": edge.id,
+                "source_dataset": req.source_dataset,
+                "target_dataset": req.target_dataset,
+                "placeholders_created": response.placeholders_created,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(edge)).into_response())
+}
+
+/// Error for a single lineage edge that failed to apply: unknown and hidden
+/// datasets are not found, ACL denials forbidden, anything else invalid.
+pub(crate) fn lineage_edge_error(
+    error: String,
+    request_id: String,
+) -> (StatusCode, Json<ErrorResponse>) {
+    if error.ends_with("not found") {
+        not_found(error, request_id)
+    } else if error.starts_with("Write access") {
+        (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error,
+                code: ErrorCode::Forbidden,
+                request_id,
+            }),
+        )
+    } else {
+        bad_request(error, request_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metafuse_catalog_core::placeholders;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('raw', '/raw', 'parquet', datetime('now'), datetime('now')),
+                    ('clean', '/clean', 'parquet', datetime('now'), datetime('now'));",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn edge(upstream: &str, downstream: &str) -> NewLineageEdge {
+        NewLineageEdge {
+            upstream: upstream.into(),
+            downstream: downstream.into(),
+        }
+    }
+
+    #[test]
+    fn test_per_edge_results() {
+        let conn = setup();
+        let req = BulkLineageRequest {
+            edges: vec![
+                edge("raw", "urn:metafuse:dataset:clean"),
+                edge("raw", "missing"),
+                edge("clean", "clean"),
+            ],
+            job: Some(LineageJob {
+                name: Some("nightly_etl".to_string()),
+                run_id: Some("run-1".to_string()),
+                metadata: None,
+            }),
+            create_placeholders: false,
+            lineage_mode: None,
+        };
+
+        let response =
+            apply_edges(&conn, &req, req.effective_mode(None), &Identity::default()).unwrap();
+        assert_eq!(
+            (response.created, response.updated, response.failed),
+            (1, 0, 2)
+        );
+        assert_eq!(
+            response.results[1].error.as_deref(),
+            Some("Dataset 'missing' not found")
+        );
+        assert!(response.placeholders_created.is_empty());
+
+        // Re-reporting refreshes the run
+        let req = BulkLineageRequest {
+            edges: vec![edge("raw", "clean")],
+            job: Some(LineageJob {
+                run_id: Some("run-2".to_string()),
+                ..Default::default()
+            }),
+            create_placeholders: false,
+            lineage_mode: None,
+        };
+        let response =
+            apply_edges(&conn, &req, req.effective_mode(None), &Identity::default()).unwrap();
+        assert_eq!(response.results[0].status, EdgeStatus::Updated);
+        let (job_name, run_id): (String, String) = conn
+            .query_row("SELECT job_name, run_id FROM lineage", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(
+            (job_name.as_str(), run_id.as_str()),
+            ("nightly_etl", "run-2")
+        );
+    }
+
+    #[test]
+    fn test_cycle_edges_fail() {
+        let conn = setup();
+        let req = BulkLineageRequest {
+            edges: vec![edge("raw", "clean"), edge("clean", "raw")],
+            job: None,
+            create_placeholders: false,
+            lineage_mode: None,
+        };
+
+        let response =
+            apply_edges(&conn, &req, req.effective_mode(None), &Identity::default()).unwrap();
+        assert_eq!((response.created, response.failed), (1, 1));
+        assert_eq!(
+            response.results[1].error.as_deref(),
+            Some("Lineage edge 'clean' -> 'raw' would create a cycle: raw -> clean -> raw")
+        );
+    }
+
+    #[test]
+    fn test_placeholders_created_when_flagged() {
+        let conn = setup();
+        let req = BulkLineageRequest {
+            edges: vec![edge("vendor_feed", "raw"), edge("vendor_feed", "clean")],
+            job: None,
+            create_placeholders: true,
+            lineage_mode: None,
+        };
+
+        let response =
+            apply_edges(&conn, &req, req.effective_mode(None), &Identity::default()).unwrap();
+        assert_eq!(response.created, 2);
+        assert_eq!(response.placeholders_created, vec!["vendor_feed"]);
+        let status: String = conn
+            .query_row(
+                "SELECT status FROM datasets WHERE name = 'vendor_feed'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(status, placeholders::STATUS_PENDING);
+    }
+
+    #[test]
+    fn test_ignore_mode_skips_unknown_endpoints() {
+        let conn = setup();
+        let req: BulkLineageRequest = serde_json::from_value(serde_json::json!({
+            "edges": [
+                { "upstream": "raw", "downstream": "clean" },
+                { "upstream": "vendor_feed", "downstream": "raw" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(req.effective_mode(None), LineageMode::Strict);
+        assert_eq!(
+            req.effective_mode(Some(LineageMode::Ignore)),
+            LineageMode::Ignore
+        );
+
+        let response = apply_edges(&conn, &req, LineageMode::Ignore, &Identity::default()).unwrap();
+        assert_eq!(
+            (response.created, response.skipped, response.failed),
+            (1, 1, 0)
+        );
+        assert_eq!(response.results[1].status, EdgeStatus::Skipped);
+        assert_eq!(response.results[1].edge_id, None);
+        assert!(response.placeholders_created.is_empty());
+    }
+
+    #[test]
+    fn test_external_nodes() {
+        let conn = setup();
+        let req: BulkLineageRequest = serde_json::from_value(serde_json::json!({
+            "edges": [
+                {
+                    "upstream": {"type": "external", "uri": "sftp://vendor/orders", "system": "vendor_sftp"},
+                    "downstream": "raw"
+                },
+                {
+                    "upstream": "clean",
+                    "downstream": {"type": "external", "uri": "https://crm.example.com/import"}
+                },
+                {
+                    "upstream": {"type": "external", "uri": "a"},
+                    "downstream": {"type": "external", "uri": "b"}
+                }
+            ]
+        }))
+        .unwrap();
+
+        let response =
+            apply_edges(&conn, &req, req.effective_mode(None), &Identity::default()).unwrap();
+        assert_eq!((response.created, response.failed), (2, 1));
+        assert!(response.results[0].external);
+        assert_eq!(response.results[0].upstream, "sftp://vendor/orders");
+
+        // External nodes never become datasets
+        let datasets: i64 = conn
+            .query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(datasets, 2);
+        let edges = external_nodes::for_dataset(&conn, 2).unwrap();
+        assert_eq!(edges[0].1, ExternalDirection::Downstream);
+    }
+
+    #[test]
+    fn test_edges_need_dataset_access() {
+        let conn = setup();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('ledger', '/ledger', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO dataset_acls (dataset_id, principal, permission)
+             VALUES (2, 'user:carol', 'read'), (3, 'user:alice', 'write');",
+        )
+        .unwrap();
+        let carol = Identity {
+            user: Some("carol".to_string()),
+            ..Default::default()
+        };
+        let req: BulkLineageRequest = serde_json::from_value(serde_json::json!({
+            "edges": [
+                {"upstream": "clean", "downstream": "raw"},
+                {"upstream": "raw", "downstream": "clean"},
+                {"upstream": "raw", "downstream": "ledger"},
+                {"upstream": "ledger", "downstream": "raw"},
+                {"upstream": {"type": "external", "uri": "sftp://vendor/orders"}, "downstream": "clean"},
+                {"upstream": "clean", "downstream": {"type": "external", "uri": "s3://exports/clean"}}
+            ]
+        }))
+        .unwrap();
+
+        let response = apply_edges(&conn, &req, LineageMode::Strict, &carol).unwrap();
+        let errors: Vec<Option<&str>> = response
+            .results
+            .iter()
+            .map(|r| r.error.as_deref())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                None,
+                Some("Write access to dataset 'clean' denied by its ACL"),
+                Some("Dataset 'ledger' not found"),
+                Some("Dataset 'ledger' not found"),
+                Some("Write access to dataset 'clean' denied by its ACL"),
+                None,
+            ]
+        );
+
+        // Outside strict mode hidden datasets are skipped, never replaced
+        let response = apply_edges(&conn, &req, LineageMode::Placeholder, &carol).unwrap();
+        assert_eq!(response.results[2].status, EdgeStatus::Skipped);
+        assert!(response.placeholders_created.is_empty());
+    }
+}
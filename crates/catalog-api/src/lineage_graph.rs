@@ -0,0 +1,1004 @@
+//! Lineage Diagram Export
+//!
+//! Renders the dataset-level lineage around one dataset as a Graphviz DOT or
+//! Mermaid flowchart for docs and pull requests:
+//!
+//! - `GET /api/v1/datasets/{name}/lineage.dot`
+//! - `GET /api/v1/datasets/{name}/lineage.mmd`
+//!
+//! The graph walks upstream and downstream edges up to `depth` hops from the
+//! root and includes external nodes linked to any dataset in it.
+//!
+//! # Styling
+//!
+//! - Fill color by domain (stable per domain name; datasets without a domain are white);
+//!   external nodes are colored by system
+//! - Border color by latest quality score: green (>= 0.8), amber (>= 0.5), red (< 0.5),
+//!   grey when not computed
+//! - The root dataset has a thick border; placeholders are dashed; external
+//!   nodes use a distinct shape
+//!
+//! # Bundles
+//!
+//! `POST /api/v1/lineage/export` merges the graphs around several roots into a
+//! self-contained JSON [`LineageBundle`] for docs pipelines: nodes carry their
+//! catalog metadata and edges the job that last reported them. Nodes, edges,
+//! and tags are sorted and the bundle has no generation timestamp, so
+//! exporting an unchanged catalog gives byte-identical output.
+
+use crate::dataset_acl;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, bad_request, dataset_not_found, internal_error, AppState, DatasetPath,
+    ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use axum::extract::{Extension, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use metafuse_catalog_core::{external_nodes, placeholders, validation, Result};
+use metafuse_catalog_storage::read_snapshot;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Default traversal depth
+pub const DEFAULT_GRAPH_DEPTH: usize = 3;
+
+/// Maximum traversal depth
+pub const MAX_GRAPH_DEPTH: usize = 10;
+
+/// Maximum root datasets in one bundle export
+pub const MAX_EXPORT_ROOTS: usize = 100;
+
+/// Bundle format version, bumped on incompatible changes
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Fill colors assigned to domains
+const DOMAIN_PALETTE: &[&str] = &[
+    "#dbeafe", "#dcfce7", "#fef9c3", "#fce7f3", "#ede9fe", "#ffedd5", "#ccfbf1", "#e0e7ff",
+];
+
+/// Which edges to follow from the root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphDirection {
+    Upstream,
+    Downstream,
+    Both,
+}
+
+impl GraphDirection {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "upstream" => Some(GraphDirection::Upstream),
+            "downstream" => Some(GraphDirection::Downstream),
+            "both" => Some(GraphDirection::Both),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphDirection::Upstream => "upstream",
+            GraphDirection::Downstream => "downstream",
+            GraphDirection::Both => "both",
+        }
+    }
+}
+
+/// Kind of node in the diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Dataset,
+    /// Placeholder dataset created from lineage (see `placeholders`)
+    Placeholder,
+    /// Source or sink outside the catalog
+    External,
+}
+
+/// A node in the lineage diagram.
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    pub label: String,
+    pub kind: NodeKind,
+    pub domain: Option<String>,
+    pub quality: Option<f64>,
+}
+
+/// Lineage graph around a root dataset.
+///
+/// Nodes are keyed by `d:<name>` for datasets and `x:<uri>` for external
+/// nodes so the two never collide.
+#[derive(Debug, Clone, Default)]
+pub struct LineageGraph {
+    pub root: String,
+    pub nodes: BTreeMap<String, GraphNode>,
+    /// (upstream key, downstream key)
+    pub edges: BTreeSet<(String, String)>,
+}
+
+impl LineageGraph {
+    /// Add another graph's nodes and edges; this graph's root is kept
+    pub fn merge(&mut self, other: LineageGraph) {
+        for (key, node) in other.nodes {
+            self.nodes.entry(key).or_insert(node);
+        }
+        self.edges.extend(other.edges);
+    }
+}
+
+fn dataset_key(name: &str) -> String {
+    format!("d:{}", name)
+}
+
+fn external_key(uri: &str) -> String {
+    format!("x:{}", uri)
+}
+
+/// Load a live dataset as a graph node with its key.
+fn load_dataset(conn: &Connection, id: i64) -> Result<Option<(String, GraphNode)>> {
+    let has_status = placeholders::has_status_column(conn)?;
+    let status_expr = if has_status { "d.status" } else { "NULL" };
+    let sql = format!(
+        r#"
+        SELECT d.name, d.domain, {status},
+               (SELECT q.overall_score FROM quality_metrics q
+                WHERE q.dataset_id = d.id ORDER BY q.computed_at DESC LIMIT 1)
+        FROM datasets d
+        WHERE d.id = ?1 AND d.deleted_at IS NULL
+        "#,
+        status = status_expr
+    );
+    let node = conn
+        .query_row(&sql, [id], |row| {
+            let name: String = row.get(0)?;
+            let status: Option<String> = row.get(2)?;
+            let kind = if status.as_deref() == Some(placeholders::STATUS_PENDING) {
+                NodeKind::Placeholder
+            } else {
+                NodeKind::Dataset
+            };
+            Ok((
+                dataset_key(&name),
+                GraphNode {
+                    label: name,
+                    kind,
+                    domain: row.get(1)?,
+                    quality: row.get(3)?,
+                },
+            ))
+        })
+        .optional()?;
+    Ok(node)
+}
+
+/// Dataset ids one hop upstream or downstream.
+fn neighbors(conn: &Connection, id: i64, upstream: bool) -> Result<Vec<i64>> {
+    let sql = if upstream {
+        "SELECT upstream_dataset_id FROM lineage WHERE downstream_dataset_id = ?1"
+    } else {
+        "SELECT downstream_dataset_id FROM lineage WHERE upstream_dataset_id = ?1"
+    };
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt
+        .query_map([id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+/// Build the lineage graph around a dataset. Returns `None` if the dataset
+/// does not exist.
+pub fn build_graph(
+    conn: &Connection,
+    root_name: &str,
+    depth: usize,
+    direction: GraphDirection,
+) -> Result<Option<LineageGraph>> {
+    let root_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [root_name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(root_id) = root_id else {
+        return Ok(None);
+    };
+
+    let mut graph = LineageGraph {
+        root: dataset_key(root_name),
+        ..Default::default()
+    };
+    let mut keys: BTreeMap<i64, String> = BTreeMap::new();
+    if let Some((key, node)) = load_dataset(conn, root_id)? {
+        keys.insert(root_id, key.clone());
+        graph.nodes.insert(key, node);
+    }
+
+    let walks: &[bool] = match direction {
+        GraphDirection::Upstream => &[true],
+        GraphDirection::Downstream => &[false],
+        GraphDirection::Both => &[true, false],
+    };
+    for &upstream in walks {
+        let mut visited = BTreeSet::from([root_id]);
+        let mut queue = VecDeque::from([(root_id, 0)]);
+        while let Some((id, hops)) = queue.pop_front() {
+            if hops >= depth {
+                continue;
+            }
+            for neighbor in neighbors(conn, id, upstream)? {
+                let neighbor_key = match keys.get(&neighbor) {
+                    Some(key) => key.clone(),
+                    // Trashed datasets are hidden, along with anything beyond them
+                    None => match load_dataset(conn, neighbor)? {
+                        Some((key, node)) => {
+                            keys.insert(neighbor, key.clone());
+                            graph.nodes.insert(key.clone(), node);
+                            key
+                        }
+                        None => continue,
+                    },
+                };
+                let key = keys[&id].clone();
+                graph.edges.insert(if upstream {
+                    (neighbor_key, key)
+                } else {
+                    (key, neighbor_key)
+                });
+                if visited.insert(neighbor) {
+                    queue.push_back((neighbor, hops + 1));
+                }
+            }
+        }
+    }
+
+    // External sources and sinks of every dataset in the graph
+    for (id, key) in &keys {
+        for (node, side) in external_nodes::for_dataset(conn, *id)? {
+            let included = match side {
+                external_nodes::ExternalDirection::Upstream => {
+                    direction != GraphDirection::Downstream
+                }
+                external_nodes::ExternalDirection::Downstream => {
+                    direction != GraphDirection::Upstream
+                }
+            };
+            if !included {
+                continue;
+            }
+            let node_key = external_key(&node.uri);
+            graph.edges.insert(match side {
+                external_nodes::ExternalDirection::Upstream => (node_key.clone(), key.clone()),
+                external_nodes::ExternalDirection::Downstream => (key.clone(), node_key.clone()),
+            });
+            graph.nodes.entry(node_key).or_insert(GraphNode {
+                label: node.name,
+                kind: NodeKind::External,
+                domain: node.system,
+                quality: None,
+            });
+        }
+    }
+
+    Ok(Some(graph))
+}
+
+/// Stable fill color for a domain.
+fn domain_color(domain: Option<&str>) -> &'static str {
+    match domain {
+        // FNV-1a, so colors do not change between releases
+        Some(domain) => {
+            let hash = domain.bytes().fold(0x811c9dc5u32, |h, b| {
+                (h ^ b as u32).wrapping_mul(0x01000193)
+            });
+            DOMAIN_PALETTE[hash as usize % DOMAIN_PALETTE.len()]
+        }
+        None => "#ffffff",
+    }
+}
+
+/// Border color for a quality score.
+fn quality_color(score: Option<f64>) -> &'static str {
+    match score {
+        Some(s) if s >= 0.8 => "#16a34a",
+        Some(s) if s >= 0.5 => "#d97706",
+        Some(_) => "#dc2626",
+        None => "#9ca3af",
+    }
+}
+
+/// Escape a string for a quoted DOT identifier or label.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escape a string for a quoted Mermaid label.
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+/// Render the graph as Graphviz DOT.
+pub fn render_dot(graph: &LineageGraph) -> String {
+    let mut out = String::from(
+        "digraph lineage {\n  rankdir=LR;\n  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n",
+    );
+    for (key, node) in &graph.nodes {
+        let mut style = vec!["filled"];
+        let shape = match node.kind {
+            NodeKind::Dataset => {
+                style.push("rounded");
+                "box"
+            }
+            NodeKind::Placeholder => {
+                style.extend(["rounded", "dashed"]);
+                "box"
+            }
+            NodeKind::External => "cds",
+        };
+        let penwidth = if *key == graph.root { 3 } else { 1 };
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\", shape={}, style=\"{}\", fillcolor=\"{}\", color=\"{}\", penwidth={}];\n",
+            dot_escape(key),
+            dot_escape(&node.label),
+            shape,
+            style.join(","),
+            domain_color(node.domain.as_deref()),
+            quality_color(node.quality),
+            penwidth
+        ));
+    }
+    for (from, to) in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\";\n",
+            dot_escape(from),
+            dot_escape(to)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Render the graph as a Mermaid flowchart.
+///
+/// Mermaid ids are restricted, so nodes get positional ids (`n0`, `n1`, ...)
+/// and the name goes in the label.
+pub fn render_mermaid(graph: &LineageGraph) -> String {
+    let ids: BTreeMap<&String, String> = graph
+        .nodes
+        .keys()
+        .enumerate()
+        .map(|(i, key)| (key, format!("n{}", i)))
+        .collect();
+
+    let mut out = String::from("flowchart LR\n");
+    for (key, node) in &graph.nodes {
+        let label = mermaid_escape(&node.label);
+        let shape = match node.kind {
+            NodeKind::Dataset | NodeKind::Placeholder => format!("(\"{}\")", label),
+            NodeKind::External => format!("[/\"{}\"/]", label),
+        };
+        out.push_str(&format!("  {}{}\n", ids[key], shape));
+    }
+    for (from, to) in &graph.edges {
+        out.push_str(&format!("  {} --> {}\n", ids[from], ids[to]));
+    }
+    for (key, node) in &graph.nodes {
+        let mut style = format!(
+            "fill:{},stroke:{}",
+            domain_color(node.domain.as_deref()),
+            quality_color(node.quality)
+        );
+        if *key == graph.root {
+            style.push_str(",stroke-width:3px");
+        }
+        if node.kind == NodeKind::Placeholder {
+            style.push_str(",stroke-dasharray:5 5");
+        }
+        out.push_str(&format!("  style {} {}\n", ids[key], style));
+    }
+    out
+}
+
+// =============================================================================
+// Bundles
+// =============================================================================
+
+/// A node in a lineage bundle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleNode {
+    /// Graph key: `d:<name>` or `x:<uri>`
+    pub id: String,
+    /// `dataset`, `placeholder`, or `external`
+    pub kind: &'static str,
+    pub name: String,
+    /// Whether the node is one of the requested roots
+    pub root: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Latest overall quality score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<f64>,
+    /// External nodes: the system they belong to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    /// External nodes: their URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+/// The job that last reported an edge.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleJob {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// An edge in a lineage bundle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleEdge {
+    /// Upstream node id
+    pub from: String,
+    /// Downstream node id
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<BundleJob>,
+}
+
+/// Self-contained lineage export around a set of roots.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LineageBundle {
+    pub format_version: u32,
+    /// Root dataset names, sorted
+    pub roots: Vec<String>,
+    pub depth: usize,
+    pub direction: &'static str,
+    /// Sorted by id
+    pub nodes: Vec<BundleNode>,
+    /// Sorted by (from, to)
+    pub edges: Vec<BundleEdge>,
+}
+
+/// Build a bundle from the graphs around each root. Returns `Err(name)` with
+/// the first root that does not exist.
+pub fn build_bundle(
+    conn: &Connection,
+    roots: &[String],
+    depth: usize,
+    direction: GraphDirection,
+) -> Result<std::result::Result<LineageBundle, String>> {
+    let roots: BTreeSet<&String> = roots.iter().collect();
+    let mut merged = LineageGraph::default();
+    for root in &roots {
+        match build_graph(conn, root, depth, direction)? {
+            Some(graph) => merged.merge(graph),
+            None => return Ok(Err(root.to_string())),
+        }
+    }
+    let root_keys: BTreeSet<String> = roots.iter().map(|r| dataset_key(r)).collect();
+
+    let mut nodes = Vec::with_capacity(merged.nodes.len());
+    for (key, node) in &merged.nodes {
+        let mut entry = BundleNode {
+            id: key.clone(),
+            kind: match node.kind {
+                NodeKind::Dataset => "dataset",
+                NodeKind::Placeholder => "placeholder",
+                NodeKind::External => "external",
+            },
+            name: node.label.clone(),
+            root: root_keys.contains(key),
+            path: None,
+            format: None,
+            description: None,
+            domain: None,
+            owner: None,
+            tags: Vec::new(),
+            quality_score: node.quality,
+            system: None,
+            uri: None,
+        };
+        match key.strip_prefix("x:") {
+            Some(uri) => {
+                entry.system = node.domain.clone();
+                entry.uri = Some(uri.to_string());
+            }
+            None => load_bundle_metadata(conn, &mut entry)?,
+        }
+        nodes.push(entry);
+    }
+
+    let has_jobs = has_lineage_jobs(conn)?;
+    let mut edges = Vec::with_capacity(merged.edges.len());
+    for (from, to) in &merged.edges {
+        let job = match (from.strip_prefix("d:"), to.strip_prefix("d:")) {
+            (Some(up), Some(down)) if has_jobs => dataset_edge_job(conn, up, down)?,
+            (Some(_), Some(_)) => None,
+            (None, Some(down)) => external_edge_job(
+                conn,
+                &from[2..],
+                down,
+                external_nodes::ExternalDirection::Upstream,
+            )?,
+            (Some(up), None) => external_edge_job(
+                conn,
+                &to[2..],
+                up,
+                external_nodes::ExternalDirection::Downstream,
+            )?,
+            (None, None) => None,
+        };
+        edges.push(BundleEdge {
+            from: from.clone(),
+            to: to.clone(),
+            job,
+        });
+    }
+
+    Ok(Ok(LineageBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        roots: roots.into_iter().cloned().collect(),
+        depth,
+        direction: direction.as_str(),
+        nodes,
+        edges,
+    }))
+}
+
+/// Fill in a dataset node's catalog metadata.
+fn load_bundle_metadata(conn: &Connection, node: &mut BundleNode) -> Result<()> {
+    let row = conn
+        .query_row(
+            "SELECT id, path, format, description, domain, owner
+             FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&node.name],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((id, path, format, description, domain, owner)) = row else {
+        return Ok(());
+    };
+    // Placeholders have no path yet
+    node.path = Some(path).filter(|p| !p.is_empty());
+    node.format = Some(format);
+    node.description = description;
+    node.domain = domain;
+    node.owner = owner;
+    let mut stmt = conn.prepare("SELECT tag FROM tags WHERE dataset_id = ?1 ORDER BY tag")?;
+    node.tags = stmt
+        .query_map([id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<String>, _>>()?;
+    Ok(())
+}
+
+/// Whether lineage edges record jobs (migration v1.18.0).
+fn has_lineage_jobs(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('lineage') WHERE name = 'job_name'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+fn bundle_job(
+    name: Option<String>,
+    run_id: Option<String>,
+    metadata: Option<String>,
+) -> Option<BundleJob> {
+    let metadata = metadata.and_then(|m| serde_json::from_str(&m).ok());
+    if name.is_none() && run_id.is_none() && metadata.is_none() {
+        return None;
+    }
+    Some(BundleJob {
+        name,
+        run_id,
+        metadata,
+    })
+}
+
+/// Job recorded on a dataset-to-dataset edge.
+fn dataset_edge_job(
+    conn: &Connection,
+    upstream: &str,
+    downstream: &str,
+) -> Result<Option<BundleJob>> {
+    let row = conn
+        .query_row(
+            r#"
+            SELECT l.job_name, l.run_id, l.job_metadata
+            FROM lineage l
+            JOIN datasets u ON u.id = l.upstream_dataset_id
+            JOIN datasets d ON d.id = l.downstream_dataset_id
+            WHERE u.name = ?1 AND d.name = ?2
+            "#,
+            [upstream, downstream],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    Ok(row.and_then(|(name, run_id, metadata)| bundle_job(name, run_id, metadata)))
+}
+
+/// Job recorded on an edge between an external node and a dataset.
+fn external_edge_job(
+    conn: &Connection,
+    uri: &str,
+    dataset: &str,
+    direction: external_nodes::ExternalDirection,
+) -> Result<Option<BundleJob>> {
+    let row = conn
+        .query_row(
+            r#"
+            SELECT l.job_name, l.run_id, l.job_metadata
+            FROM external_lineage l
+            JOIN external_nodes n ON n.id = l.external_node_id
+            JOIN datasets d ON d.id = l.dataset_id
+            WHERE n.uri = ?1 AND d.name = ?2 AND l.direction = ?3
+            "#,
+            [uri, dataset, direction.as_str()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    Ok(row.and_then(|(name, run_id, metadata)| bundle_job(name, run_id, metadata)))
+}
+
+/// Query params for lineage diagram export
+#[derive(Debug, Deserialize)]
+pub(crate) struct LineageDiagramParams {
+    /// Hops to follow from the dataset (default: 3, max: 10)
+    depth: Option<usize>,
+    /// `upstream`, `downstream`, or `both` (default)
+    direction: Option<String>,
+}
+
+/// Request to export the lineage around several datasets
+#[derive(Debug, Deserialize)]
+pub(crate) struct LineageExportRequest {
+    /// Root dataset names
+    roots: Vec<String>,
+    /// Hops to follow from each root (default: 3, max: 10)
+    depth: Option<usize>,
+    /// `upstream`, `downstream`, or `both` (default)
+    direction: Option<String>,
+}
+
+/// Depth (defaulted and capped) and direction for a lineage graph export
+pub(crate) fn lineage_graph_params(
+    depth: Option<usize>,
+    direction: Option<&str>,
+    request_id: &RequestId,
+) -> std::result::Result<(usize, GraphDirection), (StatusCode, Json<ErrorResponse>)> {
+    let depth = depth.unwrap_or(DEFAULT_GRAPH_DEPTH).min(MAX_GRAPH_DEPTH);
+    let direction = match direction {
+        Some(d) => GraphDirection::parse(d).ok_or_else(|| {
+            bad_request(
+                format!(
+                    "Invalid direction '{}': expected 'upstream', 'downstream', or 'both'",
+                    d
+                ),
+                request_id.0.clone(),
+            )
+        })?,
+        None => GraphDirection::Both,
+    };
+    Ok((depth, direction))
+}
+
+/// Build the lineage graph for a diagram export
+pub(crate) async fn lineage_diagram(
+    state: &AppState,
+    request_id: &RequestId,
+    tenant_backend: Option<&TenantBackend>,
+    identity: Option<&dataset_acl::Identity>,
+    name: &str,
+    params: &LineageDiagramParams,
+) -> std::result::Result<LineageGraph, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let (depth, direction) =
+        lineage_graph_params(params.depth, params.direction.as_deref(), request_id)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend);
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let conn =
+        read_snapshot(&conn).map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        name,
+        identity,
+        dataset_acl::AclPermission::Read,
+        request_id,
+    )?;
+
+    build_graph(&conn, name, depth, direction)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| dataset_not_found(name, request_id.0.clone()))
+}
+
+/// Export a dataset's lineage as a Graphviz DOT diagram
+pub(crate) async fn get_lineage_dot(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<LineageDiagramParams>,
+) -> std::result::Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let graph = lineage_diagram(
+        &state,
+        &request_id,
+        tenant_backend.as_ref().map(|e| &e.0),
+        identity.as_ref().map(|e| &e.0),
+        &name,
+        &params,
+    )
+    .await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+        render_dot(&graph),
+    ))
+}
+
+/// Export a dataset's lineage as a Mermaid flowchart
+pub(crate) async fn get_lineage_mermaid(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<LineageDiagramParams>,
+) -> std::result::Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let graph = lineage_diagram(
+        &state,
+        &request_id,
+        tenant_backend.as_ref().map(|e| &e.0),
+        identity.as_ref().map(|e| &e.0),
+        &name,
+        &params,
+    )
+    .await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        render_mermaid(&graph),
+    ))
+}
+
+/// Export the lineage around several datasets as a JSON bundle
+pub(crate) async fn export_lineage_bundle(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    JsonBody(req): JsonBody<LineageExportRequest>,
+) -> std::result::Result<Json<LineageBundle>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, roots = req.roots.len(), "Exporting lineage bundle");
+
+    if req.roots.is_empty() {
+        return Err(bad_request(
+            "roots must not be empty".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+    if req.roots.len() > MAX_EXPORT_ROOTS {
+        return Err(bad_request(
+            format!("At most {} roots can be exported at once", MAX_EXPORT_ROOTS),
+            request_id.0.clone(),
+        ));
+    }
+    for name in &req.roots {
+        validation::validate_dataset_name(name)
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    let (depth, direction) =
+        lineage_graph_params(req.depth, req.direction.as_deref(), &request_id)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let bundle = build_bundle(&conn, &req.roots, depth, direction)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .map_err(|name| dataset_not_found(&name, request_id.0.clone()))?;
+
+    tracing::info!(
+        roots = bundle.roots.len(),
+        nodes = bundle.nodes.len(),
+        edges = bundle.edges.len(),
+        "Lineage bundle exported"
+    );
+
+    Ok(Json(bundle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        // a -> b -> c -> d, with b in the finance domain
+        conn.execute_batch(
+            r#"
+            INSERT INTO datasets (name, path, format, domain, created_at, last_updated) VALUES
+                ('a', '/a', 'parquet', NULL, datetime('now'), datetime('now')),
+                ('b', '/b', 'parquet', 'finance', datetime('now'), datetime('now')),
+                ('c', '/c', 'parquet', NULL, datetime('now'), datetime('now')),
+                ('d', '/d', 'parquet', NULL, datetime('now'), datetime('now'));
+            INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+                (1, 2, datetime('now')), (2, 3, datetime('now')), (3, 4, datetime('now'));
+            INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+                VALUES (2, datetime('now'), 0.9);
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_build_graph_depth_and_direction() {
+        let conn = setup();
+        let graph = build_graph(&conn, "b", 1, GraphDirection::Both)
+            .unwrap()
+            .unwrap();
+        let names: Vec<&str> = graph.nodes.values().map(|n| n.label.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert_eq!(graph.edges.len(), 2);
+
+        let graph = build_graph(&conn, "b", 5, GraphDirection::Downstream)
+            .unwrap()
+            .unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert!(!graph.nodes.contains_key("d:a"));
+
+        assert!(build_graph(&conn, "missing", 1, GraphDirection::Both)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_render_styles() {
+        let conn = setup();
+        let uri = "sftp://vendor/\"drop\"";
+        let node = external_nodes::upsert_node(&conn, uri, None, Some("vendor")).unwrap();
+        external_nodes::link(
+            &conn,
+            node,
+            1,
+            external_nodes::ExternalDirection::Upstream,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let graph = build_graph(&conn, "b", 1, GraphDirection::Both)
+            .unwrap()
+            .unwrap();
+
+        let dot = render_dot(&graph);
+        assert!(dot.starts_with("digraph lineage {"));
+        assert!(dot.contains("\"d:a\" -> \"d:b\";"));
+        assert!(dot.contains("label=\"sftp://vendor/\\\"drop\\\"\", shape=cds"));
+        assert!(dot.contains(&format!(
+            "fillcolor=\"{}\", color=\"#16a34a\", penwidth=3",
+            domain_color(Some("finance"))
+        )));
+
+        let mermaid = render_mermaid(&graph);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("[/\"sftp://vendor/#quot;drop#quot;\"/]"));
+        assert!(mermaid.contains("stroke:#16a34a,stroke-width:3px"));
+    }
+
+    #[test]
+    fn test_build_bundle_is_stable() {
+        let conn = setup();
+        conn.execute_batch(
+            "UPDATE lineage SET job_name = 'load_c', run_id = 'r1', job_metadata = '{\"dag\":\"etl\"}'
+             WHERE upstream_dataset_id = 2 AND downstream_dataset_id = 3;
+             INSERT INTO tags (dataset_id, tag) VALUES (2, 'pii'), (2, 'gold');",
+        )
+        .unwrap();
+        let node = external_nodes::upsert_node(&conn, "s3://raw/a", None, Some("s3")).unwrap();
+        external_nodes::link(
+            &conn,
+            node,
+            1,
+            external_nodes::ExternalDirection::Upstream,
+            Some("ingest"),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let roots = vec!["c".to_string(), "a".to_string(), "c".to_string()];
+        let bundle = build_bundle(&conn, &roots, 1, GraphDirection::Both)
+            .unwrap()
+            .unwrap();
+        assert_eq!(bundle.roots, vec!["a", "c"]);
+        let ids: Vec<&str> = bundle.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["d:a", "d:b", "d:c", "d:d", "x:s3://raw/a"]);
+        assert!(bundle.nodes[0].root && !bundle.nodes[1].root);
+        assert_eq!(bundle.nodes[1].tags, vec!["gold", "pii"]);
+        assert_eq!(bundle.nodes[1].domain.as_deref(), Some("finance"));
+        assert_eq!(bundle.nodes[4].system.as_deref(), Some("s3"));
+
+        let edge = bundle
+            .edges
+            .iter()
+            .find(|e| e.from == "d:b" && e.to == "d:c")
+            .unwrap();
+        let job = edge.job.as_ref().unwrap();
+        assert_eq!(job.name.as_deref(), Some("load_c"));
+        assert_eq!(job.metadata, Some(serde_json::json!({"dag": "etl"})));
+        assert_eq!(
+            bundle.edges[bundle.edges.len() - 1]
+                .job
+                .as_ref()
+                .unwrap()
+                .name
+                .as_deref(),
+            Some("ingest")
+        );
+
+        // Same catalog, same bytes, whatever the root order
+        let again = build_bundle(
+            &conn,
+            &["a".to_string(), "c".to_string()],
+            1,
+            GraphDirection::Both,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            serde_json::to_string(&bundle).unwrap(),
+            serde_json::to_string(&again).unwrap()
+        );
+
+        assert_eq!(
+            build_bundle(
+                &conn,
+                &["a".to_string(), "missing".to_string()],
+                1,
+                GraphDirection::Both
+            )
+            .unwrap(),
+            Err("missing".to_string())
+        );
+    }
+}
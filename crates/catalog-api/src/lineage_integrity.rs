@@ -0,0 +1,210 @@
+//! Lineage Integrity
+//!
+//! Lineage writes reject edges that would close a cycle (see
+//! `metafuse_catalog_core::lineage_cycles`), but catalogs can still hold
+//! cycles written before the check existed or imported directly into the
+//! database. This module reports them:
+//!
+//! - `GET /api/v1/lineage/cycles` lists the cycles in the catalog, leaving
+//!   out cycles through datasets hidden from the caller by dataset ACLs
+//! - [`lineage_integrity_task`] checks the default catalog periodically and
+//!   logs a warning per cycle found
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_LINEAGE_CYCLE_CHECK_INTERVAL_SECS`: how often the default
+//!   catalog is checked (default: 3600, 0 = never)
+
+use crate::dataset_acl::{self, AclPermission, Identity};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::lineage_cycles::{self, LineageCycle};
+use metafuse_catalog_core::Result;
+use metafuse_catalog_storage::read_snapshot;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Default interval between integrity checks
+pub const DEFAULT_CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Lineage integrity configuration
+#[derive(Debug, Clone)]
+pub struct IntegrityConfig {
+    /// Seconds between checks; 0 disables the background task
+    pub check_interval_secs: u64,
+}
+
+impl Default for IntegrityConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: DEFAULT_CHECK_INTERVAL_SECS,
+        }
+    }
+}
+
+impl IntegrityConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_LINEAGE_CYCLE_CHECK_INTERVAL_SECS`: seconds between checks (0 = never)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            check_interval_secs: std::env::var("METAFUSE_LINEAGE_CYCLE_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.check_interval_secs),
+        }
+    }
+
+    /// Whether the background task runs
+    pub fn enabled(&self) -> bool {
+        self.check_interval_secs > 0
+    }
+}
+
+/// Response for the lineage cycles endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineageCyclesResponse {
+    pub cycles: Vec<LineageCycle>,
+    pub count: usize,
+}
+
+/// Find the lineage cycles in a catalog.
+pub fn check(conn: &Connection) -> Result<LineageCyclesResponse> {
+    let cycles = lineage_cycles::find_cycles(conn)?;
+    Ok(LineageCyclesResponse {
+        count: cycles.len(),
+        cycles,
+    })
+}
+
+/// Find the lineage cycles whose datasets `identity` can all read.
+pub fn check_visible(conn: &Connection, identity: &Identity) -> Result<LineageCyclesResponse> {
+    let mut cycles = Vec::new();
+    'cycles: for cycle in lineage_cycles::find_cycles(conn)? {
+        for name in &cycle.datasets {
+            let id: Option<i64> = conn
+                .query_row("SELECT id FROM datasets WHERE name = ?1", [name], |row| {
+                    row.get(0)
+                })
+                .optional()?;
+            let readable = match id {
+                Some(id) => dataset_acl::check(conn, id, identity, AclPermission::Read)?,
+                None => false,
+            };
+            if !readable {
+                continue 'cycles;
+            }
+        }
+        cycles.push(cycle);
+    }
+    Ok(LineageCyclesResponse {
+        count: cycles.len(),
+        cycles,
+    })
+}
+
+/// Background task that reports lineage cycles periodically
+pub async fn lineage_integrity_task(
+    config: IntegrityConfig,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(config.check_interval_secs);
+
+    info!(
+        interval_secs = config.check_interval_secs,
+        "Lineage integrity task started"
+    );
+
+    loop {
+        match backend.get_connection().await {
+            Ok(conn) => match check(&conn) {
+                Ok(report) => {
+                    for cycle in &report.cycles {
+                        warn!(
+                            datasets = %cycle.datasets.join(", "),
+                            path = %cycle.path.join(" -> "),
+                            "Lineage cycle found"
+                        );
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to check lineage integrity");
+                }
+            },
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for lineage integrity check");
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Lineage cycles in the catalog
+pub(crate) async fn get_lineage_cycles(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<Identity>>,
+) -> std::result::Result<Json<LineageCyclesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.0.clone();
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let report = tokio::task::spawn_blocking(move || {
+        let conn = read_snapshot(&conn)?;
+        check_visible(&conn, &identity)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_cycles() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+                ('raw', '/raw', 'parquet', datetime('now'), datetime('now')),
+                ('clean', '/clean', 'parquet', datetime('now'), datetime('now')),
+                ('mart', '/mart', 'parquet', datetime('now'), datetime('now'));
+            INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+                (1, 2, datetime('now')), (2, 3, datetime('now'));
+            "#,
+        )
+        .unwrap();
+        assert_eq!(check(&conn).unwrap().count, 0);
+
+        conn.execute(
+            "INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (3, 2, datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let report = check(&conn).unwrap();
+        assert_eq!(report.count, 1);
+        assert_eq!(report.cycles[0].datasets, vec!["clean", "mart"]);
+        assert_eq!(report.cycles[0].path, vec!["clean", "mart", "clean"]);
+    }
+}
@@ -0,0 +1,559 @@
+//! Completion Markers
+//!
+//! Readiness handshakes between producers and consumers of a dataset. A
+//! producer marks a partition complete once it is written; consumers poll for
+//! the marker instead of relying on `_SUCCESS` files in storage.
+//!
+//! # Architecture
+//!
+//! Markers live in `completion_markers` (migration v1.33.0), one row per
+//! dataset and partition. Partition identifiers are opaque strings chosen by
+//! the producer, such as `2025-11-27` or `date=2025-11-27/region=eu`. Marking
+//! a partition again replaces its marker, so reruns refresh `completed_at`.
+//! Markers are removed with their dataset.
+//!
+//! [`marker_purge_task`] deletes markers older than the retention window.
+//! Expired markers are also purged whenever a partition is marked, so tenant
+//! catalogs, which the background task does not visit, are cleaned up too.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_MARKER_RETENTION_DAYS`: days markers are kept (default: 90, 0 = forever)
+//! - `METAFUSE_MARKER_PURGE_INTERVAL_SECS`: how often the purge job runs (default: 3600)
+//!
+//! # Endpoints
+//!
+//! - `POST /api/v1/datasets/{name}/markers` - Mark a partition complete
+//! - `GET /api/v1/datasets/{name}/markers?partition=` - List markers, or poll one partition
+//! - `DELETE /api/v1/datasets/{name}/markers?partition=` - Retract a marker
+
+#[cfg(feature = "audit")]
+use crate::audit;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    accessible_dataset_id, bad_request, internal_error, not_found, AppState, AuditContext, Caller,
+    DatasetPath, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::{dataset_acl, envelope};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Default days a marker is kept
+pub const DEFAULT_RETENTION_DAYS: u32 = 90;
+
+/// Default interval between purge runs
+pub const DEFAULT_PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Longest partition identifier accepted
+pub const MAX_PARTITION_LEN: usize = 256;
+
+/// Marker retention configuration
+#[derive(Debug, Clone)]
+pub struct MarkerConfig {
+    /// Days before a marker is purged; 0 keeps markers forever
+    pub retention_days: u32,
+    /// Seconds between background purge runs
+    pub purge_interval_secs: u64,
+}
+
+impl Default for MarkerConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: DEFAULT_RETENTION_DAYS,
+            purge_interval_secs: DEFAULT_PURGE_INTERVAL_SECS,
+        }
+    }
+}
+
+impl MarkerConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_MARKER_RETENTION_DAYS`: days markers are kept (0 = forever)
+    /// - `METAFUSE_MARKER_PURGE_INTERVAL_SECS`: seconds between purge runs
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            retention_days: std::env::var("METAFUSE_MARKER_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.retention_days),
+            purge_interval_secs: std::env::var("METAFUSE_MARKER_PURGE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.purge_interval_secs),
+        }
+    }
+
+    /// Whether markers expire
+    pub fn expires(&self) -> bool {
+        self.retention_days > 0
+    }
+}
+
+/// A completed partition of a dataset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompletionMarker {
+    pub partition: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    pub marked_by: Option<String>,
+    pub completed_at: String,
+}
+
+/// Validate a partition identifier.
+pub fn validate_partition(partition: &str) -> Result<(), String> {
+    if partition.trim().is_empty() {
+        return Err("Partition cannot be empty".to_string());
+    }
+    if partition.len() > MAX_PARTITION_LEN {
+        return Err(format!(
+            "Partition too long: {} > {} characters",
+            partition.len(),
+            MAX_PARTITION_LEN
+        ));
+    }
+    if partition.chars().any(char::is_control) {
+        return Err("Partition cannot contain control characters".to_string());
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+fn marker_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CompletionMarker> {
+    let metadata: Option<String> = row.get(1)?;
+    Ok(CompletionMarker {
+        partition: row.get(0)?,
+        metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+        marked_by: row.get(2)?,
+        completed_at: row.get(3)?,
+    })
+}
+
+/// Mark a partition complete, replacing any earlier marker for it.
+pub fn mark_complete(
+    conn: &Connection,
+    dataset_id: i64,
+    partition: &str,
+    metadata: Option<&serde_json::Value>,
+    marked_by: Option<&str>,
+) -> rusqlite::Result<CompletionMarker> {
+    conn.query_row(
+        "INSERT INTO completion_markers (dataset_id, partition, metadata, marked_by)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(dataset_id, partition) DO UPDATE SET
+             metadata = ?3, marked_by = ?4, completed_at = datetime('now')
+         RETURNING partition, metadata, marked_by, completed_at",
+        params![
+            dataset_id,
+            partition,
+            metadata.map(|m| m.to_string()),
+            marked_by
+        ],
+        marker_from_row,
+    )
+}
+
+/// Markers of a dataset, most recently completed first, optionally for one
+/// partition.
+pub fn list_markers(
+    conn: &Connection,
+    dataset_id: i64,
+    partition: Option<&str>,
+    limit: usize,
+    offset: usize,
+) -> rusqlite::Result<Vec<CompletionMarker>> {
+    let mut stmt = conn.prepare(
+        "SELECT partition, metadata, marked_by, completed_at
+         FROM completion_markers
+         WHERE dataset_id = ?1 AND (?2 IS NULL OR partition = ?2)
+         ORDER BY completed_at DESC, id DESC
+         LIMIT ?3 OFFSET ?4",
+    )?;
+    let markers = stmt
+        .query_map(
+            params![dataset_id, partition, limit as i64, offset as i64],
+            marker_from_row,
+        )?
+        .collect();
+    markers
+}
+
+/// Number of a dataset's markers, or of one partition's.
+pub fn count_markers(
+    conn: &Connection,
+    dataset_id: i64,
+    partition: Option<&str>,
+) -> rusqlite::Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM completion_markers
+         WHERE dataset_id = ?1 AND (?2 IS NULL OR partition = ?2)",
+        params![dataset_id, partition],
+        |row| row.get(0),
+    )
+}
+
+/// Retract the marker of a partition. Returns false if there was none.
+pub fn remove_marker(
+    conn: &Connection,
+    dataset_id: i64,
+    partition: &str,
+) -> rusqlite::Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM completion_markers WHERE dataset_id = ?1 AND partition = ?2",
+        params![dataset_id, partition],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Delete markers completed more than `retention_days` ago. Returns how many
+/// were deleted.
+pub fn purge_expired(conn: &Connection, retention_days: u32) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM completion_markers
+         WHERE completed_at <= datetime('now', '-' || ?1 || ' days')",
+        [retention_days],
+    )
+}
+
+/// Background task that periodically purges expired markers
+pub async fn marker_purge_task(
+    config: MarkerConfig,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(config.purge_interval_secs);
+
+    info!(
+        interval_secs = config.purge_interval_secs,
+        retention_days = config.retention_days,
+        "Marker purge task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        debug!("Running periodic marker purge");
+
+        match backend.get_connection().await {
+            Ok(conn) => match purge_expired(&conn, config.retention_days) {
+                Ok(0) => {}
+                Ok(purged) => info!(count = purged, "Purged expired completion markers"),
+                Err(e) => error!(error = %e, "Failed to purge completion markers"),
+            },
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for marker purge");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MarkPartitionRequest {
+    partition: String,
+    /// Producer details, e.g. run id or row count
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MarkerQuery {
+    partition: Option<String>,
+    #[serde(default = "default_marker_limit")]
+    limit: usize,
+}
+
+pub(crate) fn default_marker_limit() -> usize {
+    100
+}
+
+/// Mark a partition of a dataset complete
+pub(crate) async fn mark_partition_complete(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<MarkPartitionRequest>,
+) -> Result<(StatusCode, Json<CompletionMarker>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    validate_partition(&req.partition).map_err(|e| bad_request(e, request_id.0.clone()))?;
+    if req.metadata.as_ref().is_some_and(|m| !m.is_object()) {
+        return Err(bad_request(
+            "Marker metadata must be a JSON object".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let identity = identity.as_ref().map(|e| &e.0);
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity,
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let marked_by = identity
+        .and_then(|i| i.user.as_ref())
+        .map(|u| format!("user:{}", u))
+        .or_else(|| {
+            audit_context
+                .api_key_id
+                .as_ref()
+                .map(|k| format!("key:{}", k))
+        });
+    let marker = mark_complete(
+        &conn,
+        dataset_id,
+        &req.partition,
+        req.metadata.as_ref(),
+        marked_by.as_deref(),
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Purge expired markers on each mark so tenant catalogs, which the
+    // background purge task does not visit, are cleaned up too
+    if state.marker_config.expires() {
+        if let Err(e) = purge_expired(&conn, state.marker_config.retention_days) {
+            tracing::warn!(error = %e, "Failed to purge expired completion markers");
+        }
+    }
+
+    tracing::info!(dataset = %name, partition = %req.partition, "Partition marked complete");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "completion_marker",
+            format!("{}:{}", name, req.partition),
+            serde_json::to_value(&marker).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(marker)))
+}
+
+/// List a dataset's completion markers; with `partition`, poll one partition
+pub(crate) async fn list_completion_markers(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(query): Query<MarkerQuery>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<CompletionMarker>>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let (limit, offset) = envelope.bounds(query.limit.min(1000));
+    let markers = list_markers(&conn, dataset_id, query.partition.as_deref(), limit, offset)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let total = envelope
+        .total(|| count_markers(&conn, dataset_id, query.partition.as_deref()))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.window(markers, total)))
+}
+
+/// Retract a partition's completion marker, e.g. before a rerun
+pub(crate) async fn retract_completion_marker(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    Query(query): Query<MarkerQuery>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let Some(partition) = query.partition else {
+        return Err(bad_request(
+            "The partition query parameter is required".to_string(),
+            request_id.0.clone(),
+        ));
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let removed = remove_marker(&conn, dataset_id, &partition)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !removed {
+        return Err(not_found(
+            format!(
+                "No completion marker for partition '{}' of dataset '{}'",
+                partition, name
+            ),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(dataset = %name, partition = %partition, "Completion marker retracted");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "completion_marker",
+            format!("{}:{}", name, partition),
+            serde_json::json!({ "dataset": name, "partition": partition }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_mark_list_and_remove() {
+        let conn = setup_db();
+        mark_complete(&conn, 1, "2025-11-26", None, Some("key:etl")).unwrap();
+        let marker = mark_complete(
+            &conn,
+            1,
+            "2025-11-27",
+            Some(&serde_json::json!({"rows": 1000})),
+            Some("key:etl"),
+        )
+        .unwrap();
+        assert_eq!(marker.metadata, Some(serde_json::json!({"rows": 1000})));
+
+        // Marking again replaces the marker
+        mark_complete(&conn, 1, "2025-11-27", None, Some("user:alice")).unwrap();
+        let markers = list_markers(&conn, 1, Some("2025-11-27"), 10, 0).unwrap();
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].marked_by.as_deref(), Some("user:alice"));
+        assert_eq!(markers[0].metadata, None);
+
+        assert_eq!(list_markers(&conn, 1, None, 10, 0).unwrap().len(), 2);
+        assert_eq!(list_markers(&conn, 1, None, 1, 0).unwrap().len(), 1);
+        let second = list_markers(&conn, 1, None, 10, 1).unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].partition, "2025-11-26");
+        assert_eq!(count_markers(&conn, 1, None).unwrap(), 2);
+        assert_eq!(count_markers(&conn, 1, Some("2025-11-27")).unwrap(), 1);
+        assert!(list_markers(&conn, 1, Some("2025-11-28"), 10, 0)
+            .unwrap()
+            .is_empty());
+
+        assert!(remove_marker(&conn, 1, "2025-11-26").unwrap());
+        assert!(!remove_marker(&conn, 1, "2025-11-26").unwrap());
+    }
+
+    #[test]
+    fn test_purge_expired_respects_retention() {
+        let conn = setup_db();
+        mark_complete(&conn, 1, "old", None, None).unwrap();
+        mark_complete(&conn, 1, "new", None, None).unwrap();
+        conn.execute(
+            "UPDATE completion_markers SET completed_at = datetime('now', '-10 days')
+             WHERE partition = 'old'",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(purge_expired(&conn, 30).unwrap(), 0);
+        assert_eq!(purge_expired(&conn, 7).unwrap(), 1);
+        let left: Vec<String> = list_markers(&conn, 1, None, 10, 0)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.partition)
+            .collect();
+        assert_eq!(left, vec!["new"]);
+    }
+
+    #[test]
+    fn test_validate_partition() {
+        assert!(validate_partition("2025-11-27").is_ok());
+        assert!(validate_partition("date=2025-11-27/region=eu").is_ok());
+        assert!(validate_partition(" ").is_err());
+        assert!(validate_partition("a\nb").is_err());
+        assert!(validate_partition(&"x".repeat(MAX_PARTITION_LEN + 1)).is_err());
+    }
+}
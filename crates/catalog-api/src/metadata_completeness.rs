@@ -0,0 +1,520 @@
+//! Metadata Completeness Scoring
+//!
+//! Scores how well a dataset is documented, separately from data quality:
+//! whether it has a description, owner, domain, tags, glossary links, and
+//! classified columns. The score is attached to list and search responses,
+//! and `GET /api/v1/analytics/metadata-completeness` ranks datasets so
+//! stewards can start with the worst-documented ones.
+//!
+//! Each criterion has a weight (see [`Criterion::weight`]). Classification
+//! earns partial credit for the share of columns classified; datasets without
+//! columns are scored on the other criteria only. Glossary links count when
+//! made to the dataset or to any of its columns. Column classifications come
+//! from migration v1.0.0; catalogs without them score classification as 0.
+
+use crate::dataset_acl;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::{placeholders, validation, Result};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Most datasets returned by the leaderboard
+pub const MAX_LEADERBOARD_LIMIT: usize = 500;
+
+/// Default number of datasets returned by the leaderboard
+pub const DEFAULT_LEADERBOARD_LIMIT: usize = 50;
+
+/// A piece of metadata that counts toward completeness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    Description,
+    Owner,
+    Domain,
+    Tags,
+    GlossaryTerms,
+    Classification,
+}
+
+impl Criterion {
+    /// All criteria, heaviest first
+    pub const ALL: [Criterion; 6] = [
+        Criterion::Description,
+        Criterion::Owner,
+        Criterion::Domain,
+        Criterion::GlossaryTerms,
+        Criterion::Classification,
+        Criterion::Tags,
+    ];
+
+    /// Share of the score this criterion is worth; weights sum to 1.0
+    pub fn weight(&self) -> f64 {
+        match self {
+            Criterion::Description => 0.25,
+            Criterion::Owner => 0.20,
+            Criterion::Domain => 0.15,
+            Criterion::GlossaryTerms => 0.15,
+            Criterion::Classification => 0.15,
+            Criterion::Tags => 0.10,
+        }
+    }
+}
+
+/// Metadata completeness of one dataset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetadataCompleteness {
+    /// Weighted share of criteria met, from 0.0 to 1.0
+    pub score: f64,
+    /// Criteria not fully met, heaviest first
+    pub missing: Vec<Criterion>,
+    /// Share of columns with a classification; `None` without columns
+    pub classification_coverage: Option<f64>,
+}
+
+/// What is documented for a dataset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetadataFacts {
+    pub has_description: bool,
+    pub has_owner: bool,
+    pub has_domain: bool,
+    pub has_tags: bool,
+    pub has_glossary_terms: bool,
+    pub field_count: i64,
+    pub classified_field_count: i64,
+}
+
+impl MetadataCompleteness {
+    /// Score a dataset's documented metadata
+    pub fn compute(facts: &MetadataFacts) -> Self {
+        let classification_coverage = (facts.field_count > 0)
+            .then(|| (facts.classified_field_count as f64 / facts.field_count as f64).min(1.0));
+
+        let mut earned = 0.0;
+        let mut possible = 0.0;
+        let mut missing = Vec::new();
+        for criterion in Criterion::ALL {
+            let credit = match criterion {
+                Criterion::Description => bool_credit(facts.has_description),
+                Criterion::Owner => bool_credit(facts.has_owner),
+                Criterion::Domain => bool_credit(facts.has_domain),
+                Criterion::Tags => bool_credit(facts.has_tags),
+                Criterion::GlossaryTerms => bool_credit(facts.has_glossary_terms),
+                // Not applicable to datasets without columns
+                Criterion::Classification => match classification_coverage {
+                    Some(coverage) => coverage,
+                    None => continue,
+                },
+            };
+            possible += criterion.weight();
+            earned += criterion.weight() * credit;
+            if credit < 1.0 {
+                missing.push(criterion);
+            }
+        }
+
+        Self {
+            score: round(earned / possible),
+            missing,
+            classification_coverage: classification_coverage.map(round),
+        }
+    }
+}
+
+fn bool_credit(met: bool) -> f64 {
+    if met {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Round to 4 decimal places so scores compare and display cleanly
+fn round(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+/// Whether the catalog has column classifications (migration v1.0.0)
+fn has_classifications(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'column_classifications'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Per-dataset fact columns, selected from `datasets d`
+fn facts_columns(conn: &Connection) -> Result<String> {
+    let classified = if has_classifications(conn)? {
+        "(SELECT COUNT(*) FROM fields f WHERE f.dataset_id = d.id AND EXISTS (
+             SELECT 1 FROM column_classifications c
+             WHERE c.field_id = f.id AND c.classification != 'unknown'))"
+    } else {
+        "0"
+    };
+    Ok(format!(
+        "COALESCE(TRIM(d.description), '') != '',
+         COALESCE(TRIM(d.owner), '') != '',
+         COALESCE(TRIM(d.domain), '') != '',
+         EXISTS (SELECT 1 FROM tags t WHERE t.dataset_id = d.id),
+         EXISTS (SELECT 1 FROM term_links l WHERE l.dataset_id = d.id
+                 OR l.field_id IN (SELECT f.id FROM fields f WHERE f.dataset_id = d.id)),
+         (SELECT COUNT(*) FROM fields f WHERE f.dataset_id = d.id),
+         {}",
+        classified
+    ))
+}
+
+/// Read [`MetadataFacts`] from the [`facts_columns`] of a row, starting at `offset`
+fn facts_from_row(row: &rusqlite::Row<'_>, offset: usize) -> rusqlite::Result<MetadataFacts> {
+    Ok(MetadataFacts {
+        has_description: row.get(offset)?,
+        has_owner: row.get(offset + 1)?,
+        has_domain: row.get(offset + 2)?,
+        has_tags: row.get(offset + 3)?,
+        has_glossary_terms: row.get(offset + 4)?,
+        field_count: row.get(offset + 5)?,
+        classified_field_count: row.get(offset + 6)?,
+    })
+}
+
+/// Metadata completeness of the given datasets, by dataset id.
+pub fn load(conn: &Connection, dataset_ids: &[i64]) -> Result<HashMap<i64, MetadataCompleteness>> {
+    let columns = facts_columns(conn)?;
+    let mut scores = HashMap::new();
+    // Chunked to stay under SQLite's bound parameter limit
+    for chunk in dataset_ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT d.id, {} FROM datasets d WHERE d.id IN ({})",
+            columns, placeholders
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+            Ok((row.get::<_, i64>(0)?, facts_from_row(row, 1)?))
+        })?;
+        for row in rows {
+            let (id, facts) = row?;
+            scores.insert(id, MetadataCompleteness::compute(&facts));
+        }
+    }
+    Ok(scores)
+}
+
+/// Leaderboard order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardOrder {
+    /// Worst-documented first
+    #[default]
+    Asc,
+    /// Best-documented first
+    Desc,
+}
+
+/// Filters for [`leaderboard`]
+#[derive(Debug, Clone, Default)]
+pub struct LeaderboardQuery {
+    pub domain: Option<String>,
+    pub owner: Option<String>,
+    /// Only datasets missing this criterion
+    pub missing: Option<Criterion>,
+    pub order: LeaderboardOrder,
+    pub limit: usize,
+    /// Extra SQL condition on `d` with its bindings, e.g. ACL visibility
+    pub visibility: Option<(String, Vec<String>)>,
+}
+
+/// A ranked dataset
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LeaderboardEntry {
+    pub dataset_name: String,
+    pub domain: Option<String>,
+    pub owner: Option<String>,
+    #[serde(flatten)]
+    pub completeness: MetadataCompleteness,
+}
+
+/// Response for the metadata completeness leaderboard
+#[derive(Debug, Clone, Serialize)]
+pub struct Leaderboard {
+    /// Datasets matching the filters, before the limit
+    pub total: usize,
+    /// Mean score of those datasets; `None` if there are none
+    pub average_score: Option<f64>,
+    pub datasets: Vec<LeaderboardEntry>,
+}
+
+/// Rank live datasets by metadata completeness.
+///
+/// Ties are broken by name. Placeholders created for unknown lineage
+/// endpoints aren't ranked: they have nothing to document yet.
+pub fn leaderboard(conn: &Connection, query: &LeaderboardQuery) -> Result<Leaderboard> {
+    let mut sql = format!(
+        "SELECT d.name, d.domain, d.owner, {} FROM datasets d WHERE d.deleted_at IS NULL",
+        facts_columns(conn)?
+    );
+    let mut bindings: Vec<String> = Vec::new();
+    if placeholders::has_status_column(conn)? {
+        sql.push_str(&format!(
+            " AND d.status != '{}'",
+            placeholders::STATUS_PENDING
+        ));
+    }
+    if let Some(domain) = &query.domain {
+        sql.push_str(" AND d.domain = ?");
+        bindings.push(domain.clone());
+    }
+    if let Some(owner) = &query.owner {
+        sql.push_str(" AND d.owner = ?");
+        bindings.push(owner.clone());
+    }
+    if let Some((clause, clause_bindings)) = &query.visibility {
+        sql.push_str(" AND ");
+        sql.push_str(clause);
+        bindings.extend(clause_bindings.iter().cloned());
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut entries = stmt
+        .query_map(rusqlite::params_from_iter(bindings.iter()), |row| {
+            Ok(LeaderboardEntry {
+                dataset_name: row.get(0)?,
+                domain: row.get(1)?,
+                owner: row.get(2)?,
+                completeness: MetadataCompleteness::compute(&facts_from_row(row, 3)?),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    if let Some(criterion) = query.missing {
+        entries.retain(|e| e.completeness.missing.contains(&criterion));
+    }
+    entries.sort_by(|a, b| {
+        let by_score = a.completeness.score.total_cmp(&b.completeness.score);
+        let by_score = match query.order {
+            LeaderboardOrder::Asc => by_score,
+            LeaderboardOrder::Desc => by_score.reverse(),
+        };
+        by_score.then_with(|| a.dataset_name.cmp(&b.dataset_name))
+    });
+
+    let total = entries.len();
+    let average_score = (total > 0)
+        .then(|| round(entries.iter().map(|e| e.completeness.score).sum::<f64>() / total as f64));
+    entries.truncate(query.limit);
+    Ok(Leaderboard {
+        total,
+        average_score,
+        datasets: entries,
+    })
+}
+
+/// Query parameters for the metadata completeness leaderboard
+#[derive(Debug, Deserialize)]
+pub(crate) struct MetadataCompletenessQueryParams {
+    domain: Option<String>,
+    owner: Option<String>,
+    missing: Option<Criterion>,
+    #[serde(default)]
+    order: LeaderboardOrder,
+    limit: Option<usize>,
+}
+
+/// Rank datasets by metadata completeness, worst-documented first
+pub(crate) async fn get_metadata_completeness_leaderboard(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<MetadataCompletenessQueryParams>,
+) -> std::result::Result<Json<Leaderboard>, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(domain) = &params.domain {
+        validation::validate_identifier(domain, "domain")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_LEADERBOARD_LIMIT)
+        .clamp(1, MAX_LEADERBOARD_LIMIT);
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let query = LeaderboardQuery {
+        domain: params.domain,
+        owner: params.owner,
+        missing: params.missing,
+        order: params.order,
+        limit,
+        visibility: dataset_acl::visibility_clause(&identity, "d.id", "d.domain"),
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.0.clone();
+    let leaderboard = tokio::task::spawn_blocking(move || leaderboard(&conn, &query))
+        .await
+        .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+        .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        total = leaderboard.total,
+        "Metadata completeness leaderboard computed"
+    );
+
+    Ok(Json(leaderboard))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_dataset(conn: &Connection, name: &str, description: Option<&str>, owner: Option<&str>) {
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, description, owner, created_at, last_updated)
+             VALUES (?1, '/data', 'parquet', ?2, ?3, datetime('now'), datetime('now'))",
+            rusqlite::params![name, description, owner],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_weights_sum_to_one() {
+        let total: f64 = Criterion::ALL.iter().map(Criterion::weight).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute() {
+        let full = MetadataFacts {
+            has_description: true,
+            has_owner: true,
+            has_domain: true,
+            has_tags: true,
+            has_glossary_terms: true,
+            field_count: 4,
+            classified_field_count: 4,
+        };
+        let score = MetadataCompleteness::compute(&full);
+        assert_eq!(score.score, 1.0);
+        assert!(score.missing.is_empty());
+
+        // Half the columns classified earns half the classification weight
+        let score = MetadataCompleteness::compute(&MetadataFacts {
+            classified_field_count: 2,
+            has_tags: false,
+            ..full
+        });
+        assert_eq!(score.score, 0.825);
+        assert_eq!(
+            score.missing,
+            vec![Criterion::Classification, Criterion::Tags]
+        );
+        assert_eq!(score.classification_coverage, Some(0.5));
+
+        // Without columns, classification doesn't count either way
+        let score = MetadataCompleteness::compute(&MetadataFacts {
+            has_description: true,
+            ..MetadataFacts::default()
+        });
+        assert_eq!(score.score, round(0.25 / 0.85));
+        assert!(!score.missing.contains(&Criterion::Classification));
+        assert_eq!(score.classification_coverage, None);
+
+        assert_eq!(
+            MetadataCompleteness::compute(&MetadataFacts::default()).score,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_load_and_leaderboard() {
+        let conn = setup();
+        add_dataset(&conn, "documented", Some("Orders"), Some("team-a"));
+        add_dataset(&conn, "bare", Some("  "), None);
+        add_dataset(&conn, "trashed", None, None);
+        conn.execute(
+            "UPDATE datasets SET deleted_at = datetime('now') WHERE name = 'trashed'",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO tags (dataset_id, tag) VALUES (1, 'gold')", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES (1, 'id', 'int', 0)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO glossary_terms (term) VALUES ('Order')", [])
+            .unwrap();
+        // A link to a column counts for the dataset
+        conn.execute(
+            "INSERT INTO term_links (term_id, field_id) VALUES (1, 1)",
+            [],
+        )
+        .unwrap();
+
+        let scores = load(&conn, &[1, 2]).unwrap();
+        assert_eq!(
+            scores[&1].missing,
+            vec![Criterion::Domain, Criterion::Classification]
+        );
+        assert_eq!(scores[&1].classification_coverage, Some(0.0));
+        // Blank descriptions don't count
+        assert_eq!(scores[&2].score, 0.0);
+
+        conn.execute(
+            "INSERT INTO column_classifications (field_id, classification) VALUES (1, 'pii')",
+            [],
+        )
+        .unwrap();
+        let board = leaderboard(
+            &conn,
+            &LeaderboardQuery {
+                limit: 10,
+                ..LeaderboardQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(board.total, 2);
+        let names: Vec<&str> = board
+            .datasets
+            .iter()
+            .map(|e| e.dataset_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["bare", "documented"]);
+        assert_eq!(board.datasets[1].completeness.score, 0.85);
+        assert_eq!(board.average_score, Some(0.425));
+
+        let board = leaderboard(
+            &conn,
+            &LeaderboardQuery {
+                owner: Some("team-a".to_string()),
+                missing: Some(Criterion::Domain),
+                order: LeaderboardOrder::Desc,
+                limit: 1,
+                ..LeaderboardQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(board.total, 1);
+        assert_eq!(board.datasets[0].dataset_name, "documented");
+    }
+}
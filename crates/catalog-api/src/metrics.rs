@@ -56,6 +56,13 @@
 //! - `lineage_edges_total` - Gauge for total lineage edges in the catalog
 //! - `lineage_transformations_total` - Counter for lineage edges by transformation type
 //!
+//! ## Delta Read Metrics
+//!
+//! - `delta_reads_total` - Counter for Delta reads against storage by bucket and status
+//! - `delta_read_duration_seconds` - Histogram for Delta read latency, including retries
+//! - `delta_read_wait_seconds` - Histogram for time spent waiting for a read permit
+//! - `delta_read_retries_total` - Counter for retried transient Delta read errors
+//!
 //! ## Cardinality Control
 //!
 //! Per-tenant metrics (those with `tenant_id` label) create a new Prometheus time series
@@ -109,6 +116,14 @@ lazy_static! {
     )
     .unwrap();
 
+    /// Counter for requests cancelled for exceeding their time budget
+    pub static ref HTTP_REQUEST_TIMEOUTS_TOTAL: CounterVec = register_counter_vec!(
+        "http_request_timeouts_total",
+        "Total requests cancelled for exceeding their time budget",
+        &["method", "path"]
+    )
+    .unwrap();
+
     /// Counter for catalog operations (emit_dataset, search, etc.)
     pub static ref CATALOG_OPERATIONS_TOTAL: CounterVec = register_counter_vec!(
         "catalog_operations_total",
@@ -380,6 +395,45 @@ lazy_static! {
         &["transformation_type"]
     )
     .unwrap();
+
+    // ==========================================================================
+    // Delta Read Metrics
+    // ==========================================================================
+
+    /// Counter for Delta reads against storage
+    /// Labels: bucket (e.g. s3://lake), status (success, error)
+    pub static ref DELTA_READS_TOTAL: CounterVec = register_counter_vec!(
+        "delta_reads_total",
+        "Total Delta reads against storage",
+        &["bucket", "status"]
+    )
+    .unwrap();
+
+    /// Histogram for Delta read duration in seconds, including retries
+    pub static ref DELTA_READ_DURATION_SECONDS: HistogramVec = register_histogram_vec!(
+        "delta_read_duration_seconds",
+        "Delta read latency in seconds, including retries",
+        &["bucket"],
+        vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]
+    )
+    .unwrap();
+
+    /// Histogram for time spent waiting for a Delta read permit
+    pub static ref DELTA_READ_WAIT_SECONDS: HistogramVec = register_histogram_vec!(
+        "delta_read_wait_seconds",
+        "Time waiting for a Delta read permit in seconds",
+        &["bucket"],
+        vec![0.001, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0]
+    )
+    .unwrap();
+
+    /// Counter for retried transient Delta read errors
+    pub static ref DELTA_READ_RETRIES_TOTAL: CounterVec = register_counter_vec!(
+        "delta_read_retries_total",
+        "Total retries of transient Delta read errors",
+        &["bucket"]
+    )
+    .unwrap();
 }
 
 // =============================================================================
@@ -512,7 +566,7 @@ impl TenantMetricsInfo {
 /// # Example
 ///
 /// ```ignore
-/// // In main.rs middleware stack (layers execute in reverse order):
+/// // In server.rs middleware stack (layers execute in reverse order):
 /// app.layer(middleware::from_fn(track_metrics))
 ///    .layer(middleware::from_fn(tenant_metrics_middleware))  // <-- This middleware
 ///    .layer(middleware::from_fn(tenant_resolver_middleware))
@@ -612,6 +666,13 @@ pub async fn metrics_handler() -> impl IntoResponse {
     }
 }
 
+/// Record a request cancelled for exceeding its time budget
+pub fn record_request_timeout(method: &str, path: &str) {
+    HTTP_REQUEST_TIMEOUTS_TOTAL
+        .with_label_values(&[method, path])
+        .inc();
+}
+
 /// Record a catalog operation metric
 pub fn record_catalog_operation(operation: &str, status: &str) {
     CATALOG_OPERATIONS_TOTAL
@@ -992,3 +1053,30 @@ pub fn record_lineage_record_success() {
 pub fn record_lineage_delete_success() {
     record_lineage_operation_success("delete");
 }
+
+// =============================================================================
+// Delta Read Metrics Helper Functions
+// =============================================================================
+
+/// Exports Delta read limiter events as Prometheus metrics
+pub struct DeltaReadMetrics;
+
+impl metafuse_catalog_delta::ReadObserver for DeltaReadMetrics {
+    fn on_permit_wait(&self, bucket: &str, wait: std::time::Duration) {
+        DELTA_READ_WAIT_SECONDS
+            .with_label_values(&[bucket])
+            .observe(wait.as_secs_f64());
+    }
+
+    fn on_retry(&self, bucket: &str) {
+        DELTA_READ_RETRIES_TOTAL.with_label_values(&[bucket]).inc();
+    }
+
+    fn on_read(&self, bucket: &str, success: bool, duration: std::time::Duration) {
+        let status = if success { "success" } else { "error" };
+        DELTA_READS_TOTAL.with_label_values(&[bucket, status]).inc();
+        DELTA_READ_DURATION_SECONDS
+            .with_label_values(&[bucket])
+            .observe(duration.as_secs_f64());
+    }
+}
@@ -44,10 +44,12 @@ use metafuse_catalog_core::Result;
 use metafuse_catalog_storage::{
     CatalogBackend, TenantBackendFactory, TenantBackendHandle, TenantContext,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[cfg(feature = "api-keys")]
 use crate::control_plane::ControlPlane;
+use crate::export_schedules::CredentialsKey;
 #[cfg(feature = "api-keys")]
 use crate::tenant_resolver::ResolvedTenant;
 
@@ -58,10 +60,16 @@ pub struct MultiTenantConfig {
     pub enabled: bool,
     /// Storage URI template for tenant catalogs (must contain {tenant_id})
     pub storage_uri_template: String,
+    /// Region-specific storage URI templates, keyed by region
+    /// (see [`crate::data_residency`])
+    pub region_storage_templates: HashMap<String, String>,
     /// Maximum number of tenant backends to cache
     pub cache_capacity: usize,
     /// Path to the control plane database
     pub control_plane_db_path: String,
+    /// Key tenant credentials profiles are encrypted with
+    /// (`METAFUSE_CREDENTIALS_KEY`, read by `ServerConfig::from_env`)
+    pub credentials_key: Option<CredentialsKey>,
     /// Allow header-only tenant resolution (X-Tenant-ID without API key).
     ///
     /// **Security Warning**: When enabled, any request can specify a tenant via header,
@@ -69,7 +77,7 @@ pub struct MultiTenantConfig {
     /// traffic, this should be `false` to require API key authentication.
     ///
     /// Default: `false` (require API key for tenant resolution)
-    /// Reserved for main.rs integration in a future phase.
+    /// Reserved for server.rs integration in a future phase.
     #[allow(dead_code)]
     pub allow_header_only_resolution: bool,
 }
@@ -79,8 +87,10 @@ impl Default for MultiTenantConfig {
         Self {
             enabled: false,
             storage_uri_template: String::new(),
+            region_storage_templates: HashMap::new(),
             cache_capacity: 100,
             control_plane_db_path: "control_plane.db".to_string(),
+            credentials_key: None,
             allow_header_only_resolution: false, // Secure default
         }
     }
@@ -92,6 +102,8 @@ impl MultiTenantConfig {
     /// Reads:
     /// - `METAFUSE_MULTI_TENANT_ENABLED`: "true" to enable
     /// - `METAFUSE_TENANT_STORAGE_TEMPLATE`: URI template with {tenant_id}
+    /// - `METAFUSE_REGION_STORAGE_TEMPLATES`: `region=template` pairs for
+    ///   region-specific storage
     /// - `METAFUSE_TENANT_CACHE_CAPACITY`: Max cached backends (default: 100)
     /// - `METAFUSE_CONTROL_PLANE_DB`: Path to control plane database
     /// - `METAFUSE_ALLOW_HEADER_ONLY_RESOLUTION`: "true" to allow X-Tenant-ID without API key
@@ -120,8 +132,10 @@ impl MultiTenantConfig {
         Self {
             enabled,
             storage_uri_template,
+            region_storage_templates: crate::data_residency::region_templates_from_env(),
             cache_capacity,
             control_plane_db_path,
+            credentials_key: None,
             allow_header_only_resolution,
         }
     }
@@ -263,13 +277,16 @@ impl MultiTenantResources {
         }
 
         let factory =
-            TenantBackendFactory::new(&config.storage_uri_template, config.cache_capacity)?;
+            TenantBackendFactory::new(&config.storage_uri_template, config.cache_capacity)?
+                .with_region_templates(config.region_storage_templates.clone())?;
 
         #[cfg(feature = "api-keys")]
         let control_plane = ControlPlane::new(
             config.control_plane_db_path.clone(),
             config.storage_uri_template.clone(),
-        )?;
+        )?
+        .with_region_storage_templates(config.region_storage_templates.clone())
+        .with_credentials_key(config.credentials_key.clone());
 
         Ok(Self {
             factory: Some(Arc::new(factory)),
@@ -300,7 +317,7 @@ impl MultiTenantResources {
     /// or None to indicate the caller should use the default backend.
     ///
     /// The returned handle maintains a connection permit that is released when dropped.
-    /// Reserved for main.rs handler integration in a future phase.
+    /// Reserved for server.rs handler integration in a future phase.
     #[allow(dead_code)]
     pub async fn get_tenant_backend(
         &self,
@@ -410,10 +427,11 @@ pub fn resolve_backend(
 
 /// Error response type for RBAC permission checks.
 ///
-/// This matches the ErrorResponse used in main.rs handlers.
+/// This matches the ErrorResponse used in server.rs handlers.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct RbacErrorResponse {
     pub error: String,
+    pub code: crate::error_codes::ErrorCode,
     pub request_id: String,
 }
 
@@ -470,6 +488,7 @@ pub fn require_write_permission(
                 axum::http::StatusCode::FORBIDDEN,
                 axum::Json(RbacErrorResponse {
                     error: "Write permission denied. Requires Editor or Admin role.".to_string(),
+                    code: crate::error_codes::ErrorCode::Forbidden,
                     request_id: request_id.to_string(),
                 }),
             ));
@@ -496,6 +515,7 @@ pub fn require_delete_permission(
                 axum::http::StatusCode::FORBIDDEN,
                 axum::Json(RbacErrorResponse {
                     error: "Delete permission denied. Requires Admin role.".to_string(),
+                    code: crate::error_codes::ErrorCode::Forbidden,
                     request_id: request_id.to_string(),
                 }),
             ));
@@ -524,6 +544,7 @@ pub fn require_admin_permission(
                 axum::http::StatusCode::FORBIDDEN,
                 axum::Json(RbacErrorResponse {
                     error: "Admin permission denied. Requires Admin role.".to_string(),
+                    code: crate::error_codes::ErrorCode::Forbidden,
                     request_id: request_id.to_string(),
                 }),
             ));
@@ -545,22 +566,92 @@ pub fn require_admin_permission(
 /// # Error Responses
 ///
 /// - 503 Service Unavailable: Connection limit reached or circuit breaker open
-/// - 403 Forbidden: Tenant is suspended
+/// - 403 Forbidden: Tenant is suspended, or a write would leave the tenant's
+///   residency zone (see [`crate::data_residency`])
 /// - 500 Internal Server Error: Backend creation failed
 ///
 /// Requires:
-/// - TenantBackendFactory as Extension
+/// - TenantBackendFactory and ControlPlane as Extensions
 /// - tenant_resolver_middleware to run first
 #[cfg(feature = "api-keys")]
 pub async fn tenant_backend_middleware(
     axum::extract::Extension(factory): axum::extract::Extension<Arc<TenantBackendFactory>>,
+    axum::extract::Extension(control_plane): axum::extract::Extension<Arc<ControlPlane>>,
     mut req: axum::extract::Request,
     next: axum::middleware::Next,
 ) -> axum::response::Response {
     // Check if tenant was resolved
     if let Some(resolved) = req.extensions().get::<ResolvedTenant>().cloned() {
         // Get tenant region for multi-region deployments
-        let region = resolved.region().map(String::from);
+        let mut region = resolved.region().map(String::from);
+
+        // Resident tenants are routed by the region on record, also when resolved by
+        // header, and may only write to region-specific storage inside their zone
+        let is_write = !req.method().is_safe();
+        match control_plane
+            .get_tenant_residency(resolved.tenant_id())
+            .await
+        {
+            Ok(Some(residency)) => {
+                if is_write {
+                    if let Err(e) = control_plane
+                        .residency_zones()
+                        .check_placement(&residency, &factory)
+                    {
+                        let reason = match e {
+                            metafuse_catalog_core::CatalogError::ValidationError(msg) => msg,
+                            e => e.to_string(),
+                        };
+                        tracing::warn!(
+                            tenant_id = %resolved.tenant_id(),
+                            zone = %residency.zone,
+                            region = ?residency.region,
+                            reason = %reason,
+                            "Write blocked by tenant data residency"
+                        );
+                        return axum::response::Response::builder()
+                            .status(axum::http::StatusCode::FORBIDDEN)
+                            .header(axum::http::header::CONTENT_TYPE, "application/json")
+                            .body(axum::body::Body::from(
+                                serde_json::json!({
+                                    "error": format!(
+                                        "Write blocked by data residency '{}': {}",
+                                        residency.zone, reason
+                                    ),
+                                    "code": "FORBIDDEN",
+                                })
+                                .to_string(),
+                            ))
+                            .unwrap();
+                    }
+                }
+                region = residency.region;
+            }
+            Ok(None) => {}
+            Err(e) if is_write => {
+                // Fail closed: a write must not land outside an unknown residency zone
+                tracing::error!(
+                    tenant_id = %resolved.tenant_id(),
+                    error = %e,
+                    "Failed to load tenant data residency"
+                );
+                return axum::response::Response::builder()
+                    .status(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+                    .header(axum::http::header::CONTENT_TYPE, "application/json")
+                    .header(axum::http::header::RETRY_AFTER, "5")
+                    .body(axum::body::Body::from(
+                        r#"{"error": "Tenant data residency unavailable", "code": "SERVICE_UNAVAILABLE", "retry_after": 5}"#,
+                    ))
+                    .unwrap();
+            }
+            Err(e) => {
+                tracing::warn!(
+                    tenant_id = %resolved.tenant_id(),
+                    error = %e,
+                    "Failed to load tenant data residency, using key region"
+                );
+            }
+        }
 
         // Get connection-limited backend handle with region
         match factory
@@ -597,7 +688,7 @@ pub async fn tenant_backend_middleware(
                         .status(axum::http::StatusCode::FORBIDDEN)
                         .header(axum::http::header::CONTENT_TYPE, "application/json")
                         .body(axum::body::Body::from(
-                            r#"{"error": "Tenant is suspended"}"#,
+                            r#"{"error": "Tenant is suspended", "code": "TENANT_SUSPENDED"}"#,
                         ))
                         .unwrap();
                 } else if error_msg.contains("Timeout") || error_msg.contains("Circuit breaker") {
@@ -606,7 +697,7 @@ pub async fn tenant_backend_middleware(
                         .header(axum::http::header::CONTENT_TYPE, "application/json")
                         .header(axum::http::header::RETRY_AFTER, "5")
                         .body(axum::body::Body::from(
-                            r#"{"error": "Service temporarily overloaded", "retry_after": 5}"#,
+                            r#"{"error": "Service temporarily overloaded", "code": "SERVICE_UNAVAILABLE", "retry_after": 5}"#,
                         ))
                         .unwrap();
                 } else {
@@ -614,7 +705,7 @@ pub async fn tenant_backend_middleware(
                         .status(axum::http::StatusCode::INTERNAL_SERVER_ERROR)
                         .header(axum::http::header::CONTENT_TYPE, "application/json")
                         .body(axum::body::Body::from(format!(
-                            r#"{{"error": "Failed to initialize tenant backend", "details": "{}"}}"#,
+                            r#"{{"error": "Failed to initialize tenant backend", "code": "INTERNAL_ERROR", "details": "{}"}}"#,
                             error_msg
                         )))
                         .unwrap();
@@ -703,8 +794,10 @@ mod tests {
         let config = MultiTenantConfig {
             enabled: true,
             storage_uri_template: format!("{}/{{tenant_id}}/catalog.db", temp.path().display()),
+            region_storage_templates: HashMap::new(),
             cache_capacity: 50,
             control_plane_db_path: temp.path().join("control.db").to_string_lossy().to_string(),
+            credentials_key: None,
             allow_header_only_resolution: false,
         };
 
@@ -758,8 +851,10 @@ mod tests {
         let config = MultiTenantConfig {
             enabled: true,
             storage_uri_template: format!("{}/{{tenant_id}}/catalog.db", temp.path().display()),
+            region_storage_templates: HashMap::new(),
             cache_capacity: 50,
             control_plane_db_path: temp.path().join("control.db").to_string_lossy().to_string(),
+            credentials_key: None,
             allow_header_only_resolution: false,
         };
 
@@ -794,8 +889,10 @@ mod tests {
         let config = MultiTenantConfig {
             enabled: true,
             storage_uri_template: format!("{}/{{tenant_id}}/catalog.db", temp.path().display()),
+            region_storage_templates: HashMap::new(),
             cache_capacity: 50,
             control_plane_db_path: temp.path().join("control.db").to_string_lossy().to_string(),
+            credentials_key: None,
             allow_header_only_resolution: false,
         };
 
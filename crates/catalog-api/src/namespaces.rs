@@ -0,0 +1,777 @@
+//! Namespaces Module
+//!
+//! Hierarchical namespaces for dataset names, so teams can each have an
+//! `orders` dataset (`sales.orders`, `marketing.orders`) without collisions.
+//!
+//! # Architecture
+//!
+//! Namespaces are registered in the `namespaces` table (migration v1.14.0).
+//! A dataset's full name is still its catalog-wide unique `name`; its namespace
+//! is the longest registered namespace prefixing that name ([`split_dataset_name`]).
+//! Nothing is stored on the dataset itself, so datasets written by the emitter
+//! join a namespace as soon as it is registered.
+//!
+//! Dotted names were already valid. A name whose prefix is not a registered
+//! namespace is a legacy flat name and resolves exactly as before.
+//!
+//! A namespace registered with a `ttl_secs` is a sandbox (`expires_at` is set,
+//! migration v1.38.0); see the [`crate::sandbox`] module.
+//!
+//! # Endpoints
+//!
+//! - `GET /api/v1/namespaces` - List namespaces (filter by `parent`)
+//! - `POST /api/v1/namespaces` - Register a namespace (parent must exist)
+//! - `GET /api/v1/namespaces/{name}` - Get a namespace
+//! - `DELETE /api/v1/namespaces/{name}` - Remove an empty namespace
+//! - `GET /api/v1/namespaces/{name}/datasets` - List datasets in a namespace
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::datasets::attach_uuids;
+use crate::datasets::parse_partition_keys;
+use crate::datasets::DatasetResponse;
+use crate::datasets::OperationalMetaResponse;
+use crate::error_codes::ErrorCode;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use crate::{dataset_acl, envelope, sandbox};
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Maximum nesting depth (`a.b.c` has depth 3)
+pub const MAX_NAMESPACE_DEPTH: usize = 8;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// A registered namespace.
+#[derive(Debug, Clone, Serialize)]
+pub struct Namespace {
+    pub id: i64,
+    pub name: String,
+    pub parent: Option<String>,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// When the namespace is purged, for sandbox namespaces
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    /// Datasets directly in this namespace (not in child namespaces)
+    pub dataset_count: i64,
+    /// Direct child namespaces
+    pub child_count: i64,
+}
+
+/// Request to register a namespace.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewNamespace {
+    pub name: String,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    /// Register a sandbox namespace purged after this many seconds
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+// =============================================================================
+// Name Handling
+// =============================================================================
+
+/// Validate a dot-separated namespace name.
+///
+/// Each segment follows identifier rules (alphanumeric, `_`, `-`).
+pub fn validate_namespace(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Namespace cannot be empty".to_string());
+    }
+    let segments: Vec<&str> = name.split('.').collect();
+    if segments.len() > MAX_NAMESPACE_DEPTH {
+        return Err(format!(
+            "Namespace '{}' is nested too deeply (max {} levels)",
+            name, MAX_NAMESPACE_DEPTH
+        ));
+    }
+    for segment in segments {
+        validation::validate_identifier(segment, "Namespace segment").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Enclosing namespace of a namespace, if nested.
+pub fn parent_of(namespace: &str) -> Option<&str> {
+    namespace.rsplit_once('.').map(|(parent, _)| parent)
+}
+
+/// Split a dataset name into its namespace and local name.
+///
+/// Uses the longest registered namespace prefixing the name. Names without a
+/// registered prefix are legacy flat names and return `(None, name)`.
+pub fn split_dataset_name<'a>(
+    namespaces: &BTreeSet<String>,
+    name: &'a str,
+) -> (Option<&'a str>, &'a str) {
+    let mut end = name.len();
+    while let Some(dot) = name[..end].rfind('.') {
+        let prefix = &name[..dot];
+        if namespaces.contains(prefix) {
+            return (Some(prefix), &name[dot + 1..]);
+        }
+        end = dot;
+    }
+    (None, name)
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+/// All registered namespace names.
+pub fn registered_namespaces(conn: &Connection) -> rusqlite::Result<BTreeSet<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM namespaces")?;
+    let names = stmt.query_map([], |row| row.get(0))?;
+    names.collect()
+}
+
+/// Count live datasets directly in each namespace.
+fn dataset_counts(
+    conn: &Connection,
+    namespaces: &BTreeSet<String>,
+) -> rusqlite::Result<HashMap<String, i64>> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM datasets WHERE instr(name, '.') > 0 AND deleted_at IS NULL")?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut counts = HashMap::new();
+    for name in names {
+        let name = name?;
+        if let (Some(namespace), _) = split_dataset_name(namespaces, &name) {
+            *counts.entry(namespace.to_string()).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+const SELECT_NAMESPACE: &str = r#"
+    SELECT n.id, n.name, n.parent, n.description, n.owner, n.created_at, n.updated_at,
+           (SELECT COUNT(*) FROM namespaces c WHERE c.parent = n.name), n.expires_at
+    FROM namespaces n
+"#;
+
+fn row_to_namespace(row: &rusqlite::Row<'_>) -> rusqlite::Result<Namespace> {
+    Ok(Namespace {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        parent: row.get(2)?,
+        description: row.get(3)?,
+        owner: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        expires_at: row.get(8)?,
+        dataset_count: 0,
+        child_count: row.get(7)?,
+    })
+}
+
+/// Register a namespace. Fails with a foreign key error if the parent is missing.
+///
+/// With `ttl_secs` the namespace is a sandbox expiring that many seconds from now.
+pub fn create_namespace(conn: &Connection, new: &NewNamespace) -> rusqlite::Result<Namespace> {
+    conn.execute(
+        "INSERT INTO namespaces (name, parent, description, owner, expires_at)
+         VALUES (?1, ?2, ?3, ?4,
+                 CASE WHEN ?5 IS NULL THEN NULL ELSE datetime('now', '+' || ?5 || ' seconds') END)",
+        params![
+            new.name,
+            parent_of(&new.name),
+            new.description,
+            new.owner,
+            new.ttl_secs.map(|secs| secs as i64)
+        ],
+    )?;
+    get_namespace(conn, &new.name)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+/// Get a namespace by name.
+pub fn get_namespace(conn: &Connection, name: &str) -> rusqlite::Result<Option<Namespace>> {
+    let namespace = conn
+        .query_row(
+            &format!("{} WHERE n.name = ?1", SELECT_NAMESPACE),
+            [name],
+            row_to_namespace,
+        )
+        .optional()?;
+
+    match namespace {
+        Some(mut namespace) => {
+            let registered = registered_namespaces(conn)?;
+            namespace.dataset_count = dataset_counts(conn, &registered)?
+                .remove(&namespace.name)
+                .unwrap_or(0);
+            Ok(Some(namespace))
+        }
+        None => Ok(None),
+    }
+}
+
+/// List namespaces, optionally only the direct children of `parent`.
+pub fn list_namespaces(
+    conn: &Connection,
+    parent: Option<&str>,
+) -> rusqlite::Result<Vec<Namespace>> {
+    let mut namespaces: Vec<Namespace> = match parent {
+        Some(parent) => {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE n.parent = ?1 ORDER BY n.name",
+                SELECT_NAMESPACE
+            ))?;
+            let rows = stmt.query_map([parent], row_to_namespace)?;
+            rows.collect::<rusqlite::Result<_>>()?
+        }
+        None => {
+            let mut stmt = conn.prepare(&format!("{} ORDER BY n.name", SELECT_NAMESPACE))?;
+            let rows = stmt.query_map([], row_to_namespace)?;
+            rows.collect::<rusqlite::Result<_>>()?
+        }
+    };
+
+    let registered = registered_namespaces(conn)?;
+    let counts = dataset_counts(conn, &registered)?;
+    for namespace in &mut namespaces {
+        namespace.dataset_count = counts.get(&namespace.name).copied().unwrap_or(0);
+    }
+    Ok(namespaces)
+}
+
+/// Remove a namespace. Fails with a foreign key error if it has children.
+///
+/// Callers must check `dataset_count` first; datasets are not reassigned.
+pub fn delete_namespace(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    let rows = conn.execute("DELETE FROM namespaces WHERE name = ?1", [name])?;
+    Ok(rows > 0)
+}
+
+/// SQL `GLOB` pattern matching dataset names under a namespace.
+///
+/// Namespaces are validated identifiers, so they contain no glob wildcards.
+pub fn dataset_glob(namespace: &str) -> String {
+    format!("{}.*", namespace)
+}
+
+/// Query parameters for listing namespaces
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListNamespacesParams {
+    /// Only list direct children of this namespace
+    parent: Option<String>,
+}
+
+/// Query parameters for listing datasets in a namespace
+#[derive(Debug, Deserialize)]
+pub(crate) struct NamespaceDatasetsParams {
+    /// Include datasets in child namespaces (default: false)
+    #[serde(default)]
+    recursive: bool,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+/// List namespaces
+pub(crate) async fn get_namespaces(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(params): Query<ListNamespacesParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<Namespace>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, parent = ?params.parent, "Listing namespaces");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let result = list_namespaces(&conn, params.parent.as_deref())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.page(result)))
+}
+
+/// Register a namespace
+pub(crate) async fn post_namespace(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    request_sandbox: Option<Extension<sandbox::Sandbox>>,
+    JsonBody(req): JsonBody<NewNamespace>,
+) -> Result<(StatusCode, Json<Namespace>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %req.name, "Creating namespace");
+
+    validate_namespace(&req.name).map_err(|e| bad_request(e, request_id.0.clone()))?;
+    if let Some(ttl_secs) = req.ttl_secs {
+        state
+            .sandbox_config
+            .validate_ttl(ttl_secs)
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    }
+    // A sandboxed request may only register its own sandbox
+    if let Some(Extension(sandbox)) = &request_sandbox {
+        if req.name != sandbox.0 || req.ttl_secs.is_none() {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    error: format!(
+                        "Sandboxed requests can only register sandbox '{}' with a ttl_secs",
+                        sandbox.0
+                    ),
+                    code: ErrorCode::Forbidden,
+                    request_id: request_id.0.clone(),
+                }),
+            ));
+        }
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Free the names of expired sandboxes (tenant catalogs have no purge task)
+    match sandbox::purge_expired(&conn) {
+        Ok(summary) if !summary.sandboxes.is_empty() => tracing::info!(
+            sandboxes = ?summary.sandboxes,
+            datasets = summary.datasets,
+            "Purged expired sandboxes"
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::warn!(error = %e, "Failed to purge expired sandboxes"),
+    }
+    if let Some(parent) = parent_of(&req.name) {
+        let parent_status = sandbox::status(&conn, parent)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        if matches!(
+            parent_status,
+            sandbox::SandboxStatus::Active | sandbox::SandboxStatus::Expired
+        ) {
+            return Err(bad_request(
+                format!("Sandbox '{}' cannot have child namespaces", parent),
+                request_id.0.clone(),
+            ));
+        }
+    }
+
+    let namespace = create_namespace(&conn, &req).map_err(|e| {
+        let message = e.to_string();
+        if message.contains("UNIQUE constraint failed") {
+            bad_request(
+                format!("Namespace '{}' already exists", req.name),
+                request_id.0.clone(),
+            )
+        } else if message.contains("FOREIGN KEY constraint failed") {
+            bad_request(
+                format!(
+                    "Parent namespace '{}' does not exist",
+                    parent_of(&req.name).unwrap_or_default()
+                ),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(message, request_id.0.clone())
+        }
+    })?;
+
+    tracing::info!(name = %namespace.name, id = namespace.id, "Namespace created successfully");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "namespace",
+            &namespace.name,
+            serde_json::json!({
+                "id": namespace.id,
+                "name": namespace.name,
+                "parent": namespace.parent,
+                "owner": namespace.owner,
+                "expires_at": namespace.expires_at,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(namespace)))
+}
+
+/// Get a namespace by name
+pub(crate) async fn get_namespace_by_name(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(name): Path<String>,
+) -> Result<Json<Namespace>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Getting namespace");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    get_namespace(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .map(Json)
+        .ok_or_else(|| {
+            not_found(
+                format!("Namespace '{}' not found", name),
+                request_id.0.clone(),
+            )
+        })
+}
+
+/// Delete an empty namespace
+pub(crate) async fn delete_namespace_by_name(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, "Deleting namespace");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let namespace = get_namespace(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Namespace '{}' not found", name),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // Datasets would silently fall back to a parent namespace or legacy names
+    if namespace.child_count > 0 || namespace.dataset_count > 0 {
+        return Err(bad_request(
+            format!(
+                "Namespace '{}' is not empty ({} child namespaces, {} datasets)",
+                name, namespace.child_count, namespace.dataset_count
+            ),
+            request_id.0.clone(),
+        ));
+    }
+
+    delete_namespace(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Namespace deleted");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "namespace",
+            &name,
+            serde_json::json!({"name": name, "parent": namespace.parent}),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List datasets in a namespace
+pub(crate) async fn list_namespace_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Path(name): Path<String>,
+    Query(params): Query<NamespaceDatasetsParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<DatasetResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, namespace = %name, "Listing datasets in namespace");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let registered = registered_namespaces(&conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !registered.contains(&name) {
+        return Err(not_found(
+            format!("Namespace '{}' not found", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    let limit = params.limit.unwrap_or(100).min(1000);
+    let offset = params.offset.unwrap_or(0);
+
+    let mut filter = String::from("name GLOB ? AND deleted_at IS NULL");
+    let mut bindings = vec![dataset_glob(&name)];
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    if let Some((clause, principals)) =
+        dataset_acl::visibility_clause(&identity, "datasets.id", "datasets.domain")
+    {
+        filter.push_str(" AND ");
+        filter.push_str(&clause);
+        bindings.extend(principals);
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+                   created_at, last_updated, row_count, size_bytes, partition_keys
+            FROM datasets
+            WHERE {}
+            ORDER BY name
+            "#,
+            filter
+        ))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let datasets: Vec<DatasetResponse> = stmt
+        .query_map(params_from_iter(bindings.iter()), |row| {
+            let row_count: Option<i64> = row.get(11)?;
+            let size_bytes: Option<i64> = row.get(12)?;
+            let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+            Ok(DatasetResponse {
+                id: row.get(0)?,
+                uuid: None,
+                name: row.get(1)?,
+                path: row.get(2)?,
+                format: row.get(3)?,
+                delta_location: row.get(4)?,
+                description: row.get(5)?,
+                tenant: row.get(6)?,
+                domain: row.get(7)?,
+                owner: row.get(8)?,
+                created_at: row.get(9)?,
+                last_updated: row.get(10)?,
+                operational: OperationalMetaResponse {
+                    row_count,
+                    size_bytes,
+                    partition_keys,
+                },
+                freshness: None,
+                metadata_completeness: None,
+                custom_metadata: None,
+                tags: None,
+                quality_summary: None,
+                archived_at: None,
+                write_report: None,
+                owner_profile: None,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .filter_map(|r| r.ok())
+        // Without `recursive`, skip datasets that belong to a child namespace
+        .filter(|d| {
+            params.recursive || split_dataset_name(&registered, &d.name).0 == Some(name.as_str())
+        })
+        .collect();
+    let total = datasets.len() as i64;
+    let mut datasets: Vec<DatasetResponse> =
+        datasets.into_iter().skip(offset).take(limit).collect();
+    attach_uuids(&conn, &mut datasets)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.window(datasets, total)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        for name in [
+            "orders",
+            "db.legacy",
+            "sales.orders",
+            "sales.emea.orders",
+            "sales.emea.returns",
+            "marketing.orders",
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', datetime('now'), datetime('now'))",
+                [name],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn new_namespace(name: &str) -> NewNamespace {
+        NewNamespace {
+            name: name.to_string(),
+            description: None,
+            owner: None,
+            ttl_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_namespace() {
+        assert!(validate_namespace("sales").is_ok());
+        assert!(validate_namespace("sales.emea-west").is_ok());
+        assert!(validate_namespace("").is_err());
+        assert!(validate_namespace("sales..emea").is_err());
+        assert!(validate_namespace("sales.").is_err());
+        assert!(validate_namespace("sales/emea").is_err());
+        assert!(validate_namespace("a.b.c.d.e.f.g.h.i").is_err());
+    }
+
+    #[test]
+    fn test_split_dataset_name_uses_longest_registered_prefix() {
+        let registered: BTreeSet<String> = ["sales", "sales.emea"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(
+            split_dataset_name(&registered, "sales.emea.orders"),
+            (Some("sales.emea"), "orders")
+        );
+        assert_eq!(
+            split_dataset_name(&registered, "sales.orders"),
+            (Some("sales"), "orders")
+        );
+        // Legacy names resolve as flat names
+        assert_eq!(split_dataset_name(&registered, "orders"), (None, "orders"));
+        assert_eq!(
+            split_dataset_name(&registered, "db.legacy"),
+            (None, "db.legacy")
+        );
+    }
+
+    #[test]
+    fn test_create_requires_parent() {
+        let conn = setup_db();
+        assert!(create_namespace(&conn, &new_namespace("sales.emea")).is_err());
+
+        let sales = create_namespace(&conn, &new_namespace("sales")).unwrap();
+        assert_eq!(sales.parent, None);
+        let emea = create_namespace(&conn, &new_namespace("sales.emea")).unwrap();
+        assert_eq!(emea.parent.as_deref(), Some("sales"));
+
+        let duplicate = create_namespace(&conn, &new_namespace("sales"));
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("UNIQUE constraint failed"));
+    }
+
+    #[test]
+    fn test_counts_and_listing() {
+        let conn = setup_db();
+        create_namespace(&conn, &new_namespace("sales")).unwrap();
+        create_namespace(&conn, &new_namespace("sales.emea")).unwrap();
+        create_namespace(&conn, &new_namespace("marketing")).unwrap();
+
+        let sales = get_namespace(&conn, "sales").unwrap().unwrap();
+        assert_eq!(sales.dataset_count, 1);
+        assert_eq!(sales.child_count, 1);
+
+        let emea = get_namespace(&conn, "sales.emea").unwrap().unwrap();
+        assert_eq!(emea.dataset_count, 2);
+
+        let all = list_namespaces(&conn, None).unwrap();
+        assert_eq!(all.len(), 3);
+        let children = list_namespaces(&conn, Some("sales")).unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "sales.emea");
+
+        assert!(get_namespace(&conn, "finance").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_delete_blocked_by_children() {
+        let conn = setup_db();
+        create_namespace(&conn, &new_namespace("sales")).unwrap();
+        create_namespace(&conn, &new_namespace("sales.emea")).unwrap();
+
+        assert!(delete_namespace(&conn, "sales").is_err());
+        assert!(delete_namespace(&conn, "sales.emea").unwrap());
+        assert!(delete_namespace(&conn, "sales").unwrap());
+        assert!(!delete_namespace(&conn, "sales").unwrap());
+    }
+}
@@ -0,0 +1,763 @@
+//! OIDC / JWT Authentication
+//!
+//! Tenant API keys (`mft_`) authenticate machines; people sign in through the
+//! organization's identity provider. With an OIDC issuer configured, a Bearer
+//! token that is a JWT is verified against the issuer's signing keys and its
+//! claims are mapped to a tenant, a role, a user and groups. The tenant
+//! resolver middleware then treats the request like one made with a tenant
+//! API key of that role (see `tenant_resolver`).
+//!
+//! # Verification
+//!
+//! - Signatures: `RS256`, `RS384`, `RS512`, `ES256` and `ES384`. Unsigned
+//!   (`none`) and shared-secret (`HS*`) tokens are rejected.
+//! - Keys come from the issuer's JWKS, found through OIDC discovery unless
+//!   configured. The keys are cached and refetched when they expire or a token
+//!   names an unknown `kid`, so rotated keys are picked up without a restart.
+//! - `iss` must be the issuer, `aud` must contain the audience, and `exp` /
+//!   `nbf` are checked with a leeway for clock skew.
+//!
+//! # Claim mapping
+//!
+//! Claim names may be dotted paths into nested objects (e.g.
+//! `realm_access.roles`).
+//!
+//! - Tenant: the tenant claim, a string or an array of tenants the user may
+//!   act in. With several, `X-Tenant-ID` picks one. Without the claim, the
+//!   default tenant is used if configured.
+//! - Role: each value of the role claim is mapped through the role map.
+//!   Without a role map, role names (`admin`, `editor`, `viewer`) map to
+//!   themselves. The highest mapped role wins; tokens without one get
+//!   `viewer`.
+//! - User and groups: the user claim (default `sub`) and groups claim become
+//!   the caller's identity for dataset ACLs, ownership and audit logs.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_OIDC_ISSUER`: issuer URL; OIDC authentication is off when unset
+//! - `METAFUSE_OIDC_AUDIENCE`: audience tokens must be issued for; required
+//!   with an issuer, so tokens the IdP issued to other apps are not accepted
+//! - `METAFUSE_OIDC_JWKS_URL`: JWKS URL (default: discovered from the issuer)
+//! - `METAFUSE_OIDC_TENANT_CLAIM`: tenant claim (default: `tenant_id`)
+//! - `METAFUSE_OIDC_DEFAULT_TENANT`: tenant for tokens without the claim
+//! - `METAFUSE_OIDC_ROLE_CLAIM`: role claim (default: `roles`)
+//! - `METAFUSE_OIDC_ROLE_MAP`: claim values per role, as
+//!   `admin=catalog-admins;editor=data-eng,analytics-eng`; an invalid map
+//!   fails startup rather than falling back to role names
+//! - `METAFUSE_OIDC_USER_CLAIM`: user claim (default: `sub`)
+//! - `METAFUSE_OIDC_GROUPS_CLAIM`: groups claim (default: `groups`)
+//! - `METAFUSE_OIDC_LEEWAY_SECS`: clock skew allowance (default: 60)
+//! - `METAFUSE_OIDC_JWKS_TTL_SECS`: seconds to cache the JWKS (default: 3600)
+
+use crate::control_plane::TenantRole;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, warn};
+
+/// Default clock skew allowance for `exp` and `nbf`
+pub const DEFAULT_LEEWAY_SECS: i64 = 60;
+
+/// Default seconds to cache the issuer's JWKS
+pub const DEFAULT_JWKS_TTL_SECS: u64 = 3600;
+
+/// Minimum seconds between JWKS fetches triggered by unknown key ids, so
+/// tokens with made-up `kid`s cannot hammer the issuer
+const MIN_REFETCH_SECS: u64 = 30;
+
+/// Timeout for discovery and JWKS requests
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// OIDC authentication configuration
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    pub issuer: String,
+    pub audience: String,
+    pub jwks_url: Option<String>,
+    pub tenant_claim: String,
+    pub default_tenant: Option<String>,
+    pub role_claim: String,
+    /// Claim value to role
+    pub role_map: HashMap<String, TenantRole>,
+    pub user_claim: String,
+    pub groups_claim: String,
+    pub leeway_secs: i64,
+    pub jwks_ttl_secs: u64,
+}
+
+impl OidcConfig {
+    /// Configuration for `issuer` and `audience` with the default claims.
+    pub fn new(issuer: &str, audience: &str) -> Self {
+        Self {
+            issuer: issuer.trim_end_matches('/').to_string(),
+            audience: audience.to_string(),
+            jwks_url: None,
+            tenant_claim: "tenant_id".to_string(),
+            default_tenant: None,
+            role_claim: "roles".to_string(),
+            role_map: HashMap::new(),
+            user_claim: "sub".to_string(),
+            groups_claim: "groups".to_string(),
+            leeway_secs: DEFAULT_LEEWAY_SECS,
+            jwks_ttl_secs: DEFAULT_JWKS_TTL_SECS,
+        }
+    }
+
+    /// Create config from environment variables; `None` when no issuer is set.
+    ///
+    /// Fails without an audience or with an invalid role map, so a
+    /// misconfigured server does not start accepting tokens.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let Some(issuer) = var("METAFUSE_OIDC_ISSUER") else {
+            return Ok(None);
+        };
+        let audience = var("METAFUSE_OIDC_AUDIENCE").ok_or_else(|| {
+            "METAFUSE_OIDC_AUDIENCE is required when METAFUSE_OIDC_ISSUER is set".to_string()
+        })?;
+        let mut config = Self::new(&issuer, &audience);
+        config.jwks_url = var("METAFUSE_OIDC_JWKS_URL");
+        config.default_tenant = var("METAFUSE_OIDC_DEFAULT_TENANT");
+        if let Some(claim) = var("METAFUSE_OIDC_TENANT_CLAIM") {
+            config.tenant_claim = claim;
+        }
+        if let Some(claim) = var("METAFUSE_OIDC_ROLE_CLAIM") {
+            config.role_claim = claim;
+        }
+        if let Some(claim) = var("METAFUSE_OIDC_USER_CLAIM") {
+            config.user_claim = claim;
+        }
+        if let Some(claim) = var("METAFUSE_OIDC_GROUPS_CLAIM") {
+            config.groups_claim = claim;
+        }
+        if let Some(spec) = var("METAFUSE_OIDC_ROLE_MAP") {
+            config.role_map = parse_role_map(&spec)
+                .map_err(|e| format!("Invalid METAFUSE_OIDC_ROLE_MAP: {}", e))?;
+        }
+        if let Some(secs) = var("METAFUSE_OIDC_LEEWAY_SECS").and_then(|v| v.parse().ok()) {
+            config.leeway_secs = secs;
+        }
+        if let Some(secs) = var("METAFUSE_OIDC_JWKS_TTL_SECS").and_then(|v| v.parse().ok()) {
+            config.jwks_ttl_secs = secs;
+        }
+        Ok(Some(config))
+    }
+
+    /// Role for the values of the role claim: the highest mapped one.
+    ///
+    /// Role names only map to themselves without a role map; with one, an
+    /// IdP value that happens to be `admin` must not bypass it.
+    pub fn role(&self, values: &[String]) -> TenantRole {
+        values
+            .iter()
+            .filter_map(|v| {
+                if self.role_map.is_empty() {
+                    v.parse::<TenantRole>().ok()
+                } else {
+                    self.role_map.get(v).copied()
+                }
+            })
+            .max_by_key(|role| match role {
+                TenantRole::Viewer => 0,
+                TenantRole::Editor => 1,
+                TenantRole::Admin => 2,
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Parse a role map written as `role=value,value;role=value`.
+pub fn parse_role_map(spec: &str) -> Result<HashMap<String, TenantRole>, String> {
+    let mut map = HashMap::new();
+    for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (role, values) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid role mapping '{}': expected role=values", entry))?;
+        let role: TenantRole = role.trim().parse()?;
+        for value in values.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            map.insert(value.to_string(), role);
+        }
+    }
+    Ok(map)
+}
+
+/// Why a token was not accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OidcError {
+    /// The token is malformed, forged, expired or not meant for this API
+    Invalid(String),
+    /// The issuer's keys could not be fetched
+    Unavailable(String),
+}
+
+impl std::fmt::Display for OidcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OidcError::Invalid(msg) => write!(f, "invalid token: {}", msg),
+            OidcError::Unavailable(msg) => write!(f, "identity provider unavailable: {}", msg),
+        }
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> OidcError {
+    OidcError::Invalid(msg.into())
+}
+
+/// The signed-in user, attached to request extensions by the tenant resolver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidcPrincipal {
+    pub user: String,
+    /// Tenants the user may act in
+    pub tenants: Vec<String>,
+    pub role: TenantRole,
+    /// Groups asserted by the token
+    pub groups: Vec<String>,
+}
+
+impl OidcPrincipal {
+    /// Pick the tenant for a request: the `X-Tenant-ID` header if the user may
+    /// act in it, otherwise the user's only tenant.
+    pub fn select_tenant(&self, header: Option<&str>) -> Result<&str, String> {
+        match (header, self.tenants.as_slice()) {
+            (Some(header), tenants) => tenants
+                .iter()
+                .find(|t| t.as_str() == header)
+                .map(String::as_str)
+                .ok_or_else(|| format!("Token does not grant access to tenant '{}'", header)),
+            (None, [tenant]) => Ok(tenant),
+            (None, []) => Err("Token does not name a tenant".to_string()),
+            (None, _) => Err(format!(
+                "Token grants access to several tenants; choose one with {}",
+                crate::tenant_resolver::TENANT_ID_HEADER
+            )),
+        }
+    }
+}
+
+/// Whether a Bearer token looks like a JWT rather than an API key.
+pub fn looks_like_jwt(token: &str) -> bool {
+    token.starts_with("eyJ") && token.split('.').count() == 3
+}
+
+/// A public key from a JWKS.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(default)]
+    pub kid: Option<String>,
+    #[serde(default)]
+    pub alg: Option<String>,
+    #[serde(default, rename = "use")]
+    pub key_use: Option<String>,
+    // RSA
+    #[serde(default)]
+    pub n: Option<String>,
+    #[serde(default)]
+    pub e: Option<String>,
+    // EC
+    #[serde(default)]
+    pub crv: Option<String>,
+    #[serde(default)]
+    pub x: Option<String>,
+    #[serde(default)]
+    pub y: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    kid: Option<String>,
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, OidcError> {
+    URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|_| invalid("malformed base64url"))
+}
+
+impl Jwk {
+    /// Key type and curve required by a signing algorithm
+    fn matches(&self, alg: &str) -> bool {
+        let shape = match alg {
+            "RS256" | "RS384" | "RS512" => self.kty == "RSA",
+            "ES256" => self.kty == "EC" && self.crv.as_deref() == Some("P-256"),
+            "ES384" => self.kty == "EC" && self.crv.as_deref() == Some("P-384"),
+            _ => false,
+        };
+        shape
+            && self.alg.as_deref().is_none_or(|a| a == alg)
+            && self.key_use.as_deref().is_none_or(|u| u == "sig")
+    }
+
+    fn verify(&self, alg: &str, message: &[u8], sig: &[u8]) -> Result<(), OidcError> {
+        let component = |value: &Option<String>| {
+            value
+                .as_deref()
+                .ok_or_else(|| invalid("signing key is incomplete"))
+                .and_then(decode_segment)
+        };
+        let verified = match alg {
+            "RS256" | "RS384" | "RS512" => {
+                let params = match alg {
+                    "RS256" => &signature::RSA_PKCS1_2048_8192_SHA256,
+                    "RS384" => &signature::RSA_PKCS1_2048_8192_SHA384,
+                    _ => &signature::RSA_PKCS1_2048_8192_SHA512,
+                };
+                RsaPublicKeyComponents {
+                    n: component(&self.n)?,
+                    e: component(&self.e)?,
+                }
+                .verify(params, message, sig)
+            }
+            "ES256" | "ES384" => {
+                let algorithm = match alg {
+                    "ES256" => &signature::ECDSA_P256_SHA256_FIXED,
+                    _ => &signature::ECDSA_P384_SHA384_FIXED,
+                };
+                let mut point = vec![0x04];
+                point.extend(component(&self.x)?);
+                point.extend(component(&self.y)?);
+                UnparsedPublicKey::new(algorithm, point).verify(message, sig)
+            }
+            _ => return Err(invalid(format!("unsupported algorithm '{}'", alg))),
+        };
+        verified.map_err(|_| invalid("signature does not verify"))
+    }
+}
+
+/// Values of a claim, found by dotted path: a string, or the strings in an array.
+fn claim_values(claims: &Value, path: &str) -> Vec<String> {
+    let value = path
+        .split('.')
+        .try_fold(claims, |value, key| value.get(key));
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Verify a token's signature with `keys` and its claims against `config`
+/// at `now` (Unix seconds), returning the signed-in user.
+pub fn verify_token(
+    token: &str,
+    keys: &[Jwk],
+    config: &OidcConfig,
+    now: i64,
+) -> Result<OidcPrincipal, OidcError> {
+    let header = token_header(token)?;
+    let mut parts = token.splitn(3, '.');
+    let (header_b64, payload_b64, sig_b64) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(h), Some(p), Some(s)) => (h, p, s),
+        _ => return Err(invalid("expected three segments")),
+    };
+    let key = select_key(keys, &header)?.ok_or_else(|| invalid("no matching signing key"))?;
+    let message = format!("{}.{}", header_b64, payload_b64);
+    key.verify(&header.alg, message.as_bytes(), &decode_segment(sig_b64)?)?;
+
+    let claims: Value = serde_json::from_slice(&decode_segment(payload_b64)?)
+        .map_err(|_| invalid("malformed claims"))?;
+    let time = |name: &str| claims.get(name).and_then(Value::as_i64);
+    match time("exp") {
+        Some(exp) if exp + config.leeway_secs < now => return Err(invalid("token has expired")),
+        Some(_) => {}
+        None => return Err(invalid("token has no expiry")),
+    }
+    if time("nbf").is_some_and(|nbf| nbf - config.leeway_secs > now) {
+        return Err(invalid("token is not yet valid"));
+    }
+    let issuer = claims.get("iss").and_then(Value::as_str).unwrap_or("");
+    if issuer.trim_end_matches('/') != config.issuer {
+        return Err(invalid(format!("unexpected issuer '{}'", issuer)));
+    }
+    if !claim_values(&claims, "aud").contains(&config.audience) {
+        return Err(invalid("token is not meant for this audience"));
+    }
+
+    let user = claim_values(&claims, &config.user_claim)
+        .into_iter()
+        .next()
+        .ok_or_else(|| invalid(format!("token has no '{}' claim", config.user_claim)))?;
+    let mut tenants = claim_values(&claims, &config.tenant_claim);
+    if tenants.is_empty() {
+        tenants.extend(config.default_tenant.clone());
+    }
+    Ok(OidcPrincipal {
+        user,
+        tenants,
+        role: config.role(&claim_values(&claims, &config.role_claim)),
+        groups: claim_values(&claims, &config.groups_claim),
+    })
+}
+
+fn token_header(token: &str) -> Result<JwtHeader, OidcError> {
+    let segment = token.split('.').next().unwrap_or("");
+    let header: JwtHeader = serde_json::from_slice(&decode_segment(segment)?)
+        .map_err(|_| invalid("malformed header"))?;
+    if !matches!(
+        header.alg.as_str(),
+        "RS256" | "RS384" | "RS512" | "ES256" | "ES384"
+    ) {
+        return Err(invalid(format!("unsupported algorithm '{}'", header.alg)));
+    }
+    Ok(header)
+}
+
+/// The key for a token: the one with its `kid`, or without a `kid` the only
+/// key of the right type. `Ok(None)` when the key set does not have it.
+fn select_key<'a>(keys: &'a [Jwk], header: &JwtHeader) -> Result<Option<&'a Jwk>, OidcError> {
+    let mut candidates = keys.iter().filter(|k| k.matches(&header.alg));
+    match &header.kid {
+        Some(kid) => Ok(candidates.find(|k| k.kid.as_deref() == Some(kid.as_str()))),
+        None => {
+            let first = candidates.next();
+            if candidates.next().is_some() {
+                return Err(invalid("token has no kid and the issuer has several keys"));
+            }
+            Ok(first)
+        }
+    }
+}
+
+#[derive(Default)]
+struct JwksState {
+    keys: Vec<Jwk>,
+    /// Last successful fetch
+    fetched_at: Option<Instant>,
+    /// Last fetch attempt, successful or not
+    attempted_at: Option<Instant>,
+    /// JWKS URL, once configured or discovered
+    jwks_url: Option<String>,
+}
+
+/// Verifies tokens from the configured issuer, caching its JWKS.
+pub struct OidcAuthenticator {
+    config: OidcConfig,
+    client: reqwest::Client,
+    state: RwLock<JwksState>,
+    /// Held while fetching keys, so only one request fetches at a time
+    refresh: Mutex<()>,
+}
+
+impl OidcAuthenticator {
+    pub fn new(config: OidcConfig) -> Self {
+        let state = JwksState {
+            jwks_url: config.jwks_url.clone(),
+            ..Default::default()
+        };
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            config,
+            state: RwLock::new(state),
+            refresh: Mutex::new(()),
+        }
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Verify a token, fetching the issuer's keys when needed.
+    ///
+    /// If the issuer cannot be reached, the last fetched keys keep being used.
+    /// Keys are fetched without holding the key cache lock, so requests with
+    /// a cached key are not held up by a slow issuer.
+    pub async fn authenticate(&self, token: &str) -> Result<OidcPrincipal, OidcError> {
+        let header = token_header(token)?;
+        let ttl = Duration::from_secs(self.config.jwks_ttl_secs);
+        let min_refetch = Duration::from_secs(MIN_REFETCH_SECS).min(ttl);
+        {
+            let state = self.state.read().await;
+            let fresh = state.fetched_at.is_some_and(|at| at.elapsed() < ttl);
+            let attempted = state
+                .attempted_at
+                .is_some_and(|at| at.elapsed() < min_refetch);
+            if (fresh || attempted) && select_key(&state.keys, &header)?.is_some() {
+                return verify_token(token, &state.keys, &self.config, now());
+            }
+        }
+
+        let _refresh = self.refresh.lock().await;
+        // Another request may have fetched the keys meanwhile
+        let jwks_url = {
+            let mut state = self.state.write().await;
+            if state
+                .attempted_at
+                .is_none_or(|at| at.elapsed() >= min_refetch)
+            {
+                state.attempted_at = Some(Instant::now());
+                Some(state.jwks_url.clone())
+            } else {
+                None
+            }
+        };
+        if let Some(jwks_url) = jwks_url {
+            let fetched = self.fetch_keys(jwks_url).await;
+            let mut state = self.state.write().await;
+            match fetched {
+                Ok((url, keys)) => {
+                    debug!(keys = keys.len(), "Fetched OIDC signing keys");
+                    state.keys = keys;
+                    state.fetched_at = Some(Instant::now());
+                    state.jwks_url = Some(url);
+                }
+                Err(e) if state.keys.is_empty() => return Err(e),
+                Err(e) => {
+                    warn!(error = %e, "Failed to refresh OIDC signing keys, using cached keys")
+                }
+            }
+        }
+        let state = self.state.read().await;
+        if state.keys.is_empty() {
+            return Err(OidcError::Unavailable(
+                "no signing keys fetched yet".to_string(),
+            ));
+        }
+        verify_token(token, &state.keys, &self.config, now())
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, OidcError> {
+        let unavailable = |e: reqwest::Error| OidcError::Unavailable(format!("{}: {}", url, e));
+        self.client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(unavailable)?
+            .json()
+            .await
+            .map_err(unavailable)
+    }
+
+    /// Fetch the JWKS, discovering its URL first if not known yet.
+    async fn fetch_keys(&self, url: Option<String>) -> Result<(String, Vec<Jwk>), OidcError> {
+        let url = match url {
+            Some(url) => url,
+            None => {
+                let discovery = format!("{}/.well-known/openid-configuration", self.config.issuer);
+                self.get_json(&discovery)
+                    .await?
+                    .get("jwks_uri")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| {
+                        OidcError::Unavailable(format!("{} has no jwks_uri", discovery))
+                    })?
+            }
+        };
+        let set: JwkSet = serde_json::from_value(self.get_json(&url).await?)
+            .map_err(|e| OidcError::Unavailable(format!("{}: malformed JWKS: {}", url, e)))?;
+        Ok((url, set.keys))
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING};
+
+    const ISSUER: &str = "https://login.example.com";
+    const NOW: i64 = 1_700_000_000;
+
+    struct Signer {
+        key_pair: EcdsaKeyPair,
+        rng: SystemRandom,
+    }
+
+    impl Signer {
+        fn new() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+            let key_pair =
+                EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng)
+                    .unwrap();
+            Self { key_pair, rng }
+        }
+
+        fn jwk(&self) -> Jwk {
+            let point = self.key_pair.public_key().as_ref();
+            Jwk {
+                kty: "EC".to_string(),
+                kid: Some("k1".to_string()),
+                alg: Some("ES256".to_string()),
+                key_use: Some("sig".to_string()),
+                n: None,
+                e: None,
+                crv: Some("P-256".to_string()),
+                x: Some(URL_SAFE_NO_PAD.encode(&point[1..33])),
+                y: Some(URL_SAFE_NO_PAD.encode(&point[33..])),
+            }
+        }
+
+        fn token(&self, alg: &str, claims: Value) -> String {
+            let header = serde_json::json!({ "alg": alg, "kid": "k1", "typ": "JWT" });
+            let message = format!(
+                "{}.{}",
+                URL_SAFE_NO_PAD.encode(header.to_string()),
+                URL_SAFE_NO_PAD.encode(claims.to_string())
+            );
+            let sig = self.key_pair.sign(&self.rng, message.as_bytes()).unwrap();
+            format!("{}.{}", message, URL_SAFE_NO_PAD.encode(sig.as_ref()))
+        }
+    }
+
+    fn config() -> OidcConfig {
+        let mut config = OidcConfig::new(ISSUER, "metafuse");
+        config.tenant_claim = "metafuse.tenants".to_string();
+        config.role_map = parse_role_map("admin=catalog-admins;editor=data-eng").unwrap();
+        config
+    }
+
+    fn claims() -> Value {
+        serde_json::json!({
+            "iss": ISSUER,
+            "aud": ["metafuse", "other"],
+            "sub": "alice",
+            "exp": NOW + 300,
+            "metafuse": { "tenants": ["acme", "globex"] },
+            "roles": ["data-eng", "everyone"],
+            "groups": ["analytics"],
+        })
+    }
+
+    #[test]
+    fn test_verify_token_maps_claims() {
+        let signer = Signer::new();
+        let token = signer.token("ES256", claims());
+        assert!(looks_like_jwt(&token));
+        assert!(!looks_like_jwt("mft_abc123"));
+
+        let principal = verify_token(&token, &[signer.jwk()], &config(), NOW).unwrap();
+        assert_eq!(principal.user, "alice");
+        assert_eq!(principal.tenants, vec!["acme", "globex"]);
+        assert_eq!(principal.role, TenantRole::Editor);
+        assert_eq!(principal.groups, vec!["analytics"]);
+
+        assert_eq!(principal.select_tenant(Some("globex")), Ok("globex"));
+        assert!(principal.select_tenant(Some("initech")).is_err());
+        assert!(principal.select_tenant(None).is_err());
+
+        // Without the tenant claim the default tenant is used
+        let mut config = config();
+        config.tenant_claim = "tenant_id".to_string();
+        config.default_tenant = Some("acme".to_string());
+        let principal = verify_token(&token, &[signer.jwk()], &config, NOW).unwrap();
+        assert_eq!(principal.select_tenant(None), Ok("acme"));
+    }
+
+    #[test]
+    fn test_verify_token_rejects_invalid_tokens() {
+        let signer = Signer::new();
+        let keys = [signer.jwk()];
+        let check =
+            |claims: Value| verify_token(&signer.token("ES256", claims), &keys, &config(), NOW);
+
+        let mut expired = claims();
+        expired["exp"] = (NOW - 3600).into();
+        assert!(matches!(check(expired), Err(OidcError::Invalid(_))));
+
+        let mut wrong_audience = claims();
+        wrong_audience["aud"] = "someone-else".into();
+        assert!(check(wrong_audience).is_err());
+
+        let mut wrong_issuer = claims();
+        wrong_issuer["iss"] = "https://evil.example.com".into();
+        assert!(check(wrong_issuer).is_err());
+
+        // Claims changed after signing
+        let token = signer.token("ES256", claims());
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let mut forged = claims();
+        forged["roles"] = serde_json::json!(["catalog-admins"]);
+        let forged = URL_SAFE_NO_PAD.encode(forged.to_string());
+        parts[1] = &forged;
+        assert!(verify_token(&parts.join("."), &keys, &config(), NOW).is_err());
+
+        // Unsigned and shared-secret tokens are never accepted
+        assert!(verify_token(&signer.token("none", claims()), &keys, &config(), NOW).is_err());
+        assert!(verify_token(&signer.token("HS256", claims()), &keys, &config(), NOW).is_err());
+
+        // Another issuer key with the same kid
+        let other = Signer::new();
+        assert!(verify_token(&other.token("ES256", claims()), &keys, &config(), NOW).is_err());
+    }
+
+    #[test]
+    fn test_role_mapping() {
+        let config = config();
+        let values = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(config.role(&values(&[])), TenantRole::Viewer);
+        assert_eq!(config.role(&values(&["everyone"])), TenantRole::Viewer);
+        assert_eq!(config.role(&values(&["data-eng"])), TenantRole::Editor);
+        assert_eq!(
+            config.role(&values(&["data-eng", "catalog-admins"])),
+            TenantRole::Admin
+        );
+        // With a role map, role names are not mapped to themselves
+        assert_eq!(config.role(&values(&["admin"])), TenantRole::Viewer);
+
+        let mut config = config;
+        config.role_map.clear();
+        assert_eq!(config.role(&values(&["editor"])), TenantRole::Editor);
+        assert_eq!(
+            config.role(&values(&["viewer", "admin"])),
+            TenantRole::Admin
+        );
+        assert!(parse_role_map("owner=platform").is_err());
+        assert!(parse_role_map("admin").is_err());
+    }
+
+    #[test]
+    fn test_config_from_env_fails_closed() {
+        std::env::remove_var("METAFUSE_OIDC_ISSUER");
+        std::env::remove_var("METAFUSE_OIDC_AUDIENCE");
+        std::env::remove_var("METAFUSE_OIDC_ROLE_MAP");
+        assert!(OidcConfig::from_env().unwrap().is_none());
+
+        std::env::set_var("METAFUSE_OIDC_ISSUER", ISSUER);
+        let error = OidcConfig::from_env().unwrap_err();
+        assert!(error.contains("METAFUSE_OIDC_AUDIENCE"), "{}", error);
+
+        std::env::set_var("METAFUSE_OIDC_AUDIENCE", "metafuse");
+        std::env::set_var("METAFUSE_OIDC_ROLE_MAP", "owner=platform");
+        let error = OidcConfig::from_env().unwrap_err();
+        assert!(error.contains("METAFUSE_OIDC_ROLE_MAP"), "{}", error);
+
+        std::env::set_var("METAFUSE_OIDC_ROLE_MAP", "admin=catalog-admins");
+        let config = OidcConfig::from_env().unwrap().unwrap();
+        assert_eq!(config.audience, "metafuse");
+        assert_eq!(config.role_map["catalog-admins"], TenantRole::Admin);
+
+        std::env::remove_var("METAFUSE_OIDC_ISSUER");
+        std::env::remove_var("METAFUSE_OIDC_AUDIENCE");
+        std::env::remove_var("METAFUSE_OIDC_ROLE_MAP");
+    }
+}
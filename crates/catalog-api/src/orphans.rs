@@ -0,0 +1,283 @@
+//! Orphaned Dataset Detection
+//!
+//! A dataset is orphaned when its pipeline has stopped producing it while
+//! something still consumes it: it hasn't been emitted in N days but has
+//! downstream datasets or downstream external nodes in its lineage.
+//!
+//! The last emission is the emitter heartbeat (`last_seen_at`, migration
+//! v1.23.0), falling back to `last_updated` for datasets without one.
+//! Placeholders, archived and trashed datasets are never orphaned, and
+//! archived or trashed downstream datasets don't count as consumers.
+//!
+//! Datasets hidden from the caller by dataset ACLs are neither listed nor
+//! counted as consumers.
+
+use crate::archive;
+use crate::dataset_acl::{self, Identity};
+use crate::freshness::parse_timestamp;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use metafuse_catalog_core::{emission_state, external_nodes, placeholders, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// A dataset with consumers that hasn't been emitted recently
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OrphanedDataset {
+    pub dataset_id: i64,
+    pub dataset_name: String,
+    /// Last emission, or `last_updated` when no heartbeat is recorded
+    pub last_seen_at: String,
+    pub days_since_seen: i64,
+    /// Downstream datasets, by name
+    pub downstream_datasets: Vec<String>,
+    /// Downstream external nodes, by URI
+    pub downstream_external: Vec<String>,
+}
+
+/// Response for the orphaned datasets endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedDatasetsResponse {
+    pub threshold_days: i64,
+    pub datasets: Vec<OrphanedDataset>,
+}
+
+/// Datasets with consumers not emitted within `threshold_days`, oldest first,
+/// as seen by `identity`.
+pub fn find_orphaned(
+    conn: &Connection,
+    threshold_days: i64,
+    now: DateTime<Utc>,
+    identity: &Identity,
+) -> Result<Vec<OrphanedDataset>> {
+    let cutoff = now - chrono::Duration::days(threshold_days);
+    let last_seen = if emission_state::has_state_table(conn)? {
+        "(SELECT last_seen_at FROM dataset_emission_state s WHERE s.dataset_id = d.id)"
+    } else {
+        "NULL"
+    };
+    let not_placeholder = if placeholders::has_status_column(conn)? {
+        format!("AND d.status != '{}'", placeholders::STATUS_PENDING)
+    } else {
+        String::new()
+    };
+
+    let (visible, bindings) = match dataset_acl::visibility_clause(identity, "d.id", "d.domain") {
+        Some((clause, values)) => (format!("AND {}", clause), values),
+        None => (String::new(), Vec::new()),
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT d.id, d.name, COALESCE({}, d.last_updated)
+         FROM datasets d
+         WHERE d.deleted_at IS NULL AND {} {} {}
+         ORDER BY d.id",
+        last_seen,
+        archive::visibility_clause("d"),
+        not_placeholder,
+        visible
+    ))?;
+    let candidates = stmt
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let (visible, bindings) = match dataset_acl::visibility_clause(identity, "dd.id", "dd.domain") {
+        Some((clause, values)) => (format!("AND {}", clause), values),
+        None => (String::new(), Vec::new()),
+    };
+    let mut downstream_stmt = conn.prepare(&format!(
+        "SELECT dd.name FROM lineage l
+         JOIN datasets dd ON dd.id = l.downstream_dataset_id
+         WHERE l.upstream_dataset_id = ?1 AND dd.deleted_at IS NULL AND {} {}
+         ORDER BY dd.name",
+        archive::visibility_clause("dd"),
+        visible
+    ))?;
+    let mut orphaned = Vec::new();
+    for (dataset_id, dataset_name, last_seen_at) in candidates {
+        // Unparseable timestamps can't be judged either way
+        let Some(seen) = parse_timestamp(&last_seen_at) else {
+            continue;
+        };
+        if seen >= cutoff {
+            continue;
+        }
+
+        let params: Vec<&dyn rusqlite::ToSql> =
+            std::iter::once(&dataset_id as &dyn rusqlite::ToSql)
+                .chain(bindings.iter().map(|b| b as &dyn rusqlite::ToSql))
+                .collect();
+        let downstream_datasets = downstream_stmt
+            .query_map(params.as_slice(), |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        let downstream_external: Vec<String> = external_nodes::for_dataset(conn, dataset_id)?
+            .into_iter()
+            .filter(|(_, direction)| *direction == external_nodes::ExternalDirection::Downstream)
+            .map(|(node, _)| node.uri)
+            .collect();
+        if downstream_datasets.is_empty() && downstream_external.is_empty() {
+            continue;
+        }
+
+        orphaned.push(OrphanedDataset {
+            dataset_id,
+            dataset_name,
+            last_seen_at,
+            days_since_seen: (now - seen).num_days(),
+            downstream_datasets,
+            downstream_external,
+        });
+    }
+    orphaned.sort_by_key(|o| std::cmp::Reverse(o.days_since_seen));
+    Ok(orphaned)
+}
+
+/// Query parameters for orphaned datasets endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct OrphanedQueryParams {
+    #[serde(default = "default_orphaned_threshold")]
+    threshold_days: i64,
+}
+
+pub(crate) fn default_orphaned_threshold() -> i64 {
+    7
+}
+
+/// Get datasets with consumers that haven't been emitted recently
+pub(crate) async fn get_orphaned_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<Identity>>,
+    Query(params): Query<OrphanedQueryParams>,
+) -> std::result::Result<Json<OrphanedDatasetsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if params.threshold_days < 1 {
+        return Err(bad_request(
+            "threshold_days must be at least 1".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let req_id = request_id.0.clone();
+    let threshold_days = params.threshold_days;
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let datasets = tokio::task::spawn_blocking(move || {
+        find_orphaned(&conn, threshold_days, chrono::Utc::now(), &identity)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(count = datasets.len(), "Orphaned datasets query completed");
+
+    Ok(Json(OrphanedDatasetsResponse {
+        threshold_days,
+        datasets,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_orphaned() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        let now = DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // raw feeds mart; both were last updated a month ago
+        for name in ["raw", "mart", "unused"] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', '2025-10-01 00:00:00', '2025-10-20 12:00:00')",
+                [name],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (1, 2, '2025-10-01 00:00:00')",
+            [],
+        )
+        .unwrap();
+
+        let orphaned = find_orphaned(&conn, 7, now, &Identity::default()).unwrap();
+        assert_eq!(orphaned.len(), 1);
+        assert_eq!(orphaned[0].dataset_name, "raw");
+        assert_eq!(orphaned[0].days_since_seen, 31);
+        assert_eq!(orphaned[0].downstream_datasets, vec!["mart"]);
+
+        // A recent heartbeat clears it, even though last_updated is old
+        emission_state::record_write(&conn, 1, "hash", now - chrono::Duration::days(1)).unwrap();
+        assert!(find_orphaned(&conn, 7, now, &Identity::default())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            find_orphaned(&conn, 0, now, &Identity::default())
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_find_orphaned_skips_archived() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        let now = DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // retired feeds mart, and legacy feeds only the archived report
+        for name in ["retired", "mart", "legacy", "report"] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', '2025-10-01 00:00:00', '2025-10-20 12:00:00')",
+                [name],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (1, 2, '2025-10-01 00:00:00'), (3, 4, '2025-10-01 00:00:00')",
+            [],
+        )
+        .unwrap();
+        let names: Vec<String> = find_orphaned(&conn, 7, now, &Identity::default())
+            .unwrap()
+            .into_iter()
+            .map(|o| o.dataset_name)
+            .collect();
+        assert_eq!(names, vec!["retired", "legacy"]);
+
+        conn.execute(
+            "UPDATE datasets SET archived_at = '2025-11-01 00:00:00', status = 'archived'
+             WHERE name IN ('retired', 'report')",
+            [],
+        )
+        .unwrap();
+        assert!(find_orphaned(&conn, 7, now, &Identity::default())
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+//! Owners
+//!
+//! The owner registry: teams and people datasets and domains can be
+//! assigned to, with their contact details.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::datasets::PaginationParams;
+use crate::envelope;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::{require_delete_permission, require_write_permission};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+/// Request to create a new owner
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateOwnerRequest {
+    owner_id: String,
+    name: String,
+    owner_type: Option<String>,
+    email: Option<String>,
+    slack_channel: Option<String>,
+    contact_info: Option<serde_json::Value>,
+}
+
+/// Request to update an existing owner
+#[derive(Debug, Deserialize)]
+pub(crate) struct UpdateOwnerRequest {
+    name: Option<String>,
+    owner_type: Option<String>,
+    email: Option<String>,
+    slack_channel: Option<String>,
+    contact_info: Option<serde_json::Value>,
+}
+
+/// Owner response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct OwnerResponse {
+    id: i64,
+    owner_id: String,
+    name: String,
+    owner_type: String,
+    email: Option<String>,
+    slack_channel: Option<String>,
+    contact_info: Option<serde_json::Value>,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Create a new owner
+pub(crate) async fn create_owner(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<CreateOwnerRequest>,
+) -> Result<(StatusCode, Json<OwnerResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, owner_id = %req.owner_id, "Creating owner");
+
+    state
+        .users
+        .check_owner(&req.owner_id)
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let owner_type = req.owner_type.as_deref().unwrap_or("user");
+    let contact_info_json = req.contact_info.as_ref().map(|v| v.to_string());
+
+    conn.execute(
+        r#"
+        INSERT INTO owners (owner_id, name, owner_type, email, slack_channel, contact_info, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now'), datetime('now'))
+        "#,
+        rusqlite::params![
+            req.owner_id,
+            req.name,
+            owner_type,
+            req.email,
+            req.slack_channel,
+            contact_info_json,
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            bad_request(
+                format!("Owner '{}' already exists", req.owner_id),
+                request_id.0.clone(),
+            )
+        } else {
+            internal_error(e.to_string(), request_id.0.clone())
+        }
+    })?;
+
+    let id = conn.last_insert_rowid();
+
+    let owner = OwnerResponse {
+        id,
+        owner_id: req.owner_id,
+        name: req.name,
+        owner_type: owner_type.to_string(),
+        email: req.email,
+        slack_channel: req.slack_channel,
+        contact_info: req.contact_info,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    tracing::info!(owner_id = %owner.owner_id, "Owner created successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "owner",
+            &owner.owner_id,
+            serde_json::json!({
+                "id": owner.id,
+                "owner_id": owner.owner_id,
+                "name": owner.name,
+                "owner_type": owner.owner_type,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(owner)))
+}
+
+/// List all owners
+pub(crate) async fn list_owners(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Query(pagination): Query<PaginationParams>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<OwnerResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        limit = pagination.limit(),
+        offset = pagination.offset(),
+        "Listing owners"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, owner_id, name, owner_type, email, slack_channel, contact_info, created_at, updated_at
+            FROM owners ORDER BY name LIMIT ?1 OFFSET ?2
+            "#,
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let owners = stmt
+        .query_map(
+            rusqlite::params![pagination.limit() as i64, pagination.offset() as i64],
+            |row| {
+                let contact_info: Option<String> = row.get(6)?;
+                Ok(OwnerResponse {
+                    id: row.get(0)?,
+                    owner_id: row.get(1)?,
+                    name: row.get(2)?,
+                    owner_type: row.get(3)?,
+                    email: row.get(4)?,
+                    slack_channel: row.get(5)?,
+                    contact_info: contact_info.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(count = owners.len(), "Listed owners successfully");
+
+    let total = envelope
+        .total(|| conn.query_row("SELECT COUNT(*) FROM owners", [], |row| row.get(0)))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(envelope.window(owners, total)))
+}
+
+/// Get an owner by ID
+pub(crate) async fn get_owner(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    Path(id): Path<String>,
+) -> Result<Json<OwnerResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, owner_id = %id, "Getting owner");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let owner = conn
+        .query_row(
+            r#"
+            SELECT id, owner_id, name, owner_type, email, slack_channel, contact_info, created_at, updated_at
+            FROM owners WHERE owner_id = ?1
+            "#,
+            [&id],
+            |row| {
+                let contact_info: Option<String> = row.get(6)?;
+                Ok(OwnerResponse {
+                    id: row.get(0)?,
+                    owner_id: row.get(1)?,
+                    name: row.get(2)?,
+                    owner_type: row.get(3)?,
+                    email: row.get(4)?,
+                    slack_channel: row.get(5)?,
+                    contact_info: contact_info.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|_| not_found(format!("Owner '{}' not found", id), request_id.0.clone()))?;
+
+    Ok(Json(owner))
+}
+
+/// Update an owner
+pub(crate) async fn update_owner(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<String>,
+    JsonBody(req): JsonBody<UpdateOwnerRequest>,
+) -> Result<Json<OwnerResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, owner_id = %id, "Updating owner");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Build dynamic update query
+    let mut updates = vec!["updated_at = datetime('now')".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if let Some(name) = &req.name {
+        updates.push(format!("name = ?{}", params.len() + 1));
+        params.push(Box::new(name.clone()));
+    }
+    if let Some(owner_type) = &req.owner_type {
+        updates.push(format!("owner_type = ?{}", params.len() + 1));
+        params.push(Box::new(owner_type.clone()));
+    }
+    if let Some(email) = &req.email {
+        updates.push(format!("email = ?{}", params.len() + 1));
+        params.push(Box::new(email.clone()));
+    }
+    if let Some(slack_channel) = &req.slack_channel {
+        updates.push(format!("slack_channel = ?{}", params.len() + 1));
+        params.push(Box::new(slack_channel.clone()));
+    }
+    if let Some(contact_info) = &req.contact_info {
+        updates.push(format!("contact_info = ?{}", params.len() + 1));
+        params.push(Box::new(contact_info.to_string()));
+    }
+
+    let sql = format!(
+        "UPDATE owners SET {} WHERE owner_id = ?{}",
+        updates.join(", "),
+        params.len() + 1
+    );
+    params.push(Box::new(id.clone()));
+
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = conn
+        .execute(&sql, params_refs.as_slice())
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows == 0 {
+        return Err(not_found(
+            format!("Owner '{}' not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    // Fetch updated owner
+    let owner = conn
+        .query_row(
+            r#"
+            SELECT id, owner_id, name, owner_type, email, slack_channel, contact_info, created_at, updated_at
+            FROM owners WHERE owner_id = ?1
+            "#,
+            [&id],
+            |row| {
+                let contact_info: Option<String> = row.get(6)?;
+                Ok(OwnerResponse {
+                    id: row.get(0)?,
+                    owner_id: row.get(1)?,
+                    name: row.get(2)?,
+                    owner_type: row.get(3)?,
+                    email: row.get(4)?,
+                    slack_channel: row.get(5)?,
+                    contact_info: contact_info.and_then(|s| serde_json::from_str(&s).ok()),
+                    created_at: row.get(7)?,
+                    updated_at: row.get(8)?,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(owner_id = %id, "Owner updated successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "owner",
+            &id,
+            serde_json::json!({}), // old values not tracked for simplicity
+            serde_json::json!({
+                "id": owner.id,
+                "owner_id": owner.owner_id,
+                "name": owner.name,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(owner))
+}
+
+/// Delete an owner
+pub(crate) async fn delete_owner(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Check delete permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, owner_id = %id, "Deleting owner");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let rows = conn
+        .execute("DELETE FROM owners WHERE owner_id = ?1", [&id])
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    if rows == 0 {
+        return Err(not_found(
+            format!("Owner '{}' not found", id),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(owner_id = %id, "Owner deleted successfully");
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "owner",
+            &id,
+            serde_json::json!({ "owner_id": id }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,288 @@
+// Allow dead_code for public API items that are used by library consumers
+// but not within the crate itself
+#![allow(dead_code)]
+
+//! Public Read-Only Catalog Mode
+//!
+//! Lets anonymous clients browse a multi-tenant catalog without an API key.
+//! When enabled, a request that carries only an `X-Tenant-ID` header is resolved
+//! as a read-only viewer, but only for the discovery endpoints:
+//!
+//! - `GET /api/v1/datasets`
+//! - `GET /api/v1/datasets/:name`
+//! - `GET /api/v1/search`
+//!
+//! Everything else (including all writes) still requires a tenant API key.
+//!
+//! # Restrictions
+//!
+//! - Tenants listed in `METAFUSE_PUBLIC_CATALOG_EXCLUDED_TENANTS` are never exposed.
+//! - Datasets carrying any tag in `METAFUSE_PUBLIC_CATALOG_EXCLUDED_TAGS` are hidden
+//!   from list/search results and return 404 on direct lookup.
+//! - Storage locations, owners and custom metadata are redacted from anonymous
+//!   responses.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_PUBLIC_CATALOG`: "true" to enable (default: false)
+//! - `METAFUSE_PUBLIC_CATALOG_EXCLUDED_TENANTS`: comma-separated tenant IDs
+//! - `METAFUSE_PUBLIC_CATALOG_EXCLUDED_TAGS`: comma-separated tags
+//!   (default: `restricted,confidential,pii`)
+
+use axum::http::Method;
+use std::collections::BTreeSet;
+
+/// Placeholder used for redacted string fields in anonymous responses.
+pub const REDACTED: &str = "[redacted]";
+
+/// Tags hidden from the public catalog when no override is configured.
+pub const DEFAULT_EXCLUDED_TAGS: &[&str] = &["restricted", "confidential", "pii"];
+
+/// Configuration for public read-only catalog mode.
+#[derive(Debug, Clone)]
+pub struct PublicCatalogConfig {
+    /// Allow anonymous read access to discovery endpoints
+    pub enabled: bool,
+    /// Tenants that are never exposed anonymously
+    pub excluded_tenants: BTreeSet<String>,
+    /// Datasets with any of these tags are hidden (compared case-insensitively)
+    pub excluded_tags: BTreeSet<String>,
+}
+
+impl Default for PublicCatalogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Secure default
+            excluded_tenants: BTreeSet::new(),
+            excluded_tags: DEFAULT_EXCLUDED_TAGS
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl PublicCatalogConfig {
+    /// Create an enabled config with the default tag exclusions.
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_PUBLIC_CATALOG`: "true" to enable
+    /// - `METAFUSE_PUBLIC_CATALOG_EXCLUDED_TENANTS`: comma-separated tenant IDs
+    /// - `METAFUSE_PUBLIC_CATALOG_EXCLUDED_TAGS`: comma-separated tags
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("METAFUSE_PUBLIC_CATALOG")
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(false);
+
+        let excluded_tenants = std::env::var("METAFUSE_PUBLIC_CATALOG_EXCLUDED_TENANTS")
+            .map(|v| parse_list(&v))
+            .unwrap_or_default();
+
+        let excluded_tags = std::env::var("METAFUSE_PUBLIC_CATALOG_EXCLUDED_TAGS")
+            .map(|v| parse_list(&v))
+            .unwrap_or_else(|_| PublicCatalogConfig::default().excluded_tags);
+
+        Self {
+            enabled,
+            excluded_tenants,
+            excluded_tags,
+        }
+    }
+
+    /// Check whether a tenant may be browsed anonymously.
+    pub fn allows_tenant(&self, tenant_id: &str) -> bool {
+        self.enabled && !self.excluded_tenants.contains(tenant_id)
+    }
+
+    /// Build the access marker attached to anonymous requests.
+    pub fn access(&self) -> PublicAccess {
+        PublicAccess {
+            excluded_tags: self.excluded_tags.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Parse a comma-separated list into a lowercase set, skipping blanks.
+fn parse_list(value: &str) -> BTreeSet<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Check whether a request targets a route served in public read-only mode.
+pub fn is_public_read_route(method: &Method, path: &str) -> bool {
+    if *method != Method::GET {
+        return false;
+    }
+
+    let path = path.trim_end_matches('/');
+    if path == "/api/v1/datasets" || path == "/api/v1/search" {
+        return true;
+    }
+
+    // GET /api/v1/datasets/:name (no sub-resources)
+    path.strip_prefix("/api/v1/datasets/")
+        .map(|name| !name.is_empty() && !name.contains('/'))
+        .unwrap_or(false)
+}
+
+/// Marker attached to request extensions for anonymous public-catalog requests.
+///
+/// Handlers use it to hide restricted datasets and redact sensitive fields.
+#[derive(Debug, Clone, Default)]
+pub struct PublicAccess {
+    excluded_tags: Vec<String>,
+}
+
+impl PublicAccess {
+    /// Tags that hide a dataset from anonymous clients (lowercase).
+    pub fn excluded_tags(&self) -> &[String] {
+        &self.excluded_tags
+    }
+
+    /// Check whether a dataset with these tags must be hidden.
+    pub fn is_restricted(&self, tags: &[String]) -> bool {
+        tags.iter()
+            .any(|tag| self.excluded_tags.contains(&tag.to_lowercase()))
+    }
+
+    /// SQL condition excluding datasets with restricted tags.
+    ///
+    /// Returns `None` if no tags are excluded. Otherwise the condition uses one
+    /// `?` placeholder per entry in [`excluded_tags`](Self::excluded_tags), which
+    /// must be bound in order.
+    pub fn exclusion_clause(&self, id_column: &str) -> Option<String> {
+        if self.excluded_tags.is_empty() {
+            return None;
+        }
+        let placeholders = vec!["?"; self.excluded_tags.len()].join(", ");
+        Some(format!(
+            "{} NOT IN (SELECT dataset_id FROM tags WHERE lower(tag) IN ({}))",
+            id_column, placeholders
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_disabled() {
+        let config = PublicCatalogConfig::default();
+        assert!(!config.enabled);
+        assert!(!config.allows_tenant("acme-corp"));
+        assert!(config.excluded_tags.contains("restricted"));
+    }
+
+    #[test]
+    fn test_allows_tenant_respects_exclusions() {
+        let mut config = PublicCatalogConfig::enabled();
+        config.excluded_tenants.insert("secret-corp".to_string());
+
+        assert!(config.allows_tenant("acme-corp"));
+        assert!(!config.allows_tenant("secret-corp"));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let parsed = parse_list(" PII, restricted ,,internal ");
+        assert_eq!(parsed.len(), 3);
+        assert!(parsed.contains("pii"));
+        assert!(parsed.contains("restricted"));
+        assert!(parsed.contains("internal"));
+    }
+
+    #[test]
+    fn test_is_public_read_route() {
+        assert!(is_public_read_route(&Method::GET, "/api/v1/datasets"));
+        assert!(is_public_read_route(&Method::GET, "/api/v1/datasets/"));
+        assert!(is_public_read_route(
+            &Method::GET,
+            "/api/v1/datasets/orders"
+        ));
+        assert!(is_public_read_route(&Method::GET, "/api/v1/search"));
+
+        assert!(!is_public_read_route(&Method::POST, "/api/v1/datasets"));
+        assert!(!is_public_read_route(
+            &Method::DELETE,
+            "/api/v1/datasets/orders"
+        ));
+        assert!(!is_public_read_route(
+            &Method::GET,
+            "/api/v1/datasets/orders/schema"
+        ));
+        assert!(!is_public_read_route(&Method::GET, "/api/v1/audit"));
+        assert!(!is_public_read_route(&Method::GET, "/api/v1/usage"));
+    }
+
+    #[test]
+    fn test_is_restricted_case_insensitive() {
+        let access = PublicCatalogConfig::enabled().access();
+        assert!(access.is_restricted(&["PII".to_string()]));
+        assert!(access.is_restricted(&["sales".to_string(), "restricted".to_string()]));
+        assert!(!access.is_restricted(&["sales".to_string()]));
+        assert!(!access.is_restricted(&[]));
+    }
+
+    #[test]
+    fn test_exclusion_clause() {
+        let access = PublicCatalogConfig::enabled().access();
+        let clause = access.exclusion_clause("d.id").unwrap();
+        assert_eq!(
+            clause,
+            "d.id NOT IN (SELECT dataset_id FROM tags WHERE lower(tag) IN (?, ?, ?))"
+        );
+
+        let empty = PublicAccess::default();
+        assert!(empty.exclusion_clause("id").is_none());
+    }
+
+    #[test]
+    fn test_exclusion_clause_filters_rows() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+
+        for name in ["public_orders", "secret_customers"] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'delta', datetime('now'), datetime('now'))",
+                [name],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO tags (dataset_id, tag)
+             SELECT id, 'PII' FROM datasets WHERE name = 'secret_customers'",
+            [],
+        )
+        .unwrap();
+
+        let access = PublicCatalogConfig::enabled().access();
+        let sql = format!(
+            "SELECT name FROM datasets WHERE {}",
+            access.exclusion_clause("id").unwrap()
+        );
+        let mut stmt = conn.prepare(&sql).unwrap();
+        let names: Vec<String> = stmt
+            .query_map(
+                rusqlite::params_from_iter(access.excluded_tags().iter()),
+                |row| row.get(0),
+            )
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(names, vec!["public_orders".to_string()]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Public Dataset Identifiers
+//!
+//! Datasets carry a stable UUID (migration v1.21.0) alongside the internal
+//! integer id. Dataset routes accept either the name or the UUID.
+//!
+//! Responses name datasets by UUID: dataset objects carry `uuid` next to
+//! `id`, and every integer dataset reference in a JSON response gets a UUID
+//! next to it (`dataset_id` -> `dataset_uuid`, `source_dataset_id` ->
+//! `source_dataset_uuid`). The server adds these to response bodies, so
+//! lineage, quality, refs, markers, consumers, impact and every other payload
+//! are covered without each handler looking UUIDs up. With integer ids
+//! hidden, the integer keys are removed as well.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_EXPOSE_INTEGER_IDS`: set to `false` to omit integer dataset ids
+//!   from responses (default: `true`)
+
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::AppState;
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::Response;
+use metafuse_catalog_core::dataset_uuids;
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Read `METAFUSE_EXPOSE_INTEGER_IDS`.
+pub fn expose_integer_ids_from_env() -> Result<bool, String> {
+    match std::env::var("METAFUSE_EXPOSE_INTEGER_IDS") {
+        Ok(v) => match v.to_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(true),
+            "false" | "0" | "no" => Ok(false),
+            _ => Err(format!(
+                "Invalid METAFUSE_EXPOSE_INTEGER_IDS '{}': expected 'true' or 'false'",
+                v
+            )),
+        },
+        Err(_) => Ok(true),
+    }
+}
+
+/// Whether a path segment is a dataset UUID rather than a name.
+pub fn is_uuid(value: &str) -> bool {
+    // Hyphenated form only; dataset names can be 32 hex characters
+    value.len() == 36 && uuid::Uuid::parse_str(value).is_ok()
+}
+
+/// Whether a response body may reference datasets by integer id, checked
+/// before parsing so other responses pass through untouched.
+pub fn mentions_dataset_ids(body: &[u8], expose_integer_ids: bool) -> bool {
+    let contains = |needle: &[u8]| body.windows(needle.len()).any(|w| w == needle);
+    contains(b"dataset_id\"") || (!expose_integer_ids && contains(b"\"uuid\""))
+}
+
+/// Integer dataset ids referenced anywhere in a response body.
+pub fn referenced_dataset_ids(value: &Value) -> Vec<i64> {
+    fn collect(value: &Value, ids: &mut BTreeSet<i64>) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object {
+                    if uuid_key(key).is_some() {
+                        ids.extend(value.as_i64());
+                    }
+                    collect(value, ids);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, ids)),
+            _ => {}
+        }
+    }
+    let mut ids = BTreeSet::new();
+    collect(value, &mut ids);
+    ids.into_iter().collect()
+}
+
+/// Add the UUID next to each integer dataset reference, and remove the
+/// integers unless they are exposed.
+///
+/// References to datasets without a UUID (e.g. since purged) get `null`.
+pub fn rewrite(value: &mut Value, uuids: &HashMap<i64, String>, expose_integer_ids: bool) {
+    match value {
+        Value::Object(object) => {
+            object
+                .values_mut()
+                .for_each(|value| rewrite(value, uuids, expose_integer_ids));
+            rewrite_object(object, uuids, expose_integer_ids);
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| rewrite(item, uuids, expose_integer_ids)),
+        _ => {}
+    }
+}
+
+fn rewrite_object(
+    object: &mut Map<String, Value>,
+    uuids: &HashMap<i64, String>,
+    expose_integer_ids: bool,
+) {
+    let references: Vec<(String, String)> = object
+        .iter()
+        .filter(|(_, value)| value.is_i64() || value.is_null())
+        .filter_map(|(key, _)| uuid_key(key).map(|uuid_key| (key.clone(), uuid_key)))
+        .collect();
+    for (key, uuid_key) in references {
+        if !object.contains_key(&uuid_key) {
+            let uuid = object[&key]
+                .as_i64()
+                .and_then(|id| uuids.get(&id))
+                .map_or(Value::Null, |uuid| Value::String(uuid.clone()));
+            object.insert(uuid_key, uuid);
+        }
+        if !expose_integer_ids {
+            object.remove(&key);
+        }
+    }
+
+    // Dataset objects already carry their own `uuid`
+    if !expose_integer_ids
+        && object.get("uuid").is_some_and(Value::is_string)
+        && object.get("id").is_some_and(Value::is_i64)
+    {
+        object.remove("id");
+    }
+}
+
+/// The UUID key for an integer dataset reference key, e.g.
+/// `source_dataset_id` -> `source_dataset_uuid`.
+fn uuid_key(key: &str) -> Option<String> {
+    let prefix = key.strip_suffix("dataset_id")?;
+    (prefix.is_empty() || prefix.ends_with('_')).then(|| format!("{}dataset_uuid", prefix))
+}
+
+/// Add dataset UUIDs to JSON responses, removing integer ids when hidden
+/// (see `public_ids`)
+pub(crate) async fn public_ids_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let tenant_backend = req.extensions().get::<TenantBackend>().cloned();
+    let response = next.run(req).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    // Streamed bodies have no exact size; JSON handlers always buffer theirs
+    if !is_json
+        || axum::body::HttpBody::size_hint(response.body())
+            .exact()
+            .is_none()
+    {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read response for dataset UUIDs");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, axum::body::Body::empty());
+        }
+    };
+    if !mentions_dataset_ids(&bytes, state.expose_integer_ids) {
+        return Response::from_parts(parts, axum::body::Body::from(bytes));
+    }
+    let Ok(mut value) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Response::from_parts(parts, axum::body::Body::from(bytes));
+    };
+
+    let ids = referenced_dataset_ids(&value);
+    let uuids = if ids.is_empty() {
+        HashMap::new()
+    } else {
+        let backend = resolve_backend(&state.backend, tenant_backend.as_ref());
+        let uuids = match backend.get_connection().await {
+            Ok(conn) => dataset_uuids::uuids_for(&conn, &ids),
+            Err(e) => Err(e),
+        };
+        // Without UUIDs the references become null rather than leaking ids
+        uuids.unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to look up dataset UUIDs for response");
+            HashMap::new()
+        })
+    };
+    rewrite(&mut value, &uuids, state.expose_integer_ids);
+
+    let Ok(rewritten) = serde_json::to_vec(&value) else {
+        return Response::from_parts(parts, axum::body::Body::from(bytes));
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, axum::body::Body::from(rewritten))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_uuid() {
+        assert!(is_uuid("7f9c2ba4-e88f-4d2a-9c1b-0b5e8f3a6d21"));
+        assert!(is_uuid("7F9C2BA4-E88F-4D2A-9C1B-0B5E8F3A6D21"));
+        assert!(!is_uuid("7f9c2ba4e88f4d2a9c1b0b5e8f3a6d21"));
+        assert!(!is_uuid("sales.emea.orders"));
+    }
+
+    #[test]
+    fn test_rewrite_dataset_references() {
+        let body = serde_json::json!({
+            "edges": [
+                {"id": 7, "source_dataset_id": 1, "target_dataset_id": 2},
+                {"id": 8, "source_dataset_id": 2, "target_dataset_id": 3}
+            ],
+            "dataset": {"id": 1, "uuid": "u-1", "name": "orders"},
+            "ref": {"dataset_id": null},
+            "candidate_dataset_ids": [1]
+        });
+        assert!(mentions_dataset_ids(body.to_string().as_bytes(), true));
+        assert_eq!(referenced_dataset_ids(&body), vec![1, 2, 3]);
+        let uuids = HashMap::from([(1, "u-1".to_string()), (2, "u-2".to_string())]);
+
+        let mut exposed = body.clone();
+        rewrite(&mut exposed, &uuids, true);
+        assert_eq!(exposed["edges"][0]["source_dataset_id"], 1);
+        assert_eq!(exposed["edges"][0]["source_dataset_uuid"], "u-1");
+        assert_eq!(exposed["edges"][1]["target_dataset_uuid"], Value::Null);
+        assert_eq!(exposed["dataset"]["id"], 1);
+
+        let mut hidden = body;
+        rewrite(&mut hidden, &uuids, false);
+        assert_eq!(
+            hidden["edges"][0],
+            serde_json::json!({"id": 7, "source_dataset_uuid": "u-1", "target_dataset_uuid": "u-2"})
+        );
+        assert_eq!(
+            hidden["dataset"],
+            serde_json::json!({"uuid": "u-1", "name": "orders"})
+        );
+        assert_eq!(hidden["ref"], serde_json::json!({"dataset_uuid": null}));
+        // Only `*dataset_id` keys are references
+        assert_eq!(hidden["candidate_dataset_ids"], serde_json::json!([1]));
+    }
+
+    #[test]
+    fn test_mentions_dataset_ids() {
+        assert!(!mentions_dataset_ids(br#"{"name": "orders"}"#, false));
+        assert!(!mentions_dataset_ids(br#"{"id": 1, "uuid": "u-1"}"#, true));
+        assert!(mentions_dataset_ids(br#"{"id": 1, "uuid": "u-1"}"#, false));
+    }
+}
@@ -8,6 +8,7 @@
 //! - **Freshness Score**: Based on last_modified vs configured SLA
 //! - **File Health Score**: Based on small file ratio and file size distribution
 //! - **Overall Score**: Weighted combination of the above
+//! - **Propagated Score** (optional): Overall score discounted by upstream quality
 //!
 //! # Architecture
 //!
@@ -17,8 +18,31 @@
 //! Partial failures are handled gracefully - if one score can't be computed,
 //! the others are still calculated and returned.
 
-use serde::Serialize;
-use tracing::{debug, info, warn};
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_writes::webhook_tenant;
+use crate::error_codes::ErrorCode;
+use crate::i18n::Locale;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    accessible_dataset_id, bad_request, dataset_not_found, internal_error, not_found,
+    require_dataset_access, AppState, AuditContext, Caller, DatasetPath, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::{dataset_acl, envelope, i18n};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use metafuse_catalog_storage::DynCatalogBackend;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
 
 /// Minimum file size in bytes considered "healthy" (128 MB)
 const SMALL_FILE_THRESHOLD_BYTES: i64 = 128 * 1024 * 1024;
@@ -51,6 +75,10 @@ pub struct QualityScores {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub overall_score: Option<f64>,
 
+    /// Overall score discounted by upstream quality (only when requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub propagated_score: Option<f64>,
+
     /// Detailed breakdown of quality metrics
     pub details: QualityDetails,
 }
@@ -93,6 +121,10 @@ pub struct QualityDetails {
     /// Last modification timestamp
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_modified: Option<String>,
+
+    /// Upstream datasets factored into the propagated score
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_contributions: Option<Vec<UpstreamContribution>>,
 }
 
 /// Response for quality endpoint
@@ -101,8 +133,33 @@ pub struct QualityResponse {
     pub dataset_id: i64,
     pub dataset_name: String,
     pub computed_at: String,
+    /// Profiler that computed the scores (`delta` for MetaFuse itself)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
     #[serde(flatten)]
     pub scores: QualityScores,
+    /// Human-readable names of the scores present, in the request's language
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+impl QualityResponse {
+    /// Add labels for the scores that were computed
+    pub fn with_labels(mut self, locale: &Locale) -> Self {
+        let scores = [
+            ("completeness_score", self.scores.completeness_score),
+            ("freshness_score", self.scores.freshness_score),
+            ("file_health_score", self.scores.file_health_score),
+            ("overall_score", self.scores.overall_score),
+            ("propagated_score", self.scores.propagated_score),
+        ];
+        self.labels = scores
+            .into_iter()
+            .filter(|(_, score)| score.is_some())
+            .map(|(name, _)| (name.to_string(), locale.label(&format!("quality.{}", name))))
+            .collect();
+        self
+    }
 }
 
 /// Response for unhealthy datasets endpoint
@@ -192,6 +249,7 @@ impl QualityCalculator {
             freshness_score: freshness,
             file_health_score: file_health,
             overall_score: overall,
+            propagated_score: None,
             details,
         };
 
@@ -347,27 +405,22 @@ pub fn compute_scores_from_metadata(
     };
 
     // Compute each score independently
-    let completeness = compute_completeness_sync(metadata, &mut details);
-    let freshness = compute_freshness_sync(conn, dataset_id, metadata, &mut details).ok();
-    let file_health = compute_file_health_sync(metadata, &mut details);
-
-    // Compute overall (requires at least one score)
-    let available_scores: Vec<(f64, f64)> = [
-        (completeness, WEIGHT_COMPLETENESS),
-        (freshness, WEIGHT_FRESHNESS),
-        (file_health, WEIGHT_FILE_HEALTH),
-    ]
-    .iter()
-    .filter_map(|(s, w)| s.map(|score| (score, *w)))
-    .collect();
-
-    let overall = if available_scores.is_empty() {
-        None
-    } else {
-        let total_weight: f64 = available_scores.iter().map(|(_, w)| w).sum();
-        let weighted_sum: f64 = available_scores.iter().map(|(s, w)| s * w).sum();
-        Some(clamp_score(weighted_sum / total_weight))
-    };
+    let total_nulls: i64 = metadata
+        .column_stats
+        .iter()
+        .filter_map(|s| s.null_count)
+        .sum();
+    let completeness = compute_completeness_sync(
+        metadata.row_count,
+        metadata.schema.fields.len() as i64,
+        total_nulls,
+        &mut details,
+    );
+    let freshness =
+        compute_freshness_sync(conn, dataset_id, metadata.last_modified, &mut details).ok();
+    let file_health =
+        compute_file_health_sync(metadata.num_files, metadata.size_bytes, &mut details);
+    let overall = overall_score(completeness, freshness, file_health);
 
     tracing::info!(
         dataset_id,
@@ -383,28 +436,22 @@ pub fn compute_scores_from_metadata(
         freshness_score: freshness,
         file_health_score: file_health,
         overall_score: overall,
+        propagated_score: None,
         details,
     })
 }
 
 /// Compute completeness score from null counts (standalone function)
 fn compute_completeness_sync(
-    metadata: &metafuse_catalog_delta::DeltaMetadata,
+    row_count: i64,
+    column_count: i64,
+    total_nulls: i64,
     details: &mut QualityDetails,
 ) -> Option<f64> {
-    let row_count = metadata.row_count;
-    let column_count = metadata.schema.fields.len() as i64;
-
     if row_count == 0 || column_count == 0 {
         return Some(1.0);
     }
 
-    let total_nulls: i64 = metadata
-        .column_stats
-        .iter()
-        .filter_map(|s| s.null_count)
-        .sum();
-
     details.total_null_count = Some(total_nulls);
 
     let total_cells = row_count * column_count;
@@ -416,7 +463,7 @@ fn compute_completeness_sync(
 fn compute_freshness_sync(
     conn: &rusqlite::Connection,
     dataset_id: i64,
-    metadata: &metafuse_catalog_delta::DeltaMetadata,
+    last_modified: chrono::DateTime<chrono::Utc>,
     details: &mut QualityDetails,
 ) -> Result<f64, QualityError> {
     let config: Option<(i64, i64)> = conn
@@ -431,7 +478,6 @@ fn compute_freshness_sync(
 
     details.freshness_sla_secs = Some(expected_interval);
 
-    let last_modified = metadata.last_modified;
     let now = chrono::Utc::now();
     let staleness_secs = (now - last_modified).num_seconds();
     details.staleness_secs = Some(staleness_secs);
@@ -450,16 +496,15 @@ fn compute_freshness_sync(
 
 /// Compute file health score based on small file ratio (standalone function)
 fn compute_file_health_sync(
-    metadata: &metafuse_catalog_delta::DeltaMetadata,
+    num_files: i64,
+    size_bytes: i64,
     details: &mut QualityDetails,
 ) -> Option<f64> {
-    let num_files = metadata.num_files;
-
     if num_files == 0 {
         return Some(1.0);
     }
 
-    let avg_file_size = metadata.size_bytes / num_files;
+    let avg_file_size = size_bytes / num_files;
     details.avg_file_size = Some(avg_file_size);
 
     let small_file_count = if avg_file_size < SMALL_FILE_THRESHOLD_BYTES {
@@ -476,6 +521,29 @@ fn compute_file_health_sync(
     Some(clamp_score(1.0 - (small_file_ratio * 0.5)))
 }
 
+/// Weighted average of the available scores, or `None` when there are none
+fn overall_score(
+    completeness: Option<f64>,
+    freshness: Option<f64>,
+    file_health: Option<f64>,
+) -> Option<f64> {
+    let available_scores: Vec<(f64, f64)> = [
+        (completeness, WEIGHT_COMPLETENESS),
+        (freshness, WEIGHT_FRESHNESS),
+        (file_health, WEIGHT_FILE_HEALTH),
+    ]
+    .iter()
+    .filter_map(|(s, w)| s.map(|score| (score, *w)))
+    .collect();
+
+    if available_scores.is_empty() {
+        return None;
+    }
+    let total_weight: f64 = available_scores.iter().map(|(_, w)| w).sum();
+    let weighted_sum: f64 = available_scores.iter().map(|(s, w)| s * w).sum();
+    Some(clamp_score(weighted_sum / total_weight))
+}
+
 /// Clamp a score to valid range [0.0, 1.0]
 fn clamp_score(score: f64) -> f64 {
     if score.is_nan() || score.is_infinite() {
@@ -518,11 +586,32 @@ impl std::error::Error for QualityError {}
 // Database Operations
 // =============================================================================
 
-/// Store quality scores in the database
+/// Store quality scores computed by MetaFuse in the database
 pub fn store_quality_scores(
     conn: &rusqlite::Connection,
     dataset_id: i64,
     scores: &QualityScores,
+) -> Result<i64, rusqlite::Error> {
+    insert_quality_scores(conn, dataset_id, scores, DELTA_STATS_SOURCE, None)
+}
+
+/// Store quality scores computed by an external profiler at `computed_at`
+pub fn store_external_quality_scores(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    scores: &QualityScores,
+    source: &str,
+    computed_at: &str,
+) -> Result<i64, rusqlite::Error> {
+    insert_quality_scores(conn, dataset_id, scores, source, Some(computed_at))
+}
+
+fn insert_quality_scores(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    scores: &QualityScores,
+    source: &str,
+    computed_at: Option<&str>,
 ) -> Result<i64, rusqlite::Error> {
     let details_json = serde_json::to_string(&scores.details).ok();
 
@@ -531,11 +620,13 @@ pub fn store_quality_scores(
         INSERT INTO quality_metrics (
             dataset_id, computed_at,
             completeness_score, freshness_score, file_health_score, overall_score,
-            row_count, file_count, size_bytes, small_file_count, avg_file_size, details
+            row_count, file_count, size_bytes, small_file_count, avg_file_size, details,
+            source
         ) VALUES (
-            ?1, datetime('now'),
+            ?1, COALESCE(?13, datetime('now')),
             ?2, ?3, ?4, ?5,
-            ?6, ?7, ?8, ?9, ?10, ?11
+            ?6, ?7, ?8, ?9, ?10, ?11,
+            ?12
         )
         "#,
         rusqlite::params![
@@ -550,6 +641,8 @@ pub fn store_quality_scores(
             scores.details.small_file_count,
             scores.details.avg_file_size,
             details_json,
+            source,
+            computed_at,
         ],
     )?;
 
@@ -567,7 +660,7 @@ pub fn get_latest_quality(
         SELECT
             completeness_score, freshness_score, file_health_score, overall_score,
             row_count, file_count, size_bytes, small_file_count, avg_file_size,
-            computed_at
+            computed_at, source
         FROM quality_metrics
         WHERE dataset_id = ?1
         ORDER BY computed_at DESC
@@ -579,11 +672,14 @@ pub fn get_latest_quality(
                 dataset_id,
                 dataset_name: dataset_name.to_string(),
                 computed_at: row.get(9)?,
+                source: row.get(10)?,
+                labels: BTreeMap::new(),
                 scores: QualityScores {
                     completeness_score: row.get(0)?,
                     freshness_score: row.get(1)?,
                     file_health_score: row.get(2)?,
                     overall_score: row.get(3)?,
+                    propagated_score: None,
                     details: QualityDetails {
                         row_count: row.get(4)?,
                         file_count: row.get(5)?,
@@ -604,12 +700,36 @@ pub fn get_latest_quality(
     }
 }
 
+/// Get the overall score of a dataset's latest quality scores
+pub fn latest_overall_score(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+) -> Result<Option<f64>, rusqlite::Error> {
+    Ok(get_latest_quality(conn, dataset_id, "")?.and_then(|q| q.scores.overall_score))
+}
+
 /// Get datasets with overall quality below threshold
+///
+/// `visibility` is an extra SQL condition on the dataset alias `d` hiding
+/// datasets from the caller, with its bindings.
 pub fn get_unhealthy_datasets(
     conn: &rusqlite::Connection,
     threshold: f64,
+    visibility: Option<(String, Vec<String>)>,
 ) -> Result<UnhealthyDatasetsResponse, rusqlite::Error> {
-    let mut stmt = conn.prepare(
+    let mut bindings: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(threshold)];
+    let visible = match visibility {
+        Some((clause, values)) => {
+            bindings.extend(
+                values
+                    .into_iter()
+                    .map(|v| Box::new(v) as Box<dyn rusqlite::ToSql>),
+            );
+            format!("AND {}", clause)
+        }
+        None => String::new(),
+    };
+    let mut stmt = conn.prepare(&format!(
         r#"
         SELECT
             d.id, d.name,
@@ -621,12 +741,14 @@ pub fn get_unhealthy_datasets(
             SELECT id FROM quality_metrics WHERE dataset_id = d.id ORDER BY computed_at DESC LIMIT 1
         )
         AND q.overall_score < ?1
+        AND d.deleted_at IS NULL {}
         ORDER BY q.overall_score ASC
         "#,
-    )?;
+        visible
+    ))?;
 
     let datasets: Vec<UnhealthyDatasetEntry> = stmt
-        .query_map([threshold], |row| {
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
             Ok(UnhealthyDatasetEntry {
                 dataset_id: row.get(0)?,
                 dataset_name: row.get(1)?,
@@ -645,6 +767,1257 @@ pub fn get_unhealthy_datasets(
     })
 }
 
+// =============================================================================
+// External Statistics
+// =============================================================================
+
+/// Source recorded for statistics and scores computed by MetaFuse from Delta
+pub const DELTA_STATS_SOURCE: &str = "delta";
+
+/// Most columns accepted in one statistics push
+pub const MAX_PUSHED_COLUMNS: usize = 2000;
+
+/// Longest a pushed `computed_at` may lie in the future (clock skew)
+const MAX_STATS_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Statistics for one column
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ColumnStatistic {
+    /// Column name
+    pub name: String,
+    #[serde(default)]
+    pub null_count: Option<i64>,
+    #[serde(default)]
+    pub distinct_count: Option<i64>,
+    #[serde(default)]
+    pub min_value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub max_value: Option<serde_json::Value>,
+}
+
+impl From<&metafuse_catalog_delta::ColumnStats> for ColumnStatistic {
+    fn from(stats: &metafuse_catalog_delta::ColumnStats) -> Self {
+        Self {
+            name: stats.name.clone(),
+            null_count: stats.null_count,
+            distinct_count: stats.distinct_count,
+            min_value: stats.min_value.clone(),
+            max_value: stats.max_value.clone(),
+        }
+    }
+}
+
+/// Statistics computed outside MetaFuse, e.g. by a Spark job
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatsPushRequest {
+    /// Label for the profiler, e.g. `spark`
+    pub source: String,
+    /// When the statistics were computed (RFC 3339)
+    pub computed_at: String,
+    #[serde(default)]
+    pub row_count: Option<i64>,
+    #[serde(default)]
+    pub file_count: Option<i64>,
+    #[serde(default)]
+    pub size_bytes: Option<i64>,
+    /// When the profiled data last changed (RFC 3339); enables the freshness score
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    #[serde(default)]
+    pub columns: Vec<ColumnStatistic>,
+}
+
+/// A validated statistics push
+#[derive(Debug, Clone)]
+pub struct ValidatedStatsPush {
+    /// `computed_at` in the `YYYY-MM-DD HH:MM:SS` form used for stored timestamps
+    pub computed_at: String,
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl StatsPushRequest {
+    /// Check the push and normalize its timestamps.
+    pub fn validate(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ValidatedStatsPush, String> {
+        metafuse_catalog_core::validation::validate_identifier(&self.source, "source")
+            .map_err(|e| e.to_string())?;
+        if self.source == DELTA_STATS_SOURCE {
+            return Err(format!(
+                "source '{}' is reserved for statistics computed by MetaFuse",
+                DELTA_STATS_SOURCE
+            ));
+        }
+
+        let computed_at = parse_pushed_timestamp(&self.computed_at, "computed_at")?;
+        if (computed_at - now).num_seconds() > MAX_STATS_CLOCK_SKEW_SECS {
+            return Err("computed_at is in the future".to_string());
+        }
+        let last_modified = self
+            .last_modified
+            .as_deref()
+            .map(|ts| parse_pushed_timestamp(ts, "last_modified"))
+            .transpose()?;
+
+        for (field, value) in [
+            ("row_count", self.row_count),
+            ("file_count", self.file_count),
+            ("size_bytes", self.size_bytes),
+        ] {
+            if value.is_some_and(|v| v < 0) {
+                return Err(format!("{} cannot be negative", field));
+            }
+        }
+
+        if self.columns.len() > MAX_PUSHED_COLUMNS {
+            return Err(format!(
+                "Too many columns: {} > {}",
+                self.columns.len(),
+                MAX_PUSHED_COLUMNS
+            ));
+        }
+        let mut names = std::collections::HashSet::new();
+        for column in &self.columns {
+            if column.name.is_empty() {
+                return Err("Column name cannot be empty".to_string());
+            }
+            if !names.insert(column.name.as_str()) {
+                return Err(format!("Duplicate column '{}'", column.name));
+            }
+            if column.null_count.is_some_and(|n| n < 0)
+                || column.distinct_count.is_some_and(|n| n < 0)
+            {
+                return Err(format!("Column '{}' has a negative count", column.name));
+            }
+            if let (Some(nulls), Some(rows)) = (column.null_count, self.row_count) {
+                if nulls > rows {
+                    return Err(format!(
+                        "Column '{}' has more nulls ({}) than rows ({})",
+                        column.name, nulls, rows
+                    ));
+                }
+            }
+        }
+
+        Ok(ValidatedStatsPush {
+            computed_at: computed_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            last_modified,
+        })
+    }
+}
+
+fn parse_pushed_timestamp(
+    value: &str,
+    field: &str,
+) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.with_timezone(&chrono::Utc))
+        .map_err(|_| format!("{} must be an RFC 3339 timestamp", field))
+}
+
+/// Compute quality scores from pushed statistics.
+///
+/// Scores use the same formulas as Delta profiling, for whichever inputs the
+/// push includes: completeness needs `row_count` and column null counts (over
+/// the columns that have one), freshness needs `last_modified` and a freshness
+/// SLA, and file health needs `file_count` and `size_bytes`.
+pub fn compute_scores_from_stats(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    stats: &StatsPushRequest,
+    last_modified: Option<chrono::DateTime<chrono::Utc>>,
+) -> QualityScores {
+    let mut details = QualityDetails {
+        row_count: stats.row_count,
+        file_count: stats.file_count,
+        size_bytes: stats.size_bytes,
+        last_modified: last_modified.map(|ts| ts.to_rfc3339()),
+        ..Default::default()
+    };
+
+    let null_counts: Vec<i64> = stats.columns.iter().filter_map(|c| c.null_count).collect();
+    let completeness = match stats.row_count {
+        Some(rows) if !null_counts.is_empty() => compute_completeness_sync(
+            rows,
+            null_counts.len() as i64,
+            null_counts.iter().sum(),
+            &mut details,
+        ),
+        _ => None,
+    };
+    let freshness = last_modified
+        .and_then(|ts| compute_freshness_sync(conn, dataset_id, ts, &mut details).ok());
+    let file_health = match (stats.file_count, stats.size_bytes) {
+        (Some(files), Some(bytes)) => compute_file_health_sync(files, bytes, &mut details),
+        _ => None,
+    };
+
+    QualityScores {
+        completeness_score: completeness,
+        freshness_score: freshness,
+        file_health_score: file_health,
+        overall_score: overall_score(completeness, freshness, file_health),
+        propagated_score: None,
+        details,
+    }
+}
+
+/// Record column statistics, keeping the latest per column.
+///
+/// Statistics older than those already stored for a column are ignored, so a
+/// delayed push can't replace newer numbers. Returns the columns updated.
+pub fn store_column_stats(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    columns: &[ColumnStatistic],
+    source: &str,
+    computed_at: &str,
+) -> Result<usize, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        r#"
+        INSERT INTO column_stats (
+            dataset_id, column_name, null_count, distinct_count,
+            min_value, max_value, source, computed_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        ON CONFLICT (dataset_id, column_name) DO UPDATE SET
+            null_count = excluded.null_count,
+            distinct_count = excluded.distinct_count,
+            min_value = excluded.min_value,
+            max_value = excluded.max_value,
+            source = excluded.source,
+            computed_at = excluded.computed_at
+        WHERE excluded.computed_at >= column_stats.computed_at
+        "#,
+    )?;
+    let mut updated = 0;
+    for column in columns {
+        updated += stmt.execute(rusqlite::params![
+            dataset_id,
+            column.name,
+            column.null_count,
+            column.distinct_count,
+            column.min_value.as_ref().map(|v| v.to_string()),
+            column.max_value.as_ref().map(|v| v.to_string()),
+            source,
+            computed_at,
+        ])?;
+    }
+    Ok(updated)
+}
+
+// =============================================================================
+// Result Reuse
+// =============================================================================
+
+/// Default seconds a stored result is served before `?include=quality`
+/// recomputes it
+pub const DEFAULT_QUALITY_STALENESS_SECS: u64 = 3600;
+
+/// How long stored quality results are reused
+#[derive(Debug, Clone)]
+pub struct QualityCacheConfig {
+    /// Stored results older than this are recomputed from Delta (0 = every request)
+    pub staleness_secs: u64,
+}
+
+impl Default for QualityCacheConfig {
+    fn default() -> Self {
+        Self {
+            staleness_secs: DEFAULT_QUALITY_STALENESS_SECS,
+        }
+    }
+}
+
+impl QualityCacheConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_QUALITY_STALENESS_SECS`: seconds a stored result is reused
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            staleness_secs: std::env::var("METAFUSE_QUALITY_STALENESS_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.staleness_secs),
+        }
+    }
+
+    /// Whether a result computed at `computed_at` can still be served at `now`
+    ///
+    /// Unreadable timestamps count as stale.
+    pub fn is_fresh(&self, computed_at: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+        crate::freshness::parse_timestamp(computed_at).is_some_and(|computed| {
+            now.signed_duration_since(computed).num_seconds() < self.staleness_secs as i64
+        })
+    }
+}
+
+// =============================================================================
+// Lineage Propagation
+// =============================================================================
+
+/// Default share of an upstream's quality shortfall passed on per hop
+pub const DEFAULT_PROPAGATION_DECAY: f64 = 0.5;
+
+/// Default number of upstream hops considered
+pub const DEFAULT_PROPAGATION_MAX_HOPS: usize = 3;
+
+/// Upstream quality propagation configuration
+#[derive(Debug, Clone)]
+pub struct QualityPropagationConfig {
+    /// Weight of a direct upstream (0.0-1.0); an upstream `n` hops away
+    /// weighs `decay^n`
+    pub decay: f64,
+    /// Upstreams further than this are ignored
+    pub max_hops: usize,
+}
+
+impl Default for QualityPropagationConfig {
+    fn default() -> Self {
+        Self {
+            decay: DEFAULT_PROPAGATION_DECAY,
+            max_hops: DEFAULT_PROPAGATION_MAX_HOPS,
+        }
+    }
+}
+
+impl QualityPropagationConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_QUALITY_PROPAGATION_DECAY`: weight of a direct upstream (0.0-1.0)
+    /// - `METAFUSE_QUALITY_PROPAGATION_MAX_HOPS`: upstream hops considered
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            decay: std::env::var("METAFUSE_QUALITY_PROPAGATION_DECAY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|decay: &f64| (0.0..=1.0).contains(decay))
+                .unwrap_or(defaults.decay),
+            max_hops: std::env::var("METAFUSE_QUALITY_PROPAGATION_MAX_HOPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|hops| *hops > 0)
+                .unwrap_or(defaults.max_hops),
+        }
+    }
+}
+
+/// An upstream dataset's effect on a propagated score
+#[derive(Debug, Clone, Serialize)]
+pub struct UpstreamContribution {
+    pub dataset_id: i64,
+    pub dataset_name: String,
+    /// Shortest lineage distance to the scored dataset
+    pub hops: usize,
+    /// The upstream's latest overall score
+    pub overall_score: f64,
+    /// `decay^hops`
+    pub weight: f64,
+    /// Multiplier applied to the propagated score: `1 - weight * (1 - overall_score)`
+    pub factor: f64,
+}
+
+/// Factor upstream quality into a dataset's scores.
+///
+/// Upstreams are found by walking lineage up to `max_hops`, each counted once
+/// at its shortest distance. Every upstream with a stored score scales the
+/// dataset's overall score by its `factor`, so a perfect upstream changes
+/// nothing and a broken one drags the result down in proportion to its
+/// weight. Upstreams without scores are skipped but still walked through;
+/// deleted datasets are not walked. Sets `propagated_score` (when the
+/// dataset has an overall score) and `details.upstream_contributions`.
+pub fn propagate_upstream_quality(
+    conn: &rusqlite::Connection,
+    quality: &mut QualityResponse,
+    config: &QualityPropagationConfig,
+) -> Result<(), rusqlite::Error> {
+    let mut upstream_stmt = conn.prepare(
+        r#"
+        SELECT d.id, d.name
+        FROM lineage l
+        JOIN datasets d ON d.id = l.upstream_dataset_id
+        WHERE l.downstream_dataset_id = ?1 AND d.deleted_at IS NULL
+        "#,
+    )?;
+
+    let mut visited = std::collections::HashSet::from([quality.dataset_id]);
+    let mut frontier = vec![quality.dataset_id];
+    let mut upstreams: Vec<(i64, String, usize)> = Vec::new();
+    for hops in 1..=config.max_hops {
+        let mut next = Vec::new();
+        for id in frontier {
+            let rows = upstream_stmt
+                .query_map([id], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<(i64, String)>, _>>()?;
+            for (upstream_id, name) in rows {
+                if visited.insert(upstream_id) {
+                    upstreams.push((upstream_id, name, hops));
+                    next.push(upstream_id);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    let mut contributions = Vec::new();
+    for (dataset_id, dataset_name, hops) in upstreams {
+        let score: Option<f64> = conn
+            .query_row(
+                "SELECT overall_score FROM quality_metrics
+                 WHERE dataset_id = ?1 ORDER BY computed_at DESC LIMIT 1",
+                [dataset_id],
+                |row| row.get(0),
+            )
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })?;
+        let Some(overall_score) = score else {
+            continue;
+        };
+        let weight = config.decay.powi(hops as i32);
+        contributions.push(UpstreamContribution {
+            dataset_id,
+            dataset_name,
+            hops,
+            overall_score,
+            weight,
+            factor: clamp_score(1.0 - weight * (1.0 - overall_score)),
+        });
+    }
+    contributions.sort_by(|a, b| {
+        a.hops
+            .cmp(&b.hops)
+            .then_with(|| a.dataset_name.cmp(&b.dataset_name))
+    });
+
+    quality.scores.propagated_score = quality.scores.overall_score.map(|overall| {
+        clamp_score(contributions.iter().map(|c| c.factor).product::<f64>() * overall)
+    });
+    quality.scores.details.upstream_contributions = Some(contributions);
+    Ok(())
+}
+
+// =============================================================================
+// Compaction
+// =============================================================================
+
+/// Default days quality history is kept at hourly resolution
+pub const DEFAULT_HOURLY_RETENTION_DAYS: u32 = 7;
+
+/// Default days quality history is kept at daily resolution (monthly after)
+pub const DEFAULT_DAILY_RETENTION_DAYS: u32 = 90;
+
+/// Default interval between compaction runs
+pub const DEFAULT_COMPACTION_INTERVAL_SECS: u64 = 3600;
+
+/// Quality history downsampling configuration
+#[derive(Debug, Clone)]
+pub struct QualityCompactionConfig {
+    /// Whether the background compaction task runs
+    pub enabled: bool,
+    /// History younger than this keeps one computation per hour
+    pub hourly_retention_days: u32,
+    /// History younger than this keeps one computation per day; older keeps one per month
+    pub daily_retention_days: u32,
+    /// Seconds between background compaction runs
+    pub interval_secs: u64,
+}
+
+impl Default for QualityCompactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hourly_retention_days: DEFAULT_HOURLY_RETENTION_DAYS,
+            daily_retention_days: DEFAULT_DAILY_RETENTION_DAYS,
+            interval_secs: DEFAULT_COMPACTION_INTERVAL_SECS,
+        }
+    }
+}
+
+impl QualityCompactionConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_QUALITY_COMPACTION_ENABLED`: "false" or "0" disables compaction
+    /// - `METAFUSE_QUALITY_HOURLY_RETENTION_DAYS`: days kept at hourly resolution
+    /// - `METAFUSE_QUALITY_DAILY_RETENTION_DAYS`: days kept at daily resolution
+    /// - `METAFUSE_QUALITY_COMPACTION_INTERVAL_SECS`: seconds between runs
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let hourly_retention_days = std::env::var("METAFUSE_QUALITY_HOURLY_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.hourly_retention_days);
+        Self {
+            enabled: std::env::var("METAFUSE_QUALITY_COMPACTION_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.enabled),
+            hourly_retention_days,
+            // The daily tier starts where the hourly tier ends
+            daily_retention_days: std::env::var("METAFUSE_QUALITY_DAILY_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.daily_retention_days)
+                .max(hourly_retention_days),
+            interval_secs: std::env::var("METAFUSE_QUALITY_COMPACTION_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.interval_secs),
+        }
+    }
+}
+
+/// Result of a compaction run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionSummary {
+    /// Rows merged into a kept row and deleted
+    pub rows_deleted: usize,
+    /// Rows kept as the representative of a bucket with several samples
+    pub buckets_compacted: usize,
+}
+
+/// Downsample quality history.
+///
+/// Each dataset's history is bucketed by hour (younger than
+/// `hourly_retention_days`), day (younger than `daily_retention_days`), or
+/// month. The latest computation in each bucket is kept, with `sample_count`
+/// set to the bucket total, and the rest are deleted. The latest computation
+/// per dataset is always kept, so current scores are unaffected.
+pub fn compact_quality_metrics(
+    conn: &rusqlite::Connection,
+    config: &QualityCompactionConfig,
+) -> Result<CompactionSummary, rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute("DROP TABLE IF EXISTS temp.quality_compaction", [])?;
+    tx.execute(
+        r#"
+        CREATE TEMP TABLE quality_compaction AS
+        WITH bucketed AS (
+            SELECT id, dataset_id, computed_at, sample_count,
+                   CASE
+                       WHEN julianday(computed_at) >= julianday('now', '-' || ?1 || ' days')
+                           THEN 'h' || strftime('%Y-%m-%d %H', computed_at)
+                       WHEN julianday(computed_at) >= julianday('now', '-' || ?2 || ' days')
+                           THEN 'd' || strftime('%Y-%m-%d', computed_at)
+                       ELSE 'm' || strftime('%Y-%m', computed_at)
+                   END AS bucket
+            FROM quality_metrics
+        )
+        SELECT id,
+               ROW_NUMBER() OVER bucket_window AS position,
+               COUNT(*) OVER (PARTITION BY dataset_id, bucket) AS bucket_rows,
+               SUM(sample_count) OVER (PARTITION BY dataset_id, bucket) AS bucket_samples
+        FROM bucketed
+        WINDOW bucket_window AS (
+            PARTITION BY dataset_id, bucket ORDER BY julianday(computed_at) DESC, id DESC
+        )
+        "#,
+        rusqlite::params![config.hourly_retention_days, config.daily_retention_days],
+    )?;
+
+    let buckets_compacted = tx.execute(
+        r#"
+        UPDATE quality_metrics
+        SET sample_count = (
+            SELECT bucket_samples FROM temp.quality_compaction c WHERE c.id = quality_metrics.id
+        )
+        WHERE id IN (
+            SELECT id FROM temp.quality_compaction WHERE position = 1 AND bucket_rows > 1
+        )
+        "#,
+        [],
+    )?;
+    let rows_deleted = tx.execute(
+        "DELETE FROM quality_metrics WHERE id IN (SELECT id FROM temp.quality_compaction WHERE position > 1)",
+        [],
+    )?;
+
+    tx.execute("DROP TABLE temp.quality_compaction", [])?;
+    tx.commit()?;
+
+    Ok(CompactionSummary {
+        rows_deleted,
+        buckets_compacted,
+    })
+}
+
+/// Background task that periodically downsamples quality history
+pub async fn quality_compaction_task(
+    config: QualityCompactionConfig,
+    backend: std::sync::Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = std::time::Duration::from_secs(config.interval_secs);
+
+    info!(
+        interval_secs = config.interval_secs,
+        hourly_retention_days = config.hourly_retention_days,
+        daily_retention_days = config.daily_retention_days,
+        "Quality compaction task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        debug!("Running periodic quality compaction");
+
+        match backend.get_connection().await {
+            Ok(conn) => match compact_quality_metrics(&conn, &config) {
+                Ok(summary) => {
+                    if summary.rows_deleted > 0 {
+                        info!(
+                            rows_deleted = summary.rows_deleted,
+                            buckets_compacted = summary.buckets_compacted,
+                            "Compacted quality history"
+                        );
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to compact quality history");
+                }
+            },
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for quality compaction");
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Request to create a quality metric
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateQualityMetricRequest {
+    completeness_score: Option<f64>,
+    freshness_score: Option<f64>,
+    file_health_score: Option<f64>,
+    overall_score: Option<f64>,
+    row_count: Option<i64>,
+    file_count: Option<i64>,
+    size_bytes: Option<i64>,
+    details: Option<serde_json::Value>,
+}
+
+/// Quality metric response structure
+#[derive(Debug, Serialize)]
+pub(crate) struct QualityMetricResponse {
+    id: i64,
+    dataset_id: i64,
+    computed_at: String,
+    completeness_score: Option<f64>,
+    freshness_score: Option<f64>,
+    file_health_score: Option<f64>,
+    overall_score: Option<f64>,
+    row_count: Option<i64>,
+    file_count: Option<i64>,
+    size_bytes: Option<i64>,
+    details: Option<serde_json::Value>,
+    /// Computations this row stands for after history compaction
+    sample_count: i64,
+}
+
+/// Query parameters for unhealthy datasets endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct UnhealthyQueryParams {
+    #[serde(default = "default_unhealthy_threshold")]
+    threshold: f64,
+}
+
+pub(crate) fn default_unhealthy_threshold() -> f64 {
+    0.7
+}
+
+/// Query parameters for dataset quality endpoints
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct QualityQueryParams {
+    /// Factor upstream quality into a `propagated_score`
+    #[serde(default)]
+    propagate: bool,
+    /// Per-hop decay overriding `METAFUSE_QUALITY_PROPAGATION_DECAY`
+    decay: Option<f64>,
+}
+
+impl QualityQueryParams {
+    /// Propagation config for the request, or `None` if not requested
+    fn propagation(
+        &self,
+        defaults: &QualityPropagationConfig,
+        request_id: &str,
+    ) -> Result<Option<QualityPropagationConfig>, (StatusCode, Json<ErrorResponse>)> {
+        if !self.propagate {
+            return Ok(None);
+        }
+        let mut config = defaults.clone();
+        if let Some(decay) = self.decay {
+            if !(0.0..=1.0).contains(&decay) {
+                return Err(bad_request(
+                    "decay must be between 0.0 and 1.0".to_string(),
+                    request_id.to_string(),
+                ));
+            }
+            config.decay = decay;
+        }
+        Ok(Some(config))
+    }
+}
+
+/// Get quality scores for a specific dataset
+pub(crate) async fn get_dataset_quality(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    locale: Option<Extension<i18n::Locale>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<QualityQueryParams>,
+) -> Result<Json<QualityResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let propagation = params.propagation(&state.quality_propagation, &request_id.0)?;
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset_name = %name, "Getting dataset quality");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Look up dataset
+    let dataset: Option<(i64, String)> = conn
+        .query_row(
+            "SELECT id, name FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+
+    let (dataset_id, dataset_name) =
+        dataset.ok_or_else(|| dataset_not_found(&name, request_id.0.clone()))?;
+
+    // Get latest quality scores
+    let result = get_latest_quality(&conn, dataset_id, &dataset_name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    match result {
+        Some(mut quality) => {
+            if let Some(config) = &propagation {
+                propagate_upstream_quality(&conn, &mut quality, config)
+                    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+            }
+            tracing::info!(
+                dataset_name = %name,
+                overall_score = ?quality.scores.overall_score,
+                propagated_score = ?quality.scores.propagated_score,
+                "Quality scores retrieved"
+            );
+            Ok(Json(
+                quality.with_labels(&locale.map(|e| e.0).unwrap_or_default()),
+            ))
+        }
+        None => Err(not_found(
+            format!("No quality scores found for dataset '{}'", name),
+            request_id.0,
+        )),
+    }
+}
+
+/// Compute a dataset's quality scores from its Delta table and store them
+/// with the column statistics behind them
+pub(crate) async fn compute_and_store_quality(
+    state: &AppState,
+    backend: &Arc<DynCatalogBackend>,
+    tenant_id: &str,
+    dataset_id: i64,
+    dataset_name: &str,
+    delta_location: &str,
+) -> Result<QualityScores, String> {
+    let delta_metadata = state
+        .delta_reader
+        .get_metadata_cached(delta_location)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let conn = backend.get_connection().await.map_err(|e| e.to_string())?;
+    let scores = compute_scores_from_metadata(&conn, dataset_id, &delta_metadata)
+        .map_err(|e| e.to_string())?;
+    let previous = latest_overall_score(&conn, dataset_id).map_err(|e| e.to_string())?;
+    store_quality_scores(&conn, dataset_id, &scores).map_err(|e| e.to_string())?;
+    state.webhooks.notify_quality(
+        &conn,
+        backend,
+        tenant_id,
+        dataset_name,
+        previous,
+        scores.overall_score,
+    );
+    let columns: Vec<ColumnStatistic> =
+        delta_metadata.column_stats.iter().map(Into::into).collect();
+    store_column_stats(
+        &conn,
+        dataset_id,
+        &columns,
+        DELTA_STATS_SOURCE,
+        &chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(scores)
+}
+
+/// Trigger quality computation for a dataset
+pub(crate) async fn compute_dataset_quality(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    locale: Option<Extension<i18n::Locale>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<QualityQueryParams>,
+) -> Result<Json<QualityResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let propagation = params.propagation(&state.quality_propagation, &request_id.0)?;
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::info!(tenant_id = %tenant_id, dataset_name = %name, "Computing dataset quality");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+
+    // First, look up the dataset and get delta_location (sync DB operation)
+    let (dataset_id, dataset_name, delta_location) = {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        accessible_dataset_id(
+            &conn,
+            &name,
+            identity.as_ref().map(|e| &e.0),
+            dataset_acl::AclPermission::Write,
+            &request_id,
+        )?;
+
+        let dataset: Option<(i64, String, Option<String>)> = conn
+            .query_row(
+                "SELECT id, name, delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+                [&name],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .ok();
+
+        let (id, ds_name, loc) =
+            dataset.ok_or_else(|| dataset_not_found(&name, request_id.0.clone()))?;
+
+        let loc = loc.ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!(
+                        "Dataset '{}' has no delta_location configured for quality computation",
+                        name
+                    ),
+                    code: ErrorCode::ValidationFailed,
+                    request_id: request_id.0.clone(),
+                }),
+            )
+        })?;
+
+        (id, ds_name, loc)
+    };
+
+    let scores = compute_and_store_quality(
+        &state,
+        &backend,
+        webhook_tenant(tenant_backend.as_ref()),
+        dataset_id,
+        &dataset_name,
+        &delta_location,
+    )
+    .await
+    .map_err(|e| internal_error(e, request_id.0.clone()))?;
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Return the response
+    let mut response = QualityResponse {
+        dataset_id,
+        dataset_name,
+        computed_at: chrono::Utc::now().to_rfc3339(),
+        source: Some(DELTA_STATS_SOURCE.to_string()),
+        scores,
+        labels: Default::default(),
+    };
+    if let Some(config) = &propagation {
+        propagate_upstream_quality(&conn, &mut response, config)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    let response = response.with_labels(&locale.map(|e| e.0).unwrap_or_default());
+
+    tracing::info!(
+        dataset_name = %name,
+        overall_score = ?response.scores.overall_score,
+        "Quality scores computed and stored"
+    );
+
+    Ok(Json(response))
+}
+
+/// Response for a statistics push
+#[derive(Debug, Serialize)]
+pub(crate) struct DatasetStatsPushResponse {
+    #[serde(flatten)]
+    quality: QualityResponse,
+    /// Columns whose stored statistics were replaced
+    columns_updated: usize,
+}
+
+/// Record statistics computed by an external profiler
+///
+/// Scores are computed from the pushed numbers with the same formulas as
+/// Delta profiling and stored with the push's source and timestamp.
+pub(crate) async fn push_dataset_stats(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<StatsPushRequest>,
+) -> Result<(StatusCode, Json<DatasetStatsPushResponse>), (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let push = req
+        .validate(chrono::Utc::now())
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let scores = compute_scores_from_stats(&conn, dataset_id, &req, push.last_modified);
+    let previous = latest_overall_score(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    store_external_quality_scores(&tx, dataset_id, &scores, &req.source, &push.computed_at)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let columns_updated = store_column_stats(
+        &tx,
+        dataset_id,
+        &req.columns,
+        &req.source,
+        &push.computed_at,
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(
+        dataset_name = %name,
+        source = %req.source,
+        columns_updated,
+        overall_score = ?scores.overall_score,
+        "External statistics recorded"
+    );
+
+    state.webhooks.notify_quality(
+        &conn,
+        &backend,
+        webhook_tenant(tenant_backend.as_ref()),
+        &name,
+        previous,
+        scores.overall_score,
+    );
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "dataset_stats",
+            &name,
+            serde_json::json!({
+                "source": req.source,
+                "computed_at": push.computed_at,
+                "row_count": req.row_count,
+                "columns": req.columns.len(),
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    let response = QualityResponse {
+        dataset_id,
+        dataset_name: name,
+        computed_at: push.computed_at,
+        source: Some(req.source),
+        scores,
+        labels: Default::default(),
+    };
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DatasetStatsPushResponse {
+            quality: response,
+            columns_updated,
+        }),
+    ))
+}
+
+/// Get datasets with quality below threshold
+pub(crate) async fn get_unhealthy_datasets_handler(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<UnhealthyQueryParams>,
+) -> Result<Json<UnhealthyDatasetsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, threshold = params.threshold, "Querying unhealthy datasets");
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+    let result = get_unhealthy_datasets(&conn, params.threshold, visibility)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(
+        count = result.datasets.len(),
+        threshold = params.threshold,
+        "Unhealthy datasets query completed"
+    );
+
+    Ok(Json(result))
+}
+
+/// Create a quality metric for a dataset
+pub(crate) async fn create_quality_metric(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<CreateQualityMetricRequest>,
+) -> Result<(StatusCode, Json<QualityMetricResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, "Creating quality metric");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    // Validate score values (must be 0.0 to 1.0)
+    if let Some(score) = req.completeness_score {
+        validation::validate_score(score, "completeness_score")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    if let Some(score) = req.freshness_score {
+        validation::validate_score(score, "freshness_score")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    if let Some(score) = req.file_health_score {
+        validation::validate_score(score, "file_health_score")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+    if let Some(score) = req.overall_score {
+        validation::validate_score(score, "overall_score")
+            .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    let details_json = req.details.as_ref().map(|v| v.to_string());
+    let previous = latest_overall_score(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    conn.execute(
+        r#"
+        INSERT INTO quality_metrics (dataset_id, computed_at, completeness_score, freshness_score, file_health_score, overall_score, row_count, file_count, size_bytes, details)
+        VALUES (?1, datetime('now'), ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        "#,
+        rusqlite::params![
+            dataset_id,
+            req.completeness_score,
+            req.freshness_score,
+            req.file_health_score,
+            req.overall_score,
+            req.row_count,
+            req.file_count,
+            req.size_bytes,
+            details_json,
+        ],
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let id = conn.last_insert_rowid();
+    state.webhooks.notify_quality(
+        &conn,
+        &backend,
+        tenant_id,
+        &name,
+        previous,
+        req.overall_score,
+    );
+
+    let metric = QualityMetricResponse {
+        id,
+        dataset_id,
+        computed_at: chrono::Utc::now().to_rfc3339(),
+        completeness_score: req.completeness_score,
+        freshness_score: req.freshness_score,
+        file_health_score: req.file_health_score,
+        overall_score: req.overall_score,
+        row_count: req.row_count,
+        file_count: req.file_count,
+        size_bytes: req.size_bytes,
+        details: req.details,
+        sample_count: 1,
+    };
+
+    tracing::info!(dataset = %name, "Quality metric created successfully");
+
+    Ok((StatusCode::CREATED, Json(metric)))
+}
+
+/// List quality metrics for a dataset
+pub(crate) async fn list_quality_metrics(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<QualityMetricResponse>>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(tenant_id = %tenant_id, dataset = %name, "Listing quality metrics");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, dataset_id, computed_at, completeness_score, freshness_score, file_health_score, overall_score, row_count, file_count, size_bytes, details, sample_count
+            FROM quality_metrics WHERE dataset_id = ?1 ORDER BY computed_at DESC
+            "#,
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let metrics = stmt
+        .query_map([dataset_id], |row| {
+            let details_str: Option<String> = row.get(10)?;
+            Ok(QualityMetricResponse {
+                id: row.get(0)?,
+                dataset_id: row.get(1)?,
+                computed_at: row.get(2)?,
+                completeness_score: row.get(3)?,
+                freshness_score: row.get(4)?,
+                file_health_score: row.get(5)?,
+                overall_score: row.get(6)?,
+                row_count: row.get(7)?,
+                file_count: row.get(8)?,
+                size_bytes: row.get(9)?,
+                details: details_str.and_then(|s| serde_json::from_str(&s).ok()),
+                sample_count: row.get(11)?,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(dataset = %name, count = metrics.len(), "Listed quality metrics successfully");
+
+    Ok(Json(envelope.page(metrics)))
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -653,6 +2026,21 @@ pub fn get_unhealthy_datasets(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_quality_cache_staleness() {
+        let now = chrono::DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let config = QualityCacheConfig::default();
+        assert!(config.is_fresh("2025-11-20 11:30:00", now));
+        assert!(config.is_fresh("2025-11-20T11:30:00Z", now));
+        assert!(!config.is_fresh("2025-11-20 10:59:59", now));
+        assert!(!config.is_fresh("yesterday", now));
+
+        let config = QualityCacheConfig { staleness_secs: 0 };
+        assert!(!config.is_fresh("2025-11-20 12:00:00", now));
+    }
+
     #[test]
     fn test_clamp_score_valid() {
         assert_eq!(clamp_score(0.5), 0.5);
@@ -720,6 +2108,7 @@ mod tests {
             freshness_score: Some(1.0),
             file_health_score: Some(0.8),
             overall_score: Some(0.92),
+            propagated_score: None,
             details: QualityDetails {
                 row_count: Some(1000),
                 file_count: Some(10),
@@ -786,6 +2175,7 @@ mod tests {
             freshness_score: Some(1.0),
             file_health_score: Some(0.9),
             overall_score: Some(0.95),
+            propagated_score: None,
             details: QualityDetails::default(),
         };
 
@@ -794,6 +2184,7 @@ mod tests {
             freshness_score: Some(0.3),
             file_health_score: Some(0.6),
             overall_score: Some(0.45),
+            propagated_score: None,
             details: QualityDetails::default(),
         };
 
@@ -801,7 +2192,7 @@ mod tests {
         store_quality_scores(&conn, unhealthy_id, &unhealthy_scores).unwrap();
 
         // Get unhealthy datasets (threshold 0.7)
-        let result = get_unhealthy_datasets(&conn, 0.7).unwrap();
+        let result = get_unhealthy_datasets(&conn, 0.7, None).unwrap();
 
         assert_eq!(result.threshold, 0.7);
         assert_eq!(result.datasets.len(), 1);
@@ -821,4 +2212,264 @@ mod tests {
         let result = get_latest_quality(&conn, 9999, "nonexistent").unwrap();
         assert!(result.is_none());
     }
+
+    fn insert_metric(conn: &rusqlite::Connection, dataset_id: i64, modifier: &str, score: f64) {
+        conn.execute(
+            "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+             VALUES (?1, datetime('now', ?2), ?3)",
+            rusqlite::params![dataset_id, modifier, score],
+        )
+        .unwrap();
+    }
+
+    fn compaction_db() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now')),
+                    ('customers', '/data2', 'parquet', datetime('now'), datetime('now'));",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_compaction_downsamples_by_age() {
+        let conn = compaction_db();
+
+        // Offsets from the start of a day/month keep samples in one bucket
+        // 3 samples in one hour two days ago
+        for minutes in ["+5 minutes", "+15 minutes", "+25 minutes"] {
+            conn.execute(
+                "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+                 VALUES (1, datetime('now', '-2 days', 'start of day', '+12 hours', ?1), 0.5)",
+                [minutes],
+            )
+            .unwrap();
+        }
+        // 3 samples on one day 30 days ago, in different hours
+        for hours in ["+1 hours", "+8 hours", "+20 hours"] {
+            conn.execute(
+                "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+                 VALUES (1, datetime('now', '-30 days', 'start of day', ?1), 0.6)",
+                [hours],
+            )
+            .unwrap();
+        }
+        // 2 samples in one month 200 days ago, on different days
+        for days in ["+1 days", "+9 days"] {
+            conn.execute(
+                "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+                 VALUES (1, datetime('now', '-200 days', 'start of month', ?1), 0.7)",
+                [days],
+            )
+            .unwrap();
+        }
+        // Latest sample for another dataset stays alone in its bucket
+        insert_metric(&conn, 2, "-1 minutes", 0.9);
+
+        let summary = compact_quality_metrics(&conn, &QualityCompactionConfig::default()).unwrap();
+        assert_eq!(
+            summary,
+            CompactionSummary {
+                rows_deleted: 5,
+                buckets_compacted: 3,
+            }
+        );
+
+        let rows: Vec<(String, i64)> = conn
+            .prepare(
+                "SELECT computed_at, sample_count FROM quality_metrics
+                 WHERE dataset_id = 1 ORDER BY computed_at DESC",
+            )
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+        // The latest sample of each bucket is kept and counts the merged ones
+        assert!(rows[0].0.ends_with("12:25:00"));
+        assert_eq!(rows[0].1, 3);
+        assert!(rows[1].0.ends_with("20:00:00"));
+        assert_eq!(rows[1].1, 3);
+        assert_eq!(rows[2].1, 2);
+
+        // Running again is a no-op
+        let again = compact_quality_metrics(&conn, &QualityCompactionConfig::default()).unwrap();
+        assert_eq!(again, CompactionSummary::default());
+    }
+
+    #[test]
+    fn test_compaction_keeps_latest_scores() {
+        let conn = compaction_db();
+        insert_metric(&conn, 1, "-3 seconds", 0.4);
+        insert_metric(&conn, 1, "-2 seconds", 0.5);
+        insert_metric(&conn, 1, "-1 seconds", 0.8);
+
+        compact_quality_metrics(&conn, &QualityCompactionConfig::default()).unwrap();
+
+        let latest = get_latest_quality(&conn, 1, "orders").unwrap().unwrap();
+        assert_eq!(latest.scores.overall_score, Some(0.8));
+    }
+
+    #[test]
+    fn test_compaction_merges_sample_counts_across_tiers() {
+        let conn = compaction_db();
+        // Two days ago, two distinct hours: separate hourly buckets
+        for hours in ["+1 hours", "+2 hours"] {
+            conn.execute(
+                "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score, sample_count)
+                 VALUES (1, datetime('now', '-2 days', 'start of day', ?1), 0.5, 4)",
+                [hours],
+            )
+            .unwrap();
+        }
+
+        // With no hourly tier they fall into one daily bucket
+        let config = QualityCompactionConfig {
+            hourly_retention_days: 0,
+            ..Default::default()
+        };
+        let summary = compact_quality_metrics(&conn, &config).unwrap();
+        assert_eq!(summary.rows_deleted, 1);
+
+        let sample_count: i64 = conn
+            .query_row("SELECT sample_count FROM quality_metrics", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(sample_count, 8);
+    }
+
+    #[test]
+    fn test_propagation_discounts_by_upstream_quality() {
+        let conn = compaction_db();
+        // raw -> customers -> orders, plus a deleted upstream of orders
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated, deleted_at)
+             VALUES ('raw', '/raw', 'parquet', datetime('now'), datetime('now'), NULL),
+                    ('gone', '/gone', 'parquet', datetime('now'), datetime('now'), datetime('now'));
+             INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (2, 1, datetime('now')), (3, 2, datetime('now')), (4, 1, datetime('now'));",
+        )
+        .unwrap();
+        insert_metric(&conn, 1, "-1 seconds", 0.9);
+        insert_metric(&conn, 3, "-1 seconds", 0.4);
+        insert_metric(&conn, 4, "-1 seconds", 0.0);
+
+        let mut quality = get_latest_quality(&conn, 1, "orders").unwrap().unwrap();
+        propagate_upstream_quality(&conn, &mut quality, &QualityPropagationConfig::default())
+            .unwrap();
+
+        // customers has no score; raw is two hops away: 1 - 0.25 * 0.6 = 0.85
+        let contributions = quality.scores.details.upstream_contributions.unwrap();
+        assert_eq!(contributions.len(), 1);
+        assert_eq!(contributions[0].dataset_name, "raw");
+        assert_eq!(contributions[0].hops, 2);
+        assert!((contributions[0].factor - 0.85).abs() < 1e-9);
+        assert!((quality.scores.propagated_score.unwrap() - 0.765).abs() < 1e-9);
+        assert_eq!(quality.scores.overall_score, Some(0.9));
+
+        // Out of range: nothing propagates
+        let mut quality = get_latest_quality(&conn, 1, "orders").unwrap().unwrap();
+        let config = QualityPropagationConfig {
+            max_hops: 1,
+            ..Default::default()
+        };
+        propagate_upstream_quality(&conn, &mut quality, &config).unwrap();
+        assert_eq!(quality.scores.propagated_score, Some(0.9));
+    }
+
+    #[test]
+    fn test_pushed_stats_scores_and_column_stats() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/o', 'delta', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let now = chrono::DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let push: StatsPushRequest = serde_json::from_value(serde_json::json!({
+            "source": "spark",
+            "computed_at": "2025-11-20T13:00:00+01:00",
+            "row_count": 100,
+            "columns": [
+                {"name": "id", "null_count": 0, "min_value": 1, "max_value": 100},
+                {"name": "email", "null_count": 20},
+                {"name": "notes"}
+            ]
+        }))
+        .unwrap();
+        let validated = push.validate(now).unwrap();
+        assert_eq!(validated.computed_at, "2025-11-20 12:00:00");
+
+        // Completeness covers the columns with null counts
+        let scores = compute_scores_from_stats(&conn, 1, &push, validated.last_modified);
+        assert_eq!(scores.completeness_score, Some(0.9));
+        assert!(scores.freshness_score.is_none());
+        assert!(scores.file_health_score.is_none());
+        assert_eq!(scores.overall_score, Some(0.9));
+
+        store_external_quality_scores(&conn, 1, &scores, "spark", &validated.computed_at).unwrap();
+        let latest = get_latest_quality(&conn, 1, "orders").unwrap().unwrap();
+        assert_eq!(latest.source.as_deref(), Some("spark"));
+        assert_eq!(latest.computed_at, "2025-11-20 12:00:00");
+
+        assert_eq!(
+            store_column_stats(&conn, 1, &push.columns, "spark", &validated.computed_at).unwrap(),
+            3
+        );
+        // Older statistics don't replace newer ones
+        let older = [ColumnStatistic {
+            name: "email".to_string(),
+            null_count: Some(50),
+            distinct_count: None,
+            min_value: None,
+            max_value: None,
+        }];
+        assert_eq!(
+            store_column_stats(&conn, 1, &older, "spark", "2025-11-19 12:00:00").unwrap(),
+            0
+        );
+        let (nulls, max): (i64, Option<String>) = conn
+            .query_row(
+                "SELECT null_count, max_value FROM column_stats WHERE column_name = 'email'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((nulls, max), (20, None));
+
+        let invalid = |patch: serde_json::Value| {
+            let mut value =
+                serde_json::json!({"source": "spark", "computed_at": "2025-11-20T12:00:00Z"});
+            value
+                .as_object_mut()
+                .unwrap()
+                .extend(patch.as_object().unwrap().clone());
+            serde_json::from_value::<StatsPushRequest>(value)
+                .unwrap()
+                .validate(now)
+                .is_err()
+        };
+        assert!(invalid(serde_json::json!({"source": "delta"})));
+        assert!(invalid(
+            serde_json::json!({"computed_at": "2025-11-21T12:00:00Z"})
+        ));
+        assert!(invalid(
+            serde_json::json!({"row_count": 10, "columns": [{"name": "a", "null_count": 11}]})
+        ));
+        assert!(invalid(
+            serde_json::json!({"columns": [{"name": "a"}, {"name": "a"}]})
+        ));
+    }
 }
@@ -15,6 +15,17 @@
 //! - `METAFUSE_TRUSTED_PROXIES`: Comma-separated list of trusted proxy IPs (optional, supports IPv4/IPv6)
 //! - `METAFUSE_RATE_LIMIT_MAX_BUCKETS`: Maximum bucket storage (default: 10000)
 //! - `METAFUSE_RATE_LIMIT_BUCKET_TTL_SECS`: Idle bucket TTL in seconds (default: 600)
+//! - `METAFUSE_RATE_LIMIT_EXEMPT_PATHS`: Comma-separated path globs that bypass rate limiting,
+//!   e.g. `/health,/metrics` (`*` matches any characters)
+//! - `METAFUSE_RATE_LIMIT_EXEMPT_CIDRS`: Comma-separated source networks that bypass rate
+//!   limiting, e.g. `10.0.0.0/8,fd00::/8`
+//!
+//! ## Exemptions
+//!
+//! Health probes and metrics scrapers shouldn't spend rate limit budget. A request is
+//! exempt when it matches every configured exemption list: with both set, only the listed
+//! paths from the listed networks bypass limiting. The source address is resolved like the
+//! rate limit key, so forwarded headers only count from trusted proxies.
 //!
 //! ## Multi-Tenant Rate Limits
 //!
@@ -24,7 +35,15 @@
 //! - Premium tier: 5000 requests/minute (configurable via `METAFUSE_RATE_LIMIT_PREMIUM`)
 //! - Enterprise tier: 10000 requests/minute (configurable via `METAFUSE_RATE_LIMIT_ENTERPRISE`)
 //!
-//! Rate limit keys in multi-tenant mode: `tenant:{tenant_id}:{api_key_or_ip}`
+//! Rate limit keys in multi-tenant mode: `tenant:{tenant_id}:{api_key_or_ip}`, or
+//! `tenant:{tenant_id}:impersonated:{operator}` for impersonation keys, so operator
+//! traffic is counted apart from the tenant's own
+//!
+//! ## Violation Tracking
+//!
+//! Rejected requests are aggregated in memory per (tenant, client, route) and drained
+//! periodically with [`RateLimiter::drain_violations`]. With the `api-keys` feature,
+//! [`violation_flush_task`] persists them to the control plane `rate_limit_events` table.
 //!
 //! ## Security
 //!
@@ -43,8 +62,9 @@
 //!     .layer(axum::middleware::from_fn(rate_limiting::rate_limit_middleware));
 //! ```
 
+use crate::error_codes::ErrorCode;
 use axum::{
-    extract::{ConnectInfo, Request},
+    extract::{ConnectInfo, MatchedPath, Request},
     http::StatusCode,
     middleware::Next,
     response::Response,
@@ -53,7 +73,7 @@ use axum::{
 use dashmap::DashMap;
 use serde_json::json;
 use std::{
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -74,6 +94,10 @@ const DEFAULT_MAX_BUCKETS: usize = 10_000;
 /// Default TTL for idle rate limit buckets (10 minutes)
 const DEFAULT_BUCKET_TTL_SECS: u64 = 600;
 
+/// Interval between violation flushes to the control plane
+#[cfg(feature = "api-keys")]
+const VIOLATION_FLUSH_INTERVAL_SECS: u64 = 60;
+
 // Tenant tier-based rate limits (requests per window)
 const DEFAULT_FREE_TIER_LIMIT: u32 = 100;
 const DEFAULT_STANDARD_TIER_LIMIT: u32 = 1000;
@@ -96,6 +120,8 @@ pub struct TenantRateLimitInfo {
     pub tenant_id: String,
     /// Tenant tier for determining rate limit
     pub tier: TenantTier,
+    /// Platform operator, when the request uses an impersonation key
+    pub impersonator: Option<String>,
 }
 
 /// Tenant tier for rate limiting purposes.
@@ -153,6 +179,121 @@ pub struct RateLimitConfig {
     pub standard_tier_limit: u32,
     pub premium_tier_limit: u32,
     pub enterprise_tier_limit: u32,
+    /// Requests that bypass rate limiting
+    pub exemptions: RateLimitExemptions,
+}
+
+/// Requests that bypass rate limiting, such as probes and metrics scrapers.
+///
+/// A request is exempt when it matches every non-empty list. With no lists
+/// configured nothing is exempt.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimitExemptions {
+    /// Path globs; `*` matches any characters, including `/`
+    pub paths: Vec<String>,
+    /// Source networks of the client address
+    pub networks: Vec<IpNetwork>,
+}
+
+impl RateLimitExemptions {
+    /// Read `METAFUSE_RATE_LIMIT_EXEMPT_PATHS` and `METAFUSE_RATE_LIMIT_EXEMPT_CIDRS`.
+    ///
+    /// An invalid network is an error: dropping it would exempt the paths for
+    /// every client.
+    pub fn from_env() -> Result<Self, String> {
+        let list = |var: &str| -> Vec<String> {
+            std::env::var(var)
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .map(|item| item.trim().to_string())
+                        .filter(|item| !item.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(Self {
+            paths: list("METAFUSE_RATE_LIMIT_EXEMPT_PATHS"),
+            networks: parse_networks(&list("METAFUSE_RATE_LIMIT_EXEMPT_CIDRS"))?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.networks.is_empty()
+    }
+
+    fn matches(&self, path: &str, client_ip: Option<IpAddr>) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let path_ok = self.paths.is_empty()
+            || self
+                .paths
+                .iter()
+                .any(|pattern| metafuse_catalog_core::auto_tagging::glob_match(pattern, path));
+        let network_ok = self.networks.is_empty()
+            || client_ip.is_some_and(|ip| self.networks.iter().any(|n| n.contains(ip)));
+        path_ok && network_ok
+    }
+}
+
+/// Parse `METAFUSE_RATE_LIMIT_EXEMPT_CIDRS` entries
+fn parse_networks(cidrs: &[String]) -> Result<Vec<IpNetwork>, String> {
+    cidrs
+        .iter()
+        .map(|cidr| {
+            IpNetwork::parse(cidr).map_err(|e| format!("METAFUSE_RATE_LIMIT_EXEMPT_CIDRS: {}", e))
+        })
+        .collect()
+}
+
+/// An IPv4 or IPv6 network in CIDR notation. A bare address is a single host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("invalid network '{}': bad address", s))?;
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid network '{}': bad prefix length", s))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 clients on dual-stack listeners show up as ::ffff:a.b.c.d
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
 }
 
 impl Default for RateLimitConfig {
@@ -198,10 +339,38 @@ impl Default for RateLimitConfig {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(DEFAULT_ENTERPRISE_TIER_LIMIT),
+            exemptions: RateLimitExemptions::default(),
         }
     }
 }
 
+impl RateLimitConfig {
+    /// The built-in limits, without reading the environment
+    pub fn builtin() -> Self {
+        Self {
+            anonymous_limit: DEFAULT_ANONYMOUS_LIMIT,
+            authenticated_limit: DEFAULT_AUTHENTICATED_LIMIT,
+            window_secs: DEFAULT_WINDOW_SECS,
+            trusted_proxies: None,
+            max_buckets: DEFAULT_MAX_BUCKETS,
+            bucket_ttl_secs: DEFAULT_BUCKET_TTL_SECS,
+            free_tier_limit: DEFAULT_FREE_TIER_LIMIT,
+            standard_tier_limit: DEFAULT_STANDARD_TIER_LIMIT,
+            premium_tier_limit: DEFAULT_PREMIUM_TIER_LIMIT,
+            enterprise_tier_limit: DEFAULT_ENTERPRISE_TIER_LIMIT,
+            exemptions: RateLimitExemptions::default(),
+        }
+    }
+
+    /// Read the limits from the environment, failing on invalid exemptions
+    pub fn from_env() -> Result<Self, String> {
+        Ok(Self {
+            exemptions: RateLimitExemptions::from_env()?,
+            ..Self::default()
+        })
+    }
+}
+
 /// Rate limit bucket for tracking requests
 #[derive(Clone, Debug)]
 struct RateLimitBucket {
@@ -210,10 +379,39 @@ struct RateLimitBucket {
     last_accessed: Instant,
 }
 
+/// Identifies an aggregated violation: (tenant, client, route)
+type ViolationKey = (Option<String>, String, String);
+
+/// In-memory violation counter between flushes
+#[derive(Clone, Debug)]
+#[allow(dead_code)] // Drained by the control plane flush task (api-keys feature)
+struct ViolationCounter {
+    count: u64,
+    first_seen: chrono::DateTime<chrono::Utc>,
+    last_seen: chrono::DateTime<chrono::Utc>,
+}
+
+/// Aggregated rate limit violations for one client on one route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(dead_code)] // Constructed for the control plane flush task (api-keys feature)
+pub struct RateLimitViolation {
+    /// Tenant the client belongs to (None for non-tenant clients)
+    pub tenant_id: Option<String>,
+    /// Client identity: `auth:<key hash>`, `ip:<address>`, or `anon:<address>`
+    pub client_key: String,
+    /// Matched route template, or the raw path if no route matched
+    pub route: String,
+    /// Number of rejected requests
+    pub count: u64,
+    pub first_seen: chrono::DateTime<chrono::Utc>,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+}
+
 /// Global rate limiter state
 pub struct RateLimiter {
     config: Arc<RateLimitConfig>,
     buckets: Arc<DashMap<String, RateLimitBucket>>,
+    violations: Arc<DashMap<ViolationKey, ViolationCounter>>,
 }
 
 impl Clone for RateLimiter {
@@ -221,6 +419,7 @@ impl Clone for RateLimiter {
         Self {
             config: Arc::clone(&self.config),
             buckets: Arc::clone(&self.buckets),
+            violations: Arc::clone(&self.violations),
         }
     }
 }
@@ -230,6 +429,7 @@ impl RateLimiter {
         Self {
             config: Arc::new(config),
             buckets: Arc::new(DashMap::new()),
+            violations: Arc::new(DashMap::new()),
         }
     }
 
@@ -299,6 +499,21 @@ impl RateLimiter {
         })
     }
 
+    /// Whether the request bypasses rate limiting.
+    pub fn is_exempt<B>(&self, req: &Request<B>) -> bool {
+        let exemptions = &self.config.exemptions;
+        if exemptions.is_empty() {
+            return false;
+        }
+        let client_ip = if exemptions.networks.is_empty() {
+            None
+        } else {
+            self.extract_client_ip(req)
+                .and_then(|ip| ip.parse::<IpAddr>().ok())
+        };
+        exemptions.matches(req.uri().path(), client_ip)
+    }
+
     /// Get rate limit for a tenant tier.
     fn get_tier_limit(&self, tier: TenantTier) -> u32 {
         match tier {
@@ -315,6 +530,18 @@ impl RateLimiter {
         if let Some(tenant_info) = req.extensions().get::<TenantRateLimitInfo>() {
             let limit = self.get_tier_limit(tenant_info.tier);
 
+            // Impersonation: counted per operator, apart from the tenant's clients
+            if let Some(operator) = &tenant_info.impersonator {
+                let key = format!("tenant:{}:impersonated:{}", tenant_info.tenant_id, operator);
+                debug!(
+                    rate_limit_key = %key,
+                    tenant_id = %tenant_info.tenant_id,
+                    limit = limit,
+                    "Using tenant + impersonating operator for rate limiting"
+                );
+                return (key, limit);
+            }
+
             // Sub-priority 0a: Tenant + API key
             if let Some(api_key) = req.extensions().get::<ApiKeyId>() {
                 let key = format!("tenant:{}:auth:{}", tenant_info.tenant_id, api_key.id);
@@ -371,6 +598,120 @@ impl RateLimiter {
     }
 }
 
+/// Snapshot of a tenant's request volume in the current rate limit window.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[allow(dead_code)] // Used by the tenant usage dashboard (api-keys feature)
+pub struct TenantRequestUsage {
+    /// Requests counted across all of the tenant's clients in their current windows
+    pub requests_in_window: u64,
+    /// Number of distinct clients (API keys or IPs) with an active window
+    pub active_clients: usize,
+    /// Rate limit window length in seconds
+    pub window_secs: u64,
+}
+
+#[allow(dead_code)] // Used by tenant-aware handlers and tasks (api-keys feature)
+impl RateLimiter {
+    /// Summarize a tenant's requests across its rate limit buckets.
+    ///
+    /// Only buckets whose window has not yet expired are counted, so the result
+    /// reflects the most recent window rather than lifetime totals.
+    pub fn tenant_usage(&self, tenant_id: &str) -> TenantRequestUsage {
+        let prefix = format!("tenant:{}:", tenant_id);
+        let now = Instant::now();
+        let window_duration = Duration::from_secs(self.config.window_secs);
+
+        let mut usage = TenantRequestUsage {
+            window_secs: self.config.window_secs,
+            ..Default::default()
+        };
+
+        for entry in self.buckets.iter() {
+            if entry.key().starts_with(&prefix)
+                && now.duration_since(entry.value().window_start) < window_duration
+            {
+                usage.requests_in_window += u64::from(entry.value().count);
+                usage.active_clients += 1;
+            }
+        }
+
+        usage
+    }
+
+    /// Get the per-window request limit for a tenant tier.
+    pub fn tier_limit(&self, tier: TenantTier) -> u32 {
+        self.get_tier_limit(tier)
+    }
+
+    /// Record a rejected request for later persistence.
+    ///
+    /// New (tenant, client, route) combinations are dropped once `max_buckets`
+    /// distinct combinations are pending, to bound memory under key-spraying abuse.
+    fn record_violation(&self, rate_limit_key: &str, route: &str) {
+        let (tenant_id, client_key) = split_rate_limit_key(rate_limit_key);
+        let key = (tenant_id, client_key, route.to_string());
+        let now = chrono::Utc::now();
+
+        if let Some(mut counter) = self.violations.get_mut(&key) {
+            counter.count += 1;
+            counter.last_seen = now;
+            return;
+        }
+
+        if self.violations.len() >= self.config.max_buckets {
+            debug!(
+                rate_limit_key = %rate_limit_key,
+                "Violation tracking full, dropping new violation key"
+            );
+            return;
+        }
+
+        self.violations
+            .entry(key)
+            .and_modify(|counter| {
+                counter.count += 1;
+                counter.last_seen = now;
+            })
+            .or_insert(ViolationCounter {
+                count: 1,
+                first_seen: now,
+                last_seen: now,
+            });
+    }
+
+    /// Take all violations recorded since the last drain.
+    pub fn drain_violations(&self) -> Vec<RateLimitViolation> {
+        let keys: Vec<ViolationKey> = self.violations.iter().map(|e| e.key().clone()).collect();
+
+        keys.into_iter()
+            .filter_map(|key| self.violations.remove(&key))
+            .map(
+                |((tenant_id, client_key, route), counter)| RateLimitViolation {
+                    tenant_id,
+                    client_key,
+                    route,
+                    count: counter.count,
+                    first_seen: counter.first_seen,
+                    last_seen: counter.last_seen,
+                },
+            )
+            .collect()
+    }
+}
+
+/// Split a rate limit key into (tenant_id, client_key).
+///
+/// `tenant:acme:auth:abc` becomes `(Some("acme"), "auth:abc")`; non-tenant keys
+/// such as `anon:1.2.3.4` are returned unchanged with no tenant.
+fn split_rate_limit_key(key: &str) -> (Option<String>, String) {
+    if let Some(rest) = key.strip_prefix("tenant:") {
+        if let Some((tenant_id, client)) = rest.split_once(':') {
+            return (Some(tenant_id.to_string()), client.to_string());
+        }
+    }
+    (None, key.to_string())
+}
+
 /// Rate limit metadata for response headers
 #[derive(Debug, Clone)]
 pub struct RateLimitMetadata {
@@ -433,6 +774,15 @@ impl RateLimiter {
                 .window_secs
                 .saturating_sub(now.duration_since(bucket.window_start).as_secs());
 
+            // Release the bucket lock before touching the violations map
+            drop(bucket);
+            let route = req
+                .extensions()
+                .get::<MatchedPath>()
+                .map(|p| p.as_str())
+                .unwrap_or_else(|| req.uri().path());
+            self.record_violation(&key, route);
+
             let metadata = RateLimitMetadata {
                 limit,
                 remaining: 0,
@@ -470,6 +820,11 @@ pub async fn rate_limit_middleware(
         .cloned()
         .unwrap_or_else(|| RateLimiter::new(RateLimitConfig::default()));
 
+    // Probes and scrapers skip limiting and get no rate limit headers
+    if rate_limiter.is_exempt(&req) {
+        return Ok(next.run(req).await);
+    }
+
     let request_id = req
         .extensions()
         .get::<uuid::Uuid>()
@@ -517,6 +872,7 @@ pub async fn rate_limit_middleware(
                     "code": "RATE_LIMIT_EXCEEDED",
                     "message": "Too many requests. Please retry after the specified time.",
                 },
+                "code": ErrorCode::RateLimited,
                 "request_id": request_id,
                 "retry_after": retry_after,
             });
@@ -558,6 +914,41 @@ pub fn create_rate_limiter() -> RateLimiter {
     RateLimiter::new(RateLimitConfig::default())
 }
 
+/// Background task that periodically persists rate limit violations to the control plane
+#[cfg(feature = "api-keys")]
+pub async fn violation_flush_task(
+    limiter: RateLimiter,
+    control_plane: Arc<crate::control_plane::ControlPlane>,
+) {
+    let interval = Duration::from_secs(VIOLATION_FLUSH_INTERVAL_SECS);
+
+    tracing::info!(
+        interval_secs = VIOLATION_FLUSH_INTERVAL_SECS,
+        "Rate limit violation flush task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let violations = limiter.drain_violations();
+        if violations.is_empty() {
+            continue;
+        }
+
+        match control_plane
+            .record_rate_limit_violations(&violations)
+            .await
+        {
+            Ok(count) => debug!(count, "Flushed rate limit violations"),
+            Err(e) => warn!(
+                error = %e,
+                dropped = violations.len(),
+                "Failed to persist rate limit violations"
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -576,9 +967,19 @@ mod tests {
             standard_tier_limit: DEFAULT_STANDARD_TIER_LIMIT,
             premium_tier_limit: DEFAULT_PREMIUM_TIER_LIMIT,
             enterprise_tier_limit: DEFAULT_ENTERPRISE_TIER_LIMIT,
+            exemptions: RateLimitExemptions::default(),
         }
     }
 
+    #[test]
+    fn test_builtin_config() {
+        let config = RateLimitConfig::builtin();
+        assert_eq!(config.anonymous_limit, DEFAULT_ANONYMOUS_LIMIT);
+        assert_eq!(config.enterprise_tier_limit, DEFAULT_ENTERPRISE_TIER_LIMIT);
+        assert!(config.trusted_proxies.is_none());
+        assert!(config.exemptions.is_empty());
+    }
+
     #[test]
     fn test_config_defaults() {
         let config = RateLimitConfig::default();
@@ -621,6 +1022,7 @@ mod tests {
         req.extensions_mut().insert(TenantRateLimitInfo {
             tenant_id: "acme-corp".to_string(),
             tier: TenantTier::Premium,
+            impersonator: None,
         });
         req.extensions_mut().insert(ApiKeyId {
             id: "key-123".to_string(),
@@ -631,6 +1033,24 @@ mod tests {
         assert_eq!(limit, DEFAULT_PREMIUM_TIER_LIMIT);
     }
 
+    #[test]
+    fn test_tenant_rate_limit_impersonation() {
+        let limiter = RateLimiter::new(test_config(100, 1000));
+        let addr: SocketAddr = "192.168.1.100:8080".parse().unwrap();
+        let mut req = Request::builder().body(()).unwrap();
+
+        req.extensions_mut().insert(TenantRateLimitInfo {
+            tenant_id: "acme-corp".to_string(),
+            tier: TenantTier::Free,
+            impersonator: Some("sam@platform.test".to_string()),
+        });
+        req.extensions_mut().insert(ConnectInfo(addr));
+
+        let (key, limit) = limiter.get_rate_limit_key(&req);
+        assert_eq!(key, "tenant:acme-corp:impersonated:sam@platform.test");
+        assert_eq!(limit, DEFAULT_FREE_TIER_LIMIT);
+    }
+
     #[test]
     fn test_tenant_rate_limit_with_ip() {
         let limiter = RateLimiter::new(test_config(100, 1000));
@@ -641,6 +1061,7 @@ mod tests {
         req.extensions_mut().insert(TenantRateLimitInfo {
             tenant_id: "test-tenant".to_string(),
             tier: TenantTier::Free,
+            impersonator: None,
         });
         req.extensions_mut().insert(ConnectInfo(addr));
 
@@ -649,6 +1070,145 @@ mod tests {
         assert_eq!(limit, DEFAULT_FREE_TIER_LIMIT);
     }
 
+    #[test]
+    fn test_tenant_usage_sums_tenant_buckets() {
+        let limiter = RateLimiter::new(test_config(100, 1000));
+
+        let tenant_request = |tenant: &str, key: &str| {
+            let mut req = Request::builder().body(()).unwrap();
+            req.extensions_mut().insert(TenantRateLimitInfo {
+                tenant_id: tenant.to_string(),
+                tier: TenantTier::Standard,
+                impersonator: None,
+            });
+            req.extensions_mut().insert(ApiKeyId {
+                id: key.to_string(),
+            });
+            req
+        };
+
+        for _ in 0..3 {
+            let (result, _) = limiter.check_rate_limit_with_metadata(&tenant_request("acme", "k1"));
+            assert!(result.is_ok());
+        }
+        let (result, _) = limiter.check_rate_limit_with_metadata(&tenant_request("acme", "k2"));
+        assert!(result.is_ok());
+        let (result, _) = limiter.check_rate_limit_with_metadata(&tenant_request("other", "k1"));
+        assert!(result.is_ok());
+
+        let usage = limiter.tenant_usage("acme");
+        assert_eq!(usage.requests_in_window, 4);
+        assert_eq!(usage.active_clients, 2);
+        assert_eq!(usage.window_secs, limiter.config().window_secs);
+
+        let empty = limiter.tenant_usage("missing");
+        assert_eq!(empty.requests_in_window, 0);
+        assert_eq!(empty.active_clients, 0);
+    }
+
+    #[test]
+    fn test_exemptions_match_paths_and_networks() {
+        let network = IpNetwork::parse("10.0.0.0/8").unwrap();
+        assert!(network.contains("10.1.2.3".parse().unwrap()));
+        assert!(network.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!network.contains("11.0.0.1".parse().unwrap()));
+        assert!(IpNetwork::parse("fd00::/8")
+            .unwrap()
+            .contains("fd12::1".parse().unwrap()));
+        assert!(IpNetwork::parse("10.0.0.0/33").is_err());
+        assert!(IpNetwork::parse("not-an-ip").is_err());
+        let cidrs = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_networks(&cidrs(&["10.0.0.0/8"])).unwrap(),
+            vec![network]
+        );
+        assert!(parse_networks(&cidrs(&["10.0.0.0/8", "10.0.0.0/33"])).is_err());
+
+        let mut config = test_config(1, 1);
+        config.exemptions = RateLimitExemptions {
+            paths: vec!["/health".to_string(), "/metrics*".to_string()],
+            networks: vec![network],
+        };
+        let limiter = RateLimiter::new(config);
+        let request = |path: &str, peer: &str| {
+            let mut req = Request::builder().uri(path).body(()).unwrap();
+            let addr: SocketAddr = peer.parse().unwrap();
+            req.extensions_mut().insert(ConnectInfo(addr));
+            req
+        };
+
+        // Both lists must match
+        assert!(limiter.is_exempt(&request("/metrics", "10.0.0.5:9000")));
+        assert!(!limiter.is_exempt(&request("/metrics", "203.0.113.7:9000")));
+        assert!(!limiter.is_exempt(&request("/api/v1/datasets", "10.0.0.5:9000")));
+
+        assert!(limiter.is_exempt(&request("/health", "10.0.0.5:9000")));
+
+        let none = RateLimiter::new(test_config(1, 1));
+        assert!(!none.is_exempt(&request("/health", "10.0.0.5:9000")));
+    }
+
+    #[test]
+    fn test_split_rate_limit_key() {
+        assert_eq!(
+            split_rate_limit_key("tenant:acme:auth:abc123"),
+            (Some("acme".to_string()), "auth:abc123".to_string())
+        );
+        assert_eq!(
+            split_rate_limit_key("tenant:acme:ip:10.0.0.1"),
+            (Some("acme".to_string()), "ip:10.0.0.1".to_string())
+        );
+        assert_eq!(
+            split_rate_limit_key("anon:10.0.0.1"),
+            (None, "anon:10.0.0.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_violations_recorded_and_drained() {
+        let mut config = test_config(100, 1000);
+        config.free_tier_limit = 2;
+        let limiter = RateLimiter::new(config);
+
+        let tenant_request = || {
+            let mut req = Request::builder()
+                .uri("/api/v1/datasets/orders")
+                .body(())
+                .unwrap();
+            req.extensions_mut().insert(TenantRateLimitInfo {
+                tenant_id: "acme".to_string(),
+                tier: TenantTier::Free,
+                impersonator: None,
+            });
+            req.extensions_mut().insert(ApiKeyId {
+                id: "k1".to_string(),
+            });
+            req
+        };
+
+        // Free tier allows two requests, then rejects
+        for _ in 0..2 {
+            let (result, _) = limiter.check_rate_limit_with_metadata(&tenant_request());
+            assert!(result.is_ok());
+        }
+        for _ in 0..3 {
+            let (result, _) = limiter.check_rate_limit_with_metadata(&tenant_request());
+            assert!(result.is_err());
+        }
+
+        let violations = limiter.drain_violations();
+        assert_eq!(violations.len(), 1);
+        let v = &violations[0];
+        assert_eq!(v.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(v.client_key, "auth:k1");
+        assert_eq!(v.route, "/api/v1/datasets/orders");
+        assert_eq!(v.count, 3);
+        assert!(v.first_seen <= v.last_seen);
+
+        // Draining clears pending violations
+        assert!(limiter.drain_violations().is_empty());
+    }
+
     #[test]
     fn test_enterprise_tier_limit() {
         let limiter = RateLimiter::new(test_config(100, 1000));
@@ -658,6 +1218,7 @@ mod tests {
         req.extensions_mut().insert(TenantRateLimitInfo {
             tenant_id: "big-corp".to_string(),
             tier: TenantTier::Enterprise,
+            impersonator: None,
         });
         req.extensions_mut().insert(ConnectInfo(addr));
 
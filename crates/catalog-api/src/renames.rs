@@ -0,0 +1,646 @@
+//! Tag and Domain Renames
+//!
+//! Renames a tag (`pii` -> `compliance:pii`) or domain across the whole
+//! catalog in one transaction, and merges it when the new name is already in
+//! use.
+//!
+//! # References Updated
+//!
+//! - Tags: dataset tags and auto-tagging rules (`add_tags`)
+//! - Domains: the `domains` registry, dataset domains, domain ACLs, glossary
+//!   term domains, and auto-tagging rules (`set_domain`)
+//!
+//! Trashed datasets are updated too, so a restore doesn't bring the old name
+//! back. The search index follows through the schema's FTS triggers. Tenant
+//! dataset defaults live in the control plane and are not changed.
+//!
+//! Each rename is recorded in `rename_history` (migration v1.31.0) and
+//! advances the catalog version.
+//!
+//! # Endpoints
+//!
+//! - `POST /api/v1/admin/tags/rename` - Rename or merge a tag
+//! - `POST /api/v1/admin/domains/rename` - Rename or merge a domain
+//! - `GET /api/v1/admin/renames` - Recorded renames, newest first
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::envelope;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_admin_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::{increment_catalog_version, validation};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// What a rename applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenameKind {
+    Tag,
+    Domain,
+}
+
+impl RenameKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenameKind::Tag => "tag",
+            RenameKind::Domain => "domain",
+        }
+    }
+}
+
+/// Outcome of a rename
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenameResult {
+    pub kind: RenameKind,
+    pub from: String,
+    pub to: String,
+    /// The new name was already in use and the two were merged
+    pub merged: bool,
+    /// Datasets whose tags or domain changed (including trashed ones)
+    pub datasets_updated: usize,
+    /// Auto-tagging rules that referenced the old name
+    pub rules_updated: usize,
+    /// Domain ACL entries moved to the new domain (domains only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acl_entries_updated: Option<usize>,
+    /// Glossary terms moved to the new domain (domains only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub glossary_terms_updated: Option<usize>,
+}
+
+/// A recorded rename
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RenameRecord {
+    pub id: i64,
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    pub merged: bool,
+    pub datasets_updated: i64,
+    pub renamed_at: String,
+}
+
+/// Whether the catalog has the rename history table.
+pub fn has_rename_history_table(conn: &Connection) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'rename_history'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Rename tag `from` to `to` on every dataset and auto-tagging rule.
+///
+/// Datasets that already have `to` keep a single copy. Returns `None` when
+/// nothing references `from`.
+pub fn rename_tag(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+) -> metafuse_catalog_core::Result<Option<RenameResult>> {
+    let tx = conn.unchecked_transaction()?;
+
+    let merged = exists(&tx, "SELECT 1 FROM tags WHERE tag = ?1", to)?;
+    tx.execute(
+        "INSERT OR IGNORE INTO tags (dataset_id, tag)
+         SELECT dataset_id, ?2 FROM tags WHERE tag = ?1",
+        params![from, to],
+    )?;
+    let datasets_updated = tx.execute("DELETE FROM tags WHERE tag = ?1", [from])?;
+
+    let rules_updated = rewrite_rule_tags(&tx, from, to)?;
+    if datasets_updated == 0 && rules_updated == 0 {
+        return Ok(None);
+    }
+
+    record(&tx, RenameKind::Tag, from, to, merged, datasets_updated)?;
+    increment_catalog_version(&tx)?;
+    tx.commit()?;
+
+    Ok(Some(RenameResult {
+        kind: RenameKind::Tag,
+        from: from.to_string(),
+        to: to.to_string(),
+        merged,
+        datasets_updated,
+        rules_updated,
+        acl_entries_updated: None,
+        glossary_terms_updated: None,
+    }))
+}
+
+/// Rename domain `from` to `to` everywhere it is referenced.
+///
+/// When `to` is already registered, `from` is merged into it: its datasets,
+/// ACL entries and glossary terms move over and its registry entry is
+/// removed. ACL entries for a principal the target domain already lists keep
+/// the target's permission. Returns `None` when nothing references `from`.
+pub fn rename_domain(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+) -> metafuse_catalog_core::Result<Option<RenameResult>> {
+    let tx = conn.unchecked_transaction()?;
+
+    let registered = exists(&tx, "SELECT 1 FROM domains WHERE name = ?1", from)?;
+    let merged = exists(&tx, "SELECT 1 FROM domains WHERE name = ?1", to)?
+        || exists(&tx, "SELECT 1 FROM datasets WHERE domain = ?1", to)?;
+
+    if registered {
+        if exists(&tx, "SELECT 1 FROM domains WHERE name = ?1", to)? {
+            tx.execute("DELETE FROM domains WHERE name = ?1", [from])?;
+        } else {
+            tx.execute(
+                "UPDATE domains SET name = ?2, updated_at = CURRENT_TIMESTAMP WHERE name = ?1",
+                params![from, to],
+            )?;
+        }
+    }
+
+    let datasets_updated = tx.execute(
+        "UPDATE datasets SET domain = ?2 WHERE domain = ?1",
+        params![from, to],
+    )?;
+    let acl_entries_updated = tx.execute(
+        "UPDATE OR IGNORE domain_acls SET domain = ?2 WHERE domain = ?1",
+        params![from, to],
+    )?;
+    // Entries left behind clashed with the target's own entry for the principal
+    let acl_entries_dropped = tx.execute("DELETE FROM domain_acls WHERE domain = ?1", [from])?;
+    let glossary_terms_updated = tx.execute(
+        "UPDATE glossary_terms SET domain = ?2 WHERE domain = ?1",
+        params![from, to],
+    )?;
+    let rules_updated = tx.execute(
+        "UPDATE auto_tag_rules SET set_domain = ?2, updated_at = CURRENT_TIMESTAMP
+         WHERE set_domain = ?1",
+        params![from, to],
+    )?;
+
+    if !registered
+        && datasets_updated == 0
+        && acl_entries_updated + acl_entries_dropped == 0
+        && glossary_terms_updated == 0
+        && rules_updated == 0
+    {
+        return Ok(None);
+    }
+
+    record(&tx, RenameKind::Domain, from, to, merged, datasets_updated)?;
+    increment_catalog_version(&tx)?;
+    tx.commit()?;
+
+    Ok(Some(RenameResult {
+        kind: RenameKind::Domain,
+        from: from.to_string(),
+        to: to.to_string(),
+        merged,
+        datasets_updated,
+        rules_updated,
+        acl_entries_updated: Some(acl_entries_updated),
+        glossary_terms_updated: Some(glossary_terms_updated),
+    }))
+}
+
+/// Recorded renames, newest first, optionally of one kind.
+pub fn list_renames(
+    conn: &Connection,
+    kind: Option<RenameKind>,
+    limit: usize,
+    offset: usize,
+) -> rusqlite::Result<Vec<RenameRecord>> {
+    if !has_rename_history_table(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, old_name, new_name, merged, datasets_updated, renamed_at
+         FROM rename_history
+         WHERE ?1 IS NULL OR kind = ?1
+         ORDER BY renamed_at DESC, id DESC
+         LIMIT ?2 OFFSET ?3",
+    )?;
+    let records = stmt
+        .query_map(
+            params![kind.map(|k| k.as_str()), limit as i64, offset as i64],
+            |row| {
+                Ok(RenameRecord {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    from: row.get(2)?,
+                    to: row.get(3)?,
+                    merged: row.get(4)?,
+                    datasets_updated: row.get(5)?,
+                    renamed_at: row.get(6)?,
+                })
+            },
+        )?
+        .collect();
+    records
+}
+
+/// Number of recorded renames, optionally of one kind.
+pub fn count_renames(conn: &Connection, kind: Option<RenameKind>) -> rusqlite::Result<i64> {
+    if !has_rename_history_table(conn)? {
+        return Ok(0);
+    }
+    conn.query_row(
+        "SELECT COUNT(*) FROM rename_history WHERE ?1 IS NULL OR kind = ?1",
+        params![kind.map(|k| k.as_str())],
+        |row| row.get(0),
+    )
+}
+
+fn exists(conn: &Connection, sql: &str, value: &str) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(sql, [value], |_| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Replace `from` with `to` in each auto-tagging rule's `add_tags` list.
+fn rewrite_rule_tags(conn: &Connection, from: &str, to: &str) -> rusqlite::Result<usize> {
+    let rules: Vec<(i64, String)> = conn
+        .prepare("SELECT id, add_tags FROM auto_tag_rules")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut updated = 0;
+    for (id, raw) in rules {
+        let Ok(tags) = serde_json::from_str::<Vec<String>>(&raw) else {
+            continue;
+        };
+        if !tags.iter().any(|t| t == from) {
+            continue;
+        }
+        let mut renamed: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = if tag == from { to.to_string() } else { tag };
+            if !renamed.contains(&tag) {
+                renamed.push(tag);
+            }
+        }
+        conn.execute(
+            "UPDATE auto_tag_rules SET add_tags = ?2, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![id, serde_json::to_string(&renamed).unwrap_or_default()],
+        )?;
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+fn record(
+    conn: &Connection,
+    kind: RenameKind,
+    from: &str,
+    to: &str,
+    merged: bool,
+    datasets_updated: usize,
+) -> rusqlite::Result<()> {
+    if has_rename_history_table(conn)? {
+        conn.execute(
+            "INSERT INTO rename_history (kind, old_name, new_name, merged, datasets_updated)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![kind.as_str(), from, to, merged, datasets_updated as i64],
+        )?;
+    }
+    Ok(())
+}
+
+/// Request to rename a tag or domain
+#[derive(Debug, Deserialize)]
+pub(crate) struct RenameRequest {
+    from: String,
+    to: String,
+}
+
+/// Query parameters for the rename history
+#[derive(Debug, Deserialize)]
+pub(crate) struct RenameHistoryQuery {
+    kind: Option<RenameKind>,
+    #[serde(default = "default_rename_history_limit")]
+    limit: usize,
+}
+
+pub(crate) fn default_rename_history_limit() -> usize {
+    100
+}
+
+/// Rename tag `from` to `to` on every dataset, merging into `to` if in use
+pub(crate) async fn post_tag_rename(
+    state: State<AppState>,
+    request_id: Extension<RequestId>,
+    audit_context: Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<RenameRequest>,
+) -> Result<Json<RenameResult>, (StatusCode, Json<ErrorResponse>)> {
+    for tag in [&req.from, &req.to] {
+        validation::validate_tag(tag)
+            .map_err(|e| bad_request(e.to_string(), request_id.0 .0.clone()))?;
+    }
+    apply_rename(
+        state,
+        request_id,
+        audit_context,
+        tenant_backend,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        RenameKind::Tag,
+        req,
+    )
+    .await
+}
+
+/// Rename domain `from` to `to` everywhere, merging into `to` if in use
+pub(crate) async fn post_domain_rename(
+    state: State<AppState>,
+    request_id: Extension<RequestId>,
+    audit_context: Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<RenameRequest>,
+) -> Result<Json<RenameResult>, (StatusCode, Json<ErrorResponse>)> {
+    for domain in [&req.from, &req.to] {
+        validation::validate_identifier(domain, "domain")
+            .map_err(|e| bad_request(e.to_string(), request_id.0 .0.clone()))?;
+    }
+    apply_rename(
+        state,
+        request_id,
+        audit_context,
+        tenant_backend,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        RenameKind::Domain,
+        req,
+    )
+    .await
+}
+
+pub(crate) async fn apply_rename(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    kind: RenameKind,
+    req: RenameRequest,
+) -> Result<Json<RenameResult>, (StatusCode, Json<ErrorResponse>)> {
+    // Renames rewrite references across the whole catalog, so they are admin-only
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    if req.from == req.to {
+        return Err(bad_request(
+            format!("Cannot rename {} '{}' to itself", kind.as_str(), req.from),
+            request_id.0.clone(),
+        ));
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let result = match kind {
+        RenameKind::Tag => rename_tag(&conn, &req.from, &req.to),
+        RenameKind::Domain => rename_domain(&conn, &req.from, &req.to),
+    }
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    .ok_or_else(|| {
+        not_found(
+            format!("No references to {} '{}' found", kind.as_str(), req.from),
+            request_id.0.clone(),
+        )
+    })?;
+
+    tracing::info!(
+        kind = kind.as_str(),
+        from = %req.from,
+        to = %req.to,
+        merged = result.merged,
+        datasets_updated = result.datasets_updated,
+        "Renamed across catalog"
+    );
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            kind.as_str(),
+            &req.from,
+            serde_json::json!({ "name": req.from }),
+            serde_json::to_value(&result).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(result))
+}
+
+/// Recorded tag and domain renames, newest first
+pub(crate) async fn get_renames(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Query(query): Query<RenameHistoryQuery>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<RenameRecord>>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let (limit, offset) = envelope.bounds(query.limit.min(1000));
+    let records = list_renames(&conn, query.kind, limit, offset)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let total = envelope
+        .total(|| count_renames(&conn, query.kind))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.window(records, total)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        for (name, domain) in [
+            ("orders", "sales"),
+            ("customers", "crm"),
+            ("events", "sales"),
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, domain, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', ?2, datetime('now'), datetime('now'))",
+                [name, domain],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO tags (dataset_id, tag) VALUES (1, 'pii'), (2, 'pii'), (2, 'compliance:pii'), (3, 'raw');
+             UPDATE datasets SET deleted_at = datetime('now') WHERE name = 'events';
+             INSERT INTO domains (name, display_name) VALUES ('sales', 'Sales'), ('commerce', 'Commerce');
+             INSERT INTO domain_acls (domain, principal, permission)
+             VALUES ('sales', 'group:analysts', 'read'), ('sales', 'group:finance', 'write'),
+                    ('commerce', 'group:finance', 'read');
+             INSERT INTO glossary_terms (term, domain) VALUES ('Revenue', 'sales');
+             INSERT INTO auto_tag_rules (name, path_pattern, set_domain, add_tags)
+             VALUES ('emails', '/data*', 'sales', '[\"pii\",\"compliance:pii\",\"raw\"]');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn tags(conn: &Connection, dataset_id: i64) -> Vec<String> {
+        conn.prepare("SELECT tag FROM tags WHERE dataset_id = ?1 ORDER BY tag")
+            .unwrap()
+            .query_map([dataset_id], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    fn query_string(conn: &Connection, sql: &str) -> String {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_rename_tag_merges_and_reindexes() {
+        let conn = setup_db();
+        let result = rename_tag(&conn, "pii", "compliance:pii").unwrap().unwrap();
+        assert!(result.merged);
+        assert_eq!(result.datasets_updated, 2);
+        assert_eq!(result.rules_updated, 1);
+
+        assert_eq!(tags(&conn, 1), vec!["compliance:pii"]);
+        assert_eq!(tags(&conn, 2), vec!["compliance:pii"]);
+        assert_eq!(
+            query_string(&conn, "SELECT add_tags FROM auto_tag_rules"),
+            r#"["compliance:pii","raw"]"#
+        );
+        assert_eq!(
+            query_string(
+                &conn,
+                "SELECT tags FROM dataset_search WHERE dataset_name = 'orders'"
+            ),
+            "compliance:pii"
+        );
+
+        let history = list_renames(&conn, Some(RenameKind::Tag), 10, 0).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            (history[0].from.as_str(), history[0].to.as_str()),
+            ("pii", "compliance:pii")
+        );
+        assert!(history[0].merged);
+
+        // Nothing references the old name any more
+        assert!(rename_tag(&conn, "pii", "other").unwrap().is_none());
+        assert_eq!(list_renames(&conn, None, 10, 0).unwrap().len(), 1);
+        assert_eq!(count_renames(&conn, None).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rename_domain() {
+        let conn = setup_db();
+        let result = rename_domain(&conn, "sales", "revenue").unwrap().unwrap();
+        assert!(!result.merged);
+        // Includes the trashed dataset
+        assert_eq!(result.datasets_updated, 2);
+        assert_eq!(result.acl_entries_updated, Some(2));
+        assert_eq!(result.glossary_terms_updated, Some(1));
+        assert_eq!(result.rules_updated, 1);
+
+        assert_eq!(
+            query_string(
+                &conn,
+                "SELECT group_concat(name, ',') FROM (SELECT name FROM domains ORDER BY name)"
+            ),
+            "commerce,revenue"
+        );
+        assert_eq!(
+            query_string(
+                &conn,
+                "SELECT domain FROM dataset_search WHERE dataset_name = 'orders'"
+            ),
+            "revenue"
+        );
+        assert_eq!(
+            query_string(&conn, "SELECT set_domain FROM auto_tag_rules"),
+            "revenue"
+        );
+        assert_eq!(
+            query_string(&conn, "SELECT domain FROM glossary_terms"),
+            "revenue"
+        );
+    }
+
+    #[test]
+    fn test_merge_domain_keeps_target_acl() {
+        let conn = setup_db();
+        let result = rename_domain(&conn, "sales", "commerce").unwrap().unwrap();
+        assert!(result.merged);
+        assert_eq!(result.acl_entries_updated, Some(1));
+
+        assert_eq!(
+            query_string(&conn, "SELECT group_concat(name, ',') FROM domains"),
+            "commerce"
+        );
+        let acls: Vec<(String, String)> = conn
+            .prepare("SELECT principal, permission FROM domain_acls WHERE domain = 'commerce' ORDER BY principal")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            acls,
+            vec![
+                ("group:analysts".to_string(), "read".to_string()),
+                ("group:finance".to_string(), "read".to_string())
+            ]
+        );
+        let leftover: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM domain_acls WHERE domain = 'sales'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(leftover, 0);
+
+        assert!(rename_domain(&conn, "unknown", "commerce")
+            .unwrap()
+            .is_none());
+    }
+}
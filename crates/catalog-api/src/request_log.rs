@@ -0,0 +1,726 @@
+//! Request Logs
+//!
+//! API request logs (route, status, latency, tenant) kept queryable in the
+//! catalog for self-service debugging, instead of only in stdout.
+//!
+//! # Architecture
+//!
+//! [`request_log_middleware`] records each routed request into a
+//! [`RequestLogSink`], an in-memory buffer. [`request_log_flush_task`] writes
+//! the buffer to the `request_logs` table (migration v1.47.0) in batches:
+//! requests of the default catalog are written to it under
+//! [`DEFAULT_TENANT`](crate::usage_analytics::DEFAULT_TENANT), and each
+//! tenant's requests to that tenant's catalog, like usage analytics.
+//! Requests rejected before routing (authentication, rate limits) are not
+//! logged.
+//!
+//! # Sampling
+//!
+//! Every error (status 400 and above) is logged. Other requests are logged at
+//! the sample rate, decided from the request ID so a request is either logged
+//! or not. When the buffer is full, new requests are dropped until the next
+//! flush and counted in the flush log.
+//!
+//! # Retention
+//!
+//! Each flush prunes rows older than the retention window from the catalogs
+//! it writes to, at most once per purge interval per catalog.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_REQUEST_LOG`: `true` to log requests (default: `false`)
+//! - `METAFUSE_REQUEST_LOG_SAMPLE_RATE`: fraction of successful requests logged (default: 1.0)
+//! - `METAFUSE_REQUEST_LOG_RETENTION_DAYS`: days requests are kept (default: 30, 0 = forever)
+//! - `METAFUSE_REQUEST_LOG_EXCLUDED_ROUTES`: comma-separated routes never logged (default: `/health,/metrics`)
+//! - `METAFUSE_REQUEST_LOG_FLUSH_INTERVAL_SECS`: seconds between flushes (default: 10)
+//! - `METAFUSE_REQUEST_LOG_BUFFER_SIZE`: requests held between flushes (default: 10000)
+//!
+//! # Endpoints
+//!
+//! - `GET /api/v1/admin/requests` - Filter logged requests across catalogs
+
+use crate::server::RequestId;
+#[cfg(feature = "api-keys")]
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse};
+#[cfg(feature = "api-keys")]
+use crate::usage_analytics::usage_catalogs;
+use crate::usage_analytics::DEFAULT_TENANT;
+#[cfg(feature = "api-keys")]
+use axum::extract::{Query, State};
+#[cfg(feature = "api-keys")]
+use axum::http::StatusCode;
+#[cfg(feature = "api-keys")]
+use axum::Json;
+use axum::{
+    extract::{Extension, MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+/// Default days a logged request is kept
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Default seconds between flushes of the buffer
+pub const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 10;
+
+/// Default number of requests held between flushes
+pub const DEFAULT_BUFFER_SIZE: usize = 10_000;
+
+/// Seconds between retention purges of one catalog
+const PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Default number of requests returned by a query
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Maximum number of requests returned by a query
+pub const MAX_QUERY_LIMIT: usize = 1000;
+
+/// Request log configuration
+#[derive(Debug, Clone)]
+pub struct RequestLogConfig {
+    pub enabled: bool,
+    /// Fraction of successful requests logged, from 0.0 to 1.0
+    pub sample_rate: f64,
+    /// Days before a logged request is pruned; 0 keeps them forever
+    pub retention_days: u32,
+    /// Route templates (or paths) never logged
+    pub excluded_routes: Vec<String>,
+    pub flush_interval_secs: u64,
+    pub buffer_size: usize,
+}
+
+impl Default for RequestLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: 1.0,
+            retention_days: DEFAULT_RETENTION_DAYS,
+            excluded_routes: vec!["/health".to_string(), "/metrics".to_string()],
+            flush_interval_secs: DEFAULT_FLUSH_INTERVAL_SECS,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+        }
+    }
+}
+
+impl RequestLogConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string());
+        Self {
+            enabled: var("METAFUSE_REQUEST_LOG")
+                .map(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes"))
+                .unwrap_or(defaults.enabled),
+            sample_rate: var("METAFUSE_REQUEST_LOG_SAMPLE_RATE")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|rate| rate.is_finite())
+                .map(|rate| rate.clamp(0.0, 1.0))
+                .unwrap_or(defaults.sample_rate),
+            retention_days: var("METAFUSE_REQUEST_LOG_RETENTION_DAYS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.retention_days),
+            excluded_routes: var("METAFUSE_REQUEST_LOG_EXCLUDED_ROUTES")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|r| !r.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or(defaults.excluded_routes),
+            flush_interval_secs: var("METAFUSE_REQUEST_LOG_FLUSH_INTERVAL_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.flush_interval_secs),
+            buffer_size: var("METAFUSE_REQUEST_LOG_BUFFER_SIZE")
+                .and_then(|v| v.parse().ok())
+                .filter(|size| *size > 0)
+                .unwrap_or(defaults.buffer_size),
+        }
+    }
+
+    /// Whether a request with this ID and status is logged
+    pub fn sampled(&self, request_id: &str, status: u16) -> bool {
+        if status >= 400 || self.sample_rate >= 1.0 {
+            return true;
+        }
+        let mut hasher = DefaultHasher::new();
+        request_id.hash(&mut hasher);
+        (hasher.finish() as f64 / u64::MAX as f64) < self.sample_rate
+    }
+}
+
+/// One logged request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestLogEntry {
+    pub request_id: String,
+    pub tenant_id: String,
+    pub method: String,
+    /// Route template, e.g. `/api/v1/datasets/{name}`
+    pub route: String,
+    pub path: String,
+    pub status: u16,
+    pub latency_ms: u64,
+    /// When the request completed
+    pub created_at: String,
+}
+
+/// Buffer of requests waiting to be written to the catalogs
+pub struct RequestLogSink {
+    config: RequestLogConfig,
+    buffer: Mutex<Vec<RequestLogEntry>>,
+    /// Requests dropped because the buffer was full, since the last flush
+    dropped: AtomicU64,
+    /// When each catalog was last purged
+    last_purge: Mutex<HashMap<String, Instant>>,
+}
+
+impl RequestLogSink {
+    pub fn new(config: RequestLogConfig) -> Self {
+        Self {
+            config,
+            buffer: Mutex::new(Vec::new()),
+            dropped: AtomicU64::new(0),
+            last_purge: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &RequestLogConfig {
+        &self.config
+    }
+
+    /// Whether requests to `route` are never logged
+    pub fn excludes(&self, route: &str) -> bool {
+        self.config.excluded_routes.iter().any(|r| r == route)
+    }
+
+    /// Buffer a request if it is sampled
+    pub fn record(&self, entry: RequestLogEntry) {
+        if !self.config.sampled(&entry.request_id, entry.status) {
+            return;
+        }
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        if buffer.len() >= self.config.buffer_size {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        buffer.push(entry);
+    }
+
+    /// Take the buffered requests, grouped by tenant
+    fn take(&self) -> BTreeMap<String, Vec<RequestLogEntry>> {
+        let entries = std::mem::take(&mut *self.buffer.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut by_tenant: BTreeMap<String, Vec<RequestLogEntry>> = BTreeMap::new();
+        for entry in entries {
+            by_tenant
+                .entry(entry.tenant_id.clone())
+                .or_default()
+                .push(entry);
+        }
+        by_tenant
+    }
+
+    /// Put back requests whose write failed, as far as the buffer has room
+    fn restore(&self, entries: Vec<RequestLogEntry>) {
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        let room = self.config.buffer_size.saturating_sub(buffer.len());
+        let lost = entries.len().saturating_sub(room);
+        buffer.extend(entries.into_iter().take(room));
+        self.dropped.fetch_add(lost as u64, Ordering::Relaxed);
+    }
+
+    /// Whether a catalog is due for a retention purge, marking it purged
+    fn purge_due(&self, tenant_id: &str) -> bool {
+        if self.config.retention_days == 0 {
+            return false;
+        }
+        let mut last_purge = self.last_purge.lock().unwrap_or_else(|e| e.into_inner());
+        let due = last_purge
+            .get(tenant_id)
+            .is_none_or(|at| at.elapsed() >= Duration::from_secs(PURGE_INTERVAL_SECS));
+        if due {
+            last_purge.insert(tenant_id.to_string(), Instant::now());
+        }
+        due
+    }
+}
+
+/// Middleware that records each request into the [`RequestLogSink`]
+///
+/// Must run inside the request ID middleware, and inside tenant resolution so
+/// requests are attributed to their tenant.
+pub async fn request_log_middleware(
+    Extension(sink): Extension<Arc<RequestLogSink>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    if sink.excludes(&route) {
+        return next.run(req).await;
+    }
+
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+    #[cfg(feature = "api-keys")]
+    let tenant_id = req
+        .extensions()
+        .get::<crate::tenant_resolver::ResolvedTenant>()
+        .map(|t| t.tenant_id().to_string())
+        .unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = DEFAULT_TENANT.to_string();
+
+    let response = next.run(req).await;
+    sink.record(RequestLogEntry {
+        request_id,
+        tenant_id,
+        method,
+        route,
+        path,
+        status: response.status().as_u16(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    });
+    response
+}
+
+/// Write requests to a catalog in one transaction.
+pub fn write_entries(conn: &Connection, entries: &[RequestLogEntry]) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO request_logs
+             (request_id, tenant_id, method, route, path, status, latency_ms, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for entry in entries {
+            stmt.execute(params![
+                entry.request_id,
+                entry.tenant_id,
+                entry.method,
+                entry.route,
+                entry.path,
+                entry.status,
+                entry.latency_ms as i64,
+                entry.created_at,
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(entries.len())
+}
+
+/// Delete requests logged more than `retention_days` ago. Returns how many
+/// were deleted.
+pub fn purge_expired(conn: &Connection, retention_days: u32) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM request_logs WHERE created_at <= datetime('now', '-' || ?1 || ' days')",
+        [retention_days],
+    )
+}
+
+/// Filters of `GET /api/v1/admin/requests`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestLogQuery {
+    /// Only this tenant (`default` for the default catalog); all when unset
+    pub tenant_id: Option<String>,
+    pub request_id: Option<String>,
+    pub method: Option<String>,
+    /// Route template, e.g. `/api/v1/datasets/{name}`
+    pub route: Option<String>,
+    /// Path prefix, e.g. `/api/v1/datasets/orders`
+    pub path: Option<String>,
+    pub status: Option<u16>,
+    pub min_status: Option<u16>,
+    pub max_status: Option<u16>,
+    pub min_latency_ms: Option<u64>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`
+    pub since: Option<String>,
+    /// RFC 3339 timestamp or `YYYY-MM-DD`
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl RequestLogQuery {
+    /// Number of requests to return
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT)
+    }
+
+    /// Check the filters and normalize `since` and `until`.
+    pub fn validate(mut self) -> Result<Self, String> {
+        if let Some(since) = &self.since {
+            self.since = Some(crate::dataset_list::normalize_bound("since", since)?);
+        }
+        if let Some(until) = &self.until {
+            self.until = Some(crate::dataset_list::normalize_bound("until", until)?);
+        }
+        if let (Some(min), Some(max)) = (self.min_status, self.max_status) {
+            if min > max {
+                return Err(format!(
+                    "min_status {} is greater than max_status {}",
+                    min, max
+                ));
+            }
+        }
+        self.method = self.method.map(|m| m.to_uppercase());
+        Ok(self)
+    }
+}
+
+/// Logged requests of one catalog matching the filters, newest first.
+pub fn query(
+    conn: &Connection,
+    filter: &RequestLogQuery,
+) -> rusqlite::Result<Vec<RequestLogEntry>> {
+    let created_at = "strftime('%Y-%m-%dT%H:%M:%SZ', created_at)";
+    let mut sql =
+        "SELECT request_id, tenant_id, method, route, path, status, latency_ms, created_at
+                   FROM request_logs WHERE 1=1"
+            .to_string();
+    let mut args: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut push = |clause: String, value: Box<dyn rusqlite::ToSql>| {
+        args.push(value);
+        sql.push_str(&format!(" AND {} ?{}", clause, args.len()));
+    };
+    if let Some(tenant_id) = &filter.tenant_id {
+        push("tenant_id =".to_string(), Box::new(tenant_id.clone()));
+    }
+    if let Some(request_id) = &filter.request_id {
+        push("request_id =".to_string(), Box::new(request_id.clone()));
+    }
+    if let Some(method) = &filter.method {
+        push("method =".to_string(), Box::new(method.clone()));
+    }
+    if let Some(route) = &filter.route {
+        push("route =".to_string(), Box::new(route.clone()));
+    }
+    if let Some(path) = &filter.path {
+        push(
+            format!("substr(path, 1, {}) =", path.chars().count()),
+            Box::new(path.clone()),
+        );
+    }
+    if let Some(status) = filter.status {
+        push("status =".to_string(), Box::new(status));
+    }
+    if let Some(min_status) = filter.min_status {
+        push("status >=".to_string(), Box::new(min_status));
+    }
+    if let Some(max_status) = filter.max_status {
+        push("status <=".to_string(), Box::new(max_status));
+    }
+    if let Some(min_latency_ms) = filter.min_latency_ms {
+        push("latency_ms >=".to_string(), Box::new(min_latency_ms as i64));
+    }
+    if let Some(since) = &filter.since {
+        push(format!("{} >=", created_at), Box::new(since.clone()));
+    }
+    if let Some(until) = &filter.until {
+        push(format!("{} <", created_at), Box::new(until.clone()));
+    }
+    sql.push_str(&format!(
+        " ORDER BY created_at DESC, id DESC LIMIT {}",
+        filter.limit()
+    ));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(
+        rusqlite::params_from_iter(args.iter().map(|a| a.as_ref())),
+        |row| {
+            Ok(RequestLogEntry {
+                request_id: row.get(0)?,
+                tenant_id: row.get(1)?,
+                method: row.get(2)?,
+                route: row.get(3)?,
+                path: row.get(4)?,
+                status: row.get(5)?,
+                latency_ms: row.get::<_, i64>(6)? as u64,
+                created_at: row.get(7)?,
+            })
+        },
+    )?;
+    rows.collect()
+}
+
+/// Background task that periodically writes buffered requests to the catalogs
+/// and prunes expired ones
+///
+/// Requests of the default catalog are written to `backend`; each tenant's
+/// requests to the catalog `tenants` resolves for it.
+pub async fn request_log_flush_task(
+    sink: Arc<RequestLogSink>,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+    tenants: Option<Arc<metafuse_catalog_storage::TenantBackendFactory>>,
+) {
+    let interval = Duration::from_secs(sink.config.flush_interval_secs);
+
+    info!(
+        interval_secs = sink.config.flush_interval_secs,
+        sample_rate = sink.config.sample_rate,
+        retention_days = sink.config.retention_days,
+        "Request log flush task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let dropped = sink.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            warn!(dropped, "Request log buffer full, requests were not logged");
+        }
+
+        for (tenant_id, entries) in sink.take() {
+            let tenant_backend = if tenant_id == DEFAULT_TENANT {
+                Arc::clone(&backend)
+            } else {
+                let Some(factory) = &tenants else {
+                    warn!(
+                        tenant_id,
+                        "No tenant catalogs configured, requests not logged"
+                    );
+                    continue;
+                };
+                match factory.get_backend_by_id(&tenant_id).await {
+                    Ok(tenant_backend) => tenant_backend,
+                    Err(e) => {
+                        warn!(tenant_id, error = %e, "Failed to resolve tenant catalog for request log");
+                        sink.restore(entries);
+                        continue;
+                    }
+                }
+            };
+
+            let conn = match tenant_backend.get_connection().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    error!(tenant_id, error = %e, "Failed to get connection for request log");
+                    sink.restore(entries);
+                    continue;
+                }
+            };
+            let purge = sink.purge_due(&tenant_id);
+            let retention_days = sink.config.retention_days;
+            let result = tokio::task::spawn_blocking(move || {
+                let written = write_entries(&conn, &entries).map_err(|e| (e, entries))?;
+                let purged = if purge {
+                    purge_expired(&conn, retention_days).unwrap_or_else(|e| {
+                        error!(error = %e, "Failed to purge request logs");
+                        0
+                    })
+                } else {
+                    0
+                };
+                Ok::<_, (rusqlite::Error, Vec<RequestLogEntry>)>((written, purged))
+            })
+            .await;
+
+            match result {
+                Ok(Ok((written, purged))) => {
+                    debug!(tenant_id, written, "Flushed request logs");
+                    if purged > 0 {
+                        info!(tenant_id, count = purged, "Purged expired request logs");
+                    }
+                }
+                Ok(Err((e, entries))) => {
+                    error!(tenant_id, error = %e, "Failed to write request logs");
+                    sink.restore(entries);
+                }
+                Err(e) => error!(tenant_id, error = %e, "Request log flush task panicked"),
+            }
+        }
+    }
+}
+
+/// Response of the request log query
+#[cfg(feature = "api-keys")]
+#[derive(Debug, Serialize)]
+pub(crate) struct RequestLogResponse {
+    requests: Vec<RequestLogEntry>,
+}
+
+/// Logged API requests across all tenants, newest first (admin endpoint)
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_list_requests(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(params): Query<RequestLogQuery>,
+) -> Result<Json<RequestLogResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let filter = params
+        .validate()
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let catalogs = usage_catalogs(&state, filter.tenant_id.as_deref(), &request_id.0).await?;
+
+    let mut requests = Vec::new();
+    for (tenant_id, backend) in catalogs {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        // Only the catalog's own tenant, in case tenants share a database
+        let filter = RequestLogQuery {
+            tenant_id: Some(tenant_id),
+            ..filter.clone()
+        };
+        let result = tokio::task::spawn_blocking(move || query(&conn, &filter))
+            .await
+            .map_err(|e| internal_error(format!("Task join error: {}", e), request_id.0.clone()))?
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        requests.extend(result);
+    }
+
+    requests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    requests.truncate(filter.limit());
+
+    Ok(Json(RequestLogResponse { requests }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn entry(request_id: &str, route: &str, status: u16, latency_ms: u64) -> RequestLogEntry {
+        RequestLogEntry {
+            request_id: request_id.to_string(),
+            tenant_id: DEFAULT_TENANT.to_string(),
+            method: "GET".to_string(),
+            route: route.to_string(),
+            path: route.replace("{name}", "orders"),
+            status,
+            latency_ms,
+            created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sampling_keeps_errors() {
+        let sink = RequestLogSink::new(RequestLogConfig {
+            enabled: true,
+            sample_rate: 0.0,
+            buffer_size: 2,
+            ..Default::default()
+        });
+        sink.record(entry("r1", "/api/v1/datasets", 200, 5));
+        sink.record(entry("r2", "/api/v1/datasets", 404, 5));
+        sink.record(entry("r3", "/api/v1/datasets", 500, 5));
+        sink.record(entry("r4", "/api/v1/datasets", 503, 5));
+
+        let taken = sink.take();
+        let ids: Vec<&str> = taken[DEFAULT_TENANT]
+            .iter()
+            .map(|e| e.request_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["r2", "r3"]);
+        assert_eq!(sink.dropped.load(Ordering::Relaxed), 1);
+        assert!(sink.excludes("/health"));
+
+        // Sampling is decided by request ID
+        let config = RequestLogConfig {
+            sample_rate: 0.5,
+            ..Default::default()
+        };
+        let sampled = (0..1000)
+            .filter(|i| config.sampled(&format!("request-{}", i), 200))
+            .count();
+        assert!((350..650).contains(&sampled), "sampled {}", sampled);
+        assert_eq!(
+            config.sampled("request-1", 200),
+            config.sampled("request-1", 200)
+        );
+    }
+
+    #[test]
+    fn test_write_query_and_purge() {
+        let conn = setup_db();
+        let mut old = entry("r0", "/api/v1/datasets", 200, 3);
+        old.created_at = "2020-01-01 00:00:00".to_string();
+        write_entries(
+            &conn,
+            &[
+                old,
+                entry("r1", "/api/v1/datasets/{name}", 200, 40),
+                entry("r2", "/api/v1/datasets/{name}", 404, 2),
+                entry("r3", "/api/v1/search", 500, 900),
+            ],
+        )
+        .unwrap();
+
+        let ids = |filter: RequestLogQuery| -> Vec<String> {
+            query(&conn, &filter.validate().unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|e| e.request_id)
+                .collect()
+        };
+        assert_eq!(
+            ids(RequestLogQuery::default()),
+            vec!["r3", "r2", "r1", "r0"]
+        );
+        assert_eq!(
+            ids(RequestLogQuery {
+                route: Some("/api/v1/datasets/{name}".to_string()),
+                min_status: Some(400),
+                ..Default::default()
+            }),
+            vec!["r2"]
+        );
+        assert_eq!(
+            ids(RequestLogQuery {
+                path: Some("/api/v1/datasets/ord".to_string()),
+                ..Default::default()
+            }),
+            vec!["r2", "r1"]
+        );
+        assert_eq!(
+            ids(RequestLogQuery {
+                min_latency_ms: Some(100),
+                method: Some("get".to_string()),
+                ..Default::default()
+            }),
+            vec!["r3"]
+        );
+        assert_eq!(
+            ids(RequestLogQuery {
+                until: Some("2021-01-01".to_string()),
+                ..Default::default()
+            }),
+            vec!["r0"]
+        );
+        assert!(RequestLogQuery {
+            since: Some("yesterday".to_string()),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+
+        assert_eq!(purge_expired(&conn, 30).unwrap(), 1);
+        assert_eq!(ids(RequestLogQuery::default()).len(), 3);
+    }
+}
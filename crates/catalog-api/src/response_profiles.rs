@@ -0,0 +1,259 @@
+//! Response Profiles
+//!
+//! `?profile=<name>` selects a server-side bundle of response options, so
+//! clients ask for a shape instead of hard-coding include and field lists:
+//!
+//! - `include`: expands `?include=` on `GET /api/v1/datasets/{name}`
+//! - `fields`: expands `?fields=` on `GET /api/v1/datasets` and `GET /api/v1/search`
+//!
+//! An explicit `?include=` or `?fields=` on the request wins over the profile.
+//!
+//! # Built-in Profiles
+//!
+//! | Profile | include | fields |
+//! |---------|---------|--------|
+//! | `minimal` | none | `id,uuid,name,format,domain,owner,last_updated` |
+//! | `ui` | `quality,lineage` | all |
+//! | `governance` | `quality,lineage` | `id,uuid,name,tenant,domain,owner,description,created_at,last_updated,freshness,metadata_completeness` |
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_RESPONSE_PROFILES`: JSON object of profiles that add to or
+//!   replace the built-ins, e.g. `{"pipeline": {"fields": ["name", "path", "format"]}}`
+//! - `METAFUSE_DEFAULT_RESPONSE_PROFILE`: profile applied to requests that
+//!   name none (default: none)
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Response options bundled under a profile name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseProfile {
+    /// Detail includes, as in `?include=`
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// List and search fields, as in `?fields=`; `None` keeps all fields
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+}
+
+/// Effective query options after applying a profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedOptions {
+    pub include: Option<String>,
+    pub fields: Option<String>,
+}
+
+/// The configured profiles.
+#[derive(Debug, Clone)]
+pub struct ResponseProfiles {
+    profiles: BTreeMap<String, ResponseProfile>,
+    default_profile: Option<String>,
+}
+
+impl Default for ResponseProfiles {
+    fn default() -> Self {
+        let profile = |include: &[&str], fields: Option<&[&str]>| ResponseProfile {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            fields: fields.map(|f| f.iter().map(|s| s.to_string()).collect()),
+        };
+        let profiles = BTreeMap::from([
+            (
+                "minimal".to_string(),
+                profile(
+                    &[],
+                    Some(&[
+                        "id",
+                        "uuid",
+                        "name",
+                        "format",
+                        "domain",
+                        "owner",
+                        "last_updated",
+                    ]),
+                ),
+            ),
+            ("ui".to_string(), profile(&["quality", "lineage"], None)),
+            (
+                "governance".to_string(),
+                profile(
+                    &["quality", "lineage"],
+                    Some(&[
+                        "id",
+                        "uuid",
+                        "name",
+                        "tenant",
+                        "domain",
+                        "owner",
+                        "description",
+                        "created_at",
+                        "last_updated",
+                        "freshness",
+                        "metadata_completeness",
+                    ]),
+                ),
+            ),
+        ]);
+        Self {
+            profiles,
+            default_profile: None,
+        }
+    }
+}
+
+impl ResponseProfiles {
+    /// Create profiles from environment variables, validating every profile
+    /// against the known include values and dataset fields.
+    pub fn from_env(valid_includes: &[&str], valid_fields: &[&str]) -> Result<Self, String> {
+        let mut profiles = Self::default();
+        if let Ok(json) = std::env::var("METAFUSE_RESPONSE_PROFILES") {
+            let custom: BTreeMap<String, ResponseProfile> = serde_json::from_str(&json)
+                .map_err(|e| format!("Invalid METAFUSE_RESPONSE_PROFILES: {}", e))?;
+            profiles.profiles.extend(custom);
+        }
+        if let Ok(name) = std::env::var("METAFUSE_DEFAULT_RESPONSE_PROFILE") {
+            let name = name.trim();
+            if !name.is_empty() {
+                profiles.default_profile = Some(name.to_string());
+            }
+        }
+        profiles.validate(valid_includes, valid_fields)?;
+        Ok(profiles)
+    }
+
+    /// Set the profile applied to requests that name none.
+    pub fn with_default_profile(mut self, name: Option<&str>) -> Self {
+        self.default_profile = name.map(str::to_string);
+        self
+    }
+
+    /// Check that every profile uses known values and the default exists.
+    pub fn validate(&self, valid_includes: &[&str], valid_fields: &[&str]) -> Result<(), String> {
+        for (name, profile) in &self.profiles {
+            if let Some(include) = profile
+                .include
+                .iter()
+                .find(|i| !valid_includes.contains(&i.as_str()))
+            {
+                return Err(format!(
+                    "Response profile '{}' has unknown include '{}'",
+                    name, include
+                ));
+            }
+            if let Some(field) = profile
+                .fields
+                .iter()
+                .flatten()
+                .find(|f| !valid_fields.contains(&f.as_str()))
+            {
+                return Err(format!(
+                    "Response profile '{}' has unknown field '{}'",
+                    name, field
+                ));
+            }
+        }
+        match &self.default_profile {
+            Some(name) if !self.profiles.contains_key(name) => Err(format!(
+                "Default response profile '{}' is not defined",
+                name
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Names of the configured profiles.
+    pub fn names(&self) -> Vec<&str> {
+        self.profiles.keys().map(String::as_str).collect()
+    }
+
+    /// Apply the requested (or default) profile to the request's explicit
+    /// `include` and `fields`, which take precedence.
+    pub fn resolve(
+        &self,
+        profile: Option<&str>,
+        include: Option<&str>,
+        fields: Option<&str>,
+    ) -> Result<ResolvedOptions, String> {
+        let profile = match profile.or(self.default_profile.as_deref()) {
+            None => None,
+            Some(name) => Some(self.profiles.get(name).ok_or_else(|| {
+                format!(
+                    "Unknown profile '{}'. Valid profiles: {}",
+                    name,
+                    self.names().join(", ")
+                )
+            })?),
+        };
+        Ok(ResolvedOptions {
+            include: include
+                .map(str::to_string)
+                .or_else(|| profile.map(|p| p.include.join(","))),
+            fields: fields
+                .map(str::to_string)
+                .or_else(|| profile.and_then(|p| p.fields.as_ref()).map(|f| f.join(","))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INCLUDES: &[&str] = &["delta", "quality", "lineage"];
+
+    #[test]
+    fn test_resolve_profiles() {
+        let profiles = ResponseProfiles::default();
+        profiles
+            .validate(INCLUDES, crate::sparse_fields::DATASET_FIELDS)
+            .unwrap();
+
+        let ui = profiles.resolve(Some("ui"), None, None).unwrap();
+        assert_eq!(ui.include.as_deref(), Some("quality,lineage"));
+        assert_eq!(ui.fields, None);
+        let minimal = profiles.resolve(Some("minimal"), None, None).unwrap();
+        assert_eq!(minimal.include.as_deref(), Some(""));
+        assert!(minimal.fields.unwrap().starts_with("id,uuid,name"));
+
+        // Explicit options win over the profile
+        let explicit = profiles
+            .resolve(Some("ui"), Some("delta"), Some("name"))
+            .unwrap();
+        assert_eq!(explicit.include.as_deref(), Some("delta"));
+        assert_eq!(explicit.fields.as_deref(), Some("name"));
+
+        assert_eq!(
+            profiles.resolve(None, None, None).unwrap(),
+            ResolvedOptions::default()
+        );
+        let err = profiles.resolve(Some("bi"), None, None).unwrap_err();
+        assert!(err.contains("governance, minimal, ui"));
+    }
+
+    #[test]
+    fn test_default_and_custom_profiles() {
+        let mut profiles = ResponseProfiles::default().with_default_profile(Some("pipeline"));
+        assert!(profiles
+            .validate(INCLUDES, crate::sparse_fields::DATASET_FIELDS)
+            .is_err());
+
+        let custom: BTreeMap<String, ResponseProfile> =
+            serde_json::from_str(r#"{"pipeline": {"fields": ["name", "path"]}}"#).unwrap();
+        profiles.profiles.extend(custom);
+        profiles
+            .validate(INCLUDES, crate::sparse_fields::DATASET_FIELDS)
+            .unwrap();
+        let resolved = profiles.resolve(None, None, None).unwrap();
+        assert_eq!(resolved.fields.as_deref(), Some("name,path"));
+        assert_eq!(resolved.include.as_deref(), Some(""));
+
+        let custom: BTreeMap<String, ResponseProfile> =
+            serde_json::from_str(r#"{"pipeline": {"include": ["schema"]}}"#).unwrap();
+        profiles.profiles.extend(custom);
+        let err = profiles
+            .validate(INCLUDES, crate::sparse_fields::DATASET_FIELDS)
+            .unwrap_err();
+        assert!(err.contains("unknown include 'schema'"));
+    }
+}
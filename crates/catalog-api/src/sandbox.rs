@@ -0,0 +1,489 @@
+//! Sandbox Namespaces Module
+//!
+//! Ephemeral namespaces that let CI runs register test datasets without
+//! polluting the real catalog.
+//!
+//! # Architecture
+//!
+//! A sandbox is a namespace registered with a `ttl_secs`; its `expires_at` is
+//! stored on the `namespaces` row (migration v1.38.0). A request selects a
+//! sandbox with the `X-MetaFuse-Sandbox` header, or through a tenant API key
+//! created with a `sandbox` attribute, which pins every request made with the
+//! key to that sandbox.
+//!
+//! In a sandboxed request:
+//! - created datasets are qualified with the sandbox name, and their upstreams
+//!   resolve to sandbox datasets first
+//! - writes to `/api/v1/datasets/{name}` are limited to datasets in the sandbox
+//! - dataset lists and search include the sandbox's datasets
+//!
+//! Other requests never see sandbox datasets in lists or search; they remain
+//! reachable by full name.
+//!
+//! [`sandbox_purge_task`] deletes expired sandboxes with their datasets.
+//! Expired sandboxes are also purged whenever a namespace is registered, so
+//! tenant catalogs, which the background task does not visit, are cleaned up too.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_SANDBOX_MAX_TTL_SECS`: longest allowed sandbox TTL (default: 604800, 7 days)
+//! - `METAFUSE_SANDBOX_PURGE_INTERVAL_SECS`: how often the purge job runs (default: 300)
+
+use crate::error_codes::ErrorCode;
+use crate::namespaces;
+use axum::{
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Header selecting a sandbox for the request
+pub const SANDBOX_HEADER: &str = "x-metafuse-sandbox";
+
+/// Default longest sandbox TTL (7 days)
+pub const DEFAULT_MAX_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// Default interval between purge runs
+pub const DEFAULT_PURGE_INTERVAL_SECS: u64 = 300;
+
+/// Sandbox configuration
+#[derive(Debug, Clone)]
+pub struct SandboxConfig {
+    /// Longest TTL a sandbox can be registered with
+    pub max_ttl_secs: u64,
+    /// Seconds between background purge runs
+    pub purge_interval_secs: u64,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            max_ttl_secs: DEFAULT_MAX_TTL_SECS,
+            purge_interval_secs: DEFAULT_PURGE_INTERVAL_SECS,
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_SANDBOX_MAX_TTL_SECS`: longest allowed sandbox TTL
+    /// - `METAFUSE_SANDBOX_PURGE_INTERVAL_SECS`: seconds between purge runs
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_ttl_secs: std::env::var("METAFUSE_SANDBOX_MAX_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.max_ttl_secs),
+            purge_interval_secs: std::env::var("METAFUSE_SANDBOX_PURGE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.purge_interval_secs),
+        }
+    }
+
+    /// Validate a requested sandbox TTL.
+    pub fn validate_ttl(&self, ttl_secs: u64) -> Result<(), String> {
+        if ttl_secs == 0 {
+            return Err("ttl_secs must be positive".to_string());
+        }
+        if ttl_secs > self.max_ttl_secs {
+            return Err(format!(
+                "ttl_secs {} exceeds the maximum of {}",
+                ttl_secs, self.max_ttl_secs
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Sandbox selected for a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandbox(pub String);
+
+impl Sandbox {
+    /// Qualify a dataset name with the sandbox, unless it already is.
+    pub fn qualify(&self, name: &str) -> String {
+        if self.contains(name) {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.0, name)
+        }
+    }
+
+    /// Whether a dataset name is in the sandbox.
+    pub fn contains(&self, name: &str) -> bool {
+        name.strip_prefix(self.0.as_str())
+            .is_some_and(|rest| rest.starts_with('.'))
+    }
+}
+
+/// Whether a namespace can be written to as a sandbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxStatus {
+    /// Registered sandbox that hasn't expired
+    Active,
+    /// Sandbox past its `expires_at`, awaiting purge
+    Expired,
+    /// Registered namespace without a TTL
+    NotSandbox,
+    /// No such namespace
+    Missing,
+}
+
+impl SandboxStatus {
+    /// Why a sandboxed write is rejected, if it is.
+    pub fn rejection(self, name: &str) -> Option<String> {
+        match self {
+            Self::Active => None,
+            Self::Expired => Some(format!("Sandbox '{}' has expired", name)),
+            Self::NotSandbox => Some(format!("Namespace '{}' is not a sandbox", name)),
+            Self::Missing => Some(format!("Sandbox '{}' does not exist", name)),
+        }
+    }
+}
+
+// =============================================================================
+// Middleware
+// =============================================================================
+
+fn reject(status: StatusCode, code: ErrorCode, message: String, req: &Request) -> Response {
+    let request_id = req
+        .extensions()
+        .get::<uuid::Uuid>()
+        .map(|id| id.to_string());
+    (
+        status,
+        Json(serde_json::json!({
+            "error": message,
+            "code": code,
+            "request_id": request_id,
+        })),
+    )
+        .into_response()
+}
+
+/// Dataset name addressed by a `/api/v1/datasets/{name}` route, if any.
+fn dataset_in_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/api/v1/datasets/")
+        .and_then(|rest| rest.split('/').next())
+        .filter(|name| !name.is_empty())
+}
+
+/// Middleware that attaches the request's [`Sandbox`].
+///
+/// A sandbox pinned by the tenant API key wins; a header naming a different
+/// sandbox is rejected (403). In a sandboxed request, writes addressing a
+/// dataset outside the sandbox are rejected (403). Must run after tenant
+/// resolution.
+pub async fn sandbox_middleware(mut req: Request, next: Next) -> Response {
+    let header = match req.headers().get(SANDBOX_HEADER).map(|v| v.to_str()) {
+        None => None,
+        Some(Ok(value)) => Some(value.trim().to_string()),
+        Some(Err(_)) => {
+            let message = format!("Invalid {} header", SANDBOX_HEADER);
+            return reject(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::ValidationFailed,
+                message,
+                &req,
+            );
+        }
+    };
+    #[cfg(feature = "api-keys")]
+    let pinned = req
+        .extensions()
+        .get::<crate::tenant_resolver::ResolvedTenant>()
+        .and_then(|tenant| tenant.sandbox())
+        .map(str::to_string);
+    #[cfg(not(feature = "api-keys"))]
+    let pinned: Option<String> = None;
+
+    let selected = match (pinned, header) {
+        (Some(pinned), Some(header)) if pinned != header => {
+            let message = format!("API key is pinned to sandbox '{}'", pinned);
+            return reject(StatusCode::FORBIDDEN, ErrorCode::Forbidden, message, &req);
+        }
+        (pinned, header) => pinned.or(header),
+    };
+    let Some(name) = selected else {
+        return next.run(req).await;
+    };
+    if let Err(e) = namespaces::validate_namespace(&name) {
+        return reject(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::ValidationFailed,
+            e,
+            &req,
+        );
+    }
+
+    let sandbox = Sandbox(name);
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        if let Some(dataset) = dataset_in_path(req.uri().path()) {
+            if !sandbox.contains(dataset) {
+                let message = format!("Dataset '{}' is outside sandbox '{}'", dataset, sandbox.0);
+                return reject(StatusCode::FORBIDDEN, ErrorCode::Forbidden, message, &req);
+            }
+        }
+    }
+    debug!(sandbox = %sandbox.0, "Sandboxed request");
+    req.extensions_mut().insert(sandbox);
+    next.run(req).await
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+/// Look up whether `name` is a writable sandbox.
+pub fn status(conn: &Connection, name: &str) -> rusqlite::Result<SandboxStatus> {
+    let row: Option<(Option<String>, bool)> = conn
+        .query_row(
+            "SELECT expires_at, COALESCE(expires_at <= datetime('now'), 0)
+             FROM namespaces WHERE name = ?1",
+            [name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(match row {
+        None => SandboxStatus::Missing,
+        Some((None, _)) => SandboxStatus::NotSandbox,
+        Some((Some(_), true)) => SandboxStatus::Expired,
+        Some((Some(_), false)) => SandboxStatus::Active,
+    })
+}
+
+/// SQL condition hiding datasets in sandboxes, except `visible`.
+///
+/// `name_column` is the table-qualified dataset name column (e.g. `d.name`);
+/// a bare `name` would bind to the namespace. Returns the clause and its bindings.
+pub fn visibility_clause(name_column: &str, visible: Option<&Sandbox>) -> (String, Vec<String>) {
+    let mut clause = format!(
+        "NOT EXISTS (SELECT 1 FROM namespaces sb WHERE sb.expires_at IS NOT NULL \
+         AND {} GLOB sb.name || '.*'",
+        name_column
+    );
+    let mut bindings = Vec::new();
+    if let Some(sandbox) = visible {
+        clause.push_str(" AND sb.name <> ?");
+        bindings.push(sandbox.0.clone());
+    }
+    clause.push(')');
+    (clause, bindings)
+}
+
+/// Resolve an upstream name for a dataset created in a sandbox.
+///
+/// Prefers the sandbox's dataset of that name, falling back to the name as given.
+pub fn resolve_upstream(
+    conn: &Connection,
+    sandbox: &Sandbox,
+    name: &str,
+) -> rusqlite::Result<String> {
+    let qualified = sandbox.qualify(name);
+    let exists = conn
+        .query_row(
+            "SELECT 1 FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&qualified],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    Ok(if exists { qualified } else { name.to_string() })
+}
+
+/// Expired sandboxes deleted by [`purge_expired`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PurgeSummary {
+    pub sandboxes: Vec<String>,
+    pub datasets: usize,
+}
+
+/// Delete expired sandboxes and every dataset in them, trashed ones included.
+///
+/// Fields, tags, lineage edges, and the FTS entries are removed by the
+/// schema's cascades and triggers.
+pub fn purge_expired(conn: &Connection) -> rusqlite::Result<PurgeSummary> {
+    let tx = conn.unchecked_transaction()?;
+    let sandboxes: Vec<String> = {
+        let mut stmt = tx.prepare(
+            "SELECT name FROM namespaces
+             WHERE expires_at IS NOT NULL AND expires_at <= datetime('now')
+             ORDER BY name",
+        )?;
+        let names = stmt.query_map([], |row| row.get(0))?;
+        names.collect::<rusqlite::Result<_>>()?
+    };
+
+    let mut datasets = 0;
+    for sandbox in &sandboxes {
+        let glob = namespaces::dataset_glob(sandbox);
+        datasets += tx.execute("DELETE FROM datasets WHERE name GLOB ?1", [&glob])?;
+        tx.execute(
+            "DELETE FROM namespaces WHERE name = ?1 OR name GLOB ?2",
+            [sandbox, &glob],
+        )?;
+    }
+    tx.commit()?;
+    Ok(PurgeSummary {
+        sandboxes,
+        datasets,
+    })
+}
+
+/// Background task that periodically purges expired sandboxes
+pub async fn sandbox_purge_task(
+    config: SandboxConfig,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(config.purge_interval_secs);
+
+    info!(
+        interval_secs = config.purge_interval_secs,
+        "Sandbox purge task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        debug!("Running periodic sandbox purge");
+
+        match backend.get_connection().await {
+            Ok(conn) => match purge_expired(&conn) {
+                Ok(summary) if summary.sandboxes.is_empty() => {}
+                Ok(summary) => info!(
+                    sandboxes = ?summary.sandboxes,
+                    datasets = summary.datasets,
+                    "Purged expired sandboxes"
+                ),
+                Err(e) => error!(error = %e, "Failed to purge sandboxes"),
+            },
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for sandbox purge");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO namespaces (name) VALUES ('sales');
+             INSERT INTO namespaces (name, expires_at) VALUES ('ci_1', datetime('now', '+1 hour'));
+             INSERT INTO namespaces (name, expires_at) VALUES ('ci_0', datetime('now', '-1 hour'));",
+        )
+        .unwrap();
+        for name in [
+            "orders",
+            "sales.orders",
+            "ci_1.orders",
+            "ci_0.orders",
+            "ci_0.raw",
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', datetime('now'), datetime('now'))",
+                [name],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn visible_names(conn: &Connection, sandbox: Option<&Sandbox>) -> Vec<String> {
+        let (clause, bindings) = visibility_clause("datasets.name", sandbox);
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT name FROM datasets WHERE {} ORDER BY name",
+                clause
+            ))
+            .unwrap();
+        let names = stmt
+            .query_map(rusqlite::params_from_iter(bindings.iter()), |row| {
+                row.get(0)
+            })
+            .unwrap();
+        names.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn test_qualify() {
+        let sandbox = Sandbox("ci_1".to_string());
+        assert_eq!(sandbox.qualify("orders"), "ci_1.orders");
+        assert_eq!(sandbox.qualify("ci_1.orders"), "ci_1.orders");
+        assert_eq!(sandbox.qualify("ci_10.orders"), "ci_1.ci_10.orders");
+        assert!(!sandbox.contains("ci_1"));
+        assert_eq!(
+            dataset_in_path("/api/v1/datasets/ci_1.orders/tags"),
+            Some("ci_1.orders")
+        );
+        assert_eq!(dataset_in_path("/api/v1/datasets"), None);
+    }
+
+    #[test]
+    fn test_validate_ttl() {
+        let config = SandboxConfig::default();
+        assert!(config.validate_ttl(3600).is_ok());
+        assert!(config.validate_ttl(0).is_err());
+        assert!(config.validate_ttl(DEFAULT_MAX_TTL_SECS + 1).is_err());
+    }
+
+    #[test]
+    fn test_status_and_visibility() {
+        let conn = setup_db();
+        assert_eq!(status(&conn, "ci_1").unwrap(), SandboxStatus::Active);
+        assert_eq!(status(&conn, "ci_0").unwrap(), SandboxStatus::Expired);
+        assert_eq!(status(&conn, "sales").unwrap(), SandboxStatus::NotSandbox);
+        assert_eq!(status(&conn, "ci_2").unwrap(), SandboxStatus::Missing);
+
+        assert_eq!(visible_names(&conn, None), vec!["orders", "sales.orders"]);
+        let sandbox = Sandbox("ci_1".to_string());
+        assert_eq!(
+            visible_names(&conn, Some(&sandbox)),
+            vec!["ci_1.orders", "orders", "sales.orders"]
+        );
+
+        assert_eq!(
+            resolve_upstream(&conn, &sandbox, "orders").unwrap(),
+            "ci_1.orders"
+        );
+        assert_eq!(
+            resolve_upstream(&conn, &sandbox, "sales.orders").unwrap(),
+            "sales.orders"
+        );
+    }
+
+    #[test]
+    fn test_purge_expired() {
+        let conn = setup_db();
+        let summary = purge_expired(&conn).unwrap();
+        assert_eq!(summary.sandboxes, vec!["ci_0".to_string()]);
+        assert_eq!(summary.datasets, 2);
+        assert_eq!(status(&conn, "ci_0").unwrap(), SandboxStatus::Missing);
+        assert_eq!(status(&conn, "ci_1").unwrap(), SandboxStatus::Active);
+
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 3);
+        assert_eq!(purge_expired(&conn).unwrap(), PurgeSummary::default());
+    }
+}
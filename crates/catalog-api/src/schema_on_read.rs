@@ -0,0 +1,160 @@
+//! Schema-on-read
+//!
+//! Datasets imported from Glue or registered by path sometimes have no
+//! fields. `GET /api/v1/datasets/{name}?resolve_schema=true` reads their
+//! schema from storage instead:
+//!
+//! - the Delta log at `delta_location`, or at `path` for `delta` datasets
+//! - a Parquet footer at `path` for `parquet` datasets
+//!
+//! With `persist_schema=true` the discovered fields are also written to the
+//! catalog, unless fields were registered in the meantime. Datasets that
+//! already have fields are returned as stored.
+
+use metafuse_catalog_core::{field_ordinals, increment_catalog_version, FieldMeta, Result};
+use metafuse_catalog_delta::{DeltaError, DeltaReader};
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Where a schema was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaSource {
+    Delta,
+    Parquet,
+}
+
+/// How a dataset's fields were resolved, reported next to them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaResolution {
+    pub source: SchemaSource,
+    pub location: String,
+    /// Whether the fields were written to the catalog
+    pub persisted: bool,
+}
+
+/// Storage a dataset's schema can be read from, if any.
+pub fn source_for(
+    format: &str,
+    path: &str,
+    delta_location: Option<&str>,
+) -> Option<(SchemaSource, String)> {
+    if let Some(location) = delta_location.filter(|l| !l.trim().is_empty()) {
+        return Some((SchemaSource::Delta, location.to_string()));
+    }
+    if path.trim().is_empty() {
+        return None;
+    }
+    match format.to_ascii_lowercase().as_str() {
+        "delta" => Some((SchemaSource::Delta, path.to_string())),
+        "parquet" => Some((SchemaSource::Parquet, path.to_string())),
+        _ => None,
+    }
+}
+
+/// Read a schema from storage as catalog fields, in schema order.
+pub async fn resolve(
+    reader: &DeltaReader,
+    source: SchemaSource,
+    location: &str,
+) -> std::result::Result<Vec<FieldMeta>, DeltaError> {
+    let schema = match source {
+        SchemaSource::Delta => reader.get_schema(location, None).await?,
+        SchemaSource::Parquet => reader.get_parquet_schema(location).await?,
+    };
+    Ok(schema
+        .fields
+        .into_iter()
+        .map(|f| FieldMeta {
+            name: f.name,
+            data_type: f.data_type,
+            nullable: f.nullable,
+            description: f.description,
+        })
+        .collect())
+}
+
+/// Store resolved fields for a dataset that still has none.
+///
+/// Returns whether they were stored.
+pub fn persist(conn: &Connection, dataset_id: i64, fields: &[FieldMeta]) -> Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let registered: bool = tx.query_row(
+        "SELECT EXISTS (SELECT 1 FROM fields WHERE dataset_id = ?1)",
+        [dataset_id],
+        |row| row.get(0),
+    )?;
+    if registered || fields.is_empty() {
+        return Ok(false);
+    }
+    field_ordinals::replace_fields(&tx, dataset_id, fields)?;
+    increment_catalog_version(&tx)?;
+    tx.commit()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_for() {
+        assert_eq!(
+            source_for("parquet", "/lake/orders", Some("s3://lake/orders_delta")),
+            Some((SchemaSource::Delta, "s3://lake/orders_delta".to_string()))
+        );
+        assert_eq!(
+            source_for("Parquet", "/lake/orders", None),
+            Some((SchemaSource::Parquet, "/lake/orders".to_string()))
+        );
+        assert_eq!(
+            source_for("delta", "/lake/events", None).map(|s| s.0),
+            Some(SchemaSource::Delta)
+        );
+        assert_eq!(source_for("csv", "/lake/raw.csv", None), None);
+        assert_eq!(source_for("parquet", "", None), None);
+    }
+
+    #[test]
+    fn test_persist_only_fills_empty_schemas() {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/lake/orders', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let fields = vec![
+            FieldMeta {
+                name: "id".to_string(),
+                data_type: "Int64".to_string(),
+                nullable: false,
+                description: None,
+            },
+            FieldMeta {
+                name: "amount".to_string(),
+                data_type: "Float64".to_string(),
+                nullable: true,
+                description: None,
+            },
+        ];
+        let version = metafuse_catalog_core::get_catalog_version(&conn).unwrap();
+
+        assert!(persist(&conn, 1, &fields).unwrap());
+        assert!(!persist(&conn, 1, &fields[..1]).unwrap());
+        let names: Vec<String> = conn
+            .prepare("SELECT name FROM fields WHERE dataset_id = 1 ORDER BY ordinal")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(names, vec!["id", "amount"]);
+        assert_eq!(
+            metafuse_catalog_core::get_catalog_version(&conn).unwrap(),
+            version + 1
+        );
+    }
+}
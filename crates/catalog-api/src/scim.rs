@@ -0,0 +1,781 @@
+//! SCIM User Provisioning
+//!
+//! A SCIM 2.0 (RFC 7643/7644) `Users` endpoint, so an identity provider such
+//! as Okta or Entra ID can provision the users of the `scim` user directory
+//! (see `users`). Users are stored in the default catalog's `directory_users`
+//! table (migration v1.45.0) with `source = 'scim'`.
+//!
+//! # Endpoints
+//!
+//! Served under `/scim/v2` when `METAFUSE_SCIM_TOKEN` is set; the identity
+//! provider authenticates with `Authorization: Bearer <token>`.
+//!
+//! - `GET /Users`: list users, with `filter=userName eq "..."`, `startIndex`
+//!   and `count`
+//! - `POST /Users`: create a user (`409` if the `userName` exists)
+//! - `GET /Users/{id}`, `PUT /Users/{id}`: read or replace a user
+//! - `PATCH /Users/{id}`: `add`/`replace`/`remove` of `active`,
+//!   `displayName`, `name.formatted` and `emails`
+//! - `DELETE /Users/{id}`: deprovision a user
+//!
+//! A user's `id` is its `userName`, the id carried in the identity user
+//! header and used in owner ids; it cannot be changed. Deleted users are kept
+//! with `active = false`, so the names in historical records still resolve.
+//!
+//! Group memberships are not provisioned here; a user's teams are their
+//! `group_members` rows (see `groups`).
+
+use crate::server::AppState;
+use crate::users;
+use crate::users::DirectoryUser;
+use axum::extract::{Extension, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// `source` of the `directory_users` rows written by SCIM
+pub const SCIM_SOURCE: &str = "scim";
+
+/// Content type of SCIM responses
+pub const CONTENT_TYPE: &str = "application/scim+json";
+
+const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+const LIST_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+
+/// Default and maximum page size of `GET /Users`
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// Email address of a SCIM user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary: bool,
+}
+
+/// Components of a user's name; only `formatted` is stored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+/// Resource metadata of a SCIM user
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: &'static str,
+    pub last_modified: String,
+}
+
+/// A user as exchanged with the identity provider.
+///
+/// Attributes MetaFuse doesn't store are accepted and dropped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub user_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<ScimName>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub emails: Vec<ScimEmail>,
+    #[serde(default = "default_active", deserialize_with = "scim_bool")]
+    pub active: bool,
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ScimMeta>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Booleans, also as the `"True"`/`"False"` strings some providers send
+fn scim_bool<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    parse_bool(&value).ok_or_else(|| serde::de::Error::custom("expected a boolean"))
+}
+
+fn parse_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) if s.eq_ignore_ascii_case("true") => Some(true),
+        Value::String(s) if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+impl ScimUser {
+    /// The stored user: display name from `displayName`, else the name, and
+    /// the primary (else first) email.
+    pub fn to_directory_user(&self) -> DirectoryUser {
+        let display_name = self.display_name.clone().or_else(|| {
+            let name = self.name.as_ref()?;
+            name.formatted.clone().or_else(|| {
+                let parts: Vec<&str> = [&name.given_name, &name.family_name]
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join(" "))
+            })
+        });
+        let email = self
+            .emails
+            .iter()
+            .find(|e| e.primary)
+            .or_else(|| self.emails.first())
+            .map(|e| e.value.clone());
+        DirectoryUser {
+            id: self.user_name.clone(),
+            display_name,
+            email,
+            teams: Vec::new(),
+            active: self.active,
+            source: SCIM_SOURCE.to_string(),
+        }
+    }
+
+    /// The SCIM representation of a stored user.
+    pub fn from_directory_user(user: DirectoryUser, last_modified: String) -> Self {
+        Self {
+            schemas: vec![USER_SCHEMA.to_string()],
+            id: Some(user.id.clone()),
+            user_name: user.id,
+            name: user.display_name.clone().map(|formatted| ScimName {
+                formatted: Some(formatted),
+                ..ScimName::default()
+            }),
+            display_name: user.display_name,
+            emails: user
+                .email
+                .map(|value| {
+                    vec![ScimEmail {
+                        value,
+                        primary: true,
+                    }]
+                })
+                .unwrap_or_default(),
+            active: user.active,
+            meta: Some(ScimMeta {
+                resource_type: "User",
+                last_modified,
+            }),
+        }
+    }
+}
+
+/// A page of users
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResponse {
+    pub schemas: Vec<&'static str>,
+    pub total_results: usize,
+    pub start_index: usize,
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<ScimUser>,
+}
+
+/// Query of `GET /Users`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuery {
+    pub filter: Option<String>,
+    pub start_index: Option<usize>,
+    pub count: Option<usize>,
+}
+
+/// A SCIM error, sent as the SCIM error message
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScimError {
+    pub status: u16,
+    /// `scimType` of `400` and `409` errors
+    pub scim_type: Option<&'static str>,
+    pub detail: String,
+}
+
+impl ScimError {
+    fn bad_request(scim_type: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            scim_type: Some(scim_type),
+            detail: detail.into(),
+        }
+    }
+
+    /// `404` for an unknown user id
+    pub fn not_found(id: &str) -> Self {
+        Self {
+            status: 404,
+            scim_type: None,
+            detail: format!("User '{}' not found", id),
+        }
+    }
+
+    /// `401` for a missing or wrong bearer token
+    pub fn unauthorized(detail: impl Into<String>) -> Self {
+        Self {
+            status: 401,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    /// `500` for a storage failure
+    pub fn internal(error: impl std::fmt::Display) -> Self {
+        Self {
+            status: 500,
+            scim_type: None,
+            detail: error.to_string(),
+        }
+    }
+
+    /// The SCIM error message body
+    pub fn body(&self) -> Value {
+        let mut body = serde_json::json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": self.status.to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = Value::from(scim_type);
+        }
+        body
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        respond(status, &self.body())
+    }
+}
+
+/// A SCIM response with the SCIM content type.
+pub fn respond(status: StatusCode, body: &impl Serialize) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        serde_json::to_string(body).unwrap_or_default(),
+    )
+        .into_response()
+}
+
+impl From<rusqlite::Error> for ScimError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::internal(e)
+    }
+}
+
+/// Parse a request body as a user.
+pub fn parse_user(body: &Value) -> Result<ScimUser, ScimError> {
+    let user: ScimUser = serde_json::from_value(body.clone())
+        .map_err(|e| ScimError::bad_request("invalidValue", format!("Invalid user: {}", e)))?;
+    if user.user_name.trim().is_empty() {
+        return Err(ScimError::bad_request(
+            "invalidValue",
+            "userName must not be empty",
+        ));
+    }
+    Ok(user)
+}
+
+/// Parse a `userName eq "..."` filter, the only one supported.
+fn parse_filter(filter: &str) -> Result<String, ScimError> {
+    let invalid = || {
+        ScimError::bad_request(
+            "invalidFilter",
+            format!(
+                "Unsupported filter '{}': only userName eq \"...\" is supported",
+                filter
+            ),
+        )
+    };
+    let mut parts = filter.trim().splitn(3, char::is_whitespace);
+    let (Some(attribute), Some(op), Some(value)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    if !attribute.eq_ignore_ascii_case("userName") || !op.eq_ignore_ascii_case("eq") {
+        return Err(invalid());
+    }
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .map(|v| v.replace("\\\"", "\""))
+        .ok_or_else(invalid)
+}
+
+fn row_to_user(row: &rusqlite::Row<'_>) -> rusqlite::Result<ScimUser> {
+    let user = DirectoryUser {
+        id: row.get(0)?,
+        display_name: row.get(1)?,
+        email: row.get(2)?,
+        teams: Vec::new(),
+        active: row.get::<_, i64>(3)? != 0,
+        source: SCIM_SOURCE.to_string(),
+    };
+    Ok(ScimUser::from_directory_user(user, row.get(4)?))
+}
+
+/// Get a provisioned user.
+pub fn get_user(conn: &Connection, id: &str) -> Result<ScimUser, ScimError> {
+    conn.query_row(
+        "SELECT user_id, display_name, email, active, synced_at FROM directory_users
+         WHERE source = ?1 AND user_id = ?2",
+        params![SCIM_SOURCE, id],
+        row_to_user,
+    )
+    .optional()?
+    .ok_or_else(|| ScimError::not_found(id))
+}
+
+/// List provisioned users, sorted by id.
+pub fn list_users(conn: &Connection, query: &ListQuery) -> Result<ListResponse, ScimError> {
+    let user_name = query.filter.as_deref().map(parse_filter).transpose()?;
+    // startIndex is 1-based; values below 1 mean 1
+    let start_index = query.start_index.unwrap_or(1).max(1);
+    let count = query.count.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    let total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM directory_users
+         WHERE source = ?1 AND (?2 IS NULL OR user_id = ?2)",
+        params![SCIM_SOURCE, user_name],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT user_id, display_name, email, active, synced_at FROM directory_users
+         WHERE source = ?1 AND (?2 IS NULL OR user_id = ?2)
+         ORDER BY user_id LIMIT ?3 OFFSET ?4",
+    )?;
+    let resources = stmt
+        .query_map(
+            params![
+                SCIM_SOURCE,
+                user_name,
+                count as i64,
+                (start_index - 1) as i64
+            ],
+            row_to_user,
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(ListResponse {
+        schemas: vec![LIST_SCHEMA],
+        total_results: total as usize,
+        start_index,
+        items_per_page: resources.len(),
+        resources,
+    })
+}
+
+fn write_user(conn: &Connection, user: &DirectoryUser) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO directory_users (source, user_id, display_name, email, active)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (source, user_id) DO UPDATE SET
+            display_name = excluded.display_name,
+            email = excluded.email,
+            active = excluded.active,
+            synced_at = CURRENT_TIMESTAMP",
+        params![
+            SCIM_SOURCE,
+            user.id,
+            user.display_name,
+            user.email,
+            user.active as i64
+        ],
+    )?;
+    Ok(())
+}
+
+/// Create a user; `409` if one with the same `userName` exists.
+pub fn create_user(conn: &Connection, user: &ScimUser) -> Result<ScimUser, ScimError> {
+    let user = user.to_directory_user();
+    if get_user(conn, &user.id).is_ok() {
+        return Err(ScimError {
+            status: 409,
+            scim_type: Some("uniqueness"),
+            detail: format!("User '{}' already exists", user.id),
+        });
+    }
+    write_user(conn, &user)?;
+    get_user(conn, &user.id)
+}
+
+/// Replace a user.
+pub fn replace_user(conn: &Connection, id: &str, user: &ScimUser) -> Result<ScimUser, ScimError> {
+    get_user(conn, id)?;
+    if user.user_name != id {
+        return Err(ScimError::bad_request(
+            "mutability",
+            format!("userName of '{}' cannot change", id),
+        ));
+    }
+    write_user(conn, &user.to_directory_user())?;
+    get_user(conn, id)
+}
+
+/// Apply a `PatchOp` request body to a user.
+pub fn patch_user(conn: &Connection, id: &str, body: &Value) -> Result<ScimUser, ScimError> {
+    let mut user = get_user(conn, id)?;
+    let operations = body
+        .get("Operations")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ScimError::bad_request("invalidSyntax", "Missing Operations"))?;
+    for operation in operations {
+        apply_operation(&mut user, operation)?;
+    }
+    user.user_name = id.to_string();
+    write_user(conn, &user.to_directory_user())?;
+    get_user(conn, id)
+}
+
+fn apply_operation(user: &mut ScimUser, operation: &Value) -> Result<(), ScimError> {
+    let op = operation
+        .get("op")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_lowercase();
+    let value = operation.get("value").cloned().unwrap_or(Value::Null);
+    let path = operation.get("path").and_then(Value::as_str);
+    match (op.as_str(), path) {
+        ("add" | "replace", None) => {
+            let Value::Object(attributes) = value else {
+                return Err(ScimError::bad_request(
+                    "invalidValue",
+                    "A patch without a path needs an object value",
+                ));
+            };
+            for (path, value) in attributes {
+                set_attribute(user, &path, value)?;
+            }
+            Ok(())
+        }
+        ("add" | "replace", Some(path)) => set_attribute(user, path, value),
+        ("remove", Some(path)) => set_attribute(user, path, Value::Null),
+        _ => Err(ScimError::bad_request(
+            "invalidSyntax",
+            format!("Unsupported patch operation '{}'", op),
+        )),
+    }
+}
+
+fn set_attribute(user: &mut ScimUser, path: &str, value: Value) -> Result<(), ScimError> {
+    let invalid = || ScimError::bad_request("invalidValue", format!("Invalid value for {}", path));
+    let text = |value: Value| match value {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s)),
+        _ => Err(invalid()),
+    };
+    // Providers address the work or primary email with a value filter
+    let path_lower = path.to_lowercase();
+    let attribute = if path_lower.starts_with("emails[") {
+        "emails.value"
+    } else {
+        path_lower.as_str()
+    };
+    match attribute {
+        "active" => user.active = parse_bool(&value).ok_or_else(invalid)?,
+        "displayname" => user.display_name = text(value)?,
+        "name.formatted" => {
+            user.name.get_or_insert_with(ScimName::default).formatted = text(value)?;
+            // The stored name follows the latest update
+            user.display_name = None;
+        }
+        "name" => {
+            user.name = match value {
+                Value::Null => None,
+                value => Some(serde_json::from_value(value).map_err(|_| invalid())?),
+            };
+            user.display_name = None;
+        }
+        "emails.value" => {
+            user.emails = text(value)?
+                .map(|value| {
+                    vec![ScimEmail {
+                        value,
+                        primary: true,
+                    }]
+                })
+                .unwrap_or_default();
+        }
+        "emails" => {
+            user.emails = match value {
+                Value::Null => Vec::new(),
+                value => serde_json::from_value(value).map_err(|_| invalid())?,
+            };
+        }
+        // Unstored attributes and the fixed userName are ignored
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Deprovision a user, keeping it with `active = false`.
+pub fn delete_user(conn: &Connection, id: &str) -> Result<(), ScimError> {
+    let updated = conn.execute(
+        "UPDATE directory_users SET active = 0, synced_at = CURRENT_TIMESTAMP
+         WHERE source = ?1 AND user_id = ?2",
+        params![SCIM_SOURCE, id],
+    )?;
+    if updated == 0 {
+        return Err(ScimError::not_found(id));
+    }
+    Ok(())
+}
+
+/// Bearer token SCIM clients authenticate with (`ServerConfig::scim_token`)
+#[derive(Clone)]
+pub(crate) struct ScimToken(pub(crate) String);
+
+/// SCIM authorization middleware.
+/// Validates the bearer token against the configured SCIM token.
+pub(crate) async fn require_scim_auth(
+    Extension(ScimToken(scim_token)): Extension<ScimToken>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token {
+        Some(token) if !scim_token.is_empty() && token == scim_token => next.run(request).await,
+        Some(_) => ScimError::unauthorized("Invalid SCIM token").into_response(),
+        None => ScimError::unauthorized("Missing bearer token").into_response(),
+    }
+}
+
+/// Reload the `scim` directory after a provisioning change, so validation
+/// sees it before the next sync.
+pub(crate) fn refresh_scim_users(state: &AppState, conn: &rusqlite::Connection) {
+    for table in state.users.tables() {
+        if users::UserDirectory::name(table) == "scim" {
+            if let Err(e) = table.refresh(conn) {
+                tracing::error!(error = %e, "Failed to reload SCIM users");
+            }
+        }
+    }
+}
+
+pub(crate) async fn scim_connection(state: &AppState) -> Result<rusqlite::Connection, ScimError> {
+    state
+        .backend
+        .get_connection()
+        .await
+        .map_err(ScimError::internal)
+}
+
+/// List SCIM-provisioned users
+pub(crate) async fn scim_list_users(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> Result<Response, ScimError> {
+    let conn = scim_connection(&state).await?;
+    let page = list_users(&conn, &query)?;
+    Ok(respond(StatusCode::OK, &page))
+}
+
+/// Provision a user
+pub(crate) async fn scim_create_user(
+    State(state): State<AppState>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Response, ScimError> {
+    let user = parse_user(&body)?;
+    let conn = scim_connection(&state).await?;
+    let user = create_user(&conn, &user)?;
+    refresh_scim_users(&state, &conn);
+    tracing::info!(user = %user.user_name, "SCIM user provisioned");
+    Ok(respond(StatusCode::CREATED, &user))
+}
+
+/// Get a SCIM-provisioned user
+pub(crate) async fn scim_get_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, ScimError> {
+    let conn = scim_connection(&state).await?;
+    let user = get_user(&conn, &id)?;
+    Ok(respond(StatusCode::OK, &user))
+}
+
+/// Replace a SCIM-provisioned user
+pub(crate) async fn scim_replace_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Response, ScimError> {
+    let user = parse_user(&body)?;
+    let conn = scim_connection(&state).await?;
+    let user = replace_user(&conn, &id, &user)?;
+    refresh_scim_users(&state, &conn);
+    Ok(respond(StatusCode::OK, &user))
+}
+
+/// Patch a SCIM-provisioned user
+pub(crate) async fn scim_patch_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Response, ScimError> {
+    let conn = scim_connection(&state).await?;
+    let user = patch_user(&conn, &id, &body)?;
+    refresh_scim_users(&state, &conn);
+    Ok(respond(StatusCode::OK, &user))
+}
+
+/// Deprovision a SCIM user
+pub(crate) async fn scim_delete_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ScimError> {
+    let conn = scim_connection(&state).await?;
+    delete_user(&conn, &id)?;
+    refresh_scim_users(&state, &conn);
+    tracing::info!(user = %id, "SCIM user deprovisioned");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn catalog() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_provisioning_lifecycle() {
+        let conn = catalog();
+        let user = parse_user(&json!({
+            "schemas": [USER_SCHEMA],
+            "userName": "ana",
+            "name": {"givenName": "Ana", "familyName": "Lima"},
+            "emails": [{"value": "ana@home.example"}, {"value": "ana@example.com", "primary": true, "type": "work"}],
+            "externalId": "00u1"
+        }))
+        .unwrap();
+        let created = create_user(&conn, &user).unwrap();
+        assert_eq!(created.id.as_deref(), Some("ana"));
+        assert_eq!(created.display_name.as_deref(), Some("Ana Lima"));
+        assert_eq!(created.emails[0].value, "ana@example.com");
+        assert!(created.active);
+        assert_eq!(create_user(&conn, &user).unwrap_err().status, 409);
+
+        // Entra ID sends string booleans and a filtered email path
+        let patched = patch_user(
+            &conn,
+            "ana",
+            &json!({"Operations": [
+                {"op": "Replace", "path": "active", "value": "False"},
+                {"op": "replace", "path": "emails[type eq \"work\"].value", "value": "ana@corp.example"}
+            ]}),
+        )
+        .unwrap();
+        assert!(!patched.active);
+        assert_eq!(patched.emails[0].value, "ana@corp.example");
+        assert_eq!(patched.display_name.as_deref(), Some("Ana Lima"));
+
+        // Okta patches without a path
+        let patched = patch_user(
+            &conn,
+            "ana",
+            &json!({"Operations": [{"op": "replace", "value": {"active": true, "displayName": "Ana L."}}]}),
+        )
+        .unwrap();
+        assert!(patched.active);
+        assert_eq!(patched.display_name.as_deref(), Some("Ana L."));
+
+        let renamed = parse_user(&json!({"userName": "ana2"})).unwrap();
+        assert_eq!(
+            replace_user(&conn, "ana", &renamed).unwrap_err().status,
+            400
+        );
+        let replaced = replace_user(
+            &conn,
+            "ana",
+            &parse_user(&json!({"userName": "ana"})).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(replaced.display_name, None);
+
+        // Deleted users are kept, deactivated
+        delete_user(&conn, "ana").unwrap();
+        assert!(!get_user(&conn, "ana").unwrap().active);
+        assert_eq!(delete_user(&conn, "bo").unwrap_err().status, 404);
+        assert_eq!(get_user(&conn, "bo").unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn test_list_users() {
+        let conn = catalog();
+        for name in ["cy", "ana", "bo"] {
+            create_user(&conn, &parse_user(&json!({ "userName": name })).unwrap()).unwrap();
+        }
+        // Rows written by other jobs are not SCIM users
+        conn.execute(
+            "INSERT INTO directory_users (source, user_id) VALUES ('hr_sync', 'dee')",
+            [],
+        )
+        .unwrap();
+
+        let page = list_users(
+            &conn,
+            &ListQuery {
+                start_index: Some(2),
+                count: Some(1),
+                ..ListQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.total_results, 3);
+        assert_eq!(page.resources[0].user_name, "bo");
+
+        let found = list_users(
+            &conn,
+            &ListQuery {
+                filter: Some("userName eq \"cy\"".to_string()),
+                ..ListQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(found.total_results, 1);
+        assert_eq!(found.resources[0].user_name, "cy");
+
+        let dee = ListQuery {
+            filter: Some("userName eq \"dee\"".to_string()),
+            ..ListQuery::default()
+        };
+        assert_eq!(list_users(&conn, &dee).unwrap().total_results, 0);
+
+        let unsupported = ListQuery {
+            filter: Some("emails co \"example\"".to_string()),
+            ..ListQuery::default()
+        };
+        let error = list_users(&conn, &unsupported).unwrap_err();
+        assert_eq!(error.body()["scimType"], "invalidFilter");
+    }
+}
@@ -0,0 +1,953 @@
+//! Semantic Search Module
+//!
+//! Embedding-based dataset search that finds conceptually similar datasets
+//! keyword FTS misses, enabled by the `semantic-search` feature.
+//!
+//! # Architecture
+//!
+//! - An [`EmbeddingProvider`] turns text into vectors. Two are built in:
+//!   [`HashingEmbedder`] (in-process, no model download) and [`HttpEmbedder`]
+//!   (any OpenAI-compatible `/embeddings` endpoint). Custom providers implement
+//!   the trait.
+//! - Vectors for each dataset's name and description are stored in the
+//!   `dataset_embeddings` table (migration v1.11.0). A semantic search starts
+//!   a background refresh of the catalog's missing or outdated vectors
+//!   ([`SemanticSearch::refresh_in_background`]) and ranks with the stored
+//!   ones, so datasets written by the emitter are picked up without a
+//!   separate indexing job and searches only wait for the query's vector.
+//!   Refreshes embed at most [`MAX_EMBEDDING_BATCH`] texts per provider call.
+//! - `GET /api/v1/search?q=...&mode=semantic` ranks by cosine similarity
+//!   blended with FTS rank ([`blend_scores`]).
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_EMBEDDING_PROVIDER`: `hashing` (default) or `http`
+//! - `METAFUSE_EMBEDDING_DIMENSIONS`: vector size for `hashing` (default: 256)
+//! - `METAFUSE_EMBEDDING_URL`: endpoint for `http` (e.g. `https://api.openai.com/v1/embeddings`)
+//! - `METAFUSE_EMBEDDING_MODEL`: model name for `http` (default: `text-embedding-3-small`)
+//! - `METAFUSE_EMBEDDING_API_KEY`: bearer token for `http` (optional)
+//! - `METAFUSE_SEMANTIC_WEIGHT`: weight of cosine similarity vs FTS, 0.0-1.0 (default: 0.7)
+
+use crate::datasets::parse_partition_keys;
+use crate::datasets::DatasetResponse;
+use crate::datasets::OperationalMetaResponse;
+use crate::server::{internal_error, ErrorResponse};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_storage::DynCatalogBackend;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Default vector size for [`HashingEmbedder`]
+pub const DEFAULT_HASHING_DIMENSIONS: usize = 256;
+
+/// Default weight of cosine similarity in the blended score
+pub const DEFAULT_SEMANTIC_WEIGHT: f32 = 0.7;
+
+/// Minimum cosine similarity for a dataset to be returned without an FTS match
+pub const MIN_SIMILARITY: f32 = 0.2;
+
+/// Timeout for external embedding API calls
+const HTTP_TIMEOUT_SECS: u64 = 30;
+
+/// Most texts embedded in one provider call (the OpenAI embeddings API limit)
+pub const MAX_EMBEDDING_BATCH: usize = 2048;
+
+// =============================================================================
+// Embedding Providers
+// =============================================================================
+
+/// Embedding errors
+#[derive(Debug)]
+pub enum EmbeddingError {
+    /// Network error calling an external provider
+    Network(String),
+    /// External provider returned an error status
+    HttpStatus(u16, String),
+    /// Response did not contain one vector per input
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingError::Network(e) => write!(f, "Network error: {}", e),
+            EmbeddingError::HttpStatus(code, body) => write!(f, "HTTP {} error: {}", code, body),
+            EmbeddingError::InvalidResponse(e) => write!(f, "Invalid embedding response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
+
+/// Future returned by [`EmbeddingProvider::embed`]
+pub type EmbeddingFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, EmbeddingError>> + Send + 'a>>;
+
+/// Computes embedding vectors for text.
+///
+/// Uses manual async (`Pin<Box<dyn Future>>`) like `CatalogBackend` so providers
+/// can be stored as trait objects.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifier stored with each vector; changing it re-embeds every dataset
+    fn model_id(&self) -> &str;
+
+    /// Embed a batch of texts, returning one vector per input in order
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a>;
+}
+
+/// In-process embedder using feature hashing of words and character trigrams.
+///
+/// Needs no model files or network access. Trigrams make it robust to
+/// pluralization, abbreviations, and naming conventions (`customer_orders` vs
+/// "customer order"), but it has no notion of synonyms; use [`HttpEmbedder`]
+/// with a trained model for that.
+pub struct HashingEmbedder {
+    dimensions: usize,
+    model_id: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions,
+            model_id: format!("hashing-{}", dimensions),
+        }
+    }
+
+    /// Embed a single text synchronously.
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        for token in tokenize(text) {
+            self.add_feature(&mut vector, &token, 1.0);
+            let padded: Vec<char> = format!("^{}$", token).chars().collect();
+            for trigram in padded.windows(3) {
+                self.add_feature(&mut vector, &trigram.iter().collect::<String>(), 0.5);
+            }
+        }
+        normalize(&mut vector);
+        vector
+    }
+
+    fn add_feature(&self, vector: &mut [f32], feature: &str, weight: f32) {
+        let hash = fnv1a(feature.as_bytes());
+        let index = (hash % self.dimensions as u64) as usize;
+        // Sign bit from the high half reduces bias from collisions
+        let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign * weight;
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASHING_DIMENSIONS)
+    }
+}
+
+impl EmbeddingProvider for HashingEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a> {
+        Box::pin(async move { Ok(texts.iter().map(|t| self.embed_text(t)).collect()) })
+    }
+}
+
+/// Embedder calling an OpenAI-compatible embeddings API.
+pub struct HttpEmbedder {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HttpEmbeddingResponse {
+    data: Vec<HttpEmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct HttpEmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+impl HttpEmbedder {
+    pub fn new(url: String, model: String, api_key: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            client,
+            url,
+            model,
+            api_key,
+        }
+    }
+}
+
+impl EmbeddingProvider for HttpEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a> {
+        Box::pin(async move {
+            if texts.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let mut request = self.client.post(&self.url).json(&serde_json::json!({
+                "model": self.model,
+                "input": texts,
+            }));
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
+            }
+
+            let response = request
+                .send()
+                .await
+                .map_err(|e| EmbeddingError::Network(e.to_string()))?;
+            if !response.status().is_success() {
+                return Err(EmbeddingError::HttpStatus(
+                    response.status().as_u16(),
+                    response.text().await.unwrap_or_default(),
+                ));
+            }
+
+            let mut body: HttpEmbeddingResponse = response
+                .json()
+                .await
+                .map_err(|e| EmbeddingError::InvalidResponse(e.to_string()))?;
+            if body.data.len() != texts.len() {
+                return Err(EmbeddingError::InvalidResponse(format!(
+                    "expected {} vectors, got {}",
+                    texts.len(),
+                    body.data.len()
+                )));
+            }
+            body.data.sort_by_key(|d| d.index);
+            Ok(body
+                .data
+                .into_iter()
+                .map(|d| {
+                    let mut v = d.embedding;
+                    normalize(&mut v);
+                    v
+                })
+                .collect())
+        })
+    }
+}
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Semantic search configuration.
+#[derive(Debug, Clone)]
+pub struct SemanticSearchConfig {
+    pub provider: ProviderConfig,
+    /// Weight of cosine similarity in the blended score (FTS gets `1 - weight`)
+    pub weight: f32,
+}
+
+/// Embedding provider selection.
+#[derive(Debug, Clone)]
+pub enum ProviderConfig {
+    Hashing {
+        dimensions: usize,
+    },
+    Http {
+        url: String,
+        model: String,
+        api_key: Option<String>,
+    },
+}
+
+impl Default for SemanticSearchConfig {
+    fn default() -> Self {
+        Self {
+            provider: ProviderConfig::Hashing {
+                dimensions: DEFAULT_HASHING_DIMENSIONS,
+            },
+            weight: DEFAULT_SEMANTIC_WEIGHT,
+        }
+    }
+}
+
+impl SemanticSearchConfig {
+    /// Load configuration from `METAFUSE_EMBEDDING_*` environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let provider = match std::env::var("METAFUSE_EMBEDDING_PROVIDER")
+            .unwrap_or_else(|_| "hashing".to_string())
+            .as_str()
+        {
+            "hashing" => {
+                let dimensions = match std::env::var("METAFUSE_EMBEDDING_DIMENSIONS") {
+                    Ok(v) => v
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|d| (8..=4096).contains(d))
+                        .ok_or_else(|| {
+                            format!(
+                                "METAFUSE_EMBEDDING_DIMENSIONS must be between 8 and 4096, got '{}'",
+                                v
+                            )
+                        })?,
+                    Err(_) => DEFAULT_HASHING_DIMENSIONS,
+                };
+                ProviderConfig::Hashing { dimensions }
+            }
+            "http" => ProviderConfig::Http {
+                url: std::env::var("METAFUSE_EMBEDDING_URL").map_err(|_| {
+                    "METAFUSE_EMBEDDING_URL is required when METAFUSE_EMBEDDING_PROVIDER=http"
+                        .to_string()
+                })?,
+                model: std::env::var("METAFUSE_EMBEDDING_MODEL")
+                    .unwrap_or_else(|_| "text-embedding-3-small".to_string()),
+                api_key: std::env::var("METAFUSE_EMBEDDING_API_KEY").ok(),
+            },
+            other => {
+                return Err(format!(
+                    "Unknown METAFUSE_EMBEDDING_PROVIDER '{}' (expected: hashing, http)",
+                    other
+                ))
+            }
+        };
+
+        let weight = match std::env::var("METAFUSE_SEMANTIC_WEIGHT") {
+            Ok(v) => v
+                .parse::<f32>()
+                .ok()
+                .filter(|w| (0.0..=1.0).contains(w))
+                .ok_or_else(|| {
+                    format!(
+                        "METAFUSE_SEMANTIC_WEIGHT must be between 0.0 and 1.0, got '{}'",
+                        v
+                    )
+                })?,
+            Err(_) => DEFAULT_SEMANTIC_WEIGHT,
+        };
+
+        Ok(Self { provider, weight })
+    }
+}
+
+/// Configured semantic search: a provider plus blending weight.
+#[derive(Clone)]
+pub struct SemanticSearch {
+    pub provider: Arc<dyn EmbeddingProvider>,
+    pub weight: f32,
+    /// Catalogs with a refresh running, by backend address
+    refreshing: Arc<Mutex<HashSet<usize>>>,
+}
+
+impl SemanticSearch {
+    pub fn new(config: &SemanticSearchConfig) -> Self {
+        let provider: Arc<dyn EmbeddingProvider> = match &config.provider {
+            ProviderConfig::Hashing { dimensions } => Arc::new(HashingEmbedder::new(*dimensions)),
+            ProviderConfig::Http {
+                url,
+                model,
+                api_key,
+            } => Arc::new(HttpEmbedder::new(
+                url.clone(),
+                model.clone(),
+                api_key.clone(),
+            )),
+        };
+        Self {
+            provider,
+            weight: config.weight,
+            refreshing: Arc::default(),
+        }
+    }
+
+    /// Refresh the catalog's stale vectors in a background task, unless a
+    /// refresh of it is already running.
+    pub fn refresh_in_background(&self, backend: Arc<DynCatalogBackend>) {
+        let key = Arc::as_ptr(&backend) as *const () as usize;
+        if !self
+            .refreshing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key)
+        {
+            return;
+        }
+        let provider = Arc::clone(&self.provider);
+        let refreshing = Arc::clone(&self.refreshing);
+        tokio::spawn(async move {
+            match refresh_embeddings(provider.as_ref(), backend.as_ref()).await {
+                Ok(0) => {}
+                Ok(count) => {
+                    debug!(count, model = %provider.model_id(), "Refreshed dataset embeddings")
+                }
+                Err(e) => warn!(error = %e, "Failed to refresh dataset embeddings"),
+            }
+            refreshing
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&key);
+        });
+    }
+}
+
+/// Embed and store a catalog's stale vectors, [`MAX_EMBEDDING_BATCH`] texts
+/// per provider call.
+///
+/// Each batch is stored once embedded, so a failing call keeps the batches
+/// before it. Returns how many vectors were refreshed.
+pub async fn refresh_embeddings(
+    provider: &dyn EmbeddingProvider,
+    backend: &DynCatalogBackend,
+) -> Result<usize, String> {
+    let model_id = provider.model_id();
+    let stale = {
+        let conn = backend.get_connection().await.map_err(|e| e.to_string())?;
+        stale_embeddings(&conn, model_id).map_err(|e| e.to_string())?
+    };
+
+    let mut refreshed = 0;
+    for batch in stale.chunks(MAX_EMBEDDING_BATCH) {
+        let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+        let vectors = provider
+            .embed(&texts)
+            .await
+            .map_err(|e| format!("Embedding provider failed: {}", e))?;
+        let embeddings: Vec<(i64, String, Vec<f32>)> = batch
+            .iter()
+            .cloned()
+            .zip(vectors)
+            .map(|((id, text), vector)| (id, text, vector))
+            .collect();
+        let conn = backend.get_connection().await.map_err(|e| e.to_string())?;
+        store_embeddings(&conn, model_id, &embeddings).map_err(|e| e.to_string())?;
+        refreshed += embeddings.len();
+    }
+    Ok(refreshed)
+}
+
+// =============================================================================
+// Vector Math
+// =============================================================================
+
+/// Cosine similarity of two vectors (0.0 if either is zero or lengths differ).
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Blend cosine similarities with FTS rank positions.
+///
+/// `fts_ranked` is in FTS order (best first); position `p` of `n` scores
+/// `1 - p / n`. Datasets without an FTS match are kept only if their
+/// similarity is at least [`MIN_SIMILARITY`]. Returns `(dataset_id, score)`
+/// sorted by score, best first.
+pub fn blend_scores(
+    similarities: &HashMap<i64, f32>,
+    fts_ranked: &[i64],
+    weight: f32,
+) -> Vec<(i64, f32)> {
+    let n = fts_ranked.len() as f32;
+    let fts: HashMap<i64, f32> = fts_ranked
+        .iter()
+        .enumerate()
+        .map(|(p, id)| (*id, 1.0 - p as f32 / n))
+        .collect();
+
+    let mut ids: Vec<i64> = similarities
+        .iter()
+        .filter(|(_, sim)| **sim >= MIN_SIMILARITY)
+        .map(|(id, _)| *id)
+        .chain(fts_ranked.iter().copied())
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let mut scored: Vec<(i64, f32)> = ids
+        .into_iter()
+        .map(|id| {
+            let sim = similarities.get(&id).copied().unwrap_or(0.0).max(0.0);
+            let rank = fts.get(&id).copied().unwrap_or(0.0);
+            (id, weight * sim + (1.0 - weight) * rank)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored
+}
+
+/// Build an FTS query matching any word of a natural-language query.
+///
+/// Returns `None` if the query has no searchable words.
+pub fn fts_any_terms_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = tokenize(query)
+        .into_iter()
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+/// Text embedded for a dataset: its name with separators as spaces, plus description.
+pub fn embedding_text(name: &str, description: Option<&str>) -> String {
+    let name = name.replace(['_', '-', '.'], " ");
+    match description {
+        Some(d) if !d.trim().is_empty() => format!("{}\n{}", name, d.trim()),
+        _ => name,
+    }
+}
+
+/// Datasets whose stored vector is missing or was computed from different text or model.
+///
+/// Returns `(dataset_id, text)` pairs to embed.
+pub fn stale_embeddings(conn: &Connection, model_id: &str) -> rusqlite::Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT d.id, d.name, d.description, e.model, e.source_text
+        FROM datasets d
+        LEFT JOIN dataset_embeddings e ON e.dataset_id = d.id
+        WHERE d.deleted_at IS NULL
+        "#,
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?;
+
+    let mut stale = Vec::new();
+    for row in rows {
+        let (id, name, description, model, source_text) = row?;
+        let text = embedding_text(&name, description.as_deref());
+        if model.as_deref() != Some(model_id) || source_text.as_deref() != Some(text.as_str()) {
+            stale.push((id, text));
+        }
+    }
+    Ok(stale)
+}
+
+/// Store vectors computed by `model_id`, replacing any existing vector per dataset.
+pub fn store_embeddings(
+    conn: &Connection,
+    model_id: &str,
+    embeddings: &[(i64, String, Vec<f32>)],
+) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            r#"
+            INSERT INTO dataset_embeddings (dataset_id, model, dimensions, embedding, source_text, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
+            ON CONFLICT(dataset_id) DO UPDATE SET
+                model = excluded.model,
+                dimensions = excluded.dimensions,
+                embedding = excluded.embedding,
+                source_text = excluded.source_text,
+                updated_at = excluded.updated_at
+            "#,
+        )?;
+        for (dataset_id, text, vector) in embeddings {
+            let blob: Vec<u8> = vector.iter().flat_map(|x| x.to_le_bytes()).collect();
+            stmt.execute(params![dataset_id, model_id, vector.len(), blob, text])?;
+        }
+    }
+    tx.commit()
+}
+
+/// Load all vectors computed by `model_id`.
+pub fn load_embeddings(
+    conn: &Connection,
+    model_id: &str,
+) -> rusqlite::Result<Vec<(i64, Vec<f32>)>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT e.dataset_id, e.embedding
+        FROM dataset_embeddings e
+        JOIN datasets d ON d.id = e.dataset_id
+        WHERE e.model = ?1 AND d.deleted_at IS NULL
+        "#,
+    )?;
+    let rows = stmt.query_map([model_id], |row| {
+        let blob: Vec<u8> = row.get(1)?;
+        let vector = blob
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        Ok((row.get::<_, i64>(0)?, vector))
+    })?;
+    rows.collect()
+}
+
+/// Default number of results for `?mode=semantic`
+pub(crate) const DEFAULT_SEMANTIC_LIMIT: usize = 20;
+
+/// Maximum number of results for `?mode=semantic`
+pub(crate) const MAX_SEMANTIC_LIMIT: usize = 100;
+
+/// Rank datasets by embedding similarity blended with FTS rank
+///
+/// Ranks with the stored dataset vectors and starts a background refresh of
+/// missing or outdated ones; only the query is embedded while the request
+/// waits. Datasets hidden by `exclusion` are dropped before the limit applies.
+pub(crate) async fn semantic_search_datasets(
+    search: &SemanticSearch,
+    backend: &Arc<DynCatalogBackend>,
+    query: &str,
+    limit: usize,
+    exclusion: Option<&(String, Vec<String>)>,
+    request_id: &str,
+) -> Result<Vec<DatasetResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let model_id = search.provider.model_id();
+    search.refresh_in_background(Arc::clone(backend));
+
+    let query_vector = search
+        .provider
+        .embed(&[query.to_string()])
+        .await
+        .map_err(|e| {
+            internal_error(
+                format!("Embedding provider failed: {}", e),
+                request_id.to_string(),
+            )
+        })?
+        .pop()
+        .unwrap_or_default();
+
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+
+    let similarities: HashMap<i64, f32> = load_embeddings(&conn, model_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?
+        .into_iter()
+        .map(|(id, vector)| (id, cosine_similarity(&query_vector, &vector)))
+        .collect();
+
+    // Match any query word so FTS contributes to recall, not just precision
+    let fts_ranked: Vec<i64> = match fts_any_terms_query(query) {
+        Some(fts_query) => {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT d.id
+                    FROM datasets d
+                    JOIN dataset_search s ON d.name = s.dataset_name
+                    WHERE dataset_search MATCH ?1 AND d.deleted_at IS NULL
+                    ORDER BY bm25(dataset_search)
+                    "#,
+                )
+                .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+            let ids = stmt
+                .query_map([fts_query], |row| row.get::<_, i64>(0))
+                .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+            ids
+        }
+        None => Vec::new(),
+    };
+
+    let mut ranked = blend_scores(&similarities, &fts_ranked, search.weight);
+
+    // Drop hidden datasets before the limit, so they don't take visible ones' places
+    if let Some((clause, bindings)) = exclusion {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT d.id FROM datasets d WHERE d.deleted_at IS NULL AND {}",
+                clause
+            ))
+            .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+        let visible = stmt
+            .query_map(params_from_iter(bindings.iter()), |row| {
+                row.get::<_, i64>(0)
+            })
+            .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?
+            .collect::<Result<HashSet<_>, _>>()
+            .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+        ranked.retain(|(id, _)| visible.contains(id));
+    }
+    ranked.truncate(limit);
+    if ranked.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        r#"
+        SELECT d.id, d.name, d.path, d.format, d.delta_location, d.description, d.tenant, d.domain, d.owner,
+               d.created_at, d.last_updated, d.row_count, d.size_bytes, d.partition_keys
+        FROM datasets d
+        WHERE d.id IN ({}) AND d.deleted_at IS NULL
+        "#,
+        vec!["?"; ranked.len()].join(", ")
+    );
+    let ids: Vec<i64> = ranked.iter().map(|(id, _)| *id).collect();
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+    let mut by_id: HashMap<i64, DatasetResponse> = stmt
+        .query_map(params_from_iter(ids.iter()), |row| {
+            let row_count: Option<i64> = row.get(11)?;
+            let size_bytes: Option<i64> = row.get(12)?;
+            let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+            Ok(DatasetResponse {
+                id: row.get(0)?,
+                uuid: None,
+                name: row.get(1)?,
+                path: row.get(2)?,
+                format: row.get(3)?,
+                delta_location: row.get(4)?,
+                description: row.get(5)?,
+                tenant: row.get(6)?,
+                domain: row.get(7)?,
+                owner: row.get(8)?,
+                created_at: row.get(9)?,
+                last_updated: row.get(10)?,
+                operational: OperationalMetaResponse {
+                    row_count,
+                    size_bytes,
+                    partition_keys,
+                },
+                freshness: None,
+                metadata_completeness: None,
+                custom_metadata: None,
+                tags: None,
+                quality_summary: None,
+                archived_at: None,
+                write_report: None,
+                owner_profile: None,
+            })
+        })
+        .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?
+        .map(|r| r.map(|d| (d.id, d)))
+        .collect::<Result<_, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+
+    Ok(ranked
+        .into_iter()
+        .filter_map(|(id, _)| by_id.remove(&id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metafuse_catalog_core::{init_sqlite_schema, migrations::run_migrations};
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        for (name, description) in [
+            ("customer_orders", "Orders placed by customers"),
+            ("web_clickstream", "Page views and clicks"),
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, description, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', ?2, datetime('now'), datetime('now'))",
+                [name, description],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_hashing_embedder_similarity() {
+        let embedder = HashingEmbedder::default();
+        let orders = embedder.embed_text("customer_orders");
+        let query = embedder.embed_text("customer order");
+        let clicks = embedder.embed_text("web clickstream");
+
+        assert_eq!(orders.len(), DEFAULT_HASHING_DIMENSIONS);
+        assert!((cosine_similarity(&orders, &orders) - 1.0).abs() < 1e-5);
+        assert!(cosine_similarity(&orders, &query) > cosine_similarity(&clicks, &query));
+        assert_eq!(embedder.embed_text("orders"), embedder.embed_text("orders"));
+    }
+
+    #[test]
+    fn test_cosine_similarity_edge_cases() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_blend_scores() {
+        let similarities = HashMap::from([(1, 0.9), (2, 0.1), (3, 0.5)]);
+
+        // Pure semantic: dataset 2 is below the similarity floor and has no FTS hit
+        let blended = blend_scores(&similarities, &[], 1.0);
+        assert_eq!(
+            blended.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        // FTS hits are always kept, and a strong keyword match can outrank similarity
+        let blended = blend_scores(&similarities, &[2], 0.3);
+        assert_eq!(blended[0].0, 2);
+        assert_eq!(blended.len(), 3);
+    }
+
+    #[test]
+    fn test_fts_any_terms_query() {
+        assert_eq!(
+            fts_any_terms_query("Customer churn!").unwrap(),
+            "\"customer\" OR \"churn\""
+        );
+        assert!(fts_any_terms_query("  --  ").is_none());
+    }
+
+    /// Hashing embedder recording the size of each call
+    struct CountingEmbedder {
+        inner: HashingEmbedder,
+        calls: Mutex<Vec<usize>>,
+    }
+
+    impl EmbeddingProvider for CountingEmbedder {
+        fn model_id(&self) -> &str {
+            self.inner.model_id()
+        }
+
+        fn embed<'a>(&'a self, texts: &'a [String]) -> EmbeddingFuture<'a> {
+            self.calls.lock().unwrap().push(texts.len());
+            self.inner.embed(texts)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_embeddings_in_batches() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(
+            metafuse_catalog_storage::backend_from_uri(
+                dir.path().join("catalog.db").to_str().unwrap(),
+            )
+            .unwrap(),
+        );
+        backend.initialize().await.unwrap();
+        {
+            let conn = backend.get_connection().await.unwrap();
+            run_migrations(&conn).unwrap();
+            let tx = conn.unchecked_transaction().unwrap();
+            for i in 0..MAX_EMBEDDING_BATCH + 5 {
+                tx.execute(
+                    "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                     VALUES (?1, '/data', 'parquet', datetime('now'), datetime('now'))",
+                    [format!("dataset_{}", i)],
+                )
+                .unwrap();
+            }
+            tx.commit().unwrap();
+        }
+        let embedder = CountingEmbedder {
+            inner: HashingEmbedder::new(8),
+            calls: Mutex::new(Vec::new()),
+        };
+
+        let refreshed = refresh_embeddings(&embedder, backend.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(refreshed, MAX_EMBEDDING_BATCH + 5);
+        assert_eq!(
+            *embedder.calls.lock().unwrap(),
+            vec![MAX_EMBEDDING_BATCH, 5]
+        );
+
+        // Nothing is stale afterwards, so nothing is embedded
+        assert_eq!(
+            refresh_embeddings(&embedder, backend.as_ref())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(embedder.calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_embedding_refresh_cycle() {
+        let conn = setup();
+        let embedder = HashingEmbedder::new(16);
+
+        let stale = stale_embeddings(&conn, embedder.model_id()).unwrap();
+        assert_eq!(stale.len(), 2);
+        assert_eq!(stale[0].1, "customer orders\nOrders placed by customers");
+
+        let embedded: Vec<_> = stale
+            .into_iter()
+            .map(|(id, text)| {
+                let v = embedder.embed_text(&text);
+                (id, text, v)
+            })
+            .collect();
+        store_embeddings(&conn, embedder.model_id(), &embedded).unwrap();
+        assert!(stale_embeddings(&conn, embedder.model_id())
+            .unwrap()
+            .is_empty());
+
+        let loaded = load_embeddings(&conn, embedder.model_id()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].1, embedded[0].2);
+
+        // Description change or model change makes vectors stale again
+        conn.execute(
+            "UPDATE datasets SET description = 'Customer purchases' WHERE name = 'customer_orders'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(
+            stale_embeddings(&conn, embedder.model_id()).unwrap().len(),
+            1
+        );
+        assert_eq!(stale_embeddings(&conn, "other-model").unwrap().len(), 2);
+        assert!(load_embeddings(&conn, "other-model").unwrap().is_empty());
+    }
+}
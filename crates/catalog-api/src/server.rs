@@ -0,0 +1,4226 @@
+//! Catalog API Server
+//!
+//! The REST API as an axum [`Router`], so it can run as the `metafuse-api`
+//! binary or be embedded in another axum application:
+//!
+//! - [`build_router`] wires handlers, middleware and background tasks around
+//!   an open catalog backend and returns a router with its state applied; it
+//!   binds no socket. Mount it with `Router::merge`, or set
+//!   `METAFUSE_BASE_PATH` and merge it to serve it under a prefix, so
+//!   self-referencing links include the prefix.
+//! - [`serve`] opens the catalog, builds the router and serves it, as the
+//!   binary does.
+//!
+//! Features are configured from the environment as for the binary (see the
+//! module docs of each feature); [`ServerConfig`] holds the startup settings.
+//! Background tasks are spawned on the current Tokio runtime and run for the
+//! life of the process. The router reads the peer address for trusted proxy
+//! checks, so serve it with `into_make_service_with_connect_info::<SocketAddr>()`.
+
+#[cfg(feature = "alerting")]
+use crate::alerting;
+use crate::archive;
+#[cfg(feature = "audit")]
+use crate::audit;
+#[cfg(feature = "audit")]
+use crate::audit_forwarder;
+use crate::badges;
+use crate::cache_control;
+use crate::catalog_stats;
+#[cfg(feature = "classification")]
+use crate::classification;
+#[cfg(feature = "classification")]
+use crate::classification_review;
+use crate::consumers;
+#[cfg(feature = "api-keys")]
+use crate::control_plane;
+#[cfg(feature = "api-keys")]
+use crate::data_residency;
+use crate::dataset_acl;
+use crate::dataset_refs;
+use crate::diagnostics;
+use crate::digests;
+use crate::envelope;
+use crate::error_codes::{self, ErrorCode};
+use crate::export_schedules;
+use crate::external_url;
+use crate::format_advisor;
+use crate::freshness;
+use crate::groups;
+use crate::i18n;
+use crate::impact;
+#[cfg(feature = "ldap-directory")]
+use crate::ldap;
+use crate::lineage_edges;
+use crate::lineage_graph;
+use crate::lineage_integrity;
+use crate::markers;
+use crate::metadata_completeness;
+#[cfg(feature = "metrics")]
+use crate::metrics;
+use crate::multi_tenant;
+use crate::namespaces;
+#[cfg(feature = "oidc")]
+use crate::oidc;
+use crate::orphans;
+#[cfg(feature = "api-keys")]
+use crate::public_catalog;
+use crate::public_ids;
+use crate::quality;
+#[cfg(feature = "rate-limiting")]
+use crate::rate_limiting;
+use crate::renames;
+#[cfg(feature = "usage-analytics")]
+use crate::request_log;
+use crate::response_profiles;
+use crate::sandbox;
+use crate::scim;
+use crate::sparse_fields::{self};
+use crate::strict_json::{self};
+use crate::subscriptions;
+use crate::suggest;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver;
+use crate::timeline;
+use crate::timeouts;
+use crate::trash;
+#[cfg(feature = "usage-analytics")]
+use crate::usage_analytics;
+use crate::users;
+use crate::webhooks;
+use crate::write_hooks;
+
+#[cfg(feature = "contracts")]
+use crate::contracts;
+
+#[cfg(feature = "semantic-search")]
+use crate::semantic_search;
+
+#[cfg(feature = "column-lineage")]
+use crate::lineage;
+
+#[cfg(feature = "api-keys")]
+use crate::admin;
+use crate::auto_tag_rules;
+use crate::catalog_export;
+use crate::custom_metadata;
+use crate::dataset_writes;
+use crate::datasets;
+use crate::delta_metadata;
+use crate::domains;
+use crate::glossary;
+use crate::governance;
+use crate::owners;
+use crate::tags;
+use axum::{
+    extract::{DefaultBodyLimit, Extension, FromRequestParts, Path, Query, Request},
+    http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+use metafuse_catalog_core::hooks::WriteHooks;
+use metafuse_catalog_core::lineage_mode::LineageMode;
+use metafuse_catalog_core::{dataset_uuids, migrations};
+use metafuse_catalog_delta::{DeltaReader, ReadLimits};
+use metafuse_catalog_storage::{backend_from_uri, DynCatalogBackend};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tower_http::cors::CorsLayer;
+use tracing::Instrument;
+use uuid::Uuid;
+
+// Multi-tenant imports
+use multi_tenant::{resolve_backend, MultiTenantConfig, MultiTenantResources, TenantBackend};
+
+#[cfg(feature = "api-keys")]
+use tenant_resolver::{ResolvedTenant, TenantResolverConfig};
+
+#[cfg(feature = "api-keys")]
+use crate::field_permissions;
+
+#[cfg(feature = "api-keys")]
+use control_plane::TenantFeatureFlags;
+
+#[cfg(all(
+    feature = "api-keys",
+    any(feature = "usage-analytics", feature = "classification")
+))]
+use control_plane::TenantFeature;
+
+/// Request ID for tracking requests through the system
+#[derive(Debug, Clone)]
+pub(crate) struct RequestId(pub(crate) String);
+
+/// Application state shared across handlers
+pub(crate) struct AppState {
+    pub(crate) backend: Arc<DynCatalogBackend>,
+    pub(crate) delta_reader: Arc<DeltaReader>,
+    #[cfg(feature = "audit")]
+    pub(crate) audit_logger: audit::AuditLogger,
+    #[cfg(feature = "usage-analytics")]
+    pub(crate) usage_tracker: Arc<usage_analytics::UsageTracker>,
+    #[cfg(feature = "semantic-search")]
+    pub(crate) semantic_search: semantic_search::SemanticSearch,
+    /// Soft delete retention for datasets
+    pub(crate) trash_config: trash::TrashConfig,
+    /// Retention for completion markers
+    pub(crate) marker_config: markers::MarkerConfig,
+    /// TTL limits for sandbox namespaces
+    pub(crate) sandbox_config: sandbox::SandboxConfig,
+    /// Slowest recent requests for diagnostics
+    pub(crate) slow_requests: Arc<diagnostics::SlowRequestLog>,
+    /// Hooks run on dataset creates, updates, and deletes
+    pub(crate) write_hooks: WriteHooks,
+    /// Queues webhook deliveries for catalog changes
+    pub(crate) webhooks: webhooks::WebhookDispatcher,
+    /// Server-wide lineage mode (`METAFUSE_LINEAGE_MODE`), if set
+    pub(crate) lineage_mode: Option<LineageMode>,
+    /// Defaults for upstream quality propagation
+    pub(crate) quality_propagation: quality::QualityPropagationConfig,
+    /// How long `?include=quality` reuses stored results
+    pub(crate) quality_cache: quality::QualityCacheConfig,
+    /// Anonymous access and caching of dataset badges
+    pub(crate) badges: badges::BadgeConfig,
+    /// Named bundles of includes and fields (`?profile=`)
+    pub(crate) response_profiles: Arc<response_profiles::ResponseProfiles>,
+    /// Days search keeps finding moved datasets by their old path; 0 disables
+    pub(crate) path_search_grace_days: u32,
+    /// Whether responses include integer dataset ids next to UUIDs
+    pub(crate) expose_integer_ids: bool,
+    /// Which tenant roles may change each dataset field
+    #[cfg(feature = "api-keys")]
+    pub(crate) field_permissions: Arc<field_permissions::FieldPermissions>,
+    /// Directories validating and describing owners, verifiers and actors
+    pub(crate) users: Arc<users::UserResolver>,
+    /// Multi-tenant resources (factory and control plane)
+    pub(crate) multi_tenant: MultiTenantResources,
+}
+
+impl Clone for AppState {
+    fn clone(&self) -> Self {
+        Self {
+            backend: Arc::clone(&self.backend),
+            delta_reader: Arc::clone(&self.delta_reader),
+            #[cfg(feature = "audit")]
+            audit_logger: self.audit_logger.clone(),
+            #[cfg(feature = "usage-analytics")]
+            usage_tracker: Arc::clone(&self.usage_tracker),
+            #[cfg(feature = "semantic-search")]
+            semantic_search: self.semantic_search.clone(),
+            trash_config: self.trash_config.clone(),
+            marker_config: self.marker_config.clone(),
+            sandbox_config: self.sandbox_config.clone(),
+            slow_requests: Arc::clone(&self.slow_requests),
+            write_hooks: self.write_hooks.clone(),
+            webhooks: self.webhooks.clone(),
+            lineage_mode: self.lineage_mode,
+            quality_propagation: self.quality_propagation.clone(),
+            quality_cache: self.quality_cache.clone(),
+            badges: self.badges.clone(),
+            response_profiles: Arc::clone(&self.response_profiles),
+            path_search_grace_days: self.path_search_grace_days,
+            expose_integer_ids: self.expose_integer_ids,
+            #[cfg(feature = "api-keys")]
+            field_permissions: Arc::clone(&self.field_permissions),
+            users: Arc::clone(&self.users),
+            multi_tenant: self.multi_tenant.clone(),
+        }
+    }
+}
+
+/// Dataset path parameter that accepts either the dataset name or its UUID.
+///
+/// UUIDs are resolved to the current name, so handlers keep looking datasets
+/// up by name. A UUID that matches no dataset is passed through unchanged and
+/// ends up as a normal not-found.
+pub(crate) struct DatasetPath(pub(crate) String);
+
+impl FromRequestParts<AppState> for DatasetPath {
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .extensions
+            .get::<RequestId>()
+            .map(|r| r.0.clone())
+            .unwrap_or_default();
+        let Path(value) = Path::<String>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| bad_request(e.body_text(), request_id.clone()))?;
+        Ok(Self(
+            resolve_dataset_ref(parts, state, value, request_id).await?,
+        ))
+    }
+}
+
+/// Dataset and field path parameters, resolving a dataset UUID like
+/// [`DatasetPath`].
+pub(crate) struct DatasetFieldPath(pub(crate) String, pub(crate) String);
+
+impl FromRequestParts<AppState> for DatasetFieldPath {
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .extensions
+            .get::<RequestId>()
+            .map(|r| r.0.clone())
+            .unwrap_or_default();
+        let Path((value, field)) = Path::<(String, String)>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| bad_request(e.body_text(), request_id.clone()))?;
+        Ok(Self(
+            resolve_dataset_ref(parts, state, value, request_id).await?,
+            field,
+        ))
+    }
+}
+
+/// Resolve a dataset name-or-UUID path value to the dataset name
+async fn resolve_dataset_ref(
+    parts: &Parts,
+    state: &AppState,
+    value: String,
+    request_id: String,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    if !public_ids::is_uuid(&value) {
+        return Ok(value);
+    }
+
+    let backend = resolve_backend(&state.backend, parts.extensions.get::<TenantBackend>());
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.clone()))?;
+    let name = dataset_uuids::name_for_uuid(&conn, &value)
+        .map_err(|e| internal_error(e.to_string(), request_id))?;
+    Ok(name.unwrap_or(value))
+}
+
+impl FromRequestParts<AppState> for envelope::EnvelopeQuery {
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .extensions
+            .get::<RequestId>()
+            .map(|r| r.0.clone())
+            .unwrap_or_default();
+        let Query(params) = Query::<envelope::EnvelopeParams>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| bad_request(e.body_text(), request_id))?;
+
+        // Page links point at the full route, also under nested routers
+        let url = parts
+            .extensions
+            .get::<external_url::ExternalUrl>()
+            .cloned()
+            .unwrap_or_default();
+        let path = parts
+            .extensions
+            .get::<axum::extract::OriginalUri>()
+            .map_or(parts.uri.path(), |uri| uri.0.path())
+            .to_string();
+        let path = url.route_path(&path).to_string();
+        let query = parts.uri.query().map(String::from);
+        Ok(Self::new(params, url, path, query))
+    }
+}
+
+/// Identity context for audit logging
+/// Extracted from request extensions - includes API key identity and client IP
+/// Always available to handlers; enrich_event only active with audit feature
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditContext {
+    pub(crate) api_key_id: Option<String>,
+    pub(crate) client_ip: Option<String>,
+    /// Platform operator, when the request uses an impersonation key
+    impersonator: Option<String>,
+    /// Signed-in user, when the request uses an OIDC token
+    user: Option<String>,
+}
+
+impl AuditContext {
+    /// Create new audit context with optional API key and client IP
+    fn new(api_key_id: Option<String>, client_ip: Option<String>) -> Self {
+        Self {
+            api_key_id,
+            client_ip,
+            impersonator: None,
+            user: None,
+        }
+    }
+
+    /// Enrich an audit event with identity context
+    ///
+    /// Impersonated requests are attributed to the operator, marked in the
+    /// event context, and OIDC-authenticated requests to the signed-in user.
+    #[cfg(feature = "audit")]
+    pub(crate) fn enrich_event(&self, event: audit::AuditEvent) -> audit::AuditEvent {
+        let event = match (&self.impersonator, &self.user, &self.api_key_id) {
+            (Some(operator), _, _) => event
+                .with_actor(operator, audit::ActorType::User)
+                .with_context(serde_json::json!({ "impersonated_by": operator })),
+            (None, Some(user), _) => event.with_actor(user, audit::ActorType::User),
+            (None, None, Some(key_id)) => event.with_actor(key_id, audit::ActorType::Service),
+            (None, None, None) => event.with_actor("anonymous", audit::ActorType::Anonymous),
+        };
+        match &self.client_ip {
+            Some(ip) => event.with_client_ip(ip),
+            None => event,
+        }
+    }
+}
+
+/// Caller context set by the tenant and auth middleware
+///
+/// Groups the optional request extensions dataset handlers need to resolve
+/// the tenant catalog and check access. Handlers destructure the parts they
+/// use and skip the rest with `..`.
+#[derive(Clone)]
+pub(crate) struct Caller {
+    pub(crate) tenant_backend: Option<Extension<TenantBackend>>,
+    pub(crate) identity: Option<Extension<dataset_acl::Identity>>,
+    #[cfg(feature = "api-keys")]
+    pub(crate) resolved_tenant: Option<Extension<ResolvedTenant>>,
+    #[cfg(feature = "api-keys")]
+    pub(crate) feature_flags: Option<Extension<TenantFeatureFlags>>,
+    #[cfg(feature = "api-keys")]
+    pub(crate) public_access: Option<Extension<public_catalog::PublicAccess>>,
+    pub(crate) request_sandbox: Option<Extension<sandbox::Sandbox>>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let extensions = &parts.extensions;
+        Ok(Self {
+            tenant_backend: extensions.get().cloned().map(Extension),
+            identity: extensions.get().cloned().map(Extension),
+            #[cfg(feature = "api-keys")]
+            resolved_tenant: extensions.get().cloned().map(Extension),
+            #[cfg(feature = "api-keys")]
+            feature_flags: extensions.get().cloned().map(Extension),
+            #[cfg(feature = "api-keys")]
+            public_access: extensions.get().cloned().map(Extension),
+            request_sandbox: extensions.get().cloned().map(Extension),
+        })
+    }
+}
+
+/// Error response with request ID for tracing
+#[derive(Debug, Serialize)]
+pub(crate) struct ErrorResponse {
+    pub(crate) error: String,
+    pub(crate) code: ErrorCode,
+    pub(crate) request_id: String,
+}
+
+/// Convert RBAC error responses to the standard ErrorResponse type
+#[cfg(feature = "api-keys")]
+pub(crate) fn rbac_error(
+    (status, json): (StatusCode, Json<multi_tenant::RbacErrorResponse>),
+) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        status,
+        Json(ErrorResponse {
+            error: json.error.clone(),
+            code: json.code,
+            request_id: json.request_id.clone(),
+        }),
+    )
+}
+
+/// Reject a write touching dataset fields the caller's role may not change.
+#[cfg(feature = "api-keys")]
+pub(crate) fn require_field_permissions<'a>(
+    permissions: &field_permissions::FieldPermissions,
+    resolved_tenant: Option<&ResolvedTenant>,
+    fields: impl IntoIterator<Item = &'a str>,
+    request_id: &str,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let Some(tenant) = resolved_tenant else {
+        return Ok(());
+    };
+    let role = tenant.effective_role();
+    let restricted = permissions.restricted(role.as_str(), fields);
+    if restricted.is_empty() {
+        return Ok(());
+    }
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            error: format!(
+                "Role '{}' cannot change restricted fields: {}",
+                role.as_str(),
+                restricted.join(", ")
+            ),
+            code: ErrorCode::Forbidden,
+            request_id: request_id.to_string(),
+        }),
+    ))
+}
+
+/// `ETag` header for a catalog version
+pub(crate) fn catalog_etag(version: i64) -> [(header::HeaderName, String); 1] {
+    [(header::ETAG, format!("\"{}\"", version))]
+}
+
+/// Catalog version required by an `If-Match` header (`*` or absent: any)
+pub(crate) fn if_match_version(
+    headers: &HeaderMap,
+    request_id: &RequestId,
+) -> Result<Option<i64>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value.to_str().unwrap_or_default().trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .and_then(|v| v.parse().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            bad_request(
+                format!(
+                    "Invalid If-Match header '{}': expected a quoted catalog version from an ETag",
+                    value
+                ),
+                request_id.0.clone(),
+            )
+        })
+}
+
+/// Fail with 412 unless the catalog is still at the expected version
+pub(crate) fn check_catalog_version(
+    conn: &rusqlite::Connection,
+    expected: Option<i64>,
+    request_id: &RequestId,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let current = metafuse_catalog_core::get_catalog_version(conn)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if current != expected {
+        return Err((
+            StatusCode::PRECONDITION_FAILED,
+            Json(ErrorResponse {
+                error: format!(
+                    "Catalog changed since version {} (now {}); re-read the dataset and retry",
+                    expected, current
+                ),
+                code: ErrorCode::PreconditionFailed,
+                request_id: request_id.0.clone(),
+            }),
+        ));
+    }
+    Ok(())
+}
+
+/// Enforce a dataset ACL for the caller.
+///
+/// Datasets the caller cannot read are reported as not found so their names
+/// do not leak; readable datasets without the required permission are 403.
+pub(crate) fn require_dataset_access(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    name: &str,
+    identity: Option<&dataset_acl::Identity>,
+    required: dataset_acl::AclPermission,
+    request_id: &RequestId,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let anonymous = dataset_acl::Identity::default();
+    let identity = identity.unwrap_or(&anonymous);
+    let allowed = |permission| {
+        dataset_acl::check(conn, dataset_id, identity, permission)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))
+    };
+    if !allowed(dataset_acl::AclPermission::Read)? {
+        return Err(dataset_not_found(name, request_id.0.clone()));
+    }
+    if required == dataset_acl::AclPermission::Write && !allowed(required)? {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: format!("Write access to dataset '{}' denied by its ACL", name),
+                code: ErrorCode::Forbidden,
+                request_id: request_id.0.clone(),
+            }),
+        ));
+    }
+    Ok(())
+}
+
+/// Check a per-tenant feature flag resolved by `tenant_feature_flags_middleware`.
+/// Requests without resolved flags (single-tenant mode) have every feature enabled.
+#[cfg(all(
+    feature = "api-keys",
+    any(feature = "usage-analytics", feature = "classification")
+))]
+pub(crate) fn feature_enabled(
+    flags: Option<&Extension<TenantFeatureFlags>>,
+    feature: TenantFeature,
+) -> bool {
+    flags.map(|f| f.0.is_enabled(feature)).unwrap_or(true)
+}
+
+// =============================================================================
+// Server Configuration
+// =============================================================================
+
+/// Startup settings of the API server
+///
+/// [`ServerConfig::from_env`] reads every setting from the environment, as
+/// the `metafuse-api` binary does. Applications embedding the router can
+/// build the config in code instead, and [`ServerConfig::default`] reads
+/// nothing from the environment.
+///
+/// [`build_router`] takes these settings from the config only. Some
+/// lower-level settings are not part of it yet and are still read from the
+/// environment while building the router or serving requests, such as
+/// `METAFUSE_ADMIN_KEY`, `METAFUSE_AUDIT_*`, `METAFUSE_QUOTA_DRY_RUN` and
+/// `METAFUSE_SCHEMA_DIFF_MAX_VERSIONS`.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Catalog URI opened by [`serve`] (`METAFUSE_CATALOG_PATH`)
+    pub catalog_path: String,
+    /// Port [`serve`] listens on (`METAFUSE_PORT` or `PORT`)
+    pub port: u16,
+    /// Apply pending schema migrations at startup (`METAFUSE_RUN_MIGRATIONS`)
+    pub run_migrations: bool,
+    /// Delta metadata cache TTL in seconds (`METAFUSE_DELTA_CACHE_TTL`)
+    pub delta_cache_ttl_secs: u64,
+    /// Limits on concurrent Delta reads and their retries
+    pub read_limits: ReadLimits,
+    /// Reject unknown request body fields (`METAFUSE_STRICT_REQUESTS`)
+    pub strict_requests: bool,
+    /// Include integer dataset ids in responses next to their UUIDs
+    /// (`METAFUSE_EXPOSE_INTEGER_IDS`)
+    pub expose_integer_ids: bool,
+    /// Hooks run on every dataset write (`METAFUSE_WRITE_*`)
+    pub write_hooks: write_hooks::WriteHookConfig,
+    /// Sinks audit events are forwarded to
+    #[cfg(feature = "audit")]
+    pub audit_forwarders: audit_forwarder::AuditForwarderConfig,
+    /// Group providers resolving the caller's teams
+    pub groups: groups::GroupResolver,
+    /// User directories for owners, verifiers and audit actors
+    pub users: users::UserResolver,
+    /// Scheduled digests of catalog changes (`alerting` feature)
+    pub digests: digests::DigestConfig,
+    /// Dataset trash retention
+    pub trash: trash::TrashConfig,
+    /// Catalog stats snapshots
+    pub stats: catalog_stats::StatsConfig,
+    /// Lineage integrity checks
+    pub lineage_integrity: lineage_integrity::IntegrityConfig,
+    /// Completion marker retention
+    pub markers: markers::MarkerConfig,
+    /// Sandbox namespaces and their expiry
+    pub sandbox: sandbox::SandboxConfig,
+    /// Slow request log
+    pub diagnostics: diagnostics::DiagnosticsConfig,
+    /// Downsampling of quality history
+    pub quality_compaction: quality::QualityCompactionConfig,
+    /// Upstream quality propagation
+    pub quality_propagation: quality::QualityPropagationConfig,
+    /// Reuse of stored quality scores
+    pub quality_cache: quality::QualityCacheConfig,
+    /// Dataset status badges
+    pub badges: badges::BadgeConfig,
+    /// Named bundles of response options
+    pub response_profiles: response_profiles::ResponseProfiles,
+    /// Days search keeps resolving a dataset's old paths
+    /// (`METAFUSE_PATH_SEARCH_GRACE_DAYS`)
+    pub path_search_grace_days: u32,
+    /// Default handling of unregistered upstreams (`METAFUSE_LINEAGE_MODE`)
+    pub lineage_mode: Option<LineageMode>,
+    #[cfg(feature = "api-keys")]
+    /// Metadata fields API keys need a permission to change
+    pub field_permissions: field_permissions::FieldPermissions,
+    /// Embedding provider for semantic search
+    #[cfg(feature = "semantic-search")]
+    pub semantic_search: semantic_search::SemanticSearchConfig,
+    /// Base path and forwarded headers for self-referencing URLs
+    pub external_url: external_url::ExternalUrlConfig,
+    /// Cache-Control policy by endpoint class
+    pub cache_control: cache_control::CacheControlConfig,
+    /// Latency budgets by route
+    pub timeouts: timeouts::TimeoutConfig,
+    /// Message bundles for Accept-Language localization
+    pub localizer: Arc<i18n::Localizer>,
+    /// Trusted identity headers for dataset ACLs
+    pub identity: dataset_acl::IdentityConfig,
+    /// Per-tenant catalogs and the control plane
+    pub multi_tenant: MultiTenantConfig,
+    /// Dataset watches (`alerting` feature)
+    pub watch: subscriptions::WatchConfig,
+    /// Destinations webhooks and watches may be sent to (`alerting` feature)
+    pub webhook_urls: webhooks::UrlPolicy,
+    /// Webhook delivery (`alerting` feature)
+    pub webhooks: webhooks::WebhookConfig,
+    /// Request log
+    #[cfg(feature = "usage-analytics")]
+    pub request_log: request_log::RequestLogConfig,
+    #[cfg(feature = "api-keys")]
+    /// Scheduled tenant exports
+    pub export_schedules: export_schedules::ExportScheduleConfig,
+    /// Request rate limits
+    #[cfg(feature = "rate-limiting")]
+    pub rate_limits: rate_limiting::RateLimitConfig,
+    /// Anonymous read-only catalog access
+    #[cfg(feature = "api-keys")]
+    pub public_catalog: public_catalog::PublicCatalogConfig,
+    /// Tenant labels on metrics
+    #[cfg(all(feature = "api-keys", feature = "metrics"))]
+    pub tenant_metrics: metrics::TenantMetricsConfig,
+    /// Issuer whose tokens are accepted alongside tenant API keys
+    #[cfg(feature = "oidc")]
+    pub oidc: Option<oidc::OidcConfig>,
+    /// Bearer token for SCIM provisioning; SCIM routes are served when set
+    /// (`METAFUSE_SCIM_TOKEN`)
+    pub scim_token: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            catalog_path: "metafuse_catalog.db".to_string(),
+            port: 8080,
+            run_migrations: false,
+            delta_cache_ttl_secs: 300, // 5 minutes
+            read_limits: ReadLimits::default(),
+            strict_requests: false,
+            expose_integer_ids: true,
+            write_hooks: write_hooks::WriteHookConfig::default(),
+            #[cfg(feature = "audit")]
+            audit_forwarders: audit_forwarder::AuditForwarderConfig::default(),
+            groups: groups::GroupResolver::default(),
+            users: users::UserResolver::default(),
+            digests: digests::DigestConfig::default(),
+            trash: trash::TrashConfig::default(),
+            stats: catalog_stats::StatsConfig::default(),
+            lineage_integrity: lineage_integrity::IntegrityConfig::default(),
+            markers: markers::MarkerConfig::default(),
+            sandbox: sandbox::SandboxConfig::default(),
+            diagnostics: diagnostics::DiagnosticsConfig::default(),
+            quality_compaction: quality::QualityCompactionConfig::default(),
+            quality_propagation: quality::QualityPropagationConfig::default(),
+            quality_cache: quality::QualityCacheConfig::default(),
+            badges: badges::BadgeConfig::default(),
+            response_profiles: response_profiles::ResponseProfiles::default(),
+            path_search_grace_days: 0,
+            lineage_mode: None,
+            #[cfg(feature = "api-keys")]
+            field_permissions: field_permissions::FieldPermissions::default(),
+            #[cfg(feature = "semantic-search")]
+            semantic_search: semantic_search::SemanticSearchConfig::default(),
+            external_url: external_url::ExternalUrlConfig::default(),
+            cache_control: cache_control::CacheControlConfig::default(),
+            timeouts: timeouts::TimeoutConfig::default(),
+            localizer: Arc::new(i18n::Localizer::default()),
+            identity: dataset_acl::IdentityConfig::default(),
+            multi_tenant: MultiTenantConfig::default(),
+            watch: subscriptions::WatchConfig::default(),
+            webhook_urls: webhooks::UrlPolicy::default(),
+            webhooks: webhooks::WebhookConfig::default(),
+            #[cfg(feature = "usage-analytics")]
+            request_log: request_log::RequestLogConfig::default(),
+            #[cfg(feature = "api-keys")]
+            export_schedules: export_schedules::ExportScheduleConfig::default(),
+            #[cfg(feature = "rate-limiting")]
+            rate_limits: rate_limiting::RateLimitConfig::builtin(),
+            #[cfg(feature = "api-keys")]
+            public_catalog: public_catalog::PublicCatalogConfig::default(),
+            #[cfg(all(feature = "api-keys", feature = "metrics"))]
+            tenant_metrics: metrics::TenantMetricsConfig {
+                include_tenant_id: false,
+            },
+            #[cfg(feature = "oidc")]
+            oidc: None,
+            scim_token: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let defaults = Self::default();
+        let port = match std::env::var("METAFUSE_PORT").or_else(|_| std::env::var("PORT")) {
+            Ok(port) => port.parse::<u16>().map_err(|e| {
+                tracing::error!("PORT must be a valid number: {}", e);
+                e
+            })?,
+            Err(_) => defaults.port,
+        };
+        Ok(Self {
+            catalog_path: std::env::var("METAFUSE_CATALOG_PATH")
+                .or_else(|_| std::env::var("METAFUSE_CATALOG"))
+                .unwrap_or(defaults.catalog_path),
+            port,
+            run_migrations: std::env::var("METAFUSE_RUN_MIGRATIONS").unwrap_or_default() == "true",
+            delta_cache_ttl_secs: std::env::var("METAFUSE_DELTA_CACHE_TTL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.delta_cache_ttl_secs),
+            read_limits: ReadLimits::from_env(),
+            strict_requests: std::env::var("METAFUSE_STRICT_REQUESTS").unwrap_or_default()
+                == "true",
+            expose_integer_ids: public_ids::expose_integer_ids_from_env()?,
+            write_hooks: write_hooks::WriteHookConfig::from_env()?,
+            #[cfg(feature = "audit")]
+            audit_forwarders: audit_forwarder::AuditForwarderConfig::from_env()?,
+            groups: groups::GroupResolver::from_env(),
+            users: users::UserResolver::from_env()?,
+            digests: digests::DigestConfig::from_env(),
+            trash: trash::TrashConfig::from_env(),
+            stats: catalog_stats::StatsConfig::from_env(),
+            lineage_integrity: lineage_integrity::IntegrityConfig::from_env(),
+            markers: markers::MarkerConfig::from_env(),
+            sandbox: sandbox::SandboxConfig::from_env(),
+            diagnostics: diagnostics::DiagnosticsConfig::from_env(),
+            quality_compaction: quality::QualityCompactionConfig::from_env(),
+            quality_propagation: quality::QualityPropagationConfig::from_env(),
+            quality_cache: quality::QualityCacheConfig::from_env(),
+            badges: badges::BadgeConfig::from_env()?,
+            response_profiles: response_profiles::ResponseProfiles::from_env(
+                datasets::VALID_INCLUDE_VALUES,
+                sparse_fields::DATASET_FIELDS,
+            )?,
+            path_search_grace_days: std::env::var("METAFUSE_PATH_SEARCH_GRACE_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.path_search_grace_days),
+            lineage_mode: lineage_edges::lineage_mode_from_env()?,
+            #[cfg(feature = "api-keys")]
+            field_permissions: field_permissions::FieldPermissions::from_env()?,
+            #[cfg(feature = "semantic-search")]
+            semantic_search: semantic_search::SemanticSearchConfig::from_env()?,
+            external_url: external_url::ExternalUrlConfig::from_env()?,
+            cache_control: cache_control::CacheControlConfig::from_env()?,
+            timeouts: timeouts::TimeoutConfig::from_env()?,
+            localizer: Arc::new(i18n::Localizer::from_env()?),
+            identity: dataset_acl::IdentityConfig::from_env(),
+            multi_tenant: MultiTenantConfig {
+                credentials_key: export_schedules::CredentialsKey::from_env()?,
+                ..MultiTenantConfig::from_env()
+            },
+            watch: subscriptions::WatchConfig::from_env(),
+            webhook_urls: webhooks::UrlPolicy::from_env(),
+            webhooks: webhooks::WebhookConfig::from_env(),
+            #[cfg(feature = "usage-analytics")]
+            request_log: request_log::RequestLogConfig::from_env(),
+            #[cfg(feature = "api-keys")]
+            export_schedules: export_schedules::ExportScheduleConfig::from_env(),
+            #[cfg(feature = "rate-limiting")]
+            rate_limits: rate_limiting::RateLimitConfig::from_env()?,
+            #[cfg(feature = "api-keys")]
+            public_catalog: public_catalog::PublicCatalogConfig::from_env(),
+            #[cfg(all(feature = "api-keys", feature = "metrics"))]
+            tenant_metrics: metrics::TenantMetricsConfig::from_env(),
+            #[cfg(feature = "oidc")]
+            oidc: oidc::OidcConfig::from_env()?,
+            scim_token: std::env::var("METAFUSE_SCIM_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+        })
+    }
+}
+
+/// Background tasks started by [`build_router`]
+///
+/// Audit writing, usage flushing, webhook delivery, purges and the other
+/// periodic jobs of enabled features run here. They stop when
+/// [`BackgroundTasks::shutdown`] is called, or are aborted when this is
+/// dropped, so keep it alive as long as the router is served.
+///
+/// The audit writer and webhook delivery drain their queues instead: they
+/// finish once the router is dropped, so shut down after the server stops.
+#[derive(Debug, Default)]
+pub struct BackgroundTasks {
+    tasks: JoinSet<()>,
+    shutdown: CancellationToken,
+}
+
+impl BackgroundTasks {
+    fn spawn<F>(&mut self, task: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let shutdown = self.shutdown.clone();
+        self.tasks.spawn(async move {
+            shutdown.run_until_cancelled(task).await;
+        });
+    }
+
+    /// Spawn a task that ends by itself once its queue closes
+    fn spawn_until_closed<F>(&mut self, task: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.spawn(task);
+    }
+
+    /// Token cancelled when the tasks are shut down
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Number of tasks still running
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Stop the tasks and wait for them to finish.
+    pub async fn shutdown(mut self) {
+        self.shutdown.cancel();
+        while let Some(result) = self.tasks.join_next().await {
+            if let Err(e) = result {
+                if e.is_panic() {
+                    tracing::error!(error = %e, "Background task panicked");
+                }
+            }
+        }
+    }
+}
+
+/// Open the catalog and serve the API until the process exits.
+///
+/// On Ctrl-C the server stops accepting connections, finishes in-flight
+/// requests and then stops the background tasks.
+pub async fn serve(config: ServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("Using catalog at: {}", config.catalog_path);
+
+    let backend = backend_from_uri(&config.catalog_path).map_err(|e| {
+        tracing::error!("Failed to create backend: {}", e);
+        e
+    })?;
+
+    // Check if catalog exists for local backends
+    if let Ok(false) = backend.exists().await {
+        tracing::warn!("Catalog does not exist, initializing new catalog");
+        backend.initialize().await.map_err(|e| {
+            tracing::error!("Failed to initialize catalog: {}", e);
+            e
+        })?;
+    }
+
+    let (app, tasks) = build_router(&config, Arc::from(backend)).await?;
+
+    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], config.port));
+    tracing::info!("MetaFuse API listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    // Connect info exposes the peer address for trusted proxy checks
+    let shutdown = tasks.shutdown_token();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => tracing::info!("Shutting down"),
+            _ = shutdown.cancelled() => {}
+        }
+    })
+    .await?;
+
+    tasks.shutdown().await;
+    Ok(())
+}
+
+/// Build the API router around a catalog backend.
+///
+/// Runs migrations if configured and starts the background tasks of enabled
+/// features. The tasks are returned next to the router; they stop when the
+/// returned [`BackgroundTasks`] is shut down or dropped.
+pub async fn build_router(
+    config: &ServerConfig,
+    backend: Arc<DynCatalogBackend>,
+) -> Result<(Router, BackgroundTasks), Box<dyn std::error::Error>> {
+    let mut tasks = BackgroundTasks::default();
+
+    // Run migrations if enabled
+    if config.run_migrations {
+        tracing::info!("Running schema migrations on startup...");
+        let conn = backend.get_connection().await.map_err(|e| {
+            tracing::error!("Failed to get connection for migrations: {}", e);
+            e
+        })?;
+        match migrations::run_migrations(&conn) {
+            Ok(count) => {
+                if count > 0 {
+                    tracing::info!("Applied {} migrations", count);
+                } else {
+                    tracing::info!("No pending migrations");
+                }
+            }
+            Err(e) => {
+                tracing::error!("Migration failed: {}", e);
+                return Err(e.into());
+            }
+        }
+    }
+
+    // Create DeltaReader with configurable cache settings
+    let cache_ttl_secs = config.delta_cache_ttl_secs;
+    let read_limits = config.read_limits.clone();
+    let delta_reader =
+        DeltaReader::new(Duration::from_secs(cache_ttl_secs)).with_limits(read_limits.clone());
+    #[cfg(feature = "metrics")]
+    let delta_reader = delta_reader.with_observer(Arc::new(metrics::DeltaReadMetrics));
+    let delta_reader = Arc::new(delta_reader);
+    tracing::info!(
+        cache_ttl_secs,
+        max_concurrent_reads = read_limits.max_concurrent,
+        max_reads_per_bucket = read_limits.max_per_bucket,
+        read_retries = read_limits.max_retries,
+        "Delta reader initialized"
+    );
+
+    // Initialize audit logger if feature enabled
+    #[cfg(feature = "audit")]
+    let audit_logger = {
+        let sinks = config.audit_forwarders.build();
+        let config = audit::AuditConfig::default();
+        if !config.db_enabled && sinks.is_empty() {
+            tracing::warn!("METAFUSE_AUDIT_DB_ENABLED=false with no forwarder configured; audit events go to tracing only");
+        }
+        let (logger, receiver) = audit::AuditLogger::new(&config);
+        // Start background worker
+        let backend_clone = Arc::clone(&backend);
+        let sink_names: Vec<String> = sinks.iter().map(|s| s.name().to_string()).collect();
+        tasks.spawn_until_closed(async move {
+            audit::audit_writer_task(receiver, backend_clone, config, sinks).await;
+        });
+        tracing::info!(forwarders = ?sink_names, "Audit logging enabled");
+        logger
+    };
+
+    // Group providers resolving the caller's teams
+    let group_resolver = config.groups.clone();
+    tracing::info!(providers = ?group_resolver.provider_names(), "Group providers configured");
+    if let Some(scim) = group_resolver.scim().cloned() {
+        let interval_secs = group_resolver.sync_interval_secs;
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            groups::group_sync_task(scim, interval_secs, backend_clone).await;
+        });
+    }
+
+    // User directories for owners, verifiers and audit actors
+    let user_resolver = config.users.clone();
+    if !user_resolver.directory_names().is_empty() {
+        tracing::info!(
+            directories = ?user_resolver.directory_names(),
+            validation = ?user_resolver.validation,
+            "User directories configured"
+        );
+    }
+    if !user_resolver.tables().is_empty() {
+        let tables = user_resolver.tables().to_vec();
+        let interval_secs = user_resolver.sync_interval_secs;
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            users::user_sync_task(tables, interval_secs, backend_clone).await;
+        });
+    }
+    #[cfg(feature = "ldap-directory")]
+    for directory in user_resolver.ldap().iter().cloned() {
+        tasks.spawn(ldap::ldap_sync_task(
+            directory,
+            user_resolver.sync_interval_secs,
+        ));
+    }
+
+    // Initialize usage tracker if feature enabled
+    #[cfg(feature = "usage-analytics")]
+    let usage_tracker = Arc::new(usage_analytics::UsageTracker::new_default());
+
+    // Initialize alerting background task if feature enabled
+    #[cfg(feature = "alerting")]
+    {
+        let webhook_client = Arc::new(alerting::WebhookClient::new_default());
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            alerting::alert_check_task(webhook_client, backend_clone).await;
+        });
+        tracing::info!("Alerting background task started");
+
+        let digest_config = config.digests.clone();
+        if digest_config.webhook_url.is_some() {
+            let webhook_client = Arc::new(alerting::WebhookClient::new_default());
+            let backend_clone = Arc::clone(&backend);
+            tasks.spawn(async move {
+                digests::digest_task(digest_config, webhook_client, backend_clone).await;
+            });
+            tracing::info!("Digest task started");
+        }
+    }
+
+    // Start trash purge task; with zero retention deletes are immediate
+    let trash_config = config.trash.clone();
+    if trash_config.enabled() {
+        let config = trash_config.clone();
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            trash::trash_purge_task(config, backend_clone).await;
+        });
+        tracing::info!(
+            retention_days = trash_config.retention_days,
+            "Dataset trash enabled"
+        );
+    }
+
+    // Start catalog stats snapshot task
+    let stats_config = config.stats.clone();
+    if stats_config.enabled() {
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            catalog_stats::stats_snapshot_task(stats_config, backend_clone).await;
+        });
+    }
+
+    // Start lineage integrity task
+    let integrity_config = config.lineage_integrity.clone();
+    if integrity_config.enabled() {
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            lineage_integrity::lineage_integrity_task(integrity_config, backend_clone).await;
+        });
+    }
+
+    // Start completion marker purge task; with zero retention markers are kept
+    let marker_config = config.markers.clone();
+    if marker_config.expires() {
+        let config = marker_config.clone();
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            markers::marker_purge_task(config, backend_clone).await;
+        });
+    }
+
+    // Start sandbox purge task
+    let sandbox_config = config.sandbox.clone();
+    {
+        let config = sandbox_config.clone();
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn(async move {
+            sandbox::sandbox_purge_task(config, backend_clone).await;
+        });
+    }
+
+    let slow_requests = Arc::new(diagnostics::SlowRequestLog::new(config.diagnostics.clone()));
+
+    // Start quality history compaction task
+    let quality_compaction = config.quality_compaction.clone();
+    if quality_compaction.enabled {
+        let backend_clone = Arc::clone(&backend);
+        tracing::info!(
+            hourly_retention_days = quality_compaction.hourly_retention_days,
+            daily_retention_days = quality_compaction.daily_retention_days,
+            "Quality history compaction enabled"
+        );
+        tasks.spawn(async move {
+            quality::quality_compaction_task(quality_compaction, backend_clone).await;
+        });
+    }
+    let quality_propagation = config.quality_propagation.clone();
+    let quality_cache = config.quality_cache.clone();
+    let badges = config.badges.clone();
+    if !badges.anonymous {
+        tracing::info!("Dataset badges require a caller identity");
+    }
+    let response_profiles = Arc::new(config.response_profiles.clone());
+
+    let path_search_grace_days = config.path_search_grace_days;
+    if path_search_grace_days > 0 {
+        tracing::info!(
+            grace_days = path_search_grace_days,
+            "Search resolves old dataset paths"
+        );
+    }
+
+    if !config.expose_integer_ids {
+        tracing::info!("Integer dataset ids hidden from responses; use uuid");
+    }
+
+    // Build write-path hooks
+    let write_hooks = config.write_hooks.build();
+    if !write_hooks.is_empty() {
+        tracing::info!(hooks = ?write_hooks, "Write hooks enabled");
+    }
+
+    let lineage_mode = config.lineage_mode;
+    if let Some(mode) = lineage_mode {
+        tracing::info!(lineage_mode = %mode, "Lineage mode configured");
+    }
+
+    #[cfg(feature = "api-keys")]
+    let field_permissions = Arc::new(config.field_permissions.clone());
+
+    // Initialize embedding provider if semantic search is enabled
+    #[cfg(feature = "semantic-search")]
+    let semantic_search = {
+        let search = semantic_search::SemanticSearch::new(&config.semantic_search);
+        tracing::info!(
+            model = %search.provider.model_id(),
+            weight = search.weight,
+            "Semantic search enabled"
+        );
+        search
+    };
+
+    // Base path and forwarded header handling for self-referencing URLs
+    let external_url_config = config.external_url.clone();
+
+    // Cache-Control policy by endpoint class
+    let cache_control_config = config
+        .cache_control
+        .clone()
+        .with_identity_headers(&config.identity);
+
+    // Latency budgets by route
+    let timeout_config = config.timeouts.clone();
+    if timeout_config.enabled {
+        tracing::info!(
+            read_ms = timeout_config.read_ms,
+            slow_ms = timeout_config.slow_ms,
+            write_ms = timeout_config.write_ms,
+            route_overrides = timeout_config.routes.len(),
+            "Request timeouts enabled"
+        );
+    }
+
+    // Message bundles for Accept-Language localization
+    let localizer = Arc::clone(&config.localizer);
+    if !localizer.languages().is_empty() {
+        tracing::info!(languages = ?localizer.languages(), "Response localization enabled");
+    }
+
+    // Trusted identity headers for dataset ACLs
+    let identity_config = config.identity.clone().with_groups(group_resolver.clone());
+    if identity_config.user_header.is_some() || identity_config.groups_header.is_some() {
+        tracing::info!(
+            user_header = ?identity_config.user_header,
+            groups_header = ?identity_config.groups_header,
+            "Dataset ACL identity headers enabled"
+        );
+    }
+
+    // Initialize multi-tenant resources
+    let mt_config = &config.multi_tenant;
+    mt_config.validate()?;
+    let multi_tenant = MultiTenantResources::new(mt_config).await?;
+    if multi_tenant.is_enabled() {
+        tracing::info!(
+            storage_template = %mt_config.storage_uri_template,
+            cache_capacity = mt_config.cache_capacity,
+            "Multi-tenant mode enabled"
+        );
+    }
+
+    // Start the dataset watch task once tenant catalogs can be resolved
+    #[cfg(feature = "alerting")]
+    {
+        let watch_config = config.watch.clone();
+        let webhook_client = Arc::new(alerting::WebhookClient::new_default());
+        let url_policy = config.webhook_urls.clone();
+        let backend_clone = Arc::clone(&backend);
+        let tenants = multi_tenant.factory().cloned();
+        #[cfg(feature = "api-keys")]
+        let control_plane = multi_tenant.control_plane().cloned();
+        let groups = group_resolver.clone();
+        tasks.spawn(async move {
+            subscriptions::watch_task(
+                watch_config,
+                webhook_client,
+                url_policy,
+                backend_clone,
+                tenants,
+                #[cfg(feature = "api-keys")]
+                control_plane,
+                groups,
+            )
+            .await;
+        });
+        tracing::info!("Dataset watch task started");
+    }
+
+    // Start the usage flush worker once tenant catalogs can be resolved
+    #[cfg(feature = "usage-analytics")]
+    {
+        let tracker_clone = Arc::clone(&usage_tracker);
+        let backend_clone = Arc::clone(&backend);
+        let tenants = multi_tenant.factory().cloned();
+        tasks.spawn(async move {
+            usage_analytics::usage_flush_task(tracker_clone, backend_clone, tenants).await;
+        });
+        tracing::info!("Usage analytics enabled");
+    }
+
+    // Start the request log flush worker; requests are only logged when enabled
+    #[cfg(feature = "usage-analytics")]
+    let request_log = {
+        let config = config.request_log.clone();
+        config.enabled.then(|| {
+            let sink = Arc::new(request_log::RequestLogSink::new(config));
+            let sink_clone = Arc::clone(&sink);
+            let backend_clone = Arc::clone(&backend);
+            let tenants = multi_tenant.factory().cloned();
+            tasks.spawn(async move {
+                request_log::request_log_flush_task(sink_clone, backend_clone, tenants).await;
+            });
+            sink
+        })
+    };
+
+    // Start webhook delivery; without alerting, webhooks are stored but not sent
+    #[cfg(feature = "alerting")]
+    let webhooks = {
+        let webhook_config = config.webhooks.clone();
+        let (dispatcher, receiver) = webhooks::WebhookDispatcher::new(&webhook_config);
+        let backend_clone = Arc::clone(&backend);
+        tasks.spawn_until_closed(async move {
+            webhooks::webhook_delivery_task(webhook_config, receiver, backend_clone).await;
+        });
+        tracing::info!("Webhook delivery task started");
+        dispatcher
+    };
+    #[cfg(not(feature = "alerting"))]
+    let webhooks = webhooks::WebhookDispatcher::disabled();
+
+    // Start scheduled exports when tenants live in a control plane
+    #[cfg(feature = "api-keys")]
+    if let (Some(control_plane), Some(tenants)) =
+        (multi_tenant.control_plane(), multi_tenant.factory())
+    {
+        let config = config.export_schedules.clone();
+        let control_plane = Arc::clone(control_plane);
+        let tenants = Arc::clone(tenants);
+        let webhooks = webhooks.clone();
+        tasks.spawn(async move {
+            export_schedules::export_schedule_task(config, control_plane, tenants, webhooks).await;
+        });
+    }
+
+    let state = AppState {
+        backend,
+        delta_reader,
+        #[cfg(feature = "audit")]
+        audit_logger,
+        #[cfg(feature = "usage-analytics")]
+        usage_tracker,
+        #[cfg(feature = "semantic-search")]
+        semantic_search,
+        trash_config,
+        marker_config,
+        sandbox_config,
+        slow_requests: Arc::clone(&slow_requests),
+        write_hooks,
+        webhooks,
+        lineage_mode,
+        quality_propagation,
+        quality_cache,
+        badges,
+        response_profiles,
+        path_search_grace_days,
+        expose_integer_ids: config.expose_integer_ids,
+        #[cfg(feature = "api-keys")]
+        field_permissions,
+        users: Arc::new(user_resolver),
+        multi_tenant,
+    };
+
+    // Build router with conditional feature routes
+    let app = Router::new()
+        .route("/health", get(health_check))
+        // Dataset endpoints
+        .route(
+            "/api/v1/datasets",
+            get(datasets::list_datasets).post(dataset_writes::create_dataset),
+        )
+        .route(
+            "/api/v1/datasets/{name}",
+            get(datasets::get_dataset)
+                .put(dataset_writes::put_dataset)
+                .patch(dataset_writes::patch_dataset)
+                .delete(dataset_writes::delete_dataset),
+        )
+        // Trash endpoints (tenant-scoped, unlike the platform admin API)
+        .route("/api/v1/admin/trash", get(trash::get_trash))
+        .route(
+            "/api/v1/admin/trash/{name}",
+            axum::routing::delete(trash::purge_trashed_dataset),
+        )
+        .route(
+            "/api/v1/admin/trash/{name}/restore",
+            post(trash::restore_trashed_dataset),
+        )
+        // Catalog-wide renames (tenant-scoped, like the trash)
+        .route("/api/v1/admin/tags/rename", post(renames::post_tag_rename))
+        .route(
+            "/api/v1/admin/domains/rename",
+            post(renames::post_domain_rename),
+        )
+        .route("/api/v1/admin/renames", get(renames::get_renames))
+        // Catalog bundles for moving metadata between environments
+        .route("/api/v1/export", post(catalog_export::export_catalog))
+        .route(
+            "/api/v1/import",
+            post(catalog_export::import_catalog)
+                .layer(DefaultBodyLimit::max(catalog_export::IMPORT_BODY_LIMIT)),
+        )
+        .route("/api/v1/datasets/{name}/tags", post(tags::add_tags))
+        .route(
+            "/api/v1/datasets/{name}/tags/remove",
+            post(tags::remove_tags),
+        )
+        // Delta-delegated endpoints
+        .route(
+            "/api/v1/datasets/{name}/schema",
+            get(delta_metadata::get_dataset_schema),
+        )
+        .route(
+            "/api/v1/datasets/{name}/schema/diff",
+            get(delta_metadata::get_dataset_schema_diff),
+        )
+        .route(
+            "/api/v1/datasets/{name}/stats",
+            get(delta_metadata::get_dataset_stats),
+        )
+        .route(
+            "/api/v1/datasets/{name}/history",
+            get(delta_metadata::get_dataset_history),
+        )
+        .route(
+            "/api/v1/datasets/{name}/timeline",
+            get(timeline::get_dataset_timeline),
+        )
+        .route(
+            "/api/v1/datasets/{name}/acl",
+            get(dataset_acl::get_dataset_acl).put(dataset_acl::put_dataset_acl),
+        )
+        .route(
+            "/api/v1/datasets/{name}/custom-metadata",
+            get(custom_metadata::get_custom_metadata).patch(custom_metadata::patch_custom_metadata),
+        )
+        .route(
+            "/api/v1/datasets/{name}/fields/{field}",
+            axum::routing::patch(dataset_writes::patch_dataset_field),
+        )
+        .route(
+            "/api/v1/custom-metadata/schema",
+            get(custom_metadata::get_custom_metadata_schema)
+                .put(custom_metadata::set_custom_metadata_schema)
+                .delete(custom_metadata::delete_custom_metadata_schema),
+        )
+        .route(
+            "/api/v1/datasets/{name}/lineage.dot",
+            get(lineage_graph::get_lineage_dot),
+        )
+        .route(
+            "/api/v1/datasets/{name}/lineage.mmd",
+            get(lineage_graph::get_lineage_mermaid),
+        )
+        .route(
+            "/api/v1/datasets/{name}/impact",
+            get(impact::get_dataset_impact),
+        )
+        // Quality metrics endpoints (scores are served at /quality below)
+        .route(
+            "/api/v1/datasets/{name}/quality/metrics",
+            get(quality::list_quality_metrics).post(quality::create_quality_metric),
+        )
+        // Freshness config endpoints
+        .route(
+            "/api/v1/datasets/{name}/freshness",
+            get(freshness::get_freshness_config).post(freshness::set_freshness_config),
+        )
+        .route("/api/v1/freshness/check", post(freshness::check_freshness))
+        // Owner endpoints
+        .route(
+            "/api/v1/owners",
+            get(owners::list_owners).post(owners::create_owner),
+        )
+        .route(
+            "/api/v1/owners/{id}",
+            get(owners::get_owner)
+                .put(owners::update_owner)
+                .delete(owners::delete_owner),
+        )
+        // User directory endpoints
+        .route("/api/v1/users", get(users::list_users))
+        .route("/api/v1/users/{id}", get(users::get_user))
+        // Domain endpoints
+        .route(
+            "/api/v1/domains",
+            get(domains::list_domains).post(domains::create_domain),
+        )
+        .route(
+            "/api/v1/domains/{name}",
+            get(domains::get_domain)
+                .put(domains::update_domain)
+                .delete(domains::delete_domain),
+        )
+        .route(
+            "/api/v1/domains/{name}/acl",
+            get(dataset_acl::get_domain_acl).put(dataset_acl::put_domain_acl),
+        )
+        .route(
+            "/api/v1/domains/{name}/datasets",
+            get(domains::list_domain_datasets),
+        )
+        // Namespace endpoints
+        .route(
+            "/api/v1/namespaces",
+            get(namespaces::get_namespaces).post(namespaces::post_namespace),
+        )
+        .route(
+            "/api/v1/namespaces/{name}",
+            get(namespaces::get_namespace_by_name).delete(namespaces::delete_namespace_by_name),
+        )
+        .route(
+            "/api/v1/namespaces/{name}/datasets",
+            get(namespaces::list_namespace_datasets),
+        )
+        // Glossary endpoints
+        .route(
+            "/api/v1/glossary",
+            get(glossary::list_glossary_terms).post(glossary::create_glossary_term),
+        )
+        .route(
+            "/api/v1/glossary/lookup",
+            get(glossary::lookup_glossary_term),
+        )
+        .route(
+            "/api/v1/glossary/global/{id}",
+            get(glossary::get_global_glossary_term),
+        )
+        .route(
+            "/api/v1/glossary/{id}",
+            get(glossary::get_glossary_term)
+                .put(glossary::update_glossary_term)
+                .delete(glossary::delete_glossary_term),
+        )
+        .route(
+            "/api/v1/glossary/{id}/links",
+            get(glossary::get_term_links)
+                .post(glossary::link_term)
+                .delete(glossary::unlink_term),
+        )
+        // Lineage endpoint
+        .route("/api/v1/lineage", post(lineage_edges::create_lineage_edge))
+        .route(
+            "/api/v1/lineage/export",
+            post(lineage_graph::export_lineage_bundle),
+        )
+        .route(
+            "/api/v1/lineage/external",
+            get(lineage_edges::list_external_nodes),
+        )
+        .route(
+            "/api/v1/lineage/cycles",
+            get(lineage_integrity::get_lineage_cycles),
+        )
+        // Dataset ref endpoints
+        .route(
+            "/api/v1/refs",
+            get(dataset_refs::list_dataset_refs).post(dataset_refs::create_dataset_ref),
+        )
+        .route("/api/v1/refs/{name}", get(dataset_refs::get_dataset_ref))
+        .route(
+            "/api/v1/refs/{name}/consumers",
+            post(dataset_refs::add_dataset_ref_consumer),
+        )
+        // Governance rules endpoints
+        .route(
+            "/api/v1/governance/rules",
+            get(governance::list_governance_rules).post(governance::create_governance_rule),
+        )
+        .route(
+            "/api/v1/governance/rules/{id}",
+            get(governance::get_governance_rule)
+                .put(governance::update_governance_rule)
+                .delete(governance::delete_governance_rule),
+        )
+        // Auto-tagging rule endpoints
+        .route(
+            "/api/v1/governance/auto-tag-rules",
+            get(auto_tag_rules::list_auto_tag_rules).post(auto_tag_rules::create_auto_tag_rule),
+        )
+        .route(
+            "/api/v1/governance/auto-tag-rules/run",
+            post(auto_tag_rules::run_auto_tag_rules),
+        )
+        .route(
+            "/api/v1/governance/auto-tag-rules/{id}",
+            get(auto_tag_rules::get_auto_tag_rule)
+                .put(auto_tag_rules::update_auto_tag_rule)
+                .delete(auto_tag_rules::delete_auto_tag_rule),
+        )
+        // Search endpoints
+        .route("/api/v1/search", get(datasets::search_datasets))
+        .route("/api/v1/suggest", get(suggest::suggest_completions));
+
+    // Add audit endpoint if audit feature is enabled
+    #[cfg(feature = "audit")]
+    let app = app.route("/api/v1/audit", get(audit::list_audit_logs));
+
+    // Add usage analytics endpoints if usage-analytics feature is enabled
+    #[cfg(feature = "usage-analytics")]
+    let app = app
+        .route(
+            "/api/v1/datasets/{name}/usage",
+            get(usage_analytics::get_dataset_usage),
+        )
+        .route(
+            "/api/v1/analytics/popular",
+            get(usage_analytics::get_popular_datasets),
+        )
+        .route(
+            "/api/v1/analytics/stale",
+            get(usage_analytics::get_stale_datasets),
+        );
+
+    // Service diagnostics; with tenants they are served by the admin API
+    #[cfg(not(feature = "api-keys"))]
+    let app = app.route("/api/v1/admin/diagnostics", get(diagnostics::diagnostics));
+    // Orphaned dataset detection (core functionality)
+    let app = app.route(
+        "/api/v1/analytics/orphaned",
+        get(orphans::get_orphaned_datasets),
+    );
+
+    // Metadata completeness leaderboard (core functionality)
+    let app = app.route(
+        "/api/v1/analytics/metadata-completeness",
+        get(metadata_completeness::get_metadata_completeness_leaderboard),
+    );
+
+    // Change digests per domain or owner (core functionality)
+    let app = app.route("/api/v1/digests", get(digests::get_digests));
+
+    // Catalog-wide statistics (core functionality)
+    let app = app.route("/api/v1/stats", get(catalog_stats::get_catalog_stats));
+
+    // Dataset subscriptions (core functionality)
+    let app = app
+        .route(
+            "/api/v1/datasets/{name}/subscription",
+            post(subscriptions::subscribe_dataset).delete(subscriptions::unsubscribe_dataset),
+        )
+        .route(
+            "/api/v1/datasets/{name}/subscribers",
+            get(subscriptions::list_dataset_subscribers),
+        )
+        .route(
+            "/api/v1/subscriptions",
+            get(subscriptions::list_my_subscriptions),
+        );
+
+    // Outbound webhooks (core functionality; delivery needs alerting)
+    let app = app
+        .route(
+            "/api/v1/webhooks",
+            get(webhooks::get_webhooks).post(webhooks::post_webhook),
+        )
+        .route(
+            "/api/v1/webhooks/{id}",
+            get(webhooks::get_webhook_by_id)
+                .put(webhooks::put_webhook)
+                .delete(webhooks::delete_webhook_by_id),
+        )
+        .route(
+            "/api/v1/webhooks/{id}/deliveries",
+            get(webhooks::list_webhook_deliveries),
+        );
+
+    // Completion markers for orchestration handoffs (core functionality)
+    let app = app.route(
+        "/api/v1/datasets/{name}/markers",
+        get(markers::list_completion_markers)
+            .post(markers::mark_partition_complete)
+            .delete(markers::retract_completion_marker),
+    );
+
+    // External consumers with SLAs (core functionality)
+    let app = app.route(
+        "/api/v1/datasets/{name}/consumers",
+        get(consumers::list_dataset_consumers)
+            .post(consumers::register_dataset_consumer)
+            .delete(consumers::unregister_dataset_consumer),
+    );
+
+    // Archival of retired datasets (core functionality)
+    let app = app.route(
+        "/api/v1/datasets/{name}/archive",
+        post(archive::post_dataset_archive).delete(archive::delete_dataset_archive),
+    );
+
+    // Storage format recommendations (core functionality)
+    let app = app.route(
+        "/api/v1/analytics/recommendations",
+        get(format_advisor::get_recommendations),
+    );
+
+    // Quality endpoints (core functionality)
+    let app = app
+        .route(
+            "/api/v1/datasets/{name}/quality",
+            get(quality::get_dataset_quality).post(quality::compute_dataset_quality),
+        )
+        .route(
+            "/api/v1/datasets/{name}/badge.svg",
+            get(badges::get_dataset_badge),
+        )
+        .route(
+            "/api/v1/datasets/{name}/stats",
+            post(quality::push_dataset_stats),
+        )
+        .route(
+            "/api/v1/quality/unhealthy",
+            get(quality::get_unhealthy_datasets_handler),
+        );
+
+    // Tenant self-service usage endpoint (requires api-keys for auth)
+    #[cfg(feature = "api-keys")]
+    let app = app
+        .route("/api/v1/usage", get(admin::get_my_usage))
+        .route(
+            "/api/v1/tenant/usage",
+            get(admin::get_tenant_usage_dashboard),
+        )
+        .route("/api/v1/tenant/defaults", get(admin::get_tenant_defaults));
+
+    // Classification endpoints if classification feature is enabled
+    #[cfg(feature = "classification")]
+    let app = app
+        .route(
+            "/api/v1/datasets/{name}/classifications",
+            get(classification::get_dataset_classifications_handler)
+                .post(classification::scan_dataset_classifications),
+        )
+        .route(
+            "/api/v1/classifications/pii",
+            get(classification::get_all_pii_columns),
+        )
+        .route(
+            "/api/v1/fields/{id}/classification",
+            axum::routing::put(classification::set_field_classification),
+        )
+        .route(
+            "/api/v1/governance/classifications/bulk-verify",
+            post(classification_review::bulk_verify_classifications),
+        );
+
+    // Alerting endpoints (v0.9.0)
+    #[cfg(feature = "alerting")]
+    let app = app.route("/api/v1/alerts", get(alerting::list_alerts));
+
+    // Contract endpoints (v0.9.0)
+    #[cfg(feature = "contracts")]
+    let app = app
+        .route(
+            "/api/v1/contracts",
+            get(contracts::list_contracts_handler).post(contracts::create_contract_handler),
+        )
+        .route(
+            "/api/v1/contracts/{name}",
+            get(contracts::get_contract_handler)
+                .put(contracts::update_contract_handler)
+                .delete(contracts::delete_contract_handler),
+        );
+
+    // Column-level lineage endpoints (v0.10.0)
+    #[cfg(feature = "column-lineage")]
+    let app = {
+        tracing::info!("Column-level lineage API enabled at /api/v1/lineage/*");
+        app.route("/api/v1/lineage/parse", post(lineage::lineage_parse))
+            .route("/api/v1/lineage/edges", post(lineage::lineage_record))
+            .route(
+                "/api/v1/lineage/dataset/{dataset_id}/columns/{column}/upstream",
+                get(lineage::lineage_upstream),
+            )
+            .route(
+                "/api/v1/lineage/dataset/{dataset_id}/columns/{column}/downstream",
+                get(lineage::lineage_downstream),
+            )
+            .route(
+                "/api/v1/lineage/dataset/{dataset_id}/columns/{column}/pii-propagation",
+                get(lineage::lineage_pii_propagation),
+            )
+            .route(
+                "/api/v1/lineage/dataset/{dataset_id}",
+                axum::routing::delete(lineage::lineage_delete_dataset),
+            )
+            .route(
+                "/api/v1/lineage/fields/{field_id}/impact",
+                get(lineage::lineage_field_impact),
+            )
+    };
+
+    // Add metrics endpoint if metrics feature is enabled
+    #[cfg(feature = "metrics")]
+    let app = {
+        tracing::info!("Metrics endpoint enabled at /metrics");
+        app.route("/metrics", get(metrics::metrics_handler))
+    };
+
+    let app = app
+        // Inside the request ID middleware so timeouts carry the request ID
+        .layer(middleware::from_fn(timeouts::timeout_middleware))
+        .layer(Extension(Arc::new(timeout_config)))
+        .layer(middleware::from_fn(diagnostics::timing_middleware))
+        .layer(Extension(slow_requests));
+
+    // Log requests outside the timeout middleware so timed out requests are kept
+    #[cfg(feature = "usage-analytics")]
+    let app = match request_log.clone() {
+        Some(sink) => app
+            .layer(middleware::from_fn(request_log::request_log_middleware))
+            .layer(Extension(sink)),
+        None => app,
+    };
+
+    let app = app
+        .layer(middleware::from_fn(request_id_middleware))
+        // Add metrics middleware if enabled
+        .layer({
+            #[cfg(feature = "metrics")]
+            {
+                middleware::from_fn(metrics::track_metrics)
+            }
+            #[cfg(not(feature = "metrics"))]
+            {
+                middleware::from_fn(|req: Request, next: Next| async move { next.run(req).await })
+            }
+        });
+
+    // Add rate limiting if enabled
+    #[cfg(feature = "rate-limiting")]
+    let app = {
+        let rate_limiter = rate_limiting::RateLimiter::new(config.rate_limits.clone());
+        tracing::info!(
+            anonymous_limit = rate_limiter.config().anonymous_limit,
+            authenticated_limit = rate_limiter.config().authenticated_limit,
+            window_secs = rate_limiter.config().window_secs,
+            exempt_paths = ?rate_limiter.config().exemptions.paths,
+            exempt_networks = rate_limiter.config().exemptions.networks.len(),
+            "Rate limiting enabled"
+        );
+
+        // Persist violation counters to the control plane for auditing
+        #[cfg(feature = "api-keys")]
+        if let Some(control_plane) = state.multi_tenant.control_plane() {
+            let control_plane = Arc::clone(control_plane);
+            let limiter = rate_limiter.clone();
+            tasks.spawn(async move {
+                rate_limiting::violation_flush_task(limiter, control_plane).await;
+            });
+        }
+
+        // The extension layer must wrap the middleware so the limiter is shared
+        app.layer(middleware::from_fn(rate_limiting::rate_limit_middleware))
+            .layer(axum::Extension(rate_limiter))
+    };
+
+    // Add audit context middleware to extract identity for audit logging
+    // Always run to make AuditContext available to handlers
+    let app = app.layer(middleware::from_fn(audit_context_middleware));
+
+    // Resolve the caller's user and groups for dataset ACLs
+    let app = app
+        .layer(middleware::from_fn(dataset_acl::identity_middleware))
+        .layer(Extension(Arc::new(identity_config)));
+
+    // Select the request's sandbox namespace (header or pinned API key)
+    let app = app.layer(middleware::from_fn(sandbox::sandbox_middleware));
+
+    // Name datasets by UUID in responses; runs inside the tenant middleware
+    // so it looks UUIDs up in the caller's catalog
+    let app = app.layer(middleware::from_fn_with_state(
+        state.clone(),
+        public_ids::public_ids_middleware,
+    ));
+
+    // Resolve external scheme/host/base path for self-referencing URLs
+    let app = app
+        .layer(middleware::from_fn(external_url::external_url_middleware))
+        .layer(Extension(Arc::new(external_url_config.clone())));
+
+    // Build admin API routes (requires api-keys feature)
+    // These routes are protected by METAFUSE_ADMIN_KEY, NOT tenant API keys
+    #[cfg(feature = "api-keys")]
+    let admin_routes = {
+        use axum::routing::delete;
+
+        // Build admin router with dedicated admin auth
+        let admin_routes = Router::new()
+            .route(
+                "/tenants",
+                get(admin::admin_list_tenants).post(admin::admin_create_tenant),
+            )
+            .route(
+                "/tenants/{tenant_id}",
+                get(admin::admin_get_tenant)
+                    .put(admin::admin_update_tenant)
+                    .delete(admin::admin_delete_tenant),
+            )
+            .route(
+                "/tenants/{tenant_id}/suspend",
+                post(admin::admin_suspend_tenant),
+            )
+            .route(
+                "/tenants/{tenant_id}/reactivate",
+                post(admin::admin_reactivate_tenant),
+            )
+            .route(
+                "/tenants/{tenant_id}/api-keys",
+                get(admin::admin_list_api_keys).post(admin::admin_create_api_key),
+            )
+            .route(
+                "/tenants/{tenant_id}/api-keys/{key_id}",
+                delete(admin::admin_revoke_api_key),
+            )
+            .route(
+                "/tenants/{tenant_id}/impersonate",
+                post(admin::admin_impersonate_tenant),
+            )
+            .route("/audit-log", get(admin::admin_get_audit_log))
+            .route(
+                "/rate-limits/violations",
+                get(admin::admin_list_rate_limit_violations),
+            )
+            .route(
+                "/tenants/{tenant_id}/usage",
+                get(admin::admin_get_tenant_usage),
+            )
+            .route(
+                "/tenants/{tenant_id}/features",
+                get(admin::admin_get_tenant_features).put(admin::admin_update_tenant_features),
+            )
+            .route(
+                "/tenants/{tenant_id}/defaults",
+                get(admin::admin_get_tenant_defaults).put(admin::admin_update_tenant_defaults),
+            )
+            .route(
+                "/tenants/{tenant_id}/residency",
+                get(data_residency::admin_get_tenant_residency)
+                    .put(data_residency::admin_update_tenant_residency),
+            )
+            .route(
+                "/tenants/{tenant_id}/exports",
+                get(export_schedules::admin_list_export_schedules)
+                    .post(export_schedules::admin_create_export_schedule),
+            )
+            .route(
+                "/tenants/{tenant_id}/exports/{schedule_id}",
+                get(export_schedules::admin_get_export_schedule)
+                    .put(export_schedules::admin_update_export_schedule)
+                    .delete(export_schedules::admin_delete_export_schedule),
+            )
+            .route(
+                "/tenants/{tenant_id}/exports/{schedule_id}/runs",
+                get(export_schedules::admin_list_export_runs),
+            )
+            .route(
+                "/tenants/{tenant_id}/credentials",
+                get(admin::admin_list_credentials_profiles),
+            )
+            .route(
+                "/tenants/{tenant_id}/credentials/{name}",
+                axum::routing::put(admin::admin_put_credentials_profile)
+                    .delete(admin::admin_delete_credentials_profile),
+            )
+            .route("/diagnostics", get(diagnostics::diagnostics))
+            .route(
+                "/glossary",
+                post(glossary::admin_create_global_glossary_term),
+            )
+            .route(
+                "/glossary/{id}",
+                axum::routing::put(glossary::admin_update_global_glossary_term)
+                    .delete(glossary::admin_delete_global_glossary_term),
+            );
+
+        #[cfg(feature = "usage-analytics")]
+        let admin_routes = admin_routes
+            .route(
+                "/usage/popular",
+                get(usage_analytics::admin_get_popular_datasets),
+            )
+            .route(
+                "/usage/stale",
+                get(usage_analytics::admin_get_stale_datasets),
+            )
+            .route("/requests", get(request_log::admin_list_requests));
+
+        let admin_routes = admin_routes.layer(middleware::from_fn(admin::require_admin_auth));
+
+        // Admin routes skip tenant resolution, so they get their own request
+        // log, request ID and audit context layers, in the same order
+        #[cfg(feature = "usage-analytics")]
+        let admin_routes = match request_log {
+            Some(sink) => admin_routes
+                .layer(middleware::from_fn(request_log::request_log_middleware))
+                .layer(Extension(sink)),
+            None => admin_routes,
+        };
+
+        tracing::info!("Admin API routes enabled at /api/v1/admin/*");
+
+        admin_routes
+            .layer(middleware::from_fn(request_id_middleware))
+            .layer(middleware::from_fn(audit_context_middleware))
+    };
+
+    // Add multi-tenant middleware if enabled (requires api-keys feature)
+    #[cfg(feature = "api-keys")]
+    let app = if mt_config.enabled {
+        use multi_tenant::tenant_backend_middleware;
+        use tenant_resolver::{
+            require_tenant_middleware, tenant_feature_flags_middleware, tenant_resolver_middleware,
+        };
+
+        let factory = state
+            .multi_tenant
+            .factory()
+            .expect("factory required when enabled")
+            .clone();
+        let control_plane = state
+            .multi_tenant
+            .control_plane()
+            .expect("control plane required when enabled")
+            .clone();
+        let public_catalog_config = config.public_catalog.clone();
+        if public_catalog_config.enabled {
+            tracing::info!(
+                excluded_tenants = public_catalog_config.excluded_tenants.len(),
+                excluded_tags = ?public_catalog_config.excluded_tags,
+                "Public read-only catalog mode enabled"
+            );
+        }
+        let resolver_config = TenantResolverConfig::with_public_catalog(public_catalog_config);
+
+        tracing::info!("Multi-tenant middleware enabled for API routes");
+
+        // Middleware layers applied in reverse order (last added = first executed):
+        // 1. tenant_resolver_middleware - Resolves tenant from API key or X-Tenant-ID header
+        // 2. tenant_feature_flags_middleware - Loads per-tenant feature flags, blocks disabled routes
+        // 3. tenant_metrics_middleware - Injects TenantMetricsInfo for metrics recording (if enabled)
+        // 4. require_tenant_middleware - Rejects requests without valid tenant context (401)
+        // 5. tenant_backend_middleware - Gets tenant-specific backend for resolved tenant
+        #[cfg(feature = "metrics")]
+        let app = {
+            let metrics_config = config.tenant_metrics.clone();
+            tracing::info!(
+                include_tenant_id = metrics_config.include_tenant_id,
+                "Tenant metrics configured (cardinality control)"
+            );
+            app.layer(middleware::from_fn(tenant_backend_middleware))
+                .layer(middleware::from_fn(require_tenant_middleware))
+                .layer(middleware::from_fn(metrics::tenant_metrics_middleware))
+                .layer(middleware::from_fn(tenant_feature_flags_middleware))
+                .layer(middleware::from_fn(tenant_resolver_middleware))
+                .layer(Extension(factory))
+                .layer(Extension(control_plane))
+                .layer(Extension(resolver_config))
+                .layer(Extension(metrics_config))
+        };
+
+        #[cfg(not(feature = "metrics"))]
+        let app = app
+            .layer(middleware::from_fn(tenant_backend_middleware))
+            .layer(middleware::from_fn(require_tenant_middleware))
+            .layer(middleware::from_fn(tenant_feature_flags_middleware))
+            .layer(middleware::from_fn(tenant_resolver_middleware))
+            .layer(Extension(factory))
+            .layer(Extension(control_plane))
+            .layer(Extension(resolver_config));
+
+        // Accept OIDC tokens from the configured issuer alongside tenant API keys
+        #[cfg(feature = "oidc")]
+        let app = match config.oidc.clone() {
+            Some(oidc_config) => {
+                tracing::info!(
+                    issuer = %oidc_config.issuer,
+                    audience = %oidc_config.audience,
+                    tenant_claim = %oidc_config.tenant_claim,
+                    role_claim = %oidc_config.role_claim,
+                    "OIDC authentication enabled"
+                );
+                let authenticator = oidc::OidcAuthenticator::new(oidc_config);
+                app.layer(Extension(Arc::new(authenticator)))
+            }
+            None => app,
+        };
+
+        app
+    } else {
+        app
+    };
+
+    // Merge admin routes outside the tenant middleware
+    #[cfg(feature = "api-keys")]
+    let app = app.nest("/api/v1/admin", admin_routes);
+
+    // Serve SCIM provisioning outside the tenant middleware; users belong to
+    // the default catalog
+    let app = if let Some(scim_token) = config.scim_token.clone() {
+        let scim_routes = Router::new()
+            .route(
+                "/Users",
+                get(scim::scim_list_users).post(scim::scim_create_user),
+            )
+            .route(
+                "/Users/{id}",
+                get(scim::scim_get_user)
+                    .put(scim::scim_replace_user)
+                    .patch(scim::scim_patch_user)
+                    .delete(scim::scim_delete_user),
+            )
+            .layer(middleware::from_fn(scim::require_scim_auth))
+            .layer(Extension(scim::ScimToken(scim_token)))
+            .layer(middleware::from_fn(request_id_middleware));
+        tracing::info!("SCIM provisioning enabled at /scim/v2/Users");
+        app.nest("/scim/v2", scim_routes)
+    } else {
+        app
+    };
+
+    // Reject unknown request body fields in every route's JSON bodies
+    if config.strict_requests {
+        tracing::info!("Strict request bodies enabled; unknown fields are rejected");
+    }
+    let app = app.layer(Extension(strict_json::StrictRequests(
+        config.strict_requests,
+    )));
+
+    // Give every error a code, including extractor rejections and plain-text errors
+    let app = app.layer(middleware::from_fn(error_codes::error_code_middleware));
+
+    // Localize error messages from every route, including admin and tenant rejections
+    let app = app
+        .layer(middleware::from_fn(i18n::i18n_middleware))
+        .layer(Extension(localizer));
+
+    // Set Cache-Control last so admin routes and tenant rejections are covered too
+    let app = app
+        .layer(middleware::from_fn(cache_control::cache_control_middleware))
+        .layer(Extension(Arc::new(cache_control_config)));
+
+    let app = app.layer(CorsLayer::permissive()).with_state(state);
+
+    // Serve all routes under the base path when deployed behind a path-prefixed ingress
+    let app = if external_url_config.base_path.is_empty() {
+        app
+    } else {
+        tracing::info!(base_path = %external_url_config.base_path, "Serving API under base path");
+        Router::new().nest(&external_url_config.base_path, app)
+    };
+
+    Ok((app, tasks))
+}
+
+/// Middleware to add request ID to every request and create tracing span
+async fn request_id_middleware(mut req: Request, next: Next) -> Response {
+    let request_id = RequestId(Uuid::new_v4().to_string());
+    req.extensions_mut().insert(request_id.clone());
+
+    // Create a span that will correlate all logs for this request
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id.0,
+        method = %req.method(),
+        uri = %req.uri(),
+    );
+
+    async move {
+        tracing::info!("Request started");
+        let mut response = next.run(req).await;
+        // Propagate request ID to response headers for client correlation
+        if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+            response
+                .headers_mut()
+                .insert(header::HeaderName::from_static("x-request-id"), value);
+        }
+        tracing::info!(status = %response.status(), "Request completed");
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// Middleware to extract audit context (API key identity + client IP)
+/// Must run after auth middleware so ApiKeyId is available in extensions
+/// Always runs to make AuditContext available to all handlers
+async fn audit_context_middleware(mut req: Request, next: Next) -> Response {
+    // Extract API key ID if present (set by auth/rate-limiting middleware)
+    #[cfg(feature = "rate-limiting")]
+    let api_key_id = req
+        .extensions()
+        .get::<rate_limiting::ApiKeyId>()
+        .map(|k| k.id.clone());
+    #[cfg(not(feature = "rate-limiting"))]
+    let api_key_id: Option<String> = None;
+
+    // Extract client IP from headers (X-Forwarded-For, X-Real-IP) or connection
+    let client_ip = extract_client_ip(&req);
+
+    // Create and insert audit context
+    #[allow(unused_mut)] // only set when api-keys feature is enabled
+    let mut audit_context = AuditContext::new(api_key_id, client_ip);
+    #[cfg(feature = "api-keys")]
+    {
+        audit_context.impersonator = req
+            .extensions()
+            .get::<ResolvedTenant>()
+            .and_then(|tenant| tenant.impersonator())
+            .map(str::to_string);
+    }
+    #[cfg(feature = "oidc")]
+    {
+        audit_context.user = req
+            .extensions()
+            .get::<oidc::OidcPrincipal>()
+            .map(|principal| principal.user.clone());
+    }
+    req.extensions_mut().insert(audit_context);
+
+    next.run(req).await
+}
+
+/// Extract client IP from request headers or connection info
+fn extract_client_ip(req: &Request) -> Option<String> {
+    // Try X-Forwarded-For first (may contain multiple IPs, take the first)
+    if let Some(forwarded) = req.headers().get("x-forwarded-for") {
+        if let Ok(value) = forwarded.to_str() {
+            if let Some(first_ip) = value.split(',').next() {
+                let ip = first_ip.trim();
+                if !ip.is_empty() {
+                    return Some(ip.to_string());
+                }
+            }
+        }
+    }
+
+    // Try X-Real-IP
+    if let Some(real_ip) = req.headers().get("x-real-ip") {
+        if let Ok(value) = real_ip.to_str() {
+            let ip = value.trim();
+            if !ip.is_empty() {
+                return Some(ip.to_string());
+            }
+        }
+    }
+
+    // Fallback: could extract from connection info but axum doesn't expose it directly here
+    None
+}
+
+/// Health check endpoint
+async fn health_check() -> &'static str {
+    "ok"
+}
+
+// =============================================================================
+// Error Helpers
+// =============================================================================
+
+/// Helper function to create internal error response
+///
+/// Logs the detailed error message internally but returns a generic message to the client
+/// to avoid leaking implementation details.
+pub(crate) fn internal_error(
+    message: String,
+    request_id: String,
+) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::error!(error = %message, "Internal server error");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Internal server error. Please contact support with the request ID.".to_string(),
+            code: ErrorCode::InternalError,
+            request_id,
+        }),
+    )
+}
+
+/// Helper function to create not found error response
+///
+/// Returns a user-friendly not found message without leaking details about what exists.
+pub(crate) fn not_found(message: String, request_id: String) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::info!(message = %message, "Resource not found");
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: message,
+            code: ErrorCode::NotFound,
+            request_id,
+        }),
+    )
+}
+
+/// Helper function to create dataset not found error response
+pub(crate) fn dataset_not_found(
+    name: &str,
+    request_id: String,
+) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::info!(dataset = %name, "Dataset not found");
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: format!("Dataset '{}' not found", name),
+            code: ErrorCode::DatasetNotFound,
+            request_id,
+        }),
+    )
+}
+
+/// Helper function to create bad request error response
+///
+/// Validation errors are user-facing and safe to return to the client.
+pub(crate) fn bad_request(
+    message: String,
+    request_id: String,
+) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::info!(message = %message, "Bad request");
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            error: message,
+            code: ErrorCode::ValidationFailed,
+            request_id,
+        }),
+    )
+}
+
+/// Look up a live dataset the caller has `required` access to
+pub(crate) fn accessible_dataset_id(
+    conn: &rusqlite::Connection,
+    name: &str,
+    identity: Option<&dataset_acl::Identity>,
+    required: dataset_acl::AclPermission,
+    request_id: &RequestId,
+) -> Result<i64, (StatusCode, Json<ErrorResponse>)> {
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(name, request_id.0.clone()))?;
+    require_dataset_access(conn, dataset_id, name, identity, required, request_id)?;
+    Ok(dataset_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog_export::NDJSON_CONTENT_TYPE;
+    use axum::body::Body;
+
+    #[test]
+    fn test_audit_context_new() {
+        let ctx = AuditContext::new(
+            Some("api-key-123".to_string()),
+            Some("192.168.1.1".to_string()),
+        );
+        assert_eq!(ctx.api_key_id, Some("api-key-123".to_string()));
+        assert_eq!(ctx.client_ip, Some("192.168.1.1".to_string()));
+    }
+
+    #[test]
+    fn test_audit_context_default() {
+        let ctx = AuditContext::default();
+        assert_eq!(ctx.api_key_id, None);
+        assert_eq!(ctx.client_ip, None);
+    }
+
+    #[cfg(feature = "audit")]
+    mod audit_enrichment_tests {
+        use super::*;
+
+        #[test]
+        fn test_enrich_event_with_api_key() {
+            let ctx = AuditContext::new(Some("api-key-123".to_string()), None);
+            let event = audit::AuditEvent::create(
+                "dataset",
+                "test_dataset",
+                serde_json::json!({}),
+                "req-123",
+            );
+            let enriched = ctx.enrich_event(event);
+            assert_eq!(enriched.actor, Some("api-key-123".to_string()));
+            assert_eq!(enriched.actor_type, audit::ActorType::Service);
+        }
+
+        #[test]
+        fn test_enrich_event_anonymous() {
+            let ctx = AuditContext::new(None, None);
+            let event = audit::AuditEvent::create(
+                "dataset",
+                "test_dataset",
+                serde_json::json!({}),
+                "req-123",
+            );
+            let enriched = ctx.enrich_event(event);
+            assert_eq!(enriched.actor, Some("anonymous".to_string()));
+            assert_eq!(enriched.actor_type, audit::ActorType::Anonymous);
+        }
+
+        #[test]
+        fn test_enrich_event_with_client_ip() {
+            let ctx = AuditContext::new(
+                Some("api-key-123".to_string()),
+                Some("10.0.0.1".to_string()),
+            );
+            let event = audit::AuditEvent::create(
+                "dataset",
+                "test_dataset",
+                serde_json::json!({}),
+                "req-123",
+            );
+            let enriched = ctx.enrich_event(event);
+            assert_eq!(enriched.client_ip, Some("10.0.0.1".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_extract_client_ip_x_forwarded_for() {
+        let req = Request::builder()
+            .header(
+                "x-forwarded-for",
+                "203.0.113.195, 70.41.3.18, 150.172.238.178",
+            )
+            .body(Body::empty())
+            .unwrap();
+        let ip = extract_client_ip(&req);
+        assert_eq!(ip, Some("203.0.113.195".to_string()));
+    }
+
+    #[test]
+    fn test_extract_client_ip_x_forwarded_for_single() {
+        let req = Request::builder()
+            .header("x-forwarded-for", "192.168.1.100")
+            .body(Body::empty())
+            .unwrap();
+        let ip = extract_client_ip(&req);
+        assert_eq!(ip, Some("192.168.1.100".to_string()));
+    }
+
+    #[test]
+    fn test_extract_client_ip_x_real_ip() {
+        let req = Request::builder()
+            .header("x-real-ip", "10.0.0.1")
+            .body(Body::empty())
+            .unwrap();
+        let ip = extract_client_ip(&req);
+        assert_eq!(ip, Some("10.0.0.1".to_string()));
+    }
+
+    #[test]
+    fn test_extract_client_ip_prefers_x_forwarded_for() {
+        let req = Request::builder()
+            .header("x-forwarded-for", "203.0.113.195")
+            .header("x-real-ip", "10.0.0.1")
+            .body(Body::empty())
+            .unwrap();
+        let ip = extract_client_ip(&req);
+        assert_eq!(ip, Some("203.0.113.195".to_string()));
+    }
+
+    #[test]
+    fn test_extract_client_ip_none() {
+        let req = Request::builder().body(Body::empty()).unwrap();
+        let ip = extract_client_ip(&req);
+        assert_eq!(ip, None);
+    }
+
+    #[test]
+    fn test_extract_client_ip_empty_header() {
+        let req = Request::builder()
+            .header("x-forwarded-for", "")
+            .body(Body::empty())
+            .unwrap();
+        let ip = extract_client_ip(&req);
+        assert_eq!(ip, None);
+    }
+
+    #[tokio::test]
+    async fn test_build_router_mounts_in_host_app() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (catalog, _tasks) = build_router(&config, Arc::from(backend)).await.unwrap();
+        let app = Router::new()
+            .route("/host", get(|| async { "host" }))
+            .merge(catalog);
+
+        let get_status = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                app.oneshot(request).await.unwrap()
+            }
+        };
+        assert_eq!(get_status("/host").await.status(), StatusCode::OK);
+        let response = get_status("/api/v1/datasets").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key("x-request-id"));
+    }
+
+    #[tokio::test]
+    async fn test_response_profiles() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES ('orders', '/lake/orders', 'parquet', datetime('now'), datetime('now'))",
+                [],
+            )
+            .unwrap();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = get("/api/v1/datasets?profile=minimal").await;
+        assert_eq!(status, StatusCode::OK);
+        let dataset = body[0].as_object().unwrap();
+        assert!(dataset.contains_key("owner"));
+        assert!(!dataset.contains_key("path"));
+
+        // Explicit fields win over the profile
+        let (_, body) = get("/api/v1/datasets?profile=minimal&fields=path").await;
+        let keys: Vec<_> = body[0].as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["path"]);
+
+        let (status, body) = get("/api/v1/datasets/orders?profile=ui").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.get("lineage").is_some());
+        let (status, _) = get("/api/v1/datasets/orders?profile=bi").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_namespaces() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+
+        let send = |method: &'static str,
+                    uri: &'static str,
+                    sandbox: Option<&'static str>,
+                    body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let mut request = Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "application/json");
+                if let Some(sandbox) = sandbox {
+                    request = request.header(sandbox::SANDBOX_HEADER, sandbox);
+                }
+                let request = request.body(Body::from(body.to_string())).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+        let names = |body: serde_json::Value| -> Vec<String> {
+            body.as_array()
+                .unwrap()
+                .iter()
+                .map(|d| d["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let dataset = |name: &str, upstreams: &[&str]| {
+            serde_json::json!({
+                "name": name,
+                "path": format!("/lake/{}", name),
+                "format": "parquet",
+                "upstream_datasets": upstreams,
+            })
+        };
+
+        let (status, _) = send("POST", "/api/v1/datasets", None, dataset("orders", &[])).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // Writes into a sandbox that doesn't exist yet are rejected
+        let (status, _) = send(
+            "POST",
+            "/api/v1/datasets",
+            Some("ci_1"),
+            dataset("orders", &[]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            "POST",
+            "/api/v1/namespaces",
+            None,
+            serde_json::json!({"name": "ci_1", "ttl_secs": 999_999_999}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            "POST",
+            "/api/v1/namespaces",
+            Some("ci_1"),
+            serde_json::json!({"name": "ci_2", "ttl_secs": 3600}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, body) = send(
+            "POST",
+            "/api/v1/namespaces",
+            Some("ci_1"),
+            serde_json::json!({"name": "ci_1", "ttl_secs": 3600}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(body["expires_at"].is_string());
+
+        let (status, body) = send(
+            "POST",
+            "/api/v1/datasets",
+            Some("ci_1"),
+            dataset("orders", &[]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["name"], "ci_1.orders");
+        let (status, body) = send(
+            "POST",
+            "/api/v1/datasets",
+            Some("ci_1"),
+            dataset("daily_orders", &["orders"]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["name"], "ci_1.daily_orders");
+        let (_, body) = send(
+            "GET",
+            "/api/v1/datasets/ci_1.daily_orders",
+            None,
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(
+            body["upstream_datasets"],
+            serde_json::json!(["ci_1.orders"])
+        );
+
+        // Production lists and search stay clean
+        let (_, body) = send("GET", "/api/v1/datasets", None, serde_json::Value::Null).await;
+        assert_eq!(names(body), vec!["orders"]);
+        let (_, body) = send(
+            "GET",
+            "/api/v1/search?q=orders",
+            None,
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(names(body), vec!["orders"]);
+        let (_, body) = send(
+            "GET",
+            "/api/v1/datasets",
+            Some("ci_1"),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(names(body).len(), 3);
+
+        // Sandboxed requests can't modify catalog datasets
+        let (status, _) = send(
+            "DELETE",
+            "/api/v1/datasets/orders",
+            Some("ci_1"),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Expired sandboxes are purged with their datasets
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute(
+                "UPDATE namespaces SET expires_at = datetime('now', '-1 minute') WHERE name = 'ci_1'",
+                [],
+            )
+            .unwrap();
+        let (status, _) = send(
+            "POST",
+            "/api/v1/datasets",
+            Some("ci_1"),
+            dataset("returns", &[]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            "POST",
+            "/api/v1/namespaces",
+            None,
+            serde_json::json!({"name": "sales"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send(
+            "GET",
+            "/api/v1/datasets/ci_1.orders",
+            None,
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(
+            "GET",
+            "/api/v1/namespaces/ci_1",
+            None,
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_export_import_bundle() {
+        use tower::ServiceExt;
+
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let mut apps = Vec::new();
+        let mut dirs = Vec::new();
+        for _ in 0..2 {
+            let dir = tempfile::TempDir::new().unwrap();
+            let backend =
+                backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+            backend.initialize().await.unwrap();
+            let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+            let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+            apps.push((app, backend));
+            dirs.push(dir);
+        }
+        apps[0]
+            .1
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO datasets (id, name, path, format, created_at, last_updated)
+                 VALUES (1, 'raw_orders', '/lake/raw_orders', 'parquet', datetime('now'), datetime('now')),
+                        (2, 'orders', '/lake/orders', 'parquet', datetime('now'), datetime('now'));
+                 INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES (2, 'id', 'Int64', 0);
+                 INSERT INTO tags (dataset_id, tag) VALUES (2, 'gold');
+                 INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+                 VALUES (1, 2, datetime('now'));",
+            )
+            .unwrap();
+
+        let send = |app: Router, uri: &str, content_type: &str, body: Vec<u8>| {
+            let request = Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status, bytes.to_vec())
+            }
+        };
+        let source = apps[0].0.clone();
+        let target = apps[1].0.clone();
+
+        let (status, json_bundle) = send(source.clone(), "/api/v1/export", "", vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        let exported: serde_json::Value = serde_json::from_slice(&json_bundle).unwrap();
+        assert_eq!(exported["format"], "metafuse-bundle");
+        assert_eq!(exported["datasets"].as_array().unwrap().len(), 2);
+
+        let (status, ndjson) =
+            send(source.clone(), "/api/v1/export?format=ndjson", "", vec![]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(ndjson.clone()).unwrap().lines().count(),
+            3
+        );
+
+        let (status, body) = send(
+            target.clone(),
+            "/api/v1/import",
+            NDJSON_CONTENT_TYPE,
+            ndjson.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(summary["datasets_created"], 2);
+        assert_eq!(summary["lineage_edges"], 1);
+
+        let (status, body) = send(
+            target.clone(),
+            "/api/v1/import?strategy=overwrite",
+            "application/json",
+            json_bundle,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(summary["datasets_updated"], 2);
+
+        let request = Request::builder()
+            .uri("/api/v1/datasets/orders")
+            .body(Body::empty())
+            .unwrap();
+        let response = target.clone().oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let dataset: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(dataset["tags"], serde_json::json!(["gold"]));
+        assert_eq!(
+            dataset["upstream_datasets"],
+            serde_json::json!(["raw_orders"])
+        );
+
+        for (uri, body) in [
+            ("/api/v1/import?strategy=replace", ndjson.clone()),
+            ("/api/v1/import", b"{\"format\": \"other\"}".to_vec()),
+        ] {
+            let (status, _) = send(target.clone(), uri, NDJSON_CONTENT_TYPE, body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        }
+        let (status, _) = send(source, "/api/v1/export?format=csv", "", vec![]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, backend).await.unwrap();
+        // With tenants, diagnostics are served by the admin API
+        #[cfg(feature = "api-keys")]
+        std::env::set_var("METAFUSE_ADMIN_KEY", "diagnostics-admin");
+
+        let get = |uri: &str| {
+            let mut request = Request::builder().uri(uri);
+            if uri.starts_with("/api/v1/admin/") {
+                request = request.header(header::AUTHORIZATION, "Bearer diagnostics-admin");
+            }
+            let request = request.body(Body::empty()).unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, _) = get("/api/v1/datasets").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = get("/api/v1/admin/diagnostics").await;
+        assert_eq!(status, StatusCode::OK);
+        let database = &body["database"];
+        assert!(database["size_bytes"].as_u64().unwrap() > 0);
+        assert!(database["fts_index_bytes"].as_u64().unwrap() > 0);
+        assert_eq!(
+            database["schema_version"],
+            database["latest_schema_version"]
+        );
+        assert_eq!(database["pending_migrations"], 0);
+        assert!(body["slowest_requests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["route"] == "/api/v1/datasets" && r["method"] == "GET"));
+        assert!(
+            body["caches"]["delta_metadata"]["capacity"]
+                .as_u64()
+                .unwrap()
+                > 0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strict_requests() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+
+        let create = |app: Router, body: serde_json::Value| {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/v1/datasets")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+        let typo = |name: &str| {
+            serde_json::json!({
+                "name": name,
+                "path": format!("/lake/{}", name),
+                "format": "parquet",
+                "descripton": "Orders",
+                "fields": [{ "name": "id", "data_type": "Int64", "nulable": false }],
+                "custom_metadata": { "any_key": true }
+            })
+        };
+
+        // Unknown fields are ignored by default
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (lenient, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        let (status, _) = create(lenient, typo("orders")).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let config = ServerConfig {
+            strict_requests: true,
+            ..config
+        };
+        let (strict, _tasks) = build_router(&config, backend).await.unwrap();
+        let (status, body) = create(strict.clone(), typo("customers")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(
+            body["unknown_fields"],
+            serde_json::json!([
+                { "field": "descripton", "suggestion": "description" },
+                { "field": "fields[0].nulable", "suggestion": "nullable" }
+            ])
+        );
+
+        // Valid bodies and type errors behave as before
+        let mut valid = typo("customers");
+        valid.as_object_mut().unwrap().remove("descripton");
+        valid["fields"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("nulable");
+        let (status, _) = create(strict.clone(), valid).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = create(strict, serde_json::json!({ "name": "x" })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_endpoints() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, backend).await.unwrap();
+
+        let send = |method: &str, uri: &str, body: Option<serde_json::Value>| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+
+        let (status, _) = send(
+            "POST",
+            "/api/v1/webhooks",
+            Some(serde_json::json!({ "url": "ftp://hooks.example.com" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Plain http and internal targets are refused
+        for url in [
+            "http://93.184.215.14/catalog",
+            "https://127.0.0.1/catalog",
+            "https://169.254.169.254/latest/meta-data",
+            "https://10.1.2.3/catalog",
+            "https://[::1]/catalog",
+            "https://[::ffff:192.168.0.1]/catalog",
+        ] {
+            let (status, body) = send(
+                "POST",
+                "/api/v1/webhooks",
+                Some(serde_json::json!({ "url": url })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}: {}", url, body);
+        }
+
+        // The secret is only returned on creation
+        let (status, created) = send(
+            "POST",
+            "/api/v1/webhooks",
+            Some(serde_json::json!({
+                "url": "https://93.184.215.14/catalog",
+                "events": ["dataset.created", "quality.dropped"]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(created["secret"].as_str().unwrap().starts_with("whsec_"));
+        assert_eq!(
+            created["events"],
+            serde_json::json!(["dataset.created", "quality.dropped"])
+        );
+        let uri = format!("/api/v1/webhooks/{}", created["id"]);
+        let (status, fetched) = send("GET", &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(fetched.get("secret").is_none());
+
+        let (status, _) = send(
+            "PUT",
+            &uri,
+            Some(serde_json::json!({ "url": "https://192.168.1.10/hook" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, updated) =
+            send("PUT", &uri, Some(serde_json::json!({ "enabled": false }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["enabled"], false);
+        assert_eq!(updated["url"], "https://93.184.215.14/catalog");
+
+        let (status, listed) = send("GET", "/api/v1/webhooks", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        let (status, deliveries) = send("GET", &format!("{}/deliveries", uri), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(deliveries.as_array().unwrap().is_empty());
+
+        let (status, _) = send("DELETE", &uri, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send("GET", &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_quality_include_reuses_stored_results() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(&format!(
+                "INSERT INTO datasets (name, path, format, delta_location, created_at, last_updated) VALUES
+                    ('pushed', '/lake/pushed', 'parquet', NULL, datetime('now'), datetime('now')),
+                    ('orders', '/lake/orders', 'delta', 'file://{}/missing', datetime('now'), datetime('now'));
+                 INSERT INTO quality_metrics (dataset_id, computed_at, overall_score, source) VALUES
+                    (1, datetime('now', '-2 days'), 0.8, 'spark'),
+                    (2, datetime('now', '-2 days'), 0.7, 'delta');",
+                dir.path().display()
+            ))
+            .unwrap();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        // Without a Delta table, the last pushed result is served however old
+        let (status, body) = get("/api/v1/datasets/pushed?include=quality").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["quality"]["overall_score"], 0.8);
+        assert_eq!(body["quality"]["source"], "spark");
+        assert_eq!(body["quality"]["recomputed"], false);
+        let (status, _) = get("/api/v1/datasets/pushed?include=quality&refresh=true").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get("/api/v1/datasets/pushed?refresh=true").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // A stale result whose recomputation fails is still served...
+        let (status, body) = get("/api/v1/datasets/orders?include=quality").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["quality"]["overall_score"], 0.7);
+        assert_eq!(body["quality"]["recomputed"], false);
+        // ...unless the caller asked for a fresh one
+        let (status, _) = get("/api/v1/datasets/orders?include=quality&refresh=true").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_history() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let table = dir.path().join("orders");
+        std::fs::create_dir_all(table.join("metadata")).unwrap();
+        std::fs::write(
+            table.join("metadata/v1.metadata.json"),
+            format!(
+                r#"{{
+                  "format-version": 2, "location": "file://{}", "last-updated-ms": 1767225600000,
+                  "current-schema-id": 0,
+                  "schemas": [{{"type": "struct", "schema-id": 0, "fields": [
+                    {{"id": 1, "name": "id", "required": true, "type": "long"}}
+                  ]}}],
+                  "current-snapshot-id": 222,
+                  "snapshots": [
+                    {{"snapshot-id": 111, "sequence-number": 1, "timestamp-ms": 1767139200000,
+                      "summary": {{"operation": "append", "added-records": "10"}}}},
+                    {{"snapshot-id": 222, "sequence-number": 2, "timestamp-ms": 1767225600000,
+                      "summary": {{"operation": "delete", "deleted-records": "4"}}}}
+                  ]
+                }}"#,
+                table.display()
+            ),
+        )
+        .unwrap();
+
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES ('orders', ?1, 'iceberg', datetime('now'), datetime('now'))",
+                [format!("file://{}", table.display())],
+            )
+            .unwrap();
+
+        let request = Request::builder()
+            .uri("/api/v1/datasets/orders/history?limit=1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let versions = body["versions"].as_array().unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0]["version"], 2);
+        assert_eq!(versions[0]["snapshot_id"], 222);
+        assert_eq!(versions[0]["operation"], "delete");
+        assert_eq!(versions[0]["parameters"]["deleted-records"], "4");
+    }
+
+    #[tokio::test]
+    async fn test_dataset_badge() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+                    ('orders', '/lake/orders', 'parquet', datetime('now'), datetime('now', '-2 hours')),
+                    ('payroll', '/lake/payroll', 'parquet', datetime('now'), datetime('now'));
+                 INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+                    VALUES (1, datetime('now'), 0.93);
+                 INSERT INTO dataset_acls (dataset_id, principal, permission)
+                    VALUES (2, 'group:hr', 'read');",
+            )
+            .unwrap();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                app.oneshot(request).await.unwrap()
+            }
+        };
+        let body = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        };
+
+        let response = get("/api/v1/datasets/orders/badge.svg").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "image/svg+xml; charset=utf-8"
+        );
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=300"
+        );
+        assert!(body(response).await.contains("<title>quality: 93%</title>"));
+
+        let response = get("/api/v1/datasets/orders/badge.svg?metric=freshness").await;
+        assert!(body(response)
+            .await
+            .contains("<title>freshness: 2h ago</title>"));
+
+        // Datasets hidden by their ACL are not found
+        let response = get("/api/v1/datasets/payroll/badge.svg").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = get("/api/v1/datasets/orders/badge.svg?metric=size").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "api-keys")]
+    async fn test_public_access_hides_restricted_lineage() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        let public = app.clone().layer(Extension(
+            public_catalog::PublicCatalogConfig::enabled().access(),
+        ));
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+                    ('orders', '/lake/orders', 'parquet', datetime('now'), datetime('now')),
+                    ('raw_payments', '/lake/raw_payments', 'parquet', datetime('now'), datetime('now')),
+                    ('customers', '/lake/customers', 'parquet', datetime('now'), datetime('now')),
+                    ('fraud_scores', '/lake/fraud_scores', 'parquet', datetime('now'), datetime('now'));
+                 UPDATE datasets SET custom_metadata = '{\"warehouse\": \"finance_wh\"}' WHERE id = 1;
+                 INSERT INTO tags (dataset_id, tag) VALUES (2, 'Restricted'), (4, 'restricted');
+                 INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+                    (2, 1, datetime('now')),
+                    (3, 1, datetime('now')),
+                    (1, 4, datetime('now'));",
+            )
+            .unwrap();
+
+        let get = |app: Router| async move {
+            let request = Request::builder()
+                .uri("/api/v1/datasets/orders?include=lineage")
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let body = get(public).await;
+        assert_eq!(body["upstream_datasets"], serde_json::json!(["customers"]));
+        assert_eq!(body["downstream_datasets"], serde_json::json!([]));
+        assert_eq!(
+            body["lineage"]["upstream"],
+            serde_json::json!(["customers"])
+        );
+        assert_eq!(body["lineage"]["downstream"], serde_json::json!([]));
+        assert!(body.get("custom_metadata").is_none());
+
+        // Authenticated callers still see the whole neighbourhood
+        let body = get(app).await;
+        let mut upstream: Vec<_> = body["upstream_datasets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_str().unwrap())
+            .collect();
+        upstream.sort();
+        assert_eq!(upstream, vec!["customers", "raw_payments"]);
+        assert_eq!(
+            body["downstream_datasets"],
+            serde_json::json!(["fraud_scores"])
+        );
+        assert_eq!(body["custom_metadata"]["warehouse"], "finance_wh");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "api-keys")]
+    async fn test_public_access_serves_stored_quality() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        let app = app.layer(Extension(
+            public_catalog::PublicCatalogConfig::enabled().access(),
+        ));
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(&format!(
+                "INSERT INTO datasets (name, path, format, delta_location, created_at, last_updated) VALUES
+                    ('orders', '/lake/orders', 'delta', 'file://{}/missing', datetime('now'), datetime('now'));
+                 INSERT INTO quality_metrics (dataset_id, computed_at, overall_score, source) VALUES
+                    (1, datetime('now', '-2 days'), 0.7, 'delta');",
+                dir.path().display()
+            ))
+            .unwrap();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = get("/api/v1/datasets/orders?include=quality&refresh=true").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "FORBIDDEN");
+
+        // A stale result is served as stored, without touching storage
+        let (status, body) = get("/api/v1/datasets/orders?include=quality").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["quality"]["overall_score"], 0.7);
+        assert_eq!(body["quality"]["recomputed"], false);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "semantic-search")]
+    async fn test_semantic_search_limits_visible_datasets() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        // The best matches are restricted to alice; these requests carry no identity
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO datasets (name, path, format, description, created_at, last_updated) VALUES
+                    ('orders_secret', '/lake/a', 'parquet', 'orders orders orders', datetime('now'), datetime('now')),
+                    ('orders_private', '/lake/b', 'parquet', 'orders orders orders', datetime('now'), datetime('now')),
+                    ('orders_weekly', '/lake/c', 'parquet', 'weekly rollup', datetime('now'), datetime('now'));
+                 INSERT INTO dataset_acls (dataset_id, principal, permission) VALUES
+                    (1, 'user:alice', 'read'), (2, 'user:alice', 'read');",
+            )
+            .unwrap();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v1/search?q=orders&mode=semantic&limit=1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let names: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["orders_weekly"]);
+    }
+
+    #[tokio::test]
+    async fn test_scim_provisions_directory_users() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            scim_token: Some("scim-secret".to_string()),
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+
+        let send = |method: &'static str, uri: &'static str, token: &'static str, body: &str| {
+            let app = app.clone();
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/scim+json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+        let ana = r#"{"schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+            "userName": "ana", "displayName": "Ana Lima",
+            "emails": [{"value": "ana@example.com", "primary": true}]}"#;
+
+        let (status, body) = send("POST", "/scim/v2/Users", "wrong", ana).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["status"], "401");
+
+        let (status, body) = send("POST", "/scim/v2/Users", "scim-secret", ana).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        assert_eq!(body["id"], "ana");
+        let (status, body) = send("POST", "/scim/v2/Users", "scim-secret", ana).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["scimType"], "uniqueness");
+
+        let (status, body) = send(
+            "GET",
+            "/scim/v2/Users?filter=userName%20eq%20%22ana%22",
+            "scim-secret",
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["totalResults"], 1);
+        assert_eq!(body["Resources"][0]["displayName"], "Ana Lima");
+
+        let (status, _) = send("DELETE", "/scim/v2/Users/ana", "scim-secret", "").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, body) = send("GET", "/scim/v2/Users/ana", "scim-secret", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["active"], false);
+
+        let (source, email, active): (String, String, i64) = backend
+            .get_connection()
+            .await
+            .unwrap()
+            .query_row(
+                "SELECT source, email, active FROM directory_users WHERE user_id = 'ana'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (source.as_str(), email.as_str(), active),
+            ("scim", "ana@example.com", 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dataset_acl_hides_restricted_dataset_on_every_route() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        // Only alice may read the ledger; these requests carry no identity
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO domains (name, display_name) VALUES ('sales', 'Sales');
+                 INSERT INTO namespaces (name) VALUES ('sales');
+                 INSERT INTO datasets (name, path, format, domain, owner, created_at, last_updated) VALUES
+                    ('sales.orders', '/lake/orders', 'parquet', 'sales', 'ops', datetime('now'), datetime('now')),
+                    ('sales.ledger', '/lake/ledger', 'parquet', 'sales', 'ledger-team', datetime('now'), datetime('now'));
+                 INSERT INTO dataset_acls (dataset_id, principal, permission) VALUES
+                    (2, 'user:alice', 'read');
+                 INSERT INTO dataset_refs (name, dataset_id, dataset_name, path, format, as_of) VALUES
+                    ('orders-q1', 1, 'sales.orders', '/lake/orders', 'parquet', '2025-04-01T00:00:00Z'),
+                    ('ledger-q1', 2, 'sales.ledger', '/lake/ledger', 'parquet', '2025-04-01T00:00:00Z');
+                 INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+                    (1, 2, datetime('now'));",
+            )
+            .unwrap();
+
+        let send = |method: &'static str, uri: String, body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+
+        #[allow(unused_mut)] // only extended with optional features
+        let mut routes = vec![
+            ("GET", "", serde_json::Value::Null),
+            ("GET", "/schema", serde_json::Value::Null),
+            ("GET", "/schema/diff?from=0&to=1", serde_json::Value::Null),
+            ("GET", "/stats", serde_json::Value::Null),
+            ("GET", "/history", serde_json::Value::Null),
+            ("GET", "/timeline", serde_json::Value::Null),
+            ("GET", "/lineage.dot", serde_json::Value::Null),
+            ("GET", "/lineage.mmd", serde_json::Value::Null),
+            ("GET", "/quality/metrics", serde_json::Value::Null),
+            ("POST", "/quality/metrics", serde_json::json!({})),
+            ("GET", "/freshness", serde_json::Value::Null),
+            (
+                "POST",
+                "/freshness",
+                serde_json::json!({"expected_interval_secs": 3600}),
+            ),
+            ("GET", "/quality", serde_json::Value::Null),
+            ("POST", "/quality", serde_json::Value::Null),
+        ];
+        #[cfg(feature = "usage-analytics")]
+        routes.push(("GET", "/usage", serde_json::Value::Null));
+        #[cfg(feature = "classification")]
+        routes.extend([
+            ("GET", "/classifications", serde_json::Value::Null),
+            ("POST", "/classifications", serde_json::Value::Null),
+        ]);
+        for (method, route, body) in routes {
+            let uri = format!("/api/v1/datasets/sales.ledger{}", route);
+            let (status, response) = send(method, uri, body).await;
+            assert_eq!(
+                status,
+                StatusCode::NOT_FOUND,
+                "{} {}: {}",
+                method,
+                route,
+                response
+            );
+        }
+
+        // Listings and suggestions leave the ledger out
+        for uri in [
+            "/api/v1/domains/sales/datasets",
+            "/api/v1/namespaces/sales/datasets",
+        ] {
+            let (status, body) = send("GET", uri.to_string(), serde_json::Value::Null).await;
+            assert_eq!(status, StatusCode::OK, "{}: {}", uri, body);
+            let names: Vec<&str> = body
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|d| d["name"].as_str().unwrap())
+                .collect();
+            assert_eq!(names, vec!["sales.orders"], "{}", uri);
+        }
+        let (status, body) = send(
+            "GET",
+            "/api/v1/suggest?q=sales".to_string(),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["suggestions"],
+            serde_json::json!([{"type": "dataset", "value": "sales.orders"}])
+        );
+        let (_, body) = send(
+            "GET",
+            "/api/v1/suggest?q=ledger&types=owner".to_string(),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(body["suggestions"], serde_json::json!([]));
+
+        // Refs to the ledger are hidden and cannot be created or used
+        let (status, body) = send("GET", "/api/v1/refs".to_string(), serde_json::Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        let names: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["orders-q1"]);
+        for (method, uri, body) in [
+            ("GET", "/api/v1/refs/ledger-q1", serde_json::Value::Null),
+            (
+                "POST",
+                "/api/v1/refs/ledger-q1/consumers",
+                serde_json::json!({"type": "run", "name": "run-001"}),
+            ),
+            (
+                "POST",
+                "/api/v1/refs",
+                serde_json::json!({"name": "ledger-q2", "dataset": "sales.ledger"}),
+            ),
+        ] {
+            let (status, response) = send(method, uri.to_string(), body).await;
+            assert_eq!(
+                status,
+                StatusCode::NOT_FOUND,
+                "{} {}: {}",
+                method,
+                uri,
+                response
+            );
+        }
+        let (status, _) = send(
+            "GET",
+            "/api/v1/refs/orders-q1".to_string(),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The open dataset is still served, without naming the ledger as its
+        // downstream neighbour
+        let (status, body) = send(
+            "GET",
+            "/api/v1/datasets/sales.orders".to_string(),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["downstream_datasets"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_stale_if_match_after_dataset_writes() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::from(backend)).await.unwrap();
+
+        let send = |method: &'static str,
+                    uri: &'static str,
+                    content_type: &'static str,
+                    if_match: Option<String>,
+                    body: String| {
+            let app = app.clone();
+            async move {
+                let mut request = Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, content_type);
+                if let Some(if_match) = if_match {
+                    request = request.header(header::IF_MATCH, if_match);
+                }
+                let request = request.body(Body::from(body)).unwrap();
+                app.oneshot(request).await.unwrap()
+            }
+        };
+        let etag = || async {
+            let response = send(
+                "GET",
+                "/api/v1/datasets/orders",
+                "application/json",
+                None,
+                String::new(),
+            )
+            .await;
+            response.headers()[header::ETAG]
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+        let replace_properties = |if_match: String| {
+            send(
+                "PATCH",
+                "/api/v1/datasets/orders",
+                "application/json-patch+json",
+                Some(if_match),
+                r#"[{"op": "replace", "path": "/properties", "value": {"tier": "bronze"}}]"#
+                    .to_string(),
+            )
+        };
+
+        let response = send(
+            "POST",
+            "/api/v1/datasets",
+            "application/json",
+            None,
+            serde_json::json!({"name": "orders", "path": "/lake/orders", "format": "parquet"})
+                .to_string(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // A custom metadata write lands after the other writer read the ETag
+        let stale = etag().await;
+        let response = send(
+            "PATCH",
+            "/api/v1/datasets/orders/custom-metadata",
+            "application/json",
+            None,
+            r#"{"tier": "gold"}"#.to_string(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = replace_properties(stale).await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        // So does a tag write
+        let stale = etag().await;
+        let response = send(
+            "POST",
+            "/api/v1/datasets/orders/tags",
+            "application/json",
+            None,
+            r#"{"tags": ["finance"]}"#.to_string(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = replace_properties(stale).await;
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        let response = replace_properties(etag().await).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_hidden_integer_ids() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            expose_integer_ids: false,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::from(backend)).await.unwrap();
+
+        let send = |method: &'static str, uri: &'static str, body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+
+        let (status, created) = send(
+            "POST",
+            "/api/v1/datasets",
+            serde_json::json!({"name": "orders", "path": "/lake/orders", "format": "parquet"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(created.get("id").is_none());
+        let uuid = created["uuid"].as_str().unwrap().to_string();
+
+        let (status, body) = send("GET", "/api/v1/datasets", serde_json::Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body[0].get("id").is_none());
+        assert_eq!(body[0]["uuid"], uuid);
+
+        // Payloads referencing datasets carry the UUID instead of the integer
+        let (status, body) = send(
+            "POST",
+            "/api/v1/refs",
+            serde_json::json!({"name": "orders-q1", "dataset": "orders"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(body.get("dataset_id").is_none());
+        assert_eq!(body["dataset_uuid"], uuid);
+    }
+
+    #[tokio::test]
+    async fn test_write_hooks_see_partial_writes() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend: Arc<DynCatalogBackend> =
+            Arc::from(backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap());
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, backend.clone()).await.unwrap();
+        let send = |app: Router,
+                    method: &'static str,
+                    uri: &'static str,
+                    content_type: &'static str,
+                    body: String| async move {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body))
+                .unwrap();
+            app.oneshot(request).await.unwrap().status()
+        };
+        let status = send(
+            app,
+            "POST",
+            "/api/v1/datasets",
+            "application/json",
+            serde_json::json!({"name": "orders", "path": "s3://scratch/orders", "format": "parquet"})
+                .to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // The stored path is denied once the hook is on, whatever the write touches
+        let config = ServerConfig {
+            write_hooks: write_hooks::WriteHookConfig {
+                deny_paths: vec!["s3://scratch/*".to_string()],
+                ..Default::default()
+            },
+            ..config
+        };
+        let (app, _tasks) = build_router(&config, backend).await.unwrap();
+        let writes = [
+            (
+                "POST",
+                "/api/v1/datasets/orders/tags",
+                "application/json",
+                r#"{"tags": ["pii"]}"#,
+            ),
+            (
+                "POST",
+                "/api/v1/datasets/orders/tags/remove",
+                "application/json",
+                r#"{"tags": ["pii"]}"#,
+            ),
+            (
+                "PATCH",
+                "/api/v1/datasets/orders",
+                "application/json-patch+json",
+                r#"[{"op": "add", "path": "/tags/-", "value": "pii"}]"#,
+            ),
+            (
+                "PATCH",
+                "/api/v1/datasets/orders",
+                "application/merge-patch+json",
+                r#"{"description": "Orders"}"#,
+            ),
+            (
+                "PATCH",
+                "/api/v1/datasets/orders/custom-metadata",
+                "application/json",
+                r#"{"tier": "gold"}"#,
+            ),
+            (
+                "POST",
+                "/api/v1/datasets/orders/archive",
+                "application/json",
+                "",
+            ),
+        ];
+        for (method, uri, content_type, body) in writes {
+            let status = send(app.clone(), method, uri, content_type, body.to_string()).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{} {}", method, uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_background_tasks_stop_on_shutdown() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, tasks) = build_router(&config, Arc::from(backend)).await.unwrap();
+        assert!(!tasks.is_empty());
+
+        let shutdown = tasks.shutdown_token();
+        drop(app);
+        tokio::time::timeout(Duration::from_secs(5), tasks.shutdown())
+            .await
+            .expect("background tasks did not stop");
+        assert!(shutdown.is_cancelled());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_upsert_dataset_by_urn() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::from(backend)).await.unwrap();
+
+        let put = |uri: &'static str, body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .method("PUT")
+                    .uri(uri)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+        let urn = "/api/v1/datasets/urn:metafuse:dataset:snowflake.orders";
+
+        // Creating needs a path and format
+        let (status, _) = put(urn, serde_json::json!({"owner": "sales@example.com"})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let body = serde_json::json!({
+            "path": "s3://lake/orders",
+            "format": "parquet",
+            "owner": "sales@example.com"
+        });
+        let (status, created) = put(urn, body.clone()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["result"], "created");
+        assert_eq!(created["dataset"]["name"], "snowflake.orders");
+
+        // The same write again updates in place
+        let (status, updated) = put(urn, body.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["result"], "updated");
+        assert_eq!(updated["dataset"]["id"], created["dataset"]["id"]);
+        assert_eq!(
+            updated["version"].as_i64().unwrap(),
+            created["version"].as_i64().unwrap() + 1
+        );
+
+        let (status, _) = put(
+            "/api/v1/datasets/urn:other:dataset:orders",
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Concurrent writes of a new URN create it once and update it after
+        let writes = (0..16)
+            .map(|_| {
+                tokio::spawn(put(
+                    "/api/v1/datasets/urn:metafuse:dataset:snowflake.customers",
+                    body.clone(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let mut results = Vec::new();
+        for write in writes {
+            let (status, response) = write.await.unwrap();
+            assert!(status.is_success(), "{}: {}", status, response);
+            results.push(response);
+        }
+        let created = results.iter().filter(|r| r["result"] == "created").count();
+        assert_eq!(created, 1);
+        let mut versions: Vec<i64> = results
+            .iter()
+            .map(|r| r["version"].as_i64().unwrap())
+            .collect();
+        versions.sort();
+        versions.dedup();
+        assert_eq!(versions.len(), 16, "each write advances the version once");
+    }
+
+    #[tokio::test]
+    async fn test_single_lineage_edge_follows_lineage_mode() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<DynCatalogBackend> = Arc::from(backend);
+        let config = ServerConfig {
+            run_migrations: true,
+            lineage_mode: Some(LineageMode::Placeholder),
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+        backend
+            .get_connection()
+            .await
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES ('orders', '/lake/orders', 'parquet', datetime('now'), datetime('now'))",
+            )
+            .unwrap();
+
+        let post = |body: serde_json::Value| {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/v1/lineage")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+                )
+            }
+        };
+
+        // The server-wide mode gives the unknown source a placeholder
+        let (status, body) =
+            post(serde_json::json!({"source_dataset": "vendor_feed", "target_dataset": "orders"}))
+                .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        assert_eq!(body["downstream_dataset_id"], 1);
+        let placeholder: String = backend
+            .get_connection()
+            .await
+            .unwrap()
+            .query_row(
+                "SELECT status FROM datasets WHERE id = ?1",
+                [body["upstream_dataset_id"].as_i64().unwrap()],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(placeholder, "pending");
+
+        // ...unless the request overrides it
+        let (status, body) = post(serde_json::json!({
+            "source_dataset": "crm_export",
+            "target_dataset": "orders",
+            "lineage_mode": "strict"
+        }))
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+        assert_eq!(body["error"], "Dataset 'crm_export' not found");
+
+        let (status, body) = post(serde_json::json!({
+            "source_dataset": "crm_export",
+            "target_dataset": "orders",
+            "lineage_mode": "ignore"
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["results"][0]["status"], "skipped");
+
+        // Cycles are still rejected
+        let (status, _) =
+            post(serde_json::json!({"source_dataset": "orders", "target_dataset": "vendor_feed"}))
+                .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
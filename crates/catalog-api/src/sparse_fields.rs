@@ -0,0 +1,125 @@
+//! Sparse Fieldsets
+//!
+//! `?fields=name,domain,owner` on the list and search endpoints trims each
+//! dataset to the requested top-level keys, so grids that only render a few
+//! columns don't pay for paths, descriptions, and operational metadata.
+//! Unknown field names are rejected rather than silently dropped.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Top-level keys of a dataset in list and search responses
+pub const DATASET_FIELDS: &[&str] = &[
+    "id",
+    "uuid",
+    "name",
+    "path",
+    "format",
+    "delta_location",
+    "description",
+    "tenant",
+    "domain",
+    "owner",
+    "created_at",
+    "last_updated",
+    "operational",
+    "freshness",
+    "metadata_completeness",
+    "archived_at",
+];
+
+/// A validated `?fields=` selection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSet(Vec<String>);
+
+impl FieldSet {
+    /// Parse a comma-separated field list. `None` or an empty list means
+    /// all fields.
+    pub fn parse(fields: Option<&str>, allowed: &[&str]) -> Result<Option<Self>, String> {
+        let Some(fields) = fields else {
+            return Ok(None);
+        };
+        let mut selected: Vec<String> = Vec::new();
+        for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            if !allowed.contains(&field) {
+                return Err(format!(
+                    "Unknown field '{}'. Valid fields: {}",
+                    field,
+                    allowed.join(", ")
+                ));
+            }
+            if !selected.iter().any(|f| f == field) {
+                selected.push(field.to_string());
+            }
+        }
+        Ok((!selected.is_empty()).then_some(Self(selected)))
+    }
+
+    /// Also keep `keys`, e.g. those added by an explicit `include`.
+    pub fn with_keys(mut self, keys: &[&str]) -> Self {
+        for key in keys {
+            if !self.0.iter().any(|f| f == key) {
+                self.0.push(key.to_string());
+            }
+        }
+        self
+    }
+
+    /// Serialize `item` keeping only the selected keys.
+    pub fn project<T: Serialize>(&self, item: &T) -> Result<Value, serde_json::Error> {
+        let Value::Object(mut object) = serde_json::to_value(item)? else {
+            return serde_json::to_value(item);
+        };
+        let mut projected = Map::with_capacity(self.0.len());
+        for field in &self.0 {
+            if let Some(value) = object.remove(field) {
+                projected.insert(field.clone(), value);
+            }
+        }
+        Ok(Value::Object(projected))
+    }
+}
+
+/// Serialize `items`, projecting each one when a field set is given.
+pub fn project_all<T: Serialize>(
+    items: &[T],
+    fields: Option<&FieldSet>,
+) -> Result<Vec<Value>, serde_json::Error> {
+    items
+        .iter()
+        .map(|item| match fields {
+            Some(fields) => fields.project(item),
+            None => serde_json::to_value(item),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_project() {
+        assert_eq!(FieldSet::parse(None, DATASET_FIELDS).unwrap(), None);
+        assert_eq!(FieldSet::parse(Some(" , "), DATASET_FIELDS).unwrap(), None);
+        assert!(FieldSet::parse(Some("name,secret"), DATASET_FIELDS).is_err());
+
+        let fields = FieldSet::parse(Some("name, owner,name"), DATASET_FIELDS)
+            .unwrap()
+            .unwrap();
+        let item = json!({"id": 1, "name": "orders", "owner": null, "path": "/data"});
+        assert_eq!(
+            fields.project(&item).unwrap(),
+            json!({"name": "orders", "owner": null})
+        );
+        assert_eq!(
+            fields.clone().with_keys(&["path"]).project(&item).unwrap(),
+            json!({"name": "orders", "owner": null, "path": "/data"})
+        );
+        assert_eq!(
+            project_all(std::slice::from_ref(&item), None).unwrap(),
+            vec![item]
+        );
+    }
+}
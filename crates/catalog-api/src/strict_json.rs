@@ -0,0 +1,398 @@
+//! Strict request bodies
+//!
+//! By default, request bodies may carry fields an endpoint doesn't know, and
+//! those fields are ignored, so a typo such as `descripton` goes unnoticed.
+//! With `METAFUSE_STRICT_REQUESTS=true`, [`JsonBody`] rejects such bodies
+//! with `400 Bad Request`, naming each unknown field with the closest
+//! accepted name:
+//!
+//! ```json
+//! {
+//!   "error": "Unknown field `descripton`, did you mean `description`?",
+//!   "code": "VALIDATION_FAILED",
+//!   "unknown_fields": [{ "field": "descripton", "suggestion": "description" }]
+//! }
+//! ```
+//!
+//! This is `#[serde(deny_unknown_fields)]` decided at runtime: the body is
+//! walked alongside the request type, and every object the type reads as a
+//! struct is checked against that struct's fields, including nested objects
+//! (`fields[0].nulable`). Objects read as maps, and fields flattened into
+//! their parent, accept any key.
+//!
+//! ## Configuration
+//!
+//! - `METAFUSE_STRICT_REQUESTS`: Reject unknown request body fields (default: false)
+
+use axum::{
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Whether request bodies with unknown fields are rejected
+///
+/// Set from `ServerConfig::strict_requests` as a request extension.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrictRequests(pub bool);
+
+/// A field in a request body that the endpoint doesn't accept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownField {
+    /// Path to the field, e.g. `fields[0].nulable`
+    pub field: String,
+    /// Closest accepted field name, when one is close enough
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl UnknownField {
+    fn describe(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => format!("`{}`, did you mean `{}`?", self.field, suggestion),
+            None => format!("`{}`", self.field),
+        }
+    }
+}
+
+/// Message naming every unknown field
+pub fn unknown_fields_message(unknown: &[UnknownField]) -> String {
+    match unknown {
+        [field] => format!("Unknown field {}", field.describe()),
+        fields => format!(
+            "Unknown fields: {}",
+            fields
+                .iter()
+                .map(UnknownField::describe)
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+    }
+}
+
+/// Fields of `value` that `T` would ignore, in body order
+pub fn unknown_fields<T: DeserializeOwned>(value: &Value) -> Vec<UnknownField> {
+    let mut found = Vec::new();
+    // Type errors are reported by the real deserialization
+    let _ = T::deserialize(Probe {
+        value: value.clone(),
+        path: String::new(),
+        found: &mut found,
+    });
+    found
+}
+
+/// Edit distance between two names, ignoring case
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Closest accepted name to an unknown field, if it is plausibly a typo
+fn closest(field: &str, accepted: &[&str]) -> Option<String> {
+    let limit = field.chars().count() / 3 + 1;
+    accepted
+        .iter()
+        .map(|name| (distance(field, name), *name))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, name)| name.to_string())
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Deserializer over a JSON value that records keys its structs don't accept
+struct Probe<'a> {
+    value: Value,
+    path: String,
+    found: &'a mut Vec<UnknownField>,
+}
+
+impl<'de> Deserializer<'de> for Probe<'_> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Object(map) => visitor.visit_map(ProbeMap {
+                entries: map.into_iter(),
+                pending: None,
+                path: self.path,
+                found: self.found,
+            }),
+            Value::Array(items) => visitor.visit_seq(ProbeSeq {
+                items: items.into_iter().enumerate(),
+                path: self.path,
+                found: self.found,
+            }),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if let Value::Object(map) = &self.value {
+            for key in map.keys().filter(|key| !fields.contains(&key.as_str())) {
+                self.found.push(UnknownField {
+                    field: child_path(&self.path, key),
+                    suggestion: closest(key, fields),
+                });
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // Ignored values are unknown already; don't report what's inside
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+struct ProbeMap<'a> {
+    entries: serde_json::map::IntoIter,
+    pending: Option<(String, Value)>,
+    path: String,
+    found: &'a mut Vec<UnknownField>,
+}
+
+impl<'de> MapAccess<'de> for ProbeMap<'_> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        let key_value = seed.deserialize(key.clone().into_deserializer())?;
+        self.pending = Some((key, value));
+        Ok(Some(key_value))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, value) = self
+            .pending
+            .take()
+            .ok_or_else(|| serde::de::Error::custom("value requested before key"))?;
+        seed.deserialize(Probe {
+            value,
+            path: child_path(&self.path, &key),
+            found: &mut *self.found,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct ProbeSeq<'a> {
+    items: std::iter::Enumerate<std::vec::IntoIter<Value>>,
+    path: String,
+    found: &'a mut Vec<UnknownField>,
+}
+
+impl<'de> SeqAccess<'de> for ProbeSeq<'_> {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some((index, value)) = self.items.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(Probe {
+            value,
+            path: format!("{}[{}]", self.path, index),
+            found: &mut *self.found,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// JSON request body extractor that honors [`StrictRequests`]
+///
+/// Behaves exactly like [`Json`] unless a `StrictRequests(true)` extension is
+/// present, in which case unknown fields are rejected with `400`.
+pub struct JsonBody<T>(pub T);
+
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let strict = req
+            .extensions()
+            .get::<StrictRequests>()
+            .is_some_and(|s| s.0);
+        if !strict {
+            let Json(value) = Json::<T>::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            return Ok(Self(value));
+        }
+
+        let Json(value) = Json::<Value>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let unknown = unknown_fields::<T>(&value);
+        if !unknown.is_empty() {
+            let message = unknown_fields_message(&unknown);
+            tracing::info!(message = %message, "Rejected unknown request fields");
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": message, "unknown_fields": unknown })),
+            )
+                .into_response());
+        }
+        // Same status and message as a `Json` data error
+        T::deserialize(value).map(Self).map_err(|e| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "Failed to deserialize the JSON body into the target type: {}",
+                    e
+                ),
+            )
+                .into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Field {
+        name: String,
+        nullable: Option<bool>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct CreateRequest {
+        name: String,
+        description: Option<String>,
+        #[serde(default)]
+        fields: Vec<Field>,
+        #[serde(default)]
+        properties: HashMap<String, Value>,
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let body = serde_json::json!({
+            "name": "orders",
+            "descripton": "typo",
+            "fields": [{ "name": "id", "nulable": false }],
+            "properties": { "anything": { "goes": 1 } },
+            "zzz": { "name": "not checked inside" }
+        });
+        let unknown = unknown_fields::<CreateRequest>(&body);
+        assert_eq!(
+            unknown,
+            vec![
+                UnknownField {
+                    field: "descripton".to_string(),
+                    suggestion: Some("description".to_string()),
+                },
+                UnknownField {
+                    field: "zzz".to_string(),
+                    suggestion: None,
+                },
+                UnknownField {
+                    field: "fields[0].nulable".to_string(),
+                    suggestion: Some("nullable".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            unknown_fields_message(&unknown[..1]),
+            "Unknown field `descripton`, did you mean `description`?"
+        );
+
+        let valid = serde_json::json!({ "name": "orders", "description": null });
+        assert!(unknown_fields::<CreateRequest>(&valid).is_empty());
+    }
+
+    #[test]
+    fn test_closest() {
+        assert_eq!(
+            closest("Owner", &["owner", "domain"]).as_deref(),
+            Some("owner")
+        );
+        assert_eq!(closest("tag", &["tags"]).as_deref(), Some("tags"));
+        assert_eq!(closest("x", &["id"]), None);
+    }
+}
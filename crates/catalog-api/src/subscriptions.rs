@@ -0,0 +1,1121 @@
+//! Dataset Subscriptions
+//!
+//! Lets callers watch datasets and be notified when they change. A
+//! subscription is keyed by the caller's identity: `user:<id>` from the
+//! identity headers (see `dataset_acl`), else `key:<id>` for the API key.
+//!
+//! # Events
+//!
+//! - `schema`: columns added, removed, or retyped
+//! - `quality`: the overall quality score dropped by at least the configured amount
+//! - `deprecation`: the dataset gained the `deprecated` tag
+//!
+//! # Delivery
+//!
+//! With the `alerting` feature, [`watch_task`] compares every watched dataset
+//! in the default catalog and each active tenant's catalog against its last
+//! snapshot (migration v1.32.0) and sends an alert payload to each interested
+//! subscriber's webhook `channel`, and to
+//! `METAFUSE_WATCH_WEBHOOK_URL` (if set) with the list of subscribers and the
+//! `teams` of its user subscribers (see `groups`) so a notification service
+//! can route it. `deprecation` alerts also list the dataset's registered
+//! `consumers` (see `consumers`). A dataset's first snapshot only records its
+//! state.
+//!
+//! Channels are caller-supplied, so they follow the outbound webhook URL
+//! policy (see `webhooks::UrlPolicy`): https only, public addresses only, and
+//! no redirects. The host is checked when subscribing and resolved again
+//! before each delivery.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_WATCH_CHECK_INTERVAL_SECS`: seconds between checks (default: 60)
+//! - `METAFUSE_WATCH_QUALITY_DROP`: score drop that notifies, 0.0-1.0 (default: 0.1)
+//! - `METAFUSE_WATCH_WEBHOOK_URL`: webhook receiving every watcher notification
+//!
+//! # Endpoints
+//!
+//! - `POST /api/v1/datasets/{name}/subscription` - Watch a dataset
+//! - `DELETE /api/v1/datasets/{name}/subscription` - Stop watching it
+//! - `GET /api/v1/datasets/{name}/subscribers` - Who watches a dataset
+//! - `GET /api/v1/subscriptions` - The caller's watch list
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::error_codes::ErrorCode;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, bad_request, internal_error, not_found, AppState, AuditContext,
+    DatasetPath, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+use crate::{dataset_acl, envelope};
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Tag marking a dataset as deprecated
+pub const DEPRECATED_TAG: &str = "deprecated";
+
+/// Default seconds between watch checks
+pub const DEFAULT_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Default quality score drop that notifies watchers
+pub const DEFAULT_QUALITY_DROP: f64 = 0.1;
+
+/// Event a subscriber can watch for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchEvent {
+    Schema,
+    Quality,
+    Deprecation,
+}
+
+impl WatchEvent {
+    /// All events, the default for a new subscription
+    pub const ALL: [WatchEvent; 3] = [
+        WatchEvent::Schema,
+        WatchEvent::Quality,
+        WatchEvent::Deprecation,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchEvent::Schema => "schema",
+            WatchEvent::Quality => "quality",
+            WatchEvent::Deprecation => "deprecation",
+        }
+    }
+}
+
+/// A subscriber watching a dataset
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Subscription {
+    pub dataset_id: i64,
+    pub dataset_name: String,
+    pub subscriber: String,
+    pub events: Vec<WatchEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    pub created_at: String,
+    pub last_notified_at: Option<String>,
+}
+
+/// Watch configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchConfig {
+    pub check_interval_secs: u64,
+    pub quality_drop: f64,
+    pub webhook_url: Option<String>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: DEFAULT_CHECK_INTERVAL_SECS,
+            quality_drop: DEFAULT_QUALITY_DROP,
+            webhook_url: None,
+        }
+    }
+}
+
+impl WatchConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            check_interval_secs: std::env::var("METAFUSE_WATCH_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.check_interval_secs),
+            quality_drop: std::env::var("METAFUSE_WATCH_QUALITY_DROP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|drop: &f64| (0.0..=1.0).contains(drop))
+                .unwrap_or(defaults.quality_drop),
+            webhook_url: std::env::var("METAFUSE_WATCH_WEBHOOK_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+        }
+    }
+}
+
+/// Subscription key for the caller, preferring the user identity.
+pub fn subscriber_key(user: Option<&str>, api_key_id: Option<&str>) -> Option<String> {
+    user.map(|u| format!("user:{}", u))
+        .or_else(|| api_key_id.map(|k| format!("key:{}", k)))
+}
+
+/// Whether the catalog has the subscription tables.
+pub fn has_subscriptions_table(conn: &Connection) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'dataset_subscriptions'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+const SUBSCRIPTION_SELECT: &str =
+    "SELECT s.dataset_id, d.name, s.subscriber, s.events, s.channel, s.created_at, s.last_notified_at
+     FROM dataset_subscriptions s
+     JOIN datasets d ON d.id = s.dataset_id
+     WHERE d.deleted_at IS NULL";
+
+fn subscription_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Subscription> {
+    let events: String = row.get(3)?;
+    Ok(Subscription {
+        dataset_id: row.get(0)?,
+        dataset_name: row.get(1)?,
+        subscriber: row.get(2)?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        channel: row.get(4)?,
+        created_at: row.get(5)?,
+        last_notified_at: row.get(6)?,
+    })
+}
+
+fn query_subscriptions(
+    conn: &Connection,
+    filter: &str,
+    param: &dyn rusqlite::ToSql,
+) -> rusqlite::Result<Vec<Subscription>> {
+    let mut stmt = conn.prepare(&format!("{} AND {}", SUBSCRIPTION_SELECT, filter))?;
+    let subscriptions = stmt.query_map([param], subscription_from_row)?.collect();
+    subscriptions
+}
+
+/// Subscribe to a dataset, replacing any existing subscription of the
+/// subscriber (events and channel).
+pub fn subscribe(
+    conn: &Connection,
+    dataset_id: i64,
+    subscriber: &str,
+    events: &[WatchEvent],
+    channel: Option<&str>,
+) -> rusqlite::Result<Subscription> {
+    let mut events = events.to_vec();
+    events.sort();
+    events.dedup();
+    conn.execute(
+        "INSERT INTO dataset_subscriptions (dataset_id, subscriber, events, channel)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(dataset_id, subscriber) DO UPDATE SET events = ?3, channel = ?4",
+        params![
+            dataset_id,
+            subscriber,
+            serde_json::to_string(&events).unwrap_or_default(),
+            channel
+        ],
+    )?;
+    conn.query_row(
+        &format!(
+            "{} AND s.dataset_id = ?1 AND s.subscriber = ?2",
+            SUBSCRIPTION_SELECT
+        ),
+        params![dataset_id, subscriber],
+        subscription_from_row,
+    )
+}
+
+/// Remove a subscription. Returns whether one existed.
+pub fn unsubscribe(conn: &Connection, dataset_id: i64, subscriber: &str) -> rusqlite::Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM dataset_subscriptions WHERE dataset_id = ?1 AND subscriber = ?2",
+        params![dataset_id, subscriber],
+    )?;
+    Ok(removed > 0)
+}
+
+/// A subscriber's watch list, by dataset name.
+pub fn subscriptions_of(
+    conn: &Connection,
+    subscriber: &str,
+) -> rusqlite::Result<Vec<Subscription>> {
+    if !has_subscriptions_table(conn)? {
+        return Ok(Vec::new());
+    }
+    query_subscriptions(conn, "s.subscriber = ?1 ORDER BY d.name", &subscriber)
+}
+
+/// Subscribers of a dataset, oldest first.
+pub fn subscribers_of(conn: &Connection, dataset_id: i64) -> rusqlite::Result<Vec<Subscription>> {
+    if !has_subscriptions_table(conn)? {
+        return Ok(Vec::new());
+    }
+    query_subscriptions(
+        conn,
+        "s.dataset_id = ?1 ORDER BY s.created_at, s.id",
+        &dataset_id,
+    )
+}
+
+/// A change to a watched dataset
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchChange {
+    Schema {
+        added: Vec<String>,
+        removed: Vec<String>,
+        changed: Vec<String>,
+    },
+    QualityDrop {
+        previous: f64,
+        current: f64,
+    },
+    Deprecated,
+}
+
+impl WatchChange {
+    pub fn event(&self) -> WatchEvent {
+        match self {
+            WatchChange::Schema { .. } => WatchEvent::Schema,
+            WatchChange::QualityDrop { .. } => WatchEvent::Quality,
+            WatchChange::Deprecated => WatchEvent::Deprecation,
+        }
+    }
+}
+
+/// Changes to one watched dataset since its last snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetChanges {
+    pub dataset_id: i64,
+    pub dataset_name: String,
+    pub changes: Vec<WatchChange>,
+}
+
+/// Snapshot every watched dataset and report what changed since the last
+/// snapshot. Datasets seen for the first time are only recorded.
+pub fn detect_changes(
+    conn: &Connection,
+    quality_drop: f64,
+) -> rusqlite::Result<Vec<DatasetChanges>> {
+    if !has_subscriptions_table(conn)? {
+        return Ok(Vec::new());
+    }
+    let watched: Vec<(i64, String)> = conn
+        .prepare(
+            "SELECT DISTINCT d.id, d.name FROM dataset_subscriptions s
+             JOIN datasets d ON d.id = s.dataset_id
+             WHERE d.deleted_at IS NULL
+             ORDER BY d.id",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    let mut detected = Vec::new();
+    for (dataset_id, dataset_name) in watched {
+        let schema: BTreeMap<String, String> = tx
+            .prepare("SELECT name, data_type FROM fields WHERE dataset_id = ?1")?
+            .query_map([dataset_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let quality: Option<f64> = tx
+            .query_row(
+                "SELECT overall_score FROM quality_metrics
+                 WHERE dataset_id = ?1 AND overall_score IS NOT NULL
+                 ORDER BY computed_at DESC, id DESC LIMIT 1",
+                [dataset_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let deprecated = tx
+            .query_row(
+                "SELECT 1 FROM tags WHERE dataset_id = ?1 AND tag = ?2",
+                params![dataset_id, DEPRECATED_TAG],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+
+        let previous: Option<(String, Option<f64>, bool)> = tx
+            .query_row(
+                "SELECT schema, quality_score, deprecated FROM watch_snapshots WHERE dataset_id = ?1",
+                [dataset_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        if let Some((previous_schema, previous_quality, was_deprecated)) = previous {
+            let previous_schema: BTreeMap<String, String> =
+                serde_json::from_str(&previous_schema).unwrap_or_default();
+            let mut changes = Vec::new();
+            if let Some(change) = schema_change(&previous_schema, &schema) {
+                changes.push(change);
+            }
+            if let (Some(previous), Some(current)) = (previous_quality, quality) {
+                if previous - current >= quality_drop {
+                    changes.push(WatchChange::QualityDrop { previous, current });
+                }
+            }
+            if deprecated && !was_deprecated {
+                changes.push(WatchChange::Deprecated);
+            }
+            if !changes.is_empty() {
+                detected.push(DatasetChanges {
+                    dataset_id,
+                    dataset_name,
+                    changes,
+                });
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO watch_snapshots (dataset_id, schema, quality_score, deprecated, checked_at)
+             VALUES (?1, ?2, ?3, ?4, datetime('now'))
+             ON CONFLICT(dataset_id) DO UPDATE SET
+                 schema = ?2, quality_score = ?3, deprecated = ?4, checked_at = datetime('now')",
+            params![
+                dataset_id,
+                serde_json::to_string(&schema).unwrap_or_default(),
+                quality,
+                deprecated
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(detected)
+}
+
+/// Columns added, removed, or retyped between two schemas, if any.
+pub fn schema_change(
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Option<WatchChange> {
+    let added: Vec<String> = current
+        .keys()
+        .filter(|name| !previous.contains_key(*name))
+        .cloned()
+        .collect();
+    let removed: Vec<String> = previous
+        .keys()
+        .filter(|name| !current.contains_key(*name))
+        .cloned()
+        .collect();
+    let changed: Vec<String> = current
+        .iter()
+        .filter(|(name, data_type)| previous.get(*name).is_some_and(|old| old != *data_type))
+        .map(|(name, _)| name.clone())
+        .collect();
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        None
+    } else {
+        Some(WatchChange::Schema {
+            added,
+            removed,
+            changed,
+        })
+    }
+}
+
+/// Groups of the user subscribers, for routing a notification to teams.
+pub fn subscriber_teams(
+    subscriptions: &[&Subscription],
+    groups: &crate::groups::GroupResolver,
+) -> Vec<String> {
+    let mut teams: Vec<String> = subscriptions
+        .iter()
+        .filter_map(|s| s.subscriber.strip_prefix("user:"))
+        .flat_map(|user| groups.groups_of(user))
+        .collect();
+    teams.sort();
+    teams.dedup();
+    teams
+}
+
+/// Record that a subscriber was notified.
+pub fn mark_notified(conn: &Connection, dataset_id: i64, subscriber: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE dataset_subscriptions SET last_notified_at = datetime('now')
+         WHERE dataset_id = ?1 AND subscriber = ?2",
+        params![dataset_id, subscriber],
+    )?;
+    Ok(())
+}
+
+/// Catalogs the watch task checks: the default catalog and, in multi-tenant
+/// mode, every active tenant's catalog
+#[cfg(feature = "alerting")]
+async fn watched_catalogs(
+    backend: &std::sync::Arc<metafuse_catalog_storage::DynCatalogBackend>,
+    tenants: Option<&std::sync::Arc<metafuse_catalog_storage::TenantBackendFactory>>,
+    #[cfg(feature = "api-keys")] control_plane: Option<
+        &std::sync::Arc<crate::control_plane::ControlPlane>,
+    >,
+) -> Vec<(
+    String,
+    std::sync::Arc<metafuse_catalog_storage::DynCatalogBackend>,
+)> {
+    #[allow(unused_mut)] // only extended with api-keys
+    let mut catalogs = vec![(
+        crate::webhooks::DEFAULT_TENANT.to_string(),
+        std::sync::Arc::clone(backend),
+    )];
+    #[cfg(feature = "api-keys")]
+    if let (Some(factory), Some(control_plane)) = (tenants, control_plane) {
+        let active = match control_plane.list_tenants(Some("active")).await {
+            Ok(active) => active,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to list tenants for watch check");
+                Vec::new()
+            }
+        };
+        for tenant in active {
+            match factory.get_backend_by_id(&tenant.tenant_id).await {
+                Ok(tenant_backend) => catalogs.push((tenant.tenant_id, tenant_backend)),
+                Err(e) => {
+                    tracing::warn!(tenant_id = %tenant.tenant_id, error = %e, "Failed to resolve tenant catalog for watch check")
+                }
+            }
+        }
+    }
+    #[cfg(not(feature = "api-keys"))]
+    let _ = tenants;
+    catalogs
+}
+
+/// Background task that notifies watchers of changes to their datasets
+#[cfg(feature = "alerting")]
+pub async fn watch_task(
+    config: WatchConfig,
+    webhook_client: std::sync::Arc<crate::alerting::WebhookClient>,
+    url_policy: crate::webhooks::UrlPolicy,
+    backend: std::sync::Arc<metafuse_catalog_storage::DynCatalogBackend>,
+    tenants: Option<std::sync::Arc<metafuse_catalog_storage::TenantBackendFactory>>,
+    #[cfg(feature = "api-keys")] control_plane: Option<
+        std::sync::Arc<crate::control_plane::ControlPlane>,
+    >,
+    groups: crate::groups::GroupResolver,
+) {
+    let interval = std::time::Duration::from_secs(config.check_interval_secs);
+    tracing::info!(
+        interval_secs = config.check_interval_secs,
+        quality_drop = config.quality_drop,
+        "Dataset watch task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let catalogs = watched_catalogs(
+            &backend,
+            tenants.as_ref(),
+            #[cfg(feature = "api-keys")]
+            control_plane.as_ref(),
+        )
+        .await;
+        for (tenant_id, tenant_backend) in catalogs {
+            check_catalog(
+                &config,
+                &webhook_client,
+                &url_policy,
+                &groups,
+                &tenant_id,
+                tenant_backend.as_ref(),
+            )
+            .await;
+        }
+    }
+}
+
+/// Notify watchers of changes to the datasets of one tenant's catalog
+#[cfg(feature = "alerting")]
+async fn check_catalog(
+    config: &WatchConfig,
+    webhook_client: &crate::alerting::WebhookClient,
+    url_policy: &crate::webhooks::UrlPolicy,
+    groups: &crate::groups::GroupResolver,
+    tenant_id: &str,
+    backend: &metafuse_catalog_storage::DynCatalogBackend,
+) {
+    use crate::alerting::{AlertPayload, MAX_DELIVERY_ATTEMPTS};
+
+    let conn = match backend.get_connection().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::warn!(tenant_id, error = %e, "Failed to get connection for watch check");
+            return;
+        }
+    };
+    let detected = match detect_changes(&conn, config.quality_drop) {
+        Ok(detected) => detected,
+        Err(e) => {
+            tracing::error!(tenant_id, error = %e, "Failed to detect changes to watched datasets");
+            return;
+        }
+    };
+
+    for dataset in detected {
+        let subscribers = match subscribers_of(&conn, dataset.dataset_id) {
+            Ok(subscribers) => subscribers,
+            Err(e) => {
+                tracing::error!(tenant_id, dataset_id = dataset.dataset_id, error = %e, "Failed to load subscribers");
+                continue;
+            }
+        };
+
+        for change in &dataset.changes {
+            let event = change.event();
+            let watchers: Vec<&Subscription> = subscribers
+                .iter()
+                .filter(|s| s.events.contains(&event))
+                .collect();
+            if watchers.is_empty() {
+                continue;
+            }
+
+            let mut payload = match change {
+                WatchChange::Schema {
+                    added,
+                    removed,
+                    changed,
+                } => AlertPayload::schema_change(
+                    &dataset.dataset_name,
+                    dataset.dataset_id,
+                    added,
+                    removed,
+                    changed,
+                ),
+                WatchChange::QualityDrop { previous, current } => AlertPayload::quality_drop(
+                    &dataset.dataset_name,
+                    dataset.dataset_id,
+                    *previous,
+                    *current,
+                ),
+                WatchChange::Deprecated => {
+                    AlertPayload::deprecation(&dataset.dataset_name, dataset.dataset_id)
+                }
+            };
+            let mut details = payload
+                .details
+                .take()
+                .unwrap_or_else(|| serde_json::json!({}));
+            details["tenant_id"] = serde_json::json!(tenant_id);
+            details["subscribers"] = serde_json::json!(watchers
+                .iter()
+                .map(|s| s.subscriber.as_str())
+                .collect::<Vec<_>>());
+            details["teams"] = serde_json::json!(subscriber_teams(&watchers, groups));
+            // Consumers outside the catalog need to move off a deprecated dataset
+            if matches!(change, WatchChange::Deprecated) {
+                match crate::consumers::list(&conn, dataset.dataset_id) {
+                    Ok(consumers) => details["consumers"] = serde_json::json!(consumers),
+                    Err(e) => {
+                        tracing::warn!(dataset_id = dataset.dataset_id, error = %e, "Failed to load dataset consumers")
+                    }
+                }
+            }
+            payload.details = Some(details);
+
+            if let Some(url) = &config.webhook_url {
+                if let Err(e) = webhook_client
+                    .send_with_retry(url, &payload, MAX_DELIVERY_ATTEMPTS)
+                    .await
+                {
+                    tracing::error!(dataset = %dataset.dataset_name, event = event.as_str(), error = %e, "Watch notification failed");
+                }
+            }
+            for watcher in &watchers {
+                let delivered = match &watcher.channel {
+                    Some(url) => {
+                        let sent = match webhook_client.pinned_to(url_policy, url).await {
+                            Ok(client) => client
+                                .send_with_retry(url, &payload, MAX_DELIVERY_ATTEMPTS)
+                                .await
+                                .map(|_| ()),
+                            Err(e) => Err(e),
+                        };
+                        sent.map_err(|e| {
+                            tracing::error!(
+                                tenant_id,
+                                dataset = %dataset.dataset_name,
+                                subscriber = %watcher.subscriber,
+                                event = event.as_str(),
+                                error = %e,
+                                "Watch notification failed"
+                            )
+                        })
+                        .is_ok()
+                    }
+                    None => config.webhook_url.is_some(),
+                };
+                if delivered {
+                    if let Err(e) = mark_notified(&conn, dataset.dataset_id, &watcher.subscriber) {
+                        tracing::warn!(error = %e, "Failed to record watch notification");
+                    }
+                }
+            }
+            tracing::info!(
+                tenant_id,
+                dataset = %dataset.dataset_name,
+                event = event.as_str(),
+                watchers = watchers.len(),
+                "Notified dataset watchers"
+            );
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct SubscribeRequest {
+    /// Events to watch (default: all)
+    events: Option<Vec<WatchEvent>>,
+    /// Webhook URL for notifications; without one, only
+    /// METAFUSE_WATCH_WEBHOOK_URL is notified
+    channel: Option<String>,
+}
+
+/// Subscription key for the caller: the user identity, else the API key
+pub(crate) fn caller_subscriber(
+    identity: Option<&dataset_acl::Identity>,
+    audit_context: &AuditContext,
+    request_id: &RequestId,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    subscriber_key(
+        identity.and_then(|i| i.user.as_deref()),
+        audit_context.api_key_id.as_deref(),
+    )
+    .ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "A user identity or API key is required to manage subscriptions".to_string(),
+                code: ErrorCode::Unauthorized,
+                request_id: request_id.0.clone(),
+            }),
+        )
+    })
+}
+
+/// Watch a dataset for schema changes, quality drops, and deprecation
+pub(crate) async fn subscribe_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<SubscribeRequest>,
+) -> Result<(StatusCode, Json<Subscription>), (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let identity = identity.as_ref().map(|e| &e.0);
+    let subscriber = caller_subscriber(identity, &audit_context, &request_id)?;
+
+    let events = req.events.unwrap_or_else(|| WatchEvent::ALL.to_vec());
+    if events.is_empty() {
+        return Err(bad_request(
+            "At least one event is required".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+    if let Some(channel) = &req.channel {
+        state
+            .webhooks
+            .url_policy()
+            .resolve(channel)
+            .await
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity,
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let subscription = subscribe(
+        &conn,
+        dataset_id,
+        &subscriber,
+        &events,
+        req.channel.as_deref(),
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(dataset = %name, subscriber = %subscriber, "Subscribed to dataset");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "subscription",
+            format!("{}:{}", name, subscriber),
+            serde_json::to_value(&subscription).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((StatusCode::CREATED, Json(subscription)))
+}
+
+/// Stop watching a dataset
+pub(crate) async fn unsubscribe_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+    let identity = identity.as_ref().map(|e| &e.0);
+    let subscriber = caller_subscriber(identity, &audit_context, &request_id)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity,
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let removed = unsubscribe(&conn, dataset_id, &subscriber)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !removed {
+        return Err(not_found(
+            format!("Not subscribed to dataset '{}'", name),
+            request_id.0.clone(),
+        ));
+    }
+
+    tracing::info!(dataset = %name, subscriber = %subscriber, "Unsubscribed from dataset");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "subscription",
+            format!("{}:{}", name, subscriber),
+            serde_json::json!({ "dataset": name, "subscriber": subscriber }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the subscribers watching a dataset
+pub(crate) async fn list_dataset_subscribers(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<Subscription>>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let dataset_id = accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Channels may embed webhook secrets, so only the subscriber sees theirs
+    let subscribers: Vec<_> = subscribers_of(&conn, dataset_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .into_iter()
+        .map(|s| Subscription { channel: None, ..s })
+        .collect();
+    Ok(Json(envelope.page(subscribers)))
+}
+
+/// List the caller's watched datasets
+pub(crate) async fn list_my_subscriptions(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<Subscription>>, (StatusCode, Json<ErrorResponse>)> {
+    let identity = identity.as_ref().map(|e| &e.0);
+    let subscriber = caller_subscriber(identity, &audit_context, &request_id)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let anonymous = dataset_acl::Identity::default();
+    let mut watched = Vec::new();
+    for subscription in subscriptions_of(&conn, &subscriber)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+    {
+        // Hide datasets whose ACL no longer grants the caller read access
+        if dataset_acl::check(
+            &conn,
+            subscription.dataset_id,
+            identity.unwrap_or(&anonymous),
+            dataset_acl::AclPermission::Read,
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        {
+            watched.push(subscription);
+        }
+    }
+    Ok(Json(envelope.page(watched)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data/orders', 'parquet', datetime('now'), datetime('now')),
+                    ('customers', '/data/customers', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO fields (dataset_id, name, data_type, nullable)
+             VALUES (1, 'id', 'Int64', 0), (1, 'amount', 'Float64', 1);
+             INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+             VALUES (1, datetime('now', '-1 hour'), 0.95);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_subscribe_and_list() {
+        let conn = setup_db();
+        subscribe(&conn, 1, "user:alice", &WatchEvent::ALL, None).unwrap();
+        subscribe(&conn, 2, "user:alice", &[WatchEvent::Schema], None).unwrap();
+        let updated = subscribe(
+            &conn,
+            1,
+            "user:alice",
+            &[WatchEvent::Quality, WatchEvent::Schema, WatchEvent::Quality],
+            Some("https://hooks.example.com/alice"),
+        )
+        .unwrap();
+        assert_eq!(
+            updated.events,
+            vec![WatchEvent::Schema, WatchEvent::Quality]
+        );
+        subscribe(&conn, 1, "key:etl", &WatchEvent::ALL, None).unwrap();
+
+        let mine: Vec<String> = subscriptions_of(&conn, "user:alice")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.dataset_name)
+            .collect();
+        assert_eq!(mine, vec!["customers", "orders"]);
+        assert_eq!(subscribers_of(&conn, 1).unwrap().len(), 2);
+
+        assert!(unsubscribe(&conn, 2, "user:alice").unwrap());
+        assert!(!unsubscribe(&conn, 2, "user:alice").unwrap());
+
+        // Trashed datasets drop off watch lists
+        conn.execute(
+            "UPDATE datasets SET deleted_at = datetime('now') WHERE id = 1",
+            [],
+        )
+        .unwrap();
+        assert!(subscriptions_of(&conn, "user:alice").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_changes() {
+        let conn = setup_db();
+        subscribe(&conn, 1, "user:alice", &WatchEvent::ALL, None).unwrap();
+
+        // The first check only records a snapshot
+        assert!(detect_changes(&conn, 0.1).unwrap().is_empty());
+        assert!(detect_changes(&conn, 0.1).unwrap().is_empty());
+
+        conn.execute_batch(
+            "DELETE FROM fields WHERE name = 'amount';
+             UPDATE fields SET data_type = 'Utf8' WHERE name = 'id';
+             INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES (1, 'status', 'Utf8', 1);
+             INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+             VALUES (1, datetime('now'), 0.7);
+             INSERT INTO tags (dataset_id, tag) VALUES (1, 'deprecated');",
+        )
+        .unwrap();
+        let detected = detect_changes(&conn, 0.1).unwrap();
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].dataset_name, "orders");
+        assert_eq!(
+            detected[0].changes,
+            vec![
+                WatchChange::Schema {
+                    added: vec!["status".to_string()],
+                    removed: vec!["amount".to_string()],
+                    changed: vec!["id".to_string()],
+                },
+                WatchChange::QualityDrop {
+                    previous: 0.95,
+                    current: 0.7
+                },
+                WatchChange::Deprecated,
+            ]
+        );
+        assert!(detect_changes(&conn, 0.1).unwrap().is_empty());
+
+        // Small drops don't notify
+        conn.execute(
+            "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+             VALUES (1, datetime('now', '+1 minute'), 0.65)",
+            [],
+        )
+        .unwrap();
+        assert!(detect_changes(&conn, 0.1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_subscriber_key() {
+        assert_eq!(
+            subscriber_key(Some("alice"), Some("k1")),
+            Some("user:alice".to_string())
+        );
+        assert_eq!(subscriber_key(None, Some("k1")), Some("key:k1".to_string()));
+        assert_eq!(subscriber_key(None, None), None);
+    }
+
+    #[test]
+    fn test_subscriber_teams() {
+        let groups = crate::groups::GroupResolver::new(vec![std::sync::Arc::new(
+            crate::groups::StaticGroups::parse("finance=alice,bob;platform=bob").unwrap(),
+        )]);
+        let subscription = |subscriber: &str| Subscription {
+            dataset_id: 1,
+            dataset_name: "orders".to_string(),
+            subscriber: subscriber.to_string(),
+            events: WatchEvent::ALL.to_vec(),
+            channel: None,
+            created_at: "2026-01-01 00:00:00".to_string(),
+            last_notified_at: None,
+        };
+        let (alice, bob, key) = (
+            subscription("user:alice"),
+            subscription("user:bob"),
+            subscription("key:finance"),
+        );
+        assert_eq!(
+            subscriber_teams(&[&alice, &bob, &key], &groups),
+            vec!["finance", "platform"]
+        );
+        assert!(subscriber_teams(&[&key], &groups).is_empty());
+    }
+
+    #[cfg(all(feature = "alerting", feature = "api-keys"))]
+    #[tokio::test]
+    async fn test_watch_checks_every_tenant_catalog() {
+        use crate::test_utils::{TestControlPlane, TestTenantBuilder};
+        use metafuse_catalog_storage::{DynCatalogBackend, TenantBackendFactory};
+        use std::sync::Arc;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let template = format!("{}/{{tenant_id}}/catalog.db", dir.path().display());
+        let cp = TestControlPlane::with_template(&template).await.unwrap();
+        TestTenantBuilder::new("acme").build(&cp).await.unwrap();
+        let tenants = Arc::new(TenantBackendFactory::new(&template, 10).unwrap());
+
+        let default_uri = dir.path().join("default.db");
+        let backend: Arc<DynCatalogBackend> = Arc::from(
+            metafuse_catalog_storage::backend_from_uri(default_uri.to_str().unwrap()).unwrap(),
+        );
+        backend.initialize().await.unwrap();
+        std::fs::create_dir_all(dir.path().join("acme")).unwrap();
+        let acme = tenants.get_backend_by_id("acme").await.unwrap();
+        acme.initialize().await.unwrap();
+        {
+            let conn = acme.get_connection().await.unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+            conn.execute_batch(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES ('orders', '/data/orders', 'parquet', datetime('now'), datetime('now'));
+                 INSERT INTO fields (dataset_id, name, data_type, nullable)
+                 VALUES (1, 'id', 'Int64', 0);",
+            )
+            .unwrap();
+            // Stored before the URL policy applied to channels
+            subscribe(
+                &conn,
+                1,
+                "user:alice",
+                &WatchEvent::ALL,
+                Some("https://127.0.0.1/hook"),
+            )
+            .unwrap();
+        }
+
+        let catalogs = watched_catalogs(&backend, Some(&tenants), Some(cp.control_plane())).await;
+        let tenant_ids: Vec<&str> = catalogs.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(tenant_ids, vec!["default", "acme"]);
+
+        let config = WatchConfig::default();
+        let client = crate::alerting::WebhookClient::new_default();
+        let policy = crate::webhooks::UrlPolicy::default();
+        let groups = crate::groups::GroupResolver::new(Vec::new());
+        let check = || async {
+            for (tenant_id, tenant_backend) in &catalogs {
+                check_catalog(
+                    &config,
+                    &client,
+                    &policy,
+                    &groups,
+                    tenant_id,
+                    tenant_backend.as_ref(),
+                )
+                .await;
+            }
+        };
+
+        // The tenant's watched dataset gets its first snapshot
+        check().await;
+        let conn = acme.get_connection().await.unwrap();
+        let snapshots: i64 = conn
+            .query_row("SELECT COUNT(*) FROM watch_snapshots", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(snapshots, 1);
+
+        // The change is detected, but the loopback channel is refused at delivery
+        conn.execute(
+            "INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES (1, 'status', 'Utf8', 1)",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+        check().await;
+        let conn = acme.get_connection().await.unwrap();
+        let subscription = &subscribers_of(&conn, 1).unwrap()[0];
+        assert_eq!(subscription.last_notified_at, None);
+        assert!(detect_changes(&conn, 0.1).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,400 @@
+//! Suggest Module
+//!
+//! Typeahead suggestions for the UI search box: case-insensitive prefix matches
+//! across dataset names, tags, owners, and glossary terms.
+//!
+//! # Architecture
+//!
+//! Each suggestion type is a `LIKE 'prefix%'` lookup served by a `NOCASE` index
+//! (migration v1.12.0), so a request is a handful of short index range scans
+//! rather than an FTS query. Results from all requested types are merged and
+//! ranked closest-completion first.
+//!
+//! # Endpoints
+//!
+//! - `GET /api/v1/suggest?q=pre&types=dataset,tag,owner,term&limit=10`
+
+use crate::dataset_acl;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{bad_request, internal_error, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Default number of suggestions returned
+pub const DEFAULT_SUGGEST_LIMIT: usize = 10;
+
+/// Maximum number of suggestions returned
+pub const MAX_SUGGEST_LIMIT: usize = 50;
+
+/// Maximum accepted prefix length
+pub const MAX_PREFIX_LENGTH: usize = 100;
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// Kind of entity a suggestion refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionType {
+    Dataset,
+    Tag,
+    Owner,
+    Term,
+}
+
+impl SuggestionType {
+    /// All types, in the order used to break ranking ties
+    pub const ALL: [SuggestionType; 4] = [
+        SuggestionType::Dataset,
+        SuggestionType::Tag,
+        SuggestionType::Owner,
+        SuggestionType::Term,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionType::Dataset => "dataset",
+            SuggestionType::Tag => "tag",
+            SuggestionType::Owner => "owner",
+            SuggestionType::Term => "term",
+        }
+    }
+
+    /// Parse a comma-separated `types` parameter.
+    ///
+    /// Returns an error message naming the first unknown type.
+    pub fn parse_list(value: &str) -> Result<Vec<SuggestionType>, String> {
+        let mut types = Vec::new();
+        for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let kind = SuggestionType::ALL
+                .into_iter()
+                .find(|t| t.as_str().eq_ignore_ascii_case(part))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid type '{}'. Valid values: dataset, tag, owner, term",
+                        part
+                    )
+                })?;
+            if !types.contains(&kind) {
+                types.push(kind);
+            }
+        }
+        if types.is_empty() {
+            return Err("'types' must list at least one type".to_string());
+        }
+        Ok(types)
+    }
+
+    /// Prefix lookup for this type, with `filter` (`" AND ..."` on `d`)
+    /// narrowing the datasets it reads. Binds the LIKE pattern, the filter's
+    /// bindings, then the limit.
+    fn query(&self, filter: &str) -> String {
+        match self {
+            SuggestionType::Dataset => format!(
+                r#"SELECT d.name, NULL FROM datasets d
+                   WHERE d.name LIKE ? ESCAPE '\' AND d.deleted_at IS NULL{}
+                   ORDER BY length(d.name), d.name COLLATE NOCASE
+                   LIMIT ?"#,
+                filter
+            ),
+            SuggestionType::Tag => format!(
+                // CROSS JOIN keeps the tag index as the outer loop
+                r#"SELECT t.tag, COUNT(*) FROM tags t
+                   CROSS JOIN datasets d ON d.id = t.dataset_id
+                   WHERE t.tag LIKE ? ESCAPE '\' AND d.deleted_at IS NULL{}
+                   GROUP BY t.tag
+                   ORDER BY length(t.tag), t.tag COLLATE NOCASE
+                   LIMIT ?"#,
+                filter
+            ),
+            SuggestionType::Owner => format!(
+                r#"SELECT d.owner, COUNT(*) FROM datasets d
+                   WHERE d.owner LIKE ? ESCAPE '\' AND d.deleted_at IS NULL{}
+                   GROUP BY d.owner
+                   ORDER BY length(d.owner), d.owner COLLATE NOCASE
+                   LIMIT ?"#,
+                filter
+            ),
+            // Glossary terms are not tied to datasets
+            SuggestionType::Term => r#"SELECT term, NULL FROM glossary_terms
+                   WHERE term LIKE ? ESCAPE '\'
+                   ORDER BY length(term), term COLLATE NOCASE
+                   LIMIT ?"#
+                .to_string(),
+        }
+    }
+}
+
+/// A single typeahead suggestion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
+    #[serde(rename = "type")]
+    pub kind: SuggestionType,
+    pub value: String,
+    /// Number of datasets carrying the tag or owned by the owner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dataset_count: Option<i64>,
+}
+
+/// Response body for `GET /api/v1/suggest`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuggestResponse {
+    pub query: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+/// Escape LIKE wildcards so the prefix is matched literally.
+fn like_prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Find up to `limit` suggestions starting with `prefix` across `types`.
+///
+/// Shorter completions rank first, so an exact match always leads.
+/// `visibility` is an extra SQL condition on `d` with its bindings (e.g. ACL
+/// visibility); hidden datasets neither appear nor count towards tags and owners.
+pub fn suggest(
+    conn: &Connection,
+    prefix: &str,
+    types: &[SuggestionType],
+    limit: usize,
+    visibility: Option<(String, Vec<String>)>,
+) -> rusqlite::Result<Vec<Suggestion>> {
+    let pattern = like_prefix_pattern(prefix);
+    let (filter, filter_bindings) = match visibility {
+        Some((clause, bindings)) => (format!(" AND {}", clause), bindings),
+        None => (String::new(), Vec::new()),
+    };
+    let mut suggestions = Vec::new();
+
+    for kind in types {
+        let mut bindings: Vec<&dyn rusqlite::ToSql> = vec![&pattern];
+        if *kind != SuggestionType::Term {
+            bindings.extend(filter_bindings.iter().map(|b| b as &dyn rusqlite::ToSql));
+        }
+        let limit = limit as i64;
+        bindings.push(&limit);
+
+        let mut stmt = conn.prepare_cached(&kind.query(&filter))?;
+        let rows = stmt.query_map(bindings.as_slice(), |row| {
+            Ok(Suggestion {
+                kind: *kind,
+                value: row.get(0)?,
+                dataset_count: row.get(1)?,
+            })
+        })?;
+        for row in rows {
+            suggestions.push(row?);
+        }
+    }
+
+    suggestions.sort_by(|a, b| {
+        a.value
+            .len()
+            .cmp(&b.value.len())
+            .then(a.kind.cmp(&b.kind))
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    suggestions.truncate(limit);
+    Ok(suggestions)
+}
+
+/// Typeahead suggestions across datasets, tags, owners, and glossary terms
+pub(crate) async fn suggest_completions(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<SuggestResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let query = params
+        .get("q")
+        .map(|q| q.trim())
+        .filter(|q| !q.is_empty())
+        .ok_or_else(|| bad_request("Missing 'q' parameter".to_string(), request_id.0.clone()))?;
+    if query.chars().count() > MAX_PREFIX_LENGTH {
+        return Err(bad_request(
+            format!("'q' must be at most {} characters", MAX_PREFIX_LENGTH),
+            request_id.0.clone(),
+        ));
+    }
+
+    let types = match params.get("types") {
+        Some(types) => {
+            SuggestionType::parse_list(types).map_err(|e| bad_request(e, request_id.0.clone()))?
+        }
+        None => SuggestionType::ALL.to_vec(),
+    };
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_SUGGEST_LIMIT)
+        .clamp(1, MAX_SUGGEST_LIMIT);
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+    let suggestions = suggest(&conn, query, &types, limit, visibility)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(SuggestResponse {
+        query: query.to_string(),
+        suggestions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        for (name, owner) in [
+            ("orders", "ops-team"),
+            ("orders_daily", "ops-team"),
+            ("Order_Items", "analytics"),
+            ("customers", "ops-team"),
+            ("pct_100%_sample", "analytics"),
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, owner, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', ?2, datetime('now'), datetime('now'))",
+                params![name, owner],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO tags (dataset_id, tag) VALUES (1, 'orders'), (2, 'orders'), (2, 'ops');
+             INSERT INTO glossary_terms (term) VALUES ('Order Value'), ('Customer');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(
+            SuggestionType::parse_list("dataset, TAG,dataset").unwrap(),
+            vec![SuggestionType::Dataset, SuggestionType::Tag]
+        );
+        assert!(SuggestionType::parse_list("dataset,column")
+            .unwrap_err()
+            .contains("column"));
+        assert!(SuggestionType::parse_list(" , ").is_err());
+    }
+
+    #[test]
+    fn test_suggest_across_types_case_insensitive() {
+        let conn = setup_db();
+        let results = suggest(&conn, "ORD", &SuggestionType::ALL, 10, None).unwrap();
+        let values: Vec<(&str, SuggestionType)> =
+            results.iter().map(|s| (s.value.as_str(), s.kind)).collect();
+
+        assert_eq!(
+            values,
+            vec![
+                ("orders", SuggestionType::Dataset),
+                ("orders", SuggestionType::Tag),
+                ("Order_Items", SuggestionType::Dataset),
+                ("Order Value", SuggestionType::Term),
+                ("orders_daily", SuggestionType::Dataset),
+            ]
+        );
+        let tag = results
+            .iter()
+            .find(|s| s.kind == SuggestionType::Tag)
+            .unwrap();
+        assert_eq!(tag.dataset_count, Some(2));
+    }
+
+    #[test]
+    fn test_suggest_respects_types_and_limit() {
+        let conn = setup_db();
+        let owners = suggest(&conn, "o", &[SuggestionType::Owner], 10, None).unwrap();
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners[0].value, "ops-team");
+        assert_eq!(owners[0].dataset_count, Some(3));
+
+        let limited = suggest(&conn, "o", &SuggestionType::ALL, 2, None).unwrap();
+        assert_eq!(limited.len(), 2);
+    }
+
+    #[test]
+    fn test_suggest_hides_invisible_datasets() {
+        let conn = setup_db();
+        let visibility = Some(("d.name <> ?".to_string(), vec!["orders".to_string()]));
+        let results = suggest(&conn, "ord", &SuggestionType::ALL, 10, visibility).unwrap();
+        let values: Vec<(&str, SuggestionType, Option<i64>)> = results
+            .iter()
+            .map(|s| (s.value.as_str(), s.kind, s.dataset_count))
+            .collect();
+
+        assert_eq!(
+            values,
+            vec![
+                ("orders", SuggestionType::Tag, Some(1)),
+                ("Order_Items", SuggestionType::Dataset, None),
+                ("Order Value", SuggestionType::Term, None),
+                ("orders_daily", SuggestionType::Dataset, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggest_treats_wildcards_literally() {
+        let conn = setup_db();
+        assert!(suggest(&conn, "%", &SuggestionType::ALL, 10, None)
+            .unwrap()
+            .is_empty());
+        let results = suggest(&conn, "pct_100%", &[SuggestionType::Dataset], 10, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].value, "pct_100%_sample");
+    }
+
+    #[test]
+    fn test_lookups_use_nocase_indexes() {
+        let conn = setup_db();
+        for kind in SuggestionType::ALL {
+            let plan: Vec<String> = conn
+                .prepare(&format!("EXPLAIN QUERY PLAN {}", kind.query("")))
+                .unwrap()
+                .query_map(params!["ord%", 10], |row| row.get(3))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert!(
+                plan.iter().any(|step| step.contains("_nocase")),
+                "{:?} lookup does not use an index: {:?}",
+                kind,
+                plan
+            );
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Dataset Tags
+//!
+//! Adding and removing tags on a dataset.
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_acl;
+use crate::dataset_writes::{
+    apply_hook_changes, run_partial_write_hooks, spawn_post_commit_hooks, stored_dataset_write,
+};
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_write_permission;
+use crate::multi_tenant::resolve_backend;
+use crate::server::{
+    bad_request, dataset_not_found, internal_error, require_dataset_access, AppState, AuditContext,
+    Caller, DatasetPath, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::server::{rbac_error, require_field_permissions};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::timeline;
+use axum::extract::{Extension, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::hooks::WriteOperation;
+use metafuse_catalog_core::validation;
+use serde::Deserialize;
+
+/// Request to add tags to a dataset
+#[derive(Debug, Deserialize)]
+pub(crate) struct AddTagsRequest {
+    tags: Vec<String>,
+}
+
+/// Request to remove tags from a dataset
+#[derive(Debug, Deserialize)]
+pub(crate) struct RemoveTagsRequest {
+    tags: Vec<String>,
+}
+
+/// Add tags to a dataset
+pub(crate) async fn add_tags(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<AddTagsRequest>,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, tags = ?req.tags, "Adding tags to dataset");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    #[cfg(feature = "api-keys")]
+    if req.tags.iter().any(|t| t == timeline::CERTIFIED_TAG) {
+        require_field_permissions(
+            &state.field_permissions,
+            resolved_tenant.as_ref().map(|e| &e.0),
+            ["certification"],
+            &request_id.0,
+        )?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    // Run pre-validate write hooks on the dataset as tagged
+    let mut intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    for tag in &req.tags {
+        if !intended.tags.contains(tag) {
+            intended.tags.push(tag.clone());
+        }
+    }
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    // Tags added here are manual, even if an emitter wrote them first
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    for tag in &req.tags {
+        tx.execute(
+            "INSERT INTO tags (dataset_id, tag) VALUES (?1, ?2)
+             ON CONFLICT(dataset_id, tag) DO UPDATE SET emitted = 0",
+            rusqlite::params![dataset_id, tag],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Get all tags for the dataset
+    let mut stmt = conn
+        .prepare("SELECT tag FROM tags WHERE dataset_id = ?1")
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let tags = stmt
+        .query_map([dataset_id], |row| row.get::<_, String>(0))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, added = req.tags.len(), "Tags added successfully");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset_tags",
+            &name,
+            serde_json::json!({}),
+            serde_json::json!({
+                "action": "add",
+                "tags": req.tags,
+            }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(tags))
+}
+
+/// Remove tags from a dataset
+pub(crate) async fn remove_tags(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    Caller {
+        tenant_backend,
+        identity,
+        #[cfg(feature = "api-keys")]
+        resolved_tenant,
+        ..
+    }: Caller,
+    DatasetPath(name): DatasetPath,
+    JsonBody(req): JsonBody<RemoveTagsRequest>,
+) -> Result<Json<Vec<String>>, (StatusCode, Json<ErrorResponse>)> {
+    // Check write permission in multi-tenant mode
+    #[cfg(feature = "api-keys")]
+    require_write_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    #[cfg(feature = "api-keys")]
+    let tenant_id = resolved_tenant
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .or_else(|| tenant_backend.as_ref().map(|e| e.0.tenant_id()))
+        .unwrap_or("default");
+    #[cfg(not(feature = "api-keys"))]
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+
+    tracing::debug!(tenant_id = %tenant_id, name = %name, tags = ?req.tags, "Removing tags from dataset");
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    #[cfg(feature = "api-keys")]
+    if req.tags.iter().any(|t| t == timeline::CERTIFIED_TAG) {
+        require_field_permissions(
+            &state.field_permissions,
+            resolved_tenant.as_ref().map(|e| &e.0),
+            ["certification"],
+            &request_id.0,
+        )?;
+    }
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id: i64 = conn
+        .query_row(
+            "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+            [&name],
+            |row| row.get(0),
+        )
+        .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+
+    require_dataset_access(
+        &conn,
+        dataset_id,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Write,
+        &request_id,
+    )?;
+
+    // Run pre-validate write hooks on the dataset as untagged
+    let mut intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Update)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    intended.tags.retain(|t| !req.tags.contains(t));
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    for tag in &req.tags {
+        tx.execute(
+            "DELETE FROM tags WHERE dataset_id = ?1 AND tag = ?2",
+            rusqlite::params![dataset_id, tag],
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    }
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Get remaining tags
+    let mut stmt = conn
+        .prepare("SELECT tag FROM tags WHERE dataset_id = ?1")
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let tags = stmt
+        .query_map([dataset_id], |row| row.get::<_, String>(0))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, removed = req.tags.len(), "Tags removed successfully");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    // Emit audit event (non-blocking)
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset_tags",
+            &name,
+            serde_json::json!({
+                "action": "remove",
+                "tags": req.tags,
+            }),
+            serde_json::json!({}),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(tags))
+}
@@ -14,11 +14,16 @@
 //! 2. **Header**: If `X-Tenant-ID` header is present, verify the tenant exists and is
 //!    active, then attach with Viewer role.
 //! 3. **Conflict**: If both are present and the tenant_ids don't match, reject with 403.
+//! 4. **OIDC** (`oidc` feature): If an OIDC issuer is configured and the Bearer token
+//!    is a JWT, verify it and take the tenant and role from its claims (see `oidc`).
+//!    `X-Tenant-ID` picks among the tenants the token grants, and must be one of them.
 //!
 //! # Security Model
 //!
 //! - Tenant API keys (prefix `mft_`) are validated against the control plane database
 //! - Global API keys (prefix `mf_`) do NOT provide tenant identity
+//! - OIDC tokens are verified against the issuer's signing keys; their tenant must
+//!   exist and be active, like an API key's
 //! - The resolved tenant role determines what operations are permitted
 //! - **Header-only resolution** verifies tenant exists and is Active, but only grants
 //!   Viewer (read-only) access. For write operations, use a tenant API key.
@@ -51,11 +56,17 @@
 //! }
 //! ```
 
-use crate::control_plane::{ControlPlane, TenantRole, ValidatedTenantKey};
+use crate::control_plane::{
+    ControlPlane, TenantFeature, TenantFeatureFlags, TenantRole, ValidatedTenantKey,
+};
+use crate::error_codes::ErrorCode;
+#[cfg(feature = "oidc")]
+use crate::oidc::{looks_like_jwt, OidcAuthenticator, OidcError, OidcPrincipal};
+use crate::public_catalog::{is_public_read_route, PublicCatalogConfig};
 #[cfg(feature = "rate-limiting")]
 use crate::rate_limiting::{TenantRateLimitInfo, TenantTier as RateLimitTier};
 use axum::{
-    extract::{Extension, Request},
+    extract::{Extension, MatchedPath, Request},
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
@@ -64,7 +75,7 @@ use axum::{
 use metafuse_catalog_storage::{TenantContext, TenantTier};
 use serde::Serialize;
 use std::sync::Arc;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 /// Convert storage TenantTier to rate limiting TenantTier
 #[cfg(feature = "rate-limiting")]
@@ -79,11 +90,17 @@ fn convert_to_rate_limit_tier(tier: TenantTier) -> RateLimitTier {
 
 /// Inject TenantRateLimitInfo into request extensions for rate limiting
 #[cfg(feature = "rate-limiting")]
-fn inject_rate_limit_info(req: &mut Request, tenant_id: &str, tier: TenantTier) {
+fn inject_rate_limit_info(
+    req: &mut Request,
+    tenant_id: &str,
+    tier: TenantTier,
+    impersonator: Option<&str>,
+) {
     let rate_limit_tier = convert_to_rate_limit_tier(tier);
     let rate_limit_info = TenantRateLimitInfo {
         tenant_id: tenant_id.to_string(),
         tier: rate_limit_tier,
+        impersonator: impersonator.map(str::to_string),
     };
     req.extensions_mut().insert(rate_limit_info);
 }
@@ -101,6 +118,15 @@ pub struct TenantResolverConfig {
     ///
     /// Default: `false`
     pub allow_header_only_resolution: bool,
+
+    /// Public read-only catalog mode.
+    ///
+    /// When enabled, header-only resolution is additionally allowed for anonymous
+    /// discovery requests (list/get/search datasets), even if
+    /// `allow_header_only_resolution` is `false`.
+    ///
+    /// Default: disabled
+    pub public_catalog: PublicCatalogConfig,
 }
 
 impl TenantResolverConfig {
@@ -110,6 +136,15 @@ impl TenantResolverConfig {
     pub fn with_header_resolution() -> Self {
         Self {
             allow_header_only_resolution: true,
+            ..Default::default()
+        }
+    }
+
+    /// Create a config that serves discovery endpoints anonymously.
+    pub fn with_public_catalog(public_catalog: PublicCatalogConfig) -> Self {
+        Self {
+            public_catalog,
+            ..Default::default()
         }
     }
 }
@@ -136,6 +171,10 @@ pub struct ResolvedTenant {
     region: Option<String>,
     /// Source of resolution
     source: TenantSource,
+    /// Platform operator, when resolved from an impersonation key
+    impersonator: Option<String>,
+    /// Sandbox namespace the API key is pinned to
+    sandbox: Option<String>,
 }
 
 /// How the tenant was resolved
@@ -147,6 +186,8 @@ pub enum TenantSource {
     Header,
     /// Resolved from both (matched)
     Both,
+    /// Resolved from an OIDC token
+    Oidc,
 }
 
 impl ResolvedTenant {
@@ -160,6 +201,8 @@ impl ResolvedTenant {
             tier: Some(key.tier),
             region: key.region.clone(),
             source: TenantSource::ApiKey,
+            impersonator: key.impersonated_by.clone(),
+            sandbox: key.sandbox.clone(),
         })
     }
 
@@ -173,6 +216,8 @@ impl ResolvedTenant {
             tier: None,   // No tier when resolved via header only (will use default limits)
             region: None, // No region when resolved via header only
             source: TenantSource::Header,
+            impersonator: None,
+            sandbox: None,
         })
     }
 
@@ -186,6 +231,8 @@ impl ResolvedTenant {
             tier: Some(tier),
             region: None, // No region when resolved via header only
             source: TenantSource::Header,
+            impersonator: None,
+            sandbox: None,
         })
     }
 
@@ -199,6 +246,27 @@ impl ResolvedTenant {
             tier: Some(key.tier),
             region: key.region.clone(),
             source: TenantSource::Both,
+            impersonator: key.impersonated_by.clone(),
+            sandbox: key.sandbox.clone(),
+        })
+    }
+
+    /// Create a new resolved tenant from an OIDC token for an active tenant
+    #[cfg(feature = "oidc")]
+    pub fn from_oidc(
+        tenant: &crate::control_plane::Tenant,
+        role: TenantRole,
+    ) -> Result<Self, String> {
+        let context = TenantContext::new(&tenant.tenant_id)
+            .map_err(|e| format!("Invalid tenant ID from token: {}", e))?;
+        Ok(Self {
+            context,
+            role: Some(role),
+            tier: tenant.tier_enum(),
+            region: tenant.region.clone(),
+            source: TenantSource::Oidc,
+            impersonator: None,
+            sandbox: None,
         })
     }
 
@@ -211,6 +279,8 @@ impl ResolvedTenant {
             tier: None, // Tests can set tier via for_testing_with_tier if needed
             region: None,
             source,
+            impersonator: None,
+            sandbox: None,
         }
     }
 
@@ -228,6 +298,8 @@ impl ResolvedTenant {
             tier,
             region: None,
             source,
+            impersonator: None,
+            sandbox: None,
         }
     }
 
@@ -281,6 +353,16 @@ impl ResolvedTenant {
         self.effective_role().can_manage_keys()
     }
 
+    /// Get the platform operator impersonating the tenant, if any.
+    pub fn impersonator(&self) -> Option<&str> {
+        self.impersonator.as_deref()
+    }
+
+    /// Get the sandbox namespace the API key is pinned to, if any.
+    pub fn sandbox(&self) -> Option<&str> {
+        self.sandbox.as_deref()
+    }
+
     /// Get the tenant region for multi-region deployments.
     ///
     /// Returns the region from the tenant's record in the control plane database.
@@ -300,8 +382,13 @@ impl std::fmt::Display for ResolvedTenant {
                 TenantSource::ApiKey => "api_key",
                 TenantSource::Header => "header",
                 TenantSource::Both => "both",
+                TenantSource::Oidc => "oidc",
             }
-        )
+        )?;
+        match &self.impersonator {
+            Some(operator) => write!(f, "[impersonated by {}]", operator),
+            None => Ok(()),
+        }
     }
 }
 
@@ -310,6 +397,7 @@ impl std::fmt::Display for ResolvedTenant {
 struct TenantErrorResponse {
     error: String,
     message: String,
+    code: ErrorCode,
     #[serde(skip_serializing_if = "Option::is_none")]
     request_id: Option<String>,
 }
@@ -367,6 +455,80 @@ fn extract_client_ip(req: &Request) -> Option<String> {
     None
 }
 
+/// Log every request made with an impersonation key, so operator activity
+/// stands out from the tenant's own.
+fn log_impersonation(req: &Request, resolved: &ResolvedTenant) {
+    if let Some(operator) = resolved.impersonator() {
+        info!(
+            tenant = %resolved.tenant_id(),
+            impersonated_by = %operator,
+            method = %req.method(),
+            path = %req.uri().path(),
+            "Impersonated request"
+        );
+    }
+}
+
+/// Verify an OIDC token and resolve the tenant it grants for this request.
+#[cfg(feature = "oidc")]
+async fn resolve_oidc(
+    control_plane: &ControlPlane,
+    authenticator: &OidcAuthenticator,
+    token: &str,
+    header_tenant: Option<&str>,
+) -> Result<(ResolvedTenant, OidcPrincipal), (ErrorCode, String)> {
+    let principal = match authenticator.authenticate(token).await {
+        Ok(principal) => principal,
+        Err(OidcError::Invalid(e)) => {
+            warn!(error = %e, "Invalid OIDC token");
+            return Err((
+                ErrorCode::Unauthorized,
+                "Invalid or expired token".to_string(),
+            ));
+        }
+        Err(OidcError::Unavailable(e)) => {
+            warn!(error = %e, "Failed to fetch OIDC signing keys");
+            return Err((
+                ErrorCode::ServiceUnavailable,
+                "Identity provider unavailable".to_string(),
+            ));
+        }
+    };
+    let tenant_id = principal.select_tenant(header_tenant).map_err(|e| {
+        warn!(user = %principal.user, header_tenant = ?header_tenant, "{}", e);
+        (ErrorCode::Forbidden, e)
+    })?;
+
+    let tenant = match control_plane.get_tenant(tenant_id).await {
+        Ok(Some(tenant)) => tenant,
+        Ok(None) => {
+            return Err((
+                ErrorCode::TenantNotFound,
+                format!("Tenant '{}' not found", tenant_id),
+            ))
+        }
+        Err(e) => {
+            warn!(error = %e, "Error looking up tenant");
+            return Err((
+                ErrorCode::InternalError,
+                "Failed to verify tenant".to_string(),
+            ));
+        }
+    };
+    if !tenant.is_operational() {
+        return Err((
+            ErrorCode::TenantSuspended,
+            format!(
+                "Tenant '{}' is not active (status: {})",
+                tenant_id, tenant.status
+            ),
+        ));
+    }
+    let resolved = ResolvedTenant::from_oidc(&tenant, principal.role)
+        .map_err(|e| (ErrorCode::InternalError, e))?;
+    Ok((resolved, principal))
+}
+
 /// Tenant resolution middleware
 ///
 /// Resolves the current tenant from API key or `X-Tenant-ID` header and attaches
@@ -384,9 +546,19 @@ fn extract_client_ip(req: &Request) -> Option<String> {
 ///    - Verify tenant exists and is active
 ///    - Attach with Viewer role (no API key = read-only access)
 ///
+/// 2b. If only `X-Tenant-ID` header is present AND public catalog mode is enabled:
+///    - Only `GET` discovery routes (list/get/search datasets) are resolved
+///    - Excluded tenants return 404
+///    - Attach with Viewer role plus a `PublicAccess` marker for redaction
+///
 /// 3. If neither is present:
 ///    - Pass through without tenant context (endpoints decide if required)
 ///
+/// 4. If an `OidcAuthenticator` extension is present and the Bearer token is a JWT:
+///    - Verify the token and map its claims to a tenant and role
+///    - `X-Tenant-ID`, if present, must be one of the token's tenants
+///    - Attach the `OidcPrincipal` for the caller's identity
+///
 /// # Security
 ///
 /// By default, header-only resolution is DISABLED. Pass `TenantResolverConfig` as an
@@ -397,10 +569,12 @@ fn extract_client_ip(req: &Request) -> Option<String> {
 /// - **401 Unauthorized**: Invalid or expired API key
 /// - **403 Forbidden**: Tenant ID mismatch between API key and header
 /// - **400 Bad Request**: Invalid tenant ID format
+/// - **503 Service Unavailable**: OIDC signing keys could not be fetched
 #[cfg(feature = "api-keys")]
 pub async fn tenant_resolver_middleware(
     Extension(control_plane): Extension<Arc<ControlPlane>>,
     config: Option<Extension<TenantResolverConfig>>,
+    #[cfg(feature = "oidc")] oidc: Option<Extension<Arc<OidcAuthenticator>>>,
     mut req: Request,
     next: Next,
 ) -> Response {
@@ -409,6 +583,58 @@ pub async fn tenant_resolver_middleware(
     let api_key = extract_api_key(&req);
     let header_tenant = extract_tenant_header(&req);
 
+    // Case 4: OIDC token from the configured issuer
+    #[cfg(feature = "oidc")]
+    if let (Some(Extension(authenticator)), Some(token)) =
+        (&oidc, api_key.as_deref().filter(|k| looks_like_jwt(k)))
+    {
+        return match resolve_oidc(
+            &control_plane,
+            authenticator,
+            token,
+            header_tenant.as_deref(),
+        )
+        .await
+        {
+            Ok((resolved, principal)) => {
+                debug!(
+                    tenant = %resolved.tenant_id(),
+                    role = %resolved.effective_role(),
+                    user = %principal.user,
+                    source = "oidc",
+                    "Resolved tenant from OIDC token"
+                );
+                // Inject rate limit info for tenant-aware rate limiting
+                #[cfg(feature = "rate-limiting")]
+                inject_rate_limit_info(
+                    &mut req,
+                    resolved.tenant_id(),
+                    resolved.tier().unwrap_or_default(),
+                    None,
+                );
+                req.extensions_mut().insert(principal);
+                req.extensions_mut().insert(resolved);
+                next.run(req).await
+            }
+            Err((code, message)) => {
+                let status = code
+                    .http_status()
+                    .and_then(|s| StatusCode::from_u16(s).ok())
+                    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                (
+                    status,
+                    Json(TenantErrorResponse {
+                        error: status.canonical_reason().unwrap_or_default().to_string(),
+                        message,
+                        code,
+                        request_id,
+                    }),
+                )
+                    .into_response()
+            }
+        };
+    }
+
     // Check if this is a tenant API key
     let is_tenant_key = api_key
         .as_ref()
@@ -438,6 +664,7 @@ pub async fn tenant_resolver_middleware(
                                     "Tenant ID mismatch: API key belongs to '{}' but header specifies '{}'",
                                     validated_key.tenant_id, header_id
                                 ),
+                                code: ErrorCode::Forbidden,
                                 request_id,
                             }),
                         )
@@ -453,12 +680,14 @@ pub async fn tenant_resolver_middleware(
                                 source = "both",
                                 "Resolved tenant from API key + header"
                             );
+                            log_impersonation(&req, &resolved);
                             // Inject rate limit info for tenant-aware rate limiting
                             #[cfg(feature = "rate-limiting")]
                             inject_rate_limit_info(
                                 &mut req,
                                 &validated_key.tenant_id,
                                 validated_key.tier,
+                                validated_key.impersonated_by.as_deref(),
                             );
                             req.extensions_mut().insert(resolved);
                         }
@@ -469,6 +698,7 @@ pub async fn tenant_resolver_middleware(
                                 Json(TenantErrorResponse {
                                     error: "Internal Server Error".to_string(),
                                     message: e,
+                                    code: ErrorCode::InternalError,
                                     request_id,
                                 }),
                             )
@@ -485,12 +715,14 @@ pub async fn tenant_resolver_middleware(
                                 source = "api_key",
                                 "Resolved tenant from API key"
                             );
+                            log_impersonation(&req, &resolved);
                             // Inject rate limit info for tenant-aware rate limiting
                             #[cfg(feature = "rate-limiting")]
                             inject_rate_limit_info(
                                 &mut req,
                                 &validated_key.tenant_id,
                                 validated_key.tier,
+                                validated_key.impersonated_by.as_deref(),
                             );
                             req.extensions_mut().insert(resolved);
                         }
@@ -501,6 +733,7 @@ pub async fn tenant_resolver_middleware(
                                 Json(TenantErrorResponse {
                                     error: "Internal Server Error".to_string(),
                                     message: e,
+                                    code: ErrorCode::InternalError,
                                     request_id,
                                 }),
                             )
@@ -517,6 +750,7 @@ pub async fn tenant_resolver_middleware(
                     Json(TenantErrorResponse {
                         error: "Unauthorized".to_string(),
                         message: "Invalid or expired API key".to_string(),
+                        code: ErrorCode::Unauthorized,
                         request_id,
                     }),
                 )
@@ -530,6 +764,7 @@ pub async fn tenant_resolver_middleware(
                     Json(TenantErrorResponse {
                         error: "Internal Server Error".to_string(),
                         message: "Failed to validate API key".to_string(),
+                        code: ErrorCode::InternalError,
                         request_id,
                     }),
                 )
@@ -539,8 +774,27 @@ pub async fn tenant_resolver_middleware(
     }
     // Case 2: Only X-Tenant-ID header present (no tenant API key)
     else if let Some(tenant_id) = header_tenant {
+        // Anonymous discovery requests are allowed through in public catalog mode
+        let public_read = !resolver_config.allow_header_only_resolution
+            && resolver_config.public_catalog.enabled
+            && is_public_read_route(req.method(), req.uri().path());
+
+        if public_read && !resolver_config.public_catalog.allows_tenant(&tenant_id) {
+            warn!(tenant_id = %tenant_id, "Tenant is excluded from the public catalog");
+            return (
+                StatusCode::NOT_FOUND,
+                Json(TenantErrorResponse {
+                    error: "Not Found".to_string(),
+                    message: format!("Tenant '{}' not found", tenant_id),
+                    code: ErrorCode::TenantNotFound,
+                    request_id,
+                }),
+            )
+                .into_response();
+        }
+
         // Check if header-only resolution is allowed
-        if !resolver_config.allow_header_only_resolution {
+        if !resolver_config.allow_header_only_resolution && !public_read {
             warn!(
                 tenant_id = %tenant_id,
                 "Header-only tenant resolution is disabled; API key required"
@@ -551,6 +805,7 @@ pub async fn tenant_resolver_middleware(
                     error: "Unauthorized".to_string(),
                     message: "Tenant API key required. Header-only resolution is disabled."
                         .to_string(),
+                    code: ErrorCode::Unauthorized,
                     request_id,
                 }),
             )
@@ -565,6 +820,7 @@ pub async fn tenant_resolver_middleware(
                 Json(TenantErrorResponse {
                     error: "Bad Request".to_string(),
                     message: format!("Invalid tenant ID format: {}", e),
+                    code: ErrorCode::ValidationFailed,
                     request_id,
                 }),
             )
@@ -588,6 +844,7 @@ pub async fn tenant_resolver_middleware(
                                 "Tenant '{}' is not active (status: {})",
                                 tenant_id, tenant.status
                             ),
+                            code: ErrorCode::TenantSuspended,
                             request_id,
                         }),
                     )
@@ -600,14 +857,20 @@ pub async fn tenant_resolver_middleware(
                         debug!(
                             tenant = %resolved.tenant_id(),
                             source = "header",
+                            public_read,
                             "Resolved tenant from header (read-only)"
                         );
+                        if public_read {
+                            req.extensions_mut()
+                                .insert(resolver_config.public_catalog.access());
+                        }
                         // Inject rate limit info for tenant-aware rate limiting
                         #[cfg(feature = "rate-limiting")]
                         inject_rate_limit_info(
                             &mut req,
                             &tenant_id,
                             tenant.tier_enum().unwrap_or_default(),
+                            None,
                         );
                         req.extensions_mut().insert(resolved);
                     }
@@ -618,6 +881,7 @@ pub async fn tenant_resolver_middleware(
                             Json(TenantErrorResponse {
                                 error: "Internal Server Error".to_string(),
                                 message: e,
+                                code: ErrorCode::InternalError,
                                 request_id,
                             }),
                         )
@@ -632,6 +896,7 @@ pub async fn tenant_resolver_middleware(
                     Json(TenantErrorResponse {
                         error: "Not Found".to_string(),
                         message: format!("Tenant '{}' not found", tenant_id),
+                        code: ErrorCode::TenantNotFound,
                         request_id,
                     }),
                 )
@@ -644,6 +909,7 @@ pub async fn tenant_resolver_middleware(
                     Json(TenantErrorResponse {
                         error: "Internal Server Error".to_string(),
                         message: "Failed to verify tenant".to_string(),
+                        code: ErrorCode::InternalError,
                         request_id,
                     }),
                 )
@@ -669,6 +935,7 @@ pub async fn tenant_resolver_middleware(
 pub async fn tenant_identity_middleware(
     Extension(control_plane): Extension<Arc<ControlPlane>>,
     config: Option<Extension<TenantResolverConfig>>,
+    #[cfg(feature = "oidc")] oidc: Option<Extension<Arc<OidcAuthenticator>>>,
     mut req: Request,
     next: Next,
 ) -> Response {
@@ -676,6 +943,33 @@ pub async fn tenant_identity_middleware(
     let api_key = extract_api_key(&req);
     let header_tenant = extract_tenant_header(&req);
 
+    #[cfg(feature = "oidc")]
+    if let (Some(Extension(authenticator)), Some(token)) =
+        (&oidc, api_key.as_deref().filter(|k| looks_like_jwt(k)))
+    {
+        match resolve_oidc(
+            &control_plane,
+            authenticator,
+            token,
+            header_tenant.as_deref(),
+        )
+        .await
+        {
+            Ok((resolved, principal)) => {
+                debug!(
+                    tenant = %resolved.tenant_id(),
+                    role = %resolved.effective_role(),
+                    user = %principal.user,
+                    "Attached tenant identity from OIDC token"
+                );
+                req.extensions_mut().insert(principal);
+                req.extensions_mut().insert(resolved);
+            }
+            Err((_, e)) => debug!(error = %e, "OIDC token not accepted, not attaching identity"),
+        }
+        return next.run(req).await;
+    }
+
     // Check if this is a tenant API key
     let is_tenant_key = api_key
         .as_ref()
@@ -705,6 +999,7 @@ pub async fn tenant_identity_middleware(
                     role = %resolved.effective_role(),
                     "Attached tenant identity from API key"
                 );
+                log_impersonation(&req, &resolved);
                 req.extensions_mut().insert(resolved);
             }
         }
@@ -759,6 +1054,7 @@ pub async fn require_tenant_middleware(req: Request, next: Next) -> Response {
                 error: "Unauthorized".to_string(),
                 message: "Tenant context required. Provide tenant API key or X-Tenant-ID header."
                     .to_string(),
+                code: ErrorCode::Unauthorized,
                 request_id,
             }),
         )
@@ -768,6 +1064,82 @@ pub async fn require_tenant_middleware(req: Request, next: Next) -> Response {
     next.run(req).await
 }
 
+/// Map a route template to the tenant feature that gates it, if any.
+///
+/// Takes the matched route (e.g. `/api/v1/datasets/{name}/usage`), not the
+/// request path, so datasets and fields named like a gated sub-resource are
+/// not gated. A base path in front of `/api/v1/` is ignored.
+pub fn feature_for_route(route: &str) -> Option<TenantFeature> {
+    let route = route.find("/api/v1/").map_or(route, |i| &route[i..]);
+    match route {
+        "/api/v1/datasets/{name}/classifications"
+        | "/api/v1/classifications/pii"
+        | "/api/v1/fields/{id}/classification" => Some(TenantFeature::Classification),
+        r if r.starts_with("/api/v1/governance/classifications/") => {
+            Some(TenantFeature::Classification)
+        }
+        "/api/v1/datasets/{name}/preview" => Some(TenantFeature::Preview),
+        "/api/v1/datasets/{name}/usage" => Some(TenantFeature::UsageAnalytics),
+        r if r.starts_with("/api/v1/analytics/") => Some(TenantFeature::UsageAnalytics),
+        _ => None,
+    }
+}
+
+/// Tenant feature flag middleware
+///
+/// Use after `tenant_resolver_middleware`. Loads the resolved tenant's feature
+/// flags from the control plane, rejects requests to routes gated by a disabled
+/// feature (403), and attaches `TenantFeatureFlags` to request extensions so
+/// handlers can skip optional work (e.g., usage tracking, auto-classification).
+///
+/// Requests without a resolved tenant pass through unchanged. If the flags
+/// cannot be loaded, defaults (all enabled) are used.
+#[cfg(feature = "api-keys")]
+pub async fn tenant_feature_flags_middleware(
+    Extension(control_plane): Extension<Arc<ControlPlane>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let tenant_id = match req.extensions().get::<ResolvedTenant>() {
+        Some(tenant) => tenant.tenant_id().to_string(),
+        None => return next.run(req).await,
+    };
+
+    let flags = match control_plane.get_tenant_feature_flags(&tenant_id).await {
+        Ok(flags) => flags,
+        Err(e) => {
+            warn!(tenant = %tenant_id, error = %e, "Failed to load tenant feature flags, using defaults");
+            TenantFeatureFlags::default()
+        }
+    };
+
+    let feature = req
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|route| feature_for_route(route.as_str()));
+    if let Some(feature) = feature {
+        if !flags.is_enabled(feature) {
+            debug!(tenant = %tenant_id, feature = %feature, "Feature disabled for tenant");
+            return (
+                StatusCode::FORBIDDEN,
+                Json(TenantErrorResponse {
+                    error: "Forbidden".to_string(),
+                    message: format!(
+                        "Feature '{}' is disabled for tenant '{}'",
+                        feature, tenant_id
+                    ),
+                    code: ErrorCode::Forbidden,
+                    request_id: get_request_id(&req),
+                }),
+            )
+                .into_response();
+        }
+    }
+
+    req.extensions_mut().insert(flags);
+    next.run(req).await
+}
+
 /// Middleware to require write permission
 ///
 /// Use after `tenant_resolver_middleware` to enforce write permission.
@@ -789,6 +1161,7 @@ pub async fn require_write_permission(req: Request, next: Next) -> Response {
                         "Write permission required. Current role: {}",
                         tenant.effective_role()
                     ),
+                    code: ErrorCode::Forbidden,
                     request_id,
                 }),
             )
@@ -801,6 +1174,7 @@ pub async fn require_write_permission(req: Request, next: Next) -> Response {
                 Json(TenantErrorResponse {
                     error: "Unauthorized".to_string(),
                     message: "Tenant context required for write operations.".to_string(),
+                    code: ErrorCode::Unauthorized,
                     request_id,
                 }),
             )
@@ -830,6 +1204,7 @@ pub async fn require_admin_permission(req: Request, next: Next) -> Response {
                         "Admin permission required. Current role: {}",
                         tenant.effective_role()
                     ),
+                    code: ErrorCode::Forbidden,
                     request_id,
                 }),
             )
@@ -842,6 +1217,7 @@ pub async fn require_admin_permission(req: Request, next: Next) -> Response {
                 Json(TenantErrorResponse {
                     error: "Unauthorized".to_string(),
                     message: "Tenant context required for admin operations.".to_string(),
+                    code: ErrorCode::Unauthorized,
                     request_id,
                 }),
             )
@@ -854,6 +1230,49 @@ pub async fn require_admin_permission(req: Request, next: Next) -> Response {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_feature_for_route() {
+        assert_eq!(
+            feature_for_route("/api/v1/datasets/{name}/classifications"),
+            Some(TenantFeature::Classification)
+        );
+        assert_eq!(
+            feature_for_route("/api/v1/classifications/pii"),
+            Some(TenantFeature::Classification)
+        );
+        assert_eq!(
+            feature_for_route("/api/v1/fields/{id}/classification"),
+            Some(TenantFeature::Classification)
+        );
+        assert_eq!(
+            feature_for_route("/api/v1/governance/classifications/bulk-verify"),
+            Some(TenantFeature::Classification)
+        );
+        assert_eq!(
+            feature_for_route("/api/v1/datasets/{name}/preview"),
+            Some(TenantFeature::Preview)
+        );
+        assert_eq!(
+            feature_for_route("/api/v1/datasets/{name}/usage"),
+            Some(TenantFeature::UsageAnalytics)
+        );
+        assert_eq!(
+            feature_for_route("/api/v1/analytics/popular"),
+            Some(TenantFeature::UsageAnalytics)
+        );
+        assert_eq!(
+            feature_for_route("/catalog/api/v1/datasets/{name}/usage"),
+            Some(TenantFeature::UsageAnalytics)
+        );
+        // Quota usage is not usage analytics
+        assert_eq!(feature_for_route("/api/v1/usage"), None);
+        assert_eq!(feature_for_route("/api/v1/datasets/{name}"), None);
+        assert_eq!(
+            feature_for_route("/api/v1/datasets/{name}/fields/{field}"),
+            None
+        );
+    }
+
     #[test]
     fn test_resolved_tenant_permissions() {
         // Admin role
@@ -863,6 +1282,8 @@ mod tests {
             tier: Some(TenantTier::Standard),
             region: None,
             source: TenantSource::ApiKey,
+            impersonator: None,
+            sandbox: None,
         };
         assert!(admin.can_read());
         assert!(admin.can_write());
@@ -876,6 +1297,8 @@ mod tests {
             tier: Some(TenantTier::Standard),
             region: None,
             source: TenantSource::ApiKey,
+            impersonator: None,
+            sandbox: None,
         };
         assert!(editor.can_read());
         assert!(editor.can_write());
@@ -889,6 +1312,8 @@ mod tests {
             tier: Some(TenantTier::Standard),
             region: None,
             source: TenantSource::ApiKey,
+            impersonator: None,
+            sandbox: None,
         };
         assert!(viewer.can_read());
         assert!(!viewer.can_write());
@@ -902,6 +1327,8 @@ mod tests {
             tier: None,
             region: None,
             source: TenantSource::Header,
+            impersonator: None,
+            sandbox: None,
         };
         assert!(header_only.can_read());
         assert!(!header_only.can_write());
@@ -917,6 +1344,8 @@ mod tests {
             tier: Some(TenantTier::Standard),
             region: None,
             source: TenantSource::ApiKey,
+            impersonator: None,
+            sandbox: None,
         };
         assert_eq!(format!("{}", tenant), "acme-corp(api_key)");
 
@@ -926,6 +1355,8 @@ mod tests {
             tier: None,
             region: None,
             source: TenantSource::Header,
+            impersonator: None,
+            sandbox: None,
         };
         assert_eq!(format!("{}", tenant), "acme-corp(header)");
 
@@ -935,6 +1366,8 @@ mod tests {
             tier: Some(TenantTier::Standard),
             region: None,
             source: TenantSource::Both,
+            impersonator: None,
+            sandbox: None,
         };
         assert_eq!(format!("{}", tenant), "acme-corp(both)");
     }
@@ -948,6 +1381,8 @@ mod tests {
             tier: Some(TenantTier::Standard),
             region: None,
             source: TenantSource::ApiKey,
+            impersonator: None,
+            sandbox: None,
         };
         assert_eq!(with_role.effective_role(), TenantRole::Admin);
 
@@ -958,6 +1393,8 @@ mod tests {
             tier: None,
             region: None,
             source: TenantSource::Header,
+            impersonator: None,
+            sandbox: None,
         };
         assert_eq!(without_role.effective_role(), TenantRole::Viewer);
     }
@@ -977,6 +1414,8 @@ mod tests {
             tier: Some(TenantTier::Premium),
             region: None,
             source: TenantSource::Both,
+            impersonator: None,
+            sandbox: None,
         };
 
         assert_eq!(tenant.tenant_id(), "my-tenant");
@@ -997,6 +1436,8 @@ mod tests {
             role: TenantRole::Admin,
             tier: TenantTier::Premium,
             region: None,
+            impersonated_by: None,
+            sandbox: None,
         };
 
         let resolved = ResolvedTenant::from_api_key(&key).unwrap();
@@ -1034,6 +1475,8 @@ mod tests {
             role: TenantRole::Viewer,
             tier: TenantTier::Free,
             region: None,
+            impersonated_by: None,
+            sandbox: None,
         };
 
         let resolved = ResolvedTenant::from_both(&key).unwrap();
@@ -1069,6 +1512,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_resolver_config_default_disables_public_catalog() {
+        let config = TenantResolverConfig::default();
+        assert!(!config.public_catalog.enabled);
+
+        let config = TenantResolverConfig::with_public_catalog(PublicCatalogConfig::enabled());
+        assert!(config.public_catalog.enabled);
+        assert!(
+            !config.allow_header_only_resolution,
+            "Public catalog mode should not enable general header-only resolution"
+        );
+    }
+
     #[test]
     fn test_resolver_config_with_header_resolution() {
         let config = TenantResolverConfig::with_header_resolution();
@@ -1102,6 +1558,8 @@ mod tests {
                 role: TenantRole::Admin,
                 tier: input_tier,
                 region: None,
+                impersonated_by: None,
+                sandbox: None,
             };
 
             let resolved = ResolvedTenant::from_api_key(&key).unwrap();
@@ -1154,6 +1612,8 @@ mod tests {
             role: TenantRole::Admin,
             tier: TenantTier::Premium,
             region: Some("us-east1".to_string()),
+            impersonated_by: None,
+            sandbox: None,
         };
 
         let resolved = ResolvedTenant::from_api_key(&key_with_region).unwrap();
@@ -1167,12 +1627,42 @@ mod tests {
             role: TenantRole::Viewer,
             tier: TenantTier::Standard,
             region: None,
+            impersonated_by: None,
+            sandbox: None,
         };
 
         let resolved = ResolvedTenant::from_api_key(&key_without_region).unwrap();
         assert_eq!(resolved.region(), None);
     }
 
+    #[test]
+    fn test_impersonation_key_marks_resolved_tenant() {
+        use crate::control_plane::ValidatedTenantKey;
+
+        let key = ValidatedTenantKey {
+            key_hash: "hash".to_string(),
+            tenant_id: "acme-corp".to_string(),
+            name: "impersonation:sam@platform.test".to_string(),
+            role: TenantRole::Viewer,
+            tier: TenantTier::Standard,
+            region: None,
+            impersonated_by: Some("sam@platform.test".to_string()),
+            sandbox: None,
+        };
+
+        let resolved = ResolvedTenant::from_api_key(&key).unwrap();
+        assert_eq!(resolved.impersonator(), Some("sam@platform.test"));
+        assert!(!resolved.can_write());
+        assert_eq!(
+            resolved.to_string(),
+            "acme-corp(api_key)[impersonated by sam@platform.test]"
+        );
+
+        let resolved = ResolvedTenant::from_header("acme-corp").unwrap();
+        assert_eq!(resolved.impersonator(), None);
+        assert_eq!(resolved.to_string(), "acme-corp(header)");
+    }
+
     #[test]
     fn test_region_from_header_is_none() {
         // Header-only resolution should not have region set
@@ -0,0 +1,642 @@
+//! Dataset Activity Timeline
+//!
+//! Merges everything that happened to a dataset into one chronological feed
+//! for the dataset page's history tab:
+//!
+//! - API changes from the audit log (create, update, delete, restore, tags);
+//!   updates carry their field-level `changes` (see `audit_diff`)
+//! - certification changes (adding or removing the `certified` tag)
+//! - quality computations from `quality_metrics`
+//! - emitter writes from `dataset_emissions` (migration v1.17.0)
+//! - Delta table versions, added by the HTTP handler for Delta-backed datasets
+//!
+//! Timestamps are normalized to RFC 3339 UTC (`2026-01-15T10:30:00Z`) so events
+//! from different sources sort correctly.
+
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, bad_request, dataset_not_found, internal_error, AppState, DatasetPath,
+    ErrorResponse, RequestId,
+};
+use crate::{audit_diff, dataset_acl};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::validation;
+use metafuse_catalog_storage::read_snapshot;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Default number of events per page
+pub const DEFAULT_TIMELINE_LIMIT: i64 = 50;
+
+/// Maximum number of events per page
+pub const MAX_TIMELINE_LIMIT: i64 = 500;
+
+/// Tag that marks a dataset as certified
+pub const CERTIFIED_TAG: &str = "certified";
+
+/// Kind of timeline event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventType {
+    Created,
+    Updated,
+    Deleted,
+    Restored,
+    TagsAdded,
+    TagsRemoved,
+    Certified,
+    Decertified,
+    SchemaVersion,
+    QualityComputed,
+    Emitted,
+}
+
+impl TimelineEventType {
+    pub const ALL: &'static [TimelineEventType] = &[
+        TimelineEventType::Created,
+        TimelineEventType::Updated,
+        TimelineEventType::Deleted,
+        TimelineEventType::Restored,
+        TimelineEventType::TagsAdded,
+        TimelineEventType::TagsRemoved,
+        TimelineEventType::Certified,
+        TimelineEventType::Decertified,
+        TimelineEventType::SchemaVersion,
+        TimelineEventType::QualityComputed,
+        TimelineEventType::Emitted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimelineEventType::Created => "created",
+            TimelineEventType::Updated => "updated",
+            TimelineEventType::Deleted => "deleted",
+            TimelineEventType::Restored => "restored",
+            TimelineEventType::TagsAdded => "tags_added",
+            TimelineEventType::TagsRemoved => "tags_removed",
+            TimelineEventType::Certified => "certified",
+            TimelineEventType::Decertified => "decertified",
+            TimelineEventType::SchemaVersion => "schema_version",
+            TimelineEventType::QualityComputed => "quality_computed",
+            TimelineEventType::Emitted => "emitted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|t| t.as_str() == s)
+    }
+}
+
+/// A single entry in a dataset's timeline.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// RFC 3339 UTC timestamp
+    pub timestamp: String,
+    pub event_type: TimelineEventType,
+    /// API key or service that caused the event, when known
+    pub actor: Option<String>,
+    pub summary: String,
+    pub details: Value,
+}
+
+/// Which events to return.
+#[derive(Debug, Clone)]
+pub struct TimelineQuery {
+    /// Only events strictly before this timestamp (for paging)
+    pub before: Option<String>,
+    pub limit: i64,
+    /// Only these event types; `None` means all
+    pub types: Option<Vec<TimelineEventType>>,
+}
+
+impl Default for TimelineQuery {
+    fn default() -> Self {
+        Self {
+            before: None,
+            limit: DEFAULT_TIMELINE_LIMIT,
+            types: None,
+        }
+    }
+}
+
+impl TimelineQuery {
+    /// Whether events of this type are requested.
+    pub fn includes(&self, event_type: TimelineEventType) -> bool {
+        self.types
+            .as_ref()
+            .is_none_or(|types| types.contains(&event_type))
+    }
+
+    /// Whether a normalized timestamp falls inside the requested page.
+    pub fn in_range(&self, timestamp: &str) -> bool {
+        self.before
+            .as_deref()
+            .is_none_or(|before| timestamp < before)
+    }
+}
+
+/// Parse a comma-separated list of event types.
+pub fn parse_types(raw: &str) -> Result<Vec<TimelineEventType>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            TimelineEventType::parse(s).ok_or_else(|| {
+                format!(
+                    "Unknown event type '{}'. Valid types: {}",
+                    s,
+                    TimelineEventType::ALL
+                        .iter()
+                        .map(|t| t.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// Normalize a `before` cursor to the timeline's timestamp format.
+pub fn normalize_timestamp(raw: &str) -> Result<String, String> {
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .map(|ts| {
+            ts.with_timezone(&chrono::Utc)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        })
+        .map_err(|_| format!("Invalid timestamp '{}': expected RFC 3339", raw))
+}
+
+/// SQL expression normalizing a stored timestamp column to RFC 3339 UTC.
+fn normalized(column: &str) -> String {
+    format!("strftime('%Y-%m-%dT%H:%M:%SZ', {})", column)
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+        [table],
+        |row| row.get(0),
+    )
+}
+
+/// Load catalog-stored events for a dataset, newest first.
+///
+/// Each source is limited separately, then the merged list is truncated, so
+/// the result is the newest `query.limit` events overall. Delta versions are
+/// not included; add them with [`merge_events`].
+pub fn dataset_events(
+    conn: &Connection,
+    dataset_id: i64,
+    name: &str,
+    query: &TimelineQuery,
+) -> rusqlite::Result<Vec<TimelineEvent>> {
+    let mut events = Vec::new();
+
+    // Audit log: entity_id holds the dataset name
+    let mut stmt = conn.prepare(&format!(
+        r#"
+        SELECT {ts}, action, entity_type, actor, old_values, new_values
+        FROM audit_log
+        WHERE entity_id = ?1 AND entity_type IN ('dataset', 'dataset_tags')
+          AND (?2 IS NULL OR {ts} < ?2)
+        ORDER BY timestamp DESC, id DESC
+        LIMIT ?3
+        "#,
+        ts = normalized("timestamp")
+    ))?;
+    let rows = stmt.query_map(params![name, query.before, query.limit], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, Option<String>>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (timestamp, action, entity_type, actor, old_values, new_values) = row?;
+        let old_values: Value = old_values
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or(Value::Null);
+        let new_values: Value = new_values
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or(Value::Null);
+        events.extend(audit_events(
+            &timestamp,
+            &action,
+            &entity_type,
+            actor,
+            old_values,
+            new_values,
+        ));
+    }
+
+    // Datasets registered by the emitter have no audit create entry
+    let has_create: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM audit_log WHERE entity_id = ?1 AND entity_type = 'dataset' AND action = 'create')",
+        [name],
+        |row| row.get(0),
+    )?;
+    if !has_create {
+        let created_at: Option<String> = conn.query_row(
+            &format!(
+                "SELECT {} FROM datasets WHERE id = ?1",
+                normalized("created_at")
+            ),
+            [dataset_id],
+            |row| row.get(0),
+        )?;
+        if let Some(timestamp) = created_at.filter(|ts| query.in_range(ts)) {
+            events.push(TimelineEvent {
+                timestamp,
+                event_type: TimelineEventType::Created,
+                actor: None,
+                summary: "Dataset registered".to_string(),
+                details: json!({}),
+            });
+        }
+    }
+
+    if query.includes(TimelineEventType::QualityComputed) {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {ts}, overall_score, completeness_score, freshness_score, file_health_score, sample_count
+            FROM quality_metrics
+            WHERE dataset_id = ?1 AND (?2 IS NULL OR {ts} < ?2)
+            ORDER BY computed_at DESC, id DESC
+            LIMIT ?3
+            "#,
+            ts = normalized("computed_at")
+        ))?;
+        let rows = stmt.query_map(params![dataset_id, query.before, query.limit], |row| {
+            let overall: Option<f64> = row.get(1)?;
+            Ok(TimelineEvent {
+                timestamp: row.get(0)?,
+                event_type: TimelineEventType::QualityComputed,
+                actor: None,
+                summary: match overall {
+                    Some(score) => format!("Quality score computed: {:.0}%", score * 100.0),
+                    None => "Quality computed".to_string(),
+                },
+                details: json!({
+                    "overall_score": overall,
+                    "completeness_score": row.get::<_, Option<f64>>(2)?,
+                    "freshness_score": row.get::<_, Option<f64>>(3)?,
+                    "file_health_score": row.get::<_, Option<f64>>(4)?,
+                    "sample_count": row.get::<_, i64>(5)?,
+                }),
+            })
+        })?;
+        events.extend(rows.collect::<rusqlite::Result<Vec<_>>>()?);
+    }
+
+    if query.includes(TimelineEventType::Emitted) && table_exists(conn, "dataset_emissions")? {
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {ts}, row_count, size_bytes, field_count
+            FROM dataset_emissions
+            WHERE dataset_id = ?1 AND (?2 IS NULL OR {ts} < ?2)
+            ORDER BY emitted_at DESC, id DESC
+            LIMIT ?3
+            "#,
+            ts = normalized("emitted_at")
+        ))?;
+        let rows = stmt.query_map(params![dataset_id, query.before, query.limit], |row| {
+            let row_count: Option<i64> = row.get(1)?;
+            Ok(TimelineEvent {
+                timestamp: row.get(0)?,
+                event_type: TimelineEventType::Emitted,
+                actor: Some("emitter".to_string()),
+                summary: match row_count {
+                    Some(rows) => format!("Emitted by pipeline ({} rows)", rows),
+                    None => "Emitted by pipeline".to_string(),
+                },
+                details: json!({
+                    "row_count": row_count,
+                    "size_bytes": row.get::<_, Option<i64>>(2)?,
+                    "field_count": row.get::<_, i64>(3)?,
+                }),
+            })
+        })?;
+        events.extend(rows.collect::<rusqlite::Result<Vec<_>>>()?);
+    }
+
+    Ok(merge_events(events, Vec::new(), query))
+}
+
+/// Turn one audit entry into timeline events.
+fn audit_events(
+    timestamp: &str,
+    action: &str,
+    entity_type: &str,
+    actor: Option<String>,
+    old_values: Value,
+    new_values: Value,
+) -> Vec<TimelineEvent> {
+    let event = |event_type, summary: String, details| TimelineEvent {
+        timestamp: timestamp.to_string(),
+        event_type,
+        actor: actor.clone(),
+        summary,
+        details,
+    };
+
+    if entity_type == "dataset_tags" {
+        // Tag changes record the action and tags on whichever side changed
+        let values = if old_values.get("action").is_some() {
+            &old_values
+        } else {
+            &new_values
+        };
+        let removed = values.get("action").and_then(Value::as_str) == Some("remove");
+        let tags: Vec<String> = values
+            .get("tags")
+            .and_then(|t| serde_json::from_value(t.clone()).ok())
+            .unwrap_or_default();
+
+        let mut events = vec![if removed {
+            event(
+                TimelineEventType::TagsRemoved,
+                format!("Tags removed: {}", tags.join(", ")),
+                json!({ "tags": tags }),
+            )
+        } else {
+            event(
+                TimelineEventType::TagsAdded,
+                format!("Tags added: {}", tags.join(", ")),
+                json!({ "tags": tags }),
+            )
+        }];
+        if tags.iter().any(|t| t == CERTIFIED_TAG) {
+            events.push(if removed {
+                event(
+                    TimelineEventType::Decertified,
+                    "Certification removed".to_string(),
+                    json!({}),
+                )
+            } else {
+                event(
+                    TimelineEventType::Certified,
+                    "Dataset certified".to_string(),
+                    json!({}),
+                )
+            });
+        }
+        return events;
+    }
+
+    match action {
+        "create" => vec![event(
+            TimelineEventType::Created,
+            "Dataset created".to_string(),
+            new_values,
+        )],
+        "delete" => vec![event(
+            TimelineEventType::Deleted,
+            "Dataset deleted".to_string(),
+            new_values,
+        )],
+        "update" if old_values.get("trashed") == Some(&Value::Bool(true)) => vec![event(
+            TimelineEventType::Restored,
+            "Dataset restored from trash".to_string(),
+            json!({}),
+        )],
+        "update" => {
+            let changes = audit_diff::diff(&old_values, &new_values);
+            let summary = match &changes {
+                Some(changes) if !changes.is_empty() => format!(
+                    "Dataset updated: {}",
+                    changes
+                        .iter()
+                        .map(|c| c.field.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                _ => "Dataset updated".to_string(),
+            };
+            let mut details = json!({ "old": old_values, "new": new_values });
+            if let Some(changes) = changes {
+                details["changes"] = json!(changes);
+            }
+            vec![event(TimelineEventType::Updated, summary, details)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Merge event lists: filter by type and page, sort newest first, truncate.
+pub fn merge_events(
+    events: Vec<TimelineEvent>,
+    more: Vec<TimelineEvent>,
+    query: &TimelineQuery,
+) -> Vec<TimelineEvent> {
+    let mut merged: Vec<TimelineEvent> = events
+        .into_iter()
+        .chain(more)
+        .filter(|e| query.includes(e.event_type) && query.in_range(&e.timestamp))
+        .collect();
+    // Stable sort keeps source order for events in the same second
+    merged.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    merged.truncate(query.limit.max(0) as usize);
+    merged
+}
+
+/// Query params for timeline endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct TimelineQueryParams {
+    /// Only events before this RFC 3339 timestamp (pass the last event's timestamp to page)
+    before: Option<String>,
+    limit: Option<i64>,
+    /// Comma-separated event types
+    types: Option<String>,
+}
+
+/// Dataset activity timeline response
+#[derive(Debug, Serialize)]
+pub(crate) struct TimelineResponse {
+    dataset_name: String,
+    events: Vec<TimelineEvent>,
+}
+
+/// Get a dataset's activity timeline
+///
+/// Merges audit entries, certification changes, quality computations, emitter
+/// writes, and Delta versions into one feed, newest first.
+pub(crate) async fn get_dataset_timeline(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<TimelineQueryParams>,
+) -> Result<Json<TimelineResponse>, (StatusCode, Json<ErrorResponse>)> {
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let query = TimelineQuery {
+        before: params
+            .before
+            .as_deref()
+            .map(normalize_timestamp)
+            .transpose()
+            .map_err(|e| bad_request(e, request_id.0.clone()))?,
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_TIMELINE_LIMIT)
+            .clamp(1, MAX_TIMELINE_LIMIT),
+        types: params
+            .types
+            .as_deref()
+            .map(parse_types)
+            .transpose()
+            .map_err(|e| bad_request(e, request_id.0.clone()))?,
+    };
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    let (delta_location, events) = {
+        let conn = read_snapshot(&conn)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let (dataset_id, delta_location): (i64, Option<String>) = conn
+            .query_row(
+                "SELECT id, delta_location FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+                [&name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|_| dataset_not_found(&name, request_id.0.clone()))?;
+        let events = dataset_events(&conn, dataset_id, &name, &query)
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        (delta_location, events)
+    };
+
+    // Delta versions are best-effort: an unreachable table shouldn't hide the rest
+    let mut versions = Vec::new();
+    if let Some(location) =
+        delta_location.filter(|_| query.includes(TimelineEventType::SchemaVersion))
+    {
+        match state
+            .delta_reader
+            .get_history(&location, query.limit as usize)
+            .await
+        {
+            Ok(history) => {
+                versions = history
+                    .into_iter()
+                    .map(|v| TimelineEvent {
+                        timestamp: v
+                            .timestamp
+                            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                        event_type: TimelineEventType::SchemaVersion,
+                        actor: None,
+                        summary: format!("Delta version {}: {}", v.version, v.operation),
+                        details: serde_json::json!({
+                            "version": v.version,
+                            "operation": v.operation,
+                            "parameters": v.parameters,
+                        }),
+                    })
+                    .collect();
+            }
+            Err(e) => {
+                tracing::warn!(dataset = %name, error = %e, "Failed to read Delta history for timeline");
+            }
+        }
+    }
+
+    Ok(Json(TimelineResponse {
+        dataset_name: name,
+        events: merge_events(events, versions, &query),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            r#"
+            INSERT INTO datasets (name, path, format, created_at, last_updated)
+            VALUES ('orders', '/data', 'parquet', '2026-01-01T00:00:00+00:00', '2026-01-01T00:00:00+00:00');
+            INSERT INTO dataset_emissions (dataset_id, emitted_at, row_count)
+            VALUES (1, '2026-01-01T00:00:00+00:00', 100);
+            INSERT INTO quality_metrics (dataset_id, computed_at, overall_score)
+            VALUES (1, '2026-01-02 00:00:00', 0.9);
+            INSERT INTO audit_log (timestamp, action, entity_type, entity_id, actor, old_values, new_values)
+            VALUES ('2026-01-03 00:00:00', 'update', 'dataset_tags', 'orders', 'key-1',
+                    '{}', '{"action": "add", "tags": ["certified"]}');
+            INSERT INTO audit_log (timestamp, action, entity_type, entity_id, actor, old_values, new_values)
+            VALUES ('2026-01-04 00:00:00', 'update', 'dataset', 'orders', 'key-2',
+                    '{"path": "/data", "owner": "sales"}', '{"path": "/new", "owner": "sales"}');
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_timeline_merges_sources_newest_first() {
+        let conn = setup();
+        let events = dataset_events(&conn, 1, "orders", &TimelineQuery::default()).unwrap();
+        let types: Vec<_> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                TimelineEventType::Updated,
+                TimelineEventType::TagsAdded,
+                TimelineEventType::Certified,
+                TimelineEventType::QualityComputed,
+                TimelineEventType::Created,
+                TimelineEventType::Emitted,
+            ]
+        );
+        assert_eq!(events[0].timestamp, "2026-01-04T00:00:00Z");
+        assert_eq!(events[0].actor.as_deref(), Some("key-2"));
+        assert_eq!(events[0].summary, "Dataset updated: path");
+        assert_eq!(
+            events[0].details["changes"],
+            json!([{"field": "path", "old": "/data", "new": "/new"}])
+        );
+    }
+
+    #[test]
+    fn test_timeline_filters_and_pages() {
+        let conn = setup();
+        let query = TimelineQuery {
+            before: Some(normalize_timestamp("2026-01-03T00:00:00Z").unwrap()),
+            limit: 1,
+            types: None,
+        };
+        let events = dataset_events(&conn, 1, "orders", &query).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, TimelineEventType::QualityComputed);
+
+        let query = TimelineQuery {
+            types: Some(parse_types("certified,decertified").unwrap()),
+            ..Default::default()
+        };
+        let events = dataset_events(&conn, 1, "orders", &query).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, TimelineEventType::Certified);
+
+        assert!(parse_types("bogus").is_err());
+    }
+}
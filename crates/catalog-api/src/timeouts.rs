@@ -0,0 +1,341 @@
+//! Request Timeouts
+//!
+//! Gives every request a latency budget by route, so a slow Delta read or a
+//! stuck query can't hold a connection forever. A request that runs past its
+//! budget is cancelled and answered with `504 Gateway Timeout` and the
+//! `TIMEOUT` error code.
+//!
+//! | Class | Routes | Default |
+//! |-------|--------|---------|
+//! | Slow | Delta-backed and quality routes (`/api/v1/datasets/{name}/stats`, `/quality`, ...) | 10s |
+//! | Read | other `GET` and `HEAD` routes | 2s |
+//! | Write | everything else | none |
+//!
+//! `GET /api/v1/datasets/{name}` reads Delta only for some queries
+//! (`?include=delta`, `?include=quality`, `?resolve_schema=true`, or a
+//! response profile that includes them), so its handler picks the slow or
+//! read budget itself once it has resolved the query.
+//!
+//! The budget covers the handler up to the response headers; streamed bodies
+//! are not cut off. Cancelling drops the handler's future, which rolls back
+//! any open transaction. Work already handed to a blocking thread finishes in
+//! the background.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_TIMEOUTS_ENABLED`: set to `false` to disable budgets (default: `true`)
+//! - `METAFUSE_TIMEOUT_READ_MS`: budget for read routes (default: 2000)
+//! - `METAFUSE_TIMEOUT_SLOW_MS`: budget for slow routes (default: 10000)
+//! - `METAFUSE_TIMEOUT_WRITE_MS`: budget for write routes (default: 0)
+//! - `METAFUSE_ROUTE_TIMEOUTS`: per-route overrides as comma-separated
+//!   `route=ms` pairs, using route templates (e.g.
+//!   `/api/v1/search=1000,/api/v1/datasets/{name}/history=30000`)
+//!
+//! A budget of `0` means no timeout.
+
+use axum::{
+    extract::{Extension, MatchedPath, Request},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use metafuse_catalog_errors::ErrorCode;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::server::RequestId;
+
+/// Default budget for read routes, in milliseconds
+pub const DEFAULT_READ_MS: u64 = 2_000;
+
+/// Default budget for slow routes, in milliseconds
+pub const DEFAULT_SLOW_MS: u64 = 10_000;
+
+/// Routes that read Delta tables or compute quality scores
+const SLOW_ROUTES: &[&str] = &[
+    "/api/v1/datasets/{name}/schema",
+    "/api/v1/datasets/{name}/schema/diff",
+    "/api/v1/datasets/{name}/stats",
+    "/api/v1/datasets/{name}/history",
+    "/api/v1/datasets/{name}/quality",
+    "/api/v1/datasets/{name}/classifications",
+];
+
+/// Read routes whose handler applies the budget, because whether the
+/// request is slow depends on its query
+const HANDLER_TIMED_ROUTES: &[&str] = &["/api/v1/datasets/{name}"];
+
+/// Request timeout configuration
+#[derive(Debug, Clone)]
+pub struct TimeoutConfig {
+    pub enabled: bool,
+    pub read_ms: u64,
+    pub slow_ms: u64,
+    pub write_ms: u64,
+    /// Budgets by route template, overriding the class budgets
+    pub routes: HashMap<String, u64>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            read_ms: DEFAULT_READ_MS,
+            slow_ms: DEFAULT_SLOW_MS,
+            write_ms: 0,
+            routes: HashMap::new(),
+        }
+    }
+}
+
+impl TimeoutConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let budget = |var: &str, default: u64| -> Result<u64, String> {
+            match std::env::var(var) {
+                Ok(v) => v
+                    .parse()
+                    .map_err(|_| format!("Invalid {} '{}': expected milliseconds", var, v)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let routes = match std::env::var("METAFUSE_ROUTE_TIMEOUTS") {
+            Ok(v) => parse_routes(&v)?,
+            Err(_) => defaults.routes,
+        };
+
+        Ok(Self {
+            enabled: std::env::var("METAFUSE_TIMEOUTS_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.enabled),
+            read_ms: budget("METAFUSE_TIMEOUT_READ_MS", defaults.read_ms)?,
+            slow_ms: budget("METAFUSE_TIMEOUT_SLOW_MS", defaults.slow_ms)?,
+            write_ms: budget("METAFUSE_TIMEOUT_WRITE_MS", defaults.write_ms)?,
+            routes,
+        })
+    }
+
+    /// Budget for a request, or `None` if it has no timeout.
+    pub fn budget(&self, method: &Method, route: &str) -> Option<Duration> {
+        if !self.enabled {
+            return None;
+        }
+        let read = method == Method::GET || method == Method::HEAD;
+        if read && HANDLER_TIMED_ROUTES.contains(&route) {
+            return None;
+        }
+        let ms = if let Some(ms) = self.routes.get(route) {
+            *ms
+        } else if SLOW_ROUTES.contains(&route) {
+            self.slow_ms
+        } else if read {
+            self.read_ms
+        } else {
+            self.write_ms
+        };
+        (ms > 0).then(|| Duration::from_millis(ms))
+    }
+
+    /// Budget a handler applies to a read, once it knows whether the read
+    /// does slow work.
+    pub fn read_budget(&self, route: &str, slow: bool) -> Option<Duration> {
+        if !self.enabled {
+            return None;
+        }
+        let ms = match self.routes.get(route) {
+            Some(ms) => *ms,
+            None if slow => self.slow_ms,
+            None => self.read_ms,
+        };
+        (ms > 0).then(|| Duration::from_millis(ms))
+    }
+}
+
+/// Parse `route=ms` pairs.
+fn parse_routes(value: &str) -> Result<HashMap<String, u64>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (route, ms) = pair
+                .rsplit_once('=')
+                .filter(|(route, _)| route.starts_with('/'))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid METAFUSE_ROUTE_TIMEOUTS entry '{}': expected '/route=ms'",
+                        pair
+                    )
+                })?;
+            let ms = ms.trim().parse().map_err(|_| {
+                format!(
+                    "Invalid METAFUSE_ROUTE_TIMEOUTS entry '{}': expected milliseconds",
+                    pair
+                )
+            })?;
+            Ok((route.trim().to_string(), ms))
+        })
+        .collect()
+}
+
+/// Middleware that cancels requests running past their route's budget.
+///
+/// Must run inside `request_id_middleware`, so timeouts carry the request ID.
+pub async fn timeout_middleware(
+    Extension(config): Extension<Arc<TimeoutConfig>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let Some(budget) = config.budget(req.method(), &route) else {
+        return next.run(req).await;
+    };
+    let method = req.method().clone();
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+
+    match tokio::time::timeout(budget, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => timed_out(&method, &route, budget, &request_id),
+    }
+}
+
+/// The `504` response for a request cancelled after `budget`.
+pub fn timed_out(method: &Method, route: &str, budget: Duration, request_id: &str) -> Response {
+    tracing::warn!(
+        method = %method,
+        route = %route,
+        budget_ms = budget.as_millis() as u64,
+        "Request timed out"
+    );
+    #[cfg(feature = "metrics")]
+    crate::metrics::record_request_timeout(method.as_str(), route);
+
+    let body = serde_json::json!({
+        "error": format!(
+            "Request exceeded its {} ms time budget and was cancelled",
+            budget.as_millis()
+        ),
+        "code": ErrorCode::Timeout,
+        "request_id": request_id,
+    });
+    let mut response = Response::new(body.to_string().into());
+    *response.status_mut() = StatusCode::GATEWAY_TIMEOUT;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_budget_by_class() {
+        let config = TimeoutConfig::default();
+        assert_eq!(
+            config.budget(&Method::GET, "/api/v1/datasets"),
+            Some(Duration::from_millis(DEFAULT_READ_MS))
+        );
+        assert_eq!(
+            config.budget(&Method::POST, "/api/v1/datasets/{name}/quality"),
+            Some(Duration::from_millis(DEFAULT_SLOW_MS))
+        );
+        assert_eq!(config.budget(&Method::POST, "/api/v1/datasets"), None);
+
+        let disabled = TimeoutConfig {
+            enabled: false,
+            ..TimeoutConfig::default()
+        };
+        assert_eq!(disabled.budget(&Method::GET, "/api/v1/datasets"), None);
+    }
+
+    #[test]
+    fn test_handler_timed_routes() {
+        let config = TimeoutConfig::default();
+        let route = "/api/v1/datasets/{name}";
+        assert_eq!(config.budget(&Method::GET, route), None);
+        assert_eq!(
+            config.read_budget(route, false),
+            Some(Duration::from_millis(DEFAULT_READ_MS))
+        );
+        assert_eq!(
+            config.read_budget(route, true),
+            Some(Duration::from_millis(DEFAULT_SLOW_MS))
+        );
+
+        let overridden = TimeoutConfig {
+            routes: parse_routes("/api/v1/datasets/{name}=500").unwrap(),
+            ..TimeoutConfig::default()
+        };
+        assert_eq!(
+            overridden.read_budget(route, true),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn test_route_overrides() {
+        let config = TimeoutConfig {
+            routes: parse_routes("/api/v1/search=500, /api/v1/datasets/{name}/stats=0").unwrap(),
+            ..TimeoutConfig::default()
+        };
+        assert_eq!(
+            config.budget(&Method::GET, "/api/v1/search"),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            config.budget(&Method::GET, "/api/v1/datasets/{name}/stats"),
+            None
+        );
+
+        assert!(parse_routes("/api/v1/search").is_err());
+        assert!(parse_routes("api/v1/search=500").is_err());
+        assert!(parse_routes("/api/v1/search=fast").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_times_out() {
+        let config = TimeoutConfig {
+            routes: parse_routes("/slow=20").unwrap(),
+            ..TimeoutConfig::default()
+        };
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(middleware::from_fn(timeout_middleware))
+            .layer(Extension(Arc::new(config)));
+
+        let request = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request("/slow")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "TIMEOUT");
+
+        let response = app.oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,619 @@
+//! Trash Module
+//!
+//! Soft delete for datasets with a retention window before permanent removal.
+//!
+//! # Architecture
+//!
+//! `DELETE /api/v1/datasets/{name}` marks the dataset with `deleted_at`
+//! (migration v1.13.0) instead of removing the row. Trashed datasets are hidden
+//! from reads but keep their fields, tags, lineage, and history, so a mistaken
+//! delete can be undone. [`trash_purge_task`] permanently deletes datasets whose
+//! retention window has expired; the row delete cascades to fields, tags,
+//! lineage edges, and the FTS entry.
+//!
+//! `DELETE /api/v1/datasets/{name}?purge=true` skips the trash and deletes the
+//! dataset immediately, like a zero retention window.
+//!
+//! A trashed dataset still holds its name, so a new dataset with the same name
+//! can only be created after the old one is restored or purged.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_TRASH_RETENTION_DAYS`: days before purge (default: 30, 0 = delete immediately)
+//! - `METAFUSE_TRASH_PURGE_INTERVAL_SECS`: how often the purge job runs (default: 3600)
+//!
+//! # Endpoints
+//!
+//! - `GET /api/v1/admin/trash` - List trashed datasets and when they will be purged
+//! - `POST /api/v1/admin/trash/{name}/restore` - Restore a trashed dataset
+//! - `DELETE /api/v1/admin/trash/{name}` - Purge a trashed dataset now
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_writes::apply_hook_changes;
+use crate::dataset_writes::run_partial_write_hooks;
+use crate::dataset_writes::spawn_post_commit_hooks;
+use crate::dataset_writes::stored_dataset_write;
+use crate::dataset_writes::write_hook_error;
+use crate::datasets::attach_uuids;
+use crate::datasets::parse_partition_keys;
+use crate::datasets::DatasetResponse;
+use crate::datasets::OperationalMetaResponse;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_delete_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::hooks::{DatasetWrite, WriteOperation, WriteSource};
+use metafuse_catalog_core::validation;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// Default days a deleted dataset stays in the trash
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Default interval between purge runs
+pub const DEFAULT_PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Trash retention configuration
+#[derive(Debug, Clone)]
+pub struct TrashConfig {
+    /// Days before a trashed dataset is purged; 0 disables the trash
+    pub retention_days: u32,
+    /// Seconds between background purge runs
+    pub purge_interval_secs: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: DEFAULT_RETENTION_DAYS,
+            purge_interval_secs: DEFAULT_PURGE_INTERVAL_SECS,
+        }
+    }
+}
+
+impl TrashConfig {
+    /// Create config from environment variables.
+    ///
+    /// Reads:
+    /// - `METAFUSE_TRASH_RETENTION_DAYS`: days before purge (0 = no trash)
+    /// - `METAFUSE_TRASH_PURGE_INTERVAL_SECS`: seconds between purge runs
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            retention_days: std::env::var("METAFUSE_TRASH_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.retention_days),
+            purge_interval_secs: std::env::var("METAFUSE_TRASH_PURGE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.purge_interval_secs),
+        }
+    }
+
+    /// Whether deletes go to the trash instead of removing the dataset
+    pub fn enabled(&self) -> bool {
+        self.retention_days > 0
+    }
+}
+
+/// A dataset in the trash.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedDataset {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub format: String,
+    pub tenant: Option<String>,
+    pub domain: Option<String>,
+    pub owner: Option<String>,
+    pub deleted_at: String,
+    pub deleted_by: Option<String>,
+    /// When the purge job will permanently delete the dataset
+    pub purge_after: String,
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+/// Move a live dataset to the trash.
+///
+/// Returns false if no live dataset has this name.
+pub fn trash_dataset(
+    conn: &Connection,
+    name: &str,
+    deleted_by: Option<&str>,
+) -> rusqlite::Result<bool> {
+    let rows = conn.execute(
+        "UPDATE datasets SET deleted_at = datetime('now'), deleted_by = ?2
+         WHERE name = ?1 AND deleted_at IS NULL",
+        params![name, deleted_by],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Permanently delete a live dataset, bypassing the trash.
+///
+/// Fields, tags, lineage edges, and the FTS entry are removed by the schema's
+/// cascades and triggers in the same statement. Returns false if no live
+/// dataset has this name.
+pub fn delete_dataset(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+        [name],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Restore a trashed dataset.
+///
+/// Returns false if no trashed dataset has this name.
+pub fn restore_dataset(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    let rows = conn.execute(
+        "UPDATE datasets SET deleted_at = NULL, deleted_by = NULL
+         WHERE name = ?1 AND deleted_at IS NOT NULL",
+        [name],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Look up the id of a trashed dataset by name.
+pub fn trashed_dataset_id(conn: &Connection, name: &str) -> rusqlite::Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM datasets WHERE name = ?1 AND deleted_at IS NOT NULL",
+        [name],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Check whether a dataset name is held by a trashed dataset.
+pub fn is_trashed(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM datasets WHERE name = ?1 AND deleted_at IS NOT NULL)",
+        [name],
+        |row| row.get(0),
+    )
+}
+
+/// List trashed datasets, oldest deletion first.
+pub fn list_trash(conn: &Connection, retention_days: u32) -> rusqlite::Result<Vec<TrashedDataset>> {
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT id, name, path, format, tenant, domain, owner, deleted_at, deleted_by,
+               datetime(deleted_at, '+' || ?1 || ' days')
+        FROM datasets
+        WHERE deleted_at IS NOT NULL
+        ORDER BY deleted_at, name
+        "#,
+    )?;
+    let rows = stmt.query_map([retention_days], |row| {
+        Ok(TrashedDataset {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            path: row.get(2)?,
+            format: row.get(3)?,
+            tenant: row.get(4)?,
+            domain: row.get(5)?,
+            owner: row.get(6)?,
+            deleted_at: row.get(7)?,
+            deleted_by: row.get(8)?,
+            purge_after: row.get(9)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Permanently delete a trashed dataset now.
+///
+/// Returns false if no trashed dataset has this name.
+pub fn purge_dataset(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM datasets WHERE name = ?1 AND deleted_at IS NOT NULL",
+        [name],
+    )?;
+    Ok(rows > 0)
+}
+
+/// Permanently delete datasets trashed more than `retention_days` ago.
+///
+/// Fields, tags, lineage edges, and the FTS entry are removed by the schema's
+/// cascades and triggers. Returns the purged dataset names.
+pub fn purge_expired(conn: &Connection, retention_days: u32) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "DELETE FROM datasets
+         WHERE deleted_at IS NOT NULL
+           AND deleted_at <= datetime('now', '-' || ?1 || ' days')
+         RETURNING name",
+    )?;
+    let names = stmt.query_map([retention_days], |row| row.get(0))?;
+    names.collect()
+}
+
+/// Background task that periodically purges expired datasets from the trash
+pub async fn trash_purge_task(
+    config: TrashConfig,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(config.purge_interval_secs);
+
+    info!(
+        interval_secs = config.purge_interval_secs,
+        retention_days = config.retention_days,
+        "Trash purge task started"
+    );
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        debug!("Running periodic trash purge");
+
+        match backend.get_connection().await {
+            Ok(conn) => match purge_expired(&conn, config.retention_days) {
+                Ok(purged) => {
+                    if !purged.is_empty() {
+                        info!(count = purged.len(), datasets = ?purged, "Purged expired datasets from trash");
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to purge trash");
+                }
+            },
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for trash purge");
+            }
+        }
+    }
+}
+
+/// Trash listing response
+#[derive(Debug, Serialize)]
+pub(crate) struct TrashResponse {
+    retention_days: u32,
+    datasets: Vec<TrashedDataset>,
+}
+
+/// List deleted datasets awaiting purge
+pub(crate) async fn get_trash(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+) -> Result<Json<TrashResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Trash may hold datasets the tenant deleted; same permission as delete
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let datasets = list_trash(&conn, state.trash_config.retention_days)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    Ok(Json(TrashResponse {
+        retention_days: state.trash_config.retention_days,
+        datasets,
+    }))
+}
+
+/// Restore a deleted dataset from the trash
+pub(crate) async fn restore_trashed_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(name): Path<String>,
+) -> Result<Json<DatasetResponse>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let dataset_id = trashed_dataset_id(&conn, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| {
+            not_found(
+                format!("Dataset '{}' not found in trash", name),
+                request_id.0.clone(),
+            )
+        })?;
+
+    // A restored dataset reappears, so hooks see it as created
+    let intended = stored_dataset_write(&conn, dataset_id, &name, WriteOperation::Create)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let write = run_partial_write_hooks(&state, &intended, &request_id).await?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let restored = restore_dataset(&tx, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !restored {
+        return Err(not_found(
+            format!("Dataset '{}' not found in trash", name),
+            request_id.0.clone(),
+        ));
+    }
+    apply_hook_changes(&tx, dataset_id, &intended, &write)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let mut dataset = conn
+        .query_row(
+            r#"
+            SELECT id, name, path, format, delta_location, description, tenant, domain, owner,
+                   created_at, last_updated, row_count, size_bytes, partition_keys
+            FROM datasets WHERE name = ?1
+            "#,
+            [&name],
+            |row| {
+                let row_count: Option<i64> = row.get(11)?;
+                let size_bytes: Option<i64> = row.get(12)?;
+                let partition_keys = parse_partition_keys(row.get::<_, Option<String>>(13)?);
+                Ok(DatasetResponse {
+                    id: row.get(0)?,
+                    uuid: None,
+                    name: row.get(1)?,
+                    path: row.get(2)?,
+                    format: row.get(3)?,
+                    delta_location: row.get(4)?,
+                    description: row.get(5)?,
+                    tenant: row.get(6)?,
+                    domain: row.get(7)?,
+                    owner: row.get(8)?,
+                    created_at: row.get(9)?,
+                    last_updated: row.get(10)?,
+                    operational: OperationalMetaResponse {
+                        row_count,
+                        size_bytes,
+                        partition_keys,
+                    },
+                    freshness: None,
+                    metadata_completeness: None,
+                    custom_metadata: None,
+                    tags: None,
+                    quality_summary: None,
+                    archived_at: None,
+                    write_report: None,
+                    owner_profile: None,
+                })
+            },
+        )
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    attach_uuids(&conn, std::slice::from_mut(&mut dataset))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Dataset restored from trash");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "dataset",
+            &name,
+            serde_json::json!({ "trashed": true }),
+            serde_json::json!({ "trashed": false }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(Json(dataset))
+}
+
+/// Permanently delete a dataset from the trash before its retention expires
+pub(crate) async fn purge_trashed_dataset(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_delete_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    validation::validate_dataset_name(&name)
+        .map_err(|e| bad_request(e.to_string(), request_id.0.clone()))?;
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Write hooks can block purges like deletes
+    let mut write = DatasetWrite::new(WriteOperation::Delete, WriteSource::Api, &name);
+    state
+        .write_hooks
+        .run_pre_validate(&mut write)
+        .await
+        .map_err(|e| write_hook_error(e, request_id.0.clone()))?;
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let purged = purge_dataset(&tx, &name)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    if !purged {
+        return Err(not_found(
+            format!("Dataset '{}' not found in trash", name),
+            request_id.0.clone(),
+        ));
+    }
+    metafuse_catalog_core::increment_catalog_version(&tx)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    tx.commit()
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(name = %name, "Dataset purged from trash");
+
+    spawn_post_commit_hooks(&state.write_hooks, write);
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "dataset",
+            &name,
+            serde_json::json!({ "name": name, "purged": true }),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        for name in ["orders", "customers"] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', datetime('now'), datetime('now'))",
+                [name],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES (1, 'id', 'Int64', 0);
+             INSERT INTO tags (dataset_id, tag) VALUES (1, 'sales');
+             INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (2, 1, datetime('now'));",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let conn = setup_db();
+
+        assert!(trash_dataset(&conn, "orders", Some("alice")).unwrap());
+        assert!(!trash_dataset(&conn, "orders", None).unwrap());
+        assert!(is_trashed(&conn, "orders").unwrap());
+
+        let trash = list_trash(&conn, 7).unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].name, "orders");
+        assert_eq!(trash[0].deleted_by.as_deref(), Some("alice"));
+        assert!(trash[0].purge_after > trash[0].deleted_at);
+
+        assert!(restore_dataset(&conn, "orders").unwrap());
+        assert!(!restore_dataset(&conn, "orders").unwrap());
+        assert!(!is_trashed(&conn, "orders").unwrap());
+        assert!(list_trash(&conn, 7).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_purge_expired_respects_retention_and_cascades() {
+        let conn = setup_db();
+        trash_dataset(&conn, "orders", None).unwrap();
+
+        assert!(purge_expired(&conn, 1).unwrap().is_empty());
+
+        conn.execute(
+            "UPDATE datasets SET deleted_at = datetime('now', '-2 days') WHERE name = 'orders'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(purge_expired(&conn, 1).unwrap(), vec!["orders".to_string()]);
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM datasets"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM fields"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM tags"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM lineage"), 0);
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM dataset_search WHERE dataset_name = 'orders'"
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_purge_dataset_only_removes_trashed() {
+        let conn = setup_db();
+        assert!(!purge_dataset(&conn, "orders").unwrap());
+
+        trash_dataset(&conn, "orders", None).unwrap();
+        assert!(purge_dataset(&conn, "orders").unwrap());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM datasets"), 1);
+    }
+
+    #[test]
+    fn test_delete_dataset_skips_trash() {
+        let conn = setup_db();
+        trash_dataset(&conn, "customers", None).unwrap();
+        assert!(!delete_dataset(&conn, "customers").unwrap());
+
+        assert!(delete_dataset(&conn, "orders").unwrap());
+        assert!(!delete_dataset(&conn, "orders").unwrap());
+        assert!(!is_trashed(&conn, "orders").unwrap());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM fields"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM tags"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM lineage"), 0);
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM dataset_search WHERE dataset_name = 'orders'"
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_config_enabled() {
+        assert!(TrashConfig::default().enabled());
+        let disabled = TrashConfig {
+            retention_days: 0,
+            ..Default::default()
+        };
+        assert!(!disabled.enabled());
+    }
+}
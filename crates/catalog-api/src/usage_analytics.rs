@@ -5,29 +5,65 @@
 //!
 //! This module provides usage tracking for MetaFuse datasets, including:
 //! - Access counting (reads, searches, API calls)
-//! - Unique user tracking (capped at 10K per day to limit memory)
+//! - Unique user counting with HyperLogLog sketches (fixed memory, no cap)
 //! - Background periodic flushing to database
 //! - Query endpoints for usage analytics
 //!
 //! # Architecture
 //!
 //! Uses lock-free DashMap for concurrent counter updates:
-//! - Key: (dataset_id, date_str) tuple
+//! - Key: (tenant_id, dataset_id, date_str) tuple
 //! - Value: UsageCounters with atomic operations
 //!
 //! A background task periodically flushes counters to the `usage_stats` table.
-
+//!
+//! # Date Rollover
+//!
+//! Flushing takes each bucket's counts atomically and puts them back if the
+//! write fails, so nothing recorded concurrently is lost. Buckets for earlier
+//! days are only dropped once they are empty and no recorder still holds them;
+//! a bucket whose flush failed is kept and retried on the next flush.
+//!
+//! # Unique Users
+//!
+//! Each bucket counts users in a [`Sketch`](crate::hll::Sketch) rather than
+//! a set of names. A flush merges it into the day's stored sketch
+//! (`usage_stats.unique_users_sketch`) and sets `unique_users` to the merged
+//! estimate, and period totals merge the daily sketches, so users active on
+//! several days are counted once. Counts are approximate (about 1.6%
+//! standard error). Rows from before the sketch column existed keep their
+//! exact `unique_users`.
+//!
+//! # Tenants
+//!
+//! Counters are kept per tenant, and each tenant's usage is flushed to that
+//! tenant's catalog with `usage_stats.tenant_id` set; usage of the default
+//! catalog is recorded under [`DEFAULT_TENANT`]. Query functions only read
+//! the given tenant's rows, so usage never leaks between tenants even if
+//! their rows share a database.
+
+use crate::dataset_acl;
+use crate::hll::{AtomicSketch, Sketch, SketchKey};
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+use crate::server::{
+    accessible_dataset_id, internal_error, not_found, AppState, DatasetPath, ErrorResponse,
+    RequestId,
+};
+use axum::extract::{Extension, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
 use dashmap::DashMap;
+#[cfg(feature = "api-keys")]
+use metafuse_catalog_storage::DynCatalogBackend;
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
-/// Maximum unique users tracked per dataset per day (memory protection)
-const MAX_UNIQUE_USERS_PER_DAY: usize = 10_000;
+/// Tenant ID for usage of the default catalog
+pub const DEFAULT_TENANT: &str = "default";
 
 /// Default flush interval in seconds
 const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 60;
@@ -70,8 +106,8 @@ pub struct UsageCounters {
     lineage_queries: AtomicU64,
     /// Number of API calls
     api_calls: AtomicU64,
-    /// Unique users who accessed (capped at MAX_UNIQUE_USERS_PER_DAY)
-    unique_users: RwLock<HashSet<String>>,
+    /// Unique users who accessed
+    unique_users: AtomicSketch,
 }
 
 impl UsageCounters {
@@ -81,7 +117,7 @@ impl UsageCounters {
             search_appearances: AtomicU64::new(0),
             lineage_queries: AtomicU64::new(0),
             api_calls: AtomicU64::new(0),
-            unique_users: RwLock::new(HashSet::new()),
+            unique_users: AtomicSketch::default(),
         }
     }
 
@@ -103,15 +139,9 @@ impl UsageCounters {
         }
     }
 
-    /// Add a user to the unique users set (if not at capacity)
-    async fn add_user(&self, user: &str) -> bool {
-        let mut users = self.unique_users.write().await;
-        if users.len() >= MAX_UNIQUE_USERS_PER_DAY {
-            false
-        } else {
-            users.insert(user.to_string());
-            true
-        }
+    /// Count a user towards unique users
+    fn add_user(&self, user: &str) {
+        self.unique_users.insert(user);
     }
 
     /// Get current counter values
@@ -131,6 +161,34 @@ impl UsageCounters {
         self.lineage_queries.store(0, Ordering::Relaxed);
         self.api_calls.store(0, Ordering::Relaxed);
     }
+
+    /// Take the current counts, leaving zeros; increments made meanwhile are
+    /// kept for the next flush
+    fn take(&self) -> CounterSnapshot {
+        CounterSnapshot {
+            read_count: self.read_count.swap(0, Ordering::Relaxed),
+            search_appearances: self.search_appearances.swap(0, Ordering::Relaxed),
+            lineage_queries: self.lineage_queries.swap(0, Ordering::Relaxed),
+            api_calls: self.api_calls.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    /// Put back counts taken by a flush that failed
+    fn restore(&self, snapshot: &CounterSnapshot) {
+        self.read_count
+            .fetch_add(snapshot.read_count, Ordering::Relaxed);
+        self.search_appearances
+            .fetch_add(snapshot.search_appearances, Ordering::Relaxed);
+        self.lineage_queries
+            .fetch_add(snapshot.lineage_queries, Ordering::Relaxed);
+        self.api_calls
+            .fetch_add(snapshot.api_calls, Ordering::Relaxed);
+    }
+
+    /// Whether there is nothing left to flush
+    fn is_idle(&self) -> bool {
+        self.snapshot().is_empty() && self.unique_users.is_empty()
+    }
 }
 
 /// Snapshot of counter values (for database writes)
@@ -142,33 +200,41 @@ struct CounterSnapshot {
     api_calls: u64,
 }
 
-/// Key for the counters map: (dataset_id, date_string)
-type CounterKey = (i64, String);
+impl CounterSnapshot {
+    fn is_empty(&self) -> bool {
+        self.read_count == 0
+            && self.search_appearances == 0
+            && self.lineage_queries == 0
+            && self.api_calls == 0
+    }
+}
+
+/// Key for the counters map: (tenant_id, dataset_id, date_string)
+type CounterKey = (String, i64, String);
 
 /// Configuration for the usage tracker
 #[derive(Debug, Clone)]
 pub struct UsageConfig {
     /// How often to flush counters to the database (seconds)
     pub flush_interval_secs: u64,
-    /// Maximum unique users to track per dataset per day
-    pub max_unique_users: usize,
 }
 
 impl Default for UsageConfig {
     fn default() -> Self {
         Self {
             flush_interval_secs: DEFAULT_FLUSH_INTERVAL_SECS,
-            max_unique_users: MAX_UNIQUE_USERS_PER_DAY,
         }
     }
 }
 
 /// Usage tracker with lock-free counters
 pub struct UsageTracker {
-    /// Lock-free map: (dataset_id, date) -> UsageCounters
+    /// Lock-free map: (tenant_id, dataset_id, date) -> UsageCounters
     counters: Arc<DashMap<CounterKey, Arc<UsageCounters>>>,
     /// Configuration
     config: UsageConfig,
+    /// When the flush task last finished a pass (creation until the first)
+    last_flush: Mutex<Instant>,
 }
 
 impl UsageTracker {
@@ -177,6 +243,7 @@ impl UsageTracker {
         Self {
             counters: Arc::new(DashMap::new()),
             config,
+            last_flush: Mutex::new(Instant::now()),
         }
     }
 
@@ -185,15 +252,28 @@ impl UsageTracker {
         Self::new(UsageConfig::default())
     }
 
-    /// Record an access event
+    /// Record an access event for a dataset in a tenant's catalog
     pub async fn record_access(
         &self,
+        tenant_id: &str,
+        dataset_id: i64,
+        user: Option<&str>,
+        access_type: AccessType,
+    ) {
+        self.record_access_on(today_string(), tenant_id, dataset_id, user, access_type)
+            .await;
+    }
+
+    /// Record an access event in the bucket for `date`
+    async fn record_access_on(
+        &self,
+        date: String,
+        tenant_id: &str,
         dataset_id: i64,
         user: Option<&str>,
         access_type: AccessType,
     ) {
-        let date = today_string();
-        let key = (dataset_id, date);
+        let key = (tenant_id.to_string(), dataset_id, date);
 
         // Get or create counters for this key
         let counters = self
@@ -207,20 +287,38 @@ impl UsageTracker {
 
         // Track unique user if provided
         if let Some(u) = user {
-            if !counters.add_user(u).await {
-                debug!(
-                    dataset_id,
-                    "Unique user limit reached for dataset, user not tracked"
-                );
-            }
+            counters.add_user(u);
         }
     }
 
-    /// Record multiple search appearances at once
-    pub async fn record_search_appearances(&self, dataset_ids: &[i64], user: Option<&str>) {
+    /// Record a search appearance for each result of a query
+    ///
+    /// Synchronous so search handlers can call it inline. The date and the
+    /// user's sketch key are computed once for the batch, and datasets that
+    /// already have a bucket are counted without allocating.
+    pub fn record_search_appearances(
+        &self,
+        tenant_id: &str,
+        dataset_ids: &[i64],
+        user: Option<&str>,
+    ) {
+        let user = user.map(SketchKey::new);
+        let mut key: CounterKey = (tenant_id.to_string(), 0, today_string());
+
         for &dataset_id in dataset_ids {
-            self.record_access(dataset_id, user, AccessType::SearchAppearance)
-                .await;
+            key.1 = dataset_id;
+            let counters = match self.counters.get(&key) {
+                Some(counters) => counters,
+                None => self
+                    .counters
+                    .entry(key.clone())
+                    .or_insert_with(|| Arc::new(UsageCounters::new()))
+                    .downgrade(),
+            };
+            counters.increment(AccessType::SearchAppearance);
+            if let Some(user) = user {
+                counters.unique_users.insert_key(user);
+            }
         }
     }
 
@@ -229,20 +327,64 @@ impl UsageTracker {
         self.counters.len()
     }
 
-    /// Flush all counters to the database
+    /// How often the flush task runs
+    pub fn flush_interval(&self) -> Duration {
+        Duration::from_secs(self.config.flush_interval_secs)
+    }
+
+    /// Time since the flush task last finished a pass
+    pub fn since_last_flush(&self) -> Duration {
+        self.last_flush
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+    }
+
+    /// Record that the flush task finished a pass
+    fn mark_flushed(&self) {
+        *self.last_flush.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Tenants with counters held in memory
+    pub fn tracked_tenants(&self) -> Vec<String> {
+        let mut tenants: Vec<String> = self.counters.iter().map(|r| r.key().0.clone()).collect();
+        tenants.sort();
+        tenants.dedup();
+        tenants
+    }
+
+    /// Flush a tenant's counters to its catalog database
     ///
     /// Returns the number of records upserted.
     pub async fn flush(
         &self,
+        tenant_id: &str,
         conn: &rusqlite::Connection,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        self.flush_as_of(tenant_id, conn, &today_string()).await
+    }
+
+    /// Flush a tenant's counters, then drop its buckets from days before
+    /// `today` that were fully flushed
+    async fn flush_as_of(
+        &self,
+        tenant_id: &str,
+        conn: &rusqlite::Connection,
+        today: &str,
     ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
         let mut upserted = 0;
+        let has_tenants = has_usage_column(conn, "tenant_id");
 
         // Collect keys to process
-        let keys: Vec<CounterKey> = self.counters.iter().map(|r| r.key().clone()).collect();
+        let keys: Vec<CounterKey> = self
+            .counters
+            .iter()
+            .map(|r| r.key().clone())
+            .filter(|(tenant, _, _)| tenant == tenant_id)
+            .collect();
 
         for key in keys {
-            let (dataset_id, stat_date) = &key;
+            let (_, dataset_id, stat_date) = &key;
 
             // Get counters and snapshot
             let counters = match self.counters.get(&key) {
@@ -250,91 +392,93 @@ impl UsageTracker {
                 None => continue,
             };
 
-            let snapshot = counters.snapshot();
-            let unique_users = counters.unique_users.read().await.len() as i64;
+            // Take the counts so increments during the write go to the next flush
+            let snapshot = counters.take();
+            let users = counters.unique_users.take();
 
             // Skip if no activity
-            if snapshot.read_count == 0
-                && snapshot.search_appearances == 0
-                && snapshot.lineage_queries == 0
-                && snapshot.api_calls == 0
-                && unique_users == 0
-            {
+            if snapshot.is_empty() && users.is_empty() {
                 continue;
             }
 
             // Upsert to database with retry
             let result = upsert_with_retry(
                 conn,
+                tenant_id,
                 *dataset_id,
                 stat_date,
                 &snapshot,
-                unique_users,
+                &users,
+                has_tenants,
                 MAX_RETRY_ATTEMPTS,
             );
 
             match result {
                 Ok(_) => {
                     upserted += 1;
-                    // Reset counters after successful write
-                    counters.reset();
-                    // Clear unique users (we've recorded the count)
-                    counters.unique_users.write().await.clear();
                 }
                 Err(e) => {
                     error!(
+                        tenant_id,
                         dataset_id,
                         stat_date,
                         error = %e,
                         "Failed to flush usage stats after retries, keeping in memory"
                     );
+                    counters.restore(&snapshot);
+                    counters.unique_users.restore(&users);
                 }
             }
         }
 
-        // Clean up old entries (dates older than today)
-        let today = today_string();
-        self.counters.retain(|(_id, date), _| date == &today);
+        // Drop earlier days' buckets once flushed. A bucket still held by a
+        // recorder may be incremented after this check, so it is kept; a
+        // recorder that arrives after removal starts a new bucket.
+        self.counters.retain(|(tenant, _id, date), counters| {
+            tenant != tenant_id
+                || date.as_str() >= today
+                || Arc::strong_count(counters) > 1
+                || !counters.is_idle()
+        });
 
         Ok(upserted)
     }
 }
 
+/// Whether `usage_stats` has a column added by a later migration
+/// (`unique_users_sketch` in v1.25.0, `tenant_id` in v1.26.0)
+fn has_usage_column(conn: &rusqlite::Connection, column: &str) -> bool {
+    conn.prepare(&format!("SELECT {} FROM usage_stats LIMIT 0", column))
+        .is_ok()
+}
+
 /// Upsert usage stats with retry logic
+#[allow(clippy::too_many_arguments)]
 fn upsert_with_retry(
     conn: &rusqlite::Connection,
+    tenant_id: &str,
     dataset_id: i64,
     stat_date: &str,
     snapshot: &CounterSnapshot,
-    unique_users: i64,
+    users: &Sketch,
+    has_tenants: bool,
     max_attempts: u32,
 ) -> Result<(), rusqlite::Error> {
     let mut attempts = 0;
     let mut last_error = None;
 
     while attempts < max_attempts {
-        let result = conn.execute(
-            r#"
-            INSERT INTO usage_stats (dataset_id, stat_date, read_count, unique_users, search_appearances, lineage_queries, api_calls, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'))
-            ON CONFLICT(dataset_id, stat_date) DO UPDATE SET
-                read_count = read_count + excluded.read_count,
-                unique_users = MAX(unique_users, excluded.unique_users),
-                search_appearances = search_appearances + excluded.search_appearances,
-                lineage_queries = lineage_queries + excluded.lineage_queries,
-                api_calls = api_calls + excluded.api_calls,
-                updated_at = datetime('now')
-            "#,
-            rusqlite::params![
+        let result = if has_tenants {
+            upsert_with_sketch(conn, tenant_id, dataset_id, stat_date, snapshot, users)
+        } else {
+            upsert_counts(
+                conn,
                 dataset_id,
                 stat_date,
-                snapshot.read_count as i64,
-                unique_users,
-                snapshot.search_appearances as i64,
-                snapshot.lineage_queries as i64,
-                snapshot.api_calls as i64,
-            ],
-        );
+                snapshot,
+                users.estimate() as i64,
+            )
+        };
 
         match result {
             Ok(_) => return Ok(()),
@@ -352,10 +496,102 @@ fn upsert_with_retry(
     Err(last_error.unwrap())
 }
 
+/// Add a snapshot's counts to the tenant's row for the day, merging its
+/// users into the stored sketch
+fn upsert_with_sketch(
+    conn: &rusqlite::Connection,
+    tenant_id: &str,
+    dataset_id: i64,
+    stat_date: &str,
+    snapshot: &CounterSnapshot,
+    users: &Sketch,
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+
+    let stored: Option<Vec<u8>> = tx
+        .query_row(
+            "SELECT unique_users_sketch FROM usage_stats WHERE dataset_id = ?1 AND stat_date = ?2",
+            rusqlite::params![dataset_id, stat_date],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    let mut sketch = users.clone();
+    if let Some(stored) = stored.as_deref().and_then(Sketch::from_bytes) {
+        sketch.merge(&stored);
+    }
+
+    tx.execute(
+        r#"
+        INSERT INTO usage_stats (dataset_id, stat_date, read_count, unique_users, unique_users_sketch, search_appearances, lineage_queries, api_calls, tenant_id, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, datetime('now'))
+        ON CONFLICT(dataset_id, stat_date) DO UPDATE SET
+            read_count = read_count + excluded.read_count,
+            unique_users = MAX(unique_users, excluded.unique_users),
+            unique_users_sketch = excluded.unique_users_sketch,
+            search_appearances = search_appearances + excluded.search_appearances,
+            lineage_queries = lineage_queries + excluded.lineage_queries,
+            api_calls = api_calls + excluded.api_calls,
+            updated_at = datetime('now')
+        "#,
+        rusqlite::params![
+            dataset_id,
+            stat_date,
+            snapshot.read_count as i64,
+            sketch.estimate() as i64,
+            sketch.to_bytes(),
+            snapshot.search_appearances as i64,
+            snapshot.lineage_queries as i64,
+            snapshot.api_calls as i64,
+            tenant_id,
+        ],
+    )?;
+
+    tx.commit()
+}
+
+/// Add a snapshot's counts to the day's row on catalogs without the tenant
+/// and sketch columns
+fn upsert_counts(
+    conn: &rusqlite::Connection,
+    dataset_id: i64,
+    stat_date: &str,
+    snapshot: &CounterSnapshot,
+    unique_users: i64,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        r#"
+        INSERT INTO usage_stats (dataset_id, stat_date, read_count, unique_users, search_appearances, lineage_queries, api_calls, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'))
+        ON CONFLICT(dataset_id, stat_date) DO UPDATE SET
+            read_count = read_count + excluded.read_count,
+            unique_users = MAX(unique_users, excluded.unique_users),
+            search_appearances = search_appearances + excluded.search_appearances,
+            lineage_queries = lineage_queries + excluded.lineage_queries,
+            api_calls = api_calls + excluded.api_calls,
+            updated_at = datetime('now')
+        "#,
+        rusqlite::params![
+            dataset_id,
+            stat_date,
+            snapshot.read_count as i64,
+            unique_users,
+            snapshot.search_appearances as i64,
+            snapshot.lineage_queries as i64,
+            snapshot.api_calls as i64,
+        ],
+    )
+    .map(|_| ())
+}
+
 /// Background task that periodically flushes usage stats to the database
+///
+/// Usage of the default catalog is written to `backend`; each tenant's usage
+/// is written to the catalog `tenants` resolves for it.
 pub async fn usage_flush_task(
     tracker: Arc<UsageTracker>,
     backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+    tenants: Option<Arc<metafuse_catalog_storage::TenantBackendFactory>>,
 ) {
     let interval = Duration::from_secs(tracker.config.flush_interval_secs);
 
@@ -369,37 +605,60 @@ pub async fn usage_flush_task(
 
         debug!("Running periodic usage stats flush");
 
-        // Get connection and flush
-        match backend.get_connection().await {
-            Ok(conn) => {
-                let result = tokio::task::spawn_blocking({
-                    let tracker = tracker.clone();
-                    move || {
-                        // Need to block on the async flush
-                        tokio::runtime::Handle::current()
-                            .block_on(async { tracker.flush(&conn).await })
+        for tenant_id in tracker.tracked_tenants() {
+            let tenant_backend = if tenant_id == DEFAULT_TENANT {
+                Arc::clone(&backend)
+            } else {
+                let Some(factory) = &tenants else {
+                    warn!(
+                        tenant_id,
+                        "No tenant catalogs configured, usage kept in memory"
+                    );
+                    continue;
+                };
+                match factory.get_backend_by_id(&tenant_id).await {
+                    Ok(tenant_backend) => tenant_backend,
+                    Err(e) => {
+                        warn!(tenant_id, error = %e, "Failed to resolve tenant catalog for usage flush");
+                        continue;
                     }
-                })
-                .await;
+                }
+            };
 
-                match result {
-                    Ok(Ok(count)) => {
-                        if count > 0 {
-                            debug!(count, "Flushed usage stats to database");
+            // Get connection and flush
+            match tenant_backend.get_connection().await {
+                Ok(conn) => {
+                    let result = tokio::task::spawn_blocking({
+                        let tracker = tracker.clone();
+                        let tenant_id = tenant_id.clone();
+                        move || {
+                            // Need to block on the async flush
+                            tokio::runtime::Handle::current()
+                                .block_on(async { tracker.flush(&tenant_id, &conn).await })
+                        }
+                    })
+                    .await;
+
+                    match result {
+                        Ok(Ok(count)) => {
+                            if count > 0 {
+                                debug!(tenant_id, count, "Flushed usage stats to database");
+                            }
+                        }
+                        Ok(Err(e)) => {
+                            error!(tenant_id, error = %e, "Failed to flush usage stats");
+                        }
+                        Err(e) => {
+                            error!(error = %e, "Usage flush task panicked");
                         }
-                    }
-                    Ok(Err(e)) => {
-                        error!(error = %e, "Failed to flush usage stats");
-                    }
-                    Err(e) => {
-                        error!(error = %e, "Usage flush task panicked");
                     }
                 }
-            }
-            Err(e) => {
-                warn!(error = %e, "Failed to get connection for usage flush");
+                Err(e) => {
+                    warn!(tenant_id, error = %e, "Failed to get connection for usage flush");
+                }
             }
         }
+        tracker.mark_flushed();
     }
 }
 
@@ -450,6 +709,9 @@ pub struct DatasetUsageResponse {
 /// Popular dataset entry
 #[derive(Debug, Clone, Serialize)]
 pub struct PopularDatasetEntry {
+    /// Set in views spanning several tenants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
     pub dataset_id: i64,
     pub dataset_name: String,
     pub total_reads: i64,
@@ -464,9 +726,23 @@ pub struct PopularDatasetsResponse {
     pub datasets: Vec<PopularDatasetEntry>,
 }
 
+/// Catalog-wide usage totals for a period
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageTotals {
+    pub period: String,
+    pub total_reads: i64,
+    pub total_search_appearances: i64,
+    pub total_api_calls: i64,
+    /// Datasets with at least one recorded access in the period
+    pub active_datasets: i64,
+}
+
 /// Stale dataset entry (no recent access)
 #[derive(Debug, Clone, Serialize)]
 pub struct StaleDatasetEntry {
+    /// Set in views spanning several tenants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
     pub dataset_id: i64,
     pub dataset_name: String,
     pub last_accessed_at: Option<String>,
@@ -484,9 +760,10 @@ pub struct StaleDatasetsResponse {
 // Query Functions
 // =============================================================================
 
-/// Get usage stats for a specific dataset
+/// Get a tenant's usage stats for a specific dataset
 pub fn query_dataset_usage(
     conn: &rusqlite::Connection,
+    tenant_id: &str,
     dataset_id: i64,
     dataset_name: &str,
     period: &str,
@@ -502,22 +779,25 @@ pub fn query_dataset_usage(
         r#"
         SELECT stat_date, read_count, unique_users, search_appearances, lineage_queries, api_calls
         FROM usage_stats
-        WHERE dataset_id = ?1 AND stat_date >= ?2
+        WHERE dataset_id = ?1 AND stat_date >= ?2 AND tenant_id = ?3
         ORDER BY stat_date DESC
         "#,
     )?;
 
     let daily_stats: Vec<UsageStatEntry> = stmt
-        .query_map(rusqlite::params![dataset_id, start_date], |row| {
-            Ok(UsageStatEntry {
-                stat_date: row.get(0)?,
-                read_count: row.get(1)?,
-                unique_users: row.get(2)?,
-                search_appearances: row.get(3)?,
-                lineage_queries: row.get(4)?,
-                api_calls: row.get(5)?,
-            })
-        })?
+        .query_map(
+            rusqlite::params![dataset_id, start_date, tenant_id],
+            |row| {
+                Ok(UsageStatEntry {
+                    stat_date: row.get(0)?,
+                    read_count: row.get(1)?,
+                    unique_users: row.get(2)?,
+                    search_appearances: row.get(3)?,
+                    lineage_queries: row.get(4)?,
+                    api_calls: row.get(5)?,
+                })
+            },
+        )?
         .collect::<Result<Vec<_>, _>>()?;
 
     // Calculate totals
@@ -526,7 +806,13 @@ pub fn query_dataset_usage(
         .iter()
         .map(|s| s.unique_users)
         .max()
-        .unwrap_or(0);
+        .unwrap_or(0)
+        .max(period_unique_users(
+            conn,
+            tenant_id,
+            dataset_id,
+            &start_date,
+        )?);
     let total_api_calls: i64 = daily_stats.iter().map(|s| s.api_calls).sum();
 
     Ok(DatasetUsageResponse {
@@ -540,11 +826,16 @@ pub fn query_dataset_usage(
     })
 }
 
-/// Get most popular datasets
+/// Get a tenant's most popular datasets
+///
+/// `visibility` is an extra SQL condition on the dataset alias `d` hiding
+/// datasets from the caller, with its bindings.
 pub fn query_popular_datasets(
     conn: &rusqlite::Connection,
+    tenant_id: &str,
     period: &str,
     limit: usize,
+    visibility: Option<(String, Vec<String>)>,
 ) -> Result<PopularDatasetsResponse, rusqlite::Error> {
     let days = parse_period_days(period);
     let start_date = chrono::Utc::now()
@@ -553,7 +844,15 @@ pub fn query_popular_datasets(
         .format("%Y-%m-%d")
         .to_string();
 
-    let mut stmt = conn.prepare(
+    let mut bindings = vec![start_date.clone(), tenant_id.to_string()];
+    let visible = match visibility {
+        Some((clause, values)) => {
+            bindings.extend(values);
+            format!("AND {}", clause)
+        }
+        None => String::new(),
+    };
+    let mut stmt = conn.prepare(&format!(
         r#"
         SELECT
             u.dataset_id,
@@ -563,16 +862,18 @@ pub fn query_popular_datasets(
             SUM(u.api_calls) as api_calls
         FROM usage_stats u
         JOIN datasets d ON d.id = u.dataset_id
-        WHERE u.stat_date >= ?1
+        WHERE u.stat_date >= ?1 AND u.tenant_id = ?2 AND d.deleted_at IS NULL {}
         GROUP BY u.dataset_id, d.name
         ORDER BY total_reads DESC
-        LIMIT ?2
+        LIMIT {}
         "#,
-    )?;
+        visible, limit
+    ))?;
 
-    let datasets: Vec<PopularDatasetEntry> = stmt
-        .query_map(rusqlite::params![start_date, limit as i64], |row| {
+    let mut datasets: Vec<PopularDatasetEntry> = stmt
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
             Ok(PopularDatasetEntry {
+                tenant_id: None,
                 dataset_id: row.get(0)?,
                 dataset_name: row.get(1)?,
                 total_reads: row.get(2)?,
@@ -582,16 +883,92 @@ pub fn query_popular_datasets(
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
+    for entry in &mut datasets {
+        entry.unique_users = entry.unique_users.max(period_unique_users(
+            conn,
+            tenant_id,
+            entry.dataset_id,
+            &start_date,
+        )?);
+    }
+
     Ok(PopularDatasetsResponse {
         period: period.to_string(),
         datasets,
     })
 }
 
-/// Get datasets with no recent access
+/// Unique users of a dataset since `start_date`, estimated by merging the
+/// daily sketches (0 when none are stored)
+fn period_unique_users(
+    conn: &rusqlite::Connection,
+    tenant_id: &str,
+    dataset_id: i64,
+    start_date: &str,
+) -> Result<i64, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT unique_users_sketch FROM usage_stats
+         WHERE dataset_id = ?1 AND stat_date >= ?2 AND tenant_id = ?3
+           AND unique_users_sketch IS NOT NULL",
+    )?;
+    let mut merged = Sketch::default();
+    for bytes in stmt.query_map(
+        rusqlite::params![dataset_id, start_date, tenant_id],
+        |row| row.get::<_, Vec<u8>>(0),
+    )? {
+        if let Some(sketch) = Sketch::from_bytes(&bytes?) {
+            merged.merge(&sketch);
+        }
+    }
+    Ok(merged.estimate() as i64)
+}
+
+/// Get a tenant's usage totals across all its datasets
+pub fn query_usage_totals(
+    conn: &rusqlite::Connection,
+    tenant_id: &str,
+    period: &str,
+) -> Result<UsageTotals, rusqlite::Error> {
+    let days = parse_period_days(period);
+    let start_date = chrono::Utc::now()
+        .checked_sub_signed(chrono::Duration::days(days))
+        .unwrap()
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let (total_reads, total_search_appearances, total_api_calls, active_datasets) = conn
+        .query_row(
+            r#"
+            SELECT
+                COALESCE(SUM(read_count), 0),
+                COALESCE(SUM(search_appearances), 0),
+                COALESCE(SUM(api_calls), 0),
+                COUNT(DISTINCT dataset_id)
+            FROM usage_stats
+            WHERE stat_date >= ?1 AND tenant_id = ?2
+            "#,
+            rusqlite::params![start_date, tenant_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+    Ok(UsageTotals {
+        period: period.to_string(),
+        total_reads,
+        total_search_appearances,
+        total_api_calls,
+        active_datasets,
+    })
+}
+
+/// Get datasets with no recent access by a tenant
+///
+/// `visibility` is an extra SQL condition on the dataset alias `d` hiding
+/// datasets from the caller, with its bindings.
 pub fn query_stale_datasets(
     conn: &rusqlite::Connection,
+    tenant_id: &str,
     stale_threshold_days: i64,
+    visibility: Option<(String, Vec<String>)>,
 ) -> Result<StaleDatasetsResponse, rusqlite::Error> {
     let threshold_date = chrono::Utc::now()
         .checked_sub_signed(chrono::Duration::days(stale_threshold_days))
@@ -599,25 +976,36 @@ pub fn query_stale_datasets(
         .format("%Y-%m-%d")
         .to_string();
 
+    let mut bindings = vec![threshold_date, tenant_id.to_string()];
+    let visible = match visibility {
+        Some((clause, values)) => {
+            bindings.extend(values);
+            format!("AND {}", clause)
+        }
+        None => String::new(),
+    };
+
     // Find datasets with no usage stats in the threshold period
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare(&format!(
         r#"
         SELECT
             d.id,
             d.name,
             MAX(u.stat_date) as last_accessed
         FROM datasets d
-        LEFT JOIN usage_stats u ON d.id = u.dataset_id
+        LEFT JOIN usage_stats u ON d.id = u.dataset_id AND u.tenant_id = ?2
+        WHERE d.deleted_at IS NULL {}
         GROUP BY d.id, d.name
         HAVING last_accessed IS NULL OR last_accessed < ?1
         ORDER BY last_accessed ASC NULLS FIRST
         "#,
-    )?;
+        visible
+    ))?;
 
     let today = chrono::Utc::now().date_naive();
 
     let datasets: Vec<StaleDatasetEntry> = stmt
-        .query_map(rusqlite::params![threshold_date], |row| {
+        .query_map(rusqlite::params_from_iter(bindings), |row| {
             let last_accessed: Option<String> = row.get(2)?;
             let days_since = last_accessed.as_ref().and_then(|d| {
                 chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
@@ -626,6 +1014,7 @@ pub fn query_stale_datasets(
             });
 
             Ok(StaleDatasetEntry {
+                tenant_id: None,
                 dataset_id: row.get(0)?,
                 dataset_name: row.get(1)?,
                 last_accessed_at: last_accessed,
@@ -651,6 +1040,342 @@ fn parse_period_days(period: &str) -> i64 {
     }
 }
 
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/// Query parameters for admin usage views
+#[cfg(feature = "api-keys")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct AdminUsageQuery {
+    /// Only this tenant (`default` for the default catalog); all when unset
+    tenant_id: Option<String>,
+    #[serde(default = "default_popular_limit")]
+    limit: usize,
+    #[serde(default = "default_period")]
+    period: String,
+    #[serde(default = "default_stale_threshold")]
+    threshold_days: i64,
+}
+
+/// Catalogs covered by an admin usage view: the default catalog and every
+/// active tenant, or only the requested tenant
+#[cfg(feature = "api-keys")]
+pub(crate) async fn usage_catalogs(
+    state: &AppState,
+    tenant_id: Option<&str>,
+    request_id: &str,
+) -> Result<Vec<(String, Arc<DynCatalogBackend>)>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_ids = match tenant_id {
+        Some(tenant_id) => vec![tenant_id.to_string()],
+        None => {
+            let mut tenant_ids = vec![DEFAULT_TENANT.to_string()];
+            if let Some(control_plane) = state.multi_tenant.control_plane() {
+                let tenants = control_plane
+                    .list_tenants(Some("active"))
+                    .await
+                    .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?;
+                tenant_ids.extend(tenants.into_iter().map(|t| t.tenant_id));
+            }
+            tenant_ids
+        }
+    };
+
+    let mut catalogs = Vec::with_capacity(tenant_ids.len());
+    for tenant_id in tenant_ids {
+        let backend = if tenant_id == DEFAULT_TENANT {
+            Arc::clone(&state.backend)
+        } else {
+            let factory = state.multi_tenant.factory().ok_or_else(|| {
+                not_found(
+                    format!("Tenant '{}' not found", tenant_id),
+                    request_id.to_string(),
+                )
+            })?;
+            factory
+                .get_backend_by_id(&tenant_id)
+                .await
+                .map_err(|e| internal_error(e.to_string(), request_id.to_string()))?
+        };
+        catalogs.push((tenant_id, backend));
+    }
+    Ok(catalogs)
+}
+
+/// Most popular datasets across all tenants (admin endpoint)
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_get_popular_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(params): Query<AdminUsageQuery>,
+) -> Result<Json<PopularDatasetsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let catalogs = usage_catalogs(&state, params.tenant_id.as_deref(), &request_id.0).await?;
+
+    let mut datasets = Vec::new();
+    for (tenant_id, backend) in catalogs {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let period = params.period.clone();
+        let limit = params.limit;
+        let tenant = tenant_id.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            query_popular_datasets(&conn, &tenant, &period, limit, None)
+        })
+        .await
+        .map_err(|e| internal_error(format!("Task join error: {}", e), request_id.0.clone()))?
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        datasets.extend(result.datasets.into_iter().map(|mut entry| {
+            entry.tenant_id = Some(tenant_id.clone());
+            entry
+        }));
+    }
+
+    datasets.sort_by_key(|d| std::cmp::Reverse(d.total_reads));
+    datasets.truncate(params.limit);
+
+    Ok(Json(PopularDatasetsResponse {
+        period: params.period,
+        datasets,
+    }))
+}
+
+/// Stale datasets across all tenants (admin endpoint)
+#[cfg(feature = "api-keys")]
+pub(crate) async fn admin_get_stale_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Query(params): Query<AdminUsageQuery>,
+) -> Result<Json<StaleDatasetsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let catalogs = usage_catalogs(&state, params.tenant_id.as_deref(), &request_id.0).await?;
+
+    let mut datasets = Vec::new();
+    for (tenant_id, backend) in catalogs {
+        let conn = backend
+            .get_connection()
+            .await
+            .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+        let threshold_days = params.threshold_days;
+        let tenant = tenant_id.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            query_stale_datasets(&conn, &tenant, threshold_days, None)
+        })
+        .await
+        .map_err(|e| internal_error(format!("Task join error: {}", e), request_id.0.clone()))?
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+        datasets.extend(result.datasets.into_iter().map(|mut entry| {
+            entry.tenant_id = Some(tenant_id.clone());
+            entry
+        }));
+    }
+
+    Ok(Json(StaleDatasetsResponse {
+        stale_threshold_days: params.threshold_days,
+        datasets,
+    }))
+}
+
+/// Query parameters for popular datasets endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct PopularQueryParams {
+    #[serde(default = "default_popular_limit")]
+    limit: usize,
+    #[serde(default = "default_period")]
+    period: String,
+}
+
+pub(crate) fn default_popular_limit() -> usize {
+    10
+}
+
+/// Query parameters for stale datasets endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct StaleQueryParams {
+    #[serde(default = "default_stale_threshold")]
+    threshold_days: i64,
+}
+
+pub(crate) fn default_stale_threshold() -> i64 {
+    30
+}
+
+/// Get usage stats for a specific dataset
+pub(crate) async fn get_dataset_usage(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    DatasetPath(name): DatasetPath,
+    Query(params): Query<UsageQueryParams>,
+) -> Result<Json<DatasetUsageResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        dataset_name = %name,
+        period = %params.period,
+        "Querying dataset usage"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    accessible_dataset_id(
+        &conn,
+        &name,
+        identity.as_ref().map(|e| &e.0),
+        dataset_acl::AclPermission::Read,
+        &request_id,
+    )?;
+
+    // Run DB queries in blocking task to avoid blocking async runtime
+    let req_id = request_id.0.clone();
+    let dataset_name_clone = name.clone();
+    let period = params.period.clone();
+    let tenant = tenant_id.to_string();
+
+    let result = tokio::task::spawn_blocking(move || {
+        // First, look up the dataset to get its ID
+        let dataset: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT id, name FROM datasets WHERE name = ?1 AND deleted_at IS NULL",
+                [&dataset_name_clone],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+
+        match dataset {
+            Some((dataset_id, dataset_name)) => {
+                query_dataset_usage(&conn, &tenant, dataset_id, &dataset_name, &period)
+                    .map_err(|e| e.to_string())
+            }
+            None => Err(format!("Dataset '{}' not found", dataset_name_clone)),
+        }
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| {
+        if e.contains("not found") {
+            not_found(e, req_id.clone())
+        } else {
+            internal_error(e, req_id.clone())
+        }
+    })?;
+
+    tracing::info!(
+        dataset_name = %name,
+        total_reads = result.total_reads,
+        "Dataset usage query completed"
+    );
+
+    Ok(Json(result))
+}
+
+/// Get most popular datasets by access count
+pub(crate) async fn get_popular_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<PopularQueryParams>,
+) -> Result<Json<PopularDatasetsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        limit = params.limit,
+        period = %params.period,
+        "Querying popular datasets"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Run DB query in blocking task to avoid blocking async runtime
+    let req_id = request_id.0.clone();
+    let period = params.period.clone();
+    let limit = params.limit;
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+
+    let tenant = tenant_id.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        query_popular_datasets(&conn, &tenant, &period, limit, visibility)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        count = result.datasets.len(),
+        "Popular datasets query completed"
+    );
+
+    Ok(Json(result))
+}
+
+/// Get stale datasets (no recent access)
+pub(crate) async fn get_stale_datasets(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    identity: Option<Extension<dataset_acl::Identity>>,
+    Query(params): Query<StaleQueryParams>,
+) -> Result<Json<StaleDatasetsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let tenant_id = tenant_backend
+        .as_ref()
+        .map(|e| e.0.tenant_id())
+        .unwrap_or("default");
+    tracing::debug!(
+        tenant_id = %tenant_id,
+        threshold_days = params.threshold_days,
+        "Querying stale datasets"
+    );
+
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    // Run DB query in blocking task to avoid blocking async runtime
+    let req_id = request_id.0.clone();
+    let threshold_days = params.threshold_days;
+
+    // Hide datasets the caller cannot read under dataset ACLs
+    let identity = identity.map(|e| e.0).unwrap_or_default();
+    let visibility = dataset_acl::visibility_clause(&identity, "d.id", "d.domain");
+
+    let tenant = tenant_id.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        query_stale_datasets(&conn, &tenant, threshold_days, visibility)
+    })
+    .await
+    .map_err(|e| internal_error(format!("Task join error: {}", e), req_id.clone()))?
+    .map_err(|e| internal_error(e.to_string(), req_id))?;
+
+    tracing::info!(
+        count = result.datasets.len(),
+        "Stale datasets query completed"
+    );
+
+    Ok(Json(result))
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -663,7 +1388,6 @@ mod tests {
     fn test_usage_config_default() {
         let config = UsageConfig::default();
         assert_eq!(config.flush_interval_secs, DEFAULT_FLUSH_INTERVAL_SECS);
-        assert_eq!(config.max_unique_users, MAX_UNIQUE_USERS_PER_DAY);
     }
 
     #[test]
@@ -680,13 +1404,13 @@ mod tests {
 
         // Record some accesses
         tracker
-            .record_access(1, Some("alice"), AccessType::Read)
+            .record_access(DEFAULT_TENANT, 1, Some("alice"), AccessType::Read)
             .await;
         tracker
-            .record_access(1, Some("bob"), AccessType::Read)
+            .record_access(DEFAULT_TENANT, 1, Some("bob"), AccessType::Read)
             .await;
         tracker
-            .record_access(1, Some("alice"), AccessType::ApiCall)
+            .record_access(DEFAULT_TENANT, 1, Some("alice"), AccessType::ApiCall)
             .await;
 
         assert_eq!(tracker.tracked_dataset_count(), 1);
@@ -697,30 +1421,45 @@ mod tests {
         let tracker = UsageTracker::new_default();
 
         tracker
-            .record_access(1, Some("alice"), AccessType::Read)
+            .record_access(DEFAULT_TENANT, 1, Some("alice"), AccessType::Read)
             .await;
         tracker
-            .record_access(2, Some("bob"), AccessType::Read)
+            .record_access(DEFAULT_TENANT, 2, Some("bob"), AccessType::Read)
             .await;
         tracker
-            .record_access(3, None, AccessType::SearchAppearance)
+            .record_access(DEFAULT_TENANT, 3, None, AccessType::SearchAppearance)
             .await;
 
         // All should be same date, so 3 different keys
         assert_eq!(tracker.tracked_dataset_count(), 3);
     }
 
-    #[tokio::test]
-    async fn test_usage_tracker_search_appearances() {
+    #[test]
+    fn test_usage_tracker_search_appearances() {
         let tracker = UsageTracker::new_default();
 
-        tracker
-            .record_search_appearances(&[1, 2, 3, 4, 5], Some("searcher"))
-            .await;
+        tracker.record_search_appearances(DEFAULT_TENANT, &[1, 2, 3, 4, 5], Some("searcher"));
 
         assert_eq!(tracker.tracked_dataset_count(), 5);
     }
 
+    #[test]
+    fn test_search_appearances_reuse_buckets() {
+        let tracker = UsageTracker::new_default();
+        let results: Vec<i64> = (1..=100).collect();
+
+        tracker.record_search_appearances(DEFAULT_TENANT, &results, Some("alice"));
+        tracker.record_search_appearances(DEFAULT_TENANT, &results, Some("bob"));
+        tracker.record_search_appearances("acme", &results[..10], None);
+        assert_eq!(tracker.tracked_dataset_count(), 110);
+
+        let key = (DEFAULT_TENANT.to_string(), 42, today_string());
+        let counters = tracker.counters.get(&key).unwrap();
+        assert_eq!(counters.snapshot().search_appearances, 2);
+        assert_eq!(counters.snapshot().read_count, 0);
+        assert_eq!(counters.unique_users.take().estimate(), 2);
+    }
+
     #[tokio::test]
     async fn test_counter_snapshot() {
         let counters = UsageCounters::new();
@@ -753,16 +1492,15 @@ mod tests {
         assert_eq!(snapshot.read_count, 0);
     }
 
-    #[tokio::test]
-    async fn test_unique_user_tracking() {
+    #[test]
+    fn test_unique_user_tracking() {
         let counters = UsageCounters::new();
 
-        assert!(counters.add_user("alice").await);
-        assert!(counters.add_user("bob").await);
-        assert!(counters.add_user("alice").await); // Duplicate, still true
+        counters.add_user("alice");
+        counters.add_user("bob");
+        counters.add_user("alice"); // Duplicate, counted once
 
-        let users = counters.unique_users.read().await;
-        assert_eq!(users.len(), 2);
+        assert_eq!(counters.unique_users.take().estimate(), 2);
     }
 
     #[test]
@@ -803,17 +1541,17 @@ mod tests {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
             tracker
-                .record_access(dataset_id, Some("alice"), AccessType::Read)
+                .record_access(DEFAULT_TENANT, dataset_id, Some("alice"), AccessType::Read)
                 .await;
             tracker
-                .record_access(dataset_id, Some("bob"), AccessType::Read)
+                .record_access(DEFAULT_TENANT, dataset_id, Some("bob"), AccessType::Read)
                 .await;
             tracker
-                .record_access(dataset_id, None, AccessType::ApiCall)
+                .record_access(DEFAULT_TENANT, dataset_id, None, AccessType::ApiCall)
                 .await;
 
             // Flush to database
-            let count = tracker.flush(&conn).await.unwrap();
+            let count = tracker.flush(DEFAULT_TENANT, &conn).await.unwrap();
             assert_eq!(count, 1);
         });
 
@@ -831,6 +1569,211 @@ mod tests {
         assert_eq!(api_calls, 1);
     }
 
+    /// A migrated catalog with one dataset
+    fn usage_db() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('test_ds', '/test', 'delta', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn read_count(conn: &rusqlite::Connection, date: &str) -> Option<i64> {
+        conn.query_row(
+            "SELECT read_count FROM usage_stats WHERE dataset_id = 1 AND stat_date = ?1",
+            [date],
+            |row| row.get(0),
+        )
+        .ok()
+    }
+
+    #[tokio::test]
+    async fn test_rollover_keeps_unflushed_buckets() {
+        let tracker = UsageTracker::new_default();
+        let yesterday = "2025-11-19".to_string();
+        let today = "2025-11-20";
+        for _ in 0..3 {
+            tracker
+                .record_access_on(
+                    yesterday.clone(),
+                    DEFAULT_TENANT,
+                    1,
+                    Some("alice"),
+                    AccessType::Read,
+                )
+                .await;
+        }
+
+        // The first flush after midnight fails: yesterday's counts are kept
+        let broken = rusqlite::Connection::open_in_memory().unwrap();
+        assert!(tracker
+            .flush_as_of(DEFAULT_TENANT, &broken, today)
+            .await
+            .is_ok());
+        assert_eq!(tracker.tracked_dataset_count(), 1);
+
+        // Late arrivals for yesterday join the retained bucket
+        tracker
+            .record_access_on(yesterday.clone(), DEFAULT_TENANT, 1, None, AccessType::Read)
+            .await;
+
+        let conn = usage_db();
+        assert_eq!(
+            tracker
+                .flush_as_of(DEFAULT_TENANT, &conn, today)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(read_count(&conn, &yesterday), Some(4));
+        assert_eq!(tracker.tracked_dataset_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rollover_waits_for_in_flight_recorders() {
+        let tracker = UsageTracker::new_default();
+        let yesterday = "2025-11-19".to_string();
+        let today = "2025-11-20";
+        let conn = usage_db();
+        tracker
+            .record_access_on(yesterday.clone(), DEFAULT_TENANT, 1, None, AccessType::Read)
+            .await;
+
+        // A recorder holding yesterday's bucket across the flush
+        let held = tracker
+            .counters
+            .get(&(DEFAULT_TENANT.to_string(), 1, yesterday.clone()))
+            .unwrap()
+            .clone();
+        tracker
+            .flush_as_of(DEFAULT_TENANT, &conn, today)
+            .await
+            .unwrap();
+        assert_eq!(tracker.tracked_dataset_count(), 1);
+        held.increment(AccessType::Read);
+        drop(held);
+
+        tracker
+            .flush_as_of(DEFAULT_TENANT, &conn, today)
+            .await
+            .unwrap();
+        assert_eq!(read_count(&conn, &yesterday), Some(2));
+        assert_eq!(tracker.tracked_dataset_count(), 0);
+
+        // Today's bucket stays for reuse even when empty
+        tracker
+            .record_access_on(today.to_string(), DEFAULT_TENANT, 1, None, AccessType::Read)
+            .await;
+        tracker
+            .flush_as_of(DEFAULT_TENANT, &conn, today)
+            .await
+            .unwrap();
+        assert_eq!(read_count(&conn, today), Some(1));
+        assert_eq!(tracker.tracked_dataset_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_partitioned_by_tenant() {
+        let tracker = UsageTracker::new_default();
+        let today = today_string();
+        tracker
+            .record_access(DEFAULT_TENANT, 1, Some("alice"), AccessType::Read)
+            .await;
+        for _ in 0..3 {
+            tracker
+                .record_access("acme", 1, Some("bob"), AccessType::Read)
+                .await;
+        }
+        assert_eq!(tracker.tracked_tenants(), vec!["acme", DEFAULT_TENANT]);
+
+        // Each tenant's counters go only to its own catalog
+        let default_db = usage_db();
+        let acme_db = usage_db();
+        assert_eq!(tracker.flush(DEFAULT_TENANT, &default_db).await.unwrap(), 1);
+        assert_eq!(tracker.flush("acme", &acme_db).await.unwrap(), 1);
+        assert_eq!(read_count(&default_db, &today), Some(1));
+        assert_eq!(read_count(&acme_db, &today), Some(3));
+        let tenant_id: String = acme_db
+            .query_row("SELECT tenant_id FROM usage_stats", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tenant_id, "acme");
+
+        // Queries ignore other tenants' rows in a shared database
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        default_db
+            .execute(
+                "INSERT INTO usage_stats (dataset_id, stat_date, read_count, tenant_id)
+                 VALUES (1, ?1, 50, 'acme')",
+                [&yesterday],
+            )
+            .unwrap();
+        let usage = query_dataset_usage(&default_db, DEFAULT_TENANT, 1, "test_ds", "7d").unwrap();
+        assert_eq!(usage.total_reads, 1);
+        let popular = query_popular_datasets(&default_db, "acme", "7d", 10, None).unwrap();
+        assert_eq!(popular.datasets[0].total_reads, 50);
+        let totals = query_usage_totals(&default_db, "globex", "7d").unwrap();
+        assert_eq!(totals.total_reads, 0);
+        let stale = query_stale_datasets(&default_db, "globex", 30, None).unwrap();
+        assert_eq!(stale.datasets.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unique_users_merge_across_flushes_and_days() {
+        let tracker = UsageTracker::new_default();
+        let conn = usage_db();
+        let today = today_string();
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let record = |date: &str, range: std::ops::Range<u32>| {
+            let date = date.to_string();
+            let tracker = &tracker;
+            async move {
+                for i in range {
+                    let user = format!("user-{i}");
+                    tracker
+                        .record_access_on(
+                            date.clone(),
+                            DEFAULT_TENANT,
+                            1,
+                            Some(&user),
+                            AccessType::Read,
+                        )
+                        .await;
+                }
+            }
+        };
+
+        // More users than the old 10K cap, split over two flushes that overlap
+        record(&yesterday, 0..8_000).await;
+        tracker.flush(DEFAULT_TENANT, &conn).await.unwrap();
+        record(&yesterday, 4_000..12_000).await;
+        tracker.flush(DEFAULT_TENANT, &conn).await.unwrap();
+        // Today's users are mostly yesterday's
+        record(&today, 10_000..14_000).await;
+        tracker.flush(DEFAULT_TENANT, &conn).await.unwrap();
+
+        let within = |estimate: i64, expected: i64| {
+            (estimate - expected).abs() as f64 / (expected as f64) < 0.05
+        };
+        let usage = query_dataset_usage(&conn, DEFAULT_TENANT, 1, "test_ds", "7d").unwrap();
+        let daily: Vec<i64> = usage.daily_stats.iter().map(|s| s.unique_users).collect();
+        assert_eq!(daily.len(), 2);
+        assert!(within(daily[0], 4_000), "today {}", daily[0]);
+        assert!(within(daily[1], 12_000), "yesterday {}", daily[1]);
+        assert!(within(usage.total_unique_users, 14_000), "{usage:?}");
+
+        let popular = query_popular_datasets(&conn, DEFAULT_TENANT, "7d", 10, None).unwrap();
+        assert!(within(popular.datasets[0].unique_users, 14_000));
+    }
+
     #[test]
     fn test_query_dataset_usage() {
         let conn = rusqlite::Connection::open_in_memory().unwrap();
@@ -865,7 +1808,8 @@ mod tests {
         .unwrap();
 
         // Query usage
-        let result = query_dataset_usage(&conn, dataset_id, "analytics_ds", "7d").unwrap();
+        let result =
+            query_dataset_usage(&conn, DEFAULT_TENANT, dataset_id, "analytics_ds", "7d").unwrap();
 
         assert_eq!(result.dataset_id, dataset_id);
         assert_eq!(result.dataset_name, "analytics_ds");
@@ -928,7 +1872,7 @@ mod tests {
         .unwrap();
 
         // Query popular
-        let result = query_popular_datasets(&conn, "7d", 10).unwrap();
+        let result = query_popular_datasets(&conn, DEFAULT_TENANT, "7d", 10, None).unwrap();
 
         assert_eq!(result.datasets.len(), 2);
         assert_eq!(result.datasets[0].dataset_name, "popular");
@@ -936,6 +1880,48 @@ mod tests {
         assert_eq!(result.datasets[1].dataset_name, "less_popular");
     }
 
+    #[test]
+    fn test_query_usage_totals() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        // Initialize schema
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+
+        // Empty catalog has zero totals
+        let empty = query_usage_totals(&conn, DEFAULT_TENANT, "7d").unwrap();
+        assert_eq!(empty.total_reads, 0);
+        assert_eq!(empty.active_datasets, 0);
+
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('a', '/a', 'delta', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('b', '/b', 'delta', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        let today = today_string();
+        conn.execute(
+            "INSERT INTO usage_stats (dataset_id, stat_date, read_count, search_appearances, api_calls)
+             SELECT id, ?1, 10, 3, 20 FROM datasets",
+            rusqlite::params![today],
+        )
+        .unwrap();
+
+        let totals = query_usage_totals(&conn, DEFAULT_TENANT, "7d").unwrap();
+        assert_eq!(totals.period, "7d");
+        assert_eq!(totals.total_reads, 20);
+        assert_eq!(totals.total_search_appearances, 6);
+        assert_eq!(totals.total_api_calls, 40);
+        assert_eq!(totals.active_datasets, 2);
+    }
+
     #[test]
     fn test_query_stale_datasets() {
         let conn = rusqlite::Connection::open_in_memory().unwrap();
@@ -974,7 +1960,7 @@ mod tests {
         .unwrap();
 
         // Query stale (30 days threshold)
-        let result = query_stale_datasets(&conn, 30).unwrap();
+        let result = query_stale_datasets(&conn, DEFAULT_TENANT, 30, None).unwrap();
 
         // Only 'never_accessed' should be stale
         assert_eq!(result.datasets.len(), 1);
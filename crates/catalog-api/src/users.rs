@@ -0,0 +1,629 @@
+//! User Directory
+//!
+//! Dataset owners, classification verifiers and audit actors are recorded as
+//! plain user ids. A [`UserDirectory`] knows who those ids belong to; the
+//! configured directories are combined by a [`UserResolver`], which is used
+//! to:
+//!
+//! - validate the `owner` of dataset writes, the `owner_id` of new owners, and
+//!   the verifier of manual classifications
+//! - enrich responses with display names and teams: `owner_profile` on
+//!   `GET /api/v1/datasets/{name}`, `verified_by_name` on classifications, and
+//!   `actor_name` / `actor_teams` on audit entries
+//! - serve `GET /api/v1/users` and `GET /api/v1/users/{id}`
+//!
+//! # Directories
+//!
+//! - `static`: users from a JSON file
+//! - `scim`: users provisioned by an identity provider through the SCIM
+//!   endpoint (see `scim`)
+//! - `table`: rows of the `directory_users` table (migration v1.45.0) from
+//!   every source, e.g. written by a custom sync job
+//! - `ldap`: users of an LDAP server or Active Directory (see `ldap`; requires
+//!   the `ldap-directory` feature)
+//!
+//! `scim` and `table` users are reloaded from the default catalog
+//! periodically, and their teams are their `group_members` rows (see
+//! `groups`). `ldap` users are reloaded from the server at the same interval,
+//! and their teams are their `memberOf` groups. When directories know the same user, the first configured one
+//! wins.
+//!
+//! # Validation
+//!
+//! - `off`: ids are not checked
+//! - `warn`: unknown or deactivated ids are logged (default)
+//! - `strict`: unknown or deactivated ids are rejected with `400 Bad Request`
+//!
+//! An owner id is known if it is a directory user or one of their teams.
+//! Without configured directories nothing is validated.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_USER_DIRECTORIES`: comma-separated directories (default: none)
+//! - `METAFUSE_USER_DIRECTORY_FILE`: JSON file for the `static` directory, an
+//!   array of `{"id", "display_name", "email", "teams", "active"}` objects
+//! - `METAFUSE_USER_VALIDATION`: `off`, `warn`, or `strict` (default: `warn`)
+//! - `METAFUSE_USER_SYNC_INTERVAL_SECS`: seconds between reloads of `scim`,
+//!   `table` and `ldap` users (default: 300)
+//! - `METAFUSE_LDAP_*`: connection settings of the `ldap` directory (see `ldap`)
+
+use crate::envelope;
+use crate::server::{not_found, AppState, ErrorResponse, RequestId};
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Default seconds between reloads of `scim`, `table` and `ldap` users
+pub const DEFAULT_SYNC_INTERVAL_SECS: u64 = 300;
+
+/// A user known to a directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryUser {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub teams: Vec<String>,
+    /// False once the user is deprovisioned
+    #[serde(default = "default_active")]
+    pub active: bool,
+    /// Directory the user comes from
+    #[serde(default)]
+    pub source: String,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Source of user profiles.
+pub trait UserDirectory: Send + Sync {
+    /// Directory name, as configured in `METAFUSE_USER_DIRECTORIES`
+    fn name(&self) -> &'static str;
+
+    /// Look up a user by id.
+    fn user(&self, id: &str) -> Option<DirectoryUser>;
+
+    /// All users of the directory.
+    fn users(&self) -> Vec<DirectoryUser>;
+}
+
+/// Users from a JSON file.
+#[derive(Debug, Clone, Default)]
+pub struct StaticUsers {
+    users: HashMap<String, DirectoryUser>,
+}
+
+impl StaticUsers {
+    /// Parse a JSON array of users.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let list: Vec<DirectoryUser> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid user directory: {}", e))?;
+        let mut users = HashMap::new();
+        for mut user in list {
+            if user.id.trim().is_empty() {
+                return Err("Invalid user directory: user with an empty id".to_string());
+            }
+            user.source = "static".to_string();
+            users.insert(user.id.clone(), user);
+        }
+        Ok(Self { users })
+    }
+
+    /// Load users from a JSON file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read user directory '{}': {}", path, e))?;
+        Self::parse(&json)
+    }
+}
+
+impl UserDirectory for StaticUsers {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    fn user(&self, id: &str) -> Option<DirectoryUser> {
+        self.users.get(id).cloned()
+    }
+
+    fn users(&self) -> Vec<DirectoryUser> {
+        self.users.values().cloned().collect()
+    }
+}
+
+/// Users from the `directory_users` table.
+///
+/// The `table` directory reads rows from every `source`, a user written by
+/// several being taken from the first source by name; the `scim` directory
+/// reads the rows of the SCIM endpoint. Lookups read an in-memory copy,
+/// refreshed by [`user_sync_task`].
+#[derive(Debug, Clone)]
+pub struct TableUsers {
+    name: &'static str,
+    source: Option<&'static str>,
+    users: Arc<RwLock<HashMap<String, DirectoryUser>>>,
+}
+
+/// The `table` directory.
+impl Default for TableUsers {
+    fn default() -> Self {
+        Self {
+            name: "table",
+            source: None,
+            users: Arc::default(),
+        }
+    }
+}
+
+impl TableUsers {
+    /// The `scim` directory, reading users provisioned through SCIM.
+    pub fn scim() -> Self {
+        Self {
+            name: "scim",
+            source: Some(crate::scim::SCIM_SOURCE),
+            ..Self::default()
+        }
+    }
+
+    /// Reload users from a catalog. Returns how many were loaded.
+    ///
+    /// Catalogs without the `directory_users` table have no users.
+    pub fn refresh(&self, conn: &Connection) -> rusqlite::Result<usize> {
+        let mut users = HashMap::new();
+        let has_table = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'directory_users'",
+                [],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if has_table {
+            let mut stmt = conn.prepare(
+                "SELECT user_id, display_name, email, active FROM directory_users
+                 WHERE ?1 IS NULL OR source = ?1
+                 ORDER BY source",
+            )?;
+            let rows = stmt.query_map([self.source], |row| {
+                Ok(DirectoryUser {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    email: row.get(2)?,
+                    teams: Vec::new(),
+                    active: row.get::<_, i64>(3)? != 0,
+                    source: self.name.to_string(),
+                })
+            })?;
+            for row in rows {
+                let user = row?;
+                users.entry(user.id.clone()).or_insert(user);
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT m.user_id, m.group_id FROM group_members m
+                 JOIN directory_users u ON u.user_id = m.user_id
+                 WHERE ?1 IS NULL OR u.source = ?1
+                 ORDER BY m.group_id",
+            )?;
+            let rows = stmt.query_map([self.source], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (user, team) = row?;
+                if let Some(user) = users.get_mut(&user) {
+                    user.teams.push(team);
+                }
+            }
+        }
+        let count = users.len();
+        *self.users.write().unwrap_or_else(|e| e.into_inner()) = users;
+        Ok(count)
+    }
+}
+
+impl UserDirectory for TableUsers {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn user(&self, id: &str) -> Option<DirectoryUser> {
+        self.users
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+            .cloned()
+    }
+
+    fn users(&self) -> Vec<DirectoryUser> {
+        self.users
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// How user ids are checked against the directories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    Off,
+    #[default]
+    Warn,
+    Strict,
+}
+
+impl ValidationMode {
+    /// Parse a validation mode name.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+/// The configured user directories, combined.
+#[derive(Clone)]
+pub struct UserResolver {
+    directories: Vec<Arc<dyn UserDirectory>>,
+    tables: Vec<TableUsers>,
+    #[cfg(feature = "ldap-directory")]
+    ldap: Vec<crate::ldap::LdapUsers>,
+    pub validation: ValidationMode,
+    /// Seconds between reloads of `scim`, `table` and `ldap` users
+    pub sync_interval_secs: u64,
+}
+
+impl std::fmt::Debug for UserResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserResolver")
+            .field("directories", &self.directory_names())
+            .field("validation", &self.validation)
+            .field("sync_interval_secs", &self.sync_interval_secs)
+            .finish()
+    }
+}
+
+/// No directories, so nothing is validated or enriched.
+impl Default for UserResolver {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl UserResolver {
+    /// Combine directories; [`TableUsers`] among them are not refreshed.
+    pub fn new(directories: Vec<Arc<dyn UserDirectory>>) -> Self {
+        Self {
+            directories,
+            tables: Vec::new(),
+            #[cfg(feature = "ldap-directory")]
+            ldap: Vec::new(),
+            validation: ValidationMode::default(),
+            sync_interval_secs: DEFAULT_SYNC_INTERVAL_SECS,
+        }
+    }
+
+    /// Create the resolver from environment variables.
+    ///
+    /// Fails if `ldap` is configured without its settings or without the
+    /// `ldap-directory` feature. Unknown directory names, an unreadable static
+    /// file, and an unknown validation mode are logged and ignored.
+    pub fn from_env() -> Result<Self, String> {
+        let names = std::env::var("METAFUSE_USER_DIRECTORIES").unwrap_or_default();
+        let mut resolver = Self::new(Vec::new());
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name {
+                "static" => {
+                    let path = std::env::var("METAFUSE_USER_DIRECTORY_FILE").unwrap_or_default();
+                    match StaticUsers::load(&path) {
+                        Ok(users) => resolver.directories.push(Arc::new(users)),
+                        Err(e) => warn!(error = %e, "Ignoring static user directory"),
+                    }
+                }
+                "scim" | "table" if !resolver.directory_names().contains(&name) => {
+                    let table = if name == "scim" {
+                        TableUsers::scim()
+                    } else {
+                        TableUsers::default()
+                    };
+                    resolver.directories.push(Arc::new(table.clone()));
+                    resolver.tables.push(table);
+                }
+                "scim" | "table" => {}
+                #[cfg(feature = "ldap-directory")]
+                "ldap" if !resolver.directory_names().contains(&name) => {
+                    let ldap = crate::ldap::LdapUsers::new(crate::ldap::LdapConfig::from_env()?);
+                    resolver.directories.push(Arc::new(ldap.clone()));
+                    resolver.ldap.push(ldap);
+                }
+                #[cfg(feature = "ldap-directory")]
+                "ldap" => {}
+                #[cfg(not(feature = "ldap-directory"))]
+                "ldap" => {
+                    return Err(
+                        "METAFUSE_USER_DIRECTORIES: the ldap directory requires the \
+                         'ldap-directory' feature"
+                            .to_string(),
+                    )
+                }
+                other => warn!(directory = %other, "Ignoring unknown user directory"),
+            }
+        }
+        if let Ok(value) = std::env::var("METAFUSE_USER_VALIDATION") {
+            match ValidationMode::parse(&value) {
+                Some(mode) => resolver.validation = mode,
+                None => warn!(value = %value, "Ignoring unknown METAFUSE_USER_VALIDATION"),
+            }
+        }
+        resolver.sync_interval_secs = std::env::var("METAFUSE_USER_SYNC_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_SYNC_INTERVAL_SECS);
+        Ok(resolver)
+    }
+
+    /// Names of the configured directories, in order.
+    pub fn directory_names(&self) -> Vec<&'static str> {
+        self.directories.iter().map(|d| d.name()).collect()
+    }
+
+    /// `scim` and `table` directories to keep refreshed.
+    pub fn tables(&self) -> &[TableUsers] {
+        &self.tables
+    }
+
+    /// `ldap` directories to keep refreshed.
+    #[cfg(feature = "ldap-directory")]
+    pub fn ldap(&self) -> &[crate::ldap::LdapUsers] {
+        &self.ldap
+    }
+
+    /// Look up a user in the directories, in order.
+    pub fn user(&self, id: &str) -> Option<DirectoryUser> {
+        self.directories.iter().find_map(|d| d.user(id))
+    }
+
+    /// Display name of a user, if a directory knows it.
+    pub fn display_name(&self, id: &str) -> Option<String> {
+        self.user(id).and_then(|u| u.display_name)
+    }
+
+    /// All known users, sorted by id.
+    pub fn users(&self) -> Vec<DirectoryUser> {
+        let mut users = BTreeMap::new();
+        for directory in &self.directories {
+            for user in directory.users() {
+                users.entry(user.id.clone()).or_insert(user);
+            }
+        }
+        users.into_values().collect()
+    }
+
+    /// Whether `id` is a team of any known user.
+    pub fn is_team(&self, id: &str) -> bool {
+        self.directories
+            .iter()
+            .flat_map(|d| d.users())
+            .any(|u| u.teams.iter().any(|t| t == id))
+    }
+
+    /// Check that `id` is an active directory user.
+    pub fn check_user(&self, id: &str) -> Result<(), String> {
+        let problem = match self.user(id) {
+            Some(user) if user.active => None,
+            Some(_) => Some(format!("User '{}' is deactivated in the directory", id)),
+            None => Some(format!("Unknown user '{}': not in the user directory", id)),
+        };
+        self.enforce(problem)
+    }
+
+    /// Check that an owner id is an active directory user or a team.
+    pub fn check_owner(&self, id: &str) -> Result<(), String> {
+        let problem = match self.user(id) {
+            Some(user) if user.active => None,
+            Some(_) => Some(format!("Owner '{}' is deactivated in the directory", id)),
+            None if self.is_team(id) => None,
+            None => Some(format!(
+                "Unknown owner '{}': not a user or team in the user directory",
+                id
+            )),
+        };
+        self.enforce(problem)
+    }
+
+    fn enforce(&self, problem: Option<String>) -> Result<(), String> {
+        let Some(problem) = problem else {
+            return Ok(());
+        };
+        if self.directories.is_empty() {
+            return Ok(());
+        }
+        match self.validation {
+            ValidationMode::Off => Ok(()),
+            ValidationMode::Warn => {
+                warn!(problem = %problem, "User directory validation failed");
+                Ok(())
+            }
+            ValidationMode::Strict => Err(problem),
+        }
+    }
+
+    /// Set `actor_name` and `actor_teams` on entries whose actor is a known
+    /// user.
+    #[cfg(feature = "audit")]
+    pub fn annotate_audit_entries(&self, entries: &mut [crate::audit::AuditLogEntry]) {
+        if self.directories.is_empty() {
+            return;
+        }
+        for entry in entries {
+            if let Some(user) = entry.actor.as_deref().and_then(|a| self.user(a)) {
+                entry.actor_name = user.display_name;
+                entry.actor_teams = Some(user.teams);
+            }
+        }
+    }
+}
+
+/// Background task that reloads `scim` and `table` users periodically
+pub async fn user_sync_task(
+    tables: Vec<TableUsers>,
+    interval_secs: u64,
+    backend: Arc<metafuse_catalog_storage::DynCatalogBackend>,
+) {
+    let interval = Duration::from_secs(interval_secs);
+
+    info!(interval_secs, "User directory sync task started");
+
+    loop {
+        match backend.get_connection().await {
+            Ok(conn) => {
+                for table in &tables {
+                    match table.refresh(&conn) {
+                        Ok(count) => {
+                            tracing::debug!(
+                                directory = table.name,
+                                users = count,
+                                "Reloaded directory users"
+                            );
+                        }
+                        Err(e) => {
+                            error!(directory = table.name, error = %e, "Failed to reload directory users");
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to get connection for user directory sync");
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// List the users known to the configured user directories
+pub(crate) async fn list_users(
+    State(state): State<AppState>,
+    envelope: envelope::EnvelopeQuery,
+) -> Json<envelope::Collection<DirectoryUser>> {
+    let users = state.users.users();
+    tracing::debug!(count = users.len(), "Listing directory users");
+    Json(envelope.page(users))
+}
+
+/// Get a user from the configured user directories
+pub(crate) async fn get_user(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Path(id): Path<String>,
+) -> Result<Json<DirectoryUser>, (StatusCode, Json<ErrorResponse>)> {
+    state.users.user(&id).map(Json).ok_or_else(|| {
+        not_found(
+            format!("User '{}' not found in the user directory", id),
+            request_id.0,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_users() {
+        let users = StaticUsers::parse(
+            r#"[{"id": "ana", "display_name": "Ana Lima", "teams": ["finance"]},
+                {"id": "bo", "active": false}]"#,
+        )
+        .unwrap();
+        let ana = users.user("ana").unwrap();
+        assert_eq!(ana.display_name.as_deref(), Some("Ana Lima"));
+        assert_eq!(ana.source, "static");
+        assert!(ana.active);
+        assert!(!users.user("bo").unwrap().active);
+        assert!(StaticUsers::parse(r#"[{"id": ""}]"#).is_err());
+        assert!(StaticUsers::parse("{").is_err());
+    }
+
+    #[test]
+    fn test_table_users_and_validation() {
+        let conn = Connection::open_in_memory().unwrap();
+        let table = TableUsers::default();
+        // Catalogs without the table have no users
+        assert_eq!(table.refresh(&conn).unwrap(), 0);
+
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO directory_users (source, user_id, display_name, active) VALUES
+                ('hr_sync', 'ana', 'Ana Lima', 1), ('hr_sync', 'cy', 'Cy Young', 0),
+                ('ad_sync', 'bo', 'Bo Diddley', 1), ('hr_sync', 'bo', 'Robert', 1);
+             INSERT INTO group_members (group_id, user_id) VALUES
+                ('platform', 'ana'), ('finance', 'ana');",
+        )
+        .unwrap();
+        // Users from every source, the first source by name winning
+        assert_eq!(table.refresh(&conn).unwrap(), 3);
+        assert_eq!(
+            table.user("ana").unwrap().teams,
+            vec!["finance", "platform"]
+        );
+        assert_eq!(
+            table.user("bo").unwrap().display_name.as_deref(),
+            Some("Bo Diddley")
+        );
+
+        // The scim directory only reads SCIM rows
+        let scim = TableUsers::scim();
+        conn.execute(
+            "INSERT INTO directory_users (source, user_id, display_name) VALUES ('scim', 'ana', 'Ana S.')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(scim.refresh(&conn).unwrap(), 1);
+        let ana = scim.user("ana").unwrap();
+        assert_eq!(ana.display_name.as_deref(), Some("Ana S."));
+        assert_eq!(ana.source, "scim");
+        assert_eq!(ana.teams, vec!["finance", "platform"]);
+        assert!(scim.user("bo").is_none());
+
+        let mut resolver = UserResolver::new(vec![
+            Arc::new(StaticUsers::parse(r#"[{"id": "ana", "display_name": "Ana"}]"#).unwrap()),
+            Arc::new(table),
+        ]);
+        // The first directory wins
+        assert_eq!(resolver.display_name("ana").as_deref(), Some("Ana"));
+        assert_eq!(resolver.users().len(), 3);
+
+        // Warn mode logs but accepts
+        assert!(resolver.check_user("zed").is_ok());
+
+        resolver.validation = ValidationMode::Strict;
+        assert!(resolver.check_user("ana").is_ok());
+        assert!(resolver.check_user("zed").is_err());
+        assert!(resolver.check_user("cy").is_err());
+        assert!(resolver.check_owner("finance").is_ok());
+        assert!(resolver.check_owner("sales").is_err());
+
+        // Without directories nothing is validated
+        let resolver = UserResolver {
+            validation: ValidationMode::Strict,
+            ..UserResolver::default()
+        };
+        assert!(resolver.check_owner("anyone").is_ok());
+    }
+}
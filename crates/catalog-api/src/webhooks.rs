@@ -0,0 +1,1615 @@
+//! Webhooks
+//!
+//! Lets tenants register HTTP endpoints that are notified when the catalog
+//! changes, e.g. to ping Slack or start automation when a schema changes.
+//!
+//! # Events
+//!
+//! - `dataset.created`, `dataset.updated`, `dataset.deleted`: dataset writes through the API
+//! - `classification.changed`: a field's classification was set
+//! - `quality.dropped`: a dataset's overall quality score dropped by at least the configured amount
+//! - `export.failed`: a scheduled catalog export failed (see [`crate::export_schedules`])
+//!
+//! # Delivery
+//!
+//! Each event queues one delivery per enabled webhook of the tenant that
+//! subscribes to it (migration v1.39.0). With the `alerting` feature,
+//! [`webhook_delivery_task`] POSTs the delivery as JSON:
+//!
+//! ```json
+//! {
+//!   "event": "dataset.updated",
+//!   "tenant_id": "acme",
+//!   "occurred_at": "2026-01-15T09:30:00Z",
+//!   "data": { "name": "orders", "operation": "update", ... }
+//! }
+//! ```
+//!
+//! The request carries `X-MetaFuse-Event`, `X-MetaFuse-Delivery` (the delivery
+//! id), and `X-MetaFuse-Signature: sha256=<hex>`, the HMAC-SHA256 of the body
+//! keyed by the webhook's secret. A delivery that doesn't get a `2xx` is
+//! retried with exponential backoff until it runs out of attempts. Redirects
+//! are not followed. Without the feature, no deliveries are queued.
+//!
+//! # URL Policy
+//!
+//! Webhook URLs must be `https` and their host must resolve only to public
+//! addresses, so tenants can't make the server call loopback, private, or
+//! link-local targets such as cloud metadata endpoints. The host is resolved
+//! when the webhook is registered and again before each delivery, which then
+//! connects to the checked addresses. Operators can allow `http` and list
+//! internal hosts that may resolve to private addresses.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_WEBHOOK_MAX_ATTEMPTS`: attempts per delivery (default: 5)
+//! - `METAFUSE_WEBHOOK_RETRY_BACKOFF_SECS`: delay before the first retry, doubled for each further retry (default: 30)
+//! - `METAFUSE_WEBHOOK_QUALITY_DROP`: score drop that fires `quality.dropped`, 0.0-1.0 (default: 0.1)
+//! - `METAFUSE_WEBHOOK_ALLOW_HTTP`: also allow `http` URLs (default: false)
+//! - `METAFUSE_WEBHOOK_ALLOWED_HOSTS`: comma-separated hosts exempt from the public address check (default: none)
+//!
+//! # Endpoints
+//!
+//! - `GET /api/v1/webhooks` - The tenant's webhooks
+//! - `POST /api/v1/webhooks` - Register a webhook
+//! - `GET /api/v1/webhooks/{id}` - A webhook
+//! - `PUT /api/v1/webhooks/{id}` - Change a webhook's URL, events, secret, or enabled state
+//! - `DELETE /api/v1/webhooks/{id}` - Remove a webhook and its deliveries
+//! - `GET /api/v1/webhooks/{id}/deliveries` - Recent deliveries, newest first
+
+#[cfg(feature = "audit")]
+use crate::audit;
+use crate::dataset_writes::webhook_tenant;
+use crate::envelope;
+#[cfg(feature = "api-keys")]
+use crate::multi_tenant::require_admin_permission;
+use crate::multi_tenant::{resolve_backend, TenantBackend};
+#[cfg(feature = "api-keys")]
+use crate::server::rbac_error;
+use crate::server::{
+    bad_request, internal_error, not_found, AppState, AuditContext, ErrorResponse, RequestId,
+};
+use crate::strict_json::JsonBody;
+#[cfg(feature = "api-keys")]
+use crate::tenant_resolver::ResolvedTenant;
+use axum::extract::{Extension, Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use metafuse_catalog_core::hooks::WriteOperation;
+use metafuse_catalog_storage::DynCatalogBackend;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Tenant of requests without a resolved tenant
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Default attempts per delivery
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+/// Default delay before the first retry
+pub const DEFAULT_RETRY_BACKOFF_SECS: u64 = 30;
+
+/// Default quality score drop that fires `quality.dropped`
+pub const DEFAULT_QUALITY_DROP: f64 = 0.1;
+
+/// Days finished deliveries are kept
+const DELIVERY_RETENTION_DAYS: u32 = 30;
+
+/// Tenants with queued deliveries the worker hasn't been told about yet
+const QUEUE_SIZE: usize = 1000;
+
+/// Event a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "dataset.created")]
+    DatasetCreated,
+    #[serde(rename = "dataset.updated")]
+    DatasetUpdated,
+    #[serde(rename = "dataset.deleted")]
+    DatasetDeleted,
+    #[serde(rename = "classification.changed")]
+    ClassificationChanged,
+    #[serde(rename = "quality.dropped")]
+    QualityDropped,
+    #[serde(rename = "export.failed")]
+    ExportFailed,
+}
+
+impl WebhookEvent {
+    /// All events, the default for a new webhook
+    pub const ALL: [WebhookEvent; 6] = [
+        WebhookEvent::DatasetCreated,
+        WebhookEvent::DatasetUpdated,
+        WebhookEvent::DatasetDeleted,
+        WebhookEvent::ClassificationChanged,
+        WebhookEvent::QualityDropped,
+        WebhookEvent::ExportFailed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::DatasetCreated => "dataset.created",
+            WebhookEvent::DatasetUpdated => "dataset.updated",
+            WebhookEvent::DatasetDeleted => "dataset.deleted",
+            WebhookEvent::ClassificationChanged => "classification.changed",
+            WebhookEvent::QualityDropped => "quality.dropped",
+            WebhookEvent::ExportFailed => "export.failed",
+        }
+    }
+
+    /// Event for a dataset write
+    pub fn for_write(operation: WriteOperation) -> Self {
+        match operation {
+            WriteOperation::Create => WebhookEvent::DatasetCreated,
+            WriteOperation::Update => WebhookEvent::DatasetUpdated,
+            WriteOperation::Delete => WebhookEvent::DatasetDeleted,
+        }
+    }
+}
+
+/// Webhook delivery configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookConfig {
+    pub max_attempts: u32,
+    pub retry_backoff_secs: u64,
+    pub quality_drop: f64,
+    pub url_policy: UrlPolicy,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_backoff_secs: DEFAULT_RETRY_BACKOFF_SECS,
+            quality_drop: DEFAULT_QUALITY_DROP,
+            url_policy: UrlPolicy::default(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_attempts: std::env::var("METAFUSE_WEBHOOK_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|attempts| *attempts > 0)
+                .unwrap_or(defaults.max_attempts),
+            retry_backoff_secs: std::env::var("METAFUSE_WEBHOOK_RETRY_BACKOFF_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.retry_backoff_secs),
+            quality_drop: std::env::var("METAFUSE_WEBHOOK_QUALITY_DROP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|drop: &f64| (0.0..=1.0).contains(drop))
+                .unwrap_or(defaults.quality_drop),
+            url_policy: UrlPolicy::from_env(),
+        }
+    }
+
+    /// Seconds to wait before the next attempt after `attempts` failures
+    pub fn retry_delay_secs(&self, attempts: u32) -> u64 {
+        self.retry_backoff_secs
+            .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+    }
+}
+
+/// A registered webhook. The secret is never returned after creation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub tenant_id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    pub created_at: String,
+}
+
+/// Changes to a webhook; unset fields are kept
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WebhookUpdate {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub secret: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Outcome of a delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    fn parse(s: &str) -> Self {
+        match s {
+            "delivered" => DeliveryStatus::Delivered,
+            "failed" => DeliveryStatus::Failed,
+            _ => DeliveryStatus::Pending,
+        }
+    }
+}
+
+/// An event sent, or to be sent, to a webhook
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<String>,
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// A delivery due for an attempt, with what's needed to send it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueDelivery {
+    pub id: i64,
+    pub event: String,
+    pub payload: String,
+    pub url: String,
+    pub secret: String,
+    pub attempts: u32,
+}
+
+/// Which webhook URLs may be registered and called
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UrlPolicy {
+    /// Also allow `http` URLs
+    pub allow_http: bool,
+    /// Hosts (lowercase) that may resolve to non-public addresses
+    pub allowed_hosts: Vec<String>,
+}
+
+impl UrlPolicy {
+    /// Create the policy from environment variables.
+    pub fn from_env() -> Self {
+        Self {
+            allow_http: std::env::var("METAFUSE_WEBHOOK_ALLOW_HTTP")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            allowed_hosts: std::env::var("METAFUSE_WEBHOOK_ALLOWED_HOSTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|h| h.trim().to_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Check a URL's scheme and host without resolving it.
+    pub fn validate_url(&self, url: &str) -> Result<url::Url, String> {
+        let parsed =
+            url::Url::parse(url).map_err(|e| format!("Invalid webhook URL '{}': {}", url, e))?;
+        match parsed.scheme() {
+            "https" => {}
+            "http" if self.allow_http => {}
+            _ if self.allow_http => {
+                return Err(format!("Invalid webhook URL '{}': expected http(s)", url))
+            }
+            _ => return Err(format!("Invalid webhook URL '{}': expected https", url)),
+        }
+        if parsed.host().is_none() {
+            return Err(format!("Invalid webhook URL '{}': missing host", url));
+        }
+        Ok(parsed)
+    }
+
+    /// Resolve a URL's host, checking that every address is public unless
+    /// the host is allowed. Returns the host and the addresses to connect to.
+    pub async fn resolve(&self, url: &str) -> Result<(String, Vec<SocketAddr>), String> {
+        let parsed = self.validate_url(url)?;
+        let port = parsed.port_or_known_default().unwrap_or(443);
+        let (host, addrs): (String, Vec<SocketAddr>) = match parsed.host() {
+            Some(url::Host::Domain(domain)) => {
+                let addrs = tokio::net::lookup_host((domain, port))
+                    .await
+                    .map_err(|e| format!("Cannot resolve webhook host '{}': {}", domain, e))?
+                    .collect();
+                (domain.to_lowercase(), addrs)
+            }
+            Some(url::Host::Ipv4(ip)) => (ip.to_string(), vec![SocketAddr::new(ip.into(), port)]),
+            Some(url::Host::Ipv6(ip)) => (ip.to_string(), vec![SocketAddr::new(ip.into(), port)]),
+            None => return Err(format!("Invalid webhook URL '{}': missing host", url)),
+        };
+        if addrs.is_empty() {
+            return Err(format!("Cannot resolve webhook host '{}'", host));
+        }
+        if !self.allowed_hosts.contains(&host) {
+            if let Some(addr) = addrs.iter().find(|a| !is_public_address(a.ip())) {
+                return Err(format!(
+                    "Webhook host '{}' resolves to non-public address {}",
+                    host,
+                    addr.ip()
+                ));
+            }
+        }
+        Ok((host, addrs))
+    }
+}
+
+/// Whether an address is publicly routable: not loopback, private,
+/// link-local, shared (CGNAT), unspecified, broadcast, multicast,
+/// documentation, benchmarking, or reserved.
+///
+/// IPv6 addresses embedding an IPv4 address (mapped, IPv4-compatible, NAT64
+/// and 6to4) are judged by the embedded address.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)
+                || (a == 198 && (b & 0xfe) == 18)
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => match embedded_ipv4(ip) {
+            Some(v4) => is_public_address(v4.into()),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// The IPv4 address carried by an IPv4-mapped (`::ffff:0:0/96`),
+/// IPv4-compatible (`::/96`), NAT64 (`64:ff9b::/96`) or 6to4 (`2002::/16`)
+/// address
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = ip.segments();
+    let octets = ip.octets();
+    let low = Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15]);
+    match segments {
+        [0, 0, 0, 0, 0, 0xffff, _, _] | [0, 0, 0, 0, 0, 0, _, _] => Some(low),
+        [0x64, 0xff9b, 0, 0, 0, 0, _, _] => Some(low),
+        [0x2002, ..] => Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5])),
+        _ => None,
+    }
+}
+
+/// Random signing secret for webhooks registered without one.
+pub fn generate_secret() -> String {
+    format!("whsec_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Whether the catalog has the webhook tables.
+pub fn has_webhooks_table(conn: &Connection) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'webhooks'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+const WEBHOOK_SELECT: &str =
+    "SELECT id, tenant_id, url, events, enabled, created_by, created_at FROM webhooks";
+
+fn webhook_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Webhook> {
+    let events: String = row.get(3)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        tenant_id: row.get(1)?,
+        url: row.get(2)?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        enabled: row.get(4)?,
+        created_by: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn events_json(events: &[WebhookEvent]) -> String {
+    let mut events = events.to_vec();
+    events.sort();
+    events.dedup();
+    serde_json::to_string(&events).unwrap_or_default()
+}
+
+/// Register a webhook for a tenant.
+pub fn create_webhook(
+    conn: &Connection,
+    tenant_id: &str,
+    url: &str,
+    secret: &str,
+    events: &[WebhookEvent],
+    created_by: Option<&str>,
+) -> rusqlite::Result<Webhook> {
+    conn.execute(
+        "INSERT INTO webhooks (tenant_id, url, secret, events, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![tenant_id, url, secret, events_json(events), created_by],
+    )?;
+    conn.query_row(
+        &format!("{} WHERE id = ?1", WEBHOOK_SELECT),
+        [conn.last_insert_rowid()],
+        webhook_from_row,
+    )
+}
+
+/// A tenant's webhooks, oldest first.
+pub fn list_webhooks(conn: &Connection, tenant_id: &str) -> rusqlite::Result<Vec<Webhook>> {
+    if !has_webhooks_table(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE tenant_id = ?1 ORDER BY id",
+        WEBHOOK_SELECT
+    ))?;
+    let webhooks = stmt.query_map([tenant_id], webhook_from_row)?.collect();
+    webhooks
+}
+
+/// A tenant's webhook by id.
+pub fn get_webhook(
+    conn: &Connection,
+    tenant_id: &str,
+    id: i64,
+) -> rusqlite::Result<Option<Webhook>> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1 AND tenant_id = ?2", WEBHOOK_SELECT),
+        params![id, tenant_id],
+        webhook_from_row,
+    )
+    .optional()
+}
+
+/// Apply changes to a tenant's webhook. Returns `None` if it doesn't exist.
+pub fn update_webhook(
+    conn: &Connection,
+    tenant_id: &str,
+    id: i64,
+    update: &WebhookUpdate,
+) -> rusqlite::Result<Option<Webhook>> {
+    conn.execute(
+        "UPDATE webhooks SET
+             url = COALESCE(?3, url),
+             events = COALESCE(?4, events),
+             secret = COALESCE(?5, secret),
+             enabled = COALESCE(?6, enabled)
+         WHERE id = ?1 AND tenant_id = ?2",
+        params![
+            id,
+            tenant_id,
+            update.url,
+            update.events.as_deref().map(events_json),
+            update.secret,
+            update.enabled
+        ],
+    )?;
+    get_webhook(conn, tenant_id, id)
+}
+
+/// Remove a tenant's webhook and its deliveries. Returns whether it existed.
+pub fn delete_webhook(conn: &Connection, tenant_id: &str, id: i64) -> rusqlite::Result<bool> {
+    let tx = conn.unchecked_transaction()?;
+    let removed = tx.execute(
+        "DELETE FROM webhooks WHERE id = ?1 AND tenant_id = ?2",
+        params![id, tenant_id],
+    )?;
+    if removed > 0 {
+        tx.execute("DELETE FROM webhook_deliveries WHERE webhook_id = ?1", [id])?;
+    }
+    tx.commit()?;
+    Ok(removed > 0)
+}
+
+/// Queue a delivery of an event to each of the tenant's enabled webhooks
+/// subscribed to it. Returns the number queued.
+pub fn enqueue(
+    conn: &Connection,
+    tenant_id: &str,
+    event: WebhookEvent,
+    data: &serde_json::Value,
+) -> rusqlite::Result<usize> {
+    if !has_webhooks_table(conn)? {
+        return Ok(0);
+    }
+    let payload = serde_json::json!({
+        "event": event,
+        "tenant_id": tenant_id,
+        "occurred_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        "data": data,
+    });
+    let queued = conn.execute(
+        "INSERT INTO webhook_deliveries (webhook_id, event, payload)
+         SELECT w.id, ?2, ?3 FROM webhooks w
+         WHERE w.tenant_id = ?1 AND w.enabled = 1
+           AND EXISTS (SELECT 1 FROM json_each(w.events) WHERE value = ?2)",
+        params![tenant_id, event.as_str(), payload.to_string()],
+    )?;
+    if queued > 0 {
+        conn.execute(
+            &format!(
+                "DELETE FROM webhook_deliveries
+                 WHERE status != 'pending' AND created_at < datetime('now', '-{} days')",
+                DELIVERY_RETENTION_DAYS
+            ),
+            [],
+        )?;
+    }
+    Ok(queued)
+}
+
+/// Deliveries of a tenant due for an attempt, oldest first.
+pub fn due_deliveries(
+    conn: &Connection,
+    tenant_id: &str,
+    limit: usize,
+) -> rusqlite::Result<Vec<DueDelivery>> {
+    if !has_webhooks_table(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.event, d.payload, w.url, w.secret, d.attempts
+         FROM webhook_deliveries d
+         JOIN webhooks w ON w.id = d.webhook_id
+         WHERE d.status = 'pending' AND d.next_attempt_at <= datetime('now')
+           AND w.tenant_id = ?1 AND w.enabled = 1
+         ORDER BY d.id
+         LIMIT ?2",
+    )?;
+    let due = stmt
+        .query_map(params![tenant_id, limit as i64], |row| {
+            Ok(DueDelivery {
+                id: row.get(0)?,
+                event: row.get(1)?,
+                payload: row.get(2)?,
+                url: row.get(3)?,
+                secret: row.get(4)?,
+                attempts: row.get(5)?,
+            })
+        })?
+        .collect();
+    due
+}
+
+/// Whether a tenant has deliveries waiting for an attempt.
+pub fn has_pending(conn: &Connection, tenant_id: &str) -> rusqlite::Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM webhook_deliveries d
+             JOIN webhooks w ON w.id = d.webhook_id
+             WHERE d.status = 'pending' AND w.tenant_id = ?1 AND w.enabled = 1
+             LIMIT 1",
+            [tenant_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Record an attempt: `Ok(status)` for a `2xx`, else the status (if the
+/// endpoint answered) and the error. Returns the delivery's new status.
+pub fn record_attempt(
+    conn: &Connection,
+    id: i64,
+    outcome: Result<u16, (Option<u16>, String)>,
+    config: &WebhookConfig,
+) -> rusqlite::Result<DeliveryStatus> {
+    let attempts: u32 = conn.query_row(
+        "SELECT attempts + 1 FROM webhook_deliveries WHERE id = ?1",
+        [id],
+        |row| row.get(0),
+    )?;
+    let status = match &outcome {
+        Ok(_) => DeliveryStatus::Delivered,
+        Err(_) if attempts >= config.max_attempts => DeliveryStatus::Failed,
+        Err(_) => DeliveryStatus::Pending,
+    };
+    let (status_code, error) = match outcome {
+        Ok(code) => (Some(code), None),
+        Err((code, error)) => (code, Some(error)),
+    };
+    conn.execute(
+        "UPDATE webhook_deliveries SET
+             attempts = ?2,
+             status = ?3,
+             last_status_code = ?4,
+             last_error = ?5,
+             next_attempt_at = datetime('now', '+' || ?6 || ' seconds'),
+             delivered_at = CASE WHEN ?3 = 'delivered' THEN datetime('now') END
+         WHERE id = ?1",
+        params![
+            id,
+            attempts,
+            match status {
+                DeliveryStatus::Pending => "pending",
+                DeliveryStatus::Delivered => "delivered",
+                DeliveryStatus::Failed => "failed",
+            },
+            status_code,
+            error,
+            config.retry_delay_secs(attempts) as i64,
+        ],
+    )?;
+    Ok(status)
+}
+
+/// A webhook's most recent deliveries, newest first.
+pub fn list_deliveries(
+    conn: &Connection,
+    webhook_id: i64,
+    limit: usize,
+    offset: usize,
+) -> rusqlite::Result<Vec<WebhookDelivery>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, webhook_id, event, status, attempts, next_attempt_at, last_status_code,
+                last_error, created_at, delivered_at
+         FROM webhook_deliveries
+         WHERE webhook_id = ?1
+         ORDER BY id DESC
+         LIMIT ?2 OFFSET ?3",
+    )?;
+    let deliveries = stmt
+        .query_map(params![webhook_id, limit as i64, offset as i64], |row| {
+            let status = DeliveryStatus::parse(&row.get::<_, String>(3)?);
+            Ok(WebhookDelivery {
+                id: row.get(0)?,
+                webhook_id: row.get(1)?,
+                event: row.get(2)?,
+                status,
+                attempts: row.get(4)?,
+                next_attempt_at: if status == DeliveryStatus::Pending {
+                    row.get(5)?
+                } else {
+                    None
+                },
+                last_status_code: row.get(6)?,
+                last_error: row.get(7)?,
+                created_at: row.get(8)?,
+                delivered_at: row.get(9)?,
+            })
+        })?
+        .collect();
+    deliveries
+}
+
+/// Number of a webhook's deliveries.
+pub fn count_deliveries(conn: &Connection, webhook_id: i64) -> rusqlite::Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM webhook_deliveries WHERE webhook_id = ?1",
+        [webhook_id],
+        |row| row.get(0),
+    )
+}
+
+/// Signature header value for a body: `sha256=` and the hex HMAC-SHA256.
+#[cfg(feature = "alerting")]
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// A tenant catalog with newly queued deliveries
+pub struct QueuedDeliveries {
+    pub tenant_id: String,
+    pub backend: Arc<DynCatalogBackend>,
+}
+
+/// Handle for queueing webhook deliveries from request handlers
+///
+/// Deliveries are written to the tenant's catalog, and the delivery task is
+/// woken to send them. A disabled dispatcher queues nothing.
+#[derive(Clone, Default)]
+pub struct WebhookDispatcher {
+    sender: Option<mpsc::Sender<QueuedDeliveries>>,
+    quality_drop: f64,
+    url_policy: UrlPolicy,
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher and the receiver for the delivery task.
+    pub fn new(config: &WebhookConfig) -> (Self, mpsc::Receiver<QueuedDeliveries>) {
+        let (sender, receiver) = mpsc::channel(QUEUE_SIZE);
+        (
+            Self {
+                sender: Some(sender),
+                quality_drop: config.quality_drop,
+                url_policy: config.url_policy.clone(),
+            },
+            receiver,
+        )
+    }
+
+    /// A dispatcher that queues nothing
+    pub fn disabled() -> Self {
+        Self {
+            url_policy: UrlPolicy::from_env(),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Policy that registered webhook URLs must pass
+    pub fn url_policy(&self) -> &UrlPolicy {
+        &self.url_policy
+    }
+
+    /// Queue an event for the tenant's webhooks. Failures are logged and
+    /// never fail the request.
+    pub fn notify(
+        &self,
+        conn: &Connection,
+        backend: &Arc<DynCatalogBackend>,
+        tenant_id: &str,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        match enqueue(conn, tenant_id, event, &data) {
+            Ok(0) => {}
+            Ok(queued) => {
+                tracing::debug!(
+                    tenant_id,
+                    event = event.as_str(),
+                    queued,
+                    "Webhook deliveries queued"
+                );
+                // A full queue means the task is busy; it polls for due deliveries
+                let _ = sender.try_send(QueuedDeliveries {
+                    tenant_id: tenant_id.to_string(),
+                    backend: Arc::clone(backend),
+                });
+            }
+            Err(e) => {
+                tracing::warn!(tenant_id, event = event.as_str(), error = %e, "Failed to queue webhook deliveries");
+            }
+        }
+    }
+
+    /// Queue `quality.dropped` if the overall score fell from `previous` to
+    /// `current` by at least the configured drop.
+    pub fn notify_quality(
+        &self,
+        conn: &Connection,
+        backend: &Arc<DynCatalogBackend>,
+        tenant_id: &str,
+        dataset: &str,
+        previous: Option<f64>,
+        current: Option<f64>,
+    ) {
+        if let (Some(previous), Some(current)) = (previous, current) {
+            if previous - current >= self.quality_drop {
+                self.notify(
+                    conn,
+                    backend,
+                    tenant_id,
+                    WebhookEvent::QualityDropped,
+                    serde_json::json!({
+                        "name": dataset,
+                        "previous_score": previous,
+                        "current_score": current,
+                    }),
+                );
+            }
+        }
+    }
+}
+
+/// Seconds between checks for deliveries due for a retry
+#[cfg(feature = "alerting")]
+const POLL_INTERVAL_SECS: u64 = 5;
+
+/// Timeout for one delivery attempt
+#[cfg(feature = "alerting")]
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+
+/// Deliveries attempted per tenant per pass
+#[cfg(feature = "alerting")]
+const DELIVERY_BATCH: usize = 100;
+
+/// An HTTP client for `url` that connects only to the addresses the policy
+/// checked and never follows redirects.
+///
+/// Resolve again before each delivery, so a DNS change after registration
+/// can't point the URL at an internal target.
+#[cfg(feature = "alerting")]
+pub async fn pinned_client(
+    policy: &UrlPolicy,
+    url: &str,
+    timeout: std::time::Duration,
+) -> Result<reqwest::Client, String> {
+    let (host, addrs) = policy.resolve(url).await?;
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(&host, &addrs)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Send one delivery: `Ok(status)` for a `2xx`, else the status (if the
+/// endpoint answered) and the error.
+///
+/// The host is resolved and checked against the URL policy first (see
+/// [`pinned_client`]).
+#[cfg(feature = "alerting")]
+async fn attempt_delivery(
+    policy: &UrlPolicy,
+    delivery: &DueDelivery,
+) -> Result<u16, (Option<u16>, String)> {
+    let timeout = std::time::Duration::from_secs(DELIVERY_TIMEOUT_SECS);
+    let client = pinned_client(policy, &delivery.url, timeout)
+        .await
+        .map_err(|e| (None, e))?;
+    match client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-MetaFuse-Event", &delivery.event)
+        .header("X-MetaFuse-Delivery", delivery.id.to_string())
+        .header(
+            "X-MetaFuse-Signature",
+            sign(&delivery.secret, delivery.payload.as_bytes()),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => Ok(response.status().as_u16()),
+        Ok(response) => Err((
+            Some(response.status().as_u16()),
+            format!("HTTP {}", response.status()),
+        )),
+        Err(e) => Err((None, e.to_string())),
+    }
+}
+
+/// Attempt a tenant's due deliveries. Returns whether any remain pending.
+#[cfg(feature = "alerting")]
+async fn deliver_due(config: &WebhookConfig, tenant_id: &str, backend: &DynCatalogBackend) -> bool {
+    let due = match backend.get_connection().await {
+        Ok(conn) => due_deliveries(&conn, tenant_id, DELIVERY_BATCH),
+        Err(e) => {
+            tracing::warn!(tenant_id, error = %e, "Failed to get connection for webhook delivery");
+            return true;
+        }
+    };
+    let due = match due {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!(tenant_id, error = %e, "Failed to load due webhook deliveries");
+            return true;
+        }
+    };
+
+    // Send without holding the connection, then record the outcomes
+    let mut outcomes = Vec::with_capacity(due.len());
+    for delivery in &due {
+        outcomes.push(attempt_delivery(&config.url_policy, delivery).await);
+    }
+
+    let conn = match backend.get_connection().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::warn!(tenant_id, error = %e, "Failed to get connection to record webhook deliveries");
+            return true;
+        }
+    };
+    for (delivery, outcome) in due.iter().zip(outcomes) {
+        match record_attempt(&conn, delivery.id, outcome, config) {
+            Ok(DeliveryStatus::Delivered) => {
+                tracing::debug!(tenant_id, delivery_id = delivery.id, event = %delivery.event, "Webhook delivered");
+            }
+            Ok(DeliveryStatus::Pending) => {
+                tracing::info!(tenant_id, delivery_id = delivery.id, event = %delivery.event, attempt = delivery.attempts + 1, "Webhook delivery failed, will retry");
+            }
+            Ok(DeliveryStatus::Failed) => {
+                tracing::warn!(tenant_id, delivery_id = delivery.id, event = %delivery.event, attempts = delivery.attempts + 1, "Webhook delivery permanently failed");
+            }
+            Err(e) => {
+                tracing::error!(tenant_id, delivery_id = delivery.id, error = %e, "Failed to record webhook delivery");
+            }
+        }
+    }
+    has_pending(&conn, tenant_id).unwrap_or(true)
+}
+
+/// Background task that sends queued webhook deliveries
+///
+/// Deliveries are sent when handlers queue them, and retried as they come
+/// due. The default catalog is always checked; other tenant catalogs are
+/// checked while they have pending deliveries.
+#[cfg(feature = "alerting")]
+pub async fn webhook_delivery_task(
+    config: WebhookConfig,
+    mut receiver: mpsc::Receiver<QueuedDeliveries>,
+    backend: Arc<DynCatalogBackend>,
+) {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    let mut tenants: HashMap<String, Arc<DynCatalogBackend>> =
+        HashMap::from([(DEFAULT_TENANT.to_string(), backend)]);
+    let mut poll = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+    tracing::info!(
+        max_attempts = config.max_attempts,
+        retry_backoff_secs = config.retry_backoff_secs,
+        "Webhook delivery task started"
+    );
+
+    loop {
+        tokio::select! {
+            queued = receiver.recv() => {
+                let Some(queued) = queued else {
+                    break;
+                };
+                deliver_due(&config, &queued.tenant_id, queued.backend.as_ref()).await;
+                tenants.insert(queued.tenant_id, queued.backend);
+            }
+            _ = poll.tick() => {
+                let mut idle = Vec::new();
+                for (tenant_id, backend) in &tenants {
+                    if !deliver_due(&config, tenant_id, backend.as_ref()).await
+                        && tenant_id != DEFAULT_TENANT
+                    {
+                        idle.push(tenant_id.clone());
+                    }
+                }
+                for tenant_id in idle {
+                    tenants.remove(&tenant_id);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CreateWebhookRequest {
+    url: String,
+    /// Signing secret; generated when omitted
+    #[serde(default)]
+    secret: Option<String>,
+    /// Events to deliver (default: all)
+    #[serde(default)]
+    events: Option<Vec<WebhookEvent>>,
+}
+
+/// A newly registered webhook, with its secret
+#[derive(Debug, Serialize)]
+pub(crate) struct CreatedWebhook {
+    #[serde(flatten)]
+    webhook: Webhook,
+    secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub(crate) struct WebhookDeliveriesQuery {
+    limit: usize,
+}
+
+impl Default for WebhookDeliveriesQuery {
+    fn default() -> Self {
+        Self { limit: 50 }
+    }
+}
+
+pub(crate) fn webhook_not_found(
+    id: i64,
+    request_id: &RequestId,
+) -> (StatusCode, Json<ErrorResponse>) {
+    not_found(format!("Webhook {} not found", id), request_id.0.clone())
+}
+
+/// The tenant's webhooks
+pub(crate) async fn get_webhooks(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<Webhook>>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let tenant_id = webhook_tenant(tenant_backend.as_ref());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let hooks = list_webhooks(&conn, tenant_id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.page(hooks)))
+}
+
+/// Register a webhook
+pub(crate) async fn post_webhook(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    JsonBody(req): JsonBody<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreatedWebhook>), (StatusCode, Json<ErrorResponse>)> {
+    // Webhooks send catalog changes off-site, so they are admin-only
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    // Reject URLs that resolve to loopback, private, or link-local targets
+    state
+        .webhooks
+        .url_policy()
+        .resolve(&req.url)
+        .await
+        .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    let events = req.events.unwrap_or_else(|| WebhookEvent::ALL.to_vec());
+    if events.is_empty() {
+        return Err(bad_request(
+            "A webhook needs at least one event".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+    let secret = match req.secret {
+        Some(secret) if secret.is_empty() => {
+            return Err(bad_request(
+                "Webhook secret must not be empty".to_string(),
+                request_id.0.clone(),
+            ))
+        }
+        Some(secret) => secret,
+        None => generate_secret(),
+    };
+
+    let tenant_id = webhook_tenant(tenant_backend.as_ref());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let webhook = create_webhook(
+        &conn,
+        tenant_id,
+        &req.url,
+        &secret,
+        &events,
+        audit_context.api_key_id.as_deref(),
+    )
+    .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(tenant_id, webhook_id = webhook.id, "Webhook registered");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::create(
+            "webhook",
+            webhook.id.to_string(),
+            serde_json::to_value(&webhook).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhook { webhook, secret }),
+    ))
+}
+
+/// A webhook by id
+pub(crate) async fn get_webhook_by_id(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+) -> Result<Json<Webhook>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let tenant_id = webhook_tenant(tenant_backend.as_ref());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    get_webhook(&conn, tenant_id, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .map(Json)
+        .ok_or_else(|| webhook_not_found(id, &request_id))
+}
+
+/// Change a webhook's URL, events, secret, or enabled state
+pub(crate) async fn put_webhook(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    JsonBody(req): JsonBody<WebhookUpdate>,
+) -> Result<Json<Webhook>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    if let Some(url) = &req.url {
+        state
+            .webhooks
+            .url_policy()
+            .resolve(url)
+            .await
+            .map_err(|e| bad_request(e, request_id.0.clone()))?;
+    }
+    if req.events.as_ref().is_some_and(|events| events.is_empty()) {
+        return Err(bad_request(
+            "A webhook needs at least one event".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+    if req.secret.as_ref().is_some_and(|secret| secret.is_empty()) {
+        return Err(bad_request(
+            "Webhook secret must not be empty".to_string(),
+            request_id.0.clone(),
+        ));
+    }
+
+    let tenant_id = webhook_tenant(tenant_backend.as_ref());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let before = get_webhook(&conn, tenant_id, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| webhook_not_found(id, &request_id))?;
+    let webhook = update_webhook(&conn, tenant_id, id, &req)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| webhook_not_found(id, &request_id))?;
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::update(
+            "webhook",
+            id.to_string(),
+            serde_json::to_value(&before).unwrap_or_default(),
+            serde_json::to_value(&webhook).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+    Ok(Json(webhook))
+}
+
+/// Remove a webhook and its deliveries
+pub(crate) async fn delete_webhook_by_id(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(audit_context): Extension<AuditContext>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let tenant_id = webhook_tenant(tenant_backend.as_ref());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    let webhook = get_webhook(&conn, tenant_id, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| webhook_not_found(id, &request_id))?;
+    delete_webhook(&conn, tenant_id, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    tracing::info!(tenant_id, webhook_id = id, "Webhook removed");
+
+    #[cfg(feature = "audit")]
+    {
+        let event = audit::AuditEvent::delete(
+            "webhook",
+            id.to_string(),
+            serde_json::to_value(&webhook).unwrap_or_default(),
+            &request_id.0,
+        );
+        state.audit_logger.log(audit_context.enrich_event(event));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A webhook's recent deliveries, newest first
+pub(crate) async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(request_id): Extension<RequestId>,
+    tenant_backend: Option<Extension<TenantBackend>>,
+    #[cfg(feature = "api-keys")] resolved_tenant: Option<Extension<ResolvedTenant>>,
+    Path(id): Path<i64>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+    envelope: envelope::EnvelopeQuery,
+) -> Result<Json<envelope::Collection<WebhookDelivery>>, (StatusCode, Json<ErrorResponse>)> {
+    #[cfg(feature = "api-keys")]
+    require_admin_permission(resolved_tenant.as_ref().map(|e| &e.0), &request_id.0)
+        .map_err(rbac_error)?;
+
+    let tenant_id = webhook_tenant(tenant_backend.as_ref());
+    let backend = resolve_backend(&state.backend, tenant_backend.as_ref().map(|e| &e.0));
+    let conn = backend
+        .get_connection()
+        .await
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+
+    get_webhook(&conn, tenant_id, id)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?
+        .ok_or_else(|| webhook_not_found(id, &request_id))?;
+    let (limit, offset) = envelope.bounds(query.limit.min(1000));
+    let deliveries = list_deliveries(&conn, id, limit, offset)
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    let total = envelope
+        .total(|| count_deliveries(&conn, id))
+        .map_err(|e| internal_error(e.to_string(), request_id.0.clone()))?;
+    Ok(Json(envelope.window(deliveries, total)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_addresses() {
+        for ip in ["93.184.215.14", "8.8.8.8", "2606:4700::1111"] {
+            assert!(is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "0.1.2.3",
+            "255.255.255.255",
+            "224.0.0.1",
+            "192.0.2.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "ff02::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_benchmarking_addresses_are_not_public() {
+        for ip in ["198.18.0.1", "198.19.255.254"] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_public_address("198.20.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_reserved_addresses_are_not_public() {
+        for ip in ["240.0.0.1", "250.1.2.3"] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_nat64_addresses_use_embedded_ipv4() {
+        for ip in [
+            "64:ff9b::7f00:1",
+            "64:ff9b::10.0.0.1",
+            "64:ff9b::169.254.169.254",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_public_address("64:ff9b::8.8.8.8".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_6to4_addresses_use_embedded_ipv4() {
+        // 2002:AABB:CCDD:: carries AA.BB.CC.DD
+        for ip in ["2002:7f00:1::", "2002:a00:1::1", "2002:c0a8:101::"] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_public_address("2002:808:808::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ipv4_compatible_addresses_use_embedded_ipv4() {
+        for ip in ["::127.0.0.1", "::10.0.0.1", "::192.168.1.1"] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        assert!(is_public_address("::8.8.8.8".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_url_policy() {
+        let policy = UrlPolicy::default();
+        assert!(policy.validate_url("https://hooks.example.com/a").is_ok());
+        assert!(policy.validate_url("http://hooks.example.com/a").is_err());
+        assert!(policy.validate_url("ftp://hooks.example.com").is_err());
+        assert!(policy.validate_url("not a url").is_err());
+
+        let (host, addrs) = policy
+            .resolve("https://93.184.215.14:8443/a")
+            .await
+            .unwrap();
+        assert_eq!(host, "93.184.215.14");
+        assert_eq!(addrs, vec!["93.184.215.14:8443".parse().unwrap()]);
+        let error = policy.resolve("https://127.0.0.1/a").await.unwrap_err();
+        assert!(error.contains("non-public address 127.0.0.1"), "{}", error);
+        // The userinfo is not the host
+        assert!(policy
+            .resolve("https://93.184.215.14@10.0.0.1/a")
+            .await
+            .is_err());
+
+        // Operators can allow http and internal hosts
+        let policy = UrlPolicy {
+            allow_http: true,
+            allowed_hosts: vec!["127.0.0.1".to_string()],
+        };
+        assert!(policy.resolve("http://127.0.0.1:9000/a").await.is_ok());
+        assert!(policy.resolve("http://10.0.0.1/a").await.is_err());
+        assert!(policy.validate_url("ftp://hooks.example.com").is_err());
+    }
+
+    #[cfg(feature = "alerting")]
+    #[tokio::test]
+    async fn test_delivery_refuses_internal_targets() {
+        // A webhook stored before the policy, or a host that now resolves internally
+        let delivery = DueDelivery {
+            id: 1,
+            event: "dataset.created".to_string(),
+            payload: "{}".to_string(),
+            url: "https://169.254.169.254/latest/meta-data".to_string(),
+            secret: "s3cret".to_string(),
+            attempts: 0,
+        };
+        let (status, error) = attempt_delivery(&UrlPolicy::default(), &delivery)
+            .await
+            .unwrap_err();
+        assert_eq!(status, None);
+        assert!(error.contains("non-public address"), "{}", error);
+    }
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+        metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_webhook_crud() {
+        let conn = setup_db();
+        let hook = create_webhook(
+            &conn,
+            "acme",
+            "https://hooks.example.com/a",
+            "s3cret",
+            &[
+                WebhookEvent::QualityDropped,
+                WebhookEvent::DatasetCreated,
+                WebhookEvent::QualityDropped,
+            ],
+            Some("key:1"),
+        )
+        .unwrap();
+        assert_eq!(
+            hook.events,
+            vec![WebhookEvent::DatasetCreated, WebhookEvent::QualityDropped]
+        );
+        assert!(hook.enabled);
+        create_webhook(
+            &conn,
+            "other",
+            "https://hooks.example.com/b",
+            "x",
+            &WebhookEvent::ALL,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(list_webhooks(&conn, "acme").unwrap(), vec![hook.clone()]);
+        assert!(get_webhook(&conn, "other", hook.id).unwrap().is_none());
+
+        let updated = update_webhook(
+            &conn,
+            "acme",
+            hook.id,
+            &WebhookUpdate {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert!(!updated.enabled);
+        assert_eq!(updated.url, hook.url);
+        assert!(
+            update_webhook(&conn, "other", hook.id, &WebhookUpdate::default())
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(!delete_webhook(&conn, "other", hook.id).unwrap());
+        assert!(delete_webhook(&conn, "acme", hook.id).unwrap());
+        assert!(list_webhooks(&conn, "acme").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_enqueue_matches_tenant_and_events() {
+        let conn = setup_db();
+        let all = create_webhook(
+            &conn,
+            "acme",
+            "https://a.example.com",
+            "s",
+            &WebhookEvent::ALL,
+            None,
+        )
+        .unwrap();
+        create_webhook(
+            &conn,
+            "acme",
+            "https://b.example.com",
+            "s",
+            &[WebhookEvent::QualityDropped],
+            None,
+        )
+        .unwrap();
+        create_webhook(
+            &conn,
+            "other",
+            "https://c.example.com",
+            "s",
+            &WebhookEvent::ALL,
+            None,
+        )
+        .unwrap();
+
+        let data = serde_json::json!({ "name": "orders" });
+        assert_eq!(
+            enqueue(&conn, "acme", WebhookEvent::DatasetUpdated, &data).unwrap(),
+            1
+        );
+        assert_eq!(
+            enqueue(&conn, "acme", WebhookEvent::QualityDropped, &data).unwrap(),
+            2
+        );
+
+        let due = due_deliveries(&conn, "acme", 10).unwrap();
+        assert_eq!(due.len(), 3);
+        assert_eq!(due[0].url, all.url);
+        let payload: serde_json::Value = serde_json::from_str(&due[0].payload).unwrap();
+        assert_eq!(payload["event"], "dataset.updated");
+        assert_eq!(payload["tenant_id"], "acme");
+        assert_eq!(payload["data"]["name"], "orders");
+        assert!(due_deliveries(&conn, "other", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_attempt_retries_then_fails() {
+        let conn = setup_db();
+        let hook = create_webhook(
+            &conn,
+            "default",
+            "https://a.example.com",
+            "s",
+            &WebhookEvent::ALL,
+            None,
+        )
+        .unwrap();
+        let config = WebhookConfig {
+            max_attempts: 2,
+            ..Default::default()
+        };
+        let data = serde_json::json!({ "name": "orders" });
+        enqueue(&conn, "default", WebhookEvent::DatasetCreated, &data).unwrap();
+        enqueue(&conn, "default", WebhookEvent::DatasetDeleted, &data).unwrap();
+        let due = due_deliveries(&conn, "default", 10).unwrap();
+
+        assert_eq!(
+            record_attempt(&conn, due[0].id, Ok(204), &config).unwrap(),
+            DeliveryStatus::Delivered
+        );
+        assert_eq!(
+            record_attempt(
+                &conn,
+                due[1].id,
+                Err((Some(500), "HTTP 500".to_string())),
+                &config
+            )
+            .unwrap(),
+            DeliveryStatus::Pending
+        );
+        // Not due again until the backoff passes
+        assert!(due_deliveries(&conn, "default", 10).unwrap().is_empty());
+        assert!(has_pending(&conn, "default").unwrap());
+        assert_eq!(
+            record_attempt(
+                &conn,
+                due[1].id,
+                Err((None, "timed out".to_string())),
+                &config
+            )
+            .unwrap(),
+            DeliveryStatus::Failed
+        );
+        assert!(!has_pending(&conn, "default").unwrap());
+
+        let deliveries = list_deliveries(&conn, hook.id, 10, 0).unwrap();
+        assert_eq!(deliveries[0].status, DeliveryStatus::Failed);
+        assert_eq!(deliveries[0].attempts, 2);
+        assert_eq!(deliveries[0].last_status_code, None);
+        assert_eq!(deliveries[0].last_error.as_deref(), Some("timed out"));
+        assert_eq!(deliveries[1].status, DeliveryStatus::Delivered);
+        assert!(deliveries[1].delivered_at.is_some());
+    }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        let config = WebhookConfig::default();
+        assert_eq!(config.retry_delay_secs(1), 30);
+        assert_eq!(config.retry_delay_secs(2), 60);
+        assert_eq!(config.retry_delay_secs(4), 240);
+    }
+
+    #[cfg(feature = "alerting")]
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
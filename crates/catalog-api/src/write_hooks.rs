@@ -0,0 +1,195 @@
+//! Write Hooks Configuration
+//!
+//! Builds the [`WriteHooks`] chain the API server runs on every dataset
+//! write. The hook interface itself lives in
+//! `metafuse_catalog_core::hooks` so the emitter can share it.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_WRITE_DENY_PATHS`: comma-separated path globs to reject (e.g. `s3://scratch/*`)
+//! - `METAFUSE_WRITE_HOOK_URL`: external hook endpoint (requires the `http-write-hook` feature)
+//! - `METAFUSE_WRITE_HOOK_TIMEOUT_MS`: per-hook timeout (default: 2000)
+//! - `METAFUSE_WRITE_HOOK_FAILURE_POLICY`: `fail-open` (default) or `fail-closed`
+//!
+//! # HTTP Hook Protocol
+//!
+//! The external hook receives `POST {"phase": "pre_validate" | "post_commit", "write": {...}}`.
+//! For `pre_validate`:
+//!
+//! - `200` with a write object in the body replaces the write (enrichment)
+//! - `204` or `200` with an empty body accepts the write unchanged
+//! - `403` or `422` rejects the write; the response body is the reason
+//! - anything else is a hook failure, handled by the failure policy
+
+use metafuse_catalog_core::hooks::{
+    FailurePolicy, HookOptions, PathDenyHook, WriteHooks, DEFAULT_HOOK_TIMEOUT,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Write hook configuration
+#[derive(Debug, Clone, Default)]
+pub struct WriteHookConfig {
+    /// Path globs rejected by the built-in path deny hook
+    pub deny_paths: Vec<String>,
+    /// External hook endpoint
+    pub url: Option<String>,
+    pub options: HookOptions,
+}
+
+impl WriteHookConfig {
+    /// Create config from environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let deny_paths = std::env::var("METAFUSE_WRITE_DENY_PATHS")
+            .map(|v| {
+                v.split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let url = std::env::var("METAFUSE_WRITE_HOOK_URL")
+            .ok()
+            .filter(|u| !u.is_empty());
+        #[cfg(not(feature = "http-write-hook"))]
+        if url.is_some() {
+            return Err(
+                "METAFUSE_WRITE_HOOK_URL requires the 'http-write-hook' feature".to_string(),
+            );
+        }
+
+        let timeout = std::env::var("METAFUSE_WRITE_HOOK_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_HOOK_TIMEOUT);
+
+        let failure_policy = match std::env::var("METAFUSE_WRITE_HOOK_FAILURE_POLICY") {
+            Ok(v) => FailurePolicy::parse(&v).ok_or_else(|| {
+                format!(
+                    "Invalid METAFUSE_WRITE_HOOK_FAILURE_POLICY '{}': expected 'fail-open' or 'fail-closed'",
+                    v
+                )
+            })?,
+            Err(_) => FailurePolicy::default(),
+        };
+
+        Ok(Self {
+            deny_paths,
+            url,
+            options: HookOptions {
+                timeout,
+                failure_policy,
+            },
+        })
+    }
+
+    /// Build the hook chain: path deny list first, then the external hook.
+    pub fn build(&self) -> WriteHooks {
+        let mut hooks = WriteHooks::new();
+        if !self.deny_paths.is_empty() {
+            hooks.register(
+                Arc::new(PathDenyHook::new(self.deny_paths.clone())),
+                self.options,
+            );
+        }
+        #[cfg(feature = "http-write-hook")]
+        if let Some(url) = &self.url {
+            hooks.register(
+                Arc::new(http::HttpWriteHook::new(url.clone())),
+                self.options,
+            );
+        }
+        hooks
+    }
+}
+
+#[cfg(feature = "http-write-hook")]
+pub mod http {
+    //! Write hook that delegates to an external HTTP endpoint.
+
+    use metafuse_catalog_core::hooks::{DatasetWrite, HookError, HookFuture, WriteHook};
+    use serde_json::json;
+
+    /// Calls an external endpoint for each write phase.
+    ///
+    /// Timeouts are enforced by the hook chain, not the HTTP client.
+    pub struct HttpWriteHook {
+        client: reqwest::Client,
+        url: String,
+    }
+
+    impl HttpWriteHook {
+        pub fn new(url: String) -> Self {
+            Self {
+                client: reqwest::Client::new(),
+                url,
+            }
+        }
+
+        async fn call(&self, phase: &str, write: &DatasetWrite) -> Result<String, HookError> {
+            let response = self
+                .client
+                .post(&self.url)
+                .json(&json!({ "phase": phase, "write": write }))
+                .send()
+                .await
+                .map_err(|e| HookError::Failed(format!("request failed: {}", e)))?;
+
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .map_err(|e| HookError::Failed(format!("failed to read response: {}", e)))?;
+
+            match status.as_u16() {
+                200..=299 => Ok(body),
+                403 | 422 => Err(HookError::Rejected(if body.is_empty() {
+                    format!("rejected with status {}", status)
+                } else {
+                    body
+                })),
+                _ => Err(HookError::Failed(format!("unexpected status {}", status))),
+            }
+        }
+    }
+
+    impl WriteHook for HttpWriteHook {
+        fn name(&self) -> &str {
+            "http"
+        }
+
+        fn pre_validate<'a>(&'a self, write: &'a mut DatasetWrite) -> HookFuture<'a> {
+            Box::pin(async move {
+                let body = self.call("pre_validate", write).await?;
+                if !body.trim().is_empty() {
+                    *write = serde_json::from_str(&body)
+                        .map_err(|e| HookError::Failed(format!("invalid response: {}", e)))?;
+                }
+                Ok(())
+            })
+        }
+
+        fn post_commit<'a>(&'a self, write: &'a DatasetWrite) -> HookFuture<'a> {
+            Box::pin(async move { self.call("post_commit", write).await.map(|_| ()) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_registers_deny_hook() {
+        assert!(WriteHookConfig::default().build().is_empty());
+
+        let config = WriteHookConfig {
+            deny_paths: vec!["s3://scratch/*".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.build().len(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! Analytics Endpoint Tests
+//!
+//! Tests the catalog-wide reporting endpoints against an in-process router,
+//! with datasets written through `metafuse-catalog-emitter` into the catalog
+//! the router reads:
+//! - `GET /api/v1/analytics/metadata-completeness`
+//! - `GET /api/v1/digests`
+//! - `GET /api/v1/stats`
+//!
+//! Run with: `cargo test -p metafuse-catalog-api --test analytics_tests`
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use metafuse_catalog_api::{build_router, ServerConfig};
+use metafuse_catalog_core::OperationalMeta;
+use metafuse_catalog_emitter::Emitter;
+use metafuse_catalog_storage::{backend_from_uri, LocalSqliteBackend};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+/// A router serving a temporary catalog.
+struct TestCatalog {
+    app: Router,
+    catalog_path: PathBuf,
+    _temp_dir: TempDir,
+}
+
+impl TestCatalog {
+    async fn new() -> Self {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog_path = temp_dir.path().join("catalog.db");
+        let backend = backend_from_uri(catalog_path.to_str().unwrap()).unwrap();
+        backend.initialize().await.unwrap();
+        let config = ServerConfig {
+            run_migrations: true,
+            ..Default::default()
+        };
+        let (app, _tasks) = build_router(&config, Arc::from(backend)).await.unwrap();
+        Self {
+            app,
+            catalog_path,
+            _temp_dir: temp_dir,
+        }
+    }
+
+    /// Emit a dataset in the `sales` domain with the given tags.
+    async fn emit(&self, name: &str, description: &str, tags: &[&str]) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("order_id", DataType::Int64, false),
+            Field::new("customer_id", DataType::Int64, false),
+            Field::new("amount", DataType::Float64, true),
+        ]));
+        Emitter::new(LocalSqliteBackend::new(&self.catalog_path))
+            .emit_dataset(
+                name,
+                &format!("s3://lake/{}", name),
+                "parquet",
+                Some(description),
+                None,
+                Some("sales"),
+                Some("data-team@example.com"),
+                schema,
+                Some(OperationalMeta {
+                    row_count: Some(1_000),
+                    size_bytes: Some(64_000),
+                    partition_keys: vec![],
+                }),
+                vec![],
+                tags.iter().map(|s| s.to_string()).collect(),
+            )
+            .await
+            .expect("emit_dataset failed");
+    }
+
+    async fn get(&self, uri: &str) -> (StatusCode, Value) {
+        let (status, _, text) = self.get_text(uri).await;
+        (status, serde_json::from_str(&text).unwrap_or_default())
+    }
+
+    /// GET returning the status, content type, and raw body.
+    async fn get_text(&self, uri: &str) -> (StatusCode, String, String) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = self.app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|v| v.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            content_type,
+            String::from_utf8(bytes.to_vec()).unwrap(),
+        )
+    }
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .expect("expected a JSON array")
+        .iter()
+        .map(|s| s.as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_metadata_completeness() {
+    let catalog = TestCatalog::new().await;
+    catalog
+        .emit("orders", "Revenue by order", &["finance"])
+        .await;
+    catalog.emit("clicks", "Website clickstream", &[]).await;
+
+    let (status, body) = catalog.get("/api/v1/search?q=revenue").await;
+    assert_eq!(status, StatusCode::OK);
+    let orders = &body[0]["metadata_completeness"];
+    assert!(strings(&orders["missing"]).contains(&"glossary_terms".to_string()));
+    assert!(!strings(&orders["missing"]).contains(&"tags".to_string()));
+
+    // Worst-documented first
+    let (status, body) = catalog.get("/api/v1/analytics/metadata-completeness").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+    let ranked: Vec<&str> = body["datasets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["dataset_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(ranked, vec!["clicks", "orders"]);
+    assert!(body["datasets"][0]["score"].as_f64() < orders["score"].as_f64());
+
+    let (_, body) = catalog
+        .get("/api/v1/analytics/metadata-completeness?missing=tags&order=desc")
+        .await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["datasets"][0]["dataset_name"], "clicks");
+
+    let (status, body) = catalog
+        .get("/api/v1/analytics/metadata-completeness?missing=everything")
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+}
+
+#[tokio::test]
+async fn test_digests() {
+    let catalog = TestCatalog::new().await;
+    catalog.emit("orders", "Revenue by order", &[]).await;
+
+    let (status, body) = catalog.get("/api/v1/digests").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["group_by"], "domain");
+    assert_eq!(body["digests"][0]["group"], "sales");
+    assert_eq!(
+        body["digests"][0]["new_datasets"][0]["dataset_name"],
+        "orders"
+    );
+
+    let (status, content_type, text) = catalog
+        .get_text("/api/v1/digests?group_by=owner&format=markdown")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("text/markdown"));
+    assert!(text.contains("# MetaFuse digest: owner `data-team@example.com`"));
+    assert!(text.contains("## New datasets (1)"));
+
+    let (status, _) = catalog.get("/api/v1/digests?hours=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_catalog_stats() {
+    let catalog = TestCatalog::new().await;
+    catalog.emit("orders", "Revenue by order", &[]).await;
+
+    let (status, body) = catalog.get("/api/v1/stats").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["current"]["dataset_count"], 1);
+    assert_eq!(body["history"].as_array().unwrap().len(), 1);
+    assert_eq!(body["history"][0]["dataset_count"], 1);
+    assert_eq!(body["change"]["dataset_count"], 0);
+
+    let (status, _) = catalog.get("/api/v1/stats?days=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
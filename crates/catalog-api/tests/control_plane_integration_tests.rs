@@ -1291,6 +1291,284 @@ mod region_support {
     }
 }
 
+// ============================================================================
+// Feature Flag Tests
+// ============================================================================
+
+mod feature_flags {
+    use super::*;
+    use metafuse_catalog_api::control_plane::{TenantFeature, UpdateTenantFeatureFlagsRequest};
+
+    #[tokio::test]
+    #[serial]
+    async fn test_feature_flags_default_enabled() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        TestTenantBuilder::new("flags-default-test")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let flags = cp
+            .control_plane()
+            .get_tenant_feature_flags("flags-default-test")
+            .await
+            .unwrap();
+
+        for feature in TenantFeature::ALL {
+            assert!(flags.is_enabled(feature));
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_feature_flags() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        TestTenantBuilder::new("flags-update-test")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        // Warm the cache so the update must invalidate it
+        cp.control_plane()
+            .get_tenant_feature_flags("flags-update-test")
+            .await
+            .unwrap();
+
+        let flags = cp
+            .control_plane()
+            .update_tenant_feature_flags(
+                "flags-update-test",
+                UpdateTenantFeatureFlagsRequest {
+                    classification: Some(false),
+                    preview: None,
+                    usage_analytics: Some(false),
+                },
+                admin_audit(),
+            )
+            .await
+            .unwrap();
+
+        assert!(!flags.classification);
+        assert!(flags.preview);
+        assert!(!flags.usage_analytics);
+
+        let fetched = cp
+            .control_plane()
+            .get_tenant_feature_flags("flags-update-test")
+            .await
+            .unwrap();
+        assert_eq!(fetched, flags);
+
+        // Re-enable a single flag
+        let flags = cp
+            .control_plane()
+            .update_tenant_feature_flags(
+                "flags-update-test",
+                UpdateTenantFeatureFlagsRequest {
+                    classification: Some(true),
+                    ..Default::default()
+                },
+                admin_audit(),
+            )
+            .await
+            .unwrap();
+        assert!(flags.classification);
+        assert!(!flags.usage_analytics);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_feature_flags_empty_request() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        TestTenantBuilder::new("flags-empty-test")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let result = cp
+            .control_plane()
+            .update_tenant_feature_flags(
+                "flags-empty-test",
+                UpdateTenantFeatureFlagsRequest::default(),
+                admin_audit(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(CatalogError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_feature_flags_unknown_tenant() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        let result = cp
+            .control_plane()
+            .update_tenant_feature_flags(
+                "no-such-tenant",
+                UpdateTenantFeatureFlagsRequest {
+                    preview: Some(false),
+                    ..Default::default()
+                },
+                admin_audit(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(CatalogError::DatasetNotFound(_))));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_feature_flags_audited() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        TestTenantBuilder::new("flags-audit-test")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        cp.control_plane()
+            .update_tenant_feature_flags(
+                "flags-audit-test",
+                UpdateTenantFeatureFlagsRequest {
+                    preview: Some(false),
+                    ..Default::default()
+                },
+                admin_audit(),
+            )
+            .await
+            .unwrap();
+
+        let logs = cp
+            .control_plane()
+            .get_audit_log(Some("flags-audit-test"), AUDIT_LOG_LIMIT)
+            .await
+            .unwrap();
+
+        assert!(logs.iter().any(|l| l.action == "feature_flags_update"));
+    }
+}
+
+// ============================================================================
+// Rate Limit Event Tests
+// ============================================================================
+
+#[cfg(feature = "rate-limiting")]
+mod rate_limit_events {
+    use super::*;
+    use metafuse_catalog_api::rate_limiting::RateLimitViolation;
+
+    fn violation(
+        tenant_id: Option<&str>,
+        client_key: &str,
+        route: &str,
+        count: u64,
+    ) -> RateLimitViolation {
+        let now = chrono::Utc::now();
+        RateLimitViolation {
+            tenant_id: tenant_id.map(String::from),
+            client_key: client_key.to_string(),
+            route: route.to_string(),
+            count,
+            first_seen: now,
+            last_seen: now,
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_record_and_list_violations() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        TestTenantBuilder::new("rate-limited-test")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let written = cp
+            .control_plane()
+            .record_rate_limit_violations(&[
+                violation(Some("rate-limited-test"), "auth:k1", "/api/v1/datasets", 5),
+                violation(Some("rate-limited-test"), "auth:k1", "/api/v1/search", 2),
+                violation(None, "anon:10.0.0.1", "/api/v1/datasets", 1),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(written, 3);
+
+        // A second flush for the same client/route is summed
+        cp.control_plane()
+            .record_rate_limit_violations(&[violation(
+                Some("rate-limited-test"),
+                "auth:k1",
+                "/api/v1/datasets",
+                3,
+            )])
+            .await
+            .unwrap();
+
+        let all = cp
+            .control_plane()
+            .list_rate_limit_violations(7, None, 100)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].route, "/api/v1/datasets");
+        assert_eq!(all[0].client_key, "auth:k1");
+        assert_eq!(all[0].violation_count, 8);
+
+        let tenant_only = cp
+            .control_plane()
+            .list_rate_limit_violations(7, Some("rate-limited-test"), 100)
+            .await
+            .unwrap();
+        assert_eq!(tenant_only.len(), 2);
+        assert!(tenant_only
+            .iter()
+            .all(|v| v.tenant_id.as_deref() == Some("rate-limited-test")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_violations_written_to_audit_log() {
+        let cp = TestControlPlane::new().await.unwrap();
+
+        TestTenantBuilder::new("rate-audit-test")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        cp.control_plane()
+            .record_rate_limit_violations(&[violation(
+                Some("rate-audit-test"),
+                "ip:10.0.0.2",
+                "/api/v1/datasets/{name}",
+                4,
+            )])
+            .await
+            .unwrap();
+
+        let logs = cp
+            .control_plane()
+            .get_audit_log(Some("rate-audit-test"), 10)
+            .await
+            .unwrap();
+        let entry = logs
+            .iter()
+            .find(|e| e.action == "rate_limit_exceeded")
+            .expect("rate limit audit entry");
+        assert_eq!(entry.actor, "rate-limiter");
+        assert!(entry
+            .details
+            .as_deref()
+            .unwrap()
+            .contains("\"violations\":4"));
+    }
+}
+
 // ============================================================================
 // Concurrent Operations Tests
 // ============================================================================
@@ -0,0 +1,314 @@
+//! Dataset ACL Tests
+//!
+//! Tests that catalog-wide listings and lineage lookups leave out datasets
+//! hidden from the caller by dataset ACLs, and that lineage cannot be
+//! written against them, against an in-process router. Every catalog holds an open
+//! dataset (`sales.orders`) and one only `user:alice` may read
+//! (`sales.ledger`); requests carry no identity.
+//!
+//! Run with: `cargo test -p metafuse-catalog-api --test dataset_acl_tests`
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use metafuse_catalog_api::{build_router, ServerConfig};
+use metafuse_catalog_storage::backend_from_uri;
+use serde_json::Value;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+/// A router over a catalog with an open and a restricted dataset, plus `seed`.
+async fn restricted_catalog(seed: &str) -> (Router, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let backend = backend_from_uri(dir.path().join("catalog.db").to_str().unwrap()).unwrap();
+    backend.initialize().await.unwrap();
+    let backend: Arc<_> = Arc::from(backend);
+    let config = ServerConfig {
+        run_migrations: true,
+        ..Default::default()
+    };
+    let (app, _tasks) = build_router(&config, Arc::clone(&backend)).await.unwrap();
+    let conn = backend.get_connection().await.unwrap();
+    conn.execute_batch(
+        "INSERT INTO datasets (name, path, format, domain, created_at, last_updated) VALUES
+            ('sales.orders', '/lake/orders', 'parquet', 'sales', datetime('now'), datetime('now')),
+            ('sales.ledger', '/lake/ledger', 'parquet', 'sales', datetime('now'), datetime('now'));
+         INSERT INTO dataset_acls (dataset_id, principal, permission) VALUES (2, 'user:alice', 'read');",
+    )
+    .unwrap();
+    conn.execute_batch(seed).unwrap();
+    (app, dir)
+}
+
+async fn request(app: &Router, uri: &str) -> (StatusCode, Value) {
+    send(
+        app,
+        Request::builder().uri(uri).body(Body::empty()).unwrap(),
+    )
+    .await
+}
+
+async fn post(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    send(app, request).await
+}
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+async fn get(app: &Router, uri: &str) -> Value {
+    let (status, body) = request(app, uri).await;
+    assert_eq!(status, StatusCode::OK, "{}: {}", uri, body);
+    body
+}
+
+/// `key` of every entry in a JSON array.
+fn column(entries: &Value, key: &str) -> Vec<String> {
+    entries
+        .as_array()
+        .expect("expected a JSON array")
+        .iter()
+        .map(|e| e[key].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+#[cfg(feature = "usage-analytics")]
+async fn test_popular_datasets_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "INSERT INTO usage_stats (dataset_id, stat_date, read_count, tenant_id) VALUES
+            (1, date('now'), 5, 'default'), (2, date('now'), 50, 'default');",
+    )
+    .await;
+    let body = get(&app, "/api/v1/analytics/popular").await;
+    assert_eq!(
+        column(&body["datasets"], "dataset_name"),
+        vec!["sales.orders"]
+    );
+}
+
+#[tokio::test]
+#[cfg(feature = "usage-analytics")]
+async fn test_stale_datasets_hide_restricted() {
+    let (app, _dir) = restricted_catalog("").await;
+    let body = get(&app, "/api/v1/analytics/stale").await;
+    assert_eq!(
+        column(&body["datasets"], "dataset_name"),
+        vec!["sales.orders"]
+    );
+}
+
+#[tokio::test]
+async fn test_unhealthy_datasets_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "INSERT INTO quality_metrics (dataset_id, computed_at, overall_score) VALUES
+            (1, datetime('now'), 0.2), (2, datetime('now'), 0.1);",
+    )
+    .await;
+    let body = get(&app, "/api/v1/quality/unhealthy").await;
+    assert_eq!(
+        column(&body["datasets"], "dataset_name"),
+        vec!["sales.orders"]
+    );
+}
+
+#[tokio::test]
+#[cfg(feature = "classification")]
+async fn test_pii_columns_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "INSERT INTO fields (dataset_id, name, data_type) VALUES
+            (1, 'email', 'string'), (2, 'ssn', 'string');
+         INSERT INTO column_classifications (field_id, classification, category) VALUES
+            (1, 'pii', 'email'), (2, 'pii', 'ssn');",
+    )
+    .await;
+    let body = get(&app, "/api/v1/classifications/pii").await;
+    assert_eq!(body["total_pii_columns"], 1);
+    assert_eq!(
+        column(&body["columns"], "dataset_name"),
+        vec!["sales.orders"]
+    );
+    assert_eq!(column(&body["columns"], "field_name"), vec!["email"]);
+}
+
+#[tokio::test]
+async fn test_term_links_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "INSERT INTO fields (dataset_id, name, data_type) VALUES
+            (1, 'amount', 'double'), (2, 'balance', 'double');
+         INSERT INTO glossary_terms (term) VALUES ('revenue');
+         INSERT INTO term_links (term_id, dataset_id, field_id) VALUES
+            (1, 1, NULL), (1, 2, NULL), (1, NULL, 1), (1, NULL, 2);",
+    )
+    .await;
+    let body = get(&app, "/api/v1/glossary/1/links").await;
+    let ids: Vec<i64> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert_eq!(body[0]["dataset_name"], "sales.orders");
+    assert_eq!(body[1]["field_name"], "amount");
+}
+
+#[tokio::test]
+async fn test_orphaned_datasets_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "UPDATE datasets SET last_updated = '2020-01-01 00:00:00';
+         INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+            ('sales.report', '/lake/report', 'parquet', datetime('now'), datetime('now'));
+         INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+            (1, 3, datetime('now')), (2, 3, datetime('now'));",
+    )
+    .await;
+    let body = get(&app, "/api/v1/analytics/orphaned").await;
+    assert_eq!(
+        column(&body["datasets"], "dataset_name"),
+        vec!["sales.orders"]
+    );
+}
+
+#[tokio::test]
+async fn test_format_recommendations_hide_restricted() {
+    let (app, _dir) =
+        restricted_catalog("UPDATE datasets SET format = 'csv', size_bytes = 4294967296;").await;
+    let body = get(&app, "/api/v1/analytics/recommendations").await;
+    assert_eq!(
+        column(&body["format_conversions"], "dataset_name"),
+        vec!["sales.orders"]
+    );
+}
+
+#[tokio::test]
+async fn test_lineage_cycles_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+            ('raw', '/lake/raw', 'parquet', datetime('now'), datetime('now')),
+            ('clean', '/lake/clean', 'parquet', datetime('now'), datetime('now'));
+         INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES
+            (1, 2, datetime('now')), (2, 1, datetime('now')),
+            (3, 4, datetime('now')), (4, 3, datetime('now'));",
+    )
+    .await;
+    let body = get(&app, "/api/v1/lineage/cycles").await;
+    assert_eq!(body["count"], 1);
+    assert_eq!(
+        body["cycles"][0]["datasets"],
+        serde_json::json!(["clean", "raw"])
+    );
+}
+
+#[tokio::test]
+async fn test_external_nodes_hide_restricted() {
+    let (app, _dir) = restricted_catalog(
+        "INSERT INTO external_nodes (uri, name) VALUES
+            ('sftp://vendor/orders', 'orders drop'),
+            ('sftp://vendor/ledger', 'ledger drop'),
+            ('sftp://vendor/unused', 'unused drop');
+         INSERT INTO external_lineage (external_node_id, dataset_id, direction) VALUES
+            (1, 1, 'upstream'), (2, 2, 'upstream');",
+    )
+    .await;
+    let body = get(&app, "/api/v1/lineage/external").await;
+    assert_eq!(
+        column(&body, "uri"),
+        vec!["sftp://vendor/orders", "sftp://vendor/unused"]
+    );
+}
+
+/// Column lineage runs `sales.orders.amount` through the restricted ledger
+/// into the open `sales.report`.
+#[cfg(feature = "column-lineage")]
+const COLUMN_LINEAGE: &str = "
+    INSERT INTO datasets (name, path, format, created_at, last_updated) VALUES
+        ('sales.report', '/lake/report', 'parquet', datetime('now'), datetime('now'));
+    INSERT INTO fields (dataset_id, name, data_type) VALUES
+        (1, 'amount', 'double'), (2, 'amount', 'double'), (3, 'amount', 'double');
+    INSERT INTO column_lineage (source_dataset_id, source_field_name, target_dataset_id, target_field_name) VALUES
+        (1, 'amount', 2, 'amount'), (2, 'amount', 3, 'amount');";
+
+#[tokio::test]
+#[cfg(feature = "column-lineage")]
+async fn test_column_lineage_hides_restricted() {
+    let (app, _dir) = restricted_catalog(COLUMN_LINEAGE).await;
+
+    let body = get(&app, "/api/v1/lineage/dataset/3/columns/amount/upstream").await;
+    assert_eq!(column(&body["nodes"], "dataset_name"), vec!["sales.orders"]);
+
+    let body = get(&app, "/api/v1/lineage/dataset/1/columns/amount/downstream").await;
+    assert_eq!(column(&body["nodes"], "dataset_name"), vec!["sales.report"]);
+
+    let body = get(
+        &app,
+        "/api/v1/lineage/dataset/1/columns/amount/pii-propagation",
+    )
+    .await;
+    assert_eq!(
+        column(&body["downstream_columns"], "dataset_name"),
+        vec!["sales.report"]
+    );
+
+    let body = get(&app, "/api/v1/lineage/fields/1/impact").await;
+    assert_eq!(
+        column(&body["affected_columns"], "dataset_name"),
+        vec!["sales.report"]
+    );
+    assert_eq!(body["summary"]["affected_datasets"], 1);
+}
+
+#[tokio::test]
+#[cfg(feature = "column-lineage")]
+async fn test_column_lineage_of_restricted_dataset_is_not_found() {
+    let (app, _dir) = restricted_catalog(COLUMN_LINEAGE).await;
+    for uri in [
+        "/api/v1/lineage/dataset/2/columns/amount/upstream",
+        "/api/v1/lineage/dataset/2/columns/amount/downstream",
+        "/api/v1/lineage/dataset/2/columns/amount/pii-propagation",
+        "/api/v1/lineage/fields/2/impact",
+    ] {
+        let (status, _) = request(&app, uri).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_lineage_edges_to_restricted_dataset_are_not_found() {
+    let (app, _dir) = restricted_catalog("").await;
+
+    let (status, body) = post(
+        &app,
+        "/api/v1/lineage",
+        serde_json::json!({"source_dataset": "sales.orders", "target_dataset": "sales.ledger"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+
+    let (status, body) = post(
+        &app,
+        "/api/v1/lineage",
+        serde_json::json!({"edges": [{"upstream": "sales.ledger", "downstream": "sales.orders"}]}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["failed"], 1);
+    assert_eq!(
+        body["results"][0]["error"],
+        "Dataset 'sales.ledger' not found"
+    );
+}
@@ -0,0 +1,1299 @@
+//! Emitter End-to-End Tests
+//!
+//! Boots the real `metafuse-api` binary on a random port against a temporary
+//! catalog, writes metadata through `metafuse-catalog-emitter`, and reads it
+//! back over HTTP. Unlike the in-process router tests, these exercise the full
+//! server startup path (routing, migrations, middleware) and catch drift between
+//! what the emitter writes and what the API reads.
+//!
+//! Run with: `cargo test -p metafuse-catalog-api --test emitter_e2e_tests`
+
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use metafuse_catalog_core::OperationalMeta;
+use metafuse_catalog_emitter::Emitter;
+use metafuse_catalog_storage::LocalSqliteBackend;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// ============================================================================
+// Test Server Harness
+// ============================================================================
+
+/// How long to wait for the server to answer `/health` after spawning.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A `metafuse-api` process serving a temporary catalog.
+///
+/// The process is killed when the harness is dropped.
+struct TestServer {
+    child: Child,
+    base_url: String,
+    catalog_path: PathBuf,
+    http: reqwest::Client,
+    _temp_dir: TempDir,
+}
+
+impl TestServer {
+    /// Spawn the server and wait until it is ready to accept requests.
+    async fn start() -> Self {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog_path = temp_dir.path().join("catalog.db");
+        let port = free_port();
+
+        let child = Command::new(env!("CARGO_BIN_EXE_metafuse-api"))
+            .env("METAFUSE_CATALOG_PATH", &catalog_path)
+            .env("METAFUSE_PORT", port.to_string())
+            .env("METAFUSE_RUN_MIGRATIONS", "true")
+            .env("RUST_LOG", "warn")
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .expect("failed to spawn metafuse-api");
+
+        let mut server = Self {
+            child,
+            base_url: format!("http://127.0.0.1:{}", port),
+            catalog_path,
+            http: reqwest::Client::new(),
+            _temp_dir: temp_dir,
+        };
+        server.wait_until_ready().await;
+        server
+    }
+
+    async fn wait_until_ready(&mut self) {
+        let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                panic!("metafuse-api exited during startup: {}", status);
+            }
+            if let Ok(resp) = self.http.get(self.url("/health")).send().await {
+                if resp.status().is_success() {
+                    return;
+                }
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "metafuse-api did not become ready within {:?}",
+                STARTUP_TIMEOUT
+            );
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Emitter writing to the same catalog file the server reads.
+    fn emitter(&self) -> Emitter<LocalSqliteBackend> {
+        Emitter::new(LocalSqliteBackend::new(&self.catalog_path))
+    }
+
+    async fn get(&self, path: &str) -> (StatusCode, Value) {
+        let resp = self.http.get(self.url(path)).send().await.unwrap();
+        let status = resp.status();
+        (status, resp.json().await.unwrap_or(Value::Null))
+    }
+
+    async fn post(&self, path: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let mut req = self.http.post(self.url(path));
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+        let resp = req.send().await.unwrap();
+        let status = resp.status();
+        (status, resp.json().await.unwrap_or(Value::Null))
+    }
+
+    async fn patch(&self, path: &str, body: Value) -> (StatusCode, Value) {
+        let resp = self
+            .http
+            .patch(self.url(path))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        let status = resp.status();
+        (status, resp.json().await.unwrap_or(Value::Null))
+    }
+
+    async fn delete(&self, path: &str) -> StatusCode {
+        self.http
+            .delete(self.url(path))
+            .send()
+            .await
+            .unwrap()
+            .status()
+    }
+
+    async fn json_patch(&self, path: &str, patch: Value) -> (StatusCode, Value) {
+        let resp = self
+            .http
+            .patch(self.url(path))
+            .header("content-type", "application/json-patch+json")
+            .body(patch.to_string())
+            .send()
+            .await
+            .unwrap();
+        let status = resp.status();
+        (status, resp.json().await.unwrap_or(Value::Null))
+    }
+
+    async fn merge_patch(&self, path: &str, patch: Value) -> (StatusCode, Value) {
+        let resp = self
+            .http
+            .patch(self.url(path))
+            .header("content-type", "application/merge-patch+json")
+            .body(patch.to_string())
+            .send()
+            .await
+            .unwrap();
+        let status = resp.status();
+        (status, resp.json().await.unwrap_or(Value::Null))
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Reserve an unused local port.
+///
+/// The listener is dropped before the server binds, so another process could
+/// grab the port in between; this is acceptable for tests.
+fn free_port() -> u16 {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+fn orders_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("order_id", DataType::Int64, false),
+        Field::new("customer_id", DataType::Int64, false),
+        Field::new("amount", DataType::Float64, true),
+    ]))
+}
+
+/// Emit a dataset with the orders schema and the given lineage and tags.
+async fn emit(
+    server: &TestServer,
+    name: &str,
+    description: &str,
+    upstream: &[&str],
+    tags: &[&str],
+) {
+    server
+        .emitter()
+        .emit_dataset(
+            name,
+            &format!("s3://lake/{}", name),
+            "parquet",
+            Some(description),
+            None,
+            Some("sales"),
+            Some("data-team@example.com"),
+            orders_schema(),
+            Some(OperationalMeta {
+                row_count: Some(1_000),
+                size_bytes: Some(64_000),
+                partition_keys: vec![],
+            }),
+            upstream.iter().map(|s| s.to_string()).collect(),
+            tags.iter().map(|s| s.to_string()).collect(),
+        )
+        .await
+        .expect("emit_dataset failed");
+}
+
+fn names(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .expect("expected a JSON array")
+        .iter()
+        .map(|d| d["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .expect("expected a JSON array")
+        .iter()
+        .map(|s| s.as_str().unwrap().to_string())
+        .collect()
+}
+
+// ============================================================================
+// Metadata Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_server_starts_with_empty_catalog() {
+    let server = TestServer::start().await;
+
+    let (status, body) = server.get("/api/v1/datasets").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_emitted_dataset_metadata() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Daily order facts", &[], &["prod"]).await;
+
+    let (status, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "orders");
+    assert_eq!(body["path"], "s3://lake/orders");
+    assert_eq!(body["format"], "parquet");
+    assert_eq!(body["domain"], "sales");
+    assert_eq!(body["owner"], "data-team@example.com");
+    assert_eq!(body["operational"]["row_count"], 1_000);
+    assert_eq!(body["operational"]["size_bytes"], 64_000);
+    assert_eq!(strings(&body["tags"]), vec!["prod"]);
+
+    let fields = names(&body["fields"]);
+    assert_eq!(fields, vec!["order_id", "customer_id", "amount"]);
+    assert_eq!(body["fields"][0]["nullable"], false);
+    assert_eq!(body["fields"][2]["nullable"], true);
+
+    let (status, body) = server.get("/api/v1/datasets").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["orders"]);
+}
+
+#[tokio::test]
+async fn test_reemit_updates_metadata() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "First version", &[], &["draft"]).await;
+    emit(&server, "orders", "Second version", &[], &["prod"]).await;
+
+    let (status, body) = server.get("/api/v1/datasets").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["orders"], "re-emit must not duplicate");
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(body["description"], "Second version");
+    assert_eq!(strings(&body["tags"]), vec!["prod"]);
+}
+
+#[tokio::test]
+async fn test_emitted_path_and_format_are_canonical() {
+    let server = TestServer::start().await;
+    server
+        .emitter()
+        .emit_dataset(
+            "orders",
+            "S3:/Lake//orders/",
+            "PQ",
+            None,
+            None,
+            None,
+            None,
+            orders_schema(),
+            None,
+            vec![],
+            vec![],
+        )
+        .await
+        .expect("emit_dataset failed");
+
+    let (status, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["path"], "s3://lake/orders");
+    assert_eq!(body["format"], "parquet");
+
+    // Parquet has no time travel, so Delta metadata does not apply
+    let (status, body) = server.get("/api/v1/datasets/orders?include=delta").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("time travel"));
+}
+
+#[tokio::test]
+async fn test_unknown_dataset_returns_404() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Daily order facts", &[], &[]).await;
+
+    let (status, body) = server.get("/api/v1/datasets/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "DATASET_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_error_payloads_carry_codes() {
+    let server = TestServer::start().await;
+
+    // Plain-text extractor rejections are wrapped with a generic code
+    let resp = server
+        .http
+        .post(server.url("/api/v1/datasets"))
+        .header("content-type", "application/json")
+        .body("{not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+    assert!(body["error"].as_str().is_some_and(|e| !e.is_empty()));
+    assert!(body["request_id"].as_str().is_some_and(|id| !id.is_empty()));
+
+    let (status, body) = server.get("/api/v1/no-such-route").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_collection_envelope() {
+    let server = TestServer::start().await;
+    for name in ["customers", "orders", "payments"] {
+        emit(&server, name, "Envelope test dataset", &[], &[]).await;
+    }
+
+    // Bare arrays unless asked, still honouring limit and offset
+    let (status, all) = server.get("/api/v1/datasets").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(all.as_array().unwrap().len(), 3);
+    let (status, body) = server.get("/api/v1/datasets?limit=1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    let (_, body) = server.get("/api/v1/datasets?limit=1&offset=1").await;
+    assert_eq!(body, serde_json::json!([all[1].clone()]));
+
+    let (status, body) = server
+        .get("/api/v1/datasets?envelope=true&limit=2&offset=1")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        body["meta"],
+        serde_json::json!({"total": 3, "limit": 2, "offset": 1})
+    );
+    assert!(body["links"].get("next").is_none());
+    let prev = body["links"]["prev"].as_str().unwrap();
+    assert!(prev.ends_with("/api/v1/datasets?envelope=true&limit=2&offset=0"));
+
+    let (status, body) = server.get("/api/v1/datasets?envelope=yes").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+}
+
+#[tokio::test]
+async fn test_list_datasets_filters_and_sort() {
+    let server = TestServer::start().await;
+    emit(&server, "customers", "Customer master", &[], &["pii"]).await;
+    emit(&server, "orders", "Order events", &[], &[]).await;
+    emit(&server, "payments", "Payment events", &[], &["pii"]).await;
+
+    let (status, body) = server
+        .get("/api/v1/datasets?tag=pii&sort_by=name&sort_dir=desc")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["payments", "customers"]);
+
+    let (status, body) = server
+        .get("/api/v1/datasets?owner=data-team@example.com&format=PARQUET&sort_by=name")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["customers", "orders", "payments"]);
+
+    let (_, body) = server.get("/api/v1/datasets?owner=someone-else").await;
+    assert!(body.as_array().unwrap().is_empty());
+    let (_, body) = server
+        .get("/api/v1/datasets?created_after=2000-01-01&created_before=2100-01-01")
+        .await;
+    assert_eq!(body.as_array().unwrap().len(), 3);
+    let (_, body) = server
+        .get("/api/v1/datasets?created_before=2000-01-01")
+        .await;
+    assert!(body.as_array().unwrap().is_empty());
+
+    for bad in [
+        "sort_by=owner",
+        "sort_dir=up",
+        "created_after=yesterday",
+        "owner=me",
+    ] {
+        let (status, _) = server.get(&format!("/api/v1/datasets?{}", bad)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", bad);
+    }
+}
+
+#[tokio::test]
+async fn test_list_datasets_includes() {
+    let server = TestServer::start().await;
+    emit(
+        &server,
+        "customers",
+        "Customer master",
+        &[],
+        &["pii", "crm"],
+    )
+    .await;
+    emit(&server, "orders", "Order events", &[], &[]).await;
+
+    let (status, body) = server
+        .get("/api/v1/datasets?include=tags,quality_summary&sort_by=name")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["tags"], serde_json::json!(["crm", "pii"]));
+    assert_eq!(body[1]["tags"], serde_json::json!([]));
+    assert_eq!(
+        body[0]["quality_summary"],
+        serde_json::json!({"overall_score": null, "last_computed": null})
+    );
+
+    // Included keys survive a sparse fieldset
+    let (_, body) = server
+        .get("/api/v1/datasets?include=tags&fields=name&sort_by=name")
+        .await;
+    let keys: Vec<_> = body[0].as_object().unwrap().keys().collect();
+    assert_eq!(keys, vec!["name", "tags"]);
+
+    let (_, body) = server.get("/api/v1/datasets").await;
+    assert!(body[0].get("tags").is_none());
+    let (status, _) = server.get("/api/v1/datasets?include=lineage").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// Field descriptions, business names, and glossary links set through the
+/// API survive a re-emit.
+#[tokio::test]
+async fn test_field_metadata_survives_reemit() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Order events", &[], &[]).await;
+    let (status, _) = server
+        .post(
+            "/api/v1/glossary",
+            Some(serde_json::json!({"term": "Customer"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = server
+        .patch(
+            "/api/v1/datasets/orders/fields/customer_id",
+            serde_json::json!({
+                "description": "Buyer of the order",
+                "business_name": "Customer ID",
+                "glossary_terms": ["Customer"]
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["business_name"], "Customer ID");
+    assert_eq!(body["ordinal"], 1);
+
+    // Unknown fields and terms are rejected
+    let (status, _) = server
+        .patch(
+            "/api/v1/datasets/orders/fields/missing",
+            serde_json::json!({"description": "x"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = server
+        .patch(
+            "/api/v1/datasets/orders/fields/amount",
+            serde_json::json!({"glossary_terms": ["Revenue"]}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    emit(&server, "orders", "Order events v2", &[], &[]).await;
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    let field = &body["fields"][1];
+    assert_eq!(field["name"], "customer_id");
+    assert_eq!(field["description"], "Buyer of the order");
+    assert_eq!(field["business_name"], "Customer ID");
+    assert_eq!(strings(&field["glossary_terms"]), vec!["Customer"]);
+
+    // Empty values clear
+    let (_, body) = server
+        .patch(
+            "/api/v1/datasets/orders/fields/customer_id",
+            serde_json::json!({"business_name": "", "glossary_terms": []}),
+        )
+        .await;
+    assert!(body.get("business_name").is_none());
+    assert!(body.get("glossary_terms").is_none());
+    assert_eq!(body["description"], "Buyer of the order");
+}
+
+// ============================================================================
+// Lineage Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_emitted_lineage() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw order events", &[], &[]).await;
+    emit(&server, "orders", "Cleaned orders", &["raw_orders"], &[]).await;
+
+    let (status, body) = server.get("/api/v1/datasets/orders?include=lineage").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(strings(&body["upstream_datasets"]), vec!["raw_orders"]);
+    assert_eq!(strings(&body["lineage"]["upstream"]), vec!["raw_orders"]);
+
+    let (status, body) = server
+        .get("/api/v1/datasets/raw_orders?include=lineage")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(strings(&body["downstream_datasets"]), vec!["orders"]);
+    assert_eq!(strings(&body["lineage"]["downstream"]), vec!["orders"]);
+}
+
+#[tokio::test]
+async fn test_impact_analysis() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw order events", &[], &[]).await;
+    emit(
+        &server,
+        "orders",
+        "Cleaned orders",
+        &["raw_orders"],
+        &["pii"],
+    )
+    .await;
+    emit(&server, "revenue", "Revenue mart", &["orders"], &[]).await;
+
+    let (status, body) = server.get("/api/v1/datasets/raw_orders/impact").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body["downstream"]), vec!["orders", "revenue"]);
+    assert_eq!(body["downstream"][0]["hops"], 1);
+    assert_eq!(strings(&body["downstream"][0]["tags"]), vec!["pii"]);
+    assert_eq!(body["downstream"][1]["via"], "orders");
+    assert_eq!(body["owners"][0]["owner"], "data-team@example.com");
+    assert_eq!(
+        strings(&body["owners"][0]["datasets"]),
+        vec!["orders", "revenue"]
+    );
+
+    let (status, _) = server.get("/api/v1/datasets/missing/impact").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_lineage_cycles_rejected() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw order events", &[], &[]).await;
+    emit(&server, "orders", "Cleaned orders", &["raw_orders"], &[]).await;
+
+    let (status, body) = server
+        .post(
+            "/api/v1/lineage",
+            Some(serde_json::json!({
+                "edges": [{"upstream": "orders", "downstream": "raw_orders"}]
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["failed"], 1);
+    assert_eq!(
+        body["results"][0]["error"],
+        "Lineage edge 'orders' -> 'raw_orders' would create a cycle: raw_orders -> orders -> raw_orders"
+    );
+
+    let (status, body) = server.get("/api/v1/lineage/cycles").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["count"], 0);
+}
+
+// ============================================================================
+// Search Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_emitted_dataset_is_searchable() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Revenue by order", &[], &["finance"]).await;
+    emit(&server, "clicks", "Website clickstream", &[], &["web"]).await;
+
+    let (status, body) = server.get("/api/v1/search?q=revenue").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["orders"]);
+
+    let (status, body) = server.get("/api/v1/search?q=clickstream").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), vec!["clicks"]);
+}
+
+#[tokio::test]
+async fn test_search_index_follows_reemit() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Legacy pipeline output", &[], &[]).await;
+    emit(&server, "orders", "Revenue by order", &[], &[]).await;
+
+    let (_, body) = server.get("/api/v1/search?q=legacy").await;
+    assert!(names(&body).is_empty(), "stale description still indexed");
+
+    let (_, body) = server.get("/api/v1/search?q=revenue").await;
+    assert_eq!(names(&body), vec!["orders"]);
+}
+
+// ============================================================================
+// Quality Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_quality_for_emitted_dataset() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Daily order facts", &[], &[]).await;
+
+    // No scores until some are recorded
+    let (status, _) = server.get("/api/v1/datasets/orders/quality").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Computed scores need a Delta location, which the emitter does not record
+    let (status, _) = server.post("/api/v1/datasets/orders/quality", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let metric = serde_json::json!({
+        "completeness_score": 0.5,
+        "overall_score": 0.6,
+        "row_count": 1_000
+    });
+    let (status, created) = server
+        .post("/api/v1/datasets/orders/quality/metrics", Some(metric))
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {}", created);
+
+    let (status, latest) = server.get("/api/v1/datasets/orders/quality").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(latest["dataset_name"], "orders");
+    assert_eq!(latest["overall_score"], 0.6);
+
+    let (status, body) = server.get("/api/v1/datasets/orders?include=quality").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["quality"]["overall_score"], 0.6);
+
+    let (status, body) = server.get("/api/v1/quality/unhealthy?threshold=0.7").await;
+    assert_eq!(status, StatusCode::OK);
+    let unhealthy = body["datasets"].as_array().unwrap();
+    assert_eq!(unhealthy.len(), 1);
+    assert_eq!(unhealthy[0]["dataset_name"], "orders");
+}
+
+// ============================================================================
+// Concurrent Write Tests
+// ============================================================================
+
+/// Writers of each kind in the stress tests
+const CONCURRENT_WRITERS: usize = 24;
+
+/// Emitters and API clients writing distinct datasets at the same time must
+/// all succeed, without lost datasets or duplicate fields.
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_concurrent_emitters_and_api_writers() {
+    let server = TestServer::start().await;
+
+    let mut tasks = Vec::new();
+    for i in 0..CONCURRENT_WRITERS {
+        let emitter = server.emitter();
+        tasks.push(tokio::spawn(async move {
+            emitter
+                .emit_dataset(
+                    &format!("emitted_{}", i),
+                    &format!("s3://lake/emitted_{}", i),
+                    "parquet",
+                    Some("Emitted concurrently"),
+                    None,
+                    Some("sales"),
+                    None,
+                    orders_schema(),
+                    None,
+                    vec![],
+                    vec!["stress".to_string()],
+                )
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("emitter {} failed: {}", i, e))
+        }));
+
+        let http = server.http.clone();
+        let url = server.url("/api/v1/datasets");
+        tasks.push(tokio::spawn(async move {
+            let body = serde_json::json!({
+                "name": format!("api_{}", i),
+                "path": format!("s3://lake/api_{}", i),
+                "format": "parquet",
+                "tags": ["stress"]
+            });
+            let resp = http.post(url).json(&body).send().await.unwrap();
+            match resp.status() {
+                StatusCode::CREATED => Ok(()),
+                status => Err(format!(
+                    "API writer {} failed with {}: {}",
+                    i,
+                    status,
+                    resp.text().await.unwrap_or_default()
+                )),
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let (status, body) = server.get("/api/v1/datasets?limit=1000").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body).len(), CONCURRENT_WRITERS * 2);
+
+    for i in [0, CONCURRENT_WRITERS - 1] {
+        let (_, body) = server.get(&format!("/api/v1/datasets/emitted_{}", i)).await;
+        assert_eq!(
+            names(&body["fields"]),
+            vec!["order_id", "customer_id", "amount"]
+        );
+        let (_, body) = server.get(&format!("/api/v1/datasets/api_{}", i)).await;
+        assert_eq!(strings(&body["tags"]), vec!["stress"]);
+    }
+}
+
+/// Concurrent writes to one dataset must all land: every tag added through
+/// the API is kept, and racing re-emits leave a single consistent dataset.
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_concurrent_writes_to_one_dataset() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Initial", &[], &[]).await;
+
+    let mut tasks = Vec::new();
+    for i in 0..CONCURRENT_WRITERS {
+        let http = server.http.clone();
+        let url = server.url("/api/v1/datasets/orders/tags");
+        tasks.push(tokio::spawn(async move {
+            let body = serde_json::json!({ "tags": [format!("tag_{:02}", i)] });
+            let resp = http.post(url).json(&body).send().await.unwrap();
+            match resp.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(format!(
+                    "tag writer {} failed with {}: {}",
+                    i,
+                    status,
+                    resp.text().await.unwrap_or_default()
+                )),
+            }
+        }));
+    }
+    for i in 0..CONCURRENT_WRITERS / 2 {
+        let emitter = server.emitter();
+        tasks.push(tokio::spawn(async move {
+            emitter
+                .emit_dataset(
+                    "shared",
+                    "s3://lake/shared",
+                    "parquet",
+                    Some(&format!("Version {}", i)),
+                    None,
+                    Some("sales"),
+                    None,
+                    orders_schema(),
+                    None,
+                    vec!["orders".to_string()],
+                    vec![],
+                )
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("emitter {} failed: {}", i, e))
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    let mut tags = strings(&body["tags"]);
+    tags.sort();
+    let expected: Vec<String> = (0..CONCURRENT_WRITERS)
+        .map(|i| format!("tag_{:02}", i))
+        .collect();
+    assert_eq!(tags, expected, "tag updates were lost");
+
+    let (_, body) = server.get("/api/v1/datasets?limit=1000").await;
+    let mut all = names(&body);
+    all.sort();
+    assert_eq!(all, vec!["orders", "shared"]);
+
+    let (_, body) = server.get("/api/v1/datasets/shared").await;
+    assert_eq!(
+        names(&body["fields"]),
+        vec!["order_id", "customer_id", "amount"]
+    );
+    assert_eq!(strings(&body["upstream_datasets"]), vec!["orders"]);
+    assert!(body["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("Version "));
+}
+
+// ============================================================================
+// Composite Create Tests
+// ============================================================================
+
+/// One create call writes the dataset with its fields, tags, lineage and
+/// glossary links, or nothing at all.
+#[tokio::test]
+async fn test_create_dataset_with_related_entities() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw order events", &[], &[]).await;
+    let (status, term) = server
+        .post(
+            "/api/v1/glossary",
+            Some(serde_json::json!({"term": "Revenue"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // An unknown glossary term rejects the whole request
+    let mut body = serde_json::json!({
+        "name": "orders",
+        "path": "s3://lake/orders",
+        "format": "parquet",
+        "tags": ["finance"],
+        "upstream_datasets": ["raw_orders"],
+        "glossary_terms": ["Revenue"],
+        "fields": [
+            {"name": "order_id", "data_type": "Int64", "nullable": false},
+            {"name": "amount", "data_type": "Float64", "glossary_terms": ["Gross Margin"]}
+        ]
+    });
+    let (status, _) = server.post("/api/v1/datasets", Some(body.clone())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    body["fields"][1]["glossary_terms"] = serde_json::json!(["Revenue"]);
+    let (status, _) = server.post("/api/v1/datasets", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body["fields"]), vec!["order_id", "amount"]);
+    assert_eq!(body["fields"][0]["nullable"], false);
+    assert_eq!(body["fields"][1]["nullable"], true);
+    assert_eq!(strings(&body["tags"]), vec!["finance"]);
+    assert_eq!(strings(&body["upstream_datasets"]), vec!["raw_orders"]);
+
+    let (status, links) = server
+        .get(&format!("/api/v1/glossary/{}/links", term["id"]))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let linked: Vec<(Value, Value)> = links
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| (l["dataset_name"].clone(), l["field_name"].clone()))
+        .collect();
+    assert_eq!(linked.len(), 2);
+    assert!(linked.contains(&(serde_json::json!("orders"), Value::Null)));
+    assert!(linked.contains(&(Value::Null, serde_json::json!("amount"))));
+}
+
+/// Datasets registered without fields can read their schema from storage.
+#[tokio::test]
+async fn test_resolve_schema_from_parquet_footer() {
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::parquet::arrow::ArrowWriter;
+
+    let server = TestServer::start().await;
+    let data_dir = TempDir::new().unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "order_id",
+        DataType::Int64,
+        false,
+    )]));
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(vec![1]))]).unwrap();
+    let file = std::fs::File::create(data_dir.path().join("part-0.parquet")).unwrap();
+    let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let (status, _) = server
+        .post(
+            "/api/v1/datasets",
+            Some(serde_json::json!({
+                "name": "orders",
+                "path": data_dir.path().to_str().unwrap(),
+                "format": "parquet"
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["fields"].as_array().unwrap().is_empty());
+    assert!(body.get("schema_resolution").is_none());
+
+    let (status, body) = server
+        .get("/api/v1/datasets/orders?resolve_schema=true&persist_schema=true")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body["fields"]), vec!["order_id"]);
+    assert_eq!(body["fields"][0]["data_type"], "Int64");
+    assert_eq!(body["schema_resolution"]["source"], "parquet");
+    assert_eq!(body["schema_resolution"]["persisted"], true);
+
+    // Storing the fields is audited (events are flushed in the background)
+    let mut audited = Value::Null;
+    for _ in 0..50 {
+        let (_, body) = server
+            .get("/api/v1/audit?entity_type=dataset&entity_id=orders&action=update")
+            .await;
+        if let Some(entry) = body["entries"].as_array().and_then(|e| e.first()) {
+            audited = entry.clone();
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(
+        audited["new_values"]["fields"],
+        serde_json::json!(["order_id"])
+    );
+    assert_eq!(audited["context"]["schema_resolution"]["source"], "parquet");
+
+    // Stored fields are returned as they are from now on
+    let (status, body) = server
+        .get("/api/v1/datasets/orders?resolve_schema=true")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body["fields"]), vec!["order_id"]);
+    assert!(body.get("schema_resolution").is_none());
+
+    let (status, _) = server
+        .get("/api/v1/datasets/orders?persist_schema=true")
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+// ============================================================================
+// JSON Patch Tests
+// ============================================================================
+
+/// PATCH applies RFC 6902 operations to description, tags and properties,
+/// all or nothing.
+#[tokio::test]
+async fn test_json_patch_dataset() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Order events", &[], &["finance", "raw"]).await;
+
+    let (status, body) = server
+        .json_patch(
+            "/api/v1/datasets/orders",
+            serde_json::json!([
+                {"op": "replace", "path": "/description", "value": "Cleaned order events"},
+                {"op": "add", "path": "/tags/-", "value": "curated"},
+                {"op": "remove", "path": "/tags/1"},
+                {"op": "add", "path": "/properties/warehouse", "value": "wh_small"}
+            ]),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(strings(&body["tags"]), vec!["curated", "finance"]);
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(body["description"], "Cleaned order events");
+    assert_eq!(strings(&body["tags"]), vec!["curated", "finance"]);
+    let (_, body) = server.get("/api/v1/datasets/orders/custom-metadata").await;
+    assert_eq!(body["custom_metadata"]["warehouse"], "wh_small");
+
+    // A disallowed path rejects the whole patch
+    let (status, _) = server
+        .json_patch(
+            "/api/v1/datasets/orders",
+            serde_json::json!([
+                {"op": "remove", "path": "/description"},
+                {"op": "replace", "path": "/path", "value": "s3://elsewhere"}
+            ]),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(body["description"], "Cleaned order events");
+
+    // Plain JSON bodies belong to PUT
+    let resp = server
+        .http
+        .patch(server.url("/api/v1/datasets/orders"))
+        .json(&serde_json::json!({"description": "x"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+/// PATCH with a JSON Merge Patch updates only the given fields, like PUT.
+#[tokio::test]
+async fn test_merge_patch_dataset() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Order events", &[], &["finance"]).await;
+
+    let (status, body) = server
+        .merge_patch(
+            "/api/v1/datasets/orders",
+            serde_json::json!({"owner": "sales@example.com", "domain": "sales"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["owner"], "sales@example.com");
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(body["owner"], "sales@example.com");
+    assert_eq!(body["domain"], "sales");
+    assert_eq!(body["description"], "Order events");
+    assert_eq!(strings(&body["tags"]), vec!["finance"]);
+
+    // Nulls, unknown members, and custom metadata are rejected
+    for patch in [
+        serde_json::json!({"owner": null}),
+        serde_json::json!({"ownr": "x"}),
+        serde_json::json!({"custom_metadata": {"warehouse": "wh_small"}}),
+        serde_json::json!(["owner"]),
+    ] {
+        let (status, _) = server
+            .merge_patch("/api/v1/datasets/orders", patch.clone())
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", patch);
+    }
+    let (status, _) = server
+        .merge_patch(
+            "/api/v1/datasets/missing",
+            serde_json::json!({"owner": "x"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// PUT and PATCH honour `If-Match` with the catalog version from the ETag.
+#[tokio::test]
+async fn test_update_dataset_if_match() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Order events", &[], &[]).await;
+
+    let resp = server
+        .http
+        .get(server.url("/api/v1/datasets/orders"))
+        .send()
+        .await
+        .unwrap();
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+
+    let put = |if_match: String, owner: &str| {
+        server
+            .http
+            .put(server.url("/api/v1/datasets/orders"))
+            .header("if-match", if_match)
+            .json(&serde_json::json!({"owner": owner}))
+            .send()
+    };
+    let resp = put(etag.clone(), "analytics@example.com").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let new_etag = resp.headers()["etag"].to_str().unwrap().to_string();
+    assert_ne!(new_etag, etag);
+
+    // A writer holding the old version loses
+    let resp = put(etag.clone(), "finance@example.com").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+    let resp = server
+        .http
+        .patch(server.url("/api/v1/datasets/orders"))
+        .header("content-type", "application/json-patch+json")
+        .header("if-match", etag.clone())
+        .body(r#"[{"op": "replace", "path": "/description", "value": "x"}]"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+    let resp = server
+        .http
+        .patch(server.url("/api/v1/datasets/orders"))
+        .header("content-type", "application/merge-patch+json")
+        .header("if-match", etag)
+        .body(r#"{"owner": "finance@example.com"}"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    assert_eq!(body["owner"], "analytics@example.com");
+    assert_eq!(body["description"], "Order events");
+
+    // Emitter writes advance the version too
+    emit(&server, "orders", "Order events", &[], &[]).await;
+    let resp = put(new_etag, "finance@example.com").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+}
+
+#[tokio::test]
+async fn test_consumers_in_impact_analysis() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw orders", &[], &[]).await;
+    emit(&server, "orders", "Orders", &["raw_orders"], &[]).await;
+
+    let consumer = serde_json::json!({
+        "name": "billing-service",
+        "contact": "#billing-oncall",
+        "read_frequency": "hourly",
+        "sla": "refreshed by 06:00 UTC"
+    });
+    let (status, body) = server
+        .post("/api/v1/datasets/orders/consumers", Some(consumer.clone()))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["read_frequency"], "hourly");
+    let (status, _) = server
+        .post("/api/v1/datasets/orders/consumers", Some(consumer))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = server
+        .post(
+            "/api/v1/datasets/orders/consumers",
+            Some(serde_json::json!({"name": "report", "read_frequency": "sometimes"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = server.get("/api/v1/datasets/orders/consumers").await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (_, body) = server.get("/api/v1/datasets/raw_orders/impact").await;
+    let consumers = body["consumers"].as_array().unwrap();
+    assert_eq!(consumers.len(), 1);
+    assert_eq!(consumers[0]["name"], "billing-service");
+    assert_eq!(consumers[0]["via"], "orders");
+
+    assert_eq!(
+        server
+            .delete("/api/v1/datasets/orders/consumers?consumer=billing-service")
+            .await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        server
+            .delete("/api/v1/datasets/orders/consumers?consumer=billing-service")
+            .await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        server.delete("/api/v1/datasets/orders/consumers").await,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_reemit_keeps_manual_tags() {
+    let server = TestServer::start().await;
+    emit(&server, "orders", "Order events", &[], &["raw", "daily"]).await;
+    let (status, _) = server
+        .post(
+            "/api/v1/datasets/orders/tags",
+            Some(serde_json::json!({"tags": ["gold", "raw"]})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The pipeline drops "daily" and "raw", but "raw" was also added by hand
+    emit(&server, "orders", "Order events v2", &[], &["hourly"]).await;
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    let mut tags = strings(&body["tags"]);
+    tags.sort();
+    assert_eq!(tags, vec!["gold", "hourly", "raw"]);
+}
+
+#[tokio::test]
+async fn test_archived_datasets_hidden_from_listings() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw order events", &[], &[]).await;
+    emit(&server, "orders", "Order events", &["raw_orders"], &[]).await;
+
+    let (status, body) = server
+        .post("/api/v1/datasets/raw_orders/archive", None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "archived");
+    assert!(body["archived_at"].is_string());
+
+    let (_, body) = server.get("/api/v1/datasets").await;
+    assert_eq!(names(&body), vec!["orders"]);
+    let (_, body) = server.get("/api/v1/search?q=order").await;
+    assert_eq!(names(&body), vec!["orders"]);
+    let (_, body) = server.get("/api/v1/datasets?include_archived=true").await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    let (_, body) = server
+        .get("/api/v1/search?q=order&include_archived=true")
+        .await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    // Still readable by name, with its lineage
+    let (status, body) = server.get("/api/v1/datasets/raw_orders").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["archived_at"].is_string());
+    let (_, body) = server.get("/api/v1/datasets/raw_orders/impact").await;
+    assert!(body.to_string().contains("\"orders\""));
+
+    let (status, _) = server.get("/api/v1/datasets?include_archived=yes").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    assert_eq!(
+        server.delete("/api/v1/datasets/raw_orders/archive").await,
+        StatusCode::OK
+    );
+    let (_, body) = server.get("/api/v1/datasets").await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    assert_eq!(
+        server.delete("/api/v1/datasets/missing/archive").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_create_dataset_write_report() {
+    let server = TestServer::start().await;
+    emit(&server, "raw_orders", "Raw order events", &[], &[]).await;
+
+    let (status, body) = server
+        .post(
+            "/api/v1/datasets",
+            Some(serde_json::json!({
+                "name": "orders",
+                "path": "s3://lake/orders",
+                "format": "parquet",
+                "tags": ["gold", "gold", "pii"],
+                "upstream_datasets": ["raw_orders", "missing", "raw_orders"]
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let report = &body["write_report"];
+    assert_eq!(strings(&report["tags"]["inserted"]), vec!["gold", "pii"]);
+    assert_eq!(strings(&report["tags"]["deduplicated"]), vec!["gold"]);
+    assert_eq!(
+        strings(&report["upstreams"]["inserted"]),
+        vec!["raw_orders"]
+    );
+    assert_eq!(strings(&report["upstreams"]["skipped"]), vec!["missing"]);
+    assert_eq!(
+        strings(&report["upstreams"]["deduplicated"]),
+        vec!["raw_orders"]
+    );
+
+    let (_, body) = server.get("/api/v1/datasets/orders").await;
+    assert!(body.get("write_report").is_none());
+    let mut tags = strings(&body["tags"]);
+    tags.sort();
+    assert_eq!(tags, vec!["gold", "pii"]);
+}
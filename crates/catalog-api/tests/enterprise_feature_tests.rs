@@ -267,6 +267,7 @@ mod quality_framework {
             freshness_score: None,
             file_health_score: None,
             overall_score: None,
+            propagated_score: None,
             details: QualityDetails::default(),
         };
 
@@ -283,6 +284,7 @@ mod quality_framework {
             freshness_score: Some(0.85),
             file_health_score: Some(0.90),
             overall_score: Some(0.90),
+            propagated_score: None,
             details: QualityDetails {
                 row_count: Some(1000),
                 file_count: Some(10),
@@ -307,6 +309,7 @@ mod quality_framework {
             freshness_score: None, // No freshness config
             file_health_score: Some(0.80),
             overall_score: Some(0.875), // Computed from available
+            propagated_score: None,
             details: QualityDetails::default(),
         };
 
@@ -328,6 +331,7 @@ mod quality_framework {
             freshness_sla_secs: Some(3600),
             staleness_secs: Some(1800),
             last_modified: Some("2025-01-15T10:00:00Z".to_string()),
+            upstream_contributions: None,
         };
 
         assert_eq!(details.row_count, Some(10000));
@@ -345,6 +349,7 @@ mod quality_framework {
             freshness_score: None,
             file_health_score: Some(0.80),
             overall_score: Some(0.875),
+            propagated_score: None,
             details: QualityDetails {
                 row_count: Some(1000),
                 ..Default::default()
@@ -367,6 +372,7 @@ mod quality_framework {
             freshness_score: Some(1.0),
             file_health_score: Some(1.0),
             overall_score: Some(1.0),
+            propagated_score: None,
             details: QualityDetails::default(),
         };
 
@@ -377,6 +383,7 @@ mod quality_framework {
             freshness_score: Some(0.0),
             file_health_score: Some(0.0),
             overall_score: Some(0.0),
+            propagated_score: None,
             details: QualityDetails::default(),
         };
 
@@ -396,6 +403,7 @@ mod quality_framework {
             freshness_sla_secs: None,
             staleness_secs: None,
             last_modified: None,
+            upstream_contributions: None,
         };
 
         assert_eq!(details.row_count, Some(0));
@@ -562,6 +570,7 @@ mod feature_combinations {
             freshness_score: None,
             file_health_score: None,
             overall_score: Some(1.0),
+            propagated_score: None,
             details: QualityDetails::default(),
         };
     }
@@ -19,6 +19,7 @@ mod rbac_tests {
     };
     use metafuse_catalog_api::{
         control_plane::TenantRole,
+        error_codes::ErrorCode,
         multi_tenant::{require_delete_permission, require_write_permission, RbacErrorResponse},
         tenant_resolver::{ResolvedTenant, TenantSource},
     };
@@ -255,6 +256,7 @@ mod rbac_tests {
     fn test_rbac_error_response_serialization() {
         let error = RbacErrorResponse {
             error: "Write permission denied".to_string(),
+            code: ErrorCode::Forbidden,
             request_id: "req-abc-123".to_string(),
         };
 
@@ -262,6 +264,7 @@ mod rbac_tests {
         let parsed: Value = serde_json::from_str(&json).expect("Should parse");
 
         assert_eq!(parsed["error"], "Write permission denied");
+        assert_eq!(parsed["code"], "FORBIDDEN");
         assert_eq!(parsed["request_id"], "req-abc-123");
     }
 
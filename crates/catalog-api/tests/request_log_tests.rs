@@ -0,0 +1,177 @@
+//! Request Log Tests
+//!
+//! Tests that requests served by the full router are logged and can be read
+//! back through `GET /api/v1/admin/requests`, which checks where the request
+//! log middleware sits among the server's layers: inside request IDs and
+//! tenant resolution, outside request timeouts.
+//!
+//! Run with: `cargo test -p metafuse-catalog-api --features "api-keys,test-utils" --test request_log_tests`
+
+// This test module requires api-keys, test-utils and usage-analytics features
+#![cfg(all(
+    feature = "api-keys",
+    feature = "test-utils",
+    feature = "usage-analytics"
+))]
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, Request, StatusCode},
+    Router,
+};
+use metafuse_catalog_api::control_plane::{ControlPlane, TenantRole};
+use metafuse_catalog_api::multi_tenant::MultiTenantConfig;
+use metafuse_catalog_api::request_log::RequestLogConfig;
+use metafuse_catalog_api::test_utils::{test_audit_context, TestTenantBuilder};
+use metafuse_catalog_api::timeouts::TimeoutConfig;
+use metafuse_catalog_api::{build_router, ServerConfig};
+use metafuse_catalog_core::migrations;
+use metafuse_catalog_storage::backend_from_uri;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::ServiceExt;
+
+const ADMIN_KEY: &str = "request-log-admin-key";
+
+/// Send a GET request; returns the status, request ID and JSON body.
+async fn get(app: &Router, uri: &str, key: Option<&str>) -> (StatusCode, String, Value) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(key) = key {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", key));
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let request_id = response
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        request_id,
+        serde_json::from_slice(&bytes).unwrap_or_default(),
+    )
+}
+
+/// Logged requests matching `query`, once at least `count` have been flushed.
+async fn logged(app: &Router, query: &str, count: usize) -> Vec<Value> {
+    for _ in 0..50 {
+        let uri = format!("/api/v1/admin/requests?{}", query);
+        let (status, _, body) = get(app, &uri, Some(ADMIN_KEY)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let requests = body["requests"].as_array().cloned().unwrap_or_default();
+        if requests.len() >= count {
+            return requests;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("{} requests matching '{}' were not flushed", count, query);
+}
+
+/// Tenant requests are logged to the tenant's catalog, timed out requests
+/// are kept, and excluded routes are not logged
+#[tokio::test]
+async fn test_requests_are_logged_through_the_router() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let template = format!("{}/{{tenant_id}}.db", dir.path().display());
+    let control_plane_db = dir.path().join("control_plane.db");
+
+    // This binary holds a single test, so setting process-wide config is safe
+    std::env::set_var("METAFUSE_ADMIN_KEY", ADMIN_KEY);
+    std::env::set_var("METAFUSE_SQLITE_BUSY_TIMEOUT_MS", "2000");
+
+    let control_plane =
+        ControlPlane::new(control_plane_db.display().to_string(), template.clone()).unwrap();
+    control_plane.initialize().await.unwrap();
+    control_plane
+        .create_tenant(
+            TestTenantBuilder::new("log-corp").build_request(),
+            test_audit_context(),
+        )
+        .await
+        .unwrap();
+    let key = control_plane
+        .create_tenant_api_key("log-corp", "viewer".to_string(), TenantRole::Viewer, None)
+        .await
+        .unwrap();
+
+    let tenant_path = template.replace("{tenant_id}", "log-corp");
+    let tenant_catalog = backend_from_uri(&tenant_path).unwrap();
+    tenant_catalog.initialize().await.unwrap();
+    let conn = tenant_catalog.get_connection().await.unwrap();
+    migrations::run_migrations(&conn).unwrap();
+    drop(conn);
+
+    let default_catalog =
+        backend_from_uri(dir.path().join("default.db").to_str().unwrap()).unwrap();
+    default_catalog.initialize().await.unwrap();
+    let config = ServerConfig {
+        run_migrations: true,
+        multi_tenant: MultiTenantConfig {
+            enabled: true,
+            storage_uri_template: template,
+            control_plane_db_path: control_plane_db.display().to_string(),
+            ..Default::default()
+        },
+        request_log: RequestLogConfig {
+            enabled: true,
+            flush_interval_secs: 1,
+            ..Default::default()
+        },
+        timeouts: TimeoutConfig {
+            routes: HashMap::from([("/api/v1/datasets/{name}".to_string(), 200)]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (app, _tasks) = build_router(&config, Arc::from(default_catalog))
+        .await
+        .unwrap();
+
+    let (status, _, body) = get(&app, "/health", Some(&key)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, listed_id, body) = get(&app, "/api/v1/datasets", Some(&key)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    // A writer holding the tenant catalog makes the read run past its budget
+    let lock = rusqlite::Connection::open(&tenant_path).unwrap();
+    lock.execute_batch("BEGIN EXCLUSIVE").unwrap();
+    let (status, timed_out_id, body) = get(&app, "/api/v1/datasets/orders", Some(&key)).await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT, "{}", body);
+    lock.execute_batch("COMMIT").unwrap();
+    drop(lock);
+
+    // The health check is excluded, so the tenant has two requests
+    let requests = logged(&app, "tenant_id=log-corp", 2).await;
+    assert_eq!(requests.len(), 2, "{:?}", requests);
+    let listed = requests
+        .iter()
+        .find(|r| r["route"] == "/api/v1/datasets")
+        .expect("dataset list logged");
+    assert_eq!(listed["request_id"], listed_id.as_str());
+    assert_eq!(listed["tenant_id"], "log-corp");
+    assert_eq!(listed["status"], 200);
+    let timed_out = requests
+        .iter()
+        .find(|r| r["route"] == "/api/v1/datasets/{name}")
+        .expect("timed out request logged");
+    assert_eq!(timed_out["request_id"], timed_out_id.as_str());
+    assert_eq!(timed_out["path"], "/api/v1/datasets/orders");
+    assert_eq!(timed_out["status"], 504);
+
+    // Admin requests are logged to the default catalog
+    let requests = logged(&app, "tenant_id=default", 1).await;
+    assert!(requests
+        .iter()
+        .all(|r| r["route"] == "/api/v1/admin/requests"));
+}
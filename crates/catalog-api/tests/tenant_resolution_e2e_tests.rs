@@ -21,13 +21,18 @@ use axum::{
     routing::get,
     Json, Router,
 };
-use metafuse_catalog_api::control_plane::{ControlPlane, TenantRole};
+use metafuse_catalog_api::control_plane::{
+    ControlPlane, TenantRole, UpdateTenantFeatureFlagsRequest,
+};
+use metafuse_catalog_api::public_catalog::{PublicAccess, PublicCatalogConfig};
 use metafuse_catalog_api::tenant_resolver::{
     require_admin_permission, require_tenant_middleware, require_write_permission,
-    tenant_resolver_middleware, ResolvedTenant, TenantResolverConfig, TenantSource,
-    TENANT_ID_HEADER,
+    tenant_feature_flags_middleware, tenant_resolver_middleware, ResolvedTenant,
+    TenantResolverConfig, TenantSource, TENANT_ID_HEADER,
+};
+use metafuse_catalog_api::test_utils::{
+    test_audit_context_with_actor, TestApiKey, TestControlPlane, TestTenantBuilder,
 };
-use metafuse_catalog_api::test_utils::{TestApiKey, TestControlPlane, TestTenantBuilder};
 use serde_json::Value;
 use std::sync::Arc;
 use tower::ServiceExt;
@@ -84,6 +89,18 @@ async fn admin_handler(Extension(tenant): Extension<ResolvedTenant>) -> impl Int
     }))
 }
 
+/// Handler that reports whether the request was served as anonymous public access
+async fn public_catalog_handler(
+    Extension(tenant): Extension<ResolvedTenant>,
+    public_access: Option<Extension<PublicAccess>>,
+) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "tenant_id": tenant.tenant_id(),
+        "role": format!("{}", tenant.effective_role()),
+        "public": public_access.is_some(),
+    }))
+}
+
 // ============================================================================
 // Test Router Builders
 // ============================================================================
@@ -132,6 +149,40 @@ fn build_router_require_admin(control_plane: Arc<ControlPlane>) -> Router {
         .layer(Extension(control_plane))
 }
 
+/// Build a router mirroring the catalog routes with public catalog mode enabled
+fn build_router_public_catalog(
+    control_plane: Arc<ControlPlane>,
+    config: PublicCatalogConfig,
+) -> Router {
+    Router::new()
+        .route(
+            "/api/v1/datasets",
+            get(public_catalog_handler).post(public_catalog_handler),
+        )
+        .route("/api/v1/datasets/{name}", get(public_catalog_handler))
+        .route(
+            "/api/v1/datasets/{name}/schema",
+            get(public_catalog_handler),
+        )
+        .route("/api/v1/search", get(public_catalog_handler))
+        .layer(middleware::from_fn(require_tenant_middleware))
+        .layer(middleware::from_fn(tenant_resolver_middleware))
+        .layer(Extension(TenantResolverConfig::with_public_catalog(config)))
+        .layer(Extension(control_plane))
+}
+
+/// Build a router with per-tenant feature gating
+fn build_router_with_feature_flags(control_plane: Arc<ControlPlane>) -> Router {
+    Router::new()
+        .route("/api/v1/datasets/{name}", get(get_tenant_info))
+        .route("/api/v1/datasets/{name}/preview", get(get_tenant_info))
+        .route("/api/v1/datasets/{name}/usage", get(get_tenant_info))
+        .route("/api/v1/analytics/popular", get(get_tenant_info))
+        .layer(middleware::from_fn(tenant_feature_flags_middleware))
+        .layer(middleware::from_fn(tenant_resolver_middleware))
+        .layer(Extension(control_plane))
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -755,6 +806,222 @@ mod permission_guards {
     }
 }
 
+// ============================================================================
+// Public Catalog Mode Tests
+// ============================================================================
+
+mod public_catalog_mode {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_anonymous_read_routes_allowed() {
+        let cp = TestControlPlane::new().await.unwrap();
+        TestTenantBuilder::new("open-corp")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        for path in [
+            "/api/v1/datasets",
+            "/api/v1/datasets/orders",
+            "/api/v1/search?q=orders",
+        ] {
+            let config = PublicCatalogConfig::enabled();
+            let router = build_router_public_catalog(cp.control_plane().clone(), config);
+            let (status, json) = make_request(router, path, None, Some("open-corp")).await;
+
+            assert_eq!(status, StatusCode::OK, "{} should be public", path);
+            assert_eq!(json["tenant_id"], "open-corp");
+            assert_eq!(json["role"], "viewer");
+            assert_eq!(json["public"], true);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_non_discovery_route_rejected() {
+        let cp = TestControlPlane::new().await.unwrap();
+        TestTenantBuilder::new("open-corp")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let config = PublicCatalogConfig::enabled();
+        let router = build_router_public_catalog(cp.control_plane().clone(), config);
+        let (status, _) = make_request(
+            router,
+            "/api/v1/datasets/orders/schema",
+            None,
+            Some("open-corp"),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_write_rejected() {
+        let cp = TestControlPlane::new().await.unwrap();
+        TestTenantBuilder::new("open-corp")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let config = PublicCatalogConfig::enabled();
+        let router = build_router_public_catalog(cp.control_plane().clone(), config);
+        let request = Request::builder()
+            .uri("/api/v1/datasets")
+            .method("POST")
+            .header(TENANT_ID_HEADER, "open-corp")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_excluded_tenant_not_found() {
+        let cp = TestControlPlane::new().await.unwrap();
+        TestTenantBuilder::new("private-corp")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let mut config = PublicCatalogConfig::enabled();
+        config.excluded_tenants.insert("private-corp".to_string());
+        let router = build_router_public_catalog(cp.control_plane().clone(), config);
+
+        let (status, _) =
+            make_request(router, "/api/v1/datasets", None, Some("private-corp")).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_by_default() {
+        let cp = TestControlPlane::new().await.unwrap();
+        TestTenantBuilder::new("open-corp")
+            .build(&cp)
+            .await
+            .unwrap();
+
+        let config = PublicCatalogConfig::default();
+        let router = build_router_public_catalog(cp.control_plane().clone(), config);
+        let (status, _) = make_request(router, "/api/v1/datasets", None, Some("open-corp")).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_requests_are_not_public() {
+        let cp = TestControlPlane::new().await.unwrap();
+        TestTenantBuilder::new("open-corp")
+            .build(&cp)
+            .await
+            .unwrap();
+        let key = TestApiKey::editor(&cp, "open-corp").await.unwrap();
+
+        let config = PublicCatalogConfig::enabled();
+        let router = build_router_public_catalog(cp.control_plane().clone(), config);
+        let (status, json) =
+            make_request(router, "/api/v1/datasets", Some(&key.auth_header()), None).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["role"], "editor");
+        assert_eq!(json["public"], false);
+    }
+}
+
+// ============================================================================
+// Feature Gating Tests
+// ============================================================================
+
+mod feature_gating {
+    use super::*;
+
+    async fn tenant_without_preview_or_analytics(cp: &TestControlPlane) -> TestApiKey {
+        TestTenantBuilder::new("gated-corp")
+            .build(cp)
+            .await
+            .unwrap();
+        cp.control_plane()
+            .update_tenant_feature_flags(
+                "gated-corp",
+                UpdateTenantFeatureFlagsRequest {
+                    preview: Some(false),
+                    usage_analytics: Some(false),
+                    ..Default::default()
+                },
+                test_audit_context_with_actor("admin@test.com"),
+            )
+            .await
+            .unwrap();
+        TestApiKey::viewer(cp, "gated-corp").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_disabled_feature_routes_rejected() {
+        let cp = TestControlPlane::new().await.unwrap();
+        let key = tenant_without_preview_or_analytics(&cp).await;
+
+        for path in [
+            "/api/v1/datasets/orders/preview",
+            "/api/v1/datasets/orders/usage",
+            "/api/v1/analytics/popular",
+        ] {
+            let router = build_router_with_feature_flags(cp.control_plane().clone());
+            let (status, _) = make_request(router, path, Some(&key.auth_header()), None).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{} should be gated", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_datasets_named_like_gated_routes_allowed() {
+        let cp = TestControlPlane::new().await.unwrap();
+        let key = tenant_without_preview_or_analytics(&cp).await;
+
+        for path in [
+            "/api/v1/datasets/preview",
+            "/api/v1/datasets/usage",
+            "/api/v1/datasets/classifications",
+        ] {
+            let router = build_router_with_feature_flags(cp.control_plane().clone());
+            let (status, json) = make_request(router, path, Some(&key.auth_header()), None).await;
+            assert_eq!(status, StatusCode::OK, "{} should not be gated", path);
+            assert_eq!(json["tenant_id"], "gated-corp");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gating_under_base_path() {
+        let cp = TestControlPlane::new().await.unwrap();
+        let key = tenant_without_preview_or_analytics(&cp).await;
+        let router = || {
+            Router::new().nest(
+                "/catalog",
+                build_router_with_feature_flags(cp.control_plane().clone()),
+            )
+        };
+
+        let (status, _) = make_request(
+            router(),
+            "/catalog/api/v1/datasets/orders/usage",
+            Some(&key.auth_header()),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = make_request(
+            router(),
+            "/catalog/api/v1/datasets/usage",
+            Some(&key.auth_header()),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
+
 // ============================================================================
 // ResolvedTenant Unit Tests
 // ============================================================================
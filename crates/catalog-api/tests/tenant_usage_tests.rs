@@ -0,0 +1,115 @@
+//! Tenant Usage Endpoint Tests
+//!
+//! Tests `GET /api/v1/usage` and `GET /api/v1/tenant/usage` against a
+//! multi-tenant server, including which datasets count against quotas.
+//!
+//! Run with: `cargo test -p metafuse-catalog-api --features "api-keys,test-utils" --test tenant_usage_tests`
+
+// This test module requires both api-keys and test-utils features
+#![cfg(all(feature = "api-keys", feature = "test-utils"))]
+
+use axum::{
+    body::Body,
+    http::{header::AUTHORIZATION, Request, StatusCode},
+    Router,
+};
+use metafuse_catalog_api::control_plane::{ControlPlane, TenantRole};
+use metafuse_catalog_api::multi_tenant::MultiTenantConfig;
+use metafuse_catalog_api::test_utils::{test_audit_context, TestTenantBuilder};
+use metafuse_catalog_api::{build_router, ServerConfig};
+use metafuse_catalog_core::migrations;
+use metafuse_catalog_storage::backend_from_uri;
+use serde_json::Value;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+async fn get(app: &Router, uri: &str, key: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", key))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or_default())
+}
+
+/// Only live, non-sandbox datasets count against the tenant's quotas
+#[tokio::test]
+async fn test_usage_excludes_trashed_and_sandbox_datasets() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let template = format!("{}/{{tenant_id}}.db", dir.path().display());
+    let control_plane_db = dir.path().join("control_plane.db");
+
+    let control_plane =
+        ControlPlane::new(control_plane_db.display().to_string(), template.clone()).unwrap();
+    control_plane.initialize().await.unwrap();
+    let request = TestTenantBuilder::new("usage-corp")
+        .quota_max_datasets(4)
+        .quota_max_storage_bytes(1000)
+        .build_request();
+    control_plane
+        .create_tenant(request, test_audit_context())
+        .await
+        .unwrap();
+    let key = control_plane
+        .create_tenant_api_key("usage-corp", "viewer".to_string(), TenantRole::Viewer, None)
+        .await
+        .unwrap();
+
+    let tenant_catalog = backend_from_uri(&template.replace("{tenant_id}", "usage-corp")).unwrap();
+    tenant_catalog.initialize().await.unwrap();
+    let conn = tenant_catalog.get_connection().await.unwrap();
+    migrations::run_migrations(&conn).unwrap();
+    conn.execute_batch(
+        "INSERT INTO datasets (name, path, format, size_bytes, created_at, last_updated) VALUES
+            ('orders', '/lake/orders', 'parquet', 300, datetime('now'), datetime('now')),
+            ('customers', '/lake/customers', 'parquet', 400, datetime('now'), datetime('now')),
+            ('old_orders', '/lake/old_orders', 'parquet', 5000, datetime('now'), datetime('now')),
+            ('ci_42.orders', '/lake/ci/orders', 'parquet', 5000, datetime('now'), datetime('now'));
+         UPDATE datasets SET deleted_at = datetime('now') WHERE name = 'old_orders';
+         INSERT INTO namespaces (name, expires_at) VALUES ('ci_42', datetime('now', '+1 day'));",
+    )
+    .unwrap();
+    drop(conn);
+
+    let default_catalog =
+        backend_from_uri(dir.path().join("default.db").to_str().unwrap()).unwrap();
+    default_catalog.initialize().await.unwrap();
+    let config = ServerConfig {
+        run_migrations: true,
+        multi_tenant: MultiTenantConfig {
+            enabled: true,
+            storage_uri_template: template,
+            control_plane_db_path: control_plane_db.display().to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (app, _tasks) = build_router(&config, Arc::from(default_catalog))
+        .await
+        .unwrap();
+
+    let (status, body) = get(&app, "/api/v1/tenant/usage", &key).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["tenant_id"], "usage-corp");
+    assert_eq!(body["datasets"]["used"], 2);
+    assert_eq!(body["datasets"]["quota"], 4);
+    assert_eq!(body["datasets"]["status"], "ok");
+    assert_eq!(body["storage_bytes"]["used"], 700);
+    assert_eq!(body["storage_bytes"]["status"], "ok");
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["warnings"], serde_json::json!([]));
+
+    // The self-service quota endpoint counts the same datasets
+    let (status, body) = get(&app, "/api/v1/usage", &key).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["dataset_count"], 2);
+    assert_eq!(body["quota_max_datasets"], 4);
+    assert_eq!(body["usage_ratio"], 0.5);
+    assert_eq!(body["status"], "ok");
+    assert!(body.get("warning").is_none());
+}
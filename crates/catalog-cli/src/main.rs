@@ -3,7 +3,7 @@
 //! Command-line interface for exploring and managing the MetaFuse catalog.
 
 use clap::{Parser, Subcommand};
-use metafuse_catalog_core::{migrations, validation};
+use metafuse_catalog_core::{auto_tagging, field_ordinals, migrations, paths, seed, validation};
 use metafuse_catalog_storage::backend_from_uri;
 
 #[cfg(feature = "api-keys")]
@@ -64,6 +64,28 @@ enum Commands {
     /// Show catalog statistics
     Stats,
 
+    /// Populate the catalog with deterministic synthetic metadata for demos and tests
+    Seed {
+        /// Number of datasets to generate
+        #[arg(short = 'n', long, default_value_t = 50)]
+        datasets: usize,
+
+        /// RNG seed (the same seed produces the same catalog)
+        #[arg(short, long, default_value_t = 42)]
+        seed: u64,
+
+        /// Days of usage history to generate per dataset
+        #[arg(long, default_value_t = 30)]
+        usage_days: u32,
+
+        /// Overwrite existing catalog if it exists
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Apply auto-tagging rules to every dataset in the catalog
+    AutoTag,
+
     /// Manage schema migrations
     Migrate {
         #[command(subcommand)]
@@ -88,6 +110,13 @@ enum MigrateCommands {
 
     /// Show migration history
     History,
+
+    /// Find non-canonical and duplicate dataset paths
+    Paths {
+        /// Rewrite non-canonical paths in place (duplicates are only reported)
+        #[arg(long)]
+        apply: bool,
+    },
 }
 
 #[cfg(feature = "api-keys")]
@@ -123,10 +152,18 @@ async fn main() {
         Commands::Show { name, lineage } => show_dataset(&cli.catalog, &name, lineage).await,
         Commands::Search { query } => search_datasets(&cli.catalog, &query).await,
         Commands::Stats => show_stats(&cli.catalog).await,
+        Commands::Seed {
+            datasets,
+            seed,
+            usage_days,
+            force,
+        } => seed_catalog(&cli.catalog, datasets, seed, usage_days, force).await,
+        Commands::AutoTag => auto_tag_catalog(&cli.catalog).await,
         Commands::Migrate { command } => match command {
             MigrateCommands::Status => migrate_status(&cli.catalog).await,
             MigrateCommands::Run => migrate_run(&cli.catalog).await,
             MigrateCommands::History => migrate_history(&cli.catalog).await,
+            MigrateCommands::Paths { apply } => migrate_paths(&cli.catalog, apply).await,
         },
         #[cfg(feature = "api-keys")]
         Commands::Keys { command } => match command {
@@ -355,8 +392,15 @@ async fn show_dataset(
         })?;
 
     println!("\nFields:");
-    let mut stmt =
-        conn.prepare("SELECT name, data_type, nullable FROM fields WHERE dataset_id = ?1")?;
+    let order_by = if field_ordinals::has_ordinal_column(&conn)? {
+        field_ordinals::ORDER_BY
+    } else {
+        "id"
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT name, data_type, nullable FROM fields WHERE dataset_id = ?1 ORDER BY {}",
+        order_by
+    ))?;
     let fields = stmt.query_map([dataset_id], |row| {
         Ok((
             row.get::<_, String>(0)?,
@@ -597,6 +641,143 @@ async fn migrate_history(path: &str) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+async fn migrate_paths(path: &str, apply: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let backend = backend_from_uri(path)?;
+
+    if !backend.exists().await? {
+        return Err("Catalog does not exist. Run 'metafuse init' first.".into());
+    }
+
+    let conn = backend.get_connection().await?;
+
+    let report = paths::analyze_paths(&conn)?;
+
+    if report.is_clean() {
+        println!("All dataset paths are canonical and unique.");
+        return Ok(());
+    }
+
+    if !report.non_canonical.is_empty() {
+        println!("Non-canonical paths:");
+        for entry in &report.non_canonical {
+            println!("  {}", entry.name);
+            println!("    {} -> {}", entry.path, entry.canonical_path);
+        }
+        println!();
+    }
+
+    if !report.invalid.is_empty() {
+        println!("Invalid paths (fix manually):");
+        for entry in &report.invalid {
+            println!("  {}: {}", entry.name, entry.path);
+            println!("    {}", entry.error);
+        }
+        println!();
+    }
+
+    if !report.duplicates.is_empty() {
+        println!("Proposed merges (datasets sharing a canonical path):");
+        for group in &report.duplicates {
+            println!("  {}", group.canonical_path);
+            println!("    keep:  {}", group.keep);
+            println!("    merge: {}", group.merge.join(", "));
+        }
+        println!();
+    }
+
+    if apply {
+        let updated = paths::apply_canonical_paths(&conn, &report)?;
+        println!("Rewrote {} path(s) to canonical form.", updated);
+        if !report.duplicates.is_empty() {
+            println!("Duplicate groups were not modified; merge them manually.");
+        }
+    } else if !report.non_canonical.is_empty() {
+        println!("Run with --apply to rewrite non-canonical paths.");
+    }
+
+    Ok(())
+}
+
+async fn auto_tag_catalog(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let backend = backend_from_uri(path)?;
+
+    if !backend.exists().await? {
+        return Err("Catalog does not exist. Run 'metafuse init' first.".into());
+    }
+
+    let conn = backend.get_connection().await?;
+    let summary = auto_tagging::apply_to_all(&conn)?;
+
+    if summary.rules_evaluated == 0 {
+        println!("No active auto-tagging rules.");
+        return Ok(());
+    }
+
+    println!("Auto-tagging complete:");
+    println!("  Rules evaluated:  {}", summary.rules_evaluated);
+    println!("  Datasets scanned: {}", summary.datasets_scanned);
+    println!("  Datasets matched: {}", summary.datasets_matched);
+    println!("  Tags added:       {}", summary.tags_added);
+    println!("  Domains set:      {}", summary.domains_set);
+
+    Ok(())
+}
+
+async fn seed_catalog(
+    path: &str,
+    datasets: usize,
+    seed: u64,
+    usage_days: u32,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if datasets == 0 {
+        return Err("--datasets must be at least 1".into());
+    }
+
+    let backend = backend_from_uri(path)?;
+
+    if backend.exists().await? {
+        if !force {
+            return Err(format!(
+                "Catalog already exists at '{}'. Use --force to overwrite.",
+                path
+            )
+            .into());
+        }
+        println!("Removing existing catalog at '{}'", path);
+        std::fs::remove_file(path)?;
+    }
+
+    backend.initialize().await?;
+    let conn = backend.get_connection().await?;
+
+    let config = seed::SeedConfig {
+        seed,
+        datasets,
+        usage_days,
+        ..Default::default()
+    };
+    let summary = seed::seed_catalog(&conn, &config)?;
+
+    println!("Seeded catalog at '{}' (seed {}):", path, seed);
+    println!();
+    println!("  Domains:         {}", summary.domains);
+    println!("  Owners:          {}", summary.owners);
+    println!("  Datasets:        {}", summary.datasets);
+    println!("  Fields:          {}", summary.fields);
+    println!("  Lineage edges:   {}", summary.lineage_edges);
+    println!("  Tags:            {}", summary.tags);
+    println!("  Glossary terms:  {}", summary.glossary_terms);
+    println!("  Term links:      {}", summary.term_links);
+    println!("  Classifications: {}", summary.classifications);
+    println!(
+        "  Usage rows:      {}",
+        format_number(summary.usage_rows as i64)
+    );
+
+    Ok(())
+}
+
 /// Format a migration version number (e.g., 1000000 -> v1.0.0)
 fn format_version(version: i64) -> String {
     if version == 0 {
@@ -7,6 +7,7 @@ use crate::types::{
     ApiError, Dataset, DatasetSummary, DeltaHistory, HealthResponse, ListDatasetsResponse,
     SearchResults,
 };
+use crate::ErrorCode;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
 use reqwest::{Method, StatusCode};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
@@ -316,8 +317,14 @@ impl MetafuseClient {
                 "Request failed"
             );
 
+            let code = api_error
+                .as_ref()
+                .and_then(|e| e.code)
+                .unwrap_or_else(|| ErrorCode::from_status(status.as_u16()));
+
             Err(Self::status_to_error(
                 status,
+                code,
                 message,
                 request_id,
                 retry_after,
@@ -328,26 +335,29 @@ impl MetafuseClient {
     /// Convert HTTP status to appropriate error type.
     fn status_to_error(
         status: StatusCode,
+        code: ErrorCode,
         message: String,
         request_id: Option<String>,
         retry_after: Option<std::time::Duration>,
     ) -> ClientError {
         match status {
-            StatusCode::NOT_FOUND => ClientError::NotFound(message),
-            StatusCode::UNAUTHORIZED => ClientError::Unauthorized(message),
-            StatusCode::FORBIDDEN => ClientError::Forbidden(message),
-            StatusCode::CONFLICT => ClientError::Conflict(message),
+            StatusCode::NOT_FOUND => ClientError::NotFound { message, code },
+            StatusCode::UNAUTHORIZED => ClientError::Unauthorized { message, code },
+            StatusCode::FORBIDDEN => ClientError::Forbidden { message, code },
+            StatusCode::CONFLICT => ClientError::Conflict { message, code },
             StatusCode::TOO_MANY_REQUESTS => ClientError::RateLimited {
                 retry_after,
                 request_id,
             },
             s if s.is_server_error() => ClientError::ServerError {
                 status: s.as_u16(),
+                code,
                 message,
                 request_id,
             },
             _ => ClientError::ServerError {
                 status: status.as_u16(),
+                code,
                 message,
                 request_id,
             },
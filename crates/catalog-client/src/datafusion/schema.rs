@@ -73,7 +73,7 @@ impl SchemaProvider for MetafuseSchemaProvider {
             .await
         {
             Ok(ds) => ds,
-            Err(crate::error::ClientError::NotFound(_)) => return Ok(None),
+            Err(crate::error::ClientError::NotFound { .. }) => return Ok(None),
             Err(e) => return Err(e.into()),
         };
 
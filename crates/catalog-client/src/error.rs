@@ -1,5 +1,9 @@
 //! Error types for the MetaFuse client SDK.
+//!
+//! Errors returned by the server carry an [`ErrorCode`]. Branch on the code,
+//! not on the message: codes are stable, messages are not.
 
+use metafuse_catalog_errors::ErrorCode;
 use std::time::Duration;
 
 /// Errors that can occur when using the MetaFuse client.
@@ -13,17 +17,32 @@ pub enum ClientError {
     #[error("HTTP request error: {0}")]
     HttpMiddleware(#[from] reqwest_middleware::Error),
 
-    /// Dataset not found (404)
-    #[error("Dataset not found: {0}")]
-    NotFound(String),
+    /// Resource not found (404), e.g. `DATASET_NOT_FOUND`
+    #[error("Not found: {message}")]
+    NotFound {
+        /// Error message from server
+        message: String,
+        /// Error code from server
+        code: ErrorCode,
+    },
 
     /// Authentication failed (401)
-    #[error("Unauthorized: {0}")]
-    Unauthorized(String),
+    #[error("Unauthorized: {message}")]
+    Unauthorized {
+        /// Error message from server
+        message: String,
+        /// Error code from server
+        code: ErrorCode,
+    },
 
-    /// Permission denied (403)
-    #[error("Forbidden: {0}")]
-    Forbidden(String),
+    /// Permission denied (403), e.g. `TENANT_SUSPENDED` or `QUOTA_EXCEEDED`
+    #[error("Forbidden: {message}")]
+    Forbidden {
+        /// Error message from server
+        message: String,
+        /// Error code from server
+        code: ErrorCode,
+    },
 
     /// Rate limited (429)
     #[error("Rate limited, retry after {retry_after:?}")]
@@ -35,14 +54,21 @@ pub enum ClientError {
     },
 
     /// Conflict (409) - e.g., optimistic locking failure
-    #[error("Conflict: {0}")]
-    Conflict(String),
+    #[error("Conflict: {message}")]
+    Conflict {
+        /// Error message from server
+        message: String,
+        /// Error code from server
+        code: ErrorCode,
+    },
 
-    /// Server error (5xx)
+    /// Server error (5xx), or a client error without a dedicated variant
     #[error("Server error ({status}): {message}")]
     ServerError {
         /// HTTP status code
         status: u16,
+        /// Error code from server
+        code: ErrorCode,
         /// Error message from server
         message: String,
         /// Request ID for tracking
@@ -88,6 +114,21 @@ impl ClientError {
         }
     }
 
+    /// Returns the error code sent by the server, if the error came from one.
+    ///
+    /// Servers that predate error codes get the generic code for the status.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::NotFound { code, .. }
+            | ClientError::Unauthorized { code, .. }
+            | ClientError::Forbidden { code, .. }
+            | ClientError::Conflict { code, .. }
+            | ClientError::ServerError { code, .. } => Some(*code),
+            ClientError::RateLimited { .. } => Some(ErrorCode::RateLimited),
+            _ => None,
+        }
+    }
+
     /// Returns the request ID if available.
     pub fn request_id(&self) -> Option<&str> {
         match self {
@@ -123,13 +164,18 @@ mod tests {
 
         let server_error = ClientError::ServerError {
             status: 503,
+            code: ErrorCode::ServiceUnavailable,
             message: "Service unavailable".to_string(),
             request_id: None,
         };
         assert!(server_error.is_retryable());
 
-        let not_found = ClientError::NotFound("test".to_string());
+        let not_found = ClientError::NotFound {
+            message: "test".to_string(),
+            code: ErrorCode::DatasetNotFound,
+        };
         assert!(!not_found.is_retryable());
+        assert_eq!(not_found.code(), Some(ErrorCode::DatasetNotFound));
     }
 
     #[test]
@@ -140,7 +186,10 @@ mod tests {
         };
         assert_eq!(error.request_id(), Some("req-456"));
 
-        let not_found = ClientError::NotFound("test".to_string());
+        let not_found = ClientError::NotFound {
+            message: "test".to_string(),
+            code: ErrorCode::NotFound,
+        };
         assert_eq!(not_found.request_id(), None);
     }
 }
@@ -47,7 +47,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! metafuse-catalog-client = { version = "0.5", features = ["datafusion"] }
+//! metafuse-catalog-client = { version = "0.6", features = ["datafusion"] }
 //! ```
 //!
 //! ```rust,ignore
@@ -86,8 +86,9 @@
 //!
 //! All operations return `Result<T, ClientError>`. Errors include:
 //!
-//! - `NotFound`: Dataset doesn't exist (404)
-//! - `Unauthorized`: Invalid or missing API key (401)
+//! - `NotFound { message, code }`: Dataset doesn't exist (404)
+//! - `Unauthorized { message, code }`: Invalid or missing API key (401)
+//! - `Forbidden { message, code }`: Permission denied or tenant suspended (403)
 //! - `RateLimited`: Too many requests (429)
 //! - `ServerError`: Server-side failures (5xx)
 //!
@@ -107,6 +108,7 @@ pub use cache::{CacheStats, MetadataCache};
 pub use client::{MetafuseClient, SharedClient};
 pub use config::{ClientConfig, ClientConfigBuilder};
 pub use error::{ClientError, Result};
+pub use metafuse_catalog_errors::ErrorCode;
 pub use types::{
     ClassificationInfo, ColumnStats, Dataset, DatasetSummary, DeltaHistory, DeltaInfo,
     DeltaVersion, Field, HealthResponse, QualityDimension, QualityInfo, SearchResults,
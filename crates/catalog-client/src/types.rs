@@ -4,6 +4,7 @@
 //! deserialization of JSON responses.
 
 use chrono::{DateTime, Utc};
+use metafuse_catalog_errors::ErrorCode;
 use serde::{Deserialize, Serialize};
 
 /// Summary information about a dataset (from list endpoints).
@@ -213,8 +214,8 @@ pub struct ListDatasetsResponse {
 pub struct ApiError {
     /// Error message
     pub error: String,
-    /// Error code (if applicable)
-    pub code: Option<String>,
+    /// Error code (absent from servers that predate error codes)
+    pub code: Option<ErrorCode>,
     /// Request ID for tracking
     pub request_id: Option<String>,
     /// Additional details
@@ -268,7 +269,7 @@ mod tests {
 
         let error: ApiError = serde_json::from_str(json).unwrap();
         assert_eq!(error.error, "Dataset not found");
-        assert_eq!(error.code, Some("NOT_FOUND".to_string()));
+        assert_eq!(error.code, Some(ErrorCode::NotFound));
         assert_eq!(error.request_id, Some("req-12345".to_string()));
     }
 }
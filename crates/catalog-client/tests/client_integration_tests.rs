@@ -7,7 +7,7 @@
 //! - API key header presence
 //! - Cache behavior
 
-use metafuse_catalog_client::{ClientConfig, ClientError, MetafuseClient};
+use metafuse_catalog_client::{ClientConfig, ClientError, ErrorCode, MetafuseClient};
 use std::time::Duration;
 use wiremock::matchers::{header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -226,8 +226,9 @@ async fn test_get_dataset_not_found() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        ClientError::NotFound(msg) => {
-            assert!(msg.contains("nonexistent"));
+        ClientError::NotFound { message, code } => {
+            assert!(message.contains("nonexistent"));
+            assert_eq!(code, ErrorCode::NotFound);
         }
         other => panic!("Expected NotFound error, got: {:?}", other),
     }
@@ -393,8 +394,9 @@ async fn test_error_401_unauthorized() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        ClientError::Unauthorized(msg) => {
-            assert!(msg.contains("API key"));
+        ClientError::Unauthorized { message, code } => {
+            assert!(message.contains("API key"));
+            assert_eq!(code, ErrorCode::Unauthorized);
         }
         other => panic!("Expected Unauthorized error, got: {:?}", other),
     }
@@ -417,13 +419,35 @@ async fn test_error_403_forbidden() {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-        ClientError::Forbidden(msg) => {
-            assert!(msg.contains("Access denied"));
+        ClientError::Forbidden { message, code } => {
+            assert!(message.contains("Access denied"));
+            assert_eq!(code, ErrorCode::Forbidden);
         }
         other => panic!("Expected Forbidden error, got: {:?}", other),
     }
 }
 
+#[tokio::test]
+async fn test_error_code_from_payload() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/datasets"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "error": "Tenant 'acme' is not active (status: suspended)",
+            "code": "TENANT_SUSPENDED",
+            "request_id": "req-403"
+        })))
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server);
+    let error = client.list_datasets().await.unwrap_err();
+
+    assert_eq!(error.code(), Some(ErrorCode::TenantSuspended));
+    assert!(matches!(error, ClientError::Forbidden { .. }));
+}
+
 #[tokio::test]
 async fn test_error_429_rate_limited() {
     let server = MockServer::start().await;
@@ -0,0 +1,616 @@
+//! Policy-driven automatic tagging
+//!
+//! Admins define rules such as "paths under `s3://finance/*` get domain
+//! `finance` and tag `tier:regulated`" or "datasets with a column matching
+//! `*email*` get tag `pii-candidate`". Rules are stored in `auto_tag_rules`
+//! (migration v1.15.0) and applied:
+//!
+//! - when a dataset is registered, by the emitter and the API
+//! - retroactively over the whole catalog with [`apply_to_all`]
+//!
+//! Rules only add: tags are inserted if missing, and the domain is only set on
+//! datasets that have none, so curated metadata is never overwritten. Every
+//! match is recorded in `auto_tag_rule_hits` for rule hit statistics.
+
+use crate::{validation, CatalogError, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Default rule priority (lower runs first)
+pub const DEFAULT_PRIORITY: i64 = 100;
+
+/// Maximum length of a path or column pattern
+pub const MAX_PATTERN_LEN: usize = 512;
+
+/// A stored auto-tagging rule with its hit statistics.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoTagRule {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// Glob matched against the dataset path
+    pub path_pattern: Option<String>,
+    /// Glob matched case-insensitively against column names
+    pub column_pattern: Option<String>,
+    /// Domain assigned to matching datasets without one
+    pub set_domain: Option<String>,
+    /// Tags added to matching datasets
+    pub add_tags: Vec<String>,
+    pub priority: i64,
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Distinct datasets this rule has matched
+    pub datasets_matched: i64,
+    /// Total matching evaluations (registrations and runs)
+    pub total_hits: i64,
+    pub last_hit_at: Option<String>,
+}
+
+/// Definition of a rule, used to create or replace one.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewAutoTagRule {
+    pub name: String,
+    pub description: Option<String>,
+    pub path_pattern: Option<String>,
+    pub column_pattern: Option<String>,
+    pub set_domain: Option<String>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default = "default_priority")]
+    pub priority: i64,
+    #[serde(default = "default_active")]
+    pub is_active: bool,
+}
+
+fn default_priority() -> i64 {
+    DEFAULT_PRIORITY
+}
+
+fn default_active() -> bool {
+    true
+}
+
+impl NewAutoTagRule {
+    /// Check that the rule has a condition, an action, and valid values.
+    pub fn validate(&self) -> Result<()> {
+        validation::validate_identifier(&self.name, "Rule name")?;
+
+        if self.path_pattern.is_none() && self.column_pattern.is_none() {
+            return Err(CatalogError::ValidationError(
+                "Rule needs a path_pattern or a column_pattern".to_string(),
+            ));
+        }
+        for pattern in [&self.path_pattern, &self.column_pattern]
+            .into_iter()
+            .flatten()
+        {
+            if pattern.is_empty() || pattern.len() > MAX_PATTERN_LEN {
+                return Err(CatalogError::ValidationError(format!(
+                    "Patterns must be 1-{} characters",
+                    MAX_PATTERN_LEN
+                )));
+            }
+        }
+
+        if self.set_domain.is_none() && self.add_tags.is_empty() {
+            return Err(CatalogError::ValidationError(
+                "Rule needs set_domain or add_tags".to_string(),
+            ));
+        }
+        if let Some(domain) = &self.set_domain {
+            validation::validate_identifier(domain, "Domain")?;
+        }
+        for tag in &self.add_tags {
+            validation::validate_tag(tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// What the rules did to one dataset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AutoTagOutcome {
+    /// Names of the rules that matched
+    pub rules_matched: Vec<String>,
+    /// Tags that were not already present
+    pub tags_added: Vec<String>,
+    /// Domain set on a dataset that had none
+    pub domain_set: Option<String>,
+}
+
+/// Result of applying rules across the catalog.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AutoTagRunSummary {
+    pub rules_evaluated: usize,
+    pub datasets_scanned: usize,
+    pub datasets_matched: usize,
+    pub tags_added: usize,
+    pub domains_set: usize,
+}
+
+// =============================================================================
+// Matching
+// =============================================================================
+
+/// Match `text` against a glob where `*` matches any run of characters
+/// (including `/`) and `?` matches exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it is matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if let Some((after_star, matched)) = star {
+            p = after_star;
+            t = matched + 1;
+            star = Some((after_star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+impl AutoTagRule {
+    /// Whether the rule matches a dataset. Both patterns must match when set.
+    pub fn matches(&self, path: &str, columns: &[String]) -> bool {
+        let path_ok = self
+            .path_pattern
+            .as_deref()
+            .is_none_or(|pattern| glob_match(pattern, path));
+        let columns_ok = self.column_pattern.as_deref().is_none_or(|pattern| {
+            let pattern = pattern.to_lowercase();
+            columns
+                .iter()
+                .any(|column| glob_match(&pattern, &column.to_lowercase()))
+        });
+        path_ok && columns_ok
+    }
+}
+
+// =============================================================================
+// Database Operations
+// =============================================================================
+
+const SELECT_RULE: &str = r#"
+    SELECT r.id, r.name, r.description, r.path_pattern, r.column_pattern, r.set_domain,
+           r.add_tags, r.priority, r.is_active, r.created_at, r.updated_at,
+           COUNT(h.dataset_id), COALESCE(SUM(h.hit_count), 0), MAX(h.last_hit_at)
+    FROM auto_tag_rules r
+    LEFT JOIN auto_tag_rule_hits h ON h.rule_id = r.id
+"#;
+
+fn row_to_rule(row: &rusqlite::Row<'_>) -> rusqlite::Result<AutoTagRule> {
+    let add_tags: String = row.get(6)?;
+    Ok(AutoTagRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        path_pattern: row.get(3)?,
+        column_pattern: row.get(4)?,
+        set_domain: row.get(5)?,
+        add_tags: serde_json::from_str(&add_tags).unwrap_or_default(),
+        priority: row.get(7)?,
+        is_active: row.get::<_, i32>(8)? == 1,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        datasets_matched: row.get(11)?,
+        total_hits: row.get(12)?,
+        last_hit_at: row.get(13)?,
+    })
+}
+
+fn tags_json(tags: &[String]) -> Result<String> {
+    serde_json::to_string(tags).map_err(|e| CatalogError::SerializationError(e.to_string()))
+}
+
+/// Whether the catalog has been migrated to v1.15.0.
+///
+/// The emitter can run against catalogs that predate auto-tagging.
+fn rules_table_exists(conn: &Connection) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'auto_tag_rules')",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Active rules in priority order. Empty if the catalog predates auto-tagging.
+pub fn load_active_rules(conn: &Connection) -> Result<Vec<AutoTagRule>> {
+    if !rules_table_exists(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE r.is_active = 1 GROUP BY r.id ORDER BY r.priority, r.name",
+        SELECT_RULE
+    ))?;
+    let rules = stmt.query_map([], row_to_rule)?;
+    Ok(rules.collect::<rusqlite::Result<_>>()?)
+}
+
+/// List all rules with hit statistics, in priority order.
+pub fn list_rules(conn: &Connection) -> Result<Vec<AutoTagRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} GROUP BY r.id ORDER BY r.priority, r.name",
+        SELECT_RULE
+    ))?;
+    let rules = stmt.query_map([], row_to_rule)?;
+    Ok(rules.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Get a rule by id.
+pub fn get_rule(conn: &Connection, id: i64) -> Result<Option<AutoTagRule>> {
+    Ok(conn
+        .query_row(
+            &format!("{} WHERE r.id = ?1 GROUP BY r.id", SELECT_RULE),
+            [id],
+            row_to_rule,
+        )
+        .optional()?)
+}
+
+/// Create a rule. The caller validates it first.
+pub fn create_rule(conn: &Connection, rule: &NewAutoTagRule) -> Result<AutoTagRule> {
+    conn.execute(
+        r#"
+        INSERT INTO auto_tag_rules
+            (name, description, path_pattern, column_pattern, set_domain, add_tags, priority, is_active)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+        params![
+            rule.name,
+            rule.description,
+            rule.path_pattern,
+            rule.column_pattern,
+            rule.set_domain,
+            tags_json(&rule.add_tags)?,
+            rule.priority,
+            rule.is_active as i32,
+        ],
+    )?;
+    get_rule(conn, conn.last_insert_rowid())?
+        .ok_or_else(|| CatalogError::Other("Created rule not found".to_string()))
+}
+
+/// Replace a rule's definition, keeping its hit statistics.
+///
+/// Returns `None` if no rule has this id.
+pub fn update_rule(
+    conn: &Connection,
+    id: i64,
+    rule: &NewAutoTagRule,
+) -> Result<Option<AutoTagRule>> {
+    let rows = conn.execute(
+        r#"
+        UPDATE auto_tag_rules
+        SET name = ?2, description = ?3, path_pattern = ?4, column_pattern = ?5,
+            set_domain = ?6, add_tags = ?7, priority = ?8, is_active = ?9,
+            updated_at = datetime('now')
+        WHERE id = ?1
+        "#,
+        params![
+            id,
+            rule.name,
+            rule.description,
+            rule.path_pattern,
+            rule.column_pattern,
+            rule.set_domain,
+            tags_json(&rule.add_tags)?,
+            rule.priority,
+            rule.is_active as i32,
+        ],
+    )?;
+    if rows == 0 {
+        return Ok(None);
+    }
+    get_rule(conn, id)
+}
+
+/// Delete a rule and its hit statistics. Tags it already added are kept.
+pub fn delete_rule(conn: &Connection, id: i64) -> Result<bool> {
+    // Explicit delete: the emitter's connections may not enable foreign keys
+    conn.execute("DELETE FROM auto_tag_rule_hits WHERE rule_id = ?1", [id])?;
+    let rows = conn.execute("DELETE FROM auto_tag_rules WHERE id = ?1", [id])?;
+    Ok(rows > 0)
+}
+
+/// Apply `rules` to one dataset, recording a hit for each matching rule.
+pub fn apply_rules(
+    conn: &Connection,
+    rules: &[AutoTagRule],
+    dataset_id: i64,
+) -> Result<AutoTagOutcome> {
+    let mut outcome = AutoTagOutcome::default();
+    if rules.is_empty() {
+        return Ok(outcome);
+    }
+
+    let (path, mut domain): (String, Option<String>) = conn.query_row(
+        "SELECT path, domain FROM datasets WHERE id = ?1",
+        [dataset_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let mut stmt = conn.prepare_cached("SELECT name FROM fields WHERE dataset_id = ?1")?;
+    let columns: Vec<String> = stmt
+        .query_map([dataset_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for rule in rules.iter().filter(|rule| rule.matches(&path, &columns)) {
+        outcome.rules_matched.push(rule.name.clone());
+
+        for tag in &rule.add_tags {
+            let added = conn.execute(
+                "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)",
+                params![dataset_id, tag],
+            )?;
+            if added > 0 {
+                outcome.tags_added.push(tag.clone());
+            }
+        }
+
+        if let (None, Some(set_domain)) = (&domain, &rule.set_domain) {
+            conn.execute(
+                "UPDATE datasets SET domain = ?2 WHERE id = ?1 AND domain IS NULL",
+                params![dataset_id, set_domain],
+            )?;
+            domain = Some(set_domain.clone());
+            outcome.domain_set = domain.clone();
+        }
+
+        conn.execute(
+            r#"
+            INSERT INTO auto_tag_rule_hits (rule_id, dataset_id)
+            VALUES (?1, ?2)
+            ON CONFLICT(rule_id, dataset_id) DO UPDATE SET
+                hit_count = hit_count + 1,
+                last_hit_at = CURRENT_TIMESTAMP
+            "#,
+            params![rule.id, dataset_id],
+        )?;
+    }
+
+    Ok(outcome)
+}
+
+/// Apply all active rules to every live dataset.
+pub fn apply_to_all(conn: &Connection) -> Result<AutoTagRunSummary> {
+    let rules = load_active_rules(conn)?;
+    let mut summary = AutoTagRunSummary {
+        rules_evaluated: rules.len(),
+        ..Default::default()
+    };
+    if rules.is_empty() {
+        return Ok(summary);
+    }
+
+    let dataset_ids: Vec<i64> = conn
+        .prepare("SELECT id FROM datasets WHERE deleted_at IS NULL ORDER BY id")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    for dataset_id in dataset_ids {
+        let outcome = apply_rules(&tx, &rules, dataset_id)?;
+        summary.datasets_scanned += 1;
+        if !outcome.rules_matched.is_empty() {
+            summary.datasets_matched += 1;
+        }
+        summary.tags_added += outcome.tags_added.len();
+        if outcome.domain_set.is_some() {
+            summary.domains_set += 1;
+        }
+    }
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+
+        for (name, path, domain) in [
+            ("ledger", "s3://finance/gl/ledger", None),
+            ("payroll", "s3://finance/hr/payroll", Some("hr")),
+            ("users", "s3://app/users", None),
+        ] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, domain, created_at, last_updated)
+                 VALUES (?1, ?2, 'parquet', ?3, datetime('now'), datetime('now'))",
+                params![name, path, domain],
+            )
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO fields (dataset_id, name, data_type, nullable) VALUES (3, 'User_Email', 'Utf8', 1);
+             INSERT INTO tags (dataset_id, tag) VALUES (1, 'tier:regulated');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn rule(name: &str) -> NewAutoTagRule {
+        NewAutoTagRule {
+            name: name.to_string(),
+            description: None,
+            path_pattern: None,
+            column_pattern: None,
+            set_domain: None,
+            add_tags: Vec::new(),
+            priority: DEFAULT_PRIORITY,
+            is_active: true,
+        }
+    }
+
+    fn finance_rule() -> NewAutoTagRule {
+        NewAutoTagRule {
+            path_pattern: Some("s3://finance/*".to_string()),
+            set_domain: Some("finance".to_string()),
+            add_tags: vec!["tier:regulated".to_string()],
+            ..rule("finance_paths")
+        }
+    }
+
+    fn email_rule() -> NewAutoTagRule {
+        NewAutoTagRule {
+            column_pattern: Some("*EMAIL*".to_string()),
+            add_tags: vec!["pii-candidate".to_string()],
+            ..rule("email_columns")
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("s3://finance/*", "s3://finance/gl/ledger"));
+        assert!(glob_match("*email*", "user_email_address"));
+        assert!(glob_match("email", "email"));
+        assert!(glob_match("dt_????", "dt_2024"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("s3://finance/*", "s3://finance-archive/x"));
+        assert!(!glob_match("email", "emails"));
+        assert!(!glob_match("dt_????", "dt_202"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(finance_rule().validate().is_ok());
+        assert!(email_rule().validate().is_ok());
+        assert!(rule("no_condition").validate().is_err());
+
+        let no_action = NewAutoTagRule {
+            column_pattern: Some("email".to_string()),
+            ..rule("no_action")
+        };
+        assert!(no_action.validate().is_err());
+
+        let bad_tag = NewAutoTagRule {
+            add_tags: vec!["has space".to_string()],
+            ..email_rule()
+        };
+        assert!(bad_tag.validate().is_err());
+    }
+
+    #[test]
+    fn test_apply_rules_adds_without_overwriting() {
+        let conn = setup_db();
+        create_rule(&conn, &finance_rule()).unwrap();
+        create_rule(&conn, &email_rule()).unwrap();
+        let rules = load_active_rules(&conn).unwrap();
+
+        // Tag already present, domain unset
+        let ledger = apply_rules(&conn, &rules, 1).unwrap();
+        assert_eq!(ledger.rules_matched, vec!["finance_paths"]);
+        assert!(ledger.tags_added.is_empty());
+        assert_eq!(ledger.domain_set.as_deref(), Some("finance"));
+
+        // Existing domain is kept
+        let payroll = apply_rules(&conn, &rules, 2).unwrap();
+        assert_eq!(payroll.tags_added, vec!["tier:regulated"]);
+        assert_eq!(payroll.domain_set, None);
+        let domain: String = conn
+            .query_row("SELECT domain FROM datasets WHERE id = 2", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(domain, "hr");
+
+        // Column patterns are case-insensitive
+        let users = apply_rules(&conn, &rules, 3).unwrap();
+        assert_eq!(users.rules_matched, vec!["email_columns"]);
+        assert_eq!(users.tags_added, vec!["pii-candidate"]);
+    }
+
+    #[test]
+    fn test_apply_to_all_records_hit_statistics() {
+        let conn = setup_db();
+        create_rule(&conn, &finance_rule()).unwrap();
+        let email = create_rule(&conn, &email_rule()).unwrap();
+        let inactive = NewAutoTagRule {
+            path_pattern: Some("*".to_string()),
+            is_active: false,
+            add_tags: vec!["everything".to_string()],
+            ..rule("inactive")
+        };
+        create_rule(&conn, &inactive).unwrap();
+
+        let summary = apply_to_all(&conn).unwrap();
+        assert_eq!(
+            summary,
+            AutoTagRunSummary {
+                rules_evaluated: 2,
+                datasets_scanned: 3,
+                datasets_matched: 3,
+                tags_added: 2,
+                domains_set: 1,
+            }
+        );
+
+        // A second run adds nothing but counts hits again
+        let again = apply_to_all(&conn).unwrap();
+        assert_eq!(again.tags_added, 0);
+        assert_eq!(again.domains_set, 0);
+
+        let rules = list_rules(&conn).unwrap();
+        let finance = rules.iter().find(|r| r.name == "finance_paths").unwrap();
+        assert_eq!(finance.datasets_matched, 2);
+        assert_eq!(finance.total_hits, 4);
+        assert!(finance.last_hit_at.is_some());
+        let inactive = rules.iter().find(|r| r.name == "inactive").unwrap();
+        assert_eq!(inactive.datasets_matched, 0);
+        assert_eq!(inactive.last_hit_at, None);
+
+        assert!(delete_rule(&conn, email.id).unwrap());
+        assert!(get_rule(&conn, email.id).unwrap().is_none());
+        let hits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM auto_tag_rule_hits", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(hits, 2);
+    }
+
+    #[test]
+    fn test_update_rule_keeps_statistics() {
+        let conn = setup_db();
+        let created = create_rule(&conn, &email_rule()).unwrap();
+        apply_to_all(&conn).unwrap();
+
+        let updated = update_rule(
+            &conn,
+            created.id,
+            &NewAutoTagRule {
+                add_tags: vec!["pii".to_string()],
+                ..email_rule()
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(updated.add_tags, vec!["pii"]);
+        assert_eq!(updated.datasets_matched, 1);
+
+        assert!(update_rule(&conn, 999, &email_rule()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unmigrated_catalog_has_no_rules() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        assert!(load_active_rules(&conn).unwrap().is_empty());
+    }
+}
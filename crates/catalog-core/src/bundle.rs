@@ -0,0 +1,1050 @@
+//! Catalog export and import
+//!
+//! [`export_catalog`] serializes a catalog into a versioned [`CatalogBundle`]
+//! and [`import_catalog`] restores one, for moving metadata between
+//! environments. A bundle holds every live dataset with its fields, column
+//! classifications, tags, and upstreams (by name), and the glossary with its
+//! dataset and field links. Trashed datasets are not exported.
+//!
+//! Bundles are written as one JSON document or as NDJSON: a `header` line
+//! followed by one `dataset` or `glossary_term` line per entry, so large
+//! catalogs can be streamed.
+//!
+//! # Conflicts
+//!
+//! Datasets are matched by name and glossary terms by term. When one already
+//! exists, the [`ConflictStrategy`] decides:
+//! - `skip` (default): leave it untouched
+//! - `overwrite`: replace its attributes, fields, tags, upstreams, and links
+//!   with the bundle's (a trashed dataset is restored)
+//! - `merge`: fill in attributes it lacks and add fields, classifications,
+//!   tags, upstreams, and links it doesn't have yet
+//!
+//! Upstreams and links naming a dataset that is neither in the bundle nor in
+//! the catalog, and edges that would close a lineage cycle, are skipped and
+//! reported in [`ImportSummary::warnings`]. The import runs in a single
+//! transaction.
+
+use crate::{increment_catalog_version, lineage_cycles, migrations, validation};
+use crate::{CatalogError, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// `format` of every bundle
+pub const BUNDLE_FORMAT: &str = "metafuse-bundle";
+
+/// Bundle version written by [`export_catalog`] and read by
+/// [`import_catalog`]
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// A serialized catalog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    #[serde(default)]
+    pub datasets: Vec<BundleDataset>,
+    #[serde(default)]
+    pub glossary_terms: Vec<BundleTerm>,
+}
+
+/// A dataset in a bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleDataset {
+    pub name: String,
+    pub path: String,
+    pub format: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tenant: Option<String>,
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub last_updated: Option<String>,
+    #[serde(default)]
+    pub row_count: Option<i64>,
+    #[serde(default)]
+    pub size_bytes: Option<i64>,
+    #[serde(default)]
+    pub partition_keys: Vec<String>,
+    #[serde(default)]
+    pub delta_location: Option<String>,
+    #[serde(default)]
+    pub custom_metadata: Option<serde_json::Value>,
+    /// Fields in schema order
+    #[serde(default)]
+    pub fields: Vec<BundleField>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Names of upstream datasets
+    #[serde(default)]
+    pub upstreams: Vec<String>,
+}
+
+/// A field of a bundled dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleField {
+    pub name: String,
+    pub data_type: String,
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub classifications: Vec<BundleClassification>,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+/// A column classification of a bundled field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleClassification {
+    pub classification: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    #[serde(default = "default_source")]
+    pub source: String,
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub verified_by: Option<String>,
+    #[serde(default)]
+    pub verified_at: Option<String>,
+}
+
+fn default_source() -> String {
+    "manual".to_string()
+}
+
+/// A glossary term in a bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleTerm {
+    pub term: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub owner_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub links: Vec<BundleTermLink>,
+}
+
+/// A link from a glossary term to a dataset, or to one of its fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleTermLink {
+    pub dataset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+/// One NDJSON line
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum BundleLine {
+    Header {
+        format: String,
+        version: u32,
+        exported_at: String,
+    },
+    Dataset(Box<BundleDataset>),
+    GlossaryTerm(BundleTerm),
+}
+
+impl CatalogBundle {
+    /// Reject bundles of another format or an unsupported version.
+    pub fn check_version(&self) -> Result<()> {
+        if self.format != BUNDLE_FORMAT {
+            return Err(CatalogError::ValidationError(format!(
+                "Not a catalog bundle: format '{}' (expected '{}')",
+                self.format, BUNDLE_FORMAT
+            )));
+        }
+        if self.version == 0 || self.version > BUNDLE_VERSION {
+            return Err(CatalogError::ValidationError(format!(
+                "Unsupported bundle version {} (supported: 1-{})",
+                self.version, BUNDLE_VERSION
+            )));
+        }
+        Ok(())
+    }
+
+    /// Serialize as NDJSON: a header line, then one line per dataset and
+    /// glossary term.
+    pub fn to_ndjson(&self) -> Result<String> {
+        let mut out = String::new();
+        let header = BundleLine::Header {
+            format: self.format.clone(),
+            version: self.version,
+            exported_at: self.exported_at.clone(),
+        };
+        let lines = std::iter::once(header)
+            .chain(
+                self.datasets
+                    .iter()
+                    .map(|d| BundleLine::Dataset(Box::new(d.clone()))),
+            )
+            .chain(
+                self.glossary_terms
+                    .iter()
+                    .cloned()
+                    .map(BundleLine::GlossaryTerm),
+            );
+        for line in lines {
+            out.push_str(&serde_json::to_string(&line).map_err(serialization_error)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Parse NDJSON written by [`Self::to_ndjson`]. Blank lines are ignored.
+    pub fn from_ndjson(input: &str) -> Result<Self> {
+        let mut bundle: Option<Self> = None;
+        for (number, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: BundleLine = serde_json::from_str(line).map_err(|e| {
+                CatalogError::SerializationError(format!("Bundle line {}: {}", number + 1, e))
+            })?;
+            match (line, bundle.as_mut()) {
+                (
+                    BundleLine::Header {
+                        format,
+                        version,
+                        exported_at,
+                    },
+                    None,
+                ) => {
+                    bundle = Some(Self {
+                        format,
+                        version,
+                        exported_at,
+                        datasets: Vec::new(),
+                        glossary_terms: Vec::new(),
+                    })
+                }
+                (BundleLine::Dataset(dataset), Some(bundle)) => bundle.datasets.push(*dataset),
+                (BundleLine::GlossaryTerm(term), Some(bundle)) => bundle.glossary_terms.push(term),
+                (_, _) => {
+                    return Err(CatalogError::SerializationError(format!(
+                        "Bundle line {}: expected exactly one header, as the first line",
+                        number + 1
+                    )))
+                }
+            }
+        }
+        bundle.ok_or_else(|| CatalogError::SerializationError("Empty bundle".to_string()))
+    }
+}
+
+/// How [`import_catalog`] treats datasets and terms that already exist.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    #[default]
+    Skip,
+    Overwrite,
+    Merge,
+}
+
+impl ConflictStrategy {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "merge" => Ok(Self::Merge),
+            other => Err(CatalogError::ValidationError(format!(
+                "Invalid conflict strategy '{}'. Valid values: skip, overwrite, merge",
+                other
+            ))),
+        }
+    }
+}
+
+/// What [`import_catalog`] wrote.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportSummary {
+    pub strategy: ConflictStrategy,
+    pub datasets_created: usize,
+    pub datasets_updated: usize,
+    pub datasets_skipped: usize,
+    pub fields: usize,
+    pub classifications: usize,
+    pub tags: usize,
+    pub lineage_edges: usize,
+    pub glossary_terms_created: usize,
+    pub glossary_terms_updated: usize,
+    pub glossary_terms_skipped: usize,
+    pub term_links: usize,
+    /// Upstreams and links that could not be restored
+    pub warnings: Vec<String>,
+}
+
+fn serialization_error(e: serde_json::Error) -> CatalogError {
+    CatalogError::SerializationError(e.to_string())
+}
+
+fn require_current_schema(conn: &Connection) -> Result<()> {
+    if migrations::needs_migration(conn)? {
+        return Err(CatalogError::Other(
+            "Catalog schema is not up to date; run `metafuse migrate run` first".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Export
+// =============================================================================
+
+/// Serialize every live dataset and the glossary.
+pub fn export_catalog(conn: &Connection) -> Result<CatalogBundle> {
+    require_current_schema(conn)?;
+
+    let mut classifications: HashMap<i64, Vec<BundleClassification>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT field_id, classification, category, confidence, source, verified,
+                verified_by, verified_at
+         FROM column_classifications ORDER BY field_id, classification",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            BundleClassification {
+                classification: row.get(1)?,
+                category: row.get(2)?,
+                confidence: row.get(3)?,
+                source: row.get(4)?,
+                verified: row.get(5)?,
+                verified_by: row.get(6)?,
+                verified_at: row.get(7)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (field_id, classification) = row?;
+        classifications
+            .entry(field_id)
+            .or_default()
+            .push(classification);
+    }
+
+    let mut fields: HashMap<i64, Vec<BundleField>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT id, dataset_id, name, data_type, nullable, description FROM fields
+         ORDER BY dataset_id, ordinal IS NULL, ordinal, id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, bool>(4)?,
+            row.get::<_, Option<String>>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, dataset_id, name, data_type, nullable, description) = row?;
+        fields.entry(dataset_id).or_default().push(BundleField {
+            name,
+            data_type,
+            nullable,
+            description,
+            classifications: classifications.remove(&id).unwrap_or_default(),
+        });
+    }
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT dataset_id, tag FROM tags ORDER BY dataset_id, tag")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+    for row in rows {
+        let (dataset_id, tag) = row?;
+        tags.entry(dataset_id).or_default().push(tag);
+    }
+
+    let mut upstreams: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT l.downstream_dataset_id, u.name FROM lineage l
+         JOIN datasets u ON u.id = l.upstream_dataset_id
+         WHERE u.deleted_at IS NULL
+         ORDER BY l.downstream_dataset_id, u.name",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+    for row in rows {
+        let (dataset_id, upstream) = row?;
+        upstreams.entry(dataset_id).or_default().push(upstream);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, path, format, description, tenant, domain, owner, created_at,
+                last_updated, row_count, size_bytes, partition_keys, delta_location,
+                custom_metadata
+         FROM datasets WHERE deleted_at IS NULL ORDER BY name",
+    )?;
+    let datasets = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let partition_keys: Option<String> = row.get(12)?;
+            let custom_metadata: Option<String> = row.get(14)?;
+            Ok(BundleDataset {
+                name: row.get(1)?,
+                path: row.get(2)?,
+                format: row.get(3)?,
+                description: row.get(4)?,
+                tenant: row.get(5)?,
+                domain: row.get(6)?,
+                owner: row.get(7)?,
+                created_at: row.get(8)?,
+                last_updated: row.get(9)?,
+                row_count: row.get(10)?,
+                size_bytes: row.get(11)?,
+                partition_keys: partition_keys
+                    .and_then(|keys| serde_json::from_str(&keys).ok())
+                    .unwrap_or_default(),
+                delta_location: row.get(13)?,
+                custom_metadata: custom_metadata.and_then(|m| serde_json::from_str(&m).ok()),
+                fields: fields.remove(&id).unwrap_or_default(),
+                tags: tags.remove(&id).unwrap_or_default(),
+                upstreams: upstreams.remove(&id).unwrap_or_default(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut links: HashMap<i64, Vec<BundleTermLink>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT l.term_id, COALESCE(d.name, fd.name), f.name FROM term_links l
+         LEFT JOIN datasets d ON d.id = l.dataset_id
+         LEFT JOIN fields f ON f.id = l.field_id
+         LEFT JOIN datasets fd ON fd.id = f.dataset_id
+         WHERE COALESCE(d.deleted_at, fd.deleted_at) IS NULL
+           AND COALESCE(d.name, fd.name) IS NOT NULL
+         ORDER BY l.term_id, 2, 3",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            BundleTermLink {
+                dataset: row.get(1)?,
+                field: row.get(2)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (term_id, link) = row?;
+        links.entry(term_id).or_default().push(link);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id, term, description, domain, owner_id, status FROM glossary_terms ORDER BY term",
+    )?;
+    let glossary_terms = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            Ok(BundleTerm {
+                term: row.get(1)?,
+                description: row.get(2)?,
+                domain: row.get(3)?,
+                owner_id: row.get(4)?,
+                status: row.get(5)?,
+                links: links.remove(&id).unwrap_or_default(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(CatalogBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        datasets,
+        glossary_terms,
+    })
+}
+
+// =============================================================================
+// Import
+// =============================================================================
+
+/// Restore a bundle into the catalog, resolving existing datasets and terms
+/// with `strategy`.
+///
+/// The bundle is validated before anything is written; an invalid dataset
+/// name, field name, or tag fails the whole import.
+pub fn import_catalog(
+    conn: &Connection,
+    bundle: &CatalogBundle,
+    strategy: ConflictStrategy,
+) -> Result<ImportSummary> {
+    bundle.check_version()?;
+    for dataset in &bundle.datasets {
+        validation::validate_dataset_name(&dataset.name)?;
+        for field in &dataset.fields {
+            validation::validate_field_name(&field.name)?;
+        }
+        for tag in &dataset.tags {
+            validation::validate_tag(tag)?;
+        }
+    }
+    require_current_schema(conn)?;
+
+    let tx = conn.unchecked_transaction()?;
+    let now = Utc::now().to_rfc3339();
+    let mut summary = ImportSummary {
+        strategy,
+        ..Default::default()
+    };
+
+    // Datasets whose upstreams are restored, by id
+    let mut written: Vec<(i64, &BundleDataset)> = Vec::new();
+    for dataset in &bundle.datasets {
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM datasets WHERE name = ?1",
+                [&dataset.name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let id = match (existing, strategy) {
+            (None, _) => {
+                let id = insert_dataset(&tx, dataset, &now)?;
+                summary.datasets_created += 1;
+                id
+            }
+            (Some(_), ConflictStrategy::Skip) => {
+                summary.datasets_skipped += 1;
+                continue;
+            }
+            (Some(id), ConflictStrategy::Overwrite) => {
+                overwrite_dataset(&tx, id, dataset, &now)?;
+                summary.datasets_updated += 1;
+                id
+            }
+            (Some(id), ConflictStrategy::Merge) => {
+                merge_dataset(&tx, id, dataset, &now)?;
+                summary.datasets_updated += 1;
+                id
+            }
+        };
+
+        let (fields, classifications) = write_fields(&tx, id, &dataset.fields)?;
+        summary.fields += fields;
+        summary.classifications += classifications;
+        for tag in &dataset.tags {
+            summary.tags += tx.execute(
+                "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+        }
+        written.push((id, dataset));
+    }
+
+    // Upstreams once every bundled dataset exists
+    for (id, dataset) in &written {
+        if strategy == ConflictStrategy::Overwrite {
+            tx.execute("DELETE FROM lineage WHERE downstream_dataset_id = ?1", [id])?;
+        }
+        for upstream in &dataset.upstreams {
+            let Some(upstream_id) = dataset_id(&tx, upstream)? else {
+                summary.warnings.push(format!(
+                    "Upstream '{}' of '{}' not found; edge skipped",
+                    upstream, dataset.name
+                ));
+                continue;
+            };
+            match lineage_cycles::check_edge(&tx, upstream_id, *id) {
+                Ok(()) => {}
+                Err(CatalogError::ValidationError(e)) => {
+                    summary.warnings.push(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+            summary.lineage_edges += tx.execute(
+                "INSERT OR IGNORE INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+                 VALUES (?1, ?2, ?3)",
+                params![upstream_id, id, now],
+            )?;
+        }
+    }
+
+    for term in &bundle.glossary_terms {
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM glossary_terms WHERE term = ?1",
+                [&term.term],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let term_id = match (existing, strategy) {
+            (None, _) => {
+                tx.execute(
+                    "INSERT INTO glossary_terms (term, description, domain, owner_id, status)
+                     VALUES (?1, ?2, ?3, ?4, COALESCE(?5, 'draft'))",
+                    params![
+                        term.term,
+                        term.description,
+                        term.domain,
+                        term.owner_id,
+                        term.status
+                    ],
+                )?;
+                summary.glossary_terms_created += 1;
+                tx.last_insert_rowid()
+            }
+            (Some(_), ConflictStrategy::Skip) => {
+                summary.glossary_terms_skipped += 1;
+                continue;
+            }
+            (Some(id), ConflictStrategy::Overwrite) => {
+                tx.execute(
+                    "UPDATE glossary_terms SET description = ?2, domain = ?3, owner_id = ?4,
+                            status = COALESCE(?5, 'draft'), updated_at = CURRENT_TIMESTAMP
+                     WHERE id = ?1",
+                    params![
+                        id,
+                        term.description,
+                        term.domain,
+                        term.owner_id,
+                        term.status
+                    ],
+                )?;
+                tx.execute("DELETE FROM term_links WHERE term_id = ?1", [id])?;
+                summary.glossary_terms_updated += 1;
+                id
+            }
+            (Some(id), ConflictStrategy::Merge) => {
+                tx.execute(
+                    "UPDATE glossary_terms SET description = COALESCE(description, ?2),
+                            domain = COALESCE(domain, ?3), owner_id = COALESCE(owner_id, ?4),
+                            updated_at = CURRENT_TIMESTAMP
+                     WHERE id = ?1",
+                    params![id, term.description, term.domain, term.owner_id],
+                )?;
+                summary.glossary_terms_updated += 1;
+                id
+            }
+        };
+
+        for link in &term.links {
+            let target = match dataset_id(&tx, &link.dataset)? {
+                None => None,
+                Some(dataset_id) => match &link.field {
+                    None => Some((Some(dataset_id), None)),
+                    Some(field) => tx
+                        .query_row(
+                            "SELECT id FROM fields WHERE dataset_id = ?1 AND name = ?2",
+                            params![dataset_id, field],
+                            |row| row.get::<_, i64>(0),
+                        )
+                        .optional()?
+                        .map(|field_id| (None, Some(field_id))),
+                },
+            };
+            let Some((dataset_id, field_id)) = target else {
+                summary.warnings.push(format!(
+                    "Link of term '{}' to '{}' not found; link skipped",
+                    term.term,
+                    match &link.field {
+                        Some(field) => format!("{}.{}", link.dataset, field),
+                        None => link.dataset.clone(),
+                    }
+                ));
+                continue;
+            };
+            summary.term_links += tx.execute(
+                "INSERT INTO term_links (term_id, dataset_id, field_id)
+                 SELECT ?1, ?2, ?3
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM term_links WHERE term_id = ?1
+                       AND dataset_id IS ?2 AND field_id IS ?3
+                 )",
+                params![term_id, dataset_id, field_id],
+            )?;
+        }
+    }
+
+    if summary.datasets_created + summary.datasets_updated > 0
+        || summary.glossary_terms_created + summary.glossary_terms_updated > 0
+    {
+        increment_catalog_version(&tx)?;
+    }
+    tx.commit()?;
+    Ok(summary)
+}
+
+fn dataset_id(conn: &Connection, name: &str) -> Result<Option<i64>> {
+    Ok(conn
+        .query_row("SELECT id FROM datasets WHERE name = ?1", [name], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+fn partition_keys_json(dataset: &BundleDataset) -> Result<Option<String>> {
+    if dataset.partition_keys.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(&dataset.partition_keys)
+        .map(Some)
+        .map_err(serialization_error)
+}
+
+fn custom_metadata_json(dataset: &BundleDataset) -> Result<Option<String>> {
+    dataset
+        .custom_metadata
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(serialization_error)
+}
+
+fn insert_dataset(conn: &Connection, dataset: &BundleDataset, now: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO datasets (name, path, format, description, tenant, domain, domain_id, owner,
+                               created_at, last_updated, row_count, size_bytes, partition_keys,
+                               delta_location, custom_metadata)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, (SELECT id FROM domains WHERE name = ?6), ?7,
+                 ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            dataset.name,
+            dataset.path,
+            dataset.format,
+            dataset.description,
+            dataset.tenant,
+            dataset.domain,
+            dataset.owner,
+            dataset.created_at.as_deref().unwrap_or(now),
+            dataset.last_updated.as_deref().unwrap_or(now),
+            dataset.row_count,
+            dataset.size_bytes,
+            partition_keys_json(dataset)?,
+            dataset.delta_location,
+            custom_metadata_json(dataset)?,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Replace a dataset's attributes, fields, and tags; upstreams are replaced
+/// by the caller.
+fn overwrite_dataset(conn: &Connection, id: i64, dataset: &BundleDataset, now: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE datasets SET path = ?2, format = ?3, description = ?4, tenant = ?5, domain = ?6,
+                domain_id = (SELECT id FROM domains WHERE name = ?6), owner = ?7,
+                created_at = COALESCE(?8, created_at), last_updated = ?9, row_count = ?10,
+                size_bytes = ?11, partition_keys = ?12, delta_location = ?13,
+                custom_metadata = ?14, deleted_at = NULL, deleted_by = NULL, status = 'active'
+         WHERE id = ?1",
+        params![
+            id,
+            dataset.path,
+            dataset.format,
+            dataset.description,
+            dataset.tenant,
+            dataset.domain,
+            dataset.owner,
+            dataset.created_at,
+            dataset.last_updated.as_deref().unwrap_or(now),
+            dataset.row_count,
+            dataset.size_bytes,
+            partition_keys_json(dataset)?,
+            dataset.delta_location,
+            custom_metadata_json(dataset)?,
+        ],
+    )?;
+    // Classifications and field links go with the fields
+    conn.execute(
+        "DELETE FROM column_classifications
+         WHERE field_id IN (SELECT id FROM fields WHERE dataset_id = ?1)",
+        [id],
+    )?;
+    conn.execute(
+        "DELETE FROM term_links WHERE field_id IN (SELECT id FROM fields WHERE dataset_id = ?1)",
+        [id],
+    )?;
+    conn.execute("DELETE FROM fields WHERE dataset_id = ?1", [id])?;
+    conn.execute("DELETE FROM tags WHERE dataset_id = ?1", [id])?;
+    Ok(())
+}
+
+/// Fill in attributes the dataset lacks.
+fn merge_dataset(conn: &Connection, id: i64, dataset: &BundleDataset, now: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE datasets SET description = COALESCE(description, ?2),
+                tenant = COALESCE(tenant, ?3), domain = COALESCE(domain, ?4),
+                domain_id = COALESCE(domain_id, (SELECT id FROM domains WHERE name = ?4)),
+                owner = COALESCE(owner, ?5), row_count = COALESCE(row_count, ?6),
+                size_bytes = COALESCE(size_bytes, ?7),
+                partition_keys = COALESCE(NULLIF(partition_keys, '[]'), ?8),
+                delta_location = COALESCE(delta_location, ?9),
+                custom_metadata = COALESCE(custom_metadata, ?10), last_updated = ?11
+         WHERE id = ?1",
+        params![
+            id,
+            dataset.description,
+            dataset.tenant,
+            dataset.domain,
+            dataset.owner,
+            dataset.row_count,
+            dataset.size_bytes,
+            partition_keys_json(dataset)?,
+            dataset.delta_location,
+            custom_metadata_json(dataset)?,
+            now,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Add the fields a dataset doesn't have (all of them after an overwrite),
+/// and classifications its fields don't have. Returns the number of fields
+/// and classifications added.
+fn write_fields(
+    conn: &Connection,
+    dataset_id: i64,
+    fields: &[BundleField],
+) -> Result<(usize, usize)> {
+    let mut next_ordinal: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(ordinal) + 1, 0) FROM fields WHERE dataset_id = ?1",
+        [dataset_id],
+        |row| row.get(0),
+    )?;
+    let (mut added_fields, mut added_classifications) = (0, 0);
+    for field in fields {
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM fields WHERE dataset_id = ?1 AND name = ?2",
+                params![dataset_id, field.name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let field_id = match existing {
+            Some(id) => id,
+            None => {
+                conn.execute(
+                    "INSERT INTO fields (dataset_id, name, data_type, nullable, description, ordinal)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        dataset_id,
+                        field.name,
+                        field.data_type,
+                        field.nullable,
+                        field.description,
+                        next_ordinal
+                    ],
+                )?;
+                next_ordinal += 1;
+                added_fields += 1;
+                conn.last_insert_rowid()
+            }
+        };
+        for c in &field.classifications {
+            added_classifications += conn.execute(
+                "INSERT INTO column_classifications
+                    (field_id, classification, category, confidence, source, verified,
+                     verified_by, verified_at)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM column_classifications WHERE field_id = ?1 AND classification = ?2
+                 )",
+                params![
+                    field_id,
+                    c.classification,
+                    c.category,
+                    c.confidence,
+                    c.source,
+                    c.verified,
+                    c.verified_by,
+                    c.verified_at
+                ],
+            )?;
+        }
+    }
+    Ok((added_fields, added_classifications))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_sqlite_schema;
+
+    fn catalog() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_sqlite_schema(&conn).unwrap();
+        migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn seeded() -> Connection {
+        let conn = catalog();
+        conn.execute_batch(
+            r#"
+            INSERT INTO datasets (id, name, path, format, owner, created_at, last_updated,
+                                  partition_keys, custom_metadata)
+            VALUES (1, 'raw_orders', 's3://lake/raw/orders', 'parquet', 'ingest',
+                    '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', '["dt"]', '{"tier": 1}'),
+                   (2, 'orders', 's3://lake/orders', 'delta', 'analytics',
+                    '2026-01-02T00:00:00Z', '2026-01-02T00:00:00Z', NULL, NULL);
+            INSERT INTO fields (id, dataset_id, name, data_type, nullable, ordinal) VALUES
+                (10, 1, 'id', 'Int64', 0, 0), (11, 1, 'email', 'Utf8', 1, 1),
+                (20, 2, 'id', 'Int64', 0, 0);
+            INSERT INTO column_classifications (field_id, classification, category, source, verified)
+            VALUES (11, 'pii', 'email', 'manual', 1);
+            INSERT INTO tags (dataset_id, tag) VALUES (1, 'raw'), (2, 'gold');
+            INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+            VALUES (1, 2, '2026-01-02T00:00:00Z');
+            INSERT INTO glossary_terms (id, term, description, status) VALUES (1, 'Order', 'A purchase', 'approved');
+            INSERT INTO term_links (term_id, dataset_id) VALUES (1, 2);
+            INSERT INTO term_links (term_id, field_id) VALUES (1, 10);
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_export_and_ndjson_round_trip() {
+        let bundle = export_catalog(&seeded()).unwrap();
+        assert_eq!(bundle.version, BUNDLE_VERSION);
+        let names: Vec<&str> = bundle.datasets.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["orders", "raw_orders"]);
+        let raw = &bundle.datasets[1];
+        assert_eq!(raw.partition_keys, vec!["dt"]);
+        assert_eq!(raw.custom_metadata, Some(serde_json::json!({"tier": 1})));
+        assert_eq!(raw.fields[1].classifications[0].classification, "pii");
+        assert_eq!(bundle.datasets[0].upstreams, vec!["raw_orders"]);
+        assert_eq!(
+            bundle.glossary_terms[0].links,
+            vec![
+                BundleTermLink {
+                    dataset: "orders".to_string(),
+                    field: None
+                },
+                BundleTermLink {
+                    dataset: "raw_orders".to_string(),
+                    field: Some("id".to_string())
+                },
+            ]
+        );
+
+        let ndjson = bundle.to_ndjson().unwrap();
+        assert_eq!(ndjson.lines().count(), 4);
+        assert!(ndjson.starts_with(r#"{"kind":"header""#));
+        assert_eq!(CatalogBundle::from_ndjson(&ndjson).unwrap(), bundle);
+        assert!(CatalogBundle::from_ndjson(ndjson.lines().nth(1).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_import_into_empty_catalog() {
+        let bundle = export_catalog(&seeded()).unwrap();
+        let conn = catalog();
+        let summary = import_catalog(&conn, &bundle, ConflictStrategy::Skip).unwrap();
+        assert_eq!(summary.datasets_created, 2);
+        assert_eq!(summary.fields, 3);
+        assert_eq!(summary.classifications, 1);
+        assert_eq!(summary.lineage_edges, 1);
+        assert_eq!(summary.term_links, 2);
+        assert!(summary.warnings.is_empty());
+
+        let mut reexported = export_catalog(&conn).unwrap();
+        reexported.exported_at = bundle.exported_at.clone();
+        assert_eq!(reexported, bundle);
+
+        let mut future = bundle.clone();
+        future.version = BUNDLE_VERSION + 1;
+        assert!(import_catalog(&conn, &future, ConflictStrategy::Skip).is_err());
+    }
+
+    #[test]
+    fn test_import_conflict_strategies() {
+        let mut bundle = export_catalog(&seeded()).unwrap();
+        let orders = &mut bundle.datasets[0];
+        orders.description = Some("Cleaned orders".to_string());
+        orders.owner = Some("platform".to_string());
+        orders.tags = vec!["silver".to_string()];
+        orders.fields.push(BundleField {
+            name: "amount".to_string(),
+            data_type: "Float64".to_string(),
+            nullable: true,
+            description: None,
+            classifications: vec![],
+        });
+        orders.upstreams.push("missing".to_string());
+
+        let conn = seeded();
+        let summary = import_catalog(&conn, &bundle, ConflictStrategy::Skip).unwrap();
+        assert_eq!(summary.datasets_skipped, 2);
+        assert_eq!(summary.glossary_terms_skipped, 1);
+
+        let summary = import_catalog(&conn, &bundle, ConflictStrategy::Merge).unwrap();
+        assert_eq!(summary.datasets_updated, 2);
+        assert_eq!(summary.fields, 1);
+        assert_eq!(summary.warnings.len(), 1);
+        let (description, owner): (String, String) = conn
+            .query_row(
+                "SELECT description, owner FROM datasets WHERE name = 'orders'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(description, "Cleaned orders");
+        assert_eq!(owner, "analytics");
+        let tags: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM tags WHERE dataset_id = 2",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tags, 2);
+
+        conn.execute(
+            "UPDATE datasets SET deleted_at = '2026-02-01T00:00:00Z' WHERE name = 'orders'",
+            [],
+        )
+        .unwrap();
+        import_catalog(&conn, &bundle, ConflictStrategy::Overwrite).unwrap();
+        let (owner, deleted_at): (String, Option<String>) = conn
+            .query_row(
+                "SELECT owner, deleted_at FROM datasets WHERE name = 'orders'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(owner, "platform");
+        assert_eq!(deleted_at, None);
+        let tags: Vec<String> = conn
+            .prepare("SELECT tag FROM tags WHERE dataset_id = 2")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(tags, vec!["silver"]);
+        let upstreams: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM lineage WHERE downstream_dataset_id = 2",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(upstreams, 1);
+
+        assert!(ConflictStrategy::parse("replace").is_err());
+    }
+}
@@ -0,0 +1,485 @@
+//! Custom metadata
+//!
+//! A JSON object per dataset for system-specific settings that have no typed
+//! column, such as a Snowflake warehouse or Spark configuration hints.
+//!
+//! A catalog may define a JSON Schema that custom metadata must match. Each
+//! tenant catalog is a separate database, so schemas are per tenant. Only a
+//! subset of JSON Schema is enforced:
+//!
+//! - `type` (a name or a list of names)
+//! - `enum`, `const`
+//! - `properties`, `required`, `additionalProperties`
+//! - `items` (a single schema)
+//! - `minimum`, `maximum`, `minLength`, `maxLength`, `minItems`, `maxItems`
+//!
+//! Other keywords are accepted and ignored.
+//!
+//! Requires migration v1.24.0.
+
+use crate::{CatalogError, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{Map, Value};
+
+/// Largest custom metadata document accepted, serialized
+pub const MAX_BYTES: usize = 64 * 1024;
+
+const TYPE_NAMES: &[&str] = &[
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+/// Whether the catalog has the custom metadata column.
+pub fn has_custom_metadata_column(conn: &Connection) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('datasets') WHERE name = 'custom_metadata')",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Check that a schema only uses the supported keywords correctly.
+pub fn validate_schema(schema: &Value) -> Result<()> {
+    let mut errors = Vec::new();
+    check_schema(schema, "", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CatalogError::ValidationError(format!(
+            "Invalid custom metadata schema: {}",
+            errors.join("; ")
+        )))
+    }
+}
+
+fn check_schema(schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let Value::Object(schema) = schema else {
+        if !schema.is_boolean() {
+            errors.push(format!("{}: schema must be an object", display(path)));
+        }
+        return;
+    };
+    if let Some(ty) = schema.get("type") {
+        let names: Vec<&Value> = match ty {
+            Value::Array(names) => names.iter().collect(),
+            other => vec![other],
+        };
+        for name in names {
+            if !name.as_str().is_some_and(|n| TYPE_NAMES.contains(&n)) {
+                errors.push(format!("{}/type: unknown type {}", path, name));
+            }
+        }
+    }
+    if let Some(properties) = schema.get("properties") {
+        match properties {
+            Value::Object(properties) => {
+                for (key, sub) in properties {
+                    check_schema(sub, &format!("{}/properties/{}", path, key), errors);
+                }
+            }
+            _ => errors.push(format!("{}/properties: must be an object", path)),
+        }
+    }
+    if let Some(required) = schema.get("required") {
+        if !required
+            .as_array()
+            .is_some_and(|r| r.iter().all(Value::is_string))
+        {
+            errors.push(format!("{}/required: must be a list of strings", path));
+        }
+    }
+    if let Some(enumerated) = schema.get("enum") {
+        if !enumerated.is_array() {
+            errors.push(format!("{}/enum: must be a list", path));
+        }
+    }
+    for key in ["additionalProperties", "items"] {
+        if let Some(sub) = schema.get(key) {
+            check_schema(sub, &format!("{}/{}", path, key), errors);
+        }
+    }
+    for key in ["minimum", "maximum"] {
+        if schema.get(key).is_some_and(|v| !v.is_number()) {
+            errors.push(format!("{}/{}: must be a number", path, key));
+        }
+    }
+    for key in ["minLength", "maxLength", "minItems", "maxItems"] {
+        if schema.get(key).is_some_and(|v| !v.is_u64()) {
+            errors.push(format!("{}/{}: must be a non-negative integer", path, key));
+        }
+    }
+}
+
+/// Validate a value against a schema, returning every violation.
+///
+/// Violations are prefixed with the JSON Pointer of the offending value.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, value, "", &mut errors);
+    errors
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            errors.push(format!("{}: not allowed", display(path)));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(ty) = schema.get("type") {
+        let names: Vec<&str> = match ty {
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        if !names.iter().any(|name| has_type(value, name)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                display(path),
+                names.join(" or "),
+                type_name(value)
+            ));
+            // Remaining keywords assume the right type
+            return;
+        }
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            errors.push(format!("{}: not one of the allowed values", display(path)));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{}: must equal {}", display(path), expected));
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, errors),
+        Value::Array(items) => {
+            check_bound(schema, "minItems", items.len(), path, "items", errors);
+            check_bound(schema, "maxItems", items.len(), path, "items", errors);
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{}/{}", path, i), errors);
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count();
+            check_bound(schema, "minLength", len, path, "characters", errors);
+            check_bound(schema, "maxLength", len, path, "characters", errors);
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    errors.push(format!("{}: must be at least {}", display(path), min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    errors.push(format!("{}: must be at most {}", display(path), max));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                errors.push(format!(
+                    "{}: missing required property '{}'",
+                    display(path),
+                    key
+                ));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, value) in object {
+        let child = format!("{}/{}", path, escape_pointer(key));
+        match properties.and_then(|p| p.get(key)) {
+            Some(sub) => validate_at(sub, value, &child, errors),
+            None => {
+                if let Some(additional) = schema.get("additionalProperties") {
+                    validate_at(additional, value, &child, errors);
+                }
+            }
+        }
+    }
+}
+
+fn check_bound(
+    schema: &Map<String, Value>,
+    keyword: &str,
+    actual: usize,
+    path: &str,
+    unit: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(bound) = schema.get(keyword).and_then(Value::as_u64) else {
+        return;
+    };
+    let actual = actual as u64;
+    if keyword.starts_with("min") && actual < bound {
+        errors.push(format!(
+            "{}: needs at least {} {}",
+            display(path),
+            bound,
+            unit
+        ));
+    } else if keyword.starts_with("max") && actual > bound {
+        errors.push(format!(
+            "{}: allows at most {} {}",
+            display(path),
+            bound,
+            unit
+        ));
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn display(path: &str) -> &str {
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
+
+/// The catalog's custom metadata schema, if one is set.
+pub fn get_schema(conn: &Connection) -> Result<Option<Value>> {
+    let has_table = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'custom_metadata_schema'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !has_table {
+        return Ok(None);
+    }
+    let schema: Option<String> = conn
+        .query_row(
+            "SELECT schema FROM custom_metadata_schema WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    schema
+        .map(|s| {
+            serde_json::from_str(&s).map_err(|e| CatalogError::SerializationError(e.to_string()))
+        })
+        .transpose()
+}
+
+/// Set the catalog's custom metadata schema. Existing metadata isn't
+/// revalidated.
+pub fn set_schema(conn: &Connection, schema: &Value) -> Result<()> {
+    validate_schema(schema)?;
+    conn.execute(
+        "INSERT INTO custom_metadata_schema (id, schema, updated_at)
+         VALUES (1, ?1, datetime('now'))
+         ON CONFLICT(id) DO UPDATE SET
+            schema = excluded.schema,
+            updated_at = excluded.updated_at",
+        [schema.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Remove the catalog's custom metadata schema. Returns whether one was set.
+pub fn clear_schema(conn: &Connection) -> Result<bool> {
+    Ok(conn.execute("DELETE FROM custom_metadata_schema WHERE id = 1", [])? > 0)
+}
+
+/// Check custom metadata before it is stored: it must be an object within
+/// [`MAX_BYTES`] that matches the catalog's schema, if any.
+pub fn check(conn: &Connection, metadata: &Value) -> Result<()> {
+    if !metadata.is_object() {
+        return Err(CatalogError::ValidationError(
+            "Custom metadata must be a JSON object".to_string(),
+        ));
+    }
+    let size = metadata.to_string().len();
+    if size > MAX_BYTES {
+        return Err(CatalogError::ValidationError(format!(
+            "Custom metadata is {} bytes, more than the limit of {}",
+            size, MAX_BYTES
+        )));
+    }
+    if let Some(schema) = get_schema(conn)? {
+        let errors = validate(&schema, metadata);
+        if !errors.is_empty() {
+            return Err(CatalogError::ValidationError(format!(
+                "Custom metadata doesn't match the schema: {}",
+                errors.join("; ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Apply a JSON Merge Patch (RFC 7396): `null` removes a key, objects merge
+/// recursively and anything else replaces.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        unreachable!("target was just made an object");
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.as_str()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Custom metadata of a dataset.
+pub fn load(conn: &Connection, dataset_id: i64) -> Result<Option<Value>> {
+    if !has_custom_metadata_column(conn)? {
+        return Ok(None);
+    }
+    let metadata: Option<String> = conn
+        .query_row(
+            "SELECT custom_metadata FROM datasets WHERE id = ?1",
+            [dataset_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    metadata
+        .map(|s| {
+            serde_json::from_str(&s).map_err(|e| CatalogError::SerializationError(e.to_string()))
+        })
+        .transpose()
+}
+
+/// Replace the custom metadata of a dataset. Callers should [`check`] it
+/// first.
+pub fn store(conn: &Connection, dataset_id: i64, metadata: Option<&Value>) -> Result<()> {
+    conn.execute(
+        "UPDATE datasets SET custom_metadata = ?2 WHERE id = ?1",
+        params![dataset_id, metadata.map(Value::to_string)],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_validation_and_merge_patch() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        // Without a schema, any object is accepted
+        assert!(check(&conn, &json!({"anything": [1, 2]})).is_ok());
+        assert!(check(&conn, &json!("warehouse")).is_err());
+
+        assert!(set_schema(&conn, &json!({"type": "map"})).is_err());
+        set_schema(
+            &conn,
+            &json!({
+                "type": "object",
+                "required": ["snowflake"],
+                "properties": {
+                    "snowflake": {
+                        "type": "object",
+                        "properties": {"warehouse": {"type": "string", "minLength": 1}},
+                        "additionalProperties": false
+                    },
+                    "spark": {"type": "object"}
+                }
+            }),
+        )
+        .unwrap();
+
+        let mut metadata = json!({"snowflake": {"warehouse": "ANALYTICS_WH"}});
+        check(&conn, &metadata).unwrap();
+        let errors = validate(
+            &get_schema(&conn).unwrap().unwrap(),
+            &json!({"snowflake": {"warehouse": "", "role": "x"}}),
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "/snowflake/role: not allowed",
+                "/snowflake/warehouse: needs at least 1 characters",
+            ]
+        );
+        assert!(check(&conn, &json!({"spark": {}})).is_err());
+
+        store(&conn, 1, Some(&metadata)).unwrap();
+        merge_patch(
+            &mut metadata,
+            &json!({"spark": {"executor.memory": "4g"}, "snowflake": {"warehouse": null}}),
+        );
+        assert_eq!(
+            metadata,
+            json!({"snowflake": {}, "spark": {"executor.memory": "4g"}})
+        );
+        assert_eq!(
+            load(&conn, 1).unwrap(),
+            Some(json!({"snowflake": {"warehouse": "ANALYTICS_WH"}}))
+        );
+
+        assert!(clear_schema(&conn).unwrap());
+        assert_eq!(get_schema(&conn).unwrap(), None);
+    }
+}
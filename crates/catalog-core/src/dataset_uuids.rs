@@ -0,0 +1,95 @@
+//! Dataset UUIDs
+//!
+//! Stable, non-sequential dataset identifiers for external use, so responses
+//! and URLs don't expose the integer primary key (which leaks catalog size
+//! and invites enumeration). UUIDs are assigned by the database on insert.
+//!
+//! Requires migration v1.21.0. On older catalogs lookups return nothing.
+
+use crate::Result;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+
+/// Whether the catalog has the dataset UUID table.
+pub fn has_uuid_table(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'dataset_uuids'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// UUIDs for the given dataset ids. Datasets without one are omitted.
+pub fn uuids_for(conn: &Connection, dataset_ids: &[i64]) -> Result<HashMap<i64, String>> {
+    let mut uuids = HashMap::new();
+    if dataset_ids.is_empty() || !has_uuid_table(conn)? {
+        return Ok(uuids);
+    }
+    // Chunked to stay under SQLite's bound parameter limit
+    for chunk in dataset_ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT dataset_id, uuid FROM dataset_uuids WHERE dataset_id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (id, uuid) = row?;
+            uuids.insert(id, uuid);
+        }
+    }
+    Ok(uuids)
+}
+
+/// Name of the live (not trashed) dataset with this UUID.
+pub fn name_for_uuid(conn: &Connection, uuid: &str) -> Result<Option<String>> {
+    if !has_uuid_table(conn)? {
+        return Ok(None);
+    }
+    Ok(conn
+        .query_row(
+            "SELECT d.name FROM dataset_uuids u
+             JOIN datasets d ON d.id = u.dataset_id
+             WHERE u.uuid = ?1 AND d.deleted_at IS NULL",
+            [uuid.to_lowercase()],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_uuid() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        let uuids = uuids_for(&conn, &[1, 2]).unwrap();
+        assert_eq!(uuids.len(), 1);
+        let uuid = &uuids[&1];
+        assert_eq!(
+            name_for_uuid(&conn, &uuid.to_uppercase())
+                .unwrap()
+                .as_deref(),
+            Some("orders")
+        );
+
+        conn.execute("UPDATE datasets SET deleted_at = datetime('now')", [])
+            .unwrap();
+        assert_eq!(name_for_uuid(&conn, uuid).unwrap(), None);
+    }
+}
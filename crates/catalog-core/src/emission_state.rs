@@ -0,0 +1,219 @@
+//! Emission state
+//!
+//! Pipelines re-emit the same metadata on every run. The emitter stores a
+//! hash of what it last wrote for each dataset; when a new emission hashes the
+//! same, it can skip the write and only record `last_seen_at`.
+//!
+//! The hash covers everything the emitter writes except timestamps: path,
+//! format, description, tenant, domain, owner, fields (in order), upstream
+//! datasets and tags (as sets), and operational metadata. Edits made through
+//! the API don't update the hash, so they are kept until the emitted metadata
+//! changes or a full write is forced.
+//!
+//! `last_seen_at` is a heartbeat: it moves on every emission, written or
+//! skipped, so it tells "the pipeline still produces this" apart from "the
+//! metadata changed" (`last_updated`).
+//!
+//! Requires migration v1.23.0. On older catalogs there is no stored state and
+//! every emission is a full write.
+
+use crate::{DatasetMeta, FieldMeta, OperationalMeta, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Bumped when the hashed content changes, so old hashes never match
+const HASH_VERSION: &str = "v1";
+
+/// The parts of a dataset that a content hash covers
+#[derive(Serialize)]
+struct HashedContent<'a> {
+    path: &'a str,
+    format: &'a str,
+    description: Option<&'a str>,
+    tenant: Option<&'a str>,
+    domain: Option<&'a str>,
+    owner: Option<&'a str>,
+    fields: &'a [FieldMeta],
+    upstream_datasets: Vec<&'a str>,
+    tags: Vec<&'a str>,
+    operational: Option<&'a OperationalMeta>,
+}
+
+/// Hex SHA-256 of the dataset's metadata, ignoring timestamps.
+pub fn content_hash(dataset: &DatasetMeta) -> Result<String> {
+    fn sorted(items: &[String]) -> Vec<&str> {
+        let mut items: Vec<&str> = items.iter().map(String::as_str).collect();
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+    let content = HashedContent {
+        path: &dataset.path,
+        format: &dataset.format,
+        description: dataset.description.as_deref(),
+        tenant: dataset.tenant.as_deref(),
+        domain: dataset.domain.as_deref(),
+        owner: dataset.owner.as_deref(),
+        fields: &dataset.fields,
+        upstream_datasets: sorted(&dataset.upstream_datasets),
+        tags: sorted(&dataset.tags),
+        operational: dataset.operational.as_ref(),
+    };
+    let json = serde_json::to_vec(&content)
+        .map_err(|e| crate::CatalogError::SerializationError(e.to_string()))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(HASH_VERSION.as_bytes());
+    hasher.update(&json);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether the catalog has the emission state table.
+pub fn has_state_table(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'dataset_emission_state'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Hash stored by the last full write of a dataset, with the dataset id.
+pub fn stored_hash(conn: &Connection, dataset_name: &str) -> Result<Option<(i64, String)>> {
+    if !has_state_table(conn)? {
+        return Ok(None);
+    }
+    Ok(conn
+        .query_row(
+            "SELECT d.id, s.content_hash FROM dataset_emission_state s
+             JOIN datasets d ON d.id = s.dataset_id
+             WHERE d.name = ?1",
+            [dataset_name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?)
+}
+
+/// Record a full write of a dataset.
+pub fn record_write(
+    conn: &Connection,
+    dataset_id: i64,
+    content_hash: &str,
+    seen_at: DateTime<Utc>,
+) -> Result<()> {
+    if !has_state_table(conn)? {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO dataset_emission_state (dataset_id, content_hash, last_seen_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(dataset_id) DO UPDATE SET
+            content_hash = excluded.content_hash,
+            last_seen_at = excluded.last_seen_at",
+        params![dataset_id, content_hash, seen_at.to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Record an emission that was skipped as unchanged.
+pub fn touch(conn: &Connection, dataset_id: i64, seen_at: DateTime<Utc>) -> Result<()> {
+    conn.execute(
+        "UPDATE dataset_emission_state SET last_seen_at = ?2 WHERE dataset_id = ?1",
+        params![dataset_id, seen_at.to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// Last emission time of the given datasets. Datasets never emitted since
+/// migration v1.23.0 are omitted.
+pub fn last_seen_for(conn: &Connection, dataset_ids: &[i64]) -> Result<HashMap<i64, String>> {
+    let mut seen = HashMap::new();
+    if dataset_ids.is_empty() || !has_state_table(conn)? {
+        return Ok(seen);
+    }
+    // Chunked to stay under SQLite's bound parameter limit
+    for chunk in dataset_ids.chunks(500) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT dataset_id, last_seen_at FROM dataset_emission_state WHERE dataset_id IN ({})",
+            placeholders
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(chunk), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (id, last_seen_at) = row?;
+            seen.insert(id, last_seen_at);
+        }
+    }
+    Ok(seen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(tags: &[&str]) -> DatasetMeta {
+        DatasetMeta {
+            name: "orders".to_string(),
+            path: "s3://bucket/orders".to_string(),
+            format: "parquet".to_string(),
+            description: None,
+            tenant: None,
+            domain: Some("sales".to_string()),
+            owner: None,
+            created_at: Utc::now(),
+            last_updated: Utc::now(),
+            fields: vec![FieldMeta {
+                name: "id".to_string(),
+                data_type: "Int64".to_string(),
+                nullable: false,
+                description: None,
+            }],
+            upstream_datasets: vec![],
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            operational: None,
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ignores_timestamps_and_set_order() {
+        let a = dataset(&["daily", "pii"]);
+        let mut b = dataset(&["pii", "daily", "pii"]);
+        b.created_at = a.created_at - chrono::Duration::days(1);
+        assert_eq!(content_hash(&a).unwrap(), content_hash(&b).unwrap());
+
+        b.domain = Some("finance".to_string());
+        assert_ne!(content_hash(&a).unwrap(), content_hash(&b).unwrap());
+
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        assert_eq!(stored_hash(&conn, "orders").unwrap(), None);
+        record_write(&conn, 1, "abc", Utc::now()).unwrap();
+        assert_eq!(
+            stored_hash(&conn, "orders").unwrap(),
+            Some((1, "abc".to_string()))
+        );
+
+        let seen_at = DateTime::parse_from_rfc3339("2025-11-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        touch(&conn, 1, seen_at).unwrap();
+        assert_eq!(
+            last_seen_for(&conn, &[1, 2]).unwrap(),
+            HashMap::from([(1, "2025-11-20T12:00:00+00:00".to_string())])
+        );
+    }
+}
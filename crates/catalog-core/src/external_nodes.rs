@@ -0,0 +1,268 @@
+//! External lineage nodes
+//!
+//! Some lineage endpoints live outside the catalog: a vendor SFTP drop feeding
+//! a raw dataset, or a SaaS export fed by a mart. These are recorded as
+//! external nodes, identified by URI, rather than as datasets, so they never
+//! show up in dataset listings or search but still appear in lineage.
+//!
+//! Requires migration v1.19.0. On older catalogs [`for_dataset`] returns no
+//! edges.
+
+use crate::{CatalogError, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Which side of the edge the external node is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalDirection {
+    /// The external node feeds the dataset
+    Upstream,
+    /// The dataset feeds the external node
+    Downstream,
+}
+
+impl ExternalDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExternalDirection::Upstream => "upstream",
+            ExternalDirection::Downstream => "downstream",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "upstream" => Some(ExternalDirection::Upstream),
+            "downstream" => Some(ExternalDirection::Downstream),
+            _ => None,
+        }
+    }
+}
+
+/// An external lineage node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalNode {
+    pub id: i64,
+    pub uri: String,
+    pub name: String,
+    pub system: Option<String>,
+}
+
+/// Whether the catalog has the external lineage tables.
+pub fn has_external_tables(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'external_lineage'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Create or update an external node by URI and return its id.
+///
+/// `name` defaults to the URI. A `None` system keeps the stored one.
+pub fn upsert_node(
+    conn: &Connection,
+    uri: &str,
+    name: Option<&str>,
+    system: Option<&str>,
+) -> Result<i64> {
+    let uri = uri.trim();
+    if uri.is_empty() {
+        return Err(CatalogError::ValidationError(
+            "External node URI cannot be empty".to_string(),
+        ));
+    }
+    let name = name.map(str::trim).filter(|n| !n.is_empty()).unwrap_or(uri);
+    conn.execute(
+        r#"
+        INSERT INTO external_nodes (uri, name, system)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT(uri) DO UPDATE SET
+            name = excluded.name,
+            system = COALESCE(excluded.system, system),
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+        params![uri, name, system],
+    )?;
+    Ok(conn.query_row(
+        "SELECT id FROM external_nodes WHERE uri = ?1",
+        [uri],
+        |row| row.get(0),
+    )?)
+}
+
+/// Link an external node to a dataset. Returns the edge id and whether the
+/// edge is new; existing edges get their job fields refreshed.
+pub fn link(
+    conn: &Connection,
+    node_id: i64,
+    dataset_id: i64,
+    direction: ExternalDirection,
+    job_name: Option<&str>,
+    run_id: Option<&str>,
+    job_metadata: Option<&str>,
+) -> Result<(i64, bool)> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM external_lineage
+             WHERE external_node_id = ?1 AND dataset_id = ?2 AND direction = ?3",
+            params![node_id, dataset_id, direction.as_str()],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match existing {
+        Some(id) => {
+            conn.execute(
+                r#"
+                UPDATE external_lineage
+                SET job_name = COALESCE(?1, job_name),
+                    run_id = COALESCE(?2, run_id),
+                    job_metadata = COALESCE(?3, job_metadata),
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE id = ?4
+                "#,
+                params![job_name, run_id, job_metadata, id],
+            )?;
+            Ok((id, false))
+        }
+        None => {
+            conn.execute(
+                r#"
+                INSERT INTO external_lineage
+                    (external_node_id, dataset_id, direction, job_name, run_id, job_metadata)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                params![
+                    node_id,
+                    dataset_id,
+                    direction.as_str(),
+                    job_name,
+                    run_id,
+                    job_metadata
+                ],
+            )?;
+            Ok((conn.last_insert_rowid(), true))
+        }
+    }
+}
+
+/// External nodes linked to a dataset, ordered by URI.
+pub fn for_dataset(
+    conn: &Connection,
+    dataset_id: i64,
+) -> Result<Vec<(ExternalNode, ExternalDirection)>> {
+    if !has_external_tables(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        r#"
+        SELECT n.id, n.uri, n.name, n.system, l.direction
+        FROM external_lineage l
+        JOIN external_nodes n ON n.id = l.external_node_id
+        WHERE l.dataset_id = ?1
+        ORDER BY n.uri, l.direction
+        "#,
+    )?;
+    let rows = stmt.query_map([dataset_id], |row| {
+        Ok((
+            ExternalNode {
+                id: row.get(0)?,
+                uri: row.get(1)?,
+                name: row.get(2)?,
+                system: row.get(3)?,
+            },
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+
+    let mut edges = Vec::new();
+    for row in rows {
+        let (node, direction) = row?;
+        if let Some(direction) = ExternalDirection::parse(&direction) {
+            edges.push((node, direction));
+        }
+    }
+    Ok(edges)
+}
+
+/// List external nodes, optionally for one system, ordered by URI.
+pub fn list_nodes(conn: &Connection, system: Option<&str>) -> Result<Vec<ExternalNode>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, uri, name, system FROM external_nodes
+         WHERE ?1 IS NULL OR system = ?1
+         ORDER BY uri",
+    )?;
+    let nodes = stmt
+        .query_map([system], |row| {
+            Ok(ExternalNode {
+                id: row.get(0)?,
+                uri: row.get(1)?,
+                name: row.get(2)?,
+                system: row.get(3)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_and_lookup() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('raw_orders', '/raw', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        let uri = "sftp://vendor.example.com/orders";
+        let node = upsert_node(&conn, uri, None, Some("vendor_sftp")).unwrap();
+        let (edge, created) = link(
+            &conn,
+            node,
+            1,
+            ExternalDirection::Upstream,
+            Some("ingest"),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(created);
+
+        // Same URI is the same node; the stored system is kept
+        assert_eq!(
+            upsert_node(&conn, uri, Some("Orders feed"), None).unwrap(),
+            node
+        );
+        let relinked = link(
+            &conn,
+            node,
+            1,
+            ExternalDirection::Upstream,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(relinked.unwrap(), (edge, false));
+
+        let edges = for_dataset(&conn, 1).unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].0.name, "Orders feed");
+        assert_eq!(edges[0].0.system.as_deref(), Some("vendor_sftp"));
+        assert_eq!(edges[0].1, ExternalDirection::Upstream);
+
+        assert_eq!(list_nodes(&conn, Some("vendor_sftp")).unwrap().len(), 1);
+        assert!(list_nodes(&conn, Some("salesforce")).unwrap().is_empty());
+        assert!(upsert_node(&conn, "  ", None, None).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Field ordinals
+//!
+//! Fields keep the order they were emitted in, so UIs can show columns in
+//! table order and schema diffs don't shuffle. Each field row stores its
+//! 0-based `ordinal`.
+//!
+//! Requires migration v1.22.0 (`fields.ordinal`). On older catalogs fields
+//! are written without it; new fields are still inserted in emission order,
+//! so `id` order is the fallback.
+
+use crate::merge_strategy::{self, MergeStrategy};
+use crate::{FieldMeta, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+
+/// `ORDER BY` clause listing a dataset's fields in schema order. Fields
+/// written before v1.22.0 have a NULL ordinal and sort by `id`.
+pub const ORDER_BY: &str = "ordinal, id";
+
+/// Whether the catalog has the `fields.ordinal` column.
+pub fn has_ordinal_column(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('fields') WHERE name = 'ordinal'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Replace a dataset's fields, recording each field's position.
+///
+/// Fields are matched to the stored ones by name and updated in place, so
+/// their ids, and the classifications, glossary links, and business names
+/// that hang off them, survive re-emits. A `None` description keeps the
+/// stored one, since emitters don't know descriptions. Stored fields missing
+/// from `fields` are deleted.
+pub fn replace_fields(conn: &Connection, dataset_id: i64, fields: &[FieldMeta]) -> Result<()> {
+    merge_fields(conn, dataset_id, fields, MergeStrategy::Merge)
+}
+
+/// Write a dataset's fields according to `strategy`, recording each field's
+/// position.
+///
+/// `merge` is [`replace_fields`]. `preserve-manual` also keeps descriptions
+/// set through the field metadata API over emitted ones, and `replace`
+/// deletes the stored fields first.
+pub fn merge_fields(
+    conn: &Connection,
+    dataset_id: i64,
+    fields: &[FieldMeta],
+    strategy: MergeStrategy,
+) -> Result<()> {
+    if strategy == MergeStrategy::Replace {
+        conn.execute("DELETE FROM fields WHERE dataset_id = ?1", [dataset_id])?;
+    }
+    let description = if strategy == MergeStrategy::PreserveManual
+        && merge_strategy::has_column(conn, "fields", "description_curated")?
+    {
+        "CASE WHEN description_curated = 1 THEN description ELSE COALESCE(?4, description) END"
+    } else {
+        "COALESCE(?4, description)"
+    };
+    let update = format!(
+        "UPDATE fields SET data_type = ?2, nullable = ?3, description = {}
+         WHERE id = ?1",
+        description
+    );
+
+    let mut existing: HashMap<String, i64> = {
+        let mut stmt = conn.prepare("SELECT name, id FROM fields WHERE dataset_id = ?1")?;
+        let rows = stmt
+            .query_map([dataset_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        rows
+    };
+
+    let with_ordinal = has_ordinal_column(conn)?;
+    for (ordinal, field) in fields.iter().enumerate() {
+        // A repeated name matches the stored field once
+        if let Some(id) = existing.remove(&field.name) {
+            conn.execute(
+                &update,
+                params![
+                    id,
+                    field.data_type,
+                    field.nullable as i32,
+                    field.description
+                ],
+            )?;
+            if with_ordinal {
+                conn.execute(
+                    "UPDATE fields SET ordinal = ?2 WHERE id = ?1",
+                    params![id, ordinal as i64],
+                )?;
+            }
+        } else if with_ordinal {
+            conn.execute(
+                "INSERT INTO fields (dataset_id, name, data_type, nullable, description, ordinal)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    dataset_id,
+                    field.name,
+                    field.data_type,
+                    field.nullable as i32,
+                    field.description,
+                    ordinal as i64,
+                ],
+            )?;
+        } else {
+            conn.execute(
+                "INSERT INTO fields (dataset_id, name, data_type, nullable, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    dataset_id,
+                    field.name,
+                    field.data_type,
+                    field.nullable as i32,
+                    field.description,
+                ],
+            )?;
+        }
+    }
+
+    for id in existing.into_values() {
+        conn.execute("DELETE FROM fields WHERE id = ?1", [id])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> FieldMeta {
+        FieldMeta {
+            name: name.to_string(),
+            data_type: "Utf8".to_string(),
+            nullable: true,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_replace_fields_keeps_emission_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        replace_fields(&conn, 1, &[field("zip"), field("amount")]).unwrap();
+        replace_fields(&conn, 1, &[field("zip"), field("id"), field("amount")]).unwrap();
+
+        let names: Vec<(String, i64)> = conn
+            .prepare(&format!(
+                "SELECT name, ordinal FROM fields WHERE dataset_id = 1 ORDER BY {}",
+                ORDER_BY
+            ))
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            names,
+            vec![
+                ("zip".to_string(), 0),
+                ("id".to_string(), 1),
+                ("amount".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_fields_keeps_curated_metadata() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        replace_fields(&conn, 1, &[field("cust_id"), field("legacy")]).unwrap();
+        conn.execute_batch(
+            "UPDATE fields SET description = 'Customer key', business_name = 'Customer ID'
+             WHERE name = 'cust_id';
+             INSERT INTO glossary_terms (term) VALUES ('Customer');
+             INSERT INTO term_links (term_id, field_id)
+             SELECT 1, id FROM fields WHERE name = 'cust_id';",
+        )
+        .unwrap();
+
+        // Re-emit without descriptions, dropping one field
+        let mut retyped = field("cust_id");
+        retyped.data_type = "Int64".to_string();
+        replace_fields(&conn, 1, &[field("amount"), retyped]).unwrap();
+
+        let (data_type, description, business_name, ordinal): (String, String, String, i64) = conn
+            .query_row(
+                "SELECT data_type, description, business_name, ordinal FROM fields
+                 WHERE name = 'cust_id'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(data_type, "Int64");
+        assert_eq!(description, "Customer key");
+        assert_eq!(business_name, "Customer ID");
+        assert_eq!(ordinal, 1);
+        let links: i64 = conn
+            .query_row("SELECT COUNT(*) FROM term_links", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(links, 1);
+        let legacy: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM fields WHERE name = 'legacy'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(legacy, 0);
+    }
+
+    #[test]
+    fn test_merge_fields_by_strategy() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let description = |conn: &Connection| -> Option<String> {
+            conn.query_row("SELECT description FROM fields", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        replace_fields(&conn, 1, &[field("cust_id")]).unwrap();
+        conn.execute(
+            "UPDATE fields SET description = 'Customer key', description_curated = 1",
+            [],
+        )
+        .unwrap();
+
+        let mut described = field("cust_id");
+        described.description = Some("customer_id from source".to_string());
+        merge_fields(
+            &conn,
+            1,
+            &[described.clone()],
+            MergeStrategy::PreserveManual,
+        )
+        .unwrap();
+        assert_eq!(description(&conn).as_deref(), Some("Customer key"));
+
+        merge_fields(&conn, 1, &[described.clone()], MergeStrategy::Merge).unwrap();
+        assert_eq!(
+            description(&conn).as_deref(),
+            Some("customer_id from source")
+        );
+
+        merge_fields(&conn, 1, &[field("cust_id")], MergeStrategy::Replace).unwrap();
+        assert_eq!(description(&conn), None);
+    }
+}
@@ -0,0 +1,139 @@
+//! Dataset format registry
+//!
+//! `format` used to be a free string, so "Parquet", "parquet", and "pq" were
+//! stored as different formats. Writers now resolve the format through
+//! [`normalize_format`], which maps aliases to a canonical name and rejects
+//! unknown formats.
+//!
+//! Each format also carries capability flags that decide which features apply
+//! to a dataset (for example, `?include=delta` needs a format with time travel).
+
+use crate::{CatalogError, Result};
+use serde::Serialize;
+
+/// A supported dataset format and its capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FormatSpec {
+    /// Canonical (stored) name
+    pub name: &'static str,
+    /// Alternative spellings accepted on write
+    pub aliases: &'static [&'static str],
+    /// Table format with versioned snapshots (Delta, Iceberg)
+    pub supports_time_travel: bool,
+    /// Files carry column statistics (row counts, min/max, null counts)
+    pub supports_stats: bool,
+}
+
+/// All known formats.
+pub const FORMATS: &[FormatSpec] = &[
+    FormatSpec {
+        name: "parquet",
+        aliases: &["pq"],
+        supports_time_travel: false,
+        supports_stats: true,
+    },
+    FormatSpec {
+        name: "delta",
+        aliases: &["deltalake", "delta_lake", "delta-lake"],
+        supports_time_travel: true,
+        supports_stats: true,
+    },
+    FormatSpec {
+        name: "iceberg",
+        aliases: &[],
+        supports_time_travel: true,
+        supports_stats: true,
+    },
+    FormatSpec {
+        name: "orc",
+        aliases: &[],
+        supports_time_travel: false,
+        supports_stats: true,
+    },
+    FormatSpec {
+        name: "avro",
+        aliases: &[],
+        supports_time_travel: false,
+        supports_stats: false,
+    },
+    FormatSpec {
+        name: "csv",
+        aliases: &["tsv"],
+        supports_time_travel: false,
+        supports_stats: false,
+    },
+    FormatSpec {
+        name: "json",
+        aliases: &["jsonl", "ndjson"],
+        supports_time_travel: false,
+        supports_stats: false,
+    },
+];
+
+/// Look up a format by canonical name or alias (case-insensitive).
+pub fn lookup_format(format: &str) -> Option<&'static FormatSpec> {
+    let format = format.trim().to_ascii_lowercase();
+    FORMATS
+        .iter()
+        .find(|spec| spec.name == format || spec.aliases.contains(&format.as_str()))
+}
+
+/// Resolve a format to its canonical name.
+///
+/// Returns a validation error listing the supported formats if unknown.
+pub fn normalize_format(format: &str) -> Result<&'static str> {
+    lookup_format(format).map(|spec| spec.name).ok_or_else(|| {
+        CatalogError::ValidationError(format!(
+            "Unsupported format '{}' (supported: {})",
+            format.trim(),
+            FORMATS
+                .iter()
+                .map(|spec| spec.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_format_aliases() {
+        assert_eq!(normalize_format("parquet").unwrap(), "parquet");
+        assert_eq!(normalize_format("Parquet").unwrap(), "parquet");
+        assert_eq!(normalize_format(" PQ ").unwrap(), "parquet");
+        assert_eq!(normalize_format("DeltaLake").unwrap(), "delta");
+        assert_eq!(normalize_format("ndjson").unwrap(), "json");
+    }
+
+    #[test]
+    fn test_normalize_format_rejects_unknown() {
+        let err = normalize_format("xlsx").unwrap_err().to_string();
+        assert!(err.contains("xlsx"));
+        assert!(err.contains("parquet"));
+        assert!(normalize_format("").is_err());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        assert!(lookup_format("delta").unwrap().supports_time_travel);
+        assert!(lookup_format("iceberg").unwrap().supports_time_travel);
+        assert!(!lookup_format("parquet").unwrap().supports_time_travel);
+        assert!(lookup_format("parquet").unwrap().supports_stats);
+        assert!(!lookup_format("csv").unwrap().supports_stats);
+    }
+
+    #[test]
+    fn test_names_and_aliases_are_unique() {
+        let mut all: Vec<&str> = FORMATS
+            .iter()
+            .flat_map(|spec| std::iter::once(spec.name).chain(spec.aliases.iter().copied()))
+            .collect();
+        let total = all.len();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), total);
+    }
+}
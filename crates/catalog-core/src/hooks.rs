@@ -0,0 +1,495 @@
+//! Write-path hooks
+//!
+//! Deployment-specific logic that runs on every dataset write, without forking
+//! the API server or emitter. Examples: enrich metadata from an internal CMDB,
+//! or block datasets under certain paths.
+//!
+//! A [`WriteHook`] has two phases:
+//!
+//! - [`WriteHook::pre_validate`] runs before the write is validated. It can
+//!   change the write's metadata (enrichment) or reject it.
+//! - [`WriteHook::post_commit`] runs after the write is committed, for
+//!   notifications. It cannot undo the write; failures are logged.
+//!
+//! Hooks are registered on a [`WriteHooks`] chain with a timeout and a
+//! [`FailurePolicy`], then attached to the API server state or an emitter.
+//! Hooks run in registration order.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default time a hook may take per phase
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Boxed future returned by hook methods
+pub type HookFuture<'a> = Pin<Box<dyn Future<Output = Result<(), HookError>> + Send + 'a>>;
+
+/// Kind of dataset write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+/// Where the write came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteSource {
+    Api,
+    Emitter,
+}
+
+/// A dataset write as seen by hooks.
+///
+/// Pre-validate hooks may change the metadata fields. Changes to `operation`,
+/// `source`, `name`, and `columns` are ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetWrite {
+    pub operation: WriteOperation,
+    pub source: WriteSource,
+    pub name: String,
+    pub path: Option<String>,
+    pub format: Option<String>,
+    pub description: Option<String>,
+    pub tenant: Option<String>,
+    pub domain: Option<String>,
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Column names, when the write carries a schema
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+impl DatasetWrite {
+    /// A write with only the identifying fields set.
+    pub fn new(operation: WriteOperation, source: WriteSource, name: impl Into<String>) -> Self {
+        Self {
+            operation,
+            source,
+            name: name.into(),
+            path: None,
+            format: None,
+            description: None,
+            tenant: None,
+            domain: None,
+            owner: None,
+            tags: Vec::new(),
+            columns: Vec::new(),
+        }
+    }
+}
+
+/// Error returned by a hook.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HookError {
+    /// The hook refused the write. Always blocks it, whatever the failure policy.
+    #[error("rejected: {0}")]
+    Rejected(String),
+
+    /// The hook could not do its job. The failure policy decides what happens.
+    #[error("failed: {0}")]
+    Failed(String),
+}
+
+/// Error returned when a hook chain stops a write.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WriteHookError {
+    /// A hook rejected the write
+    #[error("Write rejected by hook '{hook}': {reason}")]
+    Rejected { hook: String, reason: String },
+
+    /// A fail-closed hook errored or timed out
+    #[error("Write hook '{hook}' failed: {reason}")]
+    Unavailable { hook: String, reason: String },
+}
+
+/// A hook on dataset writes.
+///
+/// Both phases default to doing nothing, so a hook implements only the phase
+/// it needs.
+pub trait WriteHook: Send + Sync {
+    /// Name used in logs and error messages
+    fn name(&self) -> &str;
+
+    /// Inspect or enrich a write before validation. Return
+    /// [`HookError::Rejected`] to block it.
+    fn pre_validate<'a>(&'a self, _write: &'a mut DatasetWrite) -> HookFuture<'a> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// React to a committed write.
+    fn post_commit<'a>(&'a self, _write: &'a DatasetWrite) -> HookFuture<'a> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// What happens when a pre-validate hook errors or times out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Log the failure and continue the write without the hook's changes
+    #[default]
+    FailOpen,
+    /// Block the write
+    FailClosed,
+}
+
+impl FailurePolicy {
+    /// Parse `fail-open` or `fail-closed`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "fail-open" | "open" => Some(FailurePolicy::FailOpen),
+            "fail-closed" | "closed" => Some(FailurePolicy::FailClosed),
+            _ => None,
+        }
+    }
+}
+
+/// How a registered hook is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HookOptions {
+    /// Maximum time per phase
+    pub timeout: Duration,
+    pub failure_policy: FailurePolicy,
+}
+
+impl Default for HookOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_HOOK_TIMEOUT,
+            failure_policy: FailurePolicy::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RegisteredHook {
+    hook: Arc<dyn WriteHook>,
+    options: HookOptions,
+}
+
+/// An ordered chain of write hooks.
+#[derive(Clone, Default)]
+pub struct WriteHooks {
+    hooks: Vec<RegisteredHook>,
+}
+
+impl std::fmt::Debug for WriteHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|h| h.hook.name()))
+            .finish()
+    }
+}
+
+impl WriteHooks {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a hook to the end of the chain
+    pub fn register(&mut self, hook: Arc<dyn WriteHook>, options: HookOptions) -> &mut Self {
+        self.hooks.push(RegisteredHook { hook, options });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Run every pre-validate hook in order.
+    ///
+    /// Each hook works on a copy of the write that is kept only if the hook
+    /// succeeds, so a hook that fails or times out leaves no partial changes.
+    pub async fn run_pre_validate(&self, write: &mut DatasetWrite) -> Result<(), WriteHookError> {
+        for registered in &self.hooks {
+            let name = registered.hook.name();
+            let mut candidate = write.clone();
+            let result = tokio::time::timeout(
+                registered.options.timeout,
+                registered.hook.pre_validate(&mut candidate),
+            )
+            .await
+            .unwrap_or_else(|_| {
+                Err(HookError::Failed(format!(
+                    "timed out after {}ms",
+                    registered.options.timeout.as_millis()
+                )))
+            });
+
+            match result {
+                Ok(()) => {
+                    // Identity fields are not the hook's to change
+                    candidate.operation = write.operation;
+                    candidate.source = write.source;
+                    candidate.name = write.name.clone();
+                    candidate.columns = std::mem::take(&mut write.columns);
+                    *write = candidate;
+                }
+                Err(HookError::Rejected(reason)) => {
+                    tracing::info!(hook = %name, dataset = %write.name, reason = %reason, "Write rejected by hook");
+                    return Err(WriteHookError::Rejected {
+                        hook: name.to_string(),
+                        reason,
+                    });
+                }
+                Err(HookError::Failed(reason)) => match registered.options.failure_policy {
+                    FailurePolicy::FailOpen => {
+                        tracing::warn!(hook = %name, dataset = %write.name, error = %reason, "Write hook failed, continuing");
+                    }
+                    FailurePolicy::FailClosed => {
+                        tracing::warn!(hook = %name, dataset = %write.name, error = %reason, "Write hook failed, blocking write");
+                        return Err(WriteHookError::Unavailable {
+                            hook: name.to_string(),
+                            reason,
+                        });
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Run every post-commit hook in order. Failures and timeouts are logged.
+    pub async fn run_post_commit(&self, write: &DatasetWrite) {
+        for registered in &self.hooks {
+            let name = registered.hook.name();
+            match tokio::time::timeout(
+                registered.options.timeout,
+                registered.hook.post_commit(write),
+            )
+            .await
+            {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    tracing::warn!(hook = %name, dataset = %write.name, error = %e, "Post-commit hook failed");
+                }
+                Err(_) => {
+                    tracing::warn!(hook = %name, dataset = %write.name, "Post-commit hook timed out");
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Built-in Hooks
+// =============================================================================
+
+/// Rejects creates and updates whose path matches a deny-listed glob.
+///
+/// Paths are normalized before matching, so patterns should use the
+/// canonical form (e.g. lowercase scheme, no trailing slash).
+///
+/// Patterns use [`crate::auto_tagging::glob_match`] syntax (`*` and `?`).
+#[derive(Debug, Clone)]
+pub struct PathDenyHook {
+    patterns: Vec<String>,
+}
+
+impl PathDenyHook {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self { patterns }
+    }
+}
+
+impl WriteHook for PathDenyHook {
+    fn name(&self) -> &str {
+        "path-deny"
+    }
+
+    fn pre_validate<'a>(&'a self, write: &'a mut DatasetWrite) -> HookFuture<'a> {
+        Box::pin(async move {
+            let Some(raw) = write.path.as_deref() else {
+                return Ok(());
+            };
+            // Match the stored spelling so alternate spellings can't slip past
+            let path = crate::paths::normalize_path(raw).unwrap_or_else(|_| raw.to_string());
+            let path = path.as_str();
+            match self
+                .patterns
+                .iter()
+                .find(|pattern| crate::auto_tagging::glob_match(pattern, path))
+            {
+                Some(pattern) => Err(HookError::Rejected(format!(
+                    "path '{}' matches denied pattern '{}'",
+                    path, pattern
+                ))),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct OwnerHook;
+
+    impl WriteHook for OwnerHook {
+        fn name(&self) -> &str {
+            "owner"
+        }
+
+        fn pre_validate<'a>(&'a self, write: &'a mut DatasetWrite) -> HookFuture<'a> {
+            Box::pin(async move {
+                write.owner = Some("cmdb-team".to_string());
+                write.tags.push("cmdb".to_string());
+                // Renames are ignored
+                write.name = "renamed".to_string();
+                Ok(())
+            })
+        }
+    }
+
+    struct FailingHook {
+        calls: AtomicUsize,
+    }
+
+    impl WriteHook for FailingHook {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn pre_validate<'a>(&'a self, write: &'a mut DatasetWrite) -> HookFuture<'a> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                write.owner = Some("partial".to_string());
+                Err(HookError::Failed("cmdb unreachable".to_string()))
+            })
+        }
+
+        fn post_commit<'a>(&'a self, _write: &'a DatasetWrite) -> HookFuture<'a> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                Err(HookError::Failed("notify failed".to_string()))
+            })
+        }
+    }
+
+    struct SlowHook;
+
+    impl WriteHook for SlowHook {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn pre_validate<'a>(&'a self, _write: &'a mut DatasetWrite) -> HookFuture<'a> {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+        }
+    }
+
+    fn write(path: &str) -> DatasetWrite {
+        DatasetWrite {
+            path: Some(path.to_string()),
+            ..DatasetWrite::new(WriteOperation::Create, WriteSource::Api, "orders")
+        }
+    }
+
+    fn options(failure_policy: FailurePolicy) -> HookOptions {
+        HookOptions {
+            timeout: Duration::from_millis(50),
+            failure_policy,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pre_validate_enriches_but_keeps_identity() {
+        let mut hooks = WriteHooks::new();
+        hooks.register(Arc::new(OwnerHook), HookOptions::default());
+
+        let mut write = write("s3://bucket/orders");
+        hooks.run_pre_validate(&mut write).await.unwrap();
+        assert_eq!(write.owner.as_deref(), Some("cmdb-team"));
+        assert_eq!(write.tags, vec!["cmdb"]);
+        assert_eq!(write.name, "orders");
+    }
+
+    #[tokio::test]
+    async fn test_path_deny_rejects_regardless_of_policy() {
+        let mut hooks = WriteHooks::new();
+        hooks.register(
+            Arc::new(PathDenyHook::new(vec!["s3://scratch/*".to_string()])),
+            options(FailurePolicy::FailOpen),
+        );
+
+        let mut allowed = write("s3://bucket/orders");
+        assert!(hooks.run_pre_validate(&mut allowed).await.is_ok());
+
+        let mut denied = write("s3://scratch/tmp");
+        let err = hooks.run_pre_validate(&mut denied).await.unwrap_err();
+        assert!(matches!(err, WriteHookError::Rejected { ref hook, .. } if hook == "path-deny"));
+    }
+
+    #[tokio::test]
+    async fn test_failure_policies() {
+        let failing = Arc::new(FailingHook {
+            calls: AtomicUsize::new(0),
+        });
+
+        let mut open = WriteHooks::new();
+        open.register(failing.clone(), options(FailurePolicy::FailOpen));
+        let mut write_open = write("s3://bucket/orders");
+        open.run_pre_validate(&mut write_open).await.unwrap();
+        // Changes from a failed hook are discarded
+        assert_eq!(write_open.owner, None);
+
+        let mut closed = WriteHooks::new();
+        closed.register(failing.clone(), options(FailurePolicy::FailClosed));
+        let err = closed
+            .run_pre_validate(&mut write("s3://bucket/orders"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WriteHookError::Unavailable { .. }));
+
+        // Post-commit failures are only logged
+        closed.run_post_commit(&write("s3://bucket/orders")).await;
+        assert_eq!(failing.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_follow_failure_policy() {
+        let mut open = WriteHooks::new();
+        open.register(Arc::new(SlowHook), options(FailurePolicy::FailOpen));
+        assert!(open
+            .run_pre_validate(&mut write("s3://bucket/orders"))
+            .await
+            .is_ok());
+
+        let mut closed = WriteHooks::new();
+        closed.register(Arc::new(SlowHook), options(FailurePolicy::FailClosed));
+        let err = closed
+            .run_pre_validate(&mut write("s3://bucket/orders"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn test_parse_failure_policy() {
+        assert_eq!(
+            FailurePolicy::parse("fail-closed"),
+            Some(FailurePolicy::FailClosed)
+        );
+        assert_eq!(
+            FailurePolicy::parse("FAIL_OPEN"),
+            Some(FailurePolicy::FailOpen)
+        );
+        assert_eq!(FailurePolicy::parse("retry"), None);
+    }
+}
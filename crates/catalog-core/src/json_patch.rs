@@ -0,0 +1,366 @@
+//! JSON Patch
+//!
+//! Applies JSON Patch (RFC 6902) documents to a dataset's editable metadata,
+//! viewed as one JSON document:
+//!
+//! ```json
+//! { "description": "Orders", "tags": ["finance"], "properties": { "owner_team": "sales" } }
+//! ```
+//!
+//! `properties` is the dataset's custom metadata. Only the `add`, `remove`,
+//! and `replace` operations are supported, on these paths:
+//!
+//! - `/description`
+//! - `/tags`, `/tags/<index>`, `/tags/-`
+//! - `/properties` and anything below it
+//!
+//! A patch is applied as a whole: if any operation fails, nothing changes.
+
+use crate::{validation, CatalogError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Media type of JSON Patch documents
+pub const CONTENT_TYPE: &str = "application/json-patch+json";
+
+/// Top-level paths a patch may touch
+pub const ALLOWED_PATHS: &[&str] = &["/description", "/tags", "/properties"];
+
+/// Most operations accepted in one patch
+pub const MAX_OPERATIONS: usize = 100;
+
+/// A JSON Patch operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase", deny_unknown_fields)]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+impl PatchOperation {
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOperation::Add { path, .. }
+            | PatchOperation::Remove { path }
+            | PatchOperation::Replace { path, .. } => path,
+        }
+    }
+}
+
+/// The patchable metadata of a dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchableMetadata {
+    pub description: Option<String>,
+    /// Sorted and deduplicated
+    pub tags: Vec<String>,
+    /// Custom metadata, always an object
+    pub properties: Value,
+}
+
+impl PatchableMetadata {
+    pub fn new(
+        description: Option<String>,
+        mut tags: Vec<String>,
+        properties: Option<Value>,
+    ) -> Self {
+        tags.sort();
+        tags.dedup();
+        Self {
+            description,
+            tags,
+            properties: properties.unwrap_or_else(|| json!({})),
+        }
+    }
+
+    /// The metadata as the document patches are applied to.
+    pub fn to_document(&self) -> Value {
+        json!({
+            "description": self.description,
+            "tags": self.tags,
+            "properties": self.properties,
+        })
+    }
+
+    /// Read patched metadata back. A removed member becomes empty.
+    pub fn from_document(document: &Value) -> Result<Self> {
+        let description = match document.get("description") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(_) => {
+                return Err(CatalogError::ValidationError(
+                    "description must be a string or null".to_string(),
+                ))
+            }
+        };
+        let tags = match document.get("tags") {
+            None => Vec::new(),
+            Some(Value::Array(tags)) => tags
+                .iter()
+                .map(|tag| {
+                    let tag = tag.as_str().ok_or_else(|| {
+                        CatalogError::ValidationError("tags must be strings".to_string())
+                    })?;
+                    validation::validate_tag(tag)?;
+                    Ok(tag.to_string())
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Some(_) => {
+                return Err(CatalogError::ValidationError(
+                    "tags must be an array".to_string(),
+                ))
+            }
+        };
+        let properties = match document.get("properties") {
+            None => json!({}),
+            Some(properties @ Value::Object(_)) => properties.clone(),
+            Some(_) => {
+                return Err(CatalogError::ValidationError(
+                    "properties must be an object".to_string(),
+                ))
+            }
+        };
+        Ok(Self::new(description, tags, Some(properties)))
+    }
+}
+
+/// Parse a JSON Patch document.
+pub fn parse(body: &[u8]) -> Result<Vec<PatchOperation>> {
+    let operations: Vec<PatchOperation> = serde_json::from_slice(body)
+        .map_err(|e| CatalogError::ValidationError(format!("Invalid JSON Patch: {}", e)))?;
+    if operations.len() > MAX_OPERATIONS {
+        return Err(CatalogError::ValidationError(format!(
+            "Too many patch operations: {} > {}",
+            operations.len(),
+            MAX_OPERATIONS
+        )));
+    }
+    Ok(operations)
+}
+
+/// Apply operations to metadata, checking each path is allowed.
+pub fn apply(
+    metadata: &PatchableMetadata,
+    operations: &[PatchOperation],
+) -> Result<PatchableMetadata> {
+    let mut document = metadata.to_document();
+    for operation in operations {
+        let tokens = parse_pointer(operation.path())?;
+        check_path(operation.path(), &tokens)?;
+        match operation {
+            PatchOperation::Add { value, .. } => add(&mut document, &tokens, value.clone())?,
+            PatchOperation::Remove { .. } => {
+                remove(&mut document, &tokens)?;
+            }
+            PatchOperation::Replace { value, .. } => {
+                *target(&mut document, &tokens)? = value.clone();
+            }
+        }
+    }
+    PatchableMetadata::from_document(&document)
+}
+
+fn parse_pointer(path: &str) -> Result<Vec<String>> {
+    let Some(rest) = path.strip_prefix('/') else {
+        return Err(CatalogError::ValidationError(format!(
+            "Invalid JSON Pointer '{}'",
+            path
+        )));
+    };
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn check_path(path: &str, tokens: &[String]) -> Result<()> {
+    let allowed = match tokens[0].as_str() {
+        "description" => tokens.len() == 1,
+        "tags" => tokens.len() <= 2,
+        "properties" => true,
+        _ => false,
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(CatalogError::ValidationError(format!(
+            "Path '{}' cannot be patched (allowed: {})",
+            path,
+            ALLOWED_PATHS.join(", ")
+        )))
+    }
+}
+
+fn not_found(tokens: &[String]) -> CatalogError {
+    CatalogError::ValidationError(format!("Path '/{}' does not exist", tokens.join("/")))
+}
+
+fn array_index(token: &str, len: usize, tokens: &[String]) -> Result<usize> {
+    // RFC 6901: no leading zeros or signs
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return Err(not_found(tokens));
+    }
+    token
+        .parse::<usize>()
+        .ok()
+        .filter(|index| *index < len)
+        .ok_or_else(|| not_found(tokens))
+}
+
+/// The existing value at a path
+fn target<'a>(document: &'a mut Value, tokens: &[String]) -> Result<&'a mut Value> {
+    let mut current = document;
+    for token in tokens {
+        current = match current {
+            Value::Object(map) => map.get_mut(token),
+            Value::Array(items) => {
+                let index = array_index(token, items.len(), tokens)?;
+                items.get_mut(index)
+            }
+            _ => None,
+        }
+        .ok_or_else(|| not_found(tokens))?;
+    }
+    Ok(current)
+}
+
+fn add(document: &mut Value, tokens: &[String], value: Value) -> Result<()> {
+    let (last, parent) = tokens.split_last().expect("pointer has a token");
+    match target(document, parent)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(items) if last == "-" => items.push(value),
+        Value::Array(items) => {
+            // Adding may insert at the end
+            let index = array_index(last, items.len() + 1, tokens)?;
+            items.insert(index, value);
+        }
+        _ => return Err(not_found(tokens)),
+    }
+    Ok(())
+}
+
+fn remove(document: &mut Value, tokens: &[String]) -> Result<Value> {
+    let (last, parent) = tokens.split_last().expect("pointer has a token");
+    match target(document, parent)? {
+        Value::Object(map) => map.remove(last).ok_or_else(|| not_found(tokens)),
+        Value::Array(items) => {
+            let index = array_index(last, items.len(), tokens)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(not_found(tokens)),
+    }
+}
+
+/// Map of the top-level members a patch changed, for field permissions.
+pub fn changed_members(
+    before: &PatchableMetadata,
+    after: &PatchableMetadata,
+) -> Map<String, Value> {
+    let (before, after) = (before.to_document(), after.to_document());
+    let mut changed = Map::new();
+    for member in ["description", "tags", "properties"] {
+        if before[member] != after[member] {
+            changed.insert(member.to_string(), after[member].clone());
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> PatchableMetadata {
+        PatchableMetadata::new(
+            Some("Orders".to_string()),
+            vec!["finance".to_string(), "core".to_string()],
+            Some(json!({"team": "sales", "spark": {"partitions": 8}})),
+        )
+    }
+
+    fn patch(operations: Value) -> Vec<PatchOperation> {
+        parse(operations.to_string().as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_apply_operations() {
+        let patched = apply(
+            &metadata(),
+            &patch(json!([
+                {"op": "replace", "path": "/description", "value": "All orders"},
+                {"op": "add", "path": "/tags/-", "value": "pii"},
+                {"op": "remove", "path": "/tags/0"},
+                {"op": "add", "path": "/properties/warehouse", "value": "wh_small"},
+                {"op": "replace", "path": "/properties/spark/partitions", "value": 16},
+                {"op": "remove", "path": "/properties/team"},
+                {"op": "add", "path": "/properties/a~1b", "value": true}
+            ])),
+        )
+        .unwrap();
+        assert_eq!(patched.description.as_deref(), Some("All orders"));
+        assert_eq!(patched.tags, vec!["finance", "pii"]);
+        assert_eq!(
+            patched.properties,
+            json!({"spark": {"partitions": 16}, "warehouse": "wh_small", "a/b": true})
+        );
+
+        let cleared = apply(
+            &metadata(),
+            &patch(json!([
+                {"op": "remove", "path": "/description"},
+                {"op": "replace", "path": "/tags", "value": []}
+            ])),
+        )
+        .unwrap();
+        assert_eq!(cleared.description, None);
+        assert!(cleared.tags.is_empty());
+        assert_eq!(
+            changed_members(&metadata(), &cleared)
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["description", "tags"]
+        );
+    }
+
+    #[test]
+    fn test_rejected_patches() {
+        let rejected = |operations: Value| apply(&metadata(), &patch(operations)).is_err();
+
+        // Paths outside the allowed members
+        assert!(rejected(
+            json!([{"op": "replace", "path": "/path", "value": "/x"}])
+        ));
+        assert!(rejected(
+            json!([{"op": "add", "path": "/description/x", "value": 1}])
+        ));
+        assert!(rejected(json!([{"op": "remove", "path": ""}])));
+        // Missing targets and bad indexes
+        assert!(rejected(
+            json!([{"op": "remove", "path": "/properties/missing"}])
+        ));
+        assert!(rejected(
+            json!([{"op": "replace", "path": "/tags/5", "value": "x"}])
+        ));
+        assert!(rejected(json!([{"op": "remove", "path": "/tags/01"}])));
+        assert!(rejected(json!([{"op": "remove", "path": "/tags/+1"}])));
+        // Results of the wrong shape
+        assert!(rejected(
+            json!([{"op": "replace", "path": "/tags", "value": "pii"}])
+        ));
+        assert!(rejected(
+            json!([{"op": "add", "path": "/tags/-", "value": "bad tag"}])
+        ));
+        assert!(rejected(
+            json!([{"op": "replace", "path": "/properties", "value": []}])
+        ));
+
+        // Unsupported operations
+        assert!(parse(br#"[{"op": "move", "from": "/tags/0", "path": "/tags/1"}]"#).is_err());
+        assert!(parse(br#"{"op": "remove", "path": "/tags/0"}"#).is_err());
+    }
+}
@@ -5,8 +5,27 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+pub mod auto_tagging;
+pub mod bundle;
+pub mod custom_metadata;
+pub mod dataset_uuids;
+pub mod emission_state;
+pub mod external_nodes;
+pub mod field_ordinals;
+pub mod formats;
+pub mod hooks;
+pub mod json_patch;
+pub mod lineage_cycles;
+pub mod lineage_mode;
+pub mod merge_strategy;
 pub mod migrations;
+pub mod path_history;
+pub mod paths;
+pub mod placeholders;
+pub mod seed;
+pub mod urn;
 pub mod validation;
+pub mod write_report;
 
 /// Metadata for a dataset in the catalog
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,275 @@
+//! Lineage cycle detection
+//!
+//! Dataset-level lineage must stay acyclic: diagrams, impact analysis, and
+//! quality propagation walk it assuming every path ends. Write paths (the
+//! emitter, dataset creation, and the lineage endpoints) call [`check_edge`]
+//! before inserting an edge, which rejects edges that would close a cycle.
+//! [`find_cycles`] reports cycles already in a catalog, e.g. edges written
+//! before the check existed.
+//!
+//! Edges of trashed datasets count, since restoring the dataset brings them
+//! back.
+
+use crate::{CatalogError, Result};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// A set of datasets that reach each other through lineage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineageCycle {
+    /// Datasets in the cycle, sorted by name
+    pub datasets: Vec<String>,
+    /// One closed path through them, starting and ending at the same dataset
+    pub path: Vec<String>,
+}
+
+fn dataset_name(conn: &Connection, id: i64) -> Result<String> {
+    Ok(
+        conn.query_row("SELECT name FROM datasets WHERE id = ?1", [id], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
+/// Shortest lineage path from `from` to `to` following upstream to
+/// downstream edges, as dataset ids including both ends.
+fn downstream_path(conn: &Connection, from: i64, to: i64) -> Result<Option<Vec<i64>>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT downstream_dataset_id FROM lineage WHERE upstream_dataset_id = ?1",
+    )?;
+    let mut parents: HashMap<i64, i64> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            let mut path = vec![to];
+            let mut current = to;
+            while current != from {
+                current = parents[&current];
+                path.push(current);
+            }
+            path.reverse();
+            return Ok(Some(path));
+        }
+        let next = stmt
+            .query_map([id], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for next in next {
+            if next != from && !parents.contains_key(&next) {
+                parents.insert(next, id);
+                queue.push_back(next);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Reject an upstream -> downstream edge that would create a cycle.
+///
+/// Returns a [`CatalogError::ValidationError`] naming the path that the edge
+/// would close. Existing edges are always accepted.
+pub fn check_edge(conn: &Connection, upstream_id: i64, downstream_id: i64) -> Result<()> {
+    if upstream_id == downstream_id {
+        return Err(CatalogError::ValidationError(format!(
+            "Dataset '{}' cannot be its own upstream",
+            dataset_name(conn, upstream_id)?
+        )));
+    }
+    let exists = conn
+        .prepare_cached(
+            "SELECT 1 FROM lineage WHERE upstream_dataset_id = ?1 AND downstream_dataset_id = ?2",
+        )?
+        .exists([upstream_id, downstream_id])?;
+    if exists {
+        return Ok(());
+    }
+
+    let Some(path) = downstream_path(conn, downstream_id, upstream_id)? else {
+        return Ok(());
+    };
+    let mut names = path
+        .iter()
+        .map(|id| dataset_name(conn, *id))
+        .collect::<Result<Vec<_>>>()?;
+    names.push(names[0].clone());
+    Err(CatalogError::ValidationError(format!(
+        "Lineage edge '{}' -> '{}' would create a cycle: {}",
+        names[names.len() - 2],
+        names[0],
+        names.join(" -> ")
+    )))
+}
+
+/// Find every lineage cycle in the catalog, sorted by first dataset name.
+pub fn find_cycles(conn: &Connection) -> Result<Vec<LineageCycle>> {
+    let mut stmt = conn.prepare(
+        "SELECT upstream_dataset_id, downstream_dataset_id FROM lineage
+         ORDER BY upstream_dataset_id, downstream_dataset_id",
+    )?;
+    let mut graph: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for edge in stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))? {
+        let (upstream, downstream) = edge?;
+        graph.entry(upstream).or_default().push(downstream);
+        graph.entry(downstream).or_default();
+    }
+
+    let mut cycles = Vec::new();
+    for component in strongly_connected(&graph) {
+        let start = component[0];
+        let closed = if component.len() == 1 {
+            // A single dataset is only a cycle with an edge to itself
+            if !graph[&start].contains(&start) {
+                continue;
+            }
+            vec![start, start]
+        } else {
+            let next = graph[&start]
+                .iter()
+                .copied()
+                .find(|n| component.contains(n))
+                .expect("strongly connected component has an internal edge");
+            let mut path = vec![start];
+            path.extend(downstream_path(conn, next, start)?.unwrap_or_default());
+            path
+        };
+
+        let mut datasets = component
+            .iter()
+            .map(|id| dataset_name(conn, *id))
+            .collect::<Result<Vec<_>>>()?;
+        datasets.sort();
+        let path = closed
+            .iter()
+            .map(|id| dataset_name(conn, *id))
+            .collect::<Result<Vec<_>>>()?;
+        cycles.push(LineageCycle { datasets, path });
+    }
+    cycles.sort_by(|a, b| a.datasets.cmp(&b.datasets));
+    Ok(cycles)
+}
+
+/// Strongly connected components (Tarjan), each sorted by id.
+fn strongly_connected(graph: &BTreeMap<i64, Vec<i64>>) -> Vec<Vec<i64>> {
+    let mut index: HashMap<i64, usize> = HashMap::new();
+    let mut low: HashMap<i64, usize> = HashMap::new();
+    let mut on_stack: HashMap<i64, bool> = HashMap::new();
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+
+    for &root in graph.keys() {
+        if index.contains_key(&root) {
+            continue;
+        }
+        // Iterative DFS: (node, next neighbor to visit)
+        let mut work = vec![(root, 0usize)];
+        while let Some(&mut (node, ref mut next)) = work.last_mut() {
+            if *next == 0 && !index.contains_key(&node) {
+                let i = index.len();
+                index.insert(node, i);
+                low.insert(node, i);
+                stack.push(node);
+                on_stack.insert(node, true);
+            }
+            if let Some(&neighbor) = graph[&node].get(*next) {
+                *next += 1;
+                if !index.contains_key(&neighbor) {
+                    work.push((neighbor, 0));
+                } else if on_stack.get(&neighbor) == Some(&true) {
+                    let l = low[&node].min(index[&neighbor]);
+                    low.insert(node, l);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                let l = low[&parent].min(low[&node]);
+                low.insert(parent, l);
+            }
+            if low[&node] == index[&node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.insert(member, false);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(edges: &[(&str, &str)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        for name in ["a", "b", "c", "d", "e"] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', datetime('now'), datetime('now'))",
+                [name],
+            )
+            .unwrap();
+        }
+        for (upstream, downstream) in edges {
+            add_edge(&conn, upstream, downstream);
+        }
+        conn
+    }
+
+    fn id(conn: &Connection, name: &str) -> i64 {
+        conn.query_row("SELECT id FROM datasets WHERE name = ?1", [name], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    fn add_edge(conn: &Connection, upstream: &str, downstream: &str) {
+        conn.execute(
+            "INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (?1, ?2, datetime('now'))",
+            [id(conn, upstream), id(conn, downstream)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_check_edge_rejects_cycles() {
+        let conn = setup(&[("a", "b"), ("b", "c")]);
+
+        let err = check_edge(&conn, id(&conn, "c"), id(&conn, "a")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: Lineage edge 'c' -> 'a' would create a cycle: a -> b -> c -> a"
+        );
+        assert!(check_edge(&conn, id(&conn, "b"), id(&conn, "b")).is_err());
+
+        // Diamonds and re-reported edges are fine
+        assert!(check_edge(&conn, id(&conn, "a"), id(&conn, "c")).is_ok());
+        assert!(check_edge(&conn, id(&conn, "a"), id(&conn, "b")).is_ok());
+        assert!(check_edge(&conn, id(&conn, "d"), id(&conn, "a")).is_ok());
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let conn = setup(&[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("e", "e")]);
+        assert!(find_cycles(&setup(&[("a", "b"), ("a", "c"), ("b", "c")]))
+            .unwrap()
+            .is_empty());
+
+        let cycles = find_cycles(&conn).unwrap();
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].datasets, vec!["a", "b", "c"]);
+        assert_eq!(cycles[0].path, vec!["a", "b", "c", "a"]);
+        assert_eq!(cycles[1].datasets, vec!["e"]);
+        assert_eq!(cycles[1].path, vec!["e", "e"]);
+    }
+}
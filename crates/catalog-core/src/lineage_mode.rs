@@ -0,0 +1,158 @@
+//! Lineage modes
+//!
+//! What to do when lineage references a dataset that isn't registered:
+//!
+//! - `strict`: fail the write
+//! - `placeholder`: create a placeholder dataset (see [`crate::placeholders`])
+//!   so the edge is kept
+//! - `ignore`: skip the edge
+//!
+//! Trashed datasets count as unregistered, but can't be replaced by a
+//! placeholder while they are in the trash, so their edges are skipped.
+
+use crate::{placeholders, CatalogError, Result};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Handling of lineage to unregistered datasets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineageMode {
+    /// Fail the write
+    Strict,
+    /// Create a placeholder dataset
+    Placeholder,
+    /// Skip the edge
+    #[default]
+    Ignore,
+}
+
+impl LineageMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "strict" => Some(LineageMode::Strict),
+            "placeholder" => Some(LineageMode::Placeholder),
+            "ignore" => Some(LineageMode::Ignore),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineageMode::Strict => "strict",
+            LineageMode::Placeholder => "placeholder",
+            LineageMode::Ignore => "ignore",
+        }
+    }
+}
+
+impl fmt::Display for LineageMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a lineage endpoint was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolved {
+    /// A live dataset
+    Registered(i64),
+    /// A placeholder created for this reference
+    Placeholder(i64),
+    /// Not registered; the edge should be skipped
+    Skipped,
+}
+
+impl Resolved {
+    /// Dataset id to link, unless the edge is skipped
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            Resolved::Registered(id) | Resolved::Placeholder(id) => Some(*id),
+            Resolved::Skipped => None,
+        }
+    }
+}
+
+/// Resolve a dataset referenced by lineage, applying the lineage mode.
+///
+/// Placeholders need migration v1.18.0; on older catalogs `placeholder`
+/// mode skips the edge.
+pub fn resolve(conn: &Connection, name: &str, mode: LineageMode) -> Result<Resolved> {
+    // `deleted_at` arrives with migration v1.13.0
+    let has_trash: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('datasets') WHERE name = 'deleted_at')",
+        [],
+        |row| row.get(0),
+    )?;
+    let sql = if has_trash {
+        "SELECT id, deleted_at IS NOT NULL FROM datasets WHERE name = ?1"
+    } else {
+        "SELECT id, 0 FROM datasets WHERE name = ?1"
+    };
+    let existing: Option<(i64, bool)> = conn
+        .query_row(sql, [name], |row| Ok((row.get(0)?, row.get(1)?)))
+        .optional()?;
+
+    match (existing, mode) {
+        (Some((id, false)), _) => Ok(Resolved::Registered(id)),
+        (Some((_, true)), LineageMode::Strict) => Err(CatalogError::ValidationError(format!(
+            "Dataset '{}' is in the trash",
+            name
+        ))),
+        (None, LineageMode::Strict) => Err(CatalogError::ValidationError(format!(
+            "Dataset '{}' not found",
+            name
+        ))),
+        (None, LineageMode::Placeholder) if placeholders::has_status_column(conn)? => Ok(
+            Resolved::Placeholder(placeholders::create_placeholder(conn, name)?),
+        ),
+        _ => Ok(Resolved::Skipped),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_by_mode() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated, deleted_at)
+             VALUES ('trashed', '/data', 'parquet', datetime('now'), datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+
+        assert!(matches!(
+            resolve(&conn, "raw", LineageMode::Strict),
+            Err(CatalogError::ValidationError(_))
+        ));
+        assert_eq!(
+            resolve(&conn, "raw", LineageMode::Ignore).unwrap(),
+            Resolved::Skipped
+        );
+        let Resolved::Placeholder(id) = resolve(&conn, "raw", LineageMode::Placeholder).unwrap()
+        else {
+            panic!("expected a placeholder");
+        };
+        assert_eq!(
+            resolve(&conn, "raw", LineageMode::Strict).unwrap(),
+            Resolved::Registered(id)
+        );
+
+        assert_eq!(
+            resolve(&conn, "trashed", LineageMode::Placeholder).unwrap(),
+            Resolved::Skipped
+        );
+        assert!(resolve(&conn, "trashed", LineageMode::Strict).is_err());
+        assert_eq!(
+            LineageMode::parse("placeholder"),
+            Some(LineageMode::Placeholder)
+        );
+        assert_eq!(LineageMode::parse("lenient"), None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Merge strategies
+//!
+//! How an emission is merged with a dataset that is already registered:
+//!
+//! - `replace`: the emission is the whole truth. Fields are rewritten, losing
+//!   their descriptions, classifications, business names, and glossary links,
+//!   and tags are replaced
+//! - `merge`: fields are matched by name and updated in place; an emitted
+//!   description overwrites the stored one. Emitted tags are added and no
+//!   tag is removed
+//! - `preserve-manual`: like `merge`, but descriptions set through the field
+//!   metadata API win over emitted ones, and only tags the emitter wrote are
+//!   replaced, so tags added by hand stay
+//!
+//! Classifications hang off field rows, so `merge` and `preserve-manual` keep
+//! them, verified or not, for every field still in the schema.
+//!
+//! Telling curated metadata apart needs migration v1.43.0; on older catalogs
+//! `preserve-manual` behaves like `merge`.
+
+use crate::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Merging of an emission with the registered dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// Overwrite fields and tags
+    Replace,
+    /// Update fields in place and add tags
+    Merge,
+    /// Keep curated descriptions and tags added by hand
+    #[default]
+    PreserveManual,
+}
+
+impl MergeStrategy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "replace" => Some(MergeStrategy::Replace),
+            "merge" => Some(MergeStrategy::Merge),
+            "preserve-manual" => Some(MergeStrategy::PreserveManual),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStrategy::Replace => "replace",
+            MergeStrategy::Merge => "merge",
+            MergeStrategy::PreserveManual => "preserve-manual",
+        }
+    }
+}
+
+impl fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether `table` has `column`.
+pub(crate) fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2",
+            params![table, column],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Write a dataset's emitted tags according to `strategy`.
+pub fn write_tags(
+    conn: &Connection,
+    dataset_id: i64,
+    tags: &[String],
+    strategy: MergeStrategy,
+) -> Result<()> {
+    let tracked = has_column(conn, "tags", "emitted")?;
+    match strategy {
+        MergeStrategy::Replace => {
+            conn.execute("DELETE FROM tags WHERE dataset_id = ?1", [dataset_id])?;
+        }
+        MergeStrategy::PreserveManual if tracked => {
+            conn.execute(
+                "DELETE FROM tags WHERE dataset_id = ?1 AND emitted = 1",
+                [dataset_id],
+            )?;
+        }
+        _ => {}
+    }
+
+    // A tag already added by hand stays manual
+    let sql = if tracked {
+        "INSERT OR IGNORE INTO tags (dataset_id, tag, emitted) VALUES (?1, ?2, 1)"
+    } else {
+        "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)"
+    };
+    for tag in tags {
+        conn.execute(sql, params![dataset_id, tag])?;
+    }
+    Ok(())
+}
+
+/// Mark tags the emitter added some other way, such as auto-tagging rules,
+/// as emitted so the next emission can replace them.
+pub fn mark_emitted(conn: &Connection, dataset_id: i64, tags: &[String]) -> Result<()> {
+    if !has_column(conn, "tags", "emitted")? {
+        return Ok(());
+    }
+    for tag in tags {
+        conn.execute(
+            "UPDATE tags SET emitted = 1 WHERE dataset_id = ?1 AND tag = ?2",
+            params![dataset_id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO tags (dataset_id, tag) VALUES (1, 'gold');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn tags(conn: &Connection) -> Vec<String> {
+        conn.prepare("SELECT tag FROM tags WHERE dataset_id = 1 ORDER BY tag")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    fn emit(conn: &Connection, emitted: &[&str], strategy: MergeStrategy) {
+        let emitted: Vec<String> = emitted.iter().map(|t| t.to_string()).collect();
+        write_tags(conn, 1, &emitted, strategy).unwrap();
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        for strategy in [
+            MergeStrategy::Replace,
+            MergeStrategy::Merge,
+            MergeStrategy::PreserveManual,
+        ] {
+            assert_eq!(MergeStrategy::parse(strategy.as_str()), Some(strategy));
+        }
+        assert_eq!(MergeStrategy::parse("keep"), None);
+    }
+
+    #[test]
+    fn test_write_tags_by_strategy() {
+        let conn = setup_db();
+        emit(&conn, &["raw", "daily"], MergeStrategy::PreserveManual);
+        emit(&conn, &["raw"], MergeStrategy::PreserveManual);
+        assert_eq!(tags(&conn), vec!["gold", "raw"]);
+
+        emit(&conn, &["hourly"], MergeStrategy::Merge);
+        assert_eq!(tags(&conn), vec!["gold", "hourly", "raw"]);
+
+        emit(&conn, &["raw"], MergeStrategy::Replace);
+        assert_eq!(tags(&conn), vec!["raw"]);
+    }
+}
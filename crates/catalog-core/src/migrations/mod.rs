@@ -39,13 +39,55 @@ use crate::{CatalogError, Result};
 use rusqlite::Connection;
 
 mod v1_0_0;
+mod v1_10_0;
+mod v1_11_0;
+mod v1_12_0;
+mod v1_13_0;
+mod v1_14_0;
+mod v1_15_0;
+mod v1_16_0;
+mod v1_17_0;
+mod v1_18_0;
+mod v1_19_0;
 mod v1_1_0;
+mod v1_20_0;
+mod v1_21_0;
+mod v1_22_0;
+mod v1_23_0;
+mod v1_24_0;
+mod v1_25_0;
+mod v1_26_0;
+mod v1_27_0;
+mod v1_28_0;
+mod v1_29_0;
 mod v1_2_0;
+mod v1_30_0;
+mod v1_31_0;
+mod v1_32_0;
+mod v1_33_0;
+mod v1_34_0;
+mod v1_35_0;
+mod v1_36_0;
+mod v1_37_0;
+mod v1_38_0;
+mod v1_39_0;
 mod v1_3_0;
+mod v1_40_0;
+mod v1_41_0;
+mod v1_42_0;
+mod v1_43_0;
+mod v1_44_0;
+mod v1_45_0;
+mod v1_46_0;
+mod v1_47_0;
+mod v1_48_0;
 mod v1_4_0;
 mod v1_5_0;
 mod v1_5_1;
 mod v1_6_0;
+mod v1_7_0;
+mod v1_8_0;
+mod v1_9_0;
 
 /// Migration version number.
 pub type MigrationVersion = i64;
@@ -75,6 +117,48 @@ pub fn all_migrations() -> Vec<Migration> {
         v1_5_0::migration(),
         v1_5_1::migration(),
         v1_6_0::migration(),
+        v1_7_0::migration(),
+        v1_8_0::migration(),
+        v1_9_0::migration(),
+        v1_10_0::migration(),
+        v1_11_0::migration(),
+        v1_12_0::migration(),
+        v1_13_0::migration(),
+        v1_14_0::migration(),
+        v1_15_0::migration(),
+        v1_16_0::migration(),
+        v1_17_0::migration(),
+        v1_18_0::migration(),
+        v1_19_0::migration(),
+        v1_20_0::migration(),
+        v1_21_0::migration(),
+        v1_22_0::migration(),
+        v1_23_0::migration(),
+        v1_24_0::migration(),
+        v1_25_0::migration(),
+        v1_26_0::migration(),
+        v1_27_0::migration(),
+        v1_28_0::migration(),
+        v1_29_0::migration(),
+        v1_30_0::migration(),
+        v1_31_0::migration(),
+        v1_32_0::migration(),
+        v1_33_0::migration(),
+        v1_34_0::migration(),
+        v1_35_0::migration(),
+        v1_36_0::migration(),
+        v1_37_0::migration(),
+        v1_38_0::migration(),
+        v1_39_0::migration(),
+        v1_40_0::migration(),
+        v1_41_0::migration(),
+        v1_42_0::migration(),
+        v1_43_0::migration(),
+        v1_44_0::migration(),
+        v1_45_0::migration(),
+        v1_46_0::migration(),
+        v1_47_0::migration(),
+        v1_48_0::migration(),
     ]
 }
 
@@ -0,0 +1,152 @@
+//! Migration v1.10.0: Dataset Refs.
+//!
+//! This migration adds named, immutable references to a dataset at a specific
+//! version or point in time, for recording what a training run or model used:
+//! - `dataset_refs` table pinning a dataset to a format version and/or timestamp
+//! - `dataset_ref_consumers` table linking refs to the runs and models that used them
+//!
+//! # Semantics
+//!
+//! A ref copies the dataset's name, path, format, and Delta location when it is
+//! created, so it stays resolvable if the dataset is later moved or deleted.
+//! Pinned columns cannot be updated (enforced by trigger).
+
+use super::Migration;
+
+/// Version number: 1_010_000 represents v1.10.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_010_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.10.0: Dataset Refs",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.10.0 Schema Migration
+-- Dataset Refs
+-- ============================================================================
+
+-- Named, immutable pointers to a dataset version
+CREATE TABLE IF NOT EXISTS dataset_refs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    -- NULL once the dataset is deleted; the copied columns below keep the ref resolvable
+    dataset_id INTEGER,
+    dataset_name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    format TEXT NOT NULL,
+    delta_location TEXT,
+    -- Table format version (Delta/Iceberg), if pinned
+    version INTEGER,
+    -- Point in time (RFC 3339), if pinned
+    as_of TEXT,
+    description TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE SET NULL,
+    CHECK (version IS NOT NULL OR as_of IS NOT NULL),
+    CHECK (version IS NULL OR version >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_refs_dataset ON dataset_refs(dataset_id);
+
+CREATE TRIGGER IF NOT EXISTS dataset_refs_immutable
+BEFORE UPDATE OF name, dataset_name, path, format, delta_location, version, as_of ON dataset_refs
+BEGIN
+    SELECT RAISE(ABORT, 'dataset refs are immutable');
+END;
+
+-- Lineage from refs to the runs and models that consumed them
+CREATE TABLE IF NOT EXISTS dataset_ref_consumers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ref_id INTEGER NOT NULL,
+    consumer_type TEXT NOT NULL CHECK (consumer_type IN ('run', 'model')),
+    consumer_name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (ref_id) REFERENCES dataset_refs(id) ON DELETE CASCADE,
+    UNIQUE(ref_id, consumer_type, consumer_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_ref_consumers_consumer
+    ON dataset_ref_consumers(consumer_type, consumer_name);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO dataset_refs (name, dataset_name, path, format, version)
+             VALUES ('train_v1', 'orders', 's3://lake/orders', 'delta', 42)",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_010_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.10.0"));
+        assert!(m.description.contains("Dataset Refs"));
+    }
+
+    #[test]
+    fn test_ref_requires_version_or_timestamp() {
+        let conn = setup();
+        let result = conn.execute(
+            "INSERT INTO dataset_refs (name, dataset_name, path, format)
+             VALUES ('unpinned', 'orders', 's3://lake/orders', 'delta')",
+            [],
+        );
+        assert!(result.is_err(), "ref must pin a version or timestamp");
+    }
+
+    #[test]
+    fn test_ref_is_immutable() {
+        let conn = setup();
+        let result = conn.execute(
+            "UPDATE dataset_refs SET version = 43 WHERE name = 'train_v1'",
+            [],
+        );
+        assert!(result.is_err(), "pinned version must not change");
+
+        // Non-pinned columns can still be edited
+        conn.execute(
+            "UPDATE dataset_refs SET description = 'Q4 training set' WHERE name = 'train_v1'",
+            [],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_consumer_type_constraint() {
+        let conn = setup();
+        let result = conn.execute(
+            "INSERT INTO dataset_ref_consumers (ref_id, consumer_type, consumer_name)
+             VALUES (1, 'notebook', 'scratch')",
+            [],
+        );
+        assert!(result.is_err(), "only run and model consumers are allowed");
+    }
+}
@@ -0,0 +1,100 @@
+//! Migration v1.11.0: Dataset Embeddings.
+//!
+//! This migration adds storage for semantic search vectors:
+//! - `dataset_embeddings` table with one vector per dataset
+//!
+//! # Semantics
+//!
+//! Vectors are computed from the dataset name and description by the API's
+//! `semantic-search` feature. `model` and `source_text` record what produced each
+//! vector, so a vector is recomputed when the text or embedding model changes.
+
+use super::Migration;
+
+/// Version number: 1_011_000 represents v1.11.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_011_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.11.0: Dataset Embeddings",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.11.0 Schema Migration
+-- Dataset Embeddings
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_embeddings (
+    dataset_id INTEGER PRIMARY KEY,
+    -- Embedding model identifier (e.g., "hashing-256", "text-embedding-3-small")
+    model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    -- Little-endian f32 values, dimensions * 4 bytes
+    embedding BLOB NOT NULL,
+    -- Text the vector was computed from
+    source_text TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    CHECK (dimensions > 0),
+    CHECK (length(embedding) = dimensions * 4)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_embeddings_model ON dataset_embeddings(model);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_011_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.11.0"));
+        assert!(m.description.contains("Embeddings"));
+    }
+
+    #[test]
+    fn test_embedding_length_must_match_dimensions() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        for name in ["orders", "clicks"] {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', 'parquet', datetime('now'), datetime('now'))",
+                [name],
+            )
+            .unwrap();
+        }
+
+        let ok = conn.execute(
+            "INSERT INTO dataset_embeddings (dataset_id, model, dimensions, embedding, source_text)
+             VALUES (1, 'test', 2, zeroblob(8), 'orders')",
+            [],
+        );
+        assert!(ok.is_ok());
+
+        let bad = conn.execute(
+            "INSERT INTO dataset_embeddings (dataset_id, model, dimensions, embedding, source_text)
+             VALUES (2, 'test', 3, zeroblob(8), 'clicks')",
+            [],
+        );
+        assert!(bad.is_err(), "blob length must match dimensions");
+    }
+}
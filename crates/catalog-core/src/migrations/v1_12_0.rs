@@ -0,0 +1,75 @@
+//! Migration v1.12.0: Suggest Indexes.
+//!
+//! This migration adds case-insensitive indexes backing the typeahead endpoint:
+//! - `datasets(name)`, `datasets(owner)`, `tags(tag)`, `glossary_terms(term)`
+//!
+//! # Semantics
+//!
+//! SQLite only uses an index for `LIKE 'prefix%'` when the index has `NOCASE`
+//! collation, so the existing binary-collation indexes cannot serve prefix
+//! lookups. With these indexes each suggestion type is a short index range scan.
+
+use super::Migration;
+
+/// Version number: 1_012_000 represents v1.12.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_012_000;
+
+/// No additional columns needed (indexes only)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.12.0: Suggest Indexes",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.12.0 Schema Migration
+-- Suggest Indexes
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_datasets_name_nocase ON datasets(name COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS idx_datasets_owner_nocase ON datasets(owner COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS idx_tags_tag_nocase ON tags(tag COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS idx_glossary_terms_term_nocase ON glossary_terms(term COLLATE NOCASE);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_012_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.12.0"));
+        assert!(m.description.contains("Suggest"));
+    }
+
+    #[test]
+    fn test_prefix_like_uses_nocase_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let plan: String = conn
+            .query_row(
+                "EXPLAIN QUERY PLAN SELECT name FROM datasets WHERE name LIKE 'ord%'",
+                [],
+                |row| row.get(3),
+            )
+            .unwrap();
+        assert!(plan.contains("idx_datasets_name_nocase"), "plan: {}", plan);
+    }
+}
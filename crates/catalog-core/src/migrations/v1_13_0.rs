@@ -0,0 +1,88 @@
+//! Migration v1.13.0: Dataset Trash.
+//!
+//! This migration adds soft delete for datasets:
+//! - `deleted_at` column on `datasets` (NULL for live datasets)
+//! - `deleted_by` column on `datasets` recording who deleted it
+//!
+//! # Semantics
+//!
+//! Deleting a dataset through the API sets `deleted_at` instead of removing the
+//! row. Trashed datasets are hidden from reads and can be restored until the
+//! retention window expires, when the purge job deletes the row and its fields,
+//! tags, lineage edges, and FTS entry cascade as with a hard delete.
+
+use super::Migration;
+
+/// Version number: 1_013_000 represents v1.13.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_013_000;
+
+/// Add soft delete columns to datasets table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("datasets", "deleted_at", "TEXT"),
+    ("datasets", "deleted_by", "TEXT"),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.13.0: Dataset Trash",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.13.0 Schema Migration
+-- Dataset Trash
+-- ============================================================================
+-- deleted_at and deleted_by are added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_013_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.13.0"));
+        assert!(m.description.contains("Trash"));
+    }
+
+    #[test]
+    fn test_datasets_have_soft_delete_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let deleted_at: Option<String> = conn
+            .query_row(
+                "SELECT deleted_at FROM datasets WHERE name = 'orders'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(deleted_at.is_none());
+
+        conn.execute(
+            "UPDATE datasets SET deleted_at = datetime('now'), deleted_by = 'alice' WHERE name = 'orders'",
+            [],
+        )
+        .unwrap();
+    }
+}
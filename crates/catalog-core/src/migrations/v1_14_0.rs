@@ -0,0 +1,103 @@
+//! Migration v1.14.0: Dataset Namespaces.
+//!
+//! This migration adds hierarchical namespaces for dataset names:
+//! - `namespaces` table registering dot-separated namespaces (`sales`, `sales.emea`)
+//!
+//! # Semantics
+//!
+//! A dataset named `sales.emea.orders` belongs to the longest registered
+//! namespace that prefixes its name (`sales.emea`), and its local name is
+//! `orders`. Dataset names stay unique per catalog, so `orders` can exist once in
+//! each namespace, and tenants already have separate catalogs.
+//!
+//! Dots were already allowed in dataset names. A dotted name whose prefix is not
+//! a registered namespace is treated as a legacy flat name, so existing datasets
+//! resolve exactly as before.
+
+use super::Migration;
+
+/// Version number: 1_014_000 represents v1.14.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_014_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.14.0: Dataset Namespaces",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.14.0 Schema Migration
+-- Dataset Namespaces
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS namespaces (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Full dot-separated name (e.g., "sales.emea")
+    name TEXT UNIQUE NOT NULL,
+    -- Enclosing namespace (NULL for top-level namespaces)
+    parent TEXT,
+    description TEXT,
+    owner TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Children must be removed before their parent
+    FOREIGN KEY (parent) REFERENCES namespaces(name) ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespaces_parent ON namespaces(parent);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_014_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.14.0"));
+        assert!(m.description.contains("Namespaces"));
+    }
+
+    #[test]
+    fn test_parent_must_exist_and_outlive_children() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let orphan = conn.execute(
+            "INSERT INTO namespaces (name, parent) VALUES ('sales.emea', 'sales')",
+            [],
+        );
+        assert!(orphan.is_err(), "parent namespace must exist");
+
+        conn.execute("INSERT INTO namespaces (name) VALUES ('sales')", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO namespaces (name, parent) VALUES ('sales.emea', 'sales')",
+            [],
+        )
+        .unwrap();
+
+        let delete_parent = conn.execute("DELETE FROM namespaces WHERE name = 'sales'", []);
+        assert!(
+            delete_parent.is_err(),
+            "parent with children cannot be deleted"
+        );
+    }
+}
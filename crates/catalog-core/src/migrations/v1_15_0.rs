@@ -0,0 +1,118 @@
+//! Migration v1.15.0: Auto-Tagging Rules.
+//!
+//! This migration adds policy-driven automatic tagging:
+//! - `auto_tag_rules` table of admin-defined rules matching dataset paths and
+//!   column names, each adding tags and/or setting the domain
+//! - `auto_tag_rule_hits` table recording which datasets each rule matched,
+//!   for rule hit statistics
+//!
+//! Rules run when a dataset is registered and retroactively over the whole
+//! catalog (see `metafuse_catalog_core::auto_tagging`).
+
+use super::Migration;
+
+/// Version number: 1_015_000 represents v1.15.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_015_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.15.0: Auto-Tagging Rules",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.15.0 Schema Migration
+-- Auto-Tagging Rules
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS auto_tag_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT UNIQUE NOT NULL,
+    description TEXT,
+    -- Glob matched against the dataset path (e.g., "s3://finance/*")
+    path_pattern TEXT,
+    -- Glob matched case-insensitively against column names (e.g., "*email*")
+    column_pattern TEXT,
+    -- Domain assigned to matching datasets that have none
+    set_domain TEXT,
+    -- JSON array of tags added to matching datasets
+    add_tags TEXT NOT NULL DEFAULT '[]',
+    -- Lower runs first; the first rule to set a domain wins
+    priority INTEGER NOT NULL DEFAULT 100,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CHECK (path_pattern IS NOT NULL OR column_pattern IS NOT NULL),
+    CHECK (set_domain IS NOT NULL OR add_tags != '[]')
+);
+
+CREATE INDEX IF NOT EXISTS idx_auto_tag_rules_active ON auto_tag_rules(is_active, priority);
+
+CREATE TABLE IF NOT EXISTS auto_tag_rule_hits (
+    rule_id INTEGER NOT NULL,
+    dataset_id INTEGER NOT NULL,
+    -- Number of evaluations (registrations and runs) that matched
+    hit_count INTEGER NOT NULL DEFAULT 1,
+    first_hit_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_hit_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (rule_id, dataset_id),
+    FOREIGN KEY (rule_id) REFERENCES auto_tag_rules(id) ON DELETE CASCADE,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_auto_tag_rule_hits_dataset ON auto_tag_rule_hits(dataset_id);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_015_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.15.0"));
+        assert!(m.description.contains("Auto-Tagging"));
+    }
+
+    #[test]
+    fn test_rules_need_a_condition_and_an_action() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let no_condition = conn.execute(
+            "INSERT INTO auto_tag_rules (name, add_tags) VALUES ('a', '[\"pii\"]')",
+            [],
+        );
+        assert!(no_condition.is_err());
+
+        let no_action = conn.execute(
+            "INSERT INTO auto_tag_rules (name, column_pattern) VALUES ('b', 'email')",
+            [],
+        );
+        assert!(no_action.is_err());
+
+        conn.execute(
+            "INSERT INTO auto_tag_rules (name, path_pattern, set_domain) VALUES ('c', 's3://finance/*', 'finance')",
+            [],
+        )
+        .unwrap();
+    }
+}
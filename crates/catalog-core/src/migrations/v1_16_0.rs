@@ -0,0 +1,83 @@
+//! Migration v1.16.0: Quality Metrics Compaction.
+//!
+//! This migration supports downsampling of quality history:
+//! - `sample_count` column on `quality_metrics` (raw computations a row stands for)
+//! - `(dataset_id, computed_at)` index for per-dataset history queries
+//!
+//! # Semantics
+//!
+//! Compaction keeps the latest computation in each time bucket (hour, day, or
+//! month depending on age) and deletes the rest. The kept row's `sample_count`
+//! is the total of the rows it replaced, so repeated compactions stay accurate.
+
+use super::Migration;
+
+/// Version number: 1_016_000 represents v1.16.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_016_000;
+
+/// Add sample count to quality metrics table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[(
+    "quality_metrics",
+    "sample_count",
+    "INTEGER NOT NULL DEFAULT 1",
+)];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.16.0: Quality Metrics Compaction",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.16.0 Schema Migration
+-- Quality Metrics Compaction
+-- ============================================================================
+-- sample_count is added via add_columns helper (not in SQL)
+
+CREATE INDEX IF NOT EXISTS idx_quality_metrics_dataset_time
+    ON quality_metrics(dataset_id, computed_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_016_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.16.0"));
+        assert!(m.description.contains("Quality"));
+    }
+
+    #[test]
+    fn test_existing_metrics_count_as_one_sample() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO quality_metrics (dataset_id, overall_score) VALUES (1, 0.9);",
+        )
+        .unwrap();
+        let sample_count: i64 = conn
+            .query_row("SELECT sample_count FROM quality_metrics", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(sample_count, 1);
+    }
+}
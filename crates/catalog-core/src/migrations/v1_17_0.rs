@@ -0,0 +1,90 @@
+//! Migration v1.17.0: Dataset Emissions.
+//!
+//! This migration records each emitter write of a dataset:
+//! - `dataset_emissions` table (one row per emission, with the operational
+//!   metadata reported at the time)
+//!
+//! # Semantics
+//!
+//! `datasets.last_updated` only holds the most recent write. Emission rows keep
+//! the full history for the dataset activity timeline. Rows are removed with
+//! their dataset.
+
+use super::Migration;
+
+/// Version number: 1_017_000 represents v1.17.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_017_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.17.0: Dataset Emissions",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.17.0 Schema Migration
+-- Dataset Emissions
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_emissions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL REFERENCES datasets(id) ON DELETE CASCADE,
+    emitted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Operational metadata reported with the emission
+    row_count INTEGER,
+    size_bytes INTEGER,
+    field_count INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_emissions_dataset_time
+    ON dataset_emissions(dataset_id, emitted_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_017_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.17.0"));
+        assert!(m.description.contains("Emissions"));
+    }
+
+    #[test]
+    fn test_emissions_removed_with_dataset() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO dataset_emissions (dataset_id, row_count) VALUES (1, 100);
+             DELETE FROM datasets;",
+        )
+        .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM dataset_emissions", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
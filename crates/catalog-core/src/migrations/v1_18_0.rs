@@ -0,0 +1,82 @@
+//! Migration v1.18.0: Lineage Jobs and Placeholder Datasets.
+//!
+//! This migration supports lineage registered separately from datasets:
+//! - `job_name`, `run_id`, `job_metadata` (JSON), `updated_at` on `lineage`,
+//!   recording the orchestrator job and run that last reported each edge
+//! - `status` on `datasets`: `active` for registered datasets, `pending` for
+//!   placeholders created so an edge can reference a dataset not yet registered
+//!
+//! # Semantics
+//!
+//! A placeholder has an empty path and format `unknown`. Registering the
+//! dataset later (API create or emitter write) fills it in and marks it
+//! `active`, keeping the id and therefore the lineage edges.
+
+use super::Migration;
+
+/// Version number: 1_018_000 represents v1.18.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_018_000;
+
+/// Add job metadata to lineage and status to datasets.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("lineage", "job_name", "TEXT"),
+    ("lineage", "run_id", "TEXT"),
+    ("lineage", "job_metadata", "TEXT"),
+    ("lineage", "updated_at", "TEXT"),
+    ("datasets", "status", "TEXT NOT NULL DEFAULT 'active'"),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.18.0: Lineage Jobs and Placeholder Datasets",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.18.0 Schema Migration
+-- Lineage Jobs and Placeholder Datasets
+-- ============================================================================
+-- lineage job columns and datasets.status are added via add_columns helper
+-- (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_018_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.18.0"));
+        assert!(m.description.contains("Placeholder"));
+    }
+
+    #[test]
+    fn test_existing_datasets_are_active() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));",
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+
+        let status: String = conn
+            .query_row("SELECT status FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(status, "active");
+    }
+}
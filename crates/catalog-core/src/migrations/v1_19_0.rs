@@ -0,0 +1,113 @@
+//! Migration v1.19.0: External Lineage Nodes.
+//!
+//! This migration lets lineage reference sources and sinks that live outside
+//! the catalog (vendor SFTP drops, external APIs, SaaS exports):
+//! - `external_nodes` table (one row per external system location, keyed by URI)
+//! - `external_lineage` table (edges between an external node and a dataset)
+//!
+//! # Semantics
+//!
+//! External nodes are not datasets: they have no schema, are never emitted,
+//! and do not appear in dataset listings or search. Edges always connect an
+//! external node to a registered dataset; `direction` says which side the
+//! external node is on. Edges are removed with their dataset or node.
+
+use super::Migration;
+
+/// Version number: 1_019_000 represents v1.19.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_019_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.19.0: External Lineage Nodes",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.19.0 Schema Migration
+-- External Lineage Nodes
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS external_nodes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Location in the external system, e.g. sftp://vendor.example.com/drops/orders
+    uri TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    -- Owning system, e.g. 'salesforce', 'vendor_sftp'
+    system TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_nodes_system ON external_nodes(system);
+
+CREATE TABLE IF NOT EXISTS external_lineage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    external_node_id INTEGER NOT NULL REFERENCES external_nodes(id) ON DELETE CASCADE,
+    dataset_id INTEGER NOT NULL REFERENCES datasets(id) ON DELETE CASCADE,
+    -- 'upstream': the external node feeds the dataset
+    -- 'downstream': the dataset feeds the external node
+    direction TEXT NOT NULL CHECK (direction IN ('upstream', 'downstream')),
+    job_name TEXT,
+    run_id TEXT,
+    job_metadata TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(external_node_id, dataset_id, direction)
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_lineage_dataset ON external_lineage(dataset_id);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_019_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.19.0"));
+        assert!(m.description.contains("External"));
+    }
+
+    #[test]
+    fn test_external_edges_removed_with_dataset() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO external_nodes (uri, name) VALUES ('sftp://vendor/orders', 'orders');
+             INSERT INTO external_lineage (external_node_id, dataset_id, direction)
+             VALUES (1, 1, 'upstream');
+             DELETE FROM datasets;",
+        )
+        .unwrap();
+        let (edges, nodes): (i64, i64) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM external_lineage), (SELECT COUNT(*) FROM external_nodes)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((edges, nodes), (0, 1));
+    }
+}
@@ -0,0 +1,89 @@
+//! Migration v1.20.0: Dataset Access Control Lists.
+//!
+//! This migration adds dataset-level access control within a tenant:
+//! - `dataset_acls` table (principals allowed to read or write one dataset)
+//! - `domain_acls` table (default entries for datasets in a domain)
+//!
+//! # Semantics
+//!
+//! Principals are `user:<id>` or `group:<id>`. A dataset with entries of its
+//! own uses only those; otherwise it inherits its domain's entries. A dataset
+//! with no entries either way is governed by tenant roles alone. `write`
+//! implies `read`.
+
+use super::Migration;
+
+/// Version number: 1_020_000 represents v1.20.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_020_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.20.0: Dataset Access Control Lists",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.20.0 Schema Migration
+-- Dataset Access Control Lists
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_acls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL REFERENCES datasets(id) ON DELETE CASCADE,
+    -- 'user:<id>' or 'group:<id>'
+    principal TEXT NOT NULL,
+    permission TEXT NOT NULL CHECK (permission IN ('read', 'write')),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(dataset_id, principal)
+);
+
+CREATE TABLE IF NOT EXISTS domain_acls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Matches datasets.domain
+    domain TEXT NOT NULL,
+    principal TEXT NOT NULL,
+    permission TEXT NOT NULL CHECK (permission IN ('read', 'write')),
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(domain, principal)
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_020_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.20.0"));
+        assert!(m.description.contains("Access Control"));
+    }
+
+    #[test]
+    fn test_permission_is_checked() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let result = conn.execute(
+            "INSERT INTO domain_acls (domain, principal, permission) VALUES ('finance', 'group:fin', 'admin')",
+            [],
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Migration v1.21.0: Dataset UUIDs.
+//!
+//! This migration gives every dataset a stable, non-sequential identifier
+//! for external use:
+//! - `dataset_uuids` table (one random v4 UUID per dataset)
+//! - backfill for existing datasets
+//! - `dataset_uuids_insert` trigger assigning a UUID to new datasets
+//!
+//! # Semantics
+//!
+//! The integer `datasets.id` stays the internal key. The UUID never changes
+//! once assigned, including across renames, and is assigned by the database so
+//! every write path (API, emitter, placeholders) gets one.
+
+use super::Migration;
+
+/// Version number: 1_021_000 represents v1.21.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_021_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.21.0: Dataset UUIDs",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.21.0 Schema Migration
+-- Dataset UUIDs
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_uuids (
+    dataset_id INTEGER PRIMARY KEY REFERENCES datasets(id) ON DELETE CASCADE,
+    -- Random (v4) UUID, lowercase hyphenated
+    uuid TEXT NOT NULL UNIQUE
+);
+
+INSERT OR IGNORE INTO dataset_uuids (dataset_id, uuid)
+SELECT id,
+       lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+       substr(lower(hex(randomblob(2))), 2) || '-' ||
+       substr('89ab', 1 + (abs(random()) % 4), 1) || substr(lower(hex(randomblob(2))), 2) || '-' ||
+       lower(hex(randomblob(6)))
+FROM datasets;
+
+CREATE TRIGGER IF NOT EXISTS dataset_uuids_insert
+AFTER INSERT ON datasets
+BEGIN
+    INSERT OR IGNORE INTO dataset_uuids (dataset_id, uuid)
+    VALUES (
+        NEW.id,
+        lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+        substr(lower(hex(randomblob(2))), 2) || '-' ||
+        substr('89ab', 1 + (abs(random()) % 4), 1) || substr(lower(hex(randomblob(2))), 2) || '-' ||
+        lower(hex(randomblob(6)))
+    );
+END;
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_021_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.21.0"));
+        assert!(m.description.contains("UUID"));
+    }
+
+    #[test]
+    fn test_backfill_and_trigger() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data/orders', 'parquet', datetime('now'), datetime('now'));",
+        )
+        .unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('customers', '/data/customers', 'parquet', datetime('now'), datetime('now'));",
+        )
+        .unwrap();
+
+        let uuids: Vec<String> = conn
+            .prepare("SELECT uuid FROM dataset_uuids ORDER BY dataset_id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(uuids.len(), 2);
+        assert_ne!(uuids[0], uuids[1]);
+        for uuid in &uuids {
+            assert_eq!(uuid.len(), 36);
+            assert_eq!(&uuid[14..15], "4");
+            assert!("89ab".contains(&uuid[19..20]));
+        }
+    }
+}
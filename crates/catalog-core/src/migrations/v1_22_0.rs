@@ -0,0 +1,75 @@
+//! Migration v1.22.0: Field Ordinals.
+//!
+//! This migration records the position of each field in its dataset's schema:
+//! - `ordinal` column on `fields` (0-based, in emission order)
+//!
+//! # Semantics
+//!
+//! Writers store the column order they emit, and readers return fields by
+//! `ordinal` so schemas show in table order rather than alphabetically.
+//! Fields written before this migration have no ordinal; they were inserted
+//! in emission order, so readers fall back to `id` order.
+
+use super::Migration;
+
+/// Version number: 1_022_000 represents v1.22.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_022_000;
+
+/// Add the ordinal column to fields table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[("fields", "ordinal", "INTEGER")];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.22.0: Field Ordinals",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.22.0 Schema Migration
+-- Field Ordinals
+-- ============================================================================
+-- ordinal is added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_022_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.22.0"));
+        assert!(m.description.contains("Ordinal"));
+    }
+
+    #[test]
+    fn test_fields_have_ordinal_column() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO fields (dataset_id, name, data_type, nullable, ordinal)
+             VALUES (1, 'order_id', 'Int64', 0, 0);",
+        )
+        .unwrap();
+        let ordinal: Option<i64> = conn
+            .query_row("SELECT ordinal FROM fields", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(ordinal, Some(0));
+    }
+}
@@ -0,0 +1,91 @@
+//! Migration v1.23.0: Emission State.
+//!
+//! This migration lets writers skip emissions whose metadata hasn't changed:
+//! - `dataset_emission_state` table (content hash of the last full write and
+//!   when the dataset was last emitted)
+//!
+//! # Semantics
+//!
+//! The emitter hashes each emitted dataset and compares it with the stored
+//! hash. Unchanged emissions skip the write, so `last_updated`, the search
+//! index, and the activity timeline only move when metadata changes, while
+//! `last_seen_at` still records that the pipeline emitted. The state lives in
+//! its own table because updating `datasets` refreshes the search index.
+
+use super::Migration;
+
+/// Version number: 1_023_000 represents v1.23.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_023_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.23.0: Emission State",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.23.0 Schema Migration
+-- Emission State
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_emission_state (
+    dataset_id INTEGER PRIMARY KEY REFERENCES datasets(id) ON DELETE CASCADE,
+    -- Hash of the metadata from the last full write
+    content_hash TEXT NOT NULL,
+    -- Last emission, including ones skipped as unchanged (RFC 3339)
+    last_seen_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_emission_state_last_seen
+    ON dataset_emission_state(last_seen_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_023_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.23.0"));
+        assert!(m.description.contains("Emission"));
+    }
+
+    #[test]
+    fn test_emission_state_cascades_on_delete() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO dataset_emission_state (dataset_id, content_hash, last_seen_at)
+             VALUES (1, 'abc', datetime('now'));
+             DELETE FROM datasets;",
+        )
+        .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM dataset_emission_state", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,91 @@
+//! Migration v1.24.0: Custom Metadata.
+//!
+//! This migration lets integrators attach free-form metadata to datasets:
+//! - `custom_metadata` column on `datasets` (JSON object)
+//! - `custom_metadata_schema` table (optional JSON Schema for the catalog)
+//!
+//! # Semantics
+//!
+//! Custom metadata holds system-specific settings (a Snowflake warehouse,
+//! Spark configuration hints) that don't fit the typed dataset columns. When
+//! a schema is set, every write of custom metadata is validated against it.
+//! Tenant catalogs are separate databases, so each tenant has its own schema.
+//! Setting a schema doesn't revalidate metadata already stored.
+
+use super::Migration;
+
+/// Version number: 1_024_000 represents v1.24.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_024_000;
+
+/// Add the custom metadata column to datasets table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[("datasets", "custom_metadata", "TEXT")];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.24.0: Custom Metadata",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.24.0 Schema Migration
+-- Custom Metadata
+-- ============================================================================
+-- custom_metadata is added via add_columns helper (not in SQL)
+
+-- At most one schema per catalog
+CREATE TABLE IF NOT EXISTS custom_metadata_schema (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    -- JSON Schema document
+    schema TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_024_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.24.0"));
+        assert!(m.description.contains("Custom Metadata"));
+    }
+
+    #[test]
+    fn test_single_schema_row() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated, custom_metadata)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'), '{}')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO custom_metadata_schema (id, schema) VALUES (1, '{}')",
+            [],
+        )
+        .unwrap();
+        assert!(conn
+            .execute(
+                "INSERT INTO custom_metadata_schema (id, schema) VALUES (2, '{}')",
+                [],
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Migration v1.25.0: Unique User Sketches.
+//!
+//! This migration stores unique users per day as a mergeable sketch:
+//! - `unique_users_sketch` column on `usage_stats` (serialized HyperLogLog)
+//!
+//! # Semantics
+//!
+//! Each flush merges the in-memory sketch into the stored one and refreshes
+//! `unique_users` from the merged estimate, so daily counts are no longer
+//! capped. Unique users over a period are estimated by merging the daily
+//! sketches. Rows written before this migration have no sketch and keep
+//! their stored `unique_users`.
+
+use super::Migration;
+
+/// Version number: 1_025_000 represents v1.25.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_025_000;
+
+/// Add the sketch column to usage_stats table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[("usage_stats", "unique_users_sketch", "BLOB")];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.25.0: Unique User Sketches",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.25.0 Schema Migration
+-- Unique User Sketches
+-- ============================================================================
+-- unique_users_sketch is added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_025_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.25.0"));
+        assert!(m.description.contains("Sketch"));
+    }
+
+    #[test]
+    fn test_usage_stats_have_sketch_column() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO usage_stats (dataset_id, stat_date, unique_users, unique_users_sketch)
+             VALUES (1, '2026-01-01', 1, x'0100');",
+        )
+        .unwrap();
+        let sketch: Option<Vec<u8>> = conn
+            .query_row("SELECT unique_users_sketch FROM usage_stats", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(sketch, Some(vec![1, 0]));
+    }
+}
@@ -0,0 +1,77 @@
+//! Migration v1.26.0: Tenant Usage.
+//!
+//! This migration records which tenant each usage row belongs to:
+//! - `tenant_id` column on `usage_stats` (`default` for the default catalog)
+//!
+//! # Semantics
+//!
+//! Usage is written to the catalog of the tenant that recorded it, and usage
+//! queries only read rows for the requesting tenant. Rows written before this
+//! migration are attributed to `default`.
+
+use super::Migration;
+
+/// Version number: 1_026_000 represents v1.26.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_026_000;
+
+/// Add the tenant column to usage_stats table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[(
+    "usage_stats",
+    "tenant_id",
+    "TEXT NOT NULL DEFAULT 'default'",
+)];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.26.0: Tenant Usage",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.26.0 Schema Migration
+-- Tenant Usage
+-- ============================================================================
+-- tenant_id is added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_026_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.26.0"));
+        assert!(m.description.contains("Tenant"));
+    }
+
+    #[test]
+    fn test_usage_defaults_to_default_tenant() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO usage_stats (dataset_id, stat_date) VALUES (1, '2026-01-01');",
+        )
+        .unwrap();
+        let tenant_id: String = conn
+            .query_row("SELECT tenant_id FROM usage_stats", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tenant_id, "default");
+    }
+}
@@ -0,0 +1,95 @@
+//! Migration v1.27.0: Tenant Dataset Defaults.
+//!
+//! This migration adds per-tenant defaults for new datasets to the control plane:
+//! - `tenant_dataset_defaults` table with default tags, domain, owner, and an
+//!   owner-to-domain mapping
+//!
+//! # Semantics
+//!
+//! Defaults fill in fields a dataset create leaves out; values in the request
+//! always win. Tenants without a row get no defaults, so behavior is unchanged
+//! after upgrade.
+
+use super::Migration;
+
+/// Version number: 1_027_000 represents v1.27.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_027_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.27.0: Tenant Dataset Defaults",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.27.0 Schema Migration
+-- Tenant Dataset Defaults
+-- ============================================================================
+
+-- Defaults applied to datasets a tenant creates
+CREATE TABLE IF NOT EXISTS tenant_dataset_defaults (
+    -- Tenant these defaults apply to
+    tenant_id TEXT PRIMARY KEY,
+    -- Default tags as a JSON array; `{tenant_id}` is replaced on apply
+    tags TEXT NOT NULL DEFAULT '[]',
+    -- Default domain when the owner has no mapping
+    domain TEXT,
+    -- Default owner
+    owner TEXT,
+    -- Domain per owner as a JSON object
+    owner_domains TEXT NOT NULL DEFAULT '{}',
+    -- When the defaults were last changed
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (tenant_id) REFERENCES tenants(tenant_id) ON DELETE CASCADE
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_027_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.27.0"));
+        assert!(m.description.contains("Defaults"));
+    }
+
+    #[test]
+    fn test_tenant_dataset_defaults_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO tenants (tenant_id, display_name, admin_email, storage_uri)
+             VALUES ('acme', 'Acme', 'ops@acme.test', 'file:///tmp/acme.db');
+             INSERT INTO tenant_dataset_defaults (tenant_id, domain) VALUES ('acme', 'sales');",
+        )
+        .unwrap();
+        let (tags, owner_domains): (String, String) = conn
+            .query_row(
+                "SELECT tags, owner_domains FROM tenant_dataset_defaults WHERE tenant_id = 'acme'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(tags, "[]");
+        assert_eq!(owner_domains, "{}");
+    }
+}
@@ -0,0 +1,100 @@
+//! Migration v1.28.0: Column Statistics.
+//!
+//! This migration stores column statistics alongside quality scores:
+//! - `column_stats` table with the latest statistics per dataset column
+//! - `quality_metrics.source` column naming who computed each score
+//!
+//! # Semantics
+//!
+//! Statistics come from MetaFuse's own Delta profiling (source `delta`) or
+//! are pushed by external profilers under a label of their choosing, e.g.
+//! `spark`. Each column keeps only its latest statistics. Existing quality
+//! rows have no source and were computed by MetaFuse.
+
+use super::Migration;
+
+/// Version number: 1_028_000 represents v1.28.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_028_000;
+
+/// Source of each quality computation
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[("quality_metrics", "source", "TEXT")];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.28.0: Column Statistics",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.28.0 Schema Migration
+-- Column Statistics
+-- ============================================================================
+
+-- Latest statistics per dataset column
+CREATE TABLE IF NOT EXISTS column_stats (
+    dataset_id INTEGER NOT NULL,
+    column_name TEXT NOT NULL,
+    -- Null values in the column
+    null_count INTEGER,
+    -- Distinct values in the column
+    distinct_count INTEGER,
+    -- Minimum and maximum values as JSON
+    min_value TEXT,
+    max_value TEXT,
+    -- Profiler that computed the statistics, e.g. 'delta' or 'spark'
+    source TEXT NOT NULL,
+    -- When the statistics were computed
+    computed_at TEXT NOT NULL,
+    PRIMARY KEY (dataset_id, column_name),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_028_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.28.0"));
+        assert!(m.description.contains("Statistics"));
+    }
+
+    #[test]
+    fn test_column_stats_cascade() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/o', 'delta', datetime('now'), datetime('now'));
+             INSERT INTO column_stats (dataset_id, column_name, null_count, source, computed_at)
+             VALUES (1, 'id', 0, 'spark', datetime('now'));
+             INSERT INTO quality_metrics (dataset_id, overall_score, source)
+             VALUES (1, 0.9, 'spark');",
+        )
+        .unwrap();
+
+        conn.execute("DELETE FROM datasets WHERE id = 1", [])
+            .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM column_stats", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,86 @@
+//! Migration v1.29.0: Tenant Impersonation.
+//!
+//! This migration marks tenant API keys minted for impersonation:
+//! - `tenant_api_keys.impersonated_by` names the platform operator
+//! - `tenant_api_keys.impersonation_reason` records why
+//!
+//! # Semantics
+//!
+//! Impersonation keys are ordinary short-lived viewer keys, so they expire
+//! and are revoked like any other. Keys without `impersonated_by` are regular
+//! tenant keys; existing keys are unaffected.
+
+use super::Migration;
+
+/// Version number: 1_029_000 represents v1.29.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_029_000;
+
+/// Impersonation marker columns on tenant API keys
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("tenant_api_keys", "impersonated_by", "TEXT"),
+    ("tenant_api_keys", "impersonation_reason", "TEXT"),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.29.0: Tenant Impersonation",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.29.0 Schema Migration
+-- Tenant Impersonation
+-- ============================================================================
+
+-- impersonated_by and impersonation_reason are added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_029_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.29.0"));
+        assert!(m.description.contains("Impersonation"));
+    }
+
+    #[test]
+    fn test_impersonation_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO tenants (tenant_id, display_name, admin_email, storage_uri)
+             VALUES ('acme', 'Acme', 'ops@acme.test', 'file:///tmp/acme.db');
+             INSERT INTO tenant_api_keys (tenant_id, key_hash, name, role)
+             VALUES ('acme', 'h1', 'ci', 'editor');
+             INSERT INTO tenant_api_keys
+                 (tenant_id, key_hash, name, role, impersonated_by, impersonation_reason)
+             VALUES ('acme', 'h2', 'impersonation:sam', 'viewer', 'sam', 'ticket 42');",
+        )
+        .unwrap();
+        let impersonators: Vec<Option<String>> = conn
+            .prepare("SELECT impersonated_by FROM tenant_api_keys ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(impersonators, vec![None, Some("sam".to_string())]);
+    }
+}
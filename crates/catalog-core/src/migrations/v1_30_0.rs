@@ -0,0 +1,107 @@
+//! Migration v1.30.0: Path History.
+//!
+//! This migration records dataset relocations:
+//! - `path_history` table with one row per path change
+//! - `path_history_update` trigger recording changes to `datasets.path`
+//!
+//! # Semantics
+//!
+//! When a dataset moves (e.g. to another bucket) the old path disappears from
+//! the catalog, breaking anyone who only knows the old location. Changes are
+//! recorded by the database so every write path (API, emitter, path
+//! canonicalization) is covered. Placeholders have an empty path, so
+//! registering one is not a relocation.
+
+use super::Migration;
+
+/// Version number: 1_030_000 represents v1.30.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_030_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.30.0: Path History",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.30.0 Schema Migration
+-- Path History
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS path_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    old_path TEXT NOT NULL,
+    new_path TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_path_history_dataset ON path_history(dataset_id, changed_at);
+CREATE INDEX IF NOT EXISTS idx_path_history_old_path ON path_history(old_path);
+
+CREATE TRIGGER IF NOT EXISTS path_history_update
+AFTER UPDATE OF path ON datasets
+WHEN OLD.path <> NEW.path AND OLD.path <> ''
+BEGIN
+    INSERT INTO path_history (dataset_id, old_path, new_path)
+    VALUES (NEW.id, OLD.path, NEW.path);
+END;
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_030_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.30.0"));
+        assert!(m.description.contains("Path"));
+    }
+
+    #[test]
+    fn test_trigger_records_path_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', 's3://old/orders', 'delta', datetime('now'), datetime('now'));
+             INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('pending', '', 'unknown', datetime('now'), datetime('now'));
+             UPDATE datasets SET path = 's3://new/orders' WHERE name = 'orders';
+             UPDATE datasets SET description = 'Orders' WHERE name = 'orders';
+             UPDATE datasets SET path = 's3://new/orders' WHERE name = 'orders';
+             UPDATE datasets SET path = 's3://pending' WHERE name = 'pending';",
+        )
+        .unwrap();
+
+        let rows: Vec<(String, String)> = conn
+            .prepare("SELECT old_path, new_path FROM path_history")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![("s3://old/orders".to_string(), "s3://new/orders".to_string())]
+        );
+    }
+}
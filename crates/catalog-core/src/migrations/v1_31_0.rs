@@ -0,0 +1,90 @@
+//! Migration v1.31.0: Rename History.
+//!
+//! This migration records tag and domain renames:
+//! - `rename_history` table with one row per rename or merge
+//!
+//! # Semantics
+//!
+//! A rename rewrites every reference to the old name, after which the old name
+//! is gone from the catalog. The mapping is kept here so audits (and people
+//! searching for the old name) can tell where it went. A merge is a rename onto
+//! a name that was already in use.
+
+use super::Migration;
+
+/// Version number: 1_031_000 represents v1.31.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_031_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.31.0: Rename History",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.31.0 Schema Migration
+-- Rename History
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS rename_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- What was renamed: 'tag' or 'domain'
+    kind TEXT NOT NULL CHECK (kind IN ('tag', 'domain')),
+    old_name TEXT NOT NULL,
+    new_name TEXT NOT NULL,
+    -- 1 when the new name was already in use (merge)
+    merged INTEGER NOT NULL DEFAULT 0,
+    -- Datasets whose tags or domain changed
+    datasets_updated INTEGER NOT NULL DEFAULT 0,
+    renamed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_rename_history_kind ON rename_history(kind, renamed_at);
+CREATE INDEX IF NOT EXISTS idx_rename_history_old_name ON rename_history(kind, old_name);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_031_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.31.0"));
+        assert!(m.description.contains("Rename"));
+    }
+
+    #[test]
+    fn test_rename_history_kind_check() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO rename_history (kind, old_name, new_name) VALUES ('tag', 'pii', 'compliance:pii')",
+            [],
+        )
+        .unwrap();
+        assert!(conn
+            .execute(
+                "INSERT INTO rename_history (kind, old_name, new_name) VALUES ('owner', 'a', 'b')",
+                [],
+            )
+            .is_err());
+    }
+}
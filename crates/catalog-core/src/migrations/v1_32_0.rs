@@ -0,0 +1,111 @@
+//! Migration v1.32.0: Dataset Subscriptions.
+//!
+//! This migration adds watch lists:
+//! - `dataset_subscriptions` table with one row per watcher of a dataset
+//! - `watch_snapshots` table with the last observed state of watched datasets
+//!
+//! # Semantics
+//!
+//! A subscriber is an identity (`user:<id>`, or `key:<id>` for API keys)
+//! watching a dataset for some events (`schema`, `quality`, `deprecation`).
+//! The watcher compares each watched dataset against its snapshot and notifies
+//! subscribers of changes; the first snapshot of a dataset only records its
+//! state.
+
+use super::Migration;
+
+/// Version number: 1_032_000 represents v1.32.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_032_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.32.0: Dataset Subscriptions",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.32.0 Schema Migration
+-- Dataset Subscriptions
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_subscriptions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    -- 'user:<id>' or 'key:<id>'
+    subscriber TEXT NOT NULL,
+    -- JSON array of watched events
+    events TEXT NOT NULL DEFAULT '["schema","quality","deprecation"]',
+    -- Webhook URL notified for this subscriber (optional)
+    channel TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    last_notified_at TEXT,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    UNIQUE(dataset_id, subscriber)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_subscriptions_subscriber ON dataset_subscriptions(subscriber);
+
+CREATE TABLE IF NOT EXISTS watch_snapshots (
+    dataset_id INTEGER PRIMARY KEY,
+    -- JSON object of column name to data type
+    schema TEXT NOT NULL DEFAULT '{}',
+    -- Latest overall quality score
+    quality_score REAL,
+    deprecated INTEGER NOT NULL DEFAULT 0,
+    checked_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_032_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.32.0"));
+        assert!(m.description.contains("Subscriptions"));
+    }
+
+    #[test]
+    fn test_one_subscription_per_subscriber() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO dataset_subscriptions (dataset_id, subscriber) VALUES (1, 'user:alice');",
+        )
+        .unwrap();
+
+        let events: String = conn
+            .query_row("SELECT events FROM dataset_subscriptions", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(events, r#"["schema","quality","deprecation"]"#);
+        assert!(conn
+            .execute(
+                "INSERT INTO dataset_subscriptions (dataset_id, subscriber) VALUES (1, 'user:alice')",
+                [],
+            )
+            .is_err());
+    }
+}
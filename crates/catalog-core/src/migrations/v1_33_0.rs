@@ -0,0 +1,101 @@
+//! Migration v1.33.0: Completion Markers.
+//!
+//! This migration adds readiness markers for orchestration handoffs:
+//! - `completion_markers` table with one row per completed partition of a dataset
+//!
+//! # Semantics
+//!
+//! A producer marks a partition of a dataset (e.g. `2025-11-27`, or
+//! `date=2025-11-27/region=eu`) complete once it has been written; consumers
+//! poll for the marker instead of a `_SUCCESS` file. Marking a partition again
+//! updates its marker. Markers older than the retention window are purged.
+
+use super::Migration;
+
+/// Version number: 1_033_000 represents v1.33.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_033_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.33.0: Completion Markers",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.33.0 Schema Migration
+-- Completion Markers
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS completion_markers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    -- Partition identifier chosen by the producer
+    partition TEXT NOT NULL,
+    -- JSON object with producer details (run id, row count, ...)
+    metadata TEXT,
+    -- API key or user that marked the partition
+    marked_by TEXT,
+    completed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    UNIQUE(dataset_id, partition)
+);
+
+CREATE INDEX IF NOT EXISTS idx_completion_markers_completed ON completion_markers(completed_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_033_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.33.0"));
+        assert!(m.description.contains("Markers"));
+    }
+
+    #[test]
+    fn test_one_marker_per_partition() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO completion_markers (dataset_id, partition) VALUES (1, '2025-11-27');",
+        )
+        .unwrap();
+
+        assert!(conn
+            .execute(
+                "INSERT INTO completion_markers (dataset_id, partition) VALUES (1, '2025-11-27')",
+                [],
+            )
+            .is_err());
+
+        conn.execute("DELETE FROM datasets WHERE id = 1", [])
+            .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM completion_markers", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
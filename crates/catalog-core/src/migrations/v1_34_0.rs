@@ -0,0 +1,86 @@
+//! Migration v1.34.0: Digest Snapshots.
+//!
+//! This migration adds the schema baseline for change digests:
+//! - `digest_snapshots` table with each dataset's schema as of the last digest
+//!
+//! # Semantics
+//!
+//! The scheduled digest reports schema changes since the previous run by
+//! comparing each dataset's columns to its snapshot, then replaces the
+//! snapshots. Datasets without a snapshot report no schema change; new
+//! datasets are listed as such instead.
+
+use super::Migration;
+
+/// Version number: 1_034_000 represents v1.34.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_034_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.34.0: Digest Snapshots",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.34.0 Schema Migration
+-- Digest Snapshots
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS digest_snapshots (
+    dataset_id INTEGER PRIMARY KEY,
+    -- JSON object of column name to data type
+    schema TEXT NOT NULL DEFAULT '{}',
+    taken_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_034_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.34.0"));
+        assert!(m.description.contains("Digest"));
+    }
+
+    #[test]
+    fn test_snapshots_follow_datasets() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO digest_snapshots (dataset_id, schema) VALUES (1, '{\"id\":\"int\"}');",
+        )
+        .unwrap();
+
+        conn.execute("DELETE FROM datasets WHERE id = 1", [])
+            .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM digest_snapshots", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
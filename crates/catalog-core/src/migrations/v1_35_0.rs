@@ -0,0 +1,79 @@
+//! Migration v1.35.0: Catalog Statistics.
+//!
+//! This migration adds daily catalog-wide statistics:
+//! - `catalog_stats_snapshots` table with one row per UTC day
+//!
+//! # Semantics
+//!
+//! The API server records the catalog's totals periodically. Each record
+//! replaces the day's row, so a past day holds its last recorded state and
+//! the history shows how the catalog grew.
+
+use super::Migration;
+
+/// Version number: 1_035_000 represents v1.35.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_035_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.35.0: Catalog Statistics",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.35.0 Schema Migration
+-- Catalog Statistics
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS catalog_stats_snapshots (
+    -- UTC day (YYYY-MM-DD)
+    snapshot_date TEXT PRIMARY KEY,
+    dataset_count INTEGER NOT NULL,
+    -- Sum of datasets.size_bytes, where known
+    total_size_bytes INTEGER NOT NULL,
+    field_count INTEGER NOT NULL,
+    lineage_edge_count INTEGER NOT NULL,
+    -- Fields with a classification other than 'unknown'
+    classified_field_count INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_035_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.35.0"));
+        assert!(m.description.contains("Statistics"));
+    }
+
+    #[test]
+    fn test_one_snapshot_per_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        let insert = "INSERT INTO catalog_stats_snapshots
+             (snapshot_date, dataset_count, total_size_bytes, field_count, lineage_edge_count, classified_field_count)
+             VALUES ('2025-11-20', 1, 0, 0, 0, 0)";
+        conn.execute(insert, []).unwrap();
+        assert!(conn.execute(insert, []).is_err());
+    }
+}
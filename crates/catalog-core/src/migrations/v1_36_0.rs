@@ -0,0 +1,77 @@
+//! Migration v1.36.0: Tenant Data Residency.
+//!
+//! This migration adds per-tenant data residency to the control plane:
+//! - `tenant_residency` table holding the residency zone of a tenant
+//!
+//! # Semantics
+//!
+//! A tenant with a zone (e.g. `eu`) must keep its catalog in one of the
+//! zone's regions. Tenants without a row have no residency requirement.
+
+use super::Migration;
+
+/// Version number: 1_036_000 represents v1.36.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_036_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.36.0: Tenant Data Residency",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.36.0 Schema Migration
+-- Tenant Data Residency
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS tenant_residency (
+    tenant_id TEXT PRIMARY KEY,
+    -- Residency zone (e.g., eu, us) from METAFUSE_RESIDENCY_ZONES
+    zone TEXT NOT NULL,
+    -- When the zone was last changed
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (tenant_id) REFERENCES tenants(tenant_id) ON DELETE CASCADE
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_036_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.36.0"));
+        assert!(m.description.contains("Residency"));
+    }
+
+    #[test]
+    fn test_residency_table_created() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        let exists: bool = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tenant_residency'",
+                [],
+                |_| Ok(true),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+}
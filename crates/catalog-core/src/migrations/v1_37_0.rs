@@ -0,0 +1,84 @@
+//! Migration v1.37.0: Group Membership.
+//!
+//! This migration adds directory-synced group membership:
+//! - `group_members` table mapping users to the groups (teams) they belong to
+//!
+//! # Semantics
+//!
+//! Rows are written by a directory sync (e.g. SCIM) and read by the `scim`
+//! group provider, which resolves a caller's groups for ACLs, ownership
+//! filters and notification routing. A user with no rows belongs to no
+//! directory groups.
+
+use super::Migration;
+
+/// Version number: 1_037_000 represents v1.37.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_037_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.37.0: Group Membership",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.37.0 Schema Migration
+-- Group Membership
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS group_members (
+    -- Group (team) id, as used in `group:<id>` principals and owner ids
+    group_id TEXT NOT NULL,
+    -- User id, as sent in the identity user header
+    user_id TEXT NOT NULL,
+    -- Directory the membership was synced from (e.g., scim)
+    source TEXT NOT NULL DEFAULT 'scim',
+    -- When the membership was last synced
+    synced_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_group_members_user ON group_members(user_id);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_037_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.37.0"));
+        assert!(m.description.contains("Group"));
+    }
+
+    #[test]
+    fn test_group_members_table_created() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        let exists: bool = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'group_members'",
+                [],
+                |_| Ok(true),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+}
@@ -0,0 +1,92 @@
+//! Migration v1.38.0: Sandbox Namespaces.
+//!
+//! This migration adds ephemeral (sandbox) namespaces for CI runs:
+//! - `namespaces.expires_at` marks a namespace as a sandbox and when it expires
+//! - `tenant_api_keys.sandbox_namespace` pins a tenant key's writes to a sandbox
+//!
+//! # Semantics
+//!
+//! Datasets in a sandbox are hidden from dataset lists and search, and the
+//! sandbox is purged with its datasets after `expires_at`. Namespaces without
+//! `expires_at` and keys without `sandbox_namespace` are unaffected.
+
+use super::Migration;
+
+/// Version number: 1_038_000 represents v1.38.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_038_000;
+
+/// Sandbox columns on namespaces and tenant API keys
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("namespaces", "expires_at", "TEXT"),
+    ("tenant_api_keys", "sandbox_namespace", "TEXT"),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.38.0: Sandbox Namespaces",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.38.0 Schema Migration
+-- Sandbox Namespaces
+-- ============================================================================
+
+-- expires_at and sandbox_namespace are added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_038_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.38.0"));
+        assert!(m.description.contains("Sandbox"));
+    }
+
+    #[test]
+    fn test_sandbox_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO namespaces (name) VALUES ('sales');
+             INSERT INTO namespaces (name, expires_at)
+             VALUES ('ci_42', datetime('now', '+1 hour'));
+             INSERT INTO tenants (tenant_id, display_name, admin_email, storage_uri)
+             VALUES ('acme', 'Acme', 'ops@acme.test', 'file:///tmp/acme.db');
+             INSERT INTO tenant_api_keys (tenant_id, key_hash, name, role, sandbox_namespace)
+             VALUES ('acme', 'h1', 'ci', 'editor', 'ci_42');",
+        )
+        .unwrap();
+        let sandboxes: Vec<String> = conn
+            .prepare("SELECT name FROM namespaces WHERE expires_at IS NOT NULL")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(sandboxes, vec!["ci_42".to_string()]);
+        let pinned: Option<String> = conn
+            .query_row("SELECT sandbox_namespace FROM tenant_api_keys", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(pinned.as_deref(), Some("ci_42"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Migration v1.39.0: Webhooks.
+//!
+//! This migration adds outbound notifications of catalog changes:
+//! - `webhooks` table with one row per registered endpoint
+//! - `webhook_deliveries` table with one row per event sent to an endpoint
+//!
+//! # Semantics
+//!
+//! A webhook belongs to a tenant and subscribes to a set of events
+//! (`dataset.created`, `quality.dropped`, ...). Each matching event queues a
+//! delivery, which is retried with backoff until it succeeds or runs out of
+//! attempts. Payloads are signed with the webhook's secret.
+
+use super::Migration;
+
+/// Version number: 1_039_000 represents v1.39.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_039_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.39.0: Webhooks",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.39.0 Schema Migration
+-- Webhooks
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Tenant whose events the webhook receives
+    tenant_id TEXT NOT NULL DEFAULT 'default',
+    url TEXT NOT NULL,
+    -- HMAC-SHA256 key for the signature header
+    secret TEXT NOT NULL,
+    -- JSON array of subscribed events
+    events TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    -- API key or user that registered the webhook
+    created_by TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhooks_tenant ON webhooks(tenant_id);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id INTEGER NOT NULL,
+    event TEXT NOT NULL,
+    -- JSON body sent to the endpoint
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NOT NULL DEFAULT (datetime('now')),
+    -- HTTP status of the last attempt, if the endpoint answered
+    last_status_code INTEGER,
+    last_error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    delivered_at TEXT,
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, created_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_039_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.39.0"));
+        assert!(m.description.contains("Webhooks"));
+    }
+
+    #[test]
+    fn test_deliveries_removed_with_webhook() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            r#"INSERT INTO webhooks (url, secret, events)
+               VALUES ('https://hooks.example.com/a', 's3cret', '["dataset.created"]');
+               INSERT INTO webhook_deliveries (webhook_id, event, payload)
+               VALUES (1, 'dataset.created', '{}');"#,
+        )
+        .unwrap();
+
+        let (tenant_id, status): (String, String) = conn
+            .query_row(
+                "SELECT w.tenant_id, d.status FROM webhooks w
+                 JOIN webhook_deliveries d ON d.webhook_id = w.id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (tenant_id.as_str(), status.as_str()),
+            ("default", "pending")
+        );
+        assert!(conn
+            .execute(
+                "UPDATE webhook_deliveries SET status = 'lost' WHERE id = 1",
+                [],
+            )
+            .is_err());
+
+        conn.execute("DELETE FROM webhooks WHERE id = 1", [])
+            .unwrap();
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM webhook_deliveries", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+}
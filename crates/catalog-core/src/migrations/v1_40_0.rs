@@ -0,0 +1,123 @@
+//! Migration v1.40.0: Tenant Export Schedules.
+//!
+//! This migration adds scheduled catalog exports to the control plane:
+//! - `tenant_export_schedules` table with one row per scheduled export
+//! - `tenant_export_runs` table with the history of each schedule
+//!
+//! # Semantics
+//!
+//! A schedule exports its tenant's catalog as a bundle (`json` or `ndjson`)
+//! to an object storage prefix every hour, day, or week. `next_run_at` is
+//! advanced when a run starts, so each due run is taken once.
+
+use super::Migration;
+
+/// Version number: 1_040_000 represents v1.40.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_040_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.40.0: Tenant Export Schedules",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.40.0 Schema Migration
+-- Tenant Export Schedules
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS tenant_export_schedules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id TEXT NOT NULL,
+    -- Object storage prefix receiving the bundles (gs://, s3://, or file://)
+    target_uri TEXT NOT NULL,
+    format TEXT NOT NULL DEFAULT 'json' CHECK (format IN ('json', 'ndjson')),
+    frequency TEXT NOT NULL DEFAULT 'daily' CHECK (frequency IN ('hourly', 'daily', 'weekly')),
+    enabled INTEGER NOT NULL DEFAULT 1,
+    -- When the schedule is next due
+    next_run_at TEXT NOT NULL DEFAULT (datetime('now')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (tenant_id) REFERENCES tenants(tenant_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_export_schedules_tenant ON tenant_export_schedules(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_tenant_export_schedules_due ON tenant_export_schedules(enabled, next_run_at);
+
+CREATE TABLE IF NOT EXISTS tenant_export_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    schedule_id INTEGER NOT NULL,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    finished_at TEXT,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'succeeded', 'failed')),
+    -- Object written by a successful run
+    object_uri TEXT,
+    dataset_count INTEGER,
+    size_bytes INTEGER,
+    error TEXT,
+    FOREIGN KEY (schedule_id) REFERENCES tenant_export_schedules(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_export_runs_schedule ON tenant_export_runs(schedule_id, started_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_040_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.40.0"));
+        assert!(m.description.contains("Export Schedules"));
+    }
+
+    #[test]
+    fn test_export_schedule_defaults() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO tenants (tenant_id, display_name, admin_email, storage_uri)
+             VALUES ('acme', 'Acme', 'ops@acme.test', 'file:///tmp/acme.db');
+             INSERT INTO tenant_export_schedules (tenant_id, target_uri)
+             VALUES ('acme', 'gs://acme-exports/metafuse');
+             INSERT INTO tenant_export_runs (schedule_id) VALUES (1);",
+        )
+        .unwrap();
+
+        let (format, frequency, status): (String, String, String) = conn
+            .query_row(
+                "SELECT s.format, s.frequency, r.status FROM tenant_export_schedules s
+                 JOIN tenant_export_runs r ON r.schedule_id = s.id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (format.as_str(), frequency.as_str(), status.as_str()),
+            ("json", "daily", "running")
+        );
+        assert!(conn
+            .execute(
+                "UPDATE tenant_export_schedules SET frequency = 'monthly' WHERE id = 1",
+                [],
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! Migration v1.41.0: Field Business Names.
+//!
+//! This migration adds curated metadata to fields:
+//! - `business_name` column on `fields` (display name, e.g. "Customer ID")
+//!
+//! # Semantics
+//!
+//! Business names are set through the field metadata API, never by emitters.
+//! Fields keep their row across re-emits, so business names, descriptions, and
+//! glossary links survive schema updates; a field dropped from the schema loses
+//! them.
+
+use super::Migration;
+
+/// Version number: 1_041_000 represents v1.41.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_041_000;
+
+/// Add the business_name column to fields table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[("fields", "business_name", "TEXT")];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.41.0: Field Business Names",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.41.0 Schema Migration
+-- Field Business Names
+-- ============================================================================
+-- business_name is added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_041_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.41.0"));
+        assert!(m.description.contains("Business Names"));
+    }
+
+    #[test]
+    fn test_fields_have_business_name_column() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO fields (dataset_id, name, data_type, nullable, business_name)
+             VALUES (1, 'cust_id', 'Int64', 0, 'Customer ID');",
+        )
+        .unwrap();
+        let business_name: Option<String> = conn
+            .query_row("SELECT business_name FROM fields", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(business_name.as_deref(), Some("Customer ID"));
+    }
+}
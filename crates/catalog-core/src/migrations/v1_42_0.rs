@@ -0,0 +1,111 @@
+//! Migration v1.42.0: Dataset Consumers.
+//!
+//! This migration adds a registry of consumers outside the catalog:
+//! - `dataset_consumers` table with one row per dataset and consumer name
+//!
+//! # Semantics
+//!
+//! Lineage only records consumers that are datasets. Services, reports, and
+//! other readers register here with a contact, how often they read, and the
+//! SLA they expect, so impact analysis and deprecation notices reach them.
+//! Registering a consumer again updates it; consumers go with their dataset.
+
+use super::Migration;
+
+/// Version number: 1_042_000 represents v1.42.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_042_000;
+
+/// No additional columns needed (new tables)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.42.0: Dataset Consumers",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.42.0 Schema Migration
+-- Dataset Consumers
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dataset_consumers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    -- Consumer name, e.g. 'billing-service' or 'Weekly revenue report'
+    name TEXT NOT NULL,
+    -- Email, Slack channel, or on-call alias of the consumer's owners
+    contact TEXT,
+    -- How often the consumer reads the dataset
+    read_frequency TEXT,
+    -- What the consumer expects, e.g. 'refreshed by 06:00 UTC'
+    sla TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    UNIQUE(dataset_id, name),
+    CHECK (read_frequency IN ('continuous', 'hourly', 'daily', 'weekly', 'monthly', 'ad_hoc'))
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_042_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.42.0"));
+        assert!(m.description.contains("Consumers"));
+    }
+
+    #[test]
+    fn test_one_consumer_per_name() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO dataset_consumers (dataset_id, name, read_frequency)
+             VALUES (1, 'billing-service', 'hourly');",
+        )
+        .unwrap();
+
+        assert!(conn
+            .execute(
+                "INSERT INTO dataset_consumers (dataset_id, name) VALUES (1, 'billing-service')",
+                [],
+            )
+            .is_err());
+        assert!(conn
+            .execute(
+                "INSERT INTO dataset_consumers (dataset_id, name, read_frequency)
+                 VALUES (1, 'report', 'sometimes')",
+                [],
+            )
+            .is_err());
+
+        conn.execute("DELETE FROM datasets WHERE id = 1", [])
+            .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM dataset_consumers", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,91 @@
+//! Migration v1.43.0: Curated Metadata Provenance.
+//!
+//! This migration records which metadata came from emitters, so re-emits can
+//! keep what people curated (see [`crate::merge_strategy`]):
+//! - `emitted` column on `tags` (1 for tags the emitter wrote)
+//! - `description_curated` column on `fields` (1 for descriptions set through
+//!   the field metadata API)
+//!
+//! # Semantics
+//!
+//! Existing tags count as added by hand and existing descriptions as emitted,
+//! since their origin is unknown.
+
+use super::Migration;
+
+/// Version number: 1_043_000 represents v1.43.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_043_000;
+
+/// Add the provenance columns to tags and fields tables.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("tags", "emitted", "INTEGER NOT NULL DEFAULT 0"),
+    (
+        "fields",
+        "description_curated",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.43.0: Curated Metadata Provenance",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.43.0 Schema Migration
+-- Curated Metadata Provenance
+-- ============================================================================
+-- tags.emitted and fields.description_curated are added via add_columns
+-- helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_043_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.43.0"));
+        assert!(m.description.contains("Provenance"));
+    }
+
+    #[test]
+    fn test_provenance_defaults_to_manual_tags() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO tags (dataset_id, tag) VALUES (1, 'gold');
+             INSERT INTO fields (dataset_id, name, data_type, nullable)
+             VALUES (1, 'id', 'Int64', 0);",
+        )
+        .unwrap();
+        let emitted: bool = conn
+            .query_row("SELECT emitted FROM tags", [], |row| row.get(0))
+            .unwrap();
+        let curated: bool = conn
+            .query_row("SELECT description_curated FROM fields", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert!(!emitted);
+        assert!(!curated);
+    }
+}
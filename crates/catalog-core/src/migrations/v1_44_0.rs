@@ -0,0 +1,79 @@
+//! Migration v1.44.0: Dataset Archival.
+//!
+//! This migration adds archival for datasets that are retired but must stay
+//! on record:
+//! - `archived_at` column on `datasets` (NULL for datasets in use)
+//! - `archived_by` column on `datasets` recording who archived it
+//!
+//! # Semantics
+//!
+//! Archiving sets `archived_at` and the dataset status to `archived`. Unlike
+//! the trash, archived datasets are never purged: they keep their fields,
+//! tags, lineage, and audit history and can still be read by name, but are
+//! left out of listings and search unless asked for.
+
+use super::Migration;
+
+/// Version number: 1_044_000 represents v1.44.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_044_000;
+
+/// Add archival columns to datasets table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("datasets", "archived_at", "TEXT"),
+    ("datasets", "archived_by", "TEXT"),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.44.0: Dataset Archival",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.44.0 Schema Migration
+-- Dataset Archival
+-- ============================================================================
+-- archived_at and archived_by are added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_044_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.44.0"));
+        assert!(m.description.contains("Archival"));
+    }
+
+    #[test]
+    fn test_datasets_start_unarchived() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        let archived_at: Option<String> = conn
+            .query_row("SELECT archived_at FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert!(archived_at.is_none());
+    }
+}
@@ -0,0 +1,92 @@
+//! Migration v1.45.0: User Directory.
+//!
+//! This migration adds directory-synced users:
+//! - `directory_users` table with the display name, email and status of each
+//!   user known to an external directory
+//!
+//! # Semantics
+//!
+//! Rows are written by the SCIM provisioning endpoint (`source = 'scim'`) or
+//! by a sync job outside MetaFuse, and read by the `scim` and `table` user
+//! directories, which validate owners and verifiers and enrich them with
+//! display names. A user's teams are their `group_members` rows (v1.37.0).
+//! Deprovisioned users are kept with `active = 0` so the names in historical
+//! records still resolve.
+
+use super::Migration;
+
+/// Version number: 1_045_000 represents v1.45.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_045_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.45.0: User Directory",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.45.0 Schema Migration
+-- User Directory
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS directory_users (
+    -- Writer of the row: scim for the SCIM endpoint, else the sync job's name
+    source TEXT NOT NULL,
+    -- User id, as sent in the identity user header and used in owner ids
+    user_id TEXT NOT NULL,
+    display_name TEXT,
+    email TEXT,
+    -- 0 once the user is deprovisioned
+    active INTEGER NOT NULL DEFAULT 1,
+    -- When the user was last synced
+    synced_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (source, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_directory_users_user ON directory_users(user_id);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_045_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.45.0"));
+        assert!(m.description.contains("User Directory"));
+    }
+
+    #[test]
+    fn test_directory_users_default_active() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO directory_users (source, user_id, display_name)
+             VALUES ('scim', 'alice', 'Alice Liddell')",
+            [],
+        )
+        .unwrap();
+        let active: i64 = conn
+            .query_row("SELECT active FROM directory_users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(active, 1);
+    }
+}
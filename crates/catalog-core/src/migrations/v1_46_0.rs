@@ -0,0 +1,84 @@
+//! Migration v1.46.0: Classification Dismissals.
+//!
+//! This migration lets stewards dismiss classification findings:
+//! - `dismissed_at` column on `column_classifications` (NULL unless dismissed)
+//! - `dismissed_by` column on `column_classifications` recording who dismissed it
+//!
+//! # Semantics
+//!
+//! A dismissed finding is a false positive. It is kept rather than deleted so
+//! that scans don't raise it again, and it is left out of the PII column
+//! listing. Verified and dismissed findings count as reviewed.
+
+use super::Migration;
+
+/// Version number: 1_046_000 represents v1.46.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_046_000;
+
+/// Add dismissal columns to column_classifications table.
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[
+    ("column_classifications", "dismissed_at", "TEXT"),
+    ("column_classifications", "dismissed_by", "TEXT"),
+];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.46.0: Classification Dismissals",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.46.0 Schema Migration
+-- Classification Dismissals
+-- ============================================================================
+-- dismissed_at and dismissed_by are added via add_columns helper (not in SQL)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_046_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.46.0"));
+        assert!(m.description.contains("Dismissals"));
+    }
+
+    #[test]
+    fn test_classifications_start_undismissed() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('users', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO fields (dataset_id, name, data_type, nullable)
+             VALUES (1, 'email', 'STRING', 1);
+             INSERT INTO column_classifications (field_id, classification, category)
+             VALUES (1, 'pii', 'email');",
+        )
+        .unwrap();
+        let dismissed_at: Option<String> = conn
+            .query_row(
+                "SELECT dismissed_at FROM column_classifications",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(dismissed_at.is_none());
+    }
+}
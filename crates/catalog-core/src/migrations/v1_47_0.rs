@@ -0,0 +1,95 @@
+//! Migration v1.47.0: Request Logs.
+//!
+//! This migration adds a queryable API request log:
+//! - `request_logs` table with the route, status, latency and tenant of each
+//!   logged request
+//!
+//! # Semantics
+//!
+//! Rows are written in batches by the API server's request log sink, which
+//! samples successful requests and keeps every error. Each tenant's requests
+//! are written to that tenant's catalog. Rows older than the retention window
+//! are pruned by the sink, so the table only holds recent traffic.
+
+use super::Migration;
+
+/// Version number: 1_047_000 represents v1.47.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_047_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.47.0: Request Logs",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.47.0 Schema Migration
+-- Request Logs
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS request_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- X-Request-ID returned to the client
+    request_id TEXT NOT NULL,
+    -- Resolved tenant ('default' for the default catalog)
+    tenant_id TEXT NOT NULL,
+    method TEXT NOT NULL,
+    -- Route template, e.g. /api/v1/datasets/{name}
+    route TEXT NOT NULL,
+    -- Request path as sent, without the query string
+    path TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    -- When the request completed
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_logs_created ON request_logs(created_at);
+CREATE INDEX IF NOT EXISTS idx_request_logs_tenant ON request_logs(tenant_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_request_logs_request ON request_logs(request_id);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_047_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.47.0"));
+        assert!(m.description.contains("Request Logs"));
+    }
+
+    #[test]
+    fn test_request_logs_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO request_logs (request_id, tenant_id, method, route, path, status, latency_ms)
+             VALUES ('r1', 'default', 'GET', '/api/v1/datasets/{name}', '/api/v1/datasets/orders', 200, 12)",
+            [],
+        )
+        .unwrap();
+        let created_at: Option<String> = conn
+            .query_row("SELECT created_at FROM request_logs", [], |row| row.get(0))
+            .unwrap();
+        assert!(created_at.is_some());
+    }
+}
@@ -0,0 +1,115 @@
+//! Migration v1.48.0: Tenant Credentials Profiles.
+//!
+//! This migration lets scheduled exports upload with a tenant's own object
+//! storage credentials:
+//! - `tenant_credentials_profiles` table with a tenant's named GCS or S3
+//!   credentials, encrypted by the API server with a key kept outside the
+//!   database
+//! - `credentials_profile` on `tenant_export_schedules`, naming the profile a
+//!   schedule uploads with
+//!
+//! # Semantics
+//!
+//! Profile names are unique per tenant. A schedule without a profile uploads
+//! with the server's credentials. A profile can't be deleted while a schedule
+//! uses it.
+
+use super::Migration;
+
+/// Version number: 1_048_000 represents v1.48.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_048_000;
+
+/// Name the credentials profile of each export schedule.
+const ADD_COLUMNS: &[(&str, &str, &str)] =
+    &[("tenant_export_schedules", "credentials_profile", "TEXT")];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.48.0: Tenant Credentials Profiles",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.48.0 Schema Migration
+-- Tenant Credentials Profiles
+-- ============================================================================
+-- tenant_export_schedules.credentials_profile is added via add_columns helper
+-- (not in SQL)
+
+CREATE TABLE IF NOT EXISTS tenant_credentials_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    provider TEXT NOT NULL CHECK (provider IN ('gcs', 's3')),
+    -- Provider credentials, encrypted by the API server; never returned by the API
+    credentials TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (tenant_id, name),
+    FOREIGN KEY (tenant_id) REFERENCES tenants(tenant_id) ON DELETE CASCADE
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_048_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.48.0"));
+        assert!(m.description.contains("Credentials Profiles"));
+    }
+
+    #[test]
+    fn test_profile_names_are_unique_per_tenant() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO tenants (tenant_id, display_name, admin_email, storage_uri)
+             VALUES ('acme', 'Acme', 'ops@acme.test', 'file:///tmp/acme.db'),
+                    ('globex', 'Globex', 'ops@globex.test', 'file:///tmp/globex.db');
+             INSERT INTO tenant_credentials_profiles (tenant_id, name, provider, credentials)
+             VALUES ('acme', 'exports', 's3', '{}'), ('globex', 'exports', 'gcs', '{}');
+             INSERT INTO tenant_export_schedules (tenant_id, target_uri)
+             VALUES ('acme', 's3://acme-exports/metafuse');",
+        )
+        .unwrap();
+
+        let profile: Option<String> = conn
+            .query_row(
+                "SELECT credentials_profile FROM tenant_export_schedules",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(profile, None);
+        assert!(conn
+            .execute(
+                "INSERT INTO tenant_credentials_profiles (tenant_id, name, provider, credentials)
+                 VALUES ('acme', 'exports', 'gcs', '{}')",
+                [],
+            )
+            .is_err());
+        assert!(conn
+            .execute(
+                "INSERT INTO tenant_credentials_profiles (tenant_id, name, provider, credentials)
+                 VALUES ('acme', 'azure', 'azure', '{}')",
+                [],
+            )
+            .is_err());
+    }
+}
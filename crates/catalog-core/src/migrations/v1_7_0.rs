@@ -0,0 +1,126 @@
+//! Migration v1.7.0: Tenant Feature Flags.
+//!
+//! This migration adds per-tenant feature flags to the control plane:
+//! - `tenant_feature_flags` table storing explicit overrides per tenant
+//!
+//! # Semantics
+//!
+//! Flags that have no row for a tenant fall back to the built-in default
+//! (enabled), so existing tenants keep their current behavior after upgrade.
+
+use super::Migration;
+
+/// Version number: 1_007_000 represents v1.7.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_007_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.7.0: Tenant Feature Flags",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.7.0 Schema Migration
+-- Tenant Feature Flags
+-- ============================================================================
+
+-- Per-tenant feature flag overrides
+-- Missing rows mean "use the default" (enabled)
+CREATE TABLE IF NOT EXISTS tenant_feature_flags (
+    -- Tenant this flag applies to
+    tenant_id TEXT NOT NULL,
+    -- Feature name (e.g., classification, preview, usage_analytics)
+    flag TEXT NOT NULL,
+    -- 1 = enabled, 0 = disabled
+    enabled INTEGER NOT NULL DEFAULT 1,
+    -- When the flag was last changed
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant_id, flag),
+    FOREIGN KEY (tenant_id) REFERENCES tenants(tenant_id) ON DELETE CASCADE,
+    CHECK (enabled IN (0, 1))
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_feature_flags_tenant ON tenant_feature_flags(tenant_id);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_007_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.7.0"));
+        assert!(m.description.contains("Feature Flags"));
+    }
+
+    #[test]
+    fn test_tenant_feature_flags_table_created() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='tenant_feature_flags'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1, "tenant_feature_flags table should exist");
+    }
+
+    #[test]
+    fn test_tenant_feature_flags_upsert() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO tenants (tenant_id, display_name, storage_uri, admin_email)
+             VALUES ('acme-corp', 'Acme', '/tmp/acme.db', 'admin@acme.com')",
+            [],
+        )
+        .unwrap();
+
+        conn.execute(
+            "INSERT INTO tenant_feature_flags (tenant_id, flag, enabled)
+             VALUES ('acme-corp', 'preview', 0)
+             ON CONFLICT(tenant_id, flag) DO UPDATE SET enabled = excluded.enabled",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO tenant_feature_flags (tenant_id, flag, enabled)
+             VALUES ('acme-corp', 'preview', 1)
+             ON CONFLICT(tenant_id, flag) DO UPDATE SET enabled = excluded.enabled",
+            [],
+        )
+        .unwrap();
+
+        let (count, enabled): (i64, i64) = conn
+            .query_row(
+                "SELECT COUNT(*), MAX(enabled) FROM tenant_feature_flags WHERE tenant_id = 'acme-corp'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(enabled, 1);
+    }
+}
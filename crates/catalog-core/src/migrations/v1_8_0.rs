@@ -0,0 +1,106 @@
+//! Migration v1.8.0: Rate Limit Events.
+//!
+//! This migration adds a record of rate limit violations to the control plane:
+//! - `rate_limit_events` table storing aggregated 429 responses per client and route
+//!
+//! # Semantics
+//!
+//! Violations are aggregated in memory by the rate limiter and flushed periodically,
+//! so each row covers one (tenant, client, route) combination over one flush interval.
+
+use super::Migration;
+
+/// Version number: 1_008_000 represents v1.8.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_008_000;
+
+/// No additional columns needed (new table)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.8.0: Rate Limit Events",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.8.0 Schema Migration
+-- Rate Limit Events
+-- ============================================================================
+
+-- Aggregated rate limit violations
+-- No foreign key to tenants: history is kept after a tenant is deleted
+CREATE TABLE IF NOT EXISTS rate_limit_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Tenant the client belongs to (NULL for non-tenant clients)
+    tenant_id TEXT,
+    -- Client identity within the tenant: "auth:<key hash>" or "ip:<address>"
+    client_key TEXT NOT NULL,
+    -- Matched route template (e.g., /api/v1/datasets/{name})
+    route TEXT NOT NULL,
+    -- Number of rejected requests in this interval
+    violation_count INTEGER NOT NULL DEFAULT 1,
+    -- First and last rejection in this interval
+    first_seen_at TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL,
+    -- When the interval was flushed
+    recorded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (violation_count > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_events_recorded ON rate_limit_events(recorded_at);
+CREATE INDEX IF NOT EXISTS idx_rate_limit_events_tenant ON rate_limit_events(tenant_id, recorded_at);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_008_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.8.0"));
+        assert!(m.description.contains("Rate Limit"));
+    }
+
+    #[test]
+    fn test_rate_limit_events_table_created() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='rate_limit_events'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1, "rate_limit_events table should exist");
+    }
+
+    #[test]
+    fn test_rate_limit_events_rejects_zero_count() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        run_migrations(&conn).unwrap();
+
+        let result = conn.execute(
+            "INSERT INTO rate_limit_events (tenant_id, client_key, route, violation_count, first_seen_at, last_seen_at)
+             VALUES ('acme-corp', 'ip:10.0.0.1', '/api/v1/datasets', 0, datetime('now'), datetime('now'))",
+            [],
+        );
+        assert!(result.is_err(), "violation_count must be positive");
+    }
+}
@@ -0,0 +1,110 @@
+//! Migration v1.9.0: Canonical Dataset Formats.
+//!
+//! This migration rewrites `datasets.format` values written before the format
+//! registry (`crate::formats`) to their canonical names:
+//! - Case and surrounding whitespace are normalized ("Parquet " -> "parquet")
+//! - Known aliases are mapped to the canonical name ("pq" -> "parquet")
+//!
+//! Unknown formats are left untouched; writers reject them from now on.
+
+use super::Migration;
+
+/// Version number: 1_009_000 represents v1.9.0
+/// Format: MAJOR * 1_000_000 + MINOR * 1_000 + PATCH
+pub const VERSION: i64 = 1_009_000;
+
+/// No additional columns needed (data-only migration)
+const ADD_COLUMNS: &[(&str, &str, &str)] = &[];
+
+pub fn migration() -> Migration {
+    Migration {
+        version: VERSION,
+        description: "v1.9.0: Canonical Dataset Formats",
+        sql: SQL,
+        add_columns: ADD_COLUMNS,
+    }
+}
+
+// Keep in sync with crate::formats::FORMATS
+const SQL: &str = r#"
+-- ============================================================================
+-- MetaFuse v1.9.0 Schema Migration
+-- Canonical Dataset Formats
+-- ============================================================================
+
+UPDATE datasets
+SET format = CASE lower(trim(format))
+    WHEN 'pq' THEN 'parquet'
+    WHEN 'deltalake' THEN 'delta'
+    WHEN 'delta_lake' THEN 'delta'
+    WHEN 'delta-lake' THEN 'delta'
+    WHEN 'tsv' THEN 'csv'
+    WHEN 'jsonl' THEN 'json'
+    WHEN 'ndjson' THEN 'json'
+    ELSE lower(trim(format))
+END
+WHERE lower(trim(format)) IN (
+    'parquet', 'pq',
+    'delta', 'deltalake', 'delta_lake', 'delta-lake',
+    'iceberg', 'orc', 'avro',
+    'csv', 'tsv',
+    'json', 'jsonl', 'ndjson'
+);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::FORMATS;
+    use crate::migrations::run_migrations;
+    use rusqlite::Connection;
+
+    #[test]
+    fn test_migration_version() {
+        assert_eq!(VERSION, 1_009_000);
+    }
+
+    #[test]
+    fn test_migration_description() {
+        let m = migration();
+        assert!(m.description.contains("v1.9.0"));
+        assert!(m.description.contains("Format"));
+    }
+
+    #[test]
+    fn test_formats_canonicalized() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+
+        // Every registry spelling, upper-cased, plus an unknown format
+        let mut expected = Vec::new();
+        for spec in FORMATS {
+            for spelling in std::iter::once(spec.name).chain(spec.aliases.iter().copied()) {
+                expected.push((spelling.to_uppercase(), spec.name.to_string()));
+            }
+        }
+        expected.push(("Excel".to_string(), "Excel".to_string()));
+
+        for (i, (format, _)) in expected.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, '/data', ?2, datetime('now'), datetime('now'))",
+                rusqlite::params![format!("ds_{}", i), format],
+            )
+            .unwrap();
+        }
+
+        run_migrations(&conn).unwrap();
+
+        for (i, (_, canonical)) in expected.iter().enumerate() {
+            let format: String = conn
+                .query_row(
+                    "SELECT format FROM datasets WHERE name = ?1",
+                    [format!("ds_{}", i)],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(&format, canonical);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Dataset path history
+//!
+//! Relocations recorded by the `path_history_update` trigger whenever a
+//! dataset's path changes, so a moved dataset can still be found by its old
+//! location.
+//!
+//! Requires migration v1.30.0. On older catalogs lookups return nothing.
+
+use crate::Result;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+/// One change of a dataset's storage path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathChange {
+    pub old_path: String,
+    pub new_path: String,
+    pub changed_at: String,
+}
+
+/// Whether the catalog has the path history table.
+pub fn has_path_history_table(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'path_history'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Path changes of a dataset, newest first.
+pub fn history_for(conn: &Connection, dataset_id: i64) -> Result<Vec<PathChange>> {
+    if !has_path_history_table(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT old_path, new_path, changed_at FROM path_history
+         WHERE dataset_id = ?1
+         ORDER BY changed_at DESC, id DESC",
+    )?;
+    let changes = stmt
+        .query_map([dataset_id], |row| {
+            Ok(PathChange {
+                old_path: row.get(0)?,
+                new_path: row.get(1)?,
+                changed_at: row.get(2)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(changes)
+}
+
+/// Live datasets that moved away from `old_path` within the last `grace_days`.
+///
+/// Datasets currently at `old_path` are excluded; they are found by path
+/// already. Newest relocation first.
+pub fn relocated_from(conn: &Connection, old_path: &str, grace_days: u32) -> Result<Vec<i64>> {
+    if grace_days == 0 || !has_path_history_table(conn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT h.dataset_id FROM path_history h
+         JOIN datasets d ON d.id = h.dataset_id
+         WHERE h.old_path = ?1 AND d.path <> ?1 AND d.deleted_at IS NULL
+           AND h.changed_at >= datetime('now', ?2)
+         GROUP BY h.dataset_id
+         ORDER BY MAX(h.changed_at) DESC, h.dataset_id",
+    )?;
+    let ids = stmt
+        .query_map(
+            rusqlite::params![old_path, format!("-{} days", grace_days)],
+            |row| row.get(0),
+        )?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', 's3://a/orders', 'delta', datetime('now'), datetime('now'));
+             UPDATE datasets SET path = 's3://b/orders' WHERE name = 'orders';
+             UPDATE datasets SET path = 's3://c/orders' WHERE name = 'orders';",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_history_newest_first() {
+        let conn = setup();
+        let history = history_for(&conn, 1).unwrap();
+        let moves: Vec<(&str, &str)> = history
+            .iter()
+            .map(|c| (c.old_path.as_str(), c.new_path.as_str()))
+            .collect();
+        assert_eq!(
+            moves,
+            vec![
+                ("s3://b/orders", "s3://c/orders"),
+                ("s3://a/orders", "s3://b/orders")
+            ]
+        );
+        assert!(history_for(&conn, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_relocated_from_grace_period() {
+        let conn = setup();
+        assert_eq!(relocated_from(&conn, "s3://a/orders", 30).unwrap(), vec![1]);
+        assert!(relocated_from(&conn, "s3://a/orders", 0)
+            .unwrap()
+            .is_empty());
+        assert!(relocated_from(&conn, "s3://c/orders", 30)
+            .unwrap()
+            .is_empty());
+
+        conn.execute(
+            "UPDATE path_history SET changed_at = datetime('now', '-40 days')",
+            [],
+        )
+        .unwrap();
+        assert!(relocated_from(&conn, "s3://a/orders", 30)
+            .unwrap()
+            .is_empty());
+
+        // Moving back to the old path ends the redirect
+        conn.execute_batch(
+            "UPDATE path_history SET changed_at = datetime('now');
+             UPDATE datasets SET path = 's3://a/orders' WHERE id = 1;",
+        )
+        .unwrap();
+        assert!(relocated_from(&conn, "s3://a/orders", 30)
+            .unwrap()
+            .is_empty());
+
+        conn.execute("UPDATE datasets SET deleted_at = datetime('now')", [])
+            .unwrap();
+        assert!(relocated_from(&conn, "s3://b/orders", 30)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,362 @@
+//! Object path normalization
+//!
+//! The same storage location can be spelled many ways (`s3:/bucket/x`,
+//! `S3://Bucket/x/`, `s3://bucket//x`). Registering each spelling creates
+//! duplicate datasets, so writers store paths in one canonical form via
+//! [`normalize_path`]:
+//! - The scheme must be in [`SUPPORTED_SCHEMES`] and is lowercased
+//! - `scheme:/x` and `scheme:///x` are repaired to `scheme://x`
+//! - The bucket/container/host is lowercased
+//! - Repeated slashes, `.` segments, and trailing slashes are removed
+//! - `..` segments are rejected
+//!
+//! Paths without a scheme are treated as local filesystem paths and only have
+//! their separators normalized.
+//!
+//! [`analyze_paths`] reports datasets written before normalization whose paths
+//! are not canonical or collide with another dataset's path.
+
+use crate::{CatalogError, Result};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// URI schemes accepted for dataset paths.
+pub const SUPPORTED_SCHEMES: &[&str] = &["s3", "s3a", "gs", "az", "abfs", "abfss", "hdfs", "file"];
+
+/// Normalize and validate a dataset storage path.
+///
+/// Returns the canonical form, or a validation error if the path is empty,
+/// uses an unsupported scheme, has no bucket/host, or contains `..` segments.
+pub fn normalize_path(path: &str) -> Result<String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(CatalogError::ValidationError(
+            "Path cannot be empty".to_string(),
+        ));
+    }
+    if trimmed.chars().any(|c| c.is_control()) {
+        return Err(CatalogError::ValidationError(
+            "Path contains control characters".to_string(),
+        ));
+    }
+
+    let Some((scheme, rest)) = split_scheme(trimmed) else {
+        // Local filesystem path
+        let segments = normalize_segments(trimmed)?;
+        return match (trimmed.starts_with('/'), segments.is_empty()) {
+            (true, _) => Ok(format!("/{}", segments.join("/"))),
+            (false, false) => Ok(segments.join("/")),
+            (false, true) => Err(CatalogError::ValidationError(format!(
+                "Path '{}' does not reference a location",
+                path
+            ))),
+        };
+    };
+
+    let scheme = scheme.to_ascii_lowercase();
+    if !SUPPORTED_SCHEMES.contains(&scheme.as_str()) {
+        return Err(CatalogError::ValidationError(format!(
+            "Unsupported path scheme '{}' (supported: {})",
+            scheme,
+            SUPPORTED_SCHEMES.join(", ")
+        )));
+    }
+
+    let rest = rest.trim_start_matches('/');
+
+    // file URIs are always absolute local paths
+    if scheme == "file" {
+        let segments = normalize_segments(rest)?;
+        return Ok(format!("file:///{}", segments.join("/")));
+    }
+
+    let (authority, key) = rest.split_once('/').unwrap_or((rest, ""));
+    if authority.is_empty() {
+        return Err(CatalogError::ValidationError(format!(
+            "Path '{}' is missing a bucket or host",
+            path
+        )));
+    }
+
+    let mut normalized = format!("{}://{}", scheme, authority.to_ascii_lowercase());
+    for segment in normalize_segments(key)? {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+    Ok(normalized)
+}
+
+/// Split `scheme:rest`, where `rest` starts with a slash.
+///
+/// Single-letter schemes are treated as Windows drive letters (no scheme).
+fn split_scheme(path: &str) -> Option<(&str, &str)> {
+    let (scheme, rest) = path.split_once(':')?;
+    let valid = scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    (valid && rest.starts_with('/')).then_some((scheme, rest))
+}
+
+/// Split a path on `/`, dropping empty and `.` segments and rejecting `..`.
+fn normalize_segments(path: &str) -> Result<Vec<&str>> {
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                return Err(CatalogError::ValidationError(
+                    "Path contains traversal pattern (..)".to_string(),
+                ))
+            }
+            s => segments.push(s),
+        }
+    }
+    Ok(segments)
+}
+
+// =============================================================================
+// Path Analysis
+// =============================================================================
+
+/// A dataset whose stored path differs from its canonical form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NonCanonicalPath {
+    pub name: String,
+    pub path: String,
+    pub canonical_path: String,
+}
+
+/// A dataset whose stored path cannot be normalized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvalidPath {
+    pub name: String,
+    pub path: String,
+    pub error: String,
+}
+
+/// Datasets registered under different spellings of the same location.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathDuplicateGroup {
+    pub canonical_path: String,
+    /// Proposed survivor: the dataset already using the canonical spelling,
+    /// otherwise the most recently updated one
+    pub keep: String,
+    /// Datasets proposed for merging into `keep`
+    pub merge: Vec<String>,
+}
+
+/// Result of [`analyze_paths`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PathReport {
+    /// Non-canonical paths that can be rewritten in place (no collision)
+    pub non_canonical: Vec<NonCanonicalPath>,
+    pub invalid: Vec<InvalidPath>,
+    pub duplicates: Vec<PathDuplicateGroup>,
+}
+
+impl PathReport {
+    /// True if every dataset path is canonical and unique.
+    pub fn is_clean(&self) -> bool {
+        self.non_canonical.is_empty() && self.invalid.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+/// Scan all datasets for non-canonical, invalid, and duplicate paths.
+///
+/// This is read-only. Duplicate groups are proposals; merging tags, lineage,
+/// and usage is left to the operator.
+pub fn analyze_paths(conn: &Connection) -> Result<PathReport> {
+    let mut stmt = conn.prepare("SELECT name, path, last_updated FROM datasets ORDER BY id")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let mut report = PathReport::default();
+    // canonical path -> (name, stored path, last_updated)
+    let mut by_canonical: BTreeMap<String, Vec<(String, String, String)>> = BTreeMap::new();
+
+    for (name, path, last_updated) in rows {
+        match normalize_path(&path) {
+            Ok(canonical) => {
+                by_canonical
+                    .entry(canonical)
+                    .or_default()
+                    .push((name, path, last_updated))
+            }
+            Err(e) => report.invalid.push(InvalidPath {
+                name,
+                path,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    for (canonical_path, mut datasets) in by_canonical {
+        if datasets.len() == 1 {
+            let (name, path, _) = datasets.remove(0);
+            if path != canonical_path {
+                report.non_canonical.push(NonCanonicalPath {
+                    name,
+                    path,
+                    canonical_path,
+                });
+            }
+            continue;
+        }
+
+        // Canonical spelling first, then newest; sort is stable so ties keep id order
+        datasets.sort_by(|a, b| {
+            (b.1 == canonical_path)
+                .cmp(&(a.1 == canonical_path))
+                .then_with(|| b.2.cmp(&a.2))
+        });
+        let mut names = datasets.into_iter().map(|(name, _, _)| name);
+        let keep = names.next().unwrap_or_default();
+        report.duplicates.push(PathDuplicateGroup {
+            canonical_path,
+            keep,
+            merge: names.collect(),
+        });
+    }
+
+    Ok(report)
+}
+
+/// Rewrite the non-canonical paths listed in `report`.
+///
+/// Duplicate groups are not touched. Returns the number of datasets updated.
+pub fn apply_canonical_paths(conn: &Connection, report: &PathReport) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut updated = 0;
+    for entry in &report.non_canonical {
+        updated += tx.execute(
+            "UPDATE datasets SET path = ?1 WHERE name = ?2 AND path = ?3",
+            [&entry.canonical_path, &entry.name, &entry.path],
+        )?;
+    }
+    if updated > 0 {
+        crate::increment_catalog_version(&tx)?;
+    }
+    tx.commit()?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_object_store_paths() {
+        let cases = [
+            ("s3://bucket/data", "s3://bucket/data"),
+            ("s3:/bucket/data", "s3://bucket/data"),
+            ("s3:///bucket/data", "s3://bucket/data"),
+            ("S3://My-Bucket/Data/", "s3://my-bucket/Data"),
+            ("s3://bucket//a/./b//", "s3://bucket/a/b"),
+            ("gs://bucket", "gs://bucket"),
+            ("  gs://bucket/x  ", "gs://bucket/x"),
+            (
+                "abfss://Container@Account.dfs.core.windows.net/x",
+                "abfss://container@account.dfs.core.windows.net/x",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_path(input).unwrap(), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_normalize_local_paths() {
+        assert_eq!(normalize_path("/tmp//data/").unwrap(), "/tmp/data");
+        assert_eq!(normalize_path("data/orders/").unwrap(), "data/orders");
+        assert_eq!(normalize_path("/").unwrap(), "/");
+        assert_eq!(normalize_path("file:/tmp/x").unwrap(), "file:///tmp/x");
+        assert_eq!(normalize_path("file:///tmp/x/").unwrap(), "file:///tmp/x");
+    }
+
+    #[test]
+    fn test_normalize_is_idempotent() {
+        for input in ["S3:/Bucket//x/", "/tmp//a/", "file://tmp/x", "gs://b"] {
+            let once = normalize_path(input).unwrap();
+            assert_eq!(normalize_path(&once).unwrap(), once);
+        }
+    }
+
+    #[test]
+    fn test_normalize_rejects_invalid_paths() {
+        assert!(normalize_path("").is_err());
+        assert!(normalize_path("   ").is_err());
+        assert!(normalize_path("ftp://host/x").is_err());
+        assert!(normalize_path("s3://").is_err());
+        assert!(normalize_path("s3:///").is_err());
+        assert!(normalize_path("s3://bucket/../x").is_err());
+        assert!(normalize_path("/data/../etc").is_err());
+        assert!(normalize_path("s3://bucket/x\ny").is_err());
+        assert!(normalize_path(".").is_err());
+    }
+
+    #[test]
+    fn test_analyze_and_apply() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+
+        let datasets = [
+            ("orders", "s3://lake/orders", "2025-01-02"),
+            ("orders_old", "S3:/Lake/orders/", "2025-01-01"),
+            ("orders_copy", "s3://lake//orders", "2025-01-03"),
+            ("clicks", "gs://Web/clicks/", "2025-01-01"),
+            ("legacy", "ftp://host/x", "2025-01-01"),
+            ("events", "s3://lake/events", "2025-01-01"),
+        ];
+        for (name, path, updated) in datasets {
+            conn.execute(
+                "INSERT INTO datasets (name, path, format, created_at, last_updated)
+                 VALUES (?1, ?2, 'parquet', ?3, ?3)",
+                [name, path, updated],
+            )
+            .unwrap();
+        }
+
+        let report = analyze_paths(&conn).unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.duplicates,
+            vec![PathDuplicateGroup {
+                canonical_path: "s3://lake/orders".to_string(),
+                keep: "orders".to_string(),
+                merge: vec!["orders_copy".to_string(), "orders_old".to_string()],
+            }]
+        );
+        assert_eq!(
+            report.non_canonical,
+            vec![NonCanonicalPath {
+                name: "clicks".to_string(),
+                path: "gs://Web/clicks/".to_string(),
+                canonical_path: "gs://web/clicks".to_string(),
+            }]
+        );
+        assert_eq!(report.invalid.len(), 1);
+        assert_eq!(report.invalid[0].name, "legacy");
+
+        assert_eq!(apply_canonical_paths(&conn, &report).unwrap(), 1);
+        let path: String = conn
+            .query_row("SELECT path FROM datasets WHERE name = 'clicks'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(path, "gs://web/clicks");
+
+        let report = analyze_paths(&conn).unwrap();
+        assert!(report.non_canonical.is_empty());
+        assert_eq!(report.duplicates.len(), 1);
+    }
+}
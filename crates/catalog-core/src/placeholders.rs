@@ -0,0 +1,100 @@
+//! Placeholder datasets
+//!
+//! Lineage can reference a dataset before it is registered, e.g. when an
+//! orchestrator reports edges before the upstream job has emitted. A
+//! placeholder is a dataset row with status `pending`, an empty path, and
+//! format `unknown`. Registering the dataset fills it in and marks it
+//! `active`, so edges pointing at it survive.
+//!
+//! Requires migration v1.18.0 (`datasets.status`). On older catalogs
+//! [`activate`] is a no-op.
+
+use crate::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Status of a registered dataset
+pub const STATUS_ACTIVE: &str = "active";
+
+/// Status of a placeholder dataset
+pub const STATUS_PENDING: &str = "pending";
+
+/// Format stored for placeholders
+pub const PLACEHOLDER_FORMAT: &str = "unknown";
+
+/// Whether the catalog has the `datasets.status` column.
+pub fn has_status_column(conn: &Connection) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM pragma_table_info('datasets') WHERE name = 'status'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some())
+}
+
+/// Create a placeholder dataset and return its id.
+pub fn create_placeholder(conn: &Connection, name: &str) -> Result<i64> {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO datasets (name, path, format, status, created_at, last_updated)
+         VALUES (?1, '', ?2, ?3, ?4, ?4)",
+        params![name, PLACEHOLDER_FORMAT, STATUS_PENDING, now],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Whether a dataset is a placeholder.
+pub fn is_placeholder(conn: &Connection, dataset_id: i64) -> Result<bool> {
+    if !has_status_column(conn)? {
+        return Ok(false);
+    }
+    let status: Option<String> = conn
+        .query_row(
+            "SELECT status FROM datasets WHERE id = ?1",
+            [dataset_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(status.as_deref() == Some(STATUS_PENDING))
+}
+
+/// Mark a dataset registered. Returns true if it was a placeholder.
+pub fn activate(conn: &Connection, dataset_id: i64) -> Result<bool> {
+    if !has_status_column(conn)? {
+        return Ok(false);
+    }
+    let rows = conn.execute(
+        "UPDATE datasets SET status = ?1 WHERE id = ?2 AND status = ?3",
+        params![STATUS_ACTIVE, dataset_id, STATUS_PENDING],
+    )?;
+    Ok(rows > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_lifecycle() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+
+        let id = create_placeholder(&conn, "raw_events").unwrap();
+        assert!(is_placeholder(&conn, id).unwrap());
+
+        assert!(activate(&conn, id).unwrap());
+        assert!(!is_placeholder(&conn, id).unwrap());
+        // Already active
+        assert!(!activate(&conn, id).unwrap());
+    }
+
+    #[test]
+    fn test_activate_without_migration_is_noop() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        assert!(!has_status_column(&conn).unwrap());
+        assert!(!activate(&conn, 1).unwrap());
+    }
+}
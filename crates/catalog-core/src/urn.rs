@@ -0,0 +1,47 @@
+//! Dataset URNs
+//!
+//! A dataset URN identifies a dataset across systems:
+//! `urn:metafuse:dataset:<name>`. APIs that take a dataset reference accept
+//! either the plain name or its URN.
+
+use crate::{validation, CatalogError, Result};
+
+/// Prefix of every dataset URN
+pub const DATASET_URN_PREFIX: &str = "urn:metafuse:dataset:";
+
+/// URN for a dataset name.
+pub fn dataset_urn(name: &str) -> String {
+    format!("{}{}", DATASET_URN_PREFIX, name)
+}
+
+/// Resolve a dataset reference (name or URN) to a validated dataset name.
+pub fn resolve_dataset_ref(reference: &str) -> Result<String> {
+    let name = if reference.starts_with("urn:") {
+        reference.strip_prefix(DATASET_URN_PREFIX).ok_or_else(|| {
+            CatalogError::ValidationError(format!(
+                "Unsupported URN '{}': expected '{}<name>'",
+                reference, DATASET_URN_PREFIX
+            ))
+        })?
+    } else {
+        reference
+    };
+    validation::validate_dataset_name(name)?;
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_dataset_ref() {
+        assert_eq!(resolve_dataset_ref("orders").unwrap(), "orders");
+        assert_eq!(
+            resolve_dataset_ref(&dataset_urn("sales.orders")).unwrap(),
+            "sales.orders"
+        );
+        assert!(resolve_dataset_ref("urn:other:dataset:orders").is_err());
+        assert!(resolve_dataset_ref("urn:metafuse:dataset:").is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Write reports
+//!
+//! What a dataset write did with the upstreams and tags it was given.
+//! Emitters often repeat upstreams or tags, and unregistered upstreams are
+//! skipped in the `ignore` lineage mode; both used to go unnoticed. The
+//! report makes them visible, so pipeline authors can spot misconfigured
+//! jobs.
+//!
+//! Each upstream or tag given is reported once:
+//! - `inserted`: newly linked or added
+//! - `unchanged`: already stored before the write
+//! - `skipped`: not written, e.g. an unregistered upstream
+//!
+//! and additionally under `deduplicated` if it was given more than once.
+
+use crate::Result;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// What a write did with one kind of item
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemReport {
+    /// Newly written
+    pub inserted: Vec<String>,
+    /// Already stored before the write
+    pub unchanged: Vec<String>,
+    /// Given more than once; the repeats were dropped
+    pub deduplicated: Vec<String>,
+    /// Not written
+    pub skipped: Vec<String>,
+}
+
+impl ItemReport {
+    /// Record `item` as inserted, or unchanged if it was `already_stored`.
+    pub fn written(&mut self, item: &str, already_stored: bool) {
+        if already_stored {
+            self.unchanged.push(item.to_string());
+        } else {
+            self.inserted.push(item.to_string());
+        }
+    }
+}
+
+/// What a dataset write did with its upstreams and tags
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteReport {
+    pub upstreams: ItemReport,
+    pub tags: ItemReport,
+}
+
+impl WriteReport {
+    /// Whether anything given was deduplicated or skipped.
+    pub fn has_warnings(&self) -> bool {
+        [&self.upstreams, &self.tags]
+            .iter()
+            .any(|r| !r.deduplicated.is_empty() || !r.skipped.is_empty())
+    }
+}
+
+/// The distinct `items` in the order given, recording repeats in `report`.
+pub fn dedupe<'a>(items: &'a [String], report: &mut ItemReport) -> Vec<&'a String> {
+    let mut seen = HashSet::new();
+    let mut distinct = Vec::with_capacity(items.len());
+    for item in items {
+        if seen.insert(item) {
+            distinct.push(item);
+        } else if !report.deduplicated.contains(item) {
+            report.deduplicated.push(item.clone());
+        }
+    }
+    distinct
+}
+
+/// Ids of the datasets linked upstream of a dataset.
+pub fn linked_upstream_ids(conn: &Connection, dataset_id: i64) -> Result<HashSet<i64>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT upstream_dataset_id FROM lineage WHERE downstream_dataset_id = ?1",
+    )?;
+    let ids = stmt
+        .query_map([dataset_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(ids)
+}
+
+/// Tags stored on a dataset.
+pub fn stored_tags(conn: &Connection, dataset_id: i64) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare_cached("SELECT tag FROM tags WHERE dataset_id = ?1")?;
+    let tags = stmt
+        .query_map([dataset_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(tags)
+}
+
+/// Report for a write that was not needed because the dataset already has
+/// this metadata: upstreams that are linked and tags that are stored are
+/// unchanged, the rest skipped.
+pub fn report_stored(
+    conn: &Connection,
+    dataset_id: i64,
+    upstreams: &[String],
+    tags: &[String],
+) -> Result<WriteReport> {
+    let mut report = WriteReport::default();
+    let mut stmt = conn.prepare_cached(
+        "SELECT u.name FROM lineage l JOIN datasets u ON u.id = l.upstream_dataset_id
+         WHERE l.downstream_dataset_id = ?1",
+    )?;
+    let linked: HashSet<String> = stmt
+        .query_map([dataset_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for upstream in dedupe(upstreams, &mut report.upstreams) {
+        if linked.contains(upstream) {
+            report.upstreams.unchanged.push(upstream.clone());
+        } else {
+            report.upstreams.skipped.push(upstream.clone());
+        }
+    }
+
+    let stored = stored_tags(conn, dataset_id)?;
+    for tag in dedupe(tags, &mut report.tags) {
+        if stored.contains(tag) {
+            report.tags.unchanged.push(tag.clone());
+        } else {
+            report.tags.skipped.push(tag.clone());
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dedupe_keeps_first_occurrence() {
+        let mut report = ItemReport::default();
+        let items = strings(&["raw", "daily", "raw", "raw", "pii"]);
+        let distinct = dedupe(&items, &mut report);
+        assert_eq!(distinct, vec!["raw", "daily", "pii"]);
+        assert_eq!(report.deduplicated, vec!["raw"]);
+    }
+
+    #[test]
+    fn test_report_stored() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::init_sqlite_schema(&conn).unwrap();
+        crate::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('raw_orders', '/raw', 'parquet', datetime('now'), datetime('now')),
+                    ('orders', '/data', 'parquet', datetime('now'), datetime('now'));
+             INSERT INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at)
+             VALUES (1, 2, datetime('now'));
+             INSERT INTO tags (dataset_id, tag) VALUES (2, 'gold');",
+        )
+        .unwrap();
+
+        let report = report_stored(
+            &conn,
+            2,
+            &strings(&["raw_orders", "missing", "raw_orders"]),
+            &strings(&["gold"]),
+        )
+        .unwrap();
+        assert_eq!(report.upstreams.unchanged, vec!["raw_orders"]);
+        assert_eq!(report.upstreams.skipped, vec!["missing"]);
+        assert_eq!(report.upstreams.deduplicated, vec!["raw_orders"]);
+        assert_eq!(report.tags.unchanged, vec!["gold"]);
+        assert!(report.has_warnings());
+        assert_eq!(linked_upstream_ids(&conn, 2).unwrap(), HashSet::from([1]));
+    }
+}
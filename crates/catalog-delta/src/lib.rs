@@ -10,6 +10,7 @@
 //! - Schema, row counts, file statistics → Read live from Delta
 //! - Column-level statistics (min/max/null counts) → Aggregated from Delta file stats
 //! - Transaction history → Read from Delta log
+//! - Plain Parquet schemas → Read from a data file's footer
 //! - Caching → Optional LRU cache with configurable TTL
 //!
 //! # URL Formats
@@ -31,6 +32,12 @@
 //! This is acceptable for most use cases but can be periodically cleaned by
 //! calling `clear_cache()` if strict freshness is required.
 //!
+//! # Concurrency Limits
+//!
+//! Reads against storage run through a [`ReadLimiter`] that caps reads in
+//! flight overall and per bucket and retries transient errors with backoff
+//! (see [`limits`]). Cache hits bypass the limiter.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -52,11 +59,17 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
+pub mod limits;
+mod parquet;
+
+pub use limits::{ReadLimiter, ReadLimiterStats, ReadLimits, ReadObserver};
+
 /// Errors that can occur when reading Delta metadata.
 #[derive(Error, Debug)]
 pub enum DeltaError {
@@ -83,6 +96,12 @@ pub enum DeltaError {
 
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
+
+    #[error("Failed to read Parquet schema at '{0}': {1}")]
+    ParquetRead(String, String),
+
+    #[error("No Parquet files found at '{0}'")]
+    NoParquetFiles(String),
 }
 
 /// Result type for Delta operations.
@@ -102,6 +121,9 @@ pub struct Field {
     /// Additional metadata from Delta schema
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// 0-based position in the schema
+    #[serde(default)]
+    pub ordinal: usize,
 }
 
 /// Schema extracted from Delta table.
@@ -194,7 +216,7 @@ pub struct SchemaDiff {
     pub added_columns: Vec<Field>,
     /// Columns removed in the new version
     pub removed_columns: Vec<Field>,
-    /// Columns with type changes
+    /// Columns with type, nullability, or position changes
     pub modified_columns: Vec<FieldChange>,
 }
 
@@ -211,6 +233,12 @@ pub struct FieldChange {
     pub old_nullable: bool,
     /// New nullable flag
     pub new_nullable: bool,
+    /// Old position in the schema
+    #[serde(default)]
+    pub old_ordinal: usize,
+    /// New position in the schema
+    #[serde(default)]
+    pub new_ordinal: usize,
 }
 
 /// Cached Delta metadata with timestamp.
@@ -234,6 +262,11 @@ struct CachedDeltaMeta {
 /// **Note:** Cache TTL is checked lazily on access. Stale entries remain in
 /// the cache until accessed (and refreshed) or evicted by LRU pressure.
 ///
+/// # Concurrency Limits
+///
+/// Uncached reads are bounded by [`ReadLimits::default()`] unless other
+/// limits are set with [`DeltaReader::with_limits`].
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -252,6 +285,22 @@ struct CachedDeltaMeta {
 pub struct DeltaReader {
     cache: Arc<RwLock<LruCache<String, CachedDeltaMeta>>>,
     cache_ttl: Duration,
+    limiter: ReadLimiter,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+/// Point-in-time counters of the metadata cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeltaCacheStats {
+    /// Tables currently cached.
+    pub entries: usize,
+    /// Maximum number of cached tables.
+    pub capacity: usize,
+    /// Reads served from the cache since startup.
+    pub hits: u64,
+    /// Reads that went to storage since startup.
+    pub misses: u64,
 }
 
 impl DeltaReader {
@@ -266,6 +315,9 @@ impl DeltaReader {
         Self {
             cache: Arc::new(RwLock::new(LruCache::new(capacity))),
             cache_ttl,
+            limiter: ReadLimiter::new(ReadLimits::default()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
         }
     }
 
@@ -275,6 +327,37 @@ impl DeltaReader {
         Self {
             cache: Arc::new(RwLock::new(LruCache::new(capacity))),
             cache_ttl,
+            limiter: ReadLimiter::new(ReadLimits::default()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Replace the concurrency limits for reads against storage.
+    pub fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limiter = ReadLimiter::new(limits);
+        self
+    }
+
+    /// Report read limiter events to `observer`.
+    pub fn with_observer(mut self, observer: Arc<dyn ReadObserver>) -> Self {
+        self.limiter.set_observer(observer);
+        self
+    }
+
+    /// Current read limiter counters.
+    pub fn limiter_stats(&self) -> ReadLimiterStats {
+        self.limiter.stats()
+    }
+
+    /// Current metadata cache counters.
+    pub async fn cache_stats(&self) -> DeltaCacheStats {
+        let cache = self.cache.read().await;
+        DeltaCacheStats {
+            entries: cache.len(),
+            capacity: cache.cap().get(),
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
         }
     }
 
@@ -293,6 +376,7 @@ impl DeltaReader {
             if let Some(cached) = cache.peek(&normalized) {
                 if cached.cached_at.elapsed() < self.cache_ttl {
                     tracing::debug!(location = %location, "Delta metadata cache hit");
+                    self.cache_hits.fetch_add(1, Ordering::Relaxed);
                     return Ok(cached.metadata.clone());
                 }
             }
@@ -300,6 +384,7 @@ impl DeltaReader {
 
         // Cache miss - read from Delta
         tracing::debug!(location = %location, "Delta metadata cache miss, reading from Delta");
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
         let metadata = self.get_metadata_internal(&normalized).await?;
 
         // Update cache
@@ -386,16 +471,24 @@ impl DeltaReader {
         version: i64,
     ) -> Result<DeltaMetadata> {
         let normalized = Self::normalize_location(location)?;
-        let table = self
-            .open_table_at_version_from_url(&normalized, version)
-            .await?;
-        self.extract_metadata_from_table(&table).await
+        self.limiter
+            .run(&normalized, || async {
+                let table = self
+                    .open_table_at_version_from_url(&normalized, version)
+                    .await?;
+                self.extract_metadata_from_table(&table).await
+            })
+            .await
     }
 
     /// Internal metadata extraction from a normalized URL (latest version).
     async fn get_metadata_internal(&self, url_str: &str) -> Result<DeltaMetadata> {
-        let table = self.open_table_from_url(url_str).await?;
-        self.extract_metadata_from_table(&table).await
+        self.limiter
+            .run(url_str, || async {
+                let table = self.open_table_from_url(url_str).await?;
+                self.extract_metadata_from_table(&table).await
+            })
+            .await
     }
 
     /// Extract metadata from an opened Delta table.
@@ -465,12 +558,51 @@ impl DeltaReader {
     /// Get schema from a Delta table, optionally at a specific version.
     pub async fn get_schema(&self, location: &str, version: Option<i64>) -> Result<Schema> {
         let normalized = Self::normalize_location(location)?;
-        let table = match version {
-            Some(v) => self.open_table_at_version_from_url(&normalized, v).await?,
-            None => self.open_table_from_url(&normalized).await?,
-        };
+        self.limiter
+            .run(&normalized, || async {
+                let table = match version {
+                    Some(v) => self.open_table_at_version_from_url(&normalized, v).await?,
+                    None => self.open_table_from_url(&normalized).await?,
+                };
+                self.extract_schema(&table)
+            })
+            .await
+    }
+
+    /// Get the Arrow schema of a Delta table, optionally at a specific version.
+    ///
+    /// Unlike [`get_schema`](Self::get_schema), fields keep their Arrow types,
+    /// as DataFusion and the emitter use them.
+    pub async fn get_arrow_schema(
+        &self,
+        location: &str,
+        version: Option<i64>,
+    ) -> Result<deltalake::arrow::datatypes::SchemaRef> {
+        let normalized = Self::normalize_location(location)?;
+        self.limiter
+            .run(&normalized, || async {
+                let table = match version {
+                    Some(v) => self.open_table_at_version_from_url(&normalized, v).await?,
+                    None => self.open_table_from_url(&normalized).await?,
+                };
+                let snapshot = table.snapshot().map_err(|e| {
+                    DeltaError::SchemaRead(format!("Failed to get snapshot: {}", e))
+                })?;
+                Ok(snapshot.snapshot().arrow_schema())
+            })
+            .await
+    }
 
-        self.extract_schema(&table)
+    /// Get the schema of plain Parquet data (not a Delta table) from a
+    /// data file's footer.
+    ///
+    /// `location` is a `.parquet` file or a directory of them, possibly
+    /// partitioned; the first data file in path order is read.
+    pub async fn get_parquet_schema(&self, location: &str) -> Result<Schema> {
+        let normalized = Self::normalize_location(location)?;
+        self.limiter
+            .run(&normalized, || parquet::read_schema(&normalized))
+            .await
     }
 
     /// Get transaction history for a Delta table.
@@ -479,13 +611,17 @@ impl DeltaReader {
     /// when available, falling back to index-based calculation.
     pub async fn get_history(&self, location: &str, limit: usize) -> Result<Vec<DeltaVersion>> {
         let normalized = Self::normalize_location(location)?;
-        let table = self.open_table_from_url(&normalized).await?;
-        let current_version = table.version().unwrap_or(0);
-
-        let history = table
-            .history(Some(limit))
-            .await
-            .map_err(|e| DeltaError::HistoryRead(e.to_string()))?;
+        let (current_version, history) = self
+            .limiter
+            .run(&normalized, || async {
+                let table = self.open_table_from_url(&normalized).await?;
+                let history = table
+                    .history(Some(limit))
+                    .await
+                    .map_err(|e| DeltaError::HistoryRead(e.to_string()))?;
+                Ok((table.version().unwrap_or(0), history))
+            })
+            .await?;
 
         Ok(history
             .into_iter()
@@ -530,48 +666,8 @@ impl DeltaReader {
         let from_schema = self.get_schema(location, Some(from_version)).await?;
         let to_schema = self.get_schema(location, Some(to_version)).await?;
 
-        let from_fields: HashMap<_, _> = from_schema.fields.iter().map(|f| (&f.name, f)).collect();
-
-        let to_fields: HashMap<_, _> = to_schema.fields.iter().map(|f| (&f.name, f)).collect();
-
-        // Find added columns
-        let added_columns: Vec<Field> = to_schema
-            .fields
-            .iter()
-            .filter(|f| !from_fields.contains_key(&f.name))
-            .cloned()
-            .collect();
-
-        // Find removed columns
-        let removed_columns: Vec<Field> = from_schema
-            .fields
-            .iter()
-            .filter(|f| !to_fields.contains_key(&f.name))
-            .cloned()
-            .collect();
-
-        // Find modified columns (type or nullable changes)
-        let modified_columns: Vec<FieldChange> = from_schema
-            .fields
-            .iter()
-            .filter_map(|from_field| {
-                to_fields.get(&from_field.name).and_then(|to_field| {
-                    if from_field.data_type != to_field.data_type
-                        || from_field.nullable != to_field.nullable
-                    {
-                        Some(FieldChange {
-                            name: from_field.name.clone(),
-                            old_type: from_field.data_type.clone(),
-                            new_type: to_field.data_type.clone(),
-                            old_nullable: from_field.nullable,
-                            new_nullable: to_field.nullable,
-                        })
-                    } else {
-                        None
-                    }
-                })
-            })
-            .collect();
+        let (added_columns, removed_columns, modified_columns) =
+            diff_fields(&from_schema.fields, &to_schema.fields);
 
         Ok(SchemaDiff {
             from_version,
@@ -608,7 +704,8 @@ impl DeltaReader {
 
         let fields: Vec<Field> = delta_schema
             .fields()
-            .map(|f| {
+            .enumerate()
+            .map(|(ordinal, f)| {
                 let metadata: HashMap<String, String> = f
                     .metadata()
                     .iter()
@@ -621,6 +718,7 @@ impl DeltaReader {
                     nullable: f.is_nullable(),
                     description: metadata.get("comment").cloned(),
                     metadata,
+                    ordinal,
                 }
             })
             .collect();
@@ -774,6 +872,66 @@ impl AggregatedStats {
     }
 }
 
+/// Compare two field lists by name.
+///
+/// A column shared by both versions is modified when its type or nullability
+/// changed, or when it moved relative to the other shared columns. Shifts
+/// caused only by added or removed columns are not reported.
+fn diff_fields(from: &[Field], to: &[Field]) -> (Vec<Field>, Vec<Field>, Vec<FieldChange>) {
+    let from_fields: HashMap<_, _> = from.iter().map(|f| (&f.name, f)).collect();
+    let to_fields: HashMap<_, _> = to.iter().map(|f| (&f.name, f)).collect();
+
+    // Columns added in the new version
+    let added: Vec<Field> = to
+        .iter()
+        .filter(|f| !from_fields.contains_key(&f.name))
+        .cloned()
+        .collect();
+
+    // Columns removed in the new version
+    let removed: Vec<Field> = from
+        .iter()
+        .filter(|f| !to_fields.contains_key(&f.name))
+        .cloned()
+        .collect();
+
+    // Relative order of the shared columns in the new version
+    let shared_positions: HashMap<_, _> = to
+        .iter()
+        .filter(|f| from_fields.contains_key(&f.name))
+        .enumerate()
+        .map(|(position, f)| (&f.name, position))
+        .collect();
+
+    let modified: Vec<FieldChange> = from
+        .iter()
+        .filter(|f| to_fields.contains_key(&f.name))
+        .enumerate()
+        .filter_map(|(position, from_field)| {
+            let to_field = to_fields[&from_field.name];
+            let moved = shared_positions[&from_field.name] != position;
+            if from_field.data_type != to_field.data_type
+                || from_field.nullable != to_field.nullable
+                || moved
+            {
+                Some(FieldChange {
+                    name: from_field.name.clone(),
+                    old_type: from_field.data_type.clone(),
+                    new_type: to_field.data_type.clone(),
+                    old_nullable: from_field.nullable,
+                    new_nullable: to_field.nullable,
+                    old_ordinal: from_field.ordinal,
+                    new_ordinal: to_field.ordinal,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    (added, removed, modified)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -798,6 +956,7 @@ mod tests {
             nullable: false,
             description: None,
             metadata: HashMap::new(),
+            ordinal: 0,
         };
 
         let field2 = Field {
@@ -806,11 +965,51 @@ mod tests {
             nullable: false,
             description: None,
             metadata: HashMap::new(),
+            ordinal: 0,
         };
 
         assert_eq!(field1, field2);
     }
 
+    #[test]
+    fn test_diff_fields_reports_moves_not_shifts() {
+        let fields = |names: &[&str]| -> Vec<Field> {
+            names
+                .iter()
+                .enumerate()
+                .map(|(ordinal, name)| Field {
+                    name: name.to_string(),
+                    data_type: "Int32".to_string(),
+                    nullable: true,
+                    description: None,
+                    metadata: HashMap::new(),
+                    ordinal,
+                })
+                .collect()
+        };
+
+        // Inserting a column shifts later ones without reporting them
+        let (added, removed, modified) = diff_fields(
+            &fields(&["id", "amount"]),
+            &fields(&["id", "region", "amount"]),
+        );
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].ordinal, 1);
+        assert!(removed.is_empty());
+        assert!(modified.is_empty());
+
+        // Swapping two columns reports both with their positions
+        let (_, _, modified) = diff_fields(
+            &fields(&["id", "amount", "region"]),
+            &fields(&["id", "region", "amount"]),
+        );
+        let moves: Vec<(&str, usize, usize)> = modified
+            .iter()
+            .map(|c| (c.name.as_str(), c.old_ordinal, c.new_ordinal))
+            .collect();
+        assert_eq!(moves, vec![("amount", 1, 2), ("region", 2, 1)]);
+    }
+
     #[test]
     fn test_file_stats_deserialize() {
         let json = r#"{
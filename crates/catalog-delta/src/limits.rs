@@ -0,0 +1,417 @@
+//! Concurrency limits for Delta reads.
+//!
+//! Every read a [`DeltaReader`](crate::DeltaReader) makes against storage runs
+//! through a [`ReadLimiter`], which bounds how many reads run at once overall
+//! and per bucket, and retries transient storage errors with exponential
+//! backoff. Permits are released while backing off, so a throttled bucket
+//! does not hold slots other buckets could use.
+//!
+//! # Configuration
+//!
+//! - `METAFUSE_DELTA_MAX_CONCURRENT_READS`: reads in flight overall (default: 32, 0 = unlimited)
+//! - `METAFUSE_DELTA_MAX_READS_PER_BUCKET`: reads in flight per bucket (default: 8, 0 = unlimited)
+//! - `METAFUSE_DELTA_READ_RETRIES`: retries of a transient error (default: 3)
+//! - `METAFUSE_DELTA_RETRY_BACKOFF_MS`: first retry delay, doubled per retry up to 10s (default: 200)
+
+use crate::{DeltaError, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Longest delay between retries.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Error messages that mark a storage error as worth retrying.
+const TRANSIENT_MARKERS: &[&str] = &[
+    "timeout",
+    "timed out",
+    "connection",
+    "temporarily unavailable",
+    "too many requests",
+    "slow down",
+    "slowdown",
+    "throttl",
+    "rate limit",
+    "429",
+    "500 internal",
+    "502",
+    "503",
+    "504",
+];
+
+/// Limits for Delta reads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadLimits {
+    /// Reads in flight across all buckets (0 = unlimited).
+    pub max_concurrent: usize,
+    /// Reads in flight against a single bucket (0 = unlimited).
+    pub max_per_bucket: usize,
+    /// Retries of a read that failed with a transient error.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    pub initial_backoff: Duration,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 32,
+            max_per_bucket: 8,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+impl ReadLimits {
+    /// Create limits from environment variables, falling back to the defaults.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_concurrent: var("METAFUSE_DELTA_MAX_CONCURRENT_READS")
+                .unwrap_or(defaults.max_concurrent),
+            max_per_bucket: var("METAFUSE_DELTA_MAX_READS_PER_BUCKET")
+                .unwrap_or(defaults.max_per_bucket),
+            max_retries: var("METAFUSE_DELTA_READ_RETRIES").unwrap_or(defaults.max_retries),
+            initial_backoff: var("METAFUSE_DELTA_RETRY_BACKOFF_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.initial_backoff),
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 1).
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(MAX_BACKOFF)
+    }
+}
+
+/// Receives events from the read limiter, e.g. to export metrics.
+///
+/// All methods default to doing nothing.
+pub trait ReadObserver: Send + Sync {
+    /// A read waited `wait` for its permits.
+    fn on_permit_wait(&self, _bucket: &str, _wait: Duration) {}
+
+    /// A read failed with a transient error and is retried.
+    fn on_retry(&self, _bucket: &str) {}
+
+    /// A read finished, after any retries.
+    fn on_read(&self, _bucket: &str, _success: bool, _duration: Duration) {}
+}
+
+/// Point-in-time counters of a read limiter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadLimiterStats {
+    /// Reads holding permits.
+    pub in_flight: usize,
+    /// Reads waiting for permits.
+    pub waiting: usize,
+    /// Retries since startup.
+    pub retries: u64,
+    /// Reads that failed after all retries since startup.
+    pub failures: u64,
+}
+
+/// Bounds and retries reads against Delta storage.
+pub struct ReadLimiter {
+    limits: ReadLimits,
+    global: Option<Arc<Semaphore>>,
+    buckets: Mutex<HashMap<String, Arc<Semaphore>>>,
+    observer: Option<Arc<dyn ReadObserver>>,
+    in_flight: AtomicUsize,
+    waiting: AtomicUsize,
+    retries: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl ReadLimiter {
+    /// Create a limiter enforcing `limits`.
+    pub fn new(limits: ReadLimits) -> Self {
+        Self {
+            global: (limits.max_concurrent > 0)
+                .then(|| Arc::new(Semaphore::new(limits.max_concurrent))),
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            observer: None,
+            in_flight: AtomicUsize::new(0),
+            waiting: AtomicUsize::new(0),
+            retries: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Report events to `observer`.
+    pub fn set_observer(&mut self, observer: Arc<dyn ReadObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// The limits this limiter enforces.
+    pub fn limits(&self) -> &ReadLimits {
+        &self.limits
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> ReadLimiterStats {
+        ReadLimiterStats {
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            waiting: self.waiting.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Run `read` against the table at `url` within the limits, retrying
+    /// transient errors.
+    pub async fn run<T, F, Fut>(&self, url: &str, mut read: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let bucket = bucket_key(url);
+        let started = Instant::now();
+        let mut retry = 0;
+
+        let result = loop {
+            let permits = self.acquire(&bucket).await;
+            let in_flight = Counted::new(&self.in_flight);
+            let result = read().await;
+            drop(in_flight);
+            drop(permits);
+
+            match result {
+                Err(e) if retry < self.limits.max_retries && e.is_transient() => {
+                    retry += 1;
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    if let Some(observer) = &self.observer {
+                        observer.on_retry(&bucket);
+                    }
+                    let delay = self.limits.backoff(retry);
+                    tracing::warn!(
+                        bucket = %bucket,
+                        retry,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %e,
+                        "Transient Delta read error, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => break result,
+            }
+        };
+
+        if result.is_err() {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(observer) = &self.observer {
+            observer.on_read(&bucket, result.is_ok(), started.elapsed());
+        }
+        result
+    }
+
+    /// Acquire the bucket permit, then the global one, so a read queued
+    /// behind a busy bucket does not hold a global slot.
+    async fn acquire(&self, bucket: &str) -> Vec<OwnedSemaphorePermit> {
+        let semaphores: Vec<Arc<Semaphore>> = self
+            .bucket_semaphore(bucket)
+            .into_iter()
+            .chain(self.global.clone())
+            .collect();
+
+        let started = Instant::now();
+        let waiting = Counted::new(&self.waiting);
+        let mut permits = Vec::with_capacity(semaphores.len());
+        for semaphore in semaphores {
+            // Semaphores are never closed
+            if let Ok(permit) = semaphore.acquire_owned().await {
+                permits.push(permit);
+            }
+        }
+        drop(waiting);
+
+        if let Some(observer) = &self.observer {
+            observer.on_permit_wait(bucket, started.elapsed());
+        }
+        permits
+    }
+
+    fn bucket_semaphore(&self, bucket: &str) -> Option<Arc<Semaphore>> {
+        if self.limits.max_per_bucket == 0 {
+            return None;
+        }
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        Some(Arc::clone(
+            buckets
+                .entry(bucket.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.limits.max_per_bucket))),
+        ))
+    }
+}
+
+/// Counts itself in a gauge while alive, so reads dropped mid-flight are
+/// not counted forever.
+struct Counted<'a>(&'a AtomicUsize);
+
+impl<'a> Counted<'a> {
+    fn new(gauge: &'a AtomicUsize) -> Self {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Self(gauge)
+    }
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl DeltaError {
+    /// Whether the error looks like a transient storage failure (throttling,
+    /// timeouts, dropped connections) that may succeed on retry.
+    pub fn is_transient(&self) -> bool {
+        let message = match self {
+            DeltaError::OpenTable(_, message)
+            | DeltaError::SchemaRead(message)
+            | DeltaError::StatsRead(message)
+            | DeltaError::HistoryRead(message)
+            | DeltaError::ParquetRead(_, message) => message.to_lowercase(),
+            _ => return false,
+        };
+        TRANSIENT_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+    }
+}
+
+/// The bucket a table URL reads from: its scheme and host, e.g. `s3://bucket`.
+/// Local tables share the `file://` bucket.
+pub fn bucket_key(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or("")),
+        Err(_) => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transient() -> DeltaError {
+        DeltaError::OpenTable("s3://b/t".to_string(), "503 Slow Down".to_string())
+    }
+
+    fn limits(max_concurrent: usize, max_per_bucket: usize) -> ReadLimits {
+        ReadLimits {
+            max_concurrent,
+            max_per_bucket,
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_bucket_key() {
+        assert_eq!(bucket_key("s3://lake/sales/orders"), "s3://lake");
+        assert_eq!(bucket_key("gs://lake/orders/"), "gs://lake");
+        assert_eq!(bucket_key("file:///data/orders"), "file://");
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(transient().is_transient());
+        assert!(DeltaError::StatsRead("connection reset by peer".to_string()).is_transient());
+        assert!(
+            !DeltaError::OpenTable("s3://b/t".to_string(), "Not a Delta table".to_string())
+                .is_transient()
+        );
+        assert!(!DeltaError::NotFound("s3://b/t".to_string()).is_transient());
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let limits = ReadLimits {
+            initial_backoff: Duration::from_millis(200),
+            ..ReadLimits::default()
+        };
+        assert_eq!(limits.backoff(1), Duration::from_millis(200));
+        assert_eq!(limits.backoff(3), Duration::from_millis(800));
+        assert_eq!(limits.backoff(20), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let limiter = ReadLimiter::new(limits(4, 2));
+        let mut attempts = 0;
+        let result = limiter
+            .run("s3://lake/t", || {
+                attempts += 1;
+                let attempt = attempts;
+                async move {
+                    if attempt < 3 {
+                        Err(transient())
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(limiter.stats().retries, 2);
+        assert_eq!(limiter.stats().failures, 0);
+
+        let mut attempts = 0;
+        let result: Result<()> = limiter
+            .run("s3://lake/t", || {
+                attempts += 1;
+                async { Err(DeltaError::NoSchema) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+        assert_eq!(limiter.stats().failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_limits_reads_per_bucket() {
+        let limiter = Arc::new(ReadLimiter::new(limits(0, 2)));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let reads = (0..8).map(|i| {
+            let limiter = Arc::clone(&limiter);
+            let active = Arc::clone(&active);
+            let peak = Arc::clone(&peak);
+            tokio::spawn(async move {
+                let url = if i % 2 == 0 { "s3://a/t" } else { "s3://b/t" };
+                limiter
+                    .run(url, || {
+                        let active = Arc::clone(&active);
+                        let peak = Arc::clone(&peak);
+                        async move {
+                            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                            peak.fetch_max(now, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(10)).await;
+                            active.fetch_sub(1, Ordering::SeqCst);
+                            Ok(())
+                        }
+                    })
+                    .await
+            })
+        });
+        for read in reads.collect::<Vec<_>>() {
+            read.await.unwrap().unwrap();
+        }
+
+        // Two buckets with two slots each
+        assert!(peak.load(Ordering::SeqCst) <= 4);
+        assert_eq!(limiter.stats().in_flight, 0);
+    }
+}
@@ -0,0 +1,195 @@
+//! Schemas from Parquet footers.
+//!
+//! Datasets imported from Glue or registered by path can be plain Parquet
+//! rather than Delta. Their schema is read from the footer of one data file:
+//! the file itself when the location names a `.parquet` file, otherwise the
+//! first Parquet file under the location in path order. Partition
+//! directories (`date=2026-01-01/`) are walked into and reported as partition
+//! columns; hidden and metadata entries (`_delta_log`, `.tmp`) are skipped.
+
+use crate::{DeltaError, Field, Result, Schema};
+use deltalake::parquet::arrow::async_reader::ParquetObjectReader;
+use deltalake::parquet::arrow::ParquetRecordBatchStreamBuilder;
+use deltalake::{ObjectStore, Path};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use url::Url;
+
+/// Most directories listed while looking for a data file
+const MAX_LISTINGS: usize = 64;
+
+fn read_error(url: &str, e: impl std::fmt::Display) -> DeltaError {
+    DeltaError::ParquetRead(url.to_string(), e.to_string())
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('_') || name.starts_with('.')
+}
+
+/// First Parquet file under the store root, in path order.
+async fn find_data_file(store: &dyn ObjectStore, url: &str) -> Result<Path> {
+    let mut dirs = VecDeque::from([None::<Path>]);
+    let mut listings = 0;
+    while let Some(dir) = dirs.pop_front() {
+        if listings == MAX_LISTINGS {
+            break;
+        }
+        listings += 1;
+        let listing = store
+            .list_with_delimiter(dir.as_ref())
+            .await
+            .map_err(|e| read_error(url, e))?;
+
+        let mut files: Vec<Path> = listing
+            .objects
+            .into_iter()
+            .map(|o| o.location)
+            .filter(|p| {
+                p.filename()
+                    .is_some_and(|name| !is_hidden(name) && name.ends_with(".parquet"))
+            })
+            .collect();
+        files.sort();
+        if let Some(file) = files.into_iter().next() {
+            return Ok(file);
+        }
+
+        let mut subdirs: Vec<Path> = listing
+            .common_prefixes
+            .into_iter()
+            .filter(|p| p.filename().is_some_and(|name| !is_hidden(name)))
+            .collect();
+        subdirs.sort();
+        dirs.extend(subdirs.into_iter().map(Some));
+    }
+    Err(DeltaError::NoParquetFiles(url.to_string()))
+}
+
+/// Hive-style partition columns in a data file's path.
+fn partition_columns(path: &Path) -> Vec<String> {
+    let parts: Vec<_> = path.parts().collect();
+    parts[..parts.len().saturating_sub(1)]
+        .iter()
+        .filter_map(|part| part.as_ref().split_once('=').map(|(k, _)| k.to_string()))
+        .collect()
+}
+
+/// Read the schema of the Parquet data at a normalized location URL.
+pub(crate) async fn read_schema(url_str: &str) -> Result<Schema> {
+    let url =
+        Url::parse(url_str).map_err(|e| DeltaError::InvalidUrl(format!("{}: {}", url_str, e)))?;
+
+    // A single file is read from a store rooted at its directory
+    let (root, file) = match url.path_segments().and_then(|mut s| s.next_back()) {
+        Some(name) if name.ends_with(".parquet") => {
+            let root = url
+                .join(".")
+                .map_err(|e| DeltaError::InvalidUrl(format!("{}: {}", url_str, e)))?;
+            (root, Some(Path::from(name)))
+        }
+        _ => (url.clone(), None),
+    };
+    let store: Arc<dyn ObjectStore> =
+        deltalake::logstore::store_for(&root, std::iter::empty::<(String, String)>())
+            .map_err(|e| read_error(url_str, e))?;
+
+    let file = match file {
+        Some(file) => file,
+        None => find_data_file(store.as_ref(), url_str).await?,
+    };
+    let partition_columns = partition_columns(&file);
+
+    let meta = store.head(&file).await.map_err(|e| match e {
+        deltalake::ObjectStoreError::NotFound { .. } => DeltaError::NotFound(url_str.to_string()),
+        e => read_error(url_str, e),
+    })?;
+    let reader = ParquetObjectReader::new(store, file).with_file_size(meta.size);
+    let builder = ParquetRecordBatchStreamBuilder::new(reader)
+        .await
+        .map_err(|e| read_error(url_str, e))?;
+
+    let fields = builder
+        .schema()
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(ordinal, f)| {
+            let metadata: HashMap<String, String> = f.metadata().clone().into_iter().collect();
+            Field {
+                name: f.name().to_string(),
+                data_type: format!("{:?}", f.data_type()),
+                nullable: f.is_nullable(),
+                description: metadata.get("comment").cloned(),
+                metadata,
+                ordinal,
+            }
+        })
+        .collect();
+
+    Ok(Schema {
+        fields,
+        partition_columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::arrow::array::{Int64Array, StringArray};
+    use deltalake::arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
+    use deltalake::arrow::record_batch::RecordBatch;
+    use deltalake::parquet::arrow::ArrowWriter;
+
+    fn write_parquet(path: &std::path::Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("id", DataType::Int64, false),
+            ArrowField::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(StringArray::from(vec![Some("a")])),
+            ],
+        )
+        .unwrap();
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_schema_from_partitioned_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("_temporary")).unwrap();
+        write_parquet(&dir.path().join("date=2026-01-02/part-0.parquet"));
+        write_parquet(&dir.path().join("date=2026-01-01/part-0.parquet"));
+
+        let url = format!("file://{}", dir.path().display());
+        let schema = read_schema(&url).await.unwrap();
+        let names: Vec<_> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name"]);
+        assert_eq!(schema.fields[0].data_type, "Int64");
+        assert!(!schema.fields[0].nullable);
+        assert_eq!(schema.fields[1].ordinal, 1);
+        assert_eq!(schema.partition_columns, vec!["date"]);
+
+        let file = format!("{}/date=2026-01-01/part-0.parquet", url);
+        let schema = read_schema(&file).await.unwrap();
+        assert_eq!(schema.fields.len(), 2);
+        assert!(schema.partition_columns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_parquet_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("data.csv"), "id\n1\n").unwrap();
+        let url = format!("file://{}", dir.path().display());
+        assert!(matches!(
+            read_schema(&url).await,
+            Err(DeltaError::NoParquetFiles(_))
+        ));
+    }
+}
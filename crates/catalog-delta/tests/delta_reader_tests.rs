@@ -103,6 +103,24 @@ async fn test_read_partitioned_schema() {
     assert!(schema.partition_columns.contains(&"date".to_string()));
 }
 
+#[tokio::test]
+async fn test_read_arrow_schema() {
+    let temp_dir = TempDir::new().unwrap();
+    let table_path = format!("file://{}", temp_dir.path().to_str().unwrap());
+    create_partitioned_delta_table(&table_path).await.unwrap();
+
+    let reader = DeltaReader::new(Duration::ZERO);
+    let schema = reader.get_arrow_schema(&table_path, Some(0)).await.unwrap();
+
+    let id = schema.field_with_name("id").unwrap();
+    assert_eq!(
+        id.data_type(),
+        &deltalake::arrow::datatypes::DataType::Int32
+    );
+    assert!(!id.is_nullable());
+    assert_eq!(schema.fields().len(), 4);
+}
+
 #[tokio::test]
 async fn test_get_metadata_empty_table() {
     let temp_dir = TempDir::new().unwrap();
@@ -150,6 +168,9 @@ async fn test_cache_functionality() {
     // Third read (cache miss after invalidation)
     let meta3 = reader.get_metadata_cached(&table_path).await.unwrap();
     assert_eq!(meta1.version, meta3.version);
+
+    let stats = reader.cache_stats().await;
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 1));
 }
 
 #[tokio::test]
@@ -0,0 +1,485 @@
+//! Parquet Crawler
+//!
+//! [`Emitter::crawl_path`] walks a local or object store prefix and registers
+//! every Parquet dataset under it, for onboarding an existing lake without
+//! registering each path by hand:
+//!
+//! ```ignore
+//! let report = emitter
+//!     .crawl_path("s3://lake/warehouse", &CrawlOptions::default())
+//!     .await?;
+//! println!("Registered {} datasets", report.datasets.len());
+//! ```
+//!
+//! # Dataset Discovery
+//!
+//! - A directory holding `.parquet` files is one dataset; Hive-style
+//!   `key=value` directories below it are its partitions, and their keys
+//!   become the dataset's partition keys (added to the schema as strings when
+//!   the files don't carry them).
+//! - Datasets are named after their path below the prefix, with `/` replaced
+//!   by `_` (`sales/orders` → `sales_orders`). Files directly in the prefix
+//!   are named after the prefix's last segment.
+//! - Files and directories starting with `_` or `.` (`_SUCCESS`, `.crc`) are
+//!   ignored.
+//! - Delta tables (a `_delta_log` directory) and Iceberg tables (`metadata`
+//!   and `data` directories) are skipped and reported: register them with
+//!   `emit_delta_table` or `emit_iceberg_table`, which read their logs.
+//!
+//! The schema is read from the footer of the most recently modified file.
+//! Row counts are summed from every file's footer and sizes from the object
+//! listing; no data pages are read. A dataset whose footers can't be read,
+//! or whose emission fails, is reported as skipped and the crawl continues.
+//!
+//! # URL Formats
+//!
+//! `file:///path`, absolute local paths, `s3://bucket/prefix` (`s3`
+//! feature), and `gs://bucket/prefix` (`gcs` feature). Cloud credentials are
+//! read from the usual `AWS_*` and `GOOGLE_*` environment variables.
+
+use crate::Emitter;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::parquet::arrow::async_reader::{
+    ParquetObjectReader, ParquetRecordBatchStreamBuilder,
+};
+use metafuse_catalog_core::{CatalogError, OperationalMeta, Result};
+use metafuse_catalog_storage::CatalogBackend;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use url::Url;
+
+/// Options for [`Emitter::crawl_path`]
+#[derive(Debug, Clone, Default)]
+pub struct CrawlOptions {
+    /// Prepended to every derived dataset name, e.g. `lake_`
+    pub name_prefix: Option<String>,
+    /// Domain of every registered dataset
+    pub domain: Option<String>,
+    /// Owner of every registered dataset
+    pub owner: Option<String>,
+    /// Tags of every registered dataset
+    pub tags: Vec<String>,
+    /// Discover and read footers, but register nothing
+    pub dry_run: bool,
+}
+
+/// A dataset found by a crawl
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrawledDataset {
+    pub name: String,
+    /// Dataset root, under the crawled prefix
+    pub location: String,
+    /// Number of Parquet files
+    pub files: usize,
+    pub row_count: i64,
+    pub size_bytes: i64,
+    pub partition_keys: Vec<String>,
+}
+
+/// A location the crawl did not register
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedPath {
+    pub location: String,
+    pub reason: String,
+}
+
+/// Result of [`Emitter::crawl_path`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrawlReport {
+    /// Datasets registered (or, for a dry run, that would be)
+    pub datasets: Vec<CrawledDataset>,
+    pub skipped: Vec<SkippedPath>,
+}
+
+/// Parquet files sharing a dataset root
+#[derive(Debug, Default)]
+struct Candidate {
+    files: Vec<ObjectMeta>,
+    partition_keys: Vec<String>,
+}
+
+/// Whether a file or directory name is ignored (`_SUCCESS`, `.crc`, ...)
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('_') || name.starts_with('.')
+}
+
+/// Dataset root of a file's directory segments, and its partition keys: the
+/// root ends before the first `key=value` segment.
+fn split_partitions(dirs: &[&str]) -> (String, Vec<String>) {
+    let root_len = dirs
+        .iter()
+        .position(|s| s.contains('='))
+        .unwrap_or(dirs.len());
+    let keys = dirs[root_len..]
+        .iter()
+        .filter_map(|s| s.split_once('=').map(|(key, _)| key.to_string()))
+        .collect();
+    (dirs[..root_len].join("/"), keys)
+}
+
+/// Dataset name for a root below the prefix
+fn dataset_name(prefix: &str, root: &str, options: &CrawlOptions) -> String {
+    let base = if root.is_empty() {
+        prefix.rsplit('/').find(|s| !s.is_empty()).unwrap_or("root")
+    } else {
+        root
+    };
+    let base: String = base
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!(
+        "{}{}",
+        options.name_prefix.as_deref().unwrap_or_default(),
+        base
+    )
+}
+
+impl<B: CatalogBackend> Emitter<B> {
+    /// Register every Parquet dataset under a local or object store prefix
+    ///
+    /// Fails only if the prefix can't be listed; datasets that can't be read
+    /// or emitted are reported in [`CrawlReport::skipped`].
+    pub async fn crawl_path(&self, prefix: &str, options: &CrawlOptions) -> Result<CrawlReport> {
+        let list_error = |e: object_store::Error| {
+            CatalogError::Other(format!("Failed to list '{}': {}", prefix, e))
+        };
+        let base = prefix.trim_end_matches('/');
+        let url = if base.contains("://") {
+            base.to_string()
+        } else if std::path::Path::new(base).is_absolute() {
+            format!("file://{}", base)
+        } else {
+            return Err(CatalogError::ValidationError(format!(
+                "Crawl prefix must be a URL (file://, s3://, gs://) or an absolute path: {}",
+                prefix
+            )));
+        };
+        let url = Url::parse(&url).map_err(|e| {
+            CatalogError::ValidationError(format!("Invalid URL '{}': {}", prefix, e))
+        })?;
+        let options_env = std::env::vars()
+            .filter(|(k, _)| k.starts_with("AWS_") || k.starts_with("GOOGLE_"))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v));
+        let (store, root) = object_store::parse_url_opts(&url, options_env).map_err(|e| {
+            CatalogError::ValidationError(format!("Invalid URL '{}': {}", prefix, e))
+        })?;
+        let store: Arc<dyn ObjectStore> = Arc::from(store);
+
+        let mut report = CrawlReport::default();
+        let mut candidates: BTreeMap<String, Candidate> = BTreeMap::new();
+        let location = |rel: &str| {
+            if rel.is_empty() {
+                base.to_string()
+            } else {
+                format!("{}/{}", base, rel)
+            }
+        };
+        let relative = |path: &Path| -> Vec<String> {
+            path.prefix_match(&root)
+                .map(|parts| parts.map(|p| p.as_ref().to_string()).collect())
+                .unwrap_or_default()
+        };
+
+        let mut pending = vec![root.clone()];
+        while let Some(dir) = pending.pop() {
+            let listing = store
+                .list_with_delimiter(Some(&dir))
+                .await
+                .map_err(list_error)?;
+            let subdir = |p: &Path| p.filename().unwrap_or_default().to_string();
+            let subdirs: Vec<String> = listing.common_prefixes.iter().map(subdir).collect();
+            let rel = relative(&dir).join("/");
+
+            // Tables with their own logs are registered from those logs
+            let table_format = if subdirs.iter().any(|d| d == "_delta_log") {
+                Some("Delta")
+            } else if subdirs.iter().any(|d| d == "metadata") && subdirs.iter().any(|d| d == "data")
+            {
+                Some("Iceberg")
+            } else {
+                None
+            };
+            if let Some(format) = table_format {
+                report.skipped.push(SkippedPath {
+                    location: location(&rel),
+                    reason: format!("{} table; register it from its metadata", format),
+                });
+                continue;
+            }
+
+            for object in listing.objects {
+                let name = object.location.filename().unwrap_or_default();
+                if is_hidden(name) || !name.ends_with(".parquet") {
+                    continue;
+                }
+                let parts = relative(&object.location);
+                let dirs: Vec<&str> = parts[..parts.len().saturating_sub(1)]
+                    .iter()
+                    .map(String::as_str)
+                    .collect();
+                let (dataset_root, keys) = split_partitions(&dirs);
+                let candidate = candidates.entry(dataset_root).or_default();
+                for key in keys {
+                    if !candidate.partition_keys.contains(&key) {
+                        candidate.partition_keys.push(key);
+                    }
+                }
+                candidate.files.push(object);
+            }
+            pending.extend(
+                listing
+                    .common_prefixes
+                    .into_iter()
+                    .filter(|p| !is_hidden(p.filename().unwrap_or_default())),
+            );
+        }
+
+        for (rel, candidate) in candidates {
+            let location = location(&rel);
+            let name = dataset_name(base, &rel, options);
+            let (schema, row_count) = match read_footers(&store, &candidate).await {
+                Ok(read) => read,
+                Err(e) => {
+                    report.skipped.push(SkippedPath {
+                        location,
+                        reason: format!("Failed to read Parquet footer: {}", e),
+                    });
+                    continue;
+                }
+            };
+            let dataset = CrawledDataset {
+                name,
+                location,
+                files: candidate.files.len(),
+                row_count,
+                size_bytes: candidate.files.iter().map(|f| f.size as i64).sum(),
+                partition_keys: candidate.partition_keys,
+            };
+            tracing::debug!(
+                dataset = %dataset.name,
+                location = %dataset.location,
+                files = dataset.files,
+                row_count = dataset.row_count,
+                "Crawled Parquet dataset"
+            );
+
+            if !options.dry_run {
+                let emitted = self
+                    .emit_dataset(
+                        &dataset.name,
+                        &dataset.location,
+                        "parquet",
+                        None,
+                        None,
+                        options.domain.as_deref(),
+                        options.owner.as_deref(),
+                        with_partition_columns(schema, &dataset.partition_keys),
+                        Some(OperationalMeta {
+                            row_count: Some(dataset.row_count),
+                            size_bytes: Some(dataset.size_bytes),
+                            partition_keys: dataset.partition_keys.clone(),
+                        }),
+                        vec![],
+                        options.tags.clone(),
+                    )
+                    .await;
+                if let Err(e) = emitted {
+                    report.skipped.push(SkippedPath {
+                        location: dataset.location,
+                        reason: format!("Failed to register '{}': {}", dataset.name, e),
+                    });
+                    continue;
+                }
+            }
+            report.datasets.push(dataset);
+        }
+
+        tracing::info!(
+            prefix = %prefix,
+            datasets = report.datasets.len(),
+            skipped = report.skipped.len(),
+            dry_run = options.dry_run,
+            "Crawled Parquet datasets"
+        );
+        Ok(report)
+    }
+}
+
+/// Schema of the newest file and the total row count of all files
+async fn read_footers(
+    store: &Arc<dyn ObjectStore>,
+    candidate: &Candidate,
+) -> std::result::Result<(SchemaRef, i64), String> {
+    let mut newest: Option<(&ObjectMeta, SchemaRef)> = None;
+    let mut row_count = 0;
+    for file in &candidate.files {
+        let reader = ParquetObjectReader::new(Arc::clone(store), file.location.clone())
+            .with_file_size(file.size);
+        let builder = ParquetRecordBatchStreamBuilder::new(reader)
+            .await
+            .map_err(|e| format!("{}: {}", file.location, e))?;
+        row_count += builder.metadata().file_metadata().num_rows();
+        if newest
+            .as_ref()
+            .is_none_or(|(meta, _)| file.last_modified > meta.last_modified)
+        {
+            newest = Some((file, Arc::clone(builder.schema())));
+        }
+    }
+    let (_, schema) = newest.ok_or("no Parquet files")?;
+    Ok((schema, row_count))
+}
+
+/// Append Hive partition columns missing from the files as strings
+fn with_partition_columns(schema: SchemaRef, partition_keys: &[String]) -> SchemaRef {
+    let missing: Vec<&String> = partition_keys
+        .iter()
+        .filter(|key| schema.field_with_name(key).is_err())
+        .collect();
+    if missing.is_empty() {
+        return schema;
+    }
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.extend(
+        missing
+            .into_iter()
+            .map(|key| Field::new(key, DataType::Utf8, true)),
+    );
+    Arc::new(Schema::new(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{Int64Array, RecordBatch};
+    use datafusion::parquet::arrow::ArrowWriter;
+    use metafuse_catalog_storage::LocalSqliteBackend;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn write_parquet(path: &std::path::Path, rows: i64) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+        )
+        .unwrap();
+        let mut writer =
+            ArrowWriter::try_new(std::fs::File::create(path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_split_partitions() {
+        assert_eq!(
+            split_partitions(&["sales", "orders", "region=eu", "dt=2026-01-01"]),
+            (
+                "sales/orders".to_string(),
+                vec!["region".to_string(), "dt".to_string()]
+            )
+        );
+        assert_eq!(split_partitions(&[]), (String::new(), vec![]));
+        let options = CrawlOptions {
+            name_prefix: Some("lake_".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            dataset_name("/data/lake", "sales/orders", &options),
+            "lake_sales_orders"
+        );
+        assert_eq!(
+            dataset_name("/data/lake/", "", &CrawlOptions::default()),
+            "lake"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_crawl_path() {
+        let dir = TempDir::new().unwrap();
+        let lake = dir.path();
+        write_parquet(&lake.join("sales/orders/region=eu/part-0.parquet"), 3);
+        write_parquet(&lake.join("sales/orders/region=us/part-0.parquet"), 4);
+        write_parquet(&lake.join("customers/part-0.parquet"), 5);
+        std::fs::write(lake.join("customers/_SUCCESS"), "").unwrap();
+        write_parquet(&lake.join("events/part-0.parquet"), 1);
+        std::fs::create_dir_all(lake.join("events/_delta_log")).unwrap();
+        std::fs::write(lake.join("events/_delta_log/0.json"), "{}").unwrap();
+        std::fs::create_dir_all(lake.join("broken")).unwrap();
+        std::fs::write(lake.join("broken/part-0.parquet"), "not parquet").unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()));
+        let prefix = lake.display().to_string();
+
+        let dry_run = CrawlOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let report = emitter.crawl_path(&prefix, &dry_run).await.unwrap();
+        assert_eq!(report.datasets.len(), 2);
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let count = |conn: &rusqlite::Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+                .unwrap_or(0)
+        };
+        assert_eq!(count(&conn), 0);
+
+        let options = CrawlOptions {
+            domain: Some("lake".to_string()),
+            tags: vec!["crawled".to_string()],
+            ..Default::default()
+        };
+        let report = emitter.crawl_path(&prefix, &options).await.unwrap();
+        let names: Vec<&str> = report.datasets.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["customers", "sales_orders"]);
+        let orders = &report.datasets[1];
+        assert_eq!(orders.location, format!("{}/sales/orders", prefix));
+        assert_eq!(orders.files, 2);
+        assert_eq!(orders.row_count, 7);
+        assert_eq!(orders.partition_keys, vec!["region"]);
+
+        let mut skipped: Vec<&str> = report
+            .skipped
+            .iter()
+            .map(|s| s.location.rsplit('/').next().unwrap())
+            .collect();
+        skipped.sort();
+        assert_eq!(skipped, vec!["broken", "events"]);
+
+        assert_eq!(count(&conn), 2);
+        let (row_count, partition_keys, domain): (i64, String, String) = conn
+            .query_row(
+                "SELECT row_count, partition_keys, domain FROM datasets WHERE name = 'sales_orders'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(row_count, 7);
+        assert_eq!(partition_keys, r#"["region"]"#);
+        assert_eq!(domain, "lake");
+        let region_type: String = conn
+            .query_row(
+                "SELECT f.data_type FROM fields f JOIN datasets d ON d.id = f.dataset_id
+                 WHERE d.name = 'sales_orders' AND f.name = 'region'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(region_type, "Utf8");
+
+        assert!(emitter
+            .crawl_path("relative/lake", &CrawlOptions::default())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Delta Lake Table Introspection
+//!
+//! [`Emitter::emit_delta_table`] registers a Delta table from its transaction
+//! log, so jobs writing Delta don't assemble the schema and statistics by
+//! hand:
+//!
+//! ```ignore
+//! let metadata = emitter
+//!     .emit_delta_table("orders", "s3://lake/orders", vec!["raw_orders".to_string()], vec![])
+//!     .await?;
+//! println!("Registered orders at version {}", metadata.version);
+//! ```
+//!
+//! The schema (with Arrow types), row count, size, and partition columns of
+//! the latest version are emitted with format `delta`. The version itself is
+//! returned but not stored: the API reads it live from the log.
+
+use crate::Emitter;
+use metafuse_catalog_core::{CatalogError, OperationalMeta, Result};
+use metafuse_catalog_delta::{DeltaMetadata, DeltaReader, ReadLimits};
+use metafuse_catalog_storage::CatalogBackend;
+use std::time::Duration;
+
+impl<B: CatalogBackend> Emitter<B> {
+    /// Emit a Delta table, reading its metadata from the Delta log
+    ///
+    /// Returns the metadata that was read. Fails without writing anything if
+    /// the table can't be read.
+    pub async fn emit_delta_table(
+        &self,
+        name: &str,
+        location: &str,
+        upstream_datasets: Vec<String>,
+        tags: Vec<String>,
+    ) -> Result<DeltaMetadata> {
+        let read_error =
+            |e| CatalogError::Other(format!("Failed to read Delta table '{}': {}", location, e));
+        let reader = DeltaReader::new(Duration::ZERO).with_limits(ReadLimits::from_env());
+        let metadata = reader.get_metadata(location).await.map_err(read_error)?;
+        // The schema of the version the statistics came from
+        let schema = reader
+            .get_arrow_schema(location, Some(metadata.version))
+            .await
+            .map_err(read_error)?;
+
+        tracing::debug!(
+            dataset = %name,
+            version = metadata.version,
+            row_count = metadata.row_count,
+            "Read Delta table metadata"
+        );
+
+        self.emit_dataset(
+            name,
+            location,
+            "delta",
+            None,
+            None,
+            None,
+            None,
+            schema,
+            Some(OperationalMeta {
+                row_count: Some(metadata.row_count),
+                size_bytes: Some(metadata.size_bytes),
+                partition_keys: metadata.partition_columns.clone(),
+            }),
+            upstream_datasets,
+            tags,
+        )
+        .await?;
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deltalake::kernel::{DataType, PrimitiveType, StructField};
+    use deltalake::operations::create::CreateBuilder;
+    use metafuse_catalog_storage::LocalSqliteBackend;
+    use tempfile::{NamedTempFile, TempDir};
+
+    #[tokio::test]
+    async fn test_emit_delta_table() {
+        let dir = TempDir::new().unwrap();
+        let location = format!("file://{}", dir.path().display());
+        CreateBuilder::new()
+            .with_location(&location)
+            .with_columns(vec![
+                StructField::new("id", DataType::Primitive(PrimitiveType::Long), false),
+                StructField::new("region", DataType::Primitive(PrimitiveType::String), true),
+            ])
+            .with_partition_columns(vec!["region"])
+            .await
+            .unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()));
+        let metadata = emitter
+            .emit_delta_table("orders", &location, vec![], vec!["lake".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(metadata.version, 0);
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let (format, row_count, partition_keys): (String, i64, String) = conn
+            .query_row(
+                "SELECT format, row_count, partition_keys FROM datasets WHERE name = 'orders'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(format, "delta");
+        assert_eq!(row_count, 0);
+        assert_eq!(partition_keys, r#"["region"]"#);
+        let id_type: String = conn
+            .query_row(
+                "SELECT data_type FROM fields WHERE name = 'id'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(id_type, "Int64");
+
+        // Unreadable tables fail without writing
+        let err = emitter
+            .emit_delta_table("missing", "file:///nonexistent/table", vec![], vec![])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to read Delta table"));
+    }
+}
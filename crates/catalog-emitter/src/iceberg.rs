@@ -0,0 +1,247 @@
+//! Iceberg Table Introspection
+//!
+//! With the `iceberg` feature, [`Emitter::emit_iceberg_table`] registers an
+//! Iceberg table from its current metadata file:
+//!
+//! ```ignore
+//! let metadata = emitter
+//!     .emit_iceberg_table("orders", "s3://lake/warehouse/orders", vec!["raw_orders".to_string()], vec![])
+//!     .await?;
+//! println!("Registered orders at snapshot {:?}", metadata.current_snapshot_id);
+//! ```
+//!
+//! The current schema (converted to Arrow types), the source columns of the
+//! default partition spec, and the row count, size, and file count of the
+//! current snapshot are emitted with format `iceberg`, at the table location
+//! recorded in the metadata. Snapshots are not stored: the API reads them
+//! live.
+
+use crate::Emitter;
+use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema, TimeUnit};
+use metafuse_catalog_core::{CatalogError, OperationalMeta, Result};
+use metafuse_catalog_iceberg::{IcebergMetadata, IcebergReader, IcebergType, NestedType};
+use metafuse_catalog_storage::CatalogBackend;
+use std::sync::Arc;
+
+/// Arrow type of an Iceberg type, as Iceberg's Arrow mapping defines it
+fn arrow_type(iceberg_type: &IcebergType) -> Result<DataType> {
+    let primitive = match iceberg_type {
+        IcebergType::Primitive(name) => name.as_str(),
+        IcebergType::Nested(NestedType::Struct { fields }) => {
+            let fields = fields
+                .iter()
+                .map(|f| Ok(Field::new(&f.name, arrow_type(&f.field_type)?, !f.required)))
+                .collect::<Result<Fields>>()?;
+            return Ok(DataType::Struct(fields));
+        }
+        IcebergType::Nested(NestedType::List {
+            element,
+            element_required,
+            ..
+        }) => {
+            return Ok(DataType::List(Arc::new(Field::new(
+                "element",
+                arrow_type(element)?,
+                !element_required,
+            ))));
+        }
+        IcebergType::Nested(NestedType::Map {
+            key,
+            value,
+            value_required,
+            ..
+        }) => {
+            let entries = Fields::from(vec![
+                Field::new("key", arrow_type(key)?, false),
+                Field::new("value", arrow_type(value)?, !value_required),
+            ]);
+            return Ok(DataType::Map(
+                Arc::new(Field::new("key_value", DataType::Struct(entries), false)),
+                false,
+            ));
+        }
+    };
+
+    let unsupported = || CatalogError::Other(format!("Unsupported Iceberg type '{}'", primitive));
+    Ok(match primitive {
+        "boolean" => DataType::Boolean,
+        "int" => DataType::Int32,
+        "long" => DataType::Int64,
+        "float" => DataType::Float32,
+        "double" => DataType::Float64,
+        "date" => DataType::Date32,
+        "time" => DataType::Time64(TimeUnit::Microsecond),
+        "timestamp" => DataType::Timestamp(TimeUnit::Microsecond, None),
+        "timestamptz" => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        "timestamp_ns" => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        "timestamptz_ns" => DataType::Timestamp(TimeUnit::Nanosecond, Some("+00:00".into())),
+        "string" => DataType::Utf8,
+        "uuid" => DataType::FixedSizeBinary(16),
+        "binary" => DataType::Binary,
+        other => {
+            if let Some(length) = other
+                .strip_prefix("fixed[")
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                DataType::FixedSizeBinary(length.trim().parse().map_err(|_| unsupported())?)
+            } else if let Some(args) = other
+                .strip_prefix("decimal(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                let (precision, scale) = args.split_once(',').ok_or_else(unsupported)?;
+                DataType::Decimal128(
+                    precision.trim().parse().map_err(|_| unsupported())?,
+                    scale.trim().parse().map_err(|_| unsupported())?,
+                )
+            } else {
+                return Err(unsupported());
+            }
+        }
+    })
+}
+
+impl<B: CatalogBackend> Emitter<B> {
+    /// Emit an Iceberg table, reading its metadata from the current
+    /// metadata file
+    ///
+    /// `location` is the table root or a metadata file. Returns the metadata
+    /// that was read. Fails without writing anything if the table can't be
+    /// read or has a type without an Arrow equivalent.
+    pub async fn emit_iceberg_table(
+        &self,
+        name: &str,
+        location: &str,
+        upstream_datasets: Vec<String>,
+        tags: Vec<String>,
+    ) -> Result<IcebergMetadata> {
+        let metadata = IcebergReader::new()
+            .get_metadata(location)
+            .await
+            .map_err(|e| {
+                CatalogError::Other(format!(
+                    "Failed to read Iceberg table '{}': {}",
+                    location, e
+                ))
+            })?;
+        let fields = metadata
+            .schema
+            .fields
+            .iter()
+            .map(|f| Ok(Field::new(&f.name, arrow_type(&f.field_type)?, f.nullable)))
+            .collect::<Result<Vec<_>>>()?;
+
+        tracing::debug!(
+            dataset = %name,
+            metadata = %metadata.metadata_location,
+            snapshot = ?metadata.current_snapshot_id,
+            row_count = ?metadata.row_count,
+            "Read Iceberg table metadata"
+        );
+
+        self.emit_dataset(
+            name,
+            &metadata.location,
+            "iceberg",
+            None,
+            None,
+            None,
+            None,
+            Arc::new(Schema::new(fields)),
+            Some(OperationalMeta {
+                row_count: metadata.row_count,
+                size_bytes: metadata.size_bytes,
+                partition_keys: metadata.schema.partition_columns.clone(),
+            }),
+            upstream_datasets,
+            tags,
+        )
+        .await?;
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metafuse_catalog_storage::LocalSqliteBackend;
+    use tempfile::{NamedTempFile, TempDir};
+
+    #[test]
+    fn test_arrow_type() {
+        let parse = |json: &str| arrow_type(&serde_json::from_str(json).unwrap());
+        assert_eq!(parse(r#""long""#).unwrap(), DataType::Int64);
+        assert_eq!(
+            parse(r#""decimal(10, 2)""#).unwrap(),
+            DataType::Decimal128(10, 2)
+        );
+        assert_eq!(
+            parse(r#""fixed[16]""#).unwrap(),
+            DataType::FixedSizeBinary(16)
+        );
+        assert!(matches!(
+            parse(r#"{"type": "list", "element-id": 2, "element-required": true, "element": "string"}"#)
+                .unwrap(),
+            DataType::List(element) if element.data_type() == &DataType::Utf8 && !element.is_nullable()
+        ));
+        assert!(matches!(
+            parse(r#"{"type": "map", "key-id": 2, "key": "string", "value-id": 3, "value-required": false, "value": "double"}"#)
+                .unwrap(),
+            DataType::Map(..)
+        ));
+        assert!(parse(r#""variant""#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_emit_iceberg_table() {
+        let dir = TempDir::new().unwrap();
+        let location = format!("file://{}", dir.path().display());
+        std::fs::create_dir(dir.path().join("metadata")).unwrap();
+        std::fs::write(
+            dir.path().join("metadata/v1.metadata.json"),
+            format!(
+                r#"{{
+                  "format-version": 2, "location": "{location}", "last-updated-ms": 1767225600000,
+                  "current-schema-id": 0,
+                  "schemas": [{{"type": "struct", "schema-id": 0, "fields": [
+                    {{"id": 1, "name": "id", "required": true, "type": "long"}},
+                    {{"id": 2, "name": "region", "required": false, "type": "string"}}
+                  ]}}],
+                  "default-spec-id": 0,
+                  "partition-specs": [{{"spec-id": 0, "fields": [
+                    {{"name": "region", "transform": "identity", "source-id": 2, "field-id": 1000}}
+                  ]}}],
+                  "current-snapshot-id": 7,
+                  "snapshots": [{{"snapshot-id": 7, "sequence-number": 1, "timestamp-ms": 1767225600000,
+                    "summary": {{"operation": "append", "total-records": "42", "total-files-size": "2048"}}}}]
+                }}"#
+            ),
+        )
+        .unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()));
+        let metadata = emitter
+            .emit_iceberg_table("orders", &location, vec![], vec![])
+            .await
+            .unwrap();
+        assert_eq!(metadata.current_snapshot_id, Some(7));
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let (format, row_count, partition_keys): (String, i64, String) = conn
+            .query_row(
+                "SELECT format, row_count, partition_keys FROM datasets WHERE name = 'orders'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(format, "iceberg");
+        assert_eq!(row_count, 42);
+        assert_eq!(partition_keys, r#"["region"]"#);
+
+        let err = emitter
+            .emit_iceberg_table("missing", "file:///nonexistent/table", vec![], vec![])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to read Iceberg table"));
+    }
+}
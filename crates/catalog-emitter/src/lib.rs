@@ -4,14 +4,37 @@
 
 use chrono::Utc;
 use datafusion::arrow::datatypes::SchemaRef;
+use metafuse_catalog_core::hooks::{DatasetWrite, WriteHooks, WriteOperation, WriteSource};
+pub use metafuse_catalog_core::lineage_mode::LineageMode;
+use metafuse_catalog_core::lineage_mode::{self, Resolved};
+pub use metafuse_catalog_core::merge_strategy::MergeStrategy;
+pub use metafuse_catalog_core::write_report::{ItemReport, WriteReport};
 use metafuse_catalog_core::{
-    get_catalog_version, increment_catalog_version, init_sqlite_schema, validation, CatalogError,
-    DatasetMeta, FieldMeta, OperationalMeta, Result,
+    auto_tagging, emission_state, field_ordinals, formats, get_catalog_version,
+    increment_catalog_version, init_sqlite_schema, lineage_cycles, merge_strategy, paths,
+    placeholders, validation, write_report, CatalogError, DatasetMeta, FieldMeta, OperationalMeta,
+    Result,
 };
-use metafuse_catalog_storage::CatalogBackend;
-use rusqlite::Connection;
+use metafuse_catalog_storage::modify::{DEFAULT_CONFLICT_BASE_DELAY_MS, DEFAULT_CONFLICT_RETRIES};
+use metafuse_catalog_storage::{busy, BusyRetryPolicy, CatalogBackend};
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::Arc;
+use std::time::Instant;
 use tokio::time::Duration;
 
+pub mod crawl;
+mod delta;
+#[cfg(feature = "iceberg")]
+mod iceberg;
+pub mod metrics;
+pub mod observe;
+pub mod sql_lineage;
+
+pub use crawl::{CrawlOptions, CrawlReport, CrawledDataset, SkippedPath};
+pub use metrics::{EmitObserver, EmitOutcome};
+pub use observe::{plan_lineage, PlanLineage, PlanOutput};
+pub use sql_lineage::sql_sources;
+
 /// Emitter API for capturing metadata from DataFusion pipelines
 ///
 /// Use this to automatically register datasets, capture lineage,
@@ -47,12 +70,97 @@ use tokio::time::Duration;
 /// ```
 pub struct Emitter<B: CatalogBackend> {
     backend: B,
+    write_hooks: WriteHooks,
+    write_mode: WriteMode,
+    lineage_mode: LineageMode,
+    merge_strategy: MergeStrategy,
+    busy_policy: BusyRetryPolicy,
+    observer: Option<Arc<dyn EmitObserver>>,
+}
+
+/// How the emitter handles datasets whose metadata hasn't changed
+///
+/// Unchanged means the emitted metadata hashes the same as at the last full
+/// write (see [`emission_state`]). Catalogs before migration v1.23.0 have no
+/// stored hashes, so every emission is a full write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Skip unchanged datasets, only recording when they were last seen
+    #[default]
+    Incremental,
+    /// Skip unchanged datasets without touching the catalog at all
+    SkipUnchanged,
+    /// Always rewrite the dataset
+    Full,
 }
 
 impl<B: CatalogBackend> Emitter<B> {
     /// Create a new emitter with the given backend
     pub fn new(backend: B) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            write_hooks: WriteHooks::default(),
+            write_mode: WriteMode::default(),
+            lineage_mode: LineageMode::default(),
+            merge_strategy: MergeStrategy::default(),
+            busy_policy: BusyRetryPolicy::from_env(),
+            observer: None,
+        }
+    }
+
+    /// Run the given write hooks on every emitted dataset
+    ///
+    /// Pre-validate hooks can enrich or reject a dataset before it is
+    /// validated; post-commit hooks run after the catalog upload succeeds.
+    pub fn with_write_hooks(mut self, write_hooks: WriteHooks) -> Self {
+        self.write_hooks = write_hooks;
+        self
+    }
+
+    /// Set how unchanged datasets are handled (default: [`WriteMode::Incremental`])
+    ///
+    /// Use [`WriteMode::Full`] to force every emission to rewrite the dataset,
+    /// for example to overwrite edits made through the API that the merge
+    /// strategy doesn't keep.
+    pub fn with_write_mode(mut self, write_mode: WriteMode) -> Self {
+        self.write_mode = write_mode;
+        self
+    }
+
+    /// Set how upstreams that aren't registered yet are handled (default:
+    /// [`LineageMode::Ignore`], which drops their lineage)
+    ///
+    /// [`LineageMode::Placeholder`] keeps the edge with a placeholder dataset
+    /// (status `pending`) that becomes a regular dataset when the upstream is
+    /// emitted; [`LineageMode::Strict`] fails the emission.
+    pub fn with_lineage_mode(mut self, lineage_mode: LineageMode) -> Self {
+        self.lineage_mode = lineage_mode;
+        self
+    }
+
+    /// Set how an emission is merged with a registered dataset (default:
+    /// [`MergeStrategy::PreserveManual`], which keeps curated field
+    /// descriptions and tags added by hand)
+    ///
+    /// [`MergeStrategy::Replace`] overwrites fields and tags, losing field
+    /// classifications and glossary links; [`MergeStrategy::Merge`] keeps
+    /// every field's metadata and tag but lets emitted descriptions win.
+    pub fn with_merge_strategy(mut self, merge_strategy: MergeStrategy) -> Self {
+        self.merge_strategy = merge_strategy;
+        self
+    }
+
+    /// Set how writes wait for and retry on a catalog locked by another
+    /// writer (default: [`BusyRetryPolicy::from_env`])
+    pub fn with_busy_policy(mut self, busy_policy: BusyRetryPolicy) -> Self {
+        self.busy_policy = busy_policy;
+        self
+    }
+
+    /// Report emissions and retries to `observer`, e.g. to export metrics
+    pub fn with_observer(mut self, observer: Arc<dyn EmitObserver>) -> Self {
+        self.observer = Some(observer);
+        self
     }
 
     /// Emit metadata for a dataset
@@ -63,7 +171,7 @@ impl<B: CatalogBackend> Emitter<B> {
     /// # Arguments
     /// * `name` - Unique name for the dataset
     /// * `path` - Storage path (e.g., "s3://bucket/path" or "gs://bucket/path")
-    /// * `format` - Format type ("parquet", "delta", "iceberg", "csv", etc.)
+    /// * `format` - Format name or alias ("parquet", "delta", "iceberg", "csv", etc.), stored in canonical form
     /// * `description` - Optional human-readable description
     /// * `tenant` - Optional tenant identifier for multi-tenant deployments
     /// * `domain` - Optional business domain ("finance", "marketing", etc.)
@@ -72,6 +180,9 @@ impl<B: CatalogBackend> Emitter<B> {
     /// * `operational` - Optional operational metadata (row count, size, partition keys)
     /// * `upstream_datasets` - List of upstream dataset names this depends on
     /// * `tags` - Tags for categorization and discovery
+    ///
+    /// Returns a [`WriteReport`] of which upstreams and tags were written,
+    /// were already stored, were given more than once, or were skipped.
     #[allow(clippy::too_many_arguments)]
     pub async fn emit_dataset(
         &self,
@@ -86,7 +197,159 @@ impl<B: CatalogBackend> Emitter<B> {
         operational: Option<OperationalMeta>,
         upstream_datasets: Vec<String>,
         tags: Vec<String>,
-    ) -> Result<()> {
+    ) -> Result<WriteReport> {
+        self.emit_dataset_with_lineage_mode(
+            self.lineage_mode,
+            name,
+            path,
+            format,
+            description,
+            tenant,
+            domain,
+            owner,
+            schema,
+            operational,
+            upstream_datasets,
+            tags,
+        )
+        .await
+    }
+
+    /// Emit metadata for a dataset, overriding the emitter's lineage mode
+    ///
+    /// Takes the same arguments as [`Emitter::emit_dataset`]. With
+    /// [`LineageMode::Strict`], an unregistered upstream fails the emission
+    /// with a validation error and nothing is written.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn emit_dataset_with_lineage_mode(
+        &self,
+        lineage_mode: LineageMode,
+        name: &str,
+        path: &str,
+        format: &str,
+        description: Option<&str>,
+        tenant: Option<&str>,
+        domain: Option<&str>,
+        owner: Option<&str>,
+        schema: SchemaRef,
+        operational: Option<OperationalMeta>,
+        upstream_datasets: Vec<String>,
+        tags: Vec<String>,
+    ) -> Result<WriteReport> {
+        // Convert Arrow schema to FieldMeta
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|f| FieldMeta {
+                name: f.name().to_string(),
+                data_type: format!("{:?}", f.data_type()),
+                nullable: f.is_nullable(),
+                description: None,
+            })
+            .collect();
+        self.emit_observed(
+            lineage_mode,
+            name,
+            path,
+            format,
+            description,
+            tenant,
+            domain,
+            owner,
+            fields,
+            operational,
+            upstream_datasets,
+            tags,
+        )
+        .await
+    }
+
+    /// Emit fields as given, reporting the outcome to the observer
+    #[allow(clippy::too_many_arguments)]
+    async fn emit_observed(
+        &self,
+        lineage_mode: LineageMode,
+        name: &str,
+        path: &str,
+        format: &str,
+        description: Option<&str>,
+        tenant: Option<&str>,
+        domain: Option<&str>,
+        owner: Option<&str>,
+        fields: Vec<FieldMeta>,
+        operational: Option<OperationalMeta>,
+        upstream_datasets: Vec<String>,
+        tags: Vec<String>,
+    ) -> Result<WriteReport> {
+        let started = Instant::now();
+        let result = self
+            .emit(
+                lineage_mode,
+                name,
+                path,
+                format,
+                description,
+                tenant,
+                domain,
+                owner,
+                fields,
+                operational,
+                upstream_datasets,
+                tags,
+            )
+            .await;
+        if let Some(observer) = &self.observer {
+            let outcome = result
+                .as_ref()
+                .map(|(outcome, _)| *outcome)
+                .unwrap_or(EmitOutcome::Failed);
+            observer.on_emit(name, outcome, started.elapsed());
+        }
+        result.map(|(_, report)| report)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn emit(
+        &self,
+        lineage_mode: LineageMode,
+        name: &str,
+        path: &str,
+        format: &str,
+        description: Option<&str>,
+        tenant: Option<&str>,
+        domain: Option<&str>,
+        owner: Option<&str>,
+        fields: Vec<FieldMeta>,
+        operational: Option<OperationalMeta>,
+        upstream_datasets: Vec<String>,
+        tags: Vec<String>,
+    ) -> Result<(EmitOutcome, WriteReport)> {
+        // ===== Write Hooks =====
+        let mut write = DatasetWrite {
+            path: Some(path.to_string()),
+            format: Some(format.to_string()),
+            description: description.map(|s| s.to_string()),
+            tenant: tenant.map(|s| s.to_string()),
+            domain: domain.map(|s| s.to_string()),
+            owner: owner.map(|s| s.to_string()),
+            tags,
+            columns: fields.iter().map(|f| f.name.clone()).collect(),
+            ..DatasetWrite::new(WriteOperation::Create, WriteSource::Emitter, name)
+        };
+        self.write_hooks
+            .run_pre_validate(&mut write)
+            .await
+            .map_err(|e| CatalogError::ValidationError(e.to_string()))?;
+
+        // Hooks may have changed any of these
+        let path = write.path.as_deref().unwrap_or(path);
+        let format = write.format.as_deref().unwrap_or(format);
+        let description = write.description.as_deref();
+        let tenant = write.tenant.as_deref();
+        let domain = write.domain.as_deref();
+        let owner = write.owner.as_deref();
+        let tags = write.tags.clone();
+
         // ===== Input Validation =====
         // Validate dataset name
         validation::validate_dataset_name(name)?;
@@ -106,9 +369,9 @@ impl<B: CatalogBackend> Emitter<B> {
             validation::validate_tag(tag)?;
         }
 
-        // Validate all field names from schema
-        for field in schema.fields() {
-            validation::validate_field_name(field.name())?;
+        // Validate all field names
+        for field in &fields {
+            validation::validate_field_name(&field.name)?;
         }
 
         // Validate upstream dataset names
@@ -123,29 +386,22 @@ impl<B: CatalogBackend> Emitter<B> {
             }
         }
 
+        let format = formats::normalize_format(format)?;
+
+        // Store paths in canonical form so spellings of one location don't register twice
+        let path = paths::normalize_path(path)?;
+
         // Validate path for traversal attacks (basic check)
         if let Some(file_path) = path.strip_prefix("file://") {
             validation::validate_file_uri_path(file_path)?;
         }
         // ===== End Validation =====
 
-        // Convert Arrow schema to FieldMeta
-        let fields = schema
-            .fields()
-            .iter()
-            .map(|f| FieldMeta {
-                name: f.name().to_string(),
-                data_type: format!("{:?}", f.data_type()),
-                nullable: f.is_nullable(),
-                description: None,
-            })
-            .collect();
-
         let now = Utc::now();
 
         let dataset = DatasetMeta {
             name: name.to_string(),
-            path: path.to_string(),
+            path,
             format: format.to_string(),
             description: description.map(|s| s.to_string()),
             tenant: tenant.map(|s| s.to_string()),
@@ -159,9 +415,22 @@ impl<B: CatalogBackend> Emitter<B> {
             operational,
         };
 
-        self.write_dataset(&dataset).await?;
+        // Post-commit hooks only see writes that changed the catalog
+        let (outcome, report) = self.write_dataset(&dataset, lineage_mode).await?;
+        if outcome == EmitOutcome::Written {
+            self.write_hooks.run_post_commit(&write).await;
+        }
+        if report.has_warnings() {
+            tracing::warn!(
+                dataset = %name,
+                duplicate_upstreams = ?report.upstreams.deduplicated,
+                skipped_upstreams = ?report.upstreams.skipped,
+                duplicate_tags = ?report.tags.deduplicated,
+                "Emission repeated or skipped upstreams or tags"
+            );
+        }
 
-        Ok(())
+        Ok((outcome, report))
     }
 
     /// Write dataset metadata to the catalog with optimistic concurrency control
@@ -172,9 +441,24 @@ impl<B: CatalogBackend> Emitter<B> {
     /// 3. Validate catalog version was incremented
     /// 4. Upload modified catalog with version preconditions
     /// 5. If upload fails due to conflict, retry with exponential backoff
-    async fn write_dataset(&self, dataset: &DatasetMeta) -> Result<()> {
-        const MAX_RETRIES: u32 = 3;
+    ///
+    /// Backends no longer retry a conflicting upload themselves, so this loop
+    /// is what lets several emitters share a cloud catalog.
+    ///
+    /// Returns whether the metadata was written, or how it was skipped when it
+    /// was unchanged and the write mode allows skipping it, with the write's
+    /// report.
+    async fn write_dataset(
+        &self,
+        dataset: &DatasetMeta,
+        lineage_mode: LineageMode,
+    ) -> Result<(EmitOutcome, WriteReport)> {
+        const MAX_RETRIES: u32 = DEFAULT_CONFLICT_RETRIES;
         let mut retry_count = 0;
+        let content_hash = emission_state::content_hash(dataset)?;
+        let write_mode = self.write_mode;
+        let busy_policy = self.busy_policy;
+        let merge_strategy = self.merge_strategy;
 
         loop {
             // Download catalog (captures current version and remote metadata)
@@ -190,33 +474,103 @@ impl<B: CatalogBackend> Emitter<B> {
             // Clone data for move into spawn_blocking
             let dataset_clone = dataset.clone();
             let download_path = download.path.clone();
+            let content_hash = content_hash.clone();
+            let observer = self.observer.clone();
 
-            // Perform all SQLite operations in spawn_blocking to avoid blocking async executor
-            let new_version = tokio::task::spawn_blocking(move || -> Result<i64> {
-                // Open connection to the downloaded catalog
-                let mut conn = Connection::open(&download_path)?;
-                conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-                init_sqlite_schema(&conn)?;
-
-                // Perform all writes in a transaction
-                let tx = conn.transaction()?;
-                write_dataset_tx(&tx, &dataset_clone)?;
-                tx.commit()?;
-
-                // Verify version was incremented (sanity check)
-                let new_version = get_catalog_version(&conn)?;
-                if new_version <= expected_version {
-                    return Err(CatalogError::Other(format!(
-                        "Catalog version not incremented: expected > {}, got {}",
-                        expected_version, new_version
-                    )));
-                }
+            // Perform all SQLite operations in spawn_blocking to avoid blocking async executor.
+            // Each attempt starts over, so a write that hit a locked catalog is retried whole.
+            let outcome = tokio::task::spawn_blocking(
+                move || -> Result<(Option<(i64, bool)>, WriteReport)> {
+                    let mut attempts = 0;
+                    busy::retry_busy(&busy_policy, "emit_dataset", || {
+                        attempts += 1;
+                        if attempts > 1 {
+                            if let Some(observer) = &observer {
+                                observer.on_busy_retry(&dataset_clone.name);
+                            }
+                        }
 
-                Ok(new_version)
-            })
+                        // Open connection to the downloaded catalog
+                        let mut conn = Connection::open(&download_path)?;
+                        busy_policy.configure(&mut conn)?;
+                        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+                        init_sqlite_schema(&conn)?;
+
+                        let unchanged_id = match write_mode {
+                            WriteMode::Full => None,
+                            _ => emission_state::stored_hash(&conn, &dataset_clone.name)?
+                                .filter(|(_, stored)| *stored == content_hash)
+                                .map(|(id, _)| id),
+                        };
+                        // Upstreams that weren't linked last time need a rewrite
+                        let unchanged_id = match unchanged_id {
+                            Some(id)
+                                if lineage_is_current(&conn, id, &dataset_clone, lineage_mode)? =>
+                            {
+                                Some(id)
+                            }
+                            _ => None,
+                        };
+
+                        // Immediate (see busy policy), so it waits for other writers
+                        let tx = conn.transaction()?;
+                        let stored_report = |dataset_id| {
+                            write_report::report_stored(
+                                &tx,
+                                dataset_id,
+                                &dataset_clone.upstream_datasets,
+                                &dataset_clone.tags,
+                            )
+                        };
+                        let (written, report) = match unchanged_id {
+                            Some(dataset_id) if write_mode == WriteMode::SkipUnchanged => {
+                                return Ok((None, stored_report(dataset_id)?));
+                            }
+                            Some(dataset_id) => {
+                                let report = stored_report(dataset_id)?;
+                                emission_state::touch(&tx, dataset_id, dataset_clone.last_updated)?;
+                                increment_catalog_version(&tx)?;
+                                (false, report)
+                            }
+                            None => {
+                                let (dataset_id, report) = write_dataset_tx(
+                                    &tx,
+                                    &dataset_clone,
+                                    lineage_mode,
+                                    merge_strategy,
+                                )?;
+                                emission_state::record_write(
+                                    &tx,
+                                    dataset_id,
+                                    &content_hash,
+                                    dataset_clone.last_updated,
+                                )?;
+                                (true, report)
+                            }
+                        };
+                        tx.commit()?;
+
+                        // Verify version was incremented (sanity check)
+                        let new_version = get_catalog_version(&conn)?;
+                        if new_version <= expected_version {
+                            return Err(CatalogError::Other(format!(
+                                "Catalog version not incremented: expected > {}, got {}",
+                                expected_version, new_version
+                            )));
+                        }
+
+                        Ok((Some((new_version, written)), report))
+                    })
+                },
+            )
             .await
             .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
 
+            let (Some((new_version, written)), report) = outcome else {
+                tracing::info!(dataset = %dataset.name, "Dataset metadata unchanged, skipped");
+                return Ok((EmitOutcome::Skipped, outcome.1));
+            };
+
             tracing::debug!(
                 dataset = %dataset.name,
                 old_version = expected_version,
@@ -227,17 +581,30 @@ impl<B: CatalogBackend> Emitter<B> {
             // Upload the modified catalog with optimistic locking
             match self.backend.upload(&download).await {
                 Ok(()) => {
-                    tracing::info!(
-                        dataset = %dataset.name,
-                        version = new_version,
-                        "Dataset metadata emitted successfully"
-                    );
-                    return Ok(());
+                    if written {
+                        tracing::info!(
+                            dataset = %dataset.name,
+                            version = new_version,
+                            "Dataset metadata emitted successfully"
+                        );
+                    } else {
+                        tracing::info!(
+                            dataset = %dataset.name,
+                            version = new_version,
+                            "Dataset metadata unchanged, recorded as seen"
+                        );
+                    }
+                    let outcome = if written {
+                        EmitOutcome::Written
+                    } else {
+                        EmitOutcome::Unchanged
+                    };
+                    return Ok((outcome, report));
                 }
                 Err(CatalogError::ConflictError(msg)) if retry_count < MAX_RETRIES => {
                     retry_count += 1;
                     // Exponential backoff: 100ms, 200ms, 400ms
-                    let backoff_ms = 100 * 2_u64.pow(retry_count - 1);
+                    let backoff_ms = DEFAULT_CONFLICT_BASE_DELAY_MS * 2_u64.pow(retry_count - 1);
                     tracing::warn!(
                         dataset = %dataset.name,
                         retry = retry_count,
@@ -246,6 +613,9 @@ impl<B: CatalogBackend> Emitter<B> {
                         error = %msg,
                         "Catalog conflict detected, retrying..."
                     );
+                    if let Some(observer) = &self.observer {
+                        observer.on_conflict(&dataset.name, retry_count);
+                    }
                     tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
                     // Loop will retry with fresh download
                 }
@@ -266,8 +636,59 @@ impl<B: CatalogBackend> Emitter<B> {
     }
 }
 
-/// Perform dataset writes within a transaction
-fn write_dataset_tx(tx: &rusqlite::Transaction, dataset: &DatasetMeta) -> Result<()> {
+/// Whether rewriting the dataset would leave its lineage unchanged
+///
+/// False when an upstream without an edge has been registered since, or when
+/// the lineage mode would now create a placeholder or fail for it.
+fn lineage_is_current(
+    conn: &Connection,
+    dataset_id: i64,
+    dataset: &DatasetMeta,
+    lineage_mode: LineageMode,
+) -> Result<bool> {
+    let mut stmt = conn.prepare(
+        "SELECT EXISTS(
+            SELECT 1 FROM lineage l JOIN datasets u ON u.id = l.upstream_dataset_id
+            WHERE l.downstream_dataset_id = ?1 AND u.name = ?2
+        )",
+    )?;
+    for upstream_name in &dataset.upstream_datasets {
+        let linked: bool = stmt.query_row(rusqlite::params![dataset_id, upstream_name], |row| {
+            row.get(0)
+        })?;
+        if linked {
+            continue;
+        }
+        let trashed: Option<bool> = conn
+            .query_row(
+                "SELECT deleted_at IS NOT NULL FROM datasets WHERE name = ?1",
+                [upstream_name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let current = match trashed {
+            Some(false) => false,
+            Some(true) => lineage_mode != LineageMode::Strict,
+            None => lineage_mode == LineageMode::Ignore,
+        };
+        if !current {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Perform dataset writes within a transaction, returning the dataset ID and
+/// the write's report
+///
+/// Unregistered upstreams are handled according to `lineage_mode`, and
+/// fields and tags are merged according to `merge_strategy`.
+fn write_dataset_tx(
+    tx: &rusqlite::Transaction,
+    dataset: &DatasetMeta,
+    lineage_mode: LineageMode,
+    merge_strategy: MergeStrategy,
+) -> Result<(i64, WriteReport)> {
     // Extract operational metadata
     let (row_count, size_bytes, partition_keys_json) = if let Some(ref op) = dataset.operational {
         let partition_keys_json = if op.partition_keys.is_empty() {
@@ -324,39 +745,46 @@ fn write_dataset_tx(tx: &rusqlite::Transaction, dataset: &DatasetMeta) -> Result
         |row| row.get(0),
     )?;
 
-    // Delete existing fields and insert new ones
-    tx.execute("DELETE FROM fields WHERE dataset_id = ?1", [dataset_id])?;
-
-    for field in &dataset.fields {
-        tx.execute(
-            "INSERT INTO fields (dataset_id, name, data_type, nullable, description) VALUES (?1, ?2, ?3, ?4, ?5)",
-            rusqlite::params![
-                dataset_id,
-                field.name,
-                field.data_type,
-                field.nullable as i32,
-                field.description,
-            ],
-        )?;
+    // A placeholder created for lineage is now a registered dataset
+    if placeholders::activate(tx, dataset_id)? {
+        tracing::debug!(dataset = %dataset.name, "Placeholder dataset registered");
     }
 
+    // Fields keep emission order; curated field metadata per the merge strategy
+    field_ordinals::merge_fields(tx, dataset_id, &dataset.fields, merge_strategy)?;
+
     // Delete existing lineage and insert new ones
+    let mut report = WriteReport::default();
+    let linked_before = write_report::linked_upstream_ids(tx, dataset_id)?;
     tx.execute(
         "DELETE FROM lineage WHERE downstream_dataset_id = ?1",
         [dataset_id],
     )?;
 
-    for upstream_name in &dataset.upstream_datasets {
-        // Get or skip if upstream doesn't exist
-        let upstream_id: Option<i64> = tx
-            .query_row(
-                "SELECT id FROM datasets WHERE name = ?1",
-                [upstream_name],
-                |row| row.get(0),
-            )
-            .ok();
+    for upstream_name in write_report::dedupe(&dataset.upstream_datasets, &mut report.upstreams) {
+        let upstream_id = match lineage_mode::resolve(tx, upstream_name, lineage_mode)? {
+            Resolved::Registered(id) => Some(id),
+            Resolved::Placeholder(id) => {
+                tracing::debug!(
+                    dataset = %dataset.name,
+                    upstream = %upstream_name,
+                    "Created placeholder for unregistered upstream"
+                );
+                Some(id)
+            }
+            Resolved::Skipped => {
+                tracing::warn!(
+                    dataset = %dataset.name,
+                    upstream = %upstream_name,
+                    "Upstream dataset not registered, lineage edge skipped"
+                );
+                report.upstreams.skipped.push(upstream_name.clone());
+                None
+            }
+        };
 
         if let Some(upstream_id) = upstream_id {
+            lineage_cycles::check_edge(tx, upstream_id, dataset_id)?;
             tx.execute(
                 "INSERT OR IGNORE INTO lineage (upstream_dataset_id, downstream_dataset_id, created_at) VALUES (?1, ?2, ?3)",
                 rusqlite::params![
@@ -365,16 +793,48 @@ fn write_dataset_tx(tx: &rusqlite::Transaction, dataset: &DatasetMeta) -> Result
                     Utc::now().to_rfc3339(),
                 ],
             )?;
+            report
+                .upstreams
+                .written(upstream_name, linked_before.contains(&upstream_id));
         }
     }
 
-    // Delete existing tags and insert new ones
-    tx.execute("DELETE FROM tags WHERE dataset_id = ?1", [dataset_id])?;
+    let tags_before = write_report::stored_tags(tx, dataset_id)?;
+    merge_strategy::write_tags(tx, dataset_id, &dataset.tags, merge_strategy)?;
+    for tag in write_report::dedupe(&dataset.tags, &mut report.tags) {
+        report.tags.written(tag, tags_before.contains(tag));
+    }
 
-    for tag in &dataset.tags {
+    // Re-apply auto-tagging rules, since the tags above may have replaced
+    // earlier ones. Their tags are the emitter's to replace next time.
+    let rules = auto_tagging::load_active_rules(tx)?;
+    let outcome = auto_tagging::apply_rules(tx, &rules, dataset_id)?;
+    merge_strategy::mark_emitted(tx, dataset_id, &outcome.tags_added)?;
+    if !outcome.rules_matched.is_empty() {
+        tracing::debug!(
+            dataset = %dataset.name,
+            rules = ?outcome.rules_matched,
+            tags_added = ?outcome.tags_added,
+            "Applied auto-tagging rules"
+        );
+    }
+
+    // Record the emission for the activity timeline (catalogs from v1.17.0 on)
+    let has_emissions: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'dataset_emissions')",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_emissions {
         tx.execute(
-            "INSERT OR IGNORE INTO tags (dataset_id, tag) VALUES (?1, ?2)",
-            rusqlite::params![dataset_id, tag],
+            "INSERT INTO dataset_emissions (dataset_id, emitted_at, row_count, size_bytes, field_count) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                dataset_id,
+                dataset.last_updated.to_rfc3339(),
+                row_count,
+                size_bytes,
+                dataset.fields.len() as i64,
+            ],
         )?;
     }
 
@@ -384,7 +844,7 @@ fn write_dataset_tx(tx: &rusqlite::Transaction, dataset: &DatasetMeta) -> Result
     // Increment catalog version for optimistic concurrency control
     increment_catalog_version(tx)?;
 
-    Ok(())
+    Ok((dataset_id, report))
 }
 
 #[cfg(test)]
@@ -509,4 +969,567 @@ mod tests {
             .unwrap();
         assert_eq!(lineage_count, 1);
     }
+
+    #[tokio::test]
+    async fn test_emit_dataset_rejects_lineage_cycle() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let backend = LocalSqliteBackend::new(temp_file.path());
+        let emitter = Emitter::new(backend);
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        for (name, upstream) in [("a", vec![]), ("b", vec!["a".to_string()])] {
+            emitter
+                .emit_dataset(
+                    name,
+                    &format!("s3://bucket/{}", name),
+                    "parquet",
+                    None,
+                    None,
+                    None,
+                    None,
+                    schema.clone(),
+                    None,
+                    upstream,
+                    vec![],
+                )
+                .await
+                .unwrap();
+        }
+
+        // b -> a would close a -> b -> a
+        let err = emitter
+            .emit_dataset(
+                "a",
+                "s3://bucket/a",
+                "parquet",
+                None,
+                None,
+                None,
+                None,
+                schema,
+                None,
+                vec!["b".to_string()],
+                vec![],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::ValidationError(_)));
+        assert!(err.to_string().contains("a -> b -> a"));
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let lineage_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM lineage", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(lineage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_emit_dataset_with_placeholder_upstream() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let backend = LocalSqliteBackend::new(temp_file.path());
+        let emitter = Emitter::new(backend).with_lineage_mode(LineageMode::Placeholder);
+        {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            init_sqlite_schema(&conn).unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        }
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let emit = |name: &'static str, upstream: Vec<String>| {
+            let schema = schema.clone();
+            let emitter = &emitter;
+            async move {
+                emitter
+                    .emit_dataset(
+                        name,
+                        &format!("s3://bucket/{}", name),
+                        "parquet",
+                        None,
+                        None,
+                        None,
+                        None,
+                        schema,
+                        None,
+                        upstream,
+                        vec![],
+                    )
+                    .await
+                    .unwrap();
+            }
+        };
+        let upstream_status = || async {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            let lineage: i64 = conn
+                .query_row("SELECT COUNT(*) FROM lineage", [], |row| row.get(0))
+                .unwrap();
+            let status: String = conn
+                .query_row(
+                    "SELECT status FROM datasets WHERE name = 'upstream'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            (lineage, status)
+        };
+
+        // The downstream job runs first
+        emit("downstream", vec!["upstream".to_string()]).await;
+        assert_eq!(upstream_status().await, (1, "pending".to_string()));
+
+        emit("upstream", vec![]).await;
+        assert_eq!(upstream_status().await, (1, "active".to_string()));
+
+        // Strict mode for one call rejects the unknown upstream and writes nothing
+        let err = emitter
+            .emit_dataset_with_lineage_mode(
+                LineageMode::Strict,
+                "report",
+                "s3://bucket/report",
+                "parquet",
+                None,
+                None,
+                None,
+                None,
+                schema.clone(),
+                None,
+                vec!["missing".to_string()],
+                vec![],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::ValidationError(ref msg) if msg.contains("missing")));
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_emission_repairs_skipped_lineage() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let backend = LocalSqliteBackend::new(temp_file.path());
+        let emitter = Emitter::new(backend);
+        {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            init_sqlite_schema(&conn).unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        }
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        for (name, upstream) in [
+            ("downstream", vec!["upstream".to_string()]),
+            ("upstream", vec![]),
+            ("downstream", vec!["upstream".to_string()]),
+        ] {
+            emitter
+                .emit_dataset(
+                    name,
+                    &format!("s3://bucket/{}", name),
+                    "parquet",
+                    None,
+                    None,
+                    None,
+                    None,
+                    schema.clone(),
+                    None,
+                    upstream,
+                    vec![],
+                )
+                .await
+                .unwrap();
+        }
+
+        // The second downstream emission is unchanged but still adds the edge
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let lineage: i64 = conn
+            .query_row("SELECT COUNT(*) FROM lineage", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(lineage, 1);
+    }
+
+    #[tokio::test]
+    async fn test_emit_dataset_reports_duplicates_and_skips() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()))
+            .with_write_mode(WriteMode::SkipUnchanged);
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let emit = |name: &'static str, upstream: Vec<&str>, tags: Vec<&str>| {
+            emitter.emit_dataset(
+                name,
+                "s3://bucket/data",
+                "parquet",
+                None,
+                None,
+                None,
+                None,
+                schema.clone(),
+                None,
+                upstream.into_iter().map(String::from).collect(),
+                tags.into_iter().map(String::from).collect(),
+            )
+        };
+
+        let report = emit("raw_orders", vec![], vec!["raw"]).await.unwrap();
+        assert_eq!(report.tags.inserted, vec!["raw"]);
+        assert!(!report.has_warnings());
+
+        let upstream = vec!["raw_orders", "missing", "raw_orders"];
+        let tags = vec!["daily", "daily", "pii"];
+        let report = emit("orders", upstream.clone(), tags.clone())
+            .await
+            .unwrap();
+        assert_eq!(report.upstreams.inserted, vec!["raw_orders"]);
+        assert_eq!(report.upstreams.skipped, vec!["missing"]);
+        assert_eq!(report.upstreams.deduplicated, vec!["raw_orders"]);
+        assert_eq!(report.tags.inserted, vec!["daily", "pii"]);
+        assert_eq!(report.tags.deduplicated, vec!["daily"]);
+
+        // Skipped as unchanged, but still reported
+        let again = emit("orders", upstream, tags).await.unwrap();
+        assert_eq!(again.upstreams.unchanged, vec!["raw_orders"]);
+        assert_eq!(again.upstreams.skipped, vec!["missing"]);
+        assert_eq!(again.tags.unchanged, vec!["daily", "pii"]);
+        assert_eq!(again.tags.deduplicated, vec!["daily"]);
+    }
+
+    #[tokio::test]
+    async fn test_emit_dataset_applies_auto_tag_rules() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let backend = LocalSqliteBackend::new(temp_file.path());
+        // Full writes, so the second emission rewrites the tags
+        let emitter = Emitter::new(backend).with_write_mode(WriteMode::Full);
+
+        {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            init_sqlite_schema(&conn).unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+            auto_tagging::create_rule(
+                &conn,
+                &auto_tagging::NewAutoTagRule {
+                    name: "email_columns".to_string(),
+                    description: None,
+                    path_pattern: None,
+                    column_pattern: Some("*email*".to_string()),
+                    set_domain: None,
+                    add_tags: vec!["pii-candidate".to_string()],
+                    priority: auto_tagging::DEFAULT_PRIORITY,
+                    is_active: true,
+                },
+            )
+            .unwrap();
+        }
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "user_email",
+            DataType::Utf8,
+            true,
+        )]));
+
+        // Emitting twice must keep the rule's tag alongside the emitted ones
+        for _ in 0..2 {
+            emitter
+                .emit_dataset(
+                    "users",
+                    "s3://bucket/users",
+                    "parquet",
+                    None,
+                    None,
+                    None,
+                    None,
+                    schema.clone(),
+                    None,
+                    vec![],
+                    vec!["app".to_string()],
+                )
+                .await
+                .unwrap();
+        }
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let tags: Vec<String> = conn
+            .prepare("SELECT tag FROM tags ORDER BY tag")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(tags, vec!["app", "pii-candidate"]);
+
+        // Each emission is recorded for the activity timeline
+        let emissions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM dataset_emissions", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(emissions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_emit_unchanged_dataset_skips_write() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let backend = LocalSqliteBackend::new(temp_file.path());
+        let emitter = Emitter::new(backend);
+        {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            init_sqlite_schema(&conn).unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        }
+
+        async fn emit(emitter: &Emitter<LocalSqliteBackend>, tags: &[&str]) {
+            let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+            emitter
+                .emit_dataset(
+                    "orders",
+                    "s3://bucket/orders",
+                    "parquet",
+                    None,
+                    None,
+                    None,
+                    None,
+                    schema,
+                    None,
+                    vec![],
+                    tags.iter().map(|t| t.to_string()).collect(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // (last_updated, last_seen_at, timeline emissions)
+        async fn state(emitter: &Emitter<LocalSqliteBackend>) -> (String, String, i64) {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            conn.query_row(
+                "SELECT d.last_updated, s.last_seen_at, (SELECT COUNT(*) FROM dataset_emissions)
+                 FROM datasets d JOIN dataset_emission_state s ON s.dataset_id = d.id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+        }
+
+        emit(&emitter, &["b", "a"]).await;
+        let (updated, seen, emissions) = state(&emitter).await;
+        assert_eq!(emissions, 1);
+
+        // Tag order doesn't count as a change
+        emit(&emitter, &["a", "b"]).await;
+        let (updated_again, seen_again, emissions) = state(&emitter).await;
+        assert_eq!(updated_again, updated);
+        assert_ne!(seen_again, seen);
+        assert_eq!(emissions, 1);
+
+        // A changed dataset is written
+        emit(&emitter, &["a"]).await;
+        let (updated_changed, _, emissions) = state(&emitter).await;
+        assert_ne!(updated_changed, updated);
+        assert_eq!(emissions, 2);
+
+        // Full mode rewrites unchanged datasets
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()))
+            .with_write_mode(WriteMode::Full);
+        emit(&emitter, &["a"]).await;
+        assert_eq!(state(&emitter).await.2, 3);
+    }
+
+    #[tokio::test]
+    async fn test_emit_dataset_runs_write_hooks() {
+        use metafuse_catalog_core::hooks::{HookOptions, PathDenyHook};
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let backend = LocalSqliteBackend::new(temp_file.path());
+        let mut hooks = WriteHooks::new();
+        hooks.register(
+            Arc::new(PathDenyHook::new(vec!["s3://scratch/*".to_string()])),
+            HookOptions::default(),
+        );
+        let emitter = Emitter::new(backend).with_write_hooks(hooks);
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let err = emitter
+            .emit_dataset(
+                "scratch_data",
+                "s3://scratch/tmp",
+                "parquet",
+                None,
+                None,
+                None,
+                None,
+                schema,
+                None,
+                vec![],
+                vec![],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CatalogError::ValidationError(ref msg) if msg.contains("path-deny")));
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        init_sqlite_schema(&conn).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_emit_observer() {
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<(String, EmitOutcome)>>);
+
+        impl EmitObserver for Recorder {
+            fn on_emit(&self, dataset: &str, outcome: EmitOutcome, _duration: Duration) {
+                self.0.lock().unwrap().push((dataset.to_string(), outcome));
+            }
+        }
+
+        async fn emit(emitter: &Emitter<LocalSqliteBackend>, name: &str) -> Result<WriteReport> {
+            let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+            emitter
+                .emit_dataset(
+                    name,
+                    "s3://bucket/orders",
+                    "parquet",
+                    None,
+                    None,
+                    None,
+                    None,
+                    schema,
+                    None,
+                    vec![],
+                    vec![],
+                )
+                .await
+        }
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let emitter =
+            Emitter::new(LocalSqliteBackend::new(temp_file.path())).with_observer(recorder.clone());
+        {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            init_sqlite_schema(&conn).unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        }
+
+        emit(&emitter, "orders").await.unwrap();
+        emit(&emitter, "orders").await.unwrap();
+        assert!(emit(&emitter, "bad name!").await.is_err());
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()))
+            .with_write_mode(WriteMode::SkipUnchanged)
+            .with_observer(recorder.clone());
+        emit(&emitter, "orders").await.unwrap();
+
+        let events = recorder.0.lock().unwrap();
+        let outcomes: Vec<_> = events.iter().map(|(_, outcome)| *outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                EmitOutcome::Written,
+                EmitOutcome::Unchanged,
+                EmitOutcome::Failed,
+                EmitOutcome::Skipped
+            ]
+        );
+        assert_eq!(events[2].0, "bad name!");
+    }
+
+    #[tokio::test]
+    async fn test_emit_retries_upload_conflict() {
+        use metafuse_catalog_storage::CatalogDownload;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Mutex;
+
+        /// Local backend whose first upload loses to a concurrent writer
+        struct ConflictingBackend {
+            inner: LocalSqliteBackend,
+            downloads: AtomicU32,
+            conflicts: AtomicU32,
+        }
+
+        impl CatalogBackend for ConflictingBackend {
+            fn download(
+                &self,
+            ) -> Pin<Box<dyn Future<Output = Result<CatalogDownload>> + Send + '_>> {
+                self.downloads.fetch_add(1, Ordering::SeqCst);
+                self.inner.download()
+            }
+
+            fn upload<'a>(
+                &'a self,
+                download: &'a CatalogDownload,
+            ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+                if self.conflicts.load(Ordering::SeqCst) > 0 {
+                    self.conflicts.fetch_sub(1, Ordering::SeqCst);
+                    return Box::pin(async {
+                        Err(CatalogError::ConflictError("generation changed".into()))
+                    });
+                }
+                self.inner.upload(download)
+            }
+
+            fn get_connection(
+                &self,
+            ) -> Pin<Box<dyn Future<Output = Result<Connection>> + Send + '_>> {
+                self.inner.get_connection()
+            }
+
+            fn exists(&self) -> Pin<Box<dyn Future<Output = Result<bool>> + Send + '_>> {
+                self.inner.exists()
+            }
+
+            fn initialize(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+                self.inner.initialize()
+            }
+        }
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<(String, u32)>>);
+
+        impl EmitObserver for Recorder {
+            fn on_conflict(&self, dataset: &str, retry: u32) {
+                self.0.lock().unwrap().push((dataset.to_string(), retry));
+            }
+        }
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let backend = ConflictingBackend {
+            inner: LocalSqliteBackend::new(temp_file.path()),
+            downloads: AtomicU32::new(0),
+            conflicts: AtomicU32::new(1),
+        };
+        let emitter = Emitter::new(backend).with_observer(recorder.clone());
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        emitter
+            .emit_dataset(
+                "orders",
+                "s3://bucket/orders",
+                "parquet",
+                None,
+                None,
+                None,
+                None,
+                schema,
+                None,
+                vec![],
+                vec![],
+            )
+            .await
+            .unwrap();
+
+        // The write was redone on a fresh download after the conflict
+        assert_eq!(emitter.backend().downloads.load(Ordering::SeqCst), 2);
+        assert_eq!(*recorder.0.lock().unwrap(), vec![("orders".to_string(), 1)]);
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
 }
@@ -0,0 +1,68 @@
+//! Emission Metrics
+//!
+//! An [`EmitObserver`] set with [`Emitter::with_observer`](crate::Emitter::with_observer)
+//! receives an event for every emission and every retry, so jobs can export
+//! them to their own telemetry:
+//!
+//! ```ignore
+//! struct Prometheus { /* counters and histograms */ }
+//!
+//! impl EmitObserver for Prometheus {
+//!     fn on_emit(&self, _dataset: &str, outcome: EmitOutcome, duration: Duration) {
+//!         self.emits.with_label_values(&[outcome.as_str()]).inc();
+//!         self.duration.observe(duration.as_secs_f64());
+//!     }
+//!
+//!     fn on_conflict(&self, _dataset: &str, _retry: u32) {
+//!         self.conflicts.inc();
+//!     }
+//! }
+//!
+//! let emitter = Emitter::new(backend).with_observer(Arc::new(Prometheus::new()));
+//! ```
+//!
+//! Observers are called inline, including from the blocking thread that
+//! writes the catalog, so they should only record and return.
+
+use std::time::Duration;
+
+/// Result of an emission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitOutcome {
+    /// The dataset was written.
+    Written,
+    /// The metadata was unchanged and only recorded as seen.
+    Unchanged,
+    /// The metadata was unchanged and the catalog was not touched.
+    Skipped,
+    /// The emission failed, including validation and hook rejections.
+    Failed,
+}
+
+impl EmitOutcome {
+    /// Lowercase name, for use as a metric label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmitOutcome::Written => "written",
+            EmitOutcome::Unchanged => "unchanged",
+            EmitOutcome::Skipped => "skipped",
+            EmitOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// Receives events from the emitter, e.g. to export metrics.
+///
+/// All methods default to doing nothing.
+pub trait EmitObserver: Send + Sync {
+    /// An emission finished after `duration`, including validation, hooks,
+    /// and any retries.
+    fn on_emit(&self, _dataset: &str, _outcome: EmitOutcome, _duration: Duration) {}
+
+    /// The catalog changed during an upload, which is retried from a fresh
+    /// download. `retry` starts at 1.
+    fn on_conflict(&self, _dataset: &str, _retry: u32) {}
+
+    /// The catalog was locked by another writer, and the write is retried.
+    fn on_busy_retry(&self, _dataset: &str) {}
+}
@@ -0,0 +1,306 @@
+//! Lineage Capture from DataFusion Plans
+//!
+//! [`Emitter::observe`] runs a DataFrame and emits the dataset it wrote, with
+//! the tables it read as upstreams, so pipelines don't call
+//! [`Emitter::emit_dataset`] by hand after every query:
+//!
+//! ```ignore
+//! let df = ctx.sql("INSERT INTO daily_orders SELECT * FROM orders JOIN customers USING (id)").await?;
+//! let batches = emitter.observe(df).await?;
+//! // daily_orders is registered with upstreams orders and customers
+//! ```
+//!
+//! Upstreams are the tables scanned anywhere in the logical plan, including
+//! subqueries, named as they are registered in the `SessionContext`. The
+//! output is the target of `INSERT INTO` or `COPY ... TO`:
+//!
+//! - `INSERT INTO` a listing table (`CREATE EXTERNAL TABLE`) records the
+//!   table's location, format, and partition columns
+//! - `COPY ... TO` is named after the last segment of its location, without
+//!   the file extension
+//!
+//! Outputs without a location or in a format the catalog doesn't know, such
+//! as in-memory tables, are not emitted. Plain queries emit nothing.
+
+use crate::Emitter;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::default_table_source::source_as_provider;
+use datafusion::common::tree_node::TreeNodeRecursion;
+use datafusion::dataframe::DataFrame;
+use datafusion::datasource::listing::ListingTable;
+use datafusion::logical_expr::{LogicalPlan, WriteOp};
+use metafuse_catalog_core::{formats, CatalogError, OperationalMeta, Result};
+use metafuse_catalog_storage::CatalogBackend;
+use std::sync::Arc;
+
+/// Tables a plan reads and the dataset it writes
+#[derive(Debug, Clone, Default)]
+pub struct PlanLineage {
+    /// Scanned tables, in plan order without duplicates
+    pub inputs: Vec<String>,
+    /// Written dataset, if the plan writes one
+    pub output: Option<PlanOutput>,
+}
+
+/// Dataset written by a plan
+#[derive(Debug, Clone)]
+pub struct PlanOutput {
+    pub name: String,
+    /// Storage location, when known
+    pub path: Option<String>,
+    /// Catalog format name, when the catalog knows the format
+    pub format: Option<String>,
+    pub partition_keys: Vec<String>,
+    pub schema: SchemaRef,
+}
+
+impl PlanOutput {
+    /// Whether the output has what the catalog needs to register it
+    pub fn is_located(&self) -> bool {
+        self.path.is_some() && self.format.is_some()
+    }
+}
+
+/// Extract the lineage of a logical plan.
+pub fn plan_lineage(plan: &LogicalPlan) -> PlanLineage {
+    let mut lineage = PlanLineage::default();
+    plan.apply_with_subqueries(|node| {
+        match node {
+            LogicalPlan::TableScan(scan) => {
+                let name = scan.table_name.to_string();
+                if !lineage.inputs.contains(&name) {
+                    lineage.inputs.push(name);
+                }
+            }
+            LogicalPlan::Dml(dml)
+                if lineage.output.is_none()
+                    && matches!(dml.op, WriteOp::Insert(_) | WriteOp::Ctas) =>
+            {
+                let listing = source_as_provider(&dml.target).ok().and_then(|provider| {
+                    provider
+                        .as_any()
+                        .downcast_ref::<ListingTable>()
+                        .map(|table| {
+                            (
+                                table.table_paths().first().map(|p| p.to_string()),
+                                known_format(&table.options().format.get_ext()),
+                                table
+                                    .options()
+                                    .table_partition_cols
+                                    .iter()
+                                    .map(|(name, _)| name.clone())
+                                    .collect(),
+                            )
+                        })
+                });
+                let (path, format, partition_keys) = listing.unwrap_or_default();
+                lineage.output = Some(PlanOutput {
+                    name: dml.table_name.to_string(),
+                    path,
+                    format,
+                    partition_keys,
+                    schema: dml.target.schema(),
+                });
+            }
+            LogicalPlan::Copy(copy) if lineage.output.is_none() => {
+                let location = copy.output_url.trim_end_matches('/');
+                let file = location.rsplit('/').next().unwrap_or(location);
+                let name = file.split('.').next().unwrap_or(file);
+                lineage.output = Some(PlanOutput {
+                    name: name.to_string(),
+                    path: Some(copy.output_url.clone()),
+                    format: known_format(&copy.file_type.get_ext()),
+                    partition_keys: copy.partition_by.clone(),
+                    schema: Arc::new(copy.input.schema().as_arrow().clone()),
+                });
+            }
+            _ => {}
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })
+    .expect("lineage visitor never fails");
+
+    // A table that is read and overwritten is not its own upstream
+    if let Some(output) = &lineage.output {
+        lineage.inputs.retain(|input| input != &output.name);
+    }
+    lineage
+}
+
+/// Catalog format for a DataFusion file extension, if the catalog knows it
+fn known_format(ext: &str) -> Option<String> {
+    formats::lookup_format(ext.trim_start_matches('.')).map(|spec| spec.name.to_string())
+}
+
+impl<B: CatalogBackend> Emitter<B> {
+    /// Execute a DataFrame and emit the dataset it wrote, with the scanned
+    /// tables as upstreams
+    ///
+    /// Returns the query's batches. Queries that don't write a located
+    /// dataset are only executed. If the emission fails, the query has
+    /// already run.
+    pub async fn observe(&self, df: DataFrame) -> Result<Vec<RecordBatch>> {
+        let lineage = plan_lineage(df.logical_plan());
+        let batches = df
+            .collect()
+            .await
+            .map_err(|e| CatalogError::Other(format!("Query failed: {}", e)))?;
+        self.emit_plan_lineage(lineage).await?;
+        Ok(batches)
+    }
+
+    /// Emit the output of a plan's lineage
+    ///
+    /// Returns the emitted dataset name, or `None` when there is no located
+    /// output to emit.
+    pub async fn emit_plan_lineage(&self, lineage: PlanLineage) -> Result<Option<String>> {
+        let Some(output) = lineage.output.filter(PlanOutput::is_located) else {
+            tracing::debug!("Plan wrote no located dataset; nothing to emit");
+            return Ok(None);
+        };
+        let operational = (!output.partition_keys.is_empty()).then(|| OperationalMeta {
+            row_count: None,
+            size_bytes: None,
+            partition_keys: output.partition_keys.clone(),
+        });
+        self.emit_dataset(
+            &output.name,
+            output.path.as_deref().unwrap_or_default(),
+            output.format.as_deref().unwrap_or_default(),
+            None,
+            None,
+            None,
+            None,
+            output.schema,
+            operational,
+            lineage.inputs,
+            vec![],
+        )
+        .await?;
+        Ok(Some(output.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::prelude::SessionContext;
+    use metafuse_catalog_storage::LocalSqliteBackend;
+    use tempfile::{NamedTempFile, TempDir};
+
+    async fn context(dir: &TempDir) -> SessionContext {
+        let ctx = SessionContext::new();
+        ctx.sql("CREATE TABLE orders (id INT, customer_id INT) AS VALUES (1, 10), (2, 20)")
+            .await
+            .unwrap();
+        ctx.sql("CREATE TABLE customers (id INT, region VARCHAR) AS VALUES (10, 'eu')")
+            .await
+            .unwrap();
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE regional_orders (id INT, region VARCHAR)
+             STORED AS PARQUET PARTITIONED BY (region) LOCATION '{}/regional_orders/'",
+            dir.path().display()
+        ))
+        .await
+        .unwrap();
+        ctx
+    }
+
+    #[tokio::test]
+    async fn test_plan_lineage() {
+        let dir = TempDir::new().unwrap();
+        let ctx = context(&dir).await;
+
+        let df = ctx
+            .sql(
+                "INSERT INTO regional_orders
+                 SELECT o.id, c.region FROM orders o JOIN customers c ON o.customer_id = c.id
+                 WHERE o.id IN (SELECT id FROM orders)",
+            )
+            .await
+            .unwrap();
+        let lineage = plan_lineage(df.logical_plan());
+        assert_eq!(lineage.inputs, vec!["orders", "customers"]);
+        let output = lineage.output.unwrap();
+        assert_eq!(output.name, "regional_orders");
+        assert!(output.path.unwrap().ends_with("/regional_orders/"));
+        assert_eq!(output.format.as_deref(), Some("parquet"));
+        assert_eq!(output.partition_keys, vec!["region"]);
+
+        let df = ctx
+            .sql(&format!(
+                "COPY (SELECT id FROM orders) TO '{}/order_ids.csv' STORED AS CSV",
+                dir.path().display()
+            ))
+            .await
+            .unwrap();
+        let lineage = plan_lineage(df.logical_plan());
+        assert_eq!(lineage.inputs, vec!["orders"]);
+        let output = lineage.output.unwrap();
+        assert_eq!(output.name, "order_ids");
+        assert_eq!(output.format.as_deref(), Some("csv"));
+
+        // In-memory outputs have no location; reads write nothing
+        let df = ctx
+            .sql("INSERT INTO customers SELECT customer_id, 'us' FROM orders")
+            .await
+            .unwrap();
+        assert!(!plan_lineage(df.logical_plan()).output.unwrap().is_located());
+        let df = ctx.sql("SELECT * FROM orders").await.unwrap();
+        assert!(plan_lineage(df.logical_plan()).output.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_observe_emits_lineage() {
+        let dir = TempDir::new().unwrap();
+        let ctx = context(&dir).await;
+        let temp_file = NamedTempFile::new().unwrap();
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp_file.path()))
+            .with_lineage_mode(crate::LineageMode::Placeholder);
+        {
+            let conn = emitter.backend().get_connection().await.unwrap();
+            metafuse_catalog_core::init_sqlite_schema(&conn).unwrap();
+            metafuse_catalog_core::migrations::run_migrations(&conn).unwrap();
+        }
+
+        let df = ctx
+            .sql(
+                "INSERT INTO regional_orders
+                 SELECT o.id, c.region FROM orders o JOIN customers c ON o.customer_id = c.id",
+            )
+            .await
+            .unwrap();
+        let batches = emitter.observe(df).await.unwrap();
+        assert_eq!(batches[0].num_rows(), 1);
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let (format, partition_keys): (String, String) = conn
+            .query_row(
+                "SELECT format, partition_keys FROM datasets WHERE name = 'regional_orders'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(format, "parquet");
+        assert_eq!(partition_keys, r#"["region"]"#);
+        let mut stmt = conn
+            .prepare(
+                "SELECT u.name FROM lineage l
+                 JOIN datasets u ON u.id = l.upstream_dataset_id
+                 JOIN datasets d ON d.id = l.downstream_dataset_id
+                 WHERE d.name = 'regional_orders' ORDER BY u.name",
+            )
+            .unwrap();
+        let upstreams: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(upstreams, vec!["customers", "orders"]);
+
+        // Plain queries are only executed
+        let df = ctx.sql("SELECT * FROM orders").await.unwrap();
+        assert_eq!(emitter.observe(df).await.unwrap()[0].num_rows(), 2);
+    }
+}
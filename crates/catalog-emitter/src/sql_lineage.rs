@@ -0,0 +1,321 @@
+//! Lineage from SQL Text
+//!
+//! [`Emitter::emit_with_sql`] emits a dataset with the tables a query reads as
+//! upstreams, for jobs that run SQL without handing the emitter a DataFrame:
+//!
+//! ```ignore
+//! let sql = "INSERT INTO daily_orders SELECT * FROM lake.orders JOIN customers USING (id)";
+//! ctx.sql(sql).await?.collect().await?;
+//! emitter.emit_with_sql("daily_orders", "s3://lake/daily_orders/", "parquet", sql).await?;
+//! // daily_orders is registered with upstreams orders and customers
+//! ```
+//!
+//! Sources are the tables referenced anywhere in the statements, including
+//! subqueries and joins. Write targets (`INSERT INTO`, `CREATE TABLE ... AS`,
+//! `CREATE VIEW`) and names defined by `WITH` are not sources.
+//!
+//! A source is mapped to a registered dataset by its full name, then by
+//! dropping leading qualifiers (`datafusion.public.orders` tries
+//! `public.orders`, then `orders`), ignoring case. Sources that match no
+//! dataset are passed on as written and handled by the emitter's
+//! [`LineageMode`](crate::LineageMode).
+
+use crate::Emitter;
+use datafusion::sql::sqlparser::ast::{
+    Ident, ObjectName, ObjectNamePart, Query, Statement, TableObject, Visit, Visitor,
+};
+use datafusion::sql::sqlparser::dialect::GenericDialect;
+use datafusion::sql::sqlparser::parser::Parser;
+use metafuse_catalog_core::{field_ordinals, CatalogError, FieldMeta, Result};
+use metafuse_catalog_storage::CatalogBackend;
+use rusqlite::{Connection, OptionalExtension};
+use std::ops::ControlFlow;
+
+/// Collects referenced tables, write targets and CTE names
+#[derive(Default)]
+struct Relations {
+    referenced: Vec<String>,
+    targets: Vec<String>,
+    ctes: Vec<String>,
+}
+
+/// Dotted name of a table reference, or `None` for table functions
+fn dotted(name: &ObjectName) -> Option<String> {
+    let parts = name
+        .0
+        .iter()
+        .map(|part| match part {
+            ObjectNamePart::Identifier(Ident { value, .. }) => Some(value.as_str()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("."))
+}
+
+impl Visitor for Relations {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        if let Some(with) = &query.with {
+            self.ctes.extend(
+                with.cte_tables
+                    .iter()
+                    .map(|cte| cte.alias.name.value.to_lowercase()),
+            );
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<()> {
+        self.referenced.extend(dotted(relation));
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_statement(&mut self, statement: &Statement) -> ControlFlow<()> {
+        let target = match statement {
+            Statement::Insert(insert) => match &insert.table {
+                TableObject::TableName(name) => Some(name),
+                _ => None,
+            },
+            Statement::CreateTable(create) => Some(&create.name),
+            Statement::CreateView { name, .. } => Some(name),
+            _ => None,
+        };
+        self.targets.extend(target.and_then(dotted));
+        ControlFlow::Continue(())
+    }
+}
+
+/// Tables a SQL string reads, in order of first reference without duplicates
+///
+/// Returns a validation error if the SQL doesn't parse.
+pub fn sql_sources(sql: &str) -> Result<Vec<String>> {
+    let statements = Parser::parse_sql(&GenericDialect {}, sql)
+        .map_err(|e| CatalogError::ValidationError(format!("Invalid SQL: {}", e)))?;
+    let mut relations = Relations::default();
+    let _ = statements.visit(&mut relations);
+
+    let mut sources: Vec<String> = Vec::new();
+    for name in relations.referenced {
+        let is_cte = !name.contains('.') && relations.ctes.contains(&name.to_lowercase());
+        if is_cte || relations.targets.contains(&name) || sources.contains(&name) {
+            continue;
+        }
+        sources.push(name);
+    }
+    Ok(sources)
+}
+
+/// Filter for datasets outside the trash
+///
+/// `deleted_at` arrives with migration v1.13.0; older catalogs have no trash.
+fn live_filter(conn: &Connection) -> rusqlite::Result<&'static str> {
+    let has_trash: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM pragma_table_info('datasets') WHERE name = 'deleted_at')",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(if has_trash {
+        "AND deleted_at IS NULL"
+    } else {
+        ""
+    })
+}
+
+/// Registered dataset a source refers to, or the source as written
+///
+/// Tries the full name and then each shorter suffix, preferring an exact
+/// match over a case-insensitive one.
+fn resolve_source(conn: &Connection, live: &str, source: &str) -> rusqlite::Result<String> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT name FROM datasets
+         WHERE name = ?1 COLLATE NOCASE {}
+         ORDER BY name = ?1 DESC, name
+         LIMIT 1",
+        live
+    ))?;
+    let mut candidate = source;
+    loop {
+        if let Some(name) = stmt
+            .query_row([candidate], |row| row.get::<_, String>(0))
+            .optional()?
+        {
+            return Ok(name);
+        }
+        match candidate.split_once('.') {
+            Some((_, rest)) => candidate = rest,
+            None => return Ok(source.to_string()),
+        }
+    }
+}
+
+/// Fields of a live dataset in catalog order, empty if it isn't registered
+fn registered_fields(conn: &Connection, live: &str, name: &str) -> Result<Vec<FieldMeta>> {
+    let order_by = if field_ordinals::has_ordinal_column(conn)? {
+        field_ordinals::ORDER_BY
+    } else {
+        "id"
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT name, data_type, nullable, description FROM fields
+         WHERE dataset_id = (SELECT id FROM datasets WHERE name = ?1 {})
+         ORDER BY {}",
+        live, order_by
+    ))?;
+    let fields = stmt
+        .query_map([name], |row| {
+            Ok(FieldMeta {
+                name: row.get(0)?,
+                data_type: row.get(1)?,
+                nullable: row.get(2)?,
+                description: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(fields)
+}
+
+impl<B: CatalogBackend> Emitter<B> {
+    /// Emit a dataset with the tables a SQL string reads as upstreams
+    ///
+    /// SQL text carries no schema, so a dataset that is already registered
+    /// keeps its fields and a new one is registered without any. The dataset
+    /// itself is never its own upstream. Returns the upstreams it was emitted
+    /// with.
+    pub async fn emit_with_sql(
+        &self,
+        name: &str,
+        path: &str,
+        format: &str,
+        sql: &str,
+    ) -> Result<Vec<String>> {
+        let sources = sql_sources(sql)?;
+        let download = self.backend.download().await?;
+        let dataset = name.to_string();
+        let (upstreams, fields) =
+            tokio::task::spawn_blocking(move || -> Result<(Vec<String>, Vec<FieldMeta>)> {
+                let conn = Connection::open(&download.path)?;
+                let live = live_filter(&conn)?;
+                let mut upstreams: Vec<String> = Vec::new();
+                for source in &sources {
+                    let upstream = resolve_source(&conn, live, source)?;
+                    if upstream != dataset && !upstreams.contains(&upstream) {
+                        upstreams.push(upstream);
+                    }
+                }
+                Ok((upstreams, registered_fields(&conn, live, &dataset)?))
+            })
+            .await
+            .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        tracing::debug!(dataset = %name, upstreams = ?upstreams, "Resolved SQL lineage");
+        self.emit_observed(
+            self.lineage_mode,
+            name,
+            path,
+            format,
+            None,
+            None,
+            None,
+            None,
+            fields,
+            None,
+            upstreams.clone(),
+            vec![],
+        )
+        .await?;
+        Ok(upstreams)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use metafuse_catalog_storage::LocalSqliteBackend;
+    use std::sync::Arc;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_sql_sources() {
+        let sources = sql_sources(
+            "WITH recent AS (SELECT * FROM lake.orders WHERE ts > now())
+             INSERT INTO daily_orders
+             SELECT r.id, c.region FROM recent r
+             JOIN customers c ON r.customer_id = c.id
+             WHERE c.id IN (SELECT id FROM vip) AND r.id NOT IN (SELECT id FROM customers)",
+        )
+        .unwrap();
+        assert_eq!(sources, vec!["lake.orders", "customers", "vip"]);
+
+        let sources =
+            sql_sources("CREATE TABLE t AS SELECT * FROM a UNION ALL SELECT * FROM b").unwrap();
+        assert_eq!(sources, vec!["a", "b"]);
+
+        assert!(sql_sources("SELECT * FROM").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_emit_with_sql() {
+        let temp = NamedTempFile::new().unwrap();
+        let emitter = Emitter::new(LocalSqliteBackend::new(temp.path()));
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        for name in ["orders", "Customers", "daily_orders"] {
+            emitter
+                .emit_dataset(
+                    name,
+                    &format!("/data/{}", name),
+                    "parquet",
+                    None,
+                    None,
+                    None,
+                    None,
+                    schema.clone(),
+                    None,
+                    vec![],
+                    vec![],
+                )
+                .await
+                .unwrap();
+        }
+
+        let upstreams = emitter
+            .emit_with_sql(
+                "daily_orders",
+                "/data/daily_orders",
+                "parquet",
+                "INSERT INTO daily_orders
+                 SELECT * FROM datafusion.public.orders JOIN customers USING (id)
+                 WHERE id NOT IN (SELECT id FROM daily_orders) AND id IN (SELECT id FROM unknown)",
+            )
+            .await
+            .unwrap();
+        assert_eq!(upstreams, vec!["orders", "Customers", "unknown"]);
+
+        let conn = emitter.backend().get_connection().await.unwrap();
+        let linked: Vec<String> = conn
+            .prepare(
+                "SELECT u.name FROM lineage l
+                 JOIN datasets u ON u.id = l.upstream_dataset_id
+                 JOIN datasets d ON d.id = l.downstream_dataset_id
+                 WHERE d.name = 'daily_orders' ORDER BY u.name",
+            )
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(linked, vec!["Customers", "orders"]);
+
+        // Registered fields are kept
+        let fields: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM fields f JOIN datasets d ON d.id = f.dataset_id
+                 WHERE d.name = 'daily_orders' AND f.name = 'id'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(fields, 1);
+    }
+}
@@ -0,0 +1,242 @@
+//! Stable error codes for the MetaFuse API.
+//!
+//! Every error payload returned by the API server carries a machine-readable
+//! `code` next to the human-readable `error` message:
+//!
+//! ```json
+//! { "error": "Dataset 'orders' not found", "code": "DATASET_NOT_FOUND", "request_id": "..." }
+//! ```
+//!
+//! Messages may change wording or be translated; codes don't. Clients should
+//! branch on the code. The server and the client SDK both use [`ErrorCode`],
+//! so they can't drift apart.
+//!
+//! # Stability
+//!
+//! A code's string and meaning never change once released. New codes may be
+//! added, so clients must handle codes they don't know: they deserialize as
+//! [`ErrorCode::Unknown`], and the HTTP status still applies.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Machine-readable error code included in every API error payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The request was malformed or failed validation (400)
+    ValidationFailed,
+    /// No valid credentials were provided (401)
+    Unauthorized,
+    /// The caller may not perform this operation (403)
+    Forbidden,
+    /// The tenant's quota doesn't allow the operation (403)
+    QuotaExceeded,
+    /// The tenant is suspended or otherwise not active (403)
+    TenantSuspended,
+    /// A resource other than a dataset or tenant doesn't exist (404)
+    NotFound,
+    /// The dataset doesn't exist, or was deleted (404)
+    DatasetNotFound,
+    /// The tenant doesn't exist (404)
+    TenantNotFound,
+    /// The route doesn't support the HTTP method (405)
+    MethodNotAllowed,
+    /// The resource already exists or was modified concurrently (409)
+    Conflict,
+    /// An `If-Match` precondition didn't hold (412)
+    PreconditionFailed,
+    /// The request body is too large (413)
+    PayloadTooLarge,
+    /// The request body has an unsupported media type (415)
+    UnsupportedMediaType,
+    /// Too many requests; retry after the `Retry-After` delay (429)
+    RateLimited,
+    /// The server failed unexpectedly (500)
+    InternalError,
+    /// A dependency is unavailable or overloaded; retry later (503)
+    ServiceUnavailable,
+    /// The request ran past its route's time budget and was cancelled (504)
+    Timeout,
+    /// A code this version doesn't know, sent by a newer server
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// All codes sent by the server (everything but [`ErrorCode::Unknown`]).
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::ValidationFailed,
+        ErrorCode::Unauthorized,
+        ErrorCode::Forbidden,
+        ErrorCode::QuotaExceeded,
+        ErrorCode::TenantSuspended,
+        ErrorCode::NotFound,
+        ErrorCode::DatasetNotFound,
+        ErrorCode::TenantNotFound,
+        ErrorCode::MethodNotAllowed,
+        ErrorCode::Conflict,
+        ErrorCode::PreconditionFailed,
+        ErrorCode::PayloadTooLarge,
+        ErrorCode::UnsupportedMediaType,
+        ErrorCode::RateLimited,
+        ErrorCode::InternalError,
+        ErrorCode::ServiceUnavailable,
+        ErrorCode::Timeout,
+    ];
+
+    /// The code as it appears in payloads.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ValidationFailed => "VALIDATION_FAILED",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::QuotaExceeded => "QUOTA_EXCEEDED",
+            ErrorCode::TenantSuspended => "TENANT_SUSPENDED",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::DatasetNotFound => "DATASET_NOT_FOUND",
+            ErrorCode::TenantNotFound => "TENANT_NOT_FOUND",
+            ErrorCode::MethodNotAllowed => "METHOD_NOT_ALLOWED",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::PreconditionFailed => "PRECONDITION_FAILED",
+            ErrorCode::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ErrorCode::UnsupportedMediaType => "UNSUPPORTED_MEDIA_TYPE",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::Unknown => "UNKNOWN",
+        }
+    }
+
+    /// HTTP status the server sends with this code, if fixed.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            ErrorCode::ValidationFailed => Some(400),
+            ErrorCode::Unauthorized => Some(401),
+            ErrorCode::Forbidden | ErrorCode::QuotaExceeded | ErrorCode::TenantSuspended => {
+                Some(403)
+            }
+            ErrorCode::NotFound | ErrorCode::DatasetNotFound | ErrorCode::TenantNotFound => {
+                Some(404)
+            }
+            ErrorCode::MethodNotAllowed => Some(405),
+            ErrorCode::Conflict => Some(409),
+            ErrorCode::PreconditionFailed => Some(412),
+            ErrorCode::PayloadTooLarge => Some(413),
+            ErrorCode::UnsupportedMediaType => Some(415),
+            ErrorCode::RateLimited => Some(429),
+            ErrorCode::InternalError => Some(500),
+            ErrorCode::ServiceUnavailable => Some(503),
+            ErrorCode::Timeout => Some(504),
+            ErrorCode::Unknown => None,
+        }
+    }
+
+    /// The generic code for an HTTP error status, for payloads without one.
+    ///
+    /// Other client errors map to `VALIDATION_FAILED` and other server errors
+    /// to `INTERNAL_ERROR`; non-error statuses are `UNKNOWN`.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            405 => ErrorCode::MethodNotAllowed,
+            409 => ErrorCode::Conflict,
+            412 => ErrorCode::PreconditionFailed,
+            413 => ErrorCode::PayloadTooLarge,
+            415 => ErrorCode::UnsupportedMediaType,
+            429 => ErrorCode::RateLimited,
+            503 => ErrorCode::ServiceUnavailable,
+            504 => ErrorCode::Timeout,
+            400..=499 => ErrorCode::ValidationFailed,
+            500..=599 => ErrorCode::InternalError,
+            _ => ErrorCode::Unknown,
+        }
+    }
+
+    /// Whether retrying the same request later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::RateLimited | ErrorCode::ServiceUnavailable | ErrorCode::Timeout
+        )
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Released codes. Never edit a row: codes are part of the API contract.
+    const RELEASED: &[(ErrorCode, &str, u16)] = &[
+        (ErrorCode::ValidationFailed, "VALIDATION_FAILED", 400),
+        (ErrorCode::Unauthorized, "UNAUTHORIZED", 401),
+        (ErrorCode::Forbidden, "FORBIDDEN", 403),
+        (ErrorCode::QuotaExceeded, "QUOTA_EXCEEDED", 403),
+        (ErrorCode::TenantSuspended, "TENANT_SUSPENDED", 403),
+        (ErrorCode::NotFound, "NOT_FOUND", 404),
+        (ErrorCode::DatasetNotFound, "DATASET_NOT_FOUND", 404),
+        (ErrorCode::TenantNotFound, "TENANT_NOT_FOUND", 404),
+        (ErrorCode::MethodNotAllowed, "METHOD_NOT_ALLOWED", 405),
+        (ErrorCode::Conflict, "CONFLICT", 409),
+        (ErrorCode::PreconditionFailed, "PRECONDITION_FAILED", 412),
+        (ErrorCode::PayloadTooLarge, "PAYLOAD_TOO_LARGE", 413),
+        (
+            ErrorCode::UnsupportedMediaType,
+            "UNSUPPORTED_MEDIA_TYPE",
+            415,
+        ),
+        (ErrorCode::RateLimited, "RATE_LIMITED", 429),
+        (ErrorCode::InternalError, "INTERNAL_ERROR", 500),
+        (ErrorCode::ServiceUnavailable, "SERVICE_UNAVAILABLE", 503),
+        (ErrorCode::Timeout, "TIMEOUT", 504),
+    ];
+
+    #[test]
+    fn test_released_codes_are_stable() {
+        for (code, name, status) in RELEASED {
+            assert_eq!(code.as_str(), *name);
+            assert_eq!(code.to_string(), *name);
+            assert_eq!(code.http_status(), Some(*status), "{}", name);
+            assert_eq!(serde_json::to_value(code).unwrap(), serde_json::json!(name));
+            assert_eq!(
+                serde_json::from_value::<ErrorCode>(serde_json::json!(name)).unwrap(),
+                *code
+            );
+        }
+        // Every code the server sends is covered above
+        assert_eq!(ErrorCode::ALL.len(), RELEASED.len());
+        for code in ErrorCode::ALL {
+            assert!(RELEASED.iter().any(|(released, _, _)| released == code));
+        }
+    }
+
+    #[test]
+    fn test_unknown_codes_deserialize() {
+        let code: ErrorCode = serde_json::from_str(r#""SOME_FUTURE_CODE""#).unwrap();
+        assert_eq!(code, ErrorCode::Unknown);
+        assert_eq!(code.http_status(), None);
+    }
+
+    #[test]
+    fn test_from_status() {
+        assert_eq!(ErrorCode::from_status(404), ErrorCode::NotFound);
+        assert_eq!(ErrorCode::from_status(429), ErrorCode::RateLimited);
+        assert_eq!(ErrorCode::from_status(422), ErrorCode::ValidationFailed);
+        assert_eq!(ErrorCode::from_status(502), ErrorCode::InternalError);
+        assert_eq!(ErrorCode::from_status(302), ErrorCode::Unknown);
+        for code in ErrorCode::ALL {
+            let generic = ErrorCode::from_status(code.http_status().unwrap());
+            assert_eq!(generic.http_status(), code.http_status());
+        }
+    }
+}
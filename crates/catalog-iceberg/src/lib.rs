@@ -0,0 +1,343 @@
+//! Apache Iceberg integration for MetaFuse catalog.
+//!
+//! This crate reads metadata from Iceberg tables: the current schema,
+//! partition specs, snapshots, and the table statistics Iceberg keeps in
+//! each snapshot's summary.
+//!
+//! # Architecture
+//!
+//! As for Delta, the principle is: **"Read from Iceberg, never store what
+//! Iceberg maintains."** Everything here comes from the table's current
+//! metadata file (`metadata/*.metadata.json`); manifests are not read.
+//!
+//! - Schema and partition columns → Current schema and default partition spec
+//! - Row counts, sizes, file counts → Summary of the current snapshot
+//! - History → Snapshots recorded in the metadata file
+//!
+//! # Locating Metadata
+//!
+//! A location is either a metadata file (ending in `.metadata.json`) or a
+//! table root. For a table root, `metadata/version-hint.text` names the
+//! current version when present (Hadoop-style tables); otherwise the metadata
+//! file with the highest version number is read. Tables managed by a catalog
+//! service (Glue, REST) should be given their current metadata file.
+//!
+//! # URL Formats
+//!
+//! - `file:///path/to/table` - Local file path with scheme
+//! - `/path/to/table` - Absolute local path (auto-prefixed with `file://`)
+//! - `gs://bucket/path` - Google Cloud Storage (`gcs` feature)
+//! - `s3://bucket/path` - Amazon S3 (`s3` feature)
+//!
+//! Cloud credentials are read from the usual `AWS_*` and `GOOGLE_*`
+//! environment variables.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use metafuse_catalog_iceberg::IcebergReader;
+//!
+//! let reader = IcebergReader::new();
+//! let metadata = reader.get_metadata("s3://lake/warehouse/orders").await?;
+//!
+//! println!("Schema: {:?}", metadata.schema);
+//! println!("Row count: {:?}", metadata.row_count);
+//! println!("Current snapshot: {:?}", metadata.current_snapshot_id);
+//! ```
+
+use chrono::{DateTime, Utc};
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use url::Url;
+
+mod spec;
+pub mod types;
+
+pub use types::{IcebergType, NestedField, NestedType};
+
+/// Errors that can occur when reading Iceberg metadata.
+#[derive(Error, Debug)]
+pub enum IcebergError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Iceberg table not found at '{0}'")]
+    NotFound(String),
+
+    #[error("Failed to read Iceberg metadata at '{0}': {1}")]
+    Read(String, String),
+
+    #[error("Invalid Iceberg metadata file '{0}': {1}")]
+    Parse(String, String),
+
+    #[error("Iceberg metadata has no schema with id {0}")]
+    NoSchema(i32),
+}
+
+/// Result type for Iceberg operations.
+pub type Result<T> = std::result::Result<T, IcebergError>;
+
+/// Schema field definition extracted from Iceberg.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Field {
+    /// Iceberg field ID
+    pub id: i32,
+    /// Field name
+    pub name: String,
+    /// Data type in Iceberg notation, e.g. `long` or `list<string>`
+    pub data_type: String,
+    /// Structured data type
+    pub field_type: IcebergType,
+    /// Whether the field allows nulls (not `required`)
+    pub nullable: bool,
+    /// Field documentation (`doc`)
+    pub description: Option<String>,
+    /// 0-based position in the schema
+    pub ordinal: usize,
+}
+
+/// Current schema of an Iceberg table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Schema {
+    /// Iceberg schema ID
+    pub schema_id: i32,
+    /// Top-level fields
+    pub fields: Vec<Field>,
+    /// Source columns of the default partition spec
+    pub partition_columns: Vec<String>,
+}
+
+/// A field of a partition spec.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PartitionField {
+    /// Partition field name, e.g. `ts_day`
+    pub name: String,
+    /// Transform, e.g. `identity`, `day`, or `bucket[16]`
+    pub transform: String,
+    /// Field ID of the source column
+    pub source_id: i32,
+    /// Name of the source column in the current schema, if it still exists
+    pub source_column: Option<String>,
+    /// Partition field ID (format v2)
+    pub field_id: Option<i32>,
+}
+
+/// A partition spec of an Iceberg table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PartitionSpec {
+    pub spec_id: i32,
+    pub fields: Vec<PartitionField>,
+}
+
+/// A snapshot in Iceberg table history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub snapshot_id: i64,
+    pub parent_snapshot_id: Option<i64>,
+    /// Sequence number; position in history for format v1 tables
+    pub sequence_number: i64,
+    /// When the snapshot was committed
+    pub timestamp: DateTime<Utc>,
+    /// Operation performed (append, overwrite, delete, replace)
+    pub operation: Option<String>,
+    /// Remaining summary properties (added-records, total-records, etc.)
+    pub summary: HashMap<String, String>,
+    /// Schema the snapshot was written with
+    pub schema_id: Option<i32>,
+    /// Location of the snapshot's manifest list
+    pub manifest_list: Option<String>,
+}
+
+/// Complete metadata extracted from an Iceberg table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcebergMetadata {
+    /// Iceberg format version (1 or 2)
+    pub format_version: i32,
+    pub table_uuid: Option<String>,
+    /// Table root location
+    pub location: String,
+    /// Metadata file that was read
+    pub metadata_location: String,
+    /// Current schema
+    pub schema: Schema,
+    pub partition_specs: Vec<PartitionSpec>,
+    pub default_spec_id: i32,
+    /// Current snapshot, if the table has one
+    pub current_snapshot_id: Option<i64>,
+    /// Total row count, from the current snapshot summary
+    pub row_count: Option<i64>,
+    /// Total size of data files in bytes, from the current snapshot summary
+    pub size_bytes: Option<i64>,
+    /// Number of data files, from the current snapshot summary
+    pub num_files: Option<i64>,
+    /// Last metadata update
+    pub last_modified: DateTime<Utc>,
+    /// Table properties
+    pub properties: HashMap<String, String>,
+    /// Snapshots, oldest first
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl IcebergMetadata {
+    /// The current snapshot, if the table has one.
+    pub fn current_snapshot(&self) -> Option<&Snapshot> {
+        let id = self.current_snapshot_id?;
+        self.snapshots.iter().find(|s| s.snapshot_id == id)
+    }
+}
+
+/// Iceberg table metadata reader.
+///
+/// Every call reads the current metadata file from storage; nothing is
+/// cached.
+#[derive(Debug, Clone, Default)]
+pub struct IcebergReader;
+
+impl IcebergReader {
+    /// Create a new reader.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Normalize a location to a URL string.
+    fn normalize_location(location: &str) -> Result<Url> {
+        let url = if location.contains("://") {
+            location.to_string()
+        } else if location.starts_with('/') || std::path::Path::new(location).is_absolute() {
+            format!("file://{}", location)
+        } else {
+            return Err(IcebergError::InvalidUrl(format!(
+                "Location must be a URL (file://, gs://, s3://) or an absolute path: {}",
+                location
+            )));
+        };
+        Url::parse(&url).map_err(|e| IcebergError::InvalidUrl(format!("{}: {}", location, e)))
+    }
+
+    /// Open the store holding a location, with the location's path in it.
+    fn store_for(url: &Url) -> Result<(Box<dyn ObjectStore>, Path)> {
+        let options = std::env::vars()
+            .filter(|(k, _)| k.starts_with("AWS_") || k.starts_with("GOOGLE_"))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v));
+        object_store::parse_url_opts(url, options)
+            .map_err(|e| IcebergError::InvalidUrl(format!("{}: {}", url, e)))
+    }
+
+    async fn read(store: &dyn ObjectStore, path: &Path, url: &Url) -> Result<Vec<u8>> {
+        let result = store.get(path).await.map_err(|e| match e {
+            object_store::Error::NotFound { .. } => IcebergError::NotFound(url.to_string()),
+            e => IcebergError::Read(url.to_string(), e.to_string()),
+        })?;
+        let bytes = result
+            .bytes()
+            .await
+            .map_err(|e| IcebergError::Read(url.to_string(), e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Current metadata file of the table rooted at `table`.
+    async fn find_metadata_file(store: &dyn ObjectStore, table: &Path, url: &Url) -> Result<Path> {
+        let metadata_dir = table.child("metadata");
+        let read_error =
+            |e: object_store::Error| IcebergError::Read(url.to_string(), e.to_string());
+
+        // Hadoop-style tables name the current version in a hint file
+        match store.get(&metadata_dir.child("version-hint.text")).await {
+            Ok(hint) => {
+                let hint = hint.bytes().await.map_err(read_error)?;
+                let hint = String::from_utf8_lossy(&hint).trim().to_string();
+                let file = if hint.ends_with(".metadata.json") {
+                    hint
+                } else {
+                    format!("v{}.metadata.json", hint)
+                };
+                return Ok(metadata_dir.child(file));
+            }
+            Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(read_error(e)),
+        }
+
+        let listing = store
+            .list_with_delimiter(Some(&metadata_dir))
+            .await
+            .map_err(read_error)?;
+        listing
+            .objects
+            .into_iter()
+            .filter_map(|o| {
+                let version = o.location.filename().and_then(metadata_version)?;
+                Some((version, o.location))
+            })
+            .max_by_key(|(version, _)| *version)
+            .map(|(_, path)| path)
+            .ok_or_else(|| IcebergError::NotFound(url.to_string()))
+    }
+
+    /// Get the current metadata of an Iceberg table.
+    pub async fn get_metadata(&self, location: &str) -> Result<IcebergMetadata> {
+        let url = Self::normalize_location(location)?;
+        let (store, path) = Self::store_for(&url)?;
+
+        let metadata_path = if path.as_ref().ends_with(".metadata.json") {
+            path
+        } else {
+            Self::find_metadata_file(store.as_ref(), &path, &url).await?
+        };
+        let mut metadata_url = url.clone();
+        metadata_url.set_path(&format!("/{}", metadata_path));
+
+        tracing::debug!(location = %location, metadata = %metadata_url, "Reading Iceberg metadata");
+        let bytes = Self::read(store.as_ref(), &metadata_path, &metadata_url).await?;
+        spec::TableMetadata::parse(metadata_url.as_str(), &bytes)?
+            .into_metadata(metadata_url.to_string())
+    }
+
+    /// Get the current schema of an Iceberg table.
+    pub async fn get_schema(&self, location: &str) -> Result<Schema> {
+        Ok(self.get_metadata(location).await?.schema)
+    }
+
+    /// Get snapshot history of an Iceberg table, newest first.
+    pub async fn get_history(&self, location: &str, limit: usize) -> Result<Vec<Snapshot>> {
+        let metadata = self.get_metadata(location).await?;
+        Ok(metadata.snapshots.into_iter().rev().take(limit).collect())
+    }
+}
+
+/// Version of a metadata file name: `v3.metadata.json` or
+/// `00003-<uuid>.metadata.json`.
+fn metadata_version(file_name: &str) -> Option<u64> {
+    let stem = file_name.strip_suffix(".metadata.json")?;
+    let stem = stem.strip_prefix('v').unwrap_or(stem);
+    stem.split('-').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_version() {
+        assert_eq!(metadata_version("v3.metadata.json"), Some(3));
+        assert_eq!(
+            metadata_version("00012-6c1c5a4e-5f4e-4b8b-9d3e-1f1e4b8b9d3e.metadata.json"),
+            Some(12)
+        );
+        assert_eq!(metadata_version("snap-1-1-abc.avro"), None);
+        assert_eq!(metadata_version("version-hint.text"), None);
+    }
+
+    #[test]
+    fn test_normalize_location() {
+        assert_eq!(
+            IcebergReader::normalize_location("/data/orders")
+                .unwrap()
+                .as_str(),
+            "file:///data/orders"
+        );
+        assert!(IcebergReader::normalize_location("data/orders").is_err());
+    }
+}
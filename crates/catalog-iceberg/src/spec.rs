@@ -0,0 +1,217 @@
+//! Iceberg table metadata files.
+//!
+//! Mirrors the parts of the `*.metadata.json` layout the catalog reads, for
+//! both format versions: v1 tables may carry a single `schema` and
+//! `partition-spec` instead of the `schemas` and `partition-specs` lists, and
+//! have no snapshot sequence numbers.
+
+use crate::types::NestedField;
+use crate::{
+    Field, IcebergError, IcebergMetadata, PartitionField, PartitionSpec, Result, Schema, Snapshot,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TableMetadata {
+    format_version: i32,
+    table_uuid: Option<String>,
+    location: String,
+    last_updated_ms: i64,
+    current_schema_id: Option<i32>,
+    #[serde(default)]
+    schemas: Vec<SchemaSpec>,
+    schema: Option<SchemaSpec>,
+    default_spec_id: Option<i32>,
+    #[serde(default)]
+    partition_specs: Vec<PartitionSpecSpec>,
+    partition_spec: Option<Vec<PartitionFieldSpec>>,
+    current_snapshot_id: Option<i64>,
+    #[serde(default)]
+    snapshots: Vec<SnapshotSpec>,
+    #[serde(default)]
+    properties: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SchemaSpec {
+    #[serde(default)]
+    schema_id: i32,
+    fields: Vec<NestedField>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionSpecSpec {
+    spec_id: i32,
+    fields: Vec<PartitionFieldSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionFieldSpec {
+    name: String,
+    transform: String,
+    source_id: i32,
+    field_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SnapshotSpec {
+    snapshot_id: i64,
+    parent_snapshot_id: Option<i64>,
+    sequence_number: Option<i64>,
+    timestamp_ms: i64,
+    manifest_list: Option<String>,
+    #[serde(default)]
+    summary: HashMap<String, String>,
+    schema_id: Option<i32>,
+}
+
+fn timestamp(ms: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(ms).unwrap_or_default()
+}
+
+impl TableMetadata {
+    /// Parse a metadata file's contents.
+    pub(crate) fn parse(metadata_location: &str, bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| IcebergError::Parse(metadata_location.to_string(), e.to_string()))
+    }
+
+    /// Convert into the catalog's view of the table.
+    pub(crate) fn into_metadata(self, metadata_location: String) -> Result<IcebergMetadata> {
+        let current_schema_id = self.current_schema_id.unwrap_or_default();
+        let schema_spec = match self.schema {
+            Some(schema) if self.schemas.is_empty() => schema,
+            _ => self
+                .schemas
+                .into_iter()
+                .find(|s| s.schema_id == current_schema_id)
+                .ok_or(IcebergError::NoSchema(current_schema_id))?,
+        };
+        let column_name = |id: i32| {
+            schema_spec
+                .fields
+                .iter()
+                .find(|f| f.id == id)
+                .map(|f| f.name.clone())
+        };
+
+        let mut partition_specs: Vec<PartitionSpec> = self
+            .partition_specs
+            .into_iter()
+            .map(|spec| (spec.spec_id, spec.fields))
+            .chain(self.partition_spec.map(|fields| (0, fields)))
+            .map(|(spec_id, fields)| PartitionSpec {
+                spec_id,
+                fields: fields
+                    .into_iter()
+                    .map(|f| PartitionField {
+                        source_column: column_name(f.source_id),
+                        name: f.name,
+                        transform: f.transform,
+                        source_id: f.source_id,
+                        field_id: f.field_id,
+                    })
+                    .collect(),
+            })
+            .collect();
+        partition_specs.dedup_by_key(|spec| spec.spec_id);
+        let default_spec_id = self.default_spec_id.unwrap_or_default();
+
+        // Columns the current spec partitions by; void transforms drop a field
+        let mut partition_columns: Vec<String> = Vec::new();
+        if let Some(spec) = partition_specs
+            .iter()
+            .find(|s| s.spec_id == default_spec_id)
+        {
+            for column in spec
+                .fields
+                .iter()
+                .filter(|f| f.transform != "void")
+                .filter_map(|f| f.source_column.clone())
+            {
+                if !partition_columns.contains(&column) {
+                    partition_columns.push(column);
+                }
+            }
+        }
+
+        let fields = schema_spec
+            .fields
+            .into_iter()
+            .enumerate()
+            .map(|(ordinal, f)| Field {
+                id: f.id,
+                name: f.name,
+                data_type: f.field_type.to_string(),
+                field_type: f.field_type,
+                nullable: !f.required,
+                description: f.doc,
+                ordinal,
+            })
+            .collect();
+
+        // Oldest first; v1 snapshots are numbered by position
+        let mut snapshots = self.snapshots;
+        snapshots.sort_by_key(|s| (s.sequence_number, s.timestamp_ms));
+        let snapshots: Vec<Snapshot> = snapshots
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let mut summary = s.summary;
+                Snapshot {
+                    snapshot_id: s.snapshot_id,
+                    parent_snapshot_id: s.parent_snapshot_id,
+                    sequence_number: s.sequence_number.unwrap_or(i as i64 + 1),
+                    timestamp: timestamp(s.timestamp_ms),
+                    operation: summary.remove("operation"),
+                    summary,
+                    schema_id: s.schema_id,
+                    manifest_list: s.manifest_list,
+                }
+            })
+            .collect();
+
+        let current = self
+            .current_snapshot_id
+            .filter(|id| *id != -1)
+            .and_then(|id| snapshots.iter().find(|s| s.snapshot_id == id));
+        let total = |key: &str| current.and_then(|s| s.summary.get(key)?.parse().ok());
+        let (row_count, size_bytes, num_files) = match current {
+            Some(_) => (
+                total("total-records"),
+                total("total-files-size"),
+                total("total-data-files"),
+            ),
+            // A table without snapshots is empty
+            None => (Some(0), Some(0), Some(0)),
+        };
+
+        Ok(IcebergMetadata {
+            format_version: self.format_version,
+            table_uuid: self.table_uuid,
+            location: self.location,
+            metadata_location,
+            schema: Schema {
+                schema_id: schema_spec.schema_id,
+                fields,
+                partition_columns,
+            },
+            partition_specs,
+            default_spec_id,
+            current_snapshot_id: current.map(|s| s.snapshot_id),
+            row_count,
+            size_bytes,
+            num_files,
+            last_modified: timestamp(self.last_updated_ms),
+            properties: self.properties,
+            snapshots,
+        })
+    }
+}
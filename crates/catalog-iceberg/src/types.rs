@@ -0,0 +1,110 @@
+//! Iceberg data types.
+//!
+//! Types are kept as the table metadata spells them: primitives are strings
+//! such as `long`, `decimal(10, 2)`, or `fixed[16]`, and nested types are
+//! structs, lists, and maps of further types. [`IcebergType`] displays in the
+//! compact form used for field data types, e.g. `map<string, list<long>>`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An Iceberg field type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum IcebergType {
+    /// A primitive type, e.g. `long` or `timestamptz`
+    Primitive(String),
+    /// A struct, list, or map
+    Nested(NestedType),
+}
+
+/// A nested Iceberg type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NestedType {
+    Struct {
+        fields: Vec<NestedField>,
+    },
+    List {
+        #[serde(rename = "element-id")]
+        element_id: i32,
+        element: Box<IcebergType>,
+        #[serde(rename = "element-required")]
+        element_required: bool,
+    },
+    Map {
+        #[serde(rename = "key-id")]
+        key_id: i32,
+        key: Box<IcebergType>,
+        #[serde(rename = "value-id")]
+        value_id: i32,
+        value: Box<IcebergType>,
+        #[serde(rename = "value-required")]
+        value_required: bool,
+    },
+}
+
+/// A field of an Iceberg struct, as written in table metadata.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NestedField {
+    pub id: i32,
+    pub name: String,
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub field_type: IcebergType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+impl fmt::Display for IcebergType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IcebergType::Primitive(name) => f.write_str(name),
+            IcebergType::Nested(NestedType::Struct { fields }) => {
+                f.write_str("struct<")?;
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", field.name, field.field_type)?;
+                }
+                f.write_str(">")
+            }
+            IcebergType::Nested(NestedType::List { element, .. }) => {
+                write!(f, "list<{}>", element)
+            }
+            IcebergType::Nested(NestedType::Map { key, value, .. }) => {
+                write!(f, "map<{}, {}>", key, value)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_types() {
+        let field: NestedField = serde_json::from_str(
+            r#"{"id": 3, "name": "attrs", "required": false, "type": {
+                "type": "map", "key-id": 4, "key": "string", "value-id": 5, "value-required": true,
+                "value": {"type": "list", "element-id": 6, "element-required": false, "element": "decimal(10, 2)"}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            field.field_type.to_string(),
+            "map<string, list<decimal(10, 2)>>"
+        );
+
+        let point: IcebergType = serde_json::from_str(
+            r#"{"type": "struct", "fields": [
+                {"id": 1, "name": "x", "required": true, "type": "double"},
+                {"id": 2, "name": "y", "required": true, "type": "double"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(point.to_string(), "struct<x: double, y: double>");
+    }
+}
@@ -0,0 +1,180 @@
+//! Integration tests for IcebergReader.
+//!
+//! These tests write Iceberg metadata files the way table writers lay them
+//! out and verify that IcebergReader finds and reads the current one.
+
+use metafuse_catalog_iceberg::{IcebergError, IcebergReader};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Format v2 metadata with two snapshots, partitioned by region and day(ts).
+fn v2_metadata(location: &str) -> String {
+    format!(
+        r#"{{
+          "format-version": 2,
+          "table-uuid": "9c12d441-03fe-4693-9a96-a0705ddf69c1",
+          "location": "{location}",
+          "last-sequence-number": 2,
+          "last-updated-ms": 1767225600000,
+          "last-column-id": 4,
+          "current-schema-id": 1,
+          "schemas": [
+            {{"type": "struct", "schema-id": 0, "fields": [
+              {{"id": 1, "name": "id", "required": true, "type": "long"}}
+            ]}},
+            {{"type": "struct", "schema-id": 1, "fields": [
+              {{"id": 1, "name": "id", "required": true, "type": "long", "doc": "Order ID"}},
+              {{"id": 2, "name": "region", "required": false, "type": "string"}},
+              {{"id": 3, "name": "ts", "required": false, "type": "timestamptz"}},
+              {{"id": 4, "name": "tags", "required": false, "type":
+                {{"type": "list", "element-id": 5, "element-required": false, "element": "string"}}}}
+            ]}}
+          ],
+          "default-spec-id": 1,
+          "partition-specs": [
+            {{"spec-id": 0, "fields": []}},
+            {{"spec-id": 1, "fields": [
+              {{"name": "region", "transform": "identity", "source-id": 2, "field-id": 1000}},
+              {{"name": "ts_day", "transform": "day", "source-id": 3, "field-id": 1001}}
+            ]}}
+          ],
+          "last-partition-id": 1001,
+          "properties": {{"write.format.default": "parquet"}},
+          "current-snapshot-id": 222,
+          "snapshots": [
+            {{"snapshot-id": 111, "sequence-number": 1, "timestamp-ms": 1767139200000,
+              "manifest-list": "{location}/metadata/snap-111.avro", "schema-id": 0,
+              "summary": {{"operation": "append", "added-records": "10", "total-records": "10"}}}},
+            {{"snapshot-id": 222, "parent-snapshot-id": 111, "sequence-number": 2,
+              "timestamp-ms": 1767225600000, "manifest-list": "{location}/metadata/snap-222.avro",
+              "schema-id": 1,
+              "summary": {{"operation": "overwrite", "added-records": "5", "deleted-records": "3",
+                "total-records": "12", "total-files-size": "4096", "total-data-files": "3"}}}}
+          ],
+          "snapshot-log": [
+            {{"snapshot-id": 111, "timestamp-ms": 1767139200000}},
+            {{"snapshot-id": 222, "timestamp-ms": 1767225600000}}
+          ]
+        }}"#
+    )
+}
+
+fn write_metadata(table: &Path, file: &str, contents: &str) {
+    let metadata_dir = table.join("metadata");
+    fs::create_dir_all(&metadata_dir).unwrap();
+    fs::write(metadata_dir.join(file), contents).unwrap();
+}
+
+#[tokio::test]
+async fn test_read_metadata_from_version_hint() {
+    let dir = TempDir::new().unwrap();
+    let location = format!("file://{}", dir.path().display());
+    write_metadata(dir.path(), "v1.metadata.json", "not json");
+    write_metadata(dir.path(), "v2.metadata.json", &v2_metadata(&location));
+    write_metadata(dir.path(), "version-hint.text", "2\n");
+
+    let reader = IcebergReader::new();
+    let metadata = reader.get_metadata(&location).await.unwrap();
+
+    assert_eq!(metadata.format_version, 2);
+    assert!(metadata
+        .metadata_location
+        .ends_with("/metadata/v2.metadata.json"));
+    assert_eq!(metadata.schema.schema_id, 1);
+    let names: Vec<_> = metadata.schema.fields.iter().map(|f| &f.name).collect();
+    assert_eq!(names, vec!["id", "region", "ts", "tags"]);
+    assert!(!metadata.schema.fields[0].nullable);
+    assert_eq!(
+        metadata.schema.fields[0].description.as_deref(),
+        Some("Order ID")
+    );
+    assert_eq!(metadata.schema.fields[3].data_type, "list<string>");
+    assert_eq!(metadata.schema.partition_columns, vec!["region", "ts"]);
+    assert_eq!(metadata.partition_specs.len(), 2);
+    assert_eq!(metadata.partition_specs[1].fields[1].transform, "day");
+
+    assert_eq!(metadata.current_snapshot_id, Some(222));
+    assert_eq!(metadata.row_count, Some(12));
+    assert_eq!(metadata.size_bytes, Some(4096));
+    assert_eq!(metadata.num_files, Some(3));
+    assert_eq!(
+        metadata.current_snapshot().unwrap().operation.as_deref(),
+        Some("overwrite")
+    );
+}
+
+#[tokio::test]
+async fn test_read_latest_metadata_file_and_history() {
+    let dir = TempDir::new().unwrap();
+    let location = format!("file://{}", dir.path().display());
+    write_metadata(dir.path(), "00001-a.metadata.json", "not json");
+    write_metadata(dir.path(), "00002-b.metadata.json", &v2_metadata(&location));
+
+    let reader = IcebergReader::new();
+    let history = reader
+        .get_history(&dir.path().display().to_string(), 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].snapshot_id, 222);
+    assert_eq!(history[0].sequence_number, 2);
+    assert_eq!(history[0].summary.get("deleted-records").unwrap(), "3");
+    assert!(!history[0].summary.contains_key("operation"));
+    assert_eq!(history[1].operation.as_deref(), Some("append"));
+
+    let history = reader.get_history(&location, 1).await.unwrap();
+    assert_eq!(history.len(), 1);
+
+    // A metadata file can be read directly
+    let file = format!("{}/metadata/00002-b.metadata.json", location);
+    assert_eq!(reader.get_schema(&file).await.unwrap().fields.len(), 4);
+}
+
+#[tokio::test]
+async fn test_read_v1_metadata() {
+    let dir = TempDir::new().unwrap();
+    let location = format!("file://{}", dir.path().display());
+    let metadata = format!(
+        r#"{{
+          "format-version": 1,
+          "location": "{location}",
+          "last-updated-ms": 1767225600000,
+          "last-column-id": 2,
+          "schema": {{"type": "struct", "fields": [
+            {{"id": 1, "name": "id", "required": true, "type": "int"}},
+            {{"id": 2, "name": "country", "required": false, "type": "string"}}
+          ]}},
+          "partition-spec": [
+            {{"name": "country", "transform": "identity", "source-id": 2, "field-id": 1000}}
+          ],
+          "properties": {{}},
+          "current-snapshot-id": -1
+        }}"#
+    );
+    write_metadata(dir.path(), "v1.metadata.json", &metadata);
+
+    let metadata = IcebergReader::new().get_metadata(&location).await.unwrap();
+    assert_eq!(metadata.format_version, 1);
+    assert_eq!(metadata.schema.partition_columns, vec!["country"]);
+    assert_eq!(metadata.current_snapshot_id, None);
+    assert_eq!(metadata.row_count, Some(0));
+    assert!(metadata.snapshots.is_empty());
+}
+
+#[tokio::test]
+async fn test_missing_table() {
+    let dir = TempDir::new().unwrap();
+    let err = IcebergReader::new()
+        .get_metadata(&format!("file://{}/missing", dir.path().display()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, IcebergError::NotFound(_)), "{}", err);
+
+    write_metadata(dir.path(), "v1.metadata.json", "{}");
+    let err = IcebergReader::new()
+        .get_metadata(&dir.path().display().to_string())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, IcebergError::Parse(_, _)), "{}", err);
+}
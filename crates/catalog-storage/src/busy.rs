@@ -0,0 +1,269 @@
+//! SQLITE_BUSY handling for concurrent writers.
+//!
+//! Several processes (API servers, emitters) can write to one SQLite catalog.
+//! SQLite allows a single writer at a time, and by default fails immediately
+//! with `SQLITE_BUSY`/`SQLITE_LOCKED` when another connection holds the lock.
+//!
+//! Two layers handle this:
+//! - Every connection gets a busy timeout, so SQLite itself waits for the lock
+//!   before failing, and starts transactions as `IMMEDIATE`. A deferred
+//!   transaction takes a read lock first and can't wait to upgrade it (SQLite
+//!   returns `SQLITE_BUSY` at once to avoid a deadlock).
+//! - [`retry_busy`] re-runs a whole unit of work when it still fails with a
+//!   busy error. This covers cases the timeout can't, such as a deferred
+//!   transaction that can't upgrade its read lock. Retries are bounded and use
+//!   exponential backoff with jitter so racing writers don't retry in lockstep.
+//!
+//! The unit of work passed to [`retry_busy`] must be safe to repeat: a
+//! transaction that failed has been rolled back, so re-running it from the
+//! start is.
+
+use metafuse_catalog_core::{CatalogError, Result};
+use rand::Rng;
+use rusqlite::{Connection, ErrorCode, TransactionBehavior};
+use std::time::Duration;
+
+/// Default time SQLite waits for a lock before returning `SQLITE_BUSY`.
+pub const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5_000;
+
+/// Default number of retries after a busy error.
+pub const DEFAULT_BUSY_RETRIES: u32 = 5;
+
+/// Default delay before the first retry.
+pub const DEFAULT_BUSY_BASE_DELAY_MS: u64 = 25;
+
+/// Upper bound on a single retry delay.
+pub const DEFAULT_BUSY_MAX_DELAY_MS: u64 = 1_000;
+
+/// How connections wait for and retry on a locked database.
+///
+/// # Environment Variables
+///
+/// | Variable | Default | Description |
+/// |----------|---------|-------------|
+/// | `METAFUSE_SQLITE_BUSY_TIMEOUT_MS` | 5000 | SQLite busy timeout per statement |
+/// | `METAFUSE_SQLITE_BUSY_RETRIES` | 5 | Retries of a unit of work after a busy error |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyRetryPolicy {
+    /// Time SQLite waits for a lock before returning `SQLITE_BUSY`.
+    pub busy_timeout: Duration,
+
+    /// Retries after a busy error; 0 fails on the first one.
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled for each further retry.
+    pub base_delay: Duration,
+
+    /// Upper bound on a single retry delay.
+    pub max_delay: Duration,
+}
+
+impl Default for BusyRetryPolicy {
+    fn default() -> Self {
+        Self {
+            busy_timeout: Duration::from_millis(DEFAULT_BUSY_TIMEOUT_MS),
+            max_retries: DEFAULT_BUSY_RETRIES,
+            base_delay: Duration::from_millis(DEFAULT_BUSY_BASE_DELAY_MS),
+            max_delay: Duration::from_millis(DEFAULT_BUSY_MAX_DELAY_MS),
+        }
+    }
+}
+
+impl BusyRetryPolicy {
+    /// Create policy from environment variables.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            busy_timeout: std::env::var("METAFUSE_SQLITE_BUSY_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.busy_timeout),
+            max_retries: std::env::var("METAFUSE_SQLITE_BUSY_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_retries),
+            ..defaults
+        }
+    }
+
+    /// Policy that never waits or retries (SQLite's default behavior).
+    pub fn disabled() -> Self {
+        Self {
+            busy_timeout: Duration::ZERO,
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Apply the busy timeout to a connection and make its transactions
+    /// take the write lock when they begin.
+    pub fn configure(&self, conn: &mut Connection) -> Result<()> {
+        conn.busy_timeout(self.busy_timeout)?;
+        conn.set_transaction_behavior(TransactionBehavior::Immediate);
+        Ok(())
+    }
+
+    /// Delay before retry number `retry` (1-based).
+    ///
+    /// Exponential backoff capped at `max_delay`, with the upper half of
+    /// each delay randomized.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = exp / 2;
+        let jitter_ms = half.as_millis() as u64;
+        let jitter = if jitter_ms > 0 {
+            Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_ms))
+        } else {
+            Duration::ZERO
+        };
+        half + jitter
+    }
+}
+
+/// Whether an error means the database was locked by another connection.
+pub fn is_busy(err: &CatalogError) -> bool {
+    matches!(
+        err,
+        CatalogError::Sqlite(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Run `op`, re-running it after busy errors as allowed by `policy`.
+///
+/// Blocks the thread while waiting, so call it from `spawn_blocking` like
+/// any other SQLite work. `what` names the operation in logs.
+pub fn retry_busy<T>(
+    policy: &BusyRetryPolicy,
+    what: &str,
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut retries = 0;
+    loop {
+        match op() {
+            Err(e) if is_busy(&e) && retries < policy.max_retries => {
+                retries += 1;
+                let delay = policy.delay(retries);
+                tracing::warn!(
+                    operation = what,
+                    retry = retries,
+                    max_retries = policy.max_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %e,
+                    "Catalog database busy, retrying"
+                );
+                std::thread::sleep(delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn busy_error() -> CatalogError {
+        CatalogError::Sqlite(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            Some("database is locked".to_string()),
+        ))
+    }
+
+    fn fast_policy(max_retries: u32) -> BusyRetryPolicy {
+        BusyRetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+            ..BusyRetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_is_busy() {
+        assert!(is_busy(&busy_error()));
+        assert!(!is_busy(&CatalogError::Other("busy".to_string())));
+        assert!(!is_busy(&CatalogError::Sqlite(
+            rusqlite::Error::QueryReturnedNoRows
+        )));
+    }
+
+    #[test]
+    fn test_delay_is_bounded_with_jitter() {
+        let policy = BusyRetryPolicy::default();
+        for retry in 1..=10 {
+            let expected = (policy.base_delay * 2u32.pow(retry - 1)).min(policy.max_delay);
+            let delay = policy.delay(retry);
+            assert!(
+                delay >= expected / 2 && delay <= expected,
+                "retry {}",
+                retry
+            );
+        }
+        assert!(policy.delay(u32::MAX) <= policy.max_delay);
+    }
+
+    #[test]
+    fn test_retry_busy_until_success() {
+        let attempts = Cell::new(0);
+        let result = retry_busy(&fast_policy(3), "test", || {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 3 {
+                Err(busy_error())
+            } else {
+                Ok(attempts.get())
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_retry_busy_is_bounded() {
+        let attempts = Cell::new(0);
+        let result: Result<()> = retry_busy(&fast_policy(2), "test", || {
+            attempts.set(attempts.get() + 1);
+            Err(busy_error())
+        });
+        assert!(is_busy(&result.unwrap_err()));
+        assert_eq!(attempts.get(), 3);
+
+        // Other errors are returned immediately
+        attempts.set(0);
+        let result: Result<()> = retry_busy(&fast_policy(2), "test", || {
+            attempts.set(attempts.get() + 1);
+            Err(CatalogError::Other("boom".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn test_busy_timeout_waits_for_lock() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        let holder = Connection::open(temp.path()).unwrap();
+        holder
+            .execute_batch("CREATE TABLE t (x INTEGER); BEGIN IMMEDIATE;")
+            .unwrap();
+
+        let mut waiter = Connection::open(temp.path()).unwrap();
+        BusyRetryPolicy::disabled().configure(&mut waiter).unwrap();
+        let err = waiter
+            .execute("INSERT INTO t VALUES (1)", [])
+            .map_err(CatalogError::from)
+            .unwrap_err();
+        assert!(is_busy(&err));
+
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            holder.execute_batch("COMMIT;").unwrap();
+        });
+        BusyRetryPolicy::default().configure(&mut waiter).unwrap();
+        waiter.execute("INSERT INTO t VALUES (1)", []).unwrap();
+        handle.join().unwrap();
+    }
+}
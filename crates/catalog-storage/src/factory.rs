@@ -53,6 +53,7 @@ use dashmap::DashMap;
 use lru::LruCache;
 use metafuse_catalog_core::{CatalogError, Result};
 use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -387,12 +388,22 @@ pub struct TenantBackendFactory {
     /// URI template with {tenant_id} placeholder
     storage_uri_template: String,
 
+    /// Region-specific URI templates, used instead of the default template
+    /// for tenants in that region
+    region_templates: HashMap<String, String>,
+
     /// LRU cache of tenant backends
     cache: Mutex<LruCache<String, Arc<dyn CatalogBackend>>>,
 
     /// Configured cache capacity
     capacity: usize,
 
+    /// Lookups served from the cache since startup
+    cache_hits: AtomicU64,
+
+    /// Lookups that created a backend since startup
+    cache_misses: AtomicU64,
+
     /// Per-tenant semaphore pool for connection limiting
     semaphore_pool: TenantSemaphorePool,
 
@@ -462,13 +473,44 @@ impl TenantBackendFactory {
 
         Ok(Self {
             storage_uri_template: template,
+            region_templates: HashMap::new(),
             cache: Mutex::new(LruCache::new(capacity)),
             capacity: cache_capacity,
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
             semaphore_pool: TenantSemaphorePool::new(pool_config.clone()),
             pool_config,
         })
     }
 
+    /// Use region-specific URI templates, keyed by region.
+    ///
+    /// Tenants in a listed region get their catalog from that region's
+    /// template, e.g. an EU-only bucket, instead of the default template.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a template doesn't contain `{tenant_id}` placeholder.
+    pub fn with_region_templates(mut self, templates: HashMap<String, String>) -> Result<Self> {
+        for (region, template) in &templates {
+            if !template.contains(TENANT_ID_PLACEHOLDER) {
+                return Err(CatalogError::ValidationError(format!(
+                    "storage template for region '{}' must contain '{}' placeholder",
+                    region, TENANT_ID_PLACEHOLDER
+                )));
+            }
+        }
+        self.region_templates = templates;
+        Ok(self)
+    }
+
+    /// Whether a region's catalogs are stored in region-specific storage:
+    /// it has its own template, or the default template contains `{region}`.
+    pub fn routes_region(&self, region: &str) -> bool {
+        self.region_templates.contains_key(region)
+            || self.storage_uri_template.contains(REGION_PLACEHOLDER)
+    }
+
     /// Create a factory with specific pool configuration.
     ///
     /// Use this for custom connection limits or circuit breaker settings.
@@ -505,8 +547,11 @@ impl TenantBackendFactory {
 
         Ok(Self {
             storage_uri_template: template,
+            region_templates: HashMap::new(),
             cache: Mutex::new(LruCache::new(capacity)),
             capacity: cache_capacity,
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
             semaphore_pool: TenantSemaphorePool::new(pool_config.clone()),
             pool_config,
         })
@@ -542,6 +587,10 @@ impl TenantBackendFactory {
 
     /// Resolve a tenant ID and optional region to a storage URI.
     ///
+    /// Uses the region's own template when one is configured (see
+    /// [`with_region_templates`](Self::with_region_templates)), otherwise the
+    /// default template.
+    ///
     /// Replaces `{tenant_id}` and `{region}` placeholders with actual values.
     /// If the template contains `{region}` placeholder, it will be replaced.
     /// If no placeholder exists but region is provided for S3 URIs, appends `?region=` query param.
@@ -576,9 +625,10 @@ impl TenantBackendFactory {
             "tenant_id should be validated to prevent path traversal"
         );
 
-        let mut uri = self
-            .storage_uri_template
-            .replace(TENANT_ID_PLACEHOLDER, tenant_id);
+        let template = region
+            .and_then(|r| self.region_templates.get(r))
+            .unwrap_or(&self.storage_uri_template);
+        let mut uri = template.replace(TENANT_ID_PLACEHOLDER, tenant_id);
 
         // Handle region placeholder
         if uri.contains(REGION_PLACEHOLDER) {
@@ -649,12 +699,14 @@ impl TenantBackendFactory {
             let mut cache = self.cache.lock();
             if let Some(backend) = cache.get(&tenant_id) {
                 debug!(tenant_id = %tenant_id, "Backend cache hit");
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
                 return Ok(Arc::clone(backend));
             }
         }
 
         // Cache miss - create new backend
         debug!(tenant_id = %tenant_id, "Backend cache miss, creating new backend");
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
 
         let uri = self.resolve_uri(&tenant_id);
         let backend = backend_from_uri(&uri)?;
@@ -716,12 +768,14 @@ impl TenantBackendFactory {
             let mut cache = self.cache.lock();
             if let Some(backend) = cache.get(&cache_key) {
                 debug!(tenant_id = %tenant_id, region = ?region, "Backend cache hit");
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
                 return Ok(Arc::clone(backend));
             }
         }
 
         // Cache miss - create new backend
         debug!(tenant_id = %tenant_id, region = ?region, "Backend cache miss, creating new backend");
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
 
         let uri = self.resolve_uri_with_region(tenant_id, region);
         let backend = backend_from_uri(&uri)?;
@@ -968,6 +1022,8 @@ impl TenantBackendFactory {
             capacity: self.capacity,
             size: cache.len(),
             cached_tenants: cache.iter().map(|(k, _)| k.clone()).collect(),
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
         }
     }
 
@@ -1030,6 +1086,10 @@ pub struct TenantBackendFactoryStats {
     pub size: usize,
     /// List of currently cached tenant IDs (in LRU order, most recent first)
     pub cached_tenants: Vec<String>,
+    /// Lookups served from the cache since startup
+    pub hits: u64,
+    /// Lookups that created a backend since startup
+    pub misses: u64,
 }
 
 /// Connection pool statistics for a specific tenant.
@@ -1103,6 +1163,39 @@ mod tests {
         assert!(!uri.contains("{tenant_id}"));
     }
 
+    #[test]
+    fn test_region_templates() {
+        let factory = TenantBackendFactory::new("gs://shared/{tenant_id}/catalog.db", 10)
+            .unwrap()
+            .with_region_templates(HashMap::from([(
+                "europe-west1".to_string(),
+                "gs://eu-only/{tenant_id}/catalog.db".to_string(),
+            )]))
+            .unwrap();
+
+        assert_eq!(
+            factory.resolve_uri_with_region("acme", Some("europe-west1")),
+            "gs://eu-only/acme/catalog.db"
+        );
+        assert_eq!(
+            factory.resolve_uri_with_region("acme", Some("us-east1")),
+            "gs://shared/acme/catalog.db"
+        );
+        assert!(factory.routes_region("europe-west1"));
+        assert!(!factory.routes_region("us-east1"));
+
+        let templated = TenantBackendFactory::new("gs://lake-{region}/{tenant_id}/db", 10).unwrap();
+        assert!(templated.routes_region("us-east1"));
+
+        let invalid = TenantBackendFactory::new("gs://shared/{tenant_id}/db", 10)
+            .unwrap()
+            .with_region_templates(HashMap::from([(
+                "europe-west1".to_string(),
+                "gs://eu-only/catalog.db".to_string(),
+            )]));
+        assert!(invalid.is_err());
+    }
+
     #[tokio::test]
     async fn test_get_backend_creates_and_caches() {
         let temp_dir = TempDir::new().unwrap();
@@ -1268,10 +1361,12 @@ mod tests {
 
         let _ = factory.get_backend_by_id("alpha").await.unwrap();
         let _ = factory.get_backend_by_id("beta").await.unwrap();
+        let _ = factory.get_backend_by_id("alpha").await.unwrap();
 
         let stats = factory.stats();
         assert_eq!(stats.capacity, 50);
         assert_eq!(stats.size, 2);
+        assert_eq!((stats.hits, stats.misses), (1, 2));
         assert!(stats.cached_tenants.contains(&"alpha".to_string()));
         assert!(stats.cached_tenants.contains(&"beta".to_string()));
     }
@@ -10,6 +10,17 @@
 //!
 //! The `factory` module provides connection pooling and LRU caching for
 //! per-tenant backends. See [`factory::TenantBackendFactory`] for details.
+//!
+//! # Concurrent Writers
+//!
+//! Local catalogs can be shared by several writers. The `busy` module sets a
+//! busy timeout on every connection and retries work that still hits a locked
+//! database. See [`busy::BusyRetryPolicy`] for details. Handlers that read
+//! with several queries use [`read_snapshot`] to see one state of the catalog.
+//!
+//! Cloud catalogs are shared through optimistic concurrency: uploads fail with
+//! a conflict when another writer uploaded first. [`modify_catalog`] re-runs a
+//! change on a fresh download until its upload succeeds.
 
 use metafuse_catalog_core::{init_sqlite_schema, CatalogError, Result};
 
@@ -17,6 +28,22 @@ use metafuse_catalog_core::{init_sqlite_schema, CatalogError, Result};
 pub mod tenant;
 pub use tenant::{TenantContext, TenantStatus, TenantTier};
 
+// SQLITE_BUSY handling
+pub mod busy;
+pub use busy::BusyRetryPolicy;
+
+// Read transactions for consistent multi-query reads
+pub mod snapshot;
+pub use snapshot::read_snapshot;
+
+// Download/modify/upload with retry on conflict
+pub mod modify;
+pub use modify::modify_catalog;
+
+// Standalone object writes (export bundles)
+pub mod objects;
+pub use objects::{put_object, put_object_with_credentials, ObjectCredentials};
+
 // Connection pool configuration
 pub mod pool_config;
 pub use pool_config::{CircuitBreakerConfig, ConnectionPoolConfig};
@@ -38,7 +65,7 @@ use tempfile::NamedTempFile;
 // Cache module for cloud backends
 #[cfg(any(feature = "gcs", feature = "s3"))]
 mod cache;
-#[cfg(any(feature = "gcs", feature = "s3"))]
+#[cfg(feature = "s3")]
 use bytes::Bytes;
 #[cfg(any(feature = "gcs", feature = "s3"))]
 use cache::{CatalogCache, HeadCheckBackend};
@@ -261,6 +288,8 @@ pub fn backend_from_uri(uri: &str) -> Result<Box<dyn CatalogBackend>> {
 pub struct LocalSqliteBackend {
     /// Path to the SQLite database file
     path: PathBuf,
+    /// Busy timeout and retries for concurrent writers
+    busy_policy: BusyRetryPolicy,
 }
 
 impl LocalSqliteBackend {
@@ -278,25 +307,50 @@ impl LocalSqliteBackend {
     pub fn new<P: AsRef<Path>>(path: P) -> Self {
         Self {
             path: path.as_ref().to_path_buf(),
+            busy_policy: BusyRetryPolicy::from_env(),
         }
     }
 
+    /// Use a custom busy policy instead of the one from the environment
+    pub fn with_busy_policy(mut self, policy: BusyRetryPolicy) -> Self {
+        self.busy_policy = policy;
+        self
+    }
+
     /// Get the path to the database file
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Get the busy policy applied to connections
+    pub fn busy_policy(&self) -> &BusyRetryPolicy {
+        &self.busy_policy
+    }
+
+    /// Open a connection with the busy timeout and schema in place
+    fn open(path: &Path, policy: &BusyRetryPolicy) -> Result<Connection> {
+        let mut conn = Connection::open(path)?;
+        policy.configure(&mut conn)?;
+
+        // Enable foreign key constraints
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+        // Initialize schema if needed; this writes, so it can hit a lock too
+        busy::retry_busy(policy, "init_schema", || init_sqlite_schema(&conn))?;
+
+        Ok(conn)
+    }
 }
 
 impl CatalogBackend for LocalSqliteBackend {
     fn download(&self) -> Pin<Box<dyn Future<Output = Result<CatalogDownload>> + Send + '_>> {
         let path = self.path.clone();
+        let policy = self.busy_policy;
         Box::pin(async move {
             // All SQLite operations in spawn_blocking
             tokio::task::spawn_blocking(move || {
                 // Open connection to read current version
-                let conn = Connection::open(&path)?;
-                conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-                init_sqlite_schema(&conn)?;
+                let conn = Self::open(&path, &policy)?;
 
                 // Read current catalog version
                 let catalog_version = metafuse_catalog_core::get_catalog_version(&conn)?;
@@ -336,21 +390,12 @@ impl CatalogBackend for LocalSqliteBackend {
 
     fn get_connection(&self) -> Pin<Box<dyn Future<Output = Result<Connection>> + Send + '_>> {
         let path = self.path.clone();
+        let policy = self.busy_policy;
         Box::pin(async move {
             // All SQLite operations in spawn_blocking
-            tokio::task::spawn_blocking(move || {
-                let conn = Connection::open(&path)?;
-
-                // Enable foreign key constraints
-                conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-
-                // Initialize schema if needed
-                init_sqlite_schema(&conn)?;
-
-                Ok(conn)
-            })
-            .await
-            .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
+            tokio::task::spawn_blocking(move || Self::open(&path, &policy))
+                .await
+                .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?
         })
     }
 
@@ -364,6 +409,7 @@ impl CatalogBackend for LocalSqliteBackend {
 
     fn initialize(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
         let path = self.path.clone();
+        let policy = self.busy_policy;
         Box::pin(async move {
             // Check existence
             if path.exists() {
@@ -375,10 +421,7 @@ impl CatalogBackend for LocalSqliteBackend {
 
             // SQLite operations in spawn_blocking
             tokio::task::spawn_blocking(move || {
-                let conn = Connection::open(&path)?;
-                conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-                init_sqlite_schema(&conn)?;
-
+                Self::open(&path, &policy)?;
                 Ok(())
             })
             .await
@@ -409,6 +452,13 @@ impl CatalogBackend for LocalSqliteBackend {
 /// - Download captures current generation
 /// - Upload uses `if-generation-match` precondition
 /// - Returns `ConflictError` on 412 Precondition Failed
+///
+/// A conflict means the downloaded copy is stale, so uploading it again can't
+/// succeed. Writers redo their change on a fresh download instead, as the
+/// emitter does; use [`modify_catalog`] to get that retry loop.
+///
+/// Cached catalogs are copied to a temp file on download, so local changes
+/// never reach the cache.
 #[cfg(feature = "gcs")]
 pub struct GcsBackend {
     store: std::sync::Arc<dyn object_store::ObjectStore>,
@@ -502,7 +552,17 @@ impl CatalogBackend for GcsBackend {
             if let Some(ref cache) = self.cache {
                 if let Some(cached) = cache.get(&uri, Some(self)).await? {
                     tracing::debug!(uri = %uri, "Using cached catalog");
-                    return Ok(cached);
+                    // Hand out a copy: callers modify the file before uploading it
+                    let temp_file = NamedTempFile::new().map_err(|e| {
+                        CatalogError::Other(format!("Failed to create temp file: {}", e))
+                    })?;
+                    std::fs::copy(&cached.path, temp_file.path()).map_err(|e| {
+                        CatalogError::Other(format!("Failed to copy cached catalog: {}", e))
+                    })?;
+                    let path = temp_file.into_temp_path().keep().map_err(|e| {
+                        CatalogError::Other(format!("Failed to persist temp file: {}", e))
+                    })?;
+                    return Ok(CatalogDownload { path, ..cached });
                 }
             }
 
@@ -586,7 +646,7 @@ impl CatalogBackend for GcsBackend {
         download: &'a CatalogDownload,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
         Box::pin(async move {
-            use object_store::{PutMode, PutOptions, PutPayload};
+            use object_store::{PutMode, PutOptions, PutPayload, UpdateVersion};
 
             // Validate remote version exists
             let remote_version = download.remote_version.as_ref().ok_or_else(|| {
@@ -598,75 +658,59 @@ impl CatalogBackend for GcsBackend {
                 .as_ref()
                 .ok_or_else(|| CatalogError::Other("Missing generation for GCS upload".into()))?;
 
-            // Read catalog file data once; clone bytes cheaply across retries
             let data = std::fs::read(&download.path)
                 .map_err(|e| CatalogError::Other(format!("Failed to read catalog file: {}", e)))?;
-            let data = Bytes::from(data);
 
-            const MAX_RETRIES: u32 = 3;
-            const BASE_DELAY_MS: u64 = 100;
             let uri = format!("gs://{}", self.object_path);
-
-            for attempt in 0..=MAX_RETRIES {
-                // Native async upload (no block_on!)
-                use object_store::UpdateVersion;
-
-                let update_version = UpdateVersion {
+            let put_opts = PutOptions {
+                mode: PutMode::Update(UpdateVersion {
                     e_tag: None,
                     version: Some(generation.clone()),
-                };
-
-                let put_opts = PutOptions {
-                    mode: PutMode::Update(update_version),
-                    ..Default::default()
-                };
-
-                let result = self
-                    .store
-                    .put_opts(&self.object_path, PutPayload::from(data.clone()), put_opts)
-                    .await;
+                }),
+                ..Default::default()
+            };
 
-                match result {
-                    Ok(_) => {
-                        tracing::info!(
-                            object = %self.object_path,
-                            generation = %generation,
-                            "Uploaded catalog to GCS"
-                        );
-                        if let Some(ref cache) = self.cache {
-                            if let Err(e) = cache.invalidate(&uri) {
-                                tracing::warn!(error = %e, "Failed to invalidate cache");
-                            }
+            // Native async upload (no block_on!). Transient errors are retried
+            // by object_store; a failed precondition is final for this copy.
+            let result = self
+                .store
+                .put_opts(&self.object_path, PutPayload::from(data), put_opts)
+                .await;
+
+            match result {
+                Ok(_) => {
+                    tracing::info!(
+                        object = %self.object_path,
+                        generation = %generation,
+                        "Uploaded catalog to GCS"
+                    );
+                    if let Some(ref cache) = self.cache {
+                        if let Err(e) = cache.invalidate(&uri) {
+                            tracing::warn!(error = %e, "Failed to invalidate cache");
                         }
-                        return Ok(());
                     }
-                    Err(object_store::Error::Precondition { .. }) => {
-                        if let Some(ref cache) = self.cache {
-                            let _ = cache.invalidate(&uri);
-                        }
-                        if attempt < MAX_RETRIES {
-                            let delay = BASE_DELAY_MS * 2u64.saturating_pow(attempt);
-                            // Async sleep instead of std::thread::sleep
-                            tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-                            continue;
-                        }
-                        return Err(CatalogError::ConflictError(format!(
-                            "Catalog was modified by another process (expected generation: {}). Retry your operation.",
-                            generation
-                        )));
-                    }
-                    Err(e) => {
-                        return Err(CatalogError::Other(format!(
-                            "Failed to upload to GCS: {}",
-                            e
-                        )));
+                    Ok(())
+                }
+                Err(object_store::Error::Precondition { .. }) => {
+                    // The cached copy is stale too; the next download must refetch
+                    if let Some(ref cache) = self.cache {
+                        let _ = cache.invalidate(&uri);
                     }
+                    tracing::debug!(
+                        object = %self.object_path,
+                        generation = %generation,
+                        "GCS generation precondition failed"
+                    );
+                    Err(CatalogError::ConflictError(format!(
+                        "Catalog was modified by another process (expected generation: {}). Retry your operation.",
+                        generation
+                    )))
                 }
+                Err(e) => Err(CatalogError::Other(format!(
+                    "Failed to upload to GCS: {}",
+                    e
+                ))),
             }
-
-            Err(CatalogError::ConflictError(
-                "Exceeded retry attempts for GCS upload".into(),
-            ))
         })
     }
 
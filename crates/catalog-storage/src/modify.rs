@@ -0,0 +1,203 @@
+//! Download/modify/upload with retry on conflict.
+//!
+//! Catalogs on object storage are SQLite files: a writer downloads the file,
+//! changes it locally, and uploads it with a precondition on the version it
+//! downloaded (a GCS generation or an S3 ETag). When another writer uploaded
+//! in between, the upload fails with [`CatalogError::ConflictError`] and the
+//! change must be made again on a fresh download; uploading the same file
+//! again can never succeed.
+//!
+//! [`modify_catalog`] runs that loop for any backend. The change runs in a
+//! transaction that also increments the catalog version, and is re-run from
+//! scratch after each conflict, so it must only depend on what it reads from
+//! the catalog it is given.
+
+use crate::busy::{self, BusyRetryPolicy};
+use crate::DynCatalogBackend;
+use metafuse_catalog_core::{increment_catalog_version, init_sqlite_schema, CatalogError, Result};
+use rusqlite::{Connection, Transaction};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default number of retries after an upload conflict.
+pub const DEFAULT_CONFLICT_RETRIES: u32 = 3;
+
+/// Default delay before the first retry after a conflict.
+pub const DEFAULT_CONFLICT_BASE_DELAY_MS: u64 = 100;
+
+/// Upper bound on a single conflict retry delay.
+pub const DEFAULT_CONFLICT_MAX_DELAY_MS: u64 = 2_000;
+
+/// Delays between attempts after upload conflicts.
+///
+/// Reuses the backoff of [`BusyRetryPolicy`], so racing writers don't retry
+/// in lockstep.
+fn conflict_policy(max_retries: u32) -> BusyRetryPolicy {
+    BusyRetryPolicy {
+        max_retries,
+        base_delay: Duration::from_millis(DEFAULT_CONFLICT_BASE_DELAY_MS),
+        max_delay: Duration::from_millis(DEFAULT_CONFLICT_MAX_DELAY_MS),
+        ..BusyRetryPolicy::from_env()
+    }
+}
+
+/// Apply `op` to the catalog and upload the result, retrying on conflict.
+///
+/// Each attempt downloads the catalog, runs `op` in a transaction that also
+/// increments the catalog version, and uploads the file with the backend's
+/// version precondition. After a conflict the whole attempt is repeated, up
+/// to `max_retries` times. `what` names the operation in logs.
+pub async fn modify_catalog<T, F>(
+    backend: &DynCatalogBackend,
+    what: &str,
+    max_retries: u32,
+    op: F,
+) -> Result<T>
+where
+    T: Send + 'static,
+    F: Fn(&Transaction<'_>) -> Result<T> + Send + Sync + 'static,
+{
+    let policy = conflict_policy(max_retries);
+    let op = Arc::new(op);
+    let mut retries = 0;
+
+    loop {
+        let download = backend.download().await?;
+        let path = download.path.clone();
+        let attempt_op = Arc::clone(&op);
+        let busy_policy = policy;
+        let name = what.to_string();
+
+        let value = tokio::task::spawn_blocking(move || {
+            busy::retry_busy(&busy_policy, &name, || {
+                let mut conn = Connection::open(&path)?;
+                busy_policy.configure(&mut conn)?;
+                conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+                init_sqlite_schema(&conn)?;
+
+                let tx = conn.transaction()?;
+                let value = attempt_op(&tx)?;
+                increment_catalog_version(&tx)?;
+                tx.commit()?;
+                Ok(value)
+            })
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))??;
+
+        match backend.upload(&download).await {
+            Ok(()) => return Ok(value),
+            Err(CatalogError::ConflictError(msg)) if retries < max_retries => {
+                retries += 1;
+                let delay = policy.delay(retries);
+                tracing::warn!(
+                    operation = what,
+                    retry = retries,
+                    max_retries = max_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %msg,
+                    "Catalog conflict detected, retrying"
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(CatalogError::ConflictError(msg)) => {
+                return Err(CatalogError::ConflictError(format!(
+                    "Failed after {} retries: {}",
+                    max_retries, msg
+                )));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CatalogBackend, CatalogDownload, LocalSqliteBackend};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tempfile::TempDir;
+
+    /// Local backend whose first uploads fail as if another writer won
+    struct ConflictingBackend {
+        inner: LocalSqliteBackend,
+        conflicts: AtomicU32,
+    }
+
+    impl CatalogBackend for ConflictingBackend {
+        fn download(&self) -> Pin<Box<dyn Future<Output = Result<CatalogDownload>> + Send + '_>> {
+            self.inner.download()
+        }
+
+        fn upload<'a>(
+            &'a self,
+            download: &'a CatalogDownload,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+            if self.conflicts.load(Ordering::SeqCst) > 0 {
+                self.conflicts.fetch_sub(1, Ordering::SeqCst);
+                return Box::pin(async {
+                    Err(CatalogError::ConflictError("generation changed".into()))
+                });
+            }
+            self.inner.upload(download)
+        }
+
+        fn get_connection(&self) -> Pin<Box<dyn Future<Output = Result<Connection>> + Send + '_>> {
+            self.inner.get_connection()
+        }
+
+        fn exists(&self) -> Pin<Box<dyn Future<Output = Result<bool>> + Send + '_>> {
+            self.inner.exists()
+        }
+
+        fn initialize(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+            self.inner.initialize()
+        }
+    }
+
+    async fn backend(conflicts: u32) -> (TempDir, ConflictingBackend) {
+        let dir = TempDir::new().unwrap();
+        let inner = LocalSqliteBackend::new(dir.path().join("catalog.db"));
+        inner.initialize().await.unwrap();
+        let backend = ConflictingBackend {
+            inner,
+            conflicts: AtomicU32::new(conflicts),
+        };
+        (dir, backend)
+    }
+
+    fn add_dataset(tx: &Transaction<'_>) -> Result<usize> {
+        Ok(tx.execute(
+            "INSERT OR IGNORE INTO datasets (name, path, format, created_at, last_updated)
+             VALUES ('orders', '/data', 'parquet', datetime('now'), datetime('now'))",
+            [],
+        )?)
+    }
+
+    #[tokio::test]
+    async fn test_modify_catalog_retries_conflicts() {
+        let (_dir, backend) = backend(2).await;
+        let before = backend.download().await.unwrap().catalog_version;
+
+        modify_catalog(&backend, "add_dataset", 2, add_dataset)
+            .await
+            .unwrap();
+
+        // Every attempt ran against the local file, but it holds one dataset
+        let conn = backend.get_connection().await.unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM datasets", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(backend.download().await.unwrap().catalog_version > before);
+    }
+
+    #[tokio::test]
+    async fn test_modify_catalog_gives_up_after_retries() {
+        let (_dir, backend) = backend(3).await;
+        let result = modify_catalog(&backend, "add_dataset", 2, add_dataset).await;
+        assert!(matches!(result, Err(CatalogError::ConflictError(_))));
+    }
+}
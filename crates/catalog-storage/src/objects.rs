@@ -0,0 +1,230 @@
+//! Writing standalone objects, such as export bundles, next to catalogs.
+//!
+//! [`put_object`] takes the same URIs as catalogs (`gs://`, `s3://`,
+//! `file://`, or a plain path) and uses the same credentials: Application
+//! Default Credentials for GCS and the AWS credential chain for S3.
+//! [`put_object_with_credentials`] uploads with explicit
+//! [`ObjectCredentials`] instead, e.g. a tenant's own.
+
+use crate::{parse_catalog_uri, CatalogLocation};
+use metafuse_catalog_core::{CatalogError, Result};
+
+/// Explicit cloud credentials for an upload.
+///
+/// The server's environment is not consulted when these are given, so an
+/// upload can't fall back to the server's own identity.
+#[derive(Clone, PartialEq, Eq)]
+pub enum ObjectCredentials {
+    /// A GCS service account key (the JSON key file contents)
+    Gcs { service_account_key: String },
+    /// An AWS access key, with a session token for temporary credentials
+    S3 {
+        access_key_id: String,
+        secret_access_key: String,
+        session_token: Option<String>,
+    },
+}
+
+impl ObjectCredentials {
+    /// URI scheme these credentials are for.
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            ObjectCredentials::Gcs { .. } => "gs",
+            ObjectCredentials::S3 { .. } => "s3",
+        }
+    }
+}
+
+impl std::fmt::Debug for ObjectCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectCredentials::Gcs { .. } => f
+                .debug_struct("Gcs")
+                .field("service_account_key", &"<redacted>")
+                .finish(),
+            ObjectCredentials::S3 { access_key_id, .. } => f
+                .debug_struct("S3")
+                .field("access_key_id", access_key_id)
+                .field("secret_access_key", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+/// Write `data` to the object at `uri`, replacing any existing object.
+///
+/// Local files are written to a temporary file first and renamed into place,
+/// creating missing parent directories.
+pub async fn put_object(uri: &str, data: Vec<u8>) -> Result<()> {
+    put_object_with_credentials(uri, data, None).await
+}
+
+/// Write `data` to the object at `uri` with `credentials`, or with the
+/// server's credentials without them.
+///
+/// Fails if the credentials are for another store than `uri`, including a
+/// local path.
+pub async fn put_object_with_credentials(
+    uri: &str,
+    data: Vec<u8>,
+    credentials: Option<&ObjectCredentials>,
+) -> Result<()> {
+    let location = parse_catalog_uri(uri)?;
+    if let Some(credentials) = credentials {
+        let matches = matches!(
+            (&location, credentials),
+            (CatalogLocation::Gcs { .. }, ObjectCredentials::Gcs { .. })
+                | (CatalogLocation::S3 { .. }, ObjectCredentials::S3 { .. })
+        );
+        if !matches {
+            return Err(CatalogError::Other(format!(
+                "{} credentials can't be used to write {}",
+                credentials.scheme(),
+                uri
+            )));
+        }
+    }
+    match location {
+        CatalogLocation::Local(path) => tokio::task::spawn_blocking(move || {
+            let parent = path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or_else(|| std::path::Path::new("."));
+            let write_error = |e: std::io::Error| {
+                CatalogError::Other(format!("Failed to write {:?}: {}", path, e))
+            };
+            std::fs::create_dir_all(parent).map_err(write_error)?;
+            let mut temp = tempfile::NamedTempFile::new_in(parent).map_err(write_error)?;
+            std::io::Write::write_all(&mut temp, &data).map_err(write_error)?;
+            temp.persist(&path).map_err(|e| write_error(e.error))?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| CatalogError::Other(format!("Task join error: {}", e)))?,
+        CatalogLocation::Gcs { bucket, object } => {
+            #[cfg(feature = "gcs")]
+            {
+                use object_store::gcp::GoogleCloudStorageBuilder;
+                use object_store::{path::Path, ObjectStore, PutPayload};
+
+                let builder = match credentials {
+                    Some(ObjectCredentials::Gcs {
+                        service_account_key,
+                    }) => GoogleCloudStorageBuilder::new()
+                        .with_service_account_key(service_account_key),
+                    _ => GoogleCloudStorageBuilder::from_env(),
+                };
+                let store = builder.with_bucket_name(&bucket).build().map_err(|e| {
+                    CatalogError::Other(format!(
+                        "Failed to create GCS client. Check GOOGLE_APPLICATION_CREDENTIALS: {}",
+                        e
+                    ))
+                })?;
+                store
+                    .put(&Path::from(object), PutPayload::from(data))
+                    .await
+                    .map_err(|e| CatalogError::Other(format!("Failed to upload {}: {}", uri, e)))?;
+                Ok(())
+            }
+            #[cfg(not(feature = "gcs"))]
+            {
+                let _ = (bucket, object, data, credentials);
+                Err(CatalogError::Other(
+                    "GCS uploads require the 'gcs' feature. Rebuild with --features gcs".into(),
+                ))
+            }
+        }
+        CatalogLocation::S3 {
+            bucket,
+            key,
+            region,
+        } => {
+            #[cfg(feature = "s3")]
+            {
+                use object_store::aws::AmazonS3Builder;
+                use object_store::{path::Path, ObjectStore, PutPayload};
+
+                let mut builder = match credentials {
+                    Some(ObjectCredentials::S3 {
+                        access_key_id,
+                        secret_access_key,
+                        session_token,
+                    }) => {
+                        let builder = AmazonS3Builder::new()
+                            .with_access_key_id(access_key_id)
+                            .with_secret_access_key(secret_access_key);
+                        match session_token {
+                            Some(token) => builder.with_token(token),
+                            None => builder,
+                        }
+                    }
+                    _ => AmazonS3Builder::from_env(),
+                }
+                .with_bucket_name(&bucket);
+                if let Some(region) = region {
+                    builder = builder.with_region(region);
+                }
+                let store = builder.build().map_err(|e| {
+                    CatalogError::Other(format!(
+                        "Failed to create S3 client. Check AWS credentials/region: {}",
+                        e
+                    ))
+                })?;
+                store
+                    .put(&Path::from(key), PutPayload::from(data))
+                    .await
+                    .map_err(|e| CatalogError::Other(format!("Failed to upload {}: {}", uri, e)))?;
+                Ok(())
+            }
+            #[cfg(not(feature = "s3"))]
+            {
+                let _ = (bucket, key, region, data, credentials);
+                Err(CatalogError::Other(
+                    "S3 uploads require the 's3' feature. Rebuild with --features s3".into(),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_local_object() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("exports/acme/catalog.json");
+        let uri = format!("file://{}", path.display());
+
+        put_object(&uri, b"{\"v\":1}".to_vec()).await.unwrap();
+        put_object(&uri, b"{\"v\":2}".to_vec()).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"{\"v\":2}");
+    }
+
+    #[cfg(not(feature = "s3"))]
+    #[tokio::test]
+    async fn test_put_object_requires_cloud_feature() {
+        let err = put_object("s3://bucket/exports/catalog.json", Vec::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'s3' feature"));
+    }
+
+    #[tokio::test]
+    async fn test_put_object_rejects_mismatched_credentials() {
+        let gcs = ObjectCredentials::Gcs {
+            service_account_key: r#"{"private_key": "secret"}"#.to_string(),
+        };
+        let dir = tempfile::TempDir::new().unwrap();
+        let uri = format!("file://{}", dir.path().join("catalog.json").display());
+        for uri in [uri.as_str(), "s3://bucket/exports/catalog.json"] {
+            let err = put_object_with_credentials(uri, Vec::new(), Some(&gcs))
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("gs credentials"), "{}", err);
+        }
+        assert!(!dir.path().join("catalog.json").exists());
+        assert!(!format!("{:?}", gcs).contains("secret"));
+    }
+}
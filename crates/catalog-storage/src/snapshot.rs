@@ -0,0 +1,66 @@
+//! Consistent reads across several queries.
+//!
+//! Each statement outside a transaction sees the catalog as of when it ran,
+//! so a handler that loads a dataset, its fields, tags and lineage in
+//! separate queries can mix state from before and after a concurrent write.
+//! [`read_snapshot`] wraps those queries in one read transaction instead.
+//!
+//! Connections start transactions as `IMMEDIATE` (see [`crate::busy`]), which
+//! would take the write lock for a read. Snapshots begin `DEFERRED`: the
+//! transaction takes a shared lock at its first read and keeps it until it is
+//! dropped, so writers wait (within their busy timeout) until the reads are
+//! done. Keep snapshots short and never hold one across slow work such as
+//! object storage reads.
+
+use metafuse_catalog_core::Result;
+use rusqlite::{Connection, Transaction, TransactionBehavior};
+
+/// Begin a read transaction for a group of queries.
+///
+/// Dereferences to the [`Connection`], so queries run on it unchanged. The
+/// transaction is rolled back when dropped; it must not be used for writes.
+pub fn read_snapshot(conn: &Connection) -> Result<Transaction<'_>> {
+    Ok(Transaction::new_unchecked(
+        conn,
+        TransactionBehavior::Deferred,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BusyRetryPolicy;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_holds_writers_off() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("catalog.db");
+        let open = || {
+            let mut conn = Connection::open(&path).unwrap();
+            BusyRetryPolicy::disabled().configure(&mut conn).unwrap();
+            conn
+        };
+        let reader = open();
+        reader
+            .execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);")
+            .unwrap();
+        let writer = open();
+
+        let snapshot = read_snapshot(&reader).unwrap();
+        let count: i64 = snapshot
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(writer.execute("INSERT INTO t VALUES (2)", []).is_err());
+        let count: i64 = snapshot
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        drop(snapshot);
+
+        writer.busy_timeout(Duration::from_secs(1)).unwrap();
+        writer.execute("INSERT INTO t VALUES (2)", []).unwrap();
+    }
+}